@@ -0,0 +1,15 @@
+//! Compiles the C plugin against the generated `waav_plugin.h`
+
+fn main() {
+    println!("cargo:rerun-if-changed=c/tone_tts.c");
+    println!("cargo:rerun-if-changed=../../plugin-api/include/waav_plugin.h");
+
+    cc::Build::new()
+        .file("c/tone_tts.c")
+        .include("../../plugin-api/include")
+        .flag_if_supported("-std=c11")
+        .warnings(true)
+        .extra_warnings(true)
+        .warnings_into_errors(true)
+        .compile("tone_tts");
+}
@@ -0,0 +1,197 @@
+/*
+ * Tone TTS: a WaaV Gateway TTS plugin written in C.
+ *
+ * Every character of the text becomes 40 ms of a square-wave tone (spaces
+ * become silence), so the plugin needs no network access and its output is
+ * easy to check. It shows the full provider lifecycle of the C plugin API:
+ * create/destroy, connect, queued text, flush, clear and error reporting.
+ *
+ * The host serializes calls on one provider, so the provider needs no lock.
+ * Audio is delivered synchronously from speak/flush.
+ */
+
+#include <ctype.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "waav_plugin.h"
+
+#define DEFAULT_SAMPLE_RATE 24000
+#define CHAR_DURATION_MS 40
+#define AMPLITUDE 8000
+
+typedef struct ToneProvider {
+    WaavCTTSHost host;
+    uint32_t sample_rate;
+    bool connected;
+    /* Text queued since the last flush */
+    char *pending;
+    size_t pending_len;
+    /* Message of the last failed call, a string literal */
+    const char *error;
+} ToneProvider;
+
+/* Read "sample_rate" from the config JSON; null or missing uses the default */
+static uint32_t config_sample_rate(const char *config_json) {
+    const char *key = strstr(config_json, "\"sample_rate\"");
+    if (key == NULL) {
+        return DEFAULT_SAMPLE_RATE;
+    }
+    const char *value = key + strlen("\"sample_rate\"");
+    while (*value == ' ' || *value == ':') {
+        value++;
+    }
+    if (!isdigit((unsigned char)*value)) {
+        return DEFAULT_SAMPLE_RATE;
+    }
+    unsigned long rate = strtoul(value, NULL, 10);
+    return rate > 0 && rate <= 192000 ? (uint32_t)rate : DEFAULT_SAMPLE_RATE;
+}
+
+static uint32_t fail(ToneProvider *provider, uint32_t code, const char *message) {
+    provider->error = message;
+    return code;
+}
+
+static void *tone_create(const char *config_json, const WaavCTTSHost *host) {
+    ToneProvider *provider = calloc(1, sizeof(ToneProvider));
+    if (provider == NULL) {
+        return NULL;
+    }
+    provider->host = *host;
+    provider->sample_rate = config_sample_rate(config_json);
+    return provider;
+}
+
+static void tone_destroy(void *ptr) {
+    ToneProvider *provider = ptr;
+    free(provider->pending);
+    free(provider);
+}
+
+static uint32_t tone_connect(void *ptr) {
+    ToneProvider *provider = ptr;
+    provider->connected = true;
+    return WaavErrorCode_OK;
+}
+
+static uint32_t tone_disconnect(void *ptr) {
+    ToneProvider *provider = ptr;
+    provider->connected = false;
+    provider->pending_len = 0;
+    return WaavErrorCode_OK;
+}
+
+static bool tone_is_ready(const void *ptr) {
+    const ToneProvider *provider = ptr;
+    return provider->connected;
+}
+
+static uint32_t tone_flush(void *ptr) {
+    ToneProvider *provider = ptr;
+    if (!provider->connected) {
+        return fail(provider, WaavErrorCode_NOT_CONNECTED, "flush before connect");
+    }
+    if (provider->pending_len == 0) {
+        return WaavErrorCode_OK;
+    }
+
+    size_t char_samples = (size_t)provider->sample_rate * CHAR_DURATION_MS / 1000;
+    size_t len = provider->pending_len * char_samples * 2;
+    uint8_t *audio = malloc(len);
+    if (audio == NULL) {
+        return fail(provider, WaavErrorCode_INTERNAL_ERROR, "out of memory");
+    }
+
+    uint8_t *out = audio;
+    for (size_t c = 0; c < provider->pending_len; c++) {
+        unsigned char ch = (unsigned char)provider->pending[c];
+        uint32_t frequency = 300 + (ch % 32) * 25;
+        for (size_t i = 0; i < char_samples; i++) {
+            int16_t sample = 0;
+            if (!isspace(ch)) {
+                bool high = (i * 2 * frequency / provider->sample_rate) % 2 == 0;
+                sample = high ? AMPLITUDE : -AMPLITUDE;
+            }
+            /* linear16 is little-endian */
+            *out++ = (uint8_t)((uint16_t)sample & 0xff);
+            *out++ = (uint8_t)((uint16_t)sample >> 8);
+        }
+    }
+
+    WaavCAudioChunk chunk = {
+        .data = audio,
+        .len = len,
+        .format = "linear16",
+        .sample_rate = provider->sample_rate,
+        .duration_ms = (uint32_t)(provider->pending_len * CHAR_DURATION_MS),
+    };
+    provider->pending_len = 0;
+    if (provider->host.on_audio != NULL) {
+        provider->host.on_audio(&chunk, provider->host.host);
+    }
+    free(audio);
+    if (provider->host.on_complete != NULL) {
+        provider->host.on_complete(provider->host.host);
+    }
+    return WaavErrorCode_OK;
+}
+
+static uint32_t tone_speak(void *ptr, const char *text, size_t text_len, bool flush) {
+    ToneProvider *provider = ptr;
+    if (!provider->connected) {
+        return fail(provider, WaavErrorCode_NOT_CONNECTED, "speak before connect");
+    }
+
+    if (text_len > 0) {
+        char *pending = realloc(provider->pending, provider->pending_len + text_len);
+        if (pending == NULL) {
+            return fail(provider, WaavErrorCode_INTERNAL_ERROR, "out of memory");
+        }
+        memcpy(pending + provider->pending_len, text, text_len);
+        provider->pending = pending;
+        provider->pending_len += text_len;
+    }
+
+    return flush ? tone_flush(provider) : WaavErrorCode_OK;
+}
+
+static uint32_t tone_clear(void *ptr) {
+    ToneProvider *provider = ptr;
+    provider->pending_len = 0;
+    return WaavErrorCode_OK;
+}
+
+static const char *tone_last_error(const void *ptr) {
+    const ToneProvider *provider = ptr;
+    return provider->error;
+}
+
+static const char *tone_provider_info(const void *ptr) {
+    (void)ptr;
+    return "{\"provider\": \"c-tone-tts\", \"version\": \"1.0.0\", \"type\": \"c-plugin\"}";
+}
+
+static const WaavCTTSPlugin PLUGIN = {
+    .abi_version = WAAV_C_ABI_VERSION,
+    .id = "c-tone-tts",
+    .name = "C Tone TTS",
+    .version = "1.0.0",
+    .description = "Renders text as tones; example of a TTS plugin written in C",
+    .vtable = {
+        .create = tone_create,
+        .destroy = tone_destroy,
+        .connect = tone_connect,
+        .disconnect = tone_disconnect,
+        .is_ready = tone_is_ready,
+        .speak = tone_speak,
+        .clear = tone_clear,
+        .flush = tone_flush,
+        .last_error = tone_last_error,
+        .provider_info = tone_provider_info,
+    },
+};
+
+const WaavCTTSPlugin *waav_c_tts_plugin(void) {
+    return &PLUGIN;
+}
@@ -0,0 +1,93 @@
+//! C TTS Plugin for WaaV Gateway
+//!
+//! The provider is implemented in C (`c/tone_tts.c`) against the generated
+//! header `plugin-api/include/waav_plugin.h`. This crate is only the shim
+//! the gateway loads: `build.rs` compiles the C code, and the root module
+//! below adapts the C vtable with `waav_plugin_api::c_api`. To port another
+//! C plugin, replace the C file and keep this crate as is.
+//!
+//! # Building
+//!
+//! ```bash
+//! cargo build --release
+//! ```
+//!
+//! Requires a C11 compiler. The resulting library is
+//! `target/release/libwaav_plugin_c_tts.so` (`.dylib` on macOS,
+//! `waav_plugin_c_tts.dll` on Windows).
+//!
+//! # Installation
+//!
+//! Copy the library into the gateway's `plugin_dir` and enable plugins:
+//!
+//! ```yaml
+//! plugins:
+//!   enabled: true
+//!   plugin_dir: /opt/waav/plugins
+//! ```
+//!
+//! Sessions then select it with `"provider": "c-tone-tts"` in `tts_config`.
+
+use abi_stable::{
+    export_root_module,
+    prefix_type::PrefixTypeTrait,
+    sabi_extern_fn,
+    std_types::{ROption, RResult, RString},
+};
+use waav_plugin_api::{
+    c_api::{c_tts_manifest, create_c_tts, CTTSPlugin},
+    ffi_ok, FFIConfig, PluginManifest, PluginModule, PluginModule_Ref, TTSProvider,
+    PLUGIN_API_VERSION,
+};
+
+extern "C" {
+    /// Defined by the C plugin
+    fn waav_c_tts_plugin() -> *const CTTSPlugin;
+}
+
+/// The C plugin's description and vtable
+fn plugin() -> &'static CTTSPlugin {
+    // The C plugin returns a pointer to a static
+    unsafe { &*waav_c_tts_plugin() }
+}
+
+#[sabi_extern_fn]
+fn get_manifest() -> PluginManifest {
+    c_tts_manifest(plugin())
+}
+
+#[sabi_extern_fn]
+fn init(_config: *const FFIConfig) -> RResult<(), RString> {
+    ffi_ok()
+}
+
+#[sabi_extern_fn]
+fn shutdown() -> RResult<(), RString> {
+    ffi_ok()
+}
+
+/// Factory function to create a TTS provider
+///
+/// The config is the gateway's `TTSConfig` serialized as JSON; it is passed
+/// to the C plugin's `create` unchanged.
+#[sabi_extern_fn]
+fn create_tts(config: *const FFIConfig) -> RResult<TTSProvider, RString> {
+    create_c_tts(plugin(), config)
+}
+
+/// Export the root module that the gateway will load
+#[export_root_module]
+fn get_root_module() -> PluginModule_Ref {
+    PluginModule {
+        manifest: get_manifest,
+        init,
+        shutdown,
+        create_stt: ROption::RNone,
+        create_tts: ROption::RSome(create_tts),
+        create_realtime: ROption::RNone,
+        abi_version: PLUGIN_API_VERSION,
+        provider_capabilities: ROption::RNone,
+        handle_http: ROption::RNone,
+    }
+    .leak_into_prefix()
+}
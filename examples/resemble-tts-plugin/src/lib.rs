@@ -10,10 +10,13 @@
 //! - Configurable sample rates and audio formats
 //! - SSML support for prosody control
 //! - Production-grade reliability:
-//!   - Retry logic with exponential backoff
-//!   - Circuit breaker for failure isolation
+//!   - Retry logic with exponential backoff, honoring `Retry-After` on 429/5xx
+//!   - Circuit breaker for failure isolation, opened faster by rate limiting
 //!   - Request timeouts and size limits
-//!   - Lock-free callback invocation
+//!   - Callbacks invoked through `CallbackRegistry`, so swapping them is safe
+//!     while audio is being delivered
+//! - Voice list cached in the gateway-provisioned plugin storage and served
+//!   at `GET /plugins/resemble-tts/voices`
 //!
 //! # Building
 //!
@@ -36,12 +39,13 @@ use abi_stable::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU8, AtomicU32, AtomicU64, Ordering};
-use std::sync::{Mutex, RwLock};
-use std::time::{Duration, Instant};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 use waav_plugin_api::{
-    CompleteCallbackFn, ErrorCallbackFn, FFIAudioData, FFIConfig, PluginCapabilityType,
-    PluginManifest, PluginModule, PluginModule_Ref, ProviderHandle, TTSAudioCallbackFn,
-    TTSProvider, TTSVTable, ffi_err, ffi_ok, ErrorCode,
+    CallbackRegistry, CloneInstance, CompleteCallbackFn, ErrorCallbackFn, FFIAudioData, FFIConfig,
+    FFIHttpRequest, FFIHttpResponse, PluginCapabilityType, PluginInitConfig, PluginManifest,
+    PluginModule, PluginModule_Ref, PluginStorage, ProviderHandle, TTSAudioCallbackFn, TTSProvider,
+    TTSVTable, ffi_err, ffi_ok, ErrorCode, PLUGIN_API_VERSION,
 };
 
 // =============================================================================
@@ -55,10 +59,44 @@ const RESEMBLE_VOICES_URL: &str = "https://app.resemble.ai/api/v2/voices";
 
 /// Default configuration values
 const DEFAULT_SAMPLE_RATE: u32 = 24000;
+
+/// Sample rates accepted by Resemble (24000 is commonly used for TTS)
+const VALID_SAMPLE_RATES: [u32; 7] = [8000, 16000, 22050, 24000, 32000, 44100, 48000];
+
+/// Output format names accepted in the config, before normalization
+const VALID_OUTPUT_FORMATS: [&str; 7] =
+    ["wav", "linear16", "pcm", "pcm_16", "pcm16", "mp3", "mpeg"];
 const DEFAULT_OUTPUT_FORMAT: &str = "wav";
 const DEFAULT_MODEL: &str = "chatterbox";
 const MAX_STREAMING_CHARS: usize = 2000;
 
+// =============================================================================
+// Voice List Cache
+// =============================================================================
+
+/// Storage provisioned by the gateway at init, if any
+static STORAGE: OnceLock<PluginStorage> = OnceLock::new();
+
+/// File in the plugin storage holding the cached voice list
+const VOICES_CACHE_FILE: &str = "voices.json";
+
+/// Age after which the cached voice list is fetched again
+const VOICES_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Voices fetched when refreshing the cache
+const VOICES_PAGE_SIZE: &str = "100";
+
+/// Whether the cached voice list is missing or older than the TTL
+fn voices_cache_stale(storage: &PluginStorage) -> bool {
+    storage
+        .path(VOICES_CACHE_FILE)
+        .and_then(std::fs::metadata)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_none_or(|age| age > VOICES_CACHE_TTL)
+}
+
 // =============================================================================
 // Production Hardening Constants
 // =============================================================================
@@ -79,10 +117,18 @@ const MAX_RETRIES: u32 = 3;
 const INITIAL_RETRY_DELAY_MS: u64 = 100;
 const MAX_RETRY_DELAY_SECS: u64 = 5;
 
+/// Longest server-requested `Retry-After` honored between attempts; longer
+/// hints fail the request instead of blocking the synthesis thread
+const MAX_RETRY_AFTER_SECS: u64 = 10;
+
 /// Circuit breaker configuration
 const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
 const CIRCUIT_BREAKER_RESET_TIMEOUT_SECS: u64 = 30;
 
+/// Failures a rate-limited (429) response counts as, so that two in a row
+/// open the circuit instead of five
+const CIRCUIT_BREAKER_RATE_LIMIT_WEIGHT: u32 = 3;
+
 /// Plugin configuration parsed from JSON
 ///
 /// This config is designed to be compatible with the gateway's TTSConfig format.
@@ -190,6 +236,8 @@ struct CircuitBreaker {
     state: AtomicU8,
     /// Number of successful requests after opening
     success_count: AtomicU32,
+    /// Earliest time (Unix epoch millis) the provider asked to be retried at
+    retry_after_until_ms: AtomicU64,
 }
 
 impl CircuitBreaker {
@@ -200,6 +248,7 @@ impl CircuitBreaker {
             last_failure_ms: AtomicU64::new(0),
             state: AtomicU8::new(CircuitState::Closed as u8),
             success_count: AtomicU32::new(0),
+            retry_after_until_ms: AtomicU64::new(0),
         }
     }
 
@@ -216,9 +265,12 @@ impl CircuitBreaker {
         match self.state.load(Ordering::Acquire) {
             0 => true, // Closed - allow all
             1 => {
-                // Open - check if reset timeout has passed
-                let elapsed_ms = Self::now_ms().saturating_sub(self.last_failure_ms.load(Ordering::Acquire));
-                if elapsed_ms >= CIRCUIT_BREAKER_RESET_TIMEOUT_SECS * 1000 {
+                // Open - check if reset timeout and any Retry-After have passed
+                let now_ms = Self::now_ms();
+                let elapsed_ms = now_ms.saturating_sub(self.last_failure_ms.load(Ordering::Acquire));
+                if elapsed_ms >= CIRCUIT_BREAKER_RESET_TIMEOUT_SECS * 1000
+                    && now_ms >= self.retry_after_until_ms.load(Ordering::Acquire)
+                {
                     // Transition to half-open
                     self.state.store(CircuitState::HalfOpen as u8, Ordering::Release);
                     self.success_count.store(0, Ordering::Release);
@@ -252,7 +304,25 @@ impl CircuitBreaker {
 
     /// Record a failed request
     fn record_failure(&self) {
-        let count = self.failure_count.fetch_add(1, Ordering::AcqRel) + 1;
+        self.record_failures(1);
+    }
+
+    /// Record a rate-limited (429) response
+    ///
+    /// Counts as several failures so sustained throttling opens the circuit
+    /// quickly, and keeps an open circuit closed to requests until the
+    /// provider's `Retry-After` has passed.
+    fn record_rate_limited(&self, retry_after: Option<Duration>) {
+        if let Some(retry_after) = retry_after {
+            let until_ms = Self::now_ms().saturating_add(retry_after.as_millis() as u64);
+            self.retry_after_until_ms.fetch_max(until_ms, Ordering::AcqRel);
+        }
+        self.record_failures(CIRCUIT_BREAKER_RATE_LIMIT_WEIGHT);
+    }
+
+    /// Record a failure that counts as `weight` consecutive failures
+    fn record_failures(&self, weight: u32) {
+        let count = self.failure_count.fetch_add(weight, Ordering::AcqRel) + weight;
         self.last_failure_ms.store(Self::now_ms(), Ordering::Release);
 
         let state = self.state.load(Ordering::Acquire);
@@ -286,6 +356,34 @@ impl CircuitBreaker {
     }
 }
 
+// =============================================================================
+// Retry Classification
+// =============================================================================
+
+/// Whether an error response is worth retrying: throttling, timeouts and
+/// transient server errors
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// How long the server asked us to wait, from `retry-after-ms`,
+/// `Retry-After` (seconds) or `x-ratelimit-reset` (seconds)
+///
+/// Plugins cannot link against the gateway's retry helper, so this mirrors
+/// the parts of it Resemble uses. HTTP-date values fall back to backoff.
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str, scale: f64| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .and_then(|v| Duration::try_from_secs_f64(v * scale).ok())
+    };
+    header("retry-after-ms", 0.001)
+        .or_else(|| header("retry-after", 1.0))
+        .or_else(|| header("x-ratelimit-reset", 1.0))
+}
+
 // =============================================================================
 // Provider State
 // =============================================================================
@@ -325,17 +423,17 @@ struct ResembleState {
     /// Text buffer for batched synthesis (size-limited)
     text_buffer: Mutex<String>,
 
-    /// Audio callback (extracted before invocation to prevent deadlock)
-    audio_callback: Mutex<Option<(TTSAudioCallbackFn, *mut ())>>,
+    /// Audio callback (invoked without holding a lock)
+    audio_callback: CallbackRegistry<TTSAudioCallbackFn>,
 
-    /// Error callback (extracted before invocation to prevent deadlock)
-    error_callback: Mutex<Option<(ErrorCallbackFn, *mut ())>>,
+    /// Error callback (invoked without holding a lock)
+    error_callback: CallbackRegistry<ErrorCallbackFn>,
 
-    /// Completion callback (extracted before invocation to prevent deadlock)
-    complete_callback: Mutex<Option<(CompleteCallbackFn, *mut ())>>,
+    /// Completion callback (invoked without holding a lock)
+    complete_callback: CallbackRegistry<CompleteCallbackFn>,
 
-    /// HTTP client (reused for connection pooling)
-    client: reqwest::blocking::Client,
+    /// HTTP client (reused for connection pooling), shared by cloned instances
+    client: Arc<reqwest::blocking::Client>,
 
     /// Circuit breaker for failure isolation
     circuit_breaker: CircuitBreaker,
@@ -355,6 +453,33 @@ struct ResembleState {
 unsafe impl Send for ResembleState {}
 unsafe impl Sync for ResembleState {}
 
+impl CloneInstance for ResembleState {
+    /// New disconnected instance with the same settings and HTTP client
+    fn clone_instance(&self) -> Self {
+        Self {
+            api_key: self.api_key.clone(),
+            voice_uuid: self.voice_uuid.clone(),
+            model: self.model.clone(),
+            sample_rate: self.sample_rate,
+            output_format: self.output_format.clone(),
+            use_hd: self.use_hd,
+            precision: self.precision.clone(),
+            project_uuid: self.project_uuid.clone(),
+            streaming: self.streaming,
+            connection_state: RwLock::new(ConnectionState::Disconnected),
+            text_buffer: Mutex::new(String::with_capacity(4096)),
+            audio_callback: CallbackRegistry::new(),
+            error_callback: CallbackRegistry::new(),
+            complete_callback: CallbackRegistry::new(),
+            client: Arc::clone(&self.client),
+            circuit_breaker: CircuitBreaker::new(),
+            total_audio_bytes: AtomicU64::new(0),
+            total_requests: AtomicU64::new(0),
+            total_failures: AtomicU64::new(0),
+        }
+    }
+}
+
 impl ResembleState {
     /// Create a new state from configuration
     fn new(config: ResembleConfig) -> Result<Self, String> {
@@ -369,12 +494,10 @@ impl ResembleState {
             .ok_or_else(|| "voice_uuid (or voice_id) is required".to_string())?;
 
         // Validate sample rate
-        // Resemble supports these sample rates (24000 is commonly used for TTS)
-        let valid_rates = [8000, 16000, 22050, 24000, 32000, 44100, 48000];
-        if !valid_rates.contains(&config.sample_rate) {
+        if !VALID_SAMPLE_RATES.contains(&config.sample_rate) {
             return Err(format!(
                 "Invalid sample_rate: {}. Valid values: {:?}",
-                config.sample_rate, valid_rates
+                config.sample_rate, VALID_SAMPLE_RATES
             ));
         }
 
@@ -413,10 +536,10 @@ impl ResembleState {
             streaming: config.streaming,
             connection_state: RwLock::new(ConnectionState::Disconnected),
             text_buffer: Mutex::new(String::with_capacity(4096)), // Pre-allocate reasonable size
-            audio_callback: Mutex::new(None),
-            error_callback: Mutex::new(None),
-            complete_callback: Mutex::new(None),
-            client,
+            audio_callback: CallbackRegistry::new(),
+            error_callback: CallbackRegistry::new(),
+            complete_callback: CallbackRegistry::new(),
+            client: Arc::new(client),
             circuit_breaker: CircuitBreaker::new(),
             total_audio_bytes: AtomicU64::new(0),
             total_requests: AtomicU64::new(0),
@@ -439,45 +562,18 @@ impl ResembleState {
 
     /// Invoke error callback with error code and message
     ///
-    /// CRITICAL: This method extracts callback info before invoking to prevent deadlock.
-    /// The callback is invoked outside the lock scope.
+    /// The registry keeps the gateway's user_data alive until the callback
+    /// returns, even if the callback is replaced meanwhile.
     fn invoke_error_callback(&self, code: ErrorCode, message: &str) {
-        // Extract callback data under the lock
-        let callback_info = {
-            let guard = match self.error_callback.lock() {
-                Ok(g) => g,
-                Err(poisoned) => {
-                    tracing::error!("Error callback mutex poisoned");
-                    poisoned.into_inner()
-                }
-            };
-            guard.clone()
-        };
-        // Invoke callback outside the lock to prevent deadlock
-        if let Some((callback, user_data)) = callback_info {
+        self.error_callback.invoke(|callback, user_data| {
             let msg: RString = message.into();
             (callback.func)(code.as_u32(), &msg, user_data);
-        }
+        });
     }
 
     /// Invoke audio callback with audio data
-    ///
-    /// CRITICAL: This method extracts callback info before invoking to prevent deadlock.
-    /// The callback is invoked outside the lock scope.
     fn invoke_audio_callback(&self, data: &[u8]) {
-        // Extract callback data under the lock
-        let callback_info = {
-            let guard = match self.audio_callback.lock() {
-                Ok(g) => g,
-                Err(poisoned) => {
-                    tracing::error!("Audio callback mutex poisoned");
-                    poisoned.into_inner()
-                }
-            };
-            guard.clone()
-        };
-        // Invoke callback outside the lock to prevent deadlock
-        if let Some((callback, user_data)) = callback_info {
+        self.audio_callback.invoke(|callback, user_data| {
             let audio = FFIAudioData::new(
                 RVec::from_slice(data),
                 self.sample_rate,
@@ -486,38 +582,28 @@ impl ResembleState {
             // Track metrics
             self.total_audio_bytes.fetch_add(data.len() as u64, Ordering::Relaxed);
             (callback.func)(&audio, user_data);
-        }
+        });
     }
 
     /// Invoke completion callback
-    ///
-    /// CRITICAL: This method extracts callback info before invoking to prevent deadlock.
-    /// The callback is invoked outside the lock scope.
     fn invoke_complete_callback(&self) {
-        // Extract callback data under the lock
-        let callback_info = {
-            let guard = match self.complete_callback.lock() {
-                Ok(g) => g,
-                Err(poisoned) => {
-                    tracing::error!("Complete callback mutex poisoned");
-                    poisoned.into_inner()
-                }
-            };
-            guard.clone()
-        };
-        // Invoke callback outside the lock to prevent deadlock
-        if let Some((callback, user_data)) = callback_info {
-            (callback.func)(user_data);
-        }
+        self.complete_callback
+            .invoke(|callback, user_data| (callback.func)(user_data));
     }
 
     /// Execute HTTP request with retry logic and exponential backoff
-    fn send_with_retry<F, T>(&self, mut op: F) -> Result<T, String>
+    ///
+    /// Connection errors and timeouts are retried, as are 429, 408 and
+    /// transient 5xx responses. A `Retry-After` from the server replaces the
+    /// backoff delay up to `MAX_RETRY_AFTER_SECS`. The last error response is
+    /// returned to the caller, which reports it and records the failure.
+    fn send_with_retry<F>(&self, mut op: F) -> Result<reqwest::blocking::Response, String>
     where
-        F: FnMut() -> Result<T, reqwest::Error>,
+        F: FnMut() -> Result<reqwest::blocking::Response, reqwest::Error>,
     {
         let mut delay = Duration::from_millis(INITIAL_RETRY_DELAY_MS);
         let max_delay = Duration::from_secs(MAX_RETRY_DELAY_SECS);
+        let max_retry_after = Duration::from_secs(MAX_RETRY_AFTER_SECS);
 
         for attempt in 0..MAX_RETRIES {
             // Check circuit breaker before each attempt
@@ -526,9 +612,36 @@ impl ResembleState {
             }
 
             match op() {
-                Ok(result) => {
-                    self.circuit_breaker.record_success();
-                    return Ok(result);
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    if response.status().is_success() {
+                        self.circuit_breaker.record_success();
+                        return Ok(response);
+                    }
+                    let retry_after = parse_retry_after(response.headers());
+                    if !is_retryable_status(status)
+                        || attempt == MAX_RETRIES - 1
+                        || retry_after.is_some_and(|wait| wait > max_retry_after)
+                    {
+                        return Ok(response);
+                    }
+
+                    if status == 429 {
+                        self.circuit_breaker.record_rate_limited(retry_after);
+                    } else {
+                        self.circuit_breaker.record_failure();
+                    }
+                    let wait = retry_after.unwrap_or(delay);
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        max_retries = MAX_RETRIES,
+                        status,
+                        delay_ms = wait.as_millis(),
+                        from_retry_after = retry_after.is_some(),
+                        "Retrying request after error response"
+                    );
+                    std::thread::sleep(wait);
+                    delay = delay.saturating_mul(2).min(max_delay);
                 }
                 Err(e) => {
                     let is_retryable = e.is_timeout() || e.is_connect();
@@ -562,6 +675,16 @@ impl ResembleState {
         unreachable!()
     }
 
+    /// Record a failed response with the circuit breaker, weighting 429s
+    fn record_response_failure(&self, status: u16, retry_after: Option<Duration>) {
+        if status == 429 {
+            self.circuit_breaker.record_rate_limited(retry_after);
+        } else {
+            self.circuit_breaker.record_failure();
+        }
+        self.total_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Perform HTTP streaming synthesis with production-grade error handling
     fn synthesize_streaming(&self, text: &str) -> Result<(), String> {
         // Track request
@@ -613,13 +736,19 @@ impl ResembleState {
         // Check response status
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
             let error_text = response.text().unwrap_or_default();
-            self.circuit_breaker.record_failure();
-            self.total_failures.fetch_add(1, Ordering::Relaxed);
+            self.record_response_failure(status.as_u16(), retry_after);
 
             // Handle specific error codes
             if status.as_u16() == 429 {
-                return Err("Rate limited by Resemble API".to_string());
+                return Err(match retry_after {
+                    Some(wait) => format!(
+                        "Rate limited by Resemble API (retry after {}s)",
+                        wait.as_secs()
+                    ),
+                    None => "Rate limited by Resemble API".to_string(),
+                });
             } else if status.as_u16() == 401 {
                 return Err("Invalid API key".to_string());
             }
@@ -723,13 +852,19 @@ impl ResembleState {
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
             let error_text = response.text().unwrap_or_default();
-            self.circuit_breaker.record_failure();
-            self.total_failures.fetch_add(1, Ordering::Relaxed);
+            self.record_response_failure(status.as_u16(), retry_after);
 
             // Handle specific error codes
             if status.as_u16() == 429 {
-                return Err("Rate limited by Resemble API".to_string());
+                return Err(match retry_after {
+                    Some(wait) => format!(
+                        "Rate limited by Resemble API (retry after {}s)",
+                        wait.as_secs()
+                    ),
+                    None => "Rate limited by Resemble API".to_string(),
+                });
             } else if status.as_u16() == 401 {
                 return Err("Invalid API key".to_string());
             }
@@ -807,12 +942,12 @@ extern "C" fn resemble_connect(handle: *mut ProviderHandle) -> RResult<(), RStri
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if handle.is_null() {
             return ffi_err("Invalid handle state");
         }
 
-        let state = handle.as_mut::<ResembleState>();
+        let state = handle.as_ref::<ResembleState>();
 
         // Check current state - prevent concurrent connect attempts
         {
@@ -836,13 +971,20 @@ extern "C" fn resemble_connect(handle: *mut ProviderHandle) -> RResult<(), RStri
         // Set state to connecting
         state.set_connection_state(ConnectionState::Connecting);
 
-        // Test API connection by fetching voices with retry
+        // Test API connection by fetching voices with retry, fetching a
+        // full page when the cached voice list needs refreshing
         let api_key = state.api_key.clone();
+        let storage = STORAGE.get().filter(|storage| voices_cache_stale(storage));
+        let page_size = if storage.is_some() {
+            VOICES_PAGE_SIZE
+        } else {
+            "1"
+        };
         let response = match state.send_with_retry(|| {
             state.client
                 .get(RESEMBLE_VOICES_URL)
                 .header("Authorization", format!("Bearer {}", api_key))
-                .query(&[("page", "1"), ("page_size", "1")])
+                .query(&[("page", "1"), ("page_size", page_size)])
                 .send()
         }) {
             Ok(resp) => resp,
@@ -857,6 +999,9 @@ extern "C" fn resemble_connect(handle: *mut ProviderHandle) -> RResult<(), RStri
         };
 
         if response.status().is_success() {
+            if let Some(storage) = storage {
+                cache_voices(storage, response);
+            }
             state.set_connection_state(ConnectionState::Connected);
             tracing::info!("Connected to Resemble AI service");
             ffi_ok()
@@ -887,12 +1032,12 @@ extern "C" fn resemble_disconnect(handle: *mut ProviderHandle) -> RResult<(), RS
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if handle.is_null() {
             return ffi_err("Invalid handle state");
         }
 
-        let state = handle.as_mut::<ResembleState>();
+        let state = handle.as_ref::<ResembleState>();
 
         // Set state to disconnecting
         state.set_connection_state(ConnectionState::Disconnecting);
@@ -944,12 +1089,12 @@ extern "C" fn resemble_speak(
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if handle.is_null() {
             return ffi_err("Invalid handle state");
         }
 
-        let state = handle.as_mut::<ResembleState>();
+        let state = handle.as_ref::<ResembleState>();
         let text_str = (*text).as_str();
 
         // Check connection
@@ -1036,12 +1181,12 @@ extern "C" fn resemble_clear(handle: *mut ProviderHandle) -> RResult<(), RString
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if handle.is_null() {
             return ffi_err("Invalid handle state");
         }
 
-        let state = handle.as_mut::<ResembleState>();
+        let state = handle.as_ref::<ResembleState>();
 
         // Use proper lock handling
         match state.text_buffer.lock() {
@@ -1060,12 +1205,12 @@ extern "C" fn resemble_flush(handle: *mut ProviderHandle) -> RResult<(), RString
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if handle.is_null() {
             return ffi_err("Invalid handle state");
         }
 
-        let state = handle.as_mut::<ResembleState>();
+        let state = handle.as_ref::<ResembleState>();
 
         // Check connection
         if !state.is_connected() {
@@ -1128,10 +1273,12 @@ extern "C" fn resemble_set_audio_callback(
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if !handle.is_null() {
-            let state = handle.as_mut::<ResembleState>();
-            *state.audio_callback.lock().unwrap() = Some((callback, user_data));
+            let state = handle.as_ref::<ResembleState>();
+            // Waits for in-flight invocations, after which the gateway may
+            // free the previous user_data
+            state.audio_callback.set(callback, user_data);
         }
     }
 }
@@ -1147,10 +1294,10 @@ extern "C" fn resemble_set_error_callback(
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if !handle.is_null() {
-            let state = handle.as_mut::<ResembleState>();
-            *state.error_callback.lock().unwrap() = Some((callback, user_data));
+            let state = handle.as_ref::<ResembleState>();
+            state.error_callback.set(callback, user_data);
         }
     }
 }
@@ -1166,10 +1313,10 @@ extern "C" fn resemble_set_complete_callback(
     }
 
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if !handle.is_null() {
-            let state = handle.as_mut::<ResembleState>();
-            *state.complete_callback.lock().unwrap() = Some((callback, user_data));
+            let state = handle.as_ref::<ResembleState>();
+            state.complete_callback.set(callback, user_data);
         }
     }
 }
@@ -1191,6 +1338,7 @@ extern "C" fn resemble_get_provider_info(handle: *const ProviderHandle) -> RStri
             "connect_timeout_secs": CONNECT_TIMEOUT_SECS,
             "request_timeout_secs": REQUEST_TIMEOUT_SECS,
             "circuit_breaker_threshold": CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            "max_retry_after_secs": MAX_RETRY_AFTER_SECS,
         }
     });
 
@@ -1240,6 +1388,7 @@ extern "C" fn resemble_get_provider_info(handle: *const ProviderHandle) -> RStri
                         "connect_timeout_secs": CONNECT_TIMEOUT_SECS,
                         "request_timeout_secs": REQUEST_TIMEOUT_SECS,
                         "circuit_breaker_threshold": CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+                        "max_retry_after_secs": MAX_RETRY_AFTER_SECS,
                     }
                 });
                 return info.to_string().into();
@@ -1266,6 +1415,7 @@ const RESEMBLE_TTS_VTABLE: TTSVTable = TTSVTable {
     set_error_callback: resemble_set_error_callback,
     set_complete_callback: resemble_set_complete_callback,
     get_provider_info: resemble_get_provider_info,
+    set_timing_callback: ROption::RNone,
 };
 
 // =============================================================================
@@ -1297,8 +1447,9 @@ fn create_tts(config: *const FFIConfig) -> RResult<TTSProvider, RString> {
         }
     };
 
-    // Wrap in handle
-    let handle = ProviderHandle::new(state);
+    // Instances the gateway clones with `try_clone` get their own state but
+    // reuse the HTTP client and its connection pool
+    let handle = ProviderHandle::new_cloneable(state);
 
     RResult::ROk(TTSProvider {
         handle,
@@ -1319,12 +1470,83 @@ fn get_manifest() -> PluginManifest {
         )
 }
 
+/// Config constraints the gateway checks before calling `create_tts`
+#[sabi_extern_fn]
+fn provider_capabilities() -> RString {
+    serde_json::json!({
+        "tts": {
+            "sample_rates": VALID_SAMPLE_RATES,
+            "encodings": VALID_OUTPUT_FORMATS,
+            "requires_api_key": true
+        }
+    })
+    .to_string()
+    .into()
+}
+
+/// Save a voices response to the plugin storage
+///
+/// Caching is best effort: a failure only means the list is fetched again on
+/// the next connect.
+fn cache_voices(storage: &PluginStorage, response: reqwest::blocking::Response) {
+    let result = response
+        .bytes()
+        .map_err(|e| e.to_string())
+        .and_then(|body| {
+            storage
+                .write(VOICES_CACHE_FILE, &body)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "Failed to cache Resemble voice list");
+    }
+}
+
+/// Serve requests to `/plugins/resemble-tts/...`
+///
+/// `GET /voices` returns the voice list cached by the last connect that
+/// refreshed it, as returned by the Resemble API.
+#[sabi_extern_fn]
+fn handle_http(request: *const FFIHttpRequest) -> RResult<FFIHttpResponse, RString> {
+    if request.is_null() {
+        return RResult::RErr("Null request".into());
+    }
+    let request = unsafe { &*request };
+
+    let response = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/voices") => match STORAGE.get().map(|storage| storage.read(VOICES_CACHE_FILE)) {
+            Some(Ok(voices)) => FFIHttpResponse::new(200)
+                .with_header("content-type", "application/json")
+                .with_body(voices),
+            Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => {
+                return RResult::RErr(format!("Failed to read cached voices: {}", e).into());
+            }
+            _ => FFIHttpResponse::json(
+                404,
+                r#"{"error": "No voice list cached yet; it is fetched when a session connects"}"#,
+            ),
+        },
+        (_, "/voices") => FFIHttpResponse::new(405),
+        _ => FFIHttpResponse::new(404),
+    };
+    RResult::ROk(response)
+}
+
 /// Initialize the plugin
 #[sabi_extern_fn]
-fn init(_config: *const FFIConfig) -> RResult<(), RString> {
-    // Plugin-level initialization
-    // Currently no global state to initialize
-    tracing::info!("Resemble AI TTS plugin initialized");
+fn init(config: *const FFIConfig) -> RResult<(), RString> {
+    // Keep the voice list cache in the storage the gateway provisioned;
+    // without one the plugin writes nothing to disk
+    match unsafe { PluginInitConfig::from_ffi(config) }.storage() {
+        Some(storage) => {
+            tracing::info!(
+                storage_dir = %storage.dir().display(),
+                "Resemble AI TTS plugin initialized"
+            );
+            let _ = STORAGE.set(storage);
+        }
+        None => tracing::info!("Resemble AI TTS plugin initialized without storage"),
+    }
     ffi_ok()
 }
 
@@ -1350,6 +1572,9 @@ fn get_root_module() -> PluginModule_Ref {
         create_stt: ROption::RNone,
         create_tts: ROption::RSome(create_tts),
         create_realtime: ROption::RNone,
+        abi_version: PLUGIN_API_VERSION,
+        provider_capabilities: ROption::RSome(provider_capabilities),
+        handle_http: ROption::RSome(handle_http),
     }
     .leak_into_prefix()
 }
@@ -1504,6 +1729,33 @@ mod tests {
         assert!(err.contains("output_format"), "Expected 'output_format' in error: {}", err);
     }
 
+    #[test]
+    fn test_cloned_providers_share_http_client() {
+        let config = FFIConfig::from_json(r#"{"api_key": "test_key", "voice_uuid": "test_voice"}"#);
+        let provider = match create_tts(&config) {
+            RResult::ROk(provider) => provider,
+            RResult::RErr(e) => panic!("create_tts failed: {}", e),
+        };
+        let clone = provider.handle.try_clone().expect("Resemble handles are cloneable");
+
+        unsafe {
+            let original = provider.handle.as_ref::<ResembleState>();
+            let cloned = clone.as_ref::<ResembleState>();
+            assert!(!std::ptr::eq(original, cloned));
+            assert!(Arc::ptr_eq(&original.client, &cloned.client));
+            assert_eq!(cloned.voice_uuid, "test_voice");
+
+            // Connection state is per instance
+            original.set_connection_state(ConnectionState::Connected);
+            assert!(!cloned.is_connected());
+        }
+
+        // The clone keeps the client after the original is dropped
+        drop(provider);
+        let cloned = unsafe { clone.as_ref::<ResembleState>() };
+        assert_eq!(Arc::strong_count(&cloned.client), 1);
+    }
+
     #[test]
     fn test_manifest() {
         let manifest = get_manifest();
@@ -1513,6 +1765,38 @@ mod tests {
         assert_eq!(manifest.capabilities[0], PluginCapabilityType::TTS);
     }
 
+    #[test]
+    fn test_voices_cache_staleness() {
+        let dir = std::env::temp_dir().join(format!("resemble-voices-{}", std::process::id()));
+        let storage = PluginStorage::new(&dir, Some(1024 * 1024));
+        assert!(voices_cache_stale(&storage));
+
+        storage
+            .write(VOICES_CACHE_FILE, br#"{"items": []}"#)
+            .unwrap();
+        assert!(!voices_cache_stale(&storage));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_voices_route_without_cache() {
+        let request = FFIHttpRequest {
+            method: "GET".into(),
+            path: "/voices".into(),
+            query: "".into(),
+            headers: RVec::new(),
+            body: RVec::new(),
+        };
+        let response = handle_http(&request).unwrap();
+        assert_eq!(response.status, 404);
+
+        let request = FFIHttpRequest {
+            method: "DELETE".into(),
+            ..request
+        };
+        assert_eq!(handle_http(&request).unwrap().status, 405);
+    }
+
     #[test]
     fn test_provider_info() {
         let info = resemble_get_provider_info(std::ptr::null());
@@ -1565,6 +1849,66 @@ mod tests {
         assert!(cb.is_allowed());
     }
 
+    #[test]
+    fn test_circuit_breaker_opens_faster_when_rate_limited() {
+        let cb = CircuitBreaker::new();
+
+        cb.record_rate_limited(None);
+        assert_eq!(cb.get_state(), "closed");
+        cb.record_rate_limited(None);
+        assert_eq!(cb.get_state(), "open");
+    }
+
+    #[test]
+    fn test_circuit_breaker_stays_open_for_retry_after() {
+        let cb = CircuitBreaker::new();
+        cb.record_rate_limited(Some(Duration::from_secs(3600)));
+        cb.record_rate_limited(None);
+
+        // Pretend the reset timeout has long passed; Retry-After still holds
+        cb.last_failure_ms.store(0, Ordering::Release);
+        assert!(!cb.is_allowed());
+
+        cb.retry_after_until_ms.store(0, Ordering::Release);
+        assert!(cb.is_allowed());
+        assert_eq!(cb.get_state(), "half-open");
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        for status in [408, 429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(status), "{status} should be retried");
+        }
+        for status in [400, 401, 403, 404, 422, 501] {
+            assert!(!is_retryable_status(status), "{status} should not be retried");
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("7"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("250"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_millis(250)));
+
+        // HTTP dates are not parsed here and fall back to backoff
+        let mut dated = HeaderMap::new();
+        dated.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(parse_retry_after(&dated), None);
+    }
+
     #[test]
     fn test_connection_state_default() {
         assert_eq!(ConnectionState::default(), ConnectionState::Disconnected);
@@ -26,6 +26,20 @@
 //!      plugin_dir: /opt/waav/plugins
 //!    ```
 //! 4. Restart the gateway
+//!
+//! # Simulated Connection Loss
+//!
+//! With `"model": "flaky"` in the STT config, the provider loses its (mock)
+//! upstream connection once after 40000 bytes of audio and reports it with the
+//! recoverable `NetworkError` code. The gateway reconnects it and calls
+//! `notify_recovered`, which resumes the transcript where the lost stream
+//! ended instead of starting over.
+//!
+//! # HTTP Routes
+//!
+//! The plugin also serves a small REST surface under `/plugins/test-stt`:
+//! - `GET /plugins/test-stt/voices` - list the (mock) voices
+//! - `POST /plugins/test-stt/cache/flush` - flush the (mock) cache
 
 use abi_stable::{
     export_root_module,
@@ -34,22 +48,32 @@ use abi_stable::{
     std_types::{ROption, RResult, RString},
 };
 use waav_plugin_api::{
-    ErrorCallbackFn, FFIConfig, FFISTTResult, PluginCapabilityType, PluginManifest,
+    CallbackRegistry, ErrorCallbackFn, ErrorCode, FFIConfig, FFIHttpRequest, FFIHttpResponse,
+    FFISTTResult, PluginCapabilityType, PluginManifest,
     PluginModule, PluginModule_Ref, ProviderHandle, STTProvider, STTResultCallbackFn,
-    STTVTable, ffi_ok, ffi_err,
+    STTVTable, ffi_ok, ffi_err, PLUGIN_API_VERSION,
 };
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
+/// Audio after which a `flaky` provider loses its upstream connection
+const FLAKY_DROP_AFTER_BYTES: u64 = 40_000;
+
 /// Plugin state stored in the ProviderHandle
 struct TestSTTState {
     /// Whether the provider is connected
     connected: AtomicBool,
-    /// Audio bytes received counter
+    /// Audio bytes received on the current upstream stream
     bytes_received: AtomicU64,
+    /// Lose the upstream connection once, after this much audio
+    drop_after: Option<u64>,
+    /// Whether the connection was already lost
+    dropped: AtomicBool,
+    /// Audio of the lost stream, resumed by `notify_recovered`
+    resume_offset: AtomicU64,
     /// Result callback
-    result_callback: Option<(STTResultCallbackFn, *mut ())>,
+    result_callback: CallbackRegistry<STTResultCallbackFn>,
     /// Error callback
-    error_callback: Option<(ErrorCallbackFn, *mut ())>,
+    error_callback: CallbackRegistry<ErrorCallbackFn>,
 }
 
 impl Default for TestSTTState {
@@ -57,8 +81,11 @@ impl Default for TestSTTState {
         Self {
             connected: AtomicBool::new(false),
             bytes_received: AtomicU64::new(0),
-            result_callback: None,
-            error_callback: None,
+            drop_after: None,
+            dropped: AtomicBool::new(false),
+            resume_offset: AtomicU64::new(0),
+            result_callback: CallbackRegistry::new(),
+            error_callback: CallbackRegistry::new(),
         }
     }
 }
@@ -75,6 +102,8 @@ extern "C" fn test_stt_connect(handle: *mut ProviderHandle) -> RResult<(), RStri
             return ffi_err("Invalid handle state");
         }
         let state = handle.as_mut::<TestSTTState>();
+        // A new upstream stream starts counting from zero
+        state.bytes_received.store(0, Ordering::SeqCst);
         state.connected.store(true, Ordering::SeqCst);
     }
     ffi_ok()
@@ -125,6 +154,9 @@ extern "C" fn test_stt_send_audio(
         }
 
         let state = handle.as_mut::<TestSTTState>();
+        if !state.connected.load(Ordering::SeqCst) {
+            return ffi_err("Not connected");
+        }
 
         // Update bytes received counter
         let prev = state.bytes_received.fetch_add(audio_len as u64, Ordering::SeqCst);
@@ -132,22 +164,54 @@ extern "C" fn test_stt_send_audio(
 
         // Generate a mock transcript every 16000 bytes (about 1 second of 16kHz audio)
         if total / 16000 > prev / 16000 {
-            if let Some((callback_fn, user_data)) = &state.result_callback {
-                let transcript = format!("Test transcript at {} bytes", total);
-                let result = FFISTTResult {
-                    transcript: transcript.into(),
-                    is_final: false,
-                    is_speech_final: false,
-                    confidence: 0.95,
-                };
-                (callback_fn.func)(&result as *const _, *user_data);
-            }
+            let transcript = format!("Test transcript at {} bytes", total);
+            let result = FFISTTResult {
+                transcript: transcript.into(),
+                is_final: false,
+                is_speech_final: false,
+                confidence: 0.95,
+            };
+            state.result_callback.invoke(|callback_fn, user_data| {
+                (callback_fn.func)(&result as *const _, user_data)
+            });
+        }
+
+        // Simulate losing the upstream connection
+        if state.drop_after.is_some_and(|limit| total >= limit)
+            && !state.dropped.swap(true, Ordering::SeqCst)
+        {
+            state.resume_offset.store(total, Ordering::SeqCst);
+            state.connected.store(false, Ordering::SeqCst);
+            let message: RString = "Upstream connection lost".into();
+            state.error_callback.invoke(|callback_fn, user_data| {
+                (callback_fn.func)(ErrorCode::NetworkError.as_u32(), &message, user_data)
+            });
         }
     }
 
     ffi_ok()
 }
 
+extern "C" fn test_stt_notify_recovered(
+    handle: *mut ProviderHandle,
+    _buffered_len: usize,
+) -> RResult<(), RString> {
+    if handle.is_null() {
+        return ffi_err("Null handle");
+    }
+    unsafe {
+        let handle = &mut *handle;
+        if handle.is_null() {
+            return ffi_err("Invalid handle state");
+        }
+        let state = handle.as_mut::<TestSTTState>();
+        // Continue the lost stream instead of starting a new one
+        let offset = state.resume_offset.load(Ordering::SeqCst);
+        state.bytes_received.store(offset, Ordering::SeqCst);
+    }
+    ffi_ok()
+}
+
 extern "C" fn test_stt_set_result_callback(
     handle: *mut ProviderHandle,
     callback: STTResultCallbackFn,
@@ -157,10 +221,11 @@ extern "C" fn test_stt_set_result_callback(
         return;
     }
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if !handle.is_null() {
-            let state = handle.as_mut::<TestSTTState>();
-            state.result_callback = Some((callback, user_data));
+            let state = handle.as_ref::<TestSTTState>();
+            // Returns once no invocation still uses the previous user_data
+            state.result_callback.set(callback, user_data);
         }
     }
 }
@@ -174,10 +239,10 @@ extern "C" fn test_stt_set_error_callback(
         return;
     }
     unsafe {
-        let handle = &mut *handle;
+        let handle = &*handle;
         if !handle.is_null() {
-            let state = handle.as_mut::<TestSTTState>();
-            state.error_callback = Some((callback, user_data));
+            let state = handle.as_ref::<TestSTTState>();
+            state.error_callback.set(callback, user_data);
         }
     }
 }
@@ -197,13 +262,26 @@ const TEST_STT_VTABLE: STTVTable = STTVTable {
     set_result_callback: test_stt_set_result_callback,
     set_error_callback: test_stt_set_error_callback,
     get_provider_info: test_stt_get_provider_info,
+    notify_recovered: ROption::RSome(test_stt_notify_recovered),
 };
 
 /// Factory function to create an STT provider
 #[sabi_extern_fn]
-fn create_stt(_config: *const FFIConfig) -> RResult<STTProvider, RString> {
+fn create_stt(config: *const FFIConfig) -> RResult<STTProvider, RString> {
+    let model = if config.is_null() {
+        None
+    } else {
+        let config = unsafe { &*config };
+        serde_json::from_str::<serde_json::Value>(config.as_str())
+            .ok()
+            .and_then(|config| config["model"].as_str().map(str::to_string))
+    };
+
     // Create state using ProviderHandle::new which sets up automatic cleanup
-    let state = TestSTTState::default();
+    let state = TestSTTState {
+        drop_after: (model.as_deref() == Some("flaky")).then_some(FLAKY_DROP_AFTER_BYTES),
+        ..Default::default()
+    };
     let handle = ProviderHandle::new(state);
 
     RResult::ROk(STTProvider {
@@ -212,6 +290,35 @@ fn create_stt(_config: *const FFIConfig) -> RResult<STTProvider, RString> {
     })
 }
 
+/// Number of times the (mock) cache has been flushed
+static CACHE_FLUSHES: AtomicU64 = AtomicU64::new(0);
+
+/// Serve requests to `/plugins/test-stt/...`
+#[sabi_extern_fn]
+fn handle_http(request: *const FFIHttpRequest) -> RResult<FFIHttpResponse, RString> {
+    if request.is_null() {
+        return RResult::RErr("Null request".into());
+    }
+    let request = unsafe { &*request };
+
+    let response = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/voices") => FFIHttpResponse::json(
+            200,
+            r#"{"voices": [{"id": "test-voice", "name": "Test Voice", "language": "en-US"}]}"#,
+        ),
+        ("POST", "/cache/flush") => {
+            let flushes = CACHE_FLUSHES.fetch_add(1, Ordering::SeqCst) + 1;
+            FFIHttpResponse::json(
+                200,
+                format!(r#"{{"flushed": true, "flushes": {}}}"#, flushes),
+            )
+        }
+        (_, "/voices") | (_, "/cache/flush") => FFIHttpResponse::new(405),
+        _ => FFIHttpResponse::new(404),
+    };
+    RResult::ROk(response)
+}
+
 /// Return the plugin manifest
 #[sabi_extern_fn]
 fn get_manifest() -> PluginManifest {
@@ -246,6 +353,9 @@ fn get_root_module() -> PluginModule_Ref {
         create_stt: ROption::RSome(create_stt),
         create_tts: ROption::RNone,
         create_realtime: ROption::RNone,
+        abi_version: PLUGIN_API_VERSION,
+        provider_capabilities: ROption::RNone,
+        handle_http: ROption::RSome(handle_http),
     }
     .leak_into_prefix()
 }
@@ -0,0 +1,26 @@
+// Converts microphone samples to 16-bit PCM and posts them in 100ms chunks
+class CaptureProcessor extends AudioWorkletProcessor {
+  constructor() {
+    super();
+    this.chunk = new Int16Array(Math.round(sampleRate / 10));
+    this.length = 0;
+  }
+
+  process(inputs) {
+    const channel = inputs[0] && inputs[0][0];
+    if (!channel) {
+      return true;
+    }
+    for (let i = 0; i < channel.length; i++) {
+      const sample = Math.max(-1, Math.min(1, channel[i]));
+      this.chunk[this.length++] = sample < 0 ? sample * 0x8000 : sample * 0x7fff;
+      if (this.length === this.chunk.length) {
+        this.port.postMessage(this.chunk.buffer.slice(0));
+        this.length = 0;
+      }
+    }
+    return true;
+  }
+}
+
+registerProcessor("capture-processor", CaptureProcessor);
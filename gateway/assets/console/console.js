@@ -0,0 +1,250 @@
+// WaaV Gateway development console
+//
+// A plain client of the public /ws protocol: it sends the config message,
+// streams microphone audio as 16-bit PCM, plays linear16 TTS audio and shows
+// every message the server sends.
+(() => {
+  "use strict";
+
+  const DEFAULT_CONFIG = {
+    type: "config",
+    audio: true,
+    stt_config: {
+      provider: "deepgram",
+      language: "en-US",
+      sample_rate: 16000,
+      channels: 1,
+      punctuation: true,
+      encoding: "linear16",
+      model: "nova-2",
+    },
+    tts_config: {
+      provider: "deepgram",
+      model: "aura-asteria-en",
+      voice_id: "aura-asteria-en",
+      audio_format: "linear16",
+      sample_rate: 24000,
+    },
+  };
+
+  const $ = (id) => document.getElementById(id);
+  const ui = {
+    status: $("status"),
+    token: $("token"),
+    config: $("config"),
+    connect: $("connect"),
+    disconnect: $("disconnect"),
+    mic: $("mic"),
+    interrupt: $("interrupt"),
+    speakText: $("speak-text"),
+    speak: $("speak"),
+    transcript: $("transcript"),
+    interim: $("interim"),
+    events: $("events"),
+    clearEvents: $("clear-events"),
+  };
+
+  let socket = null;
+  let sessionConfig = null;
+  let capture = null;
+  let playback = null;
+
+  ui.config.value = JSON.stringify(DEFAULT_CONFIG, null, 2);
+
+  function setStatus(status) {
+    ui.status.textContent = status;
+    ui.status.className = "status " + status;
+    const open = status !== "disconnected";
+    const ready = status === "ready";
+    ui.connect.disabled = open;
+    ui.disconnect.disabled = !open;
+    ui.mic.disabled = !ready;
+    ui.speak.disabled = !ready;
+    ui.interrupt.disabled = !ready;
+  }
+
+  function logEvent(text, kind) {
+    const item = document.createElement("li");
+    item.textContent = new Date().toLocaleTimeString() + " " + text;
+    if (kind) {
+      item.className = kind;
+    }
+    ui.events.appendChild(item);
+    ui.events.scrollTop = ui.events.scrollHeight;
+  }
+
+  function send(message) {
+    if (!socket || socket.readyState !== WebSocket.OPEN) {
+      return;
+    }
+    socket.send(JSON.stringify(message));
+    logEvent("→ " + JSON.stringify(message), "sent");
+  }
+
+  function connect() {
+    try {
+      sessionConfig = JSON.parse(ui.config.value);
+    } catch (e) {
+      logEvent("Config is not valid JSON: " + e.message, "error");
+      return;
+    }
+
+    const scheme = location.protocol === "https:" ? "wss:" : "ws:";
+    const url = new URL(scheme + "//" + location.host + "/ws");
+    if (ui.token.value) {
+      url.searchParams.set("token", ui.token.value);
+    }
+
+    socket = new WebSocket(url);
+    socket.binaryType = "arraybuffer";
+    setStatus("connecting");
+
+    socket.onopen = () => {
+      setStatus("connected");
+      send(sessionConfig);
+    };
+    socket.onmessage = (event) => {
+      if (event.data instanceof ArrayBuffer) {
+        playAudio(event.data);
+      } else {
+        handleMessage(JSON.parse(event.data));
+      }
+    };
+    socket.onclose = (event) => {
+      logEvent("Connection closed (" + event.code + (event.reason ? " " + event.reason : "") + ")", "error");
+      stopMicrophone();
+      socket = null;
+      setStatus("disconnected");
+    };
+  }
+
+  function handleMessage(message) {
+    switch (message.type) {
+      case "ready":
+        setStatus("ready");
+        break;
+      case "stt_result":
+        showTranscript(message);
+        break;
+      case "ping":
+        // Only sent when the config asks for JSON heartbeats
+        send({ type: "pong", ts: message.ts });
+        return;
+      case "audio_level":
+        // Too frequent to log
+        return;
+    }
+    logEvent("← " + JSON.stringify(message), message.type === "error" ? "error" : "");
+  }
+
+  function showTranscript(result) {
+    if (!result.is_final) {
+      ui.interim.textContent = result.transcript;
+      return;
+    }
+    ui.interim.textContent = "";
+    if (!result.transcript) {
+      return;
+    }
+    const line = document.createElement("p");
+    line.textContent = result.transcript;
+    ui.transcript.insertBefore(line, ui.interim);
+    ui.transcript.scrollTop = ui.transcript.scrollHeight;
+  }
+
+  async function startMicrophone() {
+    const stt = sessionConfig.stt_config || {};
+    if ((stt.encoding || "linear16") !== "linear16" || (stt.channels || 1) !== 1) {
+      logEvent("The console only captures mono linear16 audio", "error");
+      return;
+    }
+    const context = new AudioContext({ sampleRate: stt.sample_rate || 16000 });
+    const stream = await navigator.mediaDevices.getUserMedia({
+      audio: { channelCount: 1, echoCancellation: true, noiseSuppression: true },
+    });
+    await context.audioWorklet.addModule("/console/capture-worklet.js");
+    const source = context.createMediaStreamSource(stream);
+    const processor = new AudioWorkletNode(context, "capture-processor");
+    processor.port.onmessage = (event) => {
+      if (socket && socket.readyState === WebSocket.OPEN) {
+        socket.send(event.data);
+      }
+    };
+    source.connect(processor);
+    capture = { context, stream };
+    ui.mic.textContent = "Stop microphone";
+    ui.mic.classList.add("active");
+  }
+
+  function stopMicrophone() {
+    if (!capture) {
+      return;
+    }
+    capture.stream.getTracks().forEach((track) => track.stop());
+    capture.context.close();
+    capture = null;
+    ui.mic.textContent = "Start microphone";
+    ui.mic.classList.remove("active");
+  }
+
+  function playAudio(data) {
+    const tts = sessionConfig.tts_config || {};
+    if ((tts.audio_format || "linear16") !== "linear16") {
+      return;
+    }
+    const sampleRate = tts.sample_rate || 24000;
+    if (!playback) {
+      playback = { context: new AudioContext({ sampleRate }), next: 0, sources: [] };
+    }
+    const samples = new Int16Array(data, 0, Math.floor(data.byteLength / 2));
+    const buffer = playback.context.createBuffer(1, samples.length, sampleRate);
+    const channel = buffer.getChannelData(0);
+    for (let i = 0; i < samples.length; i++) {
+      channel[i] = samples[i] / 0x8000;
+    }
+    const source = playback.context.createBufferSource();
+    source.buffer = buffer;
+    source.connect(playback.context.destination);
+    const start = Math.max(playback.context.currentTime, playback.next);
+    source.start(start);
+    playback.next = start + buffer.duration;
+    playback.sources.push(source);
+    source.onended = () => {
+      playback.sources = playback.sources.filter((playing) => playing !== source);
+    };
+  }
+
+  function stopPlayback() {
+    if (!playback) {
+      return;
+    }
+    playback.sources.forEach((source) => source.stop());
+    playback.sources = [];
+    playback.next = 0;
+  }
+
+  ui.connect.onclick = connect;
+  ui.disconnect.onclick = () => socket && socket.close(1000, "console disconnect");
+  ui.mic.onclick = () => {
+    if (capture) {
+      stopMicrophone();
+    } else {
+      startMicrophone().catch((e) => logEvent("Microphone: " + e.message, "error"));
+    }
+  };
+  ui.speak.onclick = () => {
+    const text = ui.speakText.value.trim();
+    if (text) {
+      send({ type: "speak", text });
+    }
+  };
+  ui.interrupt.onclick = () => {
+    stopPlayback();
+    send({ type: "clear" });
+  };
+  ui.clearEvents.onclick = () => {
+    ui.events.textContent = "";
+  };
+
+  setStatus("disconnected");
+})();
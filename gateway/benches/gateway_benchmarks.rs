@@ -165,6 +165,8 @@ fn bench_message_serialization(c: &mut Criterion) {
         is_final: true,
         is_speech_final: true,
         confidence: 0.95,
+        redactions: Vec::new(),
+        timing: None,
     };
 
     // Error message
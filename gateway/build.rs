@@ -0,0 +1,43 @@
+//! Embeds the git commit and rustc version reported by `GET /version`
+//!
+//! Builds without a git checkout (e.g. the Docker image) can pass the commit
+//! in the `WAAV_GIT_COMMIT` environment variable; otherwise it is `unknown`.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=WAAV_GIT_COMMIT");
+
+    let git_commit = env::var("WAAV_GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.trim().is_empty())
+        .or_else(git_commit)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=WAAV_GIT_COMMIT={}", git_commit.trim());
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version =
+        command_output(Command::new(rustc).arg("--version")).unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=WAAV_RUSTC_VERSION={rustc_version}");
+}
+
+/// Short hash of the checked out commit, rebuilding when it changes
+fn git_commit() -> Option<String> {
+    let git_dir = command_output(Command::new("git").args(["rev-parse", "--absolute-git-dir"]))?;
+    // HEAD changes on checkout, its reflog on every commit
+    println!("cargo:rerun-if-changed={git_dir}/HEAD");
+    println!("cargo:rerun-if-changed={git_dir}/logs/HEAD");
+
+    command_output(Command::new("git").args(["rev-parse", "--short=12", "HEAD"]))
+}
+
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let stdout = stdout.trim();
+    (!stdout.is_empty()).then(|| stdout.to_string())
+}
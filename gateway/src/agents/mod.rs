@@ -0,0 +1,23 @@
+//! # Agent Profiles
+//!
+//! An agent profile is a named bundle of session configuration: STT/TTS
+//! providers, language, greeting, LiveKit, DAG and LLM bridge settings for
+//! `/ws`, or the provider settings for `/realtime`. Clients configure a
+//! session with `{"type": "config", "agent": "support-bot-en"}` instead of
+//! sending the full configuration, optionally with field-level `overrides`
+//! the profile allows.
+//!
+//! Profiles come from the `agents:` YAML section and from the
+//! `/admin/agents` API; see [`AgentProfile`] for how a config message is
+//! resolved and [`AgentProfileStore`] for how the two sources combine.
+
+mod profile;
+mod store;
+
+pub use profile::{
+    AgentProfile, AgentProfileError, AgentSection, MAX_AGENT_NAME_LENGTH, REALTIME_FIELDS,
+    SESSION_FIELDS,
+};
+pub use store::{
+    AGENTS_CACHE_FILE, AgentProfileStore, AgentStoreError, read_agents_cache, write_agents_cache,
+};
@@ -0,0 +1,515 @@
+//! Agent profiles and how a config message is resolved against one
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// Maximum length of an agent profile name
+pub const MAX_AGENT_NAME_LENGTH: usize = 64;
+
+/// Fields of the `/ws` config message a profile can set
+pub const SESSION_FIELDS: &[&str] = &[
+    "audio",
+    "stt_config",
+    "tts_config",
+    "livekit",
+    "dag_config",
+    "agent_config",
+    "greeting",
+    "metadata",
+];
+
+/// Fields of the `/realtime` config message a profile can set
+pub const REALTIME_FIELDS: &[&str] = &[
+    "provider",
+    "model",
+    "voice",
+    "instructions",
+    "temperature",
+    "max_response_tokens",
+    "turn_detection",
+    "tools",
+    "modalities",
+    "transcribe_input",
+    "transcription_model",
+    "input_audio_format",
+    "output_audio_format",
+    "metadata",
+];
+
+/// Override path clients may always set: their own session metadata
+const METADATA_FIELD: &str = "metadata";
+
+/// Errors raised while validating or resolving agent profiles
+#[derive(Debug, Error, PartialEq)]
+pub enum AgentProfileError {
+    #[error("Unknown agent '{name}'. Available agents: {}", format_names(.available))]
+    UnknownAgent {
+        name: String,
+        /// Profiles visible to the requesting client
+        available: Vec<String>,
+    },
+
+    #[error("Agent '{agent}' has no {section} configuration")]
+    MissingSection {
+        agent: String,
+        section: AgentSection,
+    },
+
+    #[error(
+        "Field '{field}' cannot be overridden for agent '{agent}'. Overridable fields: {}",
+        format_names(.overridable)
+    )]
+    OverrideNotAllowed {
+        agent: String,
+        field: String,
+        overridable: Vec<String>,
+    },
+
+    #[error("Field '{field}' cannot be set alongside 'agent'; use 'overrides' instead")]
+    FieldWithAgent { field: String },
+
+    #[error("Invalid agent profile: {0}")]
+    Invalid(String),
+
+    #[error("Agent '{0}' is defined in the application config and cannot be changed")]
+    Protected(String),
+}
+
+fn format_names(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Which config message a profile section applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentSection {
+    /// The `/ws` config message
+    Session,
+    /// The `/realtime` config message
+    Realtime,
+}
+
+impl AgentSection {
+    /// Config message fields the section can set
+    pub fn fields(&self) -> &'static [&'static str] {
+        match self {
+            Self::Session => SESSION_FIELDS,
+            Self::Realtime => REALTIME_FIELDS,
+        }
+    }
+}
+
+impl std::fmt::Display for AgentSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Session => "session",
+            Self::Realtime => "realtime",
+        })
+    }
+}
+
+/// A named bundle of session configuration
+///
+/// Clients send `{"type": "config", "agent": "support-bot-en"}` instead of
+/// repeating the full configuration. A config message is resolved in order:
+///
+/// 1. The profile's `session` (for `/ws`) or `realtime` (for `/realtime`) fields
+/// 2. The client's `overrides`, applied as a JSON merge patch (objects merge
+///    field by field, `null` removes a field). Every overridden field must be
+///    listed in `overridable`, or sit under a listed parent
+/// 3. The client's `metadata`, merged over the profile metadata
+/// 4. Server defaults (API keys, default greeting) as for any config message
+///
+/// # Example YAML
+/// ```yaml
+/// agents:
+///   - name: support-bot-en
+///     description: "English support line"
+///     clients: ["project1"]
+///     overridable: ["tts_config.voice_id", "stt_config.language"]
+///     session:
+///       stt_config:
+///         provider: deepgram
+///         language: en-US
+///         sample_rate: 16000
+///         channels: 1
+///         punctuation: true
+///         encoding: linear16
+///         model: nova-3
+///       tts_config:
+///         provider: elevenlabs
+///         voice_id: "21m00Tcm4TlvDq8ikWAM"
+///         model: eleven_turbo_v2
+///       greeting:
+///         text: "Hi, thanks for calling. How can I help?"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AgentProfile {
+    /// Name clients send as `agent` (letters, digits, `-`, `_` and `.`)
+    #[cfg_attr(feature = "openapi", schema(example = "support-bot-en"))]
+    pub name: String,
+    /// Human-readable description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Auth client IDs that may use the profile; empty means every client
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clients: Vec<String>,
+    /// Fields clients may override, as dotted paths (`tts_config.voice_id`).
+    /// A parent path (`tts_config`) allows every field below it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overridable: Vec<String>,
+    /// Fields of the `/ws` config message (`stt_config`, `tts_config`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub session: Option<Map<String, Value>>,
+    /// Fields of the `/realtime` config message (`provider`, `model`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub realtime: Option<Map<String, Value>>,
+}
+
+impl AgentProfile {
+    /// Validate the profile's name, sections and overridable paths
+    ///
+    /// Field values are checked when a session is configured from the
+    /// profile, the same way as a config message sent in full.
+    pub fn validate(&self) -> Result<(), AgentProfileError> {
+        if self.name.is_empty() || self.name.len() > MAX_AGENT_NAME_LENGTH {
+            return Err(AgentProfileError::Invalid(format!(
+                "name must be 1-{MAX_AGENT_NAME_LENGTH} characters long"
+            )));
+        }
+        if !self
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(AgentProfileError::Invalid(format!(
+                "name '{}' may only contain letters, digits, '-', '_' and '.'",
+                self.name
+            )));
+        }
+        if self.session.is_none() && self.realtime.is_none() {
+            return Err(AgentProfileError::Invalid(format!(
+                "agent '{}' needs a session or realtime section",
+                self.name
+            )));
+        }
+
+        for (section, fields) in [
+            (AgentSection::Session, &self.session),
+            (AgentSection::Realtime, &self.realtime),
+        ] {
+            if let Some(fields) = fields
+                && let Some(field) = fields
+                    .keys()
+                    .find(|k| !section.fields().contains(&k.as_str()))
+            {
+                return Err(AgentProfileError::Invalid(format!(
+                    "agent '{}' sets unknown {section} field '{field}'",
+                    self.name
+                )));
+            }
+        }
+
+        for path in &self.overridable {
+            let top = path.split('.').next().unwrap_or_default();
+            if path.split('.').any(str::is_empty)
+                || !(SESSION_FIELDS.contains(&top) || REALTIME_FIELDS.contains(&top))
+            {
+                return Err(AgentProfileError::Invalid(format!(
+                    "agent '{}' lists unknown overridable field '{path}'",
+                    self.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a client may use the profile
+    ///
+    /// Unscoped profiles are visible to everyone, including unauthenticated
+    /// connections; scoped profiles only to the listed client IDs.
+    pub fn is_visible_to(&self, client_id: Option<&str>) -> bool {
+        self.clients.is_empty() || client_id.is_some_and(|id| self.clients.iter().any(|c| c == id))
+    }
+
+    /// Resolve the config fields of `section`
+    ///
+    /// # Arguments
+    /// * `section` - Which config message is being resolved
+    /// * `overrides` - The client's `overrides`, applied as a JSON merge patch
+    /// * `metadata` - The client's session metadata, merged over the profile's
+    ///
+    /// # Returns
+    /// * `Ok(Map)` - The config message fields to apply
+    /// * `Err(AgentProfileError)` - The profile has no such section or an
+    ///   override is not allowed
+    pub fn resolve(
+        &self,
+        section: AgentSection,
+        overrides: Option<&Map<String, Value>>,
+        metadata: Option<&Map<String, Value>>,
+    ) -> Result<Map<String, Value>, AgentProfileError> {
+        let base = match section {
+            AgentSection::Session => &self.session,
+            AgentSection::Realtime => &self.realtime,
+        };
+        let Some(base) = base else {
+            return Err(AgentProfileError::MissingSection {
+                agent: self.name.clone(),
+                section,
+            });
+        };
+        let mut resolved = base.clone();
+
+        if let Some(overrides) = overrides {
+            for path in leaf_paths(overrides) {
+                let top = path.split('.').next().unwrap_or_default();
+                if !section.fields().contains(&top) || !self.allows_override(&path) {
+                    return Err(AgentProfileError::OverrideNotAllowed {
+                        agent: self.name.clone(),
+                        field: path,
+                        overridable: self.overridable.clone(),
+                    });
+                }
+            }
+            merge_patch(&mut resolved, overrides);
+        }
+
+        if let Some(metadata) = metadata {
+            let mut patch = Map::new();
+            patch.insert(METADATA_FIELD.to_string(), Value::Object(metadata.clone()));
+            merge_patch(&mut resolved, &patch);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Whether `path` or one of its parents is overridable
+    fn allows_override(&self, path: &str) -> bool {
+        if path == METADATA_FIELD || path.starts_with("metadata.") {
+            return true;
+        }
+        self.overridable.iter().any(|allowed| {
+            path == allowed
+                || path
+                    .strip_prefix(allowed.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+}
+
+/// Dotted paths of the values a merge patch sets
+///
+/// Objects are descended into; anything else (including arrays, `null` and
+/// empty objects) is a leaf.
+fn leaf_paths(patch: &Map<String, Value>) -> Vec<String> {
+    let mut paths = Vec::new();
+    for (key, value) in patch {
+        match value {
+            Value::Object(child) if !child.is_empty() => {
+                paths.extend(
+                    leaf_paths(child)
+                        .into_iter()
+                        .map(|path| format!("{key}.{path}")),
+                );
+            }
+            _ => paths.push(key.clone()),
+        }
+    }
+    paths
+}
+
+/// Apply a JSON merge patch (RFC 7386) to `target`
+fn merge_patch(target: &mut Map<String, Value>, patch: &Map<String, Value>) {
+    for (key, value) in patch {
+        match value {
+            Value::Null => {
+                target.remove(key);
+            }
+            Value::Object(child_patch) => {
+                let entry = target
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                if let Value::Object(child) = entry {
+                    merge_patch(child, child_patch);
+                }
+            }
+            other => {
+                target.insert(key.clone(), other.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("expected an object"),
+        }
+    }
+
+    fn profile() -> AgentProfile {
+        AgentProfile {
+            name: "support-bot-en".to_string(),
+            description: None,
+            clients: vec![],
+            overridable: vec!["tts_config.voice_id".to_string(), "greeting".to_string()],
+            session: Some(object(json!({
+                "stt_config": {"provider": "deepgram", "language": "en-US"},
+                "tts_config": {"provider": "elevenlabs", "voice_id": "rachel", "model": "turbo"},
+                "greeting": {"text": "Hello"},
+                "metadata": {"team": "support", "tier": "gold"}
+            }))),
+            realtime: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_without_overrides_returns_profile() {
+        let profile = profile();
+        let resolved = profile.resolve(AgentSection::Session, None, None).unwrap();
+        assert_eq!(Some(resolved), profile.session);
+    }
+
+    #[test]
+    fn test_overrides_merge_field_by_field() {
+        let overrides = object(json!({
+            "tts_config": {"voice_id": "adam"},
+            "greeting": null
+        }));
+        let metadata = object(json!({"tier": "platinum", "call": "c-1"}));
+
+        let resolved = profile()
+            .resolve(AgentSection::Session, Some(&overrides), Some(&metadata))
+            .unwrap();
+
+        assert_eq!(
+            Value::Object(resolved),
+            json!({
+                "stt_config": {"provider": "deepgram", "language": "en-US"},
+                "tts_config": {"provider": "elevenlabs", "voice_id": "adam", "model": "turbo"},
+                "metadata": {"team": "support", "tier": "platinum", "call": "c-1"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_override_outside_overridable_is_rejected() {
+        let overrides = object(json!({"tts_config": {"provider": "openai"}}));
+        let err = profile()
+            .resolve(AgentSection::Session, Some(&overrides), None)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AgentProfileError::OverrideNotAllowed {
+                agent: "support-bot-en".to_string(),
+                field: "tts_config.provider".to_string(),
+                overridable: vec!["tts_config.voice_id".to_string(), "greeting".to_string()],
+            }
+        );
+
+        // A parent path allows every field below it, but not its siblings
+        let overrides = object(json!({"greeting": {"text": "Hi", "delay_ms": 200}}));
+        assert!(
+            profile()
+                .resolve(AgentSection::Session, Some(&overrides), None)
+                .is_ok()
+        );
+        let overrides = object(json!({"greeting_text": "Hi"}));
+        assert!(
+            profile()
+                .resolve(AgentSection::Session, Some(&overrides), None)
+                .is_err()
+        );
+
+        // Metadata is always the client's to set
+        let overrides = object(json!({"metadata": {"team": "sales"}}));
+        assert!(
+            profile()
+                .resolve(AgentSection::Session, Some(&overrides), None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_missing_section() {
+        let err = profile()
+            .resolve(AgentSection::Realtime, None, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("no realtime configuration"));
+    }
+
+    #[test]
+    fn test_visibility() {
+        let mut profile = profile();
+        assert!(profile.is_visible_to(None));
+
+        profile.clients = vec!["project1".to_string()];
+        assert!(profile.is_visible_to(Some("project1")));
+        assert!(!profile.is_visible_to(Some("project2")));
+        assert!(!profile.is_visible_to(None));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(profile().validate().is_ok());
+
+        let mut bad = profile();
+        bad.name = "support bot".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = profile();
+        bad.session
+            .as_mut()
+            .unwrap()
+            .insert("stream_id".to_string(), json!("s"));
+        assert!(
+            bad.validate()
+                .unwrap_err()
+                .to_string()
+                .contains("stream_id")
+        );
+
+        let mut bad = profile();
+        bad.overridable = vec!["tts_config..voice_id".to_string()];
+        assert!(bad.validate().is_err());
+
+        let mut bad = profile();
+        bad.session = None;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_unknown_agent_lists_available() {
+        let err = AgentProfileError::UnknownAgent {
+            name: "sales".to_string(),
+            available: vec!["support-bot-en".to_string(), "support-bot-es".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Unknown agent 'sales'. Available agents: support-bot-en, support-bot-es"
+        );
+
+        let err = AgentProfileError::UnknownAgent {
+            name: "sales".to_string(),
+            available: vec![],
+        };
+        assert!(err.to_string().ends_with("Available agents: none"));
+    }
+}
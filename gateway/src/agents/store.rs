@@ -0,0 +1,339 @@
+//! Runtime registry of agent profiles
+//!
+//! Profiles from the application config are fixed; profiles created through
+//! `/admin/agents` are persisted to `agents.json` in the cache directory and
+//! loaded again on startup.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use tokio::fs;
+use tracing::{debug, info, warn};
+
+use super::profile::{AgentProfile, AgentProfileError};
+
+/// Name of the cache file for runtime agent profiles
+pub const AGENTS_CACHE_FILE: &str = "agents.json";
+
+/// Errors raised while changing runtime agent profiles
+#[derive(Debug, Error)]
+pub enum AgentStoreError {
+    #[error(transparent)]
+    Profile(#[from] AgentProfileError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Agent profiles from the application config and the admin API
+#[derive(Debug, Clone, Default)]
+pub struct AgentProfileStore {
+    /// Profiles from YAML; these cannot be changed at runtime
+    config_profiles: Vec<AgentProfile>,
+    /// Profiles created through the admin API
+    runtime_profiles: Vec<AgentProfile>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl AgentProfileStore {
+    /// Build the store from config profiles and the cache file
+    ///
+    /// Cached profiles that are invalid or share a name with a config
+    /// profile are skipped with a warning.
+    pub async fn new(config_profiles: &[AgentProfile], cache_dir: Option<&Path>) -> Self {
+        let cached = match cache_dir {
+            Some(dir) => read_agents_cache(dir).await.unwrap_or_else(|e| {
+                warn!(
+                    cache_dir = %dir.display(),
+                    error = %e,
+                    "Failed to read agents cache, using config profiles only"
+                );
+                vec![]
+            }),
+            None => vec![],
+        };
+
+        let config_names: HashSet<&str> = config_profiles.iter().map(|p| p.name.as_str()).collect();
+        let runtime_profiles = cached
+            .into_iter()
+            .filter(|profile| {
+                if config_names.contains(profile.name.as_str()) {
+                    warn!(agent = %profile.name, "Cached agent shadows a config agent, skipping");
+                    return false;
+                }
+                if let Err(e) = profile.validate() {
+                    warn!(agent = %profile.name, error = %e, "Skipping invalid cached agent");
+                    return false;
+                }
+                true
+            })
+            .collect::<Vec<_>>();
+
+        debug!(
+            config_agents = config_profiles.len(),
+            cached_agents = runtime_profiles.len(),
+            "Initialized agent profiles"
+        );
+
+        Self {
+            config_profiles: config_profiles.to_vec(),
+            runtime_profiles,
+            cache_dir: cache_dir.map(Path::to_path_buf),
+        }
+    }
+
+    /// All profiles, config profiles first
+    pub fn list(&self) -> impl Iterator<Item = &AgentProfile> {
+        self.config_profiles.iter().chain(&self.runtime_profiles)
+    }
+
+    /// Find a profile by name
+    pub fn get(&self, name: &str) -> Option<&AgentProfile> {
+        self.list().find(|profile| profile.name == name)
+    }
+
+    /// Whether a profile comes from the application config
+    pub fn is_protected(&self, name: &str) -> bool {
+        self.config_profiles.iter().any(|p| p.name == name)
+    }
+
+    /// Names of the profiles a client may use, sorted
+    pub fn available_for(&self, client_id: Option<&str>) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .list()
+            .filter(|profile| profile.is_visible_to(client_id))
+            .map(|profile| profile.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Find the profile a client asked for
+    ///
+    /// # Errors
+    /// Returns `AgentProfileError::UnknownAgent` listing the profiles the client
+    /// may use if `name` does not exist or is scoped to other clients
+    pub fn lookup(
+        &self,
+        name: &str,
+        client_id: Option<&str>,
+    ) -> Result<&AgentProfile, AgentProfileError> {
+        self.get(name)
+            .filter(|profile| profile.is_visible_to(client_id))
+            .ok_or_else(|| AgentProfileError::UnknownAgent {
+                name: name.to_string(),
+                available: self.available_for(client_id),
+            })
+    }
+
+    /// Create or replace a runtime profile and persist the runtime profiles
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the profile was created, `Ok(false)` if it replaced one
+    ///
+    /// # Errors
+    /// Returns an error if the profile is invalid, is defined in the
+    /// application config, or the cache file cannot be written
+    pub async fn upsert(&mut self, profile: AgentProfile) -> Result<bool, AgentStoreError> {
+        profile.validate()?;
+        if self.is_protected(&profile.name) {
+            return Err(AgentProfileError::Protected(profile.name).into());
+        }
+
+        let mut runtime_profiles = self.runtime_profiles.clone();
+        let created = match runtime_profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => {
+                *existing = profile;
+                false
+            }
+            None => {
+                runtime_profiles.push(profile);
+                true
+            }
+        };
+
+        self.persist(&runtime_profiles).await?;
+        self.runtime_profiles = runtime_profiles;
+        Ok(created)
+    }
+
+    /// Delete a runtime profile and persist the runtime profiles
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the profile existed
+    ///
+    /// # Errors
+    /// Returns an error if the profile is defined in the application config
+    /// or the cache file cannot be written
+    pub async fn remove(&mut self, name: &str) -> Result<bool, AgentStoreError> {
+        if self.is_protected(name) {
+            return Err(AgentProfileError::Protected(name.to_string()).into());
+        }
+
+        let mut runtime_profiles = self.runtime_profiles.clone();
+        let before = runtime_profiles.len();
+        runtime_profiles.retain(|p| p.name != name);
+        if runtime_profiles.len() == before {
+            return Ok(false);
+        }
+
+        self.persist(&runtime_profiles).await?;
+        self.runtime_profiles = runtime_profiles;
+        Ok(true)
+    }
+
+    async fn persist(&self, profiles: &[AgentProfile]) -> Result<(), AgentStoreError> {
+        match &self.cache_dir {
+            Some(dir) => write_agents_cache(dir, profiles).await,
+            None => {
+                debug!("No cache directory configured, runtime agents are not persisted");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Read runtime agent profiles from the cache file
+///
+/// Returns an empty list if the file does not exist or is empty.
+pub async fn read_agents_cache(cache_dir: &Path) -> Result<Vec<AgentProfile>, AgentStoreError> {
+    let cache_file = cache_dir.join(AGENTS_CACHE_FILE);
+    if !cache_file.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&cache_file).await?;
+    if content.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Write runtime agent profiles to the cache file atomically
+pub async fn write_agents_cache(
+    cache_dir: &Path,
+    profiles: &[AgentProfile],
+) -> Result<(), AgentStoreError> {
+    fs::create_dir_all(cache_dir).await?;
+
+    let cache_file = cache_dir.join(AGENTS_CACHE_FILE);
+    let content = serde_json::to_string_pretty(profiles)?;
+
+    let temp_file = cache_file.with_extension("tmp");
+    fs::write(&temp_file, &content).await?;
+    fs::rename(&temp_file, &cache_file).await?;
+
+    info!(
+        cache_file = %cache_file.display(),
+        agent_count = profiles.len(),
+        "Wrote agents to cache"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn profile(name: &str, clients: &[&str]) -> AgentProfile {
+        AgentProfile {
+            name: name.to_string(),
+            description: None,
+            clients: clients.iter().map(|c| c.to_string()).collect(),
+            overridable: vec![],
+            session: json!({"tts_config": {"provider": "deepgram"}})
+                .as_object()
+                .cloned(),
+            realtime: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_is_scoped_to_client() {
+        let store = AgentProfileStore::new(
+            &[
+                profile("public", &[]),
+                profile("project1-bot", &["project1"]),
+                profile("project2-bot", &["project2"]),
+            ],
+            None,
+        )
+        .await;
+
+        assert!(store.lookup("public", None).is_ok());
+        assert!(store.lookup("project1-bot", Some("project1")).is_ok());
+
+        // Profiles of other clients are reported as unknown, listing only
+        // the profiles this client may use
+        let err = store.lookup("project2-bot", Some("project1")).unwrap_err();
+        assert_eq!(
+            err,
+            AgentProfileError::UnknownAgent {
+                name: "project2-bot".to_string(),
+                available: vec!["project1-bot".to_string(), "public".to_string()],
+            }
+        );
+
+        let err = store.lookup("missing", None).unwrap_err();
+        assert_eq!(
+            err,
+            AgentProfileError::UnknownAgent {
+                name: "missing".to_string(),
+                available: vec!["public".to_string()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runtime_profiles_persist_across_restarts() {
+        let dir = TempDir::new().unwrap();
+        let config = [profile("from-config", &[])];
+
+        let mut store = AgentProfileStore::new(&config, Some(dir.path())).await;
+        assert!(store.upsert(profile("runtime", &[])).await.unwrap());
+        let mut updated = profile("runtime", &[]);
+        updated.description = Some("updated".to_string());
+        assert!(!store.upsert(updated).await.unwrap());
+
+        let store = AgentProfileStore::new(&config, Some(dir.path())).await;
+        let names: Vec<&str> = store.list().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["from-config", "runtime"]);
+        assert_eq!(
+            store.get("runtime").unwrap().description.as_deref(),
+            Some("updated")
+        );
+
+        let mut store = store;
+        assert!(store.remove("runtime").await.unwrap());
+        assert!(!store.remove("runtime").await.unwrap());
+        let store = AgentProfileStore::new(&config, Some(dir.path())).await;
+        assert!(store.get("runtime").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_config_profiles_are_protected() {
+        let dir = TempDir::new().unwrap();
+        let config = [profile("from-config", &[])];
+        let mut store = AgentProfileStore::new(&config, Some(dir.path())).await;
+
+        assert!(matches!(
+            store.upsert(profile("from-config", &[])).await,
+            Err(AgentStoreError::Profile(AgentProfileError::Protected(_)))
+        ));
+        assert!(store.remove("from-config").await.is_err());
+
+        // A cached profile with a config profile's name is ignored on load
+        write_agents_cache(dir.path(), &[profile("from-config", &["project1"])])
+            .await
+            .unwrap();
+        let store = AgentProfileStore::new(&config, Some(dir.path())).await;
+        assert_eq!(store.list().count(), 1);
+        assert!(store.get("from-config").unwrap().clients.is_empty());
+    }
+}
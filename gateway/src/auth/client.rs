@@ -293,13 +293,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(PathBuf::from("/tmp/key.pem")),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = AuthClient::from_config(&config).await;
@@ -352,13 +385,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: None,
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = AuthClient::from_config(&config).await;
@@ -426,13 +492,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(key_path),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let client = AuthClient::from_config(&config).await.unwrap();
@@ -510,13 +609,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(key_path),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let client = AuthClient::from_config(&config).await.unwrap();
@@ -593,13 +725,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(key_path),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let client = AuthClient::from_config(&config).await.unwrap();
@@ -675,13 +840,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(key_path),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let client = AuthClient::from_config(&config).await.unwrap();
@@ -759,13 +957,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(key_path),
             auth_timeout_seconds: 1, // 1 second timeout
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let client = AuthClient::from_config(&config).await.unwrap();
@@ -844,13 +1075,46 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_signing_key_path: Some(key_path),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let client = AuthClient::from_config(&config).await.unwrap();
@@ -0,0 +1,831 @@
+//! # Load Test
+//!
+//! `waav-gateway bench` drives concurrent synthetic sessions against a running
+//! gateway to find out how many calls it can carry. Each session opens `/ws`,
+//! sends a config message and streams audio at real-time pace: a 16-bit mono
+//! WAV fixture, or generated speech-shaped noise when no fixture is given.
+//! Every pass over the audio is one turn; with a speak text, each turn ends
+//! with a TTS request.
+//!
+//! Sessions start evenly spread over the ramp-up and each runs for the
+//! configured duration once it is ready. The report gives latency
+//! percentiles for:
+//!
+//! - connect: from opening the WebSocket until the `ready` message
+//! - first transcript: from the start of a turn until its first `stt_result`
+//! - first audio byte: from a `speak` request until the first binary frame
+//!
+//! along with session and error counts and throughput.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::time::{MissedTickBehavior, timeout};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tracing::{debug, info, warn};
+
+/// Audio sent per WebSocket frame
+const CHUNK_DURATION_MS: u64 = 20;
+
+/// How long a session may take to become ready
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to keep reading after the last audio was sent
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Distinct error messages kept for the report
+const MAX_ERROR_SAMPLES: usize = 10;
+
+/// Options of a load test
+#[derive(Debug, Clone, clap::Args)]
+pub struct BenchOptions {
+    /// WebSocket URL of the gateway
+    #[arg(long, default_value = "ws://127.0.0.1:3001/ws")]
+    pub url: String,
+
+    /// Number of concurrent sessions
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub sessions: usize,
+
+    /// Seconds over which session starts are spread
+    #[arg(long = "ramp-up-secs", default_value_t = 0)]
+    pub ramp_up_secs: u64,
+
+    /// Seconds each session streams audio once ready
+    #[arg(long = "duration-secs", default_value_t = 30)]
+    pub duration_secs: u64,
+
+    /// 16-bit mono WAV file to stream (speech-shaped noise if not given)
+    #[arg(long)]
+    pub wav: Option<PathBuf>,
+
+    /// Length in milliseconds of a generated noise turn, including the
+    /// trailing silence
+    #[arg(long = "turn-ms", default_value_t = 3000)]
+    pub turn_ms: u64,
+
+    /// Sample rate of the generated noise
+    #[arg(long = "sample-rate", default_value_t = 16000)]
+    pub sample_rate: u32,
+
+    /// Text to request as TTS at the end of every turn
+    #[arg(long)]
+    pub speak: Option<String>,
+
+    /// STT provider
+    #[arg(long = "stt-provider", default_value = "deepgram")]
+    pub stt_provider: String,
+
+    /// STT model
+    #[arg(long = "stt-model", default_value = "nova-3")]
+    pub stt_model: String,
+
+    /// Language of the audio
+    #[arg(long, default_value = "en-US")]
+    pub language: String,
+
+    /// TTS provider
+    #[arg(long = "tts-provider", default_value = "deepgram")]
+    pub tts_provider: String,
+
+    /// TTS model
+    #[arg(long = "tts-model", default_value = "aura-asteria-en")]
+    pub tts_model: String,
+
+    /// TTS voice
+    #[arg(long = "voice-id")]
+    pub voice_id: Option<String>,
+
+    /// STT API key sent in the config (the gateway's own key if not given)
+    #[arg(long = "stt-api-key")]
+    pub stt_api_key: Option<String>,
+
+    /// TTS API key sent in the config (the gateway's own key if not given)
+    #[arg(long = "tts-api-key")]
+    pub tts_api_key: Option<String>,
+
+    /// Bearer token for gateways that require authentication
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Print the report as JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            url: "ws://127.0.0.1:3001/ws".to_string(),
+            sessions: 10,
+            ramp_up_secs: 0,
+            duration_secs: 30,
+            wav: None,
+            turn_ms: 3000,
+            sample_rate: 16000,
+            speak: None,
+            stt_provider: "deepgram".to_string(),
+            stt_model: "nova-3".to_string(),
+            language: "en-US".to_string(),
+            tts_provider: "deepgram".to_string(),
+            tts_model: "aura-asteria-en".to_string(),
+            voice_id: None,
+            stt_api_key: None,
+            tts_api_key: None,
+            token: None,
+            json: false,
+        }
+    }
+}
+
+/// Error that prevents a load test from starting
+#[derive(Debug, thiserror::Error)]
+pub enum BenchError {
+    #[error("Invalid bench options: {0}")]
+    InvalidOptions(String),
+    #[error("Failed to load audio fixture {path}: {message}")]
+    Audio { path: PathBuf, message: String },
+}
+
+/// 16-bit little-endian mono PCM streamed by every session
+#[derive(Debug, Clone)]
+pub struct BenchAudio {
+    pub pcm: Bytes,
+    pub sample_rate: u32,
+}
+
+impl BenchAudio {
+    /// Load a 16-bit integer mono WAV file
+    pub fn from_wav(path: &Path) -> Result<Self, BenchError> {
+        let error = |message: String| BenchError::Audio {
+            path: path.to_path_buf(),
+            message,
+        };
+        let reader = hound::WavReader::open(path).map_err(|e| error(e.to_string()))?;
+        let spec = reader.spec();
+        if spec.channels != 1
+            || spec.bits_per_sample != 16
+            || spec.sample_format != hound::SampleFormat::Int
+        {
+            return Err(error(format!(
+                "must be 16-bit mono PCM (got {} channel(s), {}-bit {:?})",
+                spec.channels, spec.bits_per_sample, spec.sample_format
+            )));
+        }
+
+        let mut pcm = Vec::with_capacity(reader.len() as usize * 2);
+        for sample in reader.into_samples::<i16>() {
+            let sample = sample.map_err(|e| error(e.to_string()))?;
+            pcm.extend_from_slice(&sample.to_le_bytes());
+        }
+        if pcm.is_empty() {
+            return Err(error("contains no audio".to_string()));
+        }
+        Ok(Self {
+            pcm: pcm.into(),
+            sample_rate: spec.sample_rate,
+        })
+    }
+
+    /// Generate one turn of speech-shaped noise
+    ///
+    /// Low-passed noise with a syllable-rate envelope for the first two
+    /// thirds of the turn, then silence so endpointing closes the utterance.
+    pub fn speech_noise(sample_rate: u32, turn: Duration) -> Self {
+        let total = (sample_rate as u128 * turn.as_millis() / 1000) as usize;
+        let voiced = total * 2 / 3;
+        let mut seed: u32 = 0x9e37_79b9;
+        let mut filtered = 0.0f32;
+        let mut pcm = Vec::with_capacity(total * 2);
+
+        for i in 0..total {
+            let sample = if i < voiced {
+                // xorshift32 white noise in [-1, 1)
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let white = seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+                // One-pole low-pass tilts the spectrum towards speech
+                filtered += 0.15 * (white - filtered);
+                let t = i as f32 / sample_rate as f32;
+                let envelope = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * 4.0 * t).cos();
+                (filtered * envelope * 3.0 * 8000.0).clamp(-32768.0, 32767.0) as i16
+            } else {
+                0
+            };
+            pcm.extend_from_slice(&sample.to_le_bytes());
+        }
+        Self {
+            pcm: pcm.into(),
+            sample_rate,
+        }
+    }
+
+    /// Bytes of audio in one frame
+    fn chunk_len(&self) -> usize {
+        (self.sample_rate as u64 * CHUNK_DURATION_MS / 1000 * 2).max(2) as usize
+    }
+}
+
+/// Latency percentiles of one measurement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    /// Nearest-rank percentiles; `None` without samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let rank =
+            |p: f64| ms[((p / 100.0 * ms.len() as f64).ceil() as usize).clamp(1, ms.len()) - 1];
+        Some(Self {
+            samples: ms.len(),
+            p50_ms: rank(50.0),
+            p90_ms: rank(90.0),
+            p99_ms: rank(99.0),
+            max_ms: ms[ms.len() - 1],
+        })
+    }
+}
+
+/// Result of a load test
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    /// Gateway URL
+    pub target: String,
+    /// Sessions started
+    pub sessions: usize,
+    /// Sessions that became ready and streamed for the whole duration
+    pub completed_sessions: usize,
+    /// Sessions that failed to connect or were closed early
+    pub failed_sessions: usize,
+    /// `failed_sessions / sessions`
+    pub session_error_rate: f64,
+    /// `error` messages received from the gateway
+    pub error_messages: u64,
+    /// Wall-clock time of the whole test
+    pub elapsed_secs: f64,
+    pub connect: Option<LatencySummary>,
+    pub first_transcript: Option<LatencySummary>,
+    pub first_audio_byte: Option<LatencySummary>,
+    /// Turns started across all sessions
+    pub turns: u64,
+    /// `stt_result` messages received
+    pub transcripts: u64,
+    /// `speak` requests sent
+    pub tts_requests: u64,
+    /// Seconds of audio streamed to the gateway
+    pub audio_sent_secs: f64,
+    /// Bytes of TTS audio received
+    pub tts_audio_bytes: u64,
+    /// `transcripts / elapsed_secs`
+    pub transcripts_per_sec: f64,
+    /// `tts_audio_bytes / elapsed_secs`
+    pub tts_audio_bytes_per_sec: f64,
+    /// First distinct failures and error messages
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Target:             {}", self.target)?;
+        writeln!(
+            f,
+            "Sessions:           {} started, {} completed, {} failed ({:.1}% errors)",
+            self.sessions,
+            self.completed_sessions,
+            self.failed_sessions,
+            self.session_error_rate * 100.0
+        )?;
+        writeln!(f, "Elapsed:            {:.1}s", self.elapsed_secs)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<18} {:>8} {:>10} {:>10} {:>10} {:>10}",
+            "Latency", "samples", "p50 ms", "p90 ms", "p99 ms", "max ms"
+        )?;
+        for (name, summary) in [
+            ("connect", &self.connect),
+            ("first transcript", &self.first_transcript),
+            ("first audio byte", &self.first_audio_byte),
+        ] {
+            match summary {
+                Some(s) => writeln!(
+                    f,
+                    "{:<18} {:>8} {:>10.1} {:>10.1} {:>10.1} {:>10.1}",
+                    name, s.samples, s.p50_ms, s.p90_ms, s.p99_ms, s.max_ms
+                )?,
+                None => writeln!(f, "{:<18} {:>8}", name, 0)?,
+            }
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Turns:              {} ({} TTS requests)",
+            self.turns, self.tts_requests
+        )?;
+        writeln!(
+            f,
+            "Transcripts:        {} ({:.1}/s)",
+            self.transcripts, self.transcripts_per_sec
+        )?;
+        writeln!(f, "Audio sent:         {:.1}s", self.audio_sent_secs)?;
+        writeln!(
+            f,
+            "TTS audio received: {} bytes ({:.0} B/s)",
+            self.tts_audio_bytes, self.tts_audio_bytes_per_sec
+        )?;
+        writeln!(f, "Error messages:     {}", self.error_messages)?;
+        for error in &self.errors {
+            writeln!(f, "  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Measurements of one session
+#[derive(Debug, Default)]
+struct SessionStats {
+    connect: Option<Duration>,
+    first_transcript: Vec<Duration>,
+    first_audio_byte: Vec<Duration>,
+    turns: u64,
+    transcripts: u64,
+    tts_requests: u64,
+    audio_sent_bytes: u64,
+    tts_audio_bytes: u64,
+    errors: Vec<String>,
+    failure: Option<String>,
+}
+
+/// Start times awaiting their first response
+#[derive(Default)]
+struct PendingClock {
+    turn_started: Option<Instant>,
+    speak_sent: Option<Instant>,
+}
+
+/// Counters the reader task fills in
+#[derive(Default)]
+struct ReceivedStats {
+    first_transcript: Vec<Duration>,
+    first_audio_byte: Vec<Duration>,
+    transcripts: u64,
+    tts_audio_bytes: u64,
+    errors: Vec<String>,
+}
+
+fn config_message(options: &BenchOptions, sample_rate: u32) -> Value {
+    let mut config = json!({
+        "type": "config",
+        "audio": true,
+        "stt_config": {
+            "provider": options.stt_provider,
+            "language": options.language,
+            "sample_rate": sample_rate,
+            "channels": 1,
+            "punctuation": true,
+            "encoding": "linear16",
+            "model": options.stt_model,
+        },
+        "tts_config": {
+            "provider": options.tts_provider,
+            "model": options.tts_model,
+            "voice_id": options.voice_id,
+            "audio_format": "linear16",
+        },
+    });
+    if let Some(key) = &options.stt_api_key {
+        config["stt_config"]["api_key"] = json!(key);
+    }
+    if let Some(key) = &options.tts_api_key {
+        config["tts_config"]["api_key"] = json!(key);
+    }
+    config
+}
+
+/// Run one synthetic session until `duration` after it became ready
+async fn run_session(
+    options: Arc<BenchOptions>,
+    audio: Arc<BenchAudio>,
+    index: usize,
+) -> SessionStats {
+    let mut stats = SessionStats::default();
+    let started = Instant::now();
+
+    let mut request = match options.url.as_str().into_client_request() {
+        Ok(request) => request,
+        Err(e) => {
+            stats.failure = Some(format!("Invalid URL {}: {e}", options.url));
+            return stats;
+        }
+    };
+    if let Some(token) = &options.token {
+        match HeaderValue::from_str(&format!("Bearer {token}")) {
+            Ok(value) => {
+                request.headers_mut().insert("authorization", value);
+            }
+            Err(e) => {
+                stats.failure = Some(format!("Invalid token: {e}"));
+                return stats;
+            }
+        }
+    }
+
+    let (ws, _) = match connect_async(request).await {
+        Ok(connected) => connected,
+        Err(e) => {
+            stats.failure = Some(format!("Connect failed: {e}"));
+            return stats;
+        }
+    };
+    let (mut sink, mut stream) = ws.split();
+
+    let config = config_message(&options, audio.sample_rate).to_string();
+    if let Err(e) = sink.send(Message::Text(config.into())).await {
+        stats.failure = Some(format!("Sending config failed: {e}"));
+        return stats;
+    }
+
+    // Wait for ready; anything else before it is an error or noise
+    let ready = timeout(READY_TIMEOUT, async {
+        while let Some(message) = stream.next().await {
+            let Ok(Message::Text(text)) = message else {
+                continue;
+            };
+            let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                continue;
+            };
+            match value.get("type").and_then(Value::as_str) {
+                Some("ready") => return Ok(()),
+                Some("error") => {
+                    return Err(value
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("error")
+                        .to_string());
+                }
+                _ => {}
+            }
+        }
+        Err("Connection closed before ready".to_string())
+    })
+    .await;
+    match ready {
+        Ok(Ok(())) => stats.connect = Some(started.elapsed()),
+        Ok(Err(message)) => {
+            stats.failure = Some(format!("Session not ready: {message}"));
+            return stats;
+        }
+        Err(_) => {
+            stats.failure = Some(format!("No ready message within {READY_TIMEOUT:?}"));
+            return stats;
+        }
+    }
+    debug!(session = index, "Bench session ready");
+
+    let clock = Arc::new(Mutex::new(PendingClock::default()));
+    let received = Arc::new(Mutex::new(ReceivedStats::default()));
+    let mut reader = {
+        let clock = clock.clone();
+        let received = received.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                match message {
+                    Message::Text(text) => {
+                        let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                            continue;
+                        };
+                        match value.get("type").and_then(Value::as_str) {
+                            Some("stt_result") => {
+                                let mut received = received.lock();
+                                received.transcripts += 1;
+                                if let Some(start) = clock.lock().turn_started.take() {
+                                    received.first_transcript.push(start.elapsed());
+                                }
+                            }
+                            Some("error") => {
+                                let message = value
+                                    .get("message")
+                                    .and_then(Value::as_str)
+                                    .unwrap_or("error");
+                                received.lock().errors.push(message.to_string());
+                            }
+                            _ => {}
+                        }
+                    }
+                    Message::Binary(data) => {
+                        let mut received = received.lock();
+                        received.tts_audio_bytes += data.len() as u64;
+                        if let Some(start) = clock.lock().speak_sent.take() {
+                            received.first_audio_byte.push(start.elapsed());
+                        }
+                    }
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+        })
+    };
+
+    // Stream turns at real-time pace until the duration is up
+    let deadline = Instant::now() + Duration::from_secs(options.duration_secs);
+    let mut ticker = tokio::time::interval(Duration::from_millis(CHUNK_DURATION_MS));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let chunk_len = audio.chunk_len();
+    'turns: while Instant::now() < deadline {
+        stats.turns += 1;
+        clock.lock().turn_started = Some(Instant::now());
+        let mut offset = 0;
+        while offset < audio.pcm.len() {
+            ticker.tick().await;
+            if Instant::now() >= deadline {
+                break 'turns;
+            }
+            let end = (offset + chunk_len).min(audio.pcm.len());
+            if let Err(e) = sink
+                .send(Message::Binary(audio.pcm.slice(offset..end)))
+                .await
+            {
+                stats.failure = Some(format!("Connection closed while streaming: {e}"));
+                break 'turns;
+            }
+            stats.audio_sent_bytes += (end - offset) as u64;
+            offset = end;
+        }
+
+        if let Some(text) = &options.speak {
+            let speak = json!({"type": "speak", "text": text, "flush": true}).to_string();
+            clock.lock().speak_sent = Some(Instant::now());
+            if let Err(e) = sink.send(Message::Text(speak.into())).await {
+                stats.failure = Some(format!("Connection closed while streaming: {e}"));
+                break;
+            }
+            stats.tts_requests += 1;
+        }
+    }
+
+    // Give in-flight transcripts and audio a moment, then hang up
+    let _ = sink.send(Message::Close(None)).await;
+    if timeout(DRAIN_TIMEOUT, &mut reader).await.is_err() {
+        reader.abort();
+    }
+
+    let received = std::mem::take(&mut *received.lock());
+    stats.first_transcript = received.first_transcript;
+    stats.first_audio_byte = received.first_audio_byte;
+    stats.transcripts = received.transcripts;
+    stats.tts_audio_bytes = received.tts_audio_bytes;
+    stats.errors = received.errors;
+    stats
+}
+
+fn summarize(
+    options: &BenchOptions,
+    audio: &BenchAudio,
+    results: Vec<SessionStats>,
+    elapsed: Duration,
+) -> BenchReport {
+    let mut connect = Vec::new();
+    let mut first_transcript = Vec::new();
+    let mut first_audio_byte = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    let mut note_error = |message: &str| {
+        if errors.len() < MAX_ERROR_SAMPLES && !errors.iter().any(|e| e == message) {
+            errors.push(message.to_string());
+        }
+    };
+
+    let mut failed_sessions = 0;
+    let mut error_messages = 0;
+    let (mut turns, mut transcripts, mut tts_requests) = (0, 0, 0);
+    let (mut audio_sent_bytes, mut tts_audio_bytes) = (0, 0);
+    for stats in &results {
+        connect.extend(stats.connect);
+        first_transcript.extend_from_slice(&stats.first_transcript);
+        first_audio_byte.extend_from_slice(&stats.first_audio_byte);
+        if let Some(failure) = &stats.failure {
+            failed_sessions += 1;
+            note_error(failure);
+        }
+        for error in &stats.errors {
+            note_error(error);
+        }
+        error_messages += stats.errors.len() as u64;
+        turns += stats.turns;
+        transcripts += stats.transcripts;
+        tts_requests += stats.tts_requests;
+        audio_sent_bytes += stats.audio_sent_bytes;
+        tts_audio_bytes += stats.tts_audio_bytes;
+    }
+
+    let elapsed_secs = elapsed.as_secs_f64();
+    let per_sec = |count: u64| {
+        if elapsed_secs > 0.0 {
+            count as f64 / elapsed_secs
+        } else {
+            0.0
+        }
+    };
+    BenchReport {
+        target: options.url.clone(),
+        sessions: results.len(),
+        completed_sessions: results.len() - failed_sessions,
+        failed_sessions,
+        session_error_rate: if results.is_empty() {
+            0.0
+        } else {
+            failed_sessions as f64 / results.len() as f64
+        },
+        error_messages,
+        elapsed_secs,
+        connect: LatencySummary::from_samples(&connect),
+        first_transcript: LatencySummary::from_samples(&first_transcript),
+        first_audio_byte: LatencySummary::from_samples(&first_audio_byte),
+        turns,
+        transcripts,
+        tts_requests,
+        audio_sent_secs: audio_sent_bytes as f64 / (audio.sample_rate as f64 * 2.0),
+        tts_audio_bytes,
+        transcripts_per_sec: per_sec(transcripts),
+        tts_audio_bytes_per_sec: per_sec(tts_audio_bytes),
+        errors,
+    }
+}
+
+/// Run a load test and report the results
+pub async fn run(options: BenchOptions) -> Result<BenchReport, BenchError> {
+    if options.sessions == 0 {
+        return Err(BenchError::InvalidOptions(
+            "sessions must be at least 1".to_string(),
+        ));
+    }
+    if options.duration_secs == 0 {
+        return Err(BenchError::InvalidOptions(
+            "duration must be at least 1 second".to_string(),
+        ));
+    }
+    let audio = match &options.wav {
+        Some(path) => BenchAudio::from_wav(path)?,
+        None => {
+            if options.sample_rate == 0 || options.turn_ms < CHUNK_DURATION_MS {
+                return Err(BenchError::InvalidOptions(format!(
+                    "sample rate must be positive and turns at least {CHUNK_DURATION_MS}ms"
+                )));
+            }
+            BenchAudio::speech_noise(options.sample_rate, Duration::from_millis(options.turn_ms))
+        }
+    };
+
+    info!(
+        target = %options.url,
+        sessions = options.sessions,
+        ramp_up_secs = options.ramp_up_secs,
+        duration_secs = options.duration_secs,
+        "Starting load test"
+    );
+
+    let options = Arc::new(options);
+    let audio = Arc::new(audio);
+    let ramp_up = Duration::from_secs(options.ramp_up_secs);
+    let started = Instant::now();
+    let handles: Vec<_> = (0..options.sessions)
+        .map(|index| {
+            let delay = ramp_up.mul_f64(index as f64 / options.sessions as f64);
+            let options = options.clone();
+            let audio = audio.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                run_session(options, audio, index).await
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(stats) => results.push(stats),
+            Err(e) => {
+                warn!("Bench session task failed: {}", e);
+                results.push(SessionStats {
+                    failure: Some(format!("Session task failed: {e}")),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    Ok(summarize(&options, &audio, results, started.elapsed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        assert_eq!(LatencySummary::from_samples(&[]), None);
+
+        let samples = ms(&(1..=100).rev().collect::<Vec<_>>());
+        let summary = LatencySummary::from_samples(&samples).unwrap();
+        assert_eq!(summary.samples, 100);
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p90_ms, 90.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+
+        let single = LatencySummary::from_samples(&ms(&[42])).unwrap();
+        assert_eq!((single.p50_ms, single.p99_ms), (42.0, 42.0));
+    }
+
+    #[test]
+    fn test_speech_noise_shape() {
+        let audio = BenchAudio::speech_noise(16000, Duration::from_millis(3000));
+        assert_eq!(audio.pcm.len(), 16000 * 3 * 2);
+        assert_eq!(audio.chunk_len(), 640);
+
+        let samples: Vec<i16> = audio
+            .pcm
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        // Voiced for two seconds, then silent
+        assert!(samples[..32000].iter().any(|&s| s.unsigned_abs() > 1000));
+        assert!(samples[32000..].iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn test_summary_counts_failures() {
+        let options = BenchOptions::default();
+        let audio = BenchAudio::speech_noise(16000, Duration::from_millis(100));
+        let results = vec![
+            SessionStats {
+                connect: Some(Duration::from_millis(10)),
+                first_transcript: ms(&[100, 200]),
+                turns: 2,
+                transcripts: 4,
+                audio_sent_bytes: 64000,
+                errors: vec!["TTS failed".to_string()],
+                ..Default::default()
+            },
+            SessionStats {
+                failure: Some("Connect failed: refused".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let report = summarize(&options, &audio, results, Duration::from_secs(2));
+        assert_eq!(report.completed_sessions, 1);
+        assert_eq!(report.failed_sessions, 1);
+        assert_eq!(report.session_error_rate, 0.5);
+        assert_eq!(report.error_messages, 1);
+        assert_eq!(report.connect.unwrap().samples, 1);
+        assert_eq!(report.first_transcript.unwrap().max_ms, 200.0);
+        assert!(report.first_audio_byte.is_none());
+        assert_eq!(report.audio_sent_secs, 2.0);
+        assert_eq!(report.transcripts_per_sec, 2.0);
+        assert_eq!(
+            report.errors,
+            vec![
+                "Connect failed: refused".to_string(),
+                "TTS failed".to_string()
+            ]
+        );
+        assert!(report.to_string().contains("first transcript"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_empty_run() {
+        let options = BenchOptions {
+            sessions: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            run(options).await,
+            Err(BenchError::InvalidOptions(_))
+        ));
+    }
+}
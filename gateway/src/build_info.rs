@@ -0,0 +1,136 @@
+//! Build details of the running gateway
+//!
+//! Reported by `GET /version` and printed at startup, so the build and
+//! feature set of each node in a deployment can be told apart. The git
+//! commit and rustc version are embedded by the build script.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::plugin::{DynamicPluginInfo, PluginRegistry};
+
+/// Gateway crate version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Commit the gateway was built from, or `unknown` outside a git checkout
+pub const GIT_COMMIT: &str = env!("WAAV_GIT_COMMIT");
+
+/// `rustc --version` of the compiler that built the gateway
+pub const RUSTC_VERSION: &str = env!("WAAV_RUSTC_VERSION");
+
+/// Cargo features the gateway was compiled with, sorted by name
+pub fn enabled_features() -> Vec<&'static str> {
+    [
+        ("dag-routing", cfg!(feature = "dag-routing")),
+        ("noise-filter", cfg!(feature = "noise-filter")),
+        ("openapi", cfg!(feature = "openapi")),
+        ("plugins-dynamic", cfg!(feature = "plugins-dynamic")),
+        ("turn-detect", cfg!(feature = "turn-detect")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+/// Version, build and plugin details of the gateway
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BuildInfo {
+    /// Gateway crate version
+    #[cfg_attr(feature = "openapi", schema(example = "1.0.0"))]
+    pub version: String,
+    /// Commit the gateway was built from
+    #[cfg_attr(feature = "openapi", schema(example = "83a4fb4c2e1d"))]
+    pub git_commit: String,
+    /// Compiler the gateway was built with
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "rustc 1.88.0 (6b00bc388 2025-06-23)")
+    )]
+    pub rustc_version: String,
+    /// Enabled cargo features
+    #[cfg_attr(feature = "openapi", schema(example = json!(["openapi", "turn-detect"])))]
+    pub features: Vec<String>,
+    /// Dynamic plugins loaded at startup
+    pub plugins: Vec<DynamicPluginInfo>,
+}
+
+impl BuildInfo {
+    /// Build details with the dynamic plugins registered in `registry`
+    pub fn collect(registry: &PluginRegistry) -> Self {
+        Self {
+            version: VERSION.to_string(),
+            git_commit: GIT_COMMIT.to_string(),
+            rustc_version: RUSTC_VERSION.to_string(),
+            features: enabled_features().into_iter().map(String::from).collect(),
+            plugins: registry.dynamic_plugins(),
+        }
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "WaaV Gateway {} (commit {}, {})",
+            self.version, self.git_commit, self.rustc_version
+        )?;
+        let features = if self.features.is_empty() {
+            "none".to_string()
+        } else {
+            self.features.join(", ")
+        };
+        writeln!(f, "  features: {features}")?;
+        let plugins = if self.plugins.is_empty() {
+            "none".to_string()
+        } else {
+            self.plugins
+                .iter()
+                .map(|plugin| format!("{} {}", plugin.id, plugin.version))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(f, "  dynamic plugins: {plugins}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_lists_features_and_plugins() {
+        let info = BuildInfo {
+            version: "1.0.0".to_string(),
+            git_commit: "abc123".to_string(),
+            rustc_version: "rustc 1.88.0".to_string(),
+            features: vec!["openapi".to_string(), "turn-detect".to_string()],
+            plugins: vec![DynamicPluginInfo {
+                id: "resemble-tts".to_string(),
+                version: "0.1.0".to_string(),
+            }],
+        };
+        assert_eq!(
+            info.to_string(),
+            concat!(
+                "WaaV Gateway 1.0.0 (commit abc123, rustc 1.88.0)\n",
+                "  features: openapi, turn-detect\n",
+                "  dynamic plugins: resemble-tts 0.1.0",
+            )
+        );
+    }
+
+    #[test]
+    fn test_display_without_features_or_plugins() {
+        let info = BuildInfo {
+            features: Vec::new(),
+            plugins: Vec::new(),
+            ..BuildInfo::collect(&PluginRegistry::new())
+        };
+        assert!(
+            info.to_string()
+                .ends_with("  features: none\n  dynamic plugins: none")
+        );
+    }
+}
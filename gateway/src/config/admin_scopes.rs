@@ -0,0 +1,110 @@
+//! Admin API scopes
+//!
+//! Admin endpoints are authorized per route: reading admin state needs
+//! `admin:read`, changing it needs `admin:write`, and injecting faults
+//! through `/admin/chaos` needs `admin:chaos`. Auth IDs in `auth.admin_ids`
+//! hold every scope; `auth.admin_scopes` grants single scopes to other IDs.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Scope an admin endpoint requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AdminScope {
+    /// Read admin state (`admin:read`)
+    #[serde(rename = "admin:read")]
+    Read,
+    /// Change admin state (`admin:write`)
+    #[serde(rename = "admin:write")]
+    Write,
+    /// Inject faults into sessions (`admin:chaos`)
+    #[serde(rename = "admin:chaos")]
+    Chaos,
+}
+
+impl AdminScope {
+    pub const ALL: [AdminScope; 3] = [AdminScope::Read, AdminScope::Write, AdminScope::Chaos];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminScope::Read => "admin:read",
+            AdminScope::Write => "admin:write",
+            AdminScope::Chaos => "admin:chaos",
+        }
+    }
+
+    /// Whether calls needing this scope change state, so they are audited
+    pub fn is_mutating(self) -> bool {
+        !matches!(self, AdminScope::Read)
+    }
+}
+
+impl fmt::Display for AdminScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Auth IDs granted single admin scopes
+///
+/// Scopes are independent: an ID that both reads and changes admin state is
+/// listed under `read` and `write`.
+///
+/// # Example YAML
+/// ```yaml
+/// auth:
+///   admin_scopes:
+///     read: ["dashboard"]
+///     write: ["ops-bot"]
+///     chaos: ["resilience-tests"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminScopeIds {
+    /// Holders of `admin:read`
+    pub read: Vec<String>,
+    /// Holders of `admin:write`
+    pub write: Vec<String>,
+    /// Holders of `admin:chaos`
+    pub chaos: Vec<String>,
+}
+
+impl AdminScopeIds {
+    /// Auth IDs granted `scope`
+    pub fn holders(&self, scope: AdminScope) -> &[String] {
+        match scope {
+            AdminScope::Read => &self.read,
+            AdminScope::Write => &self.write,
+            AdminScope::Chaos => &self.chaos,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_names() {
+        let names: Vec<&str> = AdminScope::ALL.iter().map(|s| s.as_str()).collect();
+        assert_eq!(names, vec!["admin:read", "admin:write", "admin:chaos"]);
+        assert_eq!(
+            serde_json::to_value(AdminScope::Chaos).unwrap(),
+            "admin:chaos"
+        );
+        assert!(!AdminScope::Read.is_mutating());
+        assert!(AdminScope::Write.is_mutating());
+        assert!(AdminScope::Chaos.is_mutating());
+    }
+
+    #[test]
+    fn test_holders_from_yaml() {
+        let ids: AdminScopeIds = serde_yaml::from_str("read: [dash]\nchaos: [sre]\n").unwrap();
+        assert_eq!(ids.holders(AdminScope::Read), ["dash"]);
+        assert!(ids.holders(AdminScope::Write).is_empty());
+        assert_eq!(ids.holders(AdminScope::Chaos), ["sre"]);
+        assert!(serde_yaml::from_str::<AdminScopeIds>("admin: [x]\n").is_err());
+    }
+}
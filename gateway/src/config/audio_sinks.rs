@@ -0,0 +1,245 @@
+//! Audio policies of the recording and monitor sinks
+//!
+//! Recordings and monitor streams do not need the quality of the live
+//! session, so each sink can take a cheaper rendition of the audio. The live
+//! session itself is never affected.
+//!
+//! Recordings are mixed and encoded (Ogg Opus) by LiveKit egress, which is
+//! told the sample rate and Opus bitrate to use. It always writes the mixed
+//! room, so recordings cannot be downmixed further. Monitor audio is
+//! transformed by the gateway before it is encrypted; it is sent as 16-bit
+//! PCM, so it can be downsampled and downmixed but not Opus-encoded.
+
+use serde::{Deserialize, Serialize};
+
+/// Sample rates the Opus encoder accepts
+pub const OPUS_SAMPLE_RATES: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+
+/// Lowest sample rate a sink may be downsampled to
+pub const MIN_SINK_SAMPLE_RATE: u32 = 8000;
+
+/// Highest sample rate a sink may be set to
+pub const MAX_SINK_SAMPLE_RATE: u32 = 48000;
+
+/// Lowest and highest Opus bitrate in kbps
+pub const OPUS_BITRATE_RANGE_KBPS: (u32, u32) = (6, 510);
+
+/// Transforms applied to the audio a sink receives
+///
+/// The default keeps the audio as it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinkAudioPolicy {
+    /// Sample rate the sink receives; audio at a lower rate is not upsampled
+    pub sample_rate: Option<u32>,
+    /// Mix multi-channel audio down to one channel
+    pub mono: bool,
+    /// Opus bitrate in kbps
+    pub opus_bitrate_kbps: Option<u32>,
+}
+
+impl SinkAudioPolicy {
+    /// Whether the policy leaves the audio as it is
+    pub fn is_passthrough(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Metadata entries describing the policy, for the sink's stored objects
+    ///
+    /// Entries are named `{prefix}_sample_rate`, `{prefix}_mono` and
+    /// `{prefix}_opus_bitrate_kbps`; unset settings are left out.
+    pub fn metadata(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        if let Some(rate) = self.sample_rate {
+            entries.push((format!("{prefix}_sample_rate"), rate.to_string()));
+        }
+        if self.mono {
+            entries.push((format!("{prefix}_mono"), "true".to_string()));
+        }
+        if let Some(kbps) = self.opus_bitrate_kbps {
+            entries.push((format!("{prefix}_opus_bitrate_kbps"), kbps.to_string()));
+        }
+        entries
+    }
+
+    fn validate_common(&self) -> Result<(), String> {
+        if let Some(rate) = self.sample_rate
+            && !(MIN_SINK_SAMPLE_RATE..=MAX_SINK_SAMPLE_RATE).contains(&rate)
+        {
+            return Err(format!(
+                "sample_rate must be between {MIN_SINK_SAMPLE_RATE} and {MAX_SINK_SAMPLE_RATE} (got {rate})"
+            ));
+        }
+        let (min, max) = OPUS_BITRATE_RANGE_KBPS;
+        if let Some(kbps) = self.opus_bitrate_kbps
+            && !(min..=max).contains(&kbps)
+        {
+            return Err(format!(
+                "opus_bitrate_kbps must be between {min} and {max} (got {kbps})"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Audio policies of the recording and monitor sinks
+///
+/// # Example YAML
+/// ```yaml
+/// audio_sinks:
+///   recording:
+///     sample_rate: 8000
+///     opus_bitrate_kbps: 16
+///   monitor:
+///     sample_rate: 8000
+///     mono: true
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioSinksConfig {
+    /// Room recordings made by LiveKit egress
+    pub recording: SinkAudioPolicy,
+    /// Monitor audio sent to session observers
+    pub monitor: SinkAudioPolicy,
+}
+
+impl AudioSinksConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if every setting is in range and supported by its sink
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        self.recording
+            .validate_common()
+            .map_err(|e| format!("recording.{e}"))?;
+        if let Some(rate) = self.recording.sample_rate
+            && !OPUS_SAMPLE_RATES.contains(&rate)
+        {
+            return Err(format!(
+                "recording.sample_rate must be one of {OPUS_SAMPLE_RATES:?}, the rates Opus encodes (got {rate})"
+            ));
+        }
+        if self.recording.mono {
+            return Err(
+                "recording.mono is not supported: egress records the mixed room audio".to_string(),
+            );
+        }
+
+        self.monitor
+            .validate_common()
+            .map_err(|e| format!("monitor.{e}"))?;
+        if self.monitor.opus_bitrate_kbps.is_some() {
+            return Err(
+                "monitor.opus_bitrate_kbps is not supported: monitor audio is sent as PCM"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_sinks_validation() {
+        assert!(AudioSinksConfig::default().validate().is_ok());
+
+        let config: AudioSinksConfig = serde_yaml::from_str(
+            "recording: {sample_rate: 8000, opus_bitrate_kbps: 16}\nmonitor: {sample_rate: 8000, mono: true}",
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.recording.opus_bitrate_kbps, Some(16));
+        assert!(config.monitor.mono);
+        assert!(serde_yaml::from_str::<AudioSinksConfig>("recording: {channels: 1}").is_err());
+
+        let odd_rate = AudioSinksConfig {
+            recording: SinkAudioPolicy {
+                sample_rate: Some(11025),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(odd_rate.validate().unwrap_err().contains("one of"));
+        let monitor_odd_rate = AudioSinksConfig {
+            monitor: SinkAudioPolicy {
+                sample_rate: Some(11025),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(monitor_odd_rate.validate().is_ok());
+
+        let too_low = AudioSinksConfig {
+            monitor: SinkAudioPolicy {
+                sample_rate: Some(4000),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(
+            too_low
+                .validate()
+                .unwrap_err()
+                .starts_with("monitor.sample_rate")
+        );
+        let bitrate = AudioSinksConfig {
+            recording: SinkAudioPolicy {
+                opus_bitrate_kbps: Some(1000),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(
+            bitrate
+                .validate()
+                .unwrap_err()
+                .contains("opus_bitrate_kbps")
+        );
+
+        let recording_mono = AudioSinksConfig {
+            recording: SinkAudioPolicy {
+                mono: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(
+            recording_mono
+                .validate()
+                .unwrap_err()
+                .contains("mixed room")
+        );
+        let monitor_opus = AudioSinksConfig {
+            monitor: SinkAudioPolicy {
+                opus_bitrate_kbps: Some(24),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(monitor_opus.validate().unwrap_err().contains("PCM"));
+    }
+
+    #[test]
+    fn test_sink_audio_policy_metadata() {
+        assert!(SinkAudioPolicy::default().is_passthrough());
+        assert!(SinkAudioPolicy::default().metadata("recording").is_empty());
+
+        let policy = SinkAudioPolicy {
+            sample_rate: Some(8000),
+            mono: false,
+            opus_bitrate_kbps: Some(16),
+        };
+        assert!(!policy.is_passthrough());
+        assert_eq!(
+            policy.metadata("recording"),
+            vec![
+                ("recording_sample_rate".to_string(), "8000".to_string()),
+                ("recording_opus_bitrate_kbps".to_string(), "16".to_string()),
+            ]
+        );
+    }
+}
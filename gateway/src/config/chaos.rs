@@ -0,0 +1,21 @@
+//! Chaos fault injection configuration
+//!
+//! Fault injection is compiled in only with the `chaos` cargo feature and,
+//! even then, stays off unless the server config opts in. Which faults are
+//! injected is set at runtime through `PUT /admin/chaos`.
+
+use serde::Deserialize;
+
+/// Whether `/admin/chaos` may inject faults into sessions
+///
+/// # Example YAML
+/// ```yaml
+/// chaos:
+///   enabled: true
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChaosConfig {
+    /// Allow faults to be injected; `PUT /admin/chaos` is refused otherwise
+    pub enabled: bool,
+}
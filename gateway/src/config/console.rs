@@ -0,0 +1,69 @@
+//! Development console configuration
+//!
+//! The console at `/console` is compiled in only with the `dev-console`
+//! cargo feature and, even then, stays off unless the server config opts in.
+//! It is a page of static assets that talks to `/ws` like any other client.
+
+use serde::Deserialize;
+
+/// Whether `/console` serves the development console
+///
+/// # Example YAML
+/// ```yaml
+/// console:
+///   enabled: true
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsoleConfig {
+    /// Serve the console; `/console` answers `404 Not Found` otherwise
+    pub enabled: bool,
+}
+
+impl ConsoleConfig {
+    /// Validate the configuration against the server's bind address
+    ///
+    /// The console hands out nothing the public protocol does not, but it
+    /// makes an open gateway easy to drive from any browser, so it may only
+    /// be enabled without auth when the server listens on loopback.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the console is disabled, auth is required or `host` is loopback
+    /// * `Err(String)` otherwise
+    pub fn validate(&self, auth_required: bool, host: &str) -> Result<(), String> {
+        if !self.enabled || auth_required || is_loopback_host(host) {
+            return Ok(());
+        }
+        Err(format!(
+            "enabled requires auth_required: true when binding to {host}; \
+             bind to 127.0.0.1 or enable auth"
+        ))
+    }
+}
+
+/// Whether `host` only accepts connections from this machine
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_needs_auth_off_loopback() {
+        let enabled = ConsoleConfig { enabled: true };
+        for host in ["127.0.0.1", "localhost", "::1", "[::1]", "127.0.0.2"] {
+            assert!(enabled.validate(false, host).is_ok(), "{host}");
+        }
+        for host in ["0.0.0.0", "::", "192.168.1.10", "gateway.example.com"] {
+            assert!(enabled.validate(false, host).is_err(), "{host}");
+            assert!(enabled.validate(true, host).is_ok(), "{host}");
+        }
+        assert!(ConsoleConfig::default().validate(false, "0.0.0.0").is_ok());
+    }
+}
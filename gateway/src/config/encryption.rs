@@ -0,0 +1,428 @@
+//! Encrypted values in YAML configuration files
+//!
+//! Any string in a config file can be replaced by its ciphertext tagged
+//! `!encrypted`, so the file can be committed with provider keys in it:
+//!
+//! ```yaml
+//! providers:
+//!   deepgram_api_key: !encrypted "v1:3f9a1c07:q83vEjRk..."
+//! ```
+//!
+//! Values are encrypted with AES-256-GCM under a 32-byte master key read from
+//! `CONFIG_MASTER_KEY` (base64) or from the file named by
+//! `CONFIG_MASTER_KEY_FILE`. The value is `v1:<key id>:<payload>`, where the
+//! key id is the first 4 bytes of the SHA-256 of the master key in hex and
+//! the payload is the base64 of the 12-byte nonce followed by the ciphertext
+//! and tag. The key id tells a wrong master key apart from a damaged value.
+//!
+//! `waav-gateway config encrypt-value` prints the tagged form of a value.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+/// YAML tag marking an encrypted value
+pub const ENCRYPTED_TAG: &str = "encrypted";
+
+/// Environment variable holding the base64 master key
+pub const CONFIG_MASTER_KEY_ENV: &str = "CONFIG_MASTER_KEY";
+
+/// Environment variable naming a file that holds the base64 master key
+pub const CONFIG_MASTER_KEY_FILE_ENV: &str = "CONFIG_MASTER_KEY_FILE";
+
+/// Length of the master key in bytes
+pub const MASTER_KEY_LEN: usize = 32;
+
+const ENCRYPTED_VALUE_VERSION: &str = "v1";
+const NONCE_LEN: usize = 12;
+
+/// Error decrypting the encrypted values of a config file
+///
+/// `path` is the location of the value in the file, e.g.
+/// `providers.deepgram_api_key`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfigEncryptionError {
+    #[error(
+        "Config value at {path} is encrypted but no master key is set; set {CONFIG_MASTER_KEY_ENV} or {CONFIG_MASTER_KEY_FILE_ENV}"
+    )]
+    MissingKey { path: String },
+
+    #[error("Invalid config master key: {0}")]
+    InvalidKey(String),
+
+    /// The value was encrypted under another master key
+    #[error(
+        "Config value at {path} was encrypted with a different master key (value key id {value_key_id}, master key id {key_id})"
+    )]
+    WrongKey {
+        path: String,
+        value_key_id: String,
+        key_id: String,
+    },
+
+    /// The value is malformed or was modified after encryption
+    #[error("Encrypted config value at {path} is corrupt: {reason}")]
+    CorruptCiphertext { path: String, reason: String },
+
+    #[error("Config value at {path} is tagged !{ENCRYPTED_TAG} but is not a string")]
+    NotAString { path: String },
+}
+
+/// Master key for encrypted config values; zeroed on drop
+pub struct MasterKey {
+    key: Zeroizing<[u8; MASTER_KEY_LEN]>,
+}
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MasterKey")
+            .field("key_id", &self.key_id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MasterKey {
+    /// Create a key from its raw bytes
+    pub fn from_bytes(bytes: [u8; MASTER_KEY_LEN]) -> Self {
+        Self {
+            key: Zeroizing::new(bytes),
+        }
+    }
+
+    /// Parse a base64 key, ignoring surrounding whitespace
+    pub fn from_base64(encoded: &str) -> Result<Self, ConfigEncryptionError> {
+        let decoded =
+            Zeroizing::new(BASE64.decode(encoded.trim()).map_err(|e| {
+                ConfigEncryptionError::InvalidKey(format!("not valid base64: {e}"))
+            })?);
+        let bytes: [u8; MASTER_KEY_LEN] = decoded.as_slice().try_into().map_err(|_| {
+            ConfigEncryptionError::InvalidKey(format!(
+                "expected {MASTER_KEY_LEN} bytes, got {}",
+                decoded.len()
+            ))
+        })?;
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Read the key from `CONFIG_MASTER_KEY` or `CONFIG_MASTER_KEY_FILE`
+    ///
+    /// Returns `None` when neither is set. Setting both is an error.
+    pub fn from_env() -> Result<Option<Self>, ConfigEncryptionError> {
+        let inline = std::env::var(CONFIG_MASTER_KEY_ENV)
+            .ok()
+            .map(Zeroizing::new);
+        let file = std::env::var(CONFIG_MASTER_KEY_FILE_ENV).ok();
+        match (inline, file) {
+            (Some(_), Some(_)) => Err(ConfigEncryptionError::InvalidKey(format!(
+                "set only one of {CONFIG_MASTER_KEY_ENV} and {CONFIG_MASTER_KEY_FILE_ENV}"
+            ))),
+            (Some(encoded), None) => Self::from_base64(&encoded).map(Some),
+            (None, Some(path)) => {
+                let encoded = Zeroizing::new(std::fs::read_to_string(&path).map_err(|e| {
+                    ConfigEncryptionError::InvalidKey(format!("failed to read {path}: {e}"))
+                })?);
+                Self::from_base64(&encoded).map(Some)
+            }
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Short identifier of the key, stored with every value it encrypts
+    pub fn key_id(&self) -> String {
+        hex::encode(&Sha256::digest(self.key.as_slice())[..4])
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(self.key.as_slice()))
+    }
+}
+
+/// Encrypt `plaintext` into the value stored under the `!encrypted` tag
+pub fn encrypt_value(key: &MasterKey, plaintext: &str) -> String {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    // Encrypting into a Vec only fails on allocation limits
+    let ciphertext = key
+        .cipher()
+        .encrypt(&nonce, plaintext.as_bytes())
+        .expect("AES-256-GCM encryption failed");
+    let mut payload = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+    format!(
+        "{ENCRYPTED_VALUE_VERSION}:{}:{}",
+        key.key_id(),
+        BASE64.encode(payload)
+    )
+}
+
+/// Decrypt a value produced by [`encrypt_value`]
+///
+/// `path` only labels errors.
+pub fn decrypt_value(
+    key: &MasterKey,
+    path: &str,
+    encrypted: &str,
+) -> Result<String, ConfigEncryptionError> {
+    let corrupt = |reason: &str| ConfigEncryptionError::CorruptCiphertext {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let mut parts = encrypted.trim().splitn(3, ':');
+    let (Some(version), Some(value_key_id), Some(payload)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(corrupt("expected v1:<key id>:<payload>"));
+    };
+    if version != ENCRYPTED_VALUE_VERSION {
+        return Err(corrupt(&format!("unsupported version '{version}'")));
+    }
+    let key_id = key.key_id();
+    if value_key_id != key_id {
+        return Err(ConfigEncryptionError::WrongKey {
+            path: path.to_string(),
+            value_key_id: value_key_id.to_string(),
+            key_id,
+        });
+    }
+
+    let payload = BASE64
+        .decode(payload)
+        .map_err(|_| corrupt("payload is not valid base64"))?;
+    if payload.len() < NONCE_LEN {
+        return Err(corrupt("payload is too short"));
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    // The key id matched, so a failed tag means the value was damaged
+    let mut plaintext = Zeroizing::new(
+        key.cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| corrupt("authentication failed"))?,
+    );
+    String::from_utf8(std::mem::take(&mut *plaintext)).map_err(|e| {
+        let mut bytes = e.into_bytes();
+        bytes.zeroize();
+        corrupt("plaintext is not UTF-8")
+    })
+}
+
+/// Replace every `!encrypted` value in `value` by its plaintext
+///
+/// The master key is only obtained through `load_key` when an encrypted value
+/// is found, so files without encrypted values need no key. On error the
+/// values decrypted so far are zeroed.
+///
+/// # Returns
+/// * `usize` - Number of values decrypted
+pub fn decrypt_tagged_values<F>(
+    value: &mut Value,
+    load_key: F,
+) -> Result<usize, ConfigEncryptionError>
+where
+    F: FnOnce() -> Result<Option<MasterKey>, ConfigEncryptionError>,
+{
+    let mut key = None;
+    let mut load_key = Some(load_key);
+    let mut decrypted = 0;
+    decrypt_in_place(value, String::new(), &mut |path, encrypted| {
+        if key.is_none() {
+            let load = load_key.take().expect("master key loaded once");
+            key = Some(load()?.ok_or_else(|| ConfigEncryptionError::MissingKey {
+                path: path.to_string(),
+            })?);
+        }
+        let plaintext = decrypt_value(key.as_ref().expect("master key loaded"), path, encrypted)?;
+        decrypted += 1;
+        Ok(plaintext)
+    })
+    .inspect_err(|_| zeroize_strings(value))?;
+    Ok(decrypted)
+}
+
+/// Zero every string in `value`, so decrypted values do not outlive the tree
+///
+/// Mapping keys are left as they are; values are only decrypted in place of
+/// mapping values and sequence items.
+pub fn zeroize_strings(value: &mut Value) {
+    match value {
+        Value::String(string) => string.zeroize(),
+        Value::Tagged(tagged) => zeroize_strings(&mut tagged.value),
+        Value::Mapping(mapping) => mapping.values_mut().for_each(zeroize_strings),
+        Value::Sequence(items) => items.iter_mut().for_each(zeroize_strings),
+        _ => {}
+    }
+}
+
+fn decrypt_in_place(
+    value: &mut Value,
+    path: String,
+    decrypt: &mut dyn FnMut(&str, &str) -> Result<String, ConfigEncryptionError>,
+) -> Result<(), ConfigEncryptionError> {
+    match value {
+        Value::Tagged(tagged) if tagged.tag == ENCRYPTED_TAG => {
+            let Value::String(encrypted) = &tagged.value else {
+                return Err(ConfigEncryptionError::NotAString { path });
+            };
+            *value = Value::String(decrypt(&path, encrypted)?);
+        }
+        Value::Tagged(tagged) => decrypt_in_place(&mut tagged.value, path, decrypt)?,
+        Value::Mapping(mapping) => {
+            for (key, child) in mapping.iter_mut() {
+                let name = match key {
+                    Value::String(name) => name.clone(),
+                    other => serde_yaml::to_string(other)
+                        .map(|s| s.trim_end().to_string())
+                        .unwrap_or_default(),
+                };
+                let child_path = if path.is_empty() {
+                    name
+                } else {
+                    format!("{path}.{name}")
+                };
+                decrypt_in_place(child, child_path, decrypt)?;
+            }
+        }
+        Value::Sequence(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                decrypt_in_place(child, format!("{path}[{index}]"), decrypt)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> MasterKey {
+        MasterKey::from_bytes([byte; MASTER_KEY_LEN])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = key(7);
+        let encrypted = encrypt_value(&key, "dg-secret");
+        assert!(encrypted.starts_with(&format!("v1:{}:", key.key_id())));
+        assert!(!encrypted.contains("dg-secret"));
+        assert_eq!(decrypt_value(&key, "a", &encrypted).unwrap(), "dg-secret");
+        // Every encryption uses a fresh nonce
+        assert_ne!(encrypted, encrypt_value(&key, "dg-secret"));
+    }
+
+    #[test]
+    fn test_wrong_key_and_corrupt_value_are_distinguished() {
+        let encrypted = encrypt_value(&key(1), "secret");
+        assert!(matches!(
+            decrypt_value(&key(2), "providers.x", &encrypted),
+            Err(ConfigEncryptionError::WrongKey { path, .. }) if path == "providers.x"
+        ));
+
+        // Flip a bit of the ciphertext, keeping the key id
+        let (prefix, payload) = encrypted.rsplit_once(':').unwrap();
+        let mut bytes = BASE64.decode(payload).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let tampered = format!("{prefix}:{}", BASE64.encode(bytes));
+        assert!(matches!(
+            decrypt_value(&key(1), "providers.x", &tampered),
+            Err(ConfigEncryptionError::CorruptCiphertext { .. })
+        ));
+        assert!(matches!(
+            decrypt_value(&key(1), "providers.x", "v1:nope"),
+            Err(ConfigEncryptionError::CorruptCiphertext { .. })
+        ));
+    }
+
+    #[test]
+    fn test_master_key_from_base64() {
+        let encoded = BASE64.encode([9u8; MASTER_KEY_LEN]);
+        let parsed = MasterKey::from_base64(&format!("{encoded}\n")).unwrap();
+        assert_eq!(parsed.key_id(), key(9).key_id());
+        assert!(matches!(
+            MasterKey::from_base64(&BASE64.encode([1u8; 16])),
+            Err(ConfigEncryptionError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_tagged_values_reports_path() {
+        let key_bytes = [3u8; MASTER_KEY_LEN];
+        let encrypted = encrypt_value(&MasterKey::from_bytes(key_bytes), "secret");
+        let yaml = format!("agents:\n  - id: a\n    token: !encrypted \"{encrypted}\"\n");
+
+        let mut value: Value = serde_yaml::from_str(&yaml).unwrap();
+        let count =
+            decrypt_tagged_values(&mut value, || Ok(Some(MasterKey::from_bytes(key_bytes))))
+                .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(value["agents"][0]["token"], Value::from("secret"));
+
+        let mut value: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            decrypt_tagged_values(&mut value, || Ok(None)),
+            Err(ConfigEncryptionError::MissingKey {
+                path: "agents[0].token".to_string()
+            })
+        );
+
+        let mut value: Value = serde_yaml::from_str("a: !encrypted [1]").unwrap();
+        assert_eq!(
+            decrypt_tagged_values(&mut value, || Ok(Some(key(1)))),
+            Err(ConfigEncryptionError::NotAString {
+                path: "a".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_zeroize_strings_clears_decrypted_buffers() {
+        let key_bytes = [4u8; MASTER_KEY_LEN];
+        let encrypted = encrypt_value(&MasterKey::from_bytes(key_bytes), "dg-secret");
+        let yaml = format!("providers:\n  keys:\n    - !encrypted \"{encrypted}\"\n");
+        let mut value: Value = serde_yaml::from_str(&yaml).unwrap();
+        decrypt_tagged_values(&mut value, || Ok(Some(MasterKey::from_bytes(key_bytes)))).unwrap();
+
+        let Value::String(secret) = &value["providers"]["keys"][0] else {
+            panic!("decrypted value is a string");
+        };
+        assert_eq!(secret, "dg-secret");
+        let (ptr, len) = (secret.as_ptr(), secret.len());
+
+        zeroize_strings(&mut value);
+        let Value::String(secret) = &value["providers"]["keys"][0] else {
+            panic!("zeroed value is a string");
+        };
+        assert!(secret.is_empty());
+        // Zeroing keeps the allocation, so the old bytes can still be read
+        assert_eq!(secret.as_ptr(), ptr);
+        // SAFETY: `secret` still owns the buffer, which held `len` bytes
+        let buffer = unsafe { std::slice::from_raw_parts(ptr, len) };
+        assert!(buffer.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_decrypted_values_zeroed_on_error() {
+        let key_bytes = [5u8; MASTER_KEY_LEN];
+        let encrypted = encrypt_value(&MasterKey::from_bytes(key_bytes), "secret");
+        let yaml = format!("a: !encrypted \"{encrypted}\"\nb: !encrypted [1]\n");
+        let mut value: Value = serde_yaml::from_str(&yaml).unwrap();
+
+        assert!(
+            decrypt_tagged_values(&mut value, || Ok(Some(MasterKey::from_bytes(key_bytes))))
+                .is_err()
+        );
+        assert_eq!(value["a"], Value::from(""));
+    }
+
+    #[test]
+    fn test_key_not_loaded_without_encrypted_values() {
+        let mut value: Value = serde_yaml::from_str("server:\n  port: 3001\n").unwrap();
+        let count = decrypt_tagged_values(&mut value, || panic!("key loaded")).unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -1,14 +1,34 @@
+use std::collections::HashMap;
 use std::env;
 use std::path::PathBuf;
 
+use super::admin_scopes::AdminScopeIds;
+use super::greeting::GreetingConfig;
+use super::load_shedding::LoadSheddingConfig;
 use super::parse_auth_api_secrets_json;
 use super::sip::{SipConfig, SipHookConfig};
-use super::utils::parse_bool;
+use super::trusted_proxies::parse_trusted_proxies;
+use super::usage::{UsageConfig, UsageSinkKind};
+use super::utils::{parse_bool, parse_comma_list};
 use super::validation::{
-    validate_auth_api_secrets, validate_auth_required, validate_jwt_auth, validate_security_config,
-    validate_tls_config,
+    validate_auth_api_secrets, validate_auth_required, validate_greeting_config, validate_jwt_auth,
+    validate_load_shedding_config, validate_plugin_http_max_concurrency, validate_plugin_storage,
+    validate_provider_connect_timeout, validate_provider_error_max_chars,
+    validate_replay_max_concurrent_jobs, validate_security_config, validate_tls_config,
+    validate_tts_max_pending_utterances, validate_tts_system_speak_max_chars,
+    validate_usage_config,
 };
-use super::{AuthApiSecret, PluginConfig, ServerConfig, TlsConfig};
+use super::{
+    AuthApiSecret, DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES, DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY,
+    DEFAULT_PLUGIN_HTTP_TIMEOUT_MS, DEFAULT_PLUGIN_INIT_TIMEOUT_SECS,
+    DEFAULT_PLUGIN_STORAGE_CHECK_INTERVAL_SECS, DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES,
+    DEFAULT_REPLAY_MAX_CONCURRENT_JOBS, PluginConfig, PluginConflictPolicy, ServerConfig,
+    TlsConfig,
+};
+use crate::core::voice_manager::{
+    DEFAULT_MAX_PENDING_UTTERANCES, DEFAULT_SYSTEM_SPEAK_MAX_CHARS, TTSQueuePolicy,
+};
+use crate::errors::provider_error::DEFAULT_PROVIDER_ERROR_MAX_CHARS;
 
 impl ServerConfig {
     /// Load configuration from environment variables
@@ -142,6 +162,15 @@ impl ServerConfig {
             .ok()
             .and_then(|v| parse_bool(&v))
             .unwrap_or(false);
+        let auth_admin_ids = env::var("AUTH_ADMIN_IDS")
+            .ok()
+            .map(|v| parse_comma_list(&v))
+            .unwrap_or_default();
+        let auth_monitor_audio_ids = env::var("AUTH_MONITOR_AUDIO_IDS")
+            .ok()
+            .map(|v| parse_comma_list(&v))
+            .unwrap_or_default();
+        let auth_admin_scopes = parse_admin_scopes_env();
 
         let auth_api_secrets = if let Some(json) = auth_api_secrets_json {
             parse_auth_api_secrets_json(&json)?
@@ -172,6 +201,11 @@ impl ServerConfig {
         // SIP configuration
         let sip = parse_sip_env()?;
 
+        // Greeting configuration
+        let greeting = parse_greeting_env()?;
+        let greeting_assets_dir = env::var("GREETING_ASSETS_DIR").ok().map(PathBuf::from);
+        validate_greeting_config(&greeting, &greeting_assets_dir)?;
+
         // Security configuration
         let cors_allowed_origins = env::var("CORS_ALLOWED_ORIGINS").ok();
         let rate_limit_requests_per_second = env::var("RATE_LIMIT_REQUESTS_PER_SECOND")
@@ -189,6 +223,12 @@ impl ServerConfig {
             .ok()
             .and_then(|v| v.parse::<u32>().ok())
             .unwrap_or(100);
+        let trusted_proxies = parse_trusted_proxies(
+            &env::var("TRUSTED_PROXIES")
+                .ok()
+                .map(|v| parse_comma_list(&v))
+                .unwrap_or_default(),
+        )?;
 
         // Validate security configuration
         validate_security_config(
@@ -197,6 +237,52 @@ impl ServerConfig {
             max_connections_per_ip,
         )?;
 
+        let provider_connect_timeout_secs = env::var("PROVIDER_CONNECT_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(10);
+        validate_provider_connect_timeout(provider_connect_timeout_secs)?;
+
+        let tts_fallback_voices = parse_tts_fallback_voices_env()?;
+
+        let tts_max_pending_utterances = env::var("TTS_MAX_PENDING_UTTERANCES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_PENDING_UTTERANCES);
+        validate_tts_max_pending_utterances(tts_max_pending_utterances)?;
+        let tts_queue_policy = parse_tts_queue_policy_env()?.unwrap_or_default();
+        let tts_system_speak_max_chars = env::var("TTS_SYSTEM_SPEAK_MAX_CHARS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_SYSTEM_SPEAK_MAX_CHARS);
+        validate_tts_system_speak_max_chars(tts_system_speak_max_chars)?;
+        let provider_error_max_chars = env::var("PROVIDER_ERROR_MAX_CHARS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_PROVIDER_ERROR_MAX_CHARS);
+        validate_provider_error_max_chars(provider_error_max_chars)?;
+
+        // Usage record configuration
+        let usage = parse_usage_env()?;
+        validate_usage_config(&usage)?;
+
+        // Load shedding thresholds
+        let load_shedding = parse_load_shedding_env()?;
+        validate_load_shedding_config(&load_shedding)?;
+
+        // Concurrent replay jobs
+        let replay_max_concurrent_jobs = env::var("REPLAY_MAX_CONCURRENT_JOBS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_REPLAY_MAX_CONCURRENT_JOBS);
+        validate_replay_max_concurrent_jobs(replay_max_concurrent_jobs)?;
+
+        // Strict config messages
+        let strict_config = env::var("STRICT_CONFIG")
+            .ok()
+            .and_then(|v| parse_bool(&v))
+            .unwrap_or(false);
+
         // Plugin configuration (backward compatible: enabled by default)
         let plugins_enabled = env::var("PLUGINS_ENABLED")
             .ok()
@@ -205,11 +291,73 @@ impl ServerConfig {
 
         let plugins_dir = env::var("PLUGINS_DIR").ok().map(PathBuf::from);
 
+        let plugins_dirs = env::var_os("PLUGINS_DIRS")
+            .map(|dirs| env::split_paths(&dirs).collect())
+            .unwrap_or_default();
+
+        let plugins_conflict_policy = parse_plugin_conflict_policy_env()?.unwrap_or_default();
+
+        let plugins_allow_override = env::var("PLUGINS_ALLOW_OVERRIDE")
+            .ok()
+            .and_then(|v| parse_bool(&v))
+            .unwrap_or(false);
+
+        let plugins_require_exact_abi = env::var("PLUGINS_REQUIRE_EXACT_ABI")
+            .ok()
+            .and_then(|v| parse_bool(&v))
+            .unwrap_or(false);
+
+        let plugins_init_timeout_secs = env::var("PLUGINS_INIT_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PLUGIN_INIT_TIMEOUT_SECS);
+
+        let plugins_http_max_body_bytes = env::var("PLUGINS_HTTP_MAX_BODY_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES);
+
+        let plugins_http_timeout_ms = env::var("PLUGINS_HTTP_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PLUGIN_HTTP_TIMEOUT_MS);
+
+        let plugins_http_max_concurrency = env::var("PLUGINS_HTTP_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY);
+
+        let plugins_data_dir = env::var("PLUGINS_DATA_DIR").ok().map(PathBuf::from);
+
+        let plugins_storage_quota_bytes = env::var("PLUGINS_STORAGE_QUOTA_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES);
+
+        let plugins_storage_check_interval_secs = env::var("PLUGINS_STORAGE_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PLUGIN_STORAGE_CHECK_INTERVAL_SECS);
+
         let plugins = PluginConfig {
             enabled: plugins_enabled,
             plugin_dir: plugins_dir,
+            plugin_dirs: plugins_dirs,
+            conflict_policy: plugins_conflict_policy,
+            allow_override: plugins_allow_override,
+            require_exact_abi: plugins_require_exact_abi,
+            init_timeout_secs: plugins_init_timeout_secs,
+            http_max_body_bytes: plugins_http_max_body_bytes,
+            http_timeout_ms: plugins_http_timeout_ms,
+            http_max_concurrency: plugins_http_max_concurrency,
+            http_access: Default::default(), // Scope grants are YAML-only
+            data_dir: plugins_data_dir,
+            storage_quota_bytes: plugins_storage_quota_bytes,
+            storage_check_interval_secs: plugins_storage_check_interval_secs,
             provider_config: Default::default(), // No provider config from env vars
         };
+        validate_plugin_http_max_concurrency(plugins.http_max_concurrency)?;
+        validate_plugin_storage(&plugins)?;
 
         Ok(ServerConfig {
             host,
@@ -254,6 +402,8 @@ impl ServerConfig {
             auth_api_secrets,
             auth_timeout_seconds,
             auth_required,
+            auth_admin_ids,
+            auth_monitor_audio_ids,
             sip,
             // Security configuration
             cors_allowed_origins,
@@ -261,9 +411,240 @@ impl ServerConfig {
             rate_limit_burst_size,
             max_websocket_connections,
             max_connections_per_ip,
+            trusted_proxies,
+            provider_connect_timeout_secs,
+            tts_fallback_voices,
+            tts_max_pending_utterances,
+            tts_queue_policy,
+            tts_system_speak_max_chars,
+            provider_error_max_chars,
+            deepgram_connection_budget: Default::default(),
             plugins,
+            greeting,
+            greeting_assets_dir,
+            usage,
+            agents: Vec::new(),
+            strict_config,
+            selftest: None,
+            load_shedding,
+            replay_max_concurrent_jobs,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
+        })
+    }
+}
+
+/// Parse the per-provider fallback TTS voices from `TTS_FALLBACK_VOICES`
+///
+/// The variable holds comma-separated `provider=voice_id` pairs, e.g.
+/// `elevenlabs=21m00Tcm4TlvDq8ikWAM,cartesia=a0e99841-438c-4a64-b679-ae501e7d6091`.
+///
+/// # Returns
+/// * `Result<HashMap<String, String>, Box<dyn std::error::Error>>` - Voice id by provider
+///
+/// # Errors
+/// Returns an error if an entry is not a `provider=voice_id` pair
+pub(super) fn parse_tts_fallback_voices_env()
+-> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let Ok(value) = env::var("TTS_FALLBACK_VOICES") else {
+        return Ok(HashMap::new());
+    };
+
+    parse_comma_list(&value)
+        .into_iter()
+        .map(|entry| match entry.split_once('=') {
+            Some((provider, voice_id))
+                if !provider.trim().is_empty() && !voice_id.trim().is_empty() =>
+            {
+                Ok((provider.trim().to_string(), voice_id.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Invalid TTS_FALLBACK_VOICES entry '{entry}', expected provider=voice_id"
+            )
+            .into()),
         })
+        .collect()
+}
+
+/// Parse the TTS queue policy from `TTS_QUEUE_POLICY`
+///
+/// # Returns
+/// * `Result<Option<TTSQueuePolicy>, Box<dyn std::error::Error>>` - Policy, or `None` if unset
+///
+/// # Errors
+/// Returns an error if the value is neither `reject` nor `drop_oldest`
+pub(super) fn parse_tts_queue_policy_env()
+-> Result<Option<TTSQueuePolicy>, Box<dyn std::error::Error>> {
+    match env::var("TTS_QUEUE_POLICY") {
+        Ok(value) => Ok(Some(value.parse::<TTSQueuePolicy>()?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Parse the admin scope holders from `AUTH_ADMIN_READ_IDS`,
+/// `AUTH_ADMIN_WRITE_IDS` and `AUTH_ADMIN_CHAOS_IDS`
+pub(super) fn parse_admin_scopes_env() -> AdminScopeIds {
+    let ids = |name: &str| {
+        env::var(name)
+            .ok()
+            .map(|v| parse_comma_list(&v))
+            .unwrap_or_default()
+    };
+    AdminScopeIds {
+        read: ids("AUTH_ADMIN_READ_IDS"),
+        write: ids("AUTH_ADMIN_WRITE_IDS"),
+        chaos: ids("AUTH_ADMIN_CHAOS_IDS"),
+    }
+}
+
+/// Parse the dynamic plugin conflict policy from `PLUGINS_CONFLICT_POLICY`
+pub(super) fn parse_plugin_conflict_policy_env()
+-> Result<Option<PluginConflictPolicy>, Box<dyn std::error::Error>> {
+    match env::var("PLUGINS_CONFLICT_POLICY") {
+        Ok(value) => Ok(Some(value.parse::<PluginConflictPolicy>()?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Parse the usage record configuration from environment variables
+///
+/// Reads the usage sink from the following environment variables:
+/// - USAGE_SINK: `file`, `webhook` or `both` (usage records are off when unset)
+/// - USAGE_FILE_PATH: JSON-lines file records are appended to
+/// - USAGE_FILE_MAX_BYTES: Size at which the file is rotated
+/// - USAGE_FILE_MAX_FILES: Number of rotated files kept
+/// - USAGE_WEBHOOK_URL: URL records are POSTed to
+/// - USAGE_WEBHOOK_SECRET: Secret the webhook payload is signed with
+/// - USAGE_RECONCILIATION_PATH: JSON-lines report of TTS utterances that fail
+///   reconciliation (reconciliation is off when unset)
+/// - USAGE_RECONCILIATION_TOLERANCE_PERCENT: Deviation from the expected audio
+///   length that flags an utterance
+///
+/// # Returns
+/// * `Result<Option<UsageConfig>, Box<dyn std::error::Error>>` - The usage config or None
+///
+/// # Errors
+/// Returns an error if USAGE_SINK is unknown or a size, count or percentage is not a number
+pub(super) fn parse_usage_env() -> Result<Option<UsageConfig>, Box<dyn std::error::Error>> {
+    let Ok(sink) = env::var("USAGE_SINK") else {
+        return Ok(None);
+    };
+    let mut usage = UsageConfig::new(sink.parse::<UsageSinkKind>()?);
+
+    usage.file_path = env::var("USAGE_FILE_PATH").ok().map(PathBuf::from);
+    if let Ok(v) = env::var("USAGE_FILE_MAX_BYTES") {
+        usage.file_max_bytes = v
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid USAGE_FILE_MAX_BYTES '{v}': {e}"))?;
+    }
+    if let Ok(v) = env::var("USAGE_FILE_MAX_FILES") {
+        usage.file_max_files = v
+            .parse::<usize>()
+            .map_err(|e| format!("Invalid USAGE_FILE_MAX_FILES '{v}': {e}"))?;
     }
+    usage.webhook_url = env::var("USAGE_WEBHOOK_URL").ok();
+    usage.webhook_secret = env::var("USAGE_WEBHOOK_SECRET").ok();
+    usage.reconciliation_path = env::var("USAGE_RECONCILIATION_PATH")
+        .ok()
+        .map(PathBuf::from);
+    if let Ok(v) = env::var("USAGE_RECONCILIATION_TOLERANCE_PERCENT") {
+        usage.reconciliation_tolerance_percent = v
+            .parse::<u32>()
+            .map_err(|e| format!("Invalid USAGE_RECONCILIATION_TOLERANCE_PERCENT '{v}': {e}"))?;
+    }
+
+    Ok(Some(usage))
+}
+
+/// Parse the load shedding thresholds from environment variables
+///
+/// Reads the thresholds from the following environment variables:
+/// - LOAD_SHED_MAX_ACTIVE_SESSIONS: Open sessions at which new ones are shed
+/// - LOAD_SHED_MAX_POOL_SATURATION: Provider pool saturation (0.0 to 1.0) at which new sessions are shed
+/// - LOAD_SHED_MAX_EVENT_LOOP_LAG_MS: Runtime lag at which new sessions are shed
+/// - LOAD_SHED_RETRY_AFTER_SECS: `Retry-After` sent with shed requests
+///
+/// # Returns
+/// * `Result<Option<LoadSheddingConfig>, Box<dyn std::error::Error>>` - The thresholds, or None
+///   when no threshold is set
+///
+/// # Errors
+/// Returns an error if a value is not a number
+pub(super) fn parse_load_shedding_env()
+-> Result<Option<LoadSheddingConfig>, Box<dyn std::error::Error>> {
+    let mut config = LoadSheddingConfig::default();
+
+    if let Ok(v) = env::var("LOAD_SHED_MAX_ACTIVE_SESSIONS") {
+        config.max_active_sessions = Some(
+            v.parse::<usize>()
+                .map_err(|e| format!("Invalid LOAD_SHED_MAX_ACTIVE_SESSIONS '{v}': {e}"))?,
+        );
+    }
+    if let Ok(v) = env::var("LOAD_SHED_MAX_POOL_SATURATION") {
+        config.max_pool_saturation = Some(
+            v.parse::<f64>()
+                .map_err(|e| format!("Invalid LOAD_SHED_MAX_POOL_SATURATION '{v}': {e}"))?,
+        );
+    }
+    if let Ok(v) = env::var("LOAD_SHED_MAX_EVENT_LOOP_LAG_MS") {
+        config.max_event_loop_lag_ms = Some(
+            v.parse::<u64>()
+                .map_err(|e| format!("Invalid LOAD_SHED_MAX_EVENT_LOOP_LAG_MS '{v}': {e}"))?,
+        );
+    }
+    if let Ok(v) = env::var("LOAD_SHED_RETRY_AFTER_SECS") {
+        config.retry_after_secs = v
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid LOAD_SHED_RETRY_AFTER_SECS '{v}': {e}"))?;
+    }
+
+    Ok(config.is_enabled().then_some(config))
+}
+
+/// Parse the greeting configuration from environment variables
+///
+/// Reads the greeting from the following environment variables:
+/// - GREETING_TEXT: Text spoken when a session starts
+/// - GREETING_ASSET: Name of a WAV file in GREETING_ASSETS_DIR to play instead
+/// - GREETING_DELAY_MS: Delay before the greeting starts
+///
+/// # Returns
+/// * `Result<Option<GreetingConfig>, Box<dyn std::error::Error>>` - The greeting or None
+///
+/// # Errors
+/// Returns an error if GREETING_DELAY_MS is not a number
+pub(super) fn parse_greeting_env() -> Result<Option<GreetingConfig>, Box<dyn std::error::Error>> {
+    let text = env::var("GREETING_TEXT").ok();
+    let asset = env::var("GREETING_ASSET").ok();
+    let delay_ms = env::var("GREETING_DELAY_MS")
+        .ok()
+        .map(|v| {
+            v.parse::<u64>()
+                .map_err(|e| format!("Invalid GREETING_DELAY_MS '{v}': {e}"))
+        })
+        .transpose()?;
+
+    if text.is_none() && asset.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(GreetingConfig {
+        text,
+        asset,
+        delay_ms,
+    }))
 }
 
 /// Parse SIP configuration from environment variables
@@ -373,6 +754,12 @@ mod tests {
             env::remove_var("AUTH_API_SECRET");
             env::remove_var("AUTH_API_SECRET_ID");
             env::remove_var("AUTH_TIMEOUT_SECONDS");
+            env::remove_var("AUTH_ADMIN_IDS");
+            env::remove_var("TRUSTED_PROXIES");
+            env::remove_var("AUTH_MONITOR_AUDIO_IDS");
+            env::remove_var("AUTH_ADMIN_READ_IDS");
+            env::remove_var("AUTH_ADMIN_WRITE_IDS");
+            env::remove_var("AUTH_ADMIN_CHAOS_IDS");
             env::remove_var("HOST");
             env::remove_var("PORT");
             env::remove_var("TLS_ENABLED");
@@ -383,6 +770,26 @@ mod tests {
             env::remove_var("SIP_HOOKS_JSON");
             env::remove_var("SIP_HOOK_SECRET");
             env::remove_var("RECORDING_S3_PREFIX");
+            env::remove_var("PROVIDER_CONNECT_TIMEOUT_SECS");
+            env::remove_var("TTS_FALLBACK_VOICES");
+            env::remove_var("TTS_MAX_PENDING_UTTERANCES");
+            env::remove_var("TTS_SYSTEM_SPEAK_MAX_CHARS");
+            env::remove_var("PROVIDER_ERROR_MAX_CHARS");
+            env::remove_var("TTS_QUEUE_POLICY");
+            env::remove_var("USAGE_SINK");
+            env::remove_var("USAGE_FILE_PATH");
+            env::remove_var("USAGE_FILE_MAX_BYTES");
+            env::remove_var("USAGE_FILE_MAX_FILES");
+            env::remove_var("USAGE_WEBHOOK_URL");
+            env::remove_var("USAGE_WEBHOOK_SECRET");
+            env::remove_var("USAGE_RECONCILIATION_PATH");
+            env::remove_var("USAGE_RECONCILIATION_TOLERANCE_PERCENT");
+            env::remove_var("STRICT_CONFIG");
+            env::remove_var("LOAD_SHED_MAX_ACTIVE_SESSIONS");
+            env::remove_var("LOAD_SHED_MAX_POOL_SATURATION");
+            env::remove_var("LOAD_SHED_MAX_EVENT_LOOP_LAG_MS");
+            env::remove_var("LOAD_SHED_RETRY_AFTER_SECS");
+            env::remove_var("REPLAY_MAX_CONCURRENT_JOBS");
         }
     }
 
@@ -539,6 +946,202 @@ mod tests {
         cleanup_env_vars();
     }
 
+    #[test]
+    #[serial]
+    fn test_from_env_provider_connect_timeout() {
+        cleanup_env_vars();
+
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.provider_connect_timeout_secs, 10);
+
+        unsafe {
+            env::set_var("PROVIDER_CONNECT_TIMEOUT_SECS", "3");
+        }
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.provider_connect_timeout_secs, 3);
+
+        unsafe {
+            env::set_var("PROVIDER_CONNECT_TIMEOUT_SECS", "0");
+        }
+        assert!(ServerConfig::from_env().is_err());
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_tts_fallback_voices() {
+        cleanup_env_vars();
+
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert!(config.tts_fallback_voices.is_empty());
+
+        unsafe {
+            env::set_var(
+                "TTS_FALLBACK_VOICES",
+                "elevenlabs=backup-voice, cartesia = other-voice",
+            );
+        }
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.tts_fallback_voices.len(), 2);
+        assert_eq!(config.tts_fallback_voices["elevenlabs"], "backup-voice");
+        assert_eq!(config.tts_fallback_voices["cartesia"], "other-voice");
+
+        unsafe {
+            env::set_var("TTS_FALLBACK_VOICES", "elevenlabs");
+        }
+        let err = ServerConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("TTS_FALLBACK_VOICES"));
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_tts_queue_limit() {
+        cleanup_env_vars();
+
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.tts_max_pending_utterances, 5);
+        assert_eq!(config.tts_queue_policy, TTSQueuePolicy::Reject);
+        assert_eq!(config.tts_system_speak_max_chars, 500);
+        assert_eq!(config.provider_error_max_chars, 300);
+
+        unsafe {
+            env::set_var("TTS_MAX_PENDING_UTTERANCES", "2");
+            env::set_var("TTS_QUEUE_POLICY", "drop_oldest");
+            env::set_var("TTS_SYSTEM_SPEAK_MAX_CHARS", "120");
+            env::set_var("PROVIDER_ERROR_MAX_CHARS", "80");
+        }
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.tts_max_pending_utterances, 2);
+        assert_eq!(config.tts_queue_policy, TTSQueuePolicy::DropOldest);
+        assert_eq!(config.tts_system_speak_max_chars, 120);
+        assert_eq!(config.provider_error_max_chars, 80);
+
+        unsafe {
+            env::set_var("TTS_QUEUE_POLICY", "drop_newest");
+        }
+        let err = ServerConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("drop_newest"));
+
+        unsafe {
+            env::set_var("TTS_QUEUE_POLICY", "reject");
+            env::set_var("TTS_MAX_PENDING_UTTERANCES", "0");
+        }
+        assert!(ServerConfig::from_env().is_err());
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_replay_max_concurrent_jobs() {
+        cleanup_env_vars();
+
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.replay_max_concurrent_jobs, 1);
+
+        unsafe {
+            env::set_var("REPLAY_MAX_CONCURRENT_JOBS", "3");
+        }
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert_eq!(config.replay_max_concurrent_jobs, 3);
+
+        unsafe {
+            env::set_var("REPLAY_MAX_CONCURRENT_JOBS", "0");
+        }
+        assert!(ServerConfig::from_env().is_err());
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_load_shedding() {
+        cleanup_env_vars();
+
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert!(config.load_shedding.is_none());
+
+        unsafe {
+            env::set_var("LOAD_SHED_MAX_ACTIVE_SESSIONS", "300");
+            env::set_var("LOAD_SHED_MAX_POOL_SATURATION", "0.75");
+        }
+        let load_shedding = ServerConfig::from_env()
+            .expect("Should load config")
+            .load_shedding
+            .expect("load shedding should be configured");
+        assert_eq!(load_shedding.max_active_sessions, Some(300));
+        assert_eq!(load_shedding.max_pool_saturation, Some(0.75));
+        assert_eq!(load_shedding.max_event_loop_lag_ms, None);
+        assert_eq!(load_shedding.retry_after_secs, 5);
+
+        unsafe {
+            env::set_var("LOAD_SHED_MAX_POOL_SATURATION", "1.5");
+        }
+        let err = ServerConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("max_pool_saturation"));
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_usage() {
+        cleanup_env_vars();
+
+        let config = ServerConfig::from_env().expect("Should load config");
+        assert!(config.usage.is_none());
+
+        unsafe {
+            env::set_var("USAGE_SINK", "both");
+            env::set_var("USAGE_FILE_PATH", "/var/log/waav/usage.jsonl");
+            env::set_var("USAGE_FILE_MAX_BYTES", "1048576");
+            env::set_var("USAGE_WEBHOOK_URL", "https://billing.example.com/usage");
+            env::set_var("USAGE_WEBHOOK_SECRET", "usage-signing-secret");
+            env::set_var(
+                "USAGE_RECONCILIATION_PATH",
+                "/var/log/waav/reconciliation.jsonl",
+            );
+            env::set_var("USAGE_RECONCILIATION_TOLERANCE_PERCENT", "30");
+        }
+        let usage = ServerConfig::from_env()
+            .expect("Should load config")
+            .usage
+            .expect("usage should be configured");
+        assert_eq!(usage.sink, UsageSinkKind::Both);
+        assert_eq!(
+            usage.file_path,
+            Some(PathBuf::from("/var/log/waav/usage.jsonl"))
+        );
+        assert_eq!(usage.file_max_bytes, 1048576);
+        assert_eq!(usage.file_max_files, 5);
+        assert_eq!(
+            usage.webhook_url.as_deref(),
+            Some("https://billing.example.com/usage")
+        );
+        assert_eq!(
+            usage.reconciliation_path,
+            Some(PathBuf::from("/var/log/waav/reconciliation.jsonl"))
+        );
+        assert_eq!(usage.reconciliation_tolerance_percent, 30);
+
+        // The webhook sink needs a signing secret
+        unsafe {
+            env::remove_var("USAGE_WEBHOOK_SECRET");
+        }
+        assert!(ServerConfig::from_env().is_err());
+
+        unsafe {
+            env::set_var("USAGE_SINK", "kafka");
+        }
+        let err = ServerConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("kafka"));
+
+        cleanup_env_vars();
+    }
+
     #[test]
     #[serial]
     fn test_from_env_auth_required_missing_url() {
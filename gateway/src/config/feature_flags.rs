@@ -0,0 +1,292 @@
+//! Session feature flags
+//!
+//! Flags switch optional session components on for some clients before
+//! they are on for everyone. Each flag has a default, an optional rollout
+//! percentage and per-client overrides. A session resolves the flags once,
+//! when it is configured, and keeps that set until it ends.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Flags that session components check
+///
+/// Other names are accepted so flags can be configured ahead of a release,
+/// but they are reported as warnings since nothing reads them.
+pub const KNOWN_FEATURE_FLAGS: &[&str] = &["adaptive_endpointing", "echo_guard"];
+
+/// Granularity of rollout buckets: 10000 buckets of 0.01%
+const ROLLOUT_BUCKETS: u64 = 10_000;
+
+/// Configuration of one feature flag
+///
+/// A client override wins over the rollout, and the rollout wins over the
+/// default. The same shape is accepted by `PUT /admin/feature_flags`.
+///
+/// # Example YAML
+/// ```yaml
+/// feature_flags:
+///   adaptive_endpointing:
+///     default: false
+///     rollout_percent: 25
+///     clients:
+///       project-beta: true
+///       project-legacy: false
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct FeatureFlagConfig {
+    /// Value for clients outside the rollout and without an override
+    pub default: bool,
+    /// Percentage of clients (0 to 100) that get the flag enabled
+    ///
+    /// Clients are bucketed by a hash of the flag name and client ID, so a
+    /// client stays in or out of the rollout across sessions and raising
+    /// the percentage only adds clients.
+    #[cfg_attr(feature = "openapi", schema(example = 25.0))]
+    pub rollout_percent: Option<f64>,
+    /// Per-client values (client ID -> enabled)
+    pub clients: BTreeMap<String, bool>,
+}
+
+impl FeatureFlagConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the rollout percentage is in range
+    /// * `Err(String)` describing the problem otherwise
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(percent) = self.rollout_percent
+            && !(0.0..=100.0).contains(&percent)
+        {
+            return Err(format!(
+                "rollout_percent must be between 0 and 100 (got {percent})"
+            ));
+        }
+        if self.clients.keys().any(|client| client.is_empty()) {
+            return Err("clients contains an empty client ID".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the flag named `name` is enabled for `client_key`
+    pub fn is_enabled_for(&self, name: &str, client_key: &str) -> bool {
+        if let Some(&enabled) = self.clients.get(client_key) {
+            return enabled;
+        }
+        match self.rollout_percent {
+            Some(percent) => rollout_bucket(name, client_key) < percent,
+            None => self.default,
+        }
+    }
+}
+
+/// Rollout bucket of a client for a flag, in `[0, 100)`
+///
+/// Hashing the flag name with the client key gives every flag an
+/// independent rollout population.
+pub fn rollout_bucket(name: &str, client_key: &str) -> f64 {
+    let hash = xxh3_64(format!("{name}:{client_key}").as_bytes());
+    (hash % ROLLOUT_BUCKETS) as f64 * 100.0 / ROLLOUT_BUCKETS as f64
+}
+
+/// Configured flag names that no session component checks
+pub fn unknown_feature_flags(flags: &BTreeMap<String, FeatureFlagConfig>) -> Vec<&str> {
+    flags
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !KNOWN_FEATURE_FLAGS.contains(name))
+        .collect()
+}
+
+/// Feature flags resolved for one session
+///
+/// Serializes as a map of flag name to enabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct FeatureFlags(BTreeMap<String, bool>);
+
+impl FeatureFlags {
+    /// Resolve every configured flag for `client_key`
+    ///
+    /// # Arguments
+    /// * `flags` - Flag configurations by name
+    /// * `client_key` - Authenticated client ID, or the stream ID when auth is off
+    pub fn resolve(flags: &BTreeMap<String, FeatureFlagConfig>, client_key: &str) -> Self {
+        Self(
+            flags
+                .iter()
+                .map(|(name, flag)| (name.clone(), flag.is_enabled_for(name, client_key)))
+                .collect(),
+        )
+    }
+
+    /// Whether `name` is enabled; unconfigured flags are off
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.0.get(name).copied().unwrap_or(false)
+    }
+
+    /// Whether no flags were configured
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Flag names and values in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.0
+            .iter()
+            .map(|(name, &enabled)| (name.as_str(), enabled))
+    }
+}
+
+impl From<BTreeMap<String, bool>> for FeatureFlags {
+    fn from(flags: BTreeMap<String, bool>) -> Self {
+        Self(flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(name: &str, flag: FeatureFlagConfig) -> BTreeMap<String, FeatureFlagConfig> {
+        BTreeMap::from([(name.to_string(), flag)])
+    }
+
+    #[test]
+    fn test_rollout_bucket_is_deterministic() {
+        let bucket = rollout_bucket("echo_guard", "project-1");
+        for _ in 0..3 {
+            assert_eq!(rollout_bucket("echo_guard", "project-1"), bucket);
+        }
+        assert!((0.0..100.0).contains(&bucket));
+
+        // Flags bucket clients independently
+        let differs = (0..100).any(|i| {
+            let client = format!("client-{i}");
+            rollout_bucket("echo_guard", &client) != rollout_bucket("adaptive_endpointing", &client)
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_rollout_percent_selects_matching_share() {
+        let flag = FeatureFlagConfig {
+            rollout_percent: Some(25.0),
+            ..Default::default()
+        };
+        let enabled = (0..10_000)
+            .filter(|i| flag.is_enabled_for("echo_guard", &format!("client-{i}")))
+            .count();
+        assert!((2_200..=2_800).contains(&enabled), "enabled {enabled}");
+
+        // Raising the percentage keeps every client that was already in
+        let wider = FeatureFlagConfig {
+            rollout_percent: Some(50.0),
+            ..Default::default()
+        };
+        for i in 0..1_000 {
+            let client = format!("client-{i}");
+            if flag.is_enabled_for("echo_guard", &client) {
+                assert!(wider.is_enabled_for("echo_guard", &client));
+            }
+        }
+
+        let none = FeatureFlagConfig {
+            default: true,
+            rollout_percent: Some(0.0),
+            ..Default::default()
+        };
+        let all = FeatureFlagConfig {
+            rollout_percent: Some(100.0),
+            ..Default::default()
+        };
+        for i in 0..1_000 {
+            let client = format!("client-{i}");
+            assert!(!none.is_enabled_for("echo_guard", &client));
+            assert!(all.is_enabled_for("echo_guard", &client));
+        }
+    }
+
+    #[test]
+    fn test_client_override_wins() {
+        let flag = FeatureFlagConfig {
+            default: true,
+            rollout_percent: None,
+            clients: BTreeMap::from([
+                ("opted-out".to_string(), false),
+                ("opted-in".to_string(), true),
+            ]),
+        };
+        assert!(!flag.is_enabled_for("echo_guard", "opted-out"));
+        assert!(flag.is_enabled_for("echo_guard", "anyone"));
+
+        let rollout = FeatureFlagConfig {
+            rollout_percent: Some(0.0),
+            ..flag
+        };
+        assert!(rollout.is_enabled_for("echo_guard", "opted-in"));
+        assert!(!rollout.is_enabled_for("echo_guard", "anyone"));
+    }
+
+    #[test]
+    fn test_resolve_feature_flags() {
+        let mut config = flags(
+            "echo_guard",
+            FeatureFlagConfig {
+                clients: BTreeMap::from([("beta".to_string(), true)]),
+                ..Default::default()
+            },
+        );
+        config.insert(
+            "adaptive_endpointing".to_string(),
+            FeatureFlagConfig {
+                default: true,
+                ..Default::default()
+            },
+        );
+
+        let beta = FeatureFlags::resolve(&config, "beta");
+        assert!(beta.is_enabled("echo_guard"));
+        assert!(beta.is_enabled("adaptive_endpointing"));
+        assert!(!beta.is_enabled("new_chunker"));
+
+        let other = FeatureFlags::resolve(&config, "other");
+        assert!(!other.is_enabled("echo_guard"));
+        assert_eq!(
+            serde_json::to_value(&other).unwrap(),
+            serde_json::json!({"adaptive_endpointing": true, "echo_guard": false})
+        );
+        assert!(FeatureFlags::resolve(&BTreeMap::new(), "other").is_empty());
+    }
+
+    #[test]
+    fn test_feature_flag_validation() {
+        assert!(FeatureFlagConfig::default().validate().is_ok());
+
+        for percent in [-1.0, 100.5, f64::NAN] {
+            let flag = FeatureFlagConfig {
+                rollout_percent: Some(percent),
+                ..Default::default()
+            };
+            assert!(flag.validate().unwrap_err().contains("rollout_percent"));
+        }
+
+        let empty_client = FeatureFlagConfig {
+            clients: BTreeMap::from([(String::new(), true)]),
+            ..Default::default()
+        };
+        assert!(empty_client.validate().is_err());
+
+        assert!(serde_json::from_str::<FeatureFlagConfig>(r#"{"percent": 10}"#).is_err());
+    }
+
+    #[test]
+    fn test_unknown_feature_flags() {
+        let mut config = flags("echo_guard", FeatureFlagConfig::default());
+        config.insert("new_chunker".to_string(), FeatureFlagConfig::default());
+        assert_eq!(unknown_feature_flags(&config), vec!["new_chunker"]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Session flight recorder
+//!
+//! Sessions that opt in with `"flight_recorder": true` in their config
+//! message get a log of everything they exchanged with the client: the
+//! messages received and sent, the resolved session config and the close
+//! reason. The log is kept in the cache backend for `retention_secs` and
+//! rendered with `waav-gateway session replay <file>`.
+
+use serde::Deserialize;
+
+/// Largest log kept per session (64 MiB)
+pub const MAX_FLIGHT_LOG_BYTES: usize = 64 * 1024 * 1024;
+
+/// Session flight recorder
+///
+/// A log stops growing at `max_bytes_per_session`; later entries are only
+/// counted, and the log ends with a `truncated` entry. Secrets (API keys,
+/// auth tokens, custom header values) are always redacted. With
+/// `scrub_transcripts`, transcript and speak text is replaced as well.
+///
+/// # Example YAML
+/// ```yaml
+/// flight_recorder:
+///   retention_secs: 86400
+///   max_bytes_per_session: 1048576
+///   flush_interval_secs: 10
+///   scrub_transcripts: false
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlightRecorderConfig {
+    /// How long a session's log is kept in the cache
+    pub retention_secs: u64,
+    /// Size a session's log stops growing at
+    pub max_bytes_per_session: usize,
+    /// How often an open session's log is written to the cache; it is
+    /// written once more when the session ends
+    pub flush_interval_secs: u64,
+    /// Replace transcript, speak and chat text with `[scrubbed]` (default: false)
+    pub scrub_transcripts: bool,
+}
+
+impl Default for FlightRecorderConfig {
+    fn default() -> Self {
+        Self {
+            retention_secs: 86_400,
+            max_bytes_per_session: 1024 * 1024,
+            flush_interval_secs: 10,
+            scrub_transcripts: false,
+        }
+    }
+}
+
+impl FlightRecorderConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if every setting is in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.retention_secs == 0 {
+            return Err("retention_secs must be greater than 0".to_string());
+        }
+        if !(1..=MAX_FLIGHT_LOG_BYTES).contains(&self.max_bytes_per_session) {
+            return Err(format!(
+                "max_bytes_per_session must be between 1 and {MAX_FLIGHT_LOG_BYTES} (got {})",
+                self.max_bytes_per_session
+            ));
+        }
+        if self.flush_interval_secs == 0 {
+            return Err("flush_interval_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let config: FlightRecorderConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, FlightRecorderConfig::default());
+        assert!(!config.scrub_transcripts);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation() {
+        let unbounded = FlightRecorderConfig {
+            max_bytes_per_session: MAX_FLIGHT_LOG_BYTES + 1,
+            ..Default::default()
+        };
+        assert!(
+            unbounded
+                .validate()
+                .unwrap_err()
+                .contains("max_bytes_per_session")
+        );
+
+        let forever = FlightRecorderConfig {
+            retention_secs: 0,
+            ..Default::default()
+        };
+        assert!(forever.validate().unwrap_err().contains("retention_secs"));
+        assert!(serde_yaml::from_str::<FlightRecorderConfig>("enabled: true").is_err());
+    }
+}
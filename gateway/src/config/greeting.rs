@@ -0,0 +1,234 @@
+//! Greeting configuration
+//!
+//! Defines the greeting a session speaks automatically when it starts, either
+//! synthesized text or a pre-recorded audio asset.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum size of greeting text in bytes
+pub const MAX_GREETING_TEXT_SIZE: usize = 4 * 1024;
+
+/// Maximum greeting delay (30 seconds)
+pub const MAX_GREETING_DELAY_MS: u64 = 30_000;
+
+/// Greeting spoken automatically when a session starts
+///
+/// Exactly one of `text` (synthesized with the session's TTS provider) or
+/// `asset` (a pre-recorded WAV file from the greeting assets directory) is
+/// played. A per-session greeting with neither set disables the server default.
+///
+/// # Example YAML
+/// ```yaml
+/// greeting:
+///   text: "Hi, thanks for calling. How can I help?"
+///   delay_ms: 500
+///   assets_dir: "/opt/waav/greetings"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GreetingConfig {
+    /// Text to speak through the session's TTS provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = "Hi, how can I help you today?"))]
+    pub text: Option<String>,
+    /// Name of a 16-bit mono WAV file in the greeting assets directory
+    /// (the `.wav` extension is optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = "welcome"))]
+    pub asset: Option<String>,
+    /// Delay before the greeting starts, in milliseconds (max 30000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+/// What a greeting plays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GreetingSource {
+    /// Text synthesized with TTS
+    Text(String),
+    /// Name of a pre-recorded asset
+    Asset(String),
+}
+
+impl GreetingSource {
+    /// Short name of the source kind ("text" or "asset")
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::Asset(_) => "asset",
+        }
+    }
+}
+
+impl GreetingConfig {
+    /// Get what the greeting plays, or `None` if it plays nothing
+    pub fn source(&self) -> Option<GreetingSource> {
+        if let Some(text) = self.text.as_deref().map(str::trim)
+            && !text.is_empty()
+        {
+            return Some(GreetingSource::Text(text.to_string()));
+        }
+        self.asset
+            .as_deref()
+            .map(str::trim)
+            .filter(|asset| !asset.is_empty())
+            .map(|asset| GreetingSource::Asset(asset.to_string()))
+    }
+
+    /// Delay before the greeting starts
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms.unwrap_or(0))
+    }
+
+    /// Validate the greeting
+    ///
+    /// # Returns
+    /// * `Ok(())` if the greeting is valid (including an empty greeting)
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        let has_text = self.text.as_deref().is_some_and(|t| !t.trim().is_empty());
+        let has_asset = self.asset.as_deref().is_some_and(|a| !a.trim().is_empty());
+        if has_text && has_asset {
+            return Err("greeting must set either 'text' or 'asset', not both".to_string());
+        }
+
+        if let Some(text) = &self.text
+            && text.len() > MAX_GREETING_TEXT_SIZE
+        {
+            return Err(format!(
+                "greeting text too large: {} bytes (max: {} bytes)",
+                text.len(),
+                MAX_GREETING_TEXT_SIZE
+            ));
+        }
+
+        if let Some(asset) = self.asset.as_deref().map(str::trim)
+            && has_asset
+            && !is_valid_asset_name(asset)
+        {
+            return Err(format!(
+                "invalid greeting asset name '{asset}': must be a file name without path separators"
+            ));
+        }
+
+        if let Some(delay_ms) = self.delay_ms
+            && delay_ms > MAX_GREETING_DELAY_MS
+        {
+            return Err(format!(
+                "greeting delay_ms too large: {delay_ms} (max: {MAX_GREETING_DELAY_MS})"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that an asset name cannot escape the assets directory
+pub fn is_valid_asset_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', '\0'])
+}
+
+/// Resolve an asset name to a file in the greeting assets directory
+///
+/// Names without an extension get `.wav` appended.
+pub fn greeting_asset_path(assets_dir: &Path, name: &str) -> PathBuf {
+    let path = assets_dir.join(name);
+    if path.extension().is_some() {
+        path
+    } else {
+        path.with_extension("wav")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greeting_source() {
+        let greeting = GreetingConfig {
+            text: Some("  Hello there  ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            greeting.source(),
+            Some(GreetingSource::Text("Hello there".to_string()))
+        );
+
+        let greeting = GreetingConfig {
+            asset: Some("welcome".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            greeting.source(),
+            Some(GreetingSource::Asset("welcome".to_string()))
+        );
+        assert_eq!(greeting.source().unwrap().kind(), "asset");
+
+        // An empty greeting plays nothing
+        assert_eq!(GreetingConfig::default().source(), None);
+        let greeting = GreetingConfig {
+            text: Some("   ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(greeting.source(), None);
+    }
+
+    #[test]
+    fn test_greeting_validation() {
+        assert!(GreetingConfig::default().validate().is_ok());
+
+        let both = GreetingConfig {
+            text: Some("Hello".to_string()),
+            asset: Some("welcome".to_string()),
+            delay_ms: None,
+        };
+        assert!(both.validate().unwrap_err().contains("not both"));
+
+        for name in ["../secret", "dir/welcome", "..", "a\\b"] {
+            let greeting = GreetingConfig {
+                asset: Some(name.to_string()),
+                ..Default::default()
+            };
+            assert!(greeting.validate().is_err(), "{name} should be rejected");
+        }
+
+        let too_long = GreetingConfig {
+            text: Some("a".repeat(MAX_GREETING_TEXT_SIZE + 1)),
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let too_late = GreetingConfig {
+            text: Some("Hello".to_string()),
+            delay_ms: Some(MAX_GREETING_DELAY_MS + 1),
+            ..Default::default()
+        };
+        assert!(too_late.validate().is_err());
+    }
+
+    #[test]
+    fn test_greeting_deserialization() {
+        let greeting: GreetingConfig =
+            serde_json::from_str(r#"{"asset": "welcome", "delay_ms": 250}"#).unwrap();
+        assert_eq!(greeting.asset.as_deref(), Some("welcome"));
+        assert_eq!(greeting.delay(), Duration::from_millis(250));
+        assert_eq!(GreetingConfig::default().delay(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_greeting_asset_path() {
+        let dir = Path::new("/opt/greetings");
+        assert_eq!(
+            greeting_asset_path(dir, "welcome"),
+            PathBuf::from("/opt/greetings/welcome.wav")
+        );
+        assert_eq!(
+            greeting_asset_path(dir, "welcome.wav"),
+            PathBuf::from("/opt/greetings/welcome.wav")
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Broadcast audio ingest
+//!
+//! With the `ingest` cargo feature, the gateway accepts RTMP publishers
+//! (OBS, ffmpeg, hardware encoders) and transcribes their audio in a voice
+//! session configured from an agent profile. Each accepted stream key names
+//! the profile and the client that owns the sessions.
+
+use std::collections::{BTreeMap, HashSet};
+use std::net::SocketAddr;
+
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+
+/// RTMP ingest listener
+///
+/// Publishers connect to `rtmp://<rtmp_address>/<app>/<key>`; the app name
+/// is ignored. The publish name may carry a query to pick another agent
+/// profile the stream's client may use, or another session ID:
+/// `<key>?agent=news-es&stream_id=news-es-1`.
+///
+/// # Example YAML
+/// ```yaml
+/// ingest:
+///   rtmp_address: "0.0.0.0:1935"
+///   max_streams: 16
+///   session_timeout_ms: 10000
+///   streams:
+///     - key: "sk_5f2b9c1e7a"
+///       name: "news-1"
+///       agent: "broadcast-captions"
+///       client_id: "newsroom"
+///       metadata:
+///         channel: "news-1"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IngestConfig {
+    /// Address the RTMP listener binds (`host:port`)
+    pub rtmp_address: String,
+    /// Stream keys accepted by the listener
+    pub streams: Vec<IngestStreamConfig>,
+    /// Streams ingested at once; further publishers are refused
+    #[serde(default = "default_max_streams")]
+    pub max_streams: usize,
+    /// How long a publisher waits for its session to be ready (ms)
+    #[serde(default = "default_session_timeout_ms")]
+    pub session_timeout_ms: u64,
+}
+
+/// One accepted stream key
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IngestStreamConfig {
+    /// Secret the publisher sends as its stream name
+    pub key: String,
+    /// Session ID of the stream unless the publish name sets `stream_id`;
+    /// monitors and exports address the session by it
+    pub name: String,
+    /// Agent profile the session is configured from
+    pub agent: String,
+    /// Auth client ID that owns the sessions, as if it had opened them on
+    /// `/ws`; scoped agent profiles must list it
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// Metadata attached to every session of the stream
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+fn default_max_streams() -> usize {
+    16
+}
+
+fn default_session_timeout_ms() -> u64 {
+    10_000
+}
+
+impl IngestConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the address parses and every stream is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rtmp_address.parse::<SocketAddr>().is_err() {
+            return Err(format!(
+                "rtmp_address must be a socket address like 0.0.0.0:1935 (got '{}')",
+                self.rtmp_address
+            ));
+        }
+        if self.streams.is_empty() {
+            return Err("streams must list at least one stream key".to_string());
+        }
+        if self.max_streams == 0 {
+            return Err("max_streams must be greater than 0".to_string());
+        }
+        if self.session_timeout_ms == 0 {
+            return Err("session_timeout_ms must be greater than 0".to_string());
+        }
+
+        let mut keys = HashSet::new();
+        let mut names = HashSet::new();
+        for (index, stream) in self.streams.iter().enumerate() {
+            if stream.key.is_empty() || stream.key.contains(['?', '/']) {
+                return Err(format!(
+                    "streams[{index}].key must be non-empty and may not contain '?' or '/'"
+                ));
+            }
+            if stream.name.is_empty() || stream.agent.is_empty() {
+                return Err(format!("streams[{index}] needs a name and an agent"));
+            }
+            if !keys.insert(stream.key.as_str()) {
+                return Err(format!("streams[{index}] repeats a stream key"));
+            }
+            if !names.insert(stream.name.as_str()) {
+                return Err(format!(
+                    "streams[{index}] repeats the name '{}'",
+                    stream.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The stream accepted for `key`, compared in constant time
+    pub fn stream(&self, key: &str) -> Option<&IngestStreamConfig> {
+        self.streams
+            .iter()
+            .find(|stream| bool::from(stream.key.as_bytes().ct_eq(key.as_bytes())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> IngestConfig {
+        serde_yaml::from_str(
+            r#"
+rtmp_address: "127.0.0.1:1935"
+streams:
+  - key: "secret-key"
+    name: "news-1"
+    agent: "captions"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ingest_defaults() {
+        let config = config();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.max_streams, 16);
+        assert_eq!(config.session_timeout_ms, 10_000);
+        assert_eq!(config.stream("secret-key").unwrap().name, "news-1");
+        assert!(config.stream("news-1").is_none());
+    }
+
+    #[test]
+    fn test_ingest_validation() {
+        let mut bad_address = config();
+        bad_address.rtmp_address = "rtmp://0.0.0.0".to_string();
+        assert!(bad_address.validate().unwrap_err().contains("rtmp_address"));
+
+        let mut no_streams = config();
+        no_streams.streams.clear();
+        assert!(no_streams.validate().unwrap_err().contains("streams"));
+
+        let mut query_key = config();
+        query_key.streams[0].key = "key?agent=x".to_string();
+        assert!(query_key.validate().unwrap_err().contains("key"));
+
+        let mut repeated = config();
+        let mut second = repeated.streams[0].clone();
+        second.key = "other-key".to_string();
+        repeated.streams.push(second);
+        assert!(repeated.validate().unwrap_err().contains("news-1"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        assert!(
+            serde_yaml::from_str::<IngestConfig>(
+                "rtmp_address: \"127.0.0.1:1935\"\nstreams: []\nsrt_address: \"0.0.0.0:9000\"\n"
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Load shedding configuration
+//!
+//! Watermarks above which the gateway turns away new WebSocket and realtime
+//! sessions with `503 Service Unavailable` instead of accepting sessions it
+//! cannot serve. Sessions that are already open are never affected.
+
+use serde::{Deserialize, Serialize};
+
+/// Default `Retry-After` sent with shed requests, in seconds
+pub const DEFAULT_LOAD_SHED_RETRY_AFTER_SECS: u64 = 5;
+
+/// Longest `Retry-After` that can be configured (10 minutes)
+const MAX_RETRY_AFTER_SECS: u64 = 600;
+
+/// Thresholds for shedding new sessions
+///
+/// Every threshold is optional; shedding is off while none is set. The same
+/// shape is accepted by `PUT /admin/load_shedding` to change the thresholds at
+/// runtime.
+///
+/// # Example YAML
+/// ```yaml
+/// load_shedding:
+///   max_active_sessions: 500
+///   max_pool_saturation: 0.9
+///   max_event_loop_lag_ms: 200
+///   retry_after_secs: 5
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct LoadSheddingConfig {
+    /// Shed once this many WebSocket and realtime sessions are open
+    #[cfg_attr(feature = "openapi", schema(example = 500))]
+    pub max_active_sessions: Option<usize>,
+    /// Shed once the busiest provider request pool is this full (0.0 to 1.0)
+    #[cfg_attr(feature = "openapi", schema(example = 0.9))]
+    pub max_pool_saturation: Option<f64>,
+    /// Shed once the runtime falls this far behind its timers, in milliseconds
+    #[cfg_attr(feature = "openapi", schema(example = 200))]
+    pub max_event_loop_lag_ms: Option<u64>,
+    /// Seconds clients are told to wait in the `Retry-After` header
+    #[cfg_attr(feature = "openapi", schema(example = 5))]
+    pub retry_after_secs: u64,
+}
+
+impl Default for LoadSheddingConfig {
+    fn default() -> Self {
+        Self {
+            max_active_sessions: None,
+            max_pool_saturation: None,
+            max_event_loop_lag_ms: None,
+            retry_after_secs: DEFAULT_LOAD_SHED_RETRY_AFTER_SECS,
+        }
+    }
+}
+
+impl LoadSheddingConfig {
+    /// Whether any threshold is set
+    pub fn is_enabled(&self) -> bool {
+        self.max_active_sessions.is_some()
+            || self.max_pool_saturation.is_some()
+            || self.max_event_loop_lag_ms.is_some()
+    }
+
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if every threshold is in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_active_sessions == Some(0) {
+            return Err("load_shedding max_active_sessions must be greater than 0".to_string());
+        }
+        if let Some(saturation) = self.max_pool_saturation
+            && !(saturation > 0.0 && saturation <= 1.0)
+        {
+            return Err(format!(
+                "load_shedding max_pool_saturation must be greater than 0.0 and at most 1.0 (got {saturation})"
+            ));
+        }
+        if self.max_event_loop_lag_ms == Some(0) {
+            return Err("load_shedding max_event_loop_lag_ms must be greater than 0".to_string());
+        }
+        if self.retry_after_secs == 0 || self.retry_after_secs > MAX_RETRY_AFTER_SECS {
+            return Err(format!(
+                "load_shedding retry_after_secs must be between 1 and {MAX_RETRY_AFTER_SECS} (got {})",
+                self.retry_after_secs
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_disabled() {
+        let config = LoadSheddingConfig::default();
+        assert!(!config.is_enabled());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.retry_after_secs, DEFAULT_LOAD_SHED_RETRY_AFTER_SECS);
+    }
+
+    #[test]
+    fn test_load_shedding_validation() {
+        let config = LoadSheddingConfig {
+            max_active_sessions: Some(100),
+            max_pool_saturation: Some(1.0),
+            max_event_loop_lag_ms: Some(250),
+            ..Default::default()
+        };
+        assert!(config.is_enabled());
+        assert!(config.validate().is_ok());
+
+        let zero_sessions = LoadSheddingConfig {
+            max_active_sessions: Some(0),
+            ..config.clone()
+        };
+        assert!(
+            zero_sessions
+                .validate()
+                .unwrap_err()
+                .contains("max_active_sessions")
+        );
+
+        for saturation in [0.0, 1.5, f64::NAN] {
+            let invalid = LoadSheddingConfig {
+                max_pool_saturation: Some(saturation),
+                ..config.clone()
+            };
+            assert!(
+                invalid
+                    .validate()
+                    .unwrap_err()
+                    .contains("max_pool_saturation")
+            );
+        }
+
+        let no_retry = LoadSheddingConfig {
+            retry_after_secs: 0,
+            ..config
+        };
+        assert!(
+            no_retry
+                .validate()
+                .unwrap_err()
+                .contains("retry_after_secs")
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let parsed: LoadSheddingConfig =
+            serde_json::from_str(r#"{"max_active_sessions": 10}"#).unwrap();
+        assert_eq!(parsed.max_active_sessions, Some(10));
+        assert_eq!(parsed.retry_after_secs, DEFAULT_LOAD_SHED_RETRY_AFTER_SECS);
+
+        assert!(serde_json::from_str::<LoadSheddingConfig>(r#"{"max_sessions": 10}"#).is_err());
+    }
+}
@@ -1,11 +1,29 @@
 use std::env;
 use std::path::PathBuf;
 
+use super::admin_scopes::AdminScopeIds;
+use super::env::{
+    parse_admin_scopes_env, parse_greeting_env, parse_load_shedding_env,
+    parse_plugin_conflict_policy_env, parse_tts_fallback_voices_env, parse_tts_queue_policy_env,
+    parse_usage_env,
+};
+use super::greeting::GreetingConfig;
+use super::load_shedding::LoadSheddingConfig;
 use super::parse_auth_api_secrets_json;
-use super::sip::{SipConfig, SipHookConfig};
-use super::utils::parse_bool;
+use super::selftest::SelfTestConfig;
+use super::sip::{LanguagePack, SipConfig, SipHookConfig, SipLanguageRouting};
+use super::trusted_proxies::parse_trusted_proxies;
+use super::usage::UsageConfig;
+use super::utils::{parse_bool, parse_comma_list};
 use super::yaml::YamlConfig;
-use super::{AuthApiSecret, PluginConfig, ServerConfig, TlsConfig};
+use super::{
+    AuthApiSecret, DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES, DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY,
+    DEFAULT_PLUGIN_HTTP_TIMEOUT_MS, DEFAULT_PLUGIN_INIT_TIMEOUT_SECS,
+    DEFAULT_PLUGIN_STORAGE_CHECK_INTERVAL_SECS, DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES,
+    DEFAULT_REPLAY_MAX_CONCURRENT_JOBS, PluginConfig, ServerConfig, TlsConfig,
+};
+use crate::core::voice_manager::{DEFAULT_MAX_PENDING_UTTERANCES, DEFAULT_SYSTEM_SPEAK_MAX_CHARS};
+use crate::errors::provider_error::DEFAULT_PROVIDER_ERROR_MAX_CHARS;
 
 /// Merge YAML configuration with environment variables
 ///
@@ -390,9 +408,123 @@ pub fn merge_config(
         .or_else(|| env::var("AUTH_REQUIRED").ok().and_then(|s| parse_bool(&s)))
         .unwrap_or(false);
 
+    // Admin IDs (YAML > ENV)
+    let auth_admin_ids = if let Some(yaml_auth) = yaml.auth.as_ref()
+        && !yaml_auth.admin_ids.is_empty()
+    {
+        yaml_auth.admin_ids.clone()
+    } else {
+        env::var("AUTH_ADMIN_IDS")
+            .ok()
+            .map(|s| parse_comma_list(&s))
+            .unwrap_or_default()
+    };
+
+    // Monitor audio scope holders (YAML > ENV)
+    let auth_monitor_audio_ids = if let Some(yaml_auth) = yaml.auth.as_ref()
+        && !yaml_auth.monitor_audio_ids.is_empty()
+    {
+        yaml_auth.monitor_audio_ids.clone()
+    } else {
+        env::var("AUTH_MONITOR_AUDIO_IDS")
+            .ok()
+            .map(|s| parse_comma_list(&s))
+            .unwrap_or_default()
+    };
+
+    // Admin scope holders (YAML > ENV, per scope)
+    let env_admin_scopes = parse_admin_scopes_env();
+    let yaml_admin_scopes = yaml
+        .auth
+        .as_ref()
+        .map(|a| a.admin_scopes.clone())
+        .unwrap_or_default();
+    let pick = |yaml: Vec<String>, env: Vec<String>| if yaml.is_empty() { env } else { yaml };
+    let auth_admin_scopes = AdminScopeIds {
+        read: pick(yaml_admin_scopes.read, env_admin_scopes.read),
+        write: pick(yaml_admin_scopes.write, env_admin_scopes.write),
+        chaos: pick(yaml_admin_scopes.chaos, env_admin_scopes.chaos),
+    };
+
     // SIP configuration (merge YAML and ENV)
     let sip = merge_sip_config(yaml.sip.as_ref())?;
 
+    // Greeting configuration (merge YAML and ENV)
+    let greeting = merge_greeting_config(yaml.greeting.as_ref())?;
+    let greeting_assets_dir = yaml
+        .greeting
+        .as_ref()
+        .and_then(|g| g.assets_dir.clone())
+        .or_else(|| env::var("GREETING_ASSETS_DIR").ok())
+        .map(PathBuf::from);
+
+    // Usage record configuration (merge YAML and ENV)
+    let usage = merge_usage_config(yaml.usage.as_ref())?;
+
+    // Provider self-test (YAML only)
+    let selftest = merge_selftest_config(yaml.selftest.as_ref())?;
+
+    // Load shedding thresholds (merge YAML and ENV)
+    let load_shedding = merge_load_shedding_config(yaml.load_shedding.as_ref())?;
+
+    let replay_max_concurrent_jobs = yaml
+        .recording
+        .as_ref()
+        .and_then(|r| r.replay_max_concurrent_jobs)
+        .or_else(|| {
+            env::var("REPLAY_MAX_CONCURRENT_JOBS")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+        })
+        .unwrap_or(DEFAULT_REPLAY_MAX_CONCURRENT_JOBS);
+
+    // Agent profiles (YAML only)
+    let agents = yaml.agents.clone().unwrap_or_default();
+
+    // Feature flags (YAML only)
+    let feature_flags = yaml.feature_flags.clone().unwrap_or_default();
+
+    // Session transcript limits (YAML only)
+    let transcript_buffer = yaml.transcript_buffer.unwrap_or_default();
+    let transcript_enrichment = yaml.transcript_enrichment.clone();
+    let session_export = yaml.session_export.clone();
+
+    // Recording and monitor audio policies (YAML only)
+    let audio_sinks = yaml.audio_sinks.unwrap_or_default();
+    let recording_upload = yaml.recording_upload.clone();
+
+    // Recording redaction (YAML only)
+    let recording_redaction = yaml.recording_redaction.unwrap_or_default();
+
+    // Fault injection (YAML only)
+    let chaos = yaml.chaos.unwrap_or_default();
+
+    // Voice profiles (YAML only)
+    let voice_profiles = yaml.voice_profiles.clone().unwrap_or_default();
+
+    // WebSocket heartbeat (YAML only)
+    let ws_heartbeat = yaml.ws_heartbeat.unwrap_or_default();
+
+    // Development console (YAML only)
+    let console = yaml.console.unwrap_or_default();
+
+    // Outbound webhook queue (YAML only)
+    let webhook_queue = yaml.webhook_queue;
+
+    // Broadcast ingest (YAML only)
+    let ingest = yaml.ingest.clone();
+
+    // Session flight recorder (YAML only)
+    let flight_recorder = yaml.flight_recorder;
+
+    // Strict config messages
+    let strict_config = yaml
+        .server
+        .as_ref()
+        .and_then(|s| s.strict_config)
+        .or_else(|| env::var("STRICT_CONFIG").ok().and_then(|s| parse_bool(&s)))
+        .unwrap_or(false);
+
     // Security configuration
     let cors_allowed_origins = get_optional!(
         "CORS_ALLOWED_ORIGINS",
@@ -446,6 +578,84 @@ pub fn merge_config(
         })
         .unwrap_or(100);
 
+    // Trusted proxies (YAML > ENV)
+    let trusted_proxies = parse_trusted_proxies(
+        &yaml
+            .security
+            .as_ref()
+            .and_then(|s| s.trusted_proxies.clone())
+            .or_else(|| {
+                env::var("TRUSTED_PROXIES")
+                    .ok()
+                    .map(|s| parse_comma_list(&s))
+            })
+            .unwrap_or_default(),
+    )?;
+
+    let provider_connect_timeout_secs = yaml
+        .providers
+        .as_ref()
+        .and_then(|p| p.connect_timeout_secs)
+        .or_else(|| {
+            env::var("PROVIDER_CONNECT_TIMEOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+        })
+        .unwrap_or(10);
+
+    let tts_fallback_voices = match yaml
+        .providers
+        .as_ref()
+        .and_then(|p| p.tts_fallback_voices.clone())
+    {
+        Some(voices) => voices,
+        None => parse_tts_fallback_voices_env()?,
+    };
+
+    let tts_max_pending_utterances = yaml
+        .providers
+        .as_ref()
+        .and_then(|p| p.tts_max_pending_utterances)
+        .or_else(|| {
+            env::var("TTS_MAX_PENDING_UTTERANCES")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+        })
+        .unwrap_or(DEFAULT_MAX_PENDING_UTTERANCES);
+
+    let tts_queue_policy = match yaml.providers.as_ref().and_then(|p| p.tts_queue_policy) {
+        Some(policy) => policy,
+        None => parse_tts_queue_policy_env()?.unwrap_or_default(),
+    };
+
+    let tts_system_speak_max_chars = yaml
+        .providers
+        .as_ref()
+        .and_then(|p| p.tts_system_speak_max_chars)
+        .or_else(|| {
+            env::var("TTS_SYSTEM_SPEAK_MAX_CHARS")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+        })
+        .unwrap_or(DEFAULT_SYSTEM_SPEAK_MAX_CHARS);
+
+    let provider_error_max_chars = yaml
+        .providers
+        .as_ref()
+        .and_then(|p| p.error_max_chars)
+        .or_else(|| {
+            env::var("PROVIDER_ERROR_MAX_CHARS")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+        })
+        .unwrap_or(DEFAULT_PROVIDER_ERROR_MAX_CHARS);
+
+    let deepgram_connection_budget = yaml
+        .providers
+        .as_ref()
+        .and_then(|p| p.deepgram_connection_budget)
+        .unwrap_or_default();
+
     // Plugin configuration (backward compatible: enabled by default)
     let plugins_enabled = yaml
         .plugins
@@ -465,6 +675,119 @@ pub fn merge_config(
         .or_else(|| env::var("PLUGINS_DIR").ok())
         .map(PathBuf::from);
 
+    let plugins_dirs = match yaml.plugins.as_ref().and_then(|p| p.plugin_dirs.clone()) {
+        Some(dirs) => dirs.into_iter().map(PathBuf::from).collect(),
+        None => env::var_os("PLUGINS_DIRS")
+            .map(|dirs| env::split_paths(&dirs).collect())
+            .unwrap_or_default(),
+    };
+
+    let plugins_conflict_policy = match yaml.plugins.as_ref().and_then(|p| p.conflict_policy) {
+        Some(policy) => policy,
+        None => parse_plugin_conflict_policy_env()?.unwrap_or_default(),
+    };
+
+    let plugins_allow_override = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.allow_override)
+        .or_else(|| {
+            env::var("PLUGINS_ALLOW_OVERRIDE")
+                .ok()
+                .and_then(|s| parse_bool(&s))
+        })
+        .unwrap_or(false);
+
+    let plugins_require_exact_abi = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.require_exact_abi)
+        .or_else(|| {
+            env::var("PLUGINS_REQUIRE_EXACT_ABI")
+                .ok()
+                .and_then(|s| parse_bool(&s))
+        })
+        .unwrap_or(false);
+
+    let plugins_init_timeout_secs = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.init_timeout_secs)
+        .or_else(|| {
+            env::var("PLUGINS_INIT_TIMEOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_PLUGIN_INIT_TIMEOUT_SECS);
+
+    let plugins_http_max_body_bytes = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.http_max_body_bytes)
+        .or_else(|| {
+            env::var("PLUGINS_HTTP_MAX_BODY_BYTES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES);
+
+    let plugins_http_timeout_ms = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.http_timeout_ms)
+        .or_else(|| {
+            env::var("PLUGINS_HTTP_TIMEOUT_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_PLUGIN_HTTP_TIMEOUT_MS);
+
+    let plugins_http_max_concurrency = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.http_max_concurrency)
+        .or_else(|| {
+            env::var("PLUGINS_HTTP_MAX_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY);
+
+    let plugins_data_dir = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.data_dir.clone())
+        .or_else(|| env::var("PLUGINS_DATA_DIR").ok())
+        .map(PathBuf::from);
+
+    let plugins_storage_quota_bytes = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.storage_quota_bytes)
+        .or_else(|| {
+            env::var("PLUGINS_STORAGE_QUOTA_BYTES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES);
+
+    let plugins_storage_check_interval_secs = yaml
+        .plugins
+        .as_ref()
+        .and_then(|p| p.storage_check_interval_secs)
+        .or_else(|| {
+            env::var("PLUGINS_STORAGE_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or(DEFAULT_PLUGIN_STORAGE_CHECK_INTERVAL_SECS);
+
+    let plugins_http_access = yaml
+        .plugins
+        .as_ref()
+        .map(|p| p.http_access.clone())
+        .unwrap_or_default();
+
     let plugins_provider_config = yaml
         .plugins
         .as_ref()
@@ -474,6 +797,18 @@ pub fn merge_config(
     let plugins = PluginConfig {
         enabled: plugins_enabled,
         plugin_dir: plugins_dir,
+        plugin_dirs: plugins_dirs,
+        conflict_policy: plugins_conflict_policy,
+        allow_override: plugins_allow_override,
+        require_exact_abi: plugins_require_exact_abi,
+        init_timeout_secs: plugins_init_timeout_secs,
+        http_max_body_bytes: plugins_http_max_body_bytes,
+        http_timeout_ms: plugins_http_timeout_ms,
+        http_max_concurrency: plugins_http_max_concurrency,
+        http_access: plugins_http_access,
+        data_dir: plugins_data_dir,
+        storage_quota_bytes: plugins_storage_quota_bytes,
+        storage_check_interval_secs: plugins_storage_check_interval_secs,
         provider_config: plugins_provider_config,
     };
 
@@ -520,16 +855,180 @@ pub fn merge_config(
         auth_api_secrets,
         auth_timeout_seconds,
         auth_required,
+        auth_admin_ids,
+        auth_monitor_audio_ids,
         sip,
         cors_allowed_origins,
         rate_limit_requests_per_second,
         rate_limit_burst_size,
         max_websocket_connections,
         max_connections_per_ip,
+        trusted_proxies,
+        provider_connect_timeout_secs,
+        tts_fallback_voices,
+        tts_max_pending_utterances,
+        tts_queue_policy,
+        tts_system_speak_max_chars,
+        provider_error_max_chars,
+        deepgram_connection_budget,
         plugins,
+        greeting,
+        greeting_assets_dir,
+        usage,
+        agents,
+        strict_config,
+        selftest,
+        load_shedding,
+        replay_max_concurrent_jobs,
+        feature_flags,
+        transcript_buffer,
+        transcript_enrichment,
+        session_export,
+        audio_sinks,
+        recording_upload,
+        recording_redaction,
+        chaos,
+        voice_profiles,
+        ws_heartbeat,
+        console,
+        webhook_queue,
+        ingest,
+        flight_recorder,
     })
 }
 
+/// Merge usage record configuration from YAML and environment variables
+///
+/// Priority: YAML > ENV, per setting. Usage records stay off unless a sink
+/// is set in either source.
+fn merge_usage_config(
+    yaml_usage: Option<&super::yaml::UsageYaml>,
+) -> Result<Option<UsageConfig>, Box<dyn std::error::Error>> {
+    let env_usage = parse_usage_env()?;
+    let Some(yaml_usage) = yaml_usage else {
+        return Ok(env_usage);
+    };
+
+    let Some(sink) = yaml_usage
+        .sink
+        .or_else(|| env_usage.as_ref().map(|u| u.sink))
+    else {
+        return Ok(None);
+    };
+    let base = env_usage.unwrap_or_else(|| UsageConfig::new(sink));
+
+    Ok(Some(UsageConfig {
+        sink,
+        file_path: yaml_usage
+            .file_path
+            .clone()
+            .map(PathBuf::from)
+            .or(base.file_path),
+        file_max_bytes: yaml_usage.file_max_bytes.unwrap_or(base.file_max_bytes),
+        file_max_files: yaml_usage.file_max_files.unwrap_or(base.file_max_files),
+        webhook_url: yaml_usage.webhook_url.clone().or(base.webhook_url),
+        webhook_secret: yaml_usage.webhook_secret.clone().or(base.webhook_secret),
+        reconciliation_path: yaml_usage
+            .reconciliation_path
+            .clone()
+            .map(PathBuf::from)
+            .or(base.reconciliation_path),
+        reconciliation_tolerance_percent: yaml_usage
+            .reconciliation_tolerance_percent
+            .unwrap_or(base.reconciliation_tolerance_percent),
+    }))
+}
+
+/// Merge load shedding thresholds from YAML and environment variables
+///
+/// Priority: YAML > ENV, per setting. Shedding stays off unless a threshold
+/// is set in either source.
+fn merge_load_shedding_config(
+    yaml_load_shedding: Option<&super::yaml::LoadSheddingYaml>,
+) -> Result<Option<LoadSheddingConfig>, Box<dyn std::error::Error>> {
+    let env_load_shedding = parse_load_shedding_env()?;
+    let Some(yaml_load_shedding) = yaml_load_shedding else {
+        return Ok(env_load_shedding);
+    };
+    let base = env_load_shedding.unwrap_or_default();
+
+    let config = LoadSheddingConfig {
+        max_active_sessions: yaml_load_shedding
+            .max_active_sessions
+            .or(base.max_active_sessions),
+        max_pool_saturation: yaml_load_shedding
+            .max_pool_saturation
+            .or(base.max_pool_saturation),
+        max_event_loop_lag_ms: yaml_load_shedding
+            .max_event_loop_lag_ms
+            .or(base.max_event_loop_lag_ms),
+        retry_after_secs: yaml_load_shedding
+            .retry_after_secs
+            .unwrap_or(base.retry_after_secs),
+    };
+    Ok(config.is_enabled().then_some(config))
+}
+
+/// Merge greeting configuration from YAML and environment variables
+///
+/// Priority: YAML > ENV. A YAML greeting with text or asset replaces the
+/// environment greeting entirely so the two are never mixed.
+fn merge_greeting_config(
+    yaml_greeting: Option<&super::yaml::GreetingYaml>,
+) -> Result<Option<GreetingConfig>, Box<dyn std::error::Error>> {
+    let env_greeting = parse_greeting_env()?;
+
+    let Some(yaml_greeting) = yaml_greeting.filter(|g| g.text.is_some() || g.asset.is_some())
+    else {
+        // YAML may still override the delay of an environment greeting
+        return Ok(env_greeting.map(|greeting| GreetingConfig {
+            delay_ms: yaml_greeting.and_then(|g| g.delay_ms).or(greeting.delay_ms),
+            ..greeting
+        }));
+    };
+
+    Ok(Some(GreetingConfig {
+        text: yaml_greeting.text.clone(),
+        asset: yaml_greeting.asset.clone(),
+        delay_ms: yaml_greeting
+            .delay_ms
+            .or_else(|| env_greeting.and_then(|g| g.delay_ms)),
+    }))
+}
+
+/// Build the provider self-test configuration from YAML
+///
+/// The self-test is off unless the `selftest` section is present, and the
+/// section must name both providers.
+fn merge_selftest_config(
+    yaml_selftest: Option<&super::yaml::SelfTestYaml>,
+) -> Result<Option<SelfTestConfig>, Box<dyn std::error::Error>> {
+    let Some(yaml_selftest) = yaml_selftest else {
+        return Ok(None);
+    };
+    let (Some(stt_provider), Some(tts_provider)) = (
+        yaml_selftest.stt_provider.as_ref(),
+        yaml_selftest.tts_provider.as_ref(),
+    ) else {
+        return Err("selftest requires both stt_provider and tts_provider".into());
+    };
+
+    let base = SelfTestConfig::new(stt_provider.as_str(), tts_provider.as_str());
+    Ok(Some(SelfTestConfig {
+        stt_model: yaml_selftest.stt_model.clone(),
+        tts_voice: yaml_selftest.tts_voice.clone(),
+        tts_model: yaml_selftest.tts_model.clone(),
+        language: yaml_selftest.language.clone().unwrap_or(base.language),
+        phrase: yaml_selftest.phrase.clone().unwrap_or(base.phrase),
+        sample_rate: yaml_selftest.sample_rate.unwrap_or(base.sample_rate),
+        min_similarity: yaml_selftest.min_similarity.unwrap_or(base.min_similarity),
+        timeout_secs: yaml_selftest.timeout_secs.unwrap_or(base.timeout_secs),
+        interval_secs: yaml_selftest.interval_secs,
+        required: yaml_selftest.required.unwrap_or(base.required),
+        ..base
+    }))
+}
+
 /// Merge SIP configuration from YAML and environment variables
 ///
 /// Priority: YAML > ENV
@@ -609,13 +1108,45 @@ fn merge_sip_config(
         .and_then(|s| s.naming_prefix.clone())
         .or(env_naming_prefix);
 
-    Ok(Some(SipConfig::new(
-        room_prefix,
-        allowed_addresses,
-        hooks,
-        hook_secret,
-        naming_prefix,
-    )))
+    // Language routing (YAML only)
+    let language_routing = yaml_sip
+        .and_then(|s| s.language_routing.as_ref())
+        .map(|routing| SipLanguageRouting {
+            packs: routing
+                .packs
+                .iter()
+                .map(|(name, pack)| {
+                    (
+                        name.clone(),
+                        LanguagePack {
+                            stt_language: pack.stt_language.trim().to_string(),
+                            tts_voice_id: pack.tts_voice_id.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            trunks: routing.trunks.clone(),
+            called_prefixes: routing.called_prefixes.clone(),
+            caller_countries: routing.caller_countries.clone(),
+            default_pack: routing.default_pack.clone(),
+        })
+        .unwrap_or_default();
+
+    Ok(Some(
+        SipConfig::new(
+            room_prefix,
+            allowed_addresses,
+            hooks,
+            hook_secret,
+            naming_prefix,
+        )
+        .with_language_routing(language_routing)
+        .with_outbound(
+            yaml_sip
+                .and_then(|s| s.outbound.clone())
+                .unwrap_or_default(),
+        ),
+    ))
 }
 
 /// Parse SIP hooks from JSON string
@@ -643,9 +1174,13 @@ fn parse_sip_hooks_json(json_str: &str) -> Result<Vec<SipHookConfig>, Box<dyn st
 
 #[cfg(test)]
 mod tests {
+    use super::super::PluginConflictPolicy;
     use super::super::yaml::AuthApiSecretYaml;
     use super::super::yaml::SipHookYaml;
     use super::*;
+    use crate::core::providers::connection_budget::{
+        ConnectionBudgetConfig, ConnectionBudgetPolicy,
+    };
     use serial_test::serial;
     use std::fs;
     use tempfile::TempDir;
@@ -671,11 +1206,52 @@ mod tests {
             env::remove_var("AUTH_API_SECRET");
             env::remove_var("AUTH_API_SECRET_ID");
             env::remove_var("AUTH_TIMEOUT_SECONDS");
+            env::remove_var("AUTH_ADMIN_IDS");
+            env::remove_var("AUTH_MONITOR_AUDIO_IDS");
+            env::remove_var("AUTH_ADMIN_READ_IDS");
+            env::remove_var("AUTH_ADMIN_WRITE_IDS");
+            env::remove_var("AUTH_ADMIN_CHAOS_IDS");
             env::remove_var("SIP_ROOM_PREFIX");
             env::remove_var("SIP_ALLOWED_ADDRESSES");
             env::remove_var("SIP_HOOKS_JSON");
             env::remove_var("SIP_HOOK_SECRET");
             env::remove_var("RECORDING_S3_PREFIX");
+            env::remove_var("GREETING_TEXT");
+            env::remove_var("GREETING_ASSET");
+            env::remove_var("GREETING_DELAY_MS");
+            env::remove_var("GREETING_ASSETS_DIR");
+            env::remove_var("PROVIDER_CONNECT_TIMEOUT_SECS");
+            env::remove_var("PLUGINS_INIT_TIMEOUT_SECS");
+            env::remove_var("PLUGINS_HTTP_MAX_BODY_BYTES");
+            env::remove_var("PLUGINS_HTTP_TIMEOUT_MS");
+            env::remove_var("PLUGINS_HTTP_MAX_CONCURRENCY");
+            env::remove_var("PLUGINS_DATA_DIR");
+            env::remove_var("PLUGINS_STORAGE_QUOTA_BYTES");
+            env::remove_var("PLUGINS_STORAGE_CHECK_INTERVAL_SECS");
+            env::remove_var("PLUGINS_DIR");
+            env::remove_var("PLUGINS_DIRS");
+            env::remove_var("PLUGINS_CONFLICT_POLICY");
+            env::remove_var("PLUGINS_ALLOW_OVERRIDE");
+            env::remove_var("TTS_FALLBACK_VOICES");
+            env::remove_var("TTS_MAX_PENDING_UTTERANCES");
+            env::remove_var("TTS_SYSTEM_SPEAK_MAX_CHARS");
+            env::remove_var("PROVIDER_ERROR_MAX_CHARS");
+            env::remove_var("TTS_QUEUE_POLICY");
+            env::remove_var("USAGE_SINK");
+            env::remove_var("USAGE_FILE_PATH");
+            env::remove_var("USAGE_FILE_MAX_BYTES");
+            env::remove_var("USAGE_FILE_MAX_FILES");
+            env::remove_var("USAGE_WEBHOOK_URL");
+            env::remove_var("USAGE_WEBHOOK_SECRET");
+            env::remove_var("USAGE_RECONCILIATION_PATH");
+            env::remove_var("USAGE_RECONCILIATION_TOLERANCE_PERCENT");
+            env::remove_var("STRICT_CONFIG");
+            env::remove_var("LOAD_SHED_MAX_ACTIVE_SESSIONS");
+            env::remove_var("LOAD_SHED_MAX_POOL_SATURATION");
+            env::remove_var("LOAD_SHED_MAX_EVENT_LOOP_LAG_MS");
+            env::remove_var("LOAD_SHED_RETRY_AFTER_SECS");
+            env::remove_var("REPLAY_MAX_CONCURRENT_JOBS");
+            env::remove_var("TRUSTED_PROXIES");
         }
     }
 
@@ -809,105 +1385,378 @@ mod tests {
 
     #[test]
     #[serial]
-    fn test_merge_auth_api_secrets_yaml_over_env() {
+    fn test_merge_plugins_init_timeout() {
         cleanup_env_vars();
 
+        let config = merge_config(None).unwrap();
+        assert_eq!(
+            config.plugins.init_timeout_secs,
+            DEFAULT_PLUGIN_INIT_TIMEOUT_SECS
+        );
+
+        unsafe {
+            env::set_var("PLUGINS_INIT_TIMEOUT_SECS", "12");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.plugins.init_timeout_secs, 12);
+
         let yaml = YamlConfig {
-            auth: Some(super::super::yaml::AuthYaml {
-                api_secrets: vec![
-                    AuthApiSecretYaml {
-                        id: "yaml-a".to_string(),
-                        secret: "secret-a".to_string(),
-                    },
-                    AuthApiSecretYaml {
-                        id: "yaml-b".to_string(),
-                        secret: "secret-b".to_string(),
-                    },
-                ],
+            plugins: Some(super::super::yaml::PluginsYaml {
+                init_timeout_secs: Some(2),
                 ..Default::default()
             }),
             ..Default::default()
         };
-
-        unsafe {
-            env::set_var(
-                "AUTH_API_SECRETS_JSON",
-                r#"[{"id":"env-a","secret":"env-secret"}]"#,
-            );
-            env::set_var("AUTH_API_SECRET", "legacy-secret");
-        }
-
         let config = merge_config(Some(yaml)).unwrap();
-
-        assert_eq!(config.auth_api_secrets.len(), 2);
-        assert_eq!(config.auth_api_secrets[0].id, "yaml-a");
-        assert_eq!(config.auth_api_secrets[1].id, "yaml-b");
+        assert_eq!(config.plugins.init_timeout_secs, 2);
 
         cleanup_env_vars();
     }
 
     #[test]
     #[serial]
-    fn test_merge_auth_api_secrets_env_over_legacy() {
+    fn test_merge_plugins_http_limits() {
         cleanup_env_vars();
 
+        let config = merge_config(None).unwrap();
+        assert_eq!(
+            config.plugins.http_max_body_bytes,
+            DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES
+        );
+        assert_eq!(
+            config.plugins.http_timeout_ms,
+            DEFAULT_PLUGIN_HTTP_TIMEOUT_MS
+        );
+        assert_eq!(
+            config.plugins.http_max_concurrency,
+            DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY
+        );
+
+        unsafe {
+            env::set_var("PLUGINS_HTTP_MAX_BODY_BYTES", "1024");
+            env::set_var("PLUGINS_HTTP_TIMEOUT_MS", "250");
+            env::set_var("PLUGINS_HTTP_MAX_CONCURRENCY", "4");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.plugins.http_max_body_bytes, 1024);
+        assert_eq!(config.plugins.http_timeout_ms, 250);
+        assert_eq!(config.plugins.http_max_concurrency, 4);
+
         let yaml = YamlConfig {
-            auth: Some(super::super::yaml::AuthYaml {
-                api_secret: Some("legacy-secret".to_string()),
+            plugins: Some(super::super::yaml::PluginsYaml {
+                http_timeout_ms: Some(100),
+                http_max_concurrency: Some(2),
+                http_access: [("test-stt".to_string(), vec!["ops".to_string()])].into(),
                 ..Default::default()
             }),
             ..Default::default()
         };
-
-        unsafe {
-            env::set_var(
-                "AUTH_API_SECRETS_JSON",
-                r#"[{"id":"env-a","secret":"token-a"}]"#,
-            );
-        }
-
         let config = merge_config(Some(yaml)).unwrap();
-
-        assert_eq!(config.auth_api_secrets.len(), 1);
-        assert_eq!(config.auth_api_secrets[0].id, "env-a");
-        assert_eq!(config.auth_api_secrets[0].secret, "token-a");
+        assert_eq!(config.plugins.http_max_body_bytes, 1024);
+        assert_eq!(config.plugins.http_timeout_ms, 100);
+        assert_eq!(config.plugins.http_max_concurrency, 2);
+        assert!(config.can_access_plugin("test-stt", "OPS"));
+        assert!(!config.can_access_plugin("other", "ops"));
 
         cleanup_env_vars();
     }
 
     #[test]
     #[serial]
-    fn test_merge_auth_api_secrets_empty_yaml_uses_legacy() {
+    fn test_merge_plugins_storage() {
         cleanup_env_vars();
 
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.plugins.data_dir, None);
+        assert_eq!(
+            config.plugins.storage_quota_bytes,
+            DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES
+        );
+
+        unsafe {
+            env::set_var("PLUGINS_DATA_DIR", "/var/lib/waav/plugins");
+            env::set_var("PLUGINS_STORAGE_QUOTA_BYTES", "4096");
+        }
         let yaml = YamlConfig {
-            auth: Some(super::super::yaml::AuthYaml {
-                api_secrets: Vec::new(),
-                api_secret: Some("legacy-secret".to_string()),
+            plugins: Some(super::super::yaml::PluginsYaml {
+                storage_check_interval_secs: Some(5),
                 ..Default::default()
             }),
             ..Default::default()
         };
-
         let config = merge_config(Some(yaml)).unwrap();
-
-        assert_eq!(config.auth_api_secrets.len(), 1);
-        assert_eq!(config.auth_api_secrets[0].id, "default");
-        assert_eq!(config.auth_api_secrets[0].secret, "legacy-secret");
+        assert_eq!(
+            config.plugins.data_dir,
+            Some(PathBuf::from("/var/lib/waav/plugins"))
+        );
+        assert_eq!(config.plugins.storage_quota_bytes, 4096);
+        assert_eq!(config.plugins.storage_check_interval_secs, 5);
 
         cleanup_env_vars();
     }
 
     #[test]
     #[serial]
-    fn test_merge_recording_prefix_yaml_overrides_env() {
+    fn test_merge_plugins_conflict_settings() {
         cleanup_env_vars();
 
-        let yaml = YamlConfig {
-            recording: Some(super::super::yaml::RecordingYaml {
-                s3_prefix: Some("yaml-prefix".to_string()),
-                ..Default::default()
-            }),
+        let config = merge_config(None).unwrap();
+        assert!(config.plugins.plugin_dirs.is_empty());
+        assert_eq!(
+            config.plugins.conflict_policy,
+            PluginConflictPolicy::LastDirWins
+        );
+        assert!(!config.plugins.allow_override);
+
+        let dirs = env::join_paths(["/opt/plugins-a", "/opt/plugins-b"]).unwrap();
+        unsafe {
+            env::set_var("PLUGINS_DIR", "/opt/plugins");
+            env::set_var("PLUGINS_DIRS", &dirs);
+            env::set_var("PLUGINS_CONFLICT_POLICY", "highest_version");
+            env::set_var("PLUGINS_ALLOW_OVERRIDE", "true");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(
+            config.plugins.plugin_directories(),
+            vec![
+                PathBuf::from("/opt/plugins"),
+                PathBuf::from("/opt/plugins-a"),
+                PathBuf::from("/opt/plugins-b"),
+            ]
+        );
+        assert_eq!(
+            config.plugins.conflict_policy,
+            PluginConflictPolicy::HighestVersion
+        );
+        assert!(config.plugins.allow_override);
+
+        let yaml = YamlConfig {
+            plugins: Some(super::super::yaml::PluginsYaml {
+                plugin_dirs: Some(vec!["/srv/plugins".to_string()]),
+                conflict_policy: Some(PluginConflictPolicy::Error),
+                allow_override: Some(false),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(
+            config.plugins.plugin_dirs,
+            vec![PathBuf::from("/srv/plugins")]
+        );
+        assert_eq!(config.plugins.conflict_policy, PluginConflictPolicy::Error);
+        assert!(!config.plugins.allow_override);
+
+        unsafe {
+            env::set_var("PLUGINS_CONFLICT_POLICY", "newest");
+        }
+        assert!(merge_config(None).is_err());
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_trusted_proxies_yaml_over_env() {
+        cleanup_env_vars();
+
+        let config = merge_config(None).unwrap();
+        assert!(config.trusted_proxies.is_empty());
+
+        unsafe {
+            env::set_var("TRUSTED_PROXIES", "10.0.0.0/8, 192.168.1.5");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(
+            config.trusted_proxies,
+            vec![
+                "10.0.0.0/8".parse().unwrap(),
+                "192.168.1.5/32".parse().unwrap()
+            ]
+        );
+
+        let yaml = YamlConfig {
+            security: Some(super::super::yaml::SecurityYaml {
+                trusted_proxies: Some(vec!["fd00::/8".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(config.trusted_proxies.len(), 1);
+        assert_eq!(config.trusted_proxies[0].to_string(), "fd00::/8");
+
+        unsafe {
+            env::set_var("TRUSTED_PROXIES", "10.0.0.0/40");
+        }
+        assert!(merge_config(None).is_err());
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_auth_admin_ids_yaml_over_env() {
+        cleanup_env_vars();
+
+        unsafe {
+            env::set_var("AUTH_ADMIN_IDS", "env-admin, ops");
+        }
+
+        // ENV only
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.auth_admin_ids, vec!["env-admin", "ops"]);
+
+        // YAML wins when non-empty
+        let yaml = YamlConfig {
+            auth: Some(super::super::yaml::AuthYaml {
+                admin_ids: vec!["yaml-admin".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(config.auth_admin_ids, vec!["yaml-admin"]);
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_auth_admin_scopes_yaml_over_env_per_scope() {
+        cleanup_env_vars();
+
+        unsafe {
+            env::set_var("AUTH_ADMIN_READ_IDS", "dashboard");
+            env::set_var("AUTH_ADMIN_WRITE_IDS", "env-ops");
+        }
+
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.auth_admin_scopes.read, vec!["dashboard"]);
+        assert_eq!(config.auth_admin_scopes.write, vec!["env-ops"]);
+        assert!(config.auth_admin_scopes.chaos.is_empty());
+
+        // A scope listed in YAML replaces only that scope's ENV holders
+        let yaml = YamlConfig {
+            auth: Some(super::super::yaml::AuthYaml {
+                admin_scopes: AdminScopeIds {
+                    write: vec!["yaml-ops".to_string()],
+                    chaos: vec!["sre".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(config.auth_admin_scopes.read, vec!["dashboard"]);
+        assert_eq!(config.auth_admin_scopes.write, vec!["yaml-ops"]);
+        assert_eq!(config.auth_admin_scopes.chaos, vec!["sre"]);
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_auth_api_secrets_yaml_over_env() {
+        cleanup_env_vars();
+
+        let yaml = YamlConfig {
+            auth: Some(super::super::yaml::AuthYaml {
+                api_secrets: vec![
+                    AuthApiSecretYaml {
+                        id: "yaml-a".to_string(),
+                        secret: "secret-a".to_string(),
+                    },
+                    AuthApiSecretYaml {
+                        id: "yaml-b".to_string(),
+                        secret: "secret-b".to_string(),
+                    },
+                ],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        unsafe {
+            env::set_var(
+                "AUTH_API_SECRETS_JSON",
+                r#"[{"id":"env-a","secret":"env-secret"}]"#,
+            );
+            env::set_var("AUTH_API_SECRET", "legacy-secret");
+        }
+
+        let config = merge_config(Some(yaml)).unwrap();
+
+        assert_eq!(config.auth_api_secrets.len(), 2);
+        assert_eq!(config.auth_api_secrets[0].id, "yaml-a");
+        assert_eq!(config.auth_api_secrets[1].id, "yaml-b");
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_auth_api_secrets_env_over_legacy() {
+        cleanup_env_vars();
+
+        let yaml = YamlConfig {
+            auth: Some(super::super::yaml::AuthYaml {
+                api_secret: Some("legacy-secret".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        unsafe {
+            env::set_var(
+                "AUTH_API_SECRETS_JSON",
+                r#"[{"id":"env-a","secret":"token-a"}]"#,
+            );
+        }
+
+        let config = merge_config(Some(yaml)).unwrap();
+
+        assert_eq!(config.auth_api_secrets.len(), 1);
+        assert_eq!(config.auth_api_secrets[0].id, "env-a");
+        assert_eq!(config.auth_api_secrets[0].secret, "token-a");
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_auth_api_secrets_empty_yaml_uses_legacy() {
+        cleanup_env_vars();
+
+        let yaml = YamlConfig {
+            auth: Some(super::super::yaml::AuthYaml {
+                api_secrets: Vec::new(),
+                api_secret: Some("legacy-secret".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let config = merge_config(Some(yaml)).unwrap();
+
+        assert_eq!(config.auth_api_secrets.len(), 1);
+        assert_eq!(config.auth_api_secrets[0].id, "default");
+        assert_eq!(config.auth_api_secrets[0].secret, "legacy-secret");
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_recording_prefix_yaml_overrides_env() {
+        cleanup_env_vars();
+
+        let yaml = YamlConfig {
+            recording: Some(super::super::yaml::RecordingYaml {
+                s3_prefix: Some("yaml-prefix".to_string()),
+                ..Default::default()
+            }),
             ..Default::default()
         };
 
@@ -950,6 +1799,349 @@ mod tests {
         cleanup_env_vars();
     }
 
+    #[test]
+    #[serial]
+    fn test_merge_provider_connect_timeout() {
+        cleanup_env_vars();
+
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.provider_connect_timeout_secs, 10);
+
+        unsafe {
+            env::set_var("PROVIDER_CONNECT_TIMEOUT_SECS", "20");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.provider_connect_timeout_secs, 20);
+
+        let yaml = YamlConfig {
+            providers: Some(super::super::yaml::ProvidersYaml {
+                connect_timeout_secs: Some(5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(config.provider_connect_timeout_secs, 5);
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_tts_fallback_voices() {
+        cleanup_env_vars();
+
+        unsafe {
+            env::set_var("TTS_FALLBACK_VOICES", "elevenlabs=env-voice");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.tts_fallback_voices["elevenlabs"], "env-voice");
+
+        // YAML replaces the environment map entirely
+        let yaml = YamlConfig {
+            providers: Some(super::super::yaml::ProvidersYaml {
+                tts_fallback_voices: Some(std::collections::HashMap::from([(
+                    "cartesia".to_string(),
+                    "yaml-voice".to_string(),
+                )])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(config.tts_fallback_voices.len(), 1);
+        assert_eq!(config.tts_fallback_voices["cartesia"], "yaml-voice");
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_replay_max_concurrent_jobs() {
+        cleanup_env_vars();
+
+        assert_eq!(merge_config(None).unwrap().replay_max_concurrent_jobs, 1);
+
+        unsafe {
+            env::set_var("REPLAY_MAX_CONCURRENT_JOBS", "2");
+        }
+        assert_eq!(merge_config(None).unwrap().replay_max_concurrent_jobs, 2);
+
+        let yaml = YamlConfig {
+            recording: Some(super::super::yaml::RecordingYaml {
+                replay_max_concurrent_jobs: Some(4),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            merge_config(Some(yaml)).unwrap().replay_max_concurrent_jobs,
+            4
+        );
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_tts_queue_limit() {
+        use crate::core::voice_manager::TTSQueuePolicy;
+
+        cleanup_env_vars();
+
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.tts_max_pending_utterances, 5);
+        assert_eq!(config.tts_queue_policy, TTSQueuePolicy::Reject);
+        assert_eq!(config.tts_system_speak_max_chars, 500);
+        assert_eq!(config.provider_error_max_chars, 300);
+        assert_eq!(config.deepgram_connection_budget.max_connections, None);
+
+        unsafe {
+            env::set_var("TTS_MAX_PENDING_UTTERANCES", "8");
+            env::set_var("TTS_QUEUE_POLICY", "drop_oldest");
+            env::set_var("TTS_SYSTEM_SPEAK_MAX_CHARS", "200");
+            env::set_var("PROVIDER_ERROR_MAX_CHARS", "150");
+        }
+        let config = merge_config(None).unwrap();
+        assert_eq!(config.tts_max_pending_utterances, 8);
+        assert_eq!(config.tts_queue_policy, TTSQueuePolicy::DropOldest);
+        assert_eq!(config.tts_system_speak_max_chars, 200);
+        assert_eq!(config.provider_error_max_chars, 150);
+
+        let yaml = YamlConfig {
+            providers: Some(super::super::yaml::ProvidersYaml {
+                tts_max_pending_utterances: Some(3),
+                tts_queue_policy: Some(TTSQueuePolicy::Reject),
+                tts_system_speak_max_chars: Some(80),
+                error_max_chars: Some(64),
+                deepgram_connection_budget: Some(ConnectionBudgetConfig {
+                    max_connections: Some(20),
+                    policy: ConnectionBudgetPolicy::Queue,
+                    queue_timeout_ms: 2000,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = merge_config(Some(yaml)).unwrap();
+        assert_eq!(config.tts_max_pending_utterances, 3);
+        assert_eq!(config.tts_queue_policy, TTSQueuePolicy::Reject);
+        assert_eq!(config.tts_system_speak_max_chars, 80);
+        assert_eq!(config.provider_error_max_chars, 64);
+        assert_eq!(config.deepgram_connection_budget.max_connections, Some(20));
+        assert_eq!(
+            config.deepgram_connection_budget.policy,
+            ConnectionBudgetPolicy::Queue
+        );
+
+        unsafe {
+            env::set_var("TTS_QUEUE_POLICY", "oldest");
+        }
+        assert!(merge_config(None).is_err());
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_greeting_yaml_overrides_env() {
+        cleanup_env_vars();
+        unsafe {
+            env::set_var("GREETING_TEXT", "Hello from env");
+            env::set_var("GREETING_DELAY_MS", "300");
+            env::set_var("GREETING_ASSETS_DIR", "/env/greetings");
+        }
+
+        let yaml = YamlConfig {
+            greeting: Some(super::super::yaml::GreetingYaml {
+                asset: Some("welcome".to_string()),
+                assets_dir: Some("/opt/greetings".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let config = merge_config(Some(yaml)).unwrap();
+        let greeting = config.greeting.clone().expect("greeting should be present");
+        assert_eq!(greeting.text, None);
+        assert_eq!(greeting.asset.as_deref(), Some("welcome"));
+        assert_eq!(greeting.delay_ms, Some(300));
+        assert_eq!(
+            config.greeting_assets_dir,
+            Some(PathBuf::from("/opt/greetings"))
+        );
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_greeting_env_only() {
+        cleanup_env_vars();
+        unsafe {
+            env::set_var("GREETING_TEXT", "Hello from env");
+        }
+
+        let config = merge_config(None).unwrap();
+        let greeting = config.greeting.clone().expect("greeting should be present");
+        assert_eq!(greeting.text.as_deref(), Some("Hello from env"));
+        assert_eq!(greeting.delay_ms, None);
+
+        cleanup_env_vars();
+
+        let config = merge_config(None).unwrap();
+        assert!(config.greeting.is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_usage_yaml_overrides_env() {
+        use super::super::usage::UsageSinkKind;
+
+        cleanup_env_vars();
+        unsafe {
+            env::set_var("USAGE_SINK", "webhook");
+            env::set_var("USAGE_WEBHOOK_URL", "https://env.example.com/usage");
+            env::set_var("USAGE_WEBHOOK_SECRET", "env-signing-secret");
+            env::set_var("USAGE_RECONCILIATION_TOLERANCE_PERCENT", "25");
+        }
+
+        let yaml = YamlConfig {
+            usage: Some(super::super::yaml::UsageYaml {
+                sink: Some(UsageSinkKind::Both),
+                file_path: Some("/var/log/waav/usage.jsonl".to_string()),
+                webhook_url: Some("https://yaml.example.com/usage".to_string()),
+                reconciliation_path: Some("/var/log/waav/reconciliation.jsonl".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let usage = merge_config(Some(yaml))
+            .unwrap()
+            .usage
+            .expect("usage should be configured");
+        assert_eq!(usage.sink, UsageSinkKind::Both);
+        assert_eq!(
+            usage.file_path,
+            Some(PathBuf::from("/var/log/waav/usage.jsonl"))
+        );
+        assert_eq!(
+            usage.webhook_url.as_deref(),
+            Some("https://yaml.example.com/usage")
+        );
+        // Settings missing from YAML come from the environment
+        assert_eq!(usage.webhook_secret.as_deref(), Some("env-signing-secret"));
+        assert_eq!(usage.file_max_files, 5);
+        assert_eq!(
+            usage.reconciliation_path,
+            Some(PathBuf::from("/var/log/waav/reconciliation.jsonl"))
+        );
+        assert_eq!(usage.reconciliation_tolerance_percent, 25);
+
+        cleanup_env_vars();
+
+        // Without a sink in either source usage records stay off
+        let yaml = YamlConfig {
+            usage: Some(super::super::yaml::UsageYaml {
+                file_path: Some("/var/log/waav/usage.jsonl".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(merge_config(Some(yaml)).unwrap().usage.is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_selftest_yaml() {
+        cleanup_env_vars();
+        assert!(merge_config(None).unwrap().selftest.is_none());
+
+        let yaml = YamlConfig {
+            selftest: Some(super::super::yaml::SelfTestYaml {
+                stt_provider: Some("deepgram".to_string()),
+                tts_provider: Some("elevenlabs".to_string()),
+                interval_secs: Some(3600),
+                required: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let selftest = merge_config(Some(yaml))
+            .unwrap()
+            .selftest
+            .expect("selftest should be configured");
+        assert_eq!(selftest.stt_provider, "deepgram");
+        assert_eq!(selftest.tts_provider, "elevenlabs");
+        assert_eq!(selftest.interval_secs, Some(3600));
+        assert!(selftest.required);
+        assert_eq!(selftest.phrase, super::super::DEFAULT_SELFTEST_PHRASE);
+
+        // Both providers must be named
+        let yaml = YamlConfig {
+            selftest: Some(super::super::yaml::SelfTestYaml {
+                stt_provider: Some("deepgram".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(merge_config(Some(yaml)).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_load_shedding() {
+        cleanup_env_vars();
+        assert!(merge_config(None).unwrap().load_shedding.is_none());
+
+        unsafe {
+            env::set_var("LOAD_SHED_MAX_ACTIVE_SESSIONS", "200");
+            env::set_var("LOAD_SHED_RETRY_AFTER_SECS", "10");
+        }
+        let yaml = YamlConfig {
+            load_shedding: Some(super::super::yaml::LoadSheddingYaml {
+                max_active_sessions: Some(500),
+                max_event_loop_lag_ms: Some(250),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let load_shedding = merge_config(Some(yaml))
+            .unwrap()
+            .load_shedding
+            .expect("load shedding should be configured");
+        assert_eq!(load_shedding.max_active_sessions, Some(500));
+        assert_eq!(load_shedding.max_pool_saturation, None);
+        assert_eq!(load_shedding.max_event_loop_lag_ms, Some(250));
+        assert_eq!(load_shedding.retry_after_secs, 10);
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_merge_strict_config() {
+        cleanup_env_vars();
+        assert!(!merge_config(None).unwrap().strict_config);
+
+        unsafe {
+            env::set_var("STRICT_CONFIG", "true");
+        }
+        assert!(merge_config(None).unwrap().strict_config);
+
+        // YAML takes precedence over the environment
+        let yaml = YamlConfig {
+            server: Some(super::super::yaml::ServerYaml {
+                strict_config: Some(false),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(!merge_config(Some(yaml)).unwrap().strict_config);
+
+        cleanup_env_vars();
+    }
+
     // SIP configuration merge tests
 
     #[test]
@@ -968,6 +2160,22 @@ mod tests {
                 }],
                 hook_secret: Some("global-secret".to_string()),
                 naming_prefix: None,
+                language_routing: Some(super::super::yaml::SipLanguageRoutingYaml {
+                    packs: [(
+                        "fr-ca".to_string(),
+                        super::super::yaml::LanguagePackYaml {
+                            stt_language: " fr-CA ".to_string(),
+                            tts_voice_id: Some("fr-voice".to_string()),
+                        },
+                    )]
+                    .into(),
+                    called_prefixes: [("+1514".to_string(), "fr-ca".to_string())].into(),
+                    ..Default::default()
+                }),
+                outbound: Some(super::super::sip::SipOutboundConfig {
+                    early_media_asset: Some("ringback".to_string()),
+                    ..Default::default()
+                }),
             }),
             ..Default::default()
         };
@@ -982,6 +2190,16 @@ mod tests {
         assert_eq!(sip.hooks[0].host, "example.com");
         assert_eq!(sip.hook_secret, Some("global-secret".to_string()));
         assert_eq!(sip.naming_prefix, "waav"); // default
+        let routing = &sip.language_routing;
+        assert_eq!(routing.packs["fr-ca"].stt_language, "fr-CA"); // trimmed
+        assert_eq!(
+            routing.packs["fr-ca"].tts_voice_id.as_deref(),
+            Some("fr-voice")
+        );
+        assert_eq!(routing.called_prefixes["+1514"], "fr-ca");
+        assert!(routing.trunks.is_empty());
+        assert_eq!(sip.outbound.early_media_asset.as_deref(), Some("ringback"));
+        assert_eq!(sip.outbound.no_answer_timeout_secs, 45); // default
 
         cleanup_env_vars();
     }
@@ -998,6 +2216,8 @@ mod tests {
                 hooks: vec![],
                 hook_secret: Some("yaml-secret".to_string()),
                 naming_prefix: None,
+                language_routing: None,
+                outbound: None,
             }),
             ..Default::default()
         };
@@ -1066,6 +2286,8 @@ mod tests {
                 hooks: vec![],
                 hook_secret: None,
                 naming_prefix: None,
+                language_routing: None,
+                outbound: None,
             }),
             ..Default::default()
         };
@@ -1110,6 +2332,8 @@ mod tests {
                 ],
                 hook_secret: Some("global-secret".to_string()),
                 naming_prefix: Some("custom".to_string()), // test custom naming_prefix
+                language_routing: None,
+                outbound: None,
             }),
             ..Default::default()
         };
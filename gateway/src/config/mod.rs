@@ -6,6 +6,7 @@
 //!
 //! # Modules
 //! - `yaml`: YAML configuration file loading
+//! - `encryption`: `!encrypted` values in YAML files
 //! - `env`: Environment variable loading
 //! - `merge`: Merging YAML and environment configurations
 //! - `validation`: Configuration validation logic
@@ -29,22 +30,103 @@
 //! # }
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
+use serde::Deserialize;
+
+use crate::agents::AgentProfile;
+use crate::core::providers::connection_budget::ConnectionBudgetConfig;
+use crate::core::session::TranscriptBufferConfig;
+use crate::core::voice_manager::TTSQueuePolicy;
+
+mod admin_scopes;
+mod audio_sinks;
+mod chaos;
+mod console;
+mod encryption;
 mod env;
+mod feature_flags;
+mod flight_recorder;
+mod greeting;
+mod ingest;
+mod load_shedding;
 mod merge;
 pub mod pricing;
+mod recording_redaction;
+mod recording_upload;
+mod selftest;
+mod session_export;
 mod sip;
+mod transcript_enrichment;
+mod trusted_proxies;
+mod usage;
 mod utils;
 mod validation;
+mod voice_profile;
+mod webhook_queue;
+mod ws_heartbeat;
 mod yaml;
 
+pub use admin_scopes::{AdminScope, AdminScopeIds};
+pub use audio_sinks::{
+    AudioSinksConfig, MAX_SINK_SAMPLE_RATE, MIN_SINK_SAMPLE_RATE, OPUS_BITRATE_RANGE_KBPS,
+    OPUS_SAMPLE_RATES, SinkAudioPolicy,
+};
+pub use chaos::ChaosConfig;
+pub use console::ConsoleConfig;
+pub use encryption::{
+    CONFIG_MASTER_KEY_ENV, CONFIG_MASTER_KEY_FILE_ENV, ConfigEncryptionError, ENCRYPTED_TAG,
+    MASTER_KEY_LEN, MasterKey, decrypt_value, encrypt_value,
+};
+pub use feature_flags::{
+    FeatureFlagConfig, FeatureFlags, KNOWN_FEATURE_FLAGS, rollout_bucket, unknown_feature_flags,
+};
+pub use flight_recorder::{FlightRecorderConfig, MAX_FLIGHT_LOG_BYTES};
+pub use greeting::{
+    GreetingConfig, GreetingSource, MAX_GREETING_DELAY_MS, MAX_GREETING_TEXT_SIZE,
+    greeting_asset_path, is_valid_asset_name,
+};
+pub use ingest::{IngestConfig, IngestStreamConfig};
+pub use load_shedding::{DEFAULT_LOAD_SHED_RETRY_AFTER_SECS, LoadSheddingConfig};
 pub use pricing::{
     ModelPricing, PricingUnit, estimate_stt_cost, estimate_tts_cost, get_stt_price_per_hour,
-    get_stt_pricing, get_tts_pricing, list_stt_models, list_tts_models,
+    get_stt_pricing, get_tts_pricing, list_stt_models, list_tts_models, stt_pricing_table,
+    tts_pricing_table,
+};
+pub use recording_redaction::{DEFAULT_DTMF_IDLE_MS, RecordingRedactionConfig};
+pub use recording_upload::{
+    MAX_RECORDING_PART_ATTEMPTS, MAX_RECORDING_PART_SIZE, MIN_RECORDING_PART_SIZE,
+    RecordingUploadConfig,
+};
+pub use selftest::{
+    DEFAULT_SELFTEST_MIN_SIMILARITY, DEFAULT_SELFTEST_PHRASE, DEFAULT_SELFTEST_SAMPLE_RATE,
+    DEFAULT_SELFTEST_TIMEOUT_SECS, SelfTestConfig,
+};
+pub use session_export::{
+    DEFAULT_ARTIFACT_URL_EXPIRY_SECS, MAX_ARTIFACT_URL_EXPIRY_SECS, SessionExportConfig,
+};
+pub use sip::{
+    LanguagePack, LanguagePackSource, MAX_NO_ANSWER_TIMEOUT_SECS, SipCallInfo, SipConfig,
+    SipHookConfig, SipLanguageRouting, SipOutboundConfig,
+};
+pub use transcript_enrichment::{
+    DEFAULT_ENRICHMENT_QUEUE_SIZE, DEFAULT_ENRICHMENT_WORKERS, TranscriptEnrichmentConfig,
+};
+pub use trusted_proxies::{IpNetwork, parse_trusted_proxies};
+pub use usage::{
+    DEFAULT_USAGE_FILE_MAX_BYTES, DEFAULT_USAGE_FILE_MAX_FILES, UsageConfig, UsageSinkKind,
+};
+pub use voice_profile::{
+    MAX_VOICE_PROFILE_NAME_LENGTH, ProviderVoice, VoiceProfile, resolve_voice_profile,
+};
+pub use webhook_queue::{MAX_WEBHOOK_ATTEMPTS, WebhookQueueConfig};
+pub use ws_heartbeat::{
+    DEFAULT_HEARTBEAT_INTERVAL_MS, DEFAULT_HEARTBEAT_MAX_MISSED, DEFAULT_HEARTBEAT_TIMEOUT_MS,
+    WsHeartbeatConfig,
 };
-pub use sip::{SipConfig, SipHookConfig};
 
 /// TLS configuration for HTTPS and WSS
 #[derive(Debug, Clone)]
@@ -73,21 +155,175 @@ pub struct AuthApiSecret {
 /// plugins:
 ///   enabled: true
 ///   plugin_dir: "/opt/waav/plugins"
+///   plugin_dirs: ["/opt/waav/plugins-override"]
+///   conflict_policy: last_dir_wins
+///   allow_override: false
+///   require_exact_abi: false
+///   init_timeout_secs: 5
+///   http_max_body_bytes: 65536
+///   http_timeout_ms: 5000
+///   http_max_concurrency: 16
+///   http_access:
+///     test-stt: ["ops-dashboard"]
+///   data_dir: "/var/lib/waav/plugins"
+///   storage_quota_bytes: 104857600
+///   storage_check_interval_secs: 60
 ///   providers:
 ///     deepgram:
 ///       custom_endpoint: "https://custom.deepgram.com"
 /// ```
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct PluginConfig {
     /// Whether the plugin system is enabled (default: true)
     pub enabled: bool,
     /// Directory to load external plugins from (optional, requires `plugins-dynamic` feature)
     pub plugin_dir: Option<PathBuf>,
+    /// Further plugin directories, searched after `plugin_dir` in order
+    pub plugin_dirs: Vec<PathBuf>,
+    /// Which library wins when several declare the same plugin ID
+    /// (default: `last_dir_wins`)
+    pub conflict_policy: PluginConflictPolicy,
+    /// Let dynamic plugins replace built-in providers of the same name
+    /// (default: false, such plugins are refused)
+    pub allow_override: bool,
+    /// Refuse to load plugins whose ABI version differs from the gateway's
+    /// (default: false, mismatches are logged as warnings)
+    pub require_exact_abi: bool,
+    /// Seconds each dynamic plugin may take to load and initialize before it
+    /// is marked as failed (default: 5)
+    pub init_timeout_secs: u64,
+    /// Largest request body accepted by `/plugins/{plugin_id}/...` routes
+    /// (default: 64 KiB)
+    pub http_max_body_bytes: usize,
+    /// Milliseconds a plugin may take to answer a `/plugins/{plugin_id}/...`
+    /// request before the gateway responds with 504 (default: 5000)
+    pub http_timeout_ms: u64,
+    /// Requests each plugin may be handling at once on its
+    /// `/plugins/{plugin_id}/...` routes; further requests get 503 (default: 16)
+    pub http_max_concurrency: usize,
+    /// Client ids holding the `plugin:{plugin_id}` scope, keyed by plugin ID
+    pub http_access: HashMap<String, Vec<String>>,
+    /// Directory holding one storage subdirectory per dynamic plugin ID,
+    /// passed to the plugin at init (default: none, no storage is provided)
+    pub data_dir: Option<PathBuf>,
+    /// Most bytes each plugin may keep in its storage directory
+    /// (default: 100 MiB)
+    pub storage_quota_bytes: u64,
+    /// Seconds between checks of the plugins' storage quotas (default: 60)
+    pub storage_check_interval_secs: u64,
     /// Provider-specific configuration (keyed by provider name)
     /// This allows passing custom settings to individual providers
     pub provider_config: HashMap<String, serde_json::Value>,
 }
 
+/// How the dynamic plugin loader resolves libraries declaring the same ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginConflictPolicy {
+    /// The library from the directory listed last wins
+    #[default]
+    LastDirWins,
+    /// The library with the highest semver version wins, ties go to the
+    /// later directory
+    HighestVersion,
+    /// Refuse to load any dynamic plugin while IDs collide
+    Error,
+}
+
+impl PluginConflictPolicy {
+    /// The policy's configuration name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LastDirWins => "last_dir_wins",
+            Self::HighestVersion => "highest_version",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for PluginConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PluginConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "last_dir_wins" => Ok(Self::LastDirWins),
+            "highest_version" => Ok(Self::HighestVersion),
+            "error" => Ok(Self::Error),
+            other => Err(format!(
+                "Invalid plugin conflict policy '{other}': expected 'last_dir_wins', 'highest_version' or 'error'"
+            )),
+        }
+    }
+}
+
+/// Default per-plugin load and init timeout in seconds
+pub const DEFAULT_PLUGIN_INIT_TIMEOUT_SECS: u64 = 5;
+
+/// Default body size limit of plugin HTTP routes in bytes
+pub const DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Default timeout of plugin HTTP routes in milliseconds
+pub const DEFAULT_PLUGIN_HTTP_TIMEOUT_MS: u64 = 5000;
+
+/// Default number of requests each plugin's HTTP routes handle at once
+pub const DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY: usize = 16;
+
+/// Default storage quota of each dynamic plugin in bytes
+pub const DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Default interval between plugin storage quota checks in seconds
+pub const DEFAULT_PLUGIN_STORAGE_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Default number of replay jobs that may run at once
+pub const DEFAULT_REPLAY_MAX_CONCURRENT_JOBS: usize = 1;
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            plugin_dir: None,
+            plugin_dirs: Vec::new(),
+            conflict_policy: PluginConflictPolicy::default(),
+            allow_override: false,
+            require_exact_abi: false,
+            init_timeout_secs: DEFAULT_PLUGIN_INIT_TIMEOUT_SECS,
+            http_max_body_bytes: DEFAULT_PLUGIN_HTTP_MAX_BODY_BYTES,
+            http_timeout_ms: DEFAULT_PLUGIN_HTTP_TIMEOUT_MS,
+            http_max_concurrency: DEFAULT_PLUGIN_HTTP_MAX_CONCURRENCY,
+            http_access: HashMap::new(),
+            data_dir: None,
+            storage_quota_bytes: DEFAULT_PLUGIN_STORAGE_QUOTA_BYTES,
+            storage_check_interval_secs: DEFAULT_PLUGIN_STORAGE_CHECK_INTERVAL_SECS,
+            provider_config: HashMap::new(),
+        }
+    }
+}
+
+impl PluginConfig {
+    /// Plugin directories in load order: `plugin_dir`, then `plugin_dirs`
+    pub fn plugin_directories(&self) -> Vec<PathBuf> {
+        self.plugin_dir
+            .iter()
+            .chain(&self.plugin_dirs)
+            .cloned()
+            .collect()
+    }
+
+    /// API key from a plugin provider's `api_key` setting, if configured
+    pub fn provider_api_key(&self, provider: &str) -> Option<&str> {
+        self.provider_config
+            .get(&provider.to_lowercase())
+            .and_then(|settings| settings.get("api_key"))
+            .and_then(serde_json::Value::as_str)
+    }
+}
+
 /// Server configuration
 ///
 /// Contains all configuration needed to run the WaaV Gateway server, including:
@@ -183,6 +419,16 @@ pub struct ServerConfig {
     pub auth_api_secrets: Vec<AuthApiSecret>,
     pub auth_timeout_seconds: u64,
     pub auth_required: bool,
+    /// Auth IDs (API secret ids or ids resolved by the auth service) holding
+    /// every admin scope. When auth is required and neither this nor
+    /// `auth_admin_scopes` lists an ID, admin endpoints are denied to everyone.
+    pub auth_admin_ids: Vec<String>,
+    /// Auth IDs granted the `monitor:audio` scope: they may fetch the key that
+    /// decrypts a session's monitor audio. Admin IDs always hold it.
+    pub auth_monitor_audio_ids: Vec<String>,
+    /// Auth IDs granted single admin scopes (`admin:read`, `admin:write`,
+    /// `admin:chaos`) without being admins.
+    pub auth_admin_scopes: AdminScopeIds,
 
     // SIP configuration (optional)
     pub sip: Option<SipConfig>,
@@ -207,11 +453,145 @@ pub struct ServerConfig {
     /// Maximum connections per IP address
     /// Default: 100
     pub max_connections_per_ip: u32,
+    /// Proxies whose `X-Forwarded-For` and `X-Real-IP` headers are honored
+    /// when resolving the client address for rate limits, connection limits
+    /// and auth logs
+    /// Default: empty (the socket address is always used)
+    pub trusted_proxies: Vec<IpNetwork>,
+
+    // Provider connection configuration
+    /// Maximum time to wait for a provider `connect()` during session setup
+    /// Default: 10
+    pub provider_connect_timeout_secs: u64,
+    /// Fallback TTS voice per provider (provider name -> voice id), used for the
+    /// rest of a session when its configured voice does not exist.
+    /// Default: empty (sessions with a missing voice fail at setup)
+    pub tts_fallback_voices: HashMap<String, String>,
+    /// Maximum TTS utterances pending per session before `tts_queue_policy` applies
+    /// Default: 5
+    pub tts_max_pending_utterances: usize,
+    /// What happens to a speak request when the pending TTS queue is full
+    /// Default: reject
+    pub tts_queue_policy: TTSQueuePolicy,
+    /// Longest text, in characters, accepted by a `system` priority speak
+    /// Default: 500
+    pub tts_system_speak_max_chars: usize,
+    /// Longest provider error message, in characters, sent to clients
+    /// Default: 300
+    pub provider_error_max_chars: usize,
+    /// Concurrent connections per Deepgram API key, counted across STT and
+    /// TTS, and what happens to connects past the limit (YAML only).
+    /// Default: unlimited
+    pub deepgram_connection_budget: ConnectionBudgetConfig,
 
     // Plugin configuration
     /// Plugin system configuration (optional, backward compatible)
     /// If not specified, the plugin system is enabled with built-in providers only
     pub plugins: PluginConfig,
+
+    // Greeting configuration
+    /// Greeting spoken when a session starts (optional, overridable per session)
+    pub greeting: Option<GreetingConfig>,
+    /// Directory containing greeting audio assets
+    pub greeting_assets_dir: Option<PathBuf>,
+
+    // Usage record configuration
+    /// Where per-session usage records are written (disabled when None)
+    pub usage: Option<UsageConfig>,
+
+    // Agent profiles
+    /// Named session configurations clients select with `agent` (YAML only)
+    pub agents: Vec<AgentProfile>,
+
+    // Session configuration messages
+    /// Reject config messages with unknown fields instead of ignoring them.
+    /// Sessions can override this with `strict_config` in their config message.
+    /// Default: false
+    pub strict_config: bool,
+
+    // Provider self-test
+    /// Canary synthesize-and-transcribe check run at startup (disabled when None, YAML only)
+    pub selftest: Option<SelfTestConfig>,
+
+    // Load shedding
+    /// Thresholds for turning away new sessions under load (disabled when None).
+    /// Can be changed at runtime through `PUT /admin/load_shedding`.
+    pub load_shedding: Option<LoadSheddingConfig>,
+
+    // Recording replay
+    /// Replay jobs (`POST /admin/replay`) that may run at once; further jobs
+    /// wait for a slot. Each running job holds one STT and one TTS connection.
+    /// Default: 1
+    pub replay_max_concurrent_jobs: usize,
+
+    // Feature flags
+    /// Session feature flags by name (YAML only). Can be changed at runtime
+    /// through `PUT /admin/feature_flags`; open sessions keep the flags they
+    /// were configured with.
+    pub feature_flags: BTreeMap<String, FeatureFlagConfig>,
+
+    // Session transcripts
+    /// Limits of the transcript each session keeps for exports, and what
+    /// happens to entries past them (YAML only). `spill_to_cache` writes
+    /// overflowing entries to the gateway cache.
+    pub transcript_buffer: TranscriptBufferConfig,
+    /// Post-call diarization of voice sessions, storing the labelled
+    /// transcript and posting `transcript.enriched` (disabled when None, YAML only)
+    pub transcript_enrichment: Option<TranscriptEnrichmentConfig>,
+    /// Post-call export of session artifacts with expiring download URLs,
+    /// announced with `session.ended` (disabled when None, YAML only)
+    pub session_export: Option<SessionExportConfig>,
+
+    // Recording and monitor audio
+    /// Downsampling, downmixing and Opus bitrate of recordings and monitor
+    /// audio, independent of the live session (YAML only)
+    pub audio_sinks: AudioSinksConfig,
+    /// Recording of caller audio as `input.wav`, uploaded in parts during
+    /// the call and resumed from a spill directory after outages (disabled
+    /// when None, YAML only)
+    pub recording_upload: Option<RecordingUploadConfig>,
+    /// Silences the gateway's captures while the caller keys in sensitive
+    /// digits (YAML only)
+    pub recording_redaction: RecordingRedactionConfig,
+
+    // Fault injection
+    /// Whether `/admin/chaos` may inject faults into sessions (YAML only).
+    /// Needs a gateway built with the `chaos` feature.
+    /// Default: disabled
+    pub chaos: ChaosConfig,
+
+    // Voice profiles
+    /// Logical voices by name, each mapping TTS providers to the voice they
+    /// use for it (YAML only). Sessions select one with
+    /// `tts_config.voice_profile`.
+    pub voice_profiles: BTreeMap<String, VoiceProfile>,
+
+    // WebSocket heartbeat
+    /// Heartbeat pings sent to `/ws` clients to reap half-open connections
+    pub ws_heartbeat: WsHeartbeatConfig,
+
+    // Development console
+    /// Whether `/console` serves the browser console (YAML only). Needs a
+    /// gateway built with the `dev-console` feature.
+    /// Default: disabled
+    pub console: ConsoleConfig,
+
+    // Outbound webhooks
+    /// Durable queue that usage, `session.ended` and `transcript.enriched`
+    /// webhooks are delivered through, kept in the cache backend (POSTed
+    /// inline when None, YAML only)
+    pub webhook_queue: Option<WebhookQueueConfig>,
+
+    // Broadcast ingest
+    /// RTMP listener transcribing published streams in agent-profile
+    /// sessions (disabled when None, YAML only). Needs a gateway built with
+    /// the `ingest` feature.
+    pub ingest: Option<IngestConfig>,
+
+    // Session flight recorder
+    /// Logs of the sessions that opt in, kept in the cache backend for
+    /// `session replay` (disabled when None, YAML only)
+    pub flight_recorder: Option<FlightRecorderConfig>,
 }
 
 /// Implement Drop to zeroize all secret fields when ServerConfig is dropped.
@@ -279,6 +659,12 @@ impl Drop for ServerConfig {
         for secret in &mut self.auth_api_secrets {
             secret.secret.zeroize();
         }
+        // Zeroize ingest stream keys
+        if let Some(ref mut ingest) = self.ingest {
+            for stream in &mut ingest.streams {
+                stream.key.zeroize();
+            }
+        }
         // Zeroize SIP hook secrets if present
         if let Some(ref mut sip) = self.sip {
             if let Some(ref mut hook_secret) = sip.hook_secret {
@@ -356,6 +742,36 @@ impl ServerConfig {
             &config.auth_api_secrets,
         )?;
         validation::validate_sip_config(&config.sip)?;
+        validation::validate_greeting_config(&config.greeting, &config.greeting_assets_dir)?;
+        validation::validate_provider_connect_timeout(config.provider_connect_timeout_secs)?;
+        validation::validate_tts_fallback_voices(&config.tts_fallback_voices)?;
+        validation::validate_tts_max_pending_utterances(config.tts_max_pending_utterances)?;
+        validation::validate_tts_system_speak_max_chars(config.tts_system_speak_max_chars)?;
+        validation::validate_provider_error_max_chars(config.provider_error_max_chars)?;
+        validation::validate_deepgram_connection_budget(&config.deepgram_connection_budget)?;
+        validation::validate_usage_config(&config.usage)?;
+        validation::validate_agent_profiles(&config.agents)?;
+        validation::validate_voice_profiles(&config.voice_profiles, &config.agents)?;
+        validation::validate_selftest_config(&config.selftest)?;
+        validation::validate_load_shedding_config(&config.load_shedding)?;
+        validation::validate_replay_max_concurrent_jobs(config.replay_max_concurrent_jobs)?;
+        validation::validate_plugin_http_max_concurrency(config.plugins.http_max_concurrency)?;
+        validation::validate_plugin_storage(&config.plugins)?;
+        validation::validate_feature_flags(&config.feature_flags)?;
+        validation::validate_transcript_buffer(&config.transcript_buffer)?;
+        validation::validate_transcript_enrichment(&config.transcript_enrichment)?;
+        validation::validate_session_export(&config.session_export, &config.auth_api_secrets)?;
+        validation::validate_audio_sinks(&config.audio_sinks)?;
+        validation::validate_recording_upload(
+            &config.recording_upload,
+            config.recording_s3_bucket.as_deref(),
+        )?;
+        validation::validate_recording_redaction(&config.recording_redaction)?;
+        validation::validate_ws_heartbeat(&config.ws_heartbeat)?;
+        validation::validate_console(&config.console, config.auth_required, &config.host)?;
+        validation::validate_webhook_queue(&config.webhook_queue)?;
+        validation::validate_ingest(&config.ingest)?;
+        validation::validate_flight_recorder(&config.flight_recorder)?;
 
         Ok(config)
     }
@@ -409,6 +825,53 @@ impl ServerConfig {
             .map(|entry| entry.id.as_str())
     }
 
+    /// Check if an authenticated client id has admin privileges
+    ///
+    /// Comparison is case-insensitive against `auth_admin_ids`.
+    pub fn is_admin_id(&self, id: &str) -> bool {
+        self.auth_admin_ids
+            .iter()
+            .any(|admin| admin.eq_ignore_ascii_case(id))
+    }
+
+    /// Check if an authenticated client id holds an admin scope
+    ///
+    /// Admins hold every scope; other ids must be listed for the scope in
+    /// `auth_admin_scopes` (case-insensitive).
+    pub fn has_admin_scope(&self, id: &str, scope: AdminScope) -> bool {
+        self.is_admin_id(id)
+            || self
+                .auth_admin_scopes
+                .holders(scope)
+                .iter()
+                .any(|holder| holder.eq_ignore_ascii_case(id))
+    }
+
+    /// Check if an authenticated client id holds the `monitor:audio` scope
+    ///
+    /// Admins hold every scope; other ids must be listed in
+    /// `auth_monitor_audio_ids` (case-insensitive).
+    pub fn can_monitor_audio(&self, id: &str) -> bool {
+        self.is_admin_id(id)
+            || self
+                .auth_monitor_audio_ids
+                .iter()
+                .any(|holder| holder.eq_ignore_ascii_case(id))
+    }
+
+    /// Check if an authenticated client id holds the `plugin:{plugin_id}` scope
+    ///
+    /// Admins hold every scope; other ids must be listed for the plugin in
+    /// `plugins.http_access` (case-insensitive).
+    pub fn can_access_plugin(&self, plugin_id: &str, id: &str) -> bool {
+        self.is_admin_id(id)
+            || self
+                .plugins
+                .http_access
+                .get(plugin_id)
+                .is_some_and(|holders| holders.iter().any(|holder| holder.eq_ignore_ascii_case(id)))
+    }
+
     /// Get API key for a specific provider
     ///
     /// # Arguments
@@ -537,6 +1000,38 @@ impl ServerConfig {
         }
     }
 
+    /// Get the provider credentials configured on the server
+    ///
+    /// Used to redact the gateway's own keys from provider error messages
+    /// before they reach clients. Google credentials are a file path or a
+    /// JSON document rather than a key and are not included.
+    pub fn provider_secrets(&self) -> Vec<String> {
+        [
+            &self.livekit_api_key,
+            &self.livekit_api_secret,
+            &self.deepgram_api_key,
+            &self.elevenlabs_api_key,
+            &self.azure_speech_subscription_key,
+            &self.cartesia_api_key,
+            &self.openai_api_key,
+            &self.assemblyai_api_key,
+            &self.hume_api_key,
+            &self.lmnt_api_key,
+            &self.groq_api_key,
+            &self.playht_api_key,
+            &self.ibm_watson_api_key,
+            &self.aws_access_key_id,
+            &self.aws_secret_access_key,
+            &self.gnani_token,
+            &self.gnani_access_key,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|secret| !secret.is_empty())
+        .cloned()
+        .collect()
+    }
+
     /// Get Azure Speech Services region
     ///
     /// Returns the configured Azure region, or "eastus" as default if not specified.
@@ -693,13 +1188,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         }
     }
 
@@ -763,13 +1291,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = config.get_api_key("elevenlabs");
@@ -822,13 +1383,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = config.get_api_key("deepgram");
@@ -884,13 +1478,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = config.get_api_key("unsupported_provider");
@@ -946,13 +1573,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Test uppercase
@@ -1014,13 +1674,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         assert!(config_with_jwt.has_jwt_auth());
@@ -1069,13 +1762,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         assert!(!config_without_jwt.has_jwt_auth());
@@ -1129,13 +1855,46 @@ mod tests {
             }],
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         assert!(config_with_api_secret.has_api_secret_auth());
@@ -1184,13 +1943,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         assert!(!config_without_api_secret.has_api_secret_auth());
@@ -1250,17 +2042,71 @@ mod tests {
             ],
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: vec!["client-a".to_string()],
+            auth_monitor_audio_ids: vec!["client-b".to_string()],
+            auth_admin_scopes: AdminScopeIds {
+                read: vec!["client-b".to_string()],
+                ..Default::default()
+            },
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         assert_eq!(config.find_api_secret_id("token-a"), Some("client-a"));
         assert_eq!(config.find_api_secret_id("missing"), None);
+
+        assert!(config.is_admin_id("client-a"));
+        assert!(config.is_admin_id("CLIENT-A"));
+        assert!(!config.is_admin_id("client-b"));
+
+        // Admins hold monitor:audio without being listed
+        assert!(config.can_monitor_audio("client-a"));
+        assert!(config.can_monitor_audio("CLIENT-B"));
+        assert!(!config.can_monitor_audio("client-c"));
+
+        // Admins hold every admin scope, others only those listed
+        for scope in AdminScope::ALL {
+            assert!(config.has_admin_scope("client-a", scope));
+        }
+        assert!(config.has_admin_scope("Client-B", AdminScope::Read));
+        assert!(!config.has_admin_scope("client-b", AdminScope::Write));
+        assert!(!config.has_admin_scope("client-b", AdminScope::Chaos));
+        assert!(!config.has_admin_scope("client-c", AdminScope::Read));
     }
 
     #[test]
@@ -1308,13 +2154,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Google returns the credentials path/content when configured
@@ -1369,13 +2248,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Google returns the inline JSON credentials when configured
@@ -1429,13 +2341,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Google returns empty string when not configured, allowing ADC to be used
@@ -1489,13 +2434,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Test uppercase
@@ -1554,13 +2532,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = config.get_api_key("microsoft-azure");
@@ -1613,13 +2624,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let result = config.get_api_key("microsoft-azure");
@@ -1675,13 +2719,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         assert_eq!(config.get_azure_speech_region(), "westeurope");
@@ -1732,19 +2809,67 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Default is "eastus"
         assert_eq!(config.get_azure_speech_region(), "eastus");
     }
 
+    #[test]
+    fn test_plugin_provider_api_key() {
+        let mut plugins = PluginConfig::default();
+        plugins.provider_config.insert(
+            "mock-realtime".to_string(),
+            serde_json::json!({"api_key": "plugin-key"}),
+        );
+
+        assert_eq!(
+            plugins.provider_api_key("Mock-Realtime"),
+            Some("plugin-key")
+        );
+        assert_eq!(plugins.provider_api_key("other-plugin"), None);
+    }
+
     // Helper to clean up environment variables
     fn cleanup_env_vars() {
         unsafe {
@@ -1764,6 +2889,8 @@ mod tests {
             env::remove_var("AUTH_API_SECRET_ID");
             env::remove_var("AUTH_TIMEOUT_SECONDS");
             env::remove_var("RECORDING_S3_PREFIX");
+            env::remove_var(CONFIG_MASTER_KEY_ENV);
+            env::remove_var(CONFIG_MASTER_KEY_FILE_ENV);
         }
     }
 
@@ -1880,6 +3007,97 @@ providers:
         cleanup_env_vars();
     }
 
+    #[test]
+    #[serial]
+    fn test_from_file_decrypts_encrypted_values() {
+        cleanup_env_vars();
+
+        let temp_dir = TempDir::new().unwrap();
+        let key_path = temp_dir.path().join("master.key");
+        let config_path = temp_dir.path().join("config.yaml");
+
+        let key_bytes = [42u8; MASTER_KEY_LEN];
+        let key = MasterKey::from_bytes(key_bytes);
+        let yaml_content = format!(
+            "providers:\n  deepgram_api_key: !encrypted \"{}\"\n  elevenlabs_api_key: plain-el-key\nauth:\n  api_secrets:\n    - id: ops\n      secret: !encrypted {}\n",
+            encrypt_value(&key, "dg-secret"),
+            encrypt_value(&key, "ops-secret"),
+        );
+        fs::write(&config_path, yaml_content).unwrap();
+
+        use base64::Engine;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(key_bytes);
+
+        // Key given inline
+        unsafe {
+            env::set_var(CONFIG_MASTER_KEY_ENV, &encoded);
+        }
+        let config = ServerConfig::from_file(&config_path).unwrap();
+        assert_eq!(config.deepgram_api_key, Some("dg-secret".to_string()));
+        assert_eq!(config.elevenlabs_api_key, Some("plain-el-key".to_string()));
+        assert_eq!(config.auth_api_secrets[0].secret, "ops-secret");
+
+        // Key read from a file
+        fs::write(&key_path, format!("{encoded}\n")).unwrap();
+        unsafe {
+            env::remove_var(CONFIG_MASTER_KEY_ENV);
+            env::set_var(CONFIG_MASTER_KEY_FILE_ENV, &key_path);
+        }
+        let config = ServerConfig::from_file(&config_path).unwrap();
+        assert_eq!(config.deepgram_api_key, Some("dg-secret".to_string()));
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_file_encrypted_value_errors() {
+        cleanup_env_vars();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        let encrypted = encrypt_value(&MasterKey::from_bytes([1u8; MASTER_KEY_LEN]), "secret");
+        fs::write(
+            &config_path,
+            format!("providers:\n  deepgram_api_key: !encrypted \"{encrypted}\"\n"),
+        )
+        .unwrap();
+
+        let error = ServerConfig::from_file(&config_path)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("no master key"), "{error}");
+        assert!(error.contains("providers.deepgram_api_key"), "{error}");
+
+        use base64::Engine;
+        let other_key = base64::engine::general_purpose::STANDARD.encode([2u8; MASTER_KEY_LEN]);
+        unsafe {
+            env::set_var(CONFIG_MASTER_KEY_ENV, other_key);
+        }
+        let error = ServerConfig::from_file(&config_path)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("different master key"), "{error}");
+
+        // Right key, damaged ciphertext
+        let right_key = base64::engine::general_purpose::STANDARD.encode([1u8; MASTER_KEY_LEN]);
+        unsafe {
+            env::set_var(CONFIG_MASTER_KEY_ENV, right_key);
+        }
+        let damaged = format!("{}AAAA", &encrypted[..encrypted.len() - 4]);
+        fs::write(
+            &config_path,
+            format!("providers:\n  deepgram_api_key: !encrypted \"{damaged}\"\n"),
+        )
+        .unwrap();
+        let error = ServerConfig::from_file(&config_path)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("is corrupt"), "{error}");
+
+        cleanup_env_vars();
+    }
+
     #[test]
     #[serial]
     fn test_from_file_with_auth() {
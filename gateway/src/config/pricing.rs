@@ -87,6 +87,21 @@ impl ModelPricing {
             PricingUnit::Per1KChars | PricingUnit::Per1MChars => 0.0,
         }
     }
+
+    /// Convert price to per-1K-characters rate for comparison.
+    pub fn to_per_1k_chars(&self) -> f64 {
+        match self.unit {
+            PricingUnit::Per1KChars => self.price,
+            PricingUnit::Per1MChars => self.price / 1000.0,
+            // Time-based pricing can't be converted to character-based
+            PricingUnit::PerHour | PricingUnit::PerMinute | PricingUnit::PerSecond => 0.0,
+        }
+    }
+
+    /// Whether the price is per character of text rather than per audio time.
+    pub fn is_per_character(&self) -> bool {
+        matches!(self.unit, PricingUnit::Per1KChars | PricingUnit::Per1MChars)
+    }
 }
 
 // =============================================================================
@@ -114,11 +129,23 @@ static STT_PRICING: LazyLock<HashMap<&'static str, ModelPricing>> = LazyLock::ne
             "12% WER, 216x real-time, min 10s billing",
         ),
     );
+    m.insert(
+        "groq:distil-whisper-large-v3-en",
+        ModelPricing::with_notes(0.02, PricingUnit::PerHour, "English only"),
+    );
 
     // -------------------------------------------------------------------------
     // Deepgram
     // https://deepgram.com/pricing
     // -------------------------------------------------------------------------
+    m.insert(
+        "deepgram:nova-3",
+        ModelPricing::with_notes(
+            0.0077 * 60.0,
+            PricingUnit::PerHour,
+            "Streaming pay-as-you-go rate per minute",
+        ),
+    );
     m.insert(
         "deepgram:nova-2",
         ModelPricing::with_notes(
@@ -164,6 +191,14 @@ static STT_PRICING: LazyLock<HashMap<&'static str, ModelPricing>> = LazyLock::ne
         "openai:whisper-1",
         ModelPricing::new(0.006 * 60.0, PricingUnit::PerHour), // $0.006/minute
     );
+    m.insert(
+        "openai:gpt-4o-transcribe",
+        ModelPricing::new(0.006 * 60.0, PricingUnit::PerHour),
+    );
+    m.insert(
+        "openai:gpt-4o-mini-transcribe",
+        ModelPricing::new(0.003 * 60.0, PricingUnit::PerHour),
+    );
 
     // -------------------------------------------------------------------------
     // Google Cloud Speech-to-Text
@@ -329,6 +364,14 @@ static TTS_PRICING: LazyLock<HashMap<&'static str, ModelPricing>> = LazyLock::ne
         "openai:tts-1-hd",
         ModelPricing::new(30.0, PricingUnit::Per1MChars), // $30 per 1M chars
     );
+    m.insert(
+        "openai:gpt-4o-mini-tts",
+        ModelPricing::with_notes(
+            0.015,
+            PricingUnit::PerMinute,
+            "Estimate; billed per text and audio token",
+        ),
+    );
 
     // -------------------------------------------------------------------------
     // Google Cloud Text-to-Speech
@@ -533,6 +576,16 @@ pub fn estimate_tts_cost(provider: &str, model: &str, char_count: usize) -> Opti
     })
 }
 
+/// All STT pricing entries, keyed by `"provider:model"` (lowercase).
+pub fn stt_pricing_table() -> &'static HashMap<&'static str, ModelPricing> {
+    &STT_PRICING
+}
+
+/// All TTS pricing entries, keyed by `"provider:model"` (lowercase).
+pub fn tts_pricing_table() -> &'static HashMap<&'static str, ModelPricing> {
+    &TTS_PRICING
+}
+
 /// List all available STT models for a provider.
 pub fn list_stt_models(provider: &str) -> Vec<&'static str> {
     let prefix = format!("{}:", provider.to_lowercase());
@@ -663,6 +716,18 @@ mod tests {
         assert!((per_1k_chars.to_per_hour()).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn test_to_per_1k_chars_conversion() {
+        let per_1m_chars = ModelPricing::new(15.0, PricingUnit::Per1MChars);
+        assert!((per_1m_chars.to_per_1k_chars() - 0.015).abs() < f64::EPSILON);
+        assert!(per_1m_chars.is_per_character());
+
+        // Time-based returns 0
+        let per_minute = ModelPricing::new(0.015, PricingUnit::PerMinute);
+        assert!(per_minute.to_per_1k_chars().abs() < f64::EPSILON);
+        assert!(!per_minute.is_per_character());
+    }
+
     #[test]
     fn test_openai_pricing() {
         // STT
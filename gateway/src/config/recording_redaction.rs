@@ -0,0 +1,87 @@
+//! Redaction of recordings during sensitive entry
+//!
+//! PCI DSS forbids recording the audio of a caller keying in a card number.
+//! While a redaction window is open, the gateway's captures of a session
+//! (the caller recording and monitor audio) receive silence, and the window
+//! is listed in the session's exports. Windows are opened by the client's
+//! `redaction` message or by DTMF digits from a SIP caller.
+
+use serde::Deserialize;
+
+/// Default time after the last DTMF digit a window opened by digits closes, in milliseconds
+pub const DEFAULT_DTMF_IDLE_MS: u64 = 5_000;
+
+/// When and how recordings are redacted
+///
+/// LiveKit egress cannot be paused. With `mute_egress_tracks`, the caller's
+/// audio tracks are muted server-side for the window instead, so the room
+/// recording holds silence too; the session then hears nothing either, and
+/// LiveKit must allow remote unmute (`room.enable_remote_unmute`) for the
+/// tracks to come back.
+///
+/// # Example YAML
+/// ```yaml
+/// recording_redaction:
+///   dtmf: true
+///   dtmf_idle_ms: 5000
+///   mute_egress_tracks: false
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecordingRedactionConfig {
+    /// Open a window when a SIP caller keys in DTMF digits (default: true)
+    pub dtmf: bool,
+    /// Close a window opened by DTMF digits this long after the last digit
+    pub dtmf_idle_ms: u64,
+    /// Mute the caller's LiveKit tracks while a window is open, so a running
+    /// room recording is redacted as well (default: false)
+    pub mute_egress_tracks: bool,
+}
+
+impl Default for RecordingRedactionConfig {
+    fn default() -> Self {
+        Self {
+            dtmf: true,
+            dtmf_idle_ms: DEFAULT_DTMF_IDLE_MS,
+            mute_egress_tracks: false,
+        }
+    }
+}
+
+impl RecordingRedactionConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if every setting is in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.dtmf_idle_ms == 0 {
+            return Err("dtmf_idle_ms must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let config: RecordingRedactionConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, RecordingRedactionConfig::default());
+        assert!(config.dtmf);
+        assert!(!config.mute_egress_tracks);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = RecordingRedactionConfig {
+            dtmf_idle_ms: 0,
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("dtmf_idle_ms"));
+        assert!(serde_yaml::from_str::<RecordingRedactionConfig>("tone: true").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Gateway recording of caller audio, uploaded while the call runs
+//!
+//! When enabled, the caller's input audio of every voice session is stored
+//! as `input.wav` next to the session's recording, in the recording bucket.
+//! The audio is sent as an S3 multipart upload whose parts are uploaded as
+//! they fill, so an outage at the end of the call does not lose it: parts
+//! are kept in `spill_dir` until S3 has them and a background reconciler
+//! finishes uploads that are left over, after a restart as well.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Smallest part S3 accepts for all but the last part (5 MiB)
+pub const MIN_RECORDING_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Largest part size accepted (100 MiB)
+pub const MAX_RECORDING_PART_SIZE: usize = 100 * 1024 * 1024;
+
+/// Most attempts made to upload one part before it is left to the reconciler
+pub const MAX_RECORDING_PART_ATTEMPTS: u32 = 20;
+
+/// Recording of caller audio with resumable multipart uploads
+///
+/// # Example YAML
+/// ```yaml
+/// recording_upload:
+///   spill_dir: "/var/lib/waav/recording-spill"
+///   part_size_bytes: 5242880
+///   max_part_attempts: 5
+///   retry_base_delay_ms: 500
+///   retry_max_delay_ms: 30000
+///   reconcile_interval_secs: 30
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecordingUploadConfig {
+    /// Directory unfinished uploads are kept in until S3 has every part
+    pub spill_dir: Option<PathBuf>,
+    /// Size of the uploaded parts in bytes (the last part may be smaller)
+    pub part_size_bytes: usize,
+    /// Attempts made to upload a part before it waits for the reconciler
+    pub max_part_attempts: u32,
+    /// Delay before the first retry of a part; doubles with every retry (ms)
+    pub retry_base_delay_ms: u64,
+    /// Longest delay between retries of a part (ms)
+    pub retry_max_delay_ms: u64,
+    /// How often unfinished uploads are retried in the background (seconds)
+    pub reconcile_interval_secs: u64,
+}
+
+impl Default for RecordingUploadConfig {
+    fn default() -> Self {
+        Self {
+            spill_dir: None,
+            part_size_bytes: MIN_RECORDING_PART_SIZE,
+            max_part_attempts: 5,
+            retry_base_delay_ms: 500,
+            retry_max_delay_ms: 30_000,
+            reconcile_interval_secs: 30,
+        }
+    }
+}
+
+impl RecordingUploadConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the spill directory is set and the sizes and delays are in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .spill_dir
+            .as_ref()
+            .is_none_or(|dir| dir.as_os_str().is_empty())
+        {
+            return Err("spill_dir is required".to_string());
+        }
+        if !(MIN_RECORDING_PART_SIZE..=MAX_RECORDING_PART_SIZE).contains(&self.part_size_bytes) {
+            return Err(format!(
+                "part_size_bytes must be between {MIN_RECORDING_PART_SIZE} and {MAX_RECORDING_PART_SIZE} (got {})",
+                self.part_size_bytes
+            ));
+        }
+        if !(1..=MAX_RECORDING_PART_ATTEMPTS).contains(&self.max_part_attempts) {
+            return Err(format!(
+                "max_part_attempts must be between 1 and {MAX_RECORDING_PART_ATTEMPTS} (got {})",
+                self.max_part_attempts
+            ));
+        }
+        if self.retry_base_delay_ms == 0 || self.retry_base_delay_ms > self.retry_max_delay_ms {
+            return Err(format!(
+                "retry_base_delay_ms must be between 1 and retry_max_delay_ms ({}) (got {})",
+                self.retry_max_delay_ms, self.retry_base_delay_ms
+            ));
+        }
+        if self.reconcile_interval_secs == 0 {
+            return Err("reconcile_interval_secs must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spilling() -> RecordingUploadConfig {
+        RecordingUploadConfig {
+            spill_dir: Some(PathBuf::from("/var/lib/waav/recording-spill")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recording_upload_validation() {
+        assert!(spilling().validate().is_ok());
+        assert!(
+            RecordingUploadConfig::default()
+                .validate()
+                .unwrap_err()
+                .contains("spill_dir")
+        );
+
+        let small_parts = RecordingUploadConfig {
+            part_size_bytes: 1024 * 1024,
+            ..spilling()
+        };
+        assert!(
+            small_parts
+                .validate()
+                .unwrap_err()
+                .contains("part_size_bytes")
+        );
+        let no_attempts = RecordingUploadConfig {
+            max_part_attempts: 0,
+            ..spilling()
+        };
+        assert!(no_attempts.validate().is_err());
+        let backoff = RecordingUploadConfig {
+            retry_base_delay_ms: 60_000,
+            ..spilling()
+        };
+        assert!(
+            backoff
+                .validate()
+                .unwrap_err()
+                .contains("retry_base_delay_ms")
+        );
+        let no_interval = RecordingUploadConfig {
+            reconcile_interval_secs: 0,
+            ..spilling()
+        };
+        assert!(no_interval.validate().is_err());
+
+        let config: RecordingUploadConfig =
+            serde_yaml::from_str("spill_dir: /tmp/spill\nmax_part_attempts: 3").unwrap();
+        assert_eq!(config.max_part_attempts, 3);
+        assert_eq!(config.part_size_bytes, MIN_RECORDING_PART_SIZE);
+        assert!(serde_yaml::from_str::<RecordingUploadConfig>("part_size: 10").is_err());
+    }
+}
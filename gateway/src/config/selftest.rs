@@ -0,0 +1,195 @@
+//! Provider self-test configuration
+//!
+//! The self-test synthesizes a canary phrase with a TTS provider, transcribes
+//! the audio with an STT provider and compares the transcript with the phrase.
+//! It runs at startup and optionally on an interval.
+
+use std::time::Duration;
+
+/// Phrase synthesized and transcribed by the self-test when none is configured
+pub const DEFAULT_SELFTEST_PHRASE: &str = "The quick brown fox jumps over the lazy dog";
+
+/// Default minimum transcript similarity for the self-test to pass
+pub const DEFAULT_SELFTEST_MIN_SIMILARITY: f64 = 0.8;
+
+/// Default time limit for one self-test run, in seconds
+pub const DEFAULT_SELFTEST_TIMEOUT_SECS: u64 = 30;
+
+/// Default sample rate the canary audio is synthesized and transcribed at
+pub const DEFAULT_SELFTEST_SAMPLE_RATE: u32 = 16000;
+
+/// Shortest allowed interval between periodic runs, in seconds
+const MIN_SELFTEST_INTERVAL_SECS: u64 = 60;
+
+/// Maximum length of the canary phrase in characters
+const MAX_SELFTEST_PHRASE_LENGTH: usize = 200;
+
+/// Startup and periodic provider self-test
+///
+/// # Example YAML
+/// ```yaml
+/// selftest:
+///   stt_provider: deepgram
+///   tts_provider: deepgram
+///   phrase: "The quick brown fox jumps over the lazy dog"
+///   min_similarity: 0.8
+///   timeout_secs: 30
+///   interval_secs: 3600
+///   required: false
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestConfig {
+    /// STT provider the canary audio is transcribed with
+    pub stt_provider: String,
+    /// STT model (provider default when None)
+    pub stt_model: Option<String>,
+    /// TTS provider the canary phrase is synthesized with
+    pub tts_provider: String,
+    /// TTS voice (provider default when None)
+    pub tts_voice: Option<String>,
+    /// TTS model (provider default when None)
+    pub tts_model: Option<String>,
+    /// Language of the canary phrase
+    pub language: String,
+    /// Phrase synthesized and transcribed
+    pub phrase: String,
+    /// Sample rate of the canary audio in Hz
+    pub sample_rate: u32,
+    /// Minimum similarity (0.0 to 1.0) between phrase and transcript to pass
+    pub min_similarity: f64,
+    /// Time limit for one run in seconds
+    pub timeout_secs: u64,
+    /// Seconds between periodic runs (startup only when None)
+    pub interval_secs: Option<u64>,
+    /// Report the gateway as not ready until a run passes
+    pub required: bool,
+}
+
+impl SelfTestConfig {
+    /// Create a configuration for the given providers with default settings
+    pub fn new(stt_provider: impl Into<String>, tts_provider: impl Into<String>) -> Self {
+        Self {
+            stt_provider: stt_provider.into(),
+            stt_model: None,
+            tts_provider: tts_provider.into(),
+            tts_voice: None,
+            tts_model: None,
+            language: "en-US".to_string(),
+            phrase: DEFAULT_SELFTEST_PHRASE.to_string(),
+            sample_rate: DEFAULT_SELFTEST_SAMPLE_RATE,
+            min_similarity: DEFAULT_SELFTEST_MIN_SIMILARITY,
+            timeout_secs: DEFAULT_SELFTEST_TIMEOUT_SECS,
+            interval_secs: None,
+            required: false,
+        }
+    }
+
+    /// Time limit for one run
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    /// Time between periodic runs, None when the test only runs at startup
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval_secs.map(Duration::from_secs)
+    }
+
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the providers are named and every setting is in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.stt_provider.trim().is_empty() {
+            return Err("selftest stt_provider must not be empty".to_string());
+        }
+        if self.tts_provider.trim().is_empty() {
+            return Err("selftest tts_provider must not be empty".to_string());
+        }
+
+        let phrase = self.phrase.trim();
+        if phrase.is_empty() {
+            return Err("selftest phrase must not be empty".to_string());
+        }
+        if phrase.chars().count() > MAX_SELFTEST_PHRASE_LENGTH {
+            return Err(format!(
+                "selftest phrase must be at most {MAX_SELFTEST_PHRASE_LENGTH} characters long"
+            ));
+        }
+
+        if !(self.min_similarity > 0.0 && self.min_similarity <= 1.0) {
+            return Err(format!(
+                "selftest min_similarity must be greater than 0 and at most 1, got {}",
+                self.min_similarity
+            ));
+        }
+        if self.sample_rate == 0 {
+            return Err("selftest sample_rate must be greater than 0".to_string());
+        }
+        if self.timeout_secs == 0 {
+            return Err("selftest timeout_secs must be greater than 0".to_string());
+        }
+        if let Some(interval) = self.interval_secs
+            && interval < MIN_SELFTEST_INTERVAL_SECS
+        {
+            return Err(format!(
+                "selftest interval_secs must be at least {MIN_SELFTEST_INTERVAL_SECS}, got {interval}"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selftest_defaults() {
+        let config = SelfTestConfig::new("deepgram", "elevenlabs");
+        assert!(config.validate().is_ok());
+        assert_eq!(config.phrase, DEFAULT_SELFTEST_PHRASE);
+        assert_eq!(config.timeout(), Duration::from_secs(30));
+        assert_eq!(config.interval(), None);
+        assert!(!config.required);
+    }
+
+    #[test]
+    fn test_selftest_config_validation() {
+        let base = SelfTestConfig::new("deepgram", "deepgram");
+
+        let config = SelfTestConfig {
+            stt_provider: " ".to_string(),
+            ..base.clone()
+        };
+        assert!(config.validate().unwrap_err().contains("stt_provider"));
+
+        let config = SelfTestConfig {
+            phrase: String::new(),
+            ..base.clone()
+        };
+        assert!(config.validate().unwrap_err().contains("phrase"));
+
+        for min_similarity in [0.0, 1.5, f64::NAN] {
+            let config = SelfTestConfig {
+                min_similarity,
+                ..base.clone()
+            };
+            assert!(config.validate().unwrap_err().contains("min_similarity"));
+        }
+
+        let config = SelfTestConfig {
+            interval_secs: Some(5),
+            ..base.clone()
+        };
+        assert!(config.validate().unwrap_err().contains("interval_secs"));
+
+        let config = SelfTestConfig {
+            interval_secs: Some(3600),
+            required: true,
+            ..base
+        };
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,209 @@
+//! Post-call session export configuration
+//!
+//! When enabled, every finished voice session stores its transcript (JSON
+//! and SRT) and usage record next to its recording and announces all of its
+//! artifacts with a `session.ended` webhook carrying expiring download URLs.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Default lifetime of an artifact download URL (1 hour)
+pub const DEFAULT_ARTIFACT_URL_EXPIRY_SECS: u64 = 3600;
+
+/// Longest lifetime of an artifact download URL (7 days, the S3 presigning limit)
+pub const MAX_ARTIFACT_URL_EXPIRY_SECS: u64 = 7 * 24 * 3600;
+
+/// Minimum length of the download URL signing key
+const MIN_SIGNING_KEY_LENGTH: usize = 32;
+
+/// Minimum length of the export webhook signing secret
+const MIN_WEBHOOK_SECRET_LENGTH: usize = 16;
+
+/// Export of finished sessions and their artifacts
+///
+/// Artifacts are stored in the recording bucket and linked with pre-signed
+/// S3 URLs. Without a bucket they are stored in `local_dir` and linked with
+/// gateway URLs (`{public_base_url}/downloads/...`) signed with
+/// `signing_key`.
+///
+/// # Example YAML
+/// ```yaml
+/// session_export:
+///   url_expiry_secs: 3600
+///   local_dir: "/var/lib/waav/sessions"
+///   public_base_url: "https://voice.example.com"
+///   signing_key: "a-dedicated-key-of-at-least-32-characters"
+///   webhook_url: "https://crm.example.com/waav/sessions"
+///   webhook_secret: "session-webhook-secret"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionExportConfig {
+    /// Lifetime of the download URLs in seconds
+    pub url_expiry_secs: u64,
+    /// Directory artifacts are stored in when no recording bucket is configured
+    pub local_dir: Option<PathBuf>,
+    /// Externally reachable base URL of the gateway, for signed gateway URLs
+    pub public_base_url: Option<String>,
+    /// Key gateway download URLs are signed with; never one of the auth secrets
+    pub signing_key: Option<String>,
+    /// URL the `session.ended` event is POSTed to (no webhook when None)
+    pub webhook_url: Option<String>,
+    /// Secret the webhook payload is signed with (`X-WaaV-Signature`)
+    pub webhook_secret: Option<String>,
+}
+
+impl Default for SessionExportConfig {
+    fn default() -> Self {
+        Self {
+            url_expiry_secs: DEFAULT_ARTIFACT_URL_EXPIRY_SECS,
+            local_dir: None,
+            public_base_url: None,
+            signing_key: None,
+            webhook_url: None,
+            webhook_secret: None,
+        }
+    }
+}
+
+impl SessionExportConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the expiry is in range and the gateway URLs and webhook are complete
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.url_expiry_secs == 0 || self.url_expiry_secs > MAX_ARTIFACT_URL_EXPIRY_SECS {
+            return Err(format!(
+                "url_expiry_secs must be between 1 and {MAX_ARTIFACT_URL_EXPIRY_SECS} (got {})",
+                self.url_expiry_secs
+            ));
+        }
+
+        if let Some(base_url) = &self.public_base_url {
+            validate_http_url("public_base_url", base_url)?;
+        }
+        if let Some(key) = self.signing_key.as_deref().map(str::trim)
+            && key.len() < MIN_SIGNING_KEY_LENGTH
+        {
+            return Err(format!(
+                "signing_key must be at least {MIN_SIGNING_KEY_LENGTH} characters long (got {})",
+                key.len()
+            ));
+        }
+        if self.local_dir.is_some() {
+            if self.public_base_url.is_none() {
+                return Err("public_base_url is required with local_dir".to_string());
+            }
+            if self.signing_key.is_none() {
+                return Err("signing_key is required with local_dir".to_string());
+            }
+        }
+
+        let Some(url) = &self.webhook_url else {
+            if self.webhook_secret.is_some() {
+                return Err("webhook_secret is set without webhook_url".to_string());
+            }
+            return Ok(());
+        };
+        validate_http_url("webhook_url", url)?;
+        match self.webhook_secret.as_deref().map(str::trim) {
+            None | Some("") => Err("webhook_secret is required with webhook_url".to_string()),
+            Some(secret) if secret.len() < MIN_WEBHOOK_SECRET_LENGTH => Err(format!(
+                "webhook_secret must be at least {MIN_WEBHOOK_SECRET_LENGTH} characters long (got {})",
+                secret.len()
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+fn validate_http_url(name: &str, url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("invalid {name} '{url}': {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "{name} must use http or https, got '{}'",
+            parsed.scheme()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local() -> SessionExportConfig {
+        SessionExportConfig {
+            local_dir: Some(PathBuf::from("/var/lib/waav/sessions")),
+            public_base_url: Some("https://voice.example.com".to_string()),
+            signing_key: Some("k".repeat(32)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_session_export_validation() {
+        assert!(SessionExportConfig::default().validate().is_ok());
+        assert!(local().validate().is_ok());
+
+        let no_expiry = SessionExportConfig {
+            url_expiry_secs: 0,
+            ..Default::default()
+        };
+        assert!(
+            no_expiry
+                .validate()
+                .unwrap_err()
+                .contains("url_expiry_secs")
+        );
+        let too_long = SessionExportConfig {
+            url_expiry_secs: MAX_ARTIFACT_URL_EXPIRY_SECS + 1,
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let no_key = SessionExportConfig {
+            signing_key: None,
+            ..local()
+        };
+        assert!(no_key.validate().unwrap_err().contains("signing_key"));
+        let short_key = SessionExportConfig {
+            signing_key: Some("short".to_string()),
+            ..local()
+        };
+        assert!(short_key.validate().unwrap_err().contains("at least 32"));
+        let no_base_url = SessionExportConfig {
+            public_base_url: None,
+            ..local()
+        };
+        assert!(
+            no_base_url
+                .validate()
+                .unwrap_err()
+                .contains("public_base_url")
+        );
+
+        let webhook = SessionExportConfig {
+            webhook_url: Some("https://crm.example.com/sessions".to_string()),
+            webhook_secret: Some("a-sufficiently-long-secret".to_string()),
+            ..Default::default()
+        };
+        assert!(webhook.validate().is_ok());
+        let no_secret = SessionExportConfig {
+            webhook_secret: None,
+            ..webhook.clone()
+        };
+        assert!(no_secret.validate().unwrap_err().contains("webhook_secret"));
+        let bad_scheme = SessionExportConfig {
+            webhook_url: Some("ftp://crm.example.com".to_string()),
+            ..webhook
+        };
+        assert!(bad_scheme.validate().is_err());
+
+        let config: SessionExportConfig = serde_yaml::from_str("url_expiry_secs: 600").unwrap();
+        assert_eq!(config.url_expiry_secs, 600);
+        assert!(serde_yaml::from_str::<SessionExportConfig>("expiry: 600").is_err());
+    }
+}
@@ -1,7 +1,15 @@
 //! SIP configuration structures
 //!
 //! This module defines SIP-specific configuration including room prefixes,
-//! allowed IP addresses for SIP connections, and downstream webhook targets.
+//! allowed IP addresses for SIP connections, downstream webhook targets, the
+//! language packs inbound calls start in and the handling of outbound calls
+//! before they are answered.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use super::greeting::is_valid_asset_name;
 
 use crate::utils::sip_hooks::SipHookInfo;
 
@@ -42,6 +50,8 @@ impl AsRef<str> for SipHookConfig {
 /// - Allowed IP addresses/CIDRs for SIP connections
 /// - Downstream webhook targets for event forwarding
 /// - Global signing secret for webhook requests
+/// - Language pack selection for inbound calls
+/// - Early media and no-answer handling for outbound calls
 #[derive(Debug, Clone)]
 pub struct SipConfig {
     /// Prefix for SIP room names (alphanumeric, '-', '_')
@@ -57,6 +67,10 @@ pub struct SipConfig {
     /// Format: `{naming_prefix}-{room_prefix}-trunk` and `{naming_prefix}-{room_prefix}-dispatch`
     /// Defaults to "waav" if not specified.
     pub naming_prefix: String,
+    /// Language pack for calls whose session does not set a language
+    pub language_routing: SipLanguageRouting,
+    /// Handling of outbound calls until they are answered
+    pub outbound: SipOutboundConfig,
 }
 
 impl SipConfig {
@@ -103,10 +117,299 @@ impl SipConfig {
             hooks,
             hook_secret,
             naming_prefix,
+            language_routing: SipLanguageRouting::default(),
+            outbound: SipOutboundConfig::default(),
+        }
+    }
+
+    /// Set the language pack selection for inbound calls
+    pub fn with_language_routing(mut self, language_routing: SipLanguageRouting) -> Self {
+        self.language_routing = language_routing;
+        self
+    }
+
+    /// Set the handling of outbound calls until they are answered
+    pub fn with_outbound(mut self, outbound: SipOutboundConfig) -> Self {
+        self.outbound = outbound;
+        self
+    }
+}
+
+/// Longest no-answer timer accepted for outbound calls (seconds)
+pub const MAX_NO_ANSWER_TIMEOUT_SECS: u64 = 600;
+
+/// Handling of outbound calls until they are answered
+///
+/// Applies to sessions that place a call (`livekit.outbound_call`). While
+/// the call is dialing or ringing the session plays `early_media_asset` (an
+/// asset from the greeting assets directory, e.g. ringback or a compliance
+/// message), or nothing when it is unset. A call left unanswered for
+/// `no_answer_timeout_secs` is abandoned.
+///
+/// # Example YAML
+/// ```yaml
+/// sip:
+///   outbound:
+///     early_media_asset: "ringback"
+///     no_answer_timeout_secs: 45
+///     status_poll_interval_ms: 500
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SipOutboundConfig {
+    /// Asset played while the call rings; silence when unset
+    pub early_media_asset: Option<String>,
+    /// Time the callee has to answer before the call is abandoned
+    pub no_answer_timeout_secs: u64,
+    /// How often the room is checked for the call's status while it is
+    /// unanswered, besides LiveKit webhooks (ms)
+    pub status_poll_interval_ms: u64,
+}
+
+impl Default for SipOutboundConfig {
+    fn default() -> Self {
+        Self {
+            early_media_asset: None,
+            no_answer_timeout_secs: 45,
+            status_poll_interval_ms: 500,
+        }
+    }
+}
+
+impl SipOutboundConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the asset name and timers are valid
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(asset) = &self.early_media_asset
+            && !is_valid_asset_name(asset)
+        {
+            return Err(format!(
+                "invalid early_media_asset '{asset}': must be a file name without path separators"
+            ));
+        }
+        if !(1..=MAX_NO_ANSWER_TIMEOUT_SECS).contains(&self.no_answer_timeout_secs) {
+            return Err(format!(
+                "no_answer_timeout_secs must be between 1 and {MAX_NO_ANSWER_TIMEOUT_SECS} (got {})",
+                self.no_answer_timeout_secs
+            ));
+        }
+        if !(100..=10_000).contains(&self.status_poll_interval_ms) {
+            return Err(format!(
+                "status_poll_interval_ms must be between 100 and 10000 (got {})",
+                self.status_poll_interval_ms
+            ));
         }
+        Ok(())
+    }
+}
+
+/// Session settings for calls in one language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguagePack {
+    /// STT language code (e.g. "fr-CA")
+    pub stt_language: String,
+    /// TTS voice used when the session does not choose one
+    pub tts_voice_id: Option<String>,
+}
+
+/// Where the language pack of a call was chosen from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguagePackSource {
+    /// The trunk the call came in on
+    Trunk,
+    /// The longest matching prefix of the called number
+    CalledNumber,
+    /// The longest matching country calling code of the caller's number
+    CallerCountry,
+    /// The caller's SIP `Accept-Language` header
+    AcceptLanguage,
+    /// `default_pack`
+    Default,
+}
+
+impl LanguagePackSource {
+    /// Name recorded in session metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Trunk => "trunk",
+            Self::CalledNumber => "called_number",
+            Self::CallerCountry => "caller_country",
+            Self::AcceptLanguage => "accept_language",
+            Self::Default => "default",
+        }
+    }
+}
+
+/// The call details a language pack is chosen by
+///
+/// Read from the LiveKit SIP participant's attributes. `Accept-Language` is
+/// only present when the trunk maps the header to `sip.h.accept-language`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SipCallInfo {
+    /// LiveKit SIP trunk ID (`sip.trunkID`)
+    pub trunk_id: Option<String>,
+    /// Number that was called (`sip.trunkPhoneNumber`)
+    pub called_number: Option<String>,
+    /// Number of the caller (`sip.phoneNumber`)
+    pub caller_number: Option<String>,
+    /// Caller's `Accept-Language` header (`sip.h.accept-language`)
+    pub accept_language: Option<String>,
+}
+
+impl SipCallInfo {
+    /// Read the call details from SIP participant attributes
+    pub fn from_attributes(attributes: &HashMap<String, String>) -> Self {
+        let get = |key: &str| attributes.get(key).filter(|v| !v.is_empty()).cloned();
+        Self {
+            trunk_id: get("sip.trunkID"),
+            called_number: get("sip.trunkPhoneNumber"),
+            caller_number: get("sip.phoneNumber"),
+            accept_language: get("sip.h.accept-language"),
+        }
+    }
+}
+
+/// Language pack selection for inbound SIP calls
+///
+/// A call starts in the pack of its trunk, else of the longest prefix of the
+/// called number, else of the longest country calling code matching the
+/// caller's number, else of the first `Accept-Language` tag a pack's STT
+/// language matches, else `default_pack`. Numbers and prefixes are compared
+/// by their digits, so `+44` and `44` are the same prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SipLanguageRouting {
+    /// Language packs by name
+    pub packs: BTreeMap<String, LanguagePack>,
+    /// Trunk ID -> pack name
+    pub trunks: HashMap<String, String>,
+    /// Called number prefix -> pack name
+    pub called_prefixes: HashMap<String, String>,
+    /// Caller country calling code (e.g. "+33") -> pack name
+    pub caller_countries: HashMap<String, String>,
+    /// Pack for calls no rule matches
+    pub default_pack: Option<String>,
+}
+
+impl SipLanguageRouting {
+    /// Whether no pack would ever be chosen
+    pub fn is_empty(&self) -> bool {
+        self.packs.is_empty()
+    }
+
+    /// Choose the language pack of a call
+    ///
+    /// # Returns
+    /// * `Some((name, pack, source))` - The chosen pack and the rule that chose it
+    /// * `None` - No rule matches and there is no default pack
+    pub fn resolve(&self, call: &SipCallInfo) -> Option<(&str, &LanguagePack, LanguagePackSource)> {
+        let by_trunk = call
+            .trunk_id
+            .as_ref()
+            .and_then(|trunk| self.trunks.get(trunk))
+            .map(|name| (name.as_str(), LanguagePackSource::Trunk));
+        let by_called = || {
+            longest_prefix(&self.called_prefixes, call.called_number.as_deref()?)
+                .map(|name| (name, LanguagePackSource::CalledNumber))
+        };
+        let by_country = || {
+            longest_prefix(&self.caller_countries, call.caller_number.as_deref()?)
+                .map(|name| (name, LanguagePackSource::CallerCountry))
+        };
+        let by_header = || {
+            self.accept_language_pack(call.accept_language.as_deref()?)
+                .map(|name| (name, LanguagePackSource::AcceptLanguage))
+        };
+        let by_default = || {
+            self.default_pack
+                .as_deref()
+                .map(|name| (name, LanguagePackSource::Default))
+        };
+
+        let (name, source) = by_trunk
+            .or_else(by_called)
+            .or_else(by_country)
+            .or_else(by_header)
+            .or_else(by_default)?;
+        let (name, pack) = self.packs.get_key_value(name)?;
+        Some((name.as_str(), pack, source))
+    }
+
+    /// Every pack name the rules refer to, with the rule it appears in
+    pub fn pack_references(&self) -> impl Iterator<Item = (String, &str)> {
+        let trunks = self
+            .trunks
+            .iter()
+            .map(|(trunk, name)| (format!("trunks.{trunk}"), name.as_str()));
+        let called = self
+            .called_prefixes
+            .iter()
+            .map(|(prefix, name)| (format!("called_prefixes.{prefix}"), name.as_str()));
+        let countries = self
+            .caller_countries
+            .iter()
+            .map(|(code, name)| (format!("caller_countries.{code}"), name.as_str()));
+        let default = self
+            .default_pack
+            .iter()
+            .map(|name| ("default_pack".to_string(), name.as_str()));
+        trunks.chain(called).chain(countries).chain(default)
+    }
+
+    /// First pack whose STT language matches a tag of an `Accept-Language`
+    /// header, by preference
+    ///
+    /// A tag matches a pack's full language code, or else its primary
+    /// language ("fr" matches "fr-CA").
+    fn accept_language_pack(&self, header: &str) -> Option<&str> {
+        let mut tags: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable, so equally preferred tags keep the header's order
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        tags.iter().find_map(|&(tag, _)| {
+            let primary = |code: &str| code.split('-').next().unwrap_or(code).to_lowercase();
+            let exact = self
+                .packs
+                .iter()
+                .find(|(_, pack)| pack.stt_language.eq_ignore_ascii_case(tag));
+            exact
+                .or_else(|| {
+                    self.packs
+                        .iter()
+                        .find(|(_, pack)| primary(&pack.stt_language) == primary(tag))
+                })
+                .map(|(name, _)| name.as_str())
+        })
     }
 }
 
+/// Pack of the longest prefix of `number`, comparing digits only
+fn longest_prefix<'a>(prefixes: &'a HashMap<String, String>, number: &str) -> Option<&'a str> {
+    let number = digits(number);
+    prefixes
+        .iter()
+        .map(|(prefix, name)| (digits(prefix), name))
+        .filter(|(prefix, _)| !prefix.is_empty() && number.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, name)| name.as_str())
+}
+
+fn digits(number: &str) -> String {
+    number.chars().filter(char::is_ascii_digit).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -243,4 +546,203 @@ mod tests {
 
         assert_eq!(config.naming_prefix, "waav"); // Falls back to default
     }
+
+    fn pack(stt_language: &str) -> LanguagePack {
+        LanguagePack {
+            stt_language: stt_language.to_string(),
+            tts_voice_id: None,
+        }
+    }
+
+    fn rules(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn routing() -> SipLanguageRouting {
+        SipLanguageRouting {
+            packs: [
+                ("en-us", pack("en-US")),
+                ("en-gb", pack("en-GB")),
+                ("fr-ca", pack("fr-CA")),
+                ("fr-fr", pack("fr-FR")),
+                ("es", pack("es-ES")),
+            ]
+            .into_iter()
+            .map(|(name, pack)| (name.to_string(), pack))
+            .collect(),
+            trunks: rules(&[("ST_quebec", "fr-ca")]),
+            called_prefixes: rules(&[("+1", "en-us"), ("+1514", "fr-ca"), ("+1 514 555", "en-us")]),
+            caller_countries: rules(&[("+33", "fr-fr"), ("+44", "en-gb")]),
+            default_pack: Some("en-us".to_string()),
+        }
+    }
+
+    fn resolve(call: SipCallInfo) -> Option<(String, LanguagePackSource)> {
+        routing()
+            .resolve(&call)
+            .map(|(name, _, source)| (name.to_string(), source))
+    }
+
+    #[test]
+    fn test_language_routing_longest_called_prefix_wins() {
+        let called = |number: &str| {
+            resolve(SipCallInfo {
+                called_number: Some(number.to_string()),
+                ..Default::default()
+            })
+        };
+        assert_eq!(
+            called("+15145550123"),
+            Some(("en-us".to_string(), LanguagePackSource::CalledNumber))
+        );
+        assert_eq!(
+            called("+15149870123"),
+            Some(("fr-ca".to_string(), LanguagePackSource::CalledNumber))
+        );
+        // Digits are compared, whatever the formatting
+        assert_eq!(
+            called("1 (514) 987-0123"),
+            Some(("fr-ca".to_string(), LanguagePackSource::CalledNumber))
+        );
+        assert_eq!(
+            called("+12125550123"),
+            Some(("en-us".to_string(), LanguagePackSource::CalledNumber))
+        );
+    }
+
+    #[test]
+    fn test_language_routing_precedence() {
+        let call = SipCallInfo {
+            trunk_id: Some("ST_quebec".to_string()),
+            called_number: Some("+12125550123".to_string()),
+            caller_number: Some("+33612345678".to_string()),
+            accept_language: Some("es".to_string()),
+        };
+        // Trunk beats the called number
+        assert_eq!(
+            resolve(call.clone()),
+            Some(("fr-ca".to_string(), LanguagePackSource::Trunk))
+        );
+
+        // Called number beats the caller's country
+        let call = SipCallInfo {
+            trunk_id: Some("ST_other".to_string()),
+            ..call
+        };
+        assert_eq!(
+            resolve(call.clone()),
+            Some(("en-us".to_string(), LanguagePackSource::CalledNumber))
+        );
+
+        // Caller's country beats Accept-Language
+        let call = SipCallInfo {
+            called_number: Some("+4930123456".to_string()),
+            ..call
+        };
+        assert_eq!(
+            resolve(call.clone()),
+            Some(("fr-fr".to_string(), LanguagePackSource::CallerCountry))
+        );
+
+        // Accept-Language beats the default
+        let call = SipCallInfo {
+            caller_number: Some("+4930654321".to_string()),
+            ..call
+        };
+        assert_eq!(
+            resolve(call.clone()),
+            Some(("es".to_string(), LanguagePackSource::AcceptLanguage))
+        );
+
+        assert_eq!(
+            resolve(SipCallInfo::default()),
+            Some(("en-us".to_string(), LanguagePackSource::Default))
+        );
+        let no_default = SipLanguageRouting {
+            default_pack: None,
+            ..routing()
+        };
+        assert!(no_default.resolve(&SipCallInfo::default()).is_none());
+    }
+
+    #[test]
+    fn test_language_routing_accept_language_preference() {
+        let header = |value: &str| {
+            resolve(SipCallInfo {
+                accept_language: Some(value.to_string()),
+                ..Default::default()
+            })
+            .map(|(name, _)| name)
+        };
+        // Highest quality first; exact codes before primary languages
+        assert_eq!(
+            header("de;q=0.9, fr-CA;q=0.8, es;q=0.5").as_deref(),
+            Some("fr-ca")
+        );
+        assert_eq!(header("es;q=0.2, en-GB").as_deref(), Some("en-gb"));
+        assert_eq!(header("fr").as_deref(), Some("fr-ca"));
+        // Nothing matches: the default pack
+        assert_eq!(header("de, *;q=0.1").as_deref(), Some("en-us"));
+        assert_eq!(header("es;q=0").as_deref(), Some("en-us"));
+    }
+
+    #[test]
+    fn test_sip_outbound_validation() {
+        assert!(SipOutboundConfig::default().validate().is_ok());
+
+        let parsed: SipOutboundConfig =
+            serde_json::from_str(r#"{"early_media_asset": "ringback"}"#).unwrap();
+        assert_eq!(parsed.early_media_asset.as_deref(), Some("ringback"));
+        assert_eq!(parsed.no_answer_timeout_secs, 45);
+        assert!(parsed.validate().is_ok());
+
+        let escaping = SipOutboundConfig {
+            early_media_asset: Some("../ringback".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            escaping
+                .validate()
+                .unwrap_err()
+                .contains("early_media_asset")
+        );
+
+        let no_timer = SipOutboundConfig {
+            no_answer_timeout_secs: 0,
+            ..Default::default()
+        };
+        assert!(
+            no_timer
+                .validate()
+                .unwrap_err()
+                .contains("no_answer_timeout_secs")
+        );
+
+        assert!(serde_json::from_str::<SipOutboundConfig>(r#"{"ringback": "tone"}"#).is_err());
+    }
+
+    #[test]
+    fn test_sip_call_info_from_attributes() {
+        let attributes = HashMap::from([
+            ("sip.trunkID".to_string(), "ST_abc".to_string()),
+            ("sip.phoneNumber".to_string(), "+33612345678".to_string()),
+            (
+                "sip.trunkPhoneNumber".to_string(),
+                "+15145550123".to_string(),
+            ),
+            ("sip.h.accept-language".to_string(), String::new()),
+        ]);
+        assert_eq!(
+            SipCallInfo::from_attributes(&attributes),
+            SipCallInfo {
+                trunk_id: Some("ST_abc".to_string()),
+                called_number: Some("+15145550123".to_string()),
+                caller_number: Some("+33612345678".to_string()),
+                accept_language: None,
+            }
+        );
+    }
 }
@@ -0,0 +1,136 @@
+//! Post-call transcript enrichment configuration
+//!
+//! When enabled, every voice session records when the bot spoke and the
+//! level of the caller's audio, and a background worker diarizes the
+//! finished session, stores the labelled transcript next to the recording
+//! and announces it with a `transcript.enriched` webhook.
+
+use serde::{Deserialize, Serialize};
+
+/// Default number of enrichment workers
+pub const DEFAULT_ENRICHMENT_WORKERS: usize = 2;
+
+/// Default number of finished sessions waiting for a worker
+pub const DEFAULT_ENRICHMENT_QUEUE_SIZE: usize = 64;
+
+/// Minimum length of the enrichment webhook signing secret
+const MIN_WEBHOOK_SECRET_LENGTH: usize = 16;
+
+/// Background diarization of finished sessions
+///
+/// # Example YAML
+/// ```yaml
+/// transcript_enrichment:
+///   workers: 2
+///   queue_size: 64
+///   webhook_url: "https://crm.example.com/waav/transcripts"
+///   webhook_secret: "transcript-signing-secret"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TranscriptEnrichmentConfig {
+    /// Sessions enriched at once
+    pub workers: usize,
+    /// Finished sessions waiting for a worker; further sessions are skipped
+    pub queue_size: usize,
+    /// URL the `transcript.enriched` event is POSTed to (no webhook when None)
+    pub webhook_url: Option<String>,
+    /// Secret the webhook payload is signed with (`X-WaaV-Signature`)
+    pub webhook_secret: Option<String>,
+}
+
+impl Default for TranscriptEnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            workers: DEFAULT_ENRICHMENT_WORKERS,
+            queue_size: DEFAULT_ENRICHMENT_QUEUE_SIZE,
+            webhook_url: None,
+            webhook_secret: None,
+        }
+    }
+}
+
+impl TranscriptEnrichmentConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the pool sizes are non-zero and the webhook is complete
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.workers == 0 {
+            return Err("workers must be greater than 0".to_string());
+        }
+        if self.queue_size == 0 {
+            return Err("queue_size must be greater than 0".to_string());
+        }
+
+        let Some(url) = &self.webhook_url else {
+            if self.webhook_secret.is_some() {
+                return Err("webhook_secret is set without webhook_url".to_string());
+            }
+            return Ok(());
+        };
+        let parsed =
+            url::Url::parse(url).map_err(|e| format!("invalid webhook_url '{url}': {e}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!(
+                "webhook_url must use http or https, got '{}'",
+                parsed.scheme()
+            ));
+        }
+        match self.webhook_secret.as_deref().map(str::trim) {
+            None | Some("") => Err("webhook_secret is required with webhook_url".to_string()),
+            Some(secret) if secret.len() < MIN_WEBHOOK_SECRET_LENGTH => Err(format!(
+                "webhook_secret must be at least {MIN_WEBHOOK_SECRET_LENGTH} characters long (got {})",
+                secret.len()
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_enrichment_validation() {
+        assert!(TranscriptEnrichmentConfig::default().validate().is_ok());
+
+        let no_workers = TranscriptEnrichmentConfig {
+            workers: 0,
+            ..Default::default()
+        };
+        assert!(no_workers.validate().unwrap_err().contains("workers"));
+
+        let webhook = TranscriptEnrichmentConfig {
+            webhook_url: Some("https://crm.example.com/transcripts".to_string()),
+            webhook_secret: Some("a-sufficiently-long-secret".to_string()),
+            ..Default::default()
+        };
+        assert!(webhook.validate().is_ok());
+
+        let no_secret = TranscriptEnrichmentConfig {
+            webhook_secret: None,
+            ..webhook.clone()
+        };
+        assert!(no_secret.validate().unwrap_err().contains("webhook_secret"));
+
+        let short_secret = TranscriptEnrichmentConfig {
+            webhook_secret: Some("short".to_string()),
+            ..webhook.clone()
+        };
+        assert!(short_secret.validate().unwrap_err().contains("at least 16"));
+
+        let bad_scheme = TranscriptEnrichmentConfig {
+            webhook_url: Some("ftp://crm.example.com".to_string()),
+            ..webhook
+        };
+        assert!(bad_scheme.validate().is_err());
+
+        let config: TranscriptEnrichmentConfig = serde_yaml::from_str("workers: 4").unwrap();
+        assert_eq!(config.workers, 4);
+        assert_eq!(config.queue_size, DEFAULT_ENRICHMENT_QUEUE_SIZE);
+        assert!(serde_yaml::from_str::<TranscriptEnrichmentConfig>("worker: 4").is_err());
+    }
+}
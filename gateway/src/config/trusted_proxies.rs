@@ -0,0 +1,145 @@
+//! Trusted proxy networks
+//!
+//! `X-Forwarded-For` and `X-Real-IP` are only honored on connections from
+//! the networks listed in `security.trusted_proxies` (`TRUSTED_PROXIES`);
+//! see [`crate::middleware::client_ip`].
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An IP network in CIDR notation (`10.0.0.0/8`, `fd00::/8`)
+///
+/// A bare address is a network of that one address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// The network of `addr` with a `prefix_len` bit prefix
+    ///
+    /// Returns `None` when the prefix is longer than the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        (prefix_len <= max_len).then_some(Self { addr, prefix_len })
+    }
+
+    /// Whether `ip` is in the network
+    ///
+    /// IPv4-mapped IPv6 addresses (`::ffff:10.0.0.1`) match IPv4 networks.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (
+                addr,
+                Some(
+                    prefix_len
+                        .parse::<u8>()
+                        .map_err(|_| format!("invalid prefix length in '{s}'"))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("invalid IP address in '{s}'"))?;
+        let prefix_len = prefix_len.unwrap_or(if addr.is_ipv4() { 32 } else { 128 });
+        Self::new(addr, prefix_len).ok_or_else(|| format!("prefix length too long in '{s}'"))
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// Parse the trusted proxy networks
+///
+/// # Errors
+/// Returns an error naming the first entry that is not an IP address or CIDR network
+pub fn parse_trusted_proxies<S: AsRef<str>>(
+    entries: &[S],
+) -> Result<Vec<IpNetwork>, Box<dyn std::error::Error>> {
+    entries
+        .iter()
+        .map(|entry| {
+            entry
+                .as_ref()
+                .parse()
+                .map_err(|e| format!("invalid trusted_proxies entry: {e}").into())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ipv4_network_contains() {
+        let net: IpNetwork = "10.1.0.0/16".parse().unwrap();
+        assert!(net.contains(&ip("10.1.200.3")));
+        assert!(!net.contains(&ip("10.2.0.1")));
+        assert!(net.contains(&ip("::ffff:10.1.0.9")));
+        assert!(!net.contains(&ip("fd00::1")));
+
+        let any: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(&ip("203.0.113.7")));
+    }
+
+    #[test]
+    fn test_bare_address_and_ipv6() {
+        let single: IpNetwork = "192.168.1.10".parse().unwrap();
+        assert_eq!(single.to_string(), "192.168.1.10/32");
+        assert!(single.contains(&ip("192.168.1.10")));
+        assert!(!single.contains(&ip("192.168.1.11")));
+
+        let net: IpNetwork = "fd00::/8".parse().unwrap();
+        assert!(net.contains(&ip("fd12:3456::1")));
+        assert!(!net.contains(&ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn test_invalid_networks_are_rejected() {
+        for entry in [
+            "10.0.0.0/33",
+            "::/129",
+            "10.0.0/8",
+            "proxy.internal",
+            "10.0.0.0/x",
+        ] {
+            assert!(entry.parse::<IpNetwork>().is_err(), "{entry}");
+        }
+        let err = parse_trusted_proxies(&["10.0.0.0/8", "nope"]).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+}
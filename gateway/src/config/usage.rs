@@ -0,0 +1,275 @@
+//! Usage record configuration
+//!
+//! Controls where the usage record written at the end of every session goes:
+//! a JSON-lines file with size-based rotation, a signed webhook, or both, and
+//! where TTS usage that fails billing reconciliation is reported.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Default size at which the usage file is rotated (100 MB)
+pub const DEFAULT_USAGE_FILE_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Default number of rotated usage files kept next to the active one
+pub const DEFAULT_USAGE_FILE_MAX_FILES: usize = 5;
+
+/// Default deviation from the expected TTS audio length before an utterance
+/// is flagged by reconciliation, in percent
+pub const DEFAULT_RECONCILIATION_TOLERANCE_PERCENT: u32 = 50;
+
+/// Largest accepted reconciliation tolerance, in percent
+const MAX_RECONCILIATION_TOLERANCE_PERCENT: u32 = 1000;
+
+/// Minimum length of the usage webhook signing secret
+const MIN_WEBHOOK_SECRET_LENGTH: usize = 16;
+
+/// Where usage records are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageSinkKind {
+    /// Append to a JSON-lines file
+    File,
+    /// POST to a webhook
+    Webhook,
+    /// Write to the file and POST to the webhook
+    Both,
+}
+
+impl UsageSinkKind {
+    /// Wire name of the sink
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Webhook => "webhook",
+            Self::Both => "both",
+        }
+    }
+
+    /// Whether records are appended to the usage file
+    pub fn writes_file(&self) -> bool {
+        matches!(self, Self::File | Self::Both)
+    }
+
+    /// Whether records are posted to the usage webhook
+    pub fn posts_webhook(&self) -> bool {
+        matches!(self, Self::Webhook | Self::Both)
+    }
+}
+
+impl fmt::Display for UsageSinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for UsageSinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "file" => Ok(Self::File),
+            "webhook" => Ok(Self::Webhook),
+            "both" => Ok(Self::Both),
+            other => Err(format!(
+                "Invalid usage sink '{other}', expected file, webhook or both"
+            )),
+        }
+    }
+}
+
+/// Per-session usage records written at session end
+///
+/// # Example YAML
+/// ```yaml
+/// usage:
+///   sink: both
+///   file_path: "/var/log/waav/usage.jsonl"
+///   file_max_bytes: 104857600
+///   file_max_files: 5
+///   webhook_url: "https://billing.example.com/waav/usage"
+///   webhook_secret: "usage-signing-secret"
+///   reconciliation_path: "/var/log/waav/reconciliation.jsonl"
+///   reconciliation_tolerance_percent: 50
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageConfig {
+    /// Where records are written
+    pub sink: UsageSinkKind,
+    /// JSON-lines file records are appended to (`file` and `both` sinks)
+    pub file_path: Option<PathBuf>,
+    /// Size in bytes at which the file is rotated
+    pub file_max_bytes: u64,
+    /// Number of rotated files kept (`usage.jsonl.1` is the newest)
+    pub file_max_files: usize,
+    /// URL records are POSTed to (`webhook` and `both` sinks)
+    pub webhook_url: Option<String>,
+    /// Secret the webhook payload is signed with (`X-WaaV-Signature`)
+    pub webhook_secret: Option<String>,
+    /// JSON-lines report TTS utterances whose audio length is off are
+    /// appended to; reconciliation is off when unset
+    pub reconciliation_path: Option<PathBuf>,
+    /// Deviation from the expected audio length that flags an utterance, in percent
+    pub reconciliation_tolerance_percent: u32,
+}
+
+impl UsageConfig {
+    /// Create a configuration for `sink` with default rotation settings
+    pub fn new(sink: UsageSinkKind) -> Self {
+        Self {
+            sink,
+            file_path: None,
+            file_max_bytes: DEFAULT_USAGE_FILE_MAX_BYTES,
+            file_max_files: DEFAULT_USAGE_FILE_MAX_FILES,
+            webhook_url: None,
+            webhook_secret: None,
+            reconciliation_path: None,
+            reconciliation_tolerance_percent: DEFAULT_RECONCILIATION_TOLERANCE_PERCENT,
+        }
+    }
+
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if every setting the sink needs is present and valid
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sink.writes_file() {
+            if self.file_path.is_none() {
+                return Err(format!(
+                    "usage file_path is required for the '{}' sink",
+                    self.sink
+                ));
+            }
+            if self.file_max_bytes == 0 {
+                return Err("usage file_max_bytes must be greater than 0".to_string());
+            }
+        }
+
+        if self.sink.posts_webhook() {
+            let Some(url) = &self.webhook_url else {
+                return Err(format!(
+                    "usage webhook_url is required for the '{}' sink",
+                    self.sink
+                ));
+            };
+            let parsed = url::Url::parse(url)
+                .map_err(|e| format!("invalid usage webhook_url '{url}': {e}"))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(format!(
+                    "usage webhook_url must use http or https, got '{}'",
+                    parsed.scheme()
+                ));
+            }
+
+            match self.webhook_secret.as_deref().map(str::trim) {
+                None | Some("") => {
+                    return Err(format!(
+                        "usage webhook_secret is required for the '{}' sink",
+                        self.sink
+                    ));
+                }
+                Some(secret) if secret.len() < MIN_WEBHOOK_SECRET_LENGTH => {
+                    return Err(format!(
+                        "usage webhook_secret must be at least {MIN_WEBHOOK_SECRET_LENGTH} characters long (got {})",
+                        secret.len()
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if self.reconciliation_path.is_some()
+            && !(1..=MAX_RECONCILIATION_TOLERANCE_PERCENT)
+                .contains(&self.reconciliation_tolerance_percent)
+        {
+            return Err(format!(
+                "usage reconciliation_tolerance_percent must be between 1 and {MAX_RECONCILIATION_TOLERANCE_PERCENT} (got {})",
+                self.reconciliation_tolerance_percent
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook_config() -> UsageConfig {
+        UsageConfig {
+            webhook_url: Some("https://billing.example.com/usage".to_string()),
+            webhook_secret: Some("a-sufficiently-long-secret".to_string()),
+            ..UsageConfig::new(UsageSinkKind::Webhook)
+        }
+    }
+
+    #[test]
+    fn test_usage_sink_parsing() {
+        assert_eq!("file".parse::<UsageSinkKind>(), Ok(UsageSinkKind::File));
+        assert_eq!(" Both ".parse::<UsageSinkKind>(), Ok(UsageSinkKind::Both));
+        assert!("kafka".parse::<UsageSinkKind>().is_err());
+
+        assert!(UsageSinkKind::Both.writes_file() && UsageSinkKind::Both.posts_webhook());
+        assert!(!UsageSinkKind::File.posts_webhook());
+        assert!(!UsageSinkKind::Webhook.writes_file());
+    }
+
+    #[test]
+    fn test_usage_config_validation() {
+        assert!(webhook_config().validate().is_ok());
+
+        let file = UsageConfig::new(UsageSinkKind::File);
+        assert!(file.validate().unwrap_err().contains("file_path"));
+        let file = UsageConfig {
+            file_path: Some(PathBuf::from("/tmp/usage.jsonl")),
+            file_max_bytes: 0,
+            ..file
+        };
+        assert!(file.validate().unwrap_err().contains("file_max_bytes"));
+
+        let no_secret = UsageConfig {
+            webhook_secret: None,
+            ..webhook_config()
+        };
+        assert!(no_secret.validate().unwrap_err().contains("webhook_secret"));
+
+        let short_secret = UsageConfig {
+            webhook_secret: Some("short".to_string()),
+            ..webhook_config()
+        };
+        assert!(short_secret.validate().unwrap_err().contains("at least 16"));
+
+        let bad_scheme = UsageConfig {
+            webhook_url: Some("ftp://billing.example.com".to_string()),
+            ..webhook_config()
+        };
+        assert!(bad_scheme.validate().is_err());
+
+        // Both sinks need the file and the webhook settings
+        let both = UsageConfig {
+            sink: UsageSinkKind::Both,
+            ..webhook_config()
+        };
+        assert!(both.validate().unwrap_err().contains("file_path"));
+
+        let reconciled = UsageConfig {
+            reconciliation_path: Some(PathBuf::from("/tmp/reconciliation.jsonl")),
+            ..webhook_config()
+        };
+        assert!(reconciled.validate().is_ok());
+        let no_tolerance = UsageConfig {
+            reconciliation_tolerance_percent: 0,
+            ..reconciled
+        };
+        assert!(
+            no_tolerance
+                .validate()
+                .unwrap_err()
+                .contains("reconciliation_tolerance_percent")
+        );
+    }
+}
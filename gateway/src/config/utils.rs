@@ -9,6 +9,14 @@ pub fn parse_bool(s: &str) -> Option<bool> {
     }
 }
 
+/// Parse a comma-separated list, trimming whitespace and dropping empty entries
+pub fn parse_comma_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,4 +48,11 @@ mod tests {
         assert_eq!(parse_bool(""), None);
         assert_eq!(parse_bool("maybe"), None);
     }
+
+    #[test]
+    fn test_parse_comma_list() {
+        assert_eq!(parse_comma_list("a, b ,c"), vec!["a", "b", "c"]);
+        assert_eq!(parse_comma_list(" , a,,"), vec!["a"]);
+        assert!(parse_comma_list("").is_empty());
+    }
 }
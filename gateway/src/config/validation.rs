@@ -1,8 +1,30 @@
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use tracing::warn;
 
 use super::AuthApiSecret;
+use super::PluginConfig;
 use super::TlsConfig;
-use super::sip::SipConfig;
+use super::audio_sinks::AudioSinksConfig;
+use super::console::ConsoleConfig;
+use super::feature_flags::{FeatureFlagConfig, KNOWN_FEATURE_FLAGS, unknown_feature_flags};
+use super::flight_recorder::FlightRecorderConfig;
+use super::greeting::GreetingConfig;
+use super::ingest::IngestConfig;
+use super::load_shedding::LoadSheddingConfig;
+use super::recording_redaction::RecordingRedactionConfig;
+use super::recording_upload::RecordingUploadConfig;
+use super::selftest::SelfTestConfig;
+use super::session_export::SessionExportConfig;
+use super::sip::{SipConfig, SipLanguageRouting};
+use super::transcript_enrichment::TranscriptEnrichmentConfig;
+use super::usage::UsageConfig;
+use super::voice_profile::{MAX_VOICE_PROFILE_NAME_LENGTH, VoiceProfile};
+use super::webhook_queue::WebhookQueueConfig;
+use super::ws_heartbeat::WsHeartbeatConfig;
+use crate::agents::AgentProfile;
+use crate::core::providers::connection_budget::ConnectionBudgetConfig;
+use crate::core::session::TranscriptBufferConfig;
 
 /// Validate JWT authentication configuration
 ///
@@ -173,6 +195,7 @@ pub fn validate_auth_api_secrets(
 /// - hooks list has no duplicate hosts
 /// - when hooks exist, each hook has an effective secret (either per-hook or global)
 /// - all secrets meet minimum length requirements and are not whitespace-only
+/// - language routing only refers to defined language packs
 pub fn validate_sip_config(sip: &Option<SipConfig>) -> Result<(), Box<dyn std::error::Error>> {
     let Some(config) = sip else {
         return Ok(());
@@ -248,6 +271,92 @@ pub fn validate_sip_config(sip: &Option<SipConfig>) -> Result<(), Box<dyn std::e
         }
     }
 
+    validate_sip_language_routing(&config.language_routing)?;
+
+    config
+        .outbound
+        .validate()
+        .map_err(|e| format!("Invalid SIP outbound config: {e}"))?;
+
+    Ok(())
+}
+
+/// Validate the language pack selection for inbound SIP calls
+///
+/// Ensures that every pack has an STT language, that every rule names a
+/// defined pack, and that number prefixes have digits and do not repeat once
+/// formatting is removed (`+44` and `44` are the same prefix).
+fn validate_sip_language_routing(
+    routing: &SipLanguageRouting,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (name, pack) in &routing.packs {
+        if pack.stt_language.trim().is_empty() {
+            return Err(format!("SIP language pack '{}' has no stt_language", name).into());
+        }
+    }
+
+    for (rule, name) in routing.pack_references() {
+        if !routing.packs.contains_key(name) {
+            return Err(format!(
+                "SIP language_routing.{} refers to undefined language pack '{}'",
+                rule, name
+            )
+            .into());
+        }
+    }
+
+    for (field, prefixes) in [
+        ("called_prefixes", &routing.called_prefixes),
+        ("caller_countries", &routing.caller_countries),
+    ] {
+        let mut seen = std::collections::HashSet::new();
+        for prefix in prefixes.keys() {
+            let digits: String = prefix.chars().filter(char::is_ascii_digit).collect();
+            if digits.is_empty() {
+                return Err(format!(
+                    "SIP language_routing.{} prefix '{}' has no digits",
+                    field, prefix
+                )
+                .into());
+            }
+            if field == "caller_countries" && digits.len() > 3 {
+                return Err(format!(
+                    "SIP language_routing.caller_countries code '{}' is longer than 3 digits",
+                    prefix
+                )
+                .into());
+            }
+            if !seen.insert(digits) {
+                return Err(format!(
+                    "Duplicate SIP language_routing.{} prefix: {}",
+                    field, prefix
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate the server-level greeting configuration
+///
+/// Ensures the greeting sets at most one of text or asset, stays within the
+/// size and delay limits, and that asset greetings have an assets directory.
+pub fn validate_greeting_config(
+    greeting: &Option<GreetingConfig>,
+    assets_dir: &Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(greeting) = greeting else {
+        return Ok(());
+    };
+
+    greeting.validate()?;
+
+    if greeting.asset.is_some() && assets_dir.is_none() {
+        return Err("greeting assets_dir is required when greeting asset is set".into());
+    }
+
     Ok(())
 }
 
@@ -315,11 +424,679 @@ pub fn validate_security_config(
     Ok(())
 }
 
+/// Validate the provider connect timeout
+///
+/// A zero timeout would fail every provider connection immediately.
+///
+/// # Errors
+/// Returns an error if the timeout is zero
+pub fn validate_provider_connect_timeout(
+    provider_connect_timeout_secs: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if provider_connect_timeout_secs == 0 {
+        return Err("provider_connect_timeout_secs must be positive".into());
+    }
+    Ok(())
+}
+
+/// Validate the cap on pending TTS utterances per session
+///
+/// A zero cap would reject every speak request.
+///
+/// # Errors
+/// Returns an error if the cap is zero
+pub fn validate_tts_max_pending_utterances(
+    tts_max_pending_utterances: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if tts_max_pending_utterances == 0 {
+        return Err("tts_max_pending_utterances must be positive".into());
+    }
+    Ok(())
+}
+
+/// Validate the cap on the length of `system` priority speech
+///
+/// A zero cap would reject every system announcement.
+///
+/// # Errors
+/// Returns an error if the cap is zero
+pub fn validate_tts_system_speak_max_chars(
+    tts_system_speak_max_chars: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if tts_system_speak_max_chars == 0 {
+        return Err("tts_system_speak_max_chars must be positive".into());
+    }
+    Ok(())
+}
+
+/// Validate the length limit of provider error messages sent to clients
+///
+/// A zero limit would send every provider error without a message.
+///
+/// # Errors
+/// Returns an error if the limit is zero
+pub fn validate_provider_error_max_chars(
+    provider_error_max_chars: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if provider_error_max_chars == 0 {
+        return Err("provider_error_max_chars must be positive".into());
+    }
+    Ok(())
+}
+
+/// Validate the cap on concurrent replay jobs
+///
+/// # Errors
+/// Returns an error if the cap is zero, which would never run a job
+pub fn validate_replay_max_concurrent_jobs(
+    replay_max_concurrent_jobs: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if replay_max_concurrent_jobs == 0 {
+        return Err("replay_max_concurrent_jobs must be positive".into());
+    }
+    Ok(())
+}
+
+/// Validate how many requests each plugin's HTTP routes handle at once
+///
+/// # Errors
+/// Returns an error if the limit is zero, which would refuse every request
+pub fn validate_plugin_http_max_concurrency(
+    http_max_concurrency: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if http_max_concurrency == 0 {
+        return Err("plugins.http_max_concurrency must be positive".into());
+    }
+    Ok(())
+}
+
+/// Validate agent profiles from the application config
+///
+/// # Errors
+/// Returns an error if a profile is invalid or two profiles share a name
+pub fn validate_agent_profiles(agents: &[AgentProfile]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut names = std::collections::HashSet::new();
+    for agent in agents {
+        agent.validate()?;
+        if !names.insert(agent.name.as_str()) {
+            return Err(format!("Duplicate agent name: {}", agent.name).into());
+        }
+    }
+    Ok(())
+}
+
+/// Validate the voice profiles and the agent profiles that use them
+///
+/// An agent whose `tts_config` selects a voice profile must name a profile
+/// that has a voice for the agent's TTS provider, so the session cannot be
+/// routed to a provider the profile does not cover.
+///
+/// # Errors
+/// Returns an error for an invalid profile name or voice, or an agent
+/// referencing an unknown profile or one without its provider
+pub fn validate_voice_profiles(
+    profiles: &BTreeMap<String, VoiceProfile>,
+    agents: &[AgentProfile],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (name, profile) in profiles {
+        if name.is_empty()
+            || name.len() > MAX_VOICE_PROFILE_NAME_LENGTH
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(format!(
+                "Invalid voice profile name '{name}': use 1-{MAX_VOICE_PROFILE_NAME_LENGTH} letters, digits, '-', '_' or '.'"
+            )
+            .into());
+        }
+        profile
+            .validate()
+            .map_err(|e| format!("voice profile '{name}' {e}"))?;
+    }
+
+    for agent in agents {
+        let Some(tts) = agent
+            .session
+            .as_ref()
+            .and_then(|session| session.get("tts_config"))
+        else {
+            continue;
+        };
+        let Some(profile) = tts.get("voice_profile").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let provider = tts
+            .get("provider")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        super::voice_profile::resolve_voice_profile(profiles, profile, provider)
+            .map_err(|e| format!("agent '{}': {e}", agent.name))?;
+    }
+    Ok(())
+}
+
+/// Validate the dynamic plugin storage configuration
+///
+/// # Errors
+/// Returns an error if the quota or check interval is zero, or if
+/// `data_dir` lies inside a plugin directory, where the loader would pick up
+/// libraries written by plugins
+pub fn validate_plugin_storage(plugins: &PluginConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(data_dir) = &plugins.data_dir else {
+        return Ok(());
+    };
+    if plugins.storage_quota_bytes == 0 {
+        return Err("plugins.storage_quota_bytes must be positive".into());
+    }
+    if plugins.storage_check_interval_secs == 0 {
+        return Err("plugins.storage_check_interval_secs must be positive".into());
+    }
+    if let Some(plugin_dir) = plugins
+        .plugin_directories()
+        .into_iter()
+        .find(|plugin_dir| data_dir.starts_with(plugin_dir))
+    {
+        return Err(format!(
+            "plugins.data_dir '{}' must not be inside plugin directory '{}'",
+            data_dir.display(),
+            plugin_dir.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Validate the usage record configuration
+///
+/// # Errors
+/// Returns an error if the sink is missing the file path, webhook URL or
+/// signing secret it needs
+pub fn validate_usage_config(
+    usage: &Option<UsageConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(usage) = usage {
+        usage.validate()?;
+    }
+    Ok(())
+}
+
+/// Validate the per-provider fallback TTS voices
+///
+/// # Errors
+/// Returns an error if a provider name or voice id is empty
+pub fn validate_tts_fallback_voices(
+    tts_fallback_voices: &HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (provider, voice_id) in tts_fallback_voices {
+        if provider.trim().is_empty() {
+            return Err("tts_fallback_voices contains an empty provider name".into());
+        }
+        if voice_id.trim().is_empty() {
+            return Err(
+                format!("tts_fallback_voices: empty voice id for provider '{provider}'").into(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Validate the provider self-test configuration
+///
+/// # Errors
+/// Returns an error if a provider is unnamed or a setting is out of range
+pub fn validate_selftest_config(
+    selftest: &Option<SelfTestConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(selftest) = selftest {
+        selftest.validate()?;
+    }
+    Ok(())
+}
+
+/// Validate the load shedding thresholds
+///
+/// # Errors
+/// Returns an error if a threshold or the retry delay is out of range
+pub fn validate_load_shedding_config(
+    load_shedding: &Option<LoadSheddingConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(load_shedding) = load_shedding {
+        load_shedding.validate()?;
+    }
+    Ok(())
+}
+
+/// Validate the session feature flags
+///
+/// Flags that no session component checks are logged as warnings rather
+/// than rejected, so a flag can be configured before the release that reads it.
+///
+/// # Errors
+/// Returns an error if a flag has an empty name or an invalid rollout
+pub fn validate_feature_flags(
+    feature_flags: &BTreeMap<String, FeatureFlagConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (name, flag) in feature_flags {
+        if name.trim().is_empty() {
+            return Err("feature_flags contains an empty flag name".into());
+        }
+        flag.validate()
+            .map_err(|e| format!("feature_flags.{name}: {e}"))?;
+    }
+    for name in unknown_feature_flags(feature_flags) {
+        warn!(
+            "Unknown feature flag '{}' is not checked by any session component (known flags: {})",
+            name,
+            KNOWN_FEATURE_FLAGS.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Validate the Deepgram connection budget
+///
+/// # Errors
+/// Returns an error if the limit, or the queue timeout of the `queue` policy, is zero
+pub fn validate_deepgram_connection_budget(
+    budget: &ConnectionBudgetConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    budget
+        .validate()
+        .map_err(|e| format!("deepgram_connection_budget: {e}"))?;
+    Ok(())
+}
+
+/// Validate the session transcript limits
+///
+/// # Errors
+/// Returns an error if either limit is zero
+pub fn validate_transcript_buffer(
+    transcript_buffer: &TranscriptBufferConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    transcript_buffer
+        .validate()
+        .map_err(|e| format!("transcript_buffer: {e}"))?;
+    Ok(())
+}
+
+/// Validate the post-call transcript enrichment settings
+///
+/// # Errors
+/// Returns an error if a pool size is zero or the webhook is incomplete
+pub fn validate_transcript_enrichment(
+    transcript_enrichment: &Option<TranscriptEnrichmentConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(config) = transcript_enrichment {
+        config
+            .validate()
+            .map_err(|e| format!("transcript_enrichment: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Validate the session export configuration
+///
+/// Download URLs must be signed with a dedicated key, so a `signing_key`
+/// equal to one of the API secrets is rejected.
+pub fn validate_session_export(
+    session_export: &Option<SessionExportConfig>,
+    auth_api_secrets: &[AuthApiSecret],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(config) = session_export else {
+        return Ok(());
+    };
+    config
+        .validate()
+        .map_err(|e| format!("session_export: {e}"))?;
+    if let Some(key) = &config.signing_key
+        && auth_api_secrets.iter().any(|s| &s.secret == key)
+    {
+        return Err(
+            "session_export: signing_key must be a dedicated key, not one of the auth API secrets"
+                .into(),
+        );
+    }
+    Ok(())
+}
+
+/// Validate the recording and monitor audio policies
+///
+/// # Errors
+/// Returns an error if a setting is out of range or not supported by its sink
+pub fn validate_audio_sinks(
+    audio_sinks: &AudioSinksConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    audio_sinks
+        .validate()
+        .map_err(|e| format!("audio_sinks: {e}"))?;
+    Ok(())
+}
+
+/// Validate the caller audio recording upload configuration
+///
+/// Recordings are uploaded to the recording bucket, so one must be configured.
+///
+/// # Errors
+/// Returns an error if a setting is out of range or there is no recording bucket
+pub fn validate_recording_upload(
+    recording_upload: &Option<RecordingUploadConfig>,
+    recording_s3_bucket: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(config) = recording_upload else {
+        return Ok(());
+    };
+    config
+        .validate()
+        .map_err(|e| format!("recording_upload: {e}"))?;
+    if recording_s3_bucket.is_none() {
+        return Err("recording_upload: requires the recording S3 bucket settings".into());
+    }
+    Ok(())
+}
+
+/// Validate the recording redaction settings
+///
+/// # Errors
+/// Returns an error if the DTMF idle time is zero
+pub fn validate_recording_redaction(
+    recording_redaction: &RecordingRedactionConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    recording_redaction
+        .validate()
+        .map_err(|e| format!("recording_redaction: {e}"))?;
+    Ok(())
+}
+
+/// Validate the WebSocket heartbeat settings
+///
+/// # Errors
+/// Returns an error if the interval, timeout or miss limit is out of range
+pub fn validate_ws_heartbeat(
+    ws_heartbeat: &WsHeartbeatConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    ws_heartbeat
+        .validate()
+        .map_err(|e| format!("ws_heartbeat: {e}"))?;
+    Ok(())
+}
+
+/// Validate the development console configuration
+///
+/// Refuses to serve the console without auth unless the server listens on
+/// loopback.
+pub fn validate_console(
+    console: &ConsoleConfig,
+    auth_required: bool,
+    host: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    console
+        .validate(auth_required, host)
+        .map_err(|e| format!("console: {e}"))?;
+    Ok(())
+}
+
+/// Validate the outbound webhook queue settings
+///
+/// # Errors
+/// Returns an error if a bound, the attempt limit or a retry delay is out of range
+pub fn validate_webhook_queue(
+    webhook_queue: &Option<WebhookQueueConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(config) = webhook_queue else {
+        return Ok(());
+    };
+    config
+        .validate()
+        .map_err(|e| format!("webhook_queue: {e}"))?;
+    Ok(())
+}
+
+/// Validate the broadcast ingest settings
+///
+/// # Errors
+/// Returns an error if the listen address, a bound or a stream key is invalid
+pub fn validate_ingest(ingest: &Option<IngestConfig>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(config) = ingest else {
+        return Ok(());
+    };
+    config.validate().map_err(|e| format!("ingest: {e}"))?;
+    Ok(())
+}
+
+/// Validate the session flight recorder settings
+///
+/// # Errors
+/// Returns an error if the retention, size cap or flush interval is out of range
+pub fn validate_flight_recorder(
+    flight_recorder: &Option<FlightRecorderConfig>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(config) = flight_recorder else {
+        return Ok(());
+    };
+    config
+        .validate()
+        .map_err(|e| format!("flight_recorder: {e}"))?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::sip::SipHookConfig;
 
+    #[test]
+    fn test_validate_greeting_config() {
+        assert!(validate_greeting_config(&None, &None).is_ok());
+
+        let text = GreetingConfig {
+            text: Some("Hello!".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_greeting_config(&Some(text), &None).is_ok());
+
+        let asset = GreetingConfig {
+            asset: Some("welcome".to_string()),
+            ..Default::default()
+        };
+        let err = validate_greeting_config(&Some(asset.clone()), &None).unwrap_err();
+        assert!(err.to_string().contains("assets_dir"));
+        assert!(
+            validate_greeting_config(&Some(asset), &Some(PathBuf::from("/opt/greetings"))).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validate_tts_fallback_voices() {
+        assert!(validate_tts_fallback_voices(&HashMap::new()).is_ok());
+
+        let mut voices = HashMap::from([("elevenlabs".to_string(), "backup-voice".to_string())]);
+        assert!(validate_tts_fallback_voices(&voices).is_ok());
+
+        voices.insert("cartesia".to_string(), " ".to_string());
+        let err = validate_tts_fallback_voices(&voices).unwrap_err();
+        assert!(err.to_string().contains("cartesia"));
+    }
+
+    #[test]
+    fn test_validate_transcript_buffer() {
+        assert!(validate_transcript_buffer(&TranscriptBufferConfig::default()).is_ok());
+        let zero_bytes = TranscriptBufferConfig {
+            max_bytes: 0,
+            ..Default::default()
+        };
+        let err = validate_transcript_buffer(&zero_bytes).unwrap_err();
+        assert!(err.to_string().contains("transcript_buffer: max_bytes"));
+    }
+
+    #[test]
+    fn test_validate_ws_heartbeat() {
+        assert!(validate_ws_heartbeat(&WsHeartbeatConfig::default()).is_ok());
+        let late_timeout = WsHeartbeatConfig {
+            interval_ms: 1_000,
+            timeout_ms: 2_000,
+            ..Default::default()
+        };
+        let err = validate_ws_heartbeat(&late_timeout).unwrap_err();
+        assert!(err.to_string().contains("ws_heartbeat: timeout_ms"));
+    }
+
+    #[test]
+    fn test_validate_webhook_queue() {
+        assert!(validate_webhook_queue(&None).is_ok());
+        assert!(validate_webhook_queue(&Some(WebhookQueueConfig::default())).is_ok());
+        let no_attempts = WebhookQueueConfig {
+            max_attempts: 0,
+            ..Default::default()
+        };
+        let err = validate_webhook_queue(&Some(no_attempts)).unwrap_err();
+        assert!(err.to_string().contains("webhook_queue: max_attempts"));
+    }
+
+    #[test]
+    fn test_validate_console() {
+        let enabled = ConsoleConfig { enabled: true };
+        assert!(validate_console(&enabled, false, "127.0.0.1").is_ok());
+        assert!(validate_console(&enabled, true, "0.0.0.0").is_ok());
+        let err = validate_console(&enabled, false, "0.0.0.0").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("console: enabled requires auth_required")
+        );
+    }
+
+    #[test]
+    fn test_validate_feature_flags() {
+        assert!(validate_feature_flags(&BTreeMap::new()).is_ok());
+
+        // Unknown names only warn
+        let mut flags = BTreeMap::from([
+            ("echo_guard".to_string(), FeatureFlagConfig::default()),
+            ("new_chunker".to_string(), FeatureFlagConfig::default()),
+        ]);
+        assert!(validate_feature_flags(&flags).is_ok());
+
+        flags.insert(
+            "adaptive_endpointing".to_string(),
+            FeatureFlagConfig {
+                rollout_percent: Some(150.0),
+                ..Default::default()
+            },
+        );
+        let err = validate_feature_flags(&flags).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("feature_flags.adaptive_endpointing")
+        );
+
+        let unnamed = BTreeMap::from([(" ".to_string(), FeatureFlagConfig::default())]);
+        assert!(validate_feature_flags(&unnamed).is_err());
+    }
+
+    #[test]
+    fn test_validate_agent_profiles() {
+        let agent = |name: &str| AgentProfile {
+            name: name.to_string(),
+            description: None,
+            clients: vec![],
+            overridable: vec![],
+            session: serde_json::json!({"greeting": {"text": "Hi"}})
+                .as_object()
+                .cloned(),
+            realtime: None,
+        };
+
+        assert!(validate_agent_profiles(&[]).is_ok());
+        assert!(validate_agent_profiles(&[agent("a"), agent("b")]).is_ok());
+
+        let err = validate_agent_profiles(&[agent("a"), agent("a")]).unwrap_err();
+        assert!(err.to_string().contains("Duplicate agent name: a"));
+
+        let err = validate_agent_profiles(&[agent("bad name")]).unwrap_err();
+        assert!(err.to_string().contains("bad name"));
+    }
+
+    #[test]
+    fn test_validate_voice_profiles() {
+        let profiles: BTreeMap<String, VoiceProfile> = serde_yaml::from_str(
+            "warm:\n  voices:\n    elevenlabs:\n      voice_id: rachel\n    azure:\n      voice_id: en-US-JennyNeural\n",
+        )
+        .unwrap();
+        let agent = |provider: &str, profile: &str| AgentProfile {
+            name: "support".to_string(),
+            description: None,
+            clients: vec![],
+            overridable: vec![],
+            session: serde_json::json!({
+                "tts_config": {"provider": provider, "model": "", "voice_profile": profile}
+            })
+            .as_object()
+            .cloned(),
+            realtime: None,
+        };
+
+        assert!(validate_voice_profiles(&BTreeMap::new(), &[]).is_ok());
+        assert!(validate_voice_profiles(&profiles, &[agent("elevenlabs", "warm")]).is_ok());
+        assert!(validate_voice_profiles(&profiles, &[agent("microsoft-azure", "warm")]).is_ok());
+
+        let err = validate_voice_profiles(&profiles, &[agent("deepgram", "warm")]).unwrap_err();
+        assert!(err.to_string().contains("agent 'support'"));
+        assert!(err.to_string().contains("no voice for provider 'deepgram'"));
+
+        let err = validate_voice_profiles(&profiles, &[agent("elevenlabs", "cold")]).unwrap_err();
+        assert!(err.to_string().contains("unknown voice profile 'cold'"));
+
+        let mut bad = profiles.clone();
+        bad.insert("bad name".to_string(), profiles["warm"].clone());
+        assert!(validate_voice_profiles(&bad, &[]).is_err());
+
+        let mut empty = profiles;
+        empty.insert("empty".to_string(), VoiceProfile::default());
+        let err = validate_voice_profiles(&empty, &[]).unwrap_err();
+        assert!(err.to_string().contains("voice profile 'empty'"));
+    }
+
+    #[test]
+    fn test_validate_tts_max_pending_utterances() {
+        assert!(validate_tts_max_pending_utterances(5).is_ok());
+        assert!(validate_tts_max_pending_utterances(1).is_ok());
+        let err = validate_tts_max_pending_utterances(0).unwrap_err();
+        assert!(err.to_string().contains("tts_max_pending_utterances"));
+    }
+
+    #[test]
+    fn test_validate_tts_system_speak_max_chars() {
+        assert!(validate_tts_system_speak_max_chars(500).is_ok());
+        let err = validate_tts_system_speak_max_chars(0).unwrap_err();
+        assert!(err.to_string().contains("tts_system_speak_max_chars"));
+    }
+
+    #[test]
+    fn test_validate_provider_error_max_chars() {
+        assert!(validate_provider_error_max_chars(300).is_ok());
+        let err = validate_provider_error_max_chars(0).unwrap_err();
+        assert!(err.to_string().contains("provider_error_max_chars"));
+    }
+
+    #[test]
+    fn test_validate_deepgram_connection_budget() {
+        assert!(validate_deepgram_connection_budget(&ConnectionBudgetConfig::default()).is_ok());
+        let err = validate_deepgram_connection_budget(&ConnectionBudgetConfig {
+            max_connections: Some(0),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("deepgram_connection_budget"));
+    }
+
+    #[test]
+    fn test_validate_provider_connect_timeout() {
+        assert!(validate_provider_connect_timeout(10).is_ok());
+        assert!(validate_provider_connect_timeout(1).is_ok());
+        let err = validate_provider_connect_timeout(0).unwrap_err();
+        assert!(err.to_string().contains("provider_connect_timeout_secs"));
+    }
+
     #[test]
     fn test_validate_sip_config_none() {
         let result = validate_sip_config(&None);
@@ -724,4 +1501,149 @@ mod tests {
                 .contains("must be at least 16 characters")
         );
     }
+
+    #[test]
+    fn test_validate_sip_language_routing() {
+        use crate::config::sip::LanguagePack;
+
+        let sip = |routing: SipLanguageRouting| {
+            Some(
+                SipConfig::new(
+                    "sip-".to_string(),
+                    vec!["192.168.1.0/24".to_string()],
+                    vec![],
+                    None,
+                    None,
+                )
+                .with_language_routing(routing),
+            )
+        };
+        let valid = SipLanguageRouting {
+            packs: [(
+                "fr-ca".to_string(),
+                LanguagePack {
+                    stt_language: "fr-CA".to_string(),
+                    tts_voice_id: None,
+                },
+            )]
+            .into(),
+            trunks: [("ST_quebec".to_string(), "fr-ca".to_string())].into(),
+            called_prefixes: [("+1514".to_string(), "fr-ca".to_string())].into(),
+            caller_countries: [("+33".to_string(), "fr-ca".to_string())].into(),
+            default_pack: Some("fr-ca".to_string()),
+        };
+        assert!(validate_sip_config(&sip(valid.clone())).is_ok());
+
+        let undefined = SipLanguageRouting {
+            called_prefixes: [("+1".to_string(), "en-us".to_string())].into(),
+            ..valid.clone()
+        };
+        let error = validate_sip_config(&sip(undefined))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("called_prefixes.+1"), "{error}");
+        assert!(error.contains("undefined language pack 'en-us'"), "{error}");
+
+        let undefined_default = SipLanguageRouting {
+            default_pack: Some("en-us".to_string()),
+            ..valid.clone()
+        };
+        assert!(validate_sip_config(&sip(undefined_default)).is_err());
+
+        let duplicate = SipLanguageRouting {
+            called_prefixes: [
+                ("+1514".to_string(), "fr-ca".to_string()),
+                ("1 514".to_string(), "fr-ca".to_string()),
+            ]
+            .into(),
+            ..valid.clone()
+        };
+        let error = validate_sip_config(&sip(duplicate))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Duplicate"), "{error}");
+
+        let long_country = SipLanguageRouting {
+            caller_countries: [("+3312".to_string(), "fr-ca".to_string())].into(),
+            ..valid.clone()
+        };
+        assert!(validate_sip_config(&sip(long_country)).is_err());
+
+        let no_language = SipLanguageRouting {
+            packs: [(
+                "fr-ca".to_string(),
+                LanguagePack {
+                    stt_language: " ".to_string(),
+                    tts_voice_id: None,
+                },
+            )]
+            .into(),
+            ..valid
+        };
+        assert!(validate_sip_config(&sip(no_language)).is_err());
+    }
+
+    #[test]
+    fn test_validate_plugin_storage() {
+        let mut plugins = PluginConfig {
+            plugin_dir: Some(PathBuf::from("/opt/waav/plugins")),
+            ..Default::default()
+        };
+        assert!(validate_plugin_storage(&plugins).is_ok());
+
+        plugins.data_dir = Some(PathBuf::from("/var/lib/waav/plugins"));
+        assert!(validate_plugin_storage(&plugins).is_ok());
+
+        plugins.storage_quota_bytes = 0;
+        assert!(validate_plugin_storage(&plugins).is_err());
+        plugins.storage_quota_bytes = 1024;
+
+        plugins.data_dir = Some(PathBuf::from("/opt/waav/plugins/data"));
+        let err = validate_plugin_storage(&plugins).unwrap_err();
+        assert!(err.to_string().contains("inside plugin directory"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_session_export_rejects_auth_secret_as_signing_key() {
+        let secret = "a-shared-secret-that-is-long-enough!".to_string();
+        let export = Some(SessionExportConfig {
+            signing_key: Some(secret.clone()),
+            ..Default::default()
+        });
+        assert!(validate_session_export(&export, &[]).is_ok());
+
+        let secrets = [AuthApiSecret {
+            id: "project1".to_string(),
+            secret,
+        }];
+        let err = validate_session_export(&export, &secrets).unwrap_err();
+        assert!(err.to_string().contains("dedicated key"), "{err}");
+        assert!(validate_session_export(&None, &secrets).is_ok());
+    }
+
+    #[test]
+    fn test_validate_audio_sinks() {
+        assert!(validate_audio_sinks(&AudioSinksConfig::default()).is_ok());
+
+        let mut audio_sinks = AudioSinksConfig::default();
+        audio_sinks.recording.mono = true;
+        let err = validate_audio_sinks(&audio_sinks).unwrap_err();
+        assert!(err.to_string().starts_with("audio_sinks: recording.mono"));
+    }
+
+    #[test]
+    fn test_validate_recording_upload() {
+        assert!(validate_recording_upload(&None, None).is_ok());
+
+        let upload = RecordingUploadConfig {
+            spill_dir: Some(PathBuf::from("/var/lib/waav/recording-spill")),
+            ..Default::default()
+        };
+        assert!(validate_recording_upload(&Some(upload.clone()), Some("recordings")).is_ok());
+        let err = validate_recording_upload(&Some(upload), None).unwrap_err();
+        assert!(err.to_string().contains("recording S3 bucket"));
+        let err = validate_recording_upload(&Some(RecordingUploadConfig::default()), Some("b"))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("recording_upload: spill_dir"));
+    }
 }
@@ -0,0 +1,250 @@
+//! Voice profiles
+//!
+//! A voice profile maps one logical voice ("warm-female-en") to the voice
+//! each TTS provider should use for it, so a session keeps a consistent
+//! voice whichever provider it ends up on. Sessions select a profile with
+//! `tts_config.voice_profile` and the voice of the session's provider is
+//! resolved when the session is configured.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::tts::TTSConfig;
+use crate::plugin::dispatch::resolve_tts_provider;
+
+/// Maximum length of a voice profile name
+pub const MAX_VOICE_PROFILE_NAME_LENGTH: usize = 64;
+
+/// The voice a provider uses for a profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ProviderVoice {
+    /// Provider voice ID
+    #[cfg_attr(feature = "openapi", schema(example = "21m00Tcm4TlvDq8ikWAM"))]
+    pub voice_id: String,
+    /// Provider model, replacing the session's model when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = "eleven_flash_v2_5"))]
+    pub model: Option<String>,
+    /// Speaking rate (0.25 to 4.0); sessions that set their own keep it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaking_rate: Option<f32>,
+}
+
+impl ProviderVoice {
+    /// Set the voice, and the model and speaking rate when given, on a TTS config
+    pub fn apply(&self, config: &mut TTSConfig) {
+        config.voice_id = Some(self.voice_id.clone());
+        if let Some(model) = &self.model {
+            config.model = model.clone();
+        }
+        if let Some(rate) = self.speaking_rate {
+            config.speaking_rate = Some(rate);
+        }
+    }
+}
+
+/// One logical voice, by provider
+///
+/// # Example YAML
+/// ```yaml
+/// voice_profiles:
+///   warm-female-en:
+///     description: "Warm, friendly US English"
+///     voices:
+///       elevenlabs:
+///         voice_id: "21m00Tcm4TlvDq8ikWAM"
+///         model: eleven_flash_v2_5
+///       deepgram:
+///         voice_id: aura-luna-en
+///         model: aura-luna-en
+///       microsoft-azure:
+///         voice_id: en-US-JennyNeural
+///         speaking_rate: 1.05
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(deny_unknown_fields)]
+pub struct VoiceProfile {
+    /// Human-readable description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Voice per TTS provider name (aliases such as `azure` are accepted)
+    pub voices: BTreeMap<String, ProviderVoice>,
+}
+
+impl VoiceProfile {
+    /// The voice for `provider`, matching provider aliases
+    pub fn voice_for(&self, provider: &str) -> Option<&ProviderVoice> {
+        let provider = canonical_provider(provider);
+        self.voices
+            .iter()
+            .find(|(name, _)| canonical_provider(name) == provider)
+            .map(|(_, voice)| voice)
+    }
+
+    /// Providers the profile has a voice for, as configured
+    pub fn providers(&self) -> Vec<String> {
+        self.voices.keys().cloned().collect()
+    }
+
+    /// Validate the profile's voices
+    ///
+    /// # Returns
+    /// * `Ok(())` if every voice is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.voices.is_empty() {
+            return Err("needs at least one provider voice".to_string());
+        }
+        let mut seen = BTreeMap::new();
+        for (provider, voice) in &self.voices {
+            if provider.trim().is_empty() {
+                return Err("contains an empty provider name".to_string());
+            }
+            if let Some(previous) = seen.insert(canonical_provider(provider), provider) {
+                return Err(format!(
+                    "providers '{previous}' and '{provider}' are the same provider"
+                ));
+            }
+            if voice.voice_id.trim().is_empty() {
+                return Err(format!("empty voice_id for provider '{provider}'"));
+            }
+            if let Some(rate) = voice.speaking_rate
+                && !(0.25..=4.0).contains(&rate)
+            {
+                return Err(format!(
+                    "speaking_rate for provider '{provider}' must be between 0.25 and 4.0, got {rate}"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolve the voice `profiles[name]` uses on `provider`
+///
+/// # Returns
+/// * `Ok(&ProviderVoice)` - The provider's voice
+/// * `Err(String)` - The profile does not exist or has no voice for the provider
+pub fn resolve_voice_profile<'a>(
+    profiles: &'a BTreeMap<String, VoiceProfile>,
+    name: &str,
+    provider: &str,
+) -> Result<&'a ProviderVoice, String> {
+    let profile = profiles.get(name).ok_or_else(|| {
+        let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+        if available.is_empty() {
+            format!("unknown voice profile '{name}': no voice profiles are configured")
+        } else {
+            format!(
+                "unknown voice profile '{name}'. Available voice profiles: {}",
+                available.join(", ")
+            )
+        }
+    })?;
+    profile.voice_for(provider).ok_or_else(|| {
+        format!(
+            "voice profile '{name}' has no voice for provider '{provider}' (has: {})",
+            profile.providers().join(", ")
+        )
+    })
+}
+
+/// Provider name with aliases resolved, for built-in providers
+fn canonical_provider(name: &str) -> String {
+    match resolve_tts_provider(name) {
+        Some(provider) => provider.canonical_name().to_string(),
+        None => name.to_ascii_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles() -> BTreeMap<String, VoiceProfile> {
+        let yaml = r#"
+warm-female-en:
+  voices:
+    elevenlabs:
+      voice_id: "21m00Tcm4TlvDq8ikWAM"
+      model: eleven_flash_v2_5
+    azure:
+      voice_id: en-US-JennyNeural
+      speaking_rate: 1.1
+"#;
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_resolves_voice_per_provider() {
+        let profiles = profiles();
+        let voice = resolve_voice_profile(&profiles, "warm-female-en", "ElevenLabs").unwrap();
+        assert_eq!(voice.voice_id, "21m00Tcm4TlvDq8ikWAM");
+
+        // Aliases of the same provider resolve to one voice
+        let voice = resolve_voice_profile(&profiles, "warm-female-en", "microsoft-azure").unwrap();
+        assert_eq!(voice.voice_id, "en-US-JennyNeural");
+
+        let err = resolve_voice_profile(&profiles, "warm-female-en", "deepgram").unwrap_err();
+        assert!(err.contains("no voice for provider 'deepgram'"), "{err}");
+        let err = resolve_voice_profile(&profiles, "cold-male-en", "deepgram").unwrap_err();
+        assert!(
+            err.contains("Available voice profiles: warm-female-en"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_apply_sets_provider_voice() {
+        let profiles = profiles();
+        let mut config = TTSConfig {
+            provider: "azure".to_string(),
+            ..Default::default()
+        };
+        profiles["warm-female-en"]
+            .voice_for("azure")
+            .unwrap()
+            .apply(&mut config);
+        assert_eq!(config.voice_id.as_deref(), Some("en-US-JennyNeural"));
+        assert_eq!(config.model, "");
+        assert_eq!(config.speaking_rate, Some(1.1));
+
+        let mut config = TTSConfig {
+            provider: "elevenlabs".to_string(),
+            speaking_rate: Some(0.9),
+            ..Default::default()
+        };
+        profiles["warm-female-en"]
+            .voice_for("elevenlabs")
+            .unwrap()
+            .apply(&mut config);
+        assert_eq!(config.model, "eleven_flash_v2_5");
+        assert_eq!(config.speaking_rate, Some(0.9));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(profiles()["warm-female-en"].validate().is_ok());
+        assert!(VoiceProfile::default().validate().is_err());
+
+        let mut profile = profiles().remove("warm-female-en").unwrap();
+        profile.voices.insert(
+            "microsoft-azure".to_string(),
+            ProviderVoice {
+                voice_id: "en-US-AriaNeural".to_string(),
+                model: None,
+                speaking_rate: None,
+            },
+        );
+        let err = profile.validate().unwrap_err();
+        assert!(err.contains("same provider"), "{err}");
+
+        let mut profile = profiles().remove("warm-female-en").unwrap();
+        profile.voices.get_mut("azure").unwrap().speaking_rate = Some(9.0);
+        assert!(profile.validate().is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Durable queue for outbound webhooks
+//!
+//! When enabled, usage records, `session.ended` and `transcript.enriched`
+//! events are not POSTed inline. They are queued per destination in the
+//! cache backend and delivered by a background dispatcher that retries with
+//! backoff, so events survive receiver outages and gateway restarts.
+
+use serde::Deserialize;
+
+/// Largest number of delivery attempts accepted per event
+pub const MAX_WEBHOOK_ATTEMPTS: u32 = 100;
+
+/// Outbound webhook queue
+///
+/// Each destination keeps at most `max_pending_per_destination` events; when
+/// a new event does not fit, the oldest is dropped. Events whose attempts run
+/// out are dropped as well. The last `max_dropped_per_destination` dropped
+/// events are kept for `POST /admin/webhooks/replay`.
+///
+/// # Example YAML
+/// ```yaml
+/// webhook_queue:
+///   max_pending_per_destination: 1000
+///   max_attempts: 12
+///   retry_base_delay_ms: 1000
+///   retry_max_delay_ms: 300000
+///   max_dropped_per_destination: 100
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookQueueConfig {
+    /// Events kept per destination before the oldest is dropped
+    pub max_pending_per_destination: usize,
+    /// Delivery attempts per event before it is dropped
+    pub max_attempts: u32,
+    /// Delay before the first retry of an event; doubles with every retry (ms)
+    pub retry_base_delay_ms: u64,
+    /// Longest delay between retries of an event (ms)
+    pub retry_max_delay_ms: u64,
+    /// Dropped events kept per destination for replay
+    pub max_dropped_per_destination: usize,
+}
+
+impl Default for WebhookQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_pending_per_destination: 1_000,
+            max_attempts: 12,
+            retry_base_delay_ms: 1_000,
+            retry_max_delay_ms: 300_000,
+            max_dropped_per_destination: 100,
+        }
+    }
+}
+
+impl WebhookQueueConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if the bounds and delays are in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_pending_per_destination == 0 {
+            return Err("max_pending_per_destination must be greater than 0".to_string());
+        }
+        if !(1..=MAX_WEBHOOK_ATTEMPTS).contains(&self.max_attempts) {
+            return Err(format!(
+                "max_attempts must be between 1 and {MAX_WEBHOOK_ATTEMPTS} (got {})",
+                self.max_attempts
+            ));
+        }
+        if self.retry_base_delay_ms == 0 || self.retry_base_delay_ms > self.retry_max_delay_ms {
+            return Err(format!(
+                "retry_base_delay_ms must be between 1 and retry_max_delay_ms ({}) (got {})",
+                self.retry_max_delay_ms, self.retry_base_delay_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_queue_validation() {
+        assert!(WebhookQueueConfig::default().validate().is_ok());
+
+        let unbounded = WebhookQueueConfig {
+            max_pending_per_destination: 0,
+            ..Default::default()
+        };
+        assert!(
+            unbounded
+                .validate()
+                .unwrap_err()
+                .contains("max_pending_per_destination")
+        );
+
+        let no_attempts = WebhookQueueConfig {
+            max_attempts: 0,
+            ..Default::default()
+        };
+        assert!(no_attempts.validate().unwrap_err().contains("max_attempts"));
+
+        let slow_start = WebhookQueueConfig {
+            retry_base_delay_ms: 10_000,
+            retry_max_delay_ms: 5_000,
+            ..Default::default()
+        };
+        assert!(
+            slow_start
+                .validate()
+                .unwrap_err()
+                .contains("retry_base_delay_ms")
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let parsed: WebhookQueueConfig = serde_json::from_str(r#"{"max_attempts": 3}"#).unwrap();
+        assert_eq!(parsed.max_attempts, 3);
+        assert_eq!(parsed.max_pending_per_destination, 1_000);
+
+        assert!(serde_json::from_str::<WebhookQueueConfig>(r#"{"enabled": true}"#).is_err());
+    }
+}
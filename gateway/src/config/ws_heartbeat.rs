@@ -0,0 +1,125 @@
+//! WebSocket heartbeat configuration
+//!
+//! The gateway pings every `/ws` client at a fixed interval and closes
+//! connections that stop answering, so half-open connections (e.g. a mobile
+//! client that lost its radio) are reaped in seconds instead of waiting for
+//! TCP timeouts.
+
+use serde::Deserialize;
+
+/// Default interval between heartbeat pings, in milliseconds
+pub const DEFAULT_HEARTBEAT_INTERVAL_MS: u64 = 15_000;
+
+/// Default time a client has to answer a ping, in milliseconds
+pub const DEFAULT_HEARTBEAT_TIMEOUT_MS: u64 = 10_000;
+
+/// Default number of consecutive unanswered pings before the connection is closed
+pub const DEFAULT_HEARTBEAT_MAX_MISSED: u32 = 3;
+
+/// Heartbeat pings sent to WebSocket clients
+///
+/// A ping that is not answered within `timeout_ms` is missed; the connection
+/// is closed with code `4009` (`heartbeat_timeout`) after `max_missed`
+/// consecutive misses.
+///
+/// # Example YAML
+/// ```yaml
+/// ws_heartbeat:
+///   interval_ms: 15000
+///   timeout_ms: 10000
+///   max_missed: 3
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WsHeartbeatConfig {
+    /// Send heartbeat pings (default: true)
+    pub enabled: bool,
+    /// Interval between pings in milliseconds
+    pub interval_ms: u64,
+    /// Time a client has to answer a ping in milliseconds (at most `interval_ms`)
+    pub timeout_ms: u64,
+    /// Consecutive unanswered pings before the connection is closed
+    pub max_missed: u32,
+}
+
+impl Default for WsHeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: DEFAULT_HEARTBEAT_INTERVAL_MS,
+            timeout_ms: DEFAULT_HEARTBEAT_TIMEOUT_MS,
+            max_missed: DEFAULT_HEARTBEAT_MAX_MISSED,
+        }
+    }
+}
+
+impl WsHeartbeatConfig {
+    /// Validate the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` if every setting is in range
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_ms == 0 {
+            return Err("interval_ms must be greater than 0".to_string());
+        }
+        if self.timeout_ms == 0 || self.timeout_ms > self.interval_ms {
+            return Err(format!(
+                "timeout_ms must be between 1 and interval_ms ({}) (got {})",
+                self.interval_ms, self.timeout_ms
+            ));
+        }
+        if self.max_missed == 0 {
+            return Err("max_missed must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_valid() {
+        let config = WsHeartbeatConfig::default();
+        assert!(config.enabled);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_heartbeat_validation() {
+        let zero_interval = WsHeartbeatConfig {
+            interval_ms: 0,
+            ..Default::default()
+        };
+        assert!(
+            zero_interval
+                .validate()
+                .unwrap_err()
+                .contains("interval_ms")
+        );
+
+        let late_timeout = WsHeartbeatConfig {
+            interval_ms: 5_000,
+            timeout_ms: 6_000,
+            ..Default::default()
+        };
+        assert!(late_timeout.validate().unwrap_err().contains("timeout_ms"));
+
+        let never_missed = WsHeartbeatConfig {
+            max_missed: 0,
+            ..Default::default()
+        };
+        assert!(never_missed.validate().unwrap_err().contains("max_missed"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let parsed: WsHeartbeatConfig = serde_json::from_str(r#"{"interval_ms": 20000}"#).unwrap();
+        assert_eq!(parsed.interval_ms, 20_000);
+        assert_eq!(parsed.timeout_ms, DEFAULT_HEARTBEAT_TIMEOUT_MS);
+
+        assert!(serde_json::from_str::<WsHeartbeatConfig>(r#"{"interval": 20000}"#).is_err());
+    }
+}
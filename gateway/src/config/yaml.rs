@@ -1,6 +1,14 @@
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
+use super::encryption::{MasterKey, decrypt_tagged_values, zeroize_strings};
+use super::feature_flags::FeatureFlagConfig;
+use super::sip::SipOutboundConfig;
+use crate::agents::AgentProfile;
+use crate::core::providers::connection_budget::ConnectionBudgetConfig;
+use crate::core::session::TranscriptBufferConfig;
+
 /// Complete YAML configuration structure
 ///
 /// This structure represents the full configuration that can be loaded from a YAML file.
@@ -56,6 +64,23 @@ use std::path::PathBuf;
 ///     - host: "other.com"
 ///       url: "https://webhook.other.com/events"
 ///       secret: "per-hook-override-secret"
+///   language_routing:
+///     packs:
+///       fr-ca:
+///         stt_language: "fr-CA"
+///         tts_voice_id: "fr-CA-voice"
+///       en-us:
+///         stt_language: "en-US"
+///     trunks:
+///       ST_quebec: "fr-ca"
+///     called_prefixes:
+///       "+1514": "fr-ca"
+///     caller_countries:
+///       "+33": "fr-ca"
+///     default_pack: "en-us"
+///   outbound:
+///     early_media_asset: "ringback"
+///     no_answer_timeout_secs: 45
 /// ```
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
@@ -69,6 +94,25 @@ pub struct YamlConfig {
     pub sip: Option<SipYaml>,
     pub security: Option<SecurityYaml>,
     pub plugins: Option<PluginsYaml>,
+    pub greeting: Option<GreetingYaml>,
+    pub usage: Option<UsageYaml>,
+    pub selftest: Option<SelfTestYaml>,
+    pub load_shedding: Option<LoadSheddingYaml>,
+    pub agents: Option<Vec<AgentProfile>>,
+    pub feature_flags: Option<BTreeMap<String, FeatureFlagConfig>>,
+    pub transcript_buffer: Option<TranscriptBufferConfig>,
+    pub transcript_enrichment: Option<super::transcript_enrichment::TranscriptEnrichmentConfig>,
+    pub session_export: Option<super::session_export::SessionExportConfig>,
+    pub audio_sinks: Option<super::audio_sinks::AudioSinksConfig>,
+    pub recording_upload: Option<super::recording_upload::RecordingUploadConfig>,
+    pub recording_redaction: Option<super::recording_redaction::RecordingRedactionConfig>,
+    pub chaos: Option<super::chaos::ChaosConfig>,
+    pub voice_profiles: Option<BTreeMap<String, super::voice_profile::VoiceProfile>>,
+    pub ws_heartbeat: Option<super::ws_heartbeat::WsHeartbeatConfig>,
+    pub console: Option<super::console::ConsoleConfig>,
+    pub webhook_queue: Option<super::webhook_queue::WebhookQueueConfig>,
+    pub ingest: Option<super::ingest::IngestConfig>,
+    pub flight_recorder: Option<super::flight_recorder::FlightRecorderConfig>,
 }
 
 /// Server configuration from YAML
@@ -78,6 +122,8 @@ pub struct ServerYaml {
     pub host: Option<String>,
     pub port: Option<u16>,
     pub tls: Option<TlsYaml>,
+    /// Reject config messages with unknown fields
+    pub strict_config: Option<bool>,
 }
 
 /// TLS configuration from YAML
@@ -99,7 +145,7 @@ pub struct LiveKitYaml {
     pub api_secret: Option<String>,
 }
 
-/// Provider API keys from YAML
+/// Provider API keys and connection settings from YAML
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 pub struct ProvidersYaml {
@@ -150,6 +196,20 @@ pub struct ProvidersYaml {
     pub gnani_access_key: Option<String>,
     /// Path to Gnani SSL certificate file (for mTLS authentication)
     pub gnani_certificate_path: Option<String>,
+    /// Maximum seconds to wait for a provider connection during session setup
+    pub connect_timeout_secs: Option<u64>,
+    /// Fallback TTS voice per provider, used when the configured voice does not exist
+    pub tts_fallback_voices: Option<std::collections::HashMap<String, String>>,
+    /// Maximum TTS utterances pending per session
+    pub tts_max_pending_utterances: Option<usize>,
+    /// What happens to a speak request when the pending TTS queue is full
+    pub tts_queue_policy: Option<crate::core::voice_manager::TTSQueuePolicy>,
+    /// Longest text accepted by a `system` priority speak, in characters
+    pub tts_system_speak_max_chars: Option<usize>,
+    /// Longest provider error message sent to clients, in characters
+    pub error_max_chars: Option<usize>,
+    /// Concurrent connections per Deepgram API key, shared by STT and TTS
+    pub deepgram_connection_budget: Option<ConnectionBudgetConfig>,
 }
 
 /// Recording S3 configuration from YAML
@@ -162,6 +222,8 @@ pub struct RecordingYaml {
     pub s3_access_key: Option<String>,
     pub s3_secret_key: Option<String>,
     pub s3_prefix: Option<String>,
+    /// Replay jobs that may run at once
+    pub replay_max_concurrent_jobs: Option<usize>,
 }
 
 /// Cache configuration from YAML
@@ -185,6 +247,15 @@ pub struct AuthYaml {
     /// Legacy single-secret alias. Ignored when api_secrets is non-empty.
     pub api_secret: Option<String>,
     pub timeout_seconds: Option<u64>,
+    /// Auth IDs allowed to call admin endpoints
+    #[serde(default)]
+    pub admin_ids: Vec<String>,
+    /// Auth IDs granted the `monitor:audio` scope
+    #[serde(default)]
+    pub monitor_audio_ids: Vec<String>,
+    /// Auth IDs granted single admin scopes
+    #[serde(default)]
+    pub admin_scopes: super::admin_scopes::AdminScopeIds,
 }
 
 /// API secret authentication entry in YAML
@@ -206,6 +277,37 @@ pub struct SipYaml {
     pub hook_secret: Option<String>,
     /// Prefix for SIP trunk and dispatch naming (defaults to "waav")
     pub naming_prefix: Option<String>,
+    /// Language pack selection for inbound calls
+    pub language_routing: Option<SipLanguageRoutingYaml>,
+    /// Handling of outbound calls until they are answered
+    pub outbound: Option<SipOutboundConfig>,
+}
+
+/// SIP language pack selection from YAML
+///
+/// Number prefixes are map keys and must be quoted (`"+33"`), otherwise
+/// YAML reads them as numbers.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct SipLanguageRoutingYaml {
+    /// Language packs by name
+    pub packs: BTreeMap<String, LanguagePackYaml>,
+    /// Trunk ID -> pack name
+    pub trunks: HashMap<String, String>,
+    /// Called number prefix -> pack name
+    pub called_prefixes: HashMap<String, String>,
+    /// Caller country calling code -> pack name
+    pub caller_countries: HashMap<String, String>,
+    /// Pack for calls no rule matches
+    pub default_pack: Option<String>,
+}
+
+/// Language pack from YAML
+#[derive(Debug, Clone, Deserialize)]
+pub struct LanguagePackYaml {
+    pub stt_language: String,
+    #[serde(default)]
+    pub tts_voice_id: Option<String>,
 }
 
 /// SIP webhook hook configuration from YAML
@@ -227,6 +329,7 @@ pub struct SipHookYaml {
 ///   rate_limit_burst_size: 10
 ///   max_websocket_connections: 1000
 ///   max_connections_per_ip: 100
+///   trusted_proxies: ["10.0.0.0/8", "192.168.1.5"]
 /// ```
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
@@ -241,6 +344,8 @@ pub struct SecurityYaml {
     pub max_websocket_connections: Option<usize>,
     /// Maximum connections per IP address
     pub max_connections_per_ip: Option<u32>,
+    /// Proxy addresses or CIDR networks whose forwarded headers are honored
+    pub trusted_proxies: Option<Vec<String>>,
 }
 
 /// Plugin configuration from YAML
@@ -250,6 +355,16 @@ pub struct SecurityYaml {
 /// plugins:
 ///   enabled: true
 ///   plugin_dir: "/opt/waav/plugins"
+///   require_exact_abi: false
+///   init_timeout_secs: 5
+///   http_max_body_bytes: 65536
+///   http_timeout_ms: 5000
+///   http_max_concurrency: 16
+///   http_access:
+///     my_custom_stt: ["ops-dashboard"]
+///   data_dir: "/var/lib/waav/plugins"
+///   storage_quota_bytes: 104857600
+///   storage_check_interval_secs: 60
 ///   providers:
 ///     deepgram:
 ///       custom_endpoint: "https://custom.deepgram.com"
@@ -263,11 +378,162 @@ pub struct PluginsYaml {
     pub enabled: Option<bool>,
     /// Directory to load external plugins from (optional)
     pub plugin_dir: Option<String>,
+    /// Further plugin directories, searched after `plugin_dir` in order
+    pub plugin_dirs: Option<Vec<String>>,
+    /// Which library wins when several declare the same plugin ID
+    /// (default: last_dir_wins)
+    pub conflict_policy: Option<super::PluginConflictPolicy>,
+    /// Let dynamic plugins replace built-in providers (default: false)
+    pub allow_override: Option<bool>,
+    /// Fail plugin loading on any ABI version mismatch (default: false)
+    pub require_exact_abi: Option<bool>,
+    /// Seconds each dynamic plugin may take to initialize (default: 5)
+    pub init_timeout_secs: Option<u64>,
+    /// Largest request body accepted by plugin HTTP routes (default: 65536)
+    pub http_max_body_bytes: Option<usize>,
+    /// Milliseconds a plugin may take to answer an HTTP request (default: 5000)
+    pub http_timeout_ms: Option<u64>,
+    /// Requests each plugin's HTTP routes handle at once (default: 16)
+    pub http_max_concurrency: Option<usize>,
+    /// Client ids holding the `plugin:{plugin_id}` scope, keyed by plugin ID
+    #[serde(default)]
+    pub http_access: std::collections::HashMap<String, Vec<String>>,
+    /// Directory holding each dynamic plugin's storage directory
+    pub data_dir: Option<String>,
+    /// Most bytes each plugin may keep in its storage (default: 104857600)
+    pub storage_quota_bytes: Option<u64>,
+    /// Seconds between plugin storage quota checks (default: 60)
+    pub storage_check_interval_secs: Option<u64>,
     /// Provider-specific configuration (keyed by provider name)
     #[serde(default)]
     pub providers: std::collections::HashMap<String, serde_json::Value>,
 }
 
+/// Greeting configuration from YAML
+///
+/// # Example YAML structure
+/// ```yaml
+/// greeting:
+///   text: "Hi, thanks for calling. How can I help?"
+///   # or play a pre-recorded WAV file from assets_dir instead:
+///   # asset: "welcome"
+///   delay_ms: 500
+///   assets_dir: "/opt/waav/greetings"
+/// ```
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct GreetingYaml {
+    /// Text spoken when a session starts
+    pub text: Option<String>,
+    /// Name of a WAV file in `assets_dir` played when a session starts
+    pub asset: Option<String>,
+    /// Delay before the greeting starts, in milliseconds
+    pub delay_ms: Option<u64>,
+    /// Directory containing greeting audio assets
+    pub assets_dir: Option<String>,
+}
+
+/// Usage record configuration from YAML
+///
+/// # Example YAML structure
+/// ```yaml
+/// usage:
+///   sink: both  # file, webhook or both
+///   file_path: "/var/log/waav/usage.jsonl"
+///   file_max_bytes: 104857600
+///   file_max_files: 5
+///   webhook_url: "https://billing.example.com/waav/usage"
+///   webhook_secret: "usage-signing-secret"
+///   reconciliation_path: "/var/log/waav/reconciliation.jsonl"
+///   reconciliation_tolerance_percent: 50
+/// ```
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct UsageYaml {
+    /// Where usage records are written
+    pub sink: Option<super::usage::UsageSinkKind>,
+    /// JSON-lines file records are appended to
+    pub file_path: Option<String>,
+    /// Size in bytes at which the file is rotated
+    pub file_max_bytes: Option<u64>,
+    /// Number of rotated files kept
+    pub file_max_files: Option<usize>,
+    /// URL records are POSTed to
+    pub webhook_url: Option<String>,
+    /// Secret the webhook payload is signed with
+    pub webhook_secret: Option<String>,
+    /// JSON-lines report of TTS utterances that fail reconciliation
+    pub reconciliation_path: Option<String>,
+    /// Deviation from the expected audio length that flags an utterance, in percent
+    pub reconciliation_tolerance_percent: Option<u32>,
+}
+
+/// Provider self-test configuration from YAML
+///
+/// # Example YAML structure
+/// ```yaml
+/// selftest:
+///   stt_provider: deepgram
+///   tts_provider: elevenlabs
+///   tts_voice: "21m00Tcm4TlvDq8ikWAM"
+///   phrase: "The quick brown fox jumps over the lazy dog"
+///   min_similarity: 0.8
+///   timeout_secs: 30
+///   interval_secs: 3600  # omit to run at startup only
+///   required: false      # true keeps /readyz failing until a run passes
+/// ```
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct SelfTestYaml {
+    /// STT provider the canary audio is transcribed with
+    pub stt_provider: Option<String>,
+    /// STT model
+    pub stt_model: Option<String>,
+    /// TTS provider the canary phrase is synthesized with
+    pub tts_provider: Option<String>,
+    /// TTS voice
+    pub tts_voice: Option<String>,
+    /// TTS model
+    pub tts_model: Option<String>,
+    /// Language of the canary phrase
+    pub language: Option<String>,
+    /// Phrase synthesized and transcribed
+    pub phrase: Option<String>,
+    /// Sample rate of the canary audio in Hz
+    pub sample_rate: Option<u32>,
+    /// Minimum transcript similarity to pass (0.0 to 1.0)
+    pub min_similarity: Option<f64>,
+    /// Time limit for one run in seconds
+    pub timeout_secs: Option<u64>,
+    /// Seconds between periodic runs
+    pub interval_secs: Option<u64>,
+    /// Block readiness until a run passes
+    pub required: Option<bool>,
+}
+
+/// Load shedding configuration from YAML
+///
+/// # Example YAML structure
+/// ```yaml
+/// load_shedding:
+///   max_active_sessions: 500
+///   max_pool_saturation: 0.9    # 0.0 to 1.0
+///   max_event_loop_lag_ms: 200
+///   retry_after_secs: 5
+/// ```
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct LoadSheddingYaml {
+    /// Open sessions at which new ones are shed
+    pub max_active_sessions: Option<usize>,
+    /// Provider pool saturation at which new sessions are shed
+    pub max_pool_saturation: Option<f64>,
+    /// Runtime lag in milliseconds at which new sessions are shed
+    pub max_event_loop_lag_ms: Option<u64>,
+    /// Seconds sent in the `Retry-After` header
+    pub retry_after_secs: Option<u64>,
+}
+
 impl YamlConfig {
     /// Load configuration from a YAML file
     ///
@@ -282,12 +548,28 @@ impl YamlConfig {
     /// - The file cannot be read
     /// - The YAML is malformed
     /// - Required fields have invalid types
+    /// - An `!encrypted` value cannot be decrypted with the master key
     pub fn from_file(path: &PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
         let contents = std::fs::read_to_string(path)
             .map_err(|e| format!("Failed to read config file {}: {e}", path.display()))?;
 
-        let config: YamlConfig = serde_yaml::from_str(&contents)
+        // Encrypted values are decrypted in the parsed tree, before the
+        // config is deserialized and merged with the environment
+        let mut value: serde_yaml::Value = serde_yaml::from_str(&contents)
             .map_err(|e| format!("Failed to parse YAML config: {e}"))?;
+        let decrypted = decrypt_tagged_values(&mut value, MasterKey::from_env)?;
+
+        let config: YamlConfig = if decrypted == 0 {
+            // Parsing the text again keeps line numbers in errors
+            serde_yaml::from_str(&contents)
+        } else {
+            // Deserializing by reference leaves the plaintexts in the tree to
+            // be zeroed, instead of dropping them with the tree
+            let config = YamlConfig::deserialize(&value);
+            zeroize_strings(&mut value);
+            config
+        }
+        .map_err(|e| format!("Failed to parse YAML config: {e}"))?;
 
         Ok(config)
     }
@@ -547,6 +829,311 @@ sip:
         assert_eq!(sip.hooks[1].secret, Some("per-hook-secret".to_string()));
     }
 
+    #[test]
+    fn test_yaml_config_with_agents() {
+        let yaml = r#"
+agents:
+  - name: support-bot-en
+    clients: ["project1"]
+    overridable: ["tts_config.voice_id"]
+    session:
+      tts_config:
+        provider: elevenlabs
+        voice_id: "rachel"
+        model: eleven_turbo_v2
+      greeting:
+        text: "Hi, how can I help?"
+  - name: realtime-bot
+    realtime:
+      provider: openai
+      voice: alloy
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let agents = config.agents.unwrap();
+        assert_eq!(agents.len(), 2);
+        assert_eq!(agents[0].name, "support-bot-en");
+        assert_eq!(agents[0].clients, vec!["project1"]);
+        let session = agents[0].session.as_ref().unwrap();
+        assert_eq!(session["tts_config"]["voice_id"], "rachel");
+        assert_eq!(session["greeting"]["text"], "Hi, how can I help?");
+        assert!(agents[1].session.is_none());
+        assert_eq!(agents[1].realtime.as_ref().unwrap()["voice"], "alloy");
+    }
+
+    #[test]
+    fn test_yaml_config_with_feature_flags() {
+        let yaml = r#"
+feature_flags:
+  adaptive_endpointing:
+    rollout_percent: 25
+    clients:
+      project-beta: true
+  echo_guard:
+    default: true
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let flags = config.feature_flags.unwrap();
+        let endpointing = &flags["adaptive_endpointing"];
+        assert!(!endpointing.default);
+        assert_eq!(endpointing.rollout_percent, Some(25.0));
+        assert_eq!(endpointing.clients.get("project-beta"), Some(&true));
+        assert!(flags["echo_guard"].default);
+        assert!(flags["echo_guard"].clients.is_empty());
+    }
+
+    #[test]
+    fn test_yaml_config_with_transcript_buffer() {
+        let yaml = r#"
+transcript_buffer:
+  max_entries: 500
+  overflow: spill_to_cache
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let transcript_buffer = config.transcript_buffer.unwrap();
+        assert_eq!(transcript_buffer.max_entries, 500);
+        assert_eq!(
+            transcript_buffer.max_bytes,
+            TranscriptBufferConfig::default().max_bytes
+        );
+        assert_eq!(
+            transcript_buffer.overflow,
+            crate::core::session::TranscriptOverflowPolicy::SpillToCache
+        );
+    }
+
+    #[test]
+    fn test_yaml_config_with_deepgram_connection_budget() {
+        let yaml = r#"
+providers:
+  deepgram_connection_budget:
+    max_connections: 25
+    policy: queue
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let budget = config
+            .providers
+            .unwrap()
+            .deepgram_connection_budget
+            .unwrap();
+        assert_eq!(budget.max_connections, Some(25));
+        assert_eq!(
+            budget.policy,
+            crate::core::providers::connection_budget::ConnectionBudgetPolicy::Queue
+        );
+        assert_eq!(
+            budget.queue_timeout_ms,
+            ConnectionBudgetConfig::default().queue_timeout_ms
+        );
+    }
+
+    #[test]
+    fn test_yaml_config_with_chaos() {
+        let config: YamlConfig = serde_yaml::from_str("chaos:\n  enabled: true\n").unwrap();
+        assert!(config.chaos.unwrap().enabled);
+
+        let config: YamlConfig = serde_yaml::from_str("chaos: {}\n").unwrap();
+        assert!(!config.chaos.unwrap().enabled);
+        assert!(serde_yaml::from_str::<YamlConfig>("chaos:\n  enable: true\n").is_err());
+    }
+
+    #[test]
+    fn test_yaml_config_with_console() {
+        let config: YamlConfig = serde_yaml::from_str("console:\n  enabled: true\n").unwrap();
+        assert!(config.console.unwrap().enabled);
+        assert!(serde_yaml::from_str::<YamlConfig>("console:\n  path: /dev\n").is_err());
+    }
+
+    #[test]
+    fn test_yaml_config_with_webhook_queue() {
+        let config: YamlConfig =
+            serde_yaml::from_str("webhook_queue:\n  max_attempts: 5\n").unwrap();
+        let queue = config.webhook_queue.unwrap();
+        assert_eq!(queue.max_attempts, 5);
+        assert_eq!(queue.max_pending_per_destination, 1_000);
+        assert!(serde_yaml::from_str::<YamlConfig>("webhook_queue:\n  retries: 5\n").is_err());
+    }
+
+    #[test]
+    fn test_yaml_config_with_ingest() {
+        let yaml = r#"
+ingest:
+  rtmp_address: "0.0.0.0:1935"
+  streams:
+    - key: "sk_news"
+      name: "news-1"
+      agent: "captions"
+      client_id: "newsroom"
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let ingest = config.ingest.unwrap();
+        assert_eq!(ingest.rtmp_address, "0.0.0.0:1935");
+        assert_eq!(ingest.streams[0].client_id.as_deref(), Some("newsroom"));
+        assert!(
+            serde_yaml::from_str::<YamlConfig>("ingest:\n  srt_address: \"0.0.0.0:9000\"\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_yaml_config_with_voice_profiles() {
+        let yaml = r#"
+voice_profiles:
+  warm-female-en:
+    description: "Warm US English"
+    voices:
+      elevenlabs:
+        voice_id: "21m00Tcm4TlvDq8ikWAM"
+      azure:
+        voice_id: en-US-JennyNeural
+        speaking_rate: 1.05
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let profiles = config.voice_profiles.unwrap();
+        let profile = &profiles["warm-female-en"];
+        assert_eq!(profile.voices.len(), 2);
+        assert_eq!(
+            profile.voice_for("microsoft-azure").unwrap().speaking_rate,
+            Some(1.05)
+        );
+
+        let yaml = "voice_profiles:\n  x:\n    voices:\n      deepgram:\n        voice: a\n";
+        assert!(serde_yaml::from_str::<YamlConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_yaml_config_with_transcript_enrichment() {
+        let yaml = r#"
+transcript_enrichment:
+  workers: 4
+  webhook_url: "https://crm.example.com/waav/transcripts"
+  webhook_secret: "transcript-signing-secret"
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let enrichment = config.transcript_enrichment.unwrap();
+        assert_eq!(enrichment.workers, 4);
+        assert_eq!(enrichment.queue_size, 64);
+        assert_eq!(
+            enrichment.webhook_url.as_deref(),
+            Some("https://crm.example.com/waav/transcripts")
+        );
+        assert!(YamlConfig::default().transcript_enrichment.is_none());
+    }
+
+    #[test]
+    fn test_yaml_config_with_session_export() {
+        let yaml = r#"
+session_export:
+  url_expiry_secs: 900
+  local_dir: "/var/lib/waav/sessions"
+  public_base_url: "https://voice.example.com"
+  signing_key: "a-dedicated-key-of-at-least-32-characters"
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let export = config.session_export.unwrap();
+        assert_eq!(export.url_expiry_secs, 900);
+        assert_eq!(
+            export.public_base_url.as_deref(),
+            Some("https://voice.example.com")
+        );
+        assert!(export.webhook_url.is_none());
+        assert!(YamlConfig::default().session_export.is_none());
+    }
+
+    #[test]
+    fn test_yaml_config_with_audio_sinks() {
+        let yaml = r#"
+audio_sinks:
+  recording:
+    sample_rate: 16000
+    opus_bitrate_kbps: 24
+  monitor:
+    sample_rate: 8000
+    mono: true
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let audio_sinks = config.audio_sinks.unwrap();
+        assert_eq!(audio_sinks.recording.sample_rate, Some(16000));
+        assert_eq!(audio_sinks.recording.opus_bitrate_kbps, Some(24));
+        assert!(!audio_sinks.recording.mono);
+        assert_eq!(audio_sinks.monitor.sample_rate, Some(8000));
+        assert!(audio_sinks.monitor.mono);
+    }
+
+    #[test]
+    fn test_yaml_config_with_recording_upload() {
+        let yaml = r#"
+recording_upload:
+  spill_dir: "/var/lib/waav/recording-spill"
+  max_part_attempts: 3
+  reconcile_interval_secs: 10
+"#;
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let upload = config.recording_upload.unwrap();
+        assert_eq!(
+            upload.spill_dir,
+            Some(std::path::PathBuf::from("/var/lib/waav/recording-spill"))
+        );
+        assert_eq!(upload.max_part_attempts, 3);
+        assert_eq!(upload.reconcile_interval_secs, 10);
+        assert!(upload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_yaml_config_sip_language_routing() {
+        let yaml = r#"
+sip:
+  room_prefix: "sip-"
+  language_routing:
+    packs:
+      fr-ca:
+        stt_language: "fr-CA"
+        tts_voice_id: "fr-voice"
+      en-us:
+        stt_language: "en-US"
+    trunks:
+      ST_quebec: "fr-ca"
+    called_prefixes:
+      "+1514": "fr-ca"
+    caller_countries:
+      "+33": "fr-ca"
+    default_pack: "en-us"
+"#;
+
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let routing = config.sip.unwrap().language_routing.unwrap();
+        assert_eq!(routing.packs.len(), 2);
+        assert_eq!(routing.packs["fr-ca"].stt_language, "fr-CA");
+        assert_eq!(
+            routing.packs["fr-ca"].tts_voice_id.as_deref(),
+            Some("fr-voice")
+        );
+        assert_eq!(routing.packs["en-us"].tts_voice_id, None);
+        assert_eq!(routing.trunks["ST_quebec"], "fr-ca");
+        assert_eq!(routing.called_prefixes["+1514"], "fr-ca");
+        assert_eq!(routing.caller_countries["+33"], "fr-ca");
+        assert_eq!(routing.default_pack.as_deref(), Some("en-us"));
+    }
+
+    #[test]
+    fn test_yaml_config_sip_outbound() {
+        let yaml = r#"
+sip:
+  room_prefix: "sip-"
+  outbound:
+    early_media_asset: "compliance-notice"
+    no_answer_timeout_secs: 30
+"#;
+
+        let config: YamlConfig = serde_yaml::from_str(yaml).unwrap();
+        let outbound = config.sip.unwrap().outbound.unwrap();
+        assert_eq!(
+            outbound.early_media_asset.as_deref(),
+            Some("compliance-notice")
+        );
+        assert_eq!(outbound.no_answer_timeout_secs, 30);
+        assert_eq!(outbound.status_poll_interval_ms, 500);
+    }
+
     #[test]
     fn test_yaml_config_sip_empty_arrays() {
         let yaml = r#"
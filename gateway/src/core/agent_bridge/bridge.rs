@@ -0,0 +1,440 @@
+//! Agent bridge: streams LLM replies for completed user turns into TTS
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::config::AgentBridgeConfig;
+use super::errors::{AgentBridgeError, AgentBridgeResult};
+use super::stream::{ChatStreamEvent, SentenceBuffer, SseParser, parse_chat_event};
+use crate::core::stt::STTResult;
+use crate::core::tts::segmentation::TextSegmenter;
+use crate::core::voice_manager::VoiceManager;
+
+/// Timeout for establishing a connection to the LLM endpoint
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Maximum number of user/assistant messages kept as conversation history
+const MAX_HISTORY_MESSAGES: usize = 40;
+
+/// Callback type for agent bridge errors
+pub type AgentErrorCallback =
+    Arc<dyn Fn(AgentBridgeError) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Destination for text produced by the agent bridge
+///
+/// Implemented by `VoiceManager`; tests can substitute a recording sink.
+#[async_trait]
+pub trait SpeechSink: Send + Sync {
+    /// Queue text for synthesis
+    async fn speak(&self, text: &str, flush: bool) -> AgentBridgeResult<()>;
+
+    /// Flush any text still buffered by the TTS provider
+    async fn flush(&self) -> AgentBridgeResult<()>;
+
+    /// Drop queued text and audio (barge-in)
+    async fn clear(&self) -> AgentBridgeResult<()>;
+}
+
+#[async_trait]
+impl SpeechSink for VoiceManager {
+    async fn speak(&self, text: &str, flush: bool) -> AgentBridgeResult<()> {
+        VoiceManager::speak(self, text, flush)
+            .await
+            .map_err(|e| AgentBridgeError::SpeechError(e.to_string()))
+    }
+
+    async fn flush(&self) -> AgentBridgeResult<()> {
+        self.flush_tts()
+            .await
+            .map_err(|e| AgentBridgeError::SpeechError(e.to_string()))
+    }
+
+    async fn clear(&self) -> AgentBridgeResult<()> {
+        self.clear_tts()
+            .await
+            .map_err(|e| AgentBridgeError::SpeechError(e.to_string()))
+    }
+}
+
+/// Chat message sent to the LLM endpoint
+#[derive(Debug, Clone, Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+/// Request body for an OpenAI-compatible streaming chat completion
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+}
+
+/// Bridges final user transcripts to a streaming LLM and speaks the reply
+///
+/// Final transcripts are collected until `is_speech_final`, then the turn is
+/// sent to the configured chat completions endpoint with `stream: true`.
+/// Tokens are grouped into sentences and queued on the TTS provider as they
+/// arrive (`flush = false`), with a flush once the reply is complete.
+///
+/// New user speech cancels the in-flight LLM stream and clears queued TTS
+/// (barge-in). The bridge holds a weak reference to its sink so it can live
+/// inside the sink's own STT callback.
+pub struct AgentBridge {
+    config: AgentBridgeConfig,
+    client: reqwest::Client,
+    sink: Weak<dyn SpeechSink>,
+    /// Final transcript segments of the current user turn
+    pending_turn: Mutex<String>,
+    /// Token for the most recent reply; taken on barge-in
+    active_reply: Mutex<Option<CancellationToken>>,
+    /// End the current reply after its next sentence
+    truncate: AtomicBool,
+    /// Serializes speaking against clearing so a cancelled reply cannot
+    /// queue text after the clear
+    speech_lock: tokio::sync::Mutex<()>,
+    /// Completed user/assistant exchanges sent as context
+    history: Mutex<Vec<ChatMessage>>,
+    error_callback: RwLock<Option<AgentErrorCallback>>,
+    /// Splits streamed replies into sentences for the language spoken
+    segmenter: TextSegmenter,
+}
+
+impl AgentBridge {
+    /// Create a new agent bridge
+    ///
+    /// # Arguments
+    /// * `config` - LLM endpoint configuration
+    /// * `sink` - Where reply text is spoken (usually the session's `VoiceManager`)
+    pub fn new(config: AgentBridgeConfig, sink: Weak<dyn SpeechSink>) -> AgentBridgeResult<Self> {
+        config.validate()?;
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| AgentBridgeError::InvalidConfig(format!("HTTP client: {e}")))?;
+
+        Ok(Self {
+            config,
+            client,
+            sink,
+            pending_turn: Mutex::new(String::new()),
+            active_reply: Mutex::new(None),
+            truncate: AtomicBool::new(false),
+            speech_lock: tokio::sync::Mutex::new(()),
+            history: Mutex::new(Vec::new()),
+            error_callback: RwLock::new(None),
+            segmenter: TextSegmenter::default(),
+        })
+    }
+
+    /// Segment replies with `segmenter` instead of the default Latin-script one
+    pub fn with_segmenter(mut self, segmenter: TextSegmenter) -> Self {
+        self.segmenter = segmenter;
+        self
+    }
+
+    /// Get the bridge configuration
+    pub fn config(&self) -> &AgentBridgeConfig {
+        &self.config
+    }
+
+    /// Register a callback for errors raised while handling a turn
+    pub fn on_error<F>(&self, callback: F)
+    where
+        F: Fn(AgentBridgeError) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        *self.error_callback.write() = Some(Arc::new(callback));
+    }
+
+    /// Feed an STT result into the bridge
+    ///
+    /// Any non-empty transcript interrupts the current reply. Final segments
+    /// are buffered, and `is_speech_final` starts a new LLM turn in the
+    /// background.
+    pub async fn handle_stt_result(self: &Arc<Self>, result: &STTResult) {
+        let transcript = result.transcript.trim();
+
+        if !transcript.is_empty() {
+            self.interrupt().await;
+
+            if result.is_final {
+                let mut pending = self.pending_turn.lock();
+                if !pending.is_empty() {
+                    pending.push(' ');
+                }
+                pending.push_str(transcript);
+            }
+        }
+
+        if result.is_speech_final {
+            let turn = std::mem::take(&mut *self.pending_turn.lock());
+            if !turn.is_empty() {
+                self.start_turn(turn).await;
+            }
+        }
+    }
+
+    /// Send a user turn to the LLM and speak the streamed reply
+    ///
+    /// Any reply still in progress is interrupted first.
+    pub async fn start_turn(self: &Arc<Self>, user_text: String) {
+        self.interrupt().await;
+
+        let token = CancellationToken::new();
+        *self.active_reply.lock() = Some(token.clone());
+        self.truncate.store(false, Ordering::Release);
+
+        let bridge = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = bridge.run_turn(user_text, &token).await {
+                warn!("Agent bridge turn failed: {}", e);
+                let callback = bridge.error_callback.read().clone();
+                if let Some(callback) = callback {
+                    callback(e).await;
+                }
+            }
+        });
+    }
+
+    /// Cancel the current LLM stream and clear its queued speech
+    ///
+    /// No-op when no reply has been started since the last interruption.
+    pub async fn interrupt(&self) {
+        let Some(token) = self.active_reply.lock().take() else {
+            return;
+        };
+
+        let _guard = self.speech_lock.lock().await;
+        token.cancel();
+        if let Some(sink) = self.sink.upgrade()
+            && let Err(e) = sink.clear().await
+        {
+            warn!("Agent bridge failed to clear TTS on barge-in: {}", e);
+        }
+        debug!("Agent bridge reply interrupted");
+    }
+
+    /// End the current reply after its next sentence
+    ///
+    /// For replies that are slow to start: the caller hears one sentence
+    /// instead of waiting through the whole reply. The LLM stream is dropped
+    /// once that sentence has been queued, and the text buffered after it is
+    /// never spoken.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a reply has been started since the last interruption
+    pub fn truncate_reply(&self) -> bool {
+        if self.active_reply.lock().is_none() {
+            return false;
+        }
+        self.truncate.store(true, Ordering::Release);
+        debug!("Agent bridge reply truncated");
+        true
+    }
+
+    /// Cancel the current LLM stream without touching TTS
+    ///
+    /// Used when the caller clears audio itself (e.g. a `clear` command) or
+    /// the session is shutting down.
+    pub fn cancel(&self) {
+        if let Some(token) = self.active_reply.lock().take() {
+            token.cancel();
+        }
+    }
+
+    /// Stream one reply, speaking each sentence as it completes
+    async fn run_turn(
+        &self,
+        user_text: String,
+        token: &CancellationToken,
+    ) -> AgentBridgeResult<()> {
+        let messages = self.build_messages(&user_text);
+        let body = ChatCompletionRequest {
+            model: &self.config.model,
+            messages,
+            stream: true,
+            max_tokens: self.config.max_tokens,
+        };
+
+        let mut request = self
+            .client
+            .post(&self.config.endpoint)
+            .header("Accept", "text/event-stream")
+            .json(&body);
+        if let Some(api_key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            request = request.bearer_auth(api_key);
+        }
+
+        debug!("Agent bridge sending turn to {}", self.config.endpoint);
+        let response = tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            response = request.send() => {
+                response.map_err(|e| AgentBridgeError::RequestFailed(e.to_string()))?
+            }
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AgentBridgeError::HttpStatus {
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut parser = SseParser::default();
+        let mut sentences = SentenceBuffer::new(self.segmenter);
+        let mut spoken = String::new();
+        let mut done = false;
+        let mut truncated = false;
+
+        while !done && !truncated {
+            let chunk = tokio::select! {
+                _ = token.cancelled() => break,
+                chunk = stream.next() => chunk,
+            };
+
+            let events = match chunk {
+                Some(Ok(bytes)) => parser.push(&bytes),
+                Some(Err(e)) => return Err(AgentBridgeError::StreamError(e.to_string())),
+                None => {
+                    done = true;
+                    parser.finish().into_iter().collect()
+                }
+            };
+
+            for data in events {
+                match parse_chat_event(&data)? {
+                    Some(ChatStreamEvent::Content(text)) => {
+                        for sentence in sentences.push(&text) {
+                            if !self.speak(token, &sentence, false).await? {
+                                break;
+                            }
+                            append_reply(&mut spoken, &sentence);
+                            if self.truncate.load(Ordering::Acquire) {
+                                truncated = true;
+                                break;
+                            }
+                        }
+                    }
+                    Some(ChatStreamEvent::Done) => done = true,
+                    None => {}
+                }
+                if truncated {
+                    break;
+                }
+            }
+        }
+
+        if truncated && !token.is_cancelled() {
+            if let Some(sink) = self.sink.upgrade() {
+                sink.flush().await?;
+            }
+            info!("Agent bridge reply truncated after {} chars", spoken.len());
+        } else if !token.is_cancelled() {
+            match sentences.finish() {
+                Some(rest) => {
+                    if self.speak(token, &rest, true).await? {
+                        append_reply(&mut spoken, &rest);
+                    }
+                }
+                None => {
+                    if let Some(sink) = self.sink.upgrade() {
+                        sink.flush().await?;
+                    }
+                }
+            }
+            info!("Agent bridge reply complete ({} chars)", spoken.len());
+        } else {
+            debug!("Agent bridge reply cancelled after {} chars", spoken.len());
+        }
+
+        // Record what the user actually heard so the next turn has context
+        self.record_exchange(user_text, spoken);
+        Ok(())
+    }
+
+    /// Speak one piece of the reply unless the turn has been cancelled
+    ///
+    /// # Returns
+    /// * `Ok(true)` - Text was queued
+    /// * `Ok(false)` - Turn was cancelled or the sink is gone
+    async fn speak(
+        &self,
+        token: &CancellationToken,
+        text: &str,
+        flush: bool,
+    ) -> AgentBridgeResult<bool> {
+        let _guard = self.speech_lock.lock().await;
+        if token.is_cancelled() {
+            return Ok(false);
+        }
+        let Some(sink) = self.sink.upgrade() else {
+            token.cancel();
+            return Ok(false);
+        };
+        sink.speak(text, flush).await?;
+        Ok(true)
+    }
+
+    fn build_messages(&self, user_text: &str) -> Vec<ChatMessage> {
+        let history = self.history.lock();
+        let mut messages = Vec::with_capacity(history.len() + 2);
+        if let Some(prompt) = self
+            .config
+            .system_prompt
+            .as_deref()
+            .filter(|p| !p.is_empty())
+        {
+            messages.push(ChatMessage {
+                role: "system",
+                content: prompt.to_string(),
+            });
+        }
+        messages.extend(history.iter().cloned());
+        messages.push(ChatMessage {
+            role: "user",
+            content: user_text.to_string(),
+        });
+        messages
+    }
+
+    fn record_exchange(&self, user_text: String, reply: String) {
+        let mut history = self.history.lock();
+        history.push(ChatMessage {
+            role: "user",
+            content: user_text,
+        });
+        if !reply.is_empty() {
+            history.push(ChatMessage {
+                role: "assistant",
+                content: reply,
+            });
+        }
+        if history.len() > MAX_HISTORY_MESSAGES {
+            let excess = history.len() - MAX_HISTORY_MESSAGES;
+            history.drain(..excess);
+        }
+    }
+}
+
+fn append_reply(reply: &mut String, sentence: &str) {
+    if !reply.is_empty() {
+        reply.push(' ');
+    }
+    reply.push_str(sentence);
+}
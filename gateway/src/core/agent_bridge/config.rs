@@ -0,0 +1,109 @@
+//! Configuration types for the agent bridge
+
+use serde::{Deserialize, Serialize};
+
+use super::errors::{AgentBridgeError, AgentBridgeResult};
+
+/// Configuration for the transcript-to-LLM agent bridge
+///
+/// The endpoint must speak the OpenAI chat completions protocol with
+/// `stream: true` (server-sent events).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AgentBridgeConfig {
+    /// Full URL of the chat completions endpoint
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "https://api.openai.com/v1/chat/completions")
+    )]
+    pub endpoint: String,
+    /// Bearer token sent in the `Authorization` header (optional for local endpoints)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Model name passed through to the endpoint
+    #[cfg_attr(feature = "openapi", schema(example = "gpt-4o-mini"))]
+    pub model: String,
+    /// System prompt prepended to every request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Maximum number of tokens to generate per turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = 256))]
+    pub max_tokens: Option<u32>,
+}
+
+impl AgentBridgeConfig {
+    /// Validate the configuration before a bridge is created
+    pub fn validate(&self) -> AgentBridgeResult<()> {
+        let endpoint = self.endpoint.trim();
+        if endpoint.is_empty() {
+            return Err(AgentBridgeError::InvalidConfig(
+                "endpoint is required".to_string(),
+            ));
+        }
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(AgentBridgeError::InvalidConfig(format!(
+                "endpoint must be an http(s) URL, got '{endpoint}'"
+            )));
+        }
+        if self.model.trim().is_empty() {
+            return Err(AgentBridgeError::InvalidConfig(
+                "model is required".to_string(),
+            ));
+        }
+        if self.max_tokens == Some(0) {
+            return Err(AgentBridgeError::InvalidConfig(
+                "max_tokens must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AgentBridgeConfig {
+        AgentBridgeConfig {
+            endpoint: "http://localhost:8000/v1/chat/completions".to_string(),
+            api_key: None,
+            model: "llama-3.1-8b".to_string(),
+            system_prompt: None,
+            max_tokens: Some(128),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_config() {
+        assert!(config().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_endpoint_and_model() {
+        let mut bad = config();
+        bad.endpoint = "localhost:8000".to_string();
+        assert!(matches!(
+            bad.validate(),
+            Err(AgentBridgeError::InvalidConfig(_))
+        ));
+
+        let mut bad = config();
+        bad.model = " ".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = config();
+        bad.max_tokens = Some(0);
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_deserialize_minimal_config() {
+        let json = r#"{"endpoint": "https://llm.example.com/v1/chat/completions", "model": "gpt-4o-mini"}"#;
+        let config: AgentBridgeConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.model, "gpt-4o-mini");
+        assert!(config.api_key.is_none());
+        assert!(config.system_prompt.is_none());
+        assert!(config.max_tokens.is_none());
+    }
+}
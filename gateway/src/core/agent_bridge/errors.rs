@@ -0,0 +1,34 @@
+//! Error types for agent bridge operations
+
+use crate::errors::provider_error::error_codes;
+
+/// Error types for agent bridge operations
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AgentBridgeError {
+    #[error("Invalid agent configuration: {0}")]
+    InvalidConfig(String),
+    #[error("LLM request failed: {0}")]
+    RequestFailed(String),
+    #[error("LLM endpoint returned HTTP {status}: {body}")]
+    HttpStatus { status: u16, body: String },
+    #[error("LLM stream error: {0}")]
+    StreamError(String),
+    #[error("Failed to speak LLM response: {0}")]
+    SpeechError(String),
+}
+
+impl AgentBridgeError {
+    /// Get the error code sent to clients with the sanitized message
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AgentBridgeError::InvalidConfig(_) => error_codes::AGENT_INVALID_CONFIG,
+            AgentBridgeError::RequestFailed(_)
+            | AgentBridgeError::HttpStatus { .. }
+            | AgentBridgeError::StreamError(_) => error_codes::AGENT_REQUEST_FAILED,
+            AgentBridgeError::SpeechError(_) => error_codes::AGENT_SPEECH_FAILED,
+        }
+    }
+}
+
+/// Result type for agent bridge operations
+pub type AgentBridgeResult<T> = Result<T, AgentBridgeError>;
@@ -0,0 +1,40 @@
+//! # Agent Bridge
+//!
+//! Optional component that turns a voice session into a conversational agent
+//! without waiting for full LLM responses:
+//!
+//! 1. Final STT transcripts are collected until `is_speech_final`
+//! 2. The turn is POSTed to an OpenAI-compatible chat completions endpoint
+//!    with `stream: true`
+//! 3. Streamed tokens are grouped into sentences and queued on the TTS
+//!    provider as they arrive, so speech starts after the first sentence
+//! 4. New user speech cancels both the LLM stream and queued TTS (barge-in)
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use std::sync::{Arc, Weak};
+//! use waav_gateway::core::agent_bridge::{AgentBridge, AgentBridgeConfig, SpeechSink};
+//! use waav_gateway::core::voice_manager::VoiceManager;
+//!
+//! fn attach(voice_manager: &Arc<VoiceManager>) -> Result<Arc<AgentBridge>, Box<dyn std::error::Error>> {
+//!     let config = AgentBridgeConfig {
+//!         endpoint: "https://api.openai.com/v1/chat/completions".to_string(),
+//!         api_key: Some("your-api-key".to_string()),
+//!         model: "gpt-4o-mini".to_string(),
+//!         system_prompt: Some("You are a concise voice assistant.".to_string()),
+//!         max_tokens: Some(256),
+//!     };
+//!     let sink: Weak<dyn SpeechSink> = Arc::downgrade(voice_manager) as Weak<dyn SpeechSink>;
+//!     Ok(Arc::new(AgentBridge::new(config, sink)?))
+//! }
+//! ```
+
+pub mod bridge;
+pub mod config;
+pub mod errors;
+pub mod stream;
+
+pub use bridge::{AgentBridge, AgentErrorCallback, SpeechSink};
+pub use config::AgentBridgeConfig;
+pub use errors::{AgentBridgeError, AgentBridgeResult};
@@ -0,0 +1,272 @@
+//! Streaming helpers for the agent bridge
+//!
+//! - `SseParser` turns raw response bytes into server-sent event payloads
+//! - `parse_chat_event` extracts content deltas from chat completion chunks
+//! - `SentenceBuffer` groups streamed tokens into sentences for TTS
+
+use serde::Deserialize;
+
+use super::errors::{AgentBridgeError, AgentBridgeResult};
+use crate::core::tts::segmentation::TextSegmenter;
+
+/// Payload that marks the end of an OpenAI-compatible stream
+const DONE_MARKER: &str = "[DONE]";
+
+/// Incremental parser for `text/event-stream` bodies
+///
+/// Lines are buffered as bytes so multi-byte characters split across
+/// network chunks are decoded intact.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    data: String,
+}
+
+impl SseParser {
+    /// Feed raw bytes, returning the data payload of every completed event
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            if let Some(event) = self.process_line(&String::from_utf8_lossy(&line)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flush a trailing event that was not terminated by a blank line
+    pub fn finish(&mut self) -> Option<String> {
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            self.process_line(&String::from_utf8_lossy(&line));
+        }
+        (!self.data.is_empty()).then(|| std::mem::take(&mut self.data))
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<String> {
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        // A blank line dispatches the buffered event
+        if line.is_empty() {
+            return (!self.data.is_empty()).then(|| std::mem::take(&mut self.data));
+        }
+
+        // Comments, event names and ids are not used by chat completion streams
+        if let Some(value) = line.strip_prefix("data:") {
+            if !self.data.is_empty() {
+                self.data.push('\n');
+            }
+            self.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+        }
+        None
+    }
+}
+
+/// Event decoded from a chat completion stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatStreamEvent {
+    /// Generated text to append to the reply
+    Content(String),
+    /// The endpoint finished the stream
+    Done,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Decode one SSE data payload from a chat completions stream
+///
+/// # Returns
+/// * `Ok(Some(event))` - Content delta or end of stream
+/// * `Ok(None)` - Chunk without content (role announcement, finish reason)
+/// * `Err(StreamError)` - Malformed chunk or an error object from the endpoint
+pub fn parse_chat_event(data: &str) -> AgentBridgeResult<Option<ChatStreamEvent>> {
+    let data = data.trim();
+    if data == DONE_MARKER {
+        return Ok(Some(ChatStreamEvent::Done));
+    }
+
+    let chunk: ChatCompletionChunk = serde_json::from_str(data)
+        .map_err(|e| AgentBridgeError::StreamError(format!("invalid chunk: {e}")))?;
+
+    if let Some(error) = chunk.error {
+        return Err(AgentBridgeError::StreamError(error.to_string()));
+    }
+
+    let content: String = chunk
+        .choices
+        .into_iter()
+        .filter_map(|choice| choice.delta.content)
+        .collect();
+
+    Ok((!content.is_empty()).then_some(ChatStreamEvent::Content(content)))
+}
+
+/// Accumulates streamed text and releases it a sentence at a time
+///
+/// Sentences are found by the buffer's [`TextSegmenter`]; text growing past
+/// its chunk limit without a terminator is released at the best break that
+/// fits.
+#[derive(Debug, Default)]
+pub struct SentenceBuffer {
+    buffer: String,
+    segmenter: TextSegmenter,
+}
+
+impl SentenceBuffer {
+    /// Buffer segmenting text with `segmenter`
+    pub fn new(segmenter: TextSegmenter) -> Self {
+        Self {
+            buffer: String::new(),
+            segmenter,
+        }
+    }
+
+    /// Append streamed text, returning any sentences it completes
+    pub fn push(&mut self, text: &str) -> Vec<String> {
+        self.buffer.push_str(text);
+
+        let max_bytes = self.segmenter.max_bytes();
+        let mut sentences = Vec::new();
+        loop {
+            let end = match self.segmenter.sentence_end(&self.buffer) {
+                Some(end) if end <= max_bytes => end,
+                _ if self.buffer.len() > max_bytes => self.segmenter.split_point(&self.buffer),
+                _ => break,
+            };
+            let rest = self.buffer.split_off(end);
+            let sentence = std::mem::replace(&mut self.buffer, rest);
+            let sentence = sentence.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+        }
+        sentences
+    }
+
+    /// Take whatever text remains once the stream has ended
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buffer);
+        let rest = rest.trim();
+        (!rest.is_empty()).then(|| rest.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tts::segmentation::SegmentationProfile;
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"data: {\"a\":").is_empty());
+        assert!(parser.push(b"1}\r\n").is_empty());
+        assert_eq!(
+            parser.push(b"\r\ndata: [DONE]\n\n"),
+            vec!["{\"a\":1}".to_string(), "[DONE]".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sse_parser_ignores_comments_and_keeps_utf8() {
+        let mut parser = SseParser::default();
+        let payload = "data: héllo\n\n".as_bytes();
+        // Split inside the two-byte 'é'
+        assert!(parser.push(b": keep-alive\n\n").is_empty());
+        assert!(parser.push(&payload[..8]).is_empty());
+        assert_eq!(parser.push(&payload[8..]), vec!["héllo".to_string()]);
+    }
+
+    #[test]
+    fn test_sse_parser_finish_flushes_unterminated_event() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"data: [DONE]").is_empty());
+        assert_eq!(parser.finish(), Some("[DONE]".to_string()));
+        assert_eq!(parser.finish(), None);
+    }
+
+    #[test]
+    fn test_parse_chat_event() {
+        let content = r#"{"choices":[{"index":0,"delta":{"content":"Hello"}}]}"#;
+        assert_eq!(
+            parse_chat_event(content).unwrap(),
+            Some(ChatStreamEvent::Content("Hello".to_string()))
+        );
+
+        let role = r#"{"choices":[{"index":0,"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(parse_chat_event(role).unwrap(), None);
+
+        let finish = r#"{"choices":[{"index":0,"delta":{},"finish_reason":"stop"}]}"#;
+        assert_eq!(parse_chat_event(finish).unwrap(), None);
+
+        assert_eq!(
+            parse_chat_event("[DONE]").unwrap(),
+            Some(ChatStreamEvent::Done)
+        );
+
+        let error = r#"{"error":{"message":"rate limited"}}"#;
+        assert!(matches!(
+            parse_chat_event(error),
+            Err(AgentBridgeError::StreamError(_))
+        ));
+        assert!(parse_chat_event("not json").is_err());
+    }
+
+    #[test]
+    fn test_sentence_buffer_splits_on_boundaries() {
+        let mut buffer = SentenceBuffer::default();
+        assert!(buffer.push("Sure").is_empty());
+        assert_eq!(buffer.push("! It costs 3."), vec!["Sure!".to_string()]);
+        assert_eq!(
+            buffer.push("5 dollars. "),
+            vec!["It costs 3.5 dollars.".to_string()]
+        );
+        assert!(buffer.push("Anything else?").is_empty());
+        assert_eq!(buffer.finish(), Some("Anything else?".to_string()));
+        assert_eq!(buffer.finish(), None);
+    }
+
+    #[test]
+    fn test_sentence_buffer_full_width_and_newlines() {
+        let mut buffer = SentenceBuffer::default();
+        assert_eq!(
+            buffer.push("你好。请问\n- item"),
+            vec!["你好。".to_string(), "请问".to_string()]
+        );
+        assert_eq!(buffer.finish(), Some("- item".to_string()));
+    }
+
+    #[test]
+    fn test_sentence_buffer_indic_and_long_text() {
+        let mut buffer = SentenceBuffer::new(TextSegmenter::for_language("hi-IN"));
+        assert_eq!(buffer.push("नमस्ते। आप कैसे"), vec!["नमस्ते।".to_string()]);
+        assert_eq!(buffer.push(" हैं?"), Vec::<String>::new());
+        assert_eq!(buffer.push(" ठीक"), vec!["आप कैसे हैं?".to_string()]);
+
+        // Text without terminators is released once it outgrows a chunk
+        let mut buffer = SentenceBuffer::new(TextSegmenter::new(SegmentationProfile::Spaced, 20));
+        assert_eq!(buffer.push("क्षमा कीजिए"), vec!["क्षमा".to_string()]);
+        assert_eq!(buffer.finish(), Some("कीजिए".to_string()));
+    }
+}
@@ -0,0 +1,470 @@
+//! Fault injection for resilience testing
+//!
+//! Staging deployments built with the `chaos` feature can make chosen
+//! sessions misbehave the way real providers do, to check that STT failover,
+//! retries and circuit breakers recover:
+//!
+//! - provider connects that fail
+//! - latency added to every connect, audio frame and speak call
+//! - dropped audio frames, both caller audio on its way to the STT provider
+//!   and synthesized audio on its way back
+//! - malformed provider responses, surfaced as provider errors in place of
+//!   the transcript or audio they replace
+//! - a provider connection that drops some time into the session
+//!
+//! Faults are set at runtime through `PUT /admin/chaos` and only once
+//! `chaos.enabled: true` is in the server config. They apply to a
+//! percentage of new sessions, to one targeted `stream_id`, or both;
+//! sessions that are already open keep the faults they started with.
+//!
+//! Faults are injected into the primary STT and TTS providers, underneath
+//! STT failover, so the secondary provider is what recovers from them.
+//! Every injected fault is counted in [`ChaosStats`], returned by
+//! [`Session::chaos_stats`](crate::core::session::Session::chaos_stats) and
+//! by `GET /admin/chaos` for recently targeted sessions. Which calls fail is
+//! decided by hashing the `stream_id`, so a session replays the same faults.
+
+mod stt;
+mod tts;
+
+pub use stt::ChaosSTT;
+pub use tts::ChaosTTS;
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::{ChaosConfig, rollout_bucket};
+
+/// Targeted sessions whose fault counts `GET /admin/chaos` reports
+const MAX_TRACKED_SESSIONS: usize = 256;
+
+/// Prefix of the messages of injected errors
+pub const CHAOS_ERROR_PREFIX: &str = "chaos:";
+
+/// Faults to inject and the sessions to inject them into
+///
+/// Rates are the probability of each call failing, from 0.0 to 1.0. The
+/// default injects nothing; sending `{}` to `PUT /admin/chaos` stops
+/// injecting into new sessions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct ChaosFaults {
+    /// Percentage of new sessions to inject faults into (0 to 100)
+    #[cfg_attr(feature = "openapi", schema(example = 10.0))]
+    pub session_percentage: f64,
+    /// Session to inject faults into, whatever `session_percentage` is
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Share of provider connects that fail
+    #[cfg_attr(feature = "openapi", schema(example = 0.5))]
+    pub connect_failure_rate: f64,
+    /// Milliseconds added to each connect, audio frame and speak call
+    #[cfg_attr(feature = "openapi", schema(example = 200))]
+    pub latency_ms: u64,
+    /// Share of audio frames dropped, in either direction
+    pub drop_audio_frame_rate: f64,
+    /// Share of transcripts and audio chunks replaced by a provider error
+    pub malformed_response_rate: f64,
+    /// Drop each provider connection this long after it is established
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = 30000))]
+    pub disconnect_after_ms: Option<u64>,
+}
+
+impl ChaosFaults {
+    /// Check that rates and the session percentage are in range
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=100.0).contains(&self.session_percentage) {
+            return Err(format!(
+                "session_percentage must be between 0 and 100, got {}",
+                self.session_percentage
+            ));
+        }
+        for (name, rate) in [
+            ("connect_failure_rate", self.connect_failure_rate),
+            ("drop_audio_frame_rate", self.drop_audio_frame_rate),
+            ("malformed_response_rate", self.malformed_response_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!("{name} must be between 0.0 and 1.0, got {rate}"));
+            }
+        }
+        if self.session_id.as_deref() == Some("") {
+            return Err("session_id must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether any fault is set
+    pub fn injects_faults(&self) -> bool {
+        self.connect_failure_rate > 0.0
+            || self.latency_ms > 0
+            || self.drop_audio_frame_rate > 0.0
+            || self.malformed_response_rate > 0.0
+            || self.disconnect_after_ms.is_some()
+    }
+
+    /// Whether a new session with `stream_id` gets the faults
+    fn targets(&self, stream_id: &str) -> bool {
+        self.session_id.as_deref() == Some(stream_id)
+            || (self.session_percentage > 0.0
+                && rollout_bucket("chaos", stream_id) < self.session_percentage)
+    }
+}
+
+/// Faults injected into one session so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChaosStats {
+    /// Provider connects failed
+    pub connect_failures: u64,
+    /// Connects, audio frames and speak calls delayed
+    pub delayed_calls: u64,
+    /// Audio frames dropped
+    pub dropped_audio_frames: u64,
+    /// Transcripts and audio chunks replaced by a provider error
+    pub malformed_responses: u64,
+    /// Provider connections dropped mid-session
+    pub disconnects: u64,
+}
+
+/// A fault the chaos layer injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    ConnectFailure,
+    Latency,
+    DroppedAudioFrame,
+    MalformedResponse,
+    Disconnect,
+}
+
+/// Faults of one targeted session and what was injected so far
+#[derive(Debug)]
+pub struct SessionChaos {
+    stream_id: String,
+    faults: ChaosFaults,
+    rolls: AtomicU64,
+    connect_failures: AtomicU64,
+    delayed_calls: AtomicU64,
+    dropped_audio_frames: AtomicU64,
+    malformed_responses: AtomicU64,
+    disconnects: AtomicU64,
+}
+
+impl SessionChaos {
+    /// Inject `faults` into the session with `stream_id`
+    pub fn new(stream_id: impl Into<String>, faults: ChaosFaults) -> Self {
+        Self {
+            stream_id: stream_id.into(),
+            faults,
+            rolls: AtomicU64::new(0),
+            connect_failures: AtomicU64::new(0),
+            delayed_calls: AtomicU64::new(0),
+            dropped_audio_frames: AtomicU64::new(0),
+            malformed_responses: AtomicU64::new(0),
+            disconnects: AtomicU64::new(0),
+        }
+    }
+
+    /// The session's stream id
+    pub fn stream_id(&self) -> &str {
+        &self.stream_id
+    }
+
+    /// The faults injected into the session
+    pub fn faults(&self) -> &ChaosFaults {
+        &self.faults
+    }
+
+    /// Faults injected so far
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            connect_failures: self.connect_failures.load(Ordering::Relaxed),
+            delayed_calls: self.delayed_calls.load(Ordering::Relaxed),
+            dropped_audio_frames: self.dropped_audio_frames.load(Ordering::Relaxed),
+            malformed_responses: self.malformed_responses.load(Ordering::Relaxed),
+            disconnects: self.disconnects.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Whether the next call fails, with probability `rate`
+    fn roll(&self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+        let n = self.rolls.fetch_add(1, Ordering::Relaxed);
+        rollout_bucket(&self.stream_id, &n.to_string()) < rate * 100.0
+    }
+
+    fn record(&self, fault: Fault) {
+        let counter = match fault {
+            Fault::ConnectFailure => &self.connect_failures,
+            Fault::Latency => &self.delayed_calls,
+            Fault::DroppedAudioFrame => &self.dropped_audio_frames,
+            Fault::MalformedResponse => &self.malformed_responses,
+            Fault::Disconnect => &self.disconnects,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Whether to fail the connect about to happen
+    fn fail_connect(&self) -> bool {
+        let fail = self.roll(self.faults.connect_failure_rate);
+        if fail {
+            self.record(Fault::ConnectFailure);
+        }
+        fail
+    }
+
+    /// Sleep for the configured latency, if any
+    async fn delay(&self) {
+        if self.faults.latency_ms > 0 {
+            self.record(Fault::Latency);
+            tokio::time::sleep(Duration::from_millis(self.faults.latency_ms)).await;
+        }
+    }
+
+    /// Whether to drop the audio frame at hand
+    fn drop_audio_frame(&self) -> bool {
+        let drop = self.roll(self.faults.drop_audio_frame_rate);
+        if drop {
+            self.record(Fault::DroppedAudioFrame);
+        }
+        drop
+    }
+
+    /// Whether to replace the response at hand with a provider error
+    fn malform_response(&self) -> bool {
+        let malform = self.roll(self.faults.malformed_response_rate);
+        if malform {
+            self.record(Fault::MalformedResponse);
+        }
+        malform
+    }
+
+    /// How long after connecting to drop the connection
+    fn disconnect_after(&self) -> Option<Duration> {
+        self.faults.disconnect_after_ms.map(Duration::from_millis)
+    }
+}
+
+/// Faults injected into a recently targeted session
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChaosSessionStats {
+    /// The session's stream id
+    pub stream_id: String,
+    /// Faults injected so far
+    pub stats: ChaosStats,
+}
+
+/// Current faults and what was injected into recent sessions
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChaosStatus {
+    /// Whether `chaos.enabled` is set in the server config
+    pub enabled: bool,
+    /// Faults new sessions are targeted with
+    pub faults: ChaosFaults,
+    /// Recently targeted sessions, oldest first
+    pub sessions: Vec<ChaosSessionStats>,
+}
+
+/// Decides which new sessions get faults injected
+///
+/// Shared through `AppState`; `PUT /admin/chaos` replaces the faults.
+#[derive(Debug)]
+pub struct ChaosController {
+    enabled: bool,
+    faults: RwLock<ChaosFaults>,
+    sessions: Mutex<VecDeque<Arc<SessionChaos>>>,
+}
+
+impl ChaosController {
+    /// Create a controller that injects nothing until faults are set
+    pub fn new(config: ChaosConfig) -> Self {
+        if config.enabled {
+            warn!("Chaos fault injection is enabled; do not run this build in production");
+        }
+        Self {
+            enabled: config.enabled,
+            faults: RwLock::new(ChaosFaults::default()),
+            sessions: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Whether `chaos.enabled` is set in the server config
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Replace the faults new sessions are targeted with
+    ///
+    /// # Returns
+    /// * `Result<(), String>` - Error when chaos is not enabled in the server
+    ///   config or the faults are out of range
+    pub fn update(&self, faults: ChaosFaults) -> Result<(), String> {
+        if !self.enabled {
+            return Err(
+                "chaos fault injection is disabled; set chaos.enabled: true in the server config"
+                    .to_string(),
+            );
+        }
+        faults.validate()?;
+        if faults.injects_faults() {
+            warn!(?faults, "Chaos faults set");
+        }
+        *self.faults.write() = faults;
+        Ok(())
+    }
+
+    /// Faults for a new session, if it is targeted
+    pub fn session(&self, stream_id: &str) -> Option<Arc<SessionChaos>> {
+        if !self.enabled {
+            return None;
+        }
+        let faults = self.faults.read().clone();
+        if !faults.injects_faults() || !faults.targets(stream_id) {
+            return None;
+        }
+
+        warn!(stream_id, "Injecting chaos faults into session");
+        let session = Arc::new(SessionChaos::new(stream_id, faults));
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|tracked| tracked.stream_id != stream_id);
+        if sessions.len() >= MAX_TRACKED_SESSIONS {
+            sessions.pop_front();
+        }
+        sessions.push_back(session.clone());
+        Some(session)
+    }
+
+    /// Current faults and the faults injected into recent sessions
+    pub fn status(&self) -> ChaosStatus {
+        ChaosStatus {
+            enabled: self.enabled,
+            faults: self.faults.read().clone(),
+            sessions: self
+                .sessions
+                .lock()
+                .iter()
+                .map(|session| ChaosSessionStats {
+                    stream_id: session.stream_id.clone(),
+                    stats: session.stats(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> ChaosController {
+        ChaosController::new(ChaosConfig { enabled: true })
+    }
+
+    #[test]
+    fn test_disabled_controller_refuses_faults() {
+        let controller = ChaosController::new(ChaosConfig::default());
+        let err = controller
+            .update(ChaosFaults {
+                session_percentage: 100.0,
+                connect_failure_rate: 1.0,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err.contains("chaos.enabled"), "{err}");
+        assert!(controller.session("stream-1").is_none());
+    }
+
+    #[test]
+    fn test_faults_are_validated() {
+        let controller = enabled();
+        for faults in [
+            ChaosFaults {
+                session_percentage: 101.0,
+                ..Default::default()
+            },
+            ChaosFaults {
+                drop_audio_frame_rate: 1.5,
+                ..Default::default()
+            },
+            ChaosFaults {
+                session_id: Some(String::new()),
+                ..Default::default()
+            },
+        ] {
+            assert!(controller.update(faults).is_err());
+        }
+        assert_eq!(controller.status().faults, ChaosFaults::default());
+    }
+
+    #[test]
+    fn test_sessions_are_targeted() {
+        let controller = enabled();
+        controller
+            .update(ChaosFaults {
+                session_id: Some("target".to_string()),
+                latency_ms: 50,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(controller.session("target").is_some());
+        assert!(controller.session("bystander").is_none());
+
+        controller
+            .update(ChaosFaults {
+                session_percentage: 100.0,
+                latency_ms: 50,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(controller.session("bystander").is_some());
+
+        // Targeting alone injects nothing
+        controller
+            .update(ChaosFaults {
+                session_percentage: 100.0,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(controller.session("idle").is_none());
+
+        let tracked: Vec<_> = controller
+            .status()
+            .sessions
+            .into_iter()
+            .map(|session| session.stream_id)
+            .collect();
+        assert_eq!(tracked, ["target", "bystander"]);
+    }
+
+    #[test]
+    fn test_rolls_follow_rate() {
+        let session = SessionChaos::new(
+            "stream-1",
+            ChaosFaults {
+                drop_audio_frame_rate: 0.25,
+                connect_failure_rate: 1.0,
+                ..Default::default()
+            },
+        );
+        let dropped = (0..1000).filter(|_| session.drop_audio_frame()).count();
+        assert!((150..350).contains(&dropped), "{dropped}");
+        assert!(session.fail_connect());
+        assert!(!session.malform_response());
+
+        let stats = session.stats();
+        assert_eq!(stats.dropped_audio_frames, dropped as u64);
+        assert_eq!(stats.connect_failures, 1);
+        assert_eq!(stats.malformed_responses, 0);
+    }
+}
@@ -0,0 +1,183 @@
+//! Fault-injecting STT provider wrapper
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::{CHAOS_ERROR_PREFIX, Fault, SessionChaos};
+use crate::core::stt::{
+    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResultCallback, STTVadCallback,
+};
+
+/// STT provider that fails the way the session's [`SessionChaos`] says
+///
+/// A dropped connection makes the provider report not ready, fail every
+/// `send_audio` and notify the error callback, until it is connected again.
+pub struct ChaosSTT {
+    inner: Box<dyn BaseSTT>,
+    chaos: Arc<SessionChaos>,
+    error_callback: Arc<RwLock<Option<STTErrorCallback>>>,
+    dropped: Arc<AtomicBool>,
+    disconnect_timer: Option<JoinHandle<()>>,
+}
+
+impl ChaosSTT {
+    /// Inject `chaos` into `inner`
+    pub fn wrap(inner: Box<dyn BaseSTT>, chaos: Arc<SessionChaos>) -> Self {
+        Self {
+            inner,
+            chaos,
+            error_callback: Arc::new(RwLock::new(None)),
+            dropped: Arc::new(AtomicBool::new(false)),
+            disconnect_timer: None,
+        }
+    }
+
+    /// Drop the connection once the configured time has passed
+    fn schedule_disconnect(&mut self) {
+        self.cancel_disconnect();
+        let Some(after) = self.chaos.disconnect_after() else {
+            return;
+        };
+        let chaos = self.chaos.clone();
+        let dropped = self.dropped.clone();
+        let error_callback = self.error_callback.clone();
+        self.disconnect_timer = Some(tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+            warn!(
+                stream_id = chaos.stream_id(),
+                "Chaos: dropping STT connection"
+            );
+            dropped.store(true, Ordering::Release);
+            chaos.record(Fault::Disconnect);
+            let callback = error_callback.read().clone();
+            if let Some(callback) = callback {
+                callback(STTError::ConnectionFailed(format!(
+                    "{CHAOS_ERROR_PREFIX} injected STT disconnect"
+                )))
+                .await;
+            }
+        }));
+    }
+
+    fn cancel_disconnect(&mut self) {
+        if let Some(timer) = self.disconnect_timer.take() {
+            timer.abort();
+        }
+    }
+}
+
+impl Drop for ChaosSTT {
+    fn drop(&mut self) {
+        self.cancel_disconnect();
+    }
+}
+
+#[async_trait]
+impl BaseSTT for ChaosSTT {
+    /// Not supported: the chaos layer wraps a provider, see [`ChaosSTT::wrap`]
+    fn new(_config: STTConfig) -> Result<Self, STTError> {
+        Err(STTError::ConfigurationError(
+            "ChaosSTT wraps a provider; use ChaosSTT::wrap".to_string(),
+        ))
+    }
+
+    async fn connect(&mut self) -> Result<(), STTError> {
+        self.chaos.delay().await;
+        if self.chaos.fail_connect() {
+            return Err(STTError::ConnectionFailed(format!(
+                "{CHAOS_ERROR_PREFIX} injected STT connect failure"
+            )));
+        }
+        self.inner.connect().await?;
+        self.dropped.store(false, Ordering::Release);
+        self.schedule_disconnect();
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), STTError> {
+        self.cancel_disconnect();
+        self.inner.disconnect().await
+    }
+
+    fn is_ready(&self) -> bool {
+        !self.dropped.load(Ordering::Acquire) && self.inner.is_ready()
+    }
+
+    async fn send_audio(&mut self, audio_data: Bytes) -> Result<(), STTError> {
+        if self.dropped.load(Ordering::Acquire) {
+            if self.inner.is_ready()
+                && let Err(e) = self.inner.disconnect().await
+            {
+                debug!("Chaos: failed to close the dropped STT connection: {e}");
+            }
+            return Err(STTError::ConnectionFailed(format!(
+                "{CHAOS_ERROR_PREFIX} STT connection dropped"
+            )));
+        }
+        if self.chaos.drop_audio_frame() {
+            return Ok(());
+        }
+        self.chaos.delay().await;
+        self.inner.send_audio(audio_data).await
+    }
+
+    async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+        let chaos = self.chaos.clone();
+        let error_callback = self.error_callback.clone();
+        self.inner
+            .on_result(Arc::new(move |result| {
+                let callback = callback.clone();
+                let malformed = chaos.malform_response();
+                let error_callback = error_callback.read().clone();
+                Box::pin(async move {
+                    if !malformed {
+                        callback(result).await;
+                    } else if let Some(error_callback) = error_callback {
+                        error_callback(STTError::ProviderError(format!(
+                            "{CHAOS_ERROR_PREFIX} malformed STT response"
+                        )))
+                        .await;
+                    }
+                })
+            }))
+            .await
+    }
+
+    async fn on_error(&mut self, callback: STTErrorCallback) -> Result<(), STTError> {
+        *self.error_callback.write() = Some(callback.clone());
+        self.inner.on_error(callback).await
+    }
+
+    fn get_config(&self) -> Option<&STTConfig> {
+        self.inner.get_config()
+    }
+
+    async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+        self.inner.update_config(config).await
+    }
+
+    fn get_provider_info(&self) -> &'static str {
+        self.inner.get_provider_info()
+    }
+
+    async fn set_end_of_turn_silence(&mut self, silence_ms: u32) -> Result<bool, STTError> {
+        self.inner.set_end_of_turn_silence(silence_ms).await
+    }
+
+    async fn on_vad_event(&mut self, callback: STTVadCallback) -> Result<bool, STTError> {
+        self.inner.on_vad_event(callback).await
+    }
+
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        self.inner.finalize().await
+    }
+
+    async fn set_manual_commit(&mut self, enabled: bool) -> Result<bool, STTError> {
+        self.inner.set_manual_commit(enabled).await
+    }
+}
@@ -0,0 +1,215 @@
+//! Fault-injecting TTS provider wrapper
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::{CHAOS_ERROR_PREFIX, Fault, SessionChaos};
+use crate::core::tts::provider::TTSProvider;
+use crate::core::tts::{
+    AudioCallback, AudioData, BaseTTS, ConnectionState, TTSConfig, TTSError, TTSResult,
+    TTSTimingEvent,
+};
+use crate::utils::req_manager::ReqManager;
+
+/// TTS provider that fails the way the session's [`SessionChaos`] says
+///
+/// A dropped connection makes the provider report not ready, fail every
+/// `speak` and notify the audio callback's `on_error`, until it is connected
+/// again.
+pub struct ChaosTTS {
+    inner: Box<dyn BaseTTS>,
+    chaos: Arc<SessionChaos>,
+    audio_callback: Arc<RwLock<Option<Arc<dyn AudioCallback>>>>,
+    dropped: Arc<AtomicBool>,
+    disconnect_timer: Option<JoinHandle<()>>,
+}
+
+impl ChaosTTS {
+    /// Inject `chaos` into `inner`
+    pub fn wrap(inner: Box<dyn BaseTTS>, chaos: Arc<SessionChaos>) -> Self {
+        Self {
+            inner,
+            chaos,
+            audio_callback: Arc::new(RwLock::new(None)),
+            dropped: Arc::new(AtomicBool::new(false)),
+            disconnect_timer: None,
+        }
+    }
+
+    /// Drop the connection once the configured time has passed
+    fn schedule_disconnect(&mut self) {
+        self.cancel_disconnect();
+        let Some(after) = self.chaos.disconnect_after() else {
+            return;
+        };
+        let chaos = self.chaos.clone();
+        let dropped = self.dropped.clone();
+        let audio_callback = self.audio_callback.clone();
+        self.disconnect_timer = Some(tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+            warn!(
+                stream_id = chaos.stream_id(),
+                "Chaos: dropping TTS connection"
+            );
+            dropped.store(true, Ordering::Release);
+            chaos.record(Fault::Disconnect);
+            let callback = audio_callback.read().clone();
+            if let Some(callback) = callback {
+                callback
+                    .on_error(TTSError::ConnectionFailed(format!(
+                        "{CHAOS_ERROR_PREFIX} injected TTS disconnect"
+                    )))
+                    .await;
+            }
+        }));
+    }
+
+    fn cancel_disconnect(&mut self) {
+        if let Some(timer) = self.disconnect_timer.take() {
+            timer.abort();
+        }
+    }
+}
+
+impl Drop for ChaosTTS {
+    fn drop(&mut self) {
+        self.cancel_disconnect();
+    }
+}
+
+/// Audio callback that drops chunks and replaces others with errors
+struct ChaosAudioCallback {
+    inner: Arc<dyn AudioCallback>,
+    chaos: Arc<SessionChaos>,
+}
+
+impl AudioCallback for ChaosAudioCallback {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            if self.chaos.drop_audio_frame() {
+                return;
+            }
+            if self.chaos.malform_response() {
+                self.inner
+                    .on_error(TTSError::ProviderError(format!(
+                        "{CHAOS_ERROR_PREFIX} malformed TTS response"
+                    )))
+                    .await;
+                return;
+            }
+            self.inner.on_audio(audio_data).await;
+        })
+    }
+
+    fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        self.inner.on_error(error)
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        self.inner.on_complete()
+    }
+
+    fn on_timing(
+        &self,
+        events: Vec<TTSTimingEvent>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        self.inner.on_timing(events)
+    }
+}
+
+#[async_trait]
+impl BaseTTS for ChaosTTS {
+    /// Not supported: the chaos layer wraps a provider, see [`ChaosTTS::wrap`]
+    fn new(_config: TTSConfig) -> TTSResult<Self> {
+        Err(TTSError::InvalidConfiguration(
+            "ChaosTTS wraps a provider; use ChaosTTS::wrap".to_string(),
+        ))
+    }
+
+    fn get_provider(&mut self) -> Option<&mut TTSProvider> {
+        self.inner.get_provider()
+    }
+
+    async fn connect(&mut self) -> TTSResult<()> {
+        self.chaos.delay().await;
+        if self.chaos.fail_connect() {
+            return Err(TTSError::ConnectionFailed(format!(
+                "{CHAOS_ERROR_PREFIX} injected TTS connect failure"
+            )));
+        }
+        self.inner.connect().await?;
+        self.dropped.store(false, Ordering::Release);
+        self.schedule_disconnect();
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> TTSResult<()> {
+        self.cancel_disconnect();
+        self.inner.disconnect().await
+    }
+
+    fn is_ready(&self) -> bool {
+        !self.dropped.load(Ordering::Acquire) && self.inner.is_ready()
+    }
+
+    fn get_connection_state(&self) -> ConnectionState {
+        if self.dropped.load(Ordering::Acquire) {
+            return ConnectionState::Disconnected;
+        }
+        self.inner.get_connection_state()
+    }
+
+    async fn speak(&mut self, text: &str, flush: bool) -> TTSResult<()> {
+        if self.dropped.load(Ordering::Acquire) {
+            if self.inner.is_ready()
+                && let Err(e) = self.inner.disconnect().await
+            {
+                debug!("Chaos: failed to close the dropped TTS connection: {e}");
+            }
+            return Err(TTSError::ProviderNotReady(format!(
+                "{CHAOS_ERROR_PREFIX} TTS connection dropped"
+            )));
+        }
+        self.chaos.delay().await;
+        self.inner.speak(text, flush).await
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        self.inner.clear().await
+    }
+
+    async fn flush(&self) -> TTSResult<()> {
+        self.inner.flush().await
+    }
+
+    fn on_audio(&mut self, callback: Arc<dyn AudioCallback>) -> TTSResult<()> {
+        *self.audio_callback.write() = Some(callback.clone());
+        self.inner.on_audio(Arc::new(ChaosAudioCallback {
+            inner: callback,
+            chaos: self.chaos.clone(),
+        }))
+    }
+
+    fn remove_audio_callback(&mut self) -> TTSResult<()> {
+        *self.audio_callback.write() = None;
+        self.inner.remove_audio_callback()
+    }
+
+    fn get_provider_info(&self) -> serde_json::Value {
+        self.inner.get_provider_info()
+    }
+
+    async fn validate_voice(&self) -> TTSResult<()> {
+        self.inner.validate_voice().await
+    }
+
+    async fn set_req_manager(&mut self, req_manager: Arc<ReqManager>) {
+        self.inner.set_req_manager(req_manager).await
+    }
+}
@@ -0,0 +1,99 @@
+//! Lightweight provider credential checks.
+//!
+//! Providers expose a `validate_credentials(api_key)` associated function that
+//! issues a cheap authenticated request (e.g. listing models or projects) and
+//! maps the response onto `Ok(())` or a human-readable error. This module holds
+//! the shared request execution and response classification, and the checks
+//! of providers that are only available as plugins.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// Error message returned when the provider rejects the API key.
+pub const INVALID_API_KEY: &str = "Invalid API key";
+
+/// Timeout for a single credential check request.
+const CREDENTIAL_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Resemble API base URL.
+const RESEMBLE_API_BASE_URL: &str = "https://app.resemble.ai";
+
+/// Build an HTTP client for credential checks.
+pub(crate) fn credential_check_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(CREDENTIAL_CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+/// Send an authenticated request and classify the provider's response.
+///
+/// # Returns
+/// * `Ok(())` on any 2xx response
+/// * `Err(INVALID_API_KEY)` on 401 or 403
+/// * `Err(String)` describing any other failure
+pub(crate) async fn check_credentials(request: reqwest::RequestBuilder) -> Result<(), String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach provider: {e}"))?;
+
+    classify_status(response.status())
+}
+
+/// Credential check for Resemble, whose TTS provider is loaded as a plugin.
+pub struct ResembleCredentials;
+
+impl ResembleCredentials {
+    /// Validate an API key by listing a single voice.
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(RESEMBLE_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/api/v2/voices", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .query(&[("page", "1"), ("page_size", "1")])
+                .bearer_auth(api_key),
+        )
+        .await
+    }
+}
+
+fn classify_status(status: StatusCode) -> Result<(), String> {
+    if status.is_success() {
+        Ok(())
+    } else if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        Err(INVALID_API_KEY.to_string())
+    } else {
+        Err(format!("Unexpected response from provider: {status}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_status() {
+        assert!(classify_status(StatusCode::OK).is_ok());
+        assert_eq!(
+            classify_status(StatusCode::UNAUTHORIZED),
+            Err(INVALID_API_KEY.to_string())
+        );
+        assert_eq!(
+            classify_status(StatusCode::FORBIDDEN),
+            Err(INVALID_API_KEY.to_string())
+        );
+        assert!(
+            classify_status(StatusCode::INTERNAL_SERVER_ERROR)
+                .unwrap_err()
+                .contains("500")
+        );
+    }
+}
@@ -1,11 +1,20 @@
+pub mod agent_bridge;
 pub mod cache;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod credentials;
 pub mod emotion;
 pub mod providers;
 pub mod realtime;
+pub mod redaction;
+pub mod session;
+pub mod sink_audio;
 pub mod state;
 pub mod stt;
+pub mod template;
 pub mod tts;
 pub mod turn_detect;
+pub mod validation;
 pub mod voice_manager;
 
 #[cfg(feature = "turn-detect")]
@@ -22,7 +31,7 @@ pub use stt::{
 
 pub use tts::{
     AudioCallback, AudioData, BaseTTS, BoxedTTS, ConnectionState, DeepgramTTS, TTSConfig, TTSError,
-    TTSFactory, TTSResult, create_tts_provider, get_tts_provider_urls,
+    TTSFactory, TTSOutputProfile, TTSResult, create_tts_provider, get_tts_provider_urls,
 };
 
 pub use realtime::{
@@ -36,9 +45,15 @@ pub use voice_manager::{
     VoiceManagerError, VoiceManagerResult,
 };
 
+pub use session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder, SessionResult,
+};
+
 // Re-export CoreState for external use
 pub use state::CoreState;
 
+pub use validation::ConfigIssue;
+
 // Re-export emotion types for convenience
 pub use emotion::{
     DeliveryStyle, Emotion, EmotionConfig, EmotionIntensity, EmotionMapper, EmotionMethod,
@@ -31,9 +31,20 @@
 //! 1. POST to the token endpoint with your subscription key
 //! 2. Use the returned token in the `Authorization: Bearer {token}` header
 //!
+//! [`azure_token_provider`] wraps the exchange in a [`TokenProvider`] that
+//! caches the token and fetches a new one before it expires. Sessions using
+//! the same key and region share one provider.
+//!
 //! See: <https://learn.microsoft.com/en-us/azure/ai-services/speech-service/rest-speech-to-text#authentication>
 
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
 use super::AzureRegion;
+use crate::core::providers::credential_health::credential_health;
+use crate::core::providers::token::{AccessToken, TokenError, TokenFetcher, TokenProvider};
 
 /// The HTTP header name for Azure subscription key authentication.
 ///
@@ -142,9 +153,91 @@ pub fn build_token_request_url(region: &AzureRegion) -> String {
     region.token_endpoint()
 }
 
+/// Lifetime of tokens issued by the Azure token endpoint.
+///
+/// The endpoint returns the token as plain text without an expiry, so the
+/// documented ten minute validity is assumed.
+pub const AZURE_TOKEN_LIFETIME: Duration = Duration::from_secs(600);
+
+/// Exchanges an Azure subscription key for short-lived access tokens.
+pub struct AzureTokenFetcher {
+    subscription_key: String,
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl AzureTokenFetcher {
+    /// Create a fetcher using the token endpoint of `region`.
+    pub fn new(subscription_key: impl Into<String>, region: &AzureRegion) -> Self {
+        Self::with_endpoint(subscription_key, build_token_request_url(region))
+    }
+
+    /// Create a fetcher that requests tokens from `endpoint`.
+    pub fn with_endpoint(subscription_key: impl Into<String>, endpoint: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            subscription_key: subscription_key.into(),
+            endpoint: endpoint.into(),
+            client,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenFetcher for AzureTokenFetcher {
+    async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .header(AZURE_SUBSCRIPTION_KEY_HEADER, &self.subscription_key)
+            .header("Content-Length", "0")
+            .send()
+            .await
+            .map_err(|e| {
+                TokenError::RequestFailed(format!("Failed to request Azure token: {e}"))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("Azure token request failed ({status}): {body}");
+            return Err(match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => TokenError::Rejected(message),
+                _ => TokenError::RequestFailed(message),
+            });
+        }
+
+        let token = response
+            .text()
+            .await
+            .map_err(|e| TokenError::RequestFailed(format!("Failed to read Azure token: {e}")))?;
+
+        Ok(AccessToken {
+            token: token.trim().to_string(),
+            lifetime: AZURE_TOKEN_LIFETIME,
+        })
+    }
+}
+
+/// Shared token provider for an Azure subscription key in `region`.
+pub fn azure_token_provider(subscription_key: &str, region: &AzureRegion) -> Arc<TokenProvider> {
+    credential_health().token_provider(
+        &format!("azure:{}", region.as_str()),
+        subscription_key,
+        || AzureTokenFetcher::new(subscription_key, region),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[test]
     fn test_subscription_key_header_constant() {
@@ -207,4 +300,36 @@ mod tests {
             assert_eq!(build_token_request_url(&region), expected_url);
         }
     }
+
+    #[tokio::test]
+    async fn test_azure_token_fetcher() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/sts/v1.0/issueToken"))
+            .and(header(AZURE_SUBSCRIPTION_KEY_HEADER, "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("azure-token\n"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = TokenProvider::new(AzureTokenFetcher::with_endpoint(
+            "test-key",
+            format!("{}/sts/v1.0/issueToken", server.uri()),
+        ));
+        assert_eq!(provider.token().await.unwrap(), "azure-token");
+        assert_eq!(provider.token().await.unwrap(), "azure-token");
+    }
+
+    #[tokio::test]
+    async fn test_azure_token_fetcher_rejected_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let fetcher = AzureTokenFetcher::with_endpoint("bad-key", server.uri());
+        let err = fetcher.fetch_token().await.unwrap_err();
+        assert!(matches!(err, TokenError::Rejected(ref msg) if msg.contains("401")));
+    }
 }
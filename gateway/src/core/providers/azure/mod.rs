@@ -51,7 +51,8 @@ pub mod region;
 
 // Re-export commonly used types
 pub use auth::{
-    AZURE_AUTHORIZATION_HEADER, AZURE_SUBSCRIPTION_KEY_HEADER, build_bearer_token_header,
+    AZURE_AUTHORIZATION_HEADER, AZURE_SUBSCRIPTION_KEY_HEADER, AZURE_TOKEN_LIFETIME,
+    AzureTokenFetcher, azure_token_provider, build_bearer_token_header,
     build_subscription_key_header, build_token_request_url,
 };
 pub use region::AzureRegion;
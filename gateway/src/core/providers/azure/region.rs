@@ -216,6 +216,32 @@ impl AzureRegion {
         )
     }
 
+    /// Get the WebSocket synthesis URL for the Azure Text-to-Speech Service.
+    ///
+    /// Format: `wss://<region>.tts.speech.microsoft.com/cognitiveservices/websocket/v1`
+    ///
+    /// Unlike the REST endpoint, this one also reports viseme and word
+    /// boundary events.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use waav_gateway::core::providers::azure::AzureRegion;
+    ///
+    /// let region = AzureRegion::EastUS;
+    /// assert_eq!(
+    ///     region.tts_websocket_url(),
+    ///     "wss://eastus.tts.speech.microsoft.com/cognitiveservices/websocket/v1"
+    /// );
+    /// ```
+    #[inline]
+    pub fn tts_websocket_url(&self) -> String {
+        format!(
+            "wss://{}.tts.speech.microsoft.com/cognitiveservices/websocket/v1",
+            self.as_str()
+        )
+    }
+
     /// Get the voices list endpoint URL for the Azure Text-to-Speech Service.
     ///
     /// Format: `https://<region>.tts.speech.microsoft.com/cognitiveservices/voices/list`
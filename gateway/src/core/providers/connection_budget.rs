@@ -0,0 +1,547 @@
+//! Concurrent connection budgets shared across products.
+//!
+//! Some providers limit concurrent connections per API key across all of
+//! their products: Deepgram counts STT websockets and TTS sessions against
+//! the same limit, so TTS traffic can use up the budget and make STT
+//! connects fail with opaque errors. [`ConnectionBudgets`] tracks the open
+//! connections of each key process-wide:
+//!
+//! - **Permits**: a provider acquires a [`ConnectionPermit`] before it
+//!   connects and holds it for the lifetime of the connection; dropping the
+//!   permit releases it
+//! - **Policy**: once the budget is used up, new connections fail fast or
+//!   wait in a queue for a permit, as configured per provider
+//!
+//! Like the credential health cache, budgets are keyed by provider and key
+//! fingerprint, never the key itself. Providers without a configured budget
+//! are tracked but never limited.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use super::credential_health::key_fingerprint;
+
+/// Budget shared by the Deepgram STT and TTS providers
+pub const DEEPGRAM_CONNECTION_BUDGET: &str = "deepgram";
+
+/// Keys tracked at once; the least recently used idle key is evicted beyond this
+pub const MAX_TRACKED_KEYS: usize = 1024;
+
+/// What happens to a new connection once the budget is used up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionBudgetPolicy {
+    /// Fail the connect immediately
+    #[default]
+    FailFast,
+    /// Wait up to `queue_timeout_ms` for another connection to close
+    Queue,
+}
+
+/// Concurrent connection limit of one provider's API keys
+///
+/// # Example
+/// ```yaml
+/// providers:
+///   deepgram_connection_budget:
+///     max_connections: 50
+///     policy: queue
+///     queue_timeout_ms: 3000
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConnectionBudgetConfig {
+    /// Connections each key may hold open at once (unlimited when None)
+    pub max_connections: Option<usize>,
+    /// What happens to connections past the limit
+    pub policy: ConnectionBudgetPolicy,
+    /// Longest wait for a permit with the `queue` policy
+    pub queue_timeout_ms: u64,
+}
+
+impl Default for ConnectionBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: None,
+            policy: ConnectionBudgetPolicy::FailFast,
+            queue_timeout_ms: 5000,
+        }
+    }
+}
+
+impl ConnectionBudgetConfig {
+    /// Validate the limits
+    ///
+    /// # Returns
+    /// * `Ok(())` if the limit and queue timeout are non-zero
+    /// * `Err(String)` naming the zero value otherwise
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_connections == Some(0) {
+            return Err("max_connections must be greater than 0".to_string());
+        }
+        if self.policy == ConnectionBudgetPolicy::Queue && self.queue_timeout_ms == 0 {
+            return Err("queue_timeout_ms must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A connection refused because its key's budget is used up
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "{provider} connection budget exhausted for key {fingerprint}: {in_use} of {limit} connections in use{}",
+    waited.map(|d| format!(" after waiting {}ms", d.as_millis())).unwrap_or_default()
+)]
+pub struct ConnectionBudgetExhausted {
+    /// Budget the connection was counted against
+    pub provider: String,
+    /// Fingerprint of the API key
+    pub fingerprint: String,
+    /// Connections open when the connect gave up
+    pub in_use: usize,
+    /// Configured limit
+    pub limit: usize,
+    /// How long the connect waited in the queue
+    pub waited: Option<Duration>,
+}
+
+/// Current usage of one key's connection budget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConnectionBudgetStatus {
+    #[cfg_attr(feature = "openapi", schema(example = "deepgram"))]
+    pub provider: String,
+    /// Fingerprint of the API key
+    #[cfg_attr(feature = "openapi", schema(example = "3f2a9c1e7b40d855"))]
+    pub fingerprint: String,
+    /// Open connections
+    pub in_use: usize,
+    /// Configured limit (unlimited when absent)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Connects waiting for a permit
+    pub queued: usize,
+    /// Most connections open at once
+    pub peak: usize,
+    /// Connects refused because the budget was used up
+    pub rejected: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BudgetKey {
+    provider: String,
+    fingerprint: String,
+}
+
+struct BudgetEntry {
+    in_use: usize,
+    queued: usize,
+    peak: usize,
+    rejected: u64,
+    released: Arc<Notify>,
+    last_used: Instant,
+}
+
+impl BudgetEntry {
+    fn new() -> Self {
+        Self {
+            in_use: 0,
+            queued: 0,
+            peak: 0,
+            rejected: 0,
+            released: Arc::new(Notify::new()),
+            last_used: Instant::now(),
+        }
+    }
+}
+
+/// Process-wide record of open provider connections per API key
+#[derive(Default)]
+pub struct ConnectionBudgets {
+    configs: Mutex<HashMap<String, ConnectionBudgetConfig>>,
+    entries: Mutex<HashMap<BudgetKey, BudgetEntry>>,
+}
+
+impl ConnectionBudgets {
+    /// Create a tracker without any limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the budget of `provider`'s keys
+    ///
+    /// Connections already open keep their permits; the new limit applies to
+    /// the next connect.
+    pub fn configure(&self, provider: &str, config: ConnectionBudgetConfig) {
+        self.configs.lock().insert(provider.to_string(), config);
+        // A raised limit may admit queued connects
+        for (key, entry) in self.entries.lock().iter() {
+            if key.provider == provider {
+                entry.released.notify_waiters();
+            }
+        }
+    }
+
+    /// The budget of `provider`'s keys
+    pub fn config(&self, provider: &str) -> ConnectionBudgetConfig {
+        self.configs
+            .lock()
+            .get(provider)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Take a permit for one connection of `api_key`
+    ///
+    /// # Returns
+    /// * `Ok(ConnectionPermit)` - Hold it for as long as the connection is open
+    /// * `Err(ConnectionBudgetExhausted)` - The budget is used up and the
+    ///   policy is `fail_fast`, or no permit was freed within the queue timeout
+    pub async fn acquire(
+        self: &Arc<Self>,
+        provider: &str,
+        api_key: &str,
+    ) -> Result<ConnectionPermit, ConnectionBudgetExhausted> {
+        let key = BudgetKey {
+            provider: provider.to_string(),
+            fingerprint: key_fingerprint(api_key),
+        };
+        let started = Instant::now();
+        let mut queued = false;
+
+        loop {
+            let config = self.config(provider);
+            let (notified, deadline) = {
+                let mut entries = self.entries.lock();
+                if !entries.contains_key(&key) {
+                    Self::evict_if_full(&mut entries);
+                }
+                let entry = entries.entry(key.clone()).or_insert_with(BudgetEntry::new);
+                entry.last_used = Instant::now();
+
+                let limit = match config.max_connections {
+                    Some(limit) if entry.in_use >= limit => limit,
+                    _ => {
+                        if queued {
+                            entry.queued -= 1;
+                        }
+                        entry.in_use += 1;
+                        entry.peak = entry.peak.max(entry.in_use);
+                        debug!(
+                            provider = %key.provider,
+                            fingerprint = %key.fingerprint,
+                            in_use = entry.in_use,
+                            "Connection permit acquired"
+                        );
+                        return Ok(ConnectionPermit {
+                            budgets: Arc::downgrade(self),
+                            key,
+                        });
+                    }
+                };
+
+                let deadline = started + Duration::from_millis(config.queue_timeout_ms);
+                if config.policy == ConnectionBudgetPolicy::FailFast || Instant::now() >= deadline {
+                    if queued {
+                        entry.queued -= 1;
+                    }
+                    entry.rejected += 1;
+                    let exhausted = ConnectionBudgetExhausted {
+                        provider: key.provider.clone(),
+                        fingerprint: key.fingerprint.clone(),
+                        in_use: entry.in_use,
+                        limit,
+                        waited: queued.then(|| started.elapsed()),
+                    };
+                    warn!(
+                        provider = %key.provider,
+                        fingerprint = %key.fingerprint,
+                        in_use = entry.in_use,
+                        limit,
+                        "Connection budget exhausted"
+                    );
+                    return Err(exhausted);
+                }
+
+                if !queued {
+                    queued = true;
+                    entry.queued += 1;
+                }
+                // Registered before the lock is released so a release in
+                // between is not missed
+                let mut notified = Box::pin(entry.released.clone().notified_owned());
+                notified.as_mut().enable();
+                (notified, deadline)
+            };
+
+            let _ = tokio::time::timeout_at(deadline, notified).await;
+        }
+    }
+
+    /// Usage of every tracked key, ordered by provider and fingerprint
+    pub fn snapshot(&self) -> Vec<ConnectionBudgetStatus> {
+        let configs = self.configs.lock().clone();
+        let mut statuses: Vec<_> = self
+            .entries
+            .lock()
+            .iter()
+            .map(|(key, entry)| ConnectionBudgetStatus {
+                provider: key.provider.clone(),
+                fingerprint: key.fingerprint.clone(),
+                in_use: entry.in_use,
+                limit: configs
+                    .get(&key.provider)
+                    .and_then(|config| config.max_connections),
+                queued: entry.queued,
+                peak: entry.peak,
+                rejected: entry.rejected,
+            })
+            .collect();
+        statuses.sort_by(|a, b| (&a.provider, &a.fingerprint).cmp(&(&b.provider, &b.fingerprint)));
+        statuses
+    }
+
+    fn release(&self, key: &BudgetKey) {
+        let mut entries = self.entries.lock();
+        if let Some(entry) = entries.get_mut(key) {
+            entry.in_use = entry.in_use.saturating_sub(1);
+            entry.last_used = Instant::now();
+            entry.released.notify_waiters();
+            debug!(
+                provider = %key.provider,
+                fingerprint = %key.fingerprint,
+                in_use = entry.in_use,
+                "Connection permit released"
+            );
+        }
+    }
+
+    fn evict_if_full(entries: &mut HashMap<BudgetKey, BudgetEntry>) {
+        if entries.len() < MAX_TRACKED_KEYS {
+            return;
+        }
+        if let Some(oldest) = entries
+            .iter()
+            .filter(|(_, entry)| entry.in_use == 0 && entry.queued == 0)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone())
+        {
+            entries.remove(&oldest);
+        }
+    }
+}
+
+/// One open connection counted against its key's budget, released on drop
+pub struct ConnectionPermit {
+    budgets: Weak<ConnectionBudgets>,
+    key: BudgetKey,
+}
+
+impl ConnectionPermit {
+    /// Fingerprint of the API key the permit was taken for
+    pub fn fingerprint(&self) -> &str {
+        &self.key.fingerprint
+    }
+}
+
+impl std::fmt::Debug for ConnectionPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionPermit")
+            .field("provider", &self.key.provider)
+            .field("fingerprint", &self.key.fingerprint)
+            .finish()
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some(budgets) = self.budgets.upgrade() {
+            budgets.release(&self.key);
+        }
+    }
+}
+
+static CONNECTION_BUDGETS: OnceLock<Arc<ConnectionBudgets>> = OnceLock::new();
+
+/// The process-wide connection budgets
+pub fn connection_budgets() -> &'static Arc<ConnectionBudgets> {
+    CONNECTION_BUDGETS.get_or_init(|| Arc::new(ConnectionBudgets::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "dg-secret-key-0123456789";
+
+    fn budgets(max_connections: usize, policy: ConnectionBudgetPolicy) -> Arc<ConnectionBudgets> {
+        let budgets = Arc::new(ConnectionBudgets::new());
+        budgets.configure(
+            DEEPGRAM_CONNECTION_BUDGET,
+            ConnectionBudgetConfig {
+                max_connections: Some(max_connections),
+                policy,
+                queue_timeout_ms: 200,
+            },
+        );
+        budgets
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_budget_tracks_usage() {
+        let budgets = Arc::new(ConnectionBudgets::new());
+        let permits: Vec<_> = futures::future::join_all(
+            (0..3).map(|_| budgets.acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+        let status = &budgets.snapshot()[0];
+        assert_eq!(status.in_use, 3);
+        assert_eq!(status.limit, None);
+
+        drop(permits);
+        let status = &budgets.snapshot()[0];
+        assert_eq!(status.in_use, 0);
+        assert_eq!(status.peak, 3);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_fails_fast() {
+        let budgets = budgets(2, ConnectionBudgetPolicy::FailFast);
+        let _stt = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap();
+        let _tts = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap();
+
+        let err = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap_err();
+        assert_eq!(err.fingerprint, key_fingerprint(KEY));
+        assert_eq!((err.in_use, err.limit, err.waited), (2, 2, None));
+        let message = err.to_string();
+        assert!(message.contains("deepgram connection budget exhausted"));
+        assert!(message.contains(&key_fingerprint(KEY)));
+        assert!(!message.contains(KEY), "{message}");
+
+        let status = &budgets.snapshot()[0];
+        assert_eq!((status.in_use, status.limit), (2, Some(2)));
+        assert_eq!(status.rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_keys_have_separate_budgets() {
+        let budgets = budgets(1, ConnectionBudgetPolicy::FailFast);
+        let _a = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, "key-a")
+            .await
+            .unwrap();
+        let _b = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, "key-b")
+            .await
+            .unwrap();
+        // Other providers are not limited by Deepgram's budget
+        let _other = budgets.acquire("elevenlabs", "key-a").await.unwrap();
+        assert!(
+            budgets
+                .acquire(DEEPGRAM_CONNECTION_BUDGET, "key-a")
+                .await
+                .is_err()
+        );
+        assert_eq!(budgets.snapshot().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_queue_waits_for_release() {
+        let budgets = budgets(1, ConnectionBudgetPolicy::Queue);
+        let held = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap();
+
+        let waiter = {
+            let budgets = budgets.clone();
+            tokio::spawn(async move { budgets.acquire(DEEPGRAM_CONNECTION_BUDGET, KEY).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(budgets.snapshot()[0].queued, 1);
+
+        drop(held);
+        let permit = waiter.await.unwrap().unwrap();
+        let status = &budgets.snapshot()[0];
+        assert_eq!((status.in_use, status.queued, status.rejected), (1, 0, 0));
+        drop(permit);
+    }
+
+    #[tokio::test]
+    async fn test_queue_times_out() {
+        let budgets = budgets(1, ConnectionBudgetPolicy::Queue);
+        let _held = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap();
+
+        let err = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap_err();
+        assert!(err.waited.unwrap() >= Duration::from_millis(200));
+        assert!(err.to_string().contains("after waiting"), "{err}");
+        let status = &budgets.snapshot()[0];
+        assert_eq!((status.queued, status.rejected), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_raised_limit_admits_queued_connects() {
+        let budgets = budgets(1, ConnectionBudgetPolicy::Queue);
+        let _held = budgets
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, KEY)
+            .await
+            .unwrap();
+        let waiter = {
+            let budgets = budgets.clone();
+            tokio::spawn(async move { budgets.acquire(DEEPGRAM_CONNECTION_BUDGET, KEY).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        budgets.configure(
+            DEEPGRAM_CONNECTION_BUDGET,
+            ConnectionBudgetConfig {
+                max_connections: Some(2),
+                policy: ConnectionBudgetPolicy::Queue,
+                queue_timeout_ms: 200,
+            },
+        );
+        assert!(waiter.await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(ConnectionBudgetConfig::default().validate().is_ok());
+        let zero = ConnectionBudgetConfig {
+            max_connections: Some(0),
+            ..Default::default()
+        };
+        assert!(zero.validate().unwrap_err().contains("max_connections"));
+        let no_wait = ConnectionBudgetConfig {
+            max_connections: Some(10),
+            policy: ConnectionBudgetPolicy::Queue,
+            queue_timeout_ms: 0,
+        };
+        assert!(no_wait.validate().unwrap_err().contains("queue_timeout_ms"));
+    }
+}
@@ -0,0 +1,543 @@
+//! Credential health shared across sessions.
+//!
+//! Azure and IBM exchange an API key for a short-lived token whenever a
+//! session connects. Without sharing, every session repeats that exchange,
+//! and a revoked key makes every session wait for the token endpoint to
+//! reject it. [`CredentialHealthCache`] tracks each credential process-wide:
+//!
+//! - **Shared tokens**: one [`TokenProvider`] per credential, so a session
+//!   started after a successful exchange reuses the token without a request
+//! - **Fast fail**: once the endpoint rejects a key, token requests for it
+//!   fail immediately with the cached error until a backoff expires
+//!
+//! Credentials are keyed by provider and a SHA-256 fingerprint of the key,
+//! never the key itself. Tenant and per-request key overrides therefore get
+//! their own entries, and a rotated key starts out healthy. Only rejections
+//! of the key are cached; network errors are left to the token provider's
+//! own retry backoff.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use super::token::{
+    AccessToken, MAX_RETRY_BACKOFF, MIN_RETRY_BACKOFF, TokenError, TokenFetcher, TokenProvider,
+};
+
+/// Fast-fail window after the first rejection of a key
+pub const MIN_REJECTION_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest fast-fail window for a key that keeps being rejected
+pub const MAX_REJECTION_BACKOFF: Duration = Duration::from_secs(600);
+
+/// Credentials tracked at once; the least recently used is evicted beyond this
+pub const MAX_TRACKED_CREDENTIALS: usize = 1024;
+
+/// Short, non-reversible identifier for an API key
+pub fn key_fingerprint(api_key: &str) -> String {
+    let digest = Sha256::digest(api_key.as_bytes());
+    hex::encode(&digest[..8])
+}
+
+/// Health of a credential as last observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialState {
+    /// No token has been requested yet
+    Unknown,
+    /// The last token request succeeded
+    Healthy,
+    /// The key was rejected; token requests fail fast until `retry_in_ms` elapses
+    Rejected,
+}
+
+/// Reported health of one provider credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CredentialHealthStatus {
+    /// Provider the credential belongs to
+    #[cfg_attr(feature = "openapi", schema(example = "ibm-watson"))]
+    pub provider: String,
+    /// Fingerprint of the API key
+    #[cfg_attr(feature = "openapi", schema(example = "3f2a9c1e7b40d855"))]
+    pub fingerprint: String,
+    pub state: CredentialState,
+    /// Unix time in milliseconds of the last successful token request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_ms: Option<u64>,
+    /// Unix time in milliseconds of the last rejection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure_ms: Option<u64>,
+    /// Error from the last rejection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Rejections since the last success
+    pub consecutive_failures: u32,
+    /// Time left before token requests are attempted again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CredentialKey {
+    provider: String,
+    fingerprint: String,
+}
+
+struct CredentialEntry {
+    token_provider: Option<Arc<TokenProvider>>,
+    last_success: Option<SystemTime>,
+    last_failure: Option<SystemTime>,
+    last_error: Option<String>,
+    consecutive_failures: u32,
+    blocked_until: Option<Instant>,
+    last_used: Instant,
+}
+
+impl CredentialEntry {
+    fn new() -> Self {
+        Self {
+            token_provider: None,
+            last_success: None,
+            last_failure: None,
+            last_error: None,
+            consecutive_failures: 0,
+            blocked_until: None,
+            last_used: Instant::now(),
+        }
+    }
+}
+
+/// Process-wide record of provider credential health
+pub struct CredentialHealthCache {
+    entries: Mutex<HashMap<CredentialKey, CredentialEntry>>,
+    min_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for CredentialHealthCache {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            min_backoff: MIN_REJECTION_BACKOFF,
+            max_backoff: MAX_REJECTION_BACKOFF,
+        }
+    }
+}
+
+impl CredentialHealthCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the fast-fail backoff bounds
+    pub fn with_backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max.max(min);
+        self
+    }
+
+    /// The shared token provider for `api_key`, created with `fetcher` on first use
+    ///
+    /// `provider` should distinguish anything that changes the token
+    /// endpoint, such as the Azure region.
+    pub fn token_provider<F>(
+        self: &Arc<Self>,
+        provider: &str,
+        api_key: &str,
+        fetcher: impl FnOnce() -> F,
+    ) -> Arc<TokenProvider>
+    where
+        F: TokenFetcher + 'static,
+    {
+        let key = CredentialKey {
+            provider: provider.to_string(),
+            fingerprint: key_fingerprint(api_key),
+        };
+
+        let mut entries = self.entries.lock();
+        if !entries.contains_key(&key) {
+            Self::evict_if_full(&mut entries);
+        }
+        let entry = entries
+            .entry(key.clone())
+            .or_insert_with(CredentialEntry::new);
+        entry.last_used = Instant::now();
+
+        entry
+            .token_provider
+            .get_or_insert_with(|| {
+                let tracked = HealthTrackedFetcher {
+                    inner: fetcher(),
+                    cache: Arc::downgrade(self),
+                    key,
+                };
+                // Never wait longer between attempts than the fast-fail window
+                Arc::new(TokenProvider::new(tracked).with_backoff(
+                    MIN_RETRY_BACKOFF.min(self.min_backoff),
+                    MAX_RETRY_BACKOFF.min(self.max_backoff),
+                ))
+            })
+            .clone()
+    }
+
+    /// Health of every tracked credential, ordered by provider and fingerprint
+    pub fn snapshot(&self) -> Vec<CredentialHealthStatus> {
+        let now = Instant::now();
+        let mut statuses: Vec<_> = self
+            .entries
+            .lock()
+            .iter()
+            .map(|(key, entry)| {
+                let retry_in = entry
+                    .blocked_until
+                    .filter(|until| *until > now)
+                    .map(|until| until - now);
+                let state = if retry_in.is_some() {
+                    CredentialState::Rejected
+                } else if entry.last_success.is_some() && entry.consecutive_failures == 0 {
+                    CredentialState::Healthy
+                } else {
+                    CredentialState::Unknown
+                };
+                CredentialHealthStatus {
+                    provider: key.provider.clone(),
+                    fingerprint: key.fingerprint.clone(),
+                    state,
+                    last_success_ms: entry.last_success.map(unix_millis),
+                    last_failure_ms: entry.last_failure.map(unix_millis),
+                    last_error: entry.last_error.clone(),
+                    consecutive_failures: entry.consecutive_failures,
+                    retry_in_ms: retry_in.map(|d| d.as_millis() as u64),
+                }
+            })
+            .collect();
+        statuses.sort_by(|a, b| (&a.provider, &a.fingerprint).cmp(&(&b.provider, &b.fingerprint)));
+        statuses
+    }
+
+    /// Forget all credentials, e.g. after the provider configuration changed
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    /// The cached rejection if `key` is still inside its backoff window
+    fn check(&self, key: &CredentialKey) -> Result<(), TokenError> {
+        let entries = self.entries.lock();
+        let Some(entry) = entries.get(key) else {
+            return Ok(());
+        };
+        match entry.blocked_until {
+            Some(until) if Instant::now() < until => Err(TokenError::Rejected(format!(
+                "{} (cached, retrying in {}s)",
+                entry.last_error.as_deref().unwrap_or("credential rejected"),
+                (until - Instant::now()).as_secs()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_success(&self, key: &CredentialKey) {
+        let mut entries = self.entries.lock();
+        let entry = entries
+            .entry(key.clone())
+            .or_insert_with(CredentialEntry::new);
+        if entry.consecutive_failures > 0 {
+            info!(provider = %key.provider, fingerprint = %key.fingerprint, "Credential accepted again");
+        }
+        entry.last_success = Some(SystemTime::now());
+        entry.consecutive_failures = 0;
+        entry.blocked_until = None;
+    }
+
+    fn record_rejection(&self, key: &CredentialKey, message: &str) {
+        let mut entries = self.entries.lock();
+        let entry = entries
+            .entry(key.clone())
+            .or_insert_with(CredentialEntry::new);
+        let backoff = self
+            .min_backoff
+            .saturating_mul(2u32.saturating_pow(entry.consecutive_failures))
+            .min(self.max_backoff);
+        entry.consecutive_failures += 1;
+        entry.last_failure = Some(SystemTime::now());
+        entry.last_error = Some(message.to_string());
+        entry.blocked_until = Some(Instant::now() + backoff);
+        warn!(
+            provider = %key.provider,
+            fingerprint = %key.fingerprint,
+            failures = entry.consecutive_failures,
+            backoff_secs = backoff.as_secs(),
+            "Credential rejected; failing fast until the backoff expires"
+        );
+    }
+
+    fn evict_if_full(entries: &mut HashMap<CredentialKey, CredentialEntry>) {
+        if entries.len() < MAX_TRACKED_CREDENTIALS {
+            return;
+        }
+        if let Some(oldest) = entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone())
+        {
+            entries.remove(&oldest);
+        }
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Token fetcher that consults and updates the credential's health
+struct HealthTrackedFetcher<F> {
+    inner: F,
+    cache: Weak<CredentialHealthCache>,
+    key: CredentialKey,
+}
+
+#[async_trait::async_trait]
+impl<F: TokenFetcher> TokenFetcher for HealthTrackedFetcher<F> {
+    async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+        let Some(cache) = self.cache.upgrade() else {
+            return self.inner.fetch_token().await;
+        };
+        cache.check(&self.key)?;
+
+        let result = self.inner.fetch_token().await;
+        match &result {
+            Ok(_) => cache.record_success(&self.key),
+            Err(TokenError::Rejected(message)) => cache.record_rejection(&self.key, message),
+            Err(_) => {}
+        }
+        result
+    }
+}
+
+static CREDENTIAL_HEALTH: OnceLock<Arc<CredentialHealthCache>> = OnceLock::new();
+
+/// The process-wide credential health cache
+pub fn credential_health() -> &'static Arc<CredentialHealthCache> {
+    CREDENTIAL_HEALTH.get_or_init(|| Arc::new(CredentialHealthCache::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// Counts token requests and rejects the key while `revoked` is set
+    #[derive(Clone)]
+    struct MockFetcher {
+        calls: Arc<AtomicU32>,
+        revoked: Arc<AtomicBool>,
+    }
+
+    impl MockFetcher {
+        fn new() -> Self {
+            Self {
+                calls: Arc::new(AtomicU32::new(0)),
+                revoked: Arc::new(AtomicBool::new(false)),
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenFetcher for MockFetcher {
+        async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.revoked.load(Ordering::SeqCst) {
+                return Err(TokenError::Rejected("401 Unauthorized".to_string()));
+            }
+            Ok(AccessToken {
+                token: "token".to_string(),
+                lifetime: Duration::from_secs(600),
+            })
+        }
+    }
+
+    fn cache() -> Arc<CredentialHealthCache> {
+        Arc::new(
+            CredentialHealthCache::new()
+                .with_backoff(Duration::from_millis(100), Duration::from_millis(400)),
+        )
+    }
+
+    #[test]
+    fn test_fingerprint_hides_key() {
+        let fingerprint = key_fingerprint("secret-api-key");
+        assert_eq!(fingerprint.len(), 16);
+        assert!(!fingerprint.contains("secret"));
+        assert_eq!(fingerprint, key_fingerprint("secret-api-key"));
+        assert_ne!(fingerprint, key_fingerprint("secret-api-key-2"));
+    }
+
+    #[tokio::test]
+    async fn test_sessions_share_a_token() {
+        let cache = cache();
+        let fetcher = MockFetcher::new();
+
+        for _ in 0..3 {
+            let provider = cache.token_provider("ibm-watson", "key", || fetcher.clone());
+            assert_eq!(provider.token().await.unwrap(), "token");
+        }
+        assert_eq!(fetcher.calls(), 1);
+
+        let status = &cache.snapshot()[0];
+        assert_eq!(status.state, CredentialState::Healthy);
+        assert!(status.last_success_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_key_overrides_are_tracked_separately() {
+        let cache = cache();
+        let fetcher = MockFetcher::new();
+
+        cache
+            .token_provider("ibm-watson", "tenant-a", || fetcher.clone())
+            .token()
+            .await
+            .unwrap();
+        cache
+            .token_provider("ibm-watson", "tenant-b", || fetcher.clone())
+            .token()
+            .await
+            .unwrap();
+        cache
+            .token_provider("azure:eastus", "tenant-a", || fetcher.clone())
+            .token()
+            .await
+            .unwrap();
+        assert_eq!(fetcher.calls(), 3);
+
+        let snapshot = cache.snapshot();
+        assert_eq!(snapshot.len(), 3);
+        assert!(snapshot.iter().all(|s| !s.fingerprint.contains("tenant")));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_key_fails_fast() {
+        let cache = cache();
+        let fetcher = MockFetcher::new();
+        fetcher.revoked.store(true, Ordering::SeqCst);
+
+        let err = cache
+            .token_provider("azure:eastus", "revoked", || fetcher.clone())
+            .token()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TokenError::Rejected(_)));
+        assert_eq!(fetcher.calls(), 1);
+
+        // A new session sharing the key gets the cached rejection without a request
+        let err = cache
+            .token_provider("azure:eastus", "revoked", || fetcher.clone())
+            .token()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("401"), "{err}");
+        assert_eq!(fetcher.calls(), 1);
+
+        let status = &cache.snapshot()[0];
+        assert_eq!(status.state, CredentialState::Rejected);
+        assert_eq!(status.consecutive_failures, 1);
+        assert!(status.retry_in_ms.is_some());
+        assert!(status.last_error.as_deref().unwrap().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_recovers_after_backoff() {
+        let cache = cache();
+        let fetcher = MockFetcher::new();
+        fetcher.revoked.store(true, Ordering::SeqCst);
+        let provider = cache.token_provider("ibm-watson", "key", || fetcher.clone());
+        assert!(provider.token().await.is_err());
+        // Key restored, but the rejection is still cached
+        fetcher.revoked.store(false, Ordering::SeqCst);
+        assert!(provider.token().await.is_err());
+        assert_eq!(fetcher.calls(), 1);
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert_eq!(provider.token().await.unwrap(), "token");
+        assert_eq!(fetcher.calls(), 2);
+
+        let status = &cache.snapshot()[0];
+        assert_eq!(status.state, CredentialState::Healthy);
+        assert_eq!(status.consecutive_failures, 0);
+        assert!(status.last_failure_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_rejections_back_off_longer() {
+        let cache = cache();
+        let fetcher = MockFetcher::new();
+        fetcher.revoked.store(true, Ordering::SeqCst);
+        let provider = cache.token_provider("ibm-watson", "key", || fetcher.clone());
+        let key = CredentialKey {
+            provider: "ibm-watson".to_string(),
+            fingerprint: key_fingerprint("key"),
+        };
+
+        assert!(provider.token().await.is_err());
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(cache.check(&key).is_ok());
+
+        // Second rejection doubles the window to 200ms
+        cache.record_rejection(&key, "401");
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(cache.check(&key).is_err());
+        assert_eq!(cache.snapshot()[0].consecutive_failures, 2);
+    }
+
+    #[tokio::test]
+    async fn test_network_errors_are_not_cached() {
+        struct Unreachable;
+
+        #[async_trait::async_trait]
+        impl TokenFetcher for Unreachable {
+            async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+                Err(TokenError::RequestFailed("connection refused".to_string()))
+            }
+        }
+
+        let cache = cache();
+        let provider = cache.token_provider("ibm-watson", "key", || Unreachable);
+        assert!(provider.token().await.is_err());
+
+        let status = &cache.snapshot()[0];
+        assert_eq!(status.state, CredentialState::Unknown);
+        assert_eq!(status.consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_clear_forgets_rejections() {
+        let cache = cache();
+        let fetcher = MockFetcher::new();
+        fetcher.revoked.store(true, Ordering::SeqCst);
+        let _ = cache
+            .token_provider("ibm-watson", "key", || fetcher.clone())
+            .token()
+            .await;
+
+        cache.clear();
+        fetcher.revoked.store(false, Ordering::SeqCst);
+        let provider = cache.token_provider("ibm-watson", "key", || fetcher.clone());
+        assert_eq!(provider.token().await.unwrap(), "token");
+        assert_eq!(cache.snapshot().len(), 1);
+    }
+}
@@ -42,6 +42,14 @@ use http::Extensions;
 use tracing::{debug, error};
 
 use super::error::GoogleError;
+use crate::core::providers::token::{AccessToken, TokenError, TokenFetcher};
+
+/// How long a token from [`GoogleAuthClient`] is treated as valid.
+///
+/// The credentials library does not report token expiry, so a lifetime well
+/// inside the one hour Google grants is assumed. It only decides how often
+/// the library is asked again; the library refreshes the underlying token.
+pub const GOOGLE_TOKEN_LIFETIME: std::time::Duration = std::time::Duration::from_secs(300);
 
 /// Determines the source of Google Cloud credentials based on the api_key value.
 ///
@@ -422,6 +430,21 @@ impl TokenProvider for GoogleAuthClient {
     }
 }
 
+#[async_trait::async_trait]
+impl TokenFetcher for GoogleAuthClient {
+    async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+        let token = self
+            .get_token()
+            .await
+            .map_err(|e| TokenError::RequestFailed(e.to_string()))?;
+
+        Ok(AccessToken {
+            token,
+            lifetime: GOOGLE_TOKEN_LIFETIME,
+        })
+    }
+}
+
 /// A mock token provider for testing purposes.
 #[cfg(test)]
 pub struct MockTokenProvider {
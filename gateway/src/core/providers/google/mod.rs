@@ -71,7 +71,7 @@ pub mod client;
 pub mod error;
 
 // Re-export commonly used types
-pub use auth::{CredentialSource, GoogleAuthClient, TokenProvider};
+pub use auth::{CredentialSource, GOOGLE_TOKEN_LIFETIME, GoogleAuthClient, TokenProvider};
 pub use client::{AuthenticatedChannel, create_authenticated_channel, create_grpc_channel};
 pub use error::GoogleError;
 
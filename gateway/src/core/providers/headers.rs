@@ -0,0 +1,228 @@
+//! Custom HTTP headers for provider requests.
+//!
+//! `STTConfig::custom_headers` and `TTSConfig::custom_headers` let callers
+//! attach extra headers (proxy auth, tracing ids, API gateway keys) to every
+//! request a provider makes. This module validates those maps and applies
+//! them to the three transports providers use:
+//!
+//! - reqwest requests: [`apply_custom_headers`]
+//! - WebSocket handshakes: [`insert_custom_headers`] on `request.headers_mut()`
+//! - gRPC calls: [`insert_custom_metadata`] on `request.metadata_mut()`
+//!
+//! Validation happens once, when the provider is created through the plugin
+//! registry, so the apply helpers silently skip entries that would not parse.
+
+use std::collections::HashMap;
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue, MetadataMap};
+
+/// Headers managed by the HTTP/WebSocket client that callers may not override
+const RESERVED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "upgrade",
+];
+
+/// Prefix of WebSocket handshake headers that callers may not override
+const RESERVED_HEADER_PREFIX: &str = "sec-websocket-";
+
+/// Validate a custom header map
+///
+/// Header names must be non-empty printable ASCII without whitespace or `:`,
+/// and must not be one of the transport headers the client manages itself.
+/// Values must not contain CR, LF or NUL, which would allow header injection.
+///
+/// # Returns
+/// * `Ok(())` - Every entry is valid
+/// * `Err(String)` - Description of the first invalid entry
+pub fn validate_custom_headers(headers: &HashMap<String, String>) -> Result<(), String> {
+    for (name, value) in headers {
+        validate_header_name(name)?;
+        validate_header_value(name, value)?;
+    }
+    Ok(())
+}
+
+fn validate_header_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Custom header name cannot be empty".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| !c.is_ascii_graphic() || *c == ':') {
+        return Err(format!(
+            "Custom header name '{}' contains invalid character {:?}",
+            name.escape_debug(),
+            c
+        ));
+    }
+    if HeaderName::from_bytes(name.as_bytes()).is_err() {
+        return Err(format!(
+            "Custom header name '{name}' is not a valid HTTP token"
+        ));
+    }
+
+    let lower = name.to_ascii_lowercase();
+    if RESERVED_HEADERS.contains(&lower.as_str()) || lower.starts_with(RESERVED_HEADER_PREFIX) {
+        return Err(format!(
+            "Custom header '{name}' is managed by the transport and cannot be overridden"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_header_value(name: &str, value: &str) -> Result<(), String> {
+    if value.contains(['\r', '\n', '\0']) {
+        return Err(format!(
+            "Custom header '{name}' value contains CR, LF or NUL characters"
+        ));
+    }
+    if HeaderValue::from_str(value).is_err() {
+        return Err(format!(
+            "Custom header '{name}' value must be printable ASCII"
+        ));
+    }
+    Ok(())
+}
+
+/// Insert custom headers into a header map, replacing existing values
+///
+/// Used for WebSocket handshake requests built with
+/// `tokio_tungstenite::tungstenite::http::Request`. Invalid entries are skipped.
+pub fn insert_custom_headers(map: &mut HeaderMap, headers: &HashMap<String, String>) {
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            map.insert(name, value);
+        }
+    }
+}
+
+/// Add custom headers to a reqwest request
+///
+/// Custom headers replace any header of the same name already set on the
+/// builder. Invalid entries are skipped.
+pub fn apply_custom_headers(
+    builder: reqwest::RequestBuilder,
+    headers: &HashMap<String, String>,
+) -> reqwest::RequestBuilder {
+    if headers.is_empty() {
+        return builder;
+    }
+    let mut map = HeaderMap::with_capacity(headers.len());
+    insert_custom_headers(&mut map, headers);
+    builder.headers(map)
+}
+
+/// Insert custom headers into gRPC request metadata
+///
+/// Keys are lowercased as required by gRPC. Invalid entries are skipped.
+pub fn insert_custom_metadata(metadata: &mut MetadataMap, headers: &HashMap<String, String>) {
+    for (name, value) in headers {
+        if let (Ok(key), Ok(value)) = (
+            AsciiMetadataKey::from_bytes(name.to_ascii_lowercase().as_bytes()),
+            value.parse::<AsciiMetadataValue>(),
+        ) {
+            metadata.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_headers_pass() {
+        let valid = headers(&[
+            ("X-Proxy-Auth", "token 123"),
+            ("x-request-id", "abc-def"),
+            ("Helicone-Auth", "Bearer sk-xyz"),
+        ]);
+        assert!(validate_custom_headers(&valid).is_ok());
+        assert!(validate_custom_headers(&HashMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_header_names_rejected() {
+        for name in [
+            "",
+            "X Proxy",
+            "X-Proxy:Auth",
+            "X-Tab\t",
+            "X-Ünicode",
+            "X-New\nLine",
+        ] {
+            let result = validate_custom_headers(&headers(&[(name, "value")]));
+            assert!(result.is_err(), "name {name:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_crlf_injection_in_values_rejected() {
+        for value in [
+            "value\r\nX-Injected: 1",
+            "value\nX-Injected: 1",
+            "value\r",
+            "nul\0byte",
+        ] {
+            let result = validate_custom_headers(&headers(&[("X-Custom", value)]));
+            let err = result.expect_err("value should be rejected");
+            assert!(err.contains("CR, LF or NUL"), "unexpected error: {err}");
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_values_rejected() {
+        let result = validate_custom_headers(&headers(&[("X-Custom", "café")]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_transport_headers_rejected() {
+        for name in [
+            "Host",
+            "content-length",
+            "Transfer-Encoding",
+            "Connection",
+            "Upgrade",
+            "Sec-WebSocket-Key",
+        ] {
+            let err = validate_custom_headers(&headers(&[(name, "x")])).unwrap_err();
+            assert!(
+                err.contains("managed by the transport"),
+                "unexpected error: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_insert_custom_headers_replaces_existing() {
+        let mut map = HeaderMap::new();
+        map.insert("x-trace", HeaderValue::from_static("old"));
+        insert_custom_headers(
+            &mut map,
+            &headers(&[("X-Trace", "new"), ("X-Tenant", "acme")]),
+        );
+        assert_eq!(map.get("x-trace").unwrap(), "new");
+        assert_eq!(map.get("x-tenant").unwrap(), "acme");
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_insert_custom_metadata_lowercases_keys() {
+        let mut metadata = MetadataMap::new();
+        insert_custom_metadata(&mut metadata, &headers(&[("X-Tenant", "acme")]));
+        assert_eq!(metadata.get("x-tenant").unwrap(), "acme");
+    }
+}
@@ -0,0 +1,200 @@
+//! IBM Cloud IAM authentication shared by the IBM Watson providers.
+//!
+//! IBM Watson services authenticate with bearer tokens obtained by
+//! exchanging an IBM Cloud API key at the IAM token endpoint. Tokens are
+//! valid for an hour; [`iam_token_provider`] wraps the exchange in a
+//! [`TokenProvider`] so long sessions keep getting fresh tokens, shared by
+//! every session using the same key.
+//!
+//! See: <https://cloud.ibm.com/docs/account?topic=account-iamtoken_from_apikey>
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use url::form_urlencoded;
+
+use super::credential_health::credential_health;
+use super::token::{AccessToken, TokenError, TokenFetcher, TokenProvider};
+
+/// IBM Cloud IAM token endpoint.
+pub const IBM_IAM_URL: &str = "https://iam.cloud.ibm.com/identity/token";
+
+/// Lifetime assumed when the IAM response omits `expires_in` (seconds).
+const DEFAULT_IAM_TOKEN_LIFETIME_SECS: u64 = 3600;
+
+/// IAM token response from IBM Cloud.
+#[derive(Debug, serde::Deserialize)]
+struct IamTokenResponse {
+    access_token: String,
+    /// Token lifetime in seconds.
+    #[serde(default)]
+    expires_in: u64,
+}
+
+/// Exchanges an IBM Cloud API key for IAM access tokens.
+pub struct IamTokenFetcher {
+    api_key: String,
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl IamTokenFetcher {
+    /// Create a fetcher for `api_key` using the public IAM endpoint.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self::with_endpoint(api_key, IBM_IAM_URL)
+    }
+
+    /// Create a fetcher that requests tokens from `endpoint`.
+    pub fn with_endpoint(api_key: impl Into<String>, endpoint: impl Into<String>) -> Self {
+        // Explicit timeouts so a stuck IAM endpoint cannot hang a connect
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            api_key: api_key.into(),
+            endpoint: endpoint.into(),
+            client,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenFetcher for IamTokenFetcher {
+    async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+        let encoded_api_key: String =
+            form_urlencoded::byte_serialize(self.api_key.as_bytes()).collect();
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(format!(
+                "grant_type=urn:ibm:params:oauth:grant-type:apikey&apikey={}",
+                encoded_api_key
+            ))
+            .send()
+            .await
+            .map_err(|e| TokenError::RequestFailed(format!("Failed to request IAM token: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("IAM token request failed ({status}): {body}");
+            // IAM answers 400 for an unknown or disabled API key
+            return Err(match status {
+                StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    TokenError::Rejected(message)
+                }
+                _ => TokenError::RequestFailed(message),
+            });
+        }
+
+        let token_response: IamTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| TokenError::RequestFailed(format!("Failed to parse IAM token: {e}")))?;
+
+        let lifetime_secs = match token_response.expires_in {
+            0 => DEFAULT_IAM_TOKEN_LIFETIME_SECS,
+            secs => secs,
+        };
+
+        Ok(AccessToken {
+            token: token_response.access_token,
+            lifetime: Duration::from_secs(lifetime_secs),
+        })
+    }
+}
+
+/// Shared token provider for an IBM Cloud API key.
+pub fn iam_token_provider(api_key: &str) -> Arc<TokenProvider> {
+    credential_health().token_provider("ibm-watson", api_key, || IamTokenFetcher::new(api_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn iam_server(expires_in: u64) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/identity/token"))
+            .and(body_string_contains("apikey=test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "iam-token",
+                "expires_in": expires_in,
+                "token_type": "Bearer"
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn provider(server: &MockServer) -> TokenProvider {
+        TokenProvider::new(IamTokenFetcher::with_endpoint(
+            "test-key",
+            format!("{}/identity/token", server.uri()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_fetch_iam_token() {
+        let server = iam_server(3600).await;
+        let fetcher =
+            IamTokenFetcher::with_endpoint("test-key", format!("{}/identity/token", server.uri()));
+        let token = fetcher.fetch_token().await.unwrap();
+        assert_eq!(token.token, "iam-token");
+        assert_eq!(token.lifetime, Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn test_short_lived_token_is_refreshed() {
+        let server = iam_server(1).await;
+        let provider = provider(&server);
+
+        provider.token().await.unwrap();
+        provider.token().await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        // Refreshed once 80% of the one second lifetime has passed
+        tokio::time::sleep(Duration::from_millis(850)).await;
+        provider.token().await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_connects_fetch_once() {
+        let server = iam_server(3600).await;
+        let provider = Arc::new(provider(&server));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let provider = provider.clone();
+                tokio::spawn(async move { provider.token().await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), "iam-token");
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_api_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("BXNIM0415E"))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).token().await.unwrap_err();
+        assert!(matches!(err, TokenError::Rejected(ref msg) if msg.contains("400")));
+    }
+}
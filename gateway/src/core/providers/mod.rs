@@ -8,9 +8,21 @@
 //!
 //! - **google**: Google Cloud authentication and gRPC client infrastructure
 //! - **azure**: Microsoft Azure Speech Services region and authentication infrastructure
+//! - **ibm**: IBM Cloud IAM token exchange for the IBM Watson services
+//! - **headers**: Validation and injection of user-supplied custom request headers
+//! - **token**: Cached access tokens that refresh themselves before they expire
+//! - **credential_health**: Tokens shared across sessions and fast failure for rejected keys
+//! - **connection_budget**: Concurrent connection limits shared by a provider's products
+//! - **retry**: Retry classification and `Retry-After` handling for REST providers
 
 pub mod azure;
+pub mod connection_budget;
+pub mod credential_health;
 pub mod google;
+pub mod headers;
+pub mod ibm;
+pub mod retry;
+pub mod token;
 
 // Re-export Google Cloud types for convenience
 pub use google::{
@@ -24,3 +36,8 @@ pub use azure::{
     AZURE_AUTHORIZATION_HEADER, AZURE_SUBSCRIPTION_KEY_HEADER, AzureRegion,
     build_bearer_token_header, build_subscription_key_header, build_token_request_url,
 };
+
+// Re-export custom header helpers for convenience
+pub use headers::{
+    apply_custom_headers, insert_custom_headers, insert_custom_metadata, validate_custom_headers,
+};
@@ -0,0 +1,497 @@
+//! Retry policy shared by the REST providers.
+//!
+//! Providers tell clients how long to back off in different ways: OpenAI and
+//! Groq send `retry-after` and `x-ratelimit-reset-*` durations such as
+//! `6m0s`, ElevenLabs a plain `retry-after` in seconds, and proxies in front
+//! of any of them an HTTP-date. This module turns those hints and the
+//! response status into one decision:
+//!
+//! - [`RetryReason`] classifies a failed request as worth retrying or not
+//! - [`retry_after`] reads the server's back-off hint from the headers
+//! - [`RetryPolicy`] caps that hint, falls back to exponential backoff with
+//!   jitter, and counts attempts through a per-request [`Retry`]
+//!
+//! Retries are counted in `waav_provider_retries_total{provider,reason}`.
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use tracing::warn;
+
+use crate::metrics::global_metrics;
+
+/// Why a failed request may be retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryReason {
+    /// The provider throttled the request (429, or Groq's 498 flex capacity)
+    RateLimited,
+    /// The provider failed or was unavailable (5xx)
+    ServerError,
+    /// The connection could not be established
+    Connect,
+    /// The request or connection timed out
+    Timeout,
+}
+
+impl RetryReason {
+    /// Label used in logs and metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::ServerError => "server_error",
+            Self::Connect => "connect",
+            Self::Timeout => "timeout",
+        }
+    }
+
+    /// Classify an error response, or `None` if retrying cannot help
+    ///
+    /// A 429 caused by an exhausted quota rather than a rate limit is not
+    /// retried; OpenAI reports it as `insufficient_quota` and ElevenLabs as
+    /// `quota_exceeded`.
+    pub fn from_status(status: StatusCode, body: &str) -> Option<Self> {
+        match status.as_u16() {
+            429 if body.contains("insufficient_quota") || body.contains("quota_exceeded") => None,
+            429 | 498 => Some(Self::RateLimited),
+            408 => Some(Self::Timeout),
+            500 | 502 | 503 | 504 | 520..=529 => Some(Self::ServerError),
+            _ => None,
+        }
+    }
+
+    /// Classify a transport error, or `None` if it is not transient
+    pub fn from_reqwest(error: &reqwest::Error) -> Option<Self> {
+        if error.is_timeout() {
+            Some(Self::Timeout)
+        } else if error.is_connect() {
+            Some(Self::Connect)
+        } else {
+            None
+        }
+    }
+}
+
+/// How long the server asked the client to wait before retrying
+///
+/// Reads, in order of preference:
+/// - `retry-after-ms`: milliseconds
+/// - `retry-after`: seconds or an HTTP-date
+/// - `x-ratelimit-reset-requests` / `x-ratelimit-reset-tokens`: durations
+///   such as `1s`, `250ms` or `6m0s`; only the limits whose matching
+///   `x-ratelimit-remaining-*` header is exhausted are considered, and the
+///   longest of them wins
+/// - `x-ratelimit-reset`: seconds, a Unix timestamp, or a duration
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+    };
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return duration_from_secs(ms / 1000.0);
+    }
+    if let Some(delay) = header("retry-after").and_then(parse_retry_after) {
+        return Some(delay);
+    }
+
+    let exhausted = |limit: &str| {
+        header(&format!("x-ratelimit-remaining-{limit}")).is_none_or(|remaining| remaining == "0")
+    };
+    let reset = ["requests", "tokens"]
+        .into_iter()
+        .filter(|limit| exhausted(limit))
+        .filter_map(|limit| header(&format!("x-ratelimit-reset-{limit}")))
+        .filter_map(parse_duration)
+        .max();
+    if reset.is_some() {
+        return reset;
+    }
+
+    header("x-ratelimit-reset").and_then(|value| match value.parse::<f64>() {
+        // Values this large are Unix timestamps rather than durations
+        Ok(secs) if secs >= 1_000_000_000.0 => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+            duration_from_secs(secs - now.as_secs_f64()).or(Some(Duration::ZERO))
+        }
+        Ok(secs) => duration_from_secs(secs),
+        Err(_) => parse_duration(value),
+    })
+}
+
+/// Parse a `Retry-After` value: delay seconds or an HTTP-date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = parse_http_date(value)?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parse a duration such as `1s`, `250ms`, `1.5s` or `1h2m3.5s`
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" | "" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += number * scale;
+    }
+    duration_from_secs(total)
+}
+
+/// Parse an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [_weekday, day, month, year, time, "GMT"] = parts[..] else {
+        return None;
+    };
+    let day: i64 = day.parse().ok()?;
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|m| *m == month)? as i64
+        + 1;
+    let year: i64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    if clock.next().is_some() || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60
+    {
+        return None;
+    }
+
+    // Days since the Unix epoch for a proleptic Gregorian date
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+fn duration_from_secs(secs: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// A failed request attempt and what the retry decision needs
+#[derive(Debug)]
+pub struct FailedAttempt<E> {
+    /// Error reported to the caller if the attempt is not retried
+    pub error: E,
+    /// Why the attempt may be retried, or `None` if it must not be
+    pub reason: Option<RetryReason>,
+    /// Back-off the server asked for
+    pub retry_after: Option<Duration>,
+}
+
+impl<E> FailedAttempt<E> {
+    /// An attempt that must not be retried
+    pub fn fatal(error: E) -> Self {
+        Self {
+            error,
+            reason: None,
+            retry_after: None,
+        }
+    }
+}
+
+/// Attempt limits and back-off bounds for one kind of request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Backoff before the first retry when the server gave no hint
+    pub initial_backoff: Duration,
+    /// Upper bound for the exponential backoff
+    pub max_backoff: Duration,
+    /// Upper bound for a server-provided `retry-after`; longer hints are cut
+    /// short rather than stalling the caller
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            max_retry_after: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Start tracking the attempts of one request to `provider`
+    pub fn start<'a>(&self, provider: &'a str) -> Retry<'a> {
+        Retry {
+            policy: *self,
+            provider,
+            failed: 0,
+        }
+    }
+
+    /// Delay before retrying after `failed` failed attempts
+    ///
+    /// A server hint is honored up to `max_retry_after`; without one the
+    /// delay doubles from `initial_backoff` up to `max_backoff`, with ±25%
+    /// jitter so that sessions throttled together do not retry in lockstep.
+    pub fn backoff(&self, failed: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_retry_after);
+        }
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(failed.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        // Monotonic clock as a cheap jitter source, like the request manager
+        static JITTER_BASE: OnceLock<Instant> = OnceLock::new();
+        let base = JITTER_BASE.get_or_init(Instant::now);
+        let spread = exponential.as_millis() as u64 / 2;
+        if spread == 0 {
+            return exponential;
+        }
+        let offset = base.elapsed().as_nanos() as u64 % spread;
+        exponential - Duration::from_millis(spread / 2) + Duration::from_millis(offset)
+    }
+}
+
+/// Attempts made so far for one request
+#[derive(Debug)]
+pub struct Retry<'a> {
+    policy: RetryPolicy,
+    provider: &'a str,
+    failed: u32,
+}
+
+impl Retry<'_> {
+    /// Record a failed attempt and return how long to wait before the next
+    ///
+    /// Returns `None` when the failure is not retryable or the attempts are
+    /// used up, in which case the caller reports the error.
+    pub fn next_delay(
+        &mut self,
+        reason: Option<RetryReason>,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        self.failed += 1;
+        let reason = reason?;
+        if self.failed >= self.policy.max_attempts {
+            return None;
+        }
+
+        let delay = self.policy.backoff(self.failed, retry_after);
+        global_metrics().inc_counter(
+            "waav_provider_retries_total",
+            "Provider requests retried after a transient failure",
+            &[("provider", self.provider), ("reason", reason.as_str())],
+        );
+        warn!(
+            provider = self.provider,
+            reason = reason.as_str(),
+            attempt = self.failed,
+            max_attempts = self.policy.max_attempts,
+            delay_ms = delay.as_millis() as u64,
+            server_hint = retry_after.is_some(),
+            "Retrying provider request"
+        );
+        Some(delay)
+    }
+
+    /// Number of failed attempts recorded so far
+    pub fn failed_attempts(&self) -> u32 {
+        self.failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_classify_status() {
+        let classify = |code: u16, body: &str| {
+            RetryReason::from_status(StatusCode::from_u16(code).unwrap(), body)
+        };
+        assert_eq!(classify(429, ""), Some(RetryReason::RateLimited));
+        assert_eq!(classify(498, ""), Some(RetryReason::RateLimited));
+        assert_eq!(classify(503, ""), Some(RetryReason::ServerError));
+        assert_eq!(classify(500, ""), Some(RetryReason::ServerError));
+        assert_eq!(classify(408, ""), Some(RetryReason::Timeout));
+
+        assert_eq!(classify(400, ""), None);
+        assert_eq!(classify(401, ""), None);
+        assert_eq!(classify(404, ""), None);
+        assert_eq!(classify(501, ""), None);
+        assert_eq!(
+            classify(429, r#"{"error":{"code":"insufficient_quota"}}"#),
+            None
+        );
+        assert_eq!(
+            classify(429, r#"{"detail":{"status":"quota_exceeded"}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retry_after_seconds_and_ms() {
+        assert_eq!(
+            retry_after(&headers(&[("retry-after", "3")])),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            retry_after(&headers(&[("retry-after-ms", "250"), ("retry-after", "3")])),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&headers(&[("retry-after", "soon")])), None);
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+
+        // Dates in the past mean "retry now"
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_duration("1h2m3s"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_duration("7"), Some(Duration::from_secs(7)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("2 days"), None);
+    }
+
+    #[test]
+    fn test_retry_after_rate_limit_reset() {
+        // Only the exhausted limit counts
+        let limited = headers(&[
+            ("x-ratelimit-remaining-requests", "10"),
+            ("x-ratelimit-reset-requests", "6m0s"),
+            ("x-ratelimit-remaining-tokens", "0"),
+            ("x-ratelimit-reset-tokens", "1.5s"),
+        ]);
+        assert_eq!(retry_after(&limited), Some(Duration::from_millis(1500)));
+
+        // Without remaining counts, the longest reset wins
+        let unknown = headers(&[
+            ("x-ratelimit-reset-requests", "2s"),
+            ("x-ratelimit-reset-tokens", "500ms"),
+        ]);
+        assert_eq!(retry_after(&unknown), Some(Duration::from_secs(2)));
+
+        assert_eq!(
+            retry_after(&headers(&[("x-ratelimit-reset", "4")])),
+            Some(Duration::from_secs(4))
+        );
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let epoch =
+            retry_after(&headers(&[("x-ratelimit-reset", &(now + 10).to_string())])).unwrap();
+        assert!(epoch <= Duration::from_secs(10) && epoch >= Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_backoff_honors_and_caps_server_hint() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.backoff(1, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            policy.backoff(1, Some(Duration::from_secs(3600))),
+            policy.max_retry_after
+        );
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_with_jitter() {
+        let policy = RetryPolicy::default();
+        for (failed, expected_ms) in [(1u64, 500u64), (2, 1000), (3, 2000), (10, 8000)] {
+            let delay = policy.backoff(failed as u32, None).as_millis() as u64;
+            assert!(
+                delay >= expected_ms * 3 / 4 && delay <= expected_ms * 5 / 4,
+                "attempt {failed}: {delay}ms not within 25% of {expected_ms}ms"
+            );
+        }
+    }
+
+    #[test]
+    fn test_retry_stops_after_max_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            ..Default::default()
+        };
+        let mut retry = policy.start("test");
+        let hint = Some(Duration::from_millis(10));
+        assert_eq!(retry.next_delay(Some(RetryReason::RateLimited), hint), hint);
+        assert!(retry.next_delay(Some(RetryReason::Connect), None).is_some());
+        assert_eq!(retry.next_delay(Some(RetryReason::ServerError), None), None);
+        assert_eq!(retry.failed_attempts(), 3);
+    }
+
+    #[test]
+    fn test_retry_stops_on_fatal_error() {
+        let mut retry = RetryPolicy::default().start("test");
+        assert_eq!(retry.next_delay(None, Some(Duration::from_secs(1))), None);
+    }
+}
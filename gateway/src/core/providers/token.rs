@@ -0,0 +1,354 @@
+//! Cached access tokens for providers with short-lived credentials.
+//!
+//! Azure speech tokens last 10 minutes, IBM IAM and Google OAuth tokens an
+//! hour, so a token fetched once when a session connects expires long before
+//! a long session ends. [`TokenProvider`] keeps one token per credential and
+//! hands it out until 80% of its lifetime has passed, then refreshes it:
+//!
+//! - **Single flight**: one caller refreshes; concurrent callers keep using
+//!   the current token, or wait for the refresh once it has expired
+//! - **Backoff**: a failed refresh is retried after an exponentially growing
+//!   delay, and the current token is served until it actually expires
+//!
+//! Providers call [`TokenProvider::token`] every time they authenticate a
+//! request or (re)open a stream rather than caching tokens themselves. The
+//! endpoint call itself is a [`TokenFetcher`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// Fraction of a token's lifetime after which it is refreshed
+pub const REFRESH_AT_FRACTION: f64 = 0.8;
+
+/// Delay before retrying after the first failed refresh
+pub const MIN_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest delay between retries of a failing refresh
+pub const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A token returned by a token endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessToken {
+    /// The bearer token
+    pub token: String,
+    /// How long the token is valid from when it was issued
+    pub lifetime: Duration,
+}
+
+/// Errors obtaining an access token
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TokenError {
+    /// The token endpoint could not be reached or rejected the request
+    #[error("Token request failed: {0}")]
+    RequestFailed(String),
+
+    /// The token endpoint rejected the credential itself
+    #[error("Credential rejected: {0}")]
+    Rejected(String),
+
+    /// A recent refresh failed and the next attempt is not due yet
+    #[error("Token refresh failed, retrying in {}ms: {message}", retry_in.as_millis())]
+    BackingOff { message: String, retry_in: Duration },
+}
+
+/// Fetches a new token from a provider's token endpoint
+#[async_trait::async_trait]
+pub trait TokenFetcher: Send + Sync {
+    /// Request a new token
+    async fn fetch_token(&self) -> Result<AccessToken, TokenError>;
+}
+
+struct CachedToken {
+    token: String,
+    refresh_at: Instant,
+    expires_at: Instant,
+}
+
+/// Retry state of a failing refresh
+#[derive(Default)]
+struct Backoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+    last_error: String,
+}
+
+/// Shared, self-refreshing access token
+///
+/// Cheap to share behind an `Arc`; all clones of a provider's client should
+/// use the same instance so a credential is refreshed once.
+pub struct TokenProvider {
+    fetcher: Arc<dyn TokenFetcher>,
+    cached: parking_lot::RwLock<Option<CachedToken>>,
+    /// Held while refreshing, so only one refresh runs at a time
+    refresh: Mutex<Backoff>,
+    min_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl std::fmt::Debug for TokenProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenProvider")
+            .field("cached", &self.cached.read().is_some())
+            .finish()
+    }
+}
+
+impl TokenProvider {
+    /// Create a provider that fetches tokens with `fetcher`
+    pub fn new(fetcher: impl TokenFetcher + 'static) -> Self {
+        Self {
+            fetcher: Arc::new(fetcher),
+            cached: parking_lot::RwLock::new(None),
+            refresh: Mutex::new(Backoff::default()),
+            min_backoff: MIN_RETRY_BACKOFF,
+            max_backoff: MAX_RETRY_BACKOFF,
+        }
+    }
+
+    /// Override the retry backoff bounds
+    pub fn with_backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max.max(min);
+        self
+    }
+
+    /// A valid token, refreshed if 80% of its lifetime has passed
+    ///
+    /// # Errors
+    ///
+    /// Returns the fetch error when no unexpired token is cached and the
+    /// refresh fails, or [`TokenError::BackingOff`] while waiting to retry.
+    pub async fn token(&self) -> Result<String, TokenError> {
+        if let Some(token) = self.fresh_token() {
+            return Ok(token);
+        }
+
+        // Past the refresh point: one caller refreshes, the others keep
+        // using the current token while it is still valid
+        let mut backoff = match self.refresh.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => match self.valid_token() {
+                Some(token) => return Ok(token),
+                None => self.refresh.lock().await,
+            },
+        };
+
+        // Another caller may have refreshed while this one waited
+        if let Some(token) = self.fresh_token() {
+            return Ok(token);
+        }
+
+        let now = Instant::now();
+        if let Some(retry_at) = backoff.retry_at
+            && now < retry_at
+        {
+            return self.valid_token().ok_or_else(|| TokenError::BackingOff {
+                message: backoff.last_error.clone(),
+                retry_in: retry_at - now,
+            });
+        }
+
+        match self.fetcher.fetch_token().await {
+            Ok(fetched) => {
+                let now = Instant::now();
+                debug!(
+                    lifetime_secs = fetched.lifetime.as_secs(),
+                    "Fetched new access token"
+                );
+                *self.cached.write() = Some(CachedToken {
+                    token: fetched.token.clone(),
+                    refresh_at: now + fetched.lifetime.mul_f64(REFRESH_AT_FRACTION),
+                    expires_at: now + fetched.lifetime,
+                });
+                *backoff = Backoff::default();
+                Ok(fetched.token)
+            }
+            Err(e) => {
+                let delay = self
+                    .min_backoff
+                    .saturating_mul(2u32.saturating_pow(backoff.failures))
+                    .min(self.max_backoff);
+                backoff.failures += 1;
+                backoff.retry_at = Some(Instant::now() + delay);
+                backoff.last_error = e.to_string();
+                warn!(
+                    error = %e,
+                    failures = backoff.failures,
+                    retry_in_ms = delay.as_millis() as u64,
+                    "Access token refresh failed"
+                );
+                self.valid_token().ok_or(e)
+            }
+        }
+    }
+
+    /// Drop the cached token, e.g. after the provider rejected it
+    ///
+    /// The next call to [`token`](Self::token) fetches a new one.
+    pub fn invalidate(&self) {
+        *self.cached.write() = None;
+    }
+
+    /// The cached token if it is not due for refresh
+    fn fresh_token(&self) -> Option<String> {
+        self.cached
+            .read()
+            .as_ref()
+            .filter(|cached| Instant::now() < cached.refresh_at)
+            .map(|cached| cached.token.clone())
+    }
+
+    /// The cached token if it has not expired
+    fn valid_token(&self) -> Option<String> {
+        self.cached
+            .read()
+            .as_ref()
+            .filter(|cached| Instant::now() < cached.expires_at)
+            .map(|cached| cached.token.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// Issues `token-N` tokens with a fixed lifetime, optionally failing
+    struct MockFetcher {
+        lifetime: Duration,
+        delay: Duration,
+        calls: Arc<AtomicU32>,
+        failing: Arc<AtomicBool>,
+    }
+
+    impl MockFetcher {
+        fn new(lifetime: Duration) -> (Self, Arc<AtomicU32>, Arc<AtomicBool>) {
+            let calls = Arc::new(AtomicU32::new(0));
+            let failing = Arc::new(AtomicBool::new(false));
+            let fetcher = Self {
+                lifetime,
+                delay: Duration::ZERO,
+                calls: calls.clone(),
+                failing: failing.clone(),
+            };
+            (fetcher, calls, failing)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenFetcher for MockFetcher {
+        async fn fetch_token(&self) -> Result<AccessToken, TokenError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::time::sleep(self.delay).await;
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(TokenError::RequestFailed("endpoint down".to_string()));
+            }
+            Ok(AccessToken {
+                token: format!("token-{call}"),
+                lifetime: self.lifetime,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached_until_refresh_point() {
+        let (fetcher, calls, _) = MockFetcher::new(Duration::from_millis(500));
+        let provider = TokenProvider::new(fetcher);
+
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 80% of 500ms has passed
+        tokio::time::sleep(Duration::from_millis(420)).await;
+        assert_eq!(provider.token().await.unwrap(), "token-2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_callers_share_one_refresh() {
+        let (mut fetcher, calls, _) = MockFetcher::new(Duration::from_secs(60));
+        fetcher.delay = Duration::from_millis(50);
+        let provider = Arc::new(TokenProvider::new(fetcher));
+
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let provider = provider.clone();
+                tokio::spawn(async move { provider.token().await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), "token-1");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_current_token_served_during_refresh() {
+        let (mut fetcher, calls, _) = MockFetcher::new(Duration::from_millis(200));
+        fetcher.delay = Duration::from_millis(30);
+        let provider = Arc::new(TokenProvider::new(fetcher));
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+
+        tokio::time::sleep(Duration::from_millis(170)).await;
+        let refreshing = {
+            let provider = provider.clone();
+            tokio::spawn(async move { provider.token().await })
+        };
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        // The refresh is in flight and token-1 has not expired yet
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(refreshing.await.unwrap().unwrap(), "token-2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_backs_off() {
+        let (fetcher, calls, failing) = MockFetcher::new(Duration::from_millis(200));
+        let provider = TokenProvider::new(fetcher)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(400));
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+
+        // Refresh fails, but token-1 is still valid
+        failing.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(170)).await;
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // token-1 has expired and the retry is not due yet
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        let err = provider.token().await.unwrap_err();
+        assert!(matches!(err, TokenError::BackingOff { .. }), "{err:?}");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The retry is due but fails again; the next delay doubles
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert_eq!(
+            provider.token().await.unwrap_err(),
+            TokenError::RequestFailed("endpoint down".to_string())
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(provider.token().await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Recovery resets the backoff
+        failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(provider.token().await.unwrap(), "token-4");
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_forces_refresh() {
+        let (fetcher, calls, _) = MockFetcher::new(Duration::from_secs(60));
+        let provider = TokenProvider::new(fetcher);
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        provider.invalidate();
+        assert_eq!(provider.token().await.unwrap(), "token-2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
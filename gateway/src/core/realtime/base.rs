@@ -18,6 +18,7 @@ use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 // =============================================================================
@@ -70,6 +71,15 @@ pub enum RealtimeError {
     /// Internal error
     #[error("Internal error: {0}")]
     InternalError(String),
+
+    /// Connecting to the provider took longer than the configured timeout
+    #[error("Connection to {provider} timed out after {timeout:?}")]
+    ConnectionTimeout {
+        /// Provider name
+        provider: String,
+        /// Configured connection timeout
+        timeout: Duration,
+    },
 }
 
 /// Result type for realtime operations.
@@ -527,6 +537,21 @@ pub trait BaseRealtime: Send + Sync {
     /// Connect to the realtime provider.
     async fn connect(&mut self) -> RealtimeResult<()>;
 
+    /// Connect to the realtime provider, giving up after `timeout`.
+    ///
+    /// The pending `connect()` is cancelled when the timeout elapses and
+    /// `RealtimeError::ConnectionTimeout` is returned.
+    async fn connect_with_timeout(&mut self, timeout: Duration) -> RealtimeResult<()> {
+        match tokio::time::timeout(timeout, self.connect()).await {
+            Ok(result) => result,
+            Err(_) => {
+                let info = self.get_provider_info();
+                let provider = info["provider"].as_str().unwrap_or("unknown").to_string();
+                Err(RealtimeError::ConnectionTimeout { provider, timeout })
+            }
+        }
+    }
+
     /// Disconnect from the realtime provider.
     async fn disconnect(&mut self) -> RealtimeResult<()>;
 
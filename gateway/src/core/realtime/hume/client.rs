@@ -809,4 +809,72 @@ mod tests {
         let result = evi.clear_audio_buffer().await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_hume_evi_tool_call_round_trip() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (response_tx, mut response_rx) = mpsc::unbounded_channel::<serde_json::Value>();
+
+        // Mock EVI server: issue one tool call and forward the client's replies
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let tool_call = serde_json::json!({
+                "type": "tool_call",
+                "tool_call_id": "call_1",
+                "name": "get_weather",
+                "parameters": "{\"location\":\"Paris\"}",
+                "id": "msg_1"
+            });
+            ws.send(Message::Text(tool_call.to_string().into()))
+                .await
+                .unwrap();
+
+            while let Some(Ok(msg)) = ws.next().await {
+                if let Message::Text(text) = msg {
+                    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let _ = response_tx.send(value);
+                }
+            }
+        });
+
+        let mut config = HumeEVIConfig::new("test-key");
+        config.websocket_url = format!("ws://{addr}");
+        let mut evi = HumeEVI::from_hume_config(config).unwrap();
+
+        let (call_tx, mut call_rx) = mpsc::unbounded_channel::<FunctionCallRequest>();
+        evi.on_function_call(Arc::new(move |request| {
+            let call_tx = call_tx.clone();
+            Box::pin(async move {
+                let _ = call_tx.send(request);
+            })
+        }))
+        .unwrap();
+
+        evi.connect().await.unwrap();
+
+        let request = timeout(Duration::from_secs(2), call_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.call_id, "call_1");
+        assert_eq!(request.name, "get_weather");
+        assert_eq!(request.arguments, r#"{"location":"Paris"}"#);
+        assert_eq!(request.item_id.as_deref(), Some("msg_1"));
+
+        evi.submit_function_result(&request.call_id, r#"{"temperature":18}"#)
+            .await
+            .unwrap();
+
+        let response = timeout(Duration::from_secs(2), response_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response["type"], "tool_response");
+        assert_eq!(response["tool_call_id"], "call_1");
+        assert_eq!(response["content"], r#"{"temperature":18}"#);
+
+        evi.disconnect().await.unwrap();
+    }
 }
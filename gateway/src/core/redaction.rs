@@ -0,0 +1,240 @@
+//! Redaction windows of a session's recordings
+//!
+//! While a window is open, the gateway's captures of the session (the
+//! caller recording and monitor audio) receive silence instead of the
+//! session's audio; the live audio reaching the providers and the caller is
+//! untouched. Windows are kept as [`RedactionWindow`]s, which the session's
+//! exports list next to its recordings.
+//!
+//! A window is opened by the client (`{"type": "redaction", "state":
+//! "start"}`) and stays open until the client stops it, or by DTMF digits,
+//! in which case it closes once no digit arrived for a while.
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// What opened a redaction window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionSource {
+    /// The client's `redaction` message
+    Client,
+    /// DTMF digits keyed in by the caller
+    Dtmf,
+}
+
+impl RedactionSource {
+    /// Name used in messages, logs and metrics
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Dtmf => "dtmf",
+        }
+    }
+}
+
+/// A stretch of the session its recordings hold silence for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RedactionWindow {
+    /// When the window opened (Unix ms)
+    pub start_ms: u64,
+    /// When the window closed (Unix ms)
+    pub end_ms: u64,
+    /// What opened the window
+    pub source: RedactionSource,
+}
+
+/// Redaction windows of one session
+#[derive(Default)]
+pub struct RecordingRedaction {
+    /// Whether a window is open; read for every chunk of captured audio
+    active: AtomicBool,
+    windows: Mutex<Windows>,
+}
+
+#[derive(Default)]
+struct Windows {
+    /// Start and source of the open window
+    open: Option<(u64, RedactionSource)>,
+    closed: Vec<RedactionWindow>,
+    /// Bumped by every DTMF digit, so only the last digit's idle timer can
+    /// close the window
+    dtmf_generation: u64,
+}
+
+impl RecordingRedaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a window is open
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Open a window at `now_ms`
+    ///
+    /// A client starting a window DTMF digits opened takes it over, so it
+    /// stays open until the client stops it.
+    ///
+    /// # Returns
+    /// * `true` if the window was opened, `false` if one was open already
+    pub fn start(&self, source: RedactionSource, now_ms: u64) -> bool {
+        let mut windows = self.windows.lock();
+        match &mut windows.open {
+            Some((_, open_source)) => {
+                if source == RedactionSource::Client {
+                    *open_source = source;
+                }
+                false
+            }
+            None => {
+                windows.open = Some((now_ms, source));
+                self.active.store(true, Ordering::Release);
+                true
+            }
+        }
+    }
+
+    /// Close the open window at `now_ms`
+    ///
+    /// # Returns
+    /// * The closed window, or `None` if no window was open
+    pub fn stop(&self, now_ms: u64) -> Option<RedactionWindow> {
+        let mut windows = self.windows.lock();
+        let (start_ms, source) = windows.open.take()?;
+        self.active.store(false, Ordering::Release);
+        let span = RedactionWindow {
+            start_ms,
+            end_ms: now_ms.max(start_ms),
+            source,
+        };
+        windows.closed.push(span);
+        Some(span)
+    }
+
+    /// Note a DTMF digit received at `now_ms`, opening a window if none is open
+    ///
+    /// # Returns
+    /// * Whether a window was opened, and the generation to pass to
+    ///   [`dtmf_idle`](Self::dtmf_idle) once the idle time has passed
+    pub fn dtmf_digit(&self, now_ms: u64) -> (bool, u64) {
+        let opened = self.start(RedactionSource::Dtmf, now_ms);
+        let mut windows = self.windows.lock();
+        windows.dtmf_generation += 1;
+        (opened, windows.dtmf_generation)
+    }
+
+    /// Close the window DTMF digits opened, unless another digit arrived
+    /// after the one that returned `generation`
+    ///
+    /// # Returns
+    /// * The closed window, or `None` if it stays open
+    pub fn dtmf_idle(&self, generation: u64, now_ms: u64) -> Option<RedactionWindow> {
+        {
+            let windows = self.windows.lock();
+            if windows.dtmf_generation != generation
+                || !matches!(windows.open, Some((_, RedactionSource::Dtmf)))
+            {
+                return None;
+            }
+        }
+        self.stop(now_ms)
+    }
+
+    /// Every window so far; an open window ends at `now_ms`
+    pub fn spans(&self, now_ms: u64) -> Vec<RedactionWindow> {
+        let windows = self.windows.lock();
+        let mut spans = windows.closed.clone();
+        if let Some((start_ms, source)) = windows.open {
+            spans.push(RedactionWindow {
+                start_ms,
+                end_ms: now_ms.max(start_ms),
+                source,
+            });
+        }
+        spans
+    }
+
+    /// Audio a capture receives for a chunk of 16-bit PCM
+    ///
+    /// # Returns
+    /// * `pcm` as it is, or silence of the same length while a window is open
+    pub fn redact<'a>(&self, pcm: &'a [u8]) -> Cow<'a, [u8]> {
+        if self.is_active() {
+            Cow::Owned(vec![0; pcm.len()])
+        } else {
+            Cow::Borrowed(pcm)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_silences_captures() {
+        let redaction = RecordingRedaction::new();
+        let pcm = [1u8, 2, 3, 4];
+        assert_eq!(&*redaction.redact(&pcm), &pcm);
+
+        assert!(redaction.start(RedactionSource::Client, 1_000));
+        assert!(!redaction.start(RedactionSource::Client, 1_100));
+        assert!(redaction.is_active());
+        assert_eq!(&*redaction.redact(&pcm), &[0; 4]);
+
+        let span = redaction.stop(4_000).unwrap();
+        assert_eq!(
+            span,
+            RedactionWindow {
+                start_ms: 1_000,
+                end_ms: 4_000,
+                source: RedactionSource::Client
+            }
+        );
+        assert!(redaction.stop(4_100).is_none());
+        assert_eq!(&*redaction.redact(&pcm), &pcm);
+    }
+
+    #[test]
+    fn test_dtmf_window_closes_after_last_digit() {
+        let redaction = RecordingRedaction::new();
+        let (opened, first) = redaction.dtmf_digit(1_000);
+        assert!(opened);
+        let (opened, second) = redaction.dtmf_digit(1_500);
+        assert!(!opened);
+
+        // The first digit's timer fires while the second is still fresh
+        assert!(redaction.dtmf_idle(first, 6_000).is_none());
+        assert!(redaction.is_active());
+        let span = redaction.dtmf_idle(second, 6_500).unwrap();
+        assert_eq!((span.start_ms, span.end_ms), (1_000, 6_500));
+        assert_eq!(span.source, RedactionSource::Dtmf);
+        assert!(!redaction.is_active());
+    }
+
+    #[test]
+    fn test_client_takes_over_dtmf_window() {
+        let redaction = RecordingRedaction::new();
+        let (_, generation) = redaction.dtmf_digit(1_000);
+        assert!(!redaction.start(RedactionSource::Client, 1_200));
+        assert!(redaction.dtmf_idle(generation, 6_000).is_none());
+
+        assert_eq!(
+            redaction.spans(7_000),
+            vec![RedactionWindow {
+                start_ms: 1_000,
+                end_ms: 7_000,
+                source: RedactionSource::Client
+            }]
+        );
+        redaction.stop(8_000);
+        assert_eq!(redaction.spans(9_000)[0].end_ms, 8_000);
+    }
+}
@@ -0,0 +1,327 @@
+//! Playback length and sample rate of WAV and MP3 output audio
+//!
+//! Providers that stream a container format send its header with the first
+//! chunk only, so the length of a chunk is read from whatever it holds: a
+//! WAV chunk with a RIFF header is measured by the header's byte rate, an
+//! MP3 chunk by the frames it contains. Chunks without a header fall back to
+//! [`audio_duration_ms`](super::usage::audio_duration_ms)'s byte count.
+//! The same headers give the sample rate the provider actually produced,
+//! which can differ from the one requested.
+
+/// MPEG-1 Layer III bitrates in kbit/s by bitrate index
+const MPEG1_BITRATES: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+
+/// MPEG-2 and MPEG-2.5 Layer III bitrates in kbit/s by bitrate index
+const MPEG2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// MPEG-1 sample rates in Hz; MPEG-2 halves them and MPEG-2.5 quarters them
+const MPEG1_SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+/// Playback length in milliseconds of a WAV or MP3 chunk, read from its contents
+///
+/// # Returns
+/// * `Some(ms)` - The chunk holds a WAV header or MP3 frames
+/// * `None` - Another format, or a chunk with nothing to measure
+pub fn container_duration_ms(format: &str, data: &[u8]) -> Option<u64> {
+    match format {
+        "wav" => wav_duration_ms(data),
+        "mp3" => mp3_duration_ms(data),
+        _ => None,
+    }
+}
+
+/// Sample rate declared by a WAV header or the MP3 frames of a chunk
+///
+/// # Returns
+/// * `Some(hz)` - The chunk holds a WAV header or MP3 frames
+/// * `None` - Another format, or a chunk without a header
+pub fn container_sample_rate(format: &str, data: &[u8]) -> Option<u32> {
+    match format {
+        "wav" => wav_sample_rate(data),
+        "mp3" => mp3_sample_rate(data),
+        _ => None,
+    }
+}
+
+/// Sample rate in the `fmt ` chunk of a RIFF/WAVE header
+fn wav_sample_rate(data: &[u8]) -> Option<u32> {
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return None;
+    }
+
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let chunk_size = read_u32_le(&data[offset + 4..offset + 8]) as usize;
+        let body_start = offset + 8;
+        match &data[offset..offset + 4] {
+            b"fmt " if data.len() >= body_start + 8 => {
+                let sample_rate = read_u32_le(&data[body_start + 4..body_start + 8]);
+                return (sample_rate > 0).then_some(sample_rate);
+            }
+            b"data" => return None,
+            _ => {}
+        }
+        offset = body_start.saturating_add(chunk_size + (chunk_size & 1));
+    }
+    None
+}
+
+/// Sample rate of the first MP3 frame of a chunk
+///
+/// A frame header found mid-chunk only counts when the frame after it
+/// starts with a header too, since compressed data can look like a header.
+fn mp3_sample_rate(data: &[u8]) -> Option<u32> {
+    let start = id3_tag_length(data);
+    (start..data.len()).find_map(|i| {
+        let frame = Mp3Frame::parse(&data[i..])?;
+        let next = i + frame.length;
+        let confirmed = if next + 4 <= data.len() {
+            Mp3Frame::parse(&data[next..]).is_some_and(|next| next.sample_rate == frame.sample_rate)
+        } else {
+            i == start
+        };
+        confirmed.then_some(frame.sample_rate)
+    })
+}
+
+/// Length of the data chunk of a RIFF/WAVE header
+///
+/// A streamed header declares an unknown data size, so the data is measured
+/// up to the end of the chunk.
+fn wav_duration_ms(data: &[u8]) -> Option<u64> {
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return None;
+    }
+
+    let mut byte_rate = None;
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let chunk_size = read_u32_le(&data[offset + 4..offset + 8]) as usize;
+        let body_start = offset + 8;
+        let body_end = body_start.saturating_add(chunk_size).min(data.len());
+        match &data[offset..offset + 4] {
+            b"fmt " if body_end - body_start >= 12 => {
+                byte_rate = Some(read_u32_le(&data[body_start + 8..body_start + 12]));
+            }
+            b"data" => {
+                let byte_rate = byte_rate.filter(|rate| *rate > 0)?;
+                return Some((body_end - body_start) as u64 * 1000 / byte_rate as u64);
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        offset = body_start.saturating_add(chunk_size + (chunk_size & 1));
+    }
+    None
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Layer III frame header fields needed to measure a frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Mp3Frame {
+    /// Frame length in bytes, including the header
+    length: usize,
+    /// Bitrate in bit/s
+    bitrate: u32,
+    /// Sample rate in Hz
+    sample_rate: u32,
+    /// PCM samples the frame decodes to
+    samples: u32,
+}
+
+impl Mp3Frame {
+    /// Parse the 4-byte frame header at the start of `bytes`
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+        let version = (bytes[1] >> 3) & 0x03;
+        let layer = (bytes[1] >> 1) & 0x03;
+        let bitrate_index = (bytes[2] >> 4) as usize;
+        let sample_rate_index = ((bytes[2] >> 2) & 0x03) as usize;
+        let padding = ((bytes[2] >> 1) & 0x01) as usize;
+        // Version 1 is reserved, layer 1 is Layer III; free-format and bad
+        // bitrates and the reserved sample rate cannot be measured
+        if version == 1 || layer != 1 || bitrate_index == 0 || bitrate_index == 15 {
+            return None;
+        }
+        let base_rate = *MPEG1_SAMPLE_RATES.get(sample_rate_index)?;
+
+        let (bitrates, sample_rate, samples, coefficient) = match version {
+            3 => (&MPEG1_BITRATES, base_rate, 1152, 144),
+            2 => (&MPEG2_BITRATES, base_rate / 2, 576, 72),
+            _ => (&MPEG2_BITRATES, base_rate / 4, 576, 72),
+        };
+        let bitrate = bitrates[bitrate_index] * 1000;
+        Some(Self {
+            length: (coefficient * bitrate / sample_rate) as usize + padding,
+            bitrate,
+            sample_rate,
+            samples,
+        })
+    }
+
+    /// Playback length of the frame in milliseconds
+    fn duration_ms(&self) -> f64 {
+        self.samples as f64 * 1000.0 / self.sample_rate as f64
+    }
+}
+
+/// Length of the MP3 frames in a chunk
+///
+/// Bytes before the first frame header finish a frame started in the
+/// previous chunk and a frame cut off at the end continues in the next one;
+/// both count in proportion to their bytes, so consecutive chunks add up to
+/// the length of the stream.
+fn mp3_duration_ms(data: &[u8]) -> Option<u64> {
+    let start = id3_tag_length(data);
+    let first = (start..data.len()).find(|&i| Mp3Frame::parse(&data[i..]).is_some())?;
+
+    // Continuation of a frame from the previous chunk, at the first frame's bitrate
+    let first_frame = Mp3Frame::parse(&data[first..])?;
+    let mut duration_ms = if start == 0 {
+        first as f64 * 8000.0 / first_frame.bitrate as f64
+    } else {
+        0.0
+    };
+
+    let mut offset = first;
+    while offset < data.len() {
+        let Some(frame) = Mp3Frame::parse(&data[offset..]) else {
+            // Skip junk between frames
+            offset += 1;
+            continue;
+        };
+        let available = (data.len() - offset).min(frame.length);
+        duration_ms += frame.duration_ms() * available as f64 / frame.length as f64;
+        offset += frame.length;
+    }
+    Some(duration_ms.round() as u64)
+}
+
+/// Length of an ID3v2 tag at the start of an MP3 stream, or zero
+fn id3_tag_length(data: &[u8]) -> usize {
+    if data.len() < 10 || &data[0..3] != b"ID3" {
+        return 0;
+    }
+    // The tag size is a 28-bit "syncsafe" integer, 7 bits per byte
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |size, byte| (size << 7) | (*byte & 0x7F) as usize);
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    (10 + size + footer).min(data.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(sample_rate: u32, pcm_bytes: usize, data_size: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + pcm_bytes as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        out.extend_from_slice(&1u16.to_le_bytes()); // mono
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_size.to_le_bytes());
+        out.extend(std::iter::repeat_n(0u8, pcm_bytes));
+        out
+    }
+
+    /// MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, no padding: 417 byte frames
+    fn mp3_frames(count: usize) -> Vec<u8> {
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        frame.repeat(count)
+    }
+
+    #[test]
+    fn test_wav_duration_from_header() {
+        let audio = wav(16000, 32000, 32000);
+        assert_eq!(container_duration_ms("wav", &audio), Some(1000));
+
+        // Streamed headers declare an unknown size; the data present counts
+        let streamed = wav(24000, 4800, u32::MAX);
+        assert_eq!(container_duration_ms("wav", &streamed), Some(100));
+
+        // Later chunks are raw PCM without a header
+        assert_eq!(container_duration_ms("wav", &[0u8; 3200]), None);
+    }
+
+    #[test]
+    fn test_mp3_frame_header() {
+        let frame = Mp3Frame::parse(&[0xFF, 0xFB, 0x90, 0x00]).unwrap();
+        assert_eq!(frame.length, 417);
+        assert_eq!(frame.bitrate, 128_000);
+        assert_eq!(frame.sample_rate, 44100);
+        // MPEG-2 Layer III, 64 kbit/s, 24 kHz
+        let frame = Mp3Frame::parse(&[0xFF, 0xF3, 0x84, 0x00]).unwrap();
+        assert_eq!((frame.sample_rate, frame.samples), (24000, 576));
+        assert_eq!(frame.length, 192);
+        // Layer II and free-format headers are not measured
+        assert!(Mp3Frame::parse(&[0xFF, 0xFD, 0x90, 0x00]).is_none());
+        assert!(Mp3Frame::parse(&[0xFF, 0xFB, 0x00, 0x00]).is_none());
+    }
+
+    #[test]
+    fn test_mp3_duration_counts_frames() {
+        // 38 frames of 1152 samples at 44.1 kHz is 992.65 ms
+        let audio = mp3_frames(38);
+        assert_eq!(container_duration_ms("mp3", &audio), Some(993));
+
+        // An ID3 tag is skipped
+        let mut tagged = vec![b'I', b'D', b'3', 4, 0, 0, 0, 0, 0, 20];
+        tagged.extend(std::iter::repeat_n(0u8, 20));
+        tagged.extend(mp3_frames(38));
+        assert_eq!(container_duration_ms("mp3", &tagged), Some(993));
+    }
+
+    #[test]
+    fn test_container_sample_rate() {
+        assert_eq!(
+            container_sample_rate("wav", &wav(16000, 320, 320)),
+            Some(16000)
+        );
+        assert_eq!(container_sample_rate("wav", &[0u8; 320]), None);
+
+        assert_eq!(container_sample_rate("mp3", &mp3_frames(3)), Some(44100));
+        // A chunk starting mid-frame is read from the next whole frames
+        assert_eq!(
+            container_sample_rate("mp3", &mp3_frames(3)[100..]),
+            Some(44100)
+        );
+        // A lone header-like pattern inside compressed data is not trusted
+        let mut junk = vec![0u8; 600];
+        junk[50..54].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        assert_eq!(container_sample_rate("mp3", &junk), None);
+
+        assert_eq!(
+            container_sample_rate("linear16", &wav(16000, 320, 320)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mp3_chunks_split_mid_frame_add_up() {
+        let audio = mp3_frames(38);
+        let (first, second) = audio.split_at(5000);
+        let total = container_duration_ms("mp3", first).unwrap()
+            + container_duration_ms("mp3", second).unwrap();
+        assert!((992..=994).contains(&total), "total was {total}");
+
+        assert_eq!(container_duration_ms("mp3", &[0u8; 100]), None);
+        assert_eq!(container_duration_ms("linear16", &audio), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Audio levels for client level meters
+//!
+//! Web clients draw a mic level indicator and an "assistant speaking"
+//! animation from levels the session measures, instead of running DSP in the
+//! browser. RMS and peak levels of 16-bit PCM are measured over 100ms windows
+//! and reported in dBFS, where 0 dB is a full-scale square wave.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Length of the audio window each level is measured over (ms)
+pub const LEVEL_WINDOW_MS: u32 = 100;
+
+/// Level reported for digital silence (dBFS)
+pub const SILENCE_DB: f32 = -100.0;
+
+/// Magnitude of a full-scale 16-bit sample
+const FULL_SCALE: f64 = 32768.0;
+
+/// Audio path a level was measured on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum AudioDirection {
+    /// Caller audio pushed into the session
+    In,
+    /// Audio produced by the session (TTS or realtime output)
+    Out,
+}
+
+/// Level of one window of audio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioLevel {
+    pub direction: AudioDirection,
+    /// RMS level in dBFS, rounded to 0.1 dB
+    pub rms_db: f32,
+    /// Peak sample level in dBFS, rounded to 0.1 dB
+    pub peak_db: f32,
+}
+
+/// Whether audio in `encoding` is 16-bit linear PCM and can be metered
+pub(super) fn is_pcm16(encoding: &str) -> bool {
+    matches!(encoding, "linear16" | "pcm" | "pcm16")
+}
+
+/// Measures one audio path, one window at a time
+///
+/// Keeps running sums only, so measuring a frame does not allocate.
+pub(super) struct LevelMeter {
+    direction: AudioDirection,
+    window: Mutex<Window>,
+}
+
+#[derive(Default)]
+struct Window {
+    samples: u32,
+    sum_squares: f64,
+    peak: u32,
+    /// When a level was last reported
+    last_report: Option<Instant>,
+}
+
+impl LevelMeter {
+    pub(super) fn new(direction: AudioDirection) -> Self {
+        Self {
+            direction,
+            window: Mutex::new(Window::default()),
+        }
+    }
+
+    /// Add little-endian PCM16 samples recorded at `sample_rate`
+    ///
+    /// Returns the level of the last window these samples completed, unless
+    /// a level was reported less than a window's length before `now`. Audio
+    /// that arrives faster than real time (TTS output) is therefore still
+    /// reported about every 100ms.
+    pub(super) fn measure(&self, pcm: &[u8], sample_rate: u32, now: Instant) -> Option<AudioLevel> {
+        let window_samples = (sample_rate * LEVEL_WINDOW_MS / 1000).max(1);
+        let mut window = self.window.lock();
+        let mut completed = None;
+
+        for sample in pcm.chunks_exact(2) {
+            let sample = i16::from_le_bytes([sample[0], sample[1]]) as i32;
+            window.sum_squares += (sample * sample) as f64;
+            window.peak = window.peak.max(sample.unsigned_abs());
+            window.samples += 1;
+            if window.samples == window_samples {
+                completed = Some(window.level(self.direction));
+                window.samples = 0;
+                window.sum_squares = 0.0;
+                window.peak = 0;
+            }
+        }
+
+        let level = completed?;
+        let throttle = Duration::from_millis(LEVEL_WINDOW_MS as u64);
+        if window
+            .last_report
+            .is_some_and(|last| now.saturating_duration_since(last) < throttle)
+        {
+            return None;
+        }
+        window.last_report = Some(now);
+        Some(level)
+    }
+}
+
+impl Window {
+    fn level(&self, direction: AudioDirection) -> AudioLevel {
+        let rms = (self.sum_squares / self.samples as f64).sqrt();
+        AudioLevel {
+            direction,
+            rms_db: to_dbfs(rms),
+            peak_db: to_dbfs(self.peak as f64),
+        }
+    }
+}
+
+/// Convert a sample magnitude to dBFS, floored at `SILENCE_DB`
+pub(super) fn to_dbfs(magnitude: f64) -> f32 {
+    if magnitude <= 0.0 {
+        return SILENCE_DB;
+    }
+    let db = 20.0 * (magnitude / FULL_SCALE).log10();
+    ((db * 10.0).round() / 10.0).max(SILENCE_DB as f64) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PCM16 sine wave at `amplitude` (fraction of full scale)
+    fn sine(amplitude: f64, frequency: f64, sample_rate: u32, ms: u32) -> Vec<u8> {
+        let count = sample_rate * ms / 1000;
+        (0..count)
+            .flat_map(|i| {
+                let t = i as f64 / sample_rate as f64;
+                let value =
+                    amplitude * FULL_SCALE * (2.0 * std::f64::consts::PI * frequency * t).sin();
+                (value.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16).to_le_bytes()
+            })
+            .collect()
+    }
+
+    fn assert_db(actual: f32, expected: f64) {
+        assert!(
+            (actual as f64 - expected).abs() <= 0.11,
+            "expected {expected:.2} dB, got {actual} dB"
+        );
+    }
+
+    #[test]
+    fn test_sine_levels() {
+        // A sine's RMS is its amplitude / sqrt(2), i.e. 3.01 dB below its peak
+        for (amplitude, peak_db) in [(0.5, -6.02), (0.1, -20.0), (0.01, -40.0)] {
+            let meter = LevelMeter::new(AudioDirection::In);
+            let level = meter
+                .measure(&sine(amplitude, 1000.0, 16000, 100), 16000, Instant::now())
+                .unwrap();
+            assert_eq!(level.direction, AudioDirection::In);
+            assert_db(level.peak_db, peak_db);
+            assert_db(level.rms_db, peak_db - 3.01);
+        }
+    }
+
+    #[test]
+    fn test_silence_is_floored() {
+        let meter = LevelMeter::new(AudioDirection::Out);
+        let level = meter
+            .measure(&vec![0u8; 3200], 16000, Instant::now())
+            .unwrap();
+        assert_eq!(level.rms_db, SILENCE_DB);
+        assert_eq!(level.peak_db, SILENCE_DB);
+    }
+
+    #[test]
+    fn test_windows_span_frames() {
+        let meter = LevelMeter::new(AudioDirection::In);
+        let audio = sine(0.25, 440.0, 8000, 100);
+        let now = Instant::now();
+
+        // 20ms frames: the fifth completes the window
+        for frame in audio.chunks(320).take(4) {
+            assert!(meter.measure(frame, 8000, now).is_none());
+        }
+        let level = meter.measure(&audio[1280..], 8000, now).unwrap();
+        assert_db(level.peak_db, -12.04);
+    }
+
+    #[test]
+    fn test_reports_are_throttled() {
+        let meter = LevelMeter::new(AudioDirection::Out);
+        // A second of TTS audio generated at once yields a single level
+        let burst = sine(0.5, 300.0, 24000, 1000);
+        let start = Instant::now();
+        assert!(meter.measure(&burst, 24000, start).is_some());
+        assert!(meter.measure(&burst, 24000, start).is_none());
+        assert!(
+            meter
+                .measure(&burst, 24000, start + Duration::from_millis(99))
+                .is_none()
+        );
+        assert!(
+            meter
+                .measure(&burst, 24000, start + Duration::from_millis(100))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_is_pcm16() {
+        assert!(is_pcm16("linear16"));
+        assert!(is_pcm16("pcm"));
+        assert!(!is_pcm16("mulaw"));
+        assert!(!is_pcm16("mp3"));
+    }
+}
@@ -0,0 +1,220 @@
+//! Barge-in: interrupt TTS output as soon as the caller starts speaking
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+use super::barge_in_confirmation::{BargeInConfirmation, BargeInConfirmationConfig};
+use super::echo_guard::EchoGuard;
+use super::events::{EventEmitter, SessionEvent};
+use crate::core::{
+    agent_bridge::AgentBridge,
+    stt::{STTResult, STTVadEvent},
+    voice_manager::VoiceManager,
+};
+
+/// What interrupts TTS output when the caller speaks over it
+///
+/// # Example JSON
+/// ```json
+/// "on_vad"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BargeInMode {
+    /// The STT provider's speech-started VAD event, before any transcript.
+    /// Providers without VAD events fall back to `on_interim`.
+    OnVad,
+    /// The first non-empty transcript (interim or final) of a user turn
+    OnInterim,
+    /// Nothing; output is only cleared explicitly
+    #[default]
+    Off,
+}
+
+/// Clears a voice session's TTS output at most once per user turn
+///
+/// With an echo guard, speech picked up while TTS audio is playing must pass
+/// the guard's thresholds: a VAD speech start is not trusted on its own and
+/// barge-in waits for a transcript, and transcripts repeating the spoken
+/// text are dropped before they reach the agent bridge and the client.
+///
+/// With confirmation, a VAD speech start never interrupts on its own and a
+/// transcript only interrupts once the turn's speech is confirmed; the
+/// transcripts held back are emitted as `SessionEvent::BargeInSuppressed`.
+pub(super) struct BargeIn {
+    mode: BargeInMode,
+    voice_manager: Weak<VoiceManager>,
+    agent_bridge: Option<Arc<AgentBridge>>,
+    echo_guard: Option<Arc<EchoGuard>>,
+    confirmation: Option<BargeInConfirmation>,
+    emitter: EventEmitter,
+    /// Whether the STT provider reports VAD events
+    vad_supported: AtomicBool,
+    /// Set once output has been interrupted for the current user turn
+    interrupted: AtomicBool,
+    /// Set when a VAD speech start was held back by the echo guard, so the
+    /// turn's transcripts decide instead
+    vad_deferred: AtomicBool,
+}
+
+impl BargeIn {
+    pub(super) fn new(
+        mode: BargeInMode,
+        voice_manager: &Arc<VoiceManager>,
+        agent_bridge: Option<Arc<AgentBridge>>,
+        echo_guard: Option<Arc<EchoGuard>>,
+        confirmation: Option<BargeInConfirmationConfig>,
+        emitter: EventEmitter,
+    ) -> Self {
+        Self {
+            mode,
+            voice_manager: Arc::downgrade(voice_manager),
+            agent_bridge,
+            echo_guard,
+            confirmation: confirmation.map(BargeInConfirmation::new),
+            emitter,
+            vad_supported: AtomicBool::new(false),
+            interrupted: AtomicBool::new(false),
+            vad_deferred: AtomicBool::new(false),
+        }
+    }
+
+    /// Record text sent to the TTS provider
+    pub(super) fn on_tts_text(&self, text: &str) {
+        if let Some(guard) = &self.echo_guard {
+            guard.record_text(text);
+        }
+    }
+
+    /// Record that TTS audio was emitted
+    pub(super) fn on_tts_audio(&self) {
+        if let Some(guard) = &self.echo_guard {
+            guard.record_audio(Instant::now());
+        }
+    }
+
+    /// Record whether the STT provider reports VAD events
+    pub(super) fn set_vad_supported(&self, supported: bool) {
+        if self.mode == BargeInMode::OnVad && !supported {
+            info!("STT provider does not report VAD events; barge-in uses interim results");
+        }
+        self.vad_supported.store(supported, Ordering::Release);
+    }
+
+    /// Handle a VAD event from the STT provider
+    pub(super) async fn on_vad_event(&self, event: &STTVadEvent) {
+        match event {
+            STTVadEvent::SpeechStarted { .. } if self.mode == BargeInMode::OnVad => {
+                if let Some(guard) = &self.echo_guard
+                    && guard.is_active(Instant::now())
+                {
+                    debug!("Speech started during TTS playback; waiting for a transcript");
+                    guard.suppress_vad();
+                    self.vad_deferred.store(true, Ordering::Release);
+                    return;
+                }
+                if self.confirmation.is_some() {
+                    debug!("Speech started; waiting for a transcript to confirm barge-in");
+                    self.vad_deferred.store(true, Ordering::Release);
+                    return;
+                }
+                self.interrupted.store(true, Ordering::Release);
+                self.interrupt().await;
+            }
+            STTVadEvent::UtteranceEnd { .. } => self.end_turn(),
+            _ => {}
+        }
+    }
+
+    /// Handle an STT result before it reaches the agent bridge and the client
+    ///
+    /// # Returns
+    /// * `false` if the result is an echo of TTS output and must be dropped
+    pub(super) async fn on_transcript(&self, result: &STTResult) -> bool {
+        let now = Instant::now();
+        if let Some(guard) = &self.echo_guard
+            && guard.is_echo(result, now)
+        {
+            debug!("Dropping transcript that repeats TTS output");
+            if result.is_speech_final {
+                self.end_turn();
+            }
+            return false;
+        }
+
+        let on_transcript = match self.mode {
+            BargeInMode::OnVad => {
+                !self.vad_supported.load(Ordering::Acquire)
+                    || self.vad_deferred.load(Ordering::Acquire)
+            }
+            BargeInMode::OnInterim => true,
+            BargeInMode::Off => false,
+        };
+
+        if on_transcript
+            && !result.transcript.trim().is_empty()
+            && !self.interrupted.load(Ordering::Acquire)
+            && self
+                .echo_guard
+                .as_ref()
+                .is_none_or(|guard| guard.allows_barge_in(result, now))
+            && self.confirmed(result, now).await
+            && !self.interrupted.swap(true, Ordering::AcqRel)
+        {
+            self.interrupt().await;
+        }
+
+        if result.is_speech_final {
+            self.end_turn();
+        }
+        true
+    }
+
+    fn end_turn(&self) {
+        self.interrupted.store(false, Ordering::Release);
+        self.vad_deferred.store(false, Ordering::Release);
+        if let Some(confirmation) = &self.confirmation {
+            confirmation.reset();
+        }
+    }
+
+    /// Whether the turn's speech confirms barge-in; always true without
+    /// confirmation. Emits the transcripts held back.
+    async fn confirmed(&self, result: &STTResult, now: Instant) -> bool {
+        let Some(confirmation) = &self.confirmation else {
+            return true;
+        };
+        match confirmation.confirm(result, now) {
+            Ok(()) => true,
+            Err(suppressed) => {
+                debug!(
+                    reason = ?suppressed.reason,
+                    words = suppressed.words,
+                    "Barge-in not confirmed; TTS continues"
+                );
+                self.emitter
+                    .emit(SessionEvent::BargeInSuppressed(suppressed))
+                    .await;
+                false
+            }
+        }
+    }
+
+    /// Stop the agent reply and clear queued TTS
+    async fn interrupt(&self) {
+        if let Some(bridge) = &self.agent_bridge {
+            bridge.interrupt().await;
+        }
+        let Some(voice_manager) = self.voice_manager.upgrade() else {
+            return;
+        };
+        if let Err(e) = voice_manager.clear_tts().await {
+            warn!("Barge-in failed to clear TTS: {}", e);
+        }
+        debug!("Barge-in cleared TTS output");
+    }
+}
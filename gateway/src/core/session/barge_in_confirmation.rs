@@ -0,0 +1,327 @@
+//! Barge-in confirmation: interrupt TTS output only for speech that holds up
+//!
+//! Coughs, background voices and line noise often come back from the STT
+//! provider as short, low-confidence interims. With confirmation, barge-in
+//! waits until the caller's speech adds up to a minimum number of words (or
+//! characters) with enough confidence within a time window. Interims that do
+//! not get there are reported as suppressed and TTS keeps playing.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::core::stt::STTResult;
+
+/// Longest allowed `window_ms`
+pub const MAX_CONFIRMATION_WINDOW_MS: u64 = 10_000;
+
+/// How much confident speech it takes to interrupt TTS output
+///
+/// Speech counts once it reaches `min_words` words or, for scripts written
+/// without spaces, `min_chars` characters; a threshold of 0 is not used.
+///
+/// # Example JSON
+/// ```json
+/// {"min_words": 2, "min_confidence": 0.6, "window_ms": 1500}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct BargeInConfirmationConfig {
+    /// Minimum number of words heard before barge-in
+    pub min_words: usize,
+    /// Minimum number of non-space characters heard before barge-in
+    pub min_chars: usize,
+    /// Minimum transcript confidence for speech to count
+    pub min_confidence: f32,
+    /// Time from the first confident transcript within which the speech
+    /// must reach the minimum (ms); older speech no longer counts
+    pub window_ms: u64,
+}
+
+impl Default for BargeInConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            min_words: 2,
+            min_chars: 0,
+            min_confidence: 0.6,
+            window_ms: 1500,
+        }
+    }
+}
+
+impl BargeInConfirmationConfig {
+    /// Validate the thresholds
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_words == 0 && self.min_chars == 0 {
+            return Err("one of min_words and min_chars must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(format!(
+                "min_confidence must be between 0.0 and 1.0 (got {})",
+                self.min_confidence
+            ));
+        }
+        if !(1..=MAX_CONFIRMATION_WINDOW_MS).contains(&self.window_ms) {
+            return Err(format!(
+                "window_ms must be between 1 and {MAX_CONFIRMATION_WINDOW_MS} (got {})",
+                self.window_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Why a transcript did not trigger barge-in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BargeInSuppressionReason {
+    /// The transcript's confidence was below `min_confidence`
+    LowConfidence,
+    /// The speech heard in the window was shorter than the minimum
+    TooShort,
+}
+
+/// A transcript that was held back from interrupting TTS output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BargeInSuppressed {
+    /// Text of the rejected transcript
+    pub transcript: String,
+    /// Confidence the STT provider reported for it
+    pub confidence: f32,
+    /// Whether the transcript was an interim result
+    pub interim: bool,
+    /// Words of confident speech heard in the window, including this
+    /// transcript unless its confidence was too low
+    pub words: usize,
+    /// Why barge-in was held back
+    pub reason: BargeInSuppressionReason,
+}
+
+/// Confident speech heard since the window started
+struct Evidence {
+    started: Instant,
+    /// Final transcripts of the window, joined by spaces
+    finals: String,
+}
+
+/// Accumulates a user turn's speech until it confirms barge-in
+pub(super) struct BargeInConfirmation {
+    config: BargeInConfirmationConfig,
+    evidence: Mutex<Option<Evidence>>,
+}
+
+impl BargeInConfirmation {
+    pub(super) fn new(config: BargeInConfirmationConfig) -> Self {
+        Self {
+            config,
+            evidence: Mutex::new(None),
+        }
+    }
+
+    /// Whether `result` completes enough speech to interrupt TTS output
+    ///
+    /// Interims repeat the text of the segment so far, so the speech heard is
+    /// the window's final transcripts followed by `result`. Confirming starts
+    /// over with the next call.
+    pub(super) fn confirm(
+        &self,
+        result: &STTResult,
+        now: Instant,
+    ) -> Result<(), BargeInSuppressed> {
+        let mut evidence = self.evidence.lock();
+        let window = Duration::from_millis(self.config.window_ms);
+        let current = evidence
+            .take()
+            .filter(|evidence| now.saturating_duration_since(evidence.started) <= window);
+
+        if result.confidence < self.config.min_confidence {
+            let words = current
+                .as_ref()
+                .map_or(0, |evidence| evidence.finals.split_whitespace().count());
+            *evidence = current;
+            return Err(self.suppressed(result, words, BargeInSuppressionReason::LowConfidence));
+        }
+
+        let mut current = current.unwrap_or(Evidence {
+            started: now,
+            finals: String::new(),
+        });
+        let heard = format!("{} {}", current.finals, result.transcript);
+        let words = heard.split_whitespace().count();
+        let chars = heard.chars().filter(|c| !c.is_whitespace()).count();
+        if (self.config.min_words > 0 && words >= self.config.min_words)
+            || (self.config.min_chars > 0 && chars >= self.config.min_chars)
+        {
+            return Ok(());
+        }
+
+        if result.is_final {
+            current.finals = heard.trim().to_string();
+        }
+        *evidence = Some(current);
+        Err(self.suppressed(result, words, BargeInSuppressionReason::TooShort))
+    }
+
+    /// Forget the speech heard so far, at the end of a user turn
+    pub(super) fn reset(&self) {
+        *self.evidence.lock() = None;
+    }
+
+    fn suppressed(
+        &self,
+        result: &STTResult,
+        words: usize,
+        reason: BargeInSuppressionReason,
+    ) -> BargeInSuppressed {
+        BargeInSuppressed {
+            transcript: result.transcript.clone(),
+            confidence: result.confidence,
+            interim: !result.is_final,
+            words,
+            reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interim(transcript: &str, confidence: f32) -> STTResult {
+        STTResult::new(transcript.to_string(), false, false, confidence)
+    }
+
+    fn final_result(transcript: &str, confidence: f32) -> STTResult {
+        STTResult::new(transcript.to_string(), true, false, confidence)
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(BargeInConfirmationConfig::default().validate().is_ok());
+        let config = BargeInConfirmationConfig {
+            min_words: 0,
+            min_chars: 0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = BargeInConfirmationConfig {
+            min_confidence: 1.2,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = BargeInConfirmationConfig {
+            window_ms: MAX_CONFIRMATION_WINDOW_MS + 1,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(serde_json::from_str::<BargeInConfirmationConfig>(r#"{"min_word": 2}"#).is_err());
+    }
+
+    #[test]
+    fn test_noise_is_suppressed() {
+        let confirmation = BargeInConfirmation::new(BargeInConfirmationConfig::default());
+        let start = Instant::now();
+
+        // A cough and background chatter: low-confidence fragments
+        for (i, text) in ["uh", "hm", "the", "uh huh"].into_iter().enumerate() {
+            let at = start + Duration::from_millis(100 * i as u64);
+            let suppressed = confirmation.confirm(&interim(text, 0.3), at).unwrap_err();
+            assert_eq!(suppressed.reason, BargeInSuppressionReason::LowConfidence);
+            assert_eq!(suppressed.transcript, text);
+            assert!(suppressed.interim);
+        }
+
+        // A single confident word is not enough
+        let suppressed = confirmation
+            .confirm(&interim("yeah", 0.9), start + Duration::from_millis(500))
+            .unwrap_err();
+        assert_eq!(suppressed.reason, BargeInSuppressionReason::TooShort);
+        assert_eq!(suppressed.words, 1);
+    }
+
+    #[test]
+    fn test_real_speech_triggers() {
+        let confirmation = BargeInConfirmation::new(BargeInConfirmationConfig::default());
+        let start = Instant::now();
+
+        // Interims grow as the caller keeps talking
+        assert!(confirmation.confirm(&interim("wait", 0.8), start).is_err());
+        assert!(
+            confirmation
+                .confirm(&interim("wait", 0.4), start + Duration::from_millis(100))
+                .is_err()
+        );
+        assert!(
+            confirmation
+                .confirm(
+                    &interim("wait stop", 0.85),
+                    start + Duration::from_millis(300)
+                )
+                .is_ok()
+        );
+
+        // Words of separate final segments add up
+        let confirmation = BargeInConfirmation::new(BargeInConfirmationConfig::default());
+        assert!(
+            confirmation
+                .confirm(&final_result("no", 0.9), start)
+                .is_err()
+        );
+        let confirmed =
+            confirmation.confirm(&interim("thanks", 0.9), start + Duration::from_millis(400));
+        assert!(confirmed.is_ok());
+    }
+
+    #[test]
+    fn test_speech_outside_the_window_does_not_add_up() {
+        let confirmation = BargeInConfirmation::new(BargeInConfirmationConfig {
+            window_ms: 1000,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        assert!(
+            confirmation
+                .confirm(&final_result("okay", 0.9), start)
+                .is_err()
+        );
+
+        let later = start + Duration::from_millis(1500);
+        let suppressed = confirmation
+            .confirm(&interim("so", 0.9), later)
+            .unwrap_err();
+        assert_eq!(suppressed.words, 1);
+        let confirmed =
+            confirmation.confirm(&interim("so yes", 0.9), later + Duration::from_millis(200));
+        assert!(confirmed.is_ok());
+
+        // A reset forgets the speech of the turn
+        assert!(
+            confirmation
+                .confirm(&final_result("okay", 0.9), later)
+                .is_err()
+        );
+        confirmation.reset();
+        let suppressed = confirmation
+            .confirm(&interim("so", 0.9), later)
+            .unwrap_err();
+        assert_eq!(suppressed.words, 1);
+    }
+
+    #[test]
+    fn test_character_threshold() {
+        let confirmation = BargeInConfirmation::new(BargeInConfirmationConfig {
+            min_words: 0,
+            min_chars: 4,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert!(confirmation.confirm(&interim("等一", 0.9), now).is_err());
+        assert!(confirmation.confirm(&interim("等一下吧", 0.9), now).is_ok());
+    }
+}
@@ -0,0 +1,1782 @@
+//! Builder that composes providers, turn detection and callbacks into a `Session`
+
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info};
+
+use super::audio_level::{AudioDirection, LevelMeter, is_pcm16};
+use super::barge_in::{BargeIn, BargeInMode};
+use super::barge_in_confirmation::BargeInConfirmationConfig;
+use super::consent::{ConsentConfig, ConsentGate};
+use super::diarization::SpeechActivityRecorder;
+use super::echo_guard::{EchoGuard, EchoGuardConfig};
+use super::effective::{EffectiveSessionConfig, redacted_stt, redacted_tts};
+use super::errors::{SessionError, SessionResult};
+use super::events::{EventEmitter, SessionEvent};
+use super::formatting::{TranscriptFormatter, TranscriptFormattingConfig};
+use super::greeting::load_audio_asset;
+use super::latency_budget::{LatencyBudget, LatencyBudgetConfig, PipelineMitigator};
+use super::pipeline::{Backend, REALTIME_SAMPLE_RATE, Session};
+use super::retry_budget::{RetryBudget, RetryBudgetConfig};
+use super::transcript::{
+    TranscriptBuffer, TranscriptBufferConfig, TranscriptEntry, TranscriptOverflowPolicy,
+};
+use super::turns::TurnTracker;
+use super::usage::{UsageMeter, audio_duration_ms, bytes_per_sample, now_ms};
+use super::watchdog::{PipelineWatchdog, PipelineWatchdogConfig, VoicePipelineRecoverer};
+use crate::config::FeatureFlags;
+#[cfg(feature = "chaos")]
+use crate::core::chaos::SessionChaos;
+use crate::core::{
+    agent_bridge::{AgentBridge, AgentBridgeConfig, SpeechSink},
+    cache::store::CacheStore,
+    realtime::{
+        RealtimeAudioData, RealtimeConfig, RealtimeError, TranscriptResult, TranscriptRole,
+    },
+    stt::{STTConfig, STTFailoverConfig, STTResult, STTTurnRoutingConfig, STTVadEvent},
+    tts::{AudioData, TTSConfig, TextSegmenter},
+    turn_detect::TurnDetector,
+    validation::{ConfigIssue, config_issues_message},
+    voice_manager::{
+        AdaptiveEndpointingConfig, DEFAULT_PROVIDER_CONNECT_TIMEOUT, FillerAudio,
+        FillerAudioConfig, PreemptionEvent, SpeechFinalConfig, TTSAudioQualityConfig,
+        TTSAudioQualityWarning, TTSQueueFull, TTSQueueLimit, TurnDetectionDegraded, VoiceManager,
+        VoiceManagerConfig, VoiceManagerResult,
+    },
+};
+use crate::plugin::{PluginRegistry, global_registry};
+
+/// Default time to wait for providers to become ready
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of events buffered before providers wait for the consumer
+pub const DEFAULT_EVENT_BUFFER: usize = 256;
+
+/// Builder for a [`Session`]
+///
+/// A session is either a voice pipeline (STT + TTS, with optional turn
+/// detection, TTS cache and agent bridge) or a realtime pipeline (a single
+/// audio-to-audio provider). Setting both is a configuration error.
+#[derive(Default)]
+pub struct SessionPipelineBuilder {
+    stt_config: Option<STTConfig>,
+    stt_failover: Option<STTFailoverConfig>,
+    stt_routing: Option<STTTurnRoutingConfig>,
+    tts_config: Option<TTSConfig>,
+    fallback_voice_id: Option<String>,
+    tts_queue_limit: Option<TTSQueueLimit>,
+    system_speak_max_chars: Option<usize>,
+    dedupe_partials: bool,
+    tts_audio_quality: Option<TTSAudioQualityConfig>,
+    filler_audio: Option<FillerAudioConfig>,
+    filler_assets_dir: Option<PathBuf>,
+    realtime_config: Option<RealtimeConfig>,
+    speech_final_config: Option<SpeechFinalConfig>,
+    adaptive_endpointing: Option<AdaptiveEndpointingConfig>,
+    turn_detector: Option<Arc<RwLock<TurnDetector>>>,
+    turn_detector_expected: bool,
+    tts_cache: Option<(Arc<CacheStore>, Option<String>)>,
+    agent_config: Option<AgentBridgeConfig>,
+    noise_filter: bool,
+    barge_in: BargeInMode,
+    barge_in_confirmation: Option<BargeInConfirmationConfig>,
+    echo_guard: Option<EchoGuardConfig>,
+    transcript_formatting: Option<TranscriptFormattingConfig>,
+    latency_budget: Option<LatencyBudgetConfig>,
+    watchdog: Option<PipelineWatchdogConfig>,
+    consent: Option<ConsentConfig>,
+    retry_budget: Option<RetryBudgetConfig>,
+    audio_levels: bool,
+    connect_timeout: Option<Duration>,
+    ready_timeout: Option<Duration>,
+    event_buffer: Option<usize>,
+    feature_flags: FeatureFlags,
+    transcript_buffer: TranscriptBufferConfig,
+    transcript_cache: Option<Arc<CacheStore>>,
+    speech_activity: bool,
+    plugin_registry: Option<Arc<PluginRegistry>>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<SessionChaos>>,
+}
+
+impl SessionPipelineBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the STT provider configuration
+    pub fn stt(mut self, config: STTConfig) -> Self {
+        self.stt_config = Some(config);
+        self
+    }
+
+    /// Switch to a secondary STT provider when the primary fails
+    ///
+    /// With `warm_standby` the secondary transcribes alongside the primary
+    /// and takes over without a gap, at the cost of billing both providers
+    /// for the whole session; see [`FailoverSTT`](crate::core::stt::FailoverSTT).
+    pub fn stt_failover(mut self, config: STTFailoverConfig) -> Self {
+        self.stt_failover = Some(config);
+        self
+    }
+
+    /// Route confirmation and digit turns to a fast STT provider
+    ///
+    /// Both providers stay connected and [`Session::expect_utterance`] picks
+    /// the one for the next user turn; see
+    /// [`TurnRoutedSTT`](crate::core::stt::TurnRoutedSTT). Usage reports the
+    /// audio each provider received and the provider of every turn.
+    pub fn stt_routing(mut self, config: STTTurnRoutingConfig) -> Self {
+        self.stt_routing = Some(config);
+        self
+    }
+
+    /// Set the TTS provider configuration
+    pub fn tts(mut self, config: TTSConfig) -> Self {
+        self.tts_config = Some(config);
+        self
+    }
+
+    /// Switch to `voice_id` if the configured TTS voice does not exist
+    ///
+    /// The first voice-not-found error replays the failed text with the
+    /// fallback voice, which is then kept for the rest of the session. Without
+    /// a fallback, `build()` fails if the provider reports the voice missing.
+    pub fn fallback_voice(mut self, voice_id: impl Into<String>) -> Self {
+        self.fallback_voice_id = Some(voice_id.into());
+        self
+    }
+
+    /// Cap the number of pending TTS utterances
+    ///
+    /// Defaults to 5 pending utterances with the `reject` policy. Every time
+    /// the cap is hit, `SessionEvent::TtsQueueFull` is emitted.
+    pub fn tts_queue_limit(mut self, limit: TTSQueueLimit) -> Self {
+        self.tts_queue_limit = Some(limit);
+        self
+    }
+
+    /// Cap the length of `system` priority speech, in characters
+    ///
+    /// Defaults to 500. Longer announcements fail with
+    /// `VoiceManagerError::SystemSpeakTooLong`.
+    pub fn system_speak_max_chars(mut self, max_chars: usize) -> Self {
+        self.system_speak_max_chars = Some(max_chars);
+        self
+    }
+
+    /// Send only the new text when `speak(flush=false)` partials resend the
+    /// sentence so far, replacing the pending utterance when a partial revises it
+    pub fn dedupe_partials(mut self, enabled: bool) -> Self {
+        self.dedupe_partials = enabled;
+        self
+    }
+
+    /// Set the thresholds for flagging clipped, silent or inaudible TTS audio
+    ///
+    /// The check is enabled with default thresholds unless set here. Every
+    /// failing utterance emits `SessionEvent::TtsAudioQualityWarning`; with
+    /// `retry` the provider synthesizes it once more.
+    pub fn tts_audio_quality(mut self, config: TTSAudioQualityConfig) -> Self {
+        self.tts_audio_quality = Some(config);
+        self
+    }
+
+    /// Play a filler asset while the first TTS audio of a reply is slow
+    ///
+    /// The asset is loaded from `assets_dir`, the greeting assets directory,
+    /// and must be 16-bit mono PCM at the session's output sample rate.
+    /// Filler audio is emitted as `SessionEvent::Audio` but never starts a
+    /// turn, counts as TTS usage or enters the transcript.
+    pub fn filler_audio(mut self, config: FillerAudioConfig, assets_dir: Option<PathBuf>) -> Self {
+        self.filler_audio = Some(config);
+        self.filler_assets_dir = assets_dir;
+        self
+    }
+
+    /// Use a realtime audio-to-audio provider instead of STT + TTS
+    ///
+    /// The provider is selected by `config.provider`.
+    pub fn realtime(mut self, config: RealtimeConfig) -> Self {
+        self.realtime_config = Some(config);
+        self
+    }
+
+    /// Override the speech-final timing of the voice pipeline
+    pub fn speech_final_config(mut self, config: SpeechFinalConfig) -> Self {
+        self.speech_final_config = Some(config);
+        self
+    }
+
+    /// Adapt the end-of-turn silence threshold to each utterance
+    pub fn adaptive_endpointing(mut self, config: AdaptiveEndpointingConfig) -> Self {
+        self.adaptive_endpointing = Some(config);
+        self
+    }
+
+    /// Use an ML turn detector as the speech-final fallback
+    pub fn turn_detector(mut self, turn_detector: Option<Arc<RwLock<TurnDetector>>>) -> Self {
+        self.turn_detector = turn_detector;
+        self
+    }
+
+    /// Whether a turn detector should have been set
+    ///
+    /// A session expecting one but built without it starts in silence-based
+    /// end-of-turn detection and emits [`SessionEvent::TurnDetectionDegraded`].
+    pub fn turn_detector_expected(mut self, expected: bool) -> Self {
+        self.turn_detector_expected = expected;
+        self
+    }
+
+    /// Cache synthesized audio in `cache`
+    ///
+    /// `config_hash` identifies the TTS configuration in cache keys; pass the
+    /// same hash for sessions that should share cached audio.
+    pub fn tts_cache(mut self, cache: Arc<CacheStore>, config_hash: Option<String>) -> Self {
+        self.tts_cache = Some((cache, config_hash));
+        self
+    }
+
+    /// Answer completed user turns with a streaming LLM
+    pub fn agent(mut self, config: AgentBridgeConfig) -> Self {
+        self.agent_config = Some(config);
+        self
+    }
+
+    /// Denoise audio passed to `push_audio` before it reaches the provider
+    pub fn noise_filter(mut self, enabled: bool) -> Self {
+        self.noise_filter = enabled;
+        self
+    }
+
+    /// Interrupt TTS output when the caller starts speaking
+    pub fn barge_in(mut self, mode: BargeInMode) -> Self {
+        self.barge_in = mode;
+        self
+    }
+
+    /// Only interrupt TTS output once the caller's speech reaches a minimum
+    /// length and confidence
+    ///
+    /// Transcripts held back are reported as `SessionEvent::BargeInSuppressed`.
+    /// Without it, barge-in interrupts immediately.
+    pub fn barge_in_confirmation(mut self, config: BargeInConfirmationConfig) -> Self {
+        self.barge_in_confirmation = Some(config);
+        self
+    }
+
+    /// Keep the assistant's own audio, picked up by the caller's mic, from
+    /// interrupting it or reaching the agent bridge as caller speech
+    pub fn echo_guard(mut self, config: EchoGuardConfig) -> Self {
+        self.echo_guard = Some(config);
+        self
+    }
+
+    /// Restore sentence case, drop filler words and write numbers as digits
+    /// in STT results
+    ///
+    /// Results are formatted before barge-in, the agent bridge and the
+    /// session transcript see them; see [`formatting`](super::formatting).
+    pub fn transcript_formatting(mut self, config: TranscriptFormattingConfig) -> Self {
+        self.transcript_formatting = Some(config);
+        self
+    }
+
+    /// Check each turn against a latency budget with per-stage deadlines
+    ///
+    /// Stages over their deadline are logged, reported as
+    /// `SessionEvent::LatencyBudgetExceeded` or mitigated, as configured;
+    /// see [`latency_budget`](super::latency_budget).
+    pub fn latency_budget(mut self, config: LatencyBudgetConfig) -> Self {
+        self.latency_budget = Some(config);
+        self
+    }
+
+    /// Reconnect the STT or TTS provider when it stops responding mid-session
+    ///
+    /// Recoveries are reported as `SessionEvent::PipelineRecovered` and
+    /// counted in [`Session::watchdog_stats`]; see
+    /// [`watchdog`](super::watchdog).
+    pub fn watchdog(mut self, config: PipelineWatchdogConfig) -> Self {
+        self.watchdog = Some(config);
+        self
+    }
+
+    /// Keep input audio from the providers until the caller consents
+    ///
+    /// The session starts with consent pending; see
+    /// [`Session::set_consent`] and [`consent`](super::consent). Works for
+    /// voice and realtime sessions.
+    pub fn consent(mut self, config: ConsentConfig) -> Self {
+        self.consent = Some(config);
+        self
+    }
+
+    /// Bound the provider reconnects and retries of the whole session
+    ///
+    /// The watchdog's STT and TTS reconnects draw from one token bucket, STT
+    /// first; other components take theirs from [`Session::retry_budget`].
+    /// Use is counted in [`Session::retry_budget_stats`]; see
+    /// [`retry_budget`](super::retry_budget).
+    pub fn retry_budget(mut self, config: RetryBudgetConfig) -> Self {
+        self.retry_budget = Some(config);
+        self
+    }
+
+    /// Emit `SessionEvent::AudioLevel` for input and output audio
+    ///
+    /// Levels are measured on 16-bit PCM only; input or output audio in
+    /// another encoding is not metered.
+    pub fn audio_levels(mut self, enabled: bool) -> Self {
+        self.audio_levels = enabled;
+        self
+    }
+
+    /// How long `build()` waits for each provider `connect()` call
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// How long `build()` waits for providers to become ready
+    pub fn ready_timeout(mut self, timeout: Duration) -> Self {
+        self.ready_timeout = Some(timeout);
+        self
+    }
+
+    /// Create the providers from `registry`
+    ///
+    /// Defaults to the [`global_registry`].
+    pub fn plugin_registry(mut self, registry: Arc<PluginRegistry>) -> Self {
+        self.plugin_registry = Some(registry);
+        self
+    }
+
+    /// How many events are buffered before providers wait for the consumer
+    pub fn event_buffer(mut self, capacity: usize) -> Self {
+        self.event_buffer = Some(capacity);
+        self
+    }
+
+    /// Attach the feature flags resolved for this session
+    ///
+    /// The `adaptive_endpointing` and `echo_guard` flags turn on the
+    /// component with its default settings when the session did not
+    /// configure it. The flags are reported in [`Session::usage`].
+    pub fn feature_flags(mut self, flags: FeatureFlags) -> Self {
+        self.feature_flags = flags;
+        self
+    }
+
+    /// Limit the transcript kept for [`Session::transcript`]
+    ///
+    /// Defaults to [`TranscriptBufferConfig::default`]. The `spill_to_cache`
+    /// policy writes overflowing entries to `cache`, which it requires.
+    pub fn transcript_buffer(
+        mut self,
+        config: TranscriptBufferConfig,
+        cache: Option<Arc<CacheStore>>,
+    ) -> Self {
+        self.transcript_buffer = config;
+        self.transcript_cache = cache;
+        self
+    }
+
+    /// Record when the bot spoke and the level of the caller's audio
+    ///
+    /// Enables [`Session::speech_activity`] for the post-call diarization
+    /// pass. Only voice sessions record it, and input levels need PCM16
+    /// input audio. Disabled by default.
+    pub fn speech_activity(mut self, enabled: bool) -> Self {
+        self.speech_activity = enabled;
+        self
+    }
+
+    /// Inject faults into the session's primary STT and TTS providers
+    ///
+    /// `None` leaves the providers alone. See [`crate::core::chaos`].
+    #[cfg(feature = "chaos")]
+    pub fn chaos(mut self, chaos: Option<Arc<SessionChaos>>) -> Self {
+        self.chaos = chaos;
+        self
+    }
+
+    /// Connect the providers and return a ready session
+    ///
+    /// # Returns
+    /// * `SessionResult<Session>` - Running session, or the first setup error
+    pub async fn build(mut self) -> SessionResult<Session> {
+        self.resolve()?;
+        if self.realtime_config.is_some() {
+            return self.build_realtime().await;
+        }
+        self.build_voice().await
+    }
+
+    /// Resolve the session's configuration without connecting any provider
+    ///
+    /// Runs the checks of [`build`](Self::build) and applies the feature
+    /// flags, then validates the provider configs against their providers'
+    /// rules the way the provider factories do when a session is built.
+    ///
+    /// # Returns
+    /// * `SessionResult<EffectiveSessionConfig>` - The configuration a session
+    ///   built from this builder would use, or the first setup error
+    pub fn dry_run(mut self) -> SessionResult<EffectiveSessionConfig> {
+        self.resolve()?;
+        if self.realtime_config.is_none() {
+            let (Some(stt_config), Some(tts_config)) = (&self.stt_config, &self.tts_config) else {
+                return Err(SessionError::InvalidConfig(
+                    "both stt and tts configurations are required".to_string(),
+                ));
+            };
+            let registry = self.registry();
+            let validate_stt =
+                |config: &STTConfig| registry.validate_stt_config(&config.provider, config);
+            let issues: Vec<ConfigIssue> = [
+                ("stt", validate_stt(stt_config)),
+                (
+                    "stt_failover",
+                    self.stt_failover
+                        .as_ref()
+                        .map_or(Ok(()), |failover| validate_stt(&failover.secondary)),
+                ),
+                (
+                    "stt_routing",
+                    self.stt_routing
+                        .as_ref()
+                        .map_or(Ok(()), |routing| validate_stt(&routing.fast)),
+                ),
+                (
+                    "tts",
+                    registry.validate_tts_config(&tts_config.provider, tts_config),
+                ),
+            ]
+            .into_iter()
+            .flat_map(|(section, result)| {
+                result
+                    .err()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |issue| issue.prefixed(section))
+            })
+            .collect();
+            if !issues.is_empty() {
+                return Err(SessionError::InvalidConfig(config_issues_message(&issues)));
+            }
+        }
+        Ok(self.effective_config())
+    }
+
+    /// Registry the session's providers are created from
+    fn registry(&self) -> Arc<PluginRegistry> {
+        self.plugin_registry
+            .clone()
+            .unwrap_or_else(|| global_registry().clone())
+    }
+
+    /// Check the combination of settings and apply the feature flags
+    fn resolve(&mut self) -> SessionResult<()> {
+        self.transcript_buffer
+            .validate()
+            .map_err(|e| SessionError::InvalidConfig(format!("invalid transcript buffer: {e}")))?;
+        if let Some(consent) = &self.consent {
+            consent
+                .validate()
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid consent: {e}")))?;
+        }
+        if let Some(retry_budget) = &self.retry_budget {
+            retry_budget
+                .validate()
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid retry budget: {e}")))?;
+        }
+        if self.transcript_buffer.overflow == TranscriptOverflowPolicy::SpillToCache
+            && self.transcript_cache.is_none()
+        {
+            return Err(SessionError::InvalidConfig(
+                "transcript buffer spill_to_cache requires a cache".to_string(),
+            ));
+        }
+        if self.realtime_config.is_some() {
+            if self.stt_config.is_some() || self.tts_config.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "realtime cannot be combined with stt/tts".to_string(),
+                ));
+            }
+            if self.agent_config.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "agent requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.adaptive_endpointing.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "adaptive endpointing requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.barge_in != BargeInMode::Off {
+                return Err(SessionError::InvalidConfig(
+                    "barge-in requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.barge_in_confirmation.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "barge-in confirmation requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.echo_guard.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "echo guard requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.transcript_formatting.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "transcript formatting requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.latency_budget.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "latency budget requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.watchdog.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "pipeline watchdog requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.fallback_voice_id.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "fallback voice requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.tts_queue_limit.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "tts queue limit requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.system_speak_max_chars.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "system speak limit requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.tts_audio_quality.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "tts audio quality check requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.filler_audio.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "filler audio requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.stt_failover.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "stt failover requires an stt/tts session".to_string(),
+                ));
+            }
+            if self.stt_routing.is_some() {
+                return Err(SessionError::InvalidConfig(
+                    "stt routing requires an stt/tts session".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        // Flags turn on components the session did not configure itself
+        if self.adaptive_endpointing.is_none()
+            && self.feature_flags.is_enabled("adaptive_endpointing")
+        {
+            self.adaptive_endpointing = Some(AdaptiveEndpointingConfig::default());
+        }
+        if self.echo_guard.is_none() && self.feature_flags.is_enabled("echo_guard") {
+            self.echo_guard = Some(EchoGuardConfig::default());
+        }
+        if let Some(endpointing) = &self.adaptive_endpointing {
+            endpointing.validate().map_err(|e| {
+                SessionError::InvalidConfig(format!("invalid adaptive endpointing: {e}"))
+            })?;
+        }
+        if let Some(confirmation) = &self.barge_in_confirmation {
+            confirmation.validate().map_err(|e| {
+                SessionError::InvalidConfig(format!("invalid barge-in confirmation: {e}"))
+            })?;
+        }
+        if let Some(echo_guard) = &self.echo_guard {
+            echo_guard
+                .validate()
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid echo guard: {e}")))?;
+        }
+        if let Some(formatting) = &self.transcript_formatting {
+            formatting.validate().map_err(|e| {
+                SessionError::InvalidConfig(format!("invalid transcript formatting: {e}"))
+            })?;
+        }
+        if let Some(latency_budget) = &self.latency_budget {
+            latency_budget
+                .validate()
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid latency budget: {e}")))?;
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.validate().map_err(|e| {
+                SessionError::InvalidConfig(format!("invalid pipeline watchdog: {e}"))
+            })?;
+        }
+        if let Some(audio_quality) = &self.tts_audio_quality {
+            audio_quality.validate().map_err(|e| {
+                SessionError::InvalidConfig(format!("invalid tts audio quality: {e}"))
+            })?;
+        }
+        if let Some(filler) = &self.filler_audio {
+            filler
+                .validate()
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid filler audio: {e}")))?;
+            if self.filler_assets_dir.is_none() {
+                return Err(SessionError::InvalidConfig(
+                    "filler audio requires a greeting assets directory".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The configuration the session is built with, once resolved
+    fn effective_config(&self) -> EffectiveSessionConfig {
+        EffectiveSessionConfig {
+            stt: self.stt_config.as_ref().map(redacted_stt),
+            stt_failover: self
+                .stt_failover
+                .as_ref()
+                .map(|failover| redacted_stt(&failover.secondary)),
+            stt_routing: self
+                .stt_routing
+                .as_ref()
+                .map(|routing| redacted_stt(&routing.fast)),
+            tts: self.tts_config.as_ref().map(redacted_tts),
+            realtime_provider: self
+                .realtime_config
+                .as_ref()
+                .map(|config| config.provider.clone()),
+            fallback_voice_id: self.fallback_voice_id.clone(),
+            tts_cache_key: self
+                .tts_cache
+                .as_ref()
+                .and_then(|(_, config_hash)| config_hash.clone()),
+            adaptive_endpointing: self.adaptive_endpointing,
+            barge_in: self.barge_in,
+            barge_in_confirmation: self.barge_in_confirmation,
+            echo_guard: self.echo_guard,
+            transcript_formatting: self.transcript_formatting.clone(),
+            latency_budget: self.latency_budget.clone(),
+            watchdog: self.watchdog,
+            consent: self.consent.clone(),
+            retry_budget: self.retry_budget,
+            tts_audio_quality: self.tts_audio_quality,
+            filler_audio: self.filler_audio.clone(),
+            system_speak_max_chars: self.system_speak_max_chars,
+            dedupe_partials: self.dedupe_partials,
+            agent_bridge: self.agent_config.is_some(),
+            audio_levels: self.audio_levels,
+            transcript_buffer: self.transcript_buffer,
+            feature_flags: self.feature_flags.clone(),
+        }
+    }
+
+    async fn build_voice(self) -> SessionResult<Session> {
+        let effective_config = self.effective_config();
+        let registry = self.registry();
+        let (Some(stt_config), Some(tts_config)) = (self.stt_config, self.tts_config) else {
+            return Err(SessionError::InvalidConfig(
+                "both stt and tts configurations are required".to_string(),
+            ));
+        };
+        info!(
+            "Building session with STT provider: {} and TTS provider: {}",
+            stt_config.provider, tts_config.provider
+        );
+
+        let input_sample_rate = stt_config.sample_rate;
+        let input_byte_rate = stt_config.sample_rate as u64
+            * stt_config.channels.max(1) as u64
+            * bytes_per_sample(&stt_config.encoding);
+        let input_level = (self.audio_levels && is_pcm16(&stt_config.encoding))
+            .then(|| LevelMeter::new(AudioDirection::In));
+        let speech_activity = self
+            .speech_activity
+            .then(|| Arc::new(SpeechActivityRecorder::new(is_pcm16(&stt_config.encoding))));
+        let output_level = self
+            .audio_levels
+            .then(|| Arc::new(LevelMeter::new(AudioDirection::Out)));
+        let usage =
+            UsageMeter::voice(&stt_config, &tts_config).with_feature_flags(self.feature_flags);
+        let voice_config = match self.speech_final_config {
+            Some(speech_final_config) => VoiceManagerConfig::with_speech_final_config(
+                stt_config,
+                tts_config,
+                speech_final_config,
+            ),
+            None => VoiceManagerConfig::new(stt_config, tts_config),
+        };
+        let voice_config = voice_config.with_plugin_registry(registry);
+        let voice_config = match self.adaptive_endpointing {
+            Some(endpointing) => voice_config.with_adaptive_endpointing(endpointing),
+            None => voice_config,
+        };
+        let connect_timeout = self
+            .connect_timeout
+            .unwrap_or(DEFAULT_PROVIDER_CONNECT_TIMEOUT);
+        let voice_config = voice_config.with_connect_timeout(connect_timeout);
+        let voice_config = match self.fallback_voice_id {
+            Some(voice_id) => voice_config.with_fallback_voice_id(voice_id),
+            None => voice_config,
+        };
+        let voice_config = match self.tts_queue_limit {
+            Some(limit) => voice_config.with_tts_queue_limit(limit),
+            None => voice_config,
+        };
+        let voice_config = match self.system_speak_max_chars {
+            Some(max_chars) => voice_config.with_system_speak_max_chars(max_chars),
+            None => voice_config,
+        };
+        let voice_config = voice_config
+            .with_dedupe_partials(self.dedupe_partials)
+            .with_turn_detector_expected(self.turn_detector_expected);
+        let voice_config = match self.tts_audio_quality {
+            Some(audio_quality) => voice_config.with_tts_audio_quality(audio_quality),
+            None => voice_config,
+        };
+        let voice_config = match self.stt_failover {
+            Some(failover) => voice_config.with_stt_failover(failover),
+            None => voice_config,
+        };
+        let voice_config = match self.stt_routing {
+            Some(routing) => voice_config.with_stt_routing(routing),
+            None => voice_config,
+        };
+        #[cfg(feature = "chaos")]
+        let voice_config = match self.chaos {
+            Some(chaos) => voice_config.with_chaos(chaos),
+            None => voice_config,
+        };
+
+        let voice_manager = Arc::new(
+            VoiceManager::new(voice_config, self.turn_detector)
+                .map_err(SessionError::CreateFailed)?,
+        );
+        let usage = match (
+            &voice_manager.get_config().stt_failover,
+            voice_manager.stt_failover_usage(),
+        ) {
+            (Some(failover), Some(counters)) => {
+                usage.with_stt_failover(&failover.secondary, counters)
+            }
+            _ => usage,
+        };
+        let usage = match (
+            &voice_manager.get_config().stt_routing,
+            voice_manager.stt_router(),
+        ) {
+            (Some(routing), Some(router)) => usage.with_stt_routing(&routing.fast, router),
+            _ => usage,
+        };
+        let usage = Arc::new(usage);
+
+        if let Some((cache, config_hash)) = self.tts_cache
+            && let Err(e) = voice_manager.set_tts_cache(cache, config_hash).await
+        {
+            error!("Failed to set TTS cache: {}", e);
+        }
+
+        if let (Some(config), Some(assets_dir)) = (self.filler_audio, &self.filler_assets_dir) {
+            let sample_rate = voice_manager.output_sample_rate().unwrap_or(24000);
+            let pcm = load_audio_asset(assets_dir, &config.asset, sample_rate)
+                .await
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid filler audio: {e}")))?;
+            let filler = FillerAudio::new(config, &pcm, sample_rate)
+                .map_err(|e| SessionError::InvalidConfig(format!("invalid filler audio: {e}")))?;
+            voice_manager
+                .set_filler_audio(filler)
+                .map_err(SessionError::CreateFailed)?;
+        }
+
+        voice_manager
+            .start()
+            .await
+            .map_err(SessionError::StartFailed)?;
+
+        let (emitter, events) =
+            EventEmitter::channel(self.event_buffer.unwrap_or(DEFAULT_EVENT_BUFFER));
+        let turns = Arc::new(TurnTracker::new());
+        let transcript = Arc::new(TranscriptBuffer::new(
+            self.transcript_buffer,
+            self.transcript_cache,
+        ));
+
+        // Create the agent bridge before the STT callback so it sees every result
+        let agent_bridge = match self.agent_config {
+            Some(config) => Some(create_agent_bridge(
+                config,
+                &voice_manager,
+                &emitter,
+                &turns,
+            )?),
+            None => None,
+        };
+
+        let echo_guard = self
+            .echo_guard
+            .map(|config| Arc::new(EchoGuard::new(config)));
+        let barge_in = Arc::new(BargeIn::new(
+            self.barge_in,
+            &voice_manager,
+            agent_bridge.clone(),
+            echo_guard.clone(),
+            self.barge_in_confirmation,
+            emitter.clone(),
+        ));
+        let latency_budget = self.latency_budget.map(|config| {
+            let mitigator = PipelineMitigator::new(&voice_manager, agent_bridge.clone());
+            Arc::new(LatencyBudget::new(
+                config,
+                Arc::new(mitigator),
+                emitter.clone(),
+            ))
+        });
+        let retry_budget = self
+            .retry_budget
+            .map(|config| Arc::new(RetryBudget::new(config)));
+        let watchdog = self.watchdog.map(|config| {
+            Arc::new(PipelineWatchdog::new(
+                config,
+                &voice_manager.get_config().stt_config,
+                Arc::new(VoicePipelineRecoverer::new(&voice_manager)),
+                retry_budget.clone(),
+                emitter.clone(),
+            ))
+        });
+        let formatter = self.transcript_formatting.map(|config| {
+            let language = &voice_manager.get_config().stt_config.language;
+            Arc::new(TranscriptFormatter::new(
+                config,
+                Some(language.as_str()).filter(|language| !language.is_empty()),
+            ))
+        });
+        register_voice_callbacks(
+            &voice_manager,
+            formatter,
+            agent_bridge.clone(),
+            barge_in,
+            latency_budget,
+            watchdog.clone(),
+            output_level,
+            speech_activity.clone(),
+            &emitter,
+            &usage,
+            &turns,
+            &transcript,
+        )
+        .await
+        .map_err(SessionError::CallbackRegistration)?;
+
+        let ready_timeout = self.ready_timeout.unwrap_or(DEFAULT_READY_TIMEOUT);
+        let ready_check = async {
+            while !voice_manager.is_ready().await {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        if tokio::time::timeout(ready_timeout, ready_check)
+            .await
+            .is_err()
+        {
+            return Err(SessionError::ReadyTimeout);
+        }
+        if let Some(watchdog) = &watchdog {
+            watchdog.start();
+        }
+        let consent = self
+            .consent
+            .map(|config| start_consent_gate(config, input_byte_rate, &emitter));
+
+        Ok(Session::new(
+            Backend::Voice(voice_manager),
+            agent_bridge,
+            echo_guard,
+            watchdog,
+            consent,
+            retry_budget,
+            emitter,
+            events,
+            self.noise_filter,
+            input_sample_rate,
+            input_level,
+            usage,
+            turns,
+            transcript,
+            speech_activity,
+            effective_config,
+        ))
+    }
+
+    async fn build_realtime(self) -> SessionResult<Session> {
+        let effective_config = self.effective_config();
+        let registry = self.registry();
+        let config = self.realtime_config.unwrap_or_default();
+        info!(
+            "Building realtime session with provider: {}",
+            config.provider
+        );
+
+        let provider = config.provider.clone();
+        let usage = Arc::new(UsageMeter::realtime(&config).with_feature_flags(self.feature_flags));
+        let mut realtime = registry.create_realtime(&provider, config)?;
+        let (emitter, events) =
+            EventEmitter::channel(self.event_buffer.unwrap_or(DEFAULT_EVENT_BUFFER));
+
+        let transcript = Arc::new(TranscriptBuffer::new(
+            self.transcript_buffer,
+            self.transcript_cache,
+        ));
+        let transcript_emitter = emitter.clone();
+        let transcript_buffer = transcript.clone();
+        realtime.on_transcript(Arc::new(move |transcript: TranscriptResult| {
+            let emitter = transcript_emitter.clone();
+            let buffer = transcript_buffer.clone();
+            Box::pin(async move {
+                if transcript.is_final {
+                    buffer
+                        .push(TranscriptEntry::new(
+                            transcript.role,
+                            transcript.text.clone(),
+                            None,
+                        ))
+                        .await;
+                }
+                emitter
+                    .emit(SessionEvent::RealtimeTranscript(transcript))
+                    .await;
+            })
+        }))?;
+
+        let audio_emitter = emitter.clone();
+        let output_level = self
+            .audio_levels
+            .then(|| LevelMeter::new(AudioDirection::Out));
+        realtime.on_audio(Arc::new(move |audio: RealtimeAudioData| {
+            let emitter = audio_emitter.clone();
+            let level = output_level
+                .as_ref()
+                .and_then(|meter| meter.measure(&audio.data, audio.sample_rate, Instant::now()));
+            Box::pin(async move {
+                emitter.emit(SessionEvent::RealtimeAudio(audio)).await;
+                if let Some(level) = level {
+                    emitter.emit(SessionEvent::AudioLevel(level)).await;
+                }
+            })
+        }))?;
+
+        let error_emitter = emitter.clone();
+        realtime.on_error(Arc::new(move |error: RealtimeError| {
+            let emitter = error_emitter.clone();
+            Box::pin(async move {
+                emitter.emit(SessionEvent::RealtimeError(error)).await;
+            })
+        }))?;
+
+        let connect_timeout = self
+            .connect_timeout
+            .unwrap_or(DEFAULT_PROVIDER_CONNECT_TIMEOUT);
+        realtime.connect_with_timeout(connect_timeout).await?;
+
+        let ready_timeout = self.ready_timeout.unwrap_or(DEFAULT_READY_TIMEOUT);
+        let ready_check = async {
+            while !realtime.is_ready() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        if tokio::time::timeout(ready_timeout, ready_check)
+            .await
+            .is_err()
+        {
+            return Err(SessionError::ReadyTimeout);
+        }
+        // Realtime input is 24kHz PCM16 mono
+        let consent = self
+            .consent
+            .map(|config| start_consent_gate(config, REALTIME_SAMPLE_RATE as u64 * 2, &emitter));
+
+        Ok(Session::new(
+            Backend::Realtime(tokio::sync::Mutex::new(realtime)),
+            None,
+            None,
+            None,
+            consent,
+            self.retry_budget
+                .map(|config| Arc::new(RetryBudget::new(config))),
+            emitter,
+            events,
+            self.noise_filter,
+            REALTIME_SAMPLE_RATE,
+            self.audio_levels
+                .then(|| LevelMeter::new(AudioDirection::In)),
+            usage,
+            Arc::new(TurnTracker::new()),
+            transcript,
+            None,
+            effective_config,
+        ))
+    }
+}
+
+/// Create a session's consent gate, with its timeout running from now
+fn start_consent_gate(
+    config: ConsentConfig,
+    input_byte_rate: u64,
+    emitter: &EventEmitter,
+) -> Arc<ConsentGate> {
+    let gate = Arc::new(ConsentGate::new(config, input_byte_rate, emitter.clone()));
+    gate.start_timer();
+    gate
+}
+
+/// Create the LLM agent bridge for a voice manager
+///
+/// Bridge errors are emitted as `SessionEvent::AgentError`, tagged with the
+/// latest user turn.
+fn create_agent_bridge(
+    config: AgentBridgeConfig,
+    voice_manager: &Arc<VoiceManager>,
+    emitter: &EventEmitter,
+    turns: &Arc<TurnTracker>,
+) -> SessionResult<Arc<AgentBridge>> {
+    info!(
+        "Agent bridge enabled with model {} at {}",
+        config.model, config.endpoint
+    );
+    let sink = Arc::downgrade(voice_manager) as Weak<dyn SpeechSink>;
+    // Replies are in the language the caller is transcribed in
+    let segmenter = TextSegmenter::for_language(&voice_manager.get_config().stt_config.language);
+    let bridge = Arc::new(AgentBridge::new(config, sink)?.with_segmenter(segmenter));
+
+    let emitter = emitter.clone();
+    let turns = turns.clone();
+    bridge.on_error(move |error| {
+        let emitter = emitter.clone();
+        let turn_id = turns.last_user_turn();
+        Box::pin(async move {
+            emitter
+                .emit(SessionEvent::AgentError { error, turn_id })
+                .await;
+        })
+    });
+    Ok(bridge)
+}
+
+/// Route every voice manager callback into the session event channel
+///
+/// When an agent bridge is configured, each STT result is fed to it before
+/// the transcript event is emitted, so new speech interrupts its reply.
+/// Barge-in sees VAD events and STT results before anything else and drops
+/// results the echo guard flags as echoes of TTS output. Spoken text and
+/// output audio are reported to barge-in, and output audio is counted in
+/// `usage` before it is emitted; completions and clears end the utterance
+/// `usage` pairs text and audio by. With `output_level`, the level of PCM16
+/// output audio is emitted after the audio. With `speech_activity`, output
+/// audio and clears are recorded for the post-call diarization pass.
+///
+/// Transcripts that get past barge-in, speech and errors are tagged with
+/// their turn by `turns`, and the STT provider of each turn is recorded in
+/// `usage` when turns are routed. Final transcripts and the text sent to TTS are
+/// recorded in `transcript`; final transcripts are tagged with the language
+/// the provider detected, or the configured STT language.
+///
+/// With `latency_budget`, tagged transcripts, the text sent to TTS, output
+/// audio and clears are timed against the turn's deadlines. Transcripts are
+/// timed before the agent bridge sees them, so its reply is timed from the
+/// speech-final result.
+///
+/// With `watchdog`, STT results and VAD events show the STT provider is
+/// alive, and the text sent to TTS, output audio and completions show TTS
+/// progress. Results count before barge-in, which may drop them.
+///
+/// With `formatter`, STT results are formatted before anything else sees
+/// them, so messages, the agent bridge and `transcript` share one text.
+#[allow(clippy::too_many_arguments)]
+async fn register_voice_callbacks(
+    voice_manager: &Arc<VoiceManager>,
+    formatter: Option<Arc<TranscriptFormatter>>,
+    agent_bridge: Option<Arc<AgentBridge>>,
+    barge_in: Arc<BargeIn>,
+    latency_budget: Option<Arc<LatencyBudget>>,
+    watchdog: Option<Arc<PipelineWatchdog>>,
+    output_level: Option<Arc<LevelMeter>>,
+    speech_activity: Option<Arc<SpeechActivityRecorder>>,
+    emitter: &EventEmitter,
+    usage: &Arc<UsageMeter>,
+    turns: &Arc<TurnTracker>,
+    transcript: &Arc<TranscriptBuffer>,
+) -> VoiceManagerResult<()> {
+    let stt_emitter = emitter.clone();
+    let stt_barge_in = barge_in.clone();
+    let stt_turns = turns.clone();
+    let stt_transcript = transcript.clone();
+    let stt_usage = usage.clone();
+    let stt_latency = latency_budget.clone();
+    let stt_watchdog = watchdog.clone();
+    // Language of transcripts whose provider reports none
+    let configured_language = Some(voice_manager.get_config().stt_config.language.clone())
+        .filter(|language| !language.is_empty());
+    voice_manager
+        .on_stt_result(move |mut result: STTResult| {
+            if let Some(watchdog) = &stt_watchdog {
+                watchdog.on_stt_result();
+            }
+            // Format before the future is spawned, so results keep their order
+            if let Some(formatter) = &formatter {
+                formatter.format(&mut result);
+            }
+            let emitter = stt_emitter.clone();
+            let agent_bridge = agent_bridge.clone();
+            let latency_budget = stt_latency.clone();
+            let barge_in = stt_barge_in.clone();
+            let turns = stt_turns.clone();
+            let transcript = stt_transcript.clone();
+            let usage = stt_usage.clone();
+            let configured_language = configured_language.clone();
+            Box::pin(async move {
+                if !barge_in.on_transcript(&result).await {
+                    return;
+                }
+                // Tag before the agent bridge runs, so its reply answers this turn
+                let turn_id = turns.on_transcript(result.is_speech_final);
+                usage.record_stt_turn(&turn_id);
+                result.turn_id = Some(turn_id);
+                if let Some(latency_budget) = latency_budget {
+                    latency_budget.on_transcript(&result).await;
+                }
+                if let Some(bridge) = agent_bridge {
+                    bridge.handle_stt_result(&result).await;
+                }
+                if result.is_final {
+                    let mut entry = TranscriptEntry::new(
+                        TranscriptRole::User,
+                        result.transcript.clone(),
+                        result.turn_id.clone(),
+                    );
+                    if let Some(language) = result.detected_language.clone().or(configured_language)
+                    {
+                        entry = entry.with_language(language);
+                    }
+                    if let Some(timing) = &result.timing {
+                        entry = entry.with_duration_ms(
+                            timing.end_utc_ms.saturating_sub(timing.start_utc_ms),
+                        );
+                    }
+                    transcript.push(entry).await;
+                }
+                emitter.emit(SessionEvent::Transcript(result)).await;
+            })
+        })
+        .await?;
+
+    let vad_emitter = emitter.clone();
+    let vad_barge_in = barge_in.clone();
+    let vad_watchdog = watchdog.clone();
+    let vad_supported = voice_manager
+        .on_stt_vad_event(move |event: STTVadEvent| {
+            if let Some(watchdog) = &vad_watchdog {
+                watchdog.on_vad_event(&event);
+            }
+            let emitter = vad_emitter.clone();
+            let barge_in = vad_barge_in.clone();
+            Box::pin(async move {
+                barge_in.on_vad_event(&event).await;
+                emitter.emit(SessionEvent::Vad(event)).await;
+            })
+        })
+        .await?;
+    barge_in.set_vad_supported(vad_supported);
+
+    let stt_error_emitter = emitter.clone();
+    let stt_error_turns = turns.clone();
+    voice_manager
+        .on_stt_error(move |error| {
+            let emitter = stt_error_emitter.clone();
+            let turn_id = stt_error_turns.user_turn();
+            Box::pin(async move {
+                emitter
+                    .emit(SessionEvent::SttError { error, turn_id })
+                    .await;
+            })
+        })
+        .await?;
+
+    let tts_error_emitter = emitter.clone();
+    let tts_error_turns = turns.clone();
+    voice_manager
+        .on_tts_error(move |error| {
+            let emitter = tts_error_emitter.clone();
+            let turn_id = tts_error_turns.speech_turn();
+            Box::pin(async move {
+                emitter
+                    .emit(SessionEvent::TtsError { error, turn_id })
+                    .await;
+            })
+        })
+        .await?;
+
+    let text_barge_in = barge_in.clone();
+    let text_transcript = transcript.clone();
+    let text_turns = turns.clone();
+    let text_latency = latency_budget.clone();
+    let text_watchdog = watchdog.clone();
+    voice_manager
+        .on_tts_text(move |text: String| {
+            text_barge_in.on_tts_text(&text);
+            if let Some(watchdog) = &text_watchdog {
+                watchdog.on_tts_progress();
+            }
+            let transcript = text_transcript.clone();
+            let latency_budget = text_latency.clone();
+            let entry =
+                TranscriptEntry::new(TranscriptRole::Assistant, text, text_turns.speech_turn());
+            Box::pin(async move {
+                if let Some(latency_budget) = latency_budget {
+                    latency_budget.on_reply_text().await;
+                }
+                transcript.push(entry).await;
+            })
+        })
+        .await?;
+
+    let audio_emitter = emitter.clone();
+    let audio_usage = usage.clone();
+    let audio_turns = turns.clone();
+    let audio_activity = speech_activity.clone();
+    let audio_latency = latency_budget.clone();
+    let audio_watchdog = watchdog.clone();
+    voice_manager
+        .on_tts_audio(move |audio_data: AudioData| {
+            let emitter = audio_emitter.clone();
+            let latency_budget = audio_latency.clone();
+            audio_usage.add_tts_audio(&audio_data);
+            if let Some(watchdog) = &audio_watchdog {
+                watchdog.on_tts_progress();
+            }
+            if let Some(activity) = &audio_activity {
+                activity.record_bot_audio(audio_duration_ms(&audio_data), now_ms());
+            }
+            barge_in.on_tts_audio();
+            let started = audio_turns
+                .on_audio()
+                .and_then(|(turn_id, first)| first.then_some(turn_id));
+            let level = output_level
+                .as_ref()
+                .filter(|_| is_pcm16(&audio_data.format))
+                .and_then(|meter| {
+                    meter.measure(&audio_data.data, audio_data.sample_rate, Instant::now())
+                });
+            Box::pin(async move {
+                if let Some(latency_budget) = latency_budget {
+                    latency_budget.on_audio().await;
+                }
+                if let Some(turn_id) = started {
+                    emitter
+                        .emit(SessionEvent::SpeechStarted {
+                            turn_id,
+                            timestamp: now_ms(),
+                        })
+                        .await;
+                }
+                emitter.emit(SessionEvent::Audio(audio_data)).await;
+                if let Some(level) = level {
+                    emitter.emit(SessionEvent::AudioLevel(level)).await;
+                }
+            })
+        })
+        .await?;
+
+    // Filler is not speech: no usage, turn, barge-in or transcript
+    let filler_emitter = emitter.clone();
+    voice_manager
+        .on_filler_audio(move |audio_data: AudioData| {
+            let emitter = filler_emitter.clone();
+            Box::pin(async move {
+                emitter.emit(SessionEvent::Audio(audio_data)).await;
+            })
+        })
+        .await?;
+
+    let fallback_emitter = emitter.clone();
+    let fallback_turns = turns.clone();
+    voice_manager
+        .on_tts_voice_fallback(move |from_voice_id, to_voice_id| {
+            let emitter = fallback_emitter.clone();
+            let turn_id = fallback_turns.speech_turn();
+            Box::pin(async move {
+                emitter
+                    .emit(SessionEvent::VoiceFallback {
+                        from_voice_id,
+                        to_voice_id,
+                        turn_id,
+                    })
+                    .await;
+            })
+        })
+        .await?;
+
+    let queue_full_emitter = emitter.clone();
+    voice_manager
+        .on_tts_queue_full(move |full: TTSQueueFull| {
+            let emitter = queue_full_emitter.clone();
+            Box::pin(async move {
+                emitter
+                    .emit(SessionEvent::TtsQueueFull {
+                        policy: full.policy,
+                        max_pending: full.max_pending,
+                        text: full.text,
+                    })
+                    .await;
+            })
+        })
+        .await?;
+
+    let preemption_emitter = emitter.clone();
+    voice_manager
+        .on_preemption(move |event: PreemptionEvent| {
+            let emitter = preemption_emitter.clone();
+            Box::pin(async move {
+                let event = match event {
+                    PreemptionEvent::Started { text, paused } => {
+                        SessionEvent::PreemptionStarted { text, paused }
+                    }
+                    PreemptionEvent::Ended { resumed } => SessionEvent::PreemptionEnded { resumed },
+                };
+                emitter.emit(event).await;
+            })
+        })
+        .await?;
+
+    let degraded_emitter = emitter.clone();
+    voice_manager
+        .on_turn_detection_degraded(move |degraded: TurnDetectionDegraded| {
+            let emitter = degraded_emitter.clone();
+            Box::pin(async move {
+                emitter
+                    .emit(SessionEvent::TurnDetectionDegraded(degraded))
+                    .await;
+            })
+        })
+        .await?;
+
+    let quality_emitter = emitter.clone();
+    let quality_turns = turns.clone();
+    voice_manager
+        .on_tts_audio_quality_warning(move |warning: TTSAudioQualityWarning| {
+            let emitter = quality_emitter.clone();
+            let turn_id = quality_turns.speech_turn();
+            Box::pin(async move {
+                emitter
+                    .emit(SessionEvent::TtsAudioQualityWarning { warning, turn_id })
+                    .await;
+            })
+        })
+        .await?;
+
+    let complete_emitter = emitter.clone();
+    let complete_turns = turns.clone();
+    let complete_usage = usage.clone();
+    voice_manager
+        .on_tts_complete(move || {
+            let emitter = complete_emitter.clone();
+            complete_usage.complete_tts_utterance();
+            if let Some(watchdog) = &watchdog {
+                watchdog.on_tts_progress();
+            }
+            let turn_id = complete_turns.on_speech_complete();
+            Box::pin(async move {
+                let timestamp = now_ms();
+                debug!("TTS playback completed at timestamp {}", timestamp);
+                emitter
+                    .emit(SessionEvent::SpeechComplete { timestamp, turn_id })
+                    .await;
+            })
+        })
+        .await?;
+
+    if voice_manager.get_config().tts_config.visemes_enabled() {
+        let timing_emitter = emitter.clone();
+        let timing_turns = turns.clone();
+        voice_manager
+            .on_tts_timing(move |events| {
+                let emitter = timing_emitter.clone();
+                let turn_id = timing_turns.speech_turn();
+                Box::pin(async move {
+                    emitter
+                        .emit(SessionEvent::TtsTiming { events, turn_id })
+                        .await;
+                })
+            })
+            .await?;
+    }
+
+    let clear_emitter = emitter.clone();
+    let clear_turns = turns.clone();
+    let clear_usage = usage.clone();
+    voice_manager
+        .on_audio_clear(move || {
+            let emitter = clear_emitter.clone();
+            clear_turns.clear_speech();
+            clear_usage.discard_tts_utterance();
+            if let Some(latency_budget) = &latency_budget {
+                latency_budget.on_clear();
+            }
+            if let Some(activity) = &speech_activity {
+                activity.record_clear(now_ms());
+            }
+            Box::pin(async move {
+                emitter.clear().await;
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stt_config(provider: &str) -> STTConfig {
+        STTConfig {
+            provider: provider.to_string(),
+            api_key: "test-key".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn tts_config(provider: &str) -> TTSConfig {
+        TTSConfig {
+            provider: provider.to_string(),
+            api_key: "test-key".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_voice_session_requires_stt_and_tts() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_realtime_cannot_be_combined_with_voice_providers() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .agent(AgentBridgeConfig {
+                endpoint: "http://localhost:8000/v1/chat/completions".to_string(),
+                api_key: None,
+                model: "test-model".to_string(),
+                system_prompt: None,
+                max_tokens: None,
+            })
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .barge_in(BargeInMode::OnVad)
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .fallback_voice("backup-voice")
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .echo_guard(EchoGuardConfig::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .tts_queue_limit(TTSQueueLimit::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .system_speak_max_chars(200)
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .tts_audio_quality(TTSAudioQualityConfig::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .filler_audio(
+                FillerAudioConfig::new("typing"),
+                Some(PathBuf::from("/tmp")),
+            )
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_filler_audio_requires_assets_dir() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .filler_audio(FillerAudioConfig::new("typing"), None)
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(message)) if message.contains("assets directory")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_adaptive_endpointing_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .adaptive_endpointing(AdaptiveEndpointingConfig {
+                min_silence_ms: 800,
+                max_silence_ms: 400,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tts_audio_quality_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .tts_audio_quality(TTSAudioQualityConfig {
+                max_clipped_fraction: 2.0,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_latency_budget_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .latency_budget(LatencyBudgetConfig {
+                total_ms: 500,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(ref message)) if message.starts_with("invalid latency budget")
+        ));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .latency_budget(LatencyBudgetConfig::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_watchdog_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .watchdog(PipelineWatchdogConfig {
+                stall_timeout_ms: 0,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(ref message)) if message.starts_with("invalid pipeline watchdog")
+        ));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .watchdog(PipelineWatchdogConfig::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_barge_in_confirmation_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .barge_in(BargeInMode::OnInterim)
+            .barge_in_confirmation(BargeInConfirmationConfig {
+                min_confidence: -0.5,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(ref message)) if message.starts_with("invalid barge-in confirmation")
+        ));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .barge_in_confirmation(BargeInConfirmationConfig::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_transcript_formatting_is_rejected() {
+        let mut formatting = TranscriptFormattingConfig::default();
+        formatting
+            .fillers
+            .insert("en".to_string(), vec!["you know".to_string()]);
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .transcript_formatting(formatting)
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(ref message)) if message.starts_with("invalid transcript formatting")
+        ));
+
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .transcript_formatting(TranscriptFormattingConfig::default())
+            .build()
+            .await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_consent_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .realtime(RealtimeConfig {
+                provider: "openai".to_string(),
+                ..Default::default()
+            })
+            .consent(ConsentConfig {
+                timeout_ms: 0,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(ref message)) if message.starts_with("invalid consent")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_retry_budget_is_rejected() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("deepgram"))
+            .tts(tts_config("deepgram"))
+            .retry_budget(RetryBudgetConfig {
+                capacity: 2,
+                stt_reserve: 1,
+                tts_reserve: 1,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(matches!(
+            result,
+            Err(SessionError::InvalidConfig(ref message)) if message.starts_with("invalid retry budget")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_provider_fails_creation() {
+        let result = SessionPipelineBuilder::new()
+            .stt(stt_config("no-such-provider"))
+            .tts(tts_config("deepgram"))
+            .build()
+            .await;
+        match result {
+            Err(e @ SessionError::CreateFailed(_)) => {
+                assert!(e.to_string().starts_with("Failed to create voice manager"));
+            }
+            other => panic!("expected CreateFailed, got {:?}", other.err()),
+        }
+    }
+}
@@ -0,0 +1,590 @@
+//! Recording consent: keep caller audio from the providers until the caller agrees
+//!
+//! In two-party consent jurisdictions a call may not be transcribed or
+//! recorded before the caller has agreed to it. A session built with a
+//! [`ConsentConfig`] starts with consent pending: input audio is held back
+//! (or dropped) instead of reaching the STT or realtime provider, while the
+//! session can still speak, e.g. to ask for consent.
+//!
+//! [`Session::set_consent`](super::Session::set_consent) grants or denies
+//! consent; once granted, held audio is sent on ahead of new audio. Consent
+//! still pending `timeout_ms` after the session was built (or after its hold
+//! was released) times out. Every decision is emitted once as
+//! `SessionEvent::Consent` and reported in the session's usage; a session
+//! whose consent was denied or timed out never sends input audio on.
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use super::events::{EventEmitter, SessionEvent};
+use super::usage::now_ms;
+
+/// Longest allowed `timeout_ms`
+pub const MAX_CONSENT_TIMEOUT_MS: u64 = 300_000;
+
+/// Longest allowed `max_held_ms`
+pub const MAX_HELD_AUDIO_MS: u64 = 30_000;
+
+/// Keys a DTMF keypad can send
+const DTMF_DIGITS: &str = "0123456789*#";
+
+/// What happens to input audio while consent is pending
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PendingAudioPolicy {
+    /// Keep the latest `max_held_ms` and send it on once consent is granted
+    #[default]
+    Hold,
+    /// Drop it
+    Drop,
+}
+
+/// How a session asks for and waits on the caller's recording consent
+///
+/// # Example JSON
+/// ```json
+/// {"pending_audio": "drop", "timeout_ms": 20000, "grant_digit": "1", "deny_digit": "2"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ConsentConfig {
+    /// What happens to input audio while consent is pending
+    pub pending_audio: PendingAudioPolicy,
+    /// How long the caller has to consent before the session times out (ms)
+    pub timeout_ms: u64,
+    /// Most recent audio held with `pending_audio: "hold"` (ms)
+    pub max_held_ms: u64,
+    /// DTMF key that grants consent
+    pub grant_digit: String,
+    /// DTMF key that denies consent; other keys are ignored
+    pub deny_digit: Option<String>,
+    /// Said before a call without consent is ended; `None` ends it silently
+    pub goodbye: Option<String>,
+}
+
+impl Default for ConsentConfig {
+    fn default() -> Self {
+        Self {
+            pending_audio: PendingAudioPolicy::Hold,
+            timeout_ms: 30_000,
+            max_held_ms: 5_000,
+            grant_digit: "1".to_string(),
+            deny_digit: Some("2".to_string()),
+            goodbye: Some("We can't continue this call without your consent. Goodbye.".to_string()),
+        }
+    }
+}
+
+impl ConsentConfig {
+    /// Validate the timeouts and keys
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_ms == 0 || self.timeout_ms > MAX_CONSENT_TIMEOUT_MS {
+            return Err(format!(
+                "timeout_ms must be between 1 and {MAX_CONSENT_TIMEOUT_MS} (got {})",
+                self.timeout_ms
+            ));
+        }
+        if self.max_held_ms > MAX_HELD_AUDIO_MS {
+            return Err(format!(
+                "max_held_ms must be at most {MAX_HELD_AUDIO_MS} (got {})",
+                self.max_held_ms
+            ));
+        }
+        for (field, digit) in [
+            ("grant_digit", Some(&self.grant_digit)),
+            ("deny_digit", self.deny_digit.as_ref()),
+        ] {
+            if let Some(digit) = digit
+                && (digit.chars().count() != 1 || !DTMF_DIGITS.contains(digit.as_str()))
+            {
+                return Err(format!(
+                    "{field} must be one of 0-9, * or # (got {digit:?})"
+                ));
+            }
+        }
+        if self.deny_digit.as_ref() == Some(&self.grant_digit) {
+            return Err("grant_digit and deny_digit must differ".to_string());
+        }
+        if self
+            .goodbye
+            .as_ref()
+            .is_some_and(|goodbye| goodbye.trim().is_empty())
+        {
+            return Err("goodbye must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Decision a DTMF key stands for
+    ///
+    /// # Returns
+    /// * `Some(true)` - The key grants consent
+    /// * `Some(false)` - The key denies consent
+    /// * `None` - The key means nothing to the consent gate
+    pub fn digit_decision(&self, digit: &str) -> Option<bool> {
+        if digit == self.grant_digit {
+            Some(true)
+        } else if self.deny_digit.as_deref() == Some(digit) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where a session stands on recording consent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentState {
+    /// Not decided yet; input audio is held or dropped
+    Pending,
+    /// Granted; input audio is processed
+    Granted,
+    /// Denied by the caller
+    Denied,
+    /// Not given within `timeout_ms`
+    TimedOut,
+}
+
+impl ConsentState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Granted => "granted",
+            Self::Denied => "denied",
+            Self::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// What decided a session's consent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentSource {
+    /// The session's client, e.g. with a `consent` message
+    Client,
+    /// A DTMF key pressed by a SIP caller
+    Dtmf,
+    /// `timeout_ms` passed without a decision
+    Timeout,
+}
+
+impl ConsentSource {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Dtmf => "dtmf",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
+/// A session's consent and how it was decided
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConsentRecord {
+    pub state: ConsentState,
+    /// What decided it; `None` while pending
+    pub source: Option<ConsentSource>,
+    /// Participant who decided, when known (e.g. the SIP caller who pressed
+    /// the key)
+    pub participant: Option<String>,
+    /// Unix timestamp in milliseconds of the decision; `None` while pending
+    pub timestamp: Option<u64>,
+}
+
+impl ConsentRecord {
+    fn pending() -> Self {
+        Self {
+            state: ConsentState::Pending,
+            source: None,
+            participant: None,
+            timestamp: None,
+        }
+    }
+}
+
+/// Holds a session's input audio back until consent is granted
+pub(super) struct ConsentGate {
+    config: ConsentConfig,
+    /// Most audio held while pending (bytes)
+    max_held_bytes: usize,
+    emitter: EventEmitter,
+    state: Mutex<GateState>,
+    timer: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct GateState {
+    record: ConsentRecord,
+    /// Held audio, oldest first
+    held: VecDeque<Bytes>,
+    held_bytes: usize,
+    /// Set from the grant until the held audio has been sent on; audio
+    /// arriving meanwhile queues behind it
+    flushing: bool,
+}
+
+impl ConsentGate {
+    /// Create a pending gate for input audio of `input_byte_rate` bytes per second
+    pub(super) fn new(config: ConsentConfig, input_byte_rate: u64, emitter: EventEmitter) -> Self {
+        let max_held_bytes = match config.pending_audio {
+            PendingAudioPolicy::Hold => (config.max_held_ms * input_byte_rate / 1000) as usize,
+            PendingAudioPolicy::Drop => 0,
+        };
+        Self {
+            config,
+            max_held_bytes,
+            emitter,
+            state: Mutex::new(GateState {
+                record: ConsentRecord::pending(),
+                held: VecDeque::new(),
+                held_bytes: 0,
+                flushing: false,
+            }),
+            timer: Mutex::new(None),
+        }
+    }
+
+    /// Let input audio through, or hold or drop it
+    ///
+    /// # Returns
+    /// * `bool` - Whether the audio may be sent on now
+    pub(super) fn admit(&self, audio: &Bytes) -> bool {
+        let mut state = self.state.lock();
+        match state.record.state {
+            ConsentState::Granted if !state.flushing => true,
+            // Queued behind the held audio, which is never trimmed once granted
+            ConsentState::Granted => {
+                state.held_bytes += audio.len();
+                state.held.push_back(audio.clone());
+                false
+            }
+            ConsentState::Pending if self.max_held_bytes > 0 => {
+                state.held_bytes += audio.len();
+                state.held.push_back(audio.clone());
+                while state.held_bytes > self.max_held_bytes
+                    && let Some(oldest) = state.held.pop_front()
+                {
+                    state.held_bytes -= oldest.len();
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Grant or deny pending consent
+    ///
+    /// After a grant the held audio is taken with [`next_held`](Self::next_held).
+    ///
+    /// # Returns
+    /// * `Option<ConsentRecord>` - The decision, or `None` if consent was
+    ///   already decided
+    pub(super) fn decide(
+        &self,
+        granted: bool,
+        source: ConsentSource,
+        participant: Option<String>,
+    ) -> Option<ConsentRecord> {
+        let state = if granted {
+            ConsentState::Granted
+        } else {
+            ConsentState::Denied
+        };
+        let record = self.settle(state, source, participant)?;
+        self.stop_timer();
+        Some(record)
+    }
+
+    fn settle(
+        &self,
+        decision: ConsentState,
+        source: ConsentSource,
+        participant: Option<String>,
+    ) -> Option<ConsentRecord> {
+        let mut state = self.state.lock();
+        if state.record.state != ConsentState::Pending {
+            return None;
+        }
+        state.record = ConsentRecord {
+            state: decision,
+            source: Some(source),
+            participant,
+            timestamp: Some(now_ms()),
+        };
+        if decision == ConsentState::Granted {
+            state.flushing = !state.held.is_empty();
+        } else {
+            state.held.clear();
+            state.held_bytes = 0;
+        }
+        Some(state.record.clone())
+    }
+
+    /// Take the oldest held audio after a grant
+    ///
+    /// # Returns
+    /// * `Option<Bytes>` - Held audio, or `None` once all of it was taken and
+    ///   new audio goes straight through again
+    pub(super) fn next_held(&self) -> Option<Bytes> {
+        let mut state = self.state.lock();
+        if state.record.state != ConsentState::Granted {
+            return None;
+        }
+        let audio = state.held.pop_front();
+        match &audio {
+            Some(audio) => state.held_bytes -= audio.len(),
+            None => state.flushing = false,
+        }
+        audio
+    }
+
+    /// The current consent
+    pub(super) fn record(&self) -> ConsentRecord {
+        self.state.lock().record.clone()
+    }
+
+    /// Whether consent has been granted
+    pub(super) fn is_granted(&self) -> bool {
+        self.state.lock().record.state == ConsentState::Granted
+    }
+
+    /// Bytes of input audio currently held
+    pub(super) fn held_bytes(&self) -> usize {
+        self.state.lock().held_bytes
+    }
+
+    /// Time consent out `timeout_ms` from now, unless it is decided first
+    ///
+    /// Restarting the timer starts the wait over.
+    pub(super) fn start_timer(self: &Arc<Self>) {
+        if self.state.lock().record.state != ConsentState::Pending {
+            return;
+        }
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        let gate = Arc::downgrade(self);
+        let timer = tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            let Some(gate) = gate.upgrade() else {
+                return;
+            };
+            // Detach from the handle so stopping the timer cannot cancel the emit
+            gate.timer.lock().take();
+            if let Some(record) = gate.settle(ConsentState::TimedOut, ConsentSource::Timeout, None)
+            {
+                gate.emitter.emit(SessionEvent::Consent(record)).await;
+            }
+        });
+        if let Some(previous) = self.timer.lock().replace(timer) {
+            previous.abort();
+        }
+    }
+
+    /// Stop the timeout, e.g. while the session is held or when it closes
+    pub(super) fn stop_timer(&self) {
+        if let Some(timer) = self.timer.lock().take() {
+            timer.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session::events::SessionEventStream;
+
+    /// 16kHz PCM16 mono
+    const BYTE_RATE: u64 = 32_000;
+
+    fn pending_gate(config: ConsentConfig) -> (Arc<ConsentGate>, SessionEventStream) {
+        let (emitter, events) = EventEmitter::channel(8);
+        (
+            Arc::new(ConsentGate::new(config, BYTE_RATE, emitter)),
+            events,
+        )
+    }
+
+    fn chunk(byte: u8) -> Bytes {
+        // 100ms of audio
+        Bytes::from(vec![byte; 3200])
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ConsentConfig::default().validate().is_ok());
+
+        let invalid = [
+            ConsentConfig {
+                timeout_ms: 0,
+                ..Default::default()
+            },
+            ConsentConfig {
+                timeout_ms: MAX_CONSENT_TIMEOUT_MS + 1,
+                ..Default::default()
+            },
+            ConsentConfig {
+                max_held_ms: MAX_HELD_AUDIO_MS + 1,
+                ..Default::default()
+            },
+            ConsentConfig {
+                grant_digit: "12".to_string(),
+                ..Default::default()
+            },
+            ConsentConfig {
+                deny_digit: Some("a".to_string()),
+                ..Default::default()
+            },
+            ConsentConfig {
+                deny_digit: Some("1".to_string()),
+                ..Default::default()
+            },
+            ConsentConfig {
+                goodbye: Some(" ".to_string()),
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[test]
+    fn test_digit_decision() {
+        let config = ConsentConfig {
+            grant_digit: "#".to_string(),
+            deny_digit: Some("*".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.digit_decision("#"), Some(true));
+        assert_eq!(config.digit_decision("*"), Some(false));
+        assert_eq!(config.digit_decision("1"), None);
+
+        let config = ConsentConfig {
+            deny_digit: None,
+            ..Default::default()
+        };
+        assert_eq!(config.digit_decision("2"), None);
+    }
+
+    #[test]
+    fn test_config_json() {
+        let config: ConsentConfig =
+            serde_json::from_str(r#"{"pending_audio": "drop", "timeout_ms": 20000}"#).unwrap();
+        assert_eq!(config.pending_audio, PendingAudioPolicy::Drop);
+        assert_eq!(config.timeout_ms, 20000);
+        assert_eq!(config.grant_digit, "1");
+    }
+
+    #[tokio::test]
+    async fn test_hold_keeps_latest_audio_until_granted() {
+        let (gate, _events) = pending_gate(ConsentConfig {
+            max_held_ms: 200,
+            ..Default::default()
+        });
+
+        for byte in 1..=3 {
+            assert!(!gate.admit(&chunk(byte)));
+        }
+        assert_eq!(gate.held_bytes(), 6400);
+
+        let record = gate
+            .decide(true, ConsentSource::Dtmf, Some("sip_caller".to_string()))
+            .unwrap();
+        assert_eq!(record.state, ConsentState::Granted);
+        assert_eq!(record.source, Some(ConsentSource::Dtmf));
+        assert_eq!(record.participant.as_deref(), Some("sip_caller"));
+        assert!(record.timestamp.is_some());
+
+        // Audio arriving while the held audio is sent on queues behind it
+        assert!(!gate.admit(&chunk(4)));
+        let flushed: Vec<u8> = std::iter::from_fn(|| gate.next_held())
+            .map(|audio| audio[0])
+            .collect();
+        assert_eq!(flushed, vec![2, 3, 4]);
+        assert!(gate.admit(&chunk(5)));
+
+        // Decided once
+        assert!(gate.decide(false, ConsentSource::Client, None).is_none());
+        assert_eq!(gate.record(), record);
+    }
+
+    #[tokio::test]
+    async fn test_drop_and_deny() {
+        let (gate, _events) = pending_gate(ConsentConfig {
+            pending_audio: PendingAudioPolicy::Drop,
+            ..Default::default()
+        });
+        assert!(!gate.admit(&chunk(1)));
+        assert_eq!(gate.held_bytes(), 0);
+
+        let (gate, _events) = pending_gate(ConsentConfig::default());
+        assert!(!gate.admit(&chunk(1)));
+        let record = gate.decide(false, ConsentSource::Client, None).unwrap();
+        assert_eq!(record.state, ConsentState::Denied);
+        assert_eq!(gate.held_bytes(), 0);
+        assert!(gate.next_held().is_none());
+        assert!(!gate.admit(&chunk(2)));
+        assert!(!gate.is_granted());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout() {
+        let (gate, mut events) = pending_gate(ConsentConfig {
+            timeout_ms: 1000,
+            ..Default::default()
+        });
+        gate.start_timer();
+        assert!(!gate.admit(&chunk(1)));
+
+        tokio::time::sleep(Duration::from_millis(999)).await;
+        assert_eq!(gate.record().state, ConsentState::Pending);
+
+        match events.recv().await {
+            Some(SessionEvent::Consent(record)) => {
+                assert_eq!(record.state, ConsentState::TimedOut);
+                assert_eq!(record.source, Some(ConsentSource::Timeout));
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert_eq!(gate.held_bytes(), 0);
+        assert!(gate.decide(true, ConsentSource::Client, None).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stopped_timer_does_not_fire() {
+        let (gate, mut events) = pending_gate(ConsentConfig {
+            timeout_ms: 1000,
+            ..Default::default()
+        });
+        gate.start_timer();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        gate.stop_timer();
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+        assert_eq!(gate.record().state, ConsentState::Pending);
+
+        // A restart waits the full timeout again
+        gate.start_timer();
+        tokio::time::sleep(Duration::from_millis(999)).await;
+        assert_eq!(gate.record().state, ConsentState::Pending);
+        assert!(matches!(
+            events.recv().await,
+            Some(SessionEvent::Consent(ConsentRecord {
+                state: ConsentState::TimedOut,
+                ..
+            }))
+        ));
+    }
+}
@@ -0,0 +1,513 @@
+//! Offline speaker attribution of a finished session
+//!
+//! Not every STT provider diarizes live, and on speakerphone calls the
+//! assistant's own voice comes back through the caller's mic and is
+//! transcribed as user speech. A voice session knows exactly when its TTS
+//! audio played, so after the call the energy of the caller's line can be
+//! split into speech segments and each segment attributed by turn-taking:
+//! speech while the bot was playing (plus a short echo tail) is the bot,
+//! everything else is the caller.
+//!
+//! When enabled with
+//! [`SessionPipelineBuilder::speech_activity`](super::SessionPipelineBuilder::speech_activity),
+//! the session records that timeline as a [`SpeechActivity`]. [`diarize`]
+//! turns it into [`SpeakerSegment`]s and [`annotate_speakers`] labels the
+//! transcript entries that have no speaker yet.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::audio_level::to_dbfs;
+use super::transcript::TranscriptEntry;
+use crate::core::realtime::TranscriptRole;
+
+/// Length of the input audio frames levels are measured over (ms)
+pub const ACTIVITY_FRAME_MS: u64 = 20;
+
+/// Most input frames recorded per session (3 hours); later audio is ignored
+const MAX_ACTIVITY_FRAMES: usize = 3 * 60 * 60 * 1000 / ACTIVITY_FRAME_MS as usize;
+
+/// Most bot speaking intervals recorded; later audio extends the last one
+const MAX_BOT_INTERVALS: usize = 10_000;
+
+/// Input that arrives this much later than the audio recorded so far means
+/// the client paused sending; the gap is recorded as silence (ms)
+const RESYNC_THRESHOLD_MS: u64 = 500;
+
+/// Frames at or above this RMS level count as speech (dBFS)
+pub(super) const SPEECH_THRESHOLD_DB: f32 = -45.0;
+
+/// Pauses shorter than this do not split a speech segment (ms)
+const MAX_PAUSE_MS: u64 = 300;
+
+/// Speech shorter than this is ignored as noise (ms)
+const MIN_SEGMENT_MS: u64 = 100;
+
+/// How long the bot's echo may trail the end of its audio (ms)
+const ECHO_TAIL_MS: u64 = 300;
+
+/// How long before a user transcript its speech may have ended (ms)
+const ATTRIBUTION_WINDOW_MS: u64 = 3000;
+
+/// Who is heard in a transcript entry or speech segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Speaker {
+    /// The assistant's TTS audio, or its echo on the caller's line
+    Bot,
+    /// The person on the call
+    Caller,
+}
+
+/// A span of time, in Unix milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeechInterval {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl SpeechInterval {
+    fn overlaps(&self, start_ms: u64, end_ms: u64) -> bool {
+        self.start_ms < end_ms && start_ms < self.end_ms
+    }
+}
+
+/// A stretch of speech attributed to one speaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeakerSegment {
+    pub speaker: Speaker,
+    /// Start of the speech (Unix ms)
+    pub start_ms: u64,
+    /// End of the speech (Unix ms)
+    pub end_ms: u64,
+}
+
+/// What a session recorded for the post-call diarization pass
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeechActivity {
+    /// Start of the first input frame (Unix ms)
+    pub started_at: u64,
+    /// RMS level of each `ACTIVITY_FRAME_MS` frame of input audio (dBFS)
+    pub input_levels_db: Vec<f32>,
+    /// When the session's TTS audio played, oldest first
+    pub bot_speech: Vec<SpeechInterval>,
+    /// Input audio past the recording limit was not recorded
+    pub truncated: bool,
+}
+
+impl SpeechActivity {
+    /// Start of input frame `index` (Unix ms)
+    fn frame_start(&self, index: usize) -> u64 {
+        self.started_at + index as u64 * ACTIVITY_FRAME_MS
+    }
+}
+
+/// Records the speech timeline of a voice session
+///
+/// Input levels follow the audio clock from the first input chunk, which
+/// arrives in real time. Output audio is generated faster than it plays, so
+/// each chunk is assumed to play right after the previous one, or now if
+/// the bot was silent; clearing the output ends playback immediately.
+pub(super) struct SpeechActivityRecorder {
+    measure_input: bool,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    activity: SpeechActivity,
+    /// Samples and sum of squares of the incomplete input frame
+    frame_samples: u64,
+    frame_sum_squares: f64,
+    /// When the queued bot audio finishes playing (Unix ms)
+    playhead: u64,
+}
+
+impl SpeechActivityRecorder {
+    /// Create a recorder; input levels are only measured with `measure_input`
+    /// (PCM16 input)
+    pub(super) fn new(measure_input: bool) -> Self {
+        Self {
+            measure_input,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Add little-endian PCM16 input audio that arrived at `now_ms`
+    pub(super) fn record_input(&self, pcm: &[u8], sample_rate: u32, now_ms: u64) {
+        if !self.measure_input {
+            return;
+        }
+        let sample_rate = sample_rate.max(1) as u64;
+        let samples = (pcm.len() / 2) as u64;
+        let chunk_start = now_ms.saturating_sub(samples * 1000 / sample_rate);
+        let window = (sample_rate * ACTIVITY_FRAME_MS / 1000).max(1);
+
+        let mut state = self.state.lock();
+        if state.activity.input_levels_db.is_empty() && state.frame_samples == 0 {
+            state.activity.started_at = chunk_start;
+        }
+        let recorded_until = state
+            .activity
+            .frame_start(state.activity.input_levels_db.len());
+        if chunk_start > recorded_until + RESYNC_THRESHOLD_MS {
+            let gap_frames = (chunk_start - recorded_until) / ACTIVITY_FRAME_MS;
+            for _ in 0..gap_frames {
+                state.push_level(to_dbfs(0.0));
+            }
+            state.frame_samples = 0;
+            state.frame_sum_squares = 0.0;
+        }
+
+        for sample in pcm.chunks_exact(2) {
+            let sample = i16::from_le_bytes([sample[0], sample[1]]) as f64;
+            state.frame_sum_squares += sample * sample;
+            state.frame_samples += 1;
+            if state.frame_samples == window {
+                let rms = (state.frame_sum_squares / window as f64).sqrt();
+                state.push_level(to_dbfs(rms));
+                state.frame_samples = 0;
+                state.frame_sum_squares = 0.0;
+            }
+        }
+    }
+
+    /// Record an output audio chunk of `duration_ms` generated at `now_ms`
+    pub(super) fn record_bot_audio(&self, duration_ms: u64, now_ms: u64) {
+        if duration_ms == 0 {
+            return;
+        }
+        let mut state = self.state.lock();
+        let start_ms = state.playhead.max(now_ms);
+        let end_ms = start_ms + duration_ms;
+        state.playhead = end_ms;
+
+        let intervals = &mut state.activity.bot_speech;
+        let full = intervals.len() >= MAX_BOT_INTERVALS;
+        match intervals.last_mut() {
+            Some(last) if last.end_ms >= start_ms || full => {
+                last.end_ms = end_ms;
+            }
+            _ => intervals.push(SpeechInterval { start_ms, end_ms }),
+        }
+    }
+
+    /// Record that queued output audio was cleared at `now_ms`
+    pub(super) fn record_clear(&self, now_ms: u64) {
+        let mut state = self.state.lock();
+        if state.playhead <= now_ms {
+            return;
+        }
+        state.playhead = now_ms;
+        let intervals = &mut state.activity.bot_speech;
+        if let Some(last) = intervals.last_mut() {
+            if last.start_ms >= now_ms {
+                intervals.pop();
+            } else {
+                last.end_ms = now_ms;
+            }
+        }
+    }
+
+    /// The timeline recorded so far
+    pub(super) fn snapshot(&self) -> SpeechActivity {
+        self.state.lock().activity.clone()
+    }
+}
+
+impl RecorderState {
+    fn push_level(&mut self, level_db: f32) {
+        if self.activity.input_levels_db.len() >= MAX_ACTIVITY_FRAMES {
+            self.activity.truncated = true;
+            return;
+        }
+        self.activity.input_levels_db.push(level_db);
+    }
+}
+
+/// Split a session's input into speech segments and attribute each one
+///
+/// Frames at or above the speech threshold are joined into segments across
+/// short pauses. Each segment is split where bot playback (extended by the
+/// echo tail) starts or ends; parts during playback are the bot, the rest
+/// the caller. Parts shorter than the minimum segment length are dropped and
+/// adjacent parts of the same speaker merged.
+///
+/// # Returns
+/// Segments in time order
+pub fn diarize(activity: &SpeechActivity) -> Vec<SpeakerSegment> {
+    let bot: Vec<SpeechInterval> = activity
+        .bot_speech
+        .iter()
+        .map(|interval| SpeechInterval {
+            start_ms: interval.start_ms,
+            end_ms: interval.end_ms + ECHO_TAIL_MS,
+        })
+        .collect();
+
+    let mut segments: Vec<SpeakerSegment> = Vec::new();
+    for (start_ms, end_ms) in speech_runs(activity) {
+        // Split points: bot interval edges inside the run
+        let mut edges: Vec<u64> = bot
+            .iter()
+            .filter(|interval| interval.overlaps(start_ms, end_ms))
+            .flat_map(|interval| [interval.start_ms, interval.end_ms])
+            .filter(|edge| *edge > start_ms && *edge < end_ms)
+            .collect();
+        edges.push(end_ms);
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut part_start = start_ms;
+        for part_end in edges {
+            let speaker = if bot
+                .iter()
+                .any(|interval| interval.overlaps(part_start, part_end))
+            {
+                Speaker::Bot
+            } else {
+                Speaker::Caller
+            };
+            if part_end - part_start >= MIN_SEGMENT_MS {
+                match segments.last_mut() {
+                    Some(last) if last.speaker == speaker && last.end_ms >= part_start => {
+                        last.end_ms = part_end;
+                    }
+                    _ => segments.push(SpeakerSegment {
+                        speaker,
+                        start_ms: part_start,
+                        end_ms: part_end,
+                    }),
+                }
+            }
+            part_start = part_end;
+        }
+    }
+    segments
+}
+
+/// Runs of speech frames joined across short pauses, as (start, end) in Unix ms
+fn speech_runs(activity: &SpeechActivity) -> Vec<(u64, u64)> {
+    let mut runs: Vec<(u64, u64)> = Vec::new();
+    for (index, level_db) in activity.input_levels_db.iter().enumerate() {
+        if *level_db < SPEECH_THRESHOLD_DB {
+            continue;
+        }
+        let start_ms = activity.frame_start(index);
+        let end_ms = start_ms + ACTIVITY_FRAME_MS;
+        match runs.last_mut() {
+            Some(last) if start_ms <= last.1 + MAX_PAUSE_MS => last.1 = end_ms,
+            _ => runs.push((start_ms, end_ms)),
+        }
+    }
+    runs
+}
+
+/// Label transcript entries that have no speaker from diarized segments
+///
+/// Assistant entries are the bot. A user entry is attributed to the latest
+/// segment that started before it was transcribed and ended at most
+/// `ATTRIBUTION_WINDOW_MS` earlier, so a transcript of the bot's echo is
+/// labelled `bot`; without such a segment it is the caller. Entries that
+/// already have a speaker are kept.
+///
+/// # Returns
+/// The number of entries labelled
+pub fn annotate_speakers(entries: &mut [TranscriptEntry], segments: &[SpeakerSegment]) -> usize {
+    let mut annotated = 0;
+    for entry in entries.iter_mut().filter(|entry| entry.speaker.is_none()) {
+        let speaker = match entry.role {
+            TranscriptRole::Assistant => Speaker::Bot,
+            TranscriptRole::User => segments
+                .iter()
+                .rev()
+                .find(|segment| {
+                    segment.start_ms <= entry.timestamp
+                        && segment.end_ms + ATTRIBUTION_WINDOW_MS >= entry.timestamp
+                })
+                .map_or(Speaker::Caller, |segment| segment.speaker),
+        };
+        entry.speaker = Some(speaker);
+        annotated += 1;
+    }
+    annotated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PCM16 of `ms` milliseconds at 16 kHz, loud or silent
+    fn pcm(ms: u64, loud: bool) -> Vec<u8> {
+        let sample: i16 = if loud { 8000 } else { 0 };
+        (0..ms * 16)
+            .flat_map(|i| if i % 2 == 0 { sample } else { -sample }.to_le_bytes())
+            .collect()
+    }
+
+    fn entry(role: TranscriptRole, text: &str, timestamp: u64) -> TranscriptEntry {
+        TranscriptEntry {
+            timestamp,
+            ..TranscriptEntry::new(role, text, None)
+        }
+    }
+
+    #[test]
+    fn test_bot_playback_is_queued_and_cleared() {
+        let recorder = SpeechActivityRecorder::new(true);
+        // Two seconds of audio generated at once play back to back
+        recorder.record_bot_audio(1000, 10_000);
+        recorder.record_bot_audio(1000, 10_050);
+        // Later audio after a silence starts a new interval
+        recorder.record_bot_audio(500, 15_000);
+        // Cleared half way through
+        recorder.record_clear(15_200);
+        recorder.record_bot_audio(200, 15_300);
+        recorder.record_clear(20_000);
+
+        assert_eq!(
+            recorder.snapshot().bot_speech,
+            [
+                SpeechInterval {
+                    start_ms: 10_000,
+                    end_ms: 12_000
+                },
+                SpeechInterval {
+                    start_ms: 15_000,
+                    end_ms: 15_200
+                },
+                SpeechInterval {
+                    start_ms: 15_300,
+                    end_ms: 15_500
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_levels_fill_pauses_with_silence() {
+        let recorder = SpeechActivityRecorder::new(true);
+        recorder.record_input(&pcm(100, true), 16000, 1_100);
+        // The client stopped sending for a second
+        recorder.record_input(&pcm(100, true), 16000, 2_200);
+
+        let activity = recorder.snapshot();
+        assert_eq!(activity.started_at, 1_000);
+        assert_eq!(activity.input_levels_db.len(), 5 + 50 + 5);
+        assert!(activity.input_levels_db[..5].iter().all(|db| *db > -10.0));
+        assert!(
+            activity.input_levels_db[5..55]
+                .iter()
+                .all(|db| *db <= -99.0)
+        );
+        assert_eq!(activity.frame_start(55), 2_100);
+    }
+
+    /// A call with known TTS intervals: the bot greets (echoed on the
+    /// caller's line), the caller answers, the bot replies and the caller
+    /// barges in right after it stops
+    fn synthetic_call() -> (SpeechActivity, Vec<TranscriptEntry>) {
+        let recorder = SpeechActivityRecorder::new(true);
+        let start = 100_000;
+        let input = [
+            (1000, false),
+            (2000, true), // echo of the greeting, 1.0-3.0s
+            (1000, false),
+            (1500, true), // caller answers, 4.0-5.5s
+            (1000, false),
+            (1500, true), // echo of the reply, 6.5-8.0s
+            (200, false),
+            (1300, true), // caller, 8.2-9.5s
+            (500, false),
+        ];
+        let mut now = start;
+        for (ms, loud) in input {
+            now += ms;
+            recorder.record_input(&pcm(ms, loud), 16000, now);
+        }
+        recorder.record_bot_audio(2000, start + 1000);
+        recorder.record_bot_audio(1500, start + 6500);
+
+        let transcript = vec![
+            entry(
+                TranscriptRole::Assistant,
+                "Hello, how can I help?",
+                start + 950,
+            ),
+            entry(TranscriptRole::User, "hello how can i help", start + 3400),
+            entry(
+                TranscriptRole::User,
+                "I'd like to book a table",
+                start + 5900,
+            ),
+            entry(
+                TranscriptRole::Assistant,
+                "For how many people?",
+                start + 6400,
+            ),
+            entry(TranscriptRole::User, "four please", start + 9800),
+            TranscriptEntry {
+                speaker: Some(Speaker::Caller),
+                ..entry(TranscriptRole::User, "from live diarization", start + 3500)
+            },
+        ];
+        (recorder.snapshot(), transcript)
+    }
+
+    #[test]
+    fn test_diarize_attributes_by_bot_intervals() {
+        let (activity, _) = synthetic_call();
+        let segments = diarize(&activity);
+        let spans: Vec<(Speaker, u64, u64)> = segments
+            .iter()
+            .map(|s| (s.speaker, s.start_ms - 100_000, s.end_ms - 100_000))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (Speaker::Bot, 1000, 3000),
+                (Speaker::Caller, 4000, 5500),
+                (Speaker::Bot, 6500, 8300),
+                (Speaker::Caller, 8300, 9500),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotate_speakers_where_live_diarization_is_absent() {
+        let (activity, mut transcript) = synthetic_call();
+        let annotated = annotate_speakers(&mut transcript, &diarize(&activity));
+
+        assert_eq!(annotated, 5);
+        let speakers: Vec<Speaker> = transcript.iter().map(|e| e.speaker.unwrap()).collect();
+        assert_eq!(
+            speakers,
+            [
+                Speaker::Bot,
+                // The greeting's echo was transcribed as user speech
+                Speaker::Bot,
+                Speaker::Caller,
+                Speaker::Bot,
+                Speaker::Caller,
+                // Kept from live diarization
+                Speaker::Caller,
+            ]
+        );
+        // A second pass changes nothing
+        assert_eq!(annotate_speakers(&mut transcript, &[]), 0);
+    }
+
+    #[test]
+    fn test_annotate_without_recorded_input() {
+        let mut transcript = vec![
+            entry(TranscriptRole::User, "hi", 1_000),
+            entry(TranscriptRole::Assistant, "hello", 2_000),
+        ];
+        let segments = diarize(&SpeechActivity::default());
+        assert!(segments.is_empty());
+        annotate_speakers(&mut transcript, &segments);
+        assert_eq!(transcript[0].speaker, Some(Speaker::Caller));
+        assert_eq!(transcript[1].speaker, Some(Speaker::Bot));
+    }
+}
@@ -0,0 +1,301 @@
+//! Echo guard: keep the assistant's own voice from interrupting it
+//!
+//! On speakerphone calls the TTS output comes back through the caller's mic
+//! and is transcribed like caller speech. While TTS audio is playing, and for
+//! a short tail after the last chunk, the guard raises the bar for barge-in
+//! and drops transcripts that repeat what the assistant just said.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::core::stt::STTResult;
+
+/// Longest allowed `tail_ms`
+pub const MAX_ECHO_TAIL_MS: u64 = 5000;
+
+/// Number of recent utterances transcripts are compared against
+const MAX_SPOKEN_UTTERANCES: usize = 8;
+
+/// Thresholds for treating caller speech as barge-in while TTS is playing
+///
+/// # Example JSON
+/// ```json
+/// {"tail_ms": 800, "min_confidence": 0.85, "min_words": 2}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct EchoGuardConfig {
+    /// How long the guard stays active after the last TTS audio chunk (ms)
+    pub tail_ms: u64,
+    /// Minimum transcript confidence for barge-in while the guard is active
+    pub min_confidence: f32,
+    /// Minimum number of words for barge-in while the guard is active
+    pub min_words: usize,
+    /// Drop transcripts that repeat recently spoken TTS text
+    pub match_spoken_text: bool,
+}
+
+impl Default for EchoGuardConfig {
+    fn default() -> Self {
+        Self {
+            tail_ms: 800,
+            min_confidence: 0.85,
+            min_words: 2,
+            match_spoken_text: true,
+        }
+    }
+}
+
+impl EchoGuardConfig {
+    /// Validate the thresholds
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tail_ms > MAX_ECHO_TAIL_MS {
+            return Err(format!(
+                "tail_ms must be at most {MAX_ECHO_TAIL_MS} (got {})",
+                self.tail_ms
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(format!(
+                "min_confidence must be between 0.0 and 1.0 (got {})",
+                self.min_confidence
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Counts of caller speech the echo guard held back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EchoGuardStats {
+    /// Transcripts dropped because they repeated spoken TTS text
+    pub suppressed_echoes: u64,
+    /// Barge-ins held back because the speech was below the thresholds
+    pub suppressed_barge_ins: u64,
+}
+
+/// Tracks TTS playback and spoken text for one session
+pub(super) struct EchoGuard {
+    config: EchoGuardConfig,
+    /// When the last TTS audio chunk was emitted
+    last_audio: Mutex<Option<Instant>>,
+    /// Normalized text of recent utterances, oldest first
+    spoken: Mutex<VecDeque<String>>,
+    suppressed_echoes: AtomicU64,
+    suppressed_barge_ins: AtomicU64,
+}
+
+impl EchoGuard {
+    pub(super) fn new(config: EchoGuardConfig) -> Self {
+        Self {
+            config,
+            last_audio: Mutex::new(None),
+            spoken: Mutex::new(VecDeque::new()),
+            suppressed_echoes: AtomicU64::new(0),
+            suppressed_barge_ins: AtomicU64::new(0),
+        }
+    }
+
+    /// Remember text sent to the TTS provider
+    pub(super) fn record_text(&self, text: &str) {
+        let normalized = normalize(text);
+        if normalized.is_empty() {
+            return;
+        }
+        let mut spoken = self.spoken.lock();
+        if spoken.len() == MAX_SPOKEN_UTTERANCES {
+            spoken.pop_front();
+        }
+        spoken.push_back(normalized);
+    }
+
+    /// Note that TTS audio was emitted at `now`
+    pub(super) fn record_audio(&self, now: Instant) {
+        *self.last_audio.lock() = Some(now);
+    }
+
+    /// Whether TTS audio is playing or ended less than `tail_ms` ago
+    pub(super) fn is_active(&self, now: Instant) -> bool {
+        self.last_audio.lock().is_some_and(|last| {
+            now.saturating_duration_since(last) <= Duration::from_millis(self.config.tail_ms)
+        })
+    }
+
+    /// Whether `result` repeats recently spoken text and should be dropped
+    ///
+    /// Counts the result as a suppressed echo if so.
+    pub(super) fn is_echo(&self, result: &STTResult, now: Instant) -> bool {
+        if !self.config.match_spoken_text || !self.is_active(now) {
+            return false;
+        }
+        let transcript = normalize(&result.transcript);
+        if transcript.is_empty() {
+            return false;
+        }
+
+        // Sentences are spoken one by one, so an echo can span two of them
+        let spoken = self.spoken.lock();
+        let recent = spoken
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let echo = format!(" {recent} ").contains(&format!(" {transcript} "));
+        if echo {
+            self.suppressed_echoes.fetch_add(1, Ordering::Relaxed);
+        }
+        echo
+    }
+
+    /// Whether `result` is strong enough to interrupt TTS output
+    ///
+    /// Always true while the guard is inactive. Counts the result as a
+    /// suppressed barge-in if not.
+    pub(super) fn allows_barge_in(&self, result: &STTResult, now: Instant) -> bool {
+        if !self.is_active(now) {
+            return true;
+        }
+        let words = result.transcript.split_whitespace().count();
+        let allowed =
+            result.confidence >= self.config.min_confidence && words >= self.config.min_words;
+        if !allowed {
+            self.suppressed_barge_ins.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    /// Count a VAD speech start that was not trusted for barge-in
+    pub(super) fn suppress_vad(&self) {
+        self.suppressed_barge_ins.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn stats(&self) -> EchoGuardStats {
+        EchoGuardStats {
+            suppressed_echoes: self.suppressed_echoes.load(Ordering::Relaxed),
+            suppressed_barge_ins: self.suppressed_barge_ins.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Lowercase words without punctuation, separated by single spaces
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(transcript: &str, confidence: f32) -> STTResult {
+        STTResult::new(transcript.to_string(), false, false, confidence)
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize("  Hello, World!  How's it going? "),
+            "hello world hows it going"
+        );
+        assert_eq!(normalize("... !"), "");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(EchoGuardConfig::default().validate().is_ok());
+        let config = EchoGuardConfig {
+            tail_ms: MAX_ECHO_TAIL_MS + 1,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = EchoGuardConfig {
+            min_confidence: 1.5,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_caller_repeating_last_utterance_is_dropped() {
+        let guard = EchoGuard::new(EchoGuardConfig::default());
+        let start = Instant::now();
+
+        // The bot speaks two sentences and its audio plays out
+        guard.record_text("Thanks for calling Acme.");
+        guard.record_text("How can I help you today?");
+        guard.record_audio(start);
+
+        // The mic picks the audio back up, across the sentence boundary
+        let at = start + Duration::from_millis(200);
+        assert!(guard.is_echo(&result("how can I help", 0.9), at));
+        assert!(guard.is_echo(&result("Acme how can", 0.95), at));
+        // Word boundaries matter: "help you to" is not what was said
+        assert!(!guard.is_echo(&result("help you to", 0.95), at));
+        // Real caller speech passes
+        assert!(!guard.is_echo(&result("I need to cancel my order", 0.95), at));
+
+        // Once playback and the tail are over, nothing is treated as echo
+        let later = start + Duration::from_millis(801);
+        assert!(!guard.is_echo(&result("how can I help", 0.9), later));
+
+        assert_eq!(guard.stats().suppressed_echoes, 2);
+    }
+
+    #[test]
+    fn test_barge_in_thresholds_apply_while_playing() {
+        let guard = EchoGuard::new(EchoGuardConfig::default());
+        let start = Instant::now();
+        assert!(guard.allows_barge_in(&result("no", 0.1), start));
+
+        guard.record_audio(start);
+        assert!(!guard.allows_barge_in(&result("no", 0.99), start));
+        assert!(!guard.allows_barge_in(&result("wait a moment", 0.5), start));
+        assert!(guard.allows_barge_in(&result("wait a moment", 0.9), start));
+
+        let after_tail = start + Duration::from_millis(900);
+        assert!(guard.allows_barge_in(&result("no", 0.1), after_tail));
+
+        assert_eq!(guard.stats().suppressed_barge_ins, 2);
+    }
+
+    #[test]
+    fn test_spoken_text_matching_can_be_disabled() {
+        let guard = EchoGuard::new(EchoGuardConfig {
+            match_spoken_text: false,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        guard.record_text("How can I help you today?");
+        guard.record_audio(now);
+        assert!(!guard.is_echo(&result("how can I help", 0.9), now));
+    }
+
+    #[test]
+    fn test_only_recent_utterances_are_kept() {
+        let guard = EchoGuard::new(EchoGuardConfig::default());
+        let now = Instant::now();
+        guard.record_text("first sentence");
+        for i in 0..MAX_SPOKEN_UTTERANCES {
+            guard.record_text(&format!("filler {i}"));
+        }
+        guard.record_audio(now);
+        assert!(!guard.is_echo(&result("first sentence", 0.9), now));
+        assert!(guard.is_echo(&result("filler 7", 0.9), now));
+    }
+}
@@ -0,0 +1,148 @@
+//! Configuration a session runs with
+//!
+//! [`SessionPipelineBuilder::dry_run`](super::SessionPipelineBuilder::dry_run)
+//! resolves it without connecting any provider, and
+//! [`Session::effective_config`](super::Session::effective_config) reports
+//! the one a running session was built from. Both are produced by the
+//! builder after its validation and feature-flag resolution, so a dry run
+//! shows what a real session with the same builder would use.
+//!
+//! API keys and custom header values are redacted.
+
+use serde::Serialize;
+
+use crate::config::FeatureFlags;
+use crate::core::stt::STTConfig;
+use crate::core::tts::TTSConfig;
+use crate::core::voice_manager::{
+    AdaptiveEndpointingConfig, FillerAudioConfig, TTSAudioQualityConfig,
+};
+
+use super::barge_in::BargeInMode;
+use super::barge_in_confirmation::BargeInConfirmationConfig;
+use super::consent::ConsentConfig;
+use super::echo_guard::EchoGuardConfig;
+use super::formatting::TranscriptFormattingConfig;
+use super::latency_budget::LatencyBudgetConfig;
+use super::retry_budget::RetryBudgetConfig;
+use super::transcript::TranscriptBufferConfig;
+use super::watchdog::PipelineWatchdogConfig;
+
+/// Placeholder for redacted secrets
+const REDACTED: &str = "[redacted]";
+
+/// Resolved configuration of a session
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveSessionConfig {
+    /// STT provider config of a voice session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt: Option<STTConfig>,
+    /// Secondary STT provider taking over when the primary fails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_failover: Option<STTConfig>,
+    /// Fast STT provider for routed turns
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_routing: Option<STTConfig>,
+    /// TTS provider config of a voice session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<TTSConfig>,
+    /// Provider of a realtime session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realtime_provider: Option<String>,
+    /// Voice used when the configured voice is not found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_voice_id: Option<String>,
+    /// Config hash TTS audio is cached under, when caching is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts_cache_key: Option<String>,
+    /// Adaptive endpointing, including one turned on by a feature flag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_endpointing: Option<AdaptiveEndpointingConfig>,
+    /// How caller speech interrupts TTS
+    pub barge_in: BargeInMode,
+    /// Speech required before barge-in interrupts TTS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barge_in_confirmation: Option<BargeInConfirmationConfig>,
+    /// Echo guard, including one turned on by a feature flag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub echo_guard: Option<EchoGuardConfig>,
+    /// Formatting applied to STT results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_formatting: Option<TranscriptFormattingConfig>,
+    /// Per-turn latency budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_budget: Option<LatencyBudgetConfig>,
+    /// Reconnect of providers that stop responding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchdog: Option<PipelineWatchdogConfig>,
+    /// Consent the caller must give before input audio is processed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consent: Option<ConsentConfig>,
+    /// Budget shared by the session's provider reconnects and retries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<RetryBudgetConfig>,
+    /// Check of the synthesized audio
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts_audio_quality: Option<TTSAudioQualityConfig>,
+    /// Filler played while the first TTS audio is slow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filler_audio: Option<FillerAudioConfig>,
+    /// Maximum characters of a system speak
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_speak_max_chars: Option<usize>,
+    /// Whether repeated partial speaks are dropped
+    pub dedupe_partials: bool,
+    /// Whether an LLM agent bridge answers user turns
+    pub agent_bridge: bool,
+    /// Whether audio levels are measured
+    pub audio_levels: bool,
+    /// Limits of the session transcript
+    pub transcript_buffer: TranscriptBufferConfig,
+    /// Feature flags resolved for the session
+    pub feature_flags: FeatureFlags,
+}
+
+/// An STT config with its secrets redacted
+pub(super) fn redacted_stt(config: &STTConfig) -> STTConfig {
+    let mut config = config.clone();
+    redact(&mut config.api_key);
+    config.custom_headers.values_mut().for_each(redact);
+    config
+}
+
+/// A TTS config with its secrets redacted
+pub(super) fn redacted_tts(config: &TTSConfig) -> TTSConfig {
+    let mut config = config.clone();
+    redact(&mut config.api_key);
+    config.custom_headers.values_mut().for_each(redact);
+    config
+}
+
+fn redact(secret: &mut String) {
+    if !secret.is_empty() {
+        *secret = REDACTED.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let mut stt = STTConfig {
+            api_key: "stt-secret".to_string(),
+            ..Default::default()
+        };
+        stt.custom_headers
+            .insert("X-Token".to_string(), "header-secret".to_string());
+        let redacted = redacted_stt(&stt);
+        assert_eq!(redacted.api_key, REDACTED);
+        assert_eq!(redacted.custom_headers["X-Token"], REDACTED);
+        assert_eq!(redacted.provider, stt.provider);
+
+        // Keys resolved at connect time stay empty
+        let tts = redacted_tts(&TTSConfig::default());
+        assert!(tts.api_key.is_empty());
+    }
+}
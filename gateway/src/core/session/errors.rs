@@ -0,0 +1,55 @@
+//! Error types for session pipeline operations
+
+use crate::core::{
+    agent_bridge::AgentBridgeError, realtime::RealtimeError, voice_manager::VoiceManagerError,
+};
+use crate::errors::provider_error::error_codes;
+
+/// Error types for session pipeline operations
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("Invalid session configuration: {0}")]
+    InvalidConfig(String),
+    #[error("Failed to create voice manager: {0}")]
+    CreateFailed(VoiceManagerError),
+    #[error("Failed to start voice manager: {0}")]
+    StartFailed(VoiceManagerError),
+    #[error("Failed to set up session callbacks: {0}")]
+    CallbackRegistration(VoiceManagerError),
+    #[error("Timeout waiting for voice providers to be ready")]
+    ReadyTimeout,
+    #[error(transparent)]
+    VoiceManager(#[from] VoiceManagerError),
+    #[error(transparent)]
+    AgentBridge(#[from] AgentBridgeError),
+    #[error("Realtime provider error: {0}")]
+    Realtime(#[from] RealtimeError),
+    #[error("Noise filter error: {0}")]
+    NoiseFilter(String),
+    #[error("Greeting failed: {0}")]
+    Greeting(String),
+    #[error("{0} is not supported by this session")]
+    Unsupported(&'static str),
+}
+
+impl SessionError {
+    /// Get the error code sent to clients with the sanitized message
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            SessionError::InvalidConfig(_) => error_codes::INVALID_CONFIG,
+            SessionError::CreateFailed(e)
+            | SessionError::StartFailed(e)
+            | SessionError::CallbackRegistration(e)
+            | SessionError::VoiceManager(e) => e.error_code(),
+            SessionError::ReadyTimeout => error_codes::PROVIDER_READY_TIMEOUT,
+            SessionError::AgentBridge(e) => e.error_code(),
+            SessionError::Realtime(_) => error_codes::REALTIME_PROVIDER_ERROR,
+            SessionError::NoiseFilter(_)
+            | SessionError::Greeting(_)
+            | SessionError::Unsupported(_) => error_codes::SESSION_ERROR,
+        }
+    }
+}
+
+/// Result type for session pipeline operations
+pub type SessionResult<T> = Result<T, SessionError>;
@@ -0,0 +1,309 @@
+//! Session events and the stream that delivers them
+
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+use super::audio_level::AudioLevel;
+use super::barge_in_confirmation::BargeInSuppressed;
+use super::consent::ConsentRecord;
+use super::latency_budget::LatencyBudgetExceeded;
+use super::watchdog::PipelineRecovered;
+use crate::config::GreetingSource;
+use crate::core::{
+    agent_bridge::AgentBridgeError,
+    realtime::{RealtimeAudioData, RealtimeError, TranscriptResult},
+    stt::{STTError, STTResult, STTVadEvent},
+    tts::{AudioData, TTSError, TTSTimingEvent},
+    voice_manager::{TTSAudioQualityWarning, TTSQueuePolicy, TurnDetectionDegraded},
+};
+
+/// Event produced by a running [`Session`](super::Session)
+#[derive(Debug)]
+pub enum SessionEvent {
+    /// STT result after speech-final processing, tagged with its user turn
+    Transcript(STTResult),
+    /// Voice activity event from the STT provider (e.g. speech started)
+    Vad(STTVadEvent),
+    /// Streaming error from the STT provider
+    SttError {
+        error: STTError,
+        /// User turn in progress when the error occurred
+        turn_id: Option<String>,
+    },
+    /// Synthesized (or `play_audio`) audio ready for output
+    Audio(AudioData),
+    /// Level of input or output audio, when audio levels are enabled
+    AudioLevel(AudioLevel),
+    /// Error from the TTS provider
+    TtsError {
+        error: TTSError,
+        /// Turn of the speech being synthesized
+        turn_id: Option<String>,
+    },
+    /// The configured TTS voice does not exist; the fallback voice is used
+    /// for the rest of the session
+    VoiceFallback {
+        /// Configured voice id
+        from_voice_id: String,
+        /// Fallback voice id now in use
+        to_voice_id: String,
+        /// Turn of the speech that is re-synthesized with the fallback voice
+        turn_id: Option<String>,
+    },
+    /// A `speak()` call hit the cap on pending TTS utterances
+    TtsQueueFull {
+        /// Policy that was applied
+        policy: TTSQueuePolicy,
+        /// Configured cap on pending utterances
+        max_pending: usize,
+        /// Text that was rejected (`reject`) or dropped (`drop_oldest`)
+        text: String,
+    },
+    /// A `system` priority speak stopped the current utterance and paused the queue
+    PreemptionStarted {
+        /// Text of the announcement
+        text: String,
+        /// Utterances paused until the announcement has played
+        paused: usize,
+    },
+    /// The announcement has played and the paused utterances were re-sent
+    PreemptionEnded {
+        /// Utterances re-sent to the TTS provider
+        resumed: usize,
+    },
+    /// The provider returned clipped, silent or inaudible audio for an utterance
+    TtsAudioQualityWarning {
+        /// What was measured and which thresholds failed
+        warning: TTSAudioQualityWarning,
+        /// Turn of the checked utterance
+        turn_id: Option<String>,
+    },
+    /// End of turn is decided by silence detection instead of the turn
+    /// detection model for the rest of the session
+    TurnDetectionDegraded(TurnDetectionDegraded),
+    /// A stage of a turn ran past its deadline in the latency budget
+    LatencyBudgetExceeded(LatencyBudgetExceeded),
+    /// The pipeline watchdog reconnected a provider that stopped responding
+    PipelineRecovered(PipelineRecovered),
+    /// First audio of an utterance, emitted ahead of its `Audio` event
+    SpeechStarted {
+        /// Turn the utterance belongs to
+        turn_id: String,
+        /// Unix timestamp in milliseconds
+        timestamp: u64,
+    },
+    /// All audio for a `speak()` call has been generated
+    SpeechComplete {
+        /// Unix timestamp in milliseconds
+        timestamp: u64,
+        /// Turn the utterance belonged to
+        turn_id: Option<String>,
+    },
+    /// Viseme and word timing of the utterance being synthesized, when the
+    /// TTS config enables visemes
+    TtsTiming {
+        /// Events sorted by offset from the start of the utterance's audio
+        events: Vec<TTSTimingEvent>,
+        /// Turn of the speech being synthesized
+        turn_id: Option<String>,
+    },
+    /// Caller speech was held back from interrupting TTS output because it
+    /// did not confirm barge-in
+    BargeInSuppressed(BargeInSuppressed),
+    /// Queued output audio was dropped (`interrupt()` or barge-in);
+    /// output sinks should flush anything they still buffer
+    AudioCleared,
+    /// Error raised while the agent bridge handled a turn
+    AgentError {
+        error: AgentBridgeError,
+        /// User turn the agent was answering
+        turn_id: Option<String>,
+    },
+    /// Transcript from a realtime provider
+    RealtimeTranscript(TranscriptResult),
+    /// Audio from a realtime provider
+    RealtimeAudio(RealtimeAudioData),
+    /// Error from a realtime provider
+    RealtimeError(RealtimeError),
+    /// The session greeting was queued for playback
+    GreetingPlayed {
+        /// What the greeting played
+        source: GreetingSource,
+        /// Unix timestamp in milliseconds
+        timestamp: u64,
+    },
+    /// Recording consent was granted, denied or timed out
+    Consent(ConsentRecord),
+}
+
+impl SessionEvent {
+    /// Whether this event carries output audio
+    fn is_audio(&self) -> bool {
+        matches!(self, Self::Audio(_) | Self::RealtimeAudio(_))
+    }
+}
+
+/// Event tagged with the clear generation it was emitted in
+type TaggedEvent = (u64, SessionEvent);
+
+/// Sending half of the session event channel
+///
+/// Every clear bumps the generation, so audio emitted before an
+/// `AudioCleared` event but not yet received is dropped by the stream.
+#[derive(Clone)]
+pub(super) struct EventEmitter {
+    tx: mpsc::Sender<TaggedEvent>,
+    generation: Arc<AtomicU64>,
+}
+
+impl EventEmitter {
+    /// Create an emitter and the stream it feeds
+    pub(super) fn channel(capacity: usize) -> (Self, SessionEventStream) {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let generation = Arc::new(AtomicU64::new(0));
+        (
+            Self {
+                tx,
+                generation: generation.clone(),
+            },
+            SessionEventStream { rx, generation },
+        )
+    }
+
+    /// Emit an event, waiting for room in the channel
+    ///
+    /// Events are dropped silently once the stream has been dropped.
+    pub(super) async fn emit(&self, event: SessionEvent) {
+        let generation = self.generation.load(Ordering::Acquire);
+        let _ = self.tx.send((generation, event)).await;
+    }
+
+    /// Drop pending audio and emit `AudioCleared`
+    pub(super) async fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.emit(SessionEvent::AudioCleared).await;
+    }
+}
+
+/// Stream of [`SessionEvent`]s for one session
+///
+/// Obtained once from [`Session::take_events`](super::Session::take_events).
+/// Ends when the session and its providers have been dropped.
+pub struct SessionEventStream {
+    rx: mpsc::Receiver<TaggedEvent>,
+    generation: Arc<AtomicU64>,
+}
+
+impl SessionEventStream {
+    /// Receive the next event
+    ///
+    /// # Returns
+    /// * `Option<SessionEvent>` - Next event, or `None` once the session is gone
+    pub async fn recv(&mut self) -> Option<SessionEvent> {
+        std::future::poll_fn(|cx| self.poll_event(cx)).await
+    }
+
+    fn poll_event(&mut self, cx: &mut Context<'_>) -> Poll<Option<SessionEvent>> {
+        loop {
+            match self.rx.poll_recv(cx) {
+                Poll::Ready(Some((generation, event))) => {
+                    // Audio queued before the latest clear is stale
+                    if event.is_audio() && generation != self.generation.load(Ordering::Acquire) {
+                        continue;
+                    }
+                    return Poll::Ready(Some(event));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl Stream for SessionEventStream {
+    type Item = SessionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_event(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(byte: u8) -> SessionEvent {
+        SessionEvent::Audio(AudioData {
+            data: vec![byte; 4],
+            sample_rate: 24000,
+            format: "linear16".to_string(),
+            duration_ms: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_events_delivered_in_order() {
+        let (emitter, mut events) = EventEmitter::channel(8);
+        emitter
+            .emit(SessionEvent::Transcript(STTResult::new(
+                "hello".to_string(),
+                true,
+                true,
+                0.9,
+            )))
+            .await;
+        emitter.emit(audio(1)).await;
+        emitter
+            .emit(SessionEvent::SpeechComplete {
+                timestamp: 42,
+                turn_id: None,
+            })
+            .await;
+        drop(emitter);
+
+        assert!(
+            matches!(events.recv().await, Some(SessionEvent::Transcript(r)) if r.transcript == "hello")
+        );
+        assert!(matches!(events.recv().await, Some(SessionEvent::Audio(_))));
+        assert!(matches!(
+            events.recv().await,
+            Some(SessionEvent::SpeechComplete { timestamp: 42, .. })
+        ));
+        assert!(events.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_clear_drops_audio_queued_before_it() {
+        let (emitter, mut events) = EventEmitter::channel(8);
+        emitter.emit(audio(1)).await;
+        emitter.emit(audio(2)).await;
+        emitter
+            .emit(SessionEvent::SpeechComplete {
+                timestamp: 1,
+                turn_id: None,
+            })
+            .await;
+        emitter.clear().await;
+        emitter.emit(audio(3)).await;
+        drop(emitter);
+
+        // Non-audio events survive the clear, stale audio does not
+        assert!(matches!(
+            events.recv().await,
+            Some(SessionEvent::SpeechComplete { .. })
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(SessionEvent::AudioCleared)
+        ));
+        match events.recv().await {
+            Some(SessionEvent::Audio(audio)) => assert_eq!(audio.data, vec![3; 4]),
+            other => panic!("expected fresh audio, got {other:?}"),
+        }
+        assert!(events.recv().await.is_none());
+    }
+}
@@ -0,0 +1,923 @@
+//! Transcript formatting: consistent casing, fillers and numbers
+//!
+//! Providers format transcripts differently: some punctuate and capitalize,
+//! others return lowercase chunks. The formatter runs on every STT result
+//! before the rest of the session sees it, so `stt_result` messages, the
+//! agent bridge and the session transcript (and the exports and summaries
+//! built from it) all carry the same text. It can:
+//!
+//! - restore sentence case: capitalize the first word of each sentence and,
+//!   in English, the pronoun "I"
+//! - drop filler words ("um", "uh") from a built-in or configured list for
+//!   the transcript's language
+//! - write spelled-out English numbers as digits ("twenty five thousand"
+//!   becomes "25,000", "four one five" becomes "415")
+//!
+//! Finals are formatted in full. Interims get the same formatting, except a
+//! number at their very end stays spelled out, since the caller may still be
+//! saying it: an interim never shows "20" for a final that reads "25".
+//! Sentences are tracked across finals, so a final continuing the previous
+//! final's sentence is not capitalized. Redaction placeholders are never
+//! changed and their spans move with the text. Word timings keep the
+//! provider's words.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::core::stt::{RedactedSpan, STTResult};
+
+/// Language assumed for results without a detected or configured language
+const DEFAULT_LANGUAGE: &str = "en";
+
+/// Built-in filler words, by primary language subtag
+const BUILTIN_FILLERS: &[(&str, &[&str])] = &[
+    ("de", &["äh", "ähm", "öh", "hm", "hmm"]),
+    (
+        "en",
+        &["um", "umm", "uh", "uhh", "uhm", "erm", "er", "hm", "hmm"],
+    ),
+    ("es", &["eh", "em", "emm", "mmm"]),
+    ("fr", &["euh", "heu", "hum"]),
+    ("it", &["ehm", "uhm", "mmm"]),
+    ("nl", &["eh", "ehm", "uh", "uhm"]),
+    ("pt", &["hã", "ahn", "hum"]),
+];
+
+/// Titles whose trailing period does not end a sentence
+const ABBREVIATIONS: &[&str] = &["mr", "mrs", "ms", "dr", "prof", "st", "jr", "sr", "vs"];
+
+/// Values from which digits are grouped with commas ("25,000" but "2024")
+const GROUPING_THRESHOLD: u64 = 10_000;
+
+/// Options of the transcript formatter
+///
+/// Every option is on unless turned off.
+///
+/// # Example JSON
+/// ```json
+/// {"sentence_case": true, "remove_fillers": true, "fillers": {"es": ["eh", "este"]}, "numbers": true, "min_number": 10}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct TranscriptFormattingConfig {
+    /// Capitalize the first word of each sentence and, in English, "I"
+    pub sentence_case: bool,
+    /// Drop filler words
+    pub remove_fillers: bool,
+    /// Filler words by language ("en", "es", ...), replacing the built-in
+    /// list of that language
+    pub fillers: BTreeMap<String, Vec<String>>,
+    /// Write spelled-out English numbers as digits
+    pub numbers: bool,
+    /// Smallest number written as digits; smaller ones stay spelled out.
+    /// Digit sequences ("four one five") are always written as digits.
+    pub min_number: u64,
+}
+
+impl Default for TranscriptFormattingConfig {
+    fn default() -> Self {
+        Self {
+            sentence_case: true,
+            remove_fillers: true,
+            fillers: BTreeMap::new(),
+            numbers: true,
+            min_number: 10,
+        }
+    }
+}
+
+impl TranscriptFormattingConfig {
+    /// Validate the filler lists
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        for (language, fillers) in &self.fillers {
+            if primary_language(language).is_empty() {
+                return Err(format!("fillers has an invalid language {language:?}"));
+            }
+            if let Some(filler) = fillers
+                .iter()
+                .find(|filler| filler.is_empty() || filler.contains(char::is_whitespace))
+            {
+                return Err(format!(
+                    "fillers for {language:?} must be single words (got {filler:?})"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats the STT results of one session
+pub(super) struct TranscriptFormatter {
+    config: TranscriptFormattingConfig,
+    /// Configured filler lists, lowercased and keyed by primary language
+    fillers: BTreeMap<String, Vec<String>>,
+    /// Language of results whose provider reports none
+    default_language: String,
+    /// Whether the next final starts a new sentence
+    sentence_start: AtomicBool,
+}
+
+impl TranscriptFormatter {
+    pub(super) fn new(config: TranscriptFormattingConfig, default_language: Option<&str>) -> Self {
+        let fillers = config
+            .fillers
+            .iter()
+            .map(|(language, fillers)| {
+                (
+                    primary_language(language),
+                    fillers.iter().map(|filler| filler.to_lowercase()).collect(),
+                )
+            })
+            .collect();
+        Self {
+            config,
+            fillers,
+            default_language: default_language
+                .map(primary_language)
+                .filter(|language| !language.is_empty())
+                .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string()),
+            sentence_start: AtomicBool::new(true),
+        }
+    }
+
+    /// Format `result` in place
+    ///
+    /// Finals move the sentence state on; interims are formatted against it
+    /// without changing it.
+    pub(super) fn format(&self, result: &mut STTResult) {
+        let language = result
+            .detected_language
+            .as_deref()
+            .map(primary_language)
+            .filter(|language| !language.is_empty())
+            .unwrap_or_else(|| self.default_language.clone());
+        let fillers: Vec<&str> = if !self.config.remove_fillers {
+            Vec::new()
+        } else if let Some(fillers) = self.fillers.get(&language) {
+            fillers.iter().map(String::as_str).collect()
+        } else {
+            builtin_fillers(&language).to_vec()
+        };
+        let options = FormatOptions {
+            sentence_case: self.config.sentence_case,
+            fillers: &fillers,
+            numbers: self.config.numbers && language == "en",
+            min_number: self.config.min_number,
+            english: language == "en",
+        };
+
+        let sentence_start = self.sentence_start.load(Ordering::Relaxed);
+        let Some(formatted) = format_text(
+            &result.transcript,
+            &result.redactions,
+            &options,
+            sentence_start,
+            result.is_final,
+        ) else {
+            return;
+        };
+        if result.is_final {
+            if result.is_speech_final {
+                self.sentence_start.store(true, Ordering::Relaxed);
+            } else if let Some(ends_sentence) = formatted.ends_sentence {
+                self.sentence_start.store(ends_sentence, Ordering::Relaxed);
+            }
+        }
+        result.transcript = formatted.text;
+        result.redactions = formatted.redactions;
+    }
+}
+
+/// What to apply to one transcript
+struct FormatOptions<'a> {
+    sentence_case: bool,
+    /// Lowercase filler words to drop; empty to keep them
+    fillers: &'a [&'a str],
+    numbers: bool,
+    min_number: u64,
+    /// Capitalize the pronoun "I"
+    english: bool,
+}
+
+/// A transcript after formatting
+#[derive(Debug, PartialEq)]
+struct Formatted {
+    text: String,
+    redactions: Vec<RedactedSpan>,
+    /// Whether the text ends a sentence, or `None` if it is empty
+    ends_sentence: Option<bool>,
+}
+
+/// A whitespace-separated word of the transcript
+#[derive(Debug)]
+struct Token {
+    text: String,
+    /// Byte offset of the word in the original transcript
+    offset: usize,
+    /// Overlaps a redaction span; never changed or removed
+    redacted: bool,
+}
+
+/// Format `text`, moving `redactions` with it
+///
+/// Returns `None` if a redaction span cannot be followed through the
+/// formatting, in which case the transcript is left as it is.
+fn format_text(
+    text: &str,
+    redactions: &[RedactedSpan],
+    options: &FormatOptions<'_>,
+    sentence_start: bool,
+    is_final: bool,
+) -> Option<Formatted> {
+    let mut tokens = tokenize(text, redactions);
+    if !options.fillers.is_empty() {
+        remove_fillers(&mut tokens, options.fillers);
+    }
+    if options.numbers {
+        format_numbers(&mut tokens, options.min_number, is_final);
+    }
+    if options.sentence_case {
+        apply_sentence_case(&mut tokens, sentence_start, options.english);
+    }
+
+    let mut formatted = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(tokens.len());
+    for token in &tokens {
+        if !formatted.is_empty() {
+            formatted.push(' ');
+        }
+        offsets.push(formatted.len());
+        formatted.push_str(&token.text);
+    }
+    let redactions = redactions
+        .iter()
+        .map(|span| {
+            let moved = |position: usize| {
+                tokens.iter().zip(&offsets).find_map(|(token, &offset)| {
+                    (token.redacted
+                        && position >= token.offset
+                        && position < token.offset + token.text.len())
+                    .then(|| offset + position - token.offset)
+                })
+            };
+            Some(RedactedSpan {
+                start: moved(span.start)?,
+                end: moved(span.end.checked_sub(1)?)? + 1,
+                entity_type: span.entity_type.clone(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Formatted {
+        text: formatted,
+        redactions,
+        ends_sentence: tokens.last().map(|token| ends_sentence(&token.text)),
+    })
+}
+
+fn tokenize(text: &str, redactions: &[RedactedSpan]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (index, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_whitespace(), start) {
+            (true, Some(offset)) => {
+                tokens.push(Token {
+                    text: text[offset..index].to_string(),
+                    offset,
+                    redacted: redactions
+                        .iter()
+                        .any(|span| span.start < index && span.end > offset),
+                });
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// Split a word into leading punctuation, the word itself and trailing
+/// punctuation
+fn split_word(word: &str) -> (&str, &str, &str) {
+    let Some(first) = word.find(char::is_alphanumeric) else {
+        return (word, "", "");
+    };
+    let last = word
+        .rfind(char::is_alphanumeric)
+        .map(|index| index + word[index..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(word.len());
+    (&word[..first], &word[first..last], &word[last..])
+}
+
+fn is_terminal(c: char) -> bool {
+    matches!(c, '.' | '?' | '!' | '…')
+}
+
+/// Whether a word ends its sentence
+fn ends_sentence(word: &str) -> bool {
+    let (_, core, suffix) = split_word(word);
+    let Some(terminal) = suffix
+        .trim_end_matches(['"', '\'', ')', '”', '’'])
+        .chars()
+        .last()
+    else {
+        return false;
+    };
+    is_terminal(terminal) && !(terminal == '.' && ABBREVIATIONS.contains(&&*core.to_lowercase()))
+}
+
+fn builtin_fillers(language: &str) -> &'static [&'static str] {
+    BUILTIN_FILLERS
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map_or(&[], |(_, fillers)| fillers)
+}
+
+/// Primary subtag of a language tag ("en-US" → "en")
+fn primary_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// Drop filler words, keeping the punctuation around them readable
+///
+/// A sentence end after a filler moves to the word before it ("okay um." →
+/// "okay."), and a filler set off by commas takes the commas with it
+/// ("I, uh, want" → "I want").
+fn remove_fillers(tokens: &mut Vec<Token>, fillers: &[&str]) {
+    let mut kept: Vec<Token> = Vec::with_capacity(tokens.len());
+    for token in tokens.drain(..) {
+        let (_, core, suffix) = split_word(&token.text);
+        if token.redacted || core.is_empty() || !fillers.contains(&&*core.to_lowercase()) {
+            kept.push(token);
+            continue;
+        }
+        let Some(previous) = kept.last_mut().filter(|previous| !previous.redacted) else {
+            continue;
+        };
+        let (_, _, previous_suffix) = split_word(&previous.text);
+        if let Some(terminal) = suffix.chars().find(|c| is_terminal(*c)) {
+            if previous_suffix.is_empty() {
+                previous.text.push(terminal);
+            } else if previous_suffix == "," {
+                previous.text.pop();
+                previous.text.push(terminal);
+            }
+        } else if suffix.starts_with(',') && previous_suffix == "," {
+            previous.text.pop();
+        }
+    }
+    *tokens = kept;
+}
+
+/// A spelled-out number word
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberWord {
+    /// "zero" to "nine"
+    Digit(u64),
+    /// "oh", a zero inside a digit sequence
+    Oh,
+    /// "ten" to "nineteen"
+    Teen(u64),
+    /// "twenty" to "ninety"
+    Tens(u64),
+    Hundred,
+    /// "thousand", "million" or "billion"
+    Scale(u64),
+    /// "and" after "hundred" or a scale
+    And,
+}
+
+fn number_word(word: &str) -> Option<NumberWord> {
+    const SMALL: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 8] = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    if let Some(value) = SMALL.iter().position(|small| *small == word) {
+        let value = value as u64;
+        return Some(if value < 10 {
+            NumberWord::Digit(value)
+        } else {
+            NumberWord::Teen(value)
+        });
+    }
+    if let Some(index) = TENS.iter().position(|tens| *tens == word) {
+        return Some(NumberWord::Tens(20 + 10 * index as u64));
+    }
+    match word {
+        "oh" => Some(NumberWord::Oh),
+        "hundred" => Some(NumberWord::Hundred),
+        "thousand" => Some(NumberWord::Scale(1_000)),
+        "million" => Some(NumberWord::Scale(1_000_000)),
+        "billion" => Some(NumberWord::Scale(1_000_000_000)),
+        _ => None,
+    }
+}
+
+/// Number words of a bare word, splitting hyphenated ones ("twenty-five")
+fn number_words(core: &str) -> Option<Vec<NumberWord>> {
+    let core = core.to_lowercase();
+    let words = core
+        .split('-')
+        .map(number_word)
+        .collect::<Option<Vec<_>>>()?;
+    (!words.contains(&NumberWord::Oh) || words.len() == 1).then_some(words)
+}
+
+/// Write runs of number words as digits
+///
+/// A run never crosses punctuation or a redaction. Interims keep a run
+/// that reaches their end spelled out, since more of it may follow.
+fn format_numbers(tokens: &mut Vec<Token>, min_number: u64, is_final: bool) {
+    let mut index = 0;
+    while index < tokens.len() {
+        let Some((end, words)) = number_run(tokens, index) else {
+            index += 1;
+            continue;
+        };
+        let open = !is_final
+            && (end == tokens.len()
+                || (end + 1 == tokens.len() && tokens[end].text.eq_ignore_ascii_case("and")));
+        let digits = if open {
+            None
+        } else {
+            spell_digits(&words, min_number)
+        };
+        let Some(digits) = digits else {
+            index = end;
+            continue;
+        };
+        let (prefix, _, _) = split_word(&tokens[index].text);
+        let (_, _, suffix) = split_word(&tokens[end - 1].text);
+        let text = format!("{prefix}{digits}{suffix}");
+        tokens[index].text = text;
+        tokens.drain(index + 1..end);
+        index += 1;
+    }
+}
+
+/// The run of number words starting at `start`: the index past its last
+/// token and its words
+fn number_run(tokens: &[Token], start: usize) -> Option<(usize, Vec<NumberWord>)> {
+    let first = &tokens[start];
+    if first.redacted {
+        return None;
+    }
+    let (_, core, suffix) = split_word(&first.text);
+    let mut words = number_words(core).filter(|words| words[0] != NumberWord::Oh)?;
+    let mut end = start + 1;
+    let mut closed = !suffix.is_empty();
+    while !closed && end < tokens.len() {
+        let token = &tokens[end];
+        let (prefix, core, suffix) = split_word(&token.text);
+        if token.redacted || !prefix.is_empty() {
+            break;
+        }
+        let next = if core.eq_ignore_ascii_case("and") {
+            // Only "hundred and five" or "thousand and five"
+            let follows_scale = matches!(
+                words.last(),
+                Some(NumberWord::Hundred | NumberWord::Scale(_))
+            );
+            let followed_by_number = tokens.get(end + 1).is_some_and(|next| {
+                let (prefix, core, _) = split_word(&next.text);
+                !next.redacted && prefix.is_empty() && number_words(core).is_some()
+            });
+            if !suffix.is_empty() || !follows_scale || !followed_by_number {
+                break;
+            }
+            vec![NumberWord::And]
+        } else {
+            match number_words(core) {
+                Some(next) => next,
+                None => break,
+            }
+        };
+        words.extend(next);
+        closed = !suffix.is_empty();
+        end += 1;
+    }
+    Some((end, words))
+}
+
+/// Digits for a run of number words, or `None` to keep it spelled out
+fn spell_digits(words: &[NumberWord], min_number: u64) -> Option<String> {
+    if words.len() >= 3
+        && words
+            .iter()
+            .all(|word| matches!(word, NumberWord::Digit(_) | NumberWord::Oh))
+    {
+        return Some(
+            words
+                .iter()
+                .map(|word| match word {
+                    NumberWord::Digit(digit) => char::from(b'0' + *digit as u8),
+                    _ => '0',
+                })
+                .collect(),
+        );
+    }
+    if let Some(value) = cardinal(words) {
+        return (value >= min_number).then(|| group_digits(value));
+    }
+    // Years said in pairs: "nineteen ninety five", "twenty twenty four"
+    (1..words.len()).find_map(|split| {
+        let century = cardinal(&words[..split])?;
+        let year = cardinal(&words[split..])?;
+        ((10..100).contains(&century) && (10..100).contains(&year) && century * 100 >= min_number)
+            .then(|| format!("{century}{year}"))
+    })
+}
+
+/// Value of a well-formed cardinal number
+fn cardinal(words: &[NumberWord]) -> Option<u64> {
+    if words == [NumberWord::Digit(0)] {
+        return Some(0);
+    }
+    let mut total = 0u64;
+    let mut hundreds = 0u64;
+    let mut small = 0u64;
+    let (mut has_tens, mut has_ones, mut has_hundred) = (false, false, false);
+    let mut last_scale = u64::MAX;
+    let mut after_scale = false;
+    for (index, word) in words.iter().enumerate() {
+        match *word {
+            NumberWord::Digit(0) | NumberWord::Oh => return None,
+            NumberWord::Digit(digit) => {
+                if has_ones {
+                    return None;
+                }
+                small += digit;
+                has_ones = true;
+            }
+            NumberWord::Teen(value) => {
+                if has_ones || has_tens {
+                    return None;
+                }
+                small += value;
+                has_ones = true;
+                has_tens = true;
+            }
+            NumberWord::Tens(value) => {
+                if has_ones || has_tens {
+                    return None;
+                }
+                small += value;
+                has_tens = true;
+            }
+            NumberWord::Hundred => {
+                // "fifteen hundred" only as the leading group
+                if has_hundred || small == 0 || (small >= 10 && last_scale != u64::MAX) {
+                    return None;
+                }
+                hundreds = small * 100;
+                small = 0;
+                has_hundred = true;
+                has_tens = false;
+                has_ones = false;
+            }
+            NumberWord::Scale(scale) => {
+                let group = hundreds + small;
+                if group == 0 || scale >= last_scale {
+                    return None;
+                }
+                total = total.checked_add(group.checked_mul(scale)?)?;
+                last_scale = scale;
+                hundreds = 0;
+                small = 0;
+                has_hundred = false;
+                has_tens = false;
+                has_ones = false;
+            }
+            NumberWord::And => {
+                let after_hundred = has_hundred && small == 0;
+                if !(after_hundred || after_scale) || index + 1 == words.len() {
+                    return None;
+                }
+            }
+        }
+        after_scale = matches!(word, NumberWord::Scale(_));
+    }
+    total.checked_add(hundreds + small)
+}
+
+/// Digits of `value`, grouped by thousands from [`GROUPING_THRESHOLD`] up
+fn group_digits(value: u64) -> String {
+    let digits = value.to_string();
+    if value < GROUPING_THRESHOLD {
+        return digits;
+    }
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Capitalize the first word of each sentence and, in English, "I"
+fn apply_sentence_case(tokens: &mut [Token], sentence_start: bool, english: bool) {
+    let mut start = sentence_start;
+    for token in tokens {
+        if !token.redacted {
+            let (prefix, core, _) = split_word(&token.text);
+            let pronoun = english
+                && matches!(
+                    &*core.to_lowercase().replace('’', "'"),
+                    "i" | "i'm" | "i'll" | "i've" | "i'd"
+                );
+            if (start || pronoun)
+                && let Some(first) = core.chars().next()
+                && first.is_lowercase()
+            {
+                let at = prefix.len();
+                let upper: String = first.to_uppercase().collect();
+                token.text.replace_range(at..at + first.len_utf8(), &upper);
+            }
+        }
+        start = ends_sentence(&token.text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(config: TranscriptFormattingConfig) -> TranscriptFormatter {
+        TranscriptFormatter::new(config, Some("en-US"))
+    }
+
+    fn format_final(formatter: &TranscriptFormatter, transcript: &str) -> String {
+        let mut result = STTResult::new(transcript.to_string(), true, false, 0.9);
+        formatter.format(&mut result);
+        result.transcript
+    }
+
+    fn sentence_case_only() -> TranscriptFormattingConfig {
+        TranscriptFormattingConfig {
+            remove_fillers: false,
+            numbers: false,
+            ..Default::default()
+        }
+    }
+
+    fn fillers_only() -> TranscriptFormattingConfig {
+        TranscriptFormattingConfig {
+            sentence_case: false,
+            numbers: false,
+            ..Default::default()
+        }
+    }
+
+    fn numbers_only() -> TranscriptFormattingConfig {
+        TranscriptFormattingConfig {
+            sentence_case: false,
+            remove_fillers: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sentence_case() {
+        let cases = [
+            ("hello how are you", "Hello how are you"),
+            ("yes. i think so", "Yes. I think so"),
+            ("i'm here. are you? great!", "I'm here. Are you? Great!"),
+            ("call dr. smith tomorrow", "Call dr. smith tomorrow"),
+            ("Already Formatted.", "Already Formatted."),
+            ("\"quoted\" text", "\"Quoted\" text"),
+            ("über cool", "Über cool"),
+            ("  extra   spaces ", "Extra spaces"),
+            ("", ""),
+        ];
+        for (before, after) in cases {
+            let formatter = only(sentence_case_only());
+            assert_eq!(format_final(&formatter, before), after, "{before:?}");
+        }
+    }
+
+    #[test]
+    fn test_sentence_case_follows_finals() {
+        let formatter = only(sentence_case_only());
+        assert_eq!(format_final(&formatter, "so i was"), "So I was");
+        // Continues the previous final's sentence
+        assert_eq!(format_final(&formatter, "thinking. then"), "thinking. Then");
+        assert_eq!(format_final(&formatter, "we left."), "we left.");
+        assert_eq!(format_final(&formatter, "next one"), "Next one");
+
+        // The end of the utterance ends the sentence, punctuated or not
+        let mut result = STTResult::new("no punctuation".to_string(), true, true, 0.9);
+        formatter.format(&mut result);
+        assert_eq!(result.transcript, "no punctuation");
+        assert_eq!(format_final(&formatter, "new turn"), "New turn");
+    }
+
+    #[test]
+    fn test_remove_fillers() {
+        let cases = [
+            ("um I want to go", "I want to go"),
+            ("Um, I want to go", "I want to go"),
+            ("I, uh, want to go", "I want to go"),
+            ("I want uh to go", "I want to go"),
+            ("that's it um.", "that's it."),
+            ("that's it, uh.", "that's it."),
+            ("UHM what", "what"),
+            ("umbrella and hummus", "umbrella and hummus"),
+            ("um uh", ""),
+        ];
+        for (before, after) in cases {
+            let formatter = only(fillers_only());
+            assert_eq!(format_final(&formatter, before), after, "{before:?}");
+        }
+    }
+
+    #[test]
+    fn test_fillers_per_language() {
+        let formatter = only(fillers_only());
+        let mut result = STTResult::new("äh ich bin eh hier".to_string(), true, false, 0.9)
+            .with_language("de-DE");
+        formatter.format(&mut result);
+        assert_eq!(result.transcript, "ich bin eh hier");
+
+        let mut result =
+            STTResult::new("eh yo este quiero".to_string(), true, false, 0.9).with_language("es");
+        formatter.format(&mut result);
+        assert_eq!(result.transcript, "yo este quiero");
+
+        // Configured lists replace the built-in one of their language
+        let mut config = fillers_only();
+        config
+            .fillers
+            .insert("ES-mx".to_string(), vec!["Este".to_string()]);
+        let formatter = TranscriptFormatter::new(config, Some("es"));
+        assert_eq!(
+            format_final(&formatter, "eh yo este quiero"),
+            "eh yo quiero"
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        let cases = [
+            ("I have five cats", "I have five cats"),
+            ("I have fifteen cats", "I have 15 cats"),
+            ("about twenty-five people", "about 25 people"),
+            ("twenty five thousand dollars", "25,000 dollars"),
+            ("two thousand and five", "2005"),
+            ("one hundred and one dalmatians", "101 dalmatians"),
+            ("three hundred forty two.", "342."),
+            ("fifteen hundred miles", "1500 miles"),
+            ("call four one five five five five", "call 415555"),
+            ("room two oh five", "room 205"),
+            ("in nineteen ninety five", "in 1995"),
+            ("twenty twenty four", "2024"),
+            ("twelve million three hundred thousand", "12,300,000"),
+            ("one, two, three", "one, two, three"),
+            ("forty and fifty", "40 and 50"),
+            ("oh twenty", "oh 20"),
+            ("one two", "one two"),
+        ];
+        for (before, after) in cases {
+            let formatter = only(numbers_only());
+            assert_eq!(format_final(&formatter, before), after, "{before:?}");
+        }
+    }
+
+    #[test]
+    fn test_numbers_are_english_only() {
+        let formatter = only(numbers_only());
+        let mut result =
+            STTResult::new("twenty five".to_string(), true, false, 0.9).with_language("fr");
+        formatter.format(&mut result);
+        assert_eq!(result.transcript, "twenty five");
+    }
+
+    #[test]
+    fn test_all_options() {
+        let formatter = only(TranscriptFormattingConfig::default());
+        assert_eq!(
+            format_final(
+                &formatter,
+                "um, i need uh twenty five hundred dollars. uh thanks"
+            ),
+            "I need 2500 dollars. Thanks"
+        );
+    }
+
+    #[test]
+    fn test_interims_do_not_jump() {
+        let formatter = only(TranscriptFormattingConfig::default());
+        let interims = [
+            ("um i have", "I have"),
+            ("um i have twenty", "I have twenty"),
+            ("um i have twenty five", "I have twenty five"),
+            (
+                "um i have twenty five hundred and",
+                "I have twenty five hundred and",
+            ),
+            (
+                "um i have twenty five hundred and ten",
+                "I have twenty five hundred and ten",
+            ),
+            (
+                "um i have twenty five hundred and ten uh cats",
+                "I have 2510 cats",
+            ),
+        ];
+        for (before, after) in interims {
+            let mut result = STTResult::new(before.to_string(), false, false, 0.5);
+            formatter.format(&mut result);
+            assert_eq!(result.transcript, after, "{before:?}");
+        }
+        // Interims do not move the sentence on
+        let mut result = STTResult::new("done.".to_string(), false, false, 0.5);
+        formatter.format(&mut result);
+        assert_eq!(
+            format_final(&formatter, "um i have twenty five hundred and ten"),
+            "I have 2510"
+        );
+    }
+
+    #[test]
+    fn test_redactions_move_with_the_text() {
+        let formatter = only(TranscriptFormattingConfig::default());
+        let transcript = "um my name is [PII] and uh my number is [PII]";
+        let spans: Vec<_> = transcript
+            .match_indices("[PII]")
+            .map(|(start, placeholder)| RedactedSpan {
+                start,
+                end: start + placeholder.len(),
+                entity_type: "NAME".to_string(),
+            })
+            .collect();
+        let mut result =
+            STTResult::new(transcript.to_string(), true, false, 0.9).with_redactions(spans);
+        formatter.format(&mut result);
+
+        assert_eq!(result.transcript, "My name is [PII] and my number is [PII]");
+        assert_eq!(result.redactions.len(), 2);
+        for span in &result.redactions {
+            assert_eq!(&result.transcript[span.start..span.end], "[PII]");
+        }
+
+        // Placeholders are never changed, even at the start of a sentence
+        let mut result = STTResult::new("[pii] called".to_string(), true, false, 0.9)
+            .with_redactions(vec![RedactedSpan {
+                start: 0,
+                end: 5,
+                entity_type: "NAME".to_string(),
+            }]);
+        formatter.format(&mut result);
+        assert_eq!(result.transcript, "[pii] called");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(TranscriptFormattingConfig::default().validate().is_ok());
+        let mut config = TranscriptFormattingConfig::default();
+        config
+            .fillers
+            .insert("en".to_string(), vec!["you know".to_string()]);
+        assert!(config.validate().unwrap_err().contains("single words"));
+        let mut config = TranscriptFormattingConfig::default();
+        config.fillers.insert(String::new(), vec!["um".to_string()]);
+        assert!(config.validate().unwrap_err().contains("language"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Greeting playback for sessions that speak first
+
+use std::path::Path;
+use tracing::{debug, info};
+
+use super::errors::{SessionError, SessionResult};
+use super::events::SessionEvent;
+use super::pipeline::Session;
+use crate::config::{GreetingConfig, GreetingSource, greeting_asset_path};
+
+/// Load a greeting asset as 16-bit little-endian mono PCM
+///
+/// The asset must be a 16-bit integer mono WAV file recorded at
+/// `sample_rate`, the rate the session outputs audio at.
+///
+/// # Arguments
+/// * `assets_dir` - Directory containing greeting assets
+/// * `name` - Asset name (`.wav` is appended when it has no extension)
+/// * `sample_rate` - Required sample rate of the asset
+pub async fn load_greeting_asset(
+    assets_dir: &Path,
+    name: &str,
+    sample_rate: u32,
+) -> SessionResult<Vec<u8>> {
+    load_audio_asset(assets_dir, name, sample_rate)
+        .await
+        .map_err(SessionError::Greeting)
+}
+
+/// Load an asset from the greeting assets directory as 16-bit little-endian
+/// mono PCM
+///
+/// Shared by greetings and filler audio; the requirements are those of
+/// [`load_greeting_asset`].
+pub async fn load_audio_asset(
+    assets_dir: &Path,
+    name: &str,
+    sample_rate: u32,
+) -> Result<Vec<u8>, String> {
+    let path = greeting_asset_path(assets_dir, name);
+
+    tokio::task::spawn_blocking(move || {
+        let reader = hound::WavReader::open(&path)
+            .map_err(|e| format!("failed to open asset {}: {e}", path.display()))?;
+
+        let spec = reader.spec();
+        if spec.channels != 1
+            || spec.bits_per_sample != 16
+            || spec.sample_format != hound::SampleFormat::Int
+        {
+            return Err(format!(
+                "asset {} must be 16-bit mono PCM (got {} channel(s), {}-bit {:?})",
+                path.display(),
+                spec.channels,
+                spec.bits_per_sample,
+                spec.sample_format
+            ));
+        }
+        if spec.sample_rate != sample_rate {
+            return Err(format!(
+                "asset {} is {}Hz but the session outputs {}Hz",
+                path.display(),
+                spec.sample_rate,
+                sample_rate
+            ));
+        }
+
+        let mut pcm = Vec::with_capacity(reader.len() as usize * 2);
+        for sample in reader.into_samples::<i16>() {
+            let sample =
+                sample.map_err(|e| format!("failed to read asset {}: {e}", path.display()))?;
+            pcm.extend_from_slice(&sample.to_le_bytes());
+        }
+        Ok(pcm)
+    })
+    .await
+    .map_err(|e| format!("asset loader failed: {e}"))?
+}
+
+impl Session {
+    /// Play the session greeting
+    ///
+    /// Waits for the configured delay, then speaks the greeting text through
+    /// TTS or plays the greeting asset. The caller can barge in on either.
+    /// Emits `SessionEvent::GreetingPlayed` once the greeting is queued.
+    ///
+    /// # Arguments
+    /// * `greeting` - Greeting to play
+    /// * `assets_dir` - Directory containing greeting assets (required for assets)
+    ///
+    /// # Returns
+    /// * `Ok(Some(source))` - What was played
+    /// * `Ok(None)` - The greeting is empty, nothing was played
+    pub async fn play_greeting(
+        &self,
+        greeting: &GreetingConfig,
+        assets_dir: Option<&Path>,
+    ) -> SessionResult<Option<GreetingSource>> {
+        let Some(source) = greeting.source() else {
+            debug!("Greeting is empty - nothing to play");
+            return Ok(None);
+        };
+
+        let delay = greeting.delay();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
+        match &source {
+            GreetingSource::Text(text) => {
+                self.speak_with_interruption(text, true, true).await?;
+            }
+            GreetingSource::Asset(name) => {
+                let assets_dir = assets_dir.ok_or_else(|| {
+                    SessionError::Greeting(format!(
+                        "greeting asset '{name}' requires a greeting assets directory"
+                    ))
+                })?;
+                let sample_rate = self.output_sample_rate();
+                let pcm = load_greeting_asset(assets_dir, name, sample_rate).await?;
+                self.play_audio(pcm, sample_rate, true).await?;
+            }
+        }
+
+        info!(source = source.kind(), "Greeting queued for playback");
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.emitter
+            .emit(SessionEvent::GreetingPlayed {
+                source: source.clone(),
+                timestamp,
+            })
+            .await;
+        Ok(Some(source))
+    }
+}
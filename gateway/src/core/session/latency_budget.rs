@@ -0,0 +1,689 @@
+//! Latency budget from the end of the caller's speech to the first reply audio
+//!
+//! A [`LatencyBudgetConfig`] splits the budget of each turn into deadlines
+//! for its stages:
+//!
+//! 1. `stt_final`: from the caller's last transcribed words to the
+//!    `is_speech_final` result
+//! 2. `agent`: from the speech-final result to the first reply text sent to
+//!    TTS (the agent bridge's LLM, or the client's orchestrator)
+//! 3. `tts_first_byte`: from that text to the first synthesized audio
+//!
+//! A stage that runs past its deadline is logged and counted in
+//! `waav_latency_budget_exceeded_total`, reported as
+//! [`SessionEvent::LatencyBudgetExceeded`], and mitigated, as configured
+//! with [`LatencyBudgetAction`]s:
+//!
+//! - a slow STT stage routes the next turns to the fast STT provider, when
+//!   one is configured (`stt_routing`)
+//! - a slow agent stage ends the agent bridge's reply after its first sentence
+//! - a slow TTS stage starts the filler audio without waiting for its threshold
+//!
+//! The agent and TTS stages are checked at their deadline, so the mitigation
+//! starts while the caller is still waiting. The STT stage is checked when
+//! the speech-final result arrives, since a faster provider only helps later
+//! turns. A turn whose first audio arrives after the whole budget is reported
+//! with the `total` stage, which has no mitigation.
+//!
+//! New caller speech restarts the turn, and cleared output audio ends it.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::warn;
+
+use super::events::{EventEmitter, SessionEvent};
+use crate::core::{
+    agent_bridge::AgentBridge,
+    stt::{STTResult, STTRoute},
+    voice_manager::VoiceManager,
+};
+use crate::metrics::global_metrics;
+
+/// Default budget from the end of the caller's speech to the first reply audio (ms)
+pub const DEFAULT_LATENCY_BUDGET_MS: u64 = 1200;
+
+/// Default deadline of the STT stage (ms)
+pub const DEFAULT_STT_FINAL_DEADLINE_MS: u64 = 300;
+
+/// Default deadline of the agent stage (ms)
+pub const DEFAULT_AGENT_DEADLINE_MS: u64 = 600;
+
+/// Default deadline of the TTS stage (ms)
+pub const DEFAULT_TTS_FIRST_BYTE_DEADLINE_MS: u64 = 300;
+
+/// Stage of a turn with its own deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum LatencyStage {
+    /// End of the caller's speech to the speech-final result
+    SttFinal,
+    /// Speech-final result to the first reply text
+    Agent,
+    /// First reply text to the first synthesized audio
+    TtsFirstByte,
+    /// End of the caller's speech to the first synthesized audio
+    Total,
+}
+
+impl LatencyStage {
+    /// The stage's metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SttFinal => "stt_final",
+            Self::Agent => "agent",
+            Self::TtsFirstByte => "tts_first_byte",
+            Self::Total => "total",
+        }
+    }
+}
+
+/// What happens when a stage exceeds its deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum LatencyBudgetAction {
+    /// Log a warning and count it in the process metrics
+    Log,
+    /// Emit a `latency_budget.exceeded` event to the client
+    Event,
+    /// Apply the stage's mitigation
+    Mitigate,
+}
+
+/// Per-turn latency budget and the deadlines of its stages
+///
+/// # Example JSON
+/// ```json
+/// {"total_ms": 1200, "stt_final_ms": 300, "agent_ms": 600,
+///  "tts_first_byte_ms": 300, "actions": ["log", "event", "mitigate"]}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct LatencyBudgetConfig {
+    /// Budget from the end of the caller's speech to the first reply audio (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 1200))]
+    pub total_ms: u64,
+    /// Deadline of the STT stage (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 300))]
+    pub stt_final_ms: u64,
+    /// Deadline of the agent stage (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 600))]
+    pub agent_ms: u64,
+    /// Deadline of the TTS stage (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 300))]
+    pub tts_first_byte_ms: u64,
+    /// What to do when a stage exceeds its deadline (default: log and event)
+    pub actions: Vec<LatencyBudgetAction>,
+}
+
+impl Default for LatencyBudgetConfig {
+    fn default() -> Self {
+        Self {
+            total_ms: DEFAULT_LATENCY_BUDGET_MS,
+            stt_final_ms: DEFAULT_STT_FINAL_DEADLINE_MS,
+            agent_ms: DEFAULT_AGENT_DEADLINE_MS,
+            tts_first_byte_ms: DEFAULT_TTS_FIRST_BYTE_DEADLINE_MS,
+            actions: vec![LatencyBudgetAction::Log, LatencyBudgetAction::Event],
+        }
+    }
+}
+
+impl LatencyBudgetConfig {
+    /// Validate the budget and its deadlines
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("total_ms", self.total_ms),
+            ("stt_final_ms", self.stt_final_ms),
+            ("agent_ms", self.agent_ms),
+            ("tts_first_byte_ms", self.tts_first_byte_ms),
+        ] {
+            if value == 0 {
+                return Err(format!("{name} must be greater than 0"));
+            }
+        }
+        let stages = self.stt_final_ms + self.agent_ms + self.tts_first_byte_ms;
+        if stages > self.total_ms {
+            return Err(format!(
+                "stage deadlines add up to {stages}ms, more than total_ms ({})",
+                self.total_ms
+            ));
+        }
+        if self.actions.is_empty() {
+            return Err("actions must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Deadline of `stage`
+    pub fn deadline_ms(&self, stage: LatencyStage) -> u64 {
+        match stage {
+            LatencyStage::SttFinal => self.stt_final_ms,
+            LatencyStage::Agent => self.agent_ms,
+            LatencyStage::TtsFirstByte => self.tts_first_byte_ms,
+            LatencyStage::Total => self.total_ms,
+        }
+    }
+
+    fn has(&self, action: LatencyBudgetAction) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+/// A stage of a turn exceeded its deadline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencyBudgetExceeded {
+    /// User turn the reply answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// Stage that exceeded its deadline
+    pub stage: LatencyStage,
+    /// Time the stage had taken when it was checked (ms)
+    pub elapsed_ms: u64,
+    /// Deadline of the stage (ms)
+    pub deadline_ms: u64,
+    /// Whether a mitigation was applied
+    pub mitigated: bool,
+}
+
+/// Recovers a turn whose stage is over its deadline
+pub(super) trait LatencyMitigator: Send + Sync {
+    /// Apply the mitigation of `stage`
+    ///
+    /// Returns whether there was anything to apply.
+    fn mitigate(&self, stage: LatencyStage) -> bool;
+}
+
+/// Mitigations of a voice session
+pub(super) struct PipelineMitigator {
+    voice_manager: Weak<VoiceManager>,
+    agent_bridge: Option<Arc<AgentBridge>>,
+}
+
+impl PipelineMitigator {
+    pub(super) fn new(
+        voice_manager: &Arc<VoiceManager>,
+        agent_bridge: Option<Arc<AgentBridge>>,
+    ) -> Self {
+        Self {
+            voice_manager: Arc::downgrade(voice_manager),
+            agent_bridge,
+        }
+    }
+}
+
+impl LatencyMitigator for PipelineMitigator {
+    fn mitigate(&self, stage: LatencyStage) -> bool {
+        match stage {
+            LatencyStage::SttFinal => self
+                .voice_manager
+                .upgrade()
+                .and_then(|voice_manager| voice_manager.stt_router())
+                .is_some_and(|router| router.prefer_fast() == STTRoute::Fast),
+            LatencyStage::Agent => self
+                .agent_bridge
+                .as_ref()
+                .is_some_and(|bridge| bridge.truncate_reply()),
+            LatencyStage::TtsFirstByte => self
+                .voice_manager
+                .upgrade()
+                .is_some_and(|voice_manager| voice_manager.play_filler_now()),
+            LatencyStage::Total => false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct BudgetState {
+    /// Bumped whenever the running stage changes, so stale deadline checks
+    /// do nothing
+    generation: u64,
+    /// User turn being answered, once its speech-final result arrived
+    turn_id: Option<String>,
+    /// End of the caller's speech
+    turn_start: Option<Instant>,
+    /// Stage in progress and when it started
+    stage: Option<(LatencyStage, Instant)>,
+    /// Stages of the turn already reported
+    exceeded: Vec<LatencyStage>,
+    /// Deadline check of the running stage
+    timer: Option<JoinHandle<()>>,
+}
+
+impl BudgetState {
+    /// Stop the running stage and its deadline check
+    fn end_stage(&mut self) {
+        self.generation += 1;
+        self.stage = None;
+        if let Some(timer) = self.timer.take() {
+            timer.abort();
+        }
+    }
+
+    /// Forget the turn
+    fn reset(&mut self) {
+        self.end_stage();
+        self.turn_id = None;
+        self.turn_start = None;
+        self.exceeded.clear();
+    }
+}
+
+/// Tracks the stages of each turn of a session against their deadlines
+pub(super) struct LatencyBudget {
+    config: LatencyBudgetConfig,
+    mitigator: Arc<dyn LatencyMitigator>,
+    emitter: EventEmitter,
+    state: Mutex<BudgetState>,
+}
+
+impl LatencyBudget {
+    pub(super) fn new(
+        config: LatencyBudgetConfig,
+        mitigator: Arc<dyn LatencyMitigator>,
+        emitter: EventEmitter,
+    ) -> Self {
+        Self {
+            config,
+            mitigator,
+            emitter,
+            state: Mutex::new(BudgetState::default()),
+        }
+    }
+
+    /// Track a delivered STT result, tagged with its turn
+    ///
+    /// Transcripts (re)start the STT stage; the speech-final result ends it
+    /// and starts the agent stage.
+    pub(super) async fn on_transcript(self: &Arc<Self>, result: &STTResult) {
+        let now = Instant::now();
+        let exceeded = {
+            let mut state = self.state.lock();
+            let in_stt_stage = matches!(state.stage, Some((LatencyStage::SttFinal, _)));
+            if !result.is_speech_final {
+                if !result.transcript.trim().is_empty() {
+                    state.reset();
+                    state.turn_start = Some(now);
+                    state.stage = Some((LatencyStage::SttFinal, now));
+                }
+                return;
+            }
+            // A forced speech final after the turn already ended
+            if !in_stt_stage && result.transcript.trim().is_empty() {
+                return;
+            }
+            if !in_stt_stage {
+                state.reset();
+                state.turn_start = Some(now);
+                state.stage = Some((LatencyStage::SttFinal, now));
+            }
+            state.turn_id = result.turn_id.clone();
+            let exceeded = self.finish_stage(&mut state, now);
+            self.start_stage(&mut state, LatencyStage::Agent, now);
+            exceeded
+        };
+        self.report(exceeded).await;
+    }
+
+    /// Track text sent to the TTS provider, which ends the agent stage
+    pub(super) async fn on_reply_text(self: &Arc<Self>) {
+        let now = Instant::now();
+        let exceeded = {
+            let mut state = self.state.lock();
+            if !matches!(state.stage, Some((LatencyStage::Agent, _))) {
+                return;
+            }
+            let exceeded = self.finish_stage(&mut state, now);
+            self.start_stage(&mut state, LatencyStage::TtsFirstByte, now);
+            exceeded
+        };
+        self.report(exceeded).await;
+    }
+
+    /// Track synthesized audio, which ends the turn
+    ///
+    /// Audio produced while the provider is still accepting the reply text
+    /// ends the agent stage as well.
+    pub(super) async fn on_audio(self: &Arc<Self>) {
+        let now = Instant::now();
+        let exceeded = {
+            let mut state = self.state.lock();
+            let mut exceeded = match state.stage {
+                Some((LatencyStage::Agent, _)) => {
+                    let exceeded = self.finish_stage(&mut state, now);
+                    state.stage = Some((LatencyStage::TtsFirstByte, now));
+                    exceeded
+                }
+                Some((LatencyStage::TtsFirstByte, _)) => Vec::new(),
+                _ => return,
+            };
+            exceeded.extend(self.finish_stage(&mut state, now));
+            if let Some(turn_start) = state.turn_start {
+                let elapsed_ms = now.duration_since(turn_start).as_millis() as u64;
+                if elapsed_ms > self.config.total_ms {
+                    exceeded.push(self.exceeded(&state, LatencyStage::Total, elapsed_ms));
+                }
+            }
+            state.reset();
+            exceeded
+        };
+        self.report(exceeded).await;
+    }
+
+    /// Forget the turn after its output audio was cleared
+    ///
+    /// A turn still being transcribed is kept: clearing the previous reply
+    /// is part of the caller speaking.
+    pub(super) fn on_clear(&self) {
+        let mut state = self.state.lock();
+        if !matches!(state.stage, Some((LatencyStage::SttFinal, _))) {
+            state.reset();
+        }
+    }
+
+    /// Start `stage`, checking its deadline when it is reached
+    fn start_stage(self: &Arc<Self>, state: &mut BudgetState, stage: LatencyStage, now: Instant) {
+        state.end_stage();
+        state.stage = Some((stage, now));
+        let generation = state.generation;
+        let deadline = now + Duration::from_millis(self.config.deadline_ms(stage));
+        let budget = Arc::downgrade(self);
+        state.timer = Some(tokio::spawn(async move {
+            tokio::time::sleep_until(deadline).await;
+            if let Some(budget) = budget.upgrade() {
+                budget.on_deadline(generation).await;
+            }
+        }));
+    }
+
+    /// The running stage reached its deadline
+    async fn on_deadline(&self, generation: u64) {
+        let exceeded = {
+            let mut state = self.state.lock();
+            let Some((stage, started)) = state.stage else {
+                return;
+            };
+            if state.generation != generation || state.exceeded.contains(&stage) {
+                return;
+            }
+            state.exceeded.push(stage);
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            vec![self.exceeded(&state, stage, elapsed_ms)]
+        };
+        self.report(exceeded).await;
+    }
+
+    /// End the running stage, reporting it when it ran past its deadline
+    /// without being reported yet
+    fn finish_stage(&self, state: &mut BudgetState, now: Instant) -> Vec<LatencyBudgetExceeded> {
+        let Some((stage, started)) = state.stage else {
+            return Vec::new();
+        };
+        state.end_stage();
+        let elapsed_ms = now.duration_since(started).as_millis() as u64;
+        if elapsed_ms <= self.config.deadline_ms(stage) || state.exceeded.contains(&stage) {
+            return Vec::new();
+        }
+        state.exceeded.push(stage);
+        vec![self.exceeded(state, stage, elapsed_ms)]
+    }
+
+    fn exceeded(
+        &self,
+        state: &BudgetState,
+        stage: LatencyStage,
+        elapsed_ms: u64,
+    ) -> LatencyBudgetExceeded {
+        LatencyBudgetExceeded {
+            turn_id: state.turn_id.clone(),
+            stage,
+            elapsed_ms,
+            deadline_ms: self.config.deadline_ms(stage),
+            mitigated: false,
+        }
+    }
+
+    /// Apply the configured actions to stages over their deadline
+    async fn report(&self, exceeded: Vec<LatencyBudgetExceeded>) {
+        for mut exceeded in exceeded {
+            exceeded.mitigated = self.config.has(LatencyBudgetAction::Mitigate)
+                && self.mitigator.mitigate(exceeded.stage);
+            if self.config.has(LatencyBudgetAction::Log) {
+                warn!(
+                    turn_id = exceeded.turn_id.as_deref().unwrap_or_default(),
+                    stage = exceeded.stage.as_str(),
+                    elapsed_ms = exceeded.elapsed_ms,
+                    deadline_ms = exceeded.deadline_ms,
+                    mitigated = exceeded.mitigated,
+                    "Turn exceeded its latency budget"
+                );
+                global_metrics().inc_counter(
+                    "waav_latency_budget_exceeded_total",
+                    "Turn stages that exceeded their latency budget",
+                    &[("stage", exceeded.stage.as_str())],
+                );
+            }
+            if self.config.has(LatencyBudgetAction::Event) {
+                self.emitter
+                    .emit(SessionEvent::LatencyBudgetExceeded(exceeded))
+                    .await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session::SessionEventStream;
+    use futures::FutureExt;
+
+    /// Deadline of every stage in the tests (ms)
+    const DEADLINE_MS: u64 = 100;
+
+    /// Delay of a stage that keeps its deadline
+    const FAST: Duration = Duration::from_millis(10);
+
+    /// Delay of a stage that blows its deadline
+    const SLOW: Duration = Duration::from_millis(250);
+
+    #[derive(Default)]
+    struct RecordingMitigator {
+        stages: Mutex<Vec<LatencyStage>>,
+    }
+
+    impl LatencyMitigator for RecordingMitigator {
+        fn mitigate(&self, stage: LatencyStage) -> bool {
+            self.stages.lock().push(stage);
+            true
+        }
+    }
+
+    fn config(actions: Vec<LatencyBudgetAction>) -> LatencyBudgetConfig {
+        LatencyBudgetConfig {
+            total_ms: 4 * DEADLINE_MS,
+            stt_final_ms: DEADLINE_MS,
+            agent_ms: DEADLINE_MS,
+            tts_first_byte_ms: DEADLINE_MS,
+            actions,
+        }
+    }
+
+    fn new_budget(
+        actions: Vec<LatencyBudgetAction>,
+    ) -> (
+        Arc<LatencyBudget>,
+        Arc<RecordingMitigator>,
+        SessionEventStream,
+    ) {
+        let mitigator = Arc::new(RecordingMitigator::default());
+        let (emitter, events) = EventEmitter::channel(16);
+        let budget = Arc::new(LatencyBudget::new(
+            config(actions),
+            mitigator.clone(),
+            emitter,
+        ));
+        (budget, mitigator, events)
+    }
+
+    fn transcript(text: &str, is_speech_final: bool) -> STTResult {
+        let mut result = STTResult::new(text.to_string(), true, is_speech_final, 0.9);
+        result.turn_id = Some("turn-1".to_string());
+        result
+    }
+
+    /// Run one turn with scripted delays before the speech-final result, the
+    /// reply text and the first audio
+    async fn run_turn(budget: &Arc<LatencyBudget>, stt: Duration, agent: Duration, tts: Duration) {
+        budget.on_transcript(&transcript("what's my", false)).await;
+        tokio::time::sleep(stt).await;
+        budget
+            .on_transcript(&transcript("what's my balance", true))
+            .await;
+        tokio::time::sleep(agent).await;
+        budget.on_reply_text().await;
+        tokio::time::sleep(tts).await;
+        budget.on_audio().await;
+    }
+
+    fn exceeded_stages(events: &mut SessionEventStream) -> Vec<LatencyBudgetExceeded> {
+        let mut exceeded = Vec::new();
+        while let Some(Some(event)) = events.recv().now_or_never() {
+            if let SessionEvent::LatencyBudgetExceeded(event) = event {
+                exceeded.push(event);
+            }
+        }
+        exceeded
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(LatencyBudgetConfig::default().validate().is_ok());
+        let config: LatencyBudgetConfig = serde_json::from_str(r#"{"agent_ms": 500}"#).unwrap();
+        assert_eq!(config.agent_ms, 500);
+        assert_eq!(config.total_ms, DEFAULT_LATENCY_BUDGET_MS);
+
+        for config in [
+            LatencyBudgetConfig {
+                stt_final_ms: 0,
+                ..Default::default()
+            },
+            LatencyBudgetConfig {
+                agent_ms: 1000,
+                ..Default::default()
+            },
+            LatencyBudgetConfig {
+                actions: Vec::new(),
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_turn_within_budget_is_not_reported() {
+        let (budget, mitigator, mut events) = new_budget(vec![
+            LatencyBudgetAction::Event,
+            LatencyBudgetAction::Mitigate,
+        ]);
+        run_turn(&budget, FAST, FAST, FAST).await;
+        tokio::time::sleep(SLOW).await;
+
+        assert!(mitigator.stages.lock().is_empty());
+        assert!(exceeded_stages(&mut events).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_the_slow_stage_is_mitigated() {
+        for (slow, delays) in [
+            (LatencyStage::SttFinal, [SLOW, FAST, FAST]),
+            (LatencyStage::Agent, [FAST, SLOW, FAST]),
+            (LatencyStage::TtsFirstByte, [FAST, FAST, SLOW]),
+        ] {
+            let (budget, mitigator, mut events) = new_budget(vec![
+                LatencyBudgetAction::Event,
+                LatencyBudgetAction::Mitigate,
+            ]);
+            run_turn(&budget, delays[0], delays[1], delays[2]).await;
+
+            assert_eq!(*mitigator.stages.lock(), vec![slow]);
+            let exceeded = exceeded_stages(&mut events);
+            assert_eq!(exceeded.len(), 1, "{exceeded:?}");
+            assert_eq!(exceeded[0].stage, slow);
+            assert_eq!(exceeded[0].turn_id.as_deref(), Some("turn-1"));
+            assert_eq!(exceeded[0].deadline_ms, DEADLINE_MS);
+            assert!(exceeded[0].elapsed_ms >= DEADLINE_MS);
+            assert!(exceeded[0].mitigated);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_tts_is_mitigated_before_audio_arrives() {
+        let (budget, mitigator, _events) = new_budget(vec![LatencyBudgetAction::Mitigate]);
+        budget.on_transcript(&transcript("hello", true)).await;
+        budget.on_reply_text().await;
+
+        tokio::time::sleep(SLOW).await;
+        assert_eq!(*mitigator.stages.lock(), vec![LatencyStage::TtsFirstByte]);
+
+        // Arriving late does not mitigate the stage again
+        budget.on_audio().await;
+        assert_eq!(mitigator.stages.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_whole_budget_is_reported_without_mitigation() {
+        let (budget, mitigator, mut events) = new_budget(vec![
+            LatencyBudgetAction::Event,
+            LatencyBudgetAction::Mitigate,
+        ]);
+        // A speech final slow enough to push the whole turn past the budget
+        budget.on_transcript(&transcript("what's my", false)).await;
+        tokio::time::sleep(SLOW + SLOW).await;
+        budget
+            .on_transcript(&transcript("what's my balance", true))
+            .await;
+        tokio::time::sleep(FAST).await;
+        budget.on_reply_text().await;
+        budget.on_audio().await;
+
+        let exceeded = exceeded_stages(&mut events);
+        let stages: Vec<LatencyStage> = exceeded.iter().map(|exceeded| exceeded.stage).collect();
+        assert_eq!(stages, vec![LatencyStage::SttFinal, LatencyStage::Total]);
+        assert!(!exceeded[1].mitigated);
+        assert_eq!(*mitigator.stages.lock(), vec![LatencyStage::SttFinal]);
+    }
+
+    #[tokio::test]
+    async fn test_new_speech_and_clear_cancel_the_deadline() {
+        let (budget, mitigator, mut events) = new_budget(vec![
+            LatencyBudgetAction::Event,
+            LatencyBudgetAction::Mitigate,
+        ]);
+        budget.on_transcript(&transcript("hello", true)).await;
+        // The caller keeps talking before the agent answers
+        budget
+            .on_transcript(&transcript("are you there", false))
+            .await;
+        tokio::time::sleep(FAST).await;
+        budget.on_clear();
+        budget
+            .on_transcript(&transcript("are you there", true))
+            .await;
+        budget.on_clear();
+        tokio::time::sleep(SLOW).await;
+
+        assert!(mitigator.stages.lock().is_empty());
+        assert!(exceeded_stages(&mut events).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! # Session Pipeline
+//!
+//! Composes everything a voice conversation needs into one runnable
+//! [`Session`]: STT and TTS providers (or a single realtime provider), turn
+//! detection, the TTS cache, noise filtering and the optional LLM agent
+//! bridge. This is the pipeline behind the `/ws` endpoint without the
+//! WebSocket transport, so applications can embed WaaV as a library.
+//!
+//! - Input: [`Session::push_audio`], [`Session::speak`], [`Session::interrupt`]
+//! - Output: a [`SessionEventStream`] of [`SessionEvent`]s (transcripts,
+//!   synthesized audio, completions, clears and errors)
+//!
+//! [`Session::usage`] reports the STT audio, TTS text and output audio the
+//! session has used, for billing at teardown.
+//!
+//! [`SessionPipelineBuilder::dry_run`] validates a configuration and returns
+//! the [`EffectiveSessionConfig`] a session would run with, without
+//! connecting any provider.
+//!
+//! [`Session::transcript`] exports what the user and the assistant said,
+//! within the limits of the session's [`TranscriptBufferConfig`].
+//! [`diarization`] labels its entries `bot` or `caller` after the call from
+//! the [`Session::speech_activity`] the session recorded.
+//!
+//! Transcripts, speech and errors carry a turn ID (see [`turns`]), so replies
+//! can be matched to the user turn they answer.
+//!
+//! [`SessionPipelineBuilder::latency_budget`] checks each turn against
+//! per-stage deadlines (see [`latency_budget`]).
+//! [`SessionPipelineBuilder::watchdog`] reconnects providers that stop
+//! responding mid-session (see [`watchdog`]).
+//! [`SessionPipelineBuilder::consent`] keeps input audio from the providers
+//! until the caller consents to the call being processed (see [`consent`]).
+//! [`SessionPipelineBuilder::retry_budget`] bounds the provider reconnects
+//! and retries of the whole session (see [`retry_budget`]).
+//!
+//! Audio emitted before an interruption but not yet read from the stream is
+//! dropped, so consumers never play stale speech after `AudioCleared`.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use futures::StreamExt;
+//! use waav_gateway::core::session::{SessionEvent, SessionPipelineBuilder};
+//! use waav_gateway::core::stt::STTConfig;
+//! use waav_gateway::core::tts::TTSConfig;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let session = SessionPipelineBuilder::new()
+//!         .stt(STTConfig {
+//!             provider: "deepgram".to_string(),
+//!             api_key: "your-stt-api-key".to_string(),
+//!             ..Default::default()
+//!         })
+//!         .tts(TTSConfig {
+//!             provider: "deepgram".to_string(),
+//!             api_key: "your-tts-api-key".to_string(),
+//!             voice_id: Some("aura-luna-en".to_string()),
+//!             ..Default::default()
+//!         })
+//!         .build()
+//!         .await?;
+//!
+//!     let mut events = session.take_events().expect("events are taken once");
+//!     tokio::spawn(async move {
+//!         while let Some(event) = events.next().await {
+//!             match event {
+//!                 SessionEvent::Transcript(result) if result.is_final => {
+//!                     println!("User said: {}", result.transcript);
+//!                 }
+//!                 SessionEvent::Audio(audio) => {
+//!                     // Send audio.data to a speaker, phone line, etc.
+//!                     let _ = audio.data.len();
+//!                 }
+//!                 SessionEvent::AudioCleared => {
+//!                     // Drop anything still buffered for playback
+//!                 }
+//!                 _ => {}
+//!             }
+//!         }
+//!     });
+//!
+//!     session.speak("Hello! How can I help?", true).await?;
+//!     session.push_audio(vec![0u8; 3200].into()).await?;
+//!
+//!     // Stop the reply, e.g. when the user presses a button
+//!     session.interrupt().await?;
+//!
+//!     session.close().await?;
+//!     Ok(())
+//! }
+//! ```
+
+pub mod audio_duration;
+pub mod audio_level;
+pub mod barge_in;
+pub mod barge_in_confirmation;
+pub mod builder;
+pub mod consent;
+pub mod diarization;
+pub mod echo_guard;
+pub mod effective;
+pub mod errors;
+pub mod events;
+pub mod formatting;
+pub mod greeting;
+pub mod latency_budget;
+pub mod pipeline;
+pub mod retry_budget;
+pub mod transcript;
+pub mod turns;
+pub mod usage;
+pub mod watchdog;
+
+pub use audio_duration::{container_duration_ms, container_sample_rate};
+pub use audio_level::{AudioDirection, AudioLevel};
+pub use barge_in::BargeInMode;
+pub use barge_in_confirmation::{
+    BargeInConfirmationConfig, BargeInSuppressed, BargeInSuppressionReason,
+};
+pub use builder::{DEFAULT_EVENT_BUFFER, DEFAULT_READY_TIMEOUT, SessionPipelineBuilder};
+pub use consent::{ConsentConfig, ConsentRecord, ConsentSource, ConsentState, PendingAudioPolicy};
+pub use diarization::{
+    Speaker, SpeakerSegment, SpeechActivity, SpeechInterval, annotate_speakers, diarize,
+};
+pub use echo_guard::{EchoGuardConfig, EchoGuardStats};
+pub use effective::EffectiveSessionConfig;
+pub use errors::{SessionError, SessionResult};
+pub use events::{SessionEvent, SessionEventStream};
+pub use formatting::TranscriptFormattingConfig;
+pub use greeting::{load_audio_asset, load_greeting_asset};
+pub use latency_budget::{
+    LatencyBudgetAction, LatencyBudgetConfig, LatencyBudgetExceeded, LatencyStage,
+};
+pub use pipeline::Session;
+pub use retry_budget::{
+    RetryBudget, RetryBudgetConfig, RetryBudgetStats, RetryCounts, RetryPriority,
+};
+pub use transcript::{
+    LanguageSegment, LanguageTalkTime, SPOKEN_MS_PER_WORD, TranscriptBufferConfig,
+    TranscriptBufferStats, TranscriptEntry, TranscriptOverflowPolicy, language_segments,
+    language_talk_time,
+};
+pub use usage::{ProviderModel, SessionUsage, TtsUtterance, TurnSTTUsage};
+pub use watchdog::{PipelineRecovered, PipelineWatchdogConfig, WatchdogStage, WatchdogStats};
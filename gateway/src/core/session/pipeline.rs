@@ -0,0 +1,689 @@
+//! Runnable voice session built by `SessionPipelineBuilder`
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::debug;
+
+use super::audio_level::LevelMeter;
+use super::consent::{ConsentGate, ConsentRecord, ConsentSource};
+use super::diarization::{SpeechActivity, SpeechActivityRecorder};
+use super::echo_guard::{EchoGuard, EchoGuardStats};
+use super::effective::EffectiveSessionConfig;
+use super::errors::{SessionError, SessionResult};
+use super::events::{EventEmitter, SessionEvent, SessionEventStream};
+use super::retry_budget::{RetryBudget, RetryBudgetStats};
+use super::transcript::{
+    LanguageTalkTime, TranscriptBuffer, TranscriptBufferStats, TranscriptEntry,
+};
+use super::turns::TurnTracker;
+use super::usage::{SessionUsage, UsageMeter, now_ms};
+use super::watchdog::{PipelineWatchdog, WatchdogStats};
+#[cfg(feature = "chaos")]
+use crate::core::chaos::ChaosStats;
+use crate::core::{
+    agent_bridge::AgentBridge,
+    realtime::BoxedRealtime,
+    stt::UtteranceKind,
+    voice_manager::{
+        FillerStats, SpeakPriority, TTSQueueStats, TextDedupStats, TurnDetectionStats, VoiceManager,
+    },
+};
+
+/// Sample rate of realtime provider audio (PCM16 mono, both directions)
+pub(super) const REALTIME_SAMPLE_RATE: u32 = 24000;
+
+/// Providers driving a session
+pub(super) enum Backend {
+    /// Separate STT and TTS providers coordinated by a `VoiceManager`
+    Voice(Arc<VoiceManager>),
+    /// A single audio-to-audio realtime provider
+    Realtime(tokio::sync::Mutex<BoxedRealtime>),
+}
+
+/// A running voice session
+///
+/// Owns the providers, turn detection, optional agent bridge and noise filter
+/// for one conversation. Input goes in through [`push_audio`](Self::push_audio)
+/// and [`speak`](Self::speak); everything the session produces (transcripts,
+/// audio, completions, errors) comes out of the stream returned by
+/// [`take_events`](Self::take_events).
+///
+/// Created with [`SessionPipelineBuilder`](super::SessionPipelineBuilder).
+pub struct Session {
+    backend: Backend,
+    agent_bridge: Option<Arc<AgentBridge>>,
+    echo_guard: Option<Arc<EchoGuard>>,
+    watchdog: Option<Arc<PipelineWatchdog>>,
+    /// Holds input audio back until the caller consents, when configured
+    consent: Option<Arc<ConsentGate>>,
+    /// Budget shared by the session's provider reconnects, when configured
+    retry_budget: Option<Arc<RetryBudget>>,
+    pub(super) emitter: EventEmitter,
+    events: Mutex<Option<SessionEventStream>>,
+    noise_filter: bool,
+    input_sample_rate: u32,
+    /// Meter for input audio, when audio levels are enabled
+    input_level: Option<LevelMeter>,
+    usage: Arc<UsageMeter>,
+    turns: Arc<TurnTracker>,
+    transcript: Arc<TranscriptBuffer>,
+    /// Speech timeline for the post-call diarization pass, when enabled
+    speech_activity: Option<Arc<SpeechActivityRecorder>>,
+    /// Configuration the session was built with
+    effective_config: EffectiveSessionConfig,
+}
+
+impl Session {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        backend: Backend,
+        agent_bridge: Option<Arc<AgentBridge>>,
+        echo_guard: Option<Arc<EchoGuard>>,
+        watchdog: Option<Arc<PipelineWatchdog>>,
+        consent: Option<Arc<ConsentGate>>,
+        retry_budget: Option<Arc<RetryBudget>>,
+        emitter: EventEmitter,
+        events: SessionEventStream,
+        noise_filter: bool,
+        input_sample_rate: u32,
+        input_level: Option<LevelMeter>,
+        usage: Arc<UsageMeter>,
+        turns: Arc<TurnTracker>,
+        transcript: Arc<TranscriptBuffer>,
+        speech_activity: Option<Arc<SpeechActivityRecorder>>,
+        effective_config: EffectiveSessionConfig,
+    ) -> Self {
+        Self {
+            backend,
+            agent_bridge,
+            echo_guard,
+            watchdog,
+            consent,
+            retry_budget,
+            emitter,
+            events: Mutex::new(Some(events)),
+            noise_filter,
+            input_sample_rate,
+            input_level,
+            usage,
+            turns,
+            transcript,
+            speech_activity,
+            effective_config,
+        }
+    }
+
+    /// Take the session's event stream
+    ///
+    /// The stream can be taken once; later calls return `None`. Events are
+    /// buffered until it is taken, and providers wait once the buffer is full,
+    /// so embedders should start consuming right after `build()`.
+    pub fn take_events(&self) -> Option<SessionEventStream> {
+        self.events.lock().take()
+    }
+
+    /// Feed input audio to the session
+    ///
+    /// Audio must match the configured input format (the STT config for voice
+    /// sessions, 24kHz PCM16 for realtime). When the noise filter is enabled
+    /// the audio is denoised first. With audio levels enabled, the level of
+    /// the audio as received is emitted about every 100ms. Audio pushed while
+    /// the session is [held](Self::hold) is dropped, and audio pushed before
+    /// the caller [consented](Self::set_consent) is held back or dropped.
+    pub async fn push_audio(&self, audio: Bytes) -> SessionResult<()> {
+        if self.usage.is_on_hold() {
+            return Ok(());
+        }
+        if let Some(consent) = &self.consent
+            && !consent.admit(&audio)
+        {
+            return Ok(());
+        }
+        self.process_audio(audio).await
+    }
+
+    /// Send input audio that passed the hold and consent checks on to the provider
+    async fn process_audio(&self, audio: Bytes) -> SessionResult<()> {
+        if let Some(meter) = &self.input_level
+            && let Some(level) = meter.measure(&audio, self.input_sample_rate, Instant::now())
+        {
+            self.emitter.emit(SessionEvent::AudioLevel(level)).await;
+        }
+        if let Some(activity) = &self.speech_activity {
+            activity.record_input(&audio, self.input_sample_rate, now_ms());
+        }
+
+        let audio = if self.noise_filter {
+            crate::utils::noise_filter::reduce_noise_async(audio, self.input_sample_rate)
+                .await
+                .map(Bytes::from)
+                .map_err(|e| SessionError::NoiseFilter(e.to_string()))?
+        } else {
+            audio
+        };
+
+        self.usage.add_stt_audio(audio.len());
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.on_input_audio(&audio);
+        }
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.receive_audio(audio).await?,
+            Backend::Realtime(realtime) => realtime.lock().await.send_audio(audio).await?,
+        }
+        Ok(())
+    }
+
+    /// Grant or deny the caller's recording consent
+    ///
+    /// Only the first decision counts, whether it comes from here or from
+    /// the consent timeout. It is emitted as `SessionEvent::Consent`; after a
+    /// grant, the audio held while consent was pending is sent on before any
+    /// audio pushed later.
+    ///
+    /// # Arguments
+    /// * `granted` - Whether the caller consented
+    /// * `source` - What reported the decision
+    /// * `participant` - Participant who decided, when known
+    ///
+    /// # Returns
+    /// * `SessionResult<Option<ConsentRecord>>` - The decision, or `None` when
+    ///   the session has no consent gate or consent was already decided
+    pub async fn set_consent(
+        &self,
+        granted: bool,
+        source: ConsentSource,
+        participant: Option<String>,
+    ) -> SessionResult<Option<ConsentRecord>> {
+        let Some(consent) = &self.consent else {
+            return Ok(None);
+        };
+        let Some(record) = consent.decide(granted, source, participant) else {
+            return Ok(None);
+        };
+        self.emitter
+            .emit(SessionEvent::Consent(record.clone()))
+            .await;
+        while let Some(audio) = consent.next_held() {
+            self.process_audio(audio).await?;
+        }
+        Ok(Some(record))
+    }
+
+    /// The caller's recording consent
+    ///
+    /// # Returns
+    /// * `Option<ConsentRecord>` - Consent and how it was decided, or `None`
+    ///   when the session was built without a consent gate
+    pub fn consent(&self) -> Option<ConsentRecord> {
+        self.consent.as_ref().map(|consent| consent.record())
+    }
+
+    /// Whether input audio may be processed: the session has no consent gate,
+    /// or the caller consented
+    pub fn has_consent(&self) -> bool {
+        self.consent
+            .as_ref()
+            .is_none_or(|consent| consent.is_granted())
+    }
+
+    /// Speak text
+    ///
+    /// For realtime sessions the text is sent as a user message and a
+    /// response is requested; `flush` is ignored.
+    pub async fn speak(&self, text: &str, flush: bool) -> SessionResult<()> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => {
+                self.speak_voice(
+                    voice_manager,
+                    text,
+                    flush,
+                    None,
+                    None,
+                    SpeakPriority::Normal,
+                )
+                .await?;
+            }
+            Backend::Realtime(realtime) => {
+                let mut realtime = realtime.lock().await;
+                realtime.send_text(text).await?;
+                realtime.create_response().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Speak text with interruption control
+    ///
+    /// When `allow_interruption` is false, [`interrupt`](Self::interrupt) and
+    /// barge-in are ignored until the audio has played out.
+    pub async fn speak_with_interruption(
+        &self,
+        text: &str,
+        flush: bool,
+        allow_interruption: bool,
+    ) -> SessionResult<()> {
+        self.speak_in_turn(text, flush, allow_interruption, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Speak text as part of a turn
+    ///
+    /// Like [`speak_with_interruption`](Self::speak_with_interruption); the
+    /// speech's `SpeechStarted`, `SpeechComplete` and error events carry
+    /// `turn_id`, or the ID of the latest user turn when it is `None`.
+    /// Realtime sessions do not tag events.
+    ///
+    /// # Returns
+    /// * `SessionResult<Option<String>>` - Turn ID the speech was tagged with
+    pub async fn speak_in_turn(
+        &self,
+        text: &str,
+        flush: bool,
+        allow_interruption: bool,
+        turn_id: Option<&str>,
+    ) -> SessionResult<Option<String>> {
+        self.speak_with_priority(
+            text,
+            flush,
+            allow_interruption,
+            turn_id,
+            SpeakPriority::Normal,
+        )
+        .await
+    }
+
+    /// Speak text as part of a turn with a priority
+    ///
+    /// Like [`speak_in_turn`](Self::speak_in_turn). `high` speech is exempt
+    /// from the pending TTS queue cap. `system` speech is an announcement: it
+    /// pre-empts the current utterance, ignores `allow_interruption` and
+    /// [`interrupt`](Self::interrupt) until it has played, and then the paused
+    /// speech resumes. `SessionEvent::PreemptionStarted` and
+    /// `SessionEvent::PreemptionEnded` bracket the pre-emption. Realtime
+    /// sessions only accept `normal` speech.
+    ///
+    /// # Returns
+    /// * `SessionResult<Option<String>>` - Turn ID the speech was tagged with
+    pub async fn speak_with_priority(
+        &self,
+        text: &str,
+        flush: bool,
+        allow_interruption: bool,
+        turn_id: Option<&str>,
+        priority: SpeakPriority,
+    ) -> SessionResult<Option<String>> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => self
+                .speak_voice(
+                    voice_manager,
+                    text,
+                    flush,
+                    Some(allow_interruption),
+                    turn_id,
+                    priority,
+                )
+                .await
+                .map(Some),
+            Backend::Realtime(_) if priority != SpeakPriority::Normal => {
+                Err(SessionError::Unsupported("speak priority"))
+            }
+            Backend::Realtime(_) => {
+                self.speak(text, flush).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn speak_voice(
+        &self,
+        voice_manager: &VoiceManager,
+        text: &str,
+        flush: bool,
+        allow_interruption: Option<bool>,
+        turn_id: Option<&str>,
+        priority: SpeakPriority,
+    ) -> SessionResult<String> {
+        // Queued before the provider is called, as it may produce audio at once
+        let announcement = priority == SpeakPriority::System;
+        let (turn_id, queued) = if announcement {
+            (self.turns.begin_announcement(turn_id), true)
+        } else {
+            self.turns.begin_speech(turn_id, flush)
+        };
+        let result = match allow_interruption {
+            Some(allow_interruption) => {
+                voice_manager
+                    .speak_with_priority(text, flush, allow_interruption, priority)
+                    .await
+            }
+            None => voice_manager.speak(text, flush).await,
+        };
+        if let Err(e) = result {
+            if announcement {
+                self.turns.cancel_announcement();
+            } else if queued {
+                self.turns.cancel_speech();
+            }
+            return Err(e.into());
+        }
+        self.usage.add_tts_text(text);
+        Ok(turn_id)
+    }
+
+    /// Play pre-synthesized 16-bit mono PCM through the audio output path
+    ///
+    /// Only supported by voice sessions.
+    pub async fn play_audio(
+        &self,
+        pcm: Vec<u8>,
+        sample_rate: u32,
+        allow_interruption: bool,
+    ) -> SessionResult<()> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => {
+                voice_manager
+                    .play_audio(pcm, sample_rate, allow_interruption)
+                    .await?
+            }
+            Backend::Realtime(_) => return Err(SessionError::Unsupported("play_audio")),
+        }
+        Ok(())
+    }
+
+    /// Sample rate of the audio pushed into the session
+    pub fn input_sample_rate(&self) -> u32 {
+        self.input_sample_rate
+    }
+
+    /// Sample rate of the audio the session outputs
+    pub fn output_sample_rate(&self) -> u32 {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.output_sample_rate().unwrap_or(24000),
+            Backend::Realtime(_) => REALTIME_SAMPLE_RATE,
+        }
+    }
+
+    /// Check whether non-interruptible audio is currently playing
+    pub async fn is_interruption_blocked(&self) -> bool {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.is_interruption_blocked().await,
+            Backend::Realtime(_) => false,
+        }
+    }
+
+    /// Stop the current reply
+    ///
+    /// Cancels any in-flight agent reply, clears queued TTS and emits
+    /// `SessionEvent::AudioCleared`. Ignored while non-interruptible audio is
+    /// playing.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - Output was cleared
+    /// * `Ok(false)` - Ignored because interruption is blocked
+    pub async fn interrupt(&self) -> SessionResult<bool> {
+        if self.is_interruption_blocked().await {
+            debug!("Interrupt ignored - non-interruptible audio playing");
+            return Ok(false);
+        }
+
+        // Stop any LLM reply still streaming so it does not refill the TTS queue
+        if let Some(bridge) = &self.agent_bridge {
+            bridge.cancel();
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.stop();
+        }
+
+        match &self.backend {
+            // Emits AudioCleared through the voice manager's audio clear callback
+            Backend::Voice(voice_manager) => voice_manager.clear_tts().await?,
+            Backend::Realtime(realtime) => {
+                realtime.lock().await.cancel_response().await?;
+                self.emitter.clear().await;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Check whether the session's providers are connected and ready
+    pub async fn is_ready(&self) -> bool {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.is_ready().await,
+            Backend::Realtime(realtime) => realtime.lock().await.is_ready(),
+        }
+    }
+
+    /// Get the pending TTS queue depth of a voice session
+    ///
+    /// # Returns
+    /// * `Option<TTSQueueStats>` - Queue stats, or `None` for realtime sessions
+    pub fn tts_queue_stats(&self) -> Option<TTSQueueStats> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => Some(voice_manager.tts_queue_stats()),
+            Backend::Realtime(_) => None,
+        }
+    }
+
+    /// Get how much resent partial text was dropped or corrected
+    ///
+    /// # Returns
+    /// * `Option<TextDedupStats>` - Dedup counters, or `None` without `dedupe_partials`
+    pub fn text_dedup_stats(&self) -> Option<TextDedupStats> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.text_dedup_stats(),
+            Backend::Realtime(_) => None,
+        }
+    }
+
+    /// Get how often filler audio played while TTS audio was slow
+    ///
+    /// # Returns
+    /// * `Option<FillerStats>` - Filler counters, or `None` without filler audio
+    pub fn filler_stats(&self) -> Option<FillerStats> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.filler_stats(),
+            Backend::Realtime(_) => None,
+        }
+    }
+
+    /// Get how a voice session decides end of turn
+    ///
+    /// # Returns
+    /// * `Option<TurnDetectionStats>` - Mode and model inference counters, or
+    ///   `None` for realtime sessions
+    pub fn turn_detection_stats(&self) -> Option<TurnDetectionStats> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => Some(voice_manager.turn_detection_stats()),
+            Backend::Realtime(_) => None,
+        }
+    }
+
+    /// Get how much caller speech the echo guard held back
+    ///
+    /// # Returns
+    /// * `Option<EchoGuardStats>` - Suppression counts, or `None` without an echo guard
+    pub fn echo_guard_stats(&self) -> Option<EchoGuardStats> {
+        self.echo_guard.as_ref().map(|guard| guard.stats())
+    }
+
+    /// Get how often the pipeline watchdog reconnected a stalled provider
+    ///
+    /// # Returns
+    /// * `Option<WatchdogStats>` - Recovery counts, or `None` without a watchdog
+    pub fn watchdog_stats(&self) -> Option<WatchdogStats> {
+        self.watchdog.as_ref().map(|watchdog| watchdog.stats())
+    }
+
+    /// The budget the session's provider reconnects and retries draw from
+    ///
+    /// Components outside the session, such as a voice-list refresh, take
+    /// a token with [`RetryBudget::try_acquire`] before each attempt.
+    ///
+    /// # Returns
+    /// * `Option<&Arc<RetryBudget>>` - The budget, or `None` without one
+    pub fn retry_budget(&self) -> Option<&Arc<RetryBudget>> {
+        self.retry_budget.as_ref()
+    }
+
+    /// Get the attempts the session's retry budget allowed and denied
+    ///
+    /// # Returns
+    /// * `Option<RetryBudgetStats>` - Spent and denied counts, or `None`
+    ///   without a retry budget
+    pub fn retry_budget_stats(&self) -> Option<RetryBudgetStats> {
+        self.retry_budget.as_ref().map(|budget| budget.stats())
+    }
+
+    /// Get the faults the chaos layer injected into a voice session
+    ///
+    /// # Returns
+    /// * `Option<ChaosStats>` - Injected fault counts, or `None` when the
+    ///   session was not targeted
+    #[cfg(feature = "chaos")]
+    pub fn chaos_stats(&self) -> Option<ChaosStats> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager
+                .get_config()
+                .chaos
+                .as_ref()
+                .map(|chaos| chaos.stats()),
+            Backend::Realtime(_) => None,
+        }
+    }
+
+    /// Hold the session, e.g. until an outbound call is answered
+    ///
+    /// While held, input audio is dropped before it reaches STT and no usage
+    /// is counted; audio can still be played. The consent timeout is paused.
+    pub fn hold(&self) {
+        self.usage.hold();
+        if let Some(consent) = &self.consent {
+            consent.stop_timer();
+        }
+    }
+
+    /// End the hold; usage counts from now, as if the session started now,
+    /// and pending consent times out `timeout_ms` from now
+    pub fn release_hold(&self) {
+        self.usage.release_hold();
+        if let Some(consent) = &self.consent {
+            consent.start_timer();
+        }
+    }
+
+    /// Get what the session has used so far
+    ///
+    /// Counts STT input audio, TTS text and output audio from the moment the
+    /// session was built, and the turns seen so far, and reports the caller's
+    /// consent; `ended_at` is set once [`close`](Self::close) runs.
+    pub fn usage(&self) -> SessionUsage {
+        let mut usage = self.usage.snapshot();
+        (usage.turns, usage.turn_ids) = self.turns.snapshot();
+        usage.consent = self.consent();
+        usage
+    }
+
+    /// Route the next user turn by what the caller is expected to say
+    ///
+    /// Confirmation and digit turns go to the fast STT provider set with
+    /// [`SessionPipelineBuilder::stt_routing`](super::SessionPipelineBuilder::stt_routing),
+    /// freeform turns to the configured one.
+    ///
+    /// # Returns
+    /// * `bool` - Whether per-turn STT routing is configured
+    pub fn expect_utterance(&self, kind: UtteranceKind) -> bool {
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.expect_utterance(kind),
+            Backend::Realtime(_) => false,
+        }
+    }
+
+    /// Get the voice manager of a voice session
+    pub fn voice_manager(&self) -> Option<&Arc<VoiceManager>> {
+        match &self.backend {
+            Backend::Voice(voice_manager) => Some(voice_manager),
+            Backend::Realtime(_) => None,
+        }
+    }
+
+    /// Get the agent bridge, if one was configured
+    pub fn agent_bridge(&self) -> Option<&Arc<AgentBridge>> {
+        self.agent_bridge.as_ref()
+    }
+
+    /// Whether this session is driven by a realtime provider
+    pub fn is_realtime(&self) -> bool {
+        matches!(self.backend, Backend::Realtime(_))
+    }
+
+    /// Export the session transcript, oldest entry first
+    ///
+    /// Holds final user transcripts and the text sent to TTS (or the final
+    /// transcripts of both sides for realtime sessions). Entries that
+    /// overflowed to the cache are read back and merged in order; entries
+    /// dropped by the overflow policy are missing.
+    pub async fn transcript(&self) -> Vec<TranscriptEntry> {
+        self.transcript.export().await
+    }
+
+    /// Configuration the session was built with
+    ///
+    /// The same configuration
+    /// [`SessionPipelineBuilder::dry_run`](super::SessionPipelineBuilder::dry_run)
+    /// reports for the builder, with secrets redacted.
+    pub fn effective_config(&self) -> &EffectiveSessionConfig {
+        &self.effective_config
+    }
+
+    /// Size and overflow counters of the transcript buffer
+    pub fn transcript_stats(&self) -> TranscriptBufferStats {
+        self.transcript.stats()
+    }
+
+    /// Talk time per language of the user's final transcripts, with each
+    /// language's share of the total
+    ///
+    /// Languages are listed in the order they were first heard. Counts every
+    /// final transcript, including those the transcript buffer dropped.
+    pub fn language_stats(&self) -> Vec<LanguageTalkTime> {
+        self.transcript.language_stats()
+    }
+
+    /// When the bot spoke and how loud the caller's audio was
+    ///
+    /// Input for [`diarize`](super::diarization::diarize) once the session
+    /// has ended. `None` unless enabled with
+    /// [`SessionPipelineBuilder::speech_activity`](super::SessionPipelineBuilder::speech_activity).
+    pub fn speech_activity(&self) -> Option<SpeechActivity> {
+        self.speech_activity
+            .as_ref()
+            .map(|activity| activity.snapshot())
+    }
+
+    /// Cancel the agent bridge and disconnect the providers
+    ///
+    /// Also marks the end of the session for [`usage`](Self::usage) and
+    /// removes transcript entries spilled to the cache, so export the
+    /// [`transcript`](Self::transcript) before closing.
+    ///
+    /// The event stream ends once the session has been dropped.
+    pub async fn close(&self) -> SessionResult<()> {
+        self.usage.close();
+        if let Some(consent) = &self.consent {
+            consent.stop_timer();
+        }
+        self.transcript.discard_spilled().await;
+        if let Some(bridge) = &self.agent_bridge {
+            bridge.cancel();
+        }
+
+        match &self.backend {
+            Backend::Voice(voice_manager) => voice_manager.stop().await?,
+            Backend::Realtime(realtime) => realtime.lock().await.disconnect().await?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+//! Retry budget shared by the provider operations of a session
+//!
+//! When a provider is down, every component of a session retries on its own:
+//! the watchdog reconnects STT and TTS, and voice lists are fetched again.
+//! Each is bounded alone, but together they can multiply the attempts sent
+//! to a failing provider. A [`RetryBudget`] is a token bucket all of them
+//! draw from, so the session's outbound attempts stay bounded.
+//!
+//! Attempts are ranked by [`RetryPriority`]. Lower priorities leave tokens in
+//! the bucket for the higher ones:
+//!
+//! - **STT reconnect** takes any token left.
+//! - **TTS retry** leaves `stt_reserve` tokens for STT.
+//! - **Voice-list refresh** leaves `stt_reserve + tts_reserve` tokens.
+//!
+//! So when the bucket runs low, the caller's transcription is the last
+//! thing to stop being recovered. Denied attempts are logged, counted in
+//! `waav_retry_budget_denied_total` and in [`RetryBudgetStats`].
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::metrics::global_metrics;
+
+/// Default attempts the budget allows in a burst
+pub const DEFAULT_CAPACITY: u32 = 6;
+
+/// Default attempts added back to the budget per minute
+pub const DEFAULT_REFILL_PER_MINUTE: u32 = 6;
+
+/// Default tokens kept for STT reconnects
+pub const DEFAULT_STT_RESERVE: u32 = 2;
+
+/// Default tokens kept for TTS retries (on top of the STT reserve)
+pub const DEFAULT_TTS_RESERVE: u32 = 2;
+
+/// Kind of provider attempt drawing from the budget, most important first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RetryPriority {
+    /// Reconnecting the STT provider
+    SttReconnect,
+    /// Reconnecting or retrying the TTS provider
+    TtsRetry,
+    /// Fetching the provider's voice list again
+    VoiceListRefresh,
+}
+
+impl RetryPriority {
+    /// The priority's metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SttReconnect => "stt_reconnect",
+            Self::TtsRetry => "tts_retry",
+            Self::VoiceListRefresh => "voice_list_refresh",
+        }
+    }
+}
+
+/// Size and refill rate of a session's retry budget
+///
+/// # Example JSON
+/// ```json
+/// {"capacity": 6, "refill_per_minute": 6, "stt_reserve": 2, "tts_reserve": 2}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct RetryBudgetConfig {
+    /// Attempts allowed in a burst; the budget starts full
+    #[cfg_attr(feature = "openapi", schema(example = 6))]
+    pub capacity: u32,
+    /// Attempts added back per minute, up to `capacity`
+    #[cfg_attr(feature = "openapi", schema(example = 6))]
+    pub refill_per_minute: u32,
+    /// Tokens only STT reconnects may take
+    #[cfg_attr(feature = "openapi", schema(example = 2))]
+    pub stt_reserve: u32,
+    /// Tokens above the STT reserve that voice-list refreshes leave to TTS
+    #[cfg_attr(feature = "openapi", schema(example = 2))]
+    pub tts_reserve: u32,
+}
+
+impl Default for RetryBudgetConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_CAPACITY,
+            refill_per_minute: DEFAULT_REFILL_PER_MINUTE,
+            stt_reserve: DEFAULT_STT_RESERVE,
+            tts_reserve: DEFAULT_TTS_RESERVE,
+        }
+    }
+}
+
+impl RetryBudgetConfig {
+    /// Validate the budget
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("capacity", self.capacity),
+            ("refill_per_minute", self.refill_per_minute),
+        ] {
+            if value == 0 {
+                return Err(format!("{name} must be greater than 0"));
+            }
+        }
+        let reserved = self.stt_reserve.saturating_add(self.tts_reserve);
+        if reserved >= self.capacity {
+            return Err(format!(
+                "stt_reserve + tts_reserve ({reserved}) must be less than capacity ({})",
+                self.capacity
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tokens an attempt of `priority` must leave in the bucket
+    fn reserve(&self, priority: RetryPriority) -> u32 {
+        match priority {
+            RetryPriority::SttReconnect => 0,
+            RetryPriority::TtsRetry => self.stt_reserve,
+            RetryPriority::VoiceListRefresh => self.stt_reserve + self.tts_reserve,
+        }
+    }
+}
+
+/// Attempts of one priority the budget allowed and denied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetryCounts {
+    /// Attempts that took a token
+    pub spent: u64,
+    /// Attempts held back because the budget was used up
+    pub denied: u64,
+}
+
+/// Use of a session's retry budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetryBudgetStats {
+    /// STT reconnects
+    pub stt_reconnect: RetryCounts,
+    /// TTS reconnects and retries
+    pub tts_retry: RetryCounts,
+    /// Voice-list refreshes
+    pub voice_list_refresh: RetryCounts,
+    /// Whole tokens left in the budget
+    pub available: u32,
+}
+
+impl RetryBudgetStats {
+    /// Attempts of all priorities that took a token
+    pub fn spent(&self) -> u64 {
+        self.stt_reconnect.spent + self.tts_retry.spent + self.voice_list_refresh.spent
+    }
+
+    /// Attempts of all priorities that were denied
+    pub fn denied(&self) -> u64 {
+        self.stt_reconnect.denied + self.tts_retry.denied + self.voice_list_refresh.denied
+    }
+
+    fn counts_mut(&mut self, priority: RetryPriority) -> &mut RetryCounts {
+        match priority {
+            RetryPriority::SttReconnect => &mut self.stt_reconnect,
+            RetryPriority::TtsRetry => &mut self.tts_retry,
+            RetryPriority::VoiceListRefresh => &mut self.voice_list_refresh,
+        }
+    }
+}
+
+struct BudgetState {
+    /// Tokens in the bucket, including the fraction refilled so far
+    tokens: f64,
+    /// When `tokens` was last refilled
+    refilled_at: Instant,
+    stats: RetryBudgetStats,
+}
+
+/// Token bucket bounding the provider attempts of one session
+///
+/// Shared by every component of the session that retries a provider; call
+/// [`RetryBudget::try_acquire`] before each attempt.
+pub struct RetryBudget {
+    config: RetryBudgetConfig,
+    state: Mutex<BudgetState>,
+}
+
+impl RetryBudget {
+    /// A full budget
+    pub fn new(config: RetryBudgetConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(BudgetState {
+                tokens: config.capacity as f64,
+                refilled_at: Instant::now(),
+                stats: RetryBudgetStats::default(),
+            }),
+        }
+    }
+
+    /// The budget's configuration
+    pub fn config(&self) -> &RetryBudgetConfig {
+        &self.config
+    }
+
+    /// Take a token for an attempt of `priority`
+    ///
+    /// # Returns
+    /// * `true` if the attempt may go ahead
+    /// * `false` if the budget, less the reserves of higher priorities, is
+    ///   used up; the attempt should be skipped
+    pub fn try_acquire(&self, priority: RetryPriority) -> bool {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        let refilled = now
+            .saturating_duration_since(state.refilled_at)
+            .as_secs_f64()
+            * self.config.refill_per_minute as f64
+            / 60.0;
+        state.tokens = (state.tokens + refilled).min(self.config.capacity as f64);
+        state.refilled_at = now;
+
+        let needed = self.config.reserve(priority) as f64 + 1.0;
+        if state.tokens >= needed {
+            state.tokens -= 1.0;
+            state.stats.counts_mut(priority).spent += 1;
+            return true;
+        }
+        state.stats.counts_mut(priority).denied += 1;
+        let available = state.tokens;
+        drop(state);
+
+        debug!(
+            priority = priority.as_str(),
+            available, "Retry budget used up; attempt denied"
+        );
+        global_metrics().inc_counter(
+            "waav_retry_budget_denied_total",
+            "Provider attempts denied by a session's retry budget",
+            &[("priority", priority.as_str())],
+        );
+        false
+    }
+
+    /// Attempts spent and denied so far
+    pub fn stats(&self) -> RetryBudgetStats {
+        let state = self.state.lock();
+        RetryBudgetStats {
+            available: state.tokens as u32,
+            ..state.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn budget(capacity: u32, stt_reserve: u32, tts_reserve: u32) -> RetryBudget {
+        RetryBudget::new(RetryBudgetConfig {
+            capacity,
+            refill_per_minute: 60,
+            stt_reserve,
+            tts_reserve,
+        })
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(RetryBudgetConfig::default().validate().is_ok());
+        let config: RetryBudgetConfig = serde_json::from_str(r#"{"capacity": 10}"#).unwrap();
+        assert_eq!(config.capacity, 10);
+        assert_eq!(config.stt_reserve, DEFAULT_STT_RESERVE);
+
+        for config in [
+            RetryBudgetConfig {
+                capacity: 0,
+                ..Default::default()
+            },
+            RetryBudgetConfig {
+                refill_per_minute: 0,
+                ..Default::default()
+            },
+            RetryBudgetConfig {
+                capacity: 4,
+                stt_reserve: 2,
+                tts_reserve: 2,
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_lower_priorities_leave_reserves() {
+        let budget = budget(5, 1, 2);
+
+        // Refreshes stop once only the reserves are left
+        assert!(budget.try_acquire(RetryPriority::VoiceListRefresh));
+        assert!(budget.try_acquire(RetryPriority::VoiceListRefresh));
+        assert!(!budget.try_acquire(RetryPriority::VoiceListRefresh));
+
+        // TTS takes its reserve but not STT's
+        assert!(budget.try_acquire(RetryPriority::TtsRetry));
+        assert!(budget.try_acquire(RetryPriority::TtsRetry));
+        assert!(!budget.try_acquire(RetryPriority::TtsRetry));
+
+        assert!(budget.try_acquire(RetryPriority::SttReconnect));
+        assert!(!budget.try_acquire(RetryPriority::SttReconnect));
+
+        let stats = budget.stats();
+        assert_eq!(
+            stats.voice_list_refresh,
+            RetryCounts {
+                spent: 2,
+                denied: 1
+            }
+        );
+        assert_eq!(
+            stats.tts_retry,
+            RetryCounts {
+                spent: 2,
+                denied: 1
+            }
+        );
+        assert_eq!(
+            stats.stt_reconnect,
+            RetryCounts {
+                spent: 1,
+                denied: 1
+            }
+        );
+        assert_eq!(stats.spent(), 5);
+        assert_eq!(stats.denied(), 3);
+        assert_eq!(stats.available, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refills_up_to_capacity() {
+        let budget = budget(2, 0, 0);
+        assert!(budget.try_acquire(RetryPriority::SttReconnect));
+        assert!(budget.try_acquire(RetryPriority::SttReconnect));
+        assert!(!budget.try_acquire(RetryPriority::SttReconnect));
+
+        // One token per second
+        tokio::time::advance(Duration::from_millis(1_500)).await;
+        assert!(budget.try_acquire(RetryPriority::SttReconnect));
+        assert!(!budget.try_acquire(RetryPriority::SttReconnect));
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert!(budget.try_acquire(RetryPriority::SttReconnect));
+        assert!(budget.try_acquire(RetryPriority::SttReconnect));
+        assert!(!budget.try_acquire(RetryPriority::SttReconnect));
+    }
+}
@@ -0,0 +1,666 @@
+//! Bounded transcript of a session
+//!
+//! Final user transcripts and the text sent to TTS are kept in order for
+//! exports and context replay. The buffer is capped by entry count and text
+//! bytes so a call that runs for hours does not grow without bound. What
+//! happens to entries past the cap depends on the overflow policy: they are
+//! dropped, or written to the cache backend in segments that
+//! [`TranscriptBuffer::export`] stitches back in front of the buffered entries.
+//!
+//! User entries carry the language they were spoken in. Consecutive user
+//! entries in one language form a [`LanguageSegment`], and the buffer keeps
+//! the talk time of each language for the whole session, including entries
+//! that were dropped or spilled.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::diarization::Speaker;
+use super::usage::now_ms;
+use crate::core::cache::store::CacheStore;
+use crate::core::realtime::TranscriptRole;
+
+/// What to do with transcript entries past the buffer limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptOverflowPolicy {
+    /// Discard the oldest entries
+    #[default]
+    DropOldest,
+    /// Move the oldest entries to the cache backend; exports read them back
+    SpillToCache,
+}
+
+/// Limits of the in-memory session transcript
+///
+/// # Example YAML
+/// ```yaml
+/// transcript_buffer:
+///   max_entries: 2000
+///   max_bytes: 1048576
+///   overflow: spill_to_cache
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct TranscriptBufferConfig {
+    /// Most entries kept in memory
+    pub max_entries: usize,
+    /// Most transcript text kept in memory, in bytes
+    pub max_bytes: usize,
+    /// What happens to entries past either limit
+    pub overflow: TranscriptOverflowPolicy,
+}
+
+impl Default for TranscriptBufferConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_bytes: 4 * 1024 * 1024,
+            overflow: TranscriptOverflowPolicy::DropOldest,
+        }
+    }
+}
+
+impl TranscriptBufferConfig {
+    /// Validate the limits
+    ///
+    /// # Returns
+    /// * `Ok(())` if both limits are non-zero
+    /// * `Err(String)` naming the zero limit otherwise
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_entries == 0 {
+            return Err("max_entries must be greater than 0".to_string());
+        }
+        if self.max_bytes == 0 {
+            return Err("max_bytes must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One line of the session transcript
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// Who spoke
+    pub role: TranscriptRole,
+    /// What was said
+    pub text: String,
+    /// Turn the entry belongs to, when turns are tracked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// When the entry was recorded (Unix ms)
+    pub timestamp: u64,
+    /// Who was heard, set by the post-call diarization pass
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<Speaker>,
+    /// Language the entry was spoken in: the detected language, or the
+    /// session's configured STT language when the provider reports none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Length of the audio the entry was transcribed from, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+impl TranscriptEntry {
+    /// Entry recorded now
+    pub fn new(role: TranscriptRole, text: impl Into<String>, turn_id: Option<String>) -> Self {
+        Self {
+            role,
+            text: text.into(),
+            turn_id,
+            timestamp: now_ms(),
+            speaker: None,
+            language: None,
+            duration_ms: None,
+        }
+    }
+
+    /// Set the language the entry was spoken in
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Set the length of the audio the entry was transcribed from
+    pub fn with_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = Some(duration_ms);
+        self
+    }
+
+    /// How long the entry was spoken for: `duration_ms` when known,
+    /// estimated from the word count otherwise
+    pub fn talk_time_ms(&self) -> u64 {
+        self.duration_ms
+            .unwrap_or_else(|| self.text.split_whitespace().count() as u64 * SPOKEN_MS_PER_WORD)
+    }
+}
+
+/// Estimated speaking time per word, for entries without audio timing
+pub const SPOKEN_MS_PER_WORD: u64 = 400;
+
+/// Consecutive user entries spoken in one language
+///
+/// Assistant entries between them do not end the segment; user entries
+/// without a language are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LanguageSegment {
+    /// Language of every entry in the segment
+    pub language: String,
+    /// Index of the segment's first user entry in the transcript
+    pub first_entry: usize,
+    /// Index of the segment's last user entry in the transcript
+    pub last_entry: usize,
+    /// User entries in the segment
+    pub entries: usize,
+    /// When the first entry was recorded (Unix ms)
+    pub start_ms: u64,
+    /// When the last entry was recorded (Unix ms)
+    pub end_ms: u64,
+    /// Talk time of the segment's entries, in milliseconds
+    pub talk_time_ms: u64,
+}
+
+/// Group the user entries of a transcript into language segments, in order
+pub fn language_segments(entries: &[TranscriptEntry]) -> Vec<LanguageSegment> {
+    let mut segments: Vec<LanguageSegment> = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.role != TranscriptRole::User {
+            continue;
+        }
+        let Some(language) = &entry.language else {
+            continue;
+        };
+        match segments.last_mut() {
+            Some(segment) if segment.language == *language => {
+                segment.last_entry = index;
+                segment.entries += 1;
+                segment.end_ms = entry.timestamp;
+                segment.talk_time_ms += entry.talk_time_ms();
+            }
+            _ => segments.push(LanguageSegment {
+                language: language.clone(),
+                first_entry: index,
+                last_entry: index,
+                entries: 1,
+                start_ms: entry.timestamp,
+                end_ms: entry.timestamp,
+                talk_time_ms: entry.talk_time_ms(),
+            }),
+        }
+    }
+    segments
+}
+
+/// How long the user spoke one language
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageTalkTime {
+    /// Language spoken
+    pub language: String,
+    /// User entries in the language
+    pub entries: u64,
+    /// Talk time in the language, in milliseconds
+    pub talk_time_ms: u64,
+    /// Share of the user's total talk time, 0 to 100
+    pub percent: f64,
+}
+
+/// Running talk time per language, in the order languages were first heard
+#[derive(Debug, Default)]
+struct LanguageTally {
+    languages: Vec<(String, u64, u64)>,
+}
+
+impl LanguageTally {
+    /// Count a user entry; other entries and entries without a language are ignored
+    fn add(&mut self, entry: &TranscriptEntry) {
+        if entry.role != TranscriptRole::User {
+            return;
+        }
+        let Some(language) = &entry.language else {
+            return;
+        };
+        let talk_time_ms = entry.talk_time_ms();
+        match self
+            .languages
+            .iter_mut()
+            .find(|(name, ..)| name == language)
+        {
+            Some((_, entries, total)) => {
+                *entries += 1;
+                *total += talk_time_ms;
+            }
+            None => self.languages.push((language.clone(), 1, talk_time_ms)),
+        }
+    }
+
+    fn talk_time(&self) -> Vec<LanguageTalkTime> {
+        let total: u64 = self.languages.iter().map(|(_, _, ms)| ms).sum();
+        self.languages
+            .iter()
+            .map(|(language, entries, talk_time_ms)| LanguageTalkTime {
+                language: language.clone(),
+                entries: *entries,
+                talk_time_ms: *talk_time_ms,
+                percent: if total == 0 {
+                    0.0
+                } else {
+                    *talk_time_ms as f64 * 100.0 / total as f64
+                },
+            })
+            .collect()
+    }
+}
+
+/// Talk time per language of the user entries of a transcript, in the order
+/// languages were first heard
+pub fn language_talk_time(entries: &[TranscriptEntry]) -> Vec<LanguageTalkTime> {
+    let mut tally = LanguageTally::default();
+    for entry in entries {
+        tally.add(entry);
+    }
+    tally.talk_time()
+}
+
+/// Counters of a session's transcript buffer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TranscriptBufferStats {
+    /// Entries held in memory
+    pub entries: usize,
+    /// Text bytes held in memory
+    pub bytes: usize,
+    /// Entries discarded, by `drop_oldest` or after a failed spill
+    pub dropped_entries: u64,
+    /// Entries written to the cache backend
+    pub spilled_entries: u64,
+    /// Cache writes of spilled entries
+    pub spilled_segments: u64,
+    /// Spilled segments that could not be written or read back
+    pub spill_failures: u64,
+}
+
+#[derive(Default)]
+struct BufferState {
+    entries: VecDeque<TranscriptEntry>,
+    bytes: usize,
+    /// Cache keys of spilled segments, oldest first
+    segments: Vec<String>,
+}
+
+/// Transcript of one session, capped by [`TranscriptBufferConfig`]
+pub(super) struct TranscriptBuffer {
+    config: TranscriptBufferConfig,
+    /// Cache for spilled segments and the key prefix of this session's segments
+    spill: Option<(Arc<CacheStore>, String)>,
+    state: Mutex<BufferState>,
+    /// Mirrors of the in-memory size, readable without the lock
+    entries: AtomicUsize,
+    bytes: AtomicUsize,
+    dropped_entries: AtomicU64,
+    spilled_entries: AtomicU64,
+    spilled_segments: AtomicU64,
+    spill_failures: AtomicU64,
+    /// Talk time per language of every user entry pushed
+    languages: parking_lot::Mutex<LanguageTally>,
+}
+
+impl TranscriptBuffer {
+    /// Create an empty buffer
+    ///
+    /// `cache` is only used by the `spill_to_cache` policy; without one,
+    /// overflowing entries are dropped.
+    pub(super) fn new(config: TranscriptBufferConfig, cache: Option<Arc<CacheStore>>) -> Self {
+        let spill = cache
+            .filter(|_| config.overflow == TranscriptOverflowPolicy::SpillToCache)
+            .map(|cache| (cache, format!("transcript:{}", uuid::Uuid::new_v4())));
+        Self {
+            config,
+            spill,
+            state: Mutex::new(BufferState::default()),
+            entries: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            dropped_entries: AtomicU64::new(0),
+            spilled_entries: AtomicU64::new(0),
+            spilled_segments: AtomicU64::new(0),
+            spill_failures: AtomicU64::new(0),
+            languages: parking_lot::Mutex::new(LanguageTally::default()),
+        }
+    }
+
+    /// Append an entry, moving the oldest entries out if a limit is exceeded
+    ///
+    /// Empty text is ignored. An entry larger than `max_bytes` is kept on its
+    /// own rather than rejected.
+    pub(super) async fn push(&self, entry: TranscriptEntry) {
+        if entry.text.trim().is_empty() {
+            return;
+        }
+        self.languages.lock().add(&entry);
+        // Held across the spill so segments are written in order
+        let mut state = self.state.lock().await;
+        state.bytes += entry.text.len();
+        state.entries.push_back(entry);
+
+        let mut overflow = Vec::new();
+        while state.entries.len() > 1
+            && (state.entries.len() > self.config.max_entries
+                || state.bytes > self.config.max_bytes)
+        {
+            let Some(oldest) = state.entries.pop_front() else {
+                break;
+            };
+            state.bytes -= oldest.text.len();
+            overflow.push(oldest);
+        }
+        self.entries.store(state.entries.len(), Ordering::Relaxed);
+        self.bytes.store(state.bytes, Ordering::Relaxed);
+        if overflow.is_empty() {
+            return;
+        }
+
+        let Some((cache, prefix)) = &self.spill else {
+            self.dropped_entries
+                .fetch_add(overflow.len() as u64, Ordering::Relaxed);
+            return;
+        };
+        let key = format!("{prefix}:{}", state.segments.len());
+        let stored = match serde_json::to_vec(&overflow) {
+            Ok(segment) => cache.put(&key, segment).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match stored {
+            Ok(()) => {
+                state.segments.push(key);
+                self.spilled_entries
+                    .fetch_add(overflow.len() as u64, Ordering::Relaxed);
+                self.spilled_segments.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                warn!(
+                    entries = overflow.len(),
+                    "Failed to spill transcript segment, dropping it: {}", e
+                );
+                self.spill_failures.fetch_add(1, Ordering::Relaxed);
+                self.dropped_entries
+                    .fetch_add(overflow.len() as u64, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// The whole transcript, oldest first
+    ///
+    /// Spilled segments are read back from the cache and placed before the
+    /// entries still in memory. A segment the cache no longer has (expired
+    /// or evicted) is skipped and counted in `spill_failures`.
+    pub(super) async fn export(&self) -> Vec<TranscriptEntry> {
+        let state = self.state.lock().await;
+        let mut entries = Vec::with_capacity(state.entries.len());
+        if let Some((cache, _)) = &self.spill {
+            for key in &state.segments {
+                let segment = match cache.get(key).await {
+                    Ok(Some(bytes)) => serde_json::from_slice::<Vec<TranscriptEntry>>(&bytes)
+                        .map_err(|e| e.to_string()),
+                    Ok(None) => Err("segment not in cache".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                match segment {
+                    Ok(segment) => entries.extend(segment),
+                    Err(e) => {
+                        warn!(key = %key, "Skipping spilled transcript segment: {}", e);
+                        self.spill_failures.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+        entries.extend(state.entries.iter().cloned());
+        entries
+    }
+
+    /// Remove spilled segments from the cache
+    ///
+    /// Called when the session closes; later exports only contain the
+    /// entries still in memory.
+    pub(super) async fn discard_spilled(&self) {
+        let mut state = self.state.lock().await;
+        let Some((cache, _)) = &self.spill else {
+            return;
+        };
+        for key in state.segments.drain(..) {
+            if let Err(e) = cache.delete(&key).await {
+                warn!(key = %key, "Failed to delete spilled transcript segment: {}", e);
+            }
+        }
+    }
+
+    /// Current counters
+    pub(super) fn stats(&self) -> TranscriptBufferStats {
+        TranscriptBufferStats {
+            entries: self.entries.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            dropped_entries: self.dropped_entries.load(Ordering::Relaxed),
+            spilled_entries: self.spilled_entries.load(Ordering::Relaxed),
+            spilled_segments: self.spilled_segments.load(Ordering::Relaxed),
+            spill_failures: self.spill_failures.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Talk time per language of every user entry pushed so far
+    ///
+    /// Counted as entries arrive, so entries later dropped by the overflow
+    /// policy still count.
+    pub(super) fn language_stats(&self) -> Vec<LanguageTalkTime> {
+        self.languages.lock().talk_time()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cache::store::CacheConfig;
+
+    fn config(overflow: TranscriptOverflowPolicy) -> TranscriptBufferConfig {
+        TranscriptBufferConfig {
+            max_entries: 3,
+            max_bytes: 1024,
+            overflow,
+        }
+    }
+
+    fn user(text: &str) -> TranscriptEntry {
+        TranscriptEntry::new(TranscriptRole::User, text, None)
+    }
+
+    fn texts(entries: &[TranscriptEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.text.as_str()).collect()
+    }
+
+    async fn memory_cache() -> Arc<CacheStore> {
+        Arc::new(
+            CacheStore::from_config(CacheConfig::Memory {
+                max_entries: 100,
+                max_size_bytes: None,
+                ttl_seconds: None,
+            })
+            .await
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_latest_entries() {
+        let buffer = TranscriptBuffer::new(config(TranscriptOverflowPolicy::DropOldest), None);
+        for text in ["one", "two", "three", "four", "five"] {
+            buffer.push(user(text)).await;
+        }
+        buffer.push(user("  ")).await;
+
+        assert_eq!(texts(&buffer.export().await), ["three", "four", "five"]);
+        let stats = buffer.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.bytes, 13);
+        assert_eq!(stats.dropped_entries, 2);
+        assert_eq!(stats.spilled_entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_byte_limit_and_oversized_entry() {
+        let config = TranscriptBufferConfig {
+            max_entries: 100,
+            max_bytes: 10,
+            overflow: TranscriptOverflowPolicy::DropOldest,
+        };
+        let buffer = TranscriptBuffer::new(config, None);
+        buffer.push(user("abcd")).await;
+        buffer.push(user("efgh")).await;
+        buffer.push(user("ijkl")).await;
+        assert_eq!(texts(&buffer.export().await), ["efgh", "ijkl"]);
+
+        // An entry over the byte limit replaces everything but is kept
+        buffer.push(user("a much longer sentence")).await;
+        assert_eq!(texts(&buffer.export().await), ["a much longer sentence"]);
+        assert_eq!(buffer.stats().dropped_entries, 3);
+    }
+
+    #[tokio::test]
+    async fn test_spill_to_cache_export_merges_segments() {
+        let cache = memory_cache().await;
+        let buffer = TranscriptBuffer::new(
+            config(TranscriptOverflowPolicy::SpillToCache),
+            Some(cache.clone()),
+        );
+        let lines = ["one", "two", "three", "four", "five", "six", "seven"];
+        for text in lines {
+            buffer.push(user(text)).await;
+        }
+
+        assert_eq!(texts(&buffer.export().await), lines);
+        let stats = buffer.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.dropped_entries, 0);
+        assert_eq!(stats.spilled_entries, 4);
+        assert_eq!(stats.spilled_segments, 4);
+
+        // Segments the cache lost are skipped and counted
+        cache.clear().await.unwrap();
+        assert_eq!(texts(&buffer.export().await), ["five", "six", "seven"]);
+        assert_eq!(buffer.stats().spill_failures, 4);
+    }
+
+    #[tokio::test]
+    async fn test_discard_spilled_removes_segments() {
+        let cache = memory_cache().await;
+        let buffer = TranscriptBuffer::new(
+            config(TranscriptOverflowPolicy::SpillToCache),
+            Some(cache.clone()),
+        );
+        for text in ["one", "two", "three", "four"] {
+            buffer.push(user(text)).await;
+        }
+        let (_, prefix) = buffer.spill.as_ref().unwrap();
+        let key = format!("{prefix}:0");
+        assert!(cache.exists(&key).await.unwrap());
+
+        buffer.discard_spilled().await;
+        assert!(!cache.exists(&key).await.unwrap());
+        assert_eq!(texts(&buffer.export().await), ["two", "three", "four"]);
+    }
+
+    /// A Hinglish call: the caller switches between Hindi and English, the
+    /// bot answers in English, and one final has no detected language
+    fn mixed_language_call() -> Vec<TranscriptEntry> {
+        let spoken =
+            |text: &str, language: &str, duration_ms: u64, timestamp: u64| TranscriptEntry {
+                timestamp,
+                ..user(text)
+                    .with_language(language)
+                    .with_duration_ms(duration_ms)
+            };
+        let bot = |text: &str, timestamp: u64| TranscriptEntry {
+            timestamp,
+            ..TranscriptEntry::new(TranscriptRole::Assistant, text, None)
+        };
+        vec![
+            spoken("namaste, mujhe booking karni hai", "hi", 2_000, 1_000),
+            bot("Sure, for which date?", 3_500),
+            spoken("kal ke liye", "hi", 1_000, 6_000),
+            spoken("actually make it Friday evening", "en", 1_500, 8_000),
+            spoken("for two people", "en", 500, 10_000),
+            // No duration: estimated from the word count
+            TranscriptEntry {
+                timestamp: 12_000,
+                ..user("theek hai dhanyavaad").with_language("hi")
+            },
+            // No language: part of no segment
+            user("mm"),
+        ]
+    }
+
+    #[test]
+    fn test_language_segments_of_mixed_call() {
+        let segments = language_segments(&mixed_language_call());
+        let boundaries: Vec<_> = segments
+            .iter()
+            .map(|segment| {
+                (
+                    segment.language.as_str(),
+                    segment.first_entry,
+                    segment.last_entry,
+                    segment.entries,
+                )
+            })
+            .collect();
+        // The bot's reply does not split the first Hindi segment
+        assert_eq!(
+            boundaries,
+            [("hi", 0, 2, 2), ("en", 3, 4, 2), ("hi", 5, 5, 1)]
+        );
+        assert_eq!((segments[0].start_ms, segments[0].end_ms), (1_000, 6_000));
+        assert_eq!(segments[0].talk_time_ms, 3_000);
+        assert_eq!(segments[1].talk_time_ms, 2_000);
+        assert_eq!(segments[2].talk_time_ms, 3 * SPOKEN_MS_PER_WORD);
+        assert!(language_segments(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_language_stats_count_dropped_entries() {
+        let buffer = TranscriptBuffer::new(config(TranscriptOverflowPolicy::DropOldest), None);
+        let call = mixed_language_call();
+        for entry in call.clone() {
+            buffer.push(entry).await;
+        }
+        assert!(buffer.stats().dropped_entries > 0);
+
+        let stats = buffer.language_stats();
+        assert_eq!(stats, language_talk_time(&call));
+        let shares: Vec<_> = stats
+            .iter()
+            .map(|talk| (talk.language.as_str(), talk.entries, talk.talk_time_ms))
+            .collect();
+        assert_eq!(shares, [("hi", 3, 4_200), ("en", 2, 2_000)]);
+        assert!((stats[0].percent - 67.74).abs() < 0.01);
+        assert!((stats[0].percent + stats[1].percent - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_transcript_buffer_config() {
+        assert!(TranscriptBufferConfig::default().validate().is_ok());
+        let zero_entries = TranscriptBufferConfig {
+            max_entries: 0,
+            ..Default::default()
+        };
+        assert!(zero_entries.validate().unwrap_err().contains("max_entries"));
+
+        let config: TranscriptBufferConfig =
+            serde_json::from_str(r#"{"max_bytes": 2048, "overflow": "spill_to_cache"}"#).unwrap();
+        assert_eq!(config.max_entries, 10_000);
+        assert_eq!(config.max_bytes, 2048);
+        assert_eq!(config.overflow, TranscriptOverflowPolicy::SpillToCache);
+        assert!(serde_json::from_str::<TranscriptBufferConfig>(r#"{"max": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! Turn IDs that correlate transcripts with the speech answering them
+//!
+//! Every user turn gets a server-generated ID: it opens with the first
+//! transcript after the previous turn ended and closes with the result marked
+//! `is_speech_final`. Speech is tagged with the ID the caller passed to
+//! [`Session::speak_in_turn`](super::Session::speak_in_turn), or else with the
+//! latest user turn, so replies (including agent bridge replies) carry the ID
+//! of the turn they answer.
+//!
+//! Turns are tracked by the session rather than the providers, so an ID
+//! stays the same when the STT provider reconnects or TTS falls back to
+//! another voice mid-turn.
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+/// Turn IDs kept for [`SessionUsage`](super::SessionUsage); later turns are only counted
+pub const MAX_RECORDED_TURN_IDS: usize = 1000;
+
+/// One utterance queued for synthesis
+struct Speech {
+    turn_id: String,
+    /// Whether a flush ended the utterance
+    flushed: bool,
+    /// Whether its first audio has been emitted
+    started: bool,
+    /// Whether it is a `system` announcement, which clearing output keeps
+    announcement: bool,
+}
+
+#[derive(Default)]
+struct TurnState {
+    /// User turn still receiving transcripts
+    open_user_turn: Option<String>,
+    /// Most recent user turn, open or not
+    last_user_turn: Option<String>,
+    /// Utterances in the order their audio is produced
+    speech: VecDeque<Speech>,
+    /// Turn of the utterance that produced audio last
+    last_speech_turn: Option<String>,
+    turn_ids: Vec<String>,
+    turns: u64,
+}
+
+impl TurnState {
+    fn record(&mut self, turn_id: &str) {
+        if self.turn_ids.iter().any(|id| id == turn_id) {
+            return;
+        }
+        self.turns += 1;
+        if self.turn_ids.len() < MAX_RECORDED_TURN_IDS {
+            self.turn_ids.push(turn_id.to_string());
+        }
+    }
+
+    /// Utterance whose audio is produced next, created for speech that did
+    /// not go through `speak` (e.g. agent bridge replies)
+    fn front_speech(&mut self) -> Option<&mut Speech> {
+        if self.speech.is_empty() {
+            let turn_id = self.last_user_turn.clone()?;
+            self.speech.push_back(Speech {
+                turn_id,
+                flushed: false,
+                started: false,
+                announcement: false,
+            });
+        }
+        self.speech.front_mut()
+    }
+}
+
+/// Assigns turn IDs to a session's transcripts and speech
+#[derive(Default)]
+pub(super) struct TurnTracker {
+    state: Mutex<TurnState>,
+}
+
+impl TurnTracker {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn ID of a transcript, opening a user turn if none is open
+    pub(super) fn on_transcript(&self, is_speech_final: bool) -> String {
+        let mut state = self.state.lock();
+        let turn_id = match &state.open_user_turn {
+            Some(turn_id) => turn_id.clone(),
+            None => {
+                let turn_id = Uuid::new_v4().to_string();
+                state.record(&turn_id);
+                state.last_user_turn = Some(turn_id.clone());
+                state.open_user_turn = Some(turn_id.clone());
+                turn_id
+            }
+        };
+        if is_speech_final {
+            state.open_user_turn = None;
+        }
+        turn_id
+    }
+
+    /// Turn ID of a streaming STT error: the open user turn, if any
+    pub(super) fn user_turn(&self) -> Option<String> {
+        self.state.lock().open_user_turn.clone()
+    }
+
+    /// Most recent user turn, for errors of the agent answering it
+    pub(super) fn last_user_turn(&self) -> Option<String> {
+        self.state.lock().last_user_turn.clone()
+    }
+
+    /// Queue speech for `turn_id`, or for the latest user turn when `None`
+    ///
+    /// Without either, the speech starts a turn of its own. Returns the
+    /// resolved ID and whether a new utterance was queued for it.
+    pub(super) fn begin_speech(&self, turn_id: Option<&str>, flush: bool) -> (String, bool) {
+        let mut state = self.state.lock();
+        let turn_id = match turn_id {
+            Some(turn_id) => turn_id.to_string(),
+            None => state
+                .last_user_turn
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+        };
+        state.record(&turn_id);
+
+        // Partials of the same unflushed utterance extend it
+        if let Some(last) = state.speech.back_mut()
+            && !last.flushed
+            && last.turn_id == turn_id
+        {
+            last.flushed = flush;
+            return (turn_id, false);
+        }
+        state.speech.push_back(Speech {
+            turn_id: turn_id.clone(),
+            flushed: flush,
+            started: false,
+            announcement: false,
+        });
+        (turn_id, true)
+    }
+
+    /// Queue a `system` announcement ahead of all other speech
+    ///
+    /// Announcements pre-empt the queue, so they are played after earlier
+    /// announcements but before any other utterance.
+    pub(super) fn begin_announcement(&self, turn_id: Option<&str>) -> String {
+        let mut state = self.state.lock();
+        let turn_id = match turn_id {
+            Some(turn_id) => turn_id.to_string(),
+            None => state
+                .last_user_turn
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+        };
+        state.record(&turn_id);
+
+        let position = state
+            .speech
+            .iter()
+            .take_while(|speech| speech.announcement)
+            .count();
+        state.speech.insert(
+            position,
+            Speech {
+                turn_id: turn_id.clone(),
+                flushed: true,
+                started: false,
+                announcement: true,
+            },
+        );
+        turn_id
+    }
+
+    /// Forget an announcement queued by `begin_announcement` that failed
+    pub(super) fn cancel_announcement(&self) {
+        let mut state = self.state.lock();
+        if let Some(position) = state
+            .speech
+            .iter()
+            .rposition(|speech| speech.announcement && !speech.started)
+        {
+            state.speech.remove(position);
+        }
+    }
+
+    /// Forget an utterance queued by `begin_speech` that the provider refused
+    pub(super) fn cancel_speech(&self) {
+        self.state.lock().speech.pop_back();
+    }
+
+    /// Turn ID of an output audio chunk, and whether it is the utterance's first
+    pub(super) fn on_audio(&self) -> Option<(String, bool)> {
+        let mut state = self.state.lock();
+        let speech = state.front_speech()?;
+        let first = !speech.started;
+        speech.started = true;
+        let turn_id = speech.turn_id.clone();
+        state.last_speech_turn = Some(turn_id.clone());
+        Some((turn_id, first))
+    }
+
+    /// Turn ID of the utterance being synthesized, for TTS errors and fallback
+    ///
+    /// A voice fallback is reported after the replayed text completed, so
+    /// without queued speech this is the turn that spoke last.
+    pub(super) fn speech_turn(&self) -> Option<String> {
+        let state = self.state.lock();
+        state
+            .speech
+            .front()
+            .map(|speech| speech.turn_id.clone())
+            .or_else(|| state.last_speech_turn.clone())
+            .or_else(|| state.last_user_turn.clone())
+    }
+
+    /// Turn ID of a completed utterance, which leaves the queue
+    pub(super) fn on_speech_complete(&self) -> Option<String> {
+        let mut state = self.state.lock();
+        match state.speech.pop_front() {
+            Some(speech) => Some(speech.turn_id),
+            None => state.last_user_turn.clone(),
+        }
+    }
+
+    /// Drop queued utterances after output was cleared
+    ///
+    /// Announcements are exempt from clearing and stay queued.
+    pub(super) fn clear_speech(&self) {
+        self.state
+            .lock()
+            .speech
+            .retain(|speech| speech.announcement);
+    }
+
+    /// Number of distinct turns and the first [`MAX_RECORDED_TURN_IDS`] IDs
+    pub(super) fn snapshot(&self) -> (u64, Vec<String>) {
+        let state = self.state.lock();
+        (state.turns, state.turn_ids.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_turn_spans_until_speech_final() {
+        let turns = TurnTracker::new();
+        let first = turns.on_transcript(false);
+        assert_eq!(turns.user_turn().as_deref(), Some(first.as_str()));
+        assert_eq!(turns.on_transcript(false), first);
+        assert_eq!(turns.on_transcript(true), first);
+        assert!(turns.user_turn().is_none());
+
+        let second = turns.on_transcript(true);
+        assert_ne!(second, first);
+        assert_eq!(turns.snapshot(), (2, vec![first, second]));
+    }
+
+    #[test]
+    fn test_speech_defaults_to_latest_user_turn() {
+        let turns = TurnTracker::new();
+        let user = turns.on_transcript(true);
+
+        assert_eq!(turns.begin_speech(None, false), (user.clone(), true));
+        // The rest of the utterance extends it
+        assert_eq!(turns.begin_speech(None, true), (user.clone(), false));
+        assert_eq!(turns.on_audio(), Some((user.clone(), true)));
+        assert_eq!(turns.on_audio(), Some((user.clone(), false)));
+        assert_eq!(turns.on_speech_complete(), Some(user.clone()));
+        assert_eq!(turns.snapshot(), (1, vec![user]));
+    }
+
+    #[test]
+    fn test_client_turn_ids_queue_in_order() {
+        let turns = TurnTracker::new();
+        turns.begin_speech(Some("a"), true);
+        turns.begin_speech(Some("b"), true);
+
+        assert_eq!(turns.on_audio(), Some(("a".to_string(), true)));
+        assert_eq!(turns.speech_turn().as_deref(), Some("a"));
+        assert_eq!(turns.on_speech_complete().as_deref(), Some("a"));
+        assert_eq!(turns.on_audio(), Some(("b".to_string(), true)));
+        assert_eq!(turns.on_speech_complete().as_deref(), Some("b"));
+        // Still the last speech's turn once the queue is empty
+        assert_eq!(turns.speech_turn().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_speech_without_speak_uses_latest_user_turn() {
+        let turns = TurnTracker::new();
+        assert_eq!(turns.on_audio(), None);
+
+        let user = turns.on_transcript(true);
+        assert_eq!(turns.on_audio(), Some((user.clone(), true)));
+        assert_eq!(turns.on_speech_complete(), Some(user));
+    }
+
+    #[test]
+    fn test_cancelled_and_cleared_speech_is_dropped() {
+        let turns = TurnTracker::new();
+        turns.begin_speech(Some("a"), true);
+        let (_, queued) = turns.begin_speech(Some("b"), true);
+        assert!(queued);
+        turns.cancel_speech();
+        assert_eq!(turns.on_speech_complete().as_deref(), Some("a"));
+
+        turns.begin_speech(Some("c"), false);
+        turns.clear_speech();
+        assert_eq!(turns.on_audio(), None);
+    }
+
+    #[test]
+    fn test_announcements_jump_the_queue_and_survive_clear() {
+        let turns = TurnTracker::new();
+        turns.begin_speech(Some("a"), true);
+        assert_eq!(turns.begin_announcement(Some("notice-1")), "notice-1");
+        turns.begin_announcement(Some("notice-2"));
+        turns.begin_announcement(Some("notice-3"));
+        turns.cancel_announcement();
+
+        // Clearing the pre-empted speech keeps the announcements
+        turns.clear_speech();
+        assert_eq!(turns.on_audio(), Some(("notice-1".to_string(), true)));
+        assert_eq!(turns.on_speech_complete().as_deref(), Some("notice-1"));
+        assert_eq!(turns.on_speech_complete().as_deref(), Some("notice-2"));
+        assert_eq!(turns.on_audio(), None);
+    }
+}
@@ -0,0 +1,645 @@
+//! Usage counters for billing and reporting
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::audio_duration::container_duration_ms;
+use super::consent::ConsentRecord;
+use super::turns::MAX_RECORDED_TURN_IDS;
+use crate::config::FeatureFlags;
+use crate::core::{
+    realtime::RealtimeConfig,
+    stt::{STTConfig, STTFailoverUsage, STTRoute, TurnRouter, UtteranceKind},
+    tts::AudioData,
+    tts::TTSConfig,
+};
+
+/// Provider and model a session was billed against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderModel {
+    /// Provider name (e.g. "deepgram")
+    pub provider: String,
+    /// Model name; empty when the provider default was used
+    pub model: String,
+}
+
+impl ProviderModel {
+    fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }
+    }
+}
+
+/// STT provider that transcribed a user turn, with per-turn STT routing
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnSTTUsage {
+    /// ID of the user turn
+    pub turn_id: String,
+    /// Provider that produced the turn's first transcript
+    pub stt: ProviderModel,
+    /// Whether that was the premium or the fast provider
+    pub route: STTRoute,
+    /// Latest `expect` hint when the turn started, if any
+    pub expected: Option<UtteranceKind>,
+}
+
+/// Text and output audio of one TTS utterance, from the text sent up to the
+/// provider's completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TtsUtterance {
+    /// Characters of text sent to the provider
+    pub characters: u64,
+    /// Milliseconds of audio the provider produced
+    pub audio_ms: u64,
+}
+
+/// Snapshot of what a session has used so far
+///
+/// Returned by [`Session::usage`](super::Session::usage).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionUsage {
+    /// Unix timestamp in milliseconds when the session was built, or when
+    /// its hold was released
+    pub started_at: u64,
+    /// Unix timestamp in milliseconds when the session was closed, if it has been
+    pub ended_at: Option<u64>,
+    /// STT provider (voice sessions)
+    pub stt: Option<ProviderModel>,
+    /// Seconds of input audio sent to the STT provider
+    ///
+    /// With STT failover, the audio sent to the primary provider only; with
+    /// per-turn STT routing, the audio sent to the premium provider only.
+    pub stt_audio_seconds: f64,
+    /// Secondary STT provider (voice sessions with STT failover)
+    pub stt_secondary: Option<ProviderModel>,
+    /// Seconds of input audio sent to the secondary STT provider, whether
+    /// as a warm standby or after failover
+    pub stt_secondary_audio_seconds: f64,
+    /// Fast STT provider (voice sessions with per-turn STT routing)
+    pub stt_fast: Option<ProviderModel>,
+    /// Seconds of input audio sent to the fast STT provider
+    pub stt_fast_audio_seconds: f64,
+    /// STT provider of each of the first user turns (voice sessions with
+    /// per-turn STT routing; at most [`MAX_RECORDED_TURN_IDS`])
+    pub stt_turns: Vec<TurnSTTUsage>,
+    /// TTS provider (voice sessions)
+    pub tts: Option<ProviderModel>,
+    /// Characters of text sent to the TTS provider
+    pub tts_characters: u64,
+    /// Seconds of output audio produced by the session
+    pub tts_audio_seconds: f64,
+    /// The first completed TTS utterances (at most
+    /// [`MAX_RECORDED_TURN_IDS`]); interrupted utterances are left out
+    pub tts_utterances: Vec<TtsUtterance>,
+    /// Realtime provider (realtime sessions)
+    pub realtime: Option<ProviderModel>,
+    /// Distinct turns: user turns plus turns only seen on `speak`
+    pub turns: u64,
+    /// IDs of the first turns, in the order they started (at most
+    /// [`MAX_RECORDED_TURN_IDS`](super::turns::MAX_RECORDED_TURN_IDS))
+    pub turn_ids: Vec<String>,
+    /// Feature flags the session was built with
+    pub feature_flags: FeatureFlags,
+    /// The caller's recording consent, for sessions built with a consent gate
+    pub consent: Option<ConsentRecord>,
+}
+
+impl SessionUsage {
+    /// Wall-clock duration in seconds, up to now if the session is still open
+    pub fn duration_seconds(&self) -> f64 {
+        let ended_at = self.ended_at.unwrap_or_else(now_ms);
+        ended_at.saturating_sub(self.started_at) as f64 / 1000.0
+    }
+}
+
+/// Atomic usage counters shared by a session and its callbacks
+pub(super) struct UsageMeter {
+    started_at: AtomicU64,
+    /// Nothing is counted while set (see [`hold`](Self::hold))
+    on_hold: AtomicBool,
+    /// Unix timestamp in milliseconds of `close()`; zero while open
+    ended_at: AtomicU64,
+    stt: Option<ProviderModel>,
+    tts: Option<ProviderModel>,
+    realtime: Option<ProviderModel>,
+    /// Bytes per second of STT input audio
+    input_byte_rate: u64,
+    stt_audio_bytes: AtomicU64,
+    /// Secondary STT provider and the audio sent to each STT provider
+    stt_failover: Option<(ProviderModel, Arc<STTFailoverUsage>)>,
+    /// Fast STT provider and the routing state of the session
+    stt_routing: Option<(ProviderModel, Arc<TurnRouter>)>,
+    stt_turns: Mutex<Vec<TurnSTTUsage>>,
+    tts_characters: AtomicU64,
+    tts_audio_ms: AtomicU64,
+    /// The utterance being synthesized and the completed ones
+    tts_utterances: Mutex<TtsUtterances>,
+    feature_flags: FeatureFlags,
+}
+
+/// Pairs TTS text with the audio synthesized for it
+///
+/// Providers may complete an utterance before the text is counted (the text
+/// is counted once the provider accepted it), so a completion without text
+/// waits for the text instead of closing an empty utterance.
+#[derive(Default)]
+struct TtsUtterances {
+    current: TtsUtterance,
+    completed: bool,
+    finished: Vec<TtsUtterance>,
+}
+
+impl TtsUtterances {
+    fn finish(&mut self) {
+        let utterance = std::mem::take(&mut self.current);
+        self.completed = false;
+        if self.finished.len() < MAX_RECORDED_TURN_IDS {
+            self.finished.push(utterance);
+        }
+    }
+}
+
+impl UsageMeter {
+    fn new(
+        stt: Option<ProviderModel>,
+        tts: Option<ProviderModel>,
+        realtime: Option<ProviderModel>,
+        input_byte_rate: u64,
+    ) -> Self {
+        Self {
+            started_at: AtomicU64::new(now_ms()),
+            on_hold: AtomicBool::new(false),
+            ended_at: AtomicU64::new(0),
+            stt,
+            tts,
+            realtime,
+            input_byte_rate,
+            stt_audio_bytes: AtomicU64::new(0),
+            stt_failover: None,
+            stt_routing: None,
+            stt_turns: Mutex::new(Vec::new()),
+            tts_characters: AtomicU64::new(0),
+            tts_audio_ms: AtomicU64::new(0),
+            tts_utterances: Mutex::new(TtsUtterances::default()),
+            feature_flags: FeatureFlags::default(),
+        }
+    }
+
+    /// Meter for a voice session
+    pub(super) fn voice(stt_config: &STTConfig, tts_config: &TTSConfig) -> Self {
+        let input_byte_rate = stt_config.sample_rate as u64
+            * stt_config.channels.max(1) as u64
+            * bytes_per_sample(&stt_config.encoding);
+        Self::new(
+            Some(ProviderModel::new(&stt_config.provider, &stt_config.model)),
+            Some(ProviderModel::new(&tts_config.provider, &tts_config.model)),
+            None,
+            input_byte_rate,
+        )
+    }
+
+    /// Bill STT audio to the primary and secondary provider by what each received
+    pub(super) fn with_stt_failover(
+        mut self,
+        secondary: &STTConfig,
+        counters: Arc<STTFailoverUsage>,
+    ) -> Self {
+        self.stt_failover = Some((
+            ProviderModel::new(&secondary.provider, &secondary.model),
+            counters,
+        ));
+        self
+    }
+
+    /// Bill STT audio to the premium and fast provider by what each received,
+    /// and record the provider of each user turn
+    pub(super) fn with_stt_routing(mut self, fast: &STTConfig, router: Arc<TurnRouter>) -> Self {
+        self.stt_routing = Some((ProviderModel::new(&fast.provider, &fast.model), router));
+        self
+    }
+
+    /// Report the feature flags the session was built with
+    pub(super) fn with_feature_flags(mut self, flags: FeatureFlags) -> Self {
+        self.feature_flags = flags;
+        self
+    }
+
+    /// Meter for a realtime session
+    pub(super) fn realtime(config: &RealtimeConfig) -> Self {
+        Self::new(
+            None,
+            None,
+            Some(ProviderModel::new(&config.provider, &config.model)),
+            0,
+        )
+    }
+
+    /// Stop counting until [`release_hold`](Self::release_hold)
+    pub(super) fn hold(&self) {
+        self.on_hold.store(true, Ordering::Release);
+    }
+
+    /// Count again, from now: a held session's `started_at` moves to the
+    /// moment the hold ends
+    pub(super) fn release_hold(&self) {
+        if self.on_hold.swap(false, Ordering::AcqRel) {
+            self.started_at.store(now_ms(), Ordering::Release);
+        }
+    }
+
+    /// Whether the session is held
+    pub(super) fn is_on_hold(&self) -> bool {
+        self.on_hold.load(Ordering::Acquire)
+    }
+
+    /// Count input audio sent to the STT provider
+    pub(super) fn add_stt_audio(&self, bytes: usize) {
+        if self.stt.is_some() && !self.is_on_hold() {
+            self.stt_audio_bytes
+                .fetch_add(bytes as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Record the STT provider of a user turn from its first transcript
+    ///
+    /// Does nothing without per-turn STT routing.
+    pub(super) fn record_stt_turn(&self, turn_id: &str) {
+        let Some((fast, router)) = &self.stt_routing else {
+            return;
+        };
+        let mut turns = self.stt_turns.lock();
+        // A turn's transcripts arrive together, so only the latest can repeat
+        if turns.len() >= MAX_RECORDED_TURN_IDS
+            || turns.last().is_some_and(|turn| turn.turn_id == turn_id)
+        {
+            return;
+        }
+        let route = router.last_result_route();
+        turns.push(TurnSTTUsage {
+            turn_id: turn_id.to_string(),
+            stt: match route {
+                STTRoute::Premium => self.stt.clone().unwrap_or_else(|| fast.clone()),
+                STTRoute::Fast => fast.clone(),
+            },
+            route,
+            expected: router.expected(),
+        });
+    }
+
+    /// Count text accepted by the TTS provider
+    pub(super) fn add_tts_text(&self, text: &str) {
+        if self.is_on_hold() {
+            return;
+        }
+        let characters = text.chars().count() as u64;
+        self.tts_characters.fetch_add(characters, Ordering::Relaxed);
+
+        let mut utterances = self.tts_utterances.lock();
+        utterances.current.characters += characters;
+        if utterances.completed {
+            utterances.finish();
+        }
+    }
+
+    /// Count an output audio chunk
+    pub(super) fn add_tts_audio(&self, audio: &AudioData) {
+        if self.is_on_hold() {
+            return;
+        }
+        let duration_ms = audio_duration_ms(audio);
+        self.tts_audio_ms.fetch_add(duration_ms, Ordering::Relaxed);
+        self.tts_utterances.lock().current.audio_ms += duration_ms;
+    }
+
+    /// Close the current TTS utterance when the provider completes it
+    pub(super) fn complete_tts_utterance(&self) {
+        let mut utterances = self.tts_utterances.lock();
+        if utterances.current.characters > 0 {
+            utterances.finish();
+        } else if utterances.current.audio_ms > 0 {
+            utterances.completed = true;
+        }
+    }
+
+    /// Forget the current TTS utterance after its audio was cleared
+    pub(super) fn discard_tts_utterance(&self) {
+        let mut utterances = self.tts_utterances.lock();
+        utterances.current = TtsUtterance::default();
+        utterances.completed = false;
+    }
+
+    /// Record the end of the session; later calls keep the first end time
+    pub(super) fn close(&self) {
+        let _ = self
+            .ended_at
+            .compare_exchange(0, now_ms(), Ordering::AcqRel, Ordering::Acquire);
+    }
+
+    /// Snapshot the counters
+    pub(super) fn snapshot(&self) -> SessionUsage {
+        let audio_seconds = |bytes: u64| {
+            if self.input_byte_rate == 0 {
+                0.0
+            } else {
+                bytes as f64 / self.input_byte_rate as f64
+            }
+        };
+        let (stt_audio_bytes, stt_secondary, stt_secondary_bytes) = match &self.stt_failover {
+            Some((secondary, counters)) => (
+                counters.primary_bytes(),
+                Some(secondary.clone()),
+                counters.secondary_bytes(),
+            ),
+            None => match &self.stt_routing {
+                Some((_, router)) => (router.premium_bytes(), None, 0),
+                None => (self.stt_audio_bytes.load(Ordering::Relaxed), None, 0),
+            },
+        };
+        let (stt_fast, stt_fast_bytes) = match &self.stt_routing {
+            Some((fast, router)) => (Some(fast.clone()), router.fast_bytes()),
+            None => (None, 0),
+        };
+        let ended_at = self.ended_at.load(Ordering::Acquire);
+
+        SessionUsage {
+            started_at: self.started_at.load(Ordering::Acquire),
+            ended_at: (ended_at != 0).then_some(ended_at),
+            stt: self.stt.clone(),
+            stt_audio_seconds: audio_seconds(stt_audio_bytes),
+            stt_secondary,
+            stt_secondary_audio_seconds: audio_seconds(stt_secondary_bytes),
+            stt_fast,
+            stt_fast_audio_seconds: audio_seconds(stt_fast_bytes),
+            stt_turns: self.stt_turns.lock().clone(),
+            tts: self.tts.clone(),
+            tts_characters: self.tts_characters.load(Ordering::Relaxed),
+            tts_audio_seconds: self.tts_audio_ms.load(Ordering::Relaxed) as f64 / 1000.0,
+            tts_utterances: self.tts_utterances.lock().finished.clone(),
+            realtime: self.realtime.clone(),
+            turns: 0,
+            turn_ids: Vec::new(),
+            feature_flags: self.feature_flags.clone(),
+            consent: None,
+        }
+    }
+}
+
+/// Bytes per sample of an audio encoding; G.711 is 8-bit, everything else 16-bit PCM
+pub(super) fn bytes_per_sample(encoding: &str) -> u64 {
+    if matches!(encoding, "mulaw" | "ulaw" | "alaw") {
+        1
+    } else {
+        2
+    }
+}
+
+/// Playback length of an output audio chunk in milliseconds
+///
+/// WAV and MP3 chunks are measured from their header or frames. Otherwise
+/// uses the duration the provider reported, or derives it from the size.
+pub(super) fn audio_duration_ms(audio: &AudioData) -> u64 {
+    if let Some(duration_ms) = container_duration_ms(&audio.format, &audio.data) {
+        return duration_ms;
+    }
+    match audio.duration_ms {
+        Some(duration_ms) => duration_ms as u64,
+        None => {
+            let byte_rate = audio.sample_rate.max(1) as u64 * bytes_per_sample(&audio.format);
+            audio.data.len() as u64 * 1000 / byte_rate
+        }
+    }
+}
+
+/// Current Unix time in milliseconds
+pub(super) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voice_meter(encoding: &str) -> UsageMeter {
+        UsageMeter::voice(
+            &STTConfig {
+                provider: "deepgram".to_string(),
+                model: "nova-3".to_string(),
+                sample_rate: 16000,
+                channels: 1,
+                encoding: encoding.to_string(),
+                ..Default::default()
+            },
+            &TTSConfig {
+                provider: "elevenlabs".to_string(),
+                model: "eleven_turbo_v2".to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_stt_seconds_follow_input_format() {
+        let meter = voice_meter("linear16");
+        meter.add_stt_audio(32000);
+        assert_eq!(meter.snapshot().stt_audio_seconds, 1.0);
+
+        let meter = voice_meter("mulaw");
+        meter.add_stt_audio(32000);
+        assert_eq!(meter.snapshot().stt_audio_seconds, 2.0);
+    }
+
+    #[test]
+    fn test_tts_audio_uses_duration_or_byte_length() {
+        let meter = voice_meter("linear16");
+        meter.add_tts_text("Héllo");
+        meter.add_tts_audio(&AudioData {
+            data: vec![0; 10],
+            sample_rate: 24000,
+            format: "linear16".to_string(),
+            duration_ms: Some(250),
+        });
+        meter.add_tts_audio(&AudioData {
+            data: vec![0; 8000],
+            sample_rate: 8000,
+            format: "mulaw".to_string(),
+            duration_ms: None,
+        });
+
+        let usage = meter.snapshot();
+        assert_eq!(usage.tts_characters, 5);
+        assert_eq!(usage.tts_audio_seconds, 1.25);
+        assert_eq!(
+            usage.tts,
+            Some(ProviderModel::new("elevenlabs", "eleven_turbo_v2"))
+        );
+        assert!(usage.realtime.is_none());
+    }
+
+    #[test]
+    fn test_stt_failover_bills_each_provider() {
+        let counters = Arc::new(STTFailoverUsage::default());
+        let meter = voice_meter("linear16").with_stt_failover(
+            &STTConfig {
+                provider: "assemblyai".to_string(),
+                model: "universal-streaming".to_string(),
+                ..Default::default()
+            },
+            counters.clone(),
+        );
+        // Warm standby: both providers receive the first second, then the
+        // primary fails and the secondary transcribes another second alone
+        meter.add_stt_audio(64000);
+        counters.add_primary(32000);
+        counters.add_secondary(64000);
+
+        let usage = meter.snapshot();
+        assert_eq!(usage.stt_audio_seconds, 1.0);
+        assert_eq!(
+            usage.stt_secondary,
+            Some(ProviderModel::new("assemblyai", "universal-streaming"))
+        );
+        assert_eq!(usage.stt_secondary_audio_seconds, 2.0);
+    }
+
+    #[test]
+    fn test_stt_routing_bills_each_provider_and_records_turns() {
+        let fast = STTConfig {
+            provider: "deepgram".to_string(),
+            model: "base".to_string(),
+            ..Default::default()
+        };
+        let router = Arc::new(TurnRouter::new(&fast));
+        let meter = voice_meter("linear16").with_stt_routing(&fast, router.clone());
+        meter.add_stt_audio(96000);
+        router.record_audio(STTRoute::Premium, 64000);
+        router.record_audio(STTRoute::Fast, 32000);
+        meter.record_stt_turn("turn-1");
+        meter.record_stt_turn("turn-1");
+
+        let usage = meter.snapshot();
+        assert_eq!(usage.stt_audio_seconds, 2.0);
+        assert_eq!(usage.stt_fast, Some(ProviderModel::new("deepgram", "base")));
+        assert_eq!(usage.stt_fast_audio_seconds, 1.0);
+        assert_eq!(
+            usage.stt_turns,
+            vec![TurnSTTUsage {
+                turn_id: "turn-1".to_string(),
+                stt: ProviderModel::new("deepgram", "nova-3"),
+                route: STTRoute::Premium,
+                expected: None,
+            }]
+        );
+
+        // Without routing no turns are recorded
+        let meter = voice_meter("linear16");
+        meter.record_stt_turn("turn-1");
+        assert!(meter.snapshot().stt_turns.is_empty());
+    }
+
+    #[test]
+    fn test_tts_utterances_pair_text_with_audio() {
+        let meter = voice_meter("linear16");
+        let audio = |duration_ms| AudioData {
+            data: Vec::new(),
+            sample_rate: 24000,
+            format: "linear16".to_string(),
+            duration_ms: Some(duration_ms),
+        };
+
+        meter.add_tts_text("Hello there.");
+        meter.add_tts_audio(&audio(400));
+        meter.add_tts_audio(&audio(300));
+        meter.complete_tts_utterance();
+
+        // The provider completed before the text was counted
+        meter.add_tts_audio(&audio(500));
+        meter.complete_tts_utterance();
+        meter.add_tts_text("Bye.");
+
+        // Interrupted utterances are left out
+        meter.add_tts_text("Never heard");
+        meter.add_tts_audio(&audio(200));
+        meter.discard_tts_utterance();
+
+        assert_eq!(
+            meter.snapshot().tts_utterances,
+            vec![
+                TtsUtterance {
+                    characters: 12,
+                    audio_ms: 700,
+                },
+                TtsUtterance {
+                    characters: 4,
+                    audio_ms: 500,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_container_audio_is_measured_from_its_contents() {
+        let meter = voice_meter("linear16");
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        // The byte count and the reported duration are both ignored
+        meter.add_tts_audio(&AudioData {
+            data: frame.repeat(38),
+            sample_rate: 44100,
+            format: "mp3".to_string(),
+            duration_ms: Some(10),
+        });
+        assert_eq!(meter.snapshot().tts_audio_seconds, 0.993);
+    }
+
+    #[test]
+    fn test_close_keeps_first_end_time() {
+        let meter = UsageMeter::realtime(&RealtimeConfig::default());
+        assert!(meter.snapshot().ended_at.is_none());
+        meter.close();
+        let ended_at = meter.snapshot().ended_at.unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        meter.close();
+        assert_eq!(meter.snapshot().ended_at, Some(ended_at));
+
+        // Realtime sessions do not meter STT input
+        meter.add_stt_audio(32000);
+        assert_eq!(meter.snapshot().stt_audio_seconds, 0.0);
+    }
+
+    #[test]
+    fn test_held_session_counts_from_release() {
+        let meter = voice_meter("linear16");
+        let built_at = meter.snapshot().started_at;
+        meter.hold();
+        meter.add_stt_audio(32000);
+        meter.add_tts_text("Ringing");
+        meter.add_tts_audio(&AudioData {
+            data: vec![0; 10],
+            sample_rate: 24000,
+            format: "linear16".to_string(),
+            duration_ms: Some(250),
+        });
+        let held = meter.snapshot();
+        assert_eq!(held.stt_audio_seconds, 0.0);
+        assert_eq!(held.tts_characters, 0);
+        assert_eq!(held.tts_audio_seconds, 0.0);
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        meter.release_hold();
+        meter.add_stt_audio(32000);
+        let usage = meter.snapshot();
+        assert_eq!(usage.stt_audio_seconds, 1.0);
+        assert!(usage.started_at > built_at);
+
+        // Releasing again does not move the start
+        meter.release_hold();
+        assert_eq!(meter.snapshot().started_at, usage.started_at);
+    }
+}
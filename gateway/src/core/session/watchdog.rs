@@ -0,0 +1,765 @@
+//! Watchdog that recovers a session whose providers stopped responding
+//!
+//! A provider connection can wedge without closing: the socket stays open,
+//! audio and text are accepted, and nothing comes back. The watchdog tracks,
+//! per session, when input audio last arrived, when the STT provider last
+//! showed signs of life (a result or a VAD event) and when TTS last made
+//! progress (text sent, audio or a completion), and reconnects the provider
+//! that stalled:
+//!
+//! - **STT**: audio keeps flowing, at least `min_speech_ms` of it was caller
+//!   speech, and the STT provider has not answered for `stall_timeout_ms`
+//!   since that speech began. The provider is reconnected and the unanswered
+//!   audio (up to `replay_ms`) is sent again.
+//! - **TTS**: utterances are pending and TTS has made no progress for
+//!   `stall_timeout_ms`. The provider is reconnected and the pending
+//!   utterances are dropped, as on an interrupt.
+//!
+//! Only speech counts toward an STT stall, so a silent caller never looks
+//! like a stalled provider. Audio is speech while the STT provider reports
+//! the caller speaking (`SpeechStarted` without `UtteranceEnd`), or when
+//! 16-bit PCM input is louder than the speech threshold, which keeps working
+//! after the provider stopped sending VAD events.
+//!
+//! Each recovery is logged, counted in `waav_pipeline_recoveries_total` and
+//! in [`WatchdogStats`], and reported as [`SessionEvent::PipelineRecovered`].
+//! After `max_recoveries` the watchdog stops reconnecting and only logs.
+//!
+//! With a session [`RetryBudget`], each reconnect takes a token first (STT
+//! ahead of TTS). A stall the budget denies waits another `stall_timeout_ms`
+//! and is not counted as a recovery.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use super::audio_level::{is_pcm16, to_dbfs};
+use super::diarization::SPEECH_THRESHOLD_DB;
+use super::events::{EventEmitter, SessionEvent};
+use super::retry_budget::{RetryBudget, RetryPriority};
+use super::usage::bytes_per_sample;
+use crate::core::{
+    stt::{STTConfig, STTVadEvent},
+    voice_manager::VoiceManager,
+};
+use crate::metrics::global_metrics;
+
+/// Default time without an answer before a provider counts as stalled (ms)
+pub const DEFAULT_STALL_TIMEOUT_MS: u64 = 10_000;
+
+/// Default unanswered caller speech needed before STT counts as stalled (ms)
+pub const DEFAULT_MIN_SPEECH_MS: u64 = 2_000;
+
+/// Default unanswered audio sent again after an STT reconnect (ms)
+pub const DEFAULT_REPLAY_MS: u64 = 5_000;
+
+/// Default recoveries per session before the watchdog gives up
+pub const DEFAULT_MAX_RECOVERIES: u32 = 3;
+
+/// Input audio older than this means the caller's stream paused
+const AUDIO_FLOWING_MS: u64 = 1_000;
+
+/// Pipeline stage the watchdog recovers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WatchdogStage {
+    /// The STT provider stopped returning results
+    Stt,
+    /// The TTS provider stopped returning audio
+    Tts,
+}
+
+impl WatchdogStage {
+    /// The stage's metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stt => "stt",
+            Self::Tts => "tts",
+        }
+    }
+
+    /// Priority of the stage's reconnect in the session's retry budget
+    pub fn retry_priority(&self) -> RetryPriority {
+        match self {
+            Self::Stt => RetryPriority::SttReconnect,
+            Self::Tts => RetryPriority::TtsRetry,
+        }
+    }
+}
+
+/// When the pipeline watchdog treats a provider as stalled
+///
+/// # Example JSON
+/// ```json
+/// {"stall_timeout_ms": 10000, "min_speech_ms": 2000, "replay_ms": 5000,
+///  "max_recoveries": 3}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct PipelineWatchdogConfig {
+    /// Time without an answer before a provider counts as stalled (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 10000))]
+    pub stall_timeout_ms: u64,
+    /// Unanswered caller speech needed before STT counts as stalled (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 2000))]
+    pub min_speech_ms: u64,
+    /// Unanswered audio sent again to the reconnected STT provider (ms)
+    #[cfg_attr(feature = "openapi", schema(example = 5000))]
+    pub replay_ms: u64,
+    /// Recoveries per session before the watchdog only logs
+    #[cfg_attr(feature = "openapi", schema(example = 3))]
+    pub max_recoveries: u32,
+}
+
+impl Default for PipelineWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            stall_timeout_ms: DEFAULT_STALL_TIMEOUT_MS,
+            min_speech_ms: DEFAULT_MIN_SPEECH_MS,
+            replay_ms: DEFAULT_REPLAY_MS,
+            max_recoveries: DEFAULT_MAX_RECOVERIES,
+        }
+    }
+}
+
+impl PipelineWatchdogConfig {
+    /// Validate the thresholds
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("stall_timeout_ms", self.stall_timeout_ms),
+            ("min_speech_ms", self.min_speech_ms),
+            ("max_recoveries", self.max_recoveries as u64),
+        ] {
+            if value == 0 {
+                return Err(format!("{name} must be greater than 0"));
+            }
+        }
+        if self.min_speech_ms > self.stall_timeout_ms {
+            return Err(format!(
+                "min_speech_ms ({}) must not exceed stall_timeout_ms ({})",
+                self.min_speech_ms, self.stall_timeout_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A stalled provider was reconnected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PipelineRecovered {
+    /// Stage whose provider was reconnected
+    pub stage: WatchdogStage,
+    /// Time the stage had gone without an answer (ms)
+    pub stalled_ms: u64,
+    /// Unanswered audio sent again to the STT provider (bytes)
+    pub replayed_bytes: usize,
+    /// Recoveries of the session so far, including this one
+    pub recoveries: u32,
+}
+
+/// Recoveries the watchdog made in a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WatchdogStats {
+    /// STT provider reconnects after a stall
+    pub stt_recoveries: u64,
+    /// TTS provider reconnects after a stall
+    pub tts_recoveries: u64,
+    /// Reconnects that failed
+    pub failed_recoveries: u64,
+}
+
+/// Reconnects the provider of a stalled stage
+#[async_trait]
+pub(super) trait PipelineRecoverer: Send + Sync {
+    /// Utterances sent to TTS that have not completed
+    fn pending_tts(&self) -> usize;
+
+    /// Reconnect the provider of `stage`, replaying `replay` to a new STT connection
+    async fn recover(&self, stage: WatchdogStage, replay: &[Bytes]) -> Result<(), String>;
+}
+
+/// Recovers the providers of a voice session
+pub(super) struct VoicePipelineRecoverer {
+    voice_manager: Weak<VoiceManager>,
+}
+
+impl VoicePipelineRecoverer {
+    pub(super) fn new(voice_manager: &Arc<VoiceManager>) -> Self {
+        Self {
+            voice_manager: Arc::downgrade(voice_manager),
+        }
+    }
+}
+
+#[async_trait]
+impl PipelineRecoverer for VoicePipelineRecoverer {
+    fn pending_tts(&self) -> usize {
+        self.voice_manager
+            .upgrade()
+            .map_or(0, |voice_manager| voice_manager.tts_queue_stats().pending)
+    }
+
+    async fn recover(&self, stage: WatchdogStage, replay: &[Bytes]) -> Result<(), String> {
+        let Some(voice_manager) = self.voice_manager.upgrade() else {
+            return Err("session closed".to_string());
+        };
+        let result = match stage {
+            WatchdogStage::Stt => voice_manager.reconnect_stt(replay).await,
+            WatchdogStage::Tts => voice_manager.reconnect_tts().await,
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
+struct WatchdogState {
+    /// When input audio last arrived
+    last_audio: Option<Instant>,
+    /// Whether the STT provider reports the caller speaking
+    provider_speaking: bool,
+    /// First caller speech the STT provider has not answered
+    unanswered_since: Option<Instant>,
+    /// Caller speech the STT provider has not answered (ms)
+    unanswered_speech_ms: u64,
+    /// Audio sent since the STT provider last answered, newest last
+    unanswered_audio: VecDeque<Bytes>,
+    unanswered_bytes: usize,
+    /// When TTS last accepted text, produced audio or completed
+    last_tts_progress: Instant,
+    recoveries: u32,
+    /// Whether giving up was already logged
+    gave_up: bool,
+    stats: WatchdogStats,
+}
+
+impl WatchdogState {
+    /// The STT provider answered: nothing is unanswered anymore
+    fn stt_answered(&mut self) {
+        self.unanswered_since = None;
+        self.unanswered_speech_ms = 0;
+        self.unanswered_audio.clear();
+        self.unanswered_bytes = 0;
+    }
+}
+
+/// Watches one session's providers for stalls and reconnects them
+pub(super) struct PipelineWatchdog {
+    config: PipelineWatchdogConfig,
+    recoverer: Arc<dyn PipelineRecoverer>,
+    /// Budget the session's reconnects draw from, when configured
+    retry_budget: Option<Arc<RetryBudget>>,
+    emitter: EventEmitter,
+    /// Whether input audio can be measured for speech
+    measure_input: bool,
+    /// Input audio bytes per millisecond
+    input_bytes_per_ms: u64,
+    /// Most unanswered audio kept for replay (bytes)
+    replay_bytes: usize,
+    state: Mutex<WatchdogState>,
+    check: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl PipelineWatchdog {
+    pub(super) fn new(
+        config: PipelineWatchdogConfig,
+        stt_config: &STTConfig,
+        recoverer: Arc<dyn PipelineRecoverer>,
+        retry_budget: Option<Arc<RetryBudget>>,
+        emitter: EventEmitter,
+    ) -> Self {
+        let input_bytes_per_ms = (stt_config.sample_rate as u64
+            * stt_config.channels.max(1) as u64
+            * bytes_per_sample(&stt_config.encoding)
+            / 1000)
+            .max(1);
+        Self {
+            config,
+            recoverer,
+            retry_budget,
+            emitter,
+            measure_input: is_pcm16(&stt_config.encoding),
+            input_bytes_per_ms,
+            replay_bytes: (config.replay_ms * input_bytes_per_ms) as usize,
+            state: Mutex::new(WatchdogState {
+                last_audio: None,
+                provider_speaking: false,
+                unanswered_since: None,
+                unanswered_speech_ms: 0,
+                unanswered_audio: VecDeque::new(),
+                unanswered_bytes: 0,
+                last_tts_progress: Instant::now(),
+                recoveries: 0,
+                gave_up: false,
+                stats: WatchdogStats::default(),
+            }),
+            check: Mutex::new(None),
+        }
+    }
+
+    /// Start checking for stalls about four times per stall timeout
+    pub(super) fn start(self: &Arc<Self>) {
+        let period = Duration::from_millis(self.config.stall_timeout_ms / 4)
+            .clamp(Duration::from_millis(10), Duration::from_secs(1));
+        let watchdog = Arc::downgrade(self);
+        let check = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let Some(watchdog) = watchdog.upgrade() else {
+                    return;
+                };
+                watchdog.check().await;
+            }
+        });
+        if let Some(previous) = self.check.lock().replace(check) {
+            previous.abort();
+        }
+    }
+
+    /// Stop checking, e.g. when the session closes
+    pub(super) fn stop(&self) {
+        if let Some(check) = self.check.lock().take() {
+            check.abort();
+        }
+    }
+
+    /// Track input audio sent to the STT provider
+    pub(super) fn on_input_audio(&self, audio: &Bytes) {
+        let now = Instant::now();
+        let loud = self.measure_input && rms_dbfs(audio) >= SPEECH_THRESHOLD_DB;
+        let mut state = self.state.lock();
+        state.last_audio = Some(now);
+        state.unanswered_audio.push_back(audio.clone());
+        state.unanswered_bytes += audio.len();
+        while state.unanswered_bytes > self.replay_bytes
+            && let Some(oldest) = state.unanswered_audio.pop_front()
+        {
+            state.unanswered_bytes -= oldest.len();
+        }
+        if loud || state.provider_speaking {
+            state.unanswered_since.get_or_insert(now);
+            state.unanswered_speech_ms += audio.len() as u64 / self.input_bytes_per_ms;
+        }
+    }
+
+    /// Track an STT result, which shows the provider is alive
+    pub(super) fn on_stt_result(&self) {
+        self.state.lock().stt_answered();
+    }
+
+    /// Track a VAD event, which shows the provider is alive and whether the
+    /// caller is speaking
+    pub(super) fn on_vad_event(&self, event: &STTVadEvent) {
+        let mut state = self.state.lock();
+        state.provider_speaking = matches!(event, STTVadEvent::SpeechStarted { .. });
+        state.stt_answered();
+    }
+
+    /// Track text sent to TTS, synthesized audio or a completed utterance
+    pub(super) fn on_tts_progress(&self) {
+        self.state.lock().last_tts_progress = Instant::now();
+    }
+
+    /// Recoveries made so far
+    pub(super) fn stats(&self) -> WatchdogStats {
+        self.state.lock().stats
+    }
+
+    /// Reconnect the first stalled stage, if any
+    async fn check(&self) {
+        let now = Instant::now();
+        let (stage, stalled_ms, replay) = {
+            let mut state = self.state.lock();
+            let Some((stage, stalled_ms)) = self.stalled(&state, now) else {
+                return;
+            };
+            if state.recoveries >= self.config.max_recoveries {
+                if !state.gave_up {
+                    state.gave_up = true;
+                    warn!(
+                        stage = stage.as_str(),
+                        stalled_ms,
+                        recoveries = state.recoveries,
+                        "Pipeline stalled after the last allowed recovery; not reconnecting"
+                    );
+                }
+                return;
+            }
+            if let Some(budget) = &self.retry_budget
+                && !budget.try_acquire(stage.retry_priority())
+            {
+                warn!(
+                    stage = stage.as_str(),
+                    stalled_ms,
+                    "Pipeline stalled but the retry budget is used up; not reconnecting"
+                );
+                // Check again after another stall timeout
+                match stage {
+                    WatchdogStage::Stt => state.stt_answered(),
+                    WatchdogStage::Tts => state.last_tts_progress = now,
+                }
+                return;
+            }
+            state.recoveries += 1;
+            let replay: Vec<Bytes> = match stage {
+                WatchdogStage::Stt => state.unanswered_audio.iter().cloned().collect(),
+                WatchdogStage::Tts => Vec::new(),
+            };
+            (stage, stalled_ms, replay)
+        };
+
+        warn!(
+            stage = stage.as_str(),
+            stalled_ms, "Pipeline stalled; reconnecting the provider"
+        );
+        let result = self.recoverer.recover(stage, &replay).await;
+
+        let recovered = {
+            let mut state = self.state.lock();
+            // The new connection gets a fresh stall timeout
+            match stage {
+                WatchdogStage::Stt => state.stt_answered(),
+                WatchdogStage::Tts => state.last_tts_progress = Instant::now(),
+            }
+            state.provider_speaking = false;
+            match &result {
+                Ok(()) => {
+                    match stage {
+                        WatchdogStage::Stt => state.stats.stt_recoveries += 1,
+                        WatchdogStage::Tts => state.stats.tts_recoveries += 1,
+                    }
+                    Some(PipelineRecovered {
+                        stage,
+                        stalled_ms,
+                        replayed_bytes: replay.iter().map(Bytes::len).sum(),
+                        recoveries: state.recoveries,
+                    })
+                }
+                Err(_) => {
+                    state.stats.failed_recoveries += 1;
+                    None
+                }
+            }
+        };
+
+        global_metrics().inc_counter(
+            "waav_pipeline_recoveries_total",
+            "Stalled providers reconnected by the pipeline watchdog",
+            &[
+                ("stage", stage.as_str()),
+                ("result", if result.is_ok() { "ok" } else { "error" }),
+            ],
+        );
+        match recovered {
+            Some(recovered) => {
+                info!(
+                    stage = stage.as_str(),
+                    replayed_bytes = recovered.replayed_bytes,
+                    "Pipeline recovered"
+                );
+                self.emitter
+                    .emit(SessionEvent::PipelineRecovered(recovered))
+                    .await;
+            }
+            None => {
+                if let Err(e) = result {
+                    warn!(stage = stage.as_str(), "Pipeline recovery failed: {e}");
+                }
+            }
+        }
+    }
+
+    /// The stalled stage and how long it went without an answer
+    fn stalled(&self, state: &WatchdogState, now: Instant) -> Option<(WatchdogStage, u64)> {
+        let stall_timeout = Duration::from_millis(self.config.stall_timeout_ms);
+        let audio_flowing = state.last_audio.is_some_and(|last| {
+            now.saturating_duration_since(last) < Duration::from_millis(AUDIO_FLOWING_MS)
+        });
+        if audio_flowing
+            && state.unanswered_speech_ms >= self.config.min_speech_ms
+            && let Some(since) = state.unanswered_since
+            && now.saturating_duration_since(since) >= stall_timeout
+        {
+            let stalled_ms = now.saturating_duration_since(since).as_millis() as u64;
+            return Some((WatchdogStage::Stt, stalled_ms));
+        }
+
+        let tts_idle = now.saturating_duration_since(state.last_tts_progress);
+        if tts_idle >= stall_timeout && self.recoverer.pending_tts() > 0 {
+            return Some((WatchdogStage::Tts, tts_idle.as_millis() as u64));
+        }
+        None
+    }
+}
+
+impl Drop for PipelineWatchdog {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// RMS level of little-endian PCM16 audio in dBFS
+fn rms_dbfs(pcm: &[u8]) -> f32 {
+    let mut sum_squares = 0.0;
+    let mut samples = 0u32;
+    for sample in pcm.chunks_exact(2) {
+        let sample = i16::from_le_bytes([sample[0], sample[1]]) as f64;
+        sum_squares += sample * sample;
+        samples += 1;
+    }
+    if samples == 0 {
+        return to_dbfs(0.0);
+    }
+    to_dbfs((sum_squares / samples as f64).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session::{RetryBudgetConfig, SessionEventStream};
+    use futures::FutureExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Stall timeout in the tests (ms)
+    const STALL_MS: u64 = 200;
+
+    /// One 20ms chunk of 16kHz PCM16
+    const CHUNK: Duration = Duration::from_millis(20);
+
+    #[derive(Default)]
+    struct RecordingRecoverer {
+        pending_tts: AtomicUsize,
+        recovered: Mutex<Vec<(WatchdogStage, usize)>>,
+    }
+
+    #[async_trait]
+    impl PipelineRecoverer for RecordingRecoverer {
+        fn pending_tts(&self) -> usize {
+            self.pending_tts.load(Ordering::SeqCst)
+        }
+
+        async fn recover(&self, stage: WatchdogStage, replay: &[Bytes]) -> Result<(), String> {
+            self.recovered.lock().push((stage, replay.len()));
+            Ok(())
+        }
+    }
+
+    fn new_watchdog() -> (
+        Arc<PipelineWatchdog>,
+        Arc<RecordingRecoverer>,
+        SessionEventStream,
+    ) {
+        new_watchdog_with_budget(None)
+    }
+
+    fn new_watchdog_with_budget(
+        retry_budget: Option<Arc<RetryBudget>>,
+    ) -> (
+        Arc<PipelineWatchdog>,
+        Arc<RecordingRecoverer>,
+        SessionEventStream,
+    ) {
+        let recoverer = Arc::new(RecordingRecoverer::default());
+        let (emitter, events) = EventEmitter::channel(16);
+        let config = PipelineWatchdogConfig {
+            stall_timeout_ms: STALL_MS,
+            min_speech_ms: STALL_MS / 2,
+            replay_ms: 100,
+            max_recoveries: 2,
+        };
+        let stt_config = STTConfig {
+            sample_rate: 16000,
+            encoding: "linear16".to_string(),
+            ..Default::default()
+        };
+        let watchdog = Arc::new(PipelineWatchdog::new(
+            config,
+            &stt_config,
+            recoverer.clone(),
+            retry_budget,
+            emitter,
+        ));
+        watchdog.start();
+        (watchdog, recoverer, events)
+    }
+
+    /// 20ms of 16kHz PCM16 at `amplitude` (fraction of full scale)
+    fn chunk(amplitude: f64) -> Bytes {
+        let value = (amplitude * i16::MAX as f64) as i16;
+        (0..320)
+            .flat_map(|i| {
+                let sample = if i % 2 == 0 { value } else { -value };
+                sample.to_le_bytes()
+            })
+            .collect()
+    }
+
+    /// Stream `duration` of audio in real time
+    async fn stream(watchdog: &PipelineWatchdog, amplitude: f64, duration: Duration) {
+        let started = Instant::now();
+        while started.elapsed() < duration {
+            watchdog.on_input_audio(&chunk(amplitude));
+            tokio::time::sleep(CHUNK).await;
+        }
+    }
+
+    fn recovered(events: &mut SessionEventStream) -> Vec<PipelineRecovered> {
+        let mut recovered = Vec::new();
+        while let Some(Some(event)) = events.recv().now_or_never() {
+            if let SessionEvent::PipelineRecovered(event) = event {
+                recovered.push(event);
+            }
+        }
+        recovered
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(PipelineWatchdogConfig::default().validate().is_ok());
+        let config: PipelineWatchdogConfig =
+            serde_json::from_str(r#"{"stall_timeout_ms": 5000}"#).unwrap();
+        assert_eq!(config.stall_timeout_ms, 5000);
+        assert_eq!(config.max_recoveries, DEFAULT_MAX_RECOVERIES);
+
+        for config in [
+            PipelineWatchdogConfig {
+                stall_timeout_ms: 0,
+                ..Default::default()
+            },
+            PipelineWatchdogConfig {
+                min_speech_ms: 20_000,
+                ..Default::default()
+            },
+            PipelineWatchdogConfig {
+                max_recoveries: 0,
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_silence_is_not_a_stall() {
+        let (watchdog, recoverer, mut events) = new_watchdog();
+        stream(&watchdog, 0.0001, Duration::from_millis(3 * STALL_MS)).await;
+
+        assert!(recoverer.recovered.lock().is_empty());
+        assert!(recovered(&mut events).is_empty());
+        assert_eq!(watchdog.stats(), WatchdogStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_answered_speech_is_not_a_stall() {
+        let (watchdog, recoverer, _events) = new_watchdog();
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_millis(3 * STALL_MS) {
+            stream(&watchdog, 0.3, Duration::from_millis(STALL_MS / 2)).await;
+            watchdog.on_stt_result();
+        }
+        assert!(recoverer.recovered.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_speech_reconnects_stt_with_replay() {
+        let (watchdog, recoverer, mut events) = new_watchdog();
+        // The provider answers, then stops responding mid-stream
+        stream(&watchdog, 0.3, Duration::from_millis(STALL_MS / 2)).await;
+        watchdog.on_stt_result();
+        stream(
+            &watchdog,
+            0.3,
+            Duration::from_millis(STALL_MS + STALL_MS / 2),
+        )
+        .await;
+
+        let calls = recoverer.recovered.lock().clone();
+        assert_eq!(calls.len(), 1, "{calls:?}");
+        assert_eq!(calls[0].0, WatchdogStage::Stt);
+        // replay_ms of 20ms chunks
+        assert_eq!(calls[0].1, 5);
+
+        let recovered = recovered(&mut events);
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].stage, WatchdogStage::Stt);
+        assert_eq!(recovered[0].replayed_bytes, 5 * 640);
+        assert!(recovered[0].stalled_ms >= STALL_MS);
+        assert_eq!(watchdog.stats().stt_recoveries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_provider_speaking_state_counts_quiet_audio() {
+        let (watchdog, recoverer, _events) = new_watchdog();
+        watchdog.on_vad_event(&STTVadEvent::SpeechStarted { timestamp: None });
+        stream(
+            &watchdog,
+            0.0001,
+            Duration::from_millis(STALL_MS + STALL_MS / 2),
+        )
+        .await;
+        assert_eq!(recoverer.recovered.lock().len(), 1);
+
+        // After the provider reports the end of speech, quiet audio is silence again
+        watchdog.on_vad_event(&STTVadEvent::UtteranceEnd {
+            last_word_end: None,
+        });
+        stream(&watchdog, 0.0001, Duration::from_millis(2 * STALL_MS)).await;
+        assert_eq!(recoverer.recovered.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_tts_reconnects_and_gives_up_after_max_recoveries() {
+        let (watchdog, recoverer, mut events) = new_watchdog();
+        recoverer.pending_tts.store(1, Ordering::SeqCst);
+        watchdog.on_tts_progress();
+        tokio::time::sleep(Duration::from_millis(STALL_MS / 2)).await;
+        assert!(recoverer.recovered.lock().is_empty());
+
+        tokio::time::sleep(Duration::from_millis(4 * STALL_MS)).await;
+        let stages: Vec<WatchdogStage> = recoverer
+            .recovered
+            .lock()
+            .iter()
+            .map(|(stage, _)| *stage)
+            .collect();
+        assert_eq!(stages, vec![WatchdogStage::Tts, WatchdogStage::Tts]);
+        assert_eq!(watchdog.stats().tts_recoveries, 2);
+        assert_eq!(recovered(&mut events).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_denies_tts_reconnect() {
+        // One token for TTS, the other kept for STT
+        let budget = Arc::new(RetryBudget::new(RetryBudgetConfig {
+            capacity: 2,
+            refill_per_minute: 1,
+            stt_reserve: 1,
+            tts_reserve: 0,
+        }));
+        let (watchdog, recoverer, _events) = new_watchdog_with_budget(Some(budget.clone()));
+        recoverer.pending_tts.store(1, Ordering::SeqCst);
+        watchdog.on_tts_progress();
+
+        tokio::time::sleep(Duration::from_millis(4 * STALL_MS)).await;
+        assert_eq!(recoverer.recovered.lock().len(), 1);
+        assert_eq!(watchdog.stats().tts_recoveries, 1);
+        let stats = budget.stats();
+        assert_eq!(stats.tts_retry.spent, 1);
+        assert!(stats.tts_retry.denied >= 1, "{stats:?}");
+        assert_eq!(stats.available, 1);
+    }
+}
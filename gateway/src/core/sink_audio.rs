@@ -0,0 +1,292 @@
+//! Audio transform chain of sinks that take a cheaper rendition of the audio
+//!
+//! [`SinkAudioTransform`] applies the downmix and downsample steps of a
+//! [`SinkAudioPolicy`] to a stream of 16-bit little-endian PCM: channels are
+//! averaged into one, then each remaining channel is resampled with the
+//! streaming resampler used for telephony audio. Opus encoding is left to
+//! the sink (see [`crate::config::AudioSinksConfig`]).
+
+use crate::config::SinkAudioPolicy;
+use crate::core::tts::telephony::Resampler;
+
+/// Sample rate and channel count of 16-bit PCM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinkAudioFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl SinkAudioFormat {
+    /// Mono audio at `sample_rate`
+    pub fn mono(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            channels: 1,
+        }
+    }
+
+    /// Size of one sample of every channel in bytes
+    fn frame_bytes(&self) -> usize {
+        2 * self.channels.max(1) as usize
+    }
+}
+
+/// Whether audio of `encoding` is 16-bit PCM the transform can work on
+pub fn is_pcm16(encoding: &str) -> bool {
+    matches!(encoding, "linear16" | "pcm")
+}
+
+/// Streaming transform from session audio to a sink's audio
+///
+/// Keeps resampler state and partial frames between chunks, so one transform
+/// must be used per audio stream. A change of source format starts over.
+pub struct SinkAudioTransform {
+    policy: SinkAudioPolicy,
+    source: Option<SinkAudioFormat>,
+    /// One resampler per output channel, when resampling
+    resamplers: Vec<Resampler>,
+    /// Bytes of a frame split across chunks
+    partial: Vec<u8>,
+}
+
+impl SinkAudioTransform {
+    pub fn new(policy: SinkAudioPolicy) -> Self {
+        Self {
+            policy,
+            source: None,
+            resamplers: Vec::new(),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Format the policy turns `source` audio into
+    ///
+    /// Audio is never upsampled: a sample rate above the source's keeps the
+    /// source rate.
+    pub fn output_format(policy: &SinkAudioPolicy, source: SinkAudioFormat) -> SinkAudioFormat {
+        SinkAudioFormat {
+            sample_rate: policy
+                .sample_rate
+                .map_or(source.sample_rate, |rate| rate.min(source.sample_rate)),
+            channels: if policy.mono { 1 } else { source.channels },
+        }
+    }
+
+    /// Transform a chunk of PCM
+    ///
+    /// # Returns
+    /// * The format of the output and the transformed audio. Resampled audio
+    ///   lags the input by a sample or two until [`flush`](Self::flush).
+    pub fn process(&mut self, source: SinkAudioFormat, pcm: &[u8]) -> (SinkAudioFormat, Vec<u8>) {
+        let output = Self::output_format(&self.policy, source);
+        if output == source {
+            return (output, pcm.to_vec());
+        }
+        if self.source != Some(source) {
+            self.source = Some(source);
+            self.partial.clear();
+            self.resamplers = if output.sample_rate == source.sample_rate {
+                Vec::new()
+            } else {
+                (0..output.channels)
+                    .map(|_| Resampler::new(source.sample_rate, output.sample_rate))
+                    .collect()
+            };
+        }
+
+        let frame_bytes = source.frame_bytes();
+        let mut bytes = std::mem::take(&mut self.partial);
+        bytes.extend_from_slice(pcm);
+        let whole = bytes.len() - bytes.len() % frame_bytes;
+        self.partial = bytes.split_off(whole);
+
+        let channels = deinterleave(&bytes, source.channels.max(1) as usize);
+        let channels = if output.channels == 1 && channels.len() > 1 {
+            vec![downmix(&channels)]
+        } else {
+            channels
+        };
+        let channels = if self.resamplers.is_empty() {
+            channels
+        } else {
+            channels
+                .iter()
+                .zip(&mut self.resamplers)
+                .map(|(samples, resampler)| {
+                    let mut out = Vec::with_capacity(samples.len());
+                    resampler.process(samples, &mut out);
+                    out
+                })
+                .collect()
+        };
+        (output, interleave(&channels))
+    }
+
+    /// Emit the audio held back by the resamplers at the end of a stream
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.partial.clear();
+        let channels: Vec<Vec<i16>> = self
+            .resamplers
+            .iter_mut()
+            .map(|resampler| {
+                let mut out = Vec::new();
+                resampler.flush(&mut out);
+                out
+            })
+            .collect();
+        interleave(&channels)
+    }
+}
+
+fn deinterleave(bytes: &[u8], channels: usize) -> Vec<Vec<i16>> {
+    let mut split = vec![Vec::with_capacity(bytes.len() / 2 / channels); channels];
+    for (index, pair) in bytes.chunks_exact(2).enumerate() {
+        split[index % channels].push(i16::from_le_bytes([pair[0], pair[1]]));
+    }
+    split
+}
+
+fn downmix(channels: &[Vec<i16>]) -> Vec<i16> {
+    let len = channels.iter().map(Vec::len).min().unwrap_or(0);
+    (0..len)
+        .map(|i| {
+            let sum: i32 = channels.iter().map(|channel| channel[i] as i32).sum();
+            (sum / channels.len() as i32) as i16
+        })
+        .collect()
+}
+
+fn interleave(channels: &[Vec<i16>]) -> Vec<u8> {
+    let len = channels.iter().map(Vec::len).min().unwrap_or(0);
+    let mut bytes = Vec::with_capacity(len * channels.len() * 2);
+    for i in 0..len {
+        for channel in channels {
+            bytes.extend_from_slice(&channel[i].to_le_bytes());
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16-bit PCM of `duration_ms` of a tone; channel `c` has amplitude `1000 * (c + 1)`
+    fn tone(format: SinkAudioFormat, duration_ms: u32) -> Vec<u8> {
+        let frames = format.sample_rate * duration_ms / 1000;
+        let mut bytes = Vec::new();
+        for i in 0..frames {
+            let phase = 2.0 * std::f32::consts::PI * 440.0 * i as f32 / format.sample_rate as f32;
+            for channel in 0..format.channels {
+                let sample = (phase.sin() * 1000.0 * (channel + 1) as f32) as i16;
+                bytes.extend_from_slice(&sample.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Run a whole stream through the transform in 20ms chunks plus an odd split
+    fn transform(
+        policy: SinkAudioPolicy,
+        source: SinkAudioFormat,
+        duration_ms: u32,
+    ) -> (SinkAudioFormat, Vec<u8>) {
+        let input = tone(source, duration_ms);
+        let mut transform = SinkAudioTransform::new(policy);
+        let chunk = (source.sample_rate / 50) as usize * source.frame_bytes() + 1;
+        let mut output = Vec::new();
+        let mut format = source;
+        for piece in input.chunks(chunk) {
+            let (out_format, audio) = transform.process(source, piece);
+            format = out_format;
+            output.extend(audio);
+        }
+        output.extend(transform.flush());
+        (format, output)
+    }
+
+    #[test]
+    fn test_every_policy_combination() {
+        let sources = [
+            SinkAudioFormat::mono(16000),
+            SinkAudioFormat {
+                sample_rate: 48000,
+                channels: 2,
+            },
+        ];
+        for source in sources {
+            for sample_rate in [None, Some(8000), Some(16000)] {
+                for mono in [false, true] {
+                    let policy = SinkAudioPolicy {
+                        sample_rate,
+                        mono,
+                        opus_bitrate_kbps: None,
+                    };
+                    let (format, audio) = transform(policy, source, 1000);
+
+                    let expected_rate = sample_rate.unwrap_or(source.sample_rate);
+                    let expected_channels = if mono { 1 } else { source.channels };
+                    assert_eq!(
+                        format,
+                        SinkAudioFormat {
+                            sample_rate: expected_rate,
+                            channels: expected_channels,
+                        },
+                        "{policy:?} on {source:?}"
+                    );
+                    // One second of audio stays one second long
+                    let frames = audio.len() / format.frame_bytes();
+                    assert_eq!(audio.len() % format.frame_bytes(), 0);
+                    assert_eq!(frames, expected_rate as usize, "{policy:?} on {source:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_passthrough_and_no_upsampling() {
+        let source = SinkAudioFormat::mono(8000);
+        let input = tone(source, 100);
+        let policy = SinkAudioPolicy {
+            sample_rate: Some(16000),
+            mono: true,
+            opus_bitrate_kbps: None,
+        };
+        let mut transform = SinkAudioTransform::new(policy);
+        assert_eq!(transform.process(source, &input), (source, input.clone()));
+
+        let mut passthrough = SinkAudioTransform::new(SinkAudioPolicy::default());
+        let stereo = SinkAudioFormat {
+            sample_rate: 44100,
+            channels: 2,
+        };
+        assert_eq!(
+            passthrough.process(stereo, &[1, 2, 3]),
+            (stereo, vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        let stereo = SinkAudioFormat {
+            sample_rate: 16000,
+            channels: 2,
+        };
+        let mut pcm = Vec::new();
+        for (left, right) in [(100i16, 300i16), (-200, 0), (i16::MAX, i16::MAX)] {
+            pcm.extend_from_slice(&left.to_le_bytes());
+            pcm.extend_from_slice(&right.to_le_bytes());
+        }
+        let mut transform = SinkAudioTransform::new(SinkAudioPolicy {
+            mono: true,
+            ..Default::default()
+        });
+        let (format, audio) = transform.process(stereo, &pcm);
+        assert_eq!(format, SinkAudioFormat::mono(16000));
+        let samples: Vec<i16> = audio
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        assert_eq!(samples, vec![200, -100, i16::MAX]);
+    }
+}
@@ -1,46 +1,57 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-#[cfg(feature = "turn-detect")]
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use tokio::sync::RwLock;
-#[cfg(not(feature = "turn-detect"))]
-use tracing::info;
-#[cfg(feature = "turn-detect")]
-use tracing::{debug, info, warn};
 
 use crate::config::ServerConfig;
 use crate::core::cache::store::{CacheConfig, CacheStore};
-use crate::core::tts::get_tts_provider_urls;
-#[cfg(not(feature = "turn-detect"))]
-use crate::core::turn_detect::TurnDetector;
-#[cfg(feature = "turn-detect")]
-use crate::core::turn_detect::{TurnDetector, TurnDetectorConfig};
+use crate::core::tts::tts_provider_url;
+use crate::core::turn_detect::{
+    TurnDetector, TurnDetectorConfig, TurnDetectorHealth, load_turn_detector,
+};
 use crate::state::SipHooksState;
 use crate::utils::req_manager::ReqManager;
 
+/// Request managers unused for this long are dropped; the next request
+/// for their provider creates a new one
+pub const REQ_MANAGER_IDLE_TTL: Duration = Duration::from_secs(300);
+
+/// How often request managers are checked against [`REQ_MANAGER_IDLE_TTL`]
+const REQ_MANAGER_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Concurrent requests allowed per TTS provider request manager
+const TTS_REQ_MANAGER_CONCURRENCY: usize = 4;
+
+type ReqManagers = RwLock<HashMap<String, Arc<ReqManager>>>;
+
 /// Core-specific shared state for the application.
 ///
 /// Holds resources owned by the core layer, such as HTTP request managers
 /// for TTS providers and the turn detector for speech completion detection.
 #[derive(Clone)]
 pub struct CoreState {
-    /// HTTP request managers for TTS providers - key is provider name (e.g., "deepgram")
-    pub tts_req_managers: Arc<RwLock<HashMap<String, Arc<ReqManager>>>>,
+    /// HTTP request managers for TTS providers and SIP hooks - key is provider
+    /// name (e.g., "deepgram"). Created on first use and dropped once idle
+    /// for [`REQ_MANAGER_IDLE_TTL`].
+    pub tts_req_managers: Arc<ReqManagers>,
     /// Unified cache store (in-memory by default)
     pub cache: Arc<CacheStore>,
     /// Turn detector for determining end of user speech turns
     pub turn_detector: Option<Arc<RwLock<TurnDetector>>>,
+    /// Outcome of loading the turn detection model at startup
+    pub turn_detector_health: TurnDetectorHealth,
     /// SIP hooks runtime state with preserved secrets
     pub sip_hooks_state: Option<Arc<RwLock<SipHooksState>>>,
 }
 
 impl CoreState {
-    /// Initialize core state, including TTS request managers.
+    /// Initialize core state.
+    ///
+    /// TTS request managers are not created here but on first use, so an
+    /// idle gateway holds no HTTP clients.
     pub async fn new(config: &ServerConfig) -> Arc<Self> {
-        let mut tts_req_managers = HashMap::new();
-
         // Build cache configuration based on ServerConfig
         let cache_cfg = if let Some(path) = &config.cache_path {
             CacheConfig::Filesystem {
@@ -60,26 +71,12 @@ impl CoreState {
                 .expect("cache init"),
         );
 
-        let tts_provider_urls = get_tts_provider_urls();
-        for (provider, url) in tts_provider_urls {
-            match ReqManager::new(4).await {
-                Ok(manager) => {
-                    // Optionally warm up connections to providers (e.g., Deepgram)
-                    let _ = manager.warmup(url.as_str(), "OPTIONS").await;
-                    tts_req_managers.insert(provider.clone(), Arc::new(manager));
-                    tracing::info!(
-                        "Initialized {} ReqManager with 4 concurrent connections",
-                        provider
-                    );
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create {} ReqManager: {}", provider, e);
-                }
-            }
-        }
+        let tts_req_managers = Arc::new(RwLock::new(HashMap::new()));
+        spawn_req_manager_sweeper(&tts_req_managers);
 
         // Initialize and warmup Turn Detector
-        let turn_detector = Self::initialize_turn_detector(config.cache_path.as_ref()).await;
+        let (turn_detector, turn_detector_health) =
+            Self::initialize_turn_detector(config.cache_path.as_ref()).await;
 
         let sip_hooks_state = if let Some(sip_config) = &config.sip {
             Some(Arc::new(RwLock::new(
@@ -90,133 +87,87 @@ impl CoreState {
         };
 
         Arc::new(Self {
-            tts_req_managers: Arc::new(RwLock::new(tts_req_managers)),
+            tts_req_managers,
             cache,
             turn_detector,
+            turn_detector_health,
             sip_hooks_state,
         })
     }
 
     /// Get a TTS request manager for a specific provider
+    ///
+    /// Creates the manager on first use for providers listed by
+    /// [`get_tts_provider_urls`](crate::core::tts::get_tts_provider_urls);
+    /// returns `None` for other providers.
     pub async fn get_tts_req_manager(&self, provider: &str) -> Option<Arc<ReqManager>> {
-        self.tts_req_managers.read().await.get(provider).cloned()
-    }
-
-    #[cfg(feature = "turn-detect")]
-    /// Initialize and warmup the Turn Detector model
-    async fn initialize_turn_detector(
-        cache_path: Option<&PathBuf>,
-    ) -> Option<Arc<RwLock<TurnDetector>>> {
-        info!("Initializing Turn Detector for speech completion detection");
-
-        let start = Instant::now();
-
-        // Create config with cache path
-        let config = TurnDetectorConfig {
-            cache_path: cache_path.cloned(),
-            ..Default::default()
-        };
+        if let Some(manager) = self.tts_req_managers.read().await.get(provider) {
+            return Some(manager.clone());
+        }
+        tts_provider_url(provider)?;
 
-        // Add a timeout to prevent hanging forever during initialization
-        let init_timeout = Duration::from_secs(30);
-
-        match tokio::time::timeout(init_timeout, TurnDetector::with_config(config)).await {
-            Ok(Ok(detector)) => {
-                let init_elapsed = start.elapsed();
-                info!("Turn Detector initialized in {:?}", init_elapsed);
-
-                // Warmup the model with sample inputs to ensure it's fully loaded
-                let warmup_start = Instant::now();
-                let warmup_timeout = Duration::from_secs(10);
-
-                match tokio::time::timeout(warmup_timeout, Self::warmup_turn_detector(&detector))
-                    .await
-                {
-                    Ok(Ok(())) => {
-                        let warmup_elapsed = warmup_start.elapsed();
-                        info!("Turn Detector warmup completed in {:?}", warmup_elapsed);
-                    }
-                    Ok(Err(e)) => {
-                        warn!("Turn Detector warmup failed: {:?}", e);
-                    }
-                    Err(_) => {
-                        warn!("Turn Detector warmup timed out after {:?}", warmup_timeout);
-                    }
-                }
-
-                let total_elapsed = start.elapsed();
-                info!("Turn Detector fully ready in {:?}", total_elapsed);
-
-                Some(Arc::new(RwLock::new(detector)))
-            }
-            Ok(Err(e)) => {
-                warn!(
-                    "Failed to initialize Turn Detector: {:?}. \
-                    Falling back to timer-based detection.",
-                    e
+        let mut managers = self.tts_req_managers.write().await;
+        // Another task may have created it while we waited for the write lock
+        if let Some(manager) = managers.get(provider) {
+            return Some(manager.clone());
+        }
+        match ReqManager::new(TTS_REQ_MANAGER_CONCURRENCY).await {
+            Ok(manager) => {
+                let manager = Arc::new(manager);
+                managers.insert(provider.to_string(), manager.clone());
+                tracing::info!(
+                    "Initialized {} ReqManager with {} concurrent connections",
+                    provider,
+                    TTS_REQ_MANAGER_CONCURRENCY
                 );
-                None
+                Some(manager)
             }
-            Err(_) => {
-                warn!(
-                    "Turn Detector initialization timed out after {:?}. \
-                    Falling back to timer-based detection.",
-                    init_timeout
-                );
+            Err(e) => {
+                tracing::error!("Failed to create {} ReqManager: {}", provider, e);
                 None
             }
         }
     }
 
-    #[cfg(not(feature = "turn-detect"))]
-    async fn initialize_turn_detector(
-        cache_path: Option<&PathBuf>,
-    ) -> Option<Arc<RwLock<TurnDetector>>> {
-        let _ = cache_path;
-        info!("Turn detection feature disabled; using timer-based speech_final fallback logic");
-        None
+    /// Drop request managers idle for at least `idle_ttl`
+    ///
+    /// A manager still held by a provider or serving a request is kept.
+    ///
+    /// # Returns
+    /// * `usize` - Number of managers dropped
+    pub async fn evict_idle_req_managers(&self, idle_ttl: Duration) -> usize {
+        evict_idle_req_managers(&self.tts_req_managers, idle_ttl).await
     }
 
-    #[cfg(feature = "turn-detect")]
-    /// Warmup the Turn Detector model with sample inputs
-    async fn warmup_turn_detector(detector: &TurnDetector) -> anyhow::Result<()> {
-        debug!("Starting Turn Detector warmup with sample inputs");
-
-        // Sample inputs that cover common speech patterns
-        let warmup_samples = [
-            "Hello",
-            "How are you?",
-            "What is the weather like today?",
-            "Thank you very much",
-            "I was wondering if",
-            "Can you help me with",
-            "That's great, thanks!",
-            "Let me think about it",
-            "I need to",
-        ];
-
-        // Run predictions on all samples to ensure model is fully loaded
-        for (i, sample) in warmup_samples.iter().enumerate() {
-            let start = Instant::now();
-            match detector.predict_end_of_turn(sample).await {
-                Ok(probability) => {
-                    let elapsed = start.elapsed();
-                    debug!(
-                        "Warmup sample {} ('{}...'): probability={:.3}, time={:?}",
-                        i + 1,
-                        &sample.chars().take(20).collect::<String>(),
-                        probability,
-                        elapsed
-                    );
-                }
-                Err(e) => {
-                    warn!("Warmup sample {} failed: {:?}", i + 1, e);
-                }
+    /// Fraction of busy slots in the fullest TTS request pool (0.0 to 1.0)
+    pub async fn tts_pool_saturation(&self) -> f64 {
+        let managers = self.tts_req_managers.read().await;
+        let mut saturation: f64 = 0.0;
+        for manager in managers.values() {
+            let max = manager.max_concurrent();
+            if max == 0 {
+                continue;
             }
+            let busy = max.saturating_sub(manager.available_count().await);
+            saturation = saturation.max(busy as f64 / max as f64);
         }
+        saturation
+    }
 
-        debug!("Turn Detector warmup completed");
-        Ok(())
+    /// Load and warm up the Turn Detector, reporting the outcome
+    async fn initialize_turn_detector(
+        cache_path: Option<&PathBuf>,
+    ) -> (Option<Arc<RwLock<TurnDetector>>>, TurnDetectorHealth) {
+        // Create config with cache path
+        let config = TurnDetectorConfig {
+            cache_path: cache_path.cloned(),
+            ..Default::default()
+        };
+        let (detector, health) = load_turn_detector(config).await;
+        (
+            detector.map(|detector| Arc::new(RwLock::new(detector))),
+            health,
+        )
     }
 
     /// Get the Turn Detector if available
@@ -224,8 +175,79 @@ impl CoreState {
         self.turn_detector.clone()
     }
 
+    /// Get the outcome of loading the turn detection model at startup
+    pub fn turn_detector_health(&self) -> &TurnDetectorHealth {
+        &self.turn_detector_health
+    }
+
     /// Get SIP hooks runtime state if SIP is configured.
     pub fn get_sip_hooks_state(&self) -> Option<Arc<RwLock<SipHooksState>>> {
         self.sip_hooks_state.clone()
     }
 }
+
+/// Drop the request managers in `managers` idle for at least `idle_ttl`
+async fn evict_idle_req_managers(managers: &ReqManagers, idle_ttl: Duration) -> usize {
+    let mut managers = managers.write().await;
+    let before = managers.len();
+    // Managers are only cloned under the lock, so a count of one means no
+    // provider holds this manager
+    managers.retain(|provider, manager| {
+        let idle = Arc::strong_count(manager) == 1
+            && manager.active_requests() == 0
+            && manager.idle_for() >= idle_ttl;
+        if idle {
+            tracing::debug!(provider = %provider, "Dropping idle ReqManager");
+        }
+        !idle
+    });
+    before - managers.len()
+}
+
+/// Periodically drop idle request managers, until `managers` is dropped
+fn spawn_req_manager_sweeper(managers: &Arc<ReqManagers>) {
+    let managers = Arc::downgrade(managers);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REQ_MANAGER_SWEEP_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Some(managers) = managers.upgrade() else {
+                break;
+            };
+            evict_idle_req_managers(&managers, REQ_MANAGER_IDLE_TTL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_evicts_only_idle_unheld_managers() {
+        let managers: ReqManagers = RwLock::new(HashMap::new());
+        let held = Arc::new(ReqManager::new(1).await.unwrap());
+        {
+            let mut map = managers.write().await;
+            map.insert("held".to_string(), held.clone());
+            map.insert(
+                "idle".to_string(),
+                Arc::new(ReqManager::new(1).await.unwrap()),
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(
+            evict_idle_req_managers(&managers, Duration::from_secs(60)).await,
+            0
+        );
+        assert_eq!(
+            evict_idle_req_managers(&managers, Duration::from_millis(10)).await,
+            1
+        );
+        let map = managers.read().await;
+        assert!(map.contains_key("held"));
+        assert!(!map.contains_key("idle"));
+    }
+}
@@ -48,7 +48,10 @@ pub const MAX_SAMPLE_RATE: u32 = 48000;
 use super::config::{
     AssemblyAIEncoding, AssemblyAIRegion, AssemblyAISTTConfig, AssemblyAISpeechModel,
 };
-use super::messages::{AssemblyAIMessage, ForceEndpointMessage, TerminateMessage};
+use super::messages::{
+    AssemblyAIMessage, ForceEndpointMessage, TerminateMessage, UpdateConfigurationMessage,
+};
+use crate::core::providers::headers::insert_custom_headers;
 use crate::core::stt::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -251,23 +254,26 @@ impl AssemblyAISTT {
                                 (sum / turn.words.len() as f64) as f32
                             };
 
-                            let stt_result = STTResult::new(
+                            // Log language detection if present
+                            if let (Some(lang), Some(conf)) =
+                                (&turn.language, turn.language_confidence)
+                            {
+                                debug!("Detected language: {} (confidence: {:.2})", lang, conf);
+                            }
+
+                            let mut stt_result = STTResult::new(
                                 turn.transcript,
                                 turn.end_of_turn, // is_final
                                 turn.end_of_turn, // is_speech_final
                                 confidence.clamp(0.0, 1.0),
                             );
+                            if let Some(language) = turn.language {
+                                stt_result = stt_result.with_language(language);
+                            }
 
                             if result_tx.try_send(stt_result).is_err() {
                                 warn!("Failed to send turn result - channel closed");
                             }
-
-                            // Log language detection if present
-                            if let (Some(lang), Some(conf)) =
-                                (&turn.language, turn.language_confidence)
-                            {
-                                debug!("Detected language: {} (confidence: {:.2})", lang, conf);
-                            }
                         }
 
                         AssemblyAIMessage::Termination(term) => {
@@ -372,6 +378,7 @@ impl AssemblyAISTT {
 
         // Clone necessary data for the connection task
         let api_key = config.base.api_key.clone();
+        let custom_headers = config.base.custom_headers.clone();
         let host = Self::get_host_from_region(&config.region);
 
         // Clone shared state for the connection task
@@ -393,7 +400,10 @@ impl AssemblyAISTT {
                 .header("Authorization", &api_key) // AssemblyAI uses raw API key
                 .body(())
             {
-                Ok(request) => request,
+                Ok(mut request) => {
+                    insert_custom_headers(request.headers_mut(), &custom_headers);
+                    request
+                }
                 Err(e) => {
                     let stt_error = STTError::ConnectionFailed(format!(
                         "Failed to create WebSocket request: {e}"
@@ -592,6 +602,12 @@ impl Drop for AssemblyAISTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -772,6 +788,16 @@ impl BaseSTT for AssemblyAISTT {
     fn get_provider_info(&self) -> &'static str {
         "AssemblyAI Streaming STT v3"
     }
+
+    async fn set_end_of_turn_silence(&mut self, silence_ms: u32) -> Result<bool, STTError> {
+        self.update_end_of_turn_silence(silence_ms).await?;
+        Ok(true)
+    }
+
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        self.force_endpoint().await?;
+        Ok(true)
+    }
 }
 
 // =============================================================================
@@ -822,6 +848,38 @@ impl AssemblyAISTT {
         Ok(())
     }
 
+    /// Update the end-of-turn silence threshold on the live session.
+    ///
+    /// Sends an `UpdateConfiguration` message setting
+    /// `min_end_of_turn_silence_when_confident`, which takes effect from the
+    /// next turn boundary AssemblyAI evaluates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not connected or the message fails to send.
+    pub async fn update_end_of_turn_silence(&self, silence_ms: u32) -> Result<(), STTError> {
+        if !self.is_ready() {
+            return Err(STTError::ConnectionFailed(
+                "Not connected to AssemblyAI STT".to_string(),
+            ));
+        }
+
+        let control_tx = self.control_tx.as_ref().ok_or_else(|| {
+            STTError::ConnectionFailed("Control channel not available".to_string())
+        })?;
+
+        let msg = UpdateConfigurationMessage::new(None).with_min_end_of_turn_silence(silence_ms);
+        let json = serde_json::to_string(&msg)
+            .map_err(|e| STTError::ProviderError(format!("Failed to serialize message: {e}")))?;
+
+        control_tx.send(json).await.map_err(|e| {
+            STTError::NetworkError(format!("Failed to send UpdateConfiguration: {e}"))
+        })?;
+
+        debug!("Updated AssemblyAI end-of-turn silence to {}ms", silence_ms);
+        Ok(())
+    }
+
     /// Update AssemblyAI-specific settings.
     ///
     /// This allows updating AssemblyAI-specific parameters without
@@ -898,6 +956,8 @@ mod tests {
             encoding: "linear16".to_string(),
             model: "".to_string(),
             provider: "assemblyai".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = AssemblyAISTT::new(config);
@@ -920,6 +980,8 @@ mod tests {
             encoding: "linear16".to_string(),
             model: "".to_string(),
             provider: "assemblyai".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = AssemblyAISTT::new(config);
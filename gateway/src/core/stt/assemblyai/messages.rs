@@ -159,6 +159,9 @@ pub struct UpdateConfigurationMessage {
     /// New end-of-turn confidence threshold (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_of_turn_confidence_threshold: Option<f32>,
+    /// New silence (ms) that ends a turn when end-of-turn is confident (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_end_of_turn_silence_when_confident: Option<u32>,
 }
 
 impl UpdateConfigurationMessage {
@@ -167,8 +170,15 @@ impl UpdateConfigurationMessage {
         Self {
             message_type: "UpdateConfiguration",
             end_of_turn_confidence_threshold,
+            min_end_of_turn_silence_when_confident: None,
         }
     }
+
+    /// Set the silence (ms) that ends a turn when end-of-turn is confident.
+    pub fn with_min_end_of_turn_silence(mut self, silence_ms: u32) -> Self {
+        self.min_end_of_turn_silence_when_confident = Some(silence_ms);
+        self
+    }
 }
 
 // =============================================================================
@@ -407,6 +417,11 @@ mod tests {
         let msg_none = UpdateConfigurationMessage::new(None);
         let json_none = serde_json::to_string(&msg_none).unwrap();
         assert!(!json_none.contains("end_of_turn_confidence_threshold"));
+        assert!(!json_none.contains("min_end_of_turn_silence_when_confident"));
+
+        let msg_silence = UpdateConfigurationMessage::new(None).with_min_end_of_turn_silence(320);
+        let json_silence = serde_json::to_string(&msg_silence).unwrap();
+        assert!(json_silence.contains("\"min_end_of_turn_silence_when_confident\":320"));
     }
 
     #[test]
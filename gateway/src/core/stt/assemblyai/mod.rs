@@ -87,7 +87,7 @@ mod messages;
 mod tests;
 
 // Re-export public types
-pub use client::AssemblyAISTT;
+pub use client::{AssemblyAISTT, MAX_SAMPLE_RATE, MIN_SAMPLE_RATE};
 pub use config::{
     AssemblyAIEncoding, AssemblyAIRegion, AssemblyAISTTConfig, AssemblyAISpeechModel,
 };
@@ -35,6 +35,8 @@
 //!         punctuation: true,
 //!         encoding: "pcm".to_string(),
 //!         model: String::new(),
+//!         custom_headers: Default::default(),
+//!         certificate_path: None,
 //!     };
 //!
 //!     let mut stt = AwsTranscribeSTT::new(config)?;
@@ -55,20 +57,24 @@ use std::time::Duration;
 
 use aws_config::BehaviorVersion;
 use aws_sdk_transcribestreaming::Client as TranscribeClient;
+use aws_sdk_transcribestreaming::operation::start_stream_transcription::builders::StartStreamTranscriptionFluentBuilder;
 use aws_sdk_transcribestreaming::types::{
-    AudioEvent, AudioStream, LanguageCode, MediaEncoding as AwsMediaEncoding,
-    PartialResultsStability as AwsPartialResultsStability, TranscriptResultStream,
+    AudioEvent, AudioStream, ContentRedactionType as AwsContentRedactionType, Entity, LanguageCode,
+    MediaEncoding as AwsMediaEncoding, PartialResultsStability as AwsPartialResultsStability,
+    TranscriptResultStream, VocabularyFilterMethod as AwsVocabularyFilterMethod,
 };
 use aws_smithy_types::Blob;
 use tokio::sync::{Mutex, Notify, RwLock, mpsc, oneshot};
 use tokio::time::timeout;
 
 use super::config::{
-    AwsRegion, AwsTranscribeSTTConfig, DEFAULT_CHUNK_DURATION_MS, MAX_SAMPLE_RATE, MIN_SAMPLE_RATE,
-    MediaEncoding, PartialResultsStability,
+    AwsRegion, AwsTranscribeSTTConfig, ContentRedactionType, DEFAULT_CHUNK_DURATION_MS,
+    MAX_SAMPLE_RATE, MIN_SAMPLE_RATE, MediaEncoding, PartialResultsStability,
+    VocabularyFilterMethod,
 };
+use super::messages::redacted_spans;
 use crate::core::stt::base::{
-    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
+    BaseSTT, RedactedSpan, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
 
 use tracing::{debug, error, info, warn};
@@ -268,6 +274,105 @@ impl AwsTranscribeSTT {
         }
     }
 
+    /// Convert WaaV VocabularyFilterMethod to AWS SDK type.
+    fn convert_vocabulary_filter_method(
+        method: &VocabularyFilterMethod,
+    ) -> AwsVocabularyFilterMethod {
+        match method {
+            VocabularyFilterMethod::Remove => AwsVocabularyFilterMethod::Remove,
+            VocabularyFilterMethod::Mask => AwsVocabularyFilterMethod::Mask,
+            VocabularyFilterMethod::Tag => AwsVocabularyFilterMethod::Tag,
+        }
+    }
+
+    /// Convert WaaV ContentRedactionType to AWS SDK type.
+    fn convert_content_redaction_type(
+        redaction_type: &ContentRedactionType,
+    ) -> AwsContentRedactionType {
+        match redaction_type {
+            ContentRedactionType::Pii => AwsContentRedactionType::Pii,
+        }
+    }
+
+    /// Build the streaming transcription request for `config`.
+    ///
+    /// Everything except the audio stream is set here.
+    pub(super) fn build_request(
+        client: &TranscribeClient,
+        config: &AwsTranscribeSTTConfig,
+    ) -> StartStreamTranscriptionFluentBuilder {
+        let mut request = client
+            .start_stream_transcription()
+            .media_sample_rate_hertz(config.base.sample_rate as i32)
+            .media_encoding(Self::convert_media_encoding(&config.media_encoding));
+
+        // Add language code if not using auto-detect
+        if !config.identify_language {
+            if let Some(lang) = Self::convert_language_code(&config.base.language) {
+                request = request.language_code(lang);
+            }
+        } else {
+            request = request.identify_language(true);
+        }
+
+        // Add optional parameters
+        if config.enable_partial_results_stabilization {
+            request = request
+                .enable_partial_results_stabilization(true)
+                .partial_results_stability(Self::convert_partial_results_stability(
+                    &config.partial_results_stability,
+                ));
+        }
+
+        if config.show_speaker_label {
+            request = request.show_speaker_label(true);
+        }
+
+        if let Some(vocab) = &config.vocabulary_name {
+            request = request.vocabulary_name(vocab);
+        }
+
+        if let Some(filter) = &config.vocabulary_filter_name {
+            request = request.vocabulary_filter_name(filter);
+        }
+
+        if let Some(method) = &config.vocabulary_filter_method {
+            request =
+                request.vocabulary_filter_method(Self::convert_vocabulary_filter_method(method));
+        }
+
+        if let Some(model) = &config.language_model_name {
+            request = request.language_model_name(model);
+        }
+
+        if let Some(redaction_type) = config.content_redaction_type() {
+            request = request
+                .content_redaction_type(Self::convert_content_redaction_type(&redaction_type));
+        }
+
+        if let Some(entity_types) = config.pii_entity_types_param() {
+            request = request.pii_entity_types(entity_types);
+        }
+
+        if let Some(sid) = &config.session_id {
+            request = request.session_id(sid);
+        }
+
+        request
+    }
+
+    /// Locate the redacted entities of an alternative in its transcript.
+    fn redacted_spans(transcript: &str, entities: &[Entity]) -> Vec<RedactedSpan> {
+        let mut entities: Vec<&Entity> = entities.iter().collect();
+        entities.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+        redacted_spans(
+            transcript,
+            entities
+                .into_iter()
+                .map(|entity| entity.r#type.as_deref().or(entity.category.as_deref())),
+        )
+    }
+
     /// Start the transcription stream connection.
     async fn start_connection(&mut self, config: AwsTranscribeSTTConfig) -> Result<(), STTError> {
         // Create channels for communication
@@ -286,17 +391,9 @@ impl AwsTranscribeSTT {
 
         // Clone data needed for the connection task
         let region_str = config.region.as_str().to_string();
-        let language_code = config.base.language.clone();
-        let sample_rate = config.base.sample_rate as i32;
-        let media_encoding = Self::convert_media_encoding(&config.media_encoding);
-        let enable_partial_stabilization = config.enable_partial_results_stabilization;
-        let partial_stability =
-            Self::convert_partial_results_stability(&config.partial_results_stability);
-        let show_speaker_label = config.show_speaker_label;
-        let vocabulary_name = config.vocabulary_name.clone();
-        let vocabulary_filter_name = config.vocabulary_filter_name.clone();
-        let session_id = config.session_id.clone();
-        let identify_language = config.identify_language;
+        let custom_headers = config.base.custom_headers.clone();
+        let redaction_enabled = config.enable_content_redaction;
+        let request_config = config.clone();
 
         let is_connected = self.is_connected.clone();
         let session_id_storage = self.session_id.clone();
@@ -333,46 +430,8 @@ impl AwsTranscribeSTT {
 
             let client = TranscribeClient::new(&aws_config);
 
-            // Convert language code
-            let language = Self::convert_language_code(&language_code);
-
             // Build the streaming request
-            let mut request = client
-                .start_stream_transcription()
-                .media_sample_rate_hertz(sample_rate)
-                .media_encoding(media_encoding);
-
-            // Add language code if not using auto-detect
-            if !identify_language {
-                if let Some(lang) = language {
-                    request = request.language_code(lang);
-                }
-            } else {
-                request = request.identify_language(true);
-            }
-
-            // Add optional parameters
-            if enable_partial_stabilization {
-                request = request
-                    .enable_partial_results_stabilization(true)
-                    .partial_results_stability(partial_stability);
-            }
-
-            if show_speaker_label {
-                request = request.show_speaker_label(true);
-            }
-
-            if let Some(vocab) = vocabulary_name {
-                request = request.vocabulary_name(vocab);
-            }
-
-            if let Some(filter) = vocabulary_filter_name {
-                request = request.vocabulary_filter_name(filter);
-            }
-
-            if let Some(sid) = session_id {
-                request = request.session_id(sid);
-            }
+            let request = Self::build_request(&client, &request_config);
 
             // Create the audio stream from incoming chunks
             let audio_stream = async_stream::stream! {
@@ -395,8 +454,16 @@ impl AwsTranscribeSTT {
                 }
             };
 
-            // Start the transcription stream
-            match request.audio_stream(audio_stream.into()).send().await {
+            // Start the transcription stream with any custom headers (added before signing)
+            let request = request
+                .audio_stream(audio_stream.into())
+                .customize()
+                .mutate_request(move |req| {
+                    for (name, value) in &custom_headers {
+                        let _ = req.headers_mut().try_insert(name.clone(), value.clone());
+                    }
+                });
+            match request.send().await {
                 Ok(output) => {
                     // Store session ID if provided
                     if let Some(sid) = output.session_id() {
@@ -445,12 +512,24 @@ impl AwsTranscribeSTT {
                                                         0.0
                                                     };
 
+                                                    let redactions = if redaction_enabled {
+                                                        Self::redacted_spans(
+                                                            transcript_text,
+                                                            alt.entities
+                                                                .as_deref()
+                                                                .unwrap_or_default(),
+                                                        )
+                                                    } else {
+                                                        Vec::new()
+                                                    };
+
                                                     let stt_result = STTResult::new(
                                                         transcript_text.clone(),
                                                         !is_partial,
                                                         !is_partial, // is_speech_final same as is_final for Transcribe
                                                         confidence,
-                                                    );
+                                                    )
+                                                    .with_redactions(redactions);
 
                                                     if result_tx.try_send(stt_result).is_err() {
                                                         warn!(
@@ -787,6 +866,12 @@ impl Drop for AwsTranscribeSTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -805,6 +890,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm".to_string(),
             model: String::new(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = AwsTranscribeSTT::new(config).unwrap();
@@ -823,6 +910,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm".to_string(),
             model: String::new(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = AwsTranscribeSTT::new(config);
@@ -843,6 +932,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm".to_string(),
             model: String::new(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = AwsTranscribeSTT::new(config).unwrap();
@@ -903,6 +994,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm".to_string(),
             model: String::new(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = AwsTranscribeSTT::new(config).unwrap();
@@ -356,6 +356,44 @@ impl std::fmt::Display for ContentRedactionType {
     }
 }
 
+/// PII entity types Amazon Transcribe can redact (`ALL` selects every type)
+pub const PII_ENTITY_TYPES: &[&str] = &[
+    "ADDRESS",
+    "BANK_ACCOUNT_NUMBER",
+    "BANK_ROUTING",
+    "CREDIT_DEBIT_CVV",
+    "CREDIT_DEBIT_EXPIRY",
+    "CREDIT_DEBIT_NUMBER",
+    "EMAIL",
+    "NAME",
+    "PHONE",
+    "PIN",
+    "SSN",
+    "ALL",
+];
+
+/// Languages for which streaming content redaction is available
+pub const REDACTION_LANGUAGES: &[&str] = &["en-US", "en-GB", "en-AU", "es-US"];
+
+/// Maximum length of a vocabulary, vocabulary filter or language model name
+const MAX_RESOURCE_NAME_LEN: usize = 200;
+
+/// Check a custom vocabulary, filter or language model name
+///
+/// Names are 1-200 characters of letters, digits, `.`, `_` and `-`.
+fn validate_resource_name(field: &str, name: &str) -> Result<(), String> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if name.is_empty() || name.len() > MAX_RESOURCE_NAME_LEN || !valid_chars {
+        return Err(format!(
+            "{} must be 1-{} characters of letters, digits, '.', '_' or '-', got '{}'",
+            field, MAX_RESOURCE_NAME_LEN, name
+        ));
+    }
+    Ok(())
+}
+
 // =============================================================================
 // Main Configuration
 // =============================================================================
@@ -515,6 +553,8 @@ impl Default for AwsTranscribeSTTConfig {
                 punctuation: true,
                 encoding: "pcm".to_string(),
                 model: String::new(), // Amazon Transcribe uses default model
+                custom_headers: Default::default(),
+                certificate_path: None,
             },
             region: AwsRegion::default(),
             aws_access_key_id: None,
@@ -595,6 +635,58 @@ impl AwsTranscribeSTTConfig {
             ));
         }
 
+        // Validate custom vocabulary and language model names
+        for (field, name) in [
+            ("vocabulary_name", &self.vocabulary_name),
+            ("vocabulary_filter_name", &self.vocabulary_filter_name),
+            ("language_model_name", &self.language_model_name),
+        ] {
+            if let Some(name) = name {
+                validate_resource_name(field, name)?;
+                // Language identification takes vocabulary lists per language
+                if self.identify_language {
+                    return Err(format!("{} cannot be used with identify_language", field));
+                }
+            }
+        }
+
+        if self.vocabulary_filter_method.is_some() && self.vocabulary_filter_name.is_none() {
+            return Err("vocabulary_filter_method requires vocabulary_filter_name".to_string());
+        }
+
+        // Validate content redaction
+        if !self.enable_content_redaction {
+            if !self.content_redaction_types.is_empty() || !self.pii_entity_types.is_empty() {
+                return Err(
+                    "content_redaction_types and pii_entity_types require enable_content_redaction"
+                        .to_string(),
+                );
+            }
+        } else {
+            if let Some(entity_type) = self
+                .pii_entity_types
+                .iter()
+                .find(|entity_type| !PII_ENTITY_TYPES.contains(&entity_type.as_str()))
+            {
+                return Err(format!(
+                    "Unknown PII entity type '{}', expected one of: {}",
+                    entity_type,
+                    PII_ENTITY_TYPES.join(", ")
+                ));
+            }
+            if !self.identify_language
+                && !REDACTION_LANGUAGES
+                    .iter()
+                    .any(|language| language.eq_ignore_ascii_case(&self.base.language))
+            {
+                return Err(format!(
+                    "Content redaction is not available for language '{}', supported: {}",
+                    self.base.language,
+                    REDACTION_LANGUAGES.join(", ")
+                ));
+            }
+        }
+
         // Validate language vs identify_language
         if self.identify_language && !self.base.language.is_empty() {
             tracing::warn!(
@@ -632,6 +724,26 @@ impl AwsTranscribeSTTConfig {
     pub fn has_explicit_credentials(&self) -> bool {
         self.aws_access_key_id.is_some() && self.aws_secret_access_key.is_some()
     }
+
+    /// Redaction type to request, if content redaction is enabled.
+    ///
+    /// Defaults to PII when no type is listed.
+    pub fn content_redaction_type(&self) -> Option<ContentRedactionType> {
+        self.enable_content_redaction.then(|| {
+            self.content_redaction_types
+                .first()
+                .copied()
+                .unwrap_or(ContentRedactionType::Pii)
+        })
+    }
+
+    /// Comma-separated PII entity types to redact, if any are listed.
+    ///
+    /// Without a list, Amazon Transcribe redacts every type.
+    pub fn pii_entity_types_param(&self) -> Option<String> {
+        (self.enable_content_redaction && !self.pii_entity_types.is_empty())
+            .then(|| self.pii_entity_types.join(","))
+    }
 }
 
 #[cfg(test)]
@@ -752,6 +864,91 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_config_validation_vocabulary() {
+        let mut config = AwsTranscribeSTTConfig::default();
+        config.vocabulary_filter_method = Some(VocabularyFilterMethod::Mask);
+        let result = config.validate();
+        assert!(
+            result
+                .unwrap_err()
+                .contains("requires vocabulary_filter_name")
+        );
+
+        config.vocabulary_filter_name = Some("profanity-filter".to_string());
+        config.vocabulary_name = Some("medical_terms.v2".to_string());
+        assert!(config.validate().is_ok());
+
+        config.vocabulary_name = Some("has spaces".to_string());
+        let result = config.validate();
+        assert!(result.unwrap_err().contains("vocabulary_name must be"));
+
+        config.vocabulary_name = Some("x".repeat(201));
+        assert!(config.validate().is_err());
+
+        config.vocabulary_name = None;
+        config.identify_language = true;
+        let result = config.validate();
+        assert!(
+            result
+                .unwrap_err()
+                .contains("vocabulary_filter_name cannot be used with identify_language")
+        );
+    }
+
+    #[test]
+    fn test_config_validation_content_redaction() {
+        let mut config = AwsTranscribeSTTConfig::default();
+        config.pii_entity_types = vec!["NAME".to_string()];
+        let result = config.validate();
+        assert!(
+            result
+                .unwrap_err()
+                .contains("require enable_content_redaction")
+        );
+
+        config.enable_content_redaction = true;
+        assert!(config.validate().is_ok());
+
+        config.pii_entity_types.push("SHOE_SIZE".to_string());
+        let result = config.validate();
+        assert!(
+            result
+                .unwrap_err()
+                .contains("Unknown PII entity type 'SHOE_SIZE'")
+        );
+
+        config.pii_entity_types = vec!["ALL".to_string()];
+        config.base.language = "de-DE".to_string();
+        let result = config.validate();
+        assert!(
+            result
+                .unwrap_err()
+                .contains("not available for language 'de-DE'")
+        );
+
+        config.base.language = "es-US".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_content_redaction_params() {
+        let mut config = AwsTranscribeSTTConfig::default();
+        config.pii_entity_types = vec!["NAME".to_string(), "PHONE".to_string()];
+        assert_eq!(config.content_redaction_type(), None);
+        assert_eq!(config.pii_entity_types_param(), None);
+
+        config.enable_content_redaction = true;
+        assert_eq!(
+            config.content_redaction_type(),
+            Some(ContentRedactionType::Pii)
+        );
+        assert_eq!(
+            config.pii_entity_types_param().as_deref(),
+            Some("NAME,PHONE")
+        );
+    }
+
     #[test]
     fn test_calculate_chunk_size() {
         let config = AwsTranscribeSTTConfig::default();
@@ -5,9 +5,21 @@
 //!
 //! The API returns transcription results as `TranscriptEvent` messages
 //! containing `Transcript` objects with one or more `Result` items.
+//!
+//! With content redaction enabled, each redacted entity appears in the
+//! transcript as a `[PII]` placeholder and is listed in the alternative's
+//! `Entities`, in the same order. [`redacted_spans`] pairs the two up.
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::stt::base::RedactedSpan;
+
+/// Text Amazon Transcribe puts in place of redacted content
+pub const REDACTION_PLACEHOLDER: &str = "[PII]";
+
+/// Entity type reported when Amazon Transcribe did not name one
+const DEFAULT_REDACTED_TYPE: &str = "PII";
+
 // =============================================================================
 // Transcription Results
 // =============================================================================
@@ -168,6 +180,51 @@ pub struct TranscriptEvent {
 // Helper Methods
 // =============================================================================
 
+/// Locate redaction placeholders in `transcript`
+///
+/// The n-th `[PII]` placeholder is labelled with the n-th of `entity_types`,
+/// which must be ordered by start time. Placeholders without a matching type
+/// are labelled `PII`.
+pub fn redacted_spans<'a>(
+    transcript: &str,
+    entity_types: impl IntoIterator<Item = Option<&'a str>>,
+) -> Vec<RedactedSpan> {
+    let mut entity_types = entity_types.into_iter();
+    transcript
+        .match_indices(REDACTION_PLACEHOLDER)
+        .map(|(start, placeholder)| RedactedSpan {
+            start,
+            end: start + placeholder.len(),
+            entity_type: entity_types
+                .next()
+                .flatten()
+                .unwrap_or(DEFAULT_REDACTED_TYPE)
+                .to_string(),
+        })
+        .collect()
+}
+
+impl Alternative {
+    /// Redacted spans of this alternative's transcript.
+    pub fn redacted_spans(&self) -> Vec<RedactedSpan> {
+        let Some(transcript) = &self.transcript else {
+            return Vec::new();
+        };
+        let mut entities: Vec<&Entity> = self.entities.iter().flatten().collect();
+        entities.sort_by(|a, b| {
+            a.start_time
+                .unwrap_or_default()
+                .total_cmp(&b.start_time.unwrap_or_default())
+        });
+        redacted_spans(
+            transcript,
+            entities
+                .into_iter()
+                .map(|entity| entity.entity_type.as_deref().or(entity.category.as_deref())),
+        )
+    }
+}
+
 impl Result {
     /// Get the best transcription from this result.
     ///
@@ -450,6 +507,35 @@ mod tests {
         assert!(event.has_final_results());
     }
 
+    #[test]
+    fn test_redacted_spans() {
+        let spans = redacted_spans(
+            "Call [PII] at [PII] or [PII]",
+            [Some("NAME"), Some("PHONE")],
+        );
+        assert_eq!(
+            spans,
+            vec![
+                RedactedSpan {
+                    start: 5,
+                    end: 10,
+                    entity_type: "NAME".to_string()
+                },
+                RedactedSpan {
+                    start: 14,
+                    end: 19,
+                    entity_type: "PHONE".to_string()
+                },
+                RedactedSpan {
+                    start: 23,
+                    end: 28,
+                    entity_type: "PII".to_string()
+                },
+            ]
+        );
+        assert!(redacted_spans("Nothing redacted", [Some("NAME")]).is_empty());
+    }
+
     #[test]
     fn test_transcribe_error_display() {
         let error = TranscribeError {
@@ -8,7 +8,7 @@
 //! - Partial results stabilization for live captions
 //! - Speaker diarization (speaker identification)
 //! - Custom vocabularies and language models
-//! - Content redaction (PII masking)
+//! - Content redaction (PII masking), with redacted spans marked on results
 //!
 //! # Architecture
 //!
@@ -41,6 +41,8 @@
 //!         punctuation: true,
 //!         encoding: "pcm".to_string(),
 //!         model: String::new(),
+//!         custom_headers: Default::default(),
+//!         certificate_path: None,
 //!     };
 //!
 //!     let mut stt = create_stt_provider("aws-transcribe", config)?;
@@ -87,10 +89,10 @@ mod tests;
 pub use client::AwsTranscribeSTT;
 pub use config::{
     AwsRegion, AwsTranscribeSTTConfig, ContentRedactionType, DEFAULT_CHUNK_DURATION_MS,
-    MAX_SAMPLE_RATE, MIN_SAMPLE_RATE, MediaEncoding, PartialResultsStability,
-    RECOMMENDED_SAMPLE_RATE, VocabularyFilterMethod,
+    MAX_SAMPLE_RATE, MIN_SAMPLE_RATE, MediaEncoding, PII_ENTITY_TYPES, PartialResultsStability,
+    RECOMMENDED_SAMPLE_RATE, REDACTION_LANGUAGES, VocabularyFilterMethod,
 };
 pub use messages::{
-    Alternative, Entity, Item, LanguageWithScore, Result as TranscribeResult, TranscribeError,
-    Transcript, TranscriptEvent,
+    Alternative, Entity, Item, LanguageWithScore, REDACTION_PLACEHOLDER,
+    Result as TranscribeResult, TranscribeError, Transcript, TranscriptEvent, redacted_spans,
 };
@@ -1,7 +1,14 @@
 //! Tests for Amazon Transcribe Streaming STT provider.
 
 use super::*;
-use crate::core::stt::base::{BaseSTT, STTConfig};
+use crate::core::stt::base::{BaseSTT, STTConfig, STTError};
+use aws_config::BehaviorVersion;
+use aws_sdk_transcribestreaming::config::Region;
+use aws_sdk_transcribestreaming::operation::start_stream_transcription::builders::StartStreamTranscriptionFluentBuilder;
+use aws_sdk_transcribestreaming::types::{
+    ContentRedactionType as AwsContentRedactionType,
+    VocabularyFilterMethod as AwsVocabularyFilterMethod,
+};
 
 // =============================================================================
 // Configuration Tests
@@ -365,6 +372,8 @@ async fn test_client_creation_valid_config() {
         punctuation: true,
         encoding: "pcm".to_string(),
         model: String::new(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let stt = AwsTranscribeSTT::new(config);
@@ -385,6 +394,8 @@ async fn test_client_creation_invalid_sample_rate() {
         punctuation: true,
         encoding: "pcm".to_string(),
         model: String::new(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = AwsTranscribeSTT::new(config);
@@ -402,6 +413,8 @@ async fn test_client_send_audio_not_connected() {
         punctuation: true,
         encoding: "pcm".to_string(),
         model: String::new(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut stt = AwsTranscribeSTT::new(config).unwrap();
@@ -422,6 +435,8 @@ async fn test_client_get_session_id_before_connect() {
         punctuation: true,
         encoding: "pcm".to_string(),
         model: String::new(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let stt = AwsTranscribeSTT::new(config).unwrap();
@@ -440,6 +455,8 @@ async fn test_client_with_custom_config() {
             punctuation: true,
             encoding: "pcm".to_string(),
             model: String::new(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         },
         region: AwsRegion::ApNortheast1,
         enable_partial_results_stabilization: true,
@@ -465,6 +482,8 @@ async fn test_client_get_config() {
         punctuation: true,
         encoding: "pcm".to_string(),
         model: String::new(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let stt = AwsTranscribeSTT::new(config).unwrap();
@@ -498,6 +517,173 @@ fn test_vocabulary_filter_method() {
     );
 }
 
+/// Build the streaming request for a JSON config fixture
+fn request_for(fixture: serde_json::Value) -> StartStreamTranscriptionFluentBuilder {
+    let mut config = serde_json::json!({
+        "provider": "aws-transcribe",
+        "api_key": "",
+        "language": "en-US",
+        "sample_rate": 16000,
+        "channels": 1,
+        "punctuation": true,
+        "encoding": "pcm",
+        "model": ""
+    });
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(fixture.as_object().unwrap().clone());
+    let config: AwsTranscribeSTTConfig = serde_json::from_value(config).unwrap();
+    config.validate().unwrap();
+
+    let sdk_config = aws_sdk_transcribestreaming::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-1"))
+        .build();
+    let client = aws_sdk_transcribestreaming::Client::from_conf(sdk_config);
+    AwsTranscribeSTT::build_request(&client, &config)
+}
+
+#[test]
+fn test_request_includes_vocabulary_filter() {
+    let request = request_for(serde_json::json!({
+        "vocabulary_name": "product-names",
+        "vocabulary_filter_name": "profanity",
+        "vocabulary_filter_method": "mask",
+        "language_model_name": "support-calls.v1"
+    }));
+    let input = request.as_input();
+
+    assert_eq!(
+        input.get_vocabulary_name().as_deref(),
+        Some("product-names")
+    );
+    assert_eq!(
+        input.get_vocabulary_filter_name().as_deref(),
+        Some("profanity")
+    );
+    assert_eq!(
+        input.get_vocabulary_filter_method(),
+        &Some(AwsVocabularyFilterMethod::Mask)
+    );
+    assert_eq!(
+        input.get_language_model_name().as_deref(),
+        Some("support-calls.v1")
+    );
+    assert_eq!(input.get_content_redaction_type(), &None);
+    assert_eq!(input.get_pii_entity_types(), &None);
+}
+
+#[test]
+fn test_request_includes_pii_redaction() {
+    let request = request_for(serde_json::json!({
+        "enable_content_redaction": true,
+        "pii_entity_types": ["NAME", "CREDIT_DEBIT_NUMBER"]
+    }));
+    let input = request.as_input();
+
+    assert_eq!(
+        input.get_content_redaction_type(),
+        &Some(AwsContentRedactionType::Pii)
+    );
+    assert_eq!(
+        input.get_pii_entity_types().as_deref(),
+        Some("NAME,CREDIT_DEBIT_NUMBER")
+    );
+    assert_eq!(input.get_vocabulary_name(), &None);
+
+    // Without entity types every PII type is redacted
+    let request = request_for(serde_json::json!({"enable_content_redaction": true}));
+    assert_eq!(
+        request.as_input().get_content_redaction_type(),
+        &Some(AwsContentRedactionType::Pii)
+    );
+    assert_eq!(request.as_input().get_pii_entity_types(), &None);
+}
+
+#[test]
+fn test_invalid_redaction_config_rejected() {
+    let mut config = AwsTranscribeSTTConfig::with_language("fr-FR");
+    config.enable_content_redaction = true;
+    let result = AwsTranscribeSTT::new_with_config(config);
+    assert!(matches!(result, Err(STTError::ConfigurationError(_))));
+
+    let mut config = AwsTranscribeSTTConfig::default();
+    config.identify_language = true;
+    config.vocabulary_name = Some("product-names".to_string());
+    assert!(AwsTranscribeSTT::new_with_config(config).is_err());
+}
+
+#[test]
+fn test_redacted_transcript_event_parsing() {
+    let json = r#"{
+        "Transcript": {
+            "Results": [{
+                "ResultId": "r-1",
+                "StartTime": 0.0,
+                "EndTime": 4.2,
+                "IsPartial": false,
+                "Alternatives": [{
+                    "Transcript": "Hi, I'm [PII] and my card is [PII].",
+                    "Items": [],
+                    "Entities": [
+                        {
+                            "StartTime": 2.6,
+                            "EndTime": 4.1,
+                            "Category": "PII",
+                            "Type": "CREDIT_DEBIT_NUMBER",
+                            "Content": "[PII]",
+                            "Confidence": 0.98
+                        },
+                        {
+                            "StartTime": 0.7,
+                            "EndTime": 1.1,
+                            "Category": "PII",
+                            "Type": "NAME",
+                            "Content": "[PII]",
+                            "Confidence": 0.99
+                        }
+                    ]
+                }]
+            }]
+        }
+    }"#;
+
+    let event: TranscriptEvent = serde_json::from_str(json).unwrap();
+    let results = event.results().unwrap();
+    let alternative = &results[0].alternatives.as_ref().unwrap()[0];
+    let transcript = alternative.transcript.as_deref().unwrap();
+    let spans = alternative.redacted_spans();
+
+    // Entities are matched to placeholders in time order
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].entity_type, "NAME");
+    assert_eq!(
+        &transcript[spans[0].start..spans[0].end],
+        REDACTION_PLACEHOLDER
+    );
+    assert_eq!(spans[1].entity_type, "CREDIT_DEBIT_NUMBER");
+    assert_eq!(spans[1].start, 29);
+    assert_eq!(spans[1].end, 34);
+}
+
+#[test]
+fn test_redaction_parsing_without_entities() {
+    let json = r#"{
+        "Transcript": "Email me at [PII]",
+        "Entities": [{"StartTime": 1.0, "EndTime": 2.0, "Category": "PII"}]
+    }"#;
+    let alternative: Alternative = serde_json::from_str(json).unwrap();
+    let spans = alternative.redacted_spans();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].entity_type, "PII");
+    assert_eq!((spans[0].start, spans[0].end), (12, 17));
+
+    let json = r#"{"Transcript": "Nothing to hide"}"#;
+    let alternative: Alternative = serde_json::from_str(json).unwrap();
+    assert!(alternative.redacted_spans().is_empty());
+}
+
 // =============================================================================
 // Serialization Tests
 // =============================================================================
@@ -25,7 +25,8 @@
 //!
 //! # Azure-Specific Details
 //!
-//! - Uses `Ocp-Apim-Subscription-Key` header for authentication
+//! - Uses `Ocp-Apim-Subscription-Key` header for authentication, or a
+//!   cached bearer token when `use_token_auth` is enabled
 //! - Includes `X-ConnectionId` header for debugging
 //! - Content-Type specifies PCM audio format
 //! - Messages may have header prefixes before JSON content
@@ -42,6 +43,12 @@ use tracing::{debug, error, info, warn};
 
 use super::config::AzureSTTConfig;
 use super::messages::{AzureMessage, RecognitionStatus};
+use crate::core::providers::azure::{
+    AZURE_AUTHORIZATION_HEADER, AZURE_SUBSCRIPTION_KEY_HEADER, azure_token_provider,
+    build_bearer_token_header,
+};
+use crate::core::providers::headers::insert_custom_headers;
+use crate::core::providers::token::TokenProvider;
 use crate::core::stt::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -190,6 +197,9 @@ pub struct AzureSTT {
 
     /// Connection ID for debugging (sent to Azure in headers).
     connection_id: String,
+
+    /// Access tokens for `use_token_auth`, created on first connect.
+    token_provider: Option<Arc<TokenProvider>>,
 }
 
 impl Default for AzureSTT {
@@ -208,6 +218,7 @@ impl Default for AzureSTT {
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
             connection_id: generate_key(),
+            token_provider: None,
         }
     }
 }
@@ -321,9 +332,40 @@ impl AzureSTT {
         Ok(())
     }
 
+    /// Header name and value authenticating the WebSocket handshake.
+    ///
+    /// With token auth every connect takes the current token from the
+    /// provider, which refreshes it ahead of expiry. The token is only
+    /// checked at the handshake, so an open stream outlives it.
+    async fn auth_header(
+        &mut self,
+        config: &AzureSTTConfig,
+    ) -> Result<(&'static str, String), STTError> {
+        if !config.use_token_auth {
+            return Ok((AZURE_SUBSCRIPTION_KEY_HEADER, config.base.api_key.clone()));
+        }
+
+        let provider = self
+            .token_provider
+            .get_or_insert_with(|| azure_token_provider(&config.base.api_key, &config.region));
+        let token = provider.token().await.map_err(|e| {
+            STTError::AuthenticationFailed(format!("Failed to obtain Azure access token: {e}"))
+        })?;
+
+        Ok((
+            AZURE_AUTHORIZATION_HEADER,
+            build_bearer_token_header(&token),
+        ))
+    }
+
     /// Start the WebSocket connection to Azure Speech Services.
     async fn start_connection(&mut self, config: AzureSTTConfig) -> Result<(), STTError> {
         let ws_url = config.build_websocket_url();
+        let (auth_header_name, auth_header_value) = self.auth_header(&config).await?;
+        let token_provider = self
+            .token_provider
+            .clone()
+            .filter(|_| config.use_token_auth);
 
         // Create channels for communication
         let (ws_tx, mut ws_rx) = mpsc::channel::<Bytes>(32);
@@ -340,7 +382,7 @@ impl AzureSTT {
         self.error_tx = Some(error_tx.clone());
 
         // Clone necessary data for the connection task
-        let api_key = config.base.api_key.clone();
+        let custom_headers = config.base.custom_headers.clone();
         let host = config.region.stt_hostname();
         let content_type = Self::build_content_type(&config);
         let connection_id = self.connection_id.clone();
@@ -357,15 +399,18 @@ impl AzureSTT {
                 .header("Connection", "upgrade")
                 .header("Sec-WebSocket-Key", generate_key())
                 .header("Sec-WebSocket-Version", "13")
-                // Azure-specific authentication header
-                .header("Ocp-Apim-Subscription-Key", &api_key)
+                // Subscription key or bearer token
+                .header(auth_header_name, &auth_header_value)
                 // Connection ID for debugging
                 .header("X-ConnectionId", &connection_id)
                 // Audio format specification
                 .header("Content-Type", &content_type)
                 .body(())
             {
-                Ok(request) => request,
+                Ok(mut request) => {
+                    insert_custom_headers(request.headers_mut(), &custom_headers);
+                    request
+                }
                 Err(e) => {
                     let stt_error = STTError::ConnectionFailed(format!(
                         "Failed to create WebSocket request: {e}"
@@ -398,6 +443,10 @@ impl AzureSTT {
                     let stt_error = if error_msg.contains("401")
                         || error_msg.contains("Unauthorized")
                     {
+                        // Don't hand the rejected token to the next connect
+                        if let Some(provider) = &token_provider {
+                            provider.invalidate();
+                        }
                         STTError::AuthenticationFailed(
                             "Azure authentication failed. Check subscription key and region."
                                 .to_string(),
@@ -603,6 +652,12 @@ impl Drop for AzureSTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -637,6 +692,7 @@ impl BaseSTT for AzureSTT {
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
             connection_id: generate_key(),
+            token_provider: None,
         })
     }
 
@@ -774,8 +830,12 @@ impl BaseSTT for AzureSTT {
             auto_detect_languages: existing
                 .as_ref()
                 .and_then(|c| c.auto_detect_languages.clone()),
+            use_token_auth: existing.as_ref().is_some_and(|c| c.use_token_auth),
         };
 
+        // Tokens are tied to the subscription key and region
+        self.token_provider = None;
+
         self.config = Some(azure_config);
 
         // Reconnect with new configuration
@@ -817,6 +877,7 @@ impl AzureSTT {
         if let Some(config) = &mut self.config {
             if let Some(r) = region {
                 config.region = r;
+                self.token_provider = None;
             }
             if let Some(f) = output_format {
                 config.output_format = f;
@@ -877,6 +938,16 @@ impl AzureSTT {
 
         self.connect().await
     }
+
+    /// Authenticate with bearer tokens instead of the subscription key.
+    ///
+    /// Takes effect on the next connect; call before [`BaseSTT::connect`]
+    /// or reconnect afterwards.
+    pub fn set_token_auth(&mut self, enabled: bool) {
+        if let Some(config) = &mut self.config {
+            config.use_token_auth = enabled;
+        }
+    }
 }
 
 // =============================================================================
@@ -898,6 +969,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <AzureSTT as BaseSTT>::new(config).unwrap();
@@ -916,6 +989,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = <AzureSTT as BaseSTT>::new(config);
@@ -938,6 +1013,8 @@ mod tests {
             punctuation: false,
             encoding: "linear16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <AzureSTT as BaseSTT>::new(config).unwrap();
@@ -1126,4 +1203,77 @@ mod tests {
             panic!("Expected ConnectionFailed error");
         }
     }
+
+    fn token_auth_stt(server: &wiremock::MockServer) -> AzureSTT {
+        let config = STTConfig {
+            provider: "azure".to_string(),
+            api_key: "test_key".to_string(),
+            ..Default::default()
+        };
+        let mut stt = <AzureSTT as BaseSTT>::new(config).unwrap();
+        stt.set_token_auth(true);
+        stt.token_provider = Some(Arc::new(TokenProvider::new(
+            crate::core::providers::azure::AzureTokenFetcher::with_endpoint(
+                "test_key",
+                format!("{}/sts/v1.0/issueToken", server.uri()),
+            ),
+        )));
+        stt
+    }
+
+    #[tokio::test]
+    async fn test_auth_header_subscription_key() {
+        let config = STTConfig {
+            api_key: "test_key".to_string(),
+            ..Default::default()
+        };
+        let mut stt = <AzureSTT as BaseSTT>::new(config).unwrap();
+        let azure_config = stt.config.clone().unwrap();
+
+        let (name, value) = stt.auth_header(&azure_config).await.unwrap();
+        assert_eq!(name, AZURE_SUBSCRIPTION_KEY_HEADER);
+        assert_eq!(value, "test_key");
+        assert!(stt.token_provider.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_auth_header_bearer_token_is_reused_across_connects() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header(AZURE_SUBSCRIPTION_KEY_HEADER, "test_key"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("azure-token"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut stt = token_auth_stt(&server);
+        let azure_config = stt.config.clone().unwrap();
+
+        for _ in 0..3 {
+            let (name, value) = stt.auth_header(&azure_config).await.unwrap();
+            assert_eq!(name, AZURE_AUTHORIZATION_HEADER);
+            assert_eq!(value, "Bearer azure-token");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_header_token_failure() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let mut stt = token_auth_stt(&server);
+        let azure_config = stt.config.clone().unwrap();
+
+        let result = stt.auth_header(&azure_config).await;
+        assert!(matches!(result, Err(STTError::AuthenticationFailed(_))));
+    }
 }
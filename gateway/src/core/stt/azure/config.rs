@@ -160,6 +160,14 @@ pub struct AzureSTTConfig {
     ///
     /// Example: `Some(vec!["en-US".to_string(), "es-ES".to_string()])`
     pub auto_detect_languages: Option<Vec<String>>,
+
+    /// Authenticate with a short-lived bearer token instead of the subscription key.
+    ///
+    /// When `true`, the subscription key is exchanged at the region's token
+    /// endpoint and only the resulting token is sent on the WebSocket
+    /// handshake. Tokens are cached and refreshed before they expire, so
+    /// reconnects during long sessions keep working.
+    pub use_token_auth: bool,
 }
 
 impl Default for AzureSTTConfig {
@@ -173,6 +181,7 @@ impl Default for AzureSTTConfig {
             word_level_timing: false,
             endpoint_id: None,
             auto_detect_languages: None,
+            use_token_auth: false,
         }
     }
 }
@@ -304,6 +313,7 @@ mod tests {
         assert!(!config.word_level_timing);
         assert!(config.endpoint_id.is_none());
         assert!(config.auto_detect_languages.is_none());
+        assert!(!config.use_token_auth);
     }
 
     #[test]
@@ -429,6 +439,7 @@ mod tests {
             word_level_timing: true,
             endpoint_id: Some("custom-endpoint".to_string()),
             auto_detect_languages: Some(vec!["en-GB".to_string(), "en-US".to_string()]),
+            use_token_auth: false,
         };
 
         let url = config.build_websocket_url();
@@ -1,7 +1,13 @@
 use bytes::Bytes;
+use std::collections::HashMap;
 use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
+
+use super::clock::TranscriptTiming;
+use crate::errors::provider_error::error_codes;
 
 /// Result structure containing transcription data from STT providers
 #[derive(Debug, Clone, PartialEq)]
@@ -14,6 +20,22 @@ pub struct STTResult {
     pub is_speech_final: bool,
     /// Confidence score of the transcription (0.0 to 1.0)
     pub confidence: f32,
+    /// Spans of `transcript` the provider redacted, in order
+    pub redactions: Vec<RedactedSpan>,
+    /// Start of the audio the result covers, in seconds from the start of
+    /// the provider's stream (when the provider reports it)
+    pub start: Option<f64>,
+    /// End of the audio the result covers, in the same timebase as `start`
+    pub end: Option<f64>,
+    /// Word timings in the provider's stream timebase (empty when not reported)
+    pub words: Vec<WordTiming>,
+    /// The result's timing on the session audio clock, set by the voice manager
+    pub timing: Option<TranscriptTiming>,
+    /// User turn the result belongs to, set by the session
+    pub turn_id: Option<String>,
+    /// Language the provider detected in the audio (e.g. "hi", "en-US"), when
+    /// it reports one
+    pub detected_language: Option<String>,
 }
 
 impl STTResult {
@@ -24,8 +46,66 @@ impl STTResult {
             is_final,
             is_speech_final,
             confidence: confidence.clamp(0.0, 1.0), // Ensure confidence is within valid range
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         }
     }
+
+    /// Attach the spans the provider redacted
+    pub fn with_redactions(mut self, redactions: Vec<RedactedSpan>) -> Self {
+        self.redactions = redactions;
+        self
+    }
+
+    /// Attach the audio span the provider reported, in seconds from the start
+    /// of its stream
+    pub fn with_span(mut self, start: f64, end: f64) -> Self {
+        self.start = Some(start);
+        self.end = Some(end);
+        self
+    }
+
+    /// Attach the word timings the provider reported
+    pub fn with_words(mut self, words: Vec<WordTiming>) -> Self {
+        self.words = words;
+        self
+    }
+
+    /// Attach the language the provider detected
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.detected_language = Some(language.into());
+        self
+    }
+}
+
+/// A recognized word and the audio it covers, in seconds
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WordTiming {
+    /// The word as transcribed
+    pub word: String,
+    /// Start of the word
+    pub start: f64,
+    /// End of the word
+    pub end: f64,
+}
+
+/// A span of a transcript replaced by the provider's redaction placeholder
+/// (e.g. `[PII]`)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RedactedSpan {
+    /// Byte offset of the placeholder in the transcript
+    pub start: usize,
+    /// Byte offset just past the placeholder
+    pub end: usize,
+    /// Kind of content that was redacted (e.g. "NAME", "PHONE")
+    pub entity_type: String,
 }
 
 /// Configuration for STT providers
@@ -46,6 +126,17 @@ pub struct STTConfig {
     pub encoding: String,
     /// Model to use for transcription
     pub model: String,
+    /// Extra HTTP headers sent with every request to the provider
+    ///
+    /// Useful for routing through proxies or API gateways that require their
+    /// own headers. Validated by `validate_custom_headers` before use.
+    #[serde(default)]
+    pub custom_headers: HashMap<String, String>,
+    /// Client certificate for providers that authenticate with mTLS (Gnani)
+    ///
+    /// Set by the gateway from the server configuration, never by clients.
+    #[serde(skip)]
+    pub certificate_path: Option<PathBuf>,
 }
 
 impl Default for STTConfig {
@@ -59,6 +150,8 @@ impl Default for STTConfig {
             channels: 1,
             punctuation: true,
             encoding: "linear16".to_string(),
+            custom_headers: HashMap::new(),
+            certificate_path: None,
         }
     }
 }
@@ -80,6 +173,26 @@ pub enum STTError {
     NetworkError(String),
     #[error("Invalid audio format: {0}")]
     InvalidAudioFormat(String),
+    #[error("Connection to {provider} timed out after {timeout:?}")]
+    ConnectionTimeout { provider: String, timeout: Duration },
+}
+
+impl STTError {
+    /// Get the error code sent to clients with the sanitized message
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            STTError::ConnectionFailed(_) | STTError::NetworkError(_) => {
+                error_codes::STT_CONNECTION_FAILED
+            }
+            STTError::ConnectionTimeout { .. } => error_codes::STT_CONNECTION_TIMEOUT,
+            STTError::AuthenticationFailed(_) => error_codes::STT_AUTHENTICATION_FAILED,
+            STTError::AudioProcessingError(_) | STTError::InvalidAudioFormat(_) => {
+                error_codes::STT_INVALID_AUDIO
+            }
+            STTError::ConfigurationError(_) => error_codes::STT_INVALID_CONFIG,
+            STTError::ProviderError(_) => error_codes::STT_PROVIDER_ERROR,
+        }
+    }
 }
 
 /// Type alias for STT result callback
@@ -90,6 +203,28 @@ pub type STTResultCallback =
 pub type STTErrorCallback =
     Arc<dyn Fn(STTError) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
+/// Voice activity event reported by an STT provider
+///
+/// Arrives ahead of any transcript for the same speech, so it can drive
+/// barge-in before the first interim result.
+#[derive(Debug, Clone, PartialEq)]
+pub enum STTVadEvent {
+    /// The provider detected the start of speech
+    SpeechStarted {
+        /// Offset into the audio stream in seconds, when reported
+        timestamp: Option<f64>,
+    },
+    /// The provider detected the end of an utterance
+    UtteranceEnd {
+        /// End of the last recognized word in seconds, when reported
+        last_word_end: Option<f64>,
+    },
+}
+
+/// Type alias for STT voice activity callback
+pub type STTVadCallback =
+    Arc<dyn Fn(STTVadEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
 /// Base trait for Speech-to-Text providers
 #[async_trait::async_trait]
 pub trait BaseSTT: Send + Sync {
@@ -110,6 +245,28 @@ pub trait BaseSTT: Send + Sync {
     /// * `Result<(), STTError>` - Success or error
     async fn connect(&mut self) -> Result<(), STTError>;
 
+    /// Connect to the STT provider, giving up after `timeout`
+    ///
+    /// The pending `connect()` is cancelled when the timeout elapses.
+    ///
+    /// # Arguments
+    /// * `timeout` - Upper bound on the whole connection attempt, including provider retries
+    ///
+    /// # Returns
+    /// * `Result<(), STTError>` - Success, the provider's error, or `STTError::ConnectionTimeout`
+    async fn connect_with_timeout(&mut self, timeout: Duration) -> Result<(), STTError> {
+        match tokio::time::timeout(timeout, self.connect()).await {
+            Ok(result) => result,
+            Err(_) => {
+                let provider = match self.get_config() {
+                    Some(config) if !config.provider.is_empty() => config.provider.clone(),
+                    _ => self.get_provider_info().to_string(),
+                };
+                Err(STTError::ConnectionTimeout { provider, timeout })
+            }
+        }
+    }
+
     /// Disconnect from the STT provider
     ///
     /// # Returns
@@ -166,6 +323,56 @@ pub trait BaseSTT: Send + Sync {
 
     /// Get provider-specific information
     fn get_provider_info(&self) -> &'static str;
+
+    /// Update the end-of-turn silence threshold on the live connection
+    ///
+    /// Used by adaptive endpointing. Providers that cannot change endpointing
+    /// mid-stream keep the default, and the gateway gates finals itself.
+    ///
+    /// # Arguments
+    /// * `silence_ms` - Silence after speech that ends the turn (ms)
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The provider applied the threshold
+    /// * `Ok(false)` - Live endpointing updates are not supported
+    async fn set_end_of_turn_silence(&mut self, _silence_ms: u32) -> Result<bool, STTError> {
+        Ok(false)
+    }
+
+    /// Register a callback for voice activity events
+    ///
+    /// # Arguments
+    /// * `callback` - Callback function to handle VAD events
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The provider reports VAD events to the callback
+    /// * `Ok(false)` - VAD events are not supported
+    async fn on_vad_event(&mut self, _callback: STTVadCallback) -> Result<bool, STTError> {
+        Ok(false)
+    }
+
+    /// Ask the provider to finalize the current utterance immediately
+    ///
+    /// # Returns
+    /// * `Ok(true)` - A finalize request was sent; the next final result
+    ///   closes the utterance
+    /// * `Ok(false)` - Finalizing on demand is not supported
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        Ok(false)
+    }
+
+    /// Let the gateway decide when utterances end
+    ///
+    /// With manual commit the provider stops endpointing on its own and only
+    /// commits an utterance when asked to [`finalize`](Self::finalize) it.
+    /// Takes effect on the next connection.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The provider switched its commit mode
+    /// * `Ok(false)` - Manual commit is not supported
+    async fn set_manual_commit(&mut self, _enabled: bool) -> Result<bool, STTError> {
+        Ok(false)
+    }
 }
 
 /// Factory trait for creating STT providers
@@ -240,6 +447,7 @@ mod tests {
         config: Option<STTConfig>,
         connected: AtomicBool,
         callback: Option<STTResultCallback>,
+        connect_delay: Option<Duration>,
     }
 
     #[async_trait::async_trait]
@@ -249,10 +457,14 @@ mod tests {
                 config: Some(config),
                 connected: AtomicBool::new(false),
                 callback: None,
+                connect_delay: None,
             })
         }
 
         async fn connect(&mut self) -> Result<(), STTError> {
+            if let Some(delay) = self.connect_delay {
+                tokio::time::sleep(delay).await;
+            }
             self.connected.store(true, Ordering::Relaxed);
             Ok(())
         }
@@ -324,6 +536,8 @@ mod tests {
             channels: 1,
             punctuation: true,
             encoding: "linear16".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = MockSTT::new(config.clone()).unwrap();
@@ -380,6 +594,39 @@ mod tests {
         assert_eq!(stt.get_provider_info(), "MockSTT v1.0");
     }
 
+    #[tokio::test]
+    async fn test_connect_with_timeout() {
+        let config = STTConfig {
+            provider: "mock".to_string(),
+            ..Default::default()
+        };
+        let mut stt = MockSTT::new(config).unwrap();
+        stt.connect_with_timeout(Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(stt.is_ready());
+
+        let mut stt = MockSTT::new(STTConfig {
+            provider: "mock".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        stt.connect_delay = Some(Duration::from_secs(5));
+        let timeout = Duration::from_millis(20);
+        let err = stt.connect_with_timeout(timeout).await.unwrap_err();
+        match err {
+            STTError::ConnectionTimeout {
+                provider,
+                timeout: elapsed,
+            } => {
+                assert_eq!(provider, "mock");
+                assert_eq!(elapsed, timeout);
+            }
+            other => panic!("expected ConnectionTimeout, got {other:?}"),
+        }
+        assert!(!stt.is_ready());
+    }
+
     #[test]
     fn test_stt_result_creation() {
         let result = STTResult::new("Hello world".to_string(), true, true, 0.95);
@@ -24,11 +24,13 @@ use bytes::Bytes;
 use futures::{SinkExt, StreamExt};
 use tokio::sync::{Mutex, Notify, mpsc, oneshot};
 use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use tracing::{debug, error, info, warn};
 
 use super::config::CartesiaSTTConfig;
 use super::messages::CartesiaMessage;
+use crate::core::providers::headers::insert_custom_headers;
 use crate::core::stt::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -240,6 +242,12 @@ impl CartesiaSTT {
 
         let ws_url = config.build_websocket_url(&config.base.api_key);
 
+        // Build the handshake request so custom headers can be attached
+        let mut request = ws_url
+            .into_client_request()
+            .map_err(|e| STTError::ConnectionFailed(format!("Invalid WebSocket URL: {e}")))?;
+        insert_custom_headers(request.headers_mut(), &config.base.custom_headers);
+
         // Create channels for communication (bounded for backpressure on audio)
         let (ws_tx, mut ws_rx) = mpsc::channel::<Bytes>(32);
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
@@ -260,7 +268,7 @@ impl CartesiaSTT {
         // Start the connection task
         let connection_handle = tokio::spawn(async move {
             // Connect to Cartesia WebSocket
-            let (ws_stream, _) = match connect_async(&ws_url).await {
+            let (ws_stream, _) = match connect_async(request).await {
                 Ok(result) => result,
                 Err(e) => {
                     let stt_error =
@@ -658,6 +666,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <CartesiaSTT as BaseSTT>::new(config).unwrap();
@@ -676,6 +686,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = <CartesiaSTT as BaseSTT>::new(config);
@@ -698,6 +710,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <CartesiaSTT as BaseSTT>::new(config).unwrap();
@@ -731,6 +745,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <CartesiaSTT as BaseSTT>::new(config).unwrap();
@@ -750,6 +766,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <CartesiaSTT as BaseSTT>::new(config).unwrap();
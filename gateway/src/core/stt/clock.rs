@@ -0,0 +1,305 @@
+//! Session audio clock
+//!
+//! STT providers time their results from the start of their own stream, so
+//! every reconnect restarts their timeline at zero. [`SessionAudioClock`]
+//! counts the audio sent since the session started and maps provider times
+//! onto that count, giving transcript timestamps that keep increasing across
+//! reconnects and can be anchored to wall-clock (UTC) time.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::base::{STTResult, WordTiming};
+
+/// Bytes per sample for an STT audio encoding
+///
+/// G.711 (μ-law and A-law) audio is 8-bit; everything else sent to STT
+/// providers is 16-bit PCM.
+fn bytes_per_sample(encoding: &str) -> u64 {
+    match encoding.to_ascii_lowercase().as_str() {
+        "mulaw" | "ulaw" | "mu-law" | "pcm_mulaw" | "alaw" | "a-law" | "pcm_alaw" => 1,
+        _ => 2,
+    }
+}
+
+/// Timing of a transcript on the session audio clock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TranscriptTiming {
+    /// Start of the audio the result covers, in seconds since the session's first audio
+    #[cfg_attr(feature = "openapi", schema(example = 12.48))]
+    pub start: f64,
+    /// End of the audio the result covers, in seconds since the session's first audio
+    #[cfg_attr(feature = "openapi", schema(example = 13.9))]
+    pub end: f64,
+    /// `start` as wall-clock time (milliseconds since the Unix epoch, UTC)
+    #[cfg_attr(feature = "openapi", schema(example = 1700000012480_u64))]
+    pub start_utc_ms: u64,
+    /// `end` as wall-clock time (milliseconds since the Unix epoch, UTC)
+    #[cfg_attr(feature = "openapi", schema(example = 1700000013900_u64))]
+    pub end_utc_ms: u64,
+    /// Word timings in seconds since the session's first audio (omitted when
+    /// the provider reports none)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<WordTiming>,
+}
+
+/// Monotonic clock of the audio sent to the STT provider in one session
+///
+/// The clock advances by the audio sent, not by elapsed time, so provider
+/// timestamps (which measure audio) map onto it exactly. Provider results are
+/// placed relative to the start of the current provider stream, which moves
+/// forward on every reconnect; audio replayed into a new stream moves it back
+/// by the replayed amount, since the provider hears that audio again.
+///
+/// Results never start before the previous final result ended, so the
+/// emitted timeline is monotonic even when a replay produces overlapping
+/// results.
+#[derive(Debug, Clone)]
+pub struct SessionAudioClock {
+    bytes_per_second: u64,
+    bytes_sent: u64,
+    stream_origin: u64,
+    started_at_ms: Option<u64>,
+    last_final_end: f64,
+}
+
+impl SessionAudioClock {
+    /// Create a clock for audio in the given format
+    pub fn new(sample_rate: u32, channels: u16, encoding: &str) -> Self {
+        let frame_bytes = u64::from(channels.max(1)) * bytes_per_sample(encoding);
+        Self {
+            bytes_per_second: (u64::from(sample_rate) * frame_bytes).max(1),
+            bytes_sent: 0,
+            stream_origin: 0,
+            started_at_ms: None,
+            last_final_end: 0.0,
+        }
+    }
+
+    /// Count audio sent to the provider
+    ///
+    /// The first call anchors the clock to the current wall-clock time.
+    pub fn record_audio(&mut self, len: usize) {
+        self.record_audio_at(len, now_ms());
+    }
+
+    fn record_audio_at(&mut self, len: usize, now_ms: u64) {
+        self.started_at_ms.get_or_insert(now_ms);
+        self.bytes_sent += len as u64;
+    }
+
+    /// Seconds of audio sent since the session started
+    pub fn elapsed(&self) -> f64 {
+        self.seconds(self.bytes_sent)
+    }
+
+    /// Mark the start of a new provider stream
+    ///
+    /// `replayed_bytes` is the audio already counted by [`record_audio`](Self::record_audio)
+    /// that is sent again to the new stream before any new audio.
+    pub fn stream_restarted(&mut self, replayed_bytes: usize) {
+        self.stream_origin = self
+            .bytes_sent
+            .saturating_sub(replayed_bytes as u64)
+            .max(self.stream_origin);
+    }
+
+    /// Place a result on the session clock
+    ///
+    /// Results without provider timing cover the audio since the previous
+    /// final result up to the audio sent so far.
+    pub fn anchor(&mut self, result: &STTResult) -> TranscriptTiming {
+        let origin = self.seconds(self.stream_origin);
+        let floor = self.last_final_end;
+
+        let mut cursor = floor;
+        let words: Vec<WordTiming> = result
+            .words
+            .iter()
+            .map(|word| {
+                let start = (origin + word.start).max(cursor);
+                let end = (origin + word.end).max(start);
+                cursor = end;
+                WordTiming {
+                    word: word.word.clone(),
+                    start,
+                    end,
+                }
+            })
+            .collect();
+
+        let provider_start = result
+            .start
+            .or_else(|| result.words.first().map(|w| w.start));
+        let provider_end = result
+            .end
+            .or_else(|| result.words.last().map(|w| w.end))
+            .or(provider_start);
+        let (start, end) = match (provider_start, provider_end) {
+            (Some(start), Some(end)) => (origin + start, origin + end),
+            _ => (floor, self.elapsed()),
+        };
+        let start = start.max(floor);
+        let end = end.max(start).max(cursor);
+
+        // Markers without text (such as a forced speech_final) leave the floor alone
+        if result.is_final && !result.transcript.is_empty() {
+            self.last_final_end = end;
+        }
+
+        TranscriptTiming {
+            start,
+            end,
+            start_utc_ms: self.utc_ms(start),
+            end_utc_ms: self.utc_ms(end),
+            words,
+        }
+    }
+
+    fn seconds(&self, bytes: u64) -> f64 {
+        bytes as f64 / self.bytes_per_second as f64
+    }
+
+    fn utc_ms(&self, seconds: f64) -> u64 {
+        let started_at_ms = self.started_at_ms.unwrap_or_else(now_ms);
+        started_at_ms + (seconds * 1000.0).round() as u64
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16 kHz mono: 32000 bytes per second
+    const SECOND: usize = 32_000;
+
+    fn clock() -> SessionAudioClock {
+        let mut clock = SessionAudioClock::new(16_000, 1, "linear16");
+        clock.record_audio_at(0, 1_700_000_000_000);
+        clock
+    }
+
+    fn final_result(start: f64, end: f64) -> STTResult {
+        STTResult::new("hello world".to_string(), true, false, 0.9)
+            .with_span(start, end)
+            .with_words(vec![
+                WordTiming {
+                    word: "hello".to_string(),
+                    start,
+                    end: (start + end) / 2.0,
+                },
+                WordTiming {
+                    word: "world".to_string(),
+                    start: (start + end) / 2.0,
+                    end,
+                },
+            ])
+    }
+
+    #[test]
+    fn test_anchors_to_session_and_wall_clock() {
+        let mut clock = clock();
+        clock.record_audio(3 * SECOND);
+        assert_eq!(clock.elapsed(), 3.0);
+
+        let timing = clock.anchor(&final_result(1.0, 2.0));
+        assert_eq!(timing.start, 1.0);
+        assert_eq!(timing.end, 2.0);
+        assert_eq!(timing.start_utc_ms, 1_700_000_001_000);
+        assert_eq!(timing.end_utc_ms, 1_700_000_002_000);
+        assert_eq!(timing.words.len(), 2);
+        assert_eq!(timing.words[1].start, 1.5);
+    }
+
+    #[test]
+    fn test_reconnect_with_replay_stays_monotonic() {
+        let mut clock = clock();
+        let mut finals = Vec::new();
+
+        // First stream: 4 seconds of audio, results up to 3.5s
+        clock.record_audio(4 * SECOND);
+        finals.push(clock.anchor(&final_result(0.5, 2.0)));
+        finals.push(clock.anchor(&final_result(2.0, 3.5)));
+
+        // Reconnect and replay the last second; the new stream starts at zero
+        clock.stream_restarted(SECOND);
+        clock.record_audio(2 * SECOND);
+        // The replayed second is transcribed again by the new stream
+        finals.push(clock.anchor(&final_result(0.0, 1.0)));
+        finals.push(clock.anchor(&final_result(1.25, 2.75)));
+
+        assert_eq!(finals[2].start, 3.5, "replayed audio is clamped");
+        assert_eq!(finals[2].end, 4.0);
+        assert_eq!(finals[3].start, 4.25);
+        assert_eq!(finals[3].end, 5.75);
+
+        let mut previous_end = 0.0;
+        for timing in &finals {
+            assert!(timing.start >= previous_end);
+            assert!(timing.end >= timing.start);
+            for word in &timing.words {
+                assert!(word.start >= previous_end);
+                assert!(word.end >= word.start);
+                previous_end = word.end;
+            }
+            previous_end = timing.end;
+            assert!(timing.end_utc_ms >= timing.start_utc_ms);
+        }
+    }
+
+    #[test]
+    fn test_reconnect_without_replay() {
+        let mut clock = clock();
+        clock.record_audio(2 * SECOND);
+        clock.stream_restarted(0);
+        clock.record_audio(SECOND);
+
+        let timing = clock.anchor(&final_result(0.25, 0.75));
+        assert_eq!(timing.start, 2.25);
+        assert_eq!(timing.end, 2.75);
+    }
+
+    #[test]
+    fn test_untimed_results_use_audio_position() {
+        let mut clock = clock();
+        clock.record_audio(SECOND);
+        let interim = STTResult::new("hel".to_string(), false, false, 0.5);
+        let timing = clock.anchor(&interim);
+        assert_eq!((timing.start, timing.end), (0.0, 1.0));
+
+        let final_result = STTResult::new("hello".to_string(), true, false, 0.9);
+        let timing = clock.anchor(&final_result);
+        assert_eq!((timing.start, timing.end), (0.0, 1.0));
+
+        clock.record_audio(SECOND);
+        let timing = clock.anchor(&final_result);
+        assert_eq!((timing.start, timing.end), (1.0, 2.0));
+    }
+
+    #[test]
+    fn test_g711_audio_is_one_byte_per_sample() {
+        let mut clock = SessionAudioClock::new(8_000, 1, "mulaw");
+        clock.record_audio(8_000);
+        assert_eq!(clock.elapsed(), 1.0);
+    }
+
+    #[test]
+    fn test_interim_results_do_not_move_the_floor() {
+        let mut clock = clock();
+        clock.record_audio(3 * SECOND);
+        let interim = STTResult::new("hello".to_string(), false, false, 0.5).with_span(0.0, 2.5);
+        clock.anchor(&interim);
+
+        let timing = clock.anchor(&final_result(0.0, 1.0));
+        assert_eq!(timing.start, 0.0);
+    }
+}
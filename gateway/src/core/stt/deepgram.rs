@@ -11,7 +11,15 @@ use tokio_tungstenite::tungstenite::handshake::client::generate_key;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use tracing::{debug, error, info, warn};
 
-use super::base::{BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback};
+use crate::core::credentials::{check_credentials, credential_check_client};
+use crate::core::providers::connection_budget::{DEEPGRAM_CONNECTION_BUDGET, connection_budgets};
+use crate::core::providers::headers::insert_custom_headers;
+use crate::core::tts::deepgram::DEEPGRAM_API_BASE_URL;
+
+use super::base::{
+    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback, STTVadCallback,
+    STTVadEvent, WordTiming,
+};
 
 /// Type alias for the complex callback function type
 type AsyncSTTCallback = Box<
@@ -66,7 +74,7 @@ pub struct DeepgramSTTConfig {
     pub endpointing: Option<u32>,
     /// Custom tags for request identification
     pub tag: Option<String>,
-    /// Utterance end timeout in milliseconds
+    /// Utterance end timeout in milliseconds (Deepgram requires at least 1000)
     pub utterance_end_ms: Option<u32>,
 }
 
@@ -84,7 +92,7 @@ impl Default for DeepgramSTTConfig {
             vad_events: true,
             endpointing: Some(200),
             tag: None,
-            utterance_end_ms: Some(500),
+            utterance_end_ms: Some(1000),
         }
     }
 }
@@ -113,6 +121,9 @@ pub struct DeepgramAlternative {
     pub transcript: String,
     pub confidence: f32,
     pub words: Option<Vec<DeepgramWord>>,
+    /// Languages detected in the result, most prominent first (multilingual models)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -145,6 +156,38 @@ pub struct DeepgramModelInfo {
     pub arch: String,
 }
 
+/// Deepgram voice activity message (`SpeechStarted` or `UtteranceEnd`)
+///
+/// Unlike transcription results, `channel` is a list of channel indices here,
+/// so these messages do not parse as [`DeepgramResponse`].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeepgramVadEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub channel: Option<Vec<u32>>,
+    /// Start of speech in seconds (`SpeechStarted`)
+    pub timestamp: Option<f64>,
+    /// End of the last word in seconds (`UtteranceEnd`)
+    pub last_word_end: Option<f64>,
+}
+
+impl DeepgramVadEvent {
+    /// Convert to a provider-neutral VAD event
+    ///
+    /// Returns `None` for any other message type.
+    pub fn to_stt_event(&self) -> Option<STTVadEvent> {
+        match self.event_type.as_str() {
+            "SpeechStarted" => Some(STTVadEvent::SpeechStarted {
+                timestamp: self.timestamp,
+            }),
+            "UtteranceEnd" => Some(STTVadEvent::UtteranceEnd {
+                last_word_end: self.last_word_end,
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Deepgram error response structure
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DeepgramError {
@@ -165,10 +208,14 @@ pub struct DeepgramSTT {
     state_notify: Arc<Notify>,
     /// WebSocket sender for audio data (bounded channel for backpressure)
     ws_sender: Option<mpsc::Sender<Bytes>>,
+    /// Control message sender (JSON text frames such as `Finalize`)
+    control_tx: Option<mpsc::Sender<String>>,
     /// Shutdown signal sender
     shutdown_tx: Option<oneshot::Sender<()>>,
     /// Result channel sender
     result_tx: Option<mpsc::Sender<STTResult>>,
+    /// VAD event channel sender
+    vad_tx: Option<mpsc::Sender<STTVadEvent>>,
     /// Error channel sender for streaming errors
     error_tx: Option<mpsc::Sender<STTError>>,
     /// Connection handle
@@ -177,13 +224,38 @@ pub struct DeepgramSTT {
     result_forward_handle: Option<tokio::task::JoinHandle<()>>,
     /// Error forwarding task handle
     error_forward_handle: Option<tokio::task::JoinHandle<()>>,
+    /// VAD event forwarding task handle
+    vad_forward_handle: Option<tokio::task::JoinHandle<()>>,
     /// Shared callback storage for async access
     result_callback: Arc<Mutex<Option<AsyncSTTCallback>>>,
     /// Error callback storage for streaming errors
     error_callback: Arc<Mutex<Option<AsyncErrorCallback>>>,
+    /// VAD event callback storage
+    vad_callback: Arc<Mutex<Option<STTVadCallback>>>,
 }
 
 impl DeepgramSTT {
+    /// Verify an API key with a lightweight authenticated request to `/v1/projects`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if Deepgram accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(DEEPGRAM_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/v1/projects", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .header("Authorization", format!("Token {api_key}")),
+        )
+        .await
+    }
+
     /// Build the WebSocket URL with query parameters (optimized string building)
     fn build_websocket_url(&self, config: &DeepgramSTTConfig) -> Result<String, STTError> {
         let mut url = String::with_capacity(256); // Pre-allocate expected size
@@ -210,6 +282,15 @@ impl DeepgramSTT {
             url.push_str(&endpointing.to_string());
         }
 
+        if config.vad_events {
+            url.push_str("&vad_events=true");
+        }
+
+        if let Some(utterance_end_ms) = config.utterance_end_ms {
+            url.push_str("&utterance_end_ms=");
+            url.push_str(&utterance_end_ms.to_string());
+        }
+
         if let Some(tag) = &config.tag {
             url.push_str("&tag=");
             url.push_str(tag);
@@ -227,6 +308,7 @@ impl DeepgramSTT {
     fn handle_websocket_message(
         message: Message,
         result_tx: &mpsc::Sender<STTResult>,
+        vad_tx: &mpsc::Sender<STTVadEvent>,
     ) -> Result<(), STTError> {
         match message {
             Message::Text(text) => {
@@ -240,12 +322,39 @@ impl DeepgramSTT {
                                 if let Some(channel) = response.channel
                                     && let Some(alternative) = channel.alternatives.first()
                                 {
-                                    let stt_result = STTResult::new(
+                                    let mut stt_result = STTResult::new(
                                         alternative.transcript.clone(),
                                         response.is_final.unwrap_or(false),
                                         response.speech_final.unwrap_or(false),
                                         alternative.confidence,
                                     );
+                                    if let (Some(start), Some(duration)) =
+                                        (response.start, response.duration)
+                                    {
+                                        stt_result = stt_result.with_span(start, start + duration);
+                                    }
+                                    if let Some(words) = &alternative.words {
+                                        stt_result = stt_result.with_words(
+                                            words
+                                                .iter()
+                                                .map(|w| WordTiming {
+                                                    word: w
+                                                        .punctuated_word
+                                                        .clone()
+                                                        .unwrap_or_else(|| w.word.clone()),
+                                                    start: w.start,
+                                                    end: w.end,
+                                                })
+                                                .collect(),
+                                        );
+                                    }
+                                    if let Some(language) = alternative
+                                        .languages
+                                        .as_ref()
+                                        .and_then(|languages| languages.first())
+                                    {
+                                        stt_result = stt_result.with_language(language.clone());
+                                    }
 
                                     // Send result (non-blocking with bounded channel)
                                     if let Err(e) = result_tx.try_send(stt_result) {
@@ -264,6 +373,9 @@ impl DeepgramSTT {
                                 // Log metadata for debugging but don't process
                                 debug!("Received metadata response");
                             }
+                            "SpeechStarted" | "UtteranceEnd" => {
+                                Self::forward_vad_event(&text, vad_tx);
+                            }
                             "Error" => {
                                 // Try to parse as error for better message
                                 let error_msg = if let Ok(error) =
@@ -282,6 +394,11 @@ impl DeepgramSTT {
                         }
                     }
                     Err(_) => {
+                        // VAD events carry `channel` as an index list and land here
+                        if Self::forward_vad_event(&text, vad_tx) {
+                            return Ok(());
+                        }
+
                         // Not a DeepgramResponse, might be a simple error or other message
                         if text.contains("\"type\":\"Error\"") {
                             let error_msg =
@@ -307,29 +424,62 @@ impl DeepgramSTT {
         Ok(())
     }
 
+    /// Parse a `SpeechStarted` / `UtteranceEnd` message and queue it for the VAD callback
+    ///
+    /// # Returns
+    /// * `bool` - True if the message was a VAD event
+    fn forward_vad_event(text: &str, vad_tx: &mpsc::Sender<STTVadEvent>) -> bool {
+        let Some(event) = serde_json::from_str::<DeepgramVadEvent>(text)
+            .ok()
+            .and_then(|event| event.to_stt_event())
+        else {
+            return false;
+        };
+
+        debug!("Received VAD event: {:?}", event);
+        if let Err(mpsc::error::TrySendError::Full(_)) = vad_tx.try_send(event) {
+            warn!("STT VAD event channel full - dropping event");
+        }
+        true
+    }
+
     /// Start the WebSocket connection task (optimized for minimal latency)
     async fn start_connection(&mut self, config: DeepgramSTTConfig) -> Result<(), STTError> {
         let ws_url = self.build_websocket_url(&config)?;
 
+        // Deepgram counts STT and TTS connections of a key against one budget;
+        // the connection task holds the permit until the websocket closes
+        let permit = connection_budgets()
+            .acquire(DEEPGRAM_CONNECTION_BUDGET, &config.base.api_key)
+            .await
+            .map_err(|e| STTError::ConnectionFailed(e.to_string()))?;
+
         // Create channels for communication (bounded for backpressure)
         let (ws_tx, mut ws_rx) = mpsc::channel::<Bytes>(32);
+        let (control_tx, mut control_rx) = mpsc::channel::<String>(8);
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
         // Bounded channels for backpressure - 256 should handle bursts while preventing memory exhaustion
         let (result_tx, mut result_rx) = mpsc::channel::<STTResult>(256);
         let (error_tx, mut error_rx) = mpsc::channel::<STTError>(64);
+        let (vad_tx, mut vad_rx) = mpsc::channel::<STTVadEvent>(32);
         let (connected_tx, connected_rx) = oneshot::channel::<()>();
 
         // Store channels
         self.ws_sender = Some(ws_tx);
+        self.control_tx = Some(control_tx);
         self.shutdown_tx = Some(shutdown_tx);
         self.result_tx = Some(result_tx.clone());
+        self.vad_tx = Some(vad_tx.clone());
         self.error_tx = Some(error_tx.clone());
 
         // Clone necessary data for the connection task
         let api_key = config.base.api_key.clone();
+        let custom_headers = config.base.custom_headers.clone();
 
         // Start the connection task
         let connection_handle = tokio::spawn(async move {
+            let _permit = permit;
+
             // Update state to connecting (this will be set by the main thread)
             // state_notify.notify_waiters() - don't notify here, main thread handles connecting state
 
@@ -346,7 +496,10 @@ impl DeepgramSTT {
                 .header("Authorization", format!("token {api_key}"))
                 .body(())
             {
-                Ok(request) => request,
+                Ok(mut request) => {
+                    insert_custom_headers(request.headers_mut(), &custom_headers);
+                    request
+                }
                 Err(e) => {
                     let stt_error = STTError::ConnectionFailed(format!(
                         "Failed to create WebSocket request: {e}"
@@ -399,11 +552,26 @@ impl DeepgramSTT {
                         last_activity = Instant::now();
                     }
 
+                    // Handle outgoing control messages
+                    Some(control) = control_rx.recv() => {
+                        if let Err(e) = ws_sink.send(Message::Text(control.into())).await {
+                            let stt_error = STTError::NetworkError(format!(
+                                "Failed to send control message: {e}"
+                            ));
+                            error!("{}", stt_error);
+                            let _ = error_tx.try_send(stt_error);
+                            break;
+                        }
+                        last_activity = Instant::now();
+                    }
+
                     // Handle incoming messages with idle timeout
                     message = timeout(WS_MESSAGE_TIMEOUT, ws_stream.next()) => {
                         match message {
                             Ok(Some(Ok(msg))) => {
-                                if let Err(e) = Self::handle_websocket_message(msg, &result_tx) {
+                                if let Err(e) =
+                                    Self::handle_websocket_message(msg, &result_tx, &vad_tx)
+                                {
                                     error!("Streaming error from Deepgram: {}", e);
                                     let _ = error_tx.try_send(e);
                                     break;
@@ -502,6 +670,20 @@ impl DeepgramSTT {
         // Store the error forwarding handle for cleanup
         self.error_forward_handle = Some(error_forwarding_handle);
 
+        // Start VAD event forwarding task with shared callback
+        let vad_callback_ref = self.vad_callback.clone();
+        let vad_forwarding_handle = tokio::spawn(async move {
+            while let Some(event) = vad_rx.recv().await {
+                let callback = vad_callback_ref.lock().await.clone();
+                if let Some(callback) = callback {
+                    callback(event).await;
+                }
+            }
+        });
+
+        // Store the VAD forwarding handle for cleanup
+        self.vad_forward_handle = Some(vad_forwarding_handle);
+
         // Update state and wait for connection
         self.state = ConnectionState::Connecting;
 
@@ -534,14 +716,18 @@ impl Default for DeepgramSTT {
             state: ConnectionState::Disconnected,
             state_notify: Arc::new(Notify::new()),
             ws_sender: None,
+            control_tx: None,
             shutdown_tx: None,
             result_tx: None,
+            vad_tx: None,
             error_tx: None,
             connection_handle: None,
             result_forward_handle: None,
             error_forward_handle: None,
+            vad_forward_handle: None,
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
+            vad_callback: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -569,7 +755,7 @@ impl BaseSTT for DeepgramSTT {
             vad_events: true,
             endpointing: Some(200),
             tag: None,
-            utterance_end_ms: Some(500),
+            utterance_end_ms: Some(1000),
         };
 
         Ok(Self {
@@ -577,14 +763,18 @@ impl BaseSTT for DeepgramSTT {
             state: ConnectionState::Disconnected,
             state_notify: Arc::new(Notify::new()),
             ws_sender: None,
+            control_tx: None,
             shutdown_tx: None,
             result_tx: None,
+            vad_tx: None,
             error_tx: None,
             connection_handle: None,
             result_forward_handle: None,
             error_forward_handle: None,
+            vad_forward_handle: None,
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
+            vad_callback: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -621,12 +811,21 @@ impl BaseSTT for DeepgramSTT {
             let _ = handle.await;
         }
 
+        // Clean up VAD forwarding task
+        if let Some(handle) = self.vad_forward_handle.take() {
+            handle.abort();
+            let _ = handle.await;
+        }
+
         // Clean up channels and callbacks
         self.ws_sender = None;
+        self.control_tx = None;
         self.result_tx = None;
+        self.vad_tx = None;
         self.error_tx = None;
         *self.result_callback.lock().await = None;
         *self.error_callback.lock().await = None;
+        *self.vad_callback.lock().await = None;
 
         // Update state
         self.state = ConnectionState::Disconnected;
@@ -682,6 +881,11 @@ impl BaseSTT for DeepgramSTT {
         Ok(())
     }
 
+    async fn on_vad_event(&mut self, callback: STTVadCallback) -> Result<bool, STTError> {
+        *self.vad_callback.lock().await = Some(callback);
+        Ok(self.config.as_ref().is_some_and(|c| c.vad_events))
+    }
+
     fn get_config(&self) -> Option<&STTConfig> {
         self.config.as_ref().map(|c| &c.base)
     }
@@ -705,7 +909,7 @@ impl BaseSTT for DeepgramSTT {
             vad_events: true,
             endpointing: Some(200),
             tag: None,
-            utterance_end_ms: Some(500),
+            utterance_end_ms: Some(1000),
         };
         self.config = Some(deepgram_config);
 
@@ -716,6 +920,28 @@ impl BaseSTT for DeepgramSTT {
     fn get_provider_info(&self) -> &'static str {
         "Deepgram STT WebSocket"
     }
+
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        if !self.is_ready() {
+            return Err(STTError::ConnectionFailed(
+                "Not connected to Deepgram".to_string(),
+            ));
+        }
+
+        let control_tx = self.control_tx.as_ref().ok_or_else(|| {
+            STTError::ConnectionFailed("Control channel not available".to_string())
+        })?;
+
+        // Deepgram flushes buffered audio and replies with a final result
+        // marked `from_finalize`
+        control_tx
+            .send(r#"{"type":"Finalize"}"#.to_string())
+            .await
+            .map_err(|e| STTError::NetworkError(format!("Failed to send Finalize: {e}")))?;
+
+        debug!("Sent Finalize message to Deepgram");
+        Ok(true)
+    }
 }
 
 impl Drop for DeepgramSTT {
@@ -724,6 +950,12 @@ impl Drop for DeepgramSTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -743,6 +975,8 @@ mod tests {
             channels: 1,
             punctuation: true,
             encoding: "linear16".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <DeepgramSTT as BaseSTT>::new(config).unwrap();
@@ -762,6 +996,8 @@ mod tests {
             channels: 1,
             punctuation: true,
             encoding: "linear16".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = <DeepgramSTT as BaseSTT>::new(config);
@@ -786,6 +1022,8 @@ mod tests {
                 channels: 1,
                 punctuation: false, // Set to false here for testing
                 encoding: "linear16".to_string(),
+                custom_headers: Default::default(),
+                certificate_path: None,
             },
             interim_results: true,
             smart_format: false,
@@ -808,6 +1046,8 @@ mod tests {
         assert!(url.contains("keywords=hello,world"));
         assert!(url.contains("endpointing=300"));
         assert!(url.contains("tag=test-tag"));
+        assert!(url.contains("vad_events=true"));
+        assert!(url.contains("utterance_end_ms=1000"));
     }
 
     #[tokio::test]
@@ -834,7 +1074,8 @@ mod tests {
         "#;
 
         let message = Message::Text(json_response.to_string().into());
-        let result = DeepgramSTT::handle_websocket_message(message, &result_tx);
+        let (vad_tx, _vad_rx) = mpsc::channel::<STTVadEvent>(32);
+        let result = DeepgramSTT::handle_websocket_message(message, &result_tx, &vad_tx);
 
         assert!(result.is_ok());
 
@@ -847,6 +1088,76 @@ mod tests {
         assert_eq!(received_result.confidence, 0.98);
         assert!(received_result.is_final);
         assert!(received_result.is_speech_final);
+        assert_eq!(received_result.start, Some(0.0));
+        assert_eq!(received_result.end, Some(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_word_timings() {
+        let (result_tx, mut result_rx) = mpsc::channel::<STTResult>(256);
+        let (vad_tx, _vad_rx) = mpsc::channel::<STTVadEvent>(32);
+
+        let json_response = r#"{"type":"Results","channel":{"alternatives":[{"transcript":"hello world","confidence":0.9,"words":[{"word":"hello","start":3.1,"end":3.4,"confidence":0.9,"punctuated_word":"Hello"},{"word":"world","start":3.5,"end":3.9,"confidence":0.9}]}]},"is_final":true,"speech_final":false,"duration":1.0,"start":3.0}"#;
+        let message = Message::Text(json_response.to_string().into());
+        DeepgramSTT::handle_websocket_message(message, &result_tx, &vad_tx).unwrap();
+
+        let received = result_rx.recv().await.unwrap();
+        assert_eq!((received.start, received.end), (Some(3.0), Some(4.0)));
+        assert_eq!(received.words.len(), 2);
+        assert_eq!(received.words[0].word, "Hello");
+        assert_eq!(received.words[1].word, "world");
+        assert_eq!((received.words[1].start, received.words[1].end), (3.5, 3.9));
+    }
+
+    #[tokio::test]
+    async fn test_detected_language() {
+        let (result_tx, mut result_rx) = mpsc::channel::<STTResult>(256);
+        let (vad_tx, _vad_rx) = mpsc::channel::<STTVadEvent>(32);
+
+        let json_response = r#"{"type":"Results","channel":{"alternatives":[{"transcript":"kal meeting hai","confidence":0.9,"languages":["hi","en"]}]},"is_final":true,"speech_final":true,"duration":1.0,"start":0.0}"#;
+        let message = Message::Text(json_response.to_string().into());
+        DeepgramSTT::handle_websocket_message(message, &result_tx, &vad_tx).unwrap();
+
+        let received = result_rx.recv().await.unwrap();
+        assert_eq!(received.detected_language.as_deref(), Some("hi"));
+    }
+
+    #[tokio::test]
+    async fn test_vad_event_handling() {
+        let (result_tx, mut result_rx) = mpsc::channel::<STTResult>(256);
+        let (vad_tx, mut vad_rx) = mpsc::channel::<STTVadEvent>(32);
+
+        // Frames as recorded from the Deepgram live API
+        let frames = [
+            r#"{"type":"SpeechStarted","channel":[0],"timestamp":9.54}"#,
+            r#"{"type":"UtteranceEnd","channel":[0,1],"last_word_end":2.395}"#,
+        ];
+        for frame in frames {
+            let message = Message::Text(frame.to_string().into());
+            assert!(DeepgramSTT::handle_websocket_message(message, &result_tx, &vad_tx).is_ok());
+        }
+
+        assert_eq!(
+            vad_rx.try_recv().unwrap(),
+            STTVadEvent::SpeechStarted {
+                timestamp: Some(9.54)
+            }
+        );
+        assert_eq!(
+            vad_rx.try_recv().unwrap(),
+            STTVadEvent::UtteranceEnd {
+                last_word_end: Some(2.395)
+            }
+        );
+        assert!(vad_rx.try_recv().is_err());
+        assert!(result_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_vad_event_conversion_ignores_other_types() {
+        let event: DeepgramVadEvent =
+            serde_json::from_str(r#"{"type":"Metadata","channel":[0]}"#).unwrap();
+        assert_eq!(event.to_stt_event(), None);
     }
 
     #[tokio::test]
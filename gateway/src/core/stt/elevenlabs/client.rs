@@ -17,6 +17,7 @@ use url::form_urlencoded;
 
 use super::config::{CommitStrategy, ElevenLabsAudioFormat, ElevenLabsRegion, ElevenLabsSTTConfig};
 use super::messages::{ElevenLabsMessage, InputAudioChunk};
+use crate::core::providers::headers::insert_custom_headers;
 use crate::core::stt::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -53,6 +54,17 @@ pub(crate) enum ConnectionState {
     Error(String),
 }
 
+/// Message queued for the WebSocket task
+///
+/// Commits share the audio queue so they follow the audio sent before them.
+#[derive(Debug)]
+pub(crate) enum OutgoingMessage {
+    /// Audio to transcribe
+    Audio(Bytes),
+    /// Commit the current utterance (manual commit strategy)
+    Commit,
+}
+
 // =============================================================================
 // Constants
 // =============================================================================
@@ -109,9 +121,9 @@ pub struct ElevenLabsSTT {
     /// State change notification
     state_notify: Arc<Notify>,
 
-    /// WebSocket sender for audio data
+    /// WebSocket sender for audio data and commits
     /// Uses bounded channel (32 items) to provide backpressure
-    ws_sender: Option<mpsc::Sender<Bytes>>,
+    ws_sender: Option<mpsc::Sender<OutgoingMessage>>,
 
     /// Shutdown signal sender
     shutdown_tx: Option<oneshot::Sender<()>>,
@@ -139,6 +151,9 @@ pub struct ElevenLabsSTT {
 
     /// Session ID from the ElevenLabs connection
     session_id: Option<String>,
+
+    /// WebSocket base URL used instead of the regional endpoint
+    base_url: Option<String>,
 }
 
 impl Default for ElevenLabsSTT {
@@ -157,6 +172,7 @@ impl Default for ElevenLabsSTT {
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
             session_id: None,
+            base_url: None,
         }
     }
 }
@@ -174,8 +190,13 @@ impl ElevenLabsSTT {
         // Pre-allocate with estimated capacity
         let mut url = String::with_capacity(512);
 
-        // Base URL from region
-        url.push_str(config.region.websocket_base_url());
+        // Base URL from region, unless overridden
+        url.push_str(
+            self.base_url
+                .as_deref()
+                .unwrap_or(config.region.websocket_base_url())
+                .trim_end_matches('/'),
+        );
         url.push_str("/v1/speech-to-text/realtime?");
 
         // Required: model_id (URL encoded for safety)
@@ -248,6 +269,25 @@ impl ElevenLabsSTT {
         region.host()
     }
 
+    /// Get the `Host` header value for the connection.
+    ///
+    /// Uses the host (and port) of the base URL override when one is set.
+    fn connection_host(&self, config: &ElevenLabsSTTConfig) -> Result<String, STTError> {
+        let Some(base_url) = &self.base_url else {
+            return Ok(Self::get_host_from_region(&config.region).to_string());
+        };
+        let parsed = url::Url::parse(base_url).map_err(|e| {
+            STTError::ConfigurationError(format!("Invalid ElevenLabs base URL '{base_url}': {e}"))
+        })?;
+        let host = parsed.host_str().ok_or_else(|| {
+            STTError::ConfigurationError(format!("ElevenLabs base URL '{base_url}' has no host"))
+        })?;
+        Ok(match parsed.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        })
+    }
+
     /// Encode audio data for transmission to ElevenLabs.
     ///
     /// ElevenLabs expects base64-encoded audio in JSON messages.
@@ -395,9 +435,10 @@ impl ElevenLabsSTT {
     /// Start the WebSocket connection to ElevenLabs STT API.
     async fn start_connection(&mut self, config: ElevenLabsSTTConfig) -> Result<(), STTError> {
         let ws_url = self.build_websocket_url(&config)?;
+        let host = self.connection_host(&config)?;
 
         // Create channels for communication
-        let (ws_tx, mut ws_rx) = mpsc::channel::<Bytes>(32);
+        let (ws_tx, mut ws_rx) = mpsc::channel::<OutgoingMessage>(32);
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
         // Bounded channels for backpressure - 256 should handle bursts while preventing memory exhaustion
         let (result_tx, mut result_rx) = mpsc::channel::<STTResult>(256);
@@ -412,7 +453,7 @@ impl ElevenLabsSTT {
 
         // Clone necessary data for the connection task
         let api_key = config.base.api_key.clone();
-        let host = Self::get_host_from_region(&config.region);
+        let custom_headers = config.base.custom_headers.clone();
 
         // Start the connection task
         let connection_handle = tokio::spawn(async move {
@@ -420,7 +461,7 @@ impl ElevenLabsSTT {
             let request = match tokio_tungstenite::tungstenite::http::Request::builder()
                 .method("GET")
                 .uri(&ws_url)
-                .header("Host", host)
+                .header("Host", &host)
                 .header("Upgrade", "websocket")
                 .header("Connection", "upgrade")
                 .header("Sec-WebSocket-Key", generate_key())
@@ -428,7 +469,10 @@ impl ElevenLabsSTT {
                 .header("xi-api-key", &api_key)
                 .body(())
             {
-                Ok(request) => request,
+                Ok(mut request) => {
+                    insert_custom_headers(request.headers_mut(), &custom_headers);
+                    request
+                }
                 Err(e) => {
                     let stt_error = STTError::ConnectionFailed(format!(
                         "Failed to create WebSocket request: {e}"
@@ -462,10 +506,17 @@ impl ElevenLabsSTT {
             // Main event loop
             loop {
                 tokio::select! {
-                    // Handle outgoing audio data
-                    Some(audio_data) = ws_rx.recv() => {
-                        let audio_base64 = BASE64_STANDARD.encode(&audio_data);
-                        let input_msg = InputAudioChunk::new(audio_base64);
+                    // Handle outgoing audio data and commits
+                    Some(outgoing) = ws_rx.recv() => {
+                        let (input_msg, audio_len) = match outgoing {
+                            OutgoingMessage::Audio(audio_data) => (
+                                InputAudioChunk::new(BASE64_STANDARD.encode(&audio_data)),
+                                audio_data.len(),
+                            ),
+                            OutgoingMessage::Commit => {
+                                (InputAudioChunk::new(String::new()).with_commit(true), 0)
+                            }
+                        };
 
                         let json_msg = match serde_json::to_string(&input_msg) {
                             Ok(json) => json,
@@ -489,7 +540,11 @@ impl ElevenLabsSTT {
                             break;
                         }
 
-                        debug!("Sent {} bytes of audio to ElevenLabs", audio_data.len());
+                        if input_msg.commit.is_some() {
+                            debug!("Sent commit to ElevenLabs");
+                        } else {
+                            debug!("Sent {} bytes of audio to ElevenLabs", audio_len);
+                        }
                     }
 
                     // Handle incoming messages with idle timeout
@@ -610,6 +665,12 @@ impl Drop for ElevenLabsSTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -657,6 +718,7 @@ impl BaseSTT for ElevenLabsSTT {
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
             session_id: None,
+            base_url: None,
         })
     }
 
@@ -717,7 +779,7 @@ impl BaseSTT for ElevenLabsSTT {
 
             // Zero-copy - Bytes passed directly to WebSocket
             ws_sender
-                .send(audio_data)
+                .send(OutgoingMessage::Audio(audio_data))
                 .await
                 .map_err(|e| STTError::NetworkError(format!("Failed to send audio data: {e}")))?;
 
@@ -793,6 +855,45 @@ impl BaseSTT for ElevenLabsSTT {
     fn get_provider_info(&self) -> &'static str {
         "ElevenLabs STT Real-Time WebSocket"
     }
+
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        let manual = self
+            .config
+            .as_ref()
+            .is_some_and(|c| c.commit_strategy == CommitStrategy::Manual);
+        if !manual {
+            return Ok(false);
+        }
+
+        let ws_sender = self
+            .ws_sender
+            .as_ref()
+            .filter(|_| self.is_ready())
+            .ok_or_else(|| {
+                STTError::ConnectionFailed("Not connected to ElevenLabs STT".to_string())
+            })?;
+
+        // ElevenLabs transcribes the audio received so far and replies with
+        // a committed transcript
+        ws_sender
+            .send(OutgoingMessage::Commit)
+            .await
+            .map_err(|e| STTError::NetworkError(format!("Failed to send commit: {e}")))?;
+
+        debug!("Queued commit for ElevenLabs");
+        Ok(true)
+    }
+
+    async fn set_manual_commit(&mut self, enabled: bool) -> Result<bool, STTError> {
+        if let Some(config) = &mut self.config {
+            config.commit_strategy = if enabled {
+                CommitStrategy::Manual
+            } else {
+                CommitStrategy::Vad
+            };
+        }
+        Ok(true)
+    }
 }
 
 // =============================================================================
@@ -807,6 +908,15 @@ impl ElevenLabsSTT {
         self.session_id.as_deref()
     }
 
+    /// Connect to `base_url` instead of the regional endpoint.
+    ///
+    /// For proxies and mock servers, e.g. `ws://127.0.0.1:9000`. The
+    /// `/v1/speech-to-text/realtime` path and query are appended as usual.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
     /// Update ElevenLabs-specific settings.
     ///
     /// This allows updating ElevenLabs-specific parameters without
@@ -12,6 +12,9 @@ use super::super::base::STTConfig;
 // Audio Format
 // =============================================================================
 
+/// Sample rates ElevenLabs accepts for PCM input (in Hz).
+pub const SUPPORTED_SAMPLE_RATES: &[u32] = &[8000, 16000, 22050, 24000, 44100, 48000];
+
 /// Supported audio formats for ElevenLabs STT Real-Time API.
 ///
 /// All PCM formats are 16-bit signed little-endian.
@@ -64,7 +67,8 @@ impl ElevenLabsAudioFormat {
 
     /// Create from sample rate (defaults to PCM encoding).
     ///
-    /// Unknown sample rates default to 16kHz PCM.
+    /// Unknown sample rates default to 16kHz PCM; configs are checked
+    /// against [`SUPPORTED_SAMPLE_RATES`] before a provider is created.
     #[inline]
     pub fn from_sample_rate(sample_rate: u32) -> Self {
         match sample_rate {
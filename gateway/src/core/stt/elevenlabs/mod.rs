@@ -59,7 +59,10 @@ mod tests;
 
 // Re-export public types
 pub use client::ElevenLabsSTT;
-pub use config::{CommitStrategy, ElevenLabsAudioFormat, ElevenLabsRegion, ElevenLabsSTTConfig};
+pub use config::{
+    CommitStrategy, ElevenLabsAudioFormat, ElevenLabsRegion, ElevenLabsSTTConfig,
+    SUPPORTED_SAMPLE_RATES,
+};
 pub use messages::{
     CommittedTranscript, CommittedTranscriptWithTimestamps, ElevenLabsMessage, ElevenLabsSTTError,
     EndOfStream, InputAudioChunk, PartialTranscript, SessionStarted, WordTiming,
@@ -653,6 +653,19 @@ mod client_tests {
         assert!(url.starts_with("wss://api.eu.residency.elevenlabs.io/"));
     }
 
+    #[test]
+    fn test_url_building_base_url_override() {
+        let stt = ElevenLabsSTT::default().with_base_url("ws://127.0.0.1:9000/");
+        let config = ElevenLabsSTTConfig {
+            region: ElevenLabsRegion::Us,
+            ..Default::default()
+        };
+
+        let url = stt.build_websocket_url(&config).unwrap();
+
+        assert!(url.starts_with("ws://127.0.0.1:9000/v1/speech-to-text/realtime?"));
+    }
+
     #[test]
     fn test_get_host_from_region() {
         assert_eq!(
@@ -985,6 +998,8 @@ mod base_stt_tests {
             encoding: "linear16".to_string(),
             model: "".to_string(),
             provider: "elevenlabs".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <ElevenLabsSTT as BaseSTT>::new(config);
@@ -998,6 +1013,39 @@ mod base_stt_tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_manual_commit() {
+        let config = STTConfig {
+            api_key: "test_key".to_string(),
+            ..Default::default()
+        };
+        let mut stt = <ElevenLabsSTT as BaseSTT>::new(config).unwrap();
+
+        // VAD commit strategy: ElevenLabs ends utterances itself
+        assert!(!stt.finalize().await.unwrap());
+
+        assert!(stt.set_manual_commit(true).await.unwrap());
+        let elevenlabs_config = stt.config.clone().unwrap();
+        assert_eq!(elevenlabs_config.commit_strategy, CommitStrategy::Manual);
+        assert!(
+            stt.build_websocket_url(&elevenlabs_config)
+                .unwrap()
+                .contains("commit_strategy=manual")
+        );
+
+        // A commit needs a connection
+        assert!(matches!(
+            stt.finalize().await,
+            Err(STTError::ConnectionFailed(_))
+        ));
+
+        assert!(stt.set_manual_commit(false).await.unwrap());
+        assert_eq!(
+            stt.config.as_ref().unwrap().commit_strategy,
+            CommitStrategy::Vad
+        );
+    }
+
     #[tokio::test]
     async fn test_new_empty_api_key() {
         let config = STTConfig {
@@ -1192,6 +1240,8 @@ mod base_stt_tests {
             encoding: "opus".to_string(),
             model: "custom".to_string(),
             provider: "elevenlabs".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <ElevenLabsSTT as BaseSTT>::new(config).unwrap();
@@ -0,0 +1,1196 @@
+//! STT failover to a secondary provider
+//!
+//! [`FailoverSTT`] wraps a primary and a secondary provider and switches to
+//! the secondary when the primary fails to connect, errors mid-stream or
+//! rejects audio:
+//!
+//! - By default the secondary connects on failover. Audio sent while it
+//!   connects is transcribed by neither provider.
+//! - With `warm_standby` the secondary connects alongside the primary and
+//!   receives the same audio, with its results held back. On failover it is
+//!   promoted immediately and its held-back transcript is stitched to what
+//!   the primary delivered: the two are aligned on the last words both
+//!   finalized, and only what follows is emitted, so no words around the
+//!   switchover are repeated or lost.
+//!
+//! A warm standby transcribes all audio twice and both providers bill for
+//! it. [`STTFailoverUsage`] counts the audio each provider received, which
+//! usage records report separately.
+
+use bytes::Bytes;
+use parking_lot::{Mutex, RwLock};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::base::{
+    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback, STTVadCallback,
+    STTVadEvent,
+};
+use super::clock::SessionAudioClock;
+use super::create_stt_provider;
+
+/// Default time the secondary may take to connect on failover
+pub const DEFAULT_FAILOVER_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Words of the primary's final results kept for aligning the standby transcript
+const MAX_ALIGN_WORDS: usize = 64;
+
+/// Final results of the warm standby held back while the primary is active
+const MAX_HELD_FINALS: usize = 32;
+
+/// Consecutive words both transcripts must share to be aligned
+const MIN_ALIGN_WORDS: usize = 3;
+
+/// Secondary STT provider to fail over to
+#[derive(Debug, Clone)]
+pub struct STTFailoverConfig {
+    /// Configuration of the secondary provider
+    pub secondary: STTConfig,
+    /// Keep the secondary connected and transcribing from the start, so it
+    /// takes over without a gap
+    pub warm_standby: bool,
+}
+
+/// Audio each provider of a [`FailoverSTT`] received
+#[derive(Debug, Default)]
+pub struct STTFailoverUsage {
+    primary_bytes: AtomicU64,
+    secondary_bytes: AtomicU64,
+    failed_over: AtomicBool,
+}
+
+impl STTFailoverUsage {
+    /// Bytes of audio sent to the primary provider
+    pub fn primary_bytes(&self) -> u64 {
+        self.primary_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Bytes of audio sent to the secondary provider, as standby or after failover
+    pub fn secondary_bytes(&self) -> u64 {
+        self.secondary_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Whether the secondary provider has taken over
+    pub fn failed_over(&self) -> bool {
+        self.failed_over.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_primary(&self, bytes: usize) {
+        self.primary_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_secondary(&self, bytes: usize) {
+        self.secondary_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn mark_failed_over(&self) {
+        self.failed_over.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A result of the warm standby held back while the primary is active
+struct HeldResult {
+    result: STTResult,
+    /// Final results the primary had delivered when this one arrived
+    after_final: u64,
+}
+
+/// Stitches the warm standby's transcript to the primary's on failover
+#[derive(Default)]
+struct TranscriptStitcher {
+    /// Normalized words of the primary's latest final results
+    delivered_words: VecDeque<String>,
+    /// Final results the primary delivered
+    delivered_finals: u64,
+    held_finals: VecDeque<HeldResult>,
+    held_interim: Option<HeldResult>,
+    /// Leading words the primary already delivered, stripped from the
+    /// secondary's results until its next final result
+    skip_words: usize,
+}
+
+impl TranscriptStitcher {
+    /// Record a result delivered from the primary
+    fn on_primary_result(&mut self, result: &STTResult) {
+        if !result.is_final {
+            return;
+        }
+        self.delivered_finals += 1;
+        self.delivered_words
+            .extend(words(&result.transcript).map(|(_, word)| word));
+        while self.delivered_words.len() > MAX_ALIGN_WORDS {
+            self.delivered_words.pop_front();
+        }
+    }
+
+    /// Hold back a result of the warm standby
+    fn hold(&mut self, result: STTResult) {
+        let held = HeldResult {
+            after_final: self.delivered_finals,
+            result,
+        };
+        if held.result.is_final {
+            self.held_interim = None;
+            self.held_finals.push_back(held);
+            if self.held_finals.len() > MAX_HELD_FINALS {
+                self.held_finals.pop_front();
+            }
+        } else {
+            self.held_interim = Some(held);
+        }
+    }
+
+    /// Forget the held-back results of a standby that failed
+    fn clear_held(&mut self) {
+        self.held_finals.clear();
+        self.held_interim = None;
+    }
+
+    /// The held-back results the primary did not deliver, in order
+    ///
+    /// The held transcript is aligned on the longest run of words that ends
+    /// the primary's delivered transcript, and only what follows is kept.
+    /// Without such a run, the results that arrived after the primary's last
+    /// final result are kept.
+    fn promote(&mut self) -> Vec<STTResult> {
+        let held: Vec<HeldResult> = self
+            .held_finals
+            .drain(..)
+            .chain(self.held_interim.take())
+            .collect();
+        let held_words: Vec<(usize, usize, String)> = held
+            .iter()
+            .enumerate()
+            .flat_map(|(index, held)| {
+                words(&held.result.transcript).map(move |(token, word)| (index, token, word))
+            })
+            .collect();
+
+        let Some(end) = align(&held_words, &self.delivered_words) else {
+            let delivered_finals = self.delivered_finals;
+            return held
+                .into_iter()
+                .filter(|held| held.after_final == delivered_finals)
+                .map(|held| held.result)
+                .collect();
+        };
+
+        let (aligned_index, aligned_token, _) = held_words[end];
+        let mut results = Vec::new();
+        for (index, held) in held.into_iter().enumerate().skip(aligned_index) {
+            if index > aligned_index {
+                results.push(held.result);
+                continue;
+            }
+            // The secondary's later results for this utterance repeat these words
+            if !held.result.is_final {
+                self.skip_words = aligned_token + 1;
+            }
+            results.extend(keep(trim_words(held.result, aligned_token + 1)));
+        }
+        results
+    }
+
+    /// A result of the promoted secondary, without words the primary delivered
+    fn on_promoted_result(&mut self, result: STTResult) -> Option<STTResult> {
+        if self.skip_words == 0 {
+            return Some(result);
+        }
+        let skip_words = self.skip_words;
+        if result.is_final {
+            self.skip_words = 0;
+        }
+        keep(trim_words(result, skip_words))
+    }
+}
+
+/// Lowercased alphanumeric words of a transcript with their whitespace token
+/// index, skipping tokens without letters or digits
+fn words(text: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+    text.split_whitespace()
+        .enumerate()
+        .filter_map(|(token, raw)| {
+            let word: String = raw
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect();
+            (!word.is_empty()).then_some((token, word))
+        })
+}
+
+/// Index of the held word the delivered transcript ends at, if they overlap
+fn align(held: &[(usize, usize, String)], delivered: &VecDeque<String>) -> Option<usize> {
+    let required = MIN_ALIGN_WORDS.min(delivered.len());
+    if required == 0 {
+        return None;
+    }
+
+    // Longest match wins; among equally long matches, the latest
+    let mut best: Option<(usize, usize)> = None;
+    for end in 0..held.len() {
+        let matched = held[..=end]
+            .iter()
+            .rev()
+            .zip(delivered.iter().rev())
+            .take_while(|((_, _, held), delivered)| held == *delivered)
+            .count();
+        if matched >= required && best.is_none_or(|(longest, _)| matched >= longest) {
+            best = Some((matched, end));
+        }
+    }
+    best.map(|(_, end)| end)
+}
+
+/// Drop the first `count` whitespace tokens of a result's transcript
+fn trim_words(mut result: STTResult, count: usize) -> STTResult {
+    let transcript = &result.transcript;
+    let cut = transcript
+        .char_indices()
+        .filter(|&(index, c)| {
+            !c.is_whitespace()
+                && transcript[..index]
+                    .chars()
+                    .next_back()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map(|(index, _)| index)
+        .nth(count)
+        .unwrap_or(transcript.len());
+
+    result.transcript = result.transcript[cut..].to_string();
+    result.redactions.retain(|span| span.start >= cut);
+    for span in &mut result.redactions {
+        span.start -= cut;
+        span.end -= cut;
+    }
+    if result.words.len() > count {
+        result.words.drain(..count);
+    } else {
+        result.words.clear();
+    }
+    if let Some(first) = result.words.first()
+        && result.start.is_some()
+    {
+        result.start = Some(first.start);
+    }
+    result
+}
+
+/// Drop results trimmed to nothing, unless they end the user's speech
+fn keep(result: STTResult) -> Option<STTResult> {
+    (!result.transcript.trim().is_empty() || result.is_speech_final).then_some(result)
+}
+
+/// Shift a result's timings by `offset` seconds
+pub(super) fn shift_timings(result: &mut STTResult, offset: f64) {
+    if offset == 0.0 {
+        return;
+    }
+    result.start = result.start.map(|start| start + offset);
+    result.end = result.end.map(|end| end + offset);
+    for word in &mut result.words {
+        word.start += offset;
+        word.end += offset;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Primary,
+    Secondary,
+}
+
+struct FailoverState {
+    active: Role,
+    /// The primary failed and the secondary connects on the next audio
+    primary_failed: bool,
+    /// The secondary is connected as a warm standby
+    standby_live: bool,
+    /// Both providers failed
+    exhausted: bool,
+    stitcher: TranscriptStitcher,
+    /// Seconds of audio the primary received before a cold failover, added
+    /// to the secondary's timings so they stay on the primary's timeline
+    time_offset: f64,
+}
+
+/// Callbacks registered with the failover provider
+#[derive(Clone, Default)]
+struct Callbacks {
+    result: Option<STTResultCallback>,
+    error: Option<STTErrorCallback>,
+    vad: Option<STTVadCallback>,
+}
+
+/// State shared with the callbacks registered on both providers
+struct Shared {
+    state: Mutex<FailoverState>,
+    callbacks: RwLock<Callbacks>,
+    /// Keeps stitched results in order with the secondary's next results
+    delivery: tokio::sync::Mutex<()>,
+    usage: Arc<STTFailoverUsage>,
+}
+
+impl Shared {
+    fn active(&self) -> Role {
+        self.state.lock().active
+    }
+
+    fn standby_live(&self) -> bool {
+        self.state.lock().standby_live
+    }
+
+    async fn deliver(&self, results: Vec<STTResult>) {
+        let callback = self.callbacks.read().result.clone();
+        let Some(callback) = callback else {
+            return;
+        };
+        for result in results {
+            callback(result).await;
+        }
+    }
+
+    async fn report_error(&self, error: STTError) {
+        let callback = self.callbacks.read().error.clone();
+        if let Some(callback) = callback {
+            callback(error).await;
+        }
+    }
+
+    async fn on_result(&self, role: Role, mut result: STTResult) {
+        let _delivery = self.delivery.lock().await;
+        let results = {
+            let mut state = self.state.lock();
+            match (role, state.active) {
+                (Role::Primary, Role::Primary) => {
+                    state.stitcher.on_primary_result(&result);
+                    vec![result]
+                }
+                (Role::Secondary, Role::Primary) => {
+                    if state.standby_live {
+                        state.stitcher.hold(result);
+                    }
+                    return;
+                }
+                (Role::Secondary, Role::Secondary) => {
+                    shift_timings(&mut result, state.time_offset);
+                    state
+                        .stitcher
+                        .on_promoted_result(result)
+                        .into_iter()
+                        .collect()
+                }
+                // The primary was replaced
+                (Role::Primary, Role::Secondary) => return,
+            }
+        };
+        self.deliver(results).await;
+    }
+
+    async fn on_error(&self, role: Role, error: STTError) {
+        let _delivery = self.delivery.lock().await;
+        if role == Role::Secondary && self.active() == Role::Secondary {
+            self.report_error(error).await;
+            return;
+        }
+        let promoted = {
+            let mut state = self.state.lock();
+            match (role, state.active) {
+                (Role::Primary, Role::Primary) => {
+                    if state.primary_failed || state.exhausted {
+                        return;
+                    }
+                    warn!(error = %error, "Primary STT provider failed, failing over");
+                    if !state.standby_live {
+                        // Connected by the next `send_audio`
+                        state.primary_failed = true;
+                        return;
+                    }
+                    state.active = Role::Secondary;
+                    state.standby_live = false;
+                    self.usage.mark_failed_over();
+                    state.stitcher.promote()
+                }
+                (Role::Secondary, _) => {
+                    if state.standby_live {
+                        warn!(error = %error, "Warm standby STT provider failed");
+                        state.standby_live = false;
+                        state.stitcher.clear_held();
+                    }
+                    return;
+                }
+                (Role::Primary, Role::Secondary) => {
+                    debug!(error = %error, "Ignoring error of the replaced STT provider");
+                    return;
+                }
+            }
+        };
+        self.deliver(promoted).await;
+    }
+
+    async fn on_vad(&self, role: Role, event: STTVadEvent) {
+        if self.active() != role {
+            return;
+        }
+        let callback = self.callbacks.read().vad.clone();
+        if let Some(callback) = callback {
+            callback(event).await;
+        }
+    }
+
+    fn result_callback(self: &Arc<Self>, role: Role) -> STTResultCallback {
+        let shared = self.clone();
+        Arc::new(move |result| {
+            let shared = shared.clone();
+            Box::pin(async move { shared.on_result(role, result).await })
+        })
+    }
+
+    fn error_callback(self: &Arc<Self>, role: Role) -> STTErrorCallback {
+        let shared = self.clone();
+        Arc::new(move |error| {
+            let shared = shared.clone();
+            Box::pin(async move { shared.on_error(role, error).await })
+        })
+    }
+
+    fn vad_callback(self: &Arc<Self>, role: Role) -> STTVadCallback {
+        let shared = self.clone();
+        Arc::new(move |event| {
+            let shared = shared.clone();
+            Box::pin(async move { shared.on_vad(role, event).await })
+        })
+    }
+}
+
+/// STT provider that fails over from a primary to a secondary provider
+///
+/// Results, errors and VAD events of the provider in use are forwarded to
+/// the registered callbacks; the other provider's are handled internally.
+/// Errors of the primary are only reported if the secondary cannot take
+/// over.
+pub struct FailoverSTT {
+    primary: Box<dyn BaseSTT>,
+    secondary: Box<dyn BaseSTT>,
+    warm_standby: bool,
+    connect_timeout: Duration,
+    /// Audio sent to the primary, for the timings of a cold secondary
+    primary_clock: SessionAudioClock,
+    shared: Arc<Shared>,
+}
+
+impl FailoverSTT {
+    /// Create both providers from their configurations
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the primary provider
+    /// * `failover` - Secondary provider and failover mode
+    ///
+    /// # Returns
+    /// * `Result<Self, STTError>` - The failover provider, or the first creation error
+    pub fn create(config: STTConfig, failover: STTFailoverConfig) -> Result<Self, STTError> {
+        let primary = create_stt_provider(&config.provider, config)?;
+        let secondary = create_stt_provider(&failover.secondary.provider, failover.secondary)?;
+        Ok(Self::with_providers(
+            primary,
+            secondary,
+            failover.warm_standby,
+        ))
+    }
+
+    /// Fail over between already created providers
+    pub fn with_providers(
+        primary: Box<dyn BaseSTT>,
+        secondary: Box<dyn BaseSTT>,
+        warm_standby: bool,
+    ) -> Self {
+        let config = primary.get_config().cloned().unwrap_or_default();
+        let primary_clock =
+            SessionAudioClock::new(config.sample_rate, config.channels, &config.encoding);
+        Self {
+            primary,
+            secondary,
+            warm_standby,
+            connect_timeout: DEFAULT_FAILOVER_CONNECT_TIMEOUT,
+            primary_clock,
+            shared: Arc::new(Shared {
+                state: Mutex::new(FailoverState {
+                    active: Role::Primary,
+                    primary_failed: false,
+                    standby_live: false,
+                    exhausted: false,
+                    stitcher: TranscriptStitcher::default(),
+                    time_offset: 0.0,
+                }),
+                callbacks: RwLock::new(Callbacks::default()),
+                delivery: tokio::sync::Mutex::new(()),
+                usage: Arc::new(STTFailoverUsage::default()),
+            }),
+        }
+    }
+
+    /// Set how long the secondary may take to connect on failover
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Audio counters of both providers
+    pub fn usage(&self) -> Arc<STTFailoverUsage> {
+        self.shared.usage.clone()
+    }
+
+    /// Whether the secondary has taken over
+    pub fn failed_over(&self) -> bool {
+        self.shared.active() == Role::Secondary
+    }
+
+    fn active(&self) -> &dyn BaseSTT {
+        match self.shared.active() {
+            Role::Primary => self.primary.as_ref(),
+            Role::Secondary => self.secondary.as_ref(),
+        }
+    }
+
+    fn active_mut(&mut self) -> &mut dyn BaseSTT {
+        match self.shared.active() {
+            Role::Primary => self.primary.as_mut(),
+            Role::Secondary => self.secondary.as_mut(),
+        }
+    }
+
+    /// Whether callbacks are registered on the secondary
+    fn secondary_listening(&self) -> bool {
+        self.warm_standby || self.failed_over()
+    }
+
+    /// Whether the secondary receives audio, as warm standby or after failover
+    fn secondary_streaming(&self) -> bool {
+        let state = self.shared.state.lock();
+        state.standby_live || state.active == Role::Secondary
+    }
+
+    /// Register the stored callbacks on the secondary
+    async fn register_secondary_callbacks(&mut self) -> Result<(), STTError> {
+        let callbacks = self.shared.callbacks.read().clone();
+        if callbacks.result.is_some() {
+            self.secondary
+                .on_result(self.shared.result_callback(Role::Secondary))
+                .await?;
+        }
+        if callbacks.error.is_some() {
+            self.secondary
+                .on_error(self.shared.error_callback(Role::Secondary))
+                .await?;
+        }
+        if callbacks.vad.is_some() {
+            self.secondary
+                .on_vad_event(self.shared.vad_callback(Role::Secondary))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Connect the secondary and switch to it after the primary failed
+    async fn fail_over_cold(&mut self) -> Result<(), STTError> {
+        let connected = match self
+            .secondary
+            .connect_with_timeout(self.connect_timeout)
+            .await
+        {
+            Ok(()) => self.register_secondary_callbacks().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = connected {
+            {
+                let mut state = self.shared.state.lock();
+                state.primary_failed = false;
+                state.exhausted = true;
+            }
+            self.shared.report_error(e.clone()).await;
+            return Err(e);
+        }
+
+        {
+            let mut state = self.shared.state.lock();
+            state.active = Role::Secondary;
+            state.primary_failed = false;
+            state.time_offset = self.primary_clock.elapsed();
+        }
+        self.shared.usage.mark_failed_over();
+        if let Err(e) = self.primary.disconnect().await {
+            debug!("Failed to disconnect the failed STT provider: {e}");
+        }
+        Ok(())
+    }
+
+    async fn send_to_secondary(&mut self, audio: Bytes) -> Result<(), STTError> {
+        let len = audio.len();
+        self.secondary.send_audio(audio).await?;
+        self.shared.usage.add_secondary(len);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseSTT for FailoverSTT {
+    /// Not supported: a failover provider needs a secondary provider, see
+    /// [`FailoverSTT::create`]
+    fn new(_config: STTConfig) -> Result<Self, STTError> {
+        Err(STTError::ConfigurationError(
+            "FailoverSTT needs a secondary provider; use FailoverSTT::create".to_string(),
+        ))
+    }
+
+    async fn connect(&mut self) -> Result<(), STTError> {
+        let primary = if self.warm_standby {
+            let (primary, secondary) =
+                tokio::join!(self.primary.connect(), self.secondary.connect());
+            match secondary {
+                Ok(()) => self.shared.state.lock().standby_live = true,
+                Err(e) => warn!(error = %e, "Warm standby STT provider failed to connect"),
+            }
+            primary
+        } else {
+            self.primary.connect().await
+        };
+
+        if let Err(e) = primary {
+            warn!(error = %e, "Primary STT provider failed to connect, failing over");
+            if !self.shared.standby_live() {
+                self.secondary.connect().await?;
+                self.register_secondary_callbacks().await?;
+            }
+            let mut state = self.shared.state.lock();
+            state.active = Role::Secondary;
+            state.standby_live = false;
+            self.shared.usage.mark_failed_over();
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), STTError> {
+        let (primary, secondary) =
+            tokio::join!(self.primary.disconnect(), self.secondary.disconnect());
+        // Only the provider in use must disconnect cleanly
+        match self.shared.active() {
+            Role::Primary => {
+                if let Err(e) = secondary {
+                    debug!("Failed to disconnect the secondary STT provider: {e}");
+                }
+                primary
+            }
+            Role::Secondary => {
+                if let Err(e) = primary {
+                    debug!("Failed to disconnect the primary STT provider: {e}");
+                }
+                secondary
+            }
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.active().is_ready()
+    }
+
+    async fn send_audio(&mut self, audio_data: Bytes) -> Result<(), STTError> {
+        let (primary_failed, exhausted) = {
+            let state = self.shared.state.lock();
+            (state.primary_failed, state.exhausted)
+        };
+        if exhausted {
+            return Err(STTError::ConnectionFailed(
+                "primary and secondary STT providers failed".to_string(),
+            ));
+        }
+        if primary_failed {
+            self.fail_over_cold().await?;
+        }
+        if self.shared.active() == Role::Secondary {
+            return self.send_to_secondary(audio_data).await;
+        }
+
+        let len = audio_data.len();
+        // The primary may fail while sending, promoting the standby, which
+        // then still needs this chunk
+        let standby = self.shared.standby_live();
+        let primary = self.primary.send_audio(audio_data.clone()).await;
+        if standby
+            && self.secondary_streaming()
+            && let Err(e) = self.send_to_secondary(audio_data.clone()).await
+        {
+            self.shared.on_error(Role::Secondary, e).await;
+        }
+
+        match primary {
+            Ok(()) => {
+                self.primary_clock.record_audio(len);
+                self.shared.usage.add_primary(len);
+                Ok(())
+            }
+            Err(e) => {
+                self.shared.on_error(Role::Primary, e).await;
+                if self.shared.active() == Role::Secondary {
+                    // The warm standby already has this chunk
+                    return Ok(());
+                }
+                self.fail_over_cold().await?;
+                self.send_to_secondary(audio_data).await
+            }
+        }
+    }
+
+    async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+        self.shared.callbacks.write().result = Some(callback);
+        self.primary
+            .on_result(self.shared.result_callback(Role::Primary))
+            .await?;
+        if self.secondary_listening() {
+            self.secondary
+                .on_result(self.shared.result_callback(Role::Secondary))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn on_error(&mut self, callback: STTErrorCallback) -> Result<(), STTError> {
+        self.shared.callbacks.write().error = Some(callback);
+        self.primary
+            .on_error(self.shared.error_callback(Role::Primary))
+            .await?;
+        if self.secondary_listening() {
+            self.secondary
+                .on_error(self.shared.error_callback(Role::Secondary))
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn get_config(&self) -> Option<&STTConfig> {
+        self.active().get_config()
+    }
+
+    async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+        self.active_mut().update_config(config).await
+    }
+
+    fn get_provider_info(&self) -> &'static str {
+        self.active().get_provider_info()
+    }
+
+    async fn set_end_of_turn_silence(&mut self, silence_ms: u32) -> Result<bool, STTError> {
+        if self.shared.standby_live() {
+            let _ = self.secondary.set_end_of_turn_silence(silence_ms).await;
+        }
+        self.active_mut().set_end_of_turn_silence(silence_ms).await
+    }
+
+    async fn on_vad_event(&mut self, callback: STTVadCallback) -> Result<bool, STTError> {
+        self.shared.callbacks.write().vad = Some(callback);
+        let mut supported = self
+            .primary
+            .on_vad_event(self.shared.vad_callback(Role::Primary))
+            .await?;
+        if self.secondary_listening() {
+            let secondary = self
+                .secondary
+                .on_vad_event(self.shared.vad_callback(Role::Secondary))
+                .await?;
+            if self.shared.active() == Role::Secondary {
+                supported = secondary;
+            }
+        }
+        Ok(supported)
+    }
+
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        self.active_mut().finalize().await
+    }
+
+    async fn set_manual_commit(&mut self, enabled: bool) -> Result<bool, STTError> {
+        let primary = self.primary.set_manual_commit(enabled).await?;
+        let secondary = self.secondary.set_manual_commit(enabled).await?;
+        Ok(match self.shared.active() {
+            Role::Primary => primary,
+            Role::Secondary => secondary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stt::RedactedSpan;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    fn final_result(text: &str) -> STTResult {
+        STTResult::new(text.to_string(), true, false, 0.9)
+    }
+
+    fn interim(text: &str) -> STTResult {
+        STTResult::new(text.to_string(), false, false, 0.9)
+    }
+
+    fn transcripts(results: &[STTResult]) -> Vec<(&str, bool)> {
+        results
+            .iter()
+            .map(|result| (result.transcript.as_str(), result.is_final))
+            .collect()
+    }
+
+    #[test]
+    fn test_stitch_aligns_on_last_common_final() {
+        let mut stitcher = TranscriptStitcher::default();
+        stitcher.hold(final_result("Hello there."));
+        stitcher.on_primary_result(&final_result("hello there"));
+        // The providers split the next utterance differently
+        stitcher.hold(interim("how are"));
+        stitcher.hold(final_result("How are you? I want"));
+        stitcher.on_primary_result(&final_result("how are you"));
+        stitcher.on_primary_result(&interim("I want to"));
+        stitcher.hold(interim("to book a"));
+
+        let results = stitcher.promote();
+        assert_eq!(
+            transcripts(&results),
+            vec![("I want", true), ("to book a", false)]
+        );
+        assert_eq!(
+            stitcher.on_promoted_result(final_result("to book a flight")),
+            Some(final_result("to book a flight"))
+        );
+    }
+
+    #[test]
+    fn test_stitch_strips_delivered_words_until_next_final() {
+        let mut stitcher = TranscriptStitcher::default();
+        stitcher.on_primary_result(&final_result("I'd like to book a table."));
+        stitcher.hold(interim("I'd like to book a table for two"));
+
+        let results = stitcher.promote();
+        assert_eq!(transcripts(&results), vec![("for two", false)]);
+
+        let result = stitcher
+            .on_promoted_result(final_result("I'd like to book a table for two people."))
+            .unwrap();
+        assert_eq!(result.transcript, "for two people.");
+        // Later utterances are passed through
+        let result = stitcher.on_promoted_result(interim("at seven")).unwrap();
+        assert_eq!(result.transcript, "at seven");
+    }
+
+    #[test]
+    fn test_stitch_drops_fully_delivered_results() {
+        let mut stitcher = TranscriptStitcher::default();
+        stitcher.hold(final_result("Good morning."));
+        stitcher.on_primary_result(&final_result("good morning"));
+
+        assert!(stitcher.promote().is_empty());
+    }
+
+    #[test]
+    fn test_stitch_without_overlap_keeps_results_after_last_final() {
+        let mut stitcher = TranscriptStitcher::default();
+        stitcher.hold(final_result("completely different"));
+        stitcher.on_primary_result(&final_result("yes"));
+        stitcher.hold(final_result("next one"));
+
+        let results = stitcher.promote();
+        assert_eq!(transcripts(&results), vec![("next one", true)]);
+    }
+
+    #[test]
+    fn test_stitch_before_any_primary_final_keeps_everything() {
+        let mut stitcher = TranscriptStitcher::default();
+        stitcher.hold(final_result("first"));
+        stitcher.hold(interim("second"));
+
+        let results = stitcher.promote();
+        assert_eq!(
+            transcripts(&results),
+            vec![("first", true), ("second", false)]
+        );
+    }
+
+    #[test]
+    fn test_trim_words_shifts_redactions_and_timings() {
+        let result = STTResult::new("call me at [PII] please".to_string(), true, true, 0.9)
+            .with_redactions(vec![RedactedSpan {
+                start: 11,
+                end: 16,
+                entity_type: "PHONE".to_string(),
+            }])
+            .with_span(1.0, 3.0);
+
+        let trimmed = trim_words(result, 2);
+        assert_eq!(trimmed.transcript, "at [PII] please");
+        assert_eq!(
+            trimmed.redactions,
+            vec![RedactedSpan {
+                start: 3,
+                end: 8,
+                entity_type: "PHONE".to_string(),
+            }]
+        );
+
+        let speech_final = STTResult::new("done".to_string(), true, true, 0.9);
+        assert_eq!(keep(trim_words(speech_final, 1)).unwrap().transcript, "");
+        assert!(keep(trim_words(final_result("done"), 1)).is_none());
+    }
+
+    /// A step of a scripted provider, taken on each audio chunk
+    #[derive(Clone)]
+    enum Step {
+        Result(STTResult),
+        Error,
+        Nothing,
+    }
+
+    struct ScriptedSTT {
+        config: STTConfig,
+        steps: VecDeque<Step>,
+        connected: bool,
+        fail_send: bool,
+        result_callback: Option<STTResultCallback>,
+        error_callback: Option<STTErrorCallback>,
+    }
+
+    impl ScriptedSTT {
+        fn boxed(provider: &str, steps: Vec<Step>) -> Box<dyn BaseSTT> {
+            Box::new(Self {
+                config: STTConfig {
+                    provider: provider.to_string(),
+                    ..Default::default()
+                },
+                steps: steps.into(),
+                connected: false,
+                fail_send: false,
+                result_callback: None,
+                error_callback: None,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BaseSTT for ScriptedSTT {
+        fn new(config: STTConfig) -> Result<Self, STTError> {
+            Ok(Self {
+                config,
+                steps: VecDeque::new(),
+                connected: false,
+                fail_send: false,
+                result_callback: None,
+                error_callback: None,
+            })
+        }
+
+        async fn connect(&mut self) -> Result<(), STTError> {
+            self.connected = true;
+            self.fail_send = false;
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<(), STTError> {
+            self.connected = false;
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            self.connected
+        }
+
+        async fn send_audio(&mut self, _audio_data: Bytes) -> Result<(), STTError> {
+            if self.fail_send {
+                return Err(STTError::NetworkError("stream closed".to_string()));
+            }
+            match self.steps.pop_front().unwrap_or(Step::Nothing) {
+                Step::Result(result) => {
+                    if let Some(callback) = &self.result_callback {
+                        callback(result).await;
+                    }
+                }
+                Step::Error => {
+                    self.fail_send = true;
+                    if let Some(callback) = &self.error_callback {
+                        callback(STTError::ProviderError("stream lost".to_string())).await;
+                    }
+                }
+                Step::Nothing => {}
+            }
+            Ok(())
+        }
+
+        async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+            self.result_callback = Some(callback);
+            Ok(())
+        }
+
+        async fn on_error(&mut self, callback: STTErrorCallback) -> Result<(), STTError> {
+            self.error_callback = Some(callback);
+            Ok(())
+        }
+
+        fn get_config(&self) -> Option<&STTConfig> {
+            Some(&self.config)
+        }
+
+        async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+            self.config = config;
+            Ok(())
+        }
+
+        fn get_provider_info(&self) -> &'static str {
+            "scripted"
+        }
+    }
+
+    async fn run(
+        mut stt: FailoverSTT,
+        chunks: usize,
+    ) -> (Vec<STTResult>, Vec<String>, FailoverSTT) {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        stt.connect().await.unwrap();
+        let sink = results.clone();
+        stt.on_result(Arc::new(move |result| {
+            sink.lock().push(result);
+            Box::pin(async {}) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }))
+        .await
+        .unwrap();
+        let sink = errors.clone();
+        stt.on_error(Arc::new(move |error: STTError| {
+            sink.lock().push(error.to_string());
+            Box::pin(async {}) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }))
+        .await
+        .unwrap();
+
+        // 100ms of 16 kHz linear16 audio per chunk
+        for _ in 0..chunks {
+            stt.send_audio(Bytes::from(vec![0u8; 3200])).await.unwrap();
+        }
+        let results = results.lock().clone();
+        let errors = errors.lock().clone();
+        (results, errors, stt)
+    }
+
+    #[tokio::test]
+    async fn test_warm_standby_promotes_and_stitches() {
+        let primary = ScriptedSTT::boxed(
+            "deepgram",
+            vec![
+                Step::Result(final_result("hello there")),
+                Step::Result(final_result("how are you")),
+                Step::Result(interim("I want to")),
+                Step::Error,
+            ],
+        );
+        let secondary = ScriptedSTT::boxed(
+            "assemblyai",
+            vec![
+                Step::Result(final_result("Hello there.")),
+                Step::Result(interim("how are")),
+                Step::Result(final_result("How are you? I want")),
+                Step::Result(interim("to book a")),
+                Step::Result(final_result("to book a flight")),
+            ],
+        );
+
+        let stt = FailoverSTT::with_providers(primary, secondary, true);
+        let usage = stt.usage();
+        let (results, errors, stt) = run(stt, 5).await;
+
+        assert_eq!(
+            transcripts(&results),
+            vec![
+                ("hello there", true),
+                ("how are you", true),
+                ("I want to", false),
+                ("I want", true),
+                ("to book a", false),
+                ("to book a flight", true),
+            ]
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(stt.failed_over());
+        assert_eq!(stt.get_config().unwrap().provider, "assemblyai");
+        // The standby received every chunk, the primary the four up to its failure
+        assert_eq!(usage.primary_bytes(), 4 * 3200);
+        assert_eq!(usage.secondary_bytes(), 5 * 3200);
+        assert!(usage.failed_over());
+    }
+
+    #[tokio::test]
+    async fn test_cold_failover_connects_secondary_and_shifts_timings() {
+        let primary = ScriptedSTT::boxed(
+            "deepgram",
+            vec![
+                Step::Result(final_result("hello")),
+                Step::Nothing,
+                Step::Error,
+            ],
+        );
+        let secondary = ScriptedSTT::boxed(
+            "assemblyai",
+            vec![Step::Result(final_result("again").with_span(0.0, 0.1))],
+        );
+
+        let stt = FailoverSTT::with_providers(primary, secondary, false);
+        let usage = stt.usage();
+        let (results, errors, stt) = run(stt, 4).await;
+
+        assert_eq!(
+            transcripts(&results),
+            vec![("hello", true), ("again", true)]
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(stt.failed_over());
+        // On the primary's timeline, which covers the three chunks before failover
+        assert_eq!(results[1].start, Some(0.3));
+        assert_eq!(usage.primary_bytes(), 3 * 3200);
+        assert_eq!(usage.secondary_bytes(), 3200);
+    }
+
+    #[tokio::test]
+    async fn test_failed_standby_falls_back_to_cold_failover() {
+        let primary = ScriptedSTT::boxed(
+            "deepgram",
+            vec![
+                Step::Nothing,
+                Step::Result(final_result("one")),
+                Step::Error,
+            ],
+        );
+        let secondary = ScriptedSTT::boxed(
+            "assemblyai",
+            vec![
+                Step::Result(final_result("one")),
+                Step::Error,
+                Step::Result(final_result("two")),
+            ],
+        );
+
+        let stt = FailoverSTT::with_providers(primary, secondary, true);
+        let usage = stt.usage();
+        let (results, errors, mut stt) = run(stt, 2).await;
+        assert_eq!(transcripts(&results), vec![("one", true)]);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(!stt.failed_over());
+        assert!(!stt.shared.standby_live());
+
+        // The primary fails on the third chunk; the secondary reconnects on the fourth
+        for _ in 0..2 {
+            stt.send_audio(Bytes::from(vec![0u8; 3200])).await.unwrap();
+        }
+        assert!(stt.failed_over());
+        assert_eq!(usage.secondary_bytes(), 3 * 3200);
+    }
+}
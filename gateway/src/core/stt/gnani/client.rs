@@ -18,7 +18,7 @@ use bytes::Bytes;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
-use tonic::transport::Channel;
+use tonic::transport::{Channel, Endpoint};
 use tracing::{debug, info, warn};
 
 use crate::core::stt::base::{
@@ -26,7 +26,7 @@ use crate::core::stt::base::{
 };
 
 use super::config::GnaniSTTConfig;
-use super::grpc::{create_gnani_channel, GnaniGrpcClient};
+use super::grpc::{build_gnani_endpoint, connect_gnani_channel, GnaniGrpcClient};
 
 /// Gnani Speech-to-Text client
 ///
@@ -37,6 +37,9 @@ pub struct GnaniSTT {
     /// Provider-specific configuration
     config: Option<GnaniSTTConfig>,
 
+    /// gRPC endpoint with the mTLS configuration applied
+    grpc_endpoint: Option<Endpoint>,
+
     /// gRPC channel
     grpc_channel: Option<Channel>,
 
@@ -63,6 +66,7 @@ impl Default for GnaniSTT {
     fn default() -> Self {
         Self {
             config: None,
+            grpc_endpoint: None,
             grpc_channel: None,
             is_connected: Arc::new(AtomicBool::new(false)),
             result_callback: Arc::new(RwLock::new(None)),
@@ -76,17 +80,37 @@ impl Default for GnaniSTT {
 
 impl GnaniSTT {
     /// Create a new Gnani STT instance
+    ///
+    /// If a certificate is configured, it is read and applied to the gRPC
+    /// endpoint here so an unreadable certificate fails fast with
+    /// `STTError::ConfigurationError` instead of on first connect.
     pub fn create(config: STTConfig) -> Result<Self, STTError> {
         // Convert base config to Gnani-specific config
         let gnani_config =
             GnaniSTTConfig::from_base(config).map_err(STTError::ConfigurationError)?;
 
+        Self::with_config(gnani_config)
+    }
+
+    /// Create a new Gnani STT instance from a provider-specific configuration
+    pub fn with_config(config: GnaniSTTConfig) -> Result<Self, STTError> {
+        let grpc_endpoint = Self::prepare_endpoint(&config)?;
+
         Ok(Self {
-            config: Some(gnani_config),
+            config: Some(config),
+            grpc_endpoint,
             ..Default::default()
         })
     }
 
+    /// Build the mTLS endpoint if a certificate source is configured
+    fn prepare_endpoint(config: &GnaniSTTConfig) -> Result<Option<Endpoint>, STTError> {
+        if config.certificate_path.is_none() && config.certificate_content.is_none() {
+            return Ok(None);
+        }
+        build_gnani_endpoint(config).map(Some)
+    }
+
     /// Start the gRPC streaming session
     async fn start_streaming_session(&mut self) -> Result<(), STTError> {
         let config = self
@@ -191,7 +215,12 @@ impl BaseSTT for GnaniSTT {
         );
 
         // Create gRPC channel with mTLS
-        let channel = create_gnani_channel(config).await?;
+        let endpoint = match self.grpc_endpoint {
+            Some(ref endpoint) => endpoint.clone(),
+            None => build_gnani_endpoint(config)?,
+        };
+        let channel = connect_gnani_channel(&endpoint).await?;
+        self.grpc_endpoint = Some(endpoint);
         self.grpc_channel = Some(channel);
 
         // Start streaming session
@@ -267,6 +296,7 @@ impl BaseSTT for GnaniSTT {
     async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
         let gnani_config =
             GnaniSTTConfig::from_base(config).map_err(STTError::ConfigurationError)?;
+        self.grpc_endpoint = Self::prepare_endpoint(&gnani_config)?;
         self.config = Some(gnani_config);
         Ok(())
     }
@@ -290,6 +320,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         }
     }
 
@@ -370,4 +402,36 @@ mod tests {
         let result = stt.disconnect().await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_gnani_stt_with_certificate_file() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(super::super::grpc::TEST_CERTIFICATE_PEM.as_bytes())
+            .unwrap();
+
+        let config = GnaniSTTConfig {
+            certificate_path: Some(file.path().to_path_buf()),
+            ..Default::default()
+        };
+        let stt = GnaniSTT::with_config(config).unwrap();
+        assert!(stt.grpc_endpoint.is_some());
+        assert!(!stt.is_ready());
+    }
+
+    #[test]
+    fn test_gnani_stt_unreadable_certificate() {
+        let config = GnaniSTTConfig {
+            certificate_path: Some("/nonexistent/gnani/cert.pem".into()),
+            ..Default::default()
+        };
+
+        match GnaniSTT::with_config(config) {
+            Err(STTError::ConfigurationError(msg)) => {
+                assert!(msg.contains("Gnani certificate"));
+            }
+            _ => panic!("Expected ConfigurationError"),
+        }
+    }
 }
@@ -73,6 +73,8 @@ impl Default for GnaniSTTConfig {
                 punctuation: true,
                 encoding: "pcm16".to_string(),
                 model: "default".to_string(),
+                custom_headers: Default::default(),
+                certificate_path: None,
             },
             token: String::new(),
             access_key: String::new(),
@@ -90,14 +92,18 @@ impl GnaniSTTConfig {
     /// Create GnaniSTTConfig from base STTConfig
     ///
     /// Extracts Gnani-specific credentials from environment variables if not
-    /// provided in the base config.
+    /// provided in the base config. The certificate path of the base config,
+    /// set from `providers.gnani_certificate_path`, takes precedence over
+    /// `GNANI_CERTIFICATE_PATH`.
     pub fn from_base(base: STTConfig) -> Result<Self, String> {
         // Try to get credentials from environment if not in config
         let token = std::env::var("GNANI_TOKEN").unwrap_or_default();
         let access_key = std::env::var("GNANI_ACCESS_KEY").unwrap_or_default();
-        let certificate_path = std::env::var("GNANI_CERTIFICATE_PATH")
-            .ok()
-            .map(PathBuf::from);
+        let certificate_path = base.certificate_path.clone().or_else(|| {
+            std::env::var("GNANI_CERTIFICATE_PATH")
+                .ok()
+                .map(PathBuf::from)
+        });
         let certificate_content = std::env::var("GNANI_CERTIFICATE_CONTENT").ok();
 
         // Parse language code to validate it's supported
@@ -402,6 +408,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let config = GnaniSTTConfig::from_base(base).unwrap();
@@ -409,6 +417,21 @@ mod tests {
         assert_eq!(config.audio_format, GnaniAudioFormat::Wav);
     }
 
+    #[test]
+    fn test_gnani_config_from_base_uses_server_certificate_path() {
+        let base = STTConfig {
+            provider: "gnani".to_string(),
+            certificate_path: Some(PathBuf::from("/etc/waav/gnani.pem")),
+            ..Default::default()
+        };
+
+        let config = GnaniSTTConfig::from_base(base).unwrap();
+        assert_eq!(
+            config.certificate_path,
+            Some(PathBuf::from("/etc/waav/gnani.pem"))
+        );
+    }
+
     #[test]
     fn test_gnani_config_validation_missing_token() {
         let config = GnaniSTTConfig::default();
@@ -24,6 +24,7 @@ use tracing::{debug, info, warn};
 
 use super::config::GnaniSTTConfig;
 use super::messages::{SpeechChunk, TranscriptChunk};
+use crate::core::providers::headers::insert_custom_metadata;
 use crate::core::stt::base::STTError;
 
 /// Gnani gRPC endpoint
@@ -32,11 +33,20 @@ const GNANI_GRPC_ENDPOINT: &str = "https://asr.gnani.ai:443";
 /// gRPC service path for Listener.DoSpeechToText
 const GRPC_SERVICE_PATH: &str = "/Listener/DoSpeechToText";
 
-/// Create a gRPC channel with Gnani mTLS configuration
+/// Build the gRPC endpoint with Gnani mTLS configuration
 ///
-/// This establishes a secure connection to Gnani's ASR service using the
-/// provided SSL certificate for server verification.
-pub async fn create_gnani_channel(config: &GnaniSTTConfig) -> Result<Channel, STTError> {
+/// Reads the PEM certificate from the config and applies it as the CA
+/// certificate for server verification. No connection is attempted.
+///
+/// # Errors
+/// * `STTError::ConfigurationError` if the certificate cannot be read or the
+///   TLS configuration is rejected
+pub fn build_gnani_endpoint(config: &GnaniSTTConfig) -> Result<Endpoint, STTError> {
+    build_gnani_endpoint_at(GNANI_GRPC_ENDPOINT, config)
+}
+
+/// Build a Gnani gRPC endpoint for a specific URL (useful for testing)
+pub fn build_gnani_endpoint_at(url: &str, config: &GnaniSTTConfig) -> Result<Endpoint, STTError> {
     // Load the certificate
     let cert_pem = config
         .load_certificate()
@@ -47,12 +57,19 @@ pub async fn create_gnani_channel(config: &GnaniSTTConfig) -> Result<Channel, ST
         .ca_certificate(Certificate::from_pem(&cert_pem))
         .domain_name("asr.gnani.ai");
 
-    // Build and connect the channel
-    let channel = Endpoint::from_static(GNANI_GRPC_ENDPOINT)
+    let endpoint = Endpoint::from_shared(url.to_string())
+        .map_err(|e| STTError::ConfigurationError(format!("Invalid gRPC endpoint: {}", e)))?
         .tls_config(tls_config)
         .map_err(|e| STTError::ConfigurationError(format!("TLS config error: {}", e)))?
         .connect_timeout(Duration::from_secs(config.connection_timeout_secs))
-        .timeout(Duration::from_secs(config.request_timeout_secs))
+        .timeout(Duration::from_secs(config.request_timeout_secs));
+
+    Ok(endpoint)
+}
+
+/// Connect a gRPC channel on a prepared Gnani endpoint
+pub async fn connect_gnani_channel(endpoint: &Endpoint) -> Result<Channel, STTError> {
+    let channel = endpoint
         .connect()
         .await
         .map_err(|e| STTError::ConnectionFailed(format!("gRPC connection failed: {}", e)))?;
@@ -61,6 +78,15 @@ pub async fn create_gnani_channel(config: &GnaniSTTConfig) -> Result<Channel, ST
     Ok(channel)
 }
 
+/// Create a gRPC channel with Gnani mTLS configuration
+///
+/// This establishes a secure connection to Gnani's ASR service using the
+/// provided SSL certificate for server verification.
+pub async fn create_gnani_channel(config: &GnaniSTTConfig) -> Result<Channel, STTError> {
+    let endpoint = build_gnani_endpoint(config)?;
+    connect_gnani_channel(&endpoint).await
+}
+
 /// Create metadata map with Gnani authentication headers
 ///
 /// All requests to Gnani's API require these headers for authentication
@@ -88,6 +114,9 @@ pub fn create_gnani_metadata(
         parse_header_value(&config.base.encoding, "encoding")?,
     );
 
+    // User-supplied headers (proxies, tracing)
+    insert_custom_metadata(&mut metadata, &config.base.custom_headers);
+
     Ok(metadata)
 }
 
@@ -416,9 +445,33 @@ impl From<tonic::Status> for GnaniGrpcError {
     }
 }
 
+/// Self-signed CA certificate for `asr.gnani.ai` used by tests
+#[cfg(test)]
+pub(super) const TEST_CERTIFICATE_PEM: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBhDCCASugAwIBAgIUYB7mQ1V57rXjxGRBzvXVBLNvN8gwCgYIKoZIzj0EAwIw
+FzEVMBMGA1UEAwwMYXNyLmduYW5pLmFpMCAXDTI2MTAxNjAwMTIwM1oYDzIxMjYw
+OTIyMDAxMjAzWjAXMRUwEwYDVQQDDAxhc3IuZ25hbmkuYWkwWTATBgcqhkjOPQIB
+BggqhkjOPQMBBwNCAASywA56i+BUJi86f9bPJ4zWTq64Oxzg5LuzYF4rYehUkxAG
+o2VapUKQUnzmNhZO/WFN3FkMrR9cb5T4zeDWJ3DIo1MwUTAdBgNVHQ4EFgQU35h8
+TnWoLLp0wlHYOgOF+ihLfuQwHwYDVR0jBBgwFoAU35h8TnWoLLp0wlHYOgOF+ihL
+fuQwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNHADBEAiAxIpyzb7fTvHKa
+XnughnwD/afEyyfONfdxuhCJ6fIY4QIgW4DGkPC53v4RSr498O0u5eu1nW+TAuB2
+QDk4NBmCfBQ=
+-----END CERTIFICATE-----
+";
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+    use tokio::net::TcpListener;
+
+    fn certificate_file() -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(TEST_CERTIFICATE_PEM.as_bytes()).unwrap();
+        file
+    }
 
     #[test]
     fn test_gnani_grpc_error_from_code() {
@@ -447,4 +500,54 @@ mod tests {
         assert!(parse_header_value("valid-token", "token").is_ok());
         assert!(parse_header_value("en-IN", "lang").is_ok());
     }
+
+    #[test]
+    fn test_build_gnani_endpoint_with_certificate_file() {
+        let file = certificate_file();
+        let config = GnaniSTTConfig {
+            certificate_path: Some(file.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let endpoint = build_gnani_endpoint(&config).unwrap();
+        assert_eq!(endpoint.uri().host(), Some("asr.gnani.ai"));
+    }
+
+    #[test]
+    fn test_build_gnani_endpoint_unreadable_certificate() {
+        let config = GnaniSTTConfig {
+            certificate_path: Some("/nonexistent/gnani/cert.pem".into()),
+            ..Default::default()
+        };
+
+        match build_gnani_endpoint(&config) {
+            Err(STTError::ConfigurationError(msg)) => {
+                assert!(msg.contains("Failed to read Gnani certificate"));
+            }
+            other => panic!("Expected ConfigurationError, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_gnani_channel_to_mock_server() {
+        // Mock server accepts TCP connections and closes them without a TLS handshake
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                drop(stream);
+            }
+        });
+
+        let file = certificate_file();
+        let config = GnaniSTTConfig {
+            certificate_path: Some(file.path().to_path_buf()),
+            connection_timeout_secs: 2,
+            ..Default::default()
+        };
+
+        let endpoint = build_gnani_endpoint_at(&format!("https://{}", addr), &config).unwrap();
+        let result = connect_gnani_channel(&endpoint).await;
+        assert!(matches!(result, Err(STTError::ConnectionFailed(_))));
+    }
 }
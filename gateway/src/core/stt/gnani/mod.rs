@@ -67,7 +67,10 @@ mod messages;
 
 pub use client::GnaniSTT;
 pub use config::{GnaniAudioFormat, GnaniLanguage, GnaniSTTConfig};
-pub use grpc::{GnaniGrpcError, create_gnani_channel, create_gnani_metadata};
+pub use grpc::{
+    GnaniGrpcError, build_gnani_endpoint, build_gnani_endpoint_at, connect_gnani_channel,
+    create_gnani_channel, create_gnani_metadata,
+};
 pub use messages::{
     DecodeError, SpeechChunk, StreamingError, StreamingRecognitionResponse, TranscriptChunk,
 };
@@ -87,6 +90,8 @@ mod tests {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         }
     }
 
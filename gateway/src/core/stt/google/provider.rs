@@ -10,6 +10,8 @@ use crate::core::providers::google::{
     CredentialSource, GOOGLE_CLOUD_PLATFORM_SCOPE, GOOGLE_SPEECH_ENDPOINT, GoogleAuthClient,
     GoogleError, TokenProvider, create_authenticated_channel,
 };
+use crate::core::providers::headers::insert_custom_metadata;
+use crate::core::providers::token;
 use crate::core::stt::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -37,9 +39,11 @@ pub(crate) fn google_error_to_stt(e: GoogleError) -> STTError {
 /// STT-specific wrapper for GoogleAuthClient.
 ///
 /// Validates credentials and creates an auth client with the Speech API scope.
+/// Tokens are cached in a shared [`token::TokenProvider`], so every stream
+/// (re)connect gets a current token without re-querying the credentials.
 #[derive(Debug)]
 pub struct STTGoogleAuthClient {
-    inner: GoogleAuthClient,
+    inner: token::TokenProvider,
 }
 
 impl STTGoogleAuthClient {
@@ -47,10 +51,12 @@ impl STTGoogleAuthClient {
     pub fn new(credential_source: CredentialSource) -> Result<Self, STTError> {
         credential_source.validate().map_err(google_error_to_stt)?;
 
-        let inner = GoogleAuthClient::new(credential_source, &[GOOGLE_CLOUD_PLATFORM_SCOPE])
+        let client = GoogleAuthClient::new(credential_source, &[GOOGLE_CLOUD_PLATFORM_SCOPE])
             .map_err(google_error_to_stt)?;
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner: token::TokenProvider::new(client),
+        })
     }
 
     /// Creates a new auth client from an API key string.
@@ -63,7 +69,10 @@ impl STTGoogleAuthClient {
 #[async_trait::async_trait]
 impl TokenProvider for STTGoogleAuthClient {
     async fn get_token(&self) -> Result<String, GoogleError> {
-        self.inner.get_token().await
+        self.inner
+            .token()
+            .await
+            .map_err(|e| GoogleError::AuthenticationFailed(e.to_string()))
     }
 }
 
@@ -174,6 +183,7 @@ impl GoogleSTT {
         // Get sample rate and channels for keep-alive audio generation
         let sample_rate = config.base.sample_rate;
         let channels = config.base.channels as u32;
+        let custom_headers = config.base.custom_headers.clone();
 
         let connection_handle = tokio::spawn(async move {
             // Use generic authenticated channel from providers
@@ -217,6 +227,7 @@ impl GoogleSTT {
             let channel = authenticated_channel.clone_channel();
             let mut client =
                 SpeechClient::with_interceptor(channel, move |mut req: tonic::Request<()>| {
+                    insert_custom_metadata(req.metadata_mut(), &custom_headers);
                     req.metadata_mut()
                         .insert("authorization", auth_metadata_value.clone());
                     Ok(req)
@@ -611,5 +622,11 @@ impl Drop for GoogleSTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
@@ -32,6 +32,8 @@ fn test_google_stt_new_missing_project_id() {
         channels: 1,
         punctuation: true,
         encoding: "linear16".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = <GoogleSTT as BaseSTT>::new(config);
@@ -61,6 +63,8 @@ fn test_google_stt_new_with_project_id_in_credentials() {
         channels: 1,
         punctuation: true,
         encoding: "linear16".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = <GoogleSTT as BaseSTT>::new(config);
@@ -86,6 +90,8 @@ async fn test_google_stt_new_with_project_id_in_model() {
         channels: 1,
         punctuation: true,
         encoding: "linear16".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = <GoogleSTT as BaseSTT>::new(config);
@@ -169,6 +175,8 @@ fn test_google_stt_create_google_config() {
         channels: 1,
         punctuation: true,
         encoding: "linear16".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let google_config =
@@ -309,6 +317,8 @@ fn create_test_stt_config() -> STTConfig {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "latest_long".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     }
 }
 
@@ -118,6 +118,8 @@ fn test_build_config_request() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "latest_long".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         },
         project_id: "test-project".to_string(),
         location: "global".to_string(),
@@ -32,10 +32,14 @@ use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
+use crate::core::credentials::{check_credentials, credential_check_client};
+use crate::core::providers::headers::apply_custom_headers;
+use crate::core::providers::retry::{FailedAttempt, RetryPolicy, RetryReason, retry_after};
+
 use super::super::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
-use super::config::{FlushStrategy, GroqResponseFormat, GroqSTTConfig};
+use super::config::{FlushStrategy, GROQ_API_BASE_URL, GroqResponseFormat, GroqSTTConfig};
 use super::messages::{
     GroqErrorResponse, TranscriptionResponse, TranscriptionResult, VerboseTranscriptionResponse,
     wav,
@@ -55,12 +59,6 @@ const PCM_TO_FLOAT_SCALE: f32 = 1.0 / 32768.0;
 /// Default request timeout in seconds.
 const DEFAULT_TIMEOUT_SECS: u64 = 120;
 
-/// Maximum retries for transient errors.
-const MAX_RETRIES: u32 = 3;
-
-/// Base delay for exponential backoff (milliseconds).
-const BASE_RETRY_DELAY_MS: u64 = 500;
-
 /// Default connect timeout in seconds.
 const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
 
@@ -275,6 +273,27 @@ pub struct GroqSTT {
 }
 
 impl GroqSTT {
+    /// Verify an API key with a lightweight authenticated request to `/openai/v1/models`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if Groq accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(GROQ_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/openai/v1/models", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .header("Authorization", format!("Bearer {api_key}")),
+        )
+        .await
+    }
+
     /// Create a new Groq STT client with provider-specific configuration.
     ///
     /// # Arguments
@@ -478,36 +497,15 @@ impl GroqSTT {
         // Clone config for use in retry loop (needed because send_request takes &mut self)
         let config_clone = config.clone();
 
-        // Try with retries for transient errors
+        // Retry throttled and transient failures, honoring Groq's Retry-After
+        // and x-ratelimit-reset hints
+        let policy = RetryPolicy::default();
+        let mut retry = policy.start("groq");
         // Use Option to allow moving ownership on final attempt (avoids unnecessary clone)
         let mut wav_data = Some(wav_data);
-        let mut last_error = None;
-        for attempt in 0..MAX_RETRIES {
-            if attempt > 0 {
-                // Use Retry-After header if available, otherwise exponential backoff
-                let delay = self
-                    .rate_limit_info
-                    .retry_after_ms
-                    .unwrap_or_else(|| BASE_RETRY_DELAY_MS * 2u64.pow(attempt - 1));
-
-                debug!(
-                    "Retry attempt {} after {}ms delay{}",
-                    attempt + 1,
-                    delay,
-                    if self.rate_limit_info.retry_after_ms.is_some() {
-                        " (from Retry-After header)"
-                    } else {
-                        " (exponential backoff)"
-                    }
-                );
-                tokio::time::sleep(Duration::from_millis(delay)).await;
-
-                // Clear retry_after for next iteration
-                self.rate_limit_info.retry_after_ms = None;
-            }
-
+        loop {
             // Get wav_data: clone if more retries possible, move ownership on final attempt
-            let is_last_attempt = attempt == MAX_RETRIES - 1;
+            let is_last_attempt = retry.failed_attempts() + 1 >= policy.max_attempts;
             let wav_data_for_request = if is_last_attempt {
                 // Final attempt: move ownership (no clone needed)
                 wav_data.take().ok_or_else(|| {
@@ -567,15 +565,19 @@ impl GroqSTT {
                     self.audio_buffer.clear();
                     return Ok(());
                 }
-                Err(e) => {
-                    // Check if error is retryable
-                    if Self::is_retryable_error(&e) && attempt < MAX_RETRIES - 1 {
-                        warn!("Retryable error on attempt {}: {}", attempt + 1, e);
-                        last_error = Some(e);
+                Err(failure) => {
+                    if let Some(delay) = retry.next_delay(failure.reason, failure.retry_after) {
+                        debug!(
+                            "Retryable error on attempt {}: {}",
+                            retry.failed_attempts(),
+                            failure.error
+                        );
+                        tokio::time::sleep(delay).await;
                         continue;
                     }
 
                     // Non-retryable error or max retries reached
+                    let e = failure.error;
                     if let Some(callback) = self.error_callback.lock().await.as_ref() {
                         callback(e.clone()).await;
                     }
@@ -583,42 +585,27 @@ impl GroqSTT {
                 }
             }
         }
-
-        Err(last_error.unwrap_or_else(|| {
-            STTError::ProviderError("Unknown error during transcription".to_string())
-        }))
-    }
-
-    /// Check if an error is retryable (transient).
-    pub(crate) fn is_retryable_error(error: &STTError) -> bool {
-        match error {
-            STTError::NetworkError(_) => true,
-            STTError::ProviderError(msg) => {
-                msg.contains("429")
-                    || msg.contains("rate limit")
-                    || msg.contains("500")
-                    || msg.contains("502")
-                    || msg.contains("503")
-                    || msg.contains("Service Unavailable")
-            }
-            _ => false,
-        }
     }
 
     /// Send a single request to the Groq API.
     ///
     /// Takes ownership of wav_data to avoid unnecessary copies.
     /// Updates self.rate_limit_info and self.last_request_id from response headers.
+    /// Failures carry whether and when the request may be retried.
     async fn send_request(
         &mut self,
         wav_data: Vec<u8>,
         config: &GroqSTTConfig,
-    ) -> Result<TranscriptionResult, STTError> {
+    ) -> Result<TranscriptionResult, FailedAttempt<STTError>> {
         // Build multipart form - wav_data ownership is transferred here (no copy)
         let file_part = Part::bytes(wav_data)
             .file_name(format!("audio.{}", config.audio_input_format.extension()))
             .mime_str(config.audio_input_format.mime_type())
-            .map_err(|e| STTError::ConfigurationError(format!("Invalid MIME type: {e}")))?;
+            .map_err(|e| {
+                FailedAttempt::fatal(STTError::ConfigurationError(format!(
+                    "Invalid MIME type: {e}"
+                )))
+            })?;
 
         let mut form = Form::new()
             .part("file", file_part)
@@ -654,18 +641,24 @@ impl GroqSTT {
         }
 
         // Send request to Groq API
-        let response = self
+        let request = self
             .http_client
             .post(config.api_url())
             .header("Authorization", format!("Bearer {}", config.base.api_key))
-            .multipart(form)
+            .multipart(form);
+        let response = apply_custom_headers(request, &config.base.custom_headers)
             .send()
             .await
-            .map_err(|e| STTError::NetworkError(format!("Request failed: {e}")))?;
+            .map_err(|e| FailedAttempt {
+                reason: RetryReason::from_reqwest(&e),
+                error: STTError::NetworkError(format!("Request failed: {e}")),
+                retry_after: None,
+            })?;
 
         // Extract rate limit headers before consuming response
         let rate_limit_info = RateLimitInfo::from_headers(response.headers());
         self.rate_limit_info = rate_limit_info;
+        let retry_after = retry_after(response.headers());
 
         // Extract request ID for debugging
         self.last_request_id = response
@@ -685,10 +678,11 @@ impl GroqSTT {
 
         // Check response status
         let status = response.status();
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| STTError::NetworkError(format!("Failed to read response: {e}")))?;
+        let response_text = response.text().await.map_err(|e| FailedAttempt {
+            reason: RetryReason::from_reqwest(&e),
+            error: STTError::NetworkError(format!("Failed to read response: {e}")),
+            retry_after: None,
+        })?;
 
         if !status.is_success() {
             // Try to parse as Groq error
@@ -742,11 +736,16 @@ impl GroqSTT {
                 _ => STTError::ProviderError(format!("{}{}", error_msg, request_id_suffix)),
             };
 
-            return Err(stt_error);
+            return Err(FailedAttempt {
+                error: stt_error,
+                reason: RetryReason::from_status(status, &response_text),
+                retry_after,
+            });
         }
 
         // Parse response and extract request ID from response body if available
         self.parse_response(&response_text, config)
+            .map_err(FailedAttempt::fatal)
     }
 
     /// Parse API response based on configured format.
@@ -1167,6 +1166,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <GroqSTT as BaseSTT>::new(config).unwrap();
@@ -1185,6 +1186,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = <GroqSTT as BaseSTT>::new(config);
@@ -1207,6 +1210,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <GroqSTT as BaseSTT>::new(config).unwrap();
@@ -1232,6 +1237,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <GroqSTT as BaseSTT>::new(config).unwrap();
@@ -1264,6 +1271,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <GroqSTT as BaseSTT>::new(config).unwrap();
@@ -1293,6 +1302,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <GroqSTT as BaseSTT>::new(config).unwrap();
@@ -1328,6 +1339,8 @@ mod tests {
                 punctuation: true,
                 encoding: "linear16".to_string(),
                 model: "whisper-large-v3".to_string(),
+                custom_headers: Default::default(),
+                certificate_path: None,
             },
             model: GroqSTTModel::WhisperLargeV3,
             response_format: GroqResponseFormat::VerboseJson,
@@ -1430,25 +1443,6 @@ mod tests {
         assert!(!GroqSTT::is_audio_silent(&loud_audio, 0.01));
     }
 
-    #[test]
-    fn test_is_retryable_error() {
-        assert!(GroqSTT::is_retryable_error(&STTError::NetworkError(
-            "Connection reset".to_string()
-        )));
-        assert!(GroqSTT::is_retryable_error(&STTError::ProviderError(
-            "429 rate limit exceeded".to_string()
-        )));
-        assert!(GroqSTT::is_retryable_error(&STTError::ProviderError(
-            "503 Service Unavailable".to_string()
-        )));
-        assert!(!GroqSTT::is_retryable_error(
-            &STTError::AuthenticationFailed("Invalid API key".to_string())
-        ));
-        assert!(!GroqSTT::is_retryable_error(&STTError::ConfigurationError(
-            "Invalid config".to_string()
-        )));
-    }
-
     #[test]
     fn test_estimated_cost() {
         let mut stt = GroqSTT::default();
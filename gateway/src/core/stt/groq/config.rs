@@ -31,6 +31,9 @@ use serde::{Deserialize, Serialize};
 // Constants
 // =============================================================================
 
+/// Groq REST API base URL, used for credential checks.
+pub const GROQ_API_BASE_URL: &str = "https://api.groq.com";
+
 /// Groq API base URL for audio transcriptions.
 pub const GROQ_STT_URL: &str = "https://api.groq.com/openai/v1/audio/transcriptions";
 
@@ -870,28 +870,6 @@ mod client_tests {
         assert!(!GroqSTT::is_audio_silent(&loud, 0.01));
     }
 
-    #[test]
-    fn test_client_is_retryable_error() {
-        // Retryable errors
-        assert!(GroqSTT::is_retryable_error(&STTError::NetworkError(
-            "timeout".to_string()
-        )));
-        assert!(GroqSTT::is_retryable_error(&STTError::ProviderError(
-            "429 rate limit".to_string()
-        )));
-        assert!(GroqSTT::is_retryable_error(&STTError::ProviderError(
-            "503 Service Unavailable".to_string()
-        )));
-
-        // Non-retryable errors
-        assert!(!GroqSTT::is_retryable_error(
-            &STTError::AuthenticationFailed("invalid key".to_string())
-        ));
-        assert!(!GroqSTT::is_retryable_error(&STTError::ConfigurationError(
-            "invalid config".to_string()
-        )));
-    }
-
     #[test]
     fn test_client_estimated_cost() {
         let mut stt = GroqSTT::default();
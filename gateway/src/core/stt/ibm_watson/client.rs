@@ -23,7 +23,8 @@
 //!
 //! # IBM Watson-Specific Details
 //!
-//! - Uses IAM bearer token authentication (requires token refresh)
+//! - Uses IAM bearer token authentication; tokens come from a shared
+//!   `TokenProvider`, so every (re)connect uses a fresh token
 //! - WebSocket URL includes `access_token` query parameter
 //! - Start message configures recognition parameters
 //! - Stop message signals end of audio stream
@@ -34,14 +35,17 @@ use futures::{SinkExt, StreamExt};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
-use tokio::sync::{Mutex, Notify, RwLock, mpsc, oneshot};
+use tokio::sync::{Mutex, Notify, mpsc, oneshot};
 use tokio::time::{Instant, interval, timeout};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use tracing::{debug, error, info, warn};
-use url::form_urlencoded;
 
-use super::config::{IBM_IAM_URL, IbmWatsonSTTConfig};
+use super::config::IbmWatsonSTTConfig;
 use super::messages::{IbmWatsonMessage, StopMessage};
+use crate::core::providers::headers::insert_custom_headers;
+use crate::core::providers::ibm::iam_token_provider;
+use crate::core::providers::token::TokenProvider;
 use crate::core::stt::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -72,89 +76,6 @@ type AsyncErrorCallback = Box<
         + Sync,
 >;
 
-// =============================================================================
-// IAM Token Management
-// =============================================================================
-
-/// IAM access token with expiration tracking.
-#[derive(Debug, Clone)]
-struct IamToken {
-    /// The bearer token string.
-    access_token: String,
-    /// When the token expires (Unix timestamp).
-    expires_at: std::time::Instant,
-}
-
-impl IamToken {
-    /// Check if the token is expired or about to expire (within 60 seconds).
-    fn is_expired(&self) -> bool {
-        self.expires_at <= std::time::Instant::now() + Duration::from_secs(60)
-    }
-}
-
-/// IAM token response from IBM Cloud.
-#[derive(Debug, serde::Deserialize)]
-struct IamTokenResponse {
-    access_token: String,
-    /// Token lifetime in seconds.
-    expires_in: u64,
-}
-
-/// Fetch IAM access token from IBM Cloud using API key.
-async fn fetch_iam_token(api_key: &str) -> Result<IamToken, STTError> {
-    // Create client with explicit timeouts to prevent indefinite hangs
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(10))
-        .pool_max_idle_per_host(4) // Limit connection pool size
-        .pool_idle_timeout(Duration::from_secs(90)) // Close idle connections after 90s
-        .build()
-        .map_err(|e| {
-            STTError::AuthenticationFailed(format!("Failed to create HTTP client: {e}"))
-        })?;
-
-    // URL-encode the API key
-    let encoded_api_key: String = form_urlencoded::byte_serialize(api_key.as_bytes()).collect();
-
-    let response = client
-        .post(IBM_IAM_URL)
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .body(format!(
-            "grant_type=urn:ibm:params:oauth:grant-type:apikey&apikey={}",
-            encoded_api_key
-        ))
-        .send()
-        .await
-        .map_err(|e| STTError::AuthenticationFailed(format!("Failed to request IAM token: {e}")))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(STTError::AuthenticationFailed(format!(
-            "IAM token request failed ({status}): {body}"
-        )));
-    }
-
-    let token_response: IamTokenResponse = response
-        .json()
-        .await
-        .map_err(|e| STTError::AuthenticationFailed(format!("Failed to parse IAM token: {e}")))?;
-
-    // Calculate expiration time with safety margin
-    // Use saturating_sub to avoid underflow if expires_in is unexpectedly small
-    let safety_margin = 300; // 5 minutes before expiration
-    let expires_in_secs = token_response.expires_in.saturating_sub(safety_margin);
-    let expires_at = std::time::Instant::now() + Duration::from_secs(expires_in_secs.max(60));
-
-    Ok(IamToken {
-        access_token: token_response.access_token,
-        expires_at,
-    })
-}
-
 // =============================================================================
 // Connection State
 // =============================================================================
@@ -268,8 +189,8 @@ pub struct IbmWatsonSTT {
     /// Error callback storage.
     error_callback: Arc<Mutex<Option<AsyncErrorCallback>>>,
 
-    /// Cached IAM token.
-    iam_token: Arc<RwLock<Option<IamToken>>>,
+    /// IAM token provider for the configured API key.
+    token_provider: Option<Arc<TokenProvider>>,
 
     /// Connection ready flag for atomic checks.
     connected: Arc<AtomicBool>,
@@ -290,7 +211,7 @@ impl Default for IbmWatsonSTT {
             error_forward_handle: None,
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
-            iam_token: Arc::new(RwLock::new(None)),
+            token_provider: None,
             connected: Arc::new(AtomicBool::new(false)),
         }
     }
@@ -316,35 +237,14 @@ impl IbmWatsonSTT {
 
     /// Get the current IAM token, refreshing if necessary.
     async fn get_access_token(&self) -> Result<String, STTError> {
-        let api_key = self
-            .config
-            .as_ref()
-            .map(|c| c.base.api_key.clone())
-            .ok_or_else(|| {
-                STTError::ConfigurationError("No configuration available".to_string())
-            })?;
-
-        // Check if we have a valid cached token
-        {
-            let token_guard = self.iam_token.read().await;
-            if let Some(token) = token_guard.as_ref() {
-                if !token.is_expired() {
-                    return Ok(token.access_token.clone());
-                }
-            }
-        }
-
-        // Need to fetch a new token
-        let new_token = fetch_iam_token(&api_key).await?;
-        let access_token = new_token.access_token.clone();
-
-        // Cache the new token
-        {
-            let mut token_guard = self.iam_token.write().await;
-            *token_guard = Some(new_token);
-        }
+        let token_provider = self.token_provider.as_ref().ok_or_else(|| {
+            STTError::ConfigurationError("No configuration available".to_string())
+        })?;
 
-        Ok(access_token)
+        token_provider
+            .token()
+            .await
+            .map_err(|e| STTError::AuthenticationFailed(e.to_string()))
     }
 
     /// Handle incoming WebSocket messages from IBM Watson.
@@ -465,6 +365,12 @@ impl IbmWatsonSTT {
         // Build WebSocket URL
         let ws_url = config.build_websocket_url(&access_token);
 
+        // Build the handshake request so custom headers can be attached
+        let mut request = ws_url
+            .into_client_request()
+            .map_err(|e| STTError::ConnectionFailed(format!("Invalid WebSocket URL: {e}")))?;
+        insert_custom_headers(request.headers_mut(), &config.base.custom_headers);
+
         // Create channels for communication
         let (ws_tx, mut ws_rx) = mpsc::channel::<Bytes>(32);
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
@@ -488,7 +394,7 @@ impl IbmWatsonSTT {
         let connection_handle = tokio::spawn(async move {
             // Connect to IBM Watson with timeout
             let connect_result =
-                match timeout(Duration::from_secs(30), connect_async(&ws_url)).await {
+                match timeout(Duration::from_secs(30), connect_async(request)).await {
                     Ok(result) => result,
                     Err(_) => {
                         let stt_error = STTError::ConnectionFailed(
@@ -747,6 +653,12 @@ impl Drop for IbmWatsonSTT {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
             let _ = shutdown_tx.send(());
         }
+
+        // A handshake still in flight ignores the shutdown signal (e.g. after a
+        // timed-out connect); abort it so it does not outlive the client
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -767,6 +679,7 @@ impl BaseSTT for IbmWatsonSTT {
         // Create IBM Watson-specific configuration
         // Instance ID can be set later via set_instance_id()
         let ibm_config = IbmWatsonSTTConfig::from_base(config, String::new());
+        let token_provider = iam_token_provider(&ibm_config.base.api_key);
 
         Ok(Self {
             config: Some(ibm_config),
@@ -781,7 +694,7 @@ impl BaseSTT for IbmWatsonSTT {
             error_forward_handle: None,
             result_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
-            iam_token: Arc::new(RwLock::new(None)),
+            token_provider: Some(token_provider),
             connected: Arc::new(AtomicBool::new(false)),
         })
     }
@@ -938,6 +851,13 @@ impl BaseSTT for IbmWatsonSTT {
             character_insertion_bias: existing.as_ref().and_then(|c| c.character_insertion_bias),
         };
 
+        // A new API key needs its own tokens
+        if existing.as_ref().map(|c| c.base.api_key.as_str())
+            != Some(ibm_config.base.api_key.as_str())
+        {
+            self.token_provider = Some(iam_token_provider(&ibm_config.base.api_key));
+        }
+
         self.config = Some(ibm_config);
 
         // Reconnect with new configuration
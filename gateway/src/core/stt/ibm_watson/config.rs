@@ -15,7 +15,7 @@ use url::form_urlencoded;
 pub const DEFAULT_MODEL: &str = "en-US_Multimedia";
 
 /// IBM Watson IAM authentication endpoint.
-pub const IBM_IAM_URL: &str = "https://iam.cloud.ibm.com/identity/token";
+pub use crate::core::providers::ibm::IBM_IAM_URL;
 
 /// Default inactivity timeout in seconds (30 seconds).
 pub const DEFAULT_INACTIVITY_TIMEOUT: i32 = 30;
@@ -23,6 +23,8 @@ fn test_ibm_watson_stt_creation() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let stt = <IbmWatsonSTT as BaseSTT>::new(config).unwrap();
@@ -41,6 +43,8 @@ fn test_ibm_watson_stt_empty_api_key_error() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = <IbmWatsonSTT as BaseSTT>::new(config);
@@ -63,6 +67,8 @@ fn test_ibm_watson_stt_config_access() {
         punctuation: false,
         encoding: "linear16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let stt = <IbmWatsonSTT as BaseSTT>::new(config).unwrap();
@@ -3,18 +3,29 @@ pub mod aws_transcribe;
 pub mod azure;
 mod base;
 pub mod cartesia;
+mod clock;
 pub mod deepgram;
 pub mod elevenlabs;
+pub mod failover;
 pub mod gnani;
 pub mod google;
 pub mod groq;
 pub mod ibm_watson;
 pub mod openai;
+pub mod turn_router;
 
 // Re-export public types and traits
 pub use base::{
-    BaseSTT, STTConfig, STTConnectionState, STTError, STTErrorCallback, STTFactory, STTHelper,
-    STTResult, STTResultCallback, STTStats,
+    BaseSTT, RedactedSpan, STTConfig, STTConnectionState, STTError, STTErrorCallback, STTFactory,
+    STTHelper, STTResult, STTResultCallback, STTStats, STTVadCallback, STTVadEvent, WordTiming,
+};
+pub use clock::{SessionAudioClock, TranscriptTiming};
+pub use failover::{
+    DEFAULT_FAILOVER_CONNECT_TIMEOUT, FailoverSTT, STTFailoverConfig, STTFailoverUsage,
+};
+pub use turn_router::{
+    DEFAULT_ROUTE_CONNECT_TIMEOUT, STTRoute, STTTurnRoutingConfig, TurnRoutedSTT, TurnRouter,
+    UtteranceKind,
 };
 
 // Re-export Deepgram implementation
@@ -167,6 +178,8 @@ impl std::str::FromStr for STTProvider {
 ///         punctuation: true,
 ///         encoding: "linear16".to_string(),
 ///         model: "nova-3".to_string(),
+///         custom_headers: Default::default(),
+///         certificate_path: None,
 ///     };
 ///
 ///     // Create a Deepgram STT provider
@@ -213,6 +226,8 @@ pub fn create_stt_provider(
 ///         punctuation: true,
 ///         encoding: "linear16".to_string(),
 ///         model: "nova-3".to_string(),
+///         custom_headers: Default::default(),
+///         certificate_path: None,
 ///     };
 ///
 ///     // Create a Deepgram STT provider using enum
@@ -380,6 +395,8 @@ mod factory_tests {
             channels: 1,
             punctuation: true,
             encoding: "linear16".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("deepgram", config);
@@ -400,6 +417,8 @@ mod factory_tests {
             channels: 1,
             punctuation: true,
             encoding: "linear16".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::Deepgram, config);
@@ -418,6 +437,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("elevenlabs", config);
@@ -441,6 +462,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("elevenlabs", config);
@@ -464,6 +487,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::ElevenLabs, config);
@@ -490,6 +515,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("microsoft-azure", config);
@@ -511,6 +538,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         // Test that "azure" shorthand also works
@@ -532,6 +561,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("microsoft-azure", config);
@@ -555,6 +586,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::Azure, config);
@@ -600,6 +633,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "pcm_s16le".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("cartesia", config);
@@ -621,6 +656,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "pcm_s16le".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("cartesia", config);
@@ -644,6 +681,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "pcm_s16le".to_string(),
             model: "ink-whisper".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::Cartesia, config);
@@ -694,6 +733,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "pcm_s16le".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("assemblyai", config);
@@ -715,6 +756,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "pcm_s16le".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("assemblyai", config);
@@ -738,6 +781,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "pcm_s16le".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::AssemblyAI, config);
@@ -779,6 +824,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("groq", config);
@@ -800,6 +847,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("groq", config);
@@ -823,6 +872,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-large-v3-turbo".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::Groq, config);
@@ -877,6 +928,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "audio/l16".to_string(),
             model: "en-US_Telephony".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("ibm-watson", config);
@@ -898,6 +951,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "audio/l16".to_string(),
             model: "en-US_Telephony".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("ibm-watson", config);
@@ -921,6 +976,8 @@ mod factory_tests {
             punctuation: true,
             encoding: "audio/l16".to_string(),
             model: "en-US_Telephony".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider_from_enum(STTProvider::IbmWatson, config);
@@ -960,6 +1017,8 @@ mod factory_tests {
 ///         channels: 1,
 ///         punctuation: true,
 ///         encoding: "linear16".to_string(),
+///         custom_headers: Default::default(),
+///         certificate_path: None,
 ///     };
 ///     
 ///     // Create provider using factory function
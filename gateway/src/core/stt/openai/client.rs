@@ -27,6 +27,11 @@ use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
+use crate::core::credentials::{check_credentials, credential_check_client};
+use crate::core::providers::headers::apply_custom_headers;
+use crate::core::providers::retry::{RetryPolicy, RetryReason, retry_after};
+use crate::core::tts::openai::OPENAI_API_BASE_URL;
+
 use super::super::base::{
     BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
 };
@@ -147,6 +152,27 @@ pub struct OpenAISTT {
 }
 
 impl OpenAISTT {
+    /// Verify an API key with a lightweight authenticated request to `/v1/models`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if OpenAI accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(OPENAI_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .header("Authorization", format!("Bearer {api_key}")),
+        )
+        .await
+    }
+
     /// Create a new OpenAI STT client with provider-specific configuration.
     ///
     /// # Arguments
@@ -319,43 +345,8 @@ impl OpenAISTT {
         false
     }
 
-    /// Send buffered audio to OpenAI API and process the response.
-    ///
-    /// This is called internally when:
-    /// - `disconnect()` is called
-    /// - Buffer reaches the configured threshold (if using OnThreshold strategy)
-    async fn flush_buffer(&mut self) -> Result<(), STTError> {
-        if self.audio_buffer.is_empty() {
-            debug!("No audio data to transcribe");
-            return Ok(());
-        }
-
-        let config = self.config.as_ref().ok_or_else(|| {
-            STTError::ConfigurationError("No configuration available".to_string())
-        })?;
-
-        let buffer_size = self.audio_buffer.len();
-        info!(
-            "Sending {} bytes of audio to OpenAI Whisper API",
-            buffer_size
-        );
-
-        // Check file size limit
-        if buffer_size > config.max_file_size_bytes {
-            return Err(STTError::AudioProcessingError(format!(
-                "Audio buffer ({} bytes) exceeds maximum file size ({} bytes)",
-                buffer_size, config.max_file_size_bytes
-            )));
-        }
-
-        // Create WAV file from buffered PCM data
-        let wav_data = wav::create_wav(
-            &self.audio_buffer,
-            config.base.sample_rate,
-            config.base.channels,
-        );
-
-        // Build multipart form
+    /// Build the multipart form for one transcription request.
+    fn build_form(config: &OpenAISTTConfig, wav_data: Vec<u8>) -> Result<Form, STTError> {
         let file_part = Part::bytes(wav_data)
             .file_name("audio.wav")
             .mime_str(config.audio_input_format.mime_type())
@@ -394,22 +385,81 @@ impl OpenAISTT {
             }
         }
 
-        // Send request to OpenAI API
-        let response = self
-            .http_client
-            .post(config.api_url())
-            .header("Authorization", format!("Bearer {}", config.base.api_key))
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| STTError::NetworkError(format!("Request failed: {e}")))?;
-
-        // Check response status
-        let status = response.status();
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| STTError::NetworkError(format!("Failed to read response: {e}")))?;
+        Ok(form)
+    }
+
+    /// Send buffered audio to OpenAI API and process the response.
+    ///
+    /// This is called internally when:
+    /// - `disconnect()` is called
+    /// - Buffer reaches the configured threshold (if using OnThreshold strategy)
+    async fn flush_buffer(&mut self) -> Result<(), STTError> {
+        if self.audio_buffer.is_empty() {
+            debug!("No audio data to transcribe");
+            return Ok(());
+        }
+
+        let config = self.config.as_ref().ok_or_else(|| {
+            STTError::ConfigurationError("No configuration available".to_string())
+        })?;
+
+        let buffer_size = self.audio_buffer.len();
+        info!(
+            "Sending {} bytes of audio to OpenAI Whisper API",
+            buffer_size
+        );
+
+        // Check file size limit
+        if buffer_size > config.max_file_size_bytes {
+            return Err(STTError::AudioProcessingError(format!(
+                "Audio buffer ({} bytes) exceeds maximum file size ({} bytes)",
+                buffer_size, config.max_file_size_bytes
+            )));
+        }
+
+        // Create WAV file from buffered PCM data
+        let wav_data = wav::create_wav(
+            &self.audio_buffer,
+            config.base.sample_rate,
+            config.base.channels,
+        );
+
+        // Send request to OpenAI API, retrying throttled and transient failures
+        let mut retry = RetryPolicy::default().start("openai");
+        let (status, response_text) = loop {
+            let form = Self::build_form(config, wav_data.clone())?;
+            let request = self
+                .http_client
+                .post(config.api_url())
+                .header("Authorization", format!("Bearer {}", config.base.api_key))
+                .multipart(form);
+            match apply_custom_headers(request, &config.base.custom_headers)
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    let status = response.status();
+                    let hint = retry_after(response.headers());
+                    let response_text = response.text().await.map_err(|e| {
+                        STTError::NetworkError(format!("Failed to read response: {e}"))
+                    })?;
+                    if !status.is_success()
+                        && let Some(delay) =
+                            retry.next_delay(RetryReason::from_status(status, &response_text), hint)
+                    {
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+                    break (status, response_text);
+                }
+                Err(e) => {
+                    let Some(delay) = retry.next_delay(RetryReason::from_reqwest(&e), None) else {
+                        return Err(STTError::NetworkError(format!("Request failed: {e}")));
+                    };
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        };
 
         if !status.is_success() {
             // Try to parse as OpenAI error
@@ -740,6 +790,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-1".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let stt = <OpenAISTT as BaseSTT>::new(config).unwrap();
@@ -758,6 +810,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-1".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = <OpenAISTT as BaseSTT>::new(config);
@@ -780,6 +834,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-1".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <OpenAISTT as BaseSTT>::new(config).unwrap();
@@ -805,6 +861,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-1".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <OpenAISTT as BaseSTT>::new(config).unwrap();
@@ -837,6 +895,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-1".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <OpenAISTT as BaseSTT>::new(config).unwrap();
@@ -866,6 +926,8 @@ mod tests {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "whisper-1".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let mut stt = <OpenAISTT as BaseSTT>::new(config).unwrap();
@@ -901,6 +963,8 @@ mod tests {
                 punctuation: true,
                 encoding: "linear16".to_string(),
                 model: "gpt-4o-transcribe".to_string(),
+                custom_headers: Default::default(),
+                certificate_path: None,
             },
             model: OpenAISTTModel::Gpt4oTranscribe,
             response_format: ResponseFormat::VerboseJson,
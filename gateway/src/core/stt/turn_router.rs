@@ -0,0 +1,778 @@
+//! Per-turn STT provider routing
+//!
+//! Short answers like "yes", "no" or a card number do not need a premium
+//! model. [`TurnRoutedSTT`] keeps a premium and a fast provider connected and
+//! sends the caller's audio to one of them, chosen from the orchestrator's
+//! hint of what the next user turn will be ([`TurnRouter::expect`]):
+//!
+//! - `confirmation` and `digits` turns go to the fast provider
+//! - `freeform` turns go back to the premium provider
+//!
+//! Both providers connect when the session starts, so a switch only changes
+//! where the next audio chunk goes. A hint that arrives mid-utterance is
+//! applied once the utterance ends, so a turn is never split across
+//! providers. Results the previous provider still produces for audio it
+//! already received are delivered as usual. If the fast provider fails, the
+//! premium provider transcribes every turn for the rest of the session.
+//!
+//! Each provider only hears part of the session, so its result timings are
+//! shifted onto the timeline of all audio sent. [`TurnRouter`] counts the
+//! audio each provider received, which usage records report separately.
+
+use bytes::Bytes;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::base::{
+    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback, STTVadCallback,
+    STTVadEvent,
+};
+use super::clock::SessionAudioClock;
+use super::create_stt_provider;
+use super::failover::shift_timings;
+
+/// Default time the fast provider may take to reconnect before a turn
+pub const DEFAULT_ROUTE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What the orchestrator expects the caller to say in the next turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum UtteranceKind {
+    /// A short yes/no style answer
+    Confirmation,
+    /// Digits, e.g. an account or card number
+    Digits,
+    /// Anything else
+    Freeform,
+}
+
+impl UtteranceKind {
+    /// Provider that transcribes turns of this kind
+    pub fn route(self) -> STTRoute {
+        match self {
+            UtteranceKind::Confirmation | UtteranceKind::Digits => STTRoute::Fast,
+            UtteranceKind::Freeform => STTRoute::Premium,
+        }
+    }
+}
+
+/// Provider of a [`TurnRoutedSTT`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum STTRoute {
+    /// The session's configured provider
+    Premium,
+    /// The provider for confirmation and digit turns
+    Fast,
+}
+
+impl STTRoute {
+    fn index(self) -> usize {
+        match self {
+            STTRoute::Premium => 0,
+            STTRoute::Fast => 1,
+        }
+    }
+}
+
+/// Fast STT provider to route short turns to
+#[derive(Debug, Clone)]
+pub struct STTTurnRoutingConfig {
+    /// Configuration of the provider for confirmation and digit turns
+    ///
+    /// May be the premium provider with a cheaper or keyword-constrained
+    /// model.
+    pub fast: STTConfig,
+}
+
+struct RouterState {
+    /// Provider that receives audio
+    active: STTRoute,
+    /// Provider that received the latest audio chunk
+    streaming: STTRoute,
+    /// Route to switch to once the current utterance ends
+    pending: Option<STTRoute>,
+    /// A transcript of the current utterance was delivered and its
+    /// speech_final result was not
+    in_utterance: bool,
+    /// The fast provider failed; every turn goes to the premium provider
+    fast_failed: bool,
+    /// Latest hint
+    expected: Option<UtteranceKind>,
+    /// Provider that produced the latest result
+    last_result: STTRoute,
+    /// Audio each provider received
+    clocks: [SessionAudioClock; 2],
+    /// Seconds added to each provider's timings
+    offsets: [f64; 2],
+}
+
+impl RouterState {
+    /// Route the next turn, once the current utterance ends
+    fn route_next(&mut self, route: STTRoute) {
+        if self.in_utterance && self.active != route {
+            self.pending = Some(route);
+        } else {
+            self.switch_to(route);
+        }
+    }
+
+    fn switch_to(&mut self, route: STTRoute) {
+        self.pending = None;
+        if self.active != route {
+            self.active = route;
+            debug!(route = ?route, "Switched STT route");
+        }
+    }
+}
+
+/// Callbacks registered with the routed provider
+#[derive(Clone, Default)]
+struct Callbacks {
+    result: Option<STTResultCallback>,
+    error: Option<STTErrorCallback>,
+    vad: Option<STTVadCallback>,
+}
+
+/// Routing state shared by a [`TurnRoutedSTT`] and the session driving it
+pub struct TurnRouter {
+    state: Mutex<RouterState>,
+    callbacks: RwLock<Callbacks>,
+    premium_bytes: AtomicU64,
+    fast_bytes: AtomicU64,
+}
+
+impl TurnRouter {
+    pub(crate) fn new(config: &STTConfig) -> Self {
+        let clock = SessionAudioClock::new(config.sample_rate, config.channels, &config.encoding);
+        Self {
+            state: Mutex::new(RouterState {
+                active: STTRoute::Premium,
+                streaming: STTRoute::Premium,
+                pending: None,
+                in_utterance: false,
+                fast_failed: false,
+                expected: None,
+                last_result: STTRoute::Premium,
+                clocks: [clock.clone(), clock],
+                offsets: [0.0; 2],
+            }),
+            callbacks: RwLock::new(Callbacks::default()),
+            premium_bytes: AtomicU64::new(0),
+            fast_bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Route the next user turn by what the caller is expected to say
+    ///
+    /// Takes effect immediately between turns, or when the current
+    /// utterance ends.
+    ///
+    /// # Returns
+    /// * `STTRoute` - Provider that will transcribe the next turn
+    pub fn expect(&self, kind: UtteranceKind) -> STTRoute {
+        let mut state = self.state.lock();
+        state.expected = Some(kind);
+        let route = if state.fast_failed {
+            STTRoute::Premium
+        } else {
+            kind.route()
+        };
+        state.route_next(route);
+        route
+    }
+
+    /// Route the next user turn to the fast provider without a hint
+    ///
+    /// Used when turns take too long to transcribe. The next
+    /// [`expect`](Self::expect) routes by its hint again.
+    ///
+    /// # Returns
+    /// * `STTRoute` - Provider that will transcribe the next turn
+    pub fn prefer_fast(&self) -> STTRoute {
+        let mut state = self.state.lock();
+        let route = if state.fast_failed {
+            STTRoute::Premium
+        } else {
+            STTRoute::Fast
+        };
+        state.route_next(route);
+        route
+    }
+
+    /// Track utterance boundaries from a delivered result
+    ///
+    /// Call with every result delivered to the session, including
+    /// speech_final results the gateway forces, so a pending switch applies
+    /// once the utterance ends.
+    pub fn on_transcript(&self, result: &STTResult) {
+        let mut state = self.state.lock();
+        if result.is_speech_final {
+            state.in_utterance = false;
+            if let Some(route) = state.pending {
+                state.switch_to(route);
+            }
+        } else if !result.transcript.trim().is_empty() {
+            state.in_utterance = true;
+        }
+    }
+
+    /// Provider that receives audio
+    pub fn route(&self) -> STTRoute {
+        self.state.lock().active
+    }
+
+    /// Provider that produced the latest result
+    pub fn last_result_route(&self) -> STTRoute {
+        self.state.lock().last_result
+    }
+
+    /// Latest hint, if any
+    pub fn expected(&self) -> Option<UtteranceKind> {
+        self.state.lock().expected
+    }
+
+    /// Whether the fast provider failed and turns stay on the premium provider
+    pub fn fast_failed(&self) -> bool {
+        self.state.lock().fast_failed
+    }
+
+    /// Bytes of audio sent to the premium provider
+    pub fn premium_bytes(&self) -> u64 {
+        self.premium_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Bytes of audio sent to the fast provider
+    pub fn fast_bytes(&self) -> u64 {
+        self.fast_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Provider the next audio chunk goes to
+    ///
+    /// The first chunk after a switch moves the provider's timings past the
+    /// audio the other provider received meanwhile.
+    fn begin_send(&self) -> STTRoute {
+        let mut state = self.state.lock();
+        let route = state.active;
+        if state.streaming != route {
+            let total = state.clocks[0].elapsed() + state.clocks[1].elapsed();
+            state.offsets[route.index()] = total - state.clocks[route.index()].elapsed();
+            state.streaming = route;
+        }
+        route
+    }
+
+    pub(crate) fn record_audio(&self, route: STTRoute, len: usize) {
+        self.state.lock().clocks[route.index()].record_audio(len);
+        let counter = match route {
+            STTRoute::Premium => &self.premium_bytes,
+            STTRoute::Fast => &self.fast_bytes,
+        };
+        counter.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// Send every later turn to the premium provider
+    fn fail_fast(&self, error: &STTError) {
+        let mut state = self.state.lock();
+        if state.fast_failed {
+            return;
+        }
+        warn!(error = %error, "Fast STT provider failed, routing every turn to the premium provider");
+        state.fast_failed = true;
+        // The fast provider cannot finish the utterance, so switch right away
+        state.switch_to(STTRoute::Premium);
+    }
+
+    async fn on_result(&self, route: STTRoute, mut result: STTResult) {
+        {
+            let mut state = self.state.lock();
+            shift_timings(&mut result, state.offsets[route.index()]);
+            state.last_result = route;
+        }
+        let callback = self.callbacks.read().result.clone();
+        if let Some(callback) = callback {
+            callback(result).await;
+        }
+    }
+
+    async fn on_error(&self, route: STTRoute, error: STTError) {
+        if route == STTRoute::Fast {
+            self.fail_fast(&error);
+            return;
+        }
+        let callback = self.callbacks.read().error.clone();
+        if let Some(callback) = callback {
+            callback(error).await;
+        }
+    }
+
+    async fn on_vad(&self, event: STTVadEvent) {
+        let callback = self.callbacks.read().vad.clone();
+        if let Some(callback) = callback {
+            callback(event).await;
+        }
+    }
+
+    fn result_callback(self: &Arc<Self>, route: STTRoute) -> STTResultCallback {
+        let router = self.clone();
+        Arc::new(move |result| {
+            let router = router.clone();
+            Box::pin(async move { router.on_result(route, result).await })
+        })
+    }
+
+    fn error_callback(self: &Arc<Self>, route: STTRoute) -> STTErrorCallback {
+        let router = self.clone();
+        Arc::new(move |error| {
+            let router = router.clone();
+            Box::pin(async move { router.on_error(route, error).await })
+        })
+    }
+
+    fn vad_callback(self: &Arc<Self>) -> STTVadCallback {
+        let router = self.clone();
+        Arc::new(move |event| {
+            let router = router.clone();
+            Box::pin(async move { router.on_vad(event).await })
+        })
+    }
+}
+
+/// STT provider that sends each user turn to a premium or a fast provider
+///
+/// Results and VAD events of both providers are forwarded to the registered
+/// callbacks. Errors of the fast provider move routing back to the premium
+/// provider and are not reported.
+pub struct TurnRoutedSTT {
+    premium: Box<dyn BaseSTT>,
+    fast: Box<dyn BaseSTT>,
+    connect_timeout: Duration,
+    router: Arc<TurnRouter>,
+}
+
+impl TurnRoutedSTT {
+    /// Create the fast provider from its configuration
+    ///
+    /// # Arguments
+    /// * `premium` - The session's provider, e.g. a [`FailoverSTT`](super::FailoverSTT)
+    /// * `routing` - Fast provider configuration
+    ///
+    /// # Returns
+    /// * `Result<Self, STTError>` - The routed provider, or the fast provider's creation error
+    pub fn create(
+        premium: Box<dyn BaseSTT>,
+        routing: STTTurnRoutingConfig,
+    ) -> Result<Self, STTError> {
+        let fast = create_stt_provider(&routing.fast.provider, routing.fast)?;
+        Ok(Self::with_providers(premium, fast))
+    }
+
+    /// Route between already created providers
+    pub fn with_providers(premium: Box<dyn BaseSTT>, fast: Box<dyn BaseSTT>) -> Self {
+        let config = premium.get_config().cloned().unwrap_or_default();
+        Self {
+            premium,
+            fast,
+            connect_timeout: DEFAULT_ROUTE_CONNECT_TIMEOUT,
+            router: Arc::new(TurnRouter::new(&config)),
+        }
+    }
+
+    /// Set how long the fast provider may take to reconnect before a turn
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Routing state, for hints and usage
+    pub fn router(&self) -> Arc<TurnRouter> {
+        self.router.clone()
+    }
+
+    /// Register the stored callbacks on the fast provider
+    async fn register_fast_callbacks(&mut self) -> Result<(), STTError> {
+        let callbacks = self.router.callbacks.read().clone();
+        if callbacks.result.is_some() {
+            self.fast
+                .on_result(self.router.result_callback(STTRoute::Fast))
+                .await?;
+        }
+        if callbacks.error.is_some() {
+            self.fast
+                .on_error(self.router.error_callback(STTRoute::Fast))
+                .await?;
+        }
+        if callbacks.vad.is_some() {
+            self.fast.on_vad_event(self.router.vad_callback()).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a chunk to the fast provider, reconnecting it if it dropped
+    /// while idle
+    async fn send_to_fast(&mut self, audio: Bytes) -> Result<(), STTError> {
+        if !self.fast.is_ready() {
+            debug!("Reconnecting idle fast STT provider");
+            self.fast.connect_with_timeout(self.connect_timeout).await?;
+            self.register_fast_callbacks().await?;
+        }
+        let len = audio.len();
+        self.fast.send_audio(audio).await?;
+        self.router.record_audio(STTRoute::Fast, len);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseSTT for TurnRoutedSTT {
+    /// Not supported: routing needs a fast provider, see [`TurnRoutedSTT::create`]
+    fn new(_config: STTConfig) -> Result<Self, STTError> {
+        Err(STTError::ConfigurationError(
+            "TurnRoutedSTT needs a fast provider; use TurnRoutedSTT::create".to_string(),
+        ))
+    }
+
+    async fn connect(&mut self) -> Result<(), STTError> {
+        // Both connect up front so switching adds no latency
+        let (premium, fast) = tokio::join!(self.premium.connect(), self.fast.connect());
+        if let Err(e) = fast {
+            self.router.fail_fast(&e);
+        }
+        premium
+    }
+
+    async fn disconnect(&mut self) -> Result<(), STTError> {
+        let (premium, fast) = tokio::join!(self.premium.disconnect(), self.fast.disconnect());
+        if let Err(e) = fast {
+            debug!("Failed to disconnect the fast STT provider: {e}");
+        }
+        premium
+    }
+
+    fn is_ready(&self) -> bool {
+        // The premium provider takes over whenever the fast one is unavailable
+        self.premium.is_ready()
+    }
+
+    async fn send_audio(&mut self, audio_data: Bytes) -> Result<(), STTError> {
+        if self.router.begin_send() == STTRoute::Fast {
+            match self.send_to_fast(audio_data.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    self.router.fail_fast(&e);
+                    self.router.begin_send();
+                }
+            }
+        }
+        let len = audio_data.len();
+        self.premium.send_audio(audio_data).await?;
+        self.router.record_audio(STTRoute::Premium, len);
+        Ok(())
+    }
+
+    async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+        self.router.callbacks.write().result = Some(callback);
+        self.premium
+            .on_result(self.router.result_callback(STTRoute::Premium))
+            .await?;
+        self.fast
+            .on_result(self.router.result_callback(STTRoute::Fast))
+            .await
+    }
+
+    async fn on_error(&mut self, callback: STTErrorCallback) -> Result<(), STTError> {
+        self.router.callbacks.write().error = Some(callback);
+        self.premium
+            .on_error(self.router.error_callback(STTRoute::Premium))
+            .await?;
+        self.fast
+            .on_error(self.router.error_callback(STTRoute::Fast))
+            .await
+    }
+
+    fn get_config(&self) -> Option<&STTConfig> {
+        self.premium.get_config()
+    }
+
+    async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+        self.premium.update_config(config).await
+    }
+
+    fn get_provider_info(&self) -> &'static str {
+        self.premium.get_provider_info()
+    }
+
+    async fn set_end_of_turn_silence(&mut self, silence_ms: u32) -> Result<bool, STTError> {
+        let _ = self.fast.set_end_of_turn_silence(silence_ms).await;
+        self.premium.set_end_of_turn_silence(silence_ms).await
+    }
+
+    async fn on_vad_event(&mut self, callback: STTVadCallback) -> Result<bool, STTError> {
+        self.router.callbacks.write().vad = Some(callback);
+        let supported = self
+            .premium
+            .on_vad_event(self.router.vad_callback())
+            .await?;
+        self.fast.on_vad_event(self.router.vad_callback()).await?;
+        Ok(supported)
+    }
+
+    async fn finalize(&mut self) -> Result<bool, STTError> {
+        match self.router.route() {
+            STTRoute::Premium => self.premium.finalize().await,
+            STTRoute::Fast => self.fast.finalize().await,
+        }
+    }
+
+    async fn set_manual_commit(&mut self, enabled: bool) -> Result<bool, STTError> {
+        let _ = self.fast.set_manual_commit(enabled).await;
+        self.premium.set_manual_commit(enabled).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    /// Provider that answers each audio chunk with the next scripted result
+    struct ScriptedSTT {
+        config: STTConfig,
+        results: VecDeque<Option<STTResult>>,
+        connected: bool,
+        fail: bool,
+        chunks: Arc<AtomicU64>,
+        result_callback: Option<STTResultCallback>,
+        error_callback: Option<STTErrorCallback>,
+    }
+
+    impl ScriptedSTT {
+        fn boxed(
+            provider: &str,
+            results: Vec<Option<STTResult>>,
+            chunks: Arc<AtomicU64>,
+        ) -> Box<dyn BaseSTT> {
+            Box::new(Self {
+                config: STTConfig {
+                    provider: provider.to_string(),
+                    ..Default::default()
+                },
+                results: results.into(),
+                connected: false,
+                fail: false,
+                chunks,
+                result_callback: None,
+                error_callback: None,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BaseSTT for ScriptedSTT {
+        fn new(config: STTConfig) -> Result<Self, STTError> {
+            Ok(Self {
+                config,
+                results: VecDeque::new(),
+                connected: false,
+                fail: false,
+                chunks: Arc::default(),
+                result_callback: None,
+                error_callback: None,
+            })
+        }
+
+        async fn connect(&mut self) -> Result<(), STTError> {
+            self.connected = true;
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<(), STTError> {
+            self.connected = false;
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            self.connected
+        }
+
+        async fn send_audio(&mut self, _audio_data: Bytes) -> Result<(), STTError> {
+            if self.fail {
+                if let Some(callback) = &self.error_callback {
+                    callback(STTError::ProviderError("quota exceeded".to_string())).await;
+                }
+                return Err(STTError::ProviderError("quota exceeded".to_string()));
+            }
+            self.chunks.fetch_add(1, Ordering::Relaxed);
+            if let Some(Some(result)) = self.results.pop_front()
+                && let Some(callback) = &self.result_callback
+            {
+                callback(result).await;
+            }
+            Ok(())
+        }
+
+        async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+            self.result_callback = Some(callback);
+            Ok(())
+        }
+
+        async fn on_error(&mut self, callback: STTErrorCallback) -> Result<(), STTError> {
+            self.error_callback = Some(callback);
+            Ok(())
+        }
+
+        fn get_config(&self) -> Option<&STTConfig> {
+            Some(&self.config)
+        }
+
+        async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+            self.config = config;
+            Ok(())
+        }
+
+        fn get_provider_info(&self) -> &'static str {
+            "scripted"
+        }
+    }
+
+    fn speech_final(text: &str) -> Option<STTResult> {
+        Some(STTResult::new(text.to_string(), true, true, 0.9))
+    }
+
+    fn interim(text: &str) -> Option<STTResult> {
+        Some(STTResult::new(text.to_string(), false, false, 0.9))
+    }
+
+    /// Connect and collect delivered results, tracking utterances like the
+    /// voice manager does
+    async fn connect(stt: &mut TurnRoutedSTT) -> Arc<Mutex<Vec<STTResult>>> {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        stt.connect().await.unwrap();
+        let sink = results.clone();
+        let router = stt.router();
+        stt.on_result(Arc::new(move |result| {
+            router.on_transcript(&result);
+            sink.lock().push(result);
+            Box::pin(async {}) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }))
+        .await
+        .unwrap();
+        stt.on_error(Arc::new(
+            |error: STTError| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                panic!("unexpected STT error: {error}");
+            },
+        ))
+        .await
+        .unwrap();
+        results
+    }
+
+    /// Send 250ms of 16 kHz linear16 audio
+    async fn send_chunk(stt: &mut TurnRoutedSTT) {
+        stt.send_audio(Bytes::from(vec![0u8; 8000])).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expect_hints_switch_between_turns() {
+        let premium_chunks = Arc::new(AtomicU64::new(0));
+        let fast_chunks = Arc::new(AtomicU64::new(0));
+        let premium = ScriptedSTT::boxed(
+            "deepgram",
+            vec![
+                interim("I'd like to"),
+                speech_final("I'd like to pay my bill"),
+                speech_final("my address changed").map(|result| result.with_span(0.5, 0.75)),
+            ],
+            premium_chunks.clone(),
+        );
+        let fast = ScriptedSTT::boxed(
+            "deepgram-base",
+            vec![speech_final("yes").map(|result| result.with_span(0.0, 0.25))],
+            fast_chunks.clone(),
+        );
+        let mut stt = TurnRoutedSTT::with_providers(premium, fast);
+        let router = stt.router();
+        let results = connect(&mut stt).await;
+
+        send_chunk(&mut stt).await;
+        // Mid-utterance: the hint waits for the utterance to end
+        assert_eq!(router.expect(UtteranceKind::Confirmation), STTRoute::Fast);
+        assert_eq!(router.route(), STTRoute::Premium);
+        send_chunk(&mut stt).await;
+        assert_eq!(router.route(), STTRoute::Fast);
+
+        send_chunk(&mut stt).await;
+        assert_eq!(router.last_result_route(), STTRoute::Fast);
+        // Between turns the hint applies right away
+        assert_eq!(router.expect(UtteranceKind::Freeform), STTRoute::Premium);
+        assert_eq!(router.route(), STTRoute::Premium);
+        send_chunk(&mut stt).await;
+
+        let results = results.lock();
+        let transcripts: Vec<&str> = results.iter().map(|r| r.transcript.as_str()).collect();
+        assert_eq!(
+            transcripts,
+            vec![
+                "I'd like to",
+                "I'd like to pay my bill",
+                "yes",
+                "my address changed"
+            ]
+        );
+        // Both timelines cover the whole session: the fast provider's only
+        // chunk was the third, and the premium provider missed it
+        assert_eq!(results[2].start, Some(0.5));
+        assert_eq!(results[3].start, Some(0.75));
+        assert_eq!(premium_chunks.load(Ordering::Relaxed), 3);
+        assert_eq!(fast_chunks.load(Ordering::Relaxed), 1);
+        assert_eq!(router.premium_bytes(), 3 * 8000);
+        assert_eq!(router.fast_bytes(), 8000);
+    }
+
+    #[tokio::test]
+    async fn test_failed_fast_provider_routes_to_premium() {
+        let premium_chunks = Arc::new(AtomicU64::new(0));
+        let premium = ScriptedSTT::boxed(
+            "deepgram",
+            vec![speech_final("four two")],
+            premium_chunks.clone(),
+        );
+        let mut fast = ScriptedSTT::new(STTConfig::default()).unwrap();
+        fast.fail = true;
+        let mut stt = TurnRoutedSTT::with_providers(premium, Box::new(fast));
+        let router = stt.router();
+        let results = connect(&mut stt).await;
+
+        router.expect(UtteranceKind::Digits);
+        send_chunk(&mut stt).await;
+
+        assert!(router.fast_failed());
+        assert_eq!(router.route(), STTRoute::Premium);
+        assert_eq!(results.lock()[0].transcript, "four two");
+        assert_eq!(premium_chunks.load(Ordering::Relaxed), 1);
+        assert_eq!(router.fast_bytes(), 0);
+        // Later hints keep the premium provider
+        assert_eq!(router.expect(UtteranceKind::Digits), STTRoute::Premium);
+        assert_eq!(router.prefer_fast(), STTRoute::Premium);
+    }
+
+    #[test]
+    fn test_prefer_fast_waits_for_utterance_end() {
+        let router = TurnRouter::new(&STTConfig::default());
+        router.on_transcript(&interim("my order").unwrap());
+        assert_eq!(router.prefer_fast(), STTRoute::Fast);
+        assert_eq!(router.route(), STTRoute::Premium);
+
+        router.on_transcript(&speech_final("my order number").unwrap());
+        assert_eq!(router.route(), STTRoute::Fast);
+        // Not a hint about what the caller will say
+        assert!(router.expected().is_none());
+    }
+}
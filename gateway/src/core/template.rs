@@ -0,0 +1,661 @@
+//! Session config templates
+//!
+//! String fields of a session config (STT, TTS, agent and greeting config,
+//! including configs expanded from agent profiles) may hold `{{ expr }}`
+//! templates that are evaluated once, at session start, for example to pick
+//! a voice by campaign:
+//!
+//! ```yaml
+//! voice_id: "{{ metadata.campaign == 'A' ? 'voice-1' : 'voice-2' }}"
+//! ```
+//!
+//! A field that is a single template takes the type of its result, so
+//! `"{{ 8000 * 2 }}"` becomes the number `16000`. Templates embedded in
+//! longer text are interpolated as text: `"Hi {{ metadata.name }}!"`.
+//!
+//! # Expression grammar
+//!
+//! Expressions are evaluated by the sandboxed Rhai engine DAG condition
+//! edges use ([`create_rhai_engine`](crate::dag::routing::create_rhai_engine)),
+//! so templates need the `dag-routing` feature. On top of Rhai expressions
+//! the grammar adds single-quoted strings and the conditional operator:
+//!
+//! ```text
+//! expr        := conditional
+//! conditional := rhai-expr [ "?" conditional ":" conditional ]
+//! rhai-expr   := literals, operators, property access and function calls
+//!                of a Rhai expression; no statements, assignments or loops
+//! string      := '"' chars '"' | "'" chars "'"
+//! ```
+//!
+//! `?:` binds loosest and nests to the right, so `a ? b : c ? d : e` reads
+//! `a ? b : (c ? d : e)`. Parentheses group as usual.
+//!
+//! The expression sees these variables:
+//!
+//! - `metadata` - the session metadata. Dotted keys nest, so the key
+//!   `caller.tier` is both `metadata.caller.tier` and `metadata["caller.tier"]`.
+//!   Metadata values are strings; use `parse_int` to compare numbers.
+//! - `sip` - the SIP call of the LiveKit room: `trunk_id`, `called_number`,
+//!   `caller_number`, `accept_language` and the raw participant `attributes`.
+//!   Empty when the session is not a SIP call.
+//! - `stream_id` - the session's stream id
+//!
+//! Missing metadata keys read as nothing (`()`), which compares unequal to
+//! any string. An expression must produce a string, number or boolean; any
+//! other result, and any evaluation error, rejects the session config with
+//! an issue naming the field, e.g. `tts_config.voice_id`.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::core::validation::ConfigIssue;
+
+/// Opens a template
+const TEMPLATE_OPEN: &str = "{{";
+/// Closes a template
+const TEMPLATE_CLOSE: &str = "}}";
+
+/// What templates are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    /// Session metadata
+    pub metadata: HashMap<String, String>,
+    /// Attributes of the SIP caller, when the session is a SIP call
+    pub sip_attributes: Option<HashMap<String, String>>,
+    /// The session's stream id
+    pub stream_id: String,
+}
+
+/// Whether any string in `value` holds a template
+pub fn contains_template(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.contains(TEMPLATE_OPEN),
+        Value::Array(items) => items.iter().any(contains_template),
+        Value::Object(fields) => fields.values().any(contains_template),
+        _ => false,
+    }
+}
+
+/// Evaluate every template in `value` in place
+///
+/// `field` is the path of `value` in the session config and prefixes the
+/// field names of the returned issue.
+pub fn render_templates(
+    field: &str,
+    value: &mut Value,
+    context: &TemplateContext,
+) -> Result<(), ConfigIssue> {
+    match value {
+        Value::String(s) if s.contains(TEMPLATE_OPEN) => {
+            *value = render_string(s, context).map_err(|e| ConfigIssue::new(field, e))?;
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                render_templates(&format!("{field}[{i}]"), item, context)?;
+            }
+        }
+        Value::Object(fields) => {
+            for (key, item) in fields.iter_mut() {
+                render_templates(&format!("{field}.{key}"), item, context)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Render the templates of one string field
+fn render_string(text: &str, context: &TemplateContext) -> Result<Value, String> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(TEMPLATE_OPEN) {
+        let after_open = &rest[start + TEMPLATE_OPEN.len()..];
+        let end = after_open
+            .find(TEMPLATE_CLOSE)
+            .ok_or_else(|| format!("unclosed '{TEMPLATE_OPEN}' in template"))?;
+        let expression = after_open[..end].trim();
+        if expression.is_empty() {
+            return Err("empty template".to_string());
+        }
+        parts.push((&rest[..start], evaluate(expression, context)?));
+        rest = &after_open[end + TEMPLATE_CLOSE.len()..];
+    }
+
+    // A field that is exactly one template keeps the type of its result
+    if let [("", value)] = parts.as_slice()
+        && rest.is_empty()
+    {
+        return Ok(value.clone());
+    }
+
+    let mut rendered = String::new();
+    for (prefix, value) in parts {
+        rendered.push_str(prefix);
+        match value {
+            Value::String(s) => rendered.push_str(&s),
+            other => rendered.push_str(&other.to_string()),
+        }
+    }
+    rendered.push_str(rest);
+    Ok(Value::String(rendered))
+}
+
+#[cfg(feature = "dag-routing")]
+fn evaluate(expression: &str, context: &TemplateContext) -> Result<Value, String> {
+    use std::sync::LazyLock;
+
+    use rhai::{Dynamic, Engine, Scope};
+
+    use crate::dag::routing::create_rhai_engine;
+
+    static ENGINE: LazyLock<Engine> = LazyLock::new(create_rhai_engine);
+
+    let source = translate(expression)?;
+    let ast = ENGINE.compile_expression(&source).map_err(|e| {
+        format!(
+            "invalid template expression '{expression}': {}",
+            e.err_type()
+        )
+    })?;
+
+    let mut scope = Scope::new();
+    scope.push_constant("metadata", metadata_map(&context.metadata));
+    scope.push_constant("sip", sip_map(context.sip_attributes.as_ref()));
+    scope.push_constant("stream_id", context.stream_id.clone());
+
+    let result: Dynamic = ENGINE
+        .eval_ast_with_scope(&mut scope, &ast)
+        .map_err(|mut e| {
+            e.clear_position();
+            format!("template expression '{expression}' failed: {e}")
+        })?;
+
+    if result.is_string() {
+        return Ok(Value::String(result.into_string().unwrap_or_default()));
+    }
+    if let Ok(b) = result.as_bool() {
+        return Ok(Value::Bool(b));
+    }
+    if let Ok(n) = result.as_int() {
+        return Ok(Value::from(n));
+    }
+    if let Ok(f) = result.as_float()
+        && let Some(n) = serde_json::Number::from_f64(f)
+    {
+        return Ok(Value::Number(n));
+    }
+    if result.is_unit() {
+        return Err(format!(
+            "template expression '{expression}' evaluated to nothing; is a metadata key missing?"
+        ));
+    }
+    Err(format!(
+        "template expression '{expression}' must produce a string, number or boolean, not {}",
+        result.type_name()
+    ))
+}
+
+#[cfg(not(feature = "dag-routing"))]
+fn evaluate(_expression: &str, _context: &TemplateContext) -> Result<Value, String> {
+    Err("config templates require the gateway to be built with the dag-routing feature".to_string())
+}
+
+/// Session metadata as a Rhai map, nesting dotted keys
+#[cfg(feature = "dag-routing")]
+fn metadata_map(metadata: &HashMap<String, String>) -> rhai::Map {
+    use std::collections::BTreeMap;
+
+    use rhai::Dynamic;
+
+    enum Node {
+        Leaf(String),
+        Branch(BTreeMap<String, Node>),
+    }
+
+    fn to_dynamic(node: Node) -> Dynamic {
+        match node {
+            Node::Leaf(value) => Dynamic::from(value),
+            Node::Branch(children) => Dynamic::from_map(
+                children
+                    .into_iter()
+                    .map(|(key, child)| (key.into(), to_dynamic(child)))
+                    .collect(),
+            ),
+        }
+    }
+
+    let mut map = rhai::Map::new();
+    let mut tree = BTreeMap::new();
+    // Shorter keys first, so a value set for `caller` wins over `caller.tier`
+    let mut keys: Vec<&String> = metadata.keys().collect();
+    keys.sort_by_key(|key| (key.matches('.').count(), key.as_str()));
+    for key in keys {
+        let value = metadata[key].clone();
+        map.insert(key.as_str().into(), Dynamic::from(value.clone()));
+
+        let mut segments: Vec<&str> = key.split('.').collect();
+        let Some(leaf) = segments.pop().filter(|_| !segments.is_empty()) else {
+            continue;
+        };
+        let mut node = Some(&mut tree);
+        for segment in segments {
+            let entry = node.and_then(|children| {
+                match children
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Node::Branch(BTreeMap::new()))
+                {
+                    Node::Branch(children) => Some(children),
+                    Node::Leaf(_) => None,
+                }
+            });
+            node = entry;
+        }
+        if let Some(node) = node {
+            node.entry(leaf.to_string()).or_insert(Node::Leaf(value));
+        }
+    }
+
+    // Flat keys are kept for `metadata["caller.tier"]`
+    for (key, node) in tree {
+        map.entry(key.into()).or_insert_with(|| to_dynamic(node));
+    }
+    map
+}
+
+/// The SIP call details as a Rhai map
+#[cfg(feature = "dag-routing")]
+fn sip_map(attributes: Option<&HashMap<String, String>>) -> rhai::Map {
+    use rhai::Dynamic;
+
+    use crate::config::SipCallInfo;
+
+    let mut map = rhai::Map::new();
+    let Some(attributes) = attributes else {
+        return map;
+    };
+    let call = SipCallInfo::from_attributes(attributes);
+    for (name, value) in [
+        ("trunk_id", call.trunk_id),
+        ("called_number", call.called_number),
+        ("caller_number", call.caller_number),
+        ("accept_language", call.accept_language),
+    ] {
+        map.insert(
+            name.into(),
+            value.map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+        );
+    }
+    let raw = attributes
+        .iter()
+        .map(|(key, value)| (key.as_str().into(), Dynamic::from(value.clone())))
+        .collect::<rhai::Map>();
+    map.insert("attributes".into(), Dynamic::from_map(raw));
+    map
+}
+
+/// A lexical item of a template expression
+#[derive(Debug)]
+enum Item {
+    /// Rhai source passed through as is
+    Text(String),
+    /// A string literal, already in Rhai syntax
+    Str(String),
+    /// A parenthesized or bracketed group
+    Group(char, Vec<Item>, char),
+    Question,
+    Colon,
+}
+
+/// Translate a template expression to a Rhai expression
+///
+/// Single-quoted strings become double-quoted Rhai strings and `c ? a : b`
+/// becomes the Rhai if-expression `if c { a } else { b }`.
+#[cfg_attr(not(feature = "dag-routing"), allow(dead_code))]
+fn translate(expression: &str) -> Result<String, String> {
+    let mut chars = expression.chars().peekable();
+    let items = lex(&mut chars, None)?;
+    emit(&items)
+}
+
+/// Split `chars` into items up to the `close` of an enclosing group
+fn lex(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    close: Option<char>,
+) -> Result<Vec<Item>, String> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    let flush = |text: &mut String, items: &mut Vec<Item>| {
+        if !text.is_empty() {
+            items.push(Item::Text(std::mem::take(text)));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                flush(&mut text, &mut items);
+                items.push(Item::Str(lex_string(chars, c)?));
+            }
+            '(' | '[' => {
+                flush(&mut text, &mut items);
+                let closing = if c == '(' { ')' } else { ']' };
+                let inner = lex(chars, Some(closing))?;
+                items.push(Item::Group(c, inner, closing));
+            }
+            ')' | ']' => {
+                if close != Some(c) {
+                    return Err(format!("unexpected '{c}' in template expression"));
+                }
+                flush(&mut text, &mut items);
+                return Ok(items);
+            }
+            '?' if chars.peek() == Some(&'?') || chars.peek() == Some(&'.') => {
+                // Rhai's `??` and `?.` operators
+                text.push(c);
+                text.extend(chars.next());
+            }
+            '?' => {
+                flush(&mut text, &mut items);
+                items.push(Item::Question);
+            }
+            ':' => {
+                flush(&mut text, &mut items);
+                items.push(Item::Colon);
+            }
+            _ => text.push(c),
+        }
+    }
+
+    match close {
+        Some(c) => Err(format!("missing '{c}' in template expression")),
+        None => {
+            flush(&mut text, &mut items);
+            Ok(items)
+        }
+    }
+}
+
+/// Read a string literal after its opening `quote` as a Rhai string
+fn lex_string(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    quote: char,
+) -> Result<String, String> {
+    let mut literal = String::from('"');
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars
+                    .next()
+                    .ok_or("unterminated string in template expression")?;
+                if escaped == '\'' {
+                    literal.push('\'');
+                } else {
+                    literal.push('\\');
+                    literal.push(escaped);
+                }
+            }
+            '"' if quote == '"' => {
+                literal.push('"');
+                return Ok(literal);
+            }
+            '\'' if quote == '\'' => {
+                literal.push('"');
+                return Ok(literal);
+            }
+            '"' => literal.push_str("\\\""),
+            _ => literal.push(c),
+        }
+    }
+    Err("unterminated string in template expression".to_string())
+}
+
+/// Rhai source of a sequence of items
+fn emit(items: &[Item]) -> Result<String, String> {
+    let Some(question) = items.iter().position(|item| matches!(item, Item::Question)) else {
+        if items.iter().any(|item| matches!(item, Item::Colon)) {
+            return Err("':' without a matching '?' in template expression".to_string());
+        }
+        return emit_plain(items);
+    };
+
+    // The ':' that belongs to this '?', skipping those of nested conditionals
+    let mut depth = 0;
+    let mut colon = None;
+    for (i, item) in items.iter().enumerate().skip(question + 1) {
+        match item {
+            Item::Question => depth += 1,
+            Item::Colon if depth == 0 => {
+                colon = Some(i);
+                break;
+            }
+            Item::Colon => depth -= 1,
+            _ => {}
+        }
+    }
+    let colon = colon.ok_or("'?' without a matching ':' in template expression")?;
+
+    let condition = emit(&items[..question])?;
+    let then = emit(&items[question + 1..colon])?;
+    let otherwise = emit(&items[colon + 1..])?;
+    let (condition, then, otherwise) = (condition.trim(), then.trim(), otherwise.trim());
+    if condition.is_empty() || then.is_empty() || otherwise.is_empty() {
+        return Err("incomplete '? :' in template expression".to_string());
+    }
+    Ok(format!(
+        "(if {condition} {{ {then} }} else {{ {otherwise} }})"
+    ))
+}
+
+/// Rhai source of items without a top-level conditional
+fn emit_plain(items: &[Item]) -> Result<String, String> {
+    let mut source = String::new();
+    for item in items {
+        match item {
+            Item::Text(text) | Item::Str(text) => source.push_str(text),
+            Item::Group(open, inner, close) => {
+                source.push(*open);
+                source.push_str(&emit(inner)?);
+                source.push(*close);
+            }
+            Item::Question | Item::Colon => unreachable!("handled by emit"),
+        }
+    }
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_translate_conditionals() {
+        assert_eq!(
+            translate("metadata.campaign == 'A' ? 'voice-1' : 'voice-2'").unwrap(),
+            r#"(if metadata.campaign == "A" { "voice-1" } else { "voice-2" })"#
+        );
+        // Nests to the right and inside parentheses
+        assert_eq!(
+            translate("a ? 1 : b ? 2 : 3").unwrap(),
+            "(if a { 1 } else { (if b { 2 } else { 3 }) })"
+        );
+        assert_eq!(
+            translate("'x-' + (a ? 'y' : 'z')").unwrap(),
+            r#""x-" + ((if a { "y" } else { "z" }))"#
+        );
+        assert!(translate("a ? 1").is_err());
+        assert!(translate("a : 1").is_err());
+        assert!(translate("(a").is_err());
+        assert!(translate("'open").is_err());
+    }
+
+    #[test]
+    fn test_contains_template() {
+        assert!(contains_template(
+            &json!({"voice_id": "{{ sip.trunk_id }}"})
+        ));
+        assert!(contains_template(&json!({"words": ["a", "{{ b }}"]})));
+        assert!(!contains_template(&json!({"voice_id": "aura", "rate": 1})));
+    }
+
+    #[test]
+    fn test_plain_strings_are_untouched() {
+        let mut config = json!({"voice_id": "aura-asteria-en", "speaking_rate": 1.0});
+        let expected = config.clone();
+        render_templates("tts_config", &mut config, &TemplateContext::default()).unwrap();
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn test_unclosed_template_names_field() {
+        let mut config = json!({"voice_id": "{{ metadata.campaign"});
+        let issue =
+            render_templates("tts_config", &mut config, &TemplateContext::default()).unwrap_err();
+        assert_eq!(issue.field, "tts_config.voice_id");
+        assert!(issue.message.contains("unclosed"), "{}", issue.message);
+    }
+
+    #[cfg(feature = "dag-routing")]
+    fn context() -> TemplateContext {
+        TemplateContext {
+            metadata: HashMap::from([
+                ("campaign".to_string(), "A".to_string()),
+                ("caller.tier".to_string(), "gold".to_string()),
+                ("caller.name".to_string(), "Ada".to_string()),
+                ("caller.account.region".to_string(), "eu".to_string()),
+                ("retries".to_string(), "2".to_string()),
+            ]),
+            sip_attributes: Some(HashMap::from([
+                ("sip.trunkID".to_string(), "ST_uk".to_string()),
+                (
+                    "sip.trunkPhoneNumber".to_string(),
+                    "+442071234567".to_string(),
+                ),
+                ("sip.phoneNumber".to_string(), "+15551234567".to_string()),
+            ])),
+            stream_id: "stream-1".to_string(),
+        }
+    }
+
+    #[cfg(feature = "dag-routing")]
+    fn render(template: &str) -> Result<Value, ConfigIssue> {
+        let mut config = json!({ "voice_id": template });
+        render_templates("tts_config", &mut config, &context())?;
+        Ok(config["voice_id"].clone())
+    }
+
+    #[cfg(feature = "dag-routing")]
+    #[test]
+    fn test_conditional_selects_voice() {
+        assert_eq!(
+            render("{{ metadata.campaign == 'A' ? 'voice-1' : 'voice-2' }}").unwrap(),
+            json!("voice-1")
+        );
+        assert_eq!(
+            render("{{ metadata.campaign == 'B' ? 'voice-1' : 'voice-2' }}").unwrap(),
+            json!("voice-2")
+        );
+        // Missing keys compare unequal
+        assert_eq!(
+            render("{{ metadata.missing == 'A' ? 'voice-1' : 'voice-2' }}").unwrap(),
+            json!("voice-2")
+        );
+    }
+
+    #[cfg(feature = "dag-routing")]
+    #[test]
+    fn test_nested_metadata_access() {
+        assert_eq!(
+            render("{{ metadata.caller.tier == 'gold' ? 'premium' : 'standard' }}").unwrap(),
+            json!("premium")
+        );
+        assert_eq!(
+            render("{{ metadata.caller.account.region }}").unwrap(),
+            json!("eu")
+        );
+        assert_eq!(
+            render("{{ metadata[\"caller.tier\"] }}").unwrap(),
+            json!("gold")
+        );
+        assert_eq!(
+            render("Hello {{ metadata.caller.name }}, you are on {{ stream_id }}").unwrap(),
+            json!("Hello Ada, you are on stream-1")
+        );
+    }
+
+    #[cfg(feature = "dag-routing")]
+    #[test]
+    fn test_sip_attributes() {
+        assert_eq!(
+            render("{{ sip.called_number.starts_with('+44') ? 'en-GB' : 'en-US' }}").unwrap(),
+            json!("en-GB")
+        );
+        assert_eq!(
+            render("{{ sip.attributes[\"sip.trunkID\"] }}").unwrap(),
+            json!("ST_uk")
+        );
+    }
+
+    #[cfg(feature = "dag-routing")]
+    #[test]
+    fn test_single_template_keeps_result_type() {
+        assert_eq!(render("{{ 8000 * 2 }}").unwrap(), json!(16000));
+        assert_eq!(
+            render("{{ parse_int(metadata.retries) > 1 }}").unwrap(),
+            json!(true)
+        );
+        assert_eq!(render("rate-{{ 8000 * 2 }}").unwrap(), json!("rate-16000"));
+    }
+
+    #[cfg(feature = "dag-routing")]
+    #[test]
+    fn test_type_errors_name_the_field() {
+        // Metadata values are strings
+        let issue = render("{{ metadata.retries * 2 }}").unwrap_err();
+        assert_eq!(issue.field, "tts_config.voice_id");
+        assert!(
+            issue.message.contains("Function not found"),
+            "{}",
+            issue.message
+        );
+
+        // A map is not a field value
+        let issue = render("{{ metadata.caller }}").unwrap_err();
+        assert_eq!(issue.field, "tts_config.voice_id");
+        assert!(
+            issue.message.contains("string, number or boolean"),
+            "{}",
+            issue.message
+        );
+
+        let issue = render("{{ metadata.missing }}").unwrap_err();
+        assert!(
+            issue.message.contains("evaluated to nothing"),
+            "{}",
+            issue.message
+        );
+
+        let issue = render("{{ shell('ls') }}").unwrap_err();
+        assert!(
+            issue.message.contains("Function not found"),
+            "{}",
+            issue.message
+        );
+
+        let mut config = json!({"voice_settings": {"words": ["{{ 'a' ? 1 : 2 }}"]}});
+        let issue = render_templates("tts_config", &mut config, &context()).unwrap_err();
+        assert_eq!(issue.field, "tts_config.voice_settings.words[0]");
+    }
+
+    #[cfg(not(feature = "dag-routing"))]
+    #[test]
+    fn test_templates_need_dag_routing() {
+        let mut config = json!({"voice_id": "{{ metadata.campaign }}"});
+        let issue =
+            render_templates("tts_config", &mut config, &TemplateContext::default()).unwrap_err();
+        assert_eq!(issue.field, "tts_config.voice_id");
+        assert!(issue.message.contains("dag-routing"), "{}", issue.message);
+    }
+}
@@ -568,7 +568,12 @@ impl Default for AwsPollyTTSConfig {
                 request_timeout: Some(60),
                 pronunciations: Vec::new(),
                 request_pool_size: Some(4),
+                utterance_timeout: Some(10),
                 emotion_config: None,
+                custom_headers: Default::default(),
+                output_profile: None,
+                context_hints: None,
+                visemes: None,
             },
             region: AwsRegion::default(),
             aws_access_key_id: None,
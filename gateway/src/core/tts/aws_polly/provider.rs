@@ -375,11 +375,21 @@ impl AwsPollyTTS {
             request = request.lexicon_names(lexicon.clone());
         }
 
-        // Send request
-        let response = request.send().await.map_err(|e| {
-            error!(request_id = request_id, error = %e, "Polly API error");
-            TTSError::ProviderError(format!("Polly API error: {}", e))
-        })?;
+        // Send request with any custom headers (added before SigV4 signing)
+        let custom_headers = self.config.base.custom_headers.clone();
+        let response = request
+            .customize()
+            .mutate_request(move |req| {
+                for (name, value) in &custom_headers {
+                    let _ = req.headers_mut().try_insert(name.clone(), value.clone());
+                }
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                error!(request_id = request_id, error = %e, "Polly API error");
+                TTSError::ProviderError(format!("Polly API error: {}", e))
+            })?;
 
         // Read audio stream
         let audio_stream: ByteStream = response.audio_stream;
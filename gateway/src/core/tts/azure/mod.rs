@@ -11,6 +11,8 @@
 //!   and SSML generation utilities.
 //! - **provider**: The `AzureTTS` provider implementation and `AzureRequestBuilder`
 //!   for constructing HTTP requests.
+//! - **websocket**: Synthesis over the WebSocket API, used instead of REST when
+//!   viseme and word timing is requested (`TTSConfig::visemes`).
 //!
 //! # Example
 //!
@@ -46,6 +48,7 @@
 
 mod config;
 mod provider;
+mod websocket;
 
 // Re-export configuration types
 pub use config::{
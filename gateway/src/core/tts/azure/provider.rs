@@ -41,6 +41,7 @@ use tracing::info;
 use xxhash_rust::xxh3::xxh3_128;
 
 use super::config::{AZURE_OUTPUT_FORMAT_HEADER, AzureTTSConfig};
+use super::websocket::AzureWebSocket;
 use crate::core::providers::azure::{AZURE_SUBSCRIPTION_KEY_HEADER, AzureRegion};
 use crate::core::tts::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSResult};
 use crate::core::tts::provider::{PronunciationReplacer, TTSProvider, TTSRequestBuilder};
@@ -199,6 +200,10 @@ fn compute_azure_tts_config_hash(config: &TTSConfig, azure_config: &AzureTTSConf
 /// - Pronunciation replacement
 /// - Audio caching support
 ///
+/// With [`TTSConfig::visemes`] set, text is synthesized over the WebSocket
+/// API instead, which also reports viseme and word timing through
+/// [`AudioCallback::on_timing`]. WebSocket audio is not cached.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -223,6 +228,8 @@ pub struct AzureTTS {
     request_builder: AzureRequestBuilder,
     /// Precomputed configuration hash for cache keying.
     config_hash: String,
+    /// WebSocket synthesis, used instead of REST when timing is requested.
+    websocket: Option<AzureWebSocket>,
 }
 
 impl AzureTTS {
@@ -287,10 +294,18 @@ impl AzureTTS {
         // Compute config hash for caching
         let config_hash = compute_azure_tts_config_hash(&config, &azure_config);
 
+        let websocket = config.visemes_enabled().then(|| {
+            AzureWebSocket::new(
+                azure_config.clone(),
+                request_builder.pronunciation_replacer.clone(),
+            )
+        });
+
         Ok(Self {
             provider: TTSProvider::new()?,
             request_builder,
             config_hash,
+            websocket,
         })
     }
 
@@ -325,6 +340,12 @@ impl BaseTTS for AzureTTS {
     }
 
     async fn connect(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &mut self.websocket {
+            websocket.connect().await?;
+            info!("Azure TTS provider connected over WebSocket");
+            return Ok(());
+        }
+
         let url = self.request_builder.azure_config.build_tts_url();
         self.provider
             .generic_connect_with_config(&url, &self.request_builder.config)
@@ -334,15 +355,25 @@ impl BaseTTS for AzureTTS {
     }
 
     async fn disconnect(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &mut self.websocket {
+            return websocket.disconnect().await;
+        }
         self.provider.generic_disconnect().await
     }
 
     fn is_ready(&self) -> bool {
-        self.provider.is_ready()
+        match &self.websocket {
+            Some(websocket) => websocket.is_ready(),
+            None => self.provider.is_ready(),
+        }
     }
 
     fn get_connection_state(&self) -> ConnectionState {
-        self.provider.get_connection_state()
+        match &self.websocket {
+            Some(websocket) if websocket.is_ready() => ConnectionState::Connected,
+            Some(_) => ConnectionState::Disconnected,
+            None => self.provider.get_connection_state(),
+        }
     }
 
     async fn speak(&mut self, text: &str, flush: bool) -> TTSResult<()> {
@@ -352,6 +383,10 @@ impl BaseTTS for AzureTTS {
             self.connect().await?;
         }
 
+        if let Some(websocket) = &self.websocket {
+            return websocket.speak(text, flush);
+        }
+
         // Set config hash once on first speak (idempotent)
         self.provider
             .set_tts_config_hash(self.config_hash.clone())
@@ -363,28 +398,49 @@ impl BaseTTS for AzureTTS {
     }
 
     async fn clear(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            return websocket.clear();
+        }
         self.provider.generic_clear().await
     }
 
     async fn flush(&self) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            return websocket.flush();
+        }
         self.provider.generic_flush().await
     }
 
     fn on_audio(&mut self, callback: Arc<dyn AudioCallback>) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            websocket.set_callback(Some(callback));
+            return Ok(());
+        }
         self.provider.generic_on_audio(callback)
     }
 
     fn remove_audio_callback(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            websocket.set_callback(None);
+            return Ok(());
+        }
         self.provider.generic_remove_audio_callback()
     }
 
     fn get_provider_info(&self) -> serde_json::Value {
+        let azure_config = &self.request_builder.azure_config;
+        let (api_type, endpoint) = match &self.websocket {
+            Some(_) => ("WebSocket", azure_config.region.tts_websocket_url()),
+            None => ("HTTP REST", azure_config.build_tts_url()),
+        };
+
         serde_json::json!({
             "provider": "azure",
             "version": "1.0.0",
-            "api_type": "HTTP REST",
-            "connection_pooling": true,
-            "endpoint": self.request_builder.azure_config.build_tts_url(),
+            "api_type": api_type,
+            "connection_pooling": self.websocket.is_none(),
+            "visemes": self.websocket.is_some(),
+            "endpoint": endpoint,
             "region": self.request_builder.azure_config.region.as_str(),
             "supported_formats": [
                 "raw-8khz-8bit-mono-mulaw",
@@ -438,7 +494,12 @@ mod tests {
             request_timeout: Some(60),
             pronunciations: Vec::new(),
             request_pool_size: Some(4),
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         }
     }
 
@@ -822,6 +883,24 @@ mod tests {
         assert!(info["supported_sample_rates"].is_array());
     }
 
+    #[test]
+    fn test_azure_tts_visemes_use_websocket() {
+        let config = TTSConfig {
+            visemes: Some(true),
+            ..create_test_config()
+        };
+        let tts = AzureTTS::new(config).unwrap();
+
+        let info = tts.get_provider_info();
+        assert_eq!(info["api_type"], "WebSocket");
+        assert_eq!(info["visemes"], true);
+        assert_eq!(
+            info["endpoint"],
+            "wss://eastus.tts.speech.microsoft.com/cognitiveservices/websocket/v1"
+        );
+        assert!(!tts.is_ready());
+    }
+
     #[test]
     fn test_azure_request_builder_clone() {
         let config = create_test_config();
@@ -0,0 +1,983 @@
+//! Azure TTS WebSocket transport with viseme and word timing.
+//!
+//! The REST API only returns audio. The WebSocket API used by the Speech SDK
+//! also reports viseme and word boundary events, so providers asked for
+//! timing ([`TTSConfig::visemes`](crate::core::tts::TTSConfig::visemes))
+//! synthesize over it instead.
+//!
+//! Every message carries `Name:Value` headers; `Path` names the message and
+//! `X-RequestId` the turn it belongs to. Each `speak` is one turn:
+//!
+//! - The gateway sends `synthesis.context` (output format and the metadata
+//!   to report) and `ssml` text messages
+//! - Azure answers with `turn.start`, `audio.metadata` JSON, binary `audio`
+//!   messages (a 2-byte big-endian header length, the headers, then audio)
+//!   and finally `turn.end`
+//!
+//! Turns are synthesized one at a time, in order. Metadata offsets are in
+//! 100ns ticks from the start of the turn's audio; they are reported in
+//! milliseconds from the start of the utterance, the audio delivered since
+//! the previous `on_complete`. `clear` drops queued turns and whatever is
+//! still in flight for the current one.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use super::config::{AzureAudioEncoding, AzureTTSConfig};
+use crate::core::providers::azure::AZURE_SUBSCRIPTION_KEY_HEADER;
+use crate::core::providers::headers::insert_custom_headers;
+use crate::core::tts::base::{AudioCallback, TTSError, TTSResult, TTSTimingEvent};
+use crate::core::tts::provider::{PronunciationReplacer, TTSProvider};
+
+/// How long `disconnect` waits for the connection task to close the socket
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Metadata offsets and durations are in 100ns ticks
+const TICKS_PER_MS: u64 = 10_000;
+
+// =============================================================================
+// Messages
+// =============================================================================
+
+/// Builds a text message with its headers
+fn text_message(path: &str, request_id: &str, content_type: &str, body: &str) -> String {
+    let timestamp = time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default();
+    format!(
+        "Path: {path}\r\nX-RequestId: {request_id}\r\nX-Timestamp: {timestamp}\r\n\
+         Content-Type: {content_type}\r\n\r\n{body}"
+    )
+}
+
+/// Builds the `synthesis.context` message of a turn
+fn synthesis_context_message(request_id: &str, output_format: AzureAudioEncoding) -> String {
+    let context = serde_json::json!({
+        "synthesis": {
+            "audio": {
+                "metadataOptions": {
+                    "visemeEnabled": true,
+                    "wordBoundaryEnabled": true,
+                    "sentenceBoundaryEnabled": false,
+                    "punctuationBoundaryEnabled": false,
+                    "bookmarkEnabled": false,
+                    "sessionEndEnabled": true
+                },
+                "outputFormat": output_format.as_str()
+            },
+            "language": { "autoDetection": false }
+        }
+    });
+    text_message(
+        "synthesis.context",
+        request_id,
+        "application/json",
+        &context.to_string(),
+    )
+}
+
+/// Headers of a message from Azure
+#[derive(Debug, Default, PartialEq, Eq)]
+struct FrameHeaders {
+    path: String,
+    request_id: String,
+}
+
+fn parse_headers(headers: &str) -> FrameHeaders {
+    let mut parsed = FrameHeaders::default();
+    for line in headers.split("\r\n") {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim().eq_ignore_ascii_case("path") {
+            parsed.path = value.trim().to_string();
+        } else if name.trim().eq_ignore_ascii_case("x-requestid") {
+            parsed.request_id = value.trim().to_string();
+        }
+    }
+    parsed
+}
+
+/// Splits a text message into its headers and body
+fn parse_text_frame(text: &str) -> (FrameHeaders, &str) {
+    match text.split_once("\r\n\r\n") {
+        Some((headers, body)) => (parse_headers(headers), body),
+        None => (parse_headers(text), ""),
+    }
+}
+
+/// Splits a binary message into its headers and audio
+fn parse_binary_frame(data: &[u8]) -> Option<(FrameHeaders, &[u8])> {
+    let header_len = usize::from(u16::from_be_bytes([*data.first()?, *data.get(1)?]));
+    let headers = data.get(2..2 + header_len)?;
+    let headers = std::str::from_utf8(headers).ok()?;
+    Some((parse_headers(headers), &data[2 + header_len..]))
+}
+
+/// Body of an `audio.metadata` message
+#[derive(Debug, Deserialize)]
+struct MetadataMessage {
+    #[serde(rename = "Metadata", default)]
+    metadata: Vec<MetadataEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataEntry {
+    #[serde(rename = "Type")]
+    kind: String,
+    #[serde(rename = "Data", default)]
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VisemeData {
+    offset: u64,
+    viseme_id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct WordBoundaryData {
+    offset: u64,
+    #[serde(default)]
+    duration: u64,
+    #[serde(rename = "text")]
+    text: WordBoundaryText,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct WordBoundaryText {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SessionEndData {
+    offset: u64,
+}
+
+// =============================================================================
+// Turn Tracking
+// =============================================================================
+
+/// Commands from the provider to the connection task
+#[derive(Debug)]
+pub(super) enum TurnCommand {
+    /// Text to synthesize as a turn after everything queued before it
+    Text(String),
+    /// Report completion once every turn queued before it has finished
+    Flush,
+    /// Drop queued turns and the audio of the current one
+    Clear,
+}
+
+/// Work queued behind the current turn
+#[derive(Debug)]
+enum Pending {
+    Text(String),
+    Flush,
+}
+
+/// The turn Azure is synthesizing
+#[derive(Debug)]
+struct ActiveTurn {
+    request_id: String,
+    audio_bytes: u64,
+    /// End of the turn's audio from its `SessionEnd` metadata
+    session_end_ms: Option<u64>,
+    /// End of the latest timing event, relative to the turn
+    timing_end_ms: u64,
+}
+
+/// Outcome of a command or message, for the socket or the audio callback
+#[derive(Debug)]
+enum TurnEvent {
+    /// Text message to send to Azure
+    Send(String),
+    /// Audio of the current turn
+    Audio(Vec<u8>),
+    /// Timing events, relative to the utterance
+    Timing(Vec<TTSTimingEvent>),
+    /// Every turn before a flush has finished
+    Complete,
+}
+
+/// Sends turns one at a time and maps their metadata onto the utterance
+#[derive(Debug)]
+struct TurnTracker {
+    config: AzureTTSConfig,
+    pending: VecDeque<Pending>,
+    active: Option<ActiveTurn>,
+    /// Turns dropped by `clear` whose messages may still arrive
+    cancelled: HashSet<String>,
+    /// Audio of the utterance delivered by finished turns
+    utterance_ms: u64,
+}
+
+impl TurnTracker {
+    fn new(config: AzureTTSConfig) -> Self {
+        Self {
+            config,
+            pending: VecDeque::new(),
+            active: None,
+            cancelled: HashSet::new(),
+            utterance_ms: 0,
+        }
+    }
+
+    fn handle_command(&mut self, command: TurnCommand) -> Vec<TurnEvent> {
+        match command {
+            TurnCommand::Text(text) => {
+                if !text.trim().is_empty() {
+                    self.pending.push_back(Pending::Text(text));
+                }
+            }
+            TurnCommand::Flush => self.pending.push_back(Pending::Flush),
+            TurnCommand::Clear => {
+                if let Some(turn) = self.active.take() {
+                    debug!("Dropping Azure TTS turn {}", turn.request_id);
+                    self.cancelled.insert(turn.request_id);
+                }
+                self.pending.clear();
+                self.utterance_ms = 0;
+            }
+        }
+        self.advance()
+    }
+
+    /// Starts the next turn once the current one has finished
+    fn advance(&mut self) -> Vec<TurnEvent> {
+        let mut events = Vec::new();
+        while self.active.is_none() {
+            match self.pending.pop_front() {
+                Some(Pending::Flush) => {
+                    self.utterance_ms = 0;
+                    events.push(TurnEvent::Complete);
+                }
+                Some(Pending::Text(text)) => {
+                    let request_id = Uuid::new_v4().simple().to_string().to_uppercase();
+                    events.push(TurnEvent::Send(synthesis_context_message(
+                        &request_id,
+                        self.config.output_format,
+                    )));
+                    events.push(TurnEvent::Send(text_message(
+                        "ssml",
+                        &request_id,
+                        "application/ssml+xml",
+                        &self.config.build_ssml_for_text(&text),
+                    )));
+                    debug!("Starting Azure TTS turn {}", request_id);
+                    self.active = Some(ActiveTurn {
+                        request_id,
+                        audio_bytes: 0,
+                        session_end_ms: None,
+                        timing_end_ms: 0,
+                    });
+                }
+                None => break,
+            }
+        }
+        events
+    }
+
+    /// The current turn, if the message belongs to it
+    fn turn_for(&mut self, request_id: &str, path: &str) -> Option<&mut ActiveTurn> {
+        if self.cancelled.contains(request_id) {
+            if path == "turn.end" {
+                self.cancelled.remove(request_id);
+            }
+            return None;
+        }
+        self.active
+            .as_mut()
+            .filter(|turn| turn.request_id == request_id)
+    }
+
+    fn handle_text(&mut self, text: &str) -> Vec<TurnEvent> {
+        let (headers, body) = parse_text_frame(text);
+        let utterance_ms = self.utterance_ms;
+        let Some(turn) = self.turn_for(&headers.request_id, &headers.path) else {
+            return Vec::new();
+        };
+
+        match headers.path.as_str() {
+            "audio.metadata" => {
+                let message: MetadataMessage = match serde_json::from_str(body) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        warn!("Failed to parse Azure TTS metadata: {} - raw: {}", e, body);
+                        return Vec::new();
+                    }
+                };
+                let mut timing = timing_events(message, turn);
+                if timing.is_empty() {
+                    return Vec::new();
+                }
+                for event in &mut timing {
+                    match event {
+                        TTSTimingEvent::Viseme { offset_ms, .. }
+                        | TTSTimingEvent::Word { offset_ms, .. } => *offset_ms += utterance_ms,
+                    }
+                }
+                vec![TurnEvent::Timing(timing)]
+            }
+            "turn.end" => {
+                let duration_ms = self.turn_duration_ms();
+                debug!(
+                    "Azure TTS turn {} finished after {}ms of audio",
+                    headers.request_id, duration_ms
+                );
+                self.utterance_ms += duration_ms;
+                self.active = None;
+                self.advance()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn handle_binary(&mut self, data: &[u8]) -> Vec<TurnEvent> {
+        let Some((headers, audio)) = parse_binary_frame(data) else {
+            warn!("Invalid Azure TTS binary message ({} bytes)", data.len());
+            return Vec::new();
+        };
+        if headers.path != "audio" || audio.is_empty() {
+            return Vec::new();
+        }
+        let Some(turn) = self.turn_for(&headers.request_id, &headers.path) else {
+            return Vec::new();
+        };
+        turn.audio_bytes += audio.len() as u64;
+        vec![TurnEvent::Audio(audio.to_vec())]
+    }
+
+    /// Audio of the current turn, from its byte count for raw formats
+    fn turn_duration_ms(&self) -> u64 {
+        let Some(turn) = &self.active else {
+            return 0;
+        };
+        let audio_ms = match bytes_per_second(self.config.output_format) {
+            Some(rate) => turn.audio_bytes * 1000 / rate,
+            None => turn.session_end_ms.unwrap_or(0),
+        };
+        audio_ms.max(turn.timing_end_ms)
+    }
+}
+
+/// Bytes per second of audio in a raw format (`None` when compressed)
+fn bytes_per_second(format: AzureAudioEncoding) -> Option<u64> {
+    if format.is_pcm() {
+        Some(u64::from(format.sample_rate()) * 2)
+    } else if format.is_telephony() {
+        Some(u64::from(format.sample_rate()))
+    } else {
+        None
+    }
+}
+
+/// Maps metadata onto timing events relative to the turn, in offset order
+fn timing_events(message: MetadataMessage, turn: &mut ActiveTurn) -> Vec<TTSTimingEvent> {
+    let mut events = Vec::new();
+    for entry in message.metadata {
+        match entry.kind.as_str() {
+            "Viseme" => match serde_json::from_value::<VisemeData>(entry.data) {
+                Ok(data) => events.push(TTSTimingEvent::Viseme {
+                    offset_ms: data.offset / TICKS_PER_MS,
+                    viseme_id: data.viseme_id,
+                }),
+                Err(e) => warn!("Invalid Azure TTS viseme event: {}", e),
+            },
+            "WordBoundary" => match serde_json::from_value::<WordBoundaryData>(entry.data) {
+                Ok(data) => events.push(TTSTimingEvent::Word {
+                    offset_ms: data.offset / TICKS_PER_MS,
+                    duration_ms: data.duration / TICKS_PER_MS,
+                    text: data.text.text,
+                }),
+                Err(e) => warn!("Invalid Azure TTS word boundary event: {}", e),
+            },
+            "SessionEnd" => {
+                if let Ok(data) = serde_json::from_value::<SessionEndData>(entry.data) {
+                    turn.session_end_ms = Some(data.offset / TICKS_PER_MS);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    events.sort_by_key(TTSTimingEvent::offset_ms);
+    for event in &events {
+        let end_ms = match event {
+            TTSTimingEvent::Viseme { offset_ms, .. } => *offset_ms,
+            TTSTimingEvent::Word {
+                offset_ms,
+                duration_ms,
+                ..
+            } => offset_ms + duration_ms,
+        };
+        turn.timing_end_ms = turn.timing_end_ms.max(end_ms);
+    }
+    events
+}
+
+// =============================================================================
+// WebSocket Connection
+// =============================================================================
+
+/// WebSocket connection to Azure TTS reporting viseme and word timing
+pub(super) struct AzureWebSocket {
+    config: AzureTTSConfig,
+
+    /// Compiled pronunciation patterns for text replacement
+    pronunciation_replacer: Option<PronunciationReplacer>,
+
+    /// Audio callback shared with the connection task
+    callback: Arc<RwLock<Option<Arc<dyn AudioCallback>>>>,
+
+    /// Commands for the connection task
+    commands: Option<mpsc::UnboundedSender<TurnCommand>>,
+
+    /// Shutdown signal sender
+    shutdown_tx: Option<oneshot::Sender<()>>,
+
+    /// Connection task handle
+    connection_handle: Option<tokio::task::JoinHandle<()>>,
+
+    /// Cleared by the connection task when the socket closes
+    connected: Arc<AtomicBool>,
+}
+
+impl AzureWebSocket {
+    pub(super) fn new(
+        config: AzureTTSConfig,
+        pronunciation_replacer: Option<PronunciationReplacer>,
+    ) -> Self {
+        Self {
+            config,
+            pronunciation_replacer,
+            callback: Arc::new(RwLock::new(None)),
+            commands: None,
+            shutdown_tx: None,
+            connection_handle: None,
+            connected: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub(super) fn is_ready(&self) -> bool {
+        self.commands.is_some() && self.connected.load(Ordering::Acquire)
+    }
+
+    pub(super) fn set_callback(&self, callback: Option<Arc<dyn AudioCallback>>) {
+        *self.callback.write() = callback;
+    }
+
+    /// Opens the WebSocket and starts the connection task.
+    pub(super) async fn connect(&mut self) -> TTSResult<()> {
+        if self.is_ready() {
+            return Ok(());
+        }
+        self.disconnect().await?;
+
+        let mut request = self
+            .config
+            .region
+            .tts_websocket_url()
+            .into_client_request()
+            .map_err(|e| TTSError::ConnectionFailed(format!("Invalid WebSocket URL: {e}")))?;
+        let headers = request.headers_mut();
+        insert_custom_headers(headers, &self.config.base.custom_headers);
+        headers.insert(
+            AZURE_SUBSCRIPTION_KEY_HEADER,
+            HeaderValue::from_str(&self.config.base.api_key).map_err(|_| {
+                TTSError::InvalidConfiguration("Invalid Azure subscription key".to_string())
+            })?,
+        );
+        if let Ok(connection_id) = HeaderValue::from_str(&Uuid::new_v4().simple().to_string()) {
+            headers.insert("X-ConnectionId", connection_id);
+        }
+
+        let (ws_stream, _) = connect_async(request).await.map_err(connect_error)?;
+        info!("Connected to Azure TTS WebSocket");
+
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel::<TurnCommand>();
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+        self.connected.store(true, Ordering::Release);
+
+        let mut tracker = TurnTracker::new(self.config.clone());
+        let callback = self.callback.clone();
+        let connected = self.connected.clone();
+        let format = self
+            .config
+            .base
+            .audio_format
+            .clone()
+            .unwrap_or_else(|| "linear16".to_string());
+        let sample_rate = self.config.output_format.sample_rate();
+
+        let connection_handle = tokio::spawn(async move {
+            let (mut ws_sink, mut ws_stream) = ws_stream.split();
+
+            loop {
+                let events = tokio::select! {
+                    Some(command) = command_rx.recv() => tracker.handle_command(command),
+
+                    message = ws_stream.next() => match message {
+                        Some(Ok(Message::Text(text))) => tracker.handle_text(&text),
+                        Some(Ok(Message::Binary(data))) => tracker.handle_binary(&data),
+                        Some(Ok(Message::Close(frame))) => {
+                            info!("Azure TTS WebSocket closed: {:?}", frame);
+                            if let Some(frame) = frame
+                                && !frame.reason.is_empty()
+                            {
+                                let err = TTSError::ProviderError(frame.reason.to_string());
+                                Self::report_error(&callback, err).await;
+                            }
+                            break;
+                        }
+                        Some(Ok(_)) => Vec::new(),
+                        Some(Err(e)) => {
+                            let err = TTSError::NetworkError(format!("WebSocket error: {e}"));
+                            error!("{}", err);
+                            Self::report_error(&callback, err).await;
+                            break;
+                        }
+                        None => {
+                            info!("Azure TTS WebSocket stream ended");
+                            break;
+                        }
+                    },
+
+                    _ = &mut shutdown_rx => {
+                        let _ = ws_sink.send(Message::Close(None)).await;
+                        break;
+                    }
+                };
+
+                let registered = callback.read().clone();
+                let mut send_failed = false;
+                for event in events {
+                    match event {
+                        TurnEvent::Send(text) => {
+                            if let Err(e) = ws_sink.send(Message::Text(text.into())).await {
+                                let err = TTSError::NetworkError(format!(
+                                    "Failed to send WebSocket message: {e}"
+                                ));
+                                error!("{}", err);
+                                Self::report_error(&callback, err).await;
+                                send_failed = true;
+                                break;
+                            }
+                        }
+                        TurnEvent::Audio(audio) => {
+                            if let Some(callback) = &registered {
+                                let audio_data =
+                                    TTSProvider::process_audio_chunk(audio, &format, sample_rate);
+                                callback.on_audio(audio_data).await;
+                            }
+                        }
+                        TurnEvent::Timing(timing) => {
+                            if let Some(callback) = &registered {
+                                callback.on_timing(timing).await;
+                            }
+                        }
+                        TurnEvent::Complete => {
+                            if let Some(callback) = &registered {
+                                callback.on_complete().await;
+                            }
+                        }
+                    }
+                }
+                if send_failed {
+                    break;
+                }
+            }
+
+            connected.store(false, Ordering::Release);
+            debug!("Azure TTS WebSocket task exited");
+        });
+
+        self.commands = Some(command_tx);
+        self.shutdown_tx = Some(shutdown_tx);
+        self.connection_handle = Some(connection_handle);
+        Ok(())
+    }
+
+    /// Closes the WebSocket and stops the connection task.
+    pub(super) async fn disconnect(&mut self) -> TTSResult<()> {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(mut handle) = self.connection_handle.take()
+            && tokio::time::timeout(DISCONNECT_TIMEOUT, &mut handle)
+                .await
+                .is_err()
+        {
+            handle.abort();
+        }
+        self.commands = None;
+        self.connected.store(false, Ordering::Release);
+        Ok(())
+    }
+
+    /// Queues text as a turn, completing the utterance with `flush`.
+    pub(super) fn speak(&self, text: &str, flush: bool) -> TTSResult<()> {
+        let text = match &self.pronunciation_replacer {
+            Some(replacer) => replacer.apply(text),
+            None => text.to_string(),
+        };
+        self.send(TurnCommand::Text(text))?;
+        if flush {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Completes the utterance once its queued turns have finished.
+    pub(super) fn flush(&self) -> TTSResult<()> {
+        self.send(TurnCommand::Flush)
+    }
+
+    /// Drops queued turns and the audio still in flight.
+    pub(super) fn clear(&self) -> TTSResult<()> {
+        self.send(TurnCommand::Clear)
+    }
+
+    fn send(&self, command: TurnCommand) -> TTSResult<()> {
+        let commands = self
+            .commands
+            .as_ref()
+            .ok_or_else(|| TTSError::ProviderNotReady("Azure TTS not connected".to_string()))?;
+        commands.send(command).map_err(|_| {
+            TTSError::NetworkError("Azure TTS WebSocket connection closed".to_string())
+        })
+    }
+
+    async fn report_error(callback: &RwLock<Option<Arc<dyn AudioCallback>>>, err: TTSError) {
+        let callback = callback.read().clone();
+        if let Some(callback) = callback {
+            callback.on_error(err).await;
+        }
+    }
+}
+
+impl Drop for AzureWebSocket {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Maps a failed handshake onto the error the session reports
+fn connect_error(e: tokio_tungstenite::tungstenite::Error) -> TTSError {
+    use tokio_tungstenite::tungstenite::Error;
+
+    match e {
+        Error::Http(response) => {
+            let status = response.status();
+            let message = format!("Azure TTS WebSocket handshake failed with {status}");
+            match status.as_u16() {
+                401 | 403 => TTSError::AuthenticationFailed(message),
+                429 => TTSError::RateLimited {
+                    retry_after_secs: None,
+                    message,
+                },
+                _ => TTSError::ConnectionFailed(message),
+            }
+        }
+        e => TTSError::ConnectionFailed(format!("Failed to connect to Azure TTS: {e}")),
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tts::base::TTSConfig;
+
+    /// Metadata frames recorded from a `Hello world.` turn with
+    /// en-US-JennyNeural at raw-24khz-16bit-mono-pcm; `{id}` is the turn's
+    /// request ID
+    const RECORDED_METADATA: [&str; 4] = [
+        r#"{"Metadata":[{"Type":"WordBoundary","Data":{"Offset":500000,"Duration":3250000,"text":{"Text":"Hello","Length":5,"BoundaryType":"WordBoundary"}}}]}"#,
+        r#"{"Metadata":[{"Type":"Viseme","Data":{"Offset":500000,"VisemeId":0}},{"Type":"Viseme","Data":{"Offset":1000000,"VisemeId":12}},{"Type":"Viseme","Data":{"Offset":1875000,"VisemeId":4}},{"Type":"Viseme","Data":{"Offset":2750000,"VisemeId":14}},{"Type":"Viseme","Data":{"Offset":3375000,"VisemeId":8}}]}"#,
+        r#"{"Metadata":[{"Type":"WordBoundary","Data":{"Offset":4000000,"Duration":4125000,"text":{"Text":"world","Length":5,"BoundaryType":"WordBoundary"}}},{"Type":"Viseme","Data":{"Offset":4000000,"VisemeId":7}},{"Type":"Viseme","Data":{"Offset":5250000,"VisemeId":5}},{"Type":"Viseme","Data":{"Offset":7125000,"VisemeId":14}},{"Type":"Viseme","Data":{"Offset":8125000,"VisemeId":0}}]}"#,
+        r#"{"Metadata":[{"Type":"SessionEnd","Data":{"Offset":10000000}}]}"#,
+    ];
+
+    /// Audio of the recorded turn: two 500ms chunks
+    const AUDIO_CHUNK_BYTES: usize = 24_000;
+
+    fn tracker() -> TurnTracker {
+        let base = TTSConfig {
+            provider: "azure".to_string(),
+            voice_id: Some("en-US-JennyNeural".to_string()),
+            audio_format: Some("linear16".to_string()),
+            sample_rate: Some(24000),
+            visemes: Some(true),
+            ..Default::default()
+        };
+        TurnTracker::new(AzureTTSConfig::from_base(base))
+    }
+
+    fn text_frame(request_id: &str, path: &str, body: &str) -> String {
+        format!(
+            "X-RequestId:{request_id}\r\nContent-Type:application/json; charset=utf-8\r\n\
+             Path:{path}\r\n\r\n{body}"
+        )
+    }
+
+    fn audio_frame(request_id: &str, audio: &[u8]) -> Vec<u8> {
+        let headers =
+            format!("X-RequestId:{request_id}\r\nContent-Type:audio/x-wav\r\nPath:audio\r\n");
+        let mut frame = (headers.len() as u16).to_be_bytes().to_vec();
+        frame.extend_from_slice(headers.as_bytes());
+        frame.extend_from_slice(audio);
+        frame
+    }
+
+    /// Request ID of the turn started by `events`
+    fn started_turn(events: &[TurnEvent]) -> String {
+        let sent: Vec<&String> = events
+            .iter()
+            .filter_map(|event| match event {
+                TurnEvent::Send(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sent.len(), 2, "a turn sends its context and SSML");
+        let (context, _) = parse_text_frame(sent[0]);
+        let (ssml, _) = parse_text_frame(sent[1]);
+        assert_eq!(context.path, "synthesis.context");
+        assert_eq!(ssml.path, "ssml");
+        assert_eq!(context.request_id, ssml.request_id);
+        context.request_id
+    }
+
+    /// Plays the recorded turn, returning its timing events
+    fn play_recorded_turn(tracker: &mut TurnTracker, request_id: &str) -> Vec<TurnEvent> {
+        let mut events = tracker.handle_text(&text_frame(request_id, "turn.start", "{}"));
+        events.extend(tracker.handle_text(&text_frame(
+            request_id,
+            "audio.metadata",
+            RECORDED_METADATA[0],
+        )));
+        events.extend(tracker.handle_text(&text_frame(
+            request_id,
+            "audio.metadata",
+            RECORDED_METADATA[1],
+        )));
+        events.extend(tracker.handle_binary(&audio_frame(request_id, &[0; AUDIO_CHUNK_BYTES])));
+        events.extend(tracker.handle_text(&text_frame(
+            request_id,
+            "audio.metadata",
+            RECORDED_METADATA[2],
+        )));
+        events.extend(tracker.handle_binary(&audio_frame(request_id, &[0; AUDIO_CHUNK_BYTES])));
+        events.extend(tracker.handle_text(&text_frame(
+            request_id,
+            "audio.metadata",
+            RECORDED_METADATA[3],
+        )));
+        // The last audio message of a turn is empty
+        events.extend(tracker.handle_binary(&audio_frame(request_id, &[])));
+        events.extend(tracker.handle_text(&text_frame(request_id, "turn.end", "{}")));
+        events
+    }
+
+    fn timing(events: &[TurnEvent]) -> Vec<TTSTimingEvent> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                TurnEvent::Timing(timing) => Some(timing.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    fn audio_bytes(events: &[TurnEvent]) -> usize {
+        events
+            .iter()
+            .map(|event| match event {
+                TurnEvent::Audio(audio) => audio.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn completions(events: &[TurnEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, TurnEvent::Complete))
+            .count()
+    }
+
+    #[test]
+    fn test_synthesis_context_requests_timing() {
+        let message = synthesis_context_message("ABC", AzureAudioEncoding::Raw24Khz16BitMonoPcm);
+        let (headers, body) = parse_text_frame(&message);
+        assert_eq!(
+            headers,
+            FrameHeaders {
+                path: "synthesis.context".to_string(),
+                request_id: "ABC".to_string(),
+            }
+        );
+        let context: serde_json::Value = serde_json::from_str(body).unwrap();
+        let audio = &context["synthesis"]["audio"];
+        assert_eq!(audio["metadataOptions"]["visemeEnabled"], true);
+        assert_eq!(audio["metadataOptions"]["wordBoundaryEnabled"], true);
+        assert_eq!(audio["outputFormat"], "raw-24khz-16bit-mono-pcm");
+    }
+
+    #[test]
+    fn test_parse_binary_frame() {
+        let frame = audio_frame("ABC", &[1, 2, 3, 4]);
+        let (headers, audio) = parse_binary_frame(&frame).unwrap();
+        assert_eq!(headers.path, "audio");
+        assert_eq!(headers.request_id, "ABC");
+        assert_eq!(audio, &[1, 2, 3, 4]);
+
+        assert!(parse_binary_frame(&[0]).is_none());
+        assert!(parse_binary_frame(&[0, 200, b'P']).is_none());
+    }
+
+    #[test]
+    fn test_recorded_turn_timing_is_monotonic_and_bounded() {
+        let mut tracker = tracker();
+        let events = tracker.handle_command(TurnCommand::Text("Hello world.".to_string()));
+        let request_id = started_turn(&events);
+        let sent = match &events[1] {
+            TurnEvent::Send(text) => text,
+            _ => unreachable!(),
+        };
+        assert!(sent.contains("en-US-JennyNeural") && sent.contains("Hello world."));
+
+        let mut events = play_recorded_turn(&mut tracker, &request_id);
+        events.extend(tracker.handle_command(TurnCommand::Flush));
+
+        let timing = timing(&events);
+        let visemes = timing
+            .iter()
+            .filter(|event| matches!(event, TTSTimingEvent::Viseme { .. }))
+            .count();
+        assert_eq!(visemes, 9);
+        assert_eq!(
+            timing[0],
+            TTSTimingEvent::Word {
+                offset_ms: 50,
+                duration_ms: 325,
+                text: "Hello".to_string(),
+            }
+        );
+        assert!(
+            timing
+                .windows(2)
+                .all(|pair| pair[0].offset_ms() <= pair[1].offset_ms()),
+            "offsets must not go back: {timing:?}"
+        );
+        let visemes: Vec<u64> = timing
+            .iter()
+            .filter(|event| matches!(event, TTSTimingEvent::Viseme { .. }))
+            .map(TTSTimingEvent::offset_ms)
+            .collect();
+        assert!(visemes.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // 48000 bytes of 24kHz 16-bit audio is one second
+        let duration_ms = (audio_bytes(&events) * 1000 / 48_000) as u64;
+        assert_eq!(duration_ms, 1000);
+        for event in &timing {
+            let end_ms = match event {
+                TTSTimingEvent::Word {
+                    offset_ms,
+                    duration_ms,
+                    ..
+                } => offset_ms + duration_ms,
+                event => event.offset_ms(),
+            };
+            assert!(end_ms <= duration_ms, "{event:?} ends after the audio");
+        }
+        assert_eq!(completions(&events), 1);
+    }
+
+    #[test]
+    fn test_offsets_are_relative_to_the_utterance() {
+        let mut tracker = tracker();
+        let first = started_turn(&tracker.handle_command(TurnCommand::Text("Hello".to_string())));
+        // The second turn waits for the first
+        assert!(
+            tracker
+                .handle_command(TurnCommand::Text("world".to_string()))
+                .is_empty()
+        );
+        assert!(tracker.handle_command(TurnCommand::Flush).is_empty());
+
+        let events = play_recorded_turn(&mut tracker, &first);
+        let first_timing = timing(&events);
+        let second = started_turn(&events);
+        assert_ne!(first, second);
+
+        let events = play_recorded_turn(&mut tracker, &second);
+        let second_timing = timing(&events);
+        assert_eq!(completions(&events), 1);
+        // The second turn starts after the first turn's second of audio
+        assert_eq!(second_timing[0].offset_ms(), 1050);
+        assert!(first_timing.last().unwrap().offset_ms() < second_timing[0].offset_ms());
+
+        // A new utterance starts from zero again
+        let third = started_turn(&tracker.handle_command(TurnCommand::Text("Hi".to_string())));
+        let events = play_recorded_turn(&mut tracker, &third);
+        assert_eq!(timing(&events)[0].offset_ms(), 50);
+    }
+
+    #[test]
+    fn test_clear_drops_the_current_turn() {
+        let mut tracker = tracker();
+        let cleared =
+            started_turn(&tracker.handle_command(TurnCommand::Text("Interrupted".to_string())));
+        tracker.handle_command(TurnCommand::Text("Queued".to_string()));
+        assert!(tracker.handle_command(TurnCommand::Clear).is_empty());
+
+        let next = started_turn(&tracker.handle_command(TurnCommand::Text("Next".to_string())));
+        let late = play_recorded_turn(&mut tracker, &cleared);
+        assert!(late.is_empty(), "messages of a cleared turn are dropped");
+
+        let events = play_recorded_turn(&mut tracker, &next);
+        assert_eq!(audio_bytes(&events), 2 * AUDIO_CHUNK_BYTES);
+        assert_eq!(timing(&events)[0].offset_ms(), 50);
+        assert!(tracker.cancelled.is_empty());
+    }
+
+    #[test]
+    fn test_compressed_turns_end_at_session_end() {
+        let base = TTSConfig {
+            audio_format: Some("mp3".to_string()),
+            ..Default::default()
+        };
+        let mut tracker = TurnTracker::new(AzureTTSConfig::from_base(base));
+        let first = started_turn(&tracker.handle_command(TurnCommand::Text("One".to_string())));
+        tracker.handle_command(TurnCommand::Text("Two".to_string()));
+        let second = started_turn(&play_recorded_turn(&mut tracker, &first));
+        let events = play_recorded_turn(&mut tracker, &second);
+        assert_eq!(timing(&events)[0].offset_ms(), 1050);
+    }
+}
@@ -78,10 +78,16 @@ use async_trait::async_trait;
 use futures::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
+use super::telephony::TTSOutputProfile;
 use crate::core::emotion::EmotionConfig;
+use crate::errors::provider_error::error_codes;
 use crate::utils::req_manager::ReqManager;
 
+/// Default per-utterance synthesis timeout in seconds (time without audio progress)
+pub const DEFAULT_UTTERANCE_TIMEOUT_SECS: u64 = 10;
+
 /// Audio data structure for TTS output
 #[derive(Debug, Clone)]
 pub struct AudioData {
@@ -95,6 +101,33 @@ pub struct AudioData {
     pub duration_ms: Option<u32>,
 }
 
+/// Timing of synthesized speech, for avatar lip-sync and word highlighting
+///
+/// Offsets are in milliseconds from the start of the utterance's audio: the
+/// audio delivered since the previous [`AudioCallback::on_complete`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum TTSTimingEvent {
+    /// The mouth position from this offset on (Azure viseme IDs, 0-21)
+    Viseme { offset_ms: u64, viseme_id: u32 },
+    /// A word spoken from this offset on
+    Word {
+        offset_ms: u64,
+        duration_ms: u64,
+        text: String,
+    },
+}
+
+impl TTSTimingEvent {
+    /// Offset of the event from the start of the utterance's audio
+    pub fn offset_ms(&self) -> u64 {
+        match self {
+            Self::Viseme { offset_ms, .. } | Self::Word { offset_ms, .. } => *offset_ms,
+        }
+    }
+}
+
 /// TTS-specific error types
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum TTSError {
@@ -132,6 +165,34 @@ pub enum TTSError {
 
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
+
+    #[error("Connection to {provider} timed out after {timeout:?}")]
+    ConnectionTimeout { provider: String, timeout: Duration },
+
+    /// The configured voice does not exist (e.g. it was deleted from the account)
+    #[error("Voice not found: {0}")]
+    VoiceNotFound(String),
+}
+
+impl TTSError {
+    /// Get the error code sent to clients with the sanitized message
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            TTSError::ConnectionFailed(_)
+            | TTSError::NetworkError(_)
+            | TTSError::ProviderNotReady(_) => error_codes::TTS_CONNECTION_FAILED,
+            TTSError::ConnectionTimeout { .. } => error_codes::TTS_CONNECTION_TIMEOUT,
+            TTSError::AuthenticationFailed(_) => error_codes::TTS_AUTHENTICATION_FAILED,
+            TTSError::InvalidConfiguration(_) => error_codes::TTS_INVALID_CONFIG,
+            TTSError::RateLimited { .. } => error_codes::TTS_RATE_LIMITED,
+            TTSError::TimeoutError(_) => error_codes::TTS_TIMEOUT,
+            TTSError::VoiceNotFound(_) => error_codes::TTS_VOICE_NOT_FOUND,
+            TTSError::AudioGenerationFailed(_) => error_codes::TTS_SYNTHESIS_FAILED,
+            TTSError::ProviderError(_) | TTSError::InternalError(_) => {
+                error_codes::TTS_PROVIDER_ERROR
+            }
+        }
+    }
 }
 
 /// Result type for TTS operations
@@ -152,6 +213,25 @@ pub enum ConnectionState {
     Error(String),
 }
 
+/// Statistics for TTS utterances
+#[derive(Debug, Default, Clone)]
+pub struct TTSStats {
+    /// Number of utterances fully synthesized
+    pub utterances_completed: u64,
+    /// Number of utterances that ended early (provider error or timeout)
+    pub utterances_incomplete: u64,
+    /// Number of utterances abandoned by the per-utterance timeout
+    pub utterance_timeouts: u64,
+    /// Total audio bytes delivered to the audio callback
+    pub total_audio_bytes: u64,
+    /// Retries of chunks that failed transiently before streaming audio
+    pub chunk_retries: u64,
+    /// Chunks that still failed after their retries, abandoning their utterance
+    pub chunks_failed: u64,
+    /// Chunks skipped because an earlier chunk of their utterance failed
+    pub chunks_abandoned: u64,
+}
+
 /// Audio callback trait for handling audio data from TTS providers
 pub trait AudioCallback: Send + Sync {
     /// Called when audio data is received from the TTS provider
@@ -162,6 +242,18 @@ pub trait AudioCallback: Send + Sync {
 
     /// Called when the TTS provider finishes processing all queued text
     fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+
+    /// Called with timing events of the audio being synthesized
+    ///
+    /// Only providers asked for them with [`TTSConfig::visemes`] report
+    /// events, in offset order. Ignored unless overridden.
+    fn on_timing(
+        &self,
+        events: Vec<TTSTimingEvent>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = events;
+        Box::pin(async {})
+    }
 }
 
 /// Pronunciation replacement configuration
@@ -200,6 +292,12 @@ pub struct TTSConfig {
     pub pronunciations: Vec<Pronunciation>,
     /// Request pool size for concurrent HTTP requests
     pub request_pool_size: Option<usize>,
+    /// Per-utterance synthesis timeout in seconds
+    ///
+    /// An utterance is abandoned when no audio arrives from the provider for this
+    /// long. Audio already received is still delivered. `None` disables the timeout.
+    #[serde(default)]
+    pub utterance_timeout: Option<u64>,
     /// Emotion configuration for TTS providers that support emotional expression
     ///
     /// When set, providers that support emotions (Hume, ElevenLabs, Azure) will
@@ -207,6 +305,47 @@ pub struct TTSConfig {
     /// don't support emotions will log a warning and proceed with default synthesis.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub emotion_config: Option<EmotionConfig>,
+    /// Extra HTTP headers sent with every request to the provider
+    ///
+    /// Useful for routing through proxies or API gateways that require their
+    /// own headers. Validated by `validate_custom_headers` before use.
+    #[serde(default)]
+    pub custom_headers: HashMap<String, String>,
+    /// Output profile for synthesized audio
+    ///
+    /// `Telephony` delivers 8kHz μ-law in 20ms frames regardless of what the
+    /// provider produces. `None` is equivalent to `Native`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_profile: Option<TTSOutputProfile>,
+    /// Whether to send the text around each chunk of an utterance as context
+    ///
+    /// Providers that support it (ElevenLabs `previous_text`/`next_text`) use
+    /// it to keep prosody continuous across chunks. `None` is equivalent to `true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_hints: Option<bool>,
+    /// Whether to report viseme and word timing through [`AudioCallback::on_timing`]
+    ///
+    /// Supported by Azure, which synthesizes over its WebSocket API to get
+    /// them, and by plugins that report timing. `None` is equivalent to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visemes: Option<bool>,
+}
+
+impl TTSConfig {
+    /// Whether audio should be delivered as 8kHz μ-law telephony frames
+    pub fn is_telephony(&self) -> bool {
+        self.output_profile == Some(TTSOutputProfile::Telephony)
+    }
+
+    /// Whether surrounding text should be sent with each request
+    pub fn context_hints_enabled(&self) -> bool {
+        self.context_hints != Some(false)
+    }
+
+    /// Whether viseme and word timing should be reported
+    pub fn visemes_enabled(&self) -> bool {
+        self.visemes == Some(true)
+    }
 }
 
 impl Default for TTSConfig {
@@ -223,7 +362,12 @@ impl Default for TTSConfig {
             request_timeout: Some(60),
             pronunciations: Vec::new(),
             request_pool_size: Some(4),
+            utterance_timeout: Some(DEFAULT_UTTERANCE_TIMEOUT_SECS),
             emotion_config: None,
+            custom_headers: HashMap::new(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         }
     }
 }
@@ -266,6 +410,26 @@ pub trait BaseTTS: Send + Sync {
         ))
     }
 
+    /// Connect to the TTS provider, giving up after `timeout`
+    ///
+    /// The pending `connect()` is cancelled when the timeout elapses.
+    ///
+    /// # Arguments
+    /// * `timeout` - Upper bound on the whole connection attempt, including provider retries
+    ///
+    /// # Returns
+    /// * `TTSResult<()>` - Success, the provider's error, or `TTSError::ConnectionTimeout`
+    async fn connect_with_timeout(&mut self, timeout: Duration) -> TTSResult<()> {
+        match tokio::time::timeout(timeout, self.connect()).await {
+            Ok(result) => result,
+            Err(_) => {
+                let info = self.get_provider_info();
+                let provider = info["provider"].as_str().unwrap_or("unknown").to_string();
+                Err(TTSError::ConnectionTimeout { provider, timeout })
+            }
+        }
+    }
+
     /// Disconnect from the TTS provider
     ///
     /// This method cleanly closes the connection to the TTS provider and releases any resources.
@@ -394,6 +558,18 @@ pub trait BaseTTS: Send + Sync {
         })
     }
 
+    /// Check that the configured voice exists before the first synthesis
+    ///
+    /// Providers that can look up a voice cheaply override this to return
+    /// `TTSError::VoiceNotFound` for unknown voices. The default implementation
+    /// performs no check.
+    ///
+    /// # Returns
+    /// * `TTSResult<()>` - Success, `TTSError::VoiceNotFound`, or a lookup failure
+    async fn validate_voice(&self) -> TTSResult<()> {
+        Ok(())
+    }
+
     /// Set the request manager for pooled HTTP clients.
     ///
     /// This method allows providers to use a shared connection pool for HTTP requests,
@@ -563,4 +739,33 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), TTSError::ProviderNotReady(_)));
     }
+
+    #[tokio::test]
+    async fn test_connect_with_timeout() {
+        let config = TTSConfig {
+            audio_format: Some("pcm".to_string()),
+            ..Default::default()
+        };
+
+        // The mock takes 100ms to connect
+        let mut tts = MockTTS::new(config.clone()).unwrap();
+        let timeout = Duration::from_millis(10);
+        let err = tts.connect_with_timeout(timeout).await.unwrap_err();
+        match err {
+            TTSError::ConnectionTimeout {
+                provider,
+                timeout: elapsed,
+            } => {
+                assert_eq!(provider, "mock");
+                assert_eq!(elapsed, timeout);
+            }
+            other => panic!("expected ConnectionTimeout, got {other:?}"),
+        }
+
+        let mut tts = MockTTS::new(config).unwrap();
+        tts.connect_with_timeout(Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(tts.is_ready());
+    }
 }
@@ -32,6 +32,7 @@
 
 use crate::core::tts::base::{TTSConfig, TTSError};
 use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
 
 // =============================================================================
 // Constants
@@ -40,6 +41,12 @@ use serde::{Deserialize, Serialize};
 /// Cartesia TTS REST API endpoint for byte streaming.
 pub const CARTESIA_TTS_URL: &str = "https://api.cartesia.ai/tts/bytes";
 
+/// Cartesia TTS WebSocket API endpoint for streaming with continuation contexts.
+pub const CARTESIA_TTS_WS_URL: &str = "wss://api.cartesia.ai/tts/websocket";
+
+/// Cartesia REST API base URL, used for credential checks
+pub const CARTESIA_API_BASE_URL: &str = "https://api.cartesia.ai";
+
 /// Default API version for Cartesia TTS requests.
 /// Format: YYYY-MM-DD
 pub const DEFAULT_API_VERSION: &str = "2025-04-16";
@@ -754,6 +761,31 @@ impl CartesiaTTSConfig {
         serde_json::to_value(&self.output_format)
             .expect("CartesiaOutputFormat serialization should never fail")
     }
+
+    /// Returns true if synthesis streams over the WebSocket API.
+    ///
+    /// The WebSocket API only produces raw audio, so WAV and MP3 output
+    /// use the `/tts/bytes` REST endpoint instead.
+    #[inline]
+    pub fn uses_websocket(&self) -> bool {
+        self.output_format.container == CartesiaAudioContainer::Raw
+    }
+
+    /// Builds the WebSocket URL with authentication and API version.
+    ///
+    /// Browser-style WebSocket clients cannot set headers, so Cartesia
+    /// accepts both as query parameters.
+    pub fn build_websocket_url(&self) -> String {
+        let encode =
+            |s: &str| -> String { form_urlencoded::byte_serialize(s.as_bytes()).collect() };
+
+        format!(
+            "{}?api_key={}&cartesia_version={}",
+            CARTESIA_TTS_WS_URL,
+            encode(&self.base.api_key),
+            encode(&self.api_version)
+        )
+    }
 }
 
 impl Default for CartesiaTTSConfig {
@@ -1326,6 +1358,37 @@ mod tests {
         assert_eq!(json["bit_rate"], 128000);
     }
 
+    #[test]
+    fn test_config_uses_websocket_for_raw_output() {
+        for (format, expected) in [
+            ("linear16", true),
+            ("mulaw", true),
+            ("wav", false),
+            ("mp3", false),
+        ] {
+            let base = TTSConfig {
+                audio_format: Some(format.to_string()),
+                ..Default::default()
+            };
+            let config = CartesiaTTSConfig::from_base(base);
+            assert_eq!(config.uses_websocket(), expected, "{format}");
+        }
+    }
+
+    #[test]
+    fn test_config_build_websocket_url() {
+        let base = TTSConfig {
+            api_key: "key with/slash".to_string(),
+            ..Default::default()
+        };
+        let config = CartesiaTTSConfig::from_base(base);
+
+        assert_eq!(
+            config.build_websocket_url(),
+            "wss://api.cartesia.ai/tts/websocket?api_key=key+with%2Fslash&cartesia_version=2025-04-16"
+        );
+    }
+
     // =========================================================================
     // Constants Tests
     // =========================================================================
@@ -1333,6 +1396,7 @@ mod tests {
     #[test]
     fn test_constants() {
         assert_eq!(CARTESIA_TTS_URL, "https://api.cartesia.ai/tts/bytes");
+        assert_eq!(CARTESIA_TTS_WS_URL, "wss://api.cartesia.ai/tts/websocket");
         assert_eq!(DEFAULT_API_VERSION, "2025-04-16");
         assert_eq!(DEFAULT_MODEL, "sonic-3");
         assert_eq!(
@@ -0,0 +1,222 @@
+//! WebSocket message types for the Cartesia TTS WebSocket API.
+//!
+//! Every message carries a `context_id`. A context is one utterance whose
+//! transcript may arrive in several chunks; Cartesia synthesizes the chunks
+//! of a context with continuous prosody.
+//!
+//! - **Outgoing messages**: Messages sent from client to server
+//!   - [`CartesiaGenerationRequest`]: One transcript chunk for a context
+//!   - [`CartesiaCancelRequest`]: Stop generating audio for a context
+//!
+//! - **Incoming messages**: [`CartesiaTTSMessage`]
+//!   - `chunk`: Base64-encoded audio for a context
+//!   - `timestamps`: Word timestamps (not requested, ignored)
+//!   - `done`: All audio for a finalized context has been sent
+//!   - `flush_done`: Acknowledgment of a manual flush (ignored)
+//!   - `error`: The context failed
+//!
+//! # Context Lifecycle
+//!
+//! ```text
+//! {"context_id": "c1", "transcript": "Hello there. ", "continue": true, ...}
+//! {"context_id": "c1", "transcript": "How are you?", "continue": true, ...}
+//! {"context_id": "c1", "transcript": "", "continue": false, ...}
+//!     <- {"type": "chunk", "context_id": "c1", "data": "..."}  (repeated)
+//!     <- {"type": "done", "context_id": "c1"}
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::config::CartesiaOutputFormat;
+
+// =============================================================================
+// Outgoing Messages (Client to Server)
+// =============================================================================
+
+/// Generation request carrying one transcript chunk of a context.
+///
+/// The first request for a `context_id` creates the context. Sending
+/// `continue: false` finalizes it; Cartesia then answers with `done` once
+/// the remaining audio has been sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct CartesiaGenerationRequest<'a> {
+    /// Sonic model identifier
+    pub model_id: &'a str,
+    /// Text to synthesize; empty when only finalizing the context
+    pub transcript: &'a str,
+    /// Voice specifier, e.g. `{"mode": "id", "id": "<uuid>"}`
+    pub voice: serde_json::Value,
+    /// Output format; the WebSocket API only supports the raw container
+    pub output_format: &'a CartesiaOutputFormat,
+    /// Language code
+    pub language: &'a str,
+    /// Context the chunk belongs to
+    pub context_id: &'a str,
+    /// Whether more chunks follow for this context
+    #[serde(rename = "continue")]
+    pub continue_: bool,
+}
+
+/// Request to stop generating audio for a context.
+///
+/// Audio already sent by the server for the context may still arrive.
+#[derive(Debug, Clone, Serialize)]
+pub struct CartesiaCancelRequest<'a> {
+    /// Context to cancel
+    pub context_id: &'a str,
+    /// Always `true`
+    pub cancel: bool,
+}
+
+impl<'a> CartesiaCancelRequest<'a> {
+    /// Creates a cancel request for `context_id`.
+    pub fn new(context_id: &'a str) -> Self {
+        Self {
+            context_id,
+            cancel: true,
+        }
+    }
+}
+
+// =============================================================================
+// Incoming Messages (Server to Client)
+// =============================================================================
+
+/// Message received from the Cartesia TTS WebSocket API.
+///
+/// # Example
+///
+/// ```rust
+/// use waav_gateway::core::tts::cartesia::CartesiaTTSMessage;
+///
+/// let frame = r#"{"type":"done","context_id":"c1","done":true}"#;
+/// let msg = CartesiaTTSMessage::parse(frame).unwrap();
+/// assert_eq!(msg.context_id(), Some("c1"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CartesiaTTSMessage {
+    /// Audio chunk for a context
+    Chunk {
+        context_id: Option<String>,
+        /// Base64-encoded audio in the requested output format
+        data: String,
+    },
+    /// Word timestamps for a context
+    Timestamps { context_id: Option<String> },
+    /// All audio for the context has been sent
+    Done { context_id: Option<String> },
+    /// Manual flush acknowledged
+    FlushDone { context_id: Option<String> },
+    /// Generation failed for the context (or the connection, without one)
+    Error {
+        context_id: Option<String>,
+        /// Human-readable error description
+        error: Option<String>,
+        /// HTTP-style status code
+        status_code: Option<u16>,
+    },
+    /// Message type not handled by this client
+    #[serde(other)]
+    Unknown,
+}
+
+impl CartesiaTTSMessage {
+    /// Parses a text WebSocket frame.
+    pub fn parse(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    /// Returns the context the message belongs to, if any.
+    pub fn context_id(&self) -> Option<&str> {
+        match self {
+            Self::Chunk { context_id, .. }
+            | Self::Timestamps { context_id }
+            | Self::Done { context_id }
+            | Self::FlushDone { context_id }
+            | Self::Error { context_id, .. } => context_id.as_deref(),
+            Self::Unknown => None,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tts::cartesia::CartesiaAudioEncoding;
+
+    #[test]
+    fn test_generation_request_serialization() {
+        let output_format = CartesiaOutputFormat::raw(CartesiaAudioEncoding::PcmS16le, 24000);
+        let request = CartesiaGenerationRequest {
+            model_id: "sonic-3",
+            transcript: "Hello there. ",
+            voice: serde_json::json!({"mode": "id", "id": "voice-1"}),
+            output_format: &output_format,
+            language: "en",
+            context_id: "c1",
+            continue_: true,
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["model_id"], "sonic-3");
+        assert_eq!(json["transcript"], "Hello there. ");
+        assert_eq!(json["voice"]["id"], "voice-1");
+        assert_eq!(json["output_format"]["container"], "raw");
+        assert_eq!(json["context_id"], "c1");
+        assert_eq!(json["continue"], true);
+        assert!(json.get("continue_").is_none());
+    }
+
+    #[test]
+    fn test_cancel_request_serialization() {
+        let json = serde_json::to_string(&CartesiaCancelRequest::new("c1")).unwrap();
+        assert_eq!(json, r#"{"context_id":"c1","cancel":true}"#);
+    }
+
+    #[test]
+    fn test_parse_chunk() {
+        let msg = CartesiaTTSMessage::parse(
+            r#"{"type":"chunk","data":"AAEC","done":false,"status_code":206,"step_time":12.5,"context_id":"c1"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            msg,
+            CartesiaTTSMessage::Chunk {
+                context_id: Some("c1".to_string()),
+                data: "AAEC".to_string(),
+            }
+        );
+        assert_eq!(msg.context_id(), Some("c1"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let msg = CartesiaTTSMessage::parse(
+            r#"{"type":"error","context_id":"c1","status_code":400,"done":true,"error":"Invalid voice"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            msg,
+            CartesiaTTSMessage::Error {
+                context_id: Some("c1".to_string()),
+                error: Some("Invalid voice".to_string()),
+                status_code: Some(400),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_type() {
+        let msg = CartesiaTTSMessage::parse(r#"{"type":"phoneme_timestamps","context_id":"c1"}"#)
+            .unwrap();
+        assert_eq!(msg, CartesiaTTSMessage::Unknown);
+        assert_eq!(msg.context_id(), None);
+    }
+}
@@ -1,22 +1,39 @@
 //! Cartesia Text-to-Speech provider.
 //!
-//! This module provides integration with Cartesia's TTS REST API for
-//! high-quality speech synthesis using the Sonic voice models.
+//! This module provides integration with Cartesia's TTS WebSocket and REST
+//! APIs for high-quality speech synthesis using the Sonic voice models.
 //!
 //! # Architecture
 //!
-//! The Cartesia TTS provider follows WaaV Gateway's HTTP-based TTS pattern:
+//! Raw PCM output (`linear16`, `mulaw`, `alaw`) streams over the WebSocket
+//! API. WAV and MP3 output follow WaaV Gateway's HTTP-based TTS pattern:
 //!
 //! ```text
 //! CartesiaTTS
 //!     │
-//!     └── TTSProvider (generic HTTP infrastructure)
+//!     ├── CartesiaWebSocket (raw output)
+//!     │       │
+//!     │       └── ContextTracker (one continuation context per utterance)
+//!     │
+//!     └── TTSProvider (generic HTTP infrastructure, WAV/MP3 output)
 //!             │
 //!             ├── ReqManager (connection pooling)
 //!             ├── Dispatcher (ordered audio delivery)
 //!             └── QueueWorker (sequential request processing)
 //! ```
 //!
+//! # Continuation Contexts
+//!
+//! Over the WebSocket, every `speak` call until the next flush belongs to the
+//! same utterance and is sent to one Cartesia context, so the sentences of an
+//! LLM reply share prosody instead of resetting pitch at each boundary:
+//!
+//! - The first chunk creates the context with `continue: true`
+//! - `speak(text, true)` or `flush()` finalizes it with `continue: false`
+//! - `clear()` cancels unfinished contexts server-side; audio still in flight
+//!   for them is dropped
+//! - `on_complete` fires when Cartesia reports `done` for the last context
+//!
 //! The module is organized into:
 //! - **config**: Cartesia-specific configuration types
 //!   - `CartesiaTTSConfig` - Provider configuration
@@ -24,10 +41,17 @@
 //!   - `CartesiaAudioContainer` - Container type enum
 //!   - `CartesiaAudioEncoding` - PCM encoding enum
 //!
+//! - **messages**: WebSocket message types
+//!   - `CartesiaGenerationRequest` - Transcript chunk for a context
+//!   - `CartesiaCancelRequest` - Context cancellation
+//!   - `CartesiaTTSMessage` - Incoming chunk/done/error messages
+//!
 //! - **provider**: Request builder and main provider
-//!   - `CartesiaRequestBuilder` - HTTP request construction
+//!   - `CartesiaRequestBuilder` - HTTP and WebSocket request construction
 //!   - `CartesiaTTS` - Main provider implementing `BaseTTS`
 //!
+//! - **websocket**: WebSocket transport and context tracking
+//!
 //! # Quick Start
 //!
 //! ## Via Factory Function (Recommended)
@@ -93,10 +117,13 @@
 //!
 //! # API Reference
 //!
-//! - Endpoint: `https://api.cartesia.ai/tts/bytes`
+//! - WebSocket endpoint: `wss://api.cartesia.ai/tts/websocket`
+//!   (`api_key` and `cartesia_version` query parameters)
+//! - REST endpoint: `https://api.cartesia.ai/tts/bytes`
 //! - Authentication: Bearer token in Authorization header
 //! - Required header: `Cartesia-Version` (API version date)
-//! - Documentation: <https://docs.cartesia.ai/api-reference/tts/bytes>
+//! - Documentation: <https://docs.cartesia.ai/api-reference/tts/websocket>,
+//!   <https://docs.cartesia.ai/api-reference/tts/bytes>
 //!
 //! # See Also
 //!
@@ -105,7 +132,9 @@
 //! - [`crate::core::stt::cartesia`] - Cartesia STT provider
 
 mod config;
+mod messages;
 mod provider;
+mod websocket;
 
 // =============================================================================
 // Public Re-exports
@@ -113,9 +142,13 @@ mod provider;
 
 // Configuration types
 pub use config::{
-    CARTESIA_TTS_URL, CartesiaAudioContainer, CartesiaAudioEncoding, CartesiaOutputFormat,
-    CartesiaTTSConfig, DEFAULT_API_VERSION, DEFAULT_MODEL, SUPPORTED_SAMPLE_RATES,
+    CARTESIA_TTS_URL, CARTESIA_TTS_WS_URL, CartesiaAudioContainer, CartesiaAudioEncoding,
+    CartesiaOutputFormat, CartesiaTTSConfig, DEFAULT_API_VERSION, DEFAULT_MODEL,
+    SUPPORTED_SAMPLE_RATES,
 };
 
+// WebSocket message types
+pub use messages::{CartesiaCancelRequest, CartesiaGenerationRequest, CartesiaTTSMessage};
+
 // Provider types
 pub use provider::{CartesiaRequestBuilder, CartesiaTTS};
@@ -13,7 +13,9 @@
 //! - Content-Type: `application/json`
 //! - Accept: Based on output format (application/octet-stream, audio/wav, audio/mpeg)
 //!
-//! The `CartesiaTTS` struct is the main TTS provider that uses the generic `TTSProvider`
+//! The `CartesiaTTS` struct is the main TTS provider. Raw PCM output (the default)
+//! streams over the Cartesia WebSocket API with one continuation context per
+//! utterance (see `websocket`); WAV and MP3 output use the generic `TTSProvider`
 //! infrastructure with `CartesiaRequestBuilder` for Cartesia-specific request construction.
 //!
 //! # Example
@@ -42,7 +44,13 @@ use serde_json::json;
 use tracing::{debug, info};
 use xxhash_rust::xxh3::xxh3_128;
 
-use super::config::{CARTESIA_TTS_URL, CartesiaTTSConfig};
+use super::config::{
+    CARTESIA_API_BASE_URL, CARTESIA_TTS_URL, CARTESIA_TTS_WS_URL, CartesiaTTSConfig,
+    DEFAULT_API_VERSION,
+};
+use super::messages::CartesiaGenerationRequest;
+use super::websocket::CartesiaWebSocket;
+use crate::core::credentials::{check_credentials, credential_check_client};
 use crate::core::tts::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSResult};
 use crate::core::tts::provider::{PronunciationReplacer, TTSProvider, TTSRequestBuilder};
 use crate::utils::req_manager::ReqManager;
@@ -146,6 +154,44 @@ impl CartesiaRequestBuilder {
         // TODO: Consider adding language to TTSConfig or CartesiaTTSConfig
         "en"
     }
+
+    /// Returns the Cartesia-specific configuration.
+    #[inline]
+    pub(super) fn cartesia_config(&self) -> &CartesiaTTSConfig {
+        &self.cartesia_config
+    }
+
+    /// Builds a WebSocket generation request for one chunk of a context.
+    ///
+    /// # Arguments
+    /// * `context_id` - Context (utterance) the chunk belongs to
+    /// * `text` - Text to synthesize, already pronunciation-adjusted
+    /// * `continue_` - Whether more chunks follow for this context
+    pub fn build_ws_request<'a>(
+        &'a self,
+        context_id: &'a str,
+        text: &'a str,
+        continue_: bool,
+    ) -> CartesiaGenerationRequest<'a> {
+        CartesiaGenerationRequest {
+            model_id: &self.cartesia_config.model,
+            transcript: text,
+            voice: self.build_voice_json(),
+            output_format: &self.cartesia_config.output_format,
+            language: self.get_language(),
+            context_id,
+            continue_,
+        }
+    }
+}
+
+/// Whether a Cartesia error message reports an unknown voice
+///
+/// Cartesia reports missing voices with messages such as "Voice not found"
+/// on both the HTTP and WebSocket APIs.
+pub(super) fn is_voice_not_found_message(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("voice") && (message.contains("not found") || message.contains("not_found"))
 }
 
 impl TTSRequestBuilder for CartesiaRequestBuilder {
@@ -226,6 +272,11 @@ impl TTSRequestBuilder for CartesiaRequestBuilder {
     fn get_pronunciation_replacer(&self) -> Option<&PronunciationReplacer> {
         self.pronunciation_replacer.as_ref()
     }
+
+    /// Detects "voice not found" error responses.
+    fn is_voice_not_found(&self, _status: u16, body: &str) -> bool {
+        is_voice_not_found_message(body)
+    }
 }
 
 // =============================================================================
@@ -289,13 +340,17 @@ fn compute_cartesia_tts_config_hash(
 
 /// Cartesia Text-to-Speech provider implementation.
 ///
-/// Uses the Cartesia TTS REST API with the Sonic voice models for
+/// Uses the Cartesia TTS API with the Sonic voice models for
 /// high-quality, low-latency speech synthesis.
 ///
 /// # Architecture
 ///
-/// This provider delegates connection pooling and audio streaming to the
-/// generic `TTSProvider` infrastructure, which handles:
+/// Raw PCM output streams over a WebSocket. Text passed to `speak` until the
+/// next flush forms one utterance, synthesized in a single Cartesia context so
+/// prosody carries across sentences; `clear` cancels the context server-side.
+///
+/// WAV and MP3 output use the REST API. This path delegates connection pooling
+/// and audio streaming to the generic `TTSProvider` infrastructure, which handles:
 /// - HTTP connection pooling via `ReqManager`
 /// - Audio chunk buffering and streaming
 /// - Ordered delivery via dispatcher task
@@ -338,9 +393,34 @@ pub struct CartesiaTTS {
     /// Precomputed configuration hash for cache keying
     /// Computed once at construction, reused for all requests
     config_hash: String,
+
+    /// WebSocket transport, used instead of `provider` for raw output
+    websocket: Option<CartesiaWebSocket>,
 }
 
 impl CartesiaTTS {
+    /// Verify an API key with a lightweight authenticated request to `/voices`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if Cartesia accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(CARTESIA_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/voices", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .header("X-API-Key", api_key)
+                .header("Cartesia-Version", DEFAULT_API_VERSION),
+        )
+        .await
+    }
+
     /// Creates a new Cartesia TTS provider instance.
     ///
     /// # Arguments
@@ -383,10 +463,15 @@ impl CartesiaTTS {
             cartesia_config.output_format.container
         );
 
+        let websocket = cartesia_config
+            .uses_websocket()
+            .then(|| CartesiaWebSocket::new(request_builder.clone()));
+
         Ok(Self {
             provider: TTSProvider::new()?,
             request_builder,
             config_hash,
+            websocket,
         })
     }
 
@@ -436,8 +521,8 @@ impl BaseTTS for CartesiaTTS {
 
     /// Connect to the TTS provider.
     ///
-    /// Initializes the HTTP connection pool with configuration-based
-    /// timeouts and pool sizes.
+    /// Opens the WebSocket for raw output. Otherwise initializes the HTTP
+    /// connection pool with configuration-based timeouts and pool sizes.
     ///
     /// # Connection Pool Configuration
     /// - Pool size: From `config.request_pool_size` (default: 4)
@@ -448,6 +533,12 @@ impl BaseTTS for CartesiaTTS {
     /// * `Ok(())` - Connection pool initialized successfully
     /// * `Err(TTSError::ConnectionFailed)` - Failed to create connection pool
     async fn connect(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &mut self.websocket {
+            websocket.connect().await?;
+            info!("Cartesia TTS provider connected and ready");
+            return Ok(());
+        }
+
         self.provider
             .generic_connect_with_config(CARTESIA_TTS_URL, &self.request_builder.config)
             .await?;
@@ -458,9 +549,12 @@ impl BaseTTS for CartesiaTTS {
 
     /// Disconnect from the TTS provider.
     ///
-    /// Stops all background tasks (queue worker, dispatcher),
-    /// clears pending requests, and releases the connection pool.
+    /// Closes the WebSocket, or stops all background tasks (queue worker,
+    /// dispatcher), clears pending requests, and releases the connection pool.
     async fn disconnect(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &mut self.websocket {
+            return websocket.disconnect().await;
+        }
         self.provider.generic_disconnect().await
     }
 
@@ -469,7 +563,10 @@ impl BaseTTS for CartesiaTTS {
     /// Returns true after `connect()` succeeds and before `disconnect()`.
     #[inline]
     fn is_ready(&self) -> bool {
-        self.provider.is_ready()
+        match &self.websocket {
+            Some(websocket) => websocket.is_ready(),
+            None => self.provider.is_ready(),
+        }
     }
 
     /// Get the current connection state.
@@ -479,7 +576,11 @@ impl BaseTTS for CartesiaTTS {
     /// - `Connected` - Ready for requests
     #[inline]
     fn get_connection_state(&self) -> ConnectionState {
-        self.provider.get_connection_state()
+        match &self.websocket {
+            Some(websocket) if websocket.is_ready() => ConnectionState::Connected,
+            Some(_) => ConnectionState::Disconnected,
+            None => self.provider.get_connection_state(),
+        }
     }
 
     /// Send text to the TTS provider for synthesis.
@@ -488,6 +589,10 @@ impl BaseTTS for CartesiaTTS {
     /// If the provider is not ready, attempts automatic reconnection
     /// before processing the request.
     ///
+    /// # WebSocket Flow
+    /// The text is sent to the current utterance's context, which is created
+    /// on the first chunk. With `flush`, the chunk finalizes the context.
+    ///
     /// # Request Flow
     /// 1. Validates provider is ready (reconnects if needed)
     /// 2. Sets config hash for caching (idempotent)
@@ -508,6 +613,10 @@ impl BaseTTS for CartesiaTTS {
             self.connect().await?;
         }
 
+        if let Some(websocket) = &self.websocket {
+            return websocket.speak(text, flush);
+        }
+
         // Set config hash once on first speak (idempotent)
         self.provider
             .set_tts_config_hash(self.config_hash.clone())
@@ -521,18 +630,26 @@ impl BaseTTS for CartesiaTTS {
 
     /// Clear any queued text from the synthesis queue.
     ///
-    /// Cancels all pending requests and clears the queue.
-    /// Does not affect audio already being played.
+    /// Cancels all pending requests and clears the queue. Over the WebSocket,
+    /// unfinished contexts are cancelled server-side and audio still in flight
+    /// for them is dropped. Does not affect audio already being played.
     async fn clear(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            return websocket.clear();
+        }
         self.provider.generic_clear().await
     }
 
     /// Flush the TTS provider queue.
     ///
-    /// Forces immediate processing of any queued text.
-    /// Note: In the current implementation, this is a no-op since
-    /// requests are processed immediately when enqueued.
+    /// Over the WebSocket, finalizes the current utterance's context so
+    /// Cartesia synthesizes the remaining text and reports `done`.
+    /// Over HTTP, this is a no-op since requests are processed immediately
+    /// when enqueued.
     async fn flush(&self) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            return websocket.flush();
+        }
         self.provider.generic_flush().await
     }
 
@@ -548,6 +665,10 @@ impl BaseTTS for CartesiaTTS {
     /// # Arguments
     /// * `callback` - Arc-wrapped callback implementation
     fn on_audio(&mut self, callback: Arc<dyn AudioCallback>) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            websocket.set_callback(Some(callback));
+            return Ok(());
+        }
         self.provider.generic_on_audio(callback)
     }
 
@@ -555,6 +676,10 @@ impl BaseTTS for CartesiaTTS {
     ///
     /// After removal, no callbacks will be invoked for audio events.
     fn remove_audio_callback(&mut self) -> TTSResult<()> {
+        if let Some(websocket) = &self.websocket {
+            websocket.set_callback(None);
+            return Ok(());
+        }
         self.provider.generic_remove_audio_callback()
     }
 
@@ -563,12 +688,18 @@ impl BaseTTS for CartesiaTTS {
     /// Returns metadata about this provider instance for debugging
     /// and informational purposes.
     fn get_provider_info(&self) -> serde_json::Value {
+        let (api_type, endpoint) = if self.websocket.is_some() {
+            ("WebSocket", CARTESIA_TTS_WS_URL)
+        } else {
+            ("HTTP REST", CARTESIA_TTS_URL)
+        };
+
         serde_json::json!({
             "provider": "cartesia",
             "version": "1.0.0",
-            "api_type": "HTTP REST",
-            "connection_pooling": true,
-            "endpoint": CARTESIA_TTS_URL,
+            "api_type": api_type,
+            "connection_pooling": self.websocket.is_none(),
+            "endpoint": endpoint,
             "model": &self.request_builder.cartesia_config.model,
             "api_version": &self.request_builder.cartesia_config.api_version,
             "supported_formats": ["raw", "wav", "mp3"],
@@ -591,7 +722,7 @@ impl BaseTTS for CartesiaTTS {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::tts::base::Pronunciation;
+    use crate::core::tts::base::{Pronunciation, TTSError};
     use crate::core::tts::cartesia::{CartesiaAudioContainer, CartesiaAudioEncoding};
 
     // =========================================================================
@@ -611,7 +742,12 @@ mod tests {
             request_timeout: Some(60),
             pronunciations: Vec::new(),
             request_pool_size: Some(4),
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         }
     }
 
@@ -1011,9 +1147,9 @@ mod tests {
 
         assert_eq!(info["provider"], "cartesia");
         assert_eq!(info["version"], "1.0.0");
-        assert_eq!(info["api_type"], "HTTP REST");
-        assert_eq!(info["connection_pooling"], true);
-        assert_eq!(info["endpoint"], CARTESIA_TTS_URL);
+        assert_eq!(info["api_type"], "WebSocket");
+        assert_eq!(info["connection_pooling"], false);
+        assert_eq!(info["endpoint"], CARTESIA_TTS_WS_URL);
         assert_eq!(info["model"], "sonic-3");
         assert_eq!(info["api_version"], "2025-04-16");
         assert!(info["supported_formats"].is_array());
@@ -1028,6 +1164,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cartesia_tts_get_provider_info_http() {
+        let mut config = create_test_config();
+        config.audio_format = Some("mp3".to_string());
+        let tts = CartesiaTTS::new(config).unwrap();
+
+        let info = tts.get_provider_info();
+
+        assert_eq!(info["api_type"], "HTTP REST");
+        assert_eq!(info["connection_pooling"], true);
+        assert_eq!(info["endpoint"], CARTESIA_TTS_URL);
+    }
+
+    #[test]
+    fn test_cartesia_tts_transport_follows_output_format() {
+        let tts = CartesiaTTS::new(create_test_config()).unwrap();
+        assert!(tts.websocket.is_some());
+
+        let mut config = create_test_config();
+        config.audio_format = Some("wav".to_string());
+        let tts = CartesiaTTS::new(config).unwrap();
+        assert!(tts.websocket.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cartesia_tts_websocket_speak_requires_api_key() {
+        let mut config = create_test_config();
+        config.api_key = String::new();
+        let mut tts = CartesiaTTS::new(config).unwrap();
+
+        // Auto-connect validates the configuration before dialing
+        let result = tts.speak("Hello", true).await;
+        assert!(matches!(result, Err(TTSError::InvalidConfiguration(_))));
+        assert!(!tts.is_ready());
+    }
+
+    #[test]
+    fn test_is_voice_not_found() {
+        let builder = CartesiaRequestBuilder::new(
+            TTSConfig::default(),
+            CartesiaTTSConfig::from_base(TTSConfig::default()),
+        );
+
+        assert!(builder.is_voice_not_found(404, r#"{"error":"Voice not found"}"#));
+        assert!(builder.is_voice_not_found(400, "voice_not_found"));
+        assert!(!builder.is_voice_not_found(400, r#"{"error":"Invalid model_id"}"#));
+        assert!(!builder.is_voice_not_found(500, "Internal server error"));
+    }
+
+    #[test]
+    fn test_build_ws_request() {
+        let mut config = create_test_config();
+        config.audio_format = Some("mulaw".to_string());
+        config.sample_rate = Some(8000);
+        let cartesia_config = CartesiaTTSConfig::from_base(config.clone());
+        let builder = CartesiaRequestBuilder::new(config, cartesia_config);
+
+        let request = builder.build_ws_request("context-1", "Hello there.", true);
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["model_id"], "sonic-3");
+        assert_eq!(json["transcript"], "Hello there.");
+        assert_eq!(json["voice"]["mode"], "id");
+        assert_eq!(json["voice"]["id"], "a0e99841-438c-4a64-b679-ae501e7d6091");
+        assert_eq!(json["output_format"]["container"], "raw");
+        assert_eq!(json["output_format"]["encoding"], "pcm_mulaw");
+        assert_eq!(json["output_format"]["sample_rate"], 8000);
+        assert_eq!(json["language"], "en");
+        assert_eq!(json["context_id"], "context-1");
+        assert_eq!(json["continue"], true);
+    }
+
     // =========================================================================
     // Edge Cases
     // =========================================================================
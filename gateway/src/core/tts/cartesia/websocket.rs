@@ -0,0 +1,687 @@
+//! Cartesia TTS WebSocket transport with continuation contexts.
+//!
+//! Each utterance is synthesized in its own Cartesia context so that
+//! successive text chunks keep consistent prosody instead of restarting
+//! pitch and pacing at every sentence:
+//!
+//! - The first `speak` of an utterance creates a context (`continue: true`)
+//! - Further `speak` calls send their text to the same context
+//! - `speak(.., flush = true)` or `flush` finalizes it (`continue: false`)
+//! - `clear` cancels every unfinished context server-side
+//!
+//! Audio of a cancelled context that is already in flight is dropped, and
+//! `done` / `error` messages are matched to the context they belong to.
+//! `on_complete` fires once no context has audio outstanding.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::{SinkExt, StreamExt};
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use super::config::CartesiaTTSConfig;
+use super::messages::{CartesiaCancelRequest, CartesiaTTSMessage};
+use super::provider::{CartesiaRequestBuilder, is_voice_not_found_message};
+use crate::core::providers::headers::insert_custom_headers;
+use crate::core::tts::base::{AudioCallback, TTSError, TTSResult};
+use crate::core::tts::provider::{TTSProvider, TTSRequestBuilder};
+
+/// How long `disconnect` waits for the connection task to close the socket
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// =============================================================================
+// Context Tracking
+// =============================================================================
+
+/// Outcome of an incoming message, delivered to the audio callback
+#[derive(Debug)]
+enum ContextEvent {
+    /// Decoded audio for a live context
+    Audio(Vec<u8>),
+    /// No context has audio outstanding anymore
+    Complete,
+    /// A context (or the connection) failed
+    Error(TTSError),
+}
+
+/// Tracks which contexts are open, generating and cancelled
+#[derive(Debug, Default)]
+struct ContextTracker {
+    /// Context receiving the current utterance's chunks, until finalized
+    open: Option<String>,
+    /// Contexts whose audio has not finished yet (includes `open`)
+    active: HashSet<String>,
+    /// Contexts cancelled by `clear` whose audio may still be in flight
+    cancelled: HashSet<String>,
+}
+
+impl ContextTracker {
+    /// Returns the context for the next chunk, creating one if none is open.
+    ///
+    /// With `finalize`, the chunk is the last one of the context.
+    fn next_chunk(&mut self, finalize: bool) -> String {
+        let context_id = self
+            .open
+            .get_or_insert_with(|| Uuid::new_v4().to_string())
+            .clone();
+        self.active.insert(context_id.clone());
+        if finalize {
+            self.open = None;
+        }
+        context_id
+    }
+
+    /// Closes the open context, returning it so it can be finalized.
+    fn finalize(&mut self) -> Option<String> {
+        self.open.take()
+    }
+
+    /// Cancels every unfinished context, returning their ids.
+    fn cancel_all(&mut self) -> Vec<String> {
+        self.open = None;
+        let context_ids: Vec<String> = self.active.drain().collect();
+        self.cancelled.extend(context_ids.iter().cloned());
+        context_ids
+    }
+
+    /// Handles a text frame from Cartesia.
+    fn handle_message(&mut self, text: &str) -> Vec<ContextEvent> {
+        let message = match CartesiaTTSMessage::parse(text) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(
+                    "Failed to parse Cartesia TTS message: {} - raw: {}",
+                    e, text
+                );
+                return Vec::new();
+            }
+        };
+
+        let context_id = message.context_id().map(str::to_string);
+        match message {
+            CartesiaTTSMessage::Chunk { data, .. } => {
+                if self.is_cancelled(context_id.as_deref()) {
+                    debug!("Dropping audio for cancelled context {:?}", context_id);
+                    return Vec::new();
+                }
+                match BASE64.decode(data) {
+                    Ok(audio) => vec![ContextEvent::Audio(audio)],
+                    Err(e) => vec![ContextEvent::Error(TTSError::AudioGenerationFailed(
+                        format!("Invalid audio chunk from Cartesia: {e}"),
+                    ))],
+                }
+            }
+            CartesiaTTSMessage::Done { .. } => {
+                if self.finish(context_id.as_deref()) {
+                    return Vec::new();
+                }
+                self.completion()
+            }
+            CartesiaTTSMessage::Error {
+                error, status_code, ..
+            } => {
+                if self.finish(context_id.as_deref()) {
+                    return Vec::new();
+                }
+                let message = error.unwrap_or_else(|| "unknown error".to_string());
+                let error = match status_code {
+                    _ if is_voice_not_found_message(&message) => TTSError::VoiceNotFound(message),
+                    Some(401 | 403) => TTSError::AuthenticationFailed(message),
+                    Some(429) => TTSError::RateLimited {
+                        retry_after_secs: None,
+                        message,
+                    },
+                    _ => TTSError::ProviderError(message),
+                };
+                let mut events = vec![ContextEvent::Error(error)];
+                events.extend(self.completion());
+                events
+            }
+            CartesiaTTSMessage::Timestamps { .. }
+            | CartesiaTTSMessage::FlushDone { .. }
+            | CartesiaTTSMessage::Unknown => Vec::new(),
+        }
+    }
+
+    fn is_cancelled(&self, context_id: Option<&str>) -> bool {
+        context_id.is_some_and(|id| self.cancelled.contains(id))
+    }
+
+    /// Forgets a context that ended; returns true if it had been cancelled
+    fn finish(&mut self, context_id: Option<&str>) -> bool {
+        let Some(context_id) = context_id else {
+            return false;
+        };
+        if self.cancelled.remove(context_id) {
+            return true;
+        }
+        self.active.remove(context_id);
+        if self.open.as_deref() == Some(context_id) {
+            self.open = None;
+        }
+        false
+    }
+
+    fn completion(&self) -> Vec<ContextEvent> {
+        if self.active.is_empty() {
+            vec![ContextEvent::Complete]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// =============================================================================
+// WebSocket Connection
+// =============================================================================
+
+/// WebSocket connection to Cartesia TTS with per-utterance contexts
+pub(super) struct CartesiaWebSocket {
+    /// Builds generation requests (model, voice, output format)
+    request_builder: CartesiaRequestBuilder,
+
+    /// Context state shared with the connection task
+    contexts: Arc<Mutex<ContextTracker>>,
+
+    /// Audio callback shared with the connection task
+    callback: Arc<RwLock<Option<Arc<dyn AudioCallback>>>>,
+
+    /// Outgoing text frames
+    ws_sender: Option<mpsc::UnboundedSender<String>>,
+
+    /// Shutdown signal sender
+    shutdown_tx: Option<oneshot::Sender<()>>,
+
+    /// Connection task handle
+    connection_handle: Option<tokio::task::JoinHandle<()>>,
+
+    /// Cleared by the connection task when the socket closes
+    connected: Arc<AtomicBool>,
+}
+
+impl CartesiaWebSocket {
+    pub(super) fn new(request_builder: CartesiaRequestBuilder) -> Self {
+        Self {
+            request_builder,
+            contexts: Arc::new(Mutex::new(ContextTracker::default())),
+            callback: Arc::new(RwLock::new(None)),
+            ws_sender: None,
+            shutdown_tx: None,
+            connection_handle: None,
+            connected: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn config(&self) -> &CartesiaTTSConfig {
+        self.request_builder.cartesia_config()
+    }
+
+    pub(super) fn is_ready(&self) -> bool {
+        self.ws_sender.is_some() && self.connected.load(Ordering::Acquire)
+    }
+
+    pub(super) fn set_callback(&self, callback: Option<Arc<dyn AudioCallback>>) {
+        *self.callback.write() = callback;
+    }
+
+    /// Opens the WebSocket and starts the connection task.
+    pub(super) async fn connect(&mut self) -> TTSResult<()> {
+        if self.is_ready() {
+            return Ok(());
+        }
+        self.disconnect().await?;
+
+        let config = self.config().clone();
+        config.validate()?;
+
+        let mut request = config
+            .build_websocket_url()
+            .into_client_request()
+            .map_err(|e| TTSError::ConnectionFailed(format!("Invalid WebSocket URL: {e}")))?;
+        insert_custom_headers(request.headers_mut(), &config.base.custom_headers);
+
+        let (ws_stream, _) = connect_async(request).await.map_err(|e| {
+            TTSError::ConnectionFailed(format!("Failed to connect to Cartesia: {e}"))
+        })?;
+        info!("Connected to Cartesia TTS WebSocket");
+
+        let (ws_tx, mut ws_rx) = mpsc::unbounded_channel::<String>();
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+        *self.contexts.lock() = ContextTracker::default();
+        self.connected.store(true, Ordering::Release);
+
+        let contexts = self.contexts.clone();
+        let callback = self.callback.clone();
+        let connected = self.connected.clone();
+        let format = config
+            .base
+            .audio_format
+            .clone()
+            .unwrap_or_else(|| "linear16".to_string());
+        let sample_rate = config.output_format.sample_rate;
+
+        let connection_handle = tokio::spawn(async move {
+            let (mut ws_sink, mut ws_stream) = ws_stream.split();
+
+            loop {
+                tokio::select! {
+                    Some(text) = ws_rx.recv() => {
+                        if let Err(e) = ws_sink.send(Message::Text(text.into())).await {
+                            let err = TTSError::NetworkError(format!(
+                                "Failed to send WebSocket message: {e}"
+                            ));
+                            error!("{}", err);
+                            let events = vec![ContextEvent::Error(err)];
+                            Self::dispatch(&callback, events, &format, sample_rate).await;
+                            break;
+                        }
+                    }
+
+                    message = ws_stream.next() => {
+                        match message {
+                            Some(Ok(Message::Text(text))) => {
+                                let events = contexts.lock().handle_message(&text);
+                                Self::dispatch(&callback, events, &format, sample_rate).await;
+                            }
+                            Some(Ok(Message::Close(frame))) => {
+                                info!("Cartesia TTS WebSocket closed: {:?}", frame);
+                                break;
+                            }
+                            Some(Ok(_)) => {}
+                            Some(Err(e)) => {
+                                let err = TTSError::NetworkError(format!("WebSocket error: {e}"));
+                                error!("{}", err);
+                                let events = vec![ContextEvent::Error(err)];
+                                Self::dispatch(&callback, events, &format, sample_rate).await;
+                                break;
+                            }
+                            None => {
+                                info!("Cartesia TTS WebSocket stream ended");
+                                break;
+                            }
+                        }
+                    }
+
+                    _ = &mut shutdown_rx => {
+                        let _ = ws_sink.send(Message::Close(None)).await;
+                        break;
+                    }
+                }
+            }
+
+            connected.store(false, Ordering::Release);
+            debug!("Cartesia TTS WebSocket task exited");
+        });
+
+        self.ws_sender = Some(ws_tx);
+        self.shutdown_tx = Some(shutdown_tx);
+        self.connection_handle = Some(connection_handle);
+        Ok(())
+    }
+
+    /// Closes the WebSocket and stops the connection task.
+    pub(super) async fn disconnect(&mut self) -> TTSResult<()> {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(mut handle) = self.connection_handle.take()
+            && tokio::time::timeout(DISCONNECT_TIMEOUT, &mut handle)
+                .await
+                .is_err()
+        {
+            handle.abort();
+        }
+        self.ws_sender = None;
+        self.connected.store(false, Ordering::Release);
+        *self.contexts.lock() = ContextTracker::default();
+        Ok(())
+    }
+
+    /// Sends a transcript chunk to the current utterance's context.
+    pub(super) fn speak(&self, text: &str, flush: bool) -> TTSResult<()> {
+        if text.trim().is_empty() {
+            return if flush { self.flush() } else { Ok(()) };
+        }
+
+        let text = match self.request_builder.get_pronunciation_replacer() {
+            Some(replacer) => replacer.apply(text),
+            None => text.to_string(),
+        };
+
+        let context_id = self.contexts.lock().next_chunk(flush);
+        let request = self
+            .request_builder
+            .build_ws_request(&context_id, &text, !flush);
+        debug!(
+            "Sending Cartesia chunk: context={}, continue={}, {} chars",
+            context_id,
+            !flush,
+            text.len()
+        );
+        self.send(&request)
+    }
+
+    /// Finalizes the open context, if any.
+    pub(super) fn flush(&self) -> TTSResult<()> {
+        let Some(context_id) = self.contexts.lock().finalize() else {
+            return Ok(());
+        };
+        let request = self
+            .request_builder
+            .build_ws_request(&context_id, "", false);
+        self.send(&request)
+    }
+
+    /// Cancels every unfinished context server-side.
+    pub(super) fn clear(&self) -> TTSResult<()> {
+        let context_ids = self.contexts.lock().cancel_all();
+        for context_id in &context_ids {
+            debug!("Cancelling Cartesia context {}", context_id);
+            self.send(&CartesiaCancelRequest::new(context_id))?;
+        }
+        Ok(())
+    }
+
+    fn send(&self, message: &impl serde::Serialize) -> TTSResult<()> {
+        let sender = self
+            .ws_sender
+            .as_ref()
+            .ok_or_else(|| TTSError::ProviderNotReady("Cartesia TTS not connected".to_string()))?;
+        let text = serde_json::to_string(message)
+            .map_err(|e| TTSError::InternalError(format!("Failed to serialize request: {e}")))?;
+        sender.send(text).map_err(|_| {
+            TTSError::NetworkError("Cartesia TTS WebSocket connection closed".to_string())
+        })
+    }
+
+    /// Delivers events to the registered audio callback
+    async fn dispatch(
+        callback: &RwLock<Option<Arc<dyn AudioCallback>>>,
+        events: Vec<ContextEvent>,
+        format: &str,
+        sample_rate: u32,
+    ) {
+        if events.is_empty() {
+            return;
+        }
+        let callback = callback.read().clone();
+        let Some(callback) = callback else {
+            return;
+        };
+        for event in events {
+            match event {
+                ContextEvent::Audio(audio) => {
+                    let audio_data = TTSProvider::process_audio_chunk(audio, format, sample_rate);
+                    callback.on_audio(audio_data).await;
+                }
+                ContextEvent::Complete => callback.on_complete().await,
+                ContextEvent::Error(err) => callback.on_error(err).await,
+            }
+        }
+    }
+}
+
+impl Drop for CartesiaWebSocket {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(handle) = self.connection_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tts::base::TTSConfig;
+
+    /// Base64 of four bytes of PCM, as carried by recorded `chunk` frames
+    const AUDIO_B64: &str = "AAEAAQ==";
+
+    fn create_websocket() -> (CartesiaWebSocket, mpsc::UnboundedReceiver<String>) {
+        let config = TTSConfig {
+            provider: "cartesia".to_string(),
+            api_key: "test-api-key".to_string(),
+            voice_id: Some("a0e99841-438c-4a64-b679-ae501e7d6091".to_string()),
+            audio_format: Some("linear16".to_string()),
+            sample_rate: Some(24000),
+            ..Default::default()
+        };
+        let cartesia_config = CartesiaTTSConfig::from_base(config.clone());
+        let mut websocket =
+            CartesiaWebSocket::new(CartesiaRequestBuilder::new(config, cartesia_config));
+
+        // Capture outgoing frames instead of writing to a socket
+        let (ws_tx, ws_rx) = mpsc::unbounded_channel();
+        websocket.ws_sender = Some(ws_tx);
+        websocket.connected.store(true, Ordering::Release);
+        (websocket, ws_rx)
+    }
+
+    fn is_audio(events: Vec<ContextEvent>) -> bool {
+        matches!(events.as_slice(), [ContextEvent::Audio(audio)] if audio == &[0, 1, 0, 1])
+    }
+
+    fn is_complete(events: Vec<ContextEvent>) -> bool {
+        matches!(events.as_slice(), [ContextEvent::Complete])
+    }
+
+    fn next_frame(ws_rx: &mut mpsc::UnboundedReceiver<String>) -> serde_json::Value {
+        serde_json::from_str(&ws_rx.try_recv().expect("expected an outgoing frame")).unwrap()
+    }
+
+    /// Recorded frames for a context, with the generated id substituted
+    fn chunk_frame(context_id: &str) -> String {
+        format!(
+            r#"{{"type":"chunk","data":"{AUDIO_B64}","done":false,"status_code":206,"step_time":48.7,"context_id":"{context_id}"}}"#
+        )
+    }
+
+    fn done_frame(context_id: &str) -> String {
+        format!(r#"{{"type":"done","done":true,"status_code":200,"context_id":"{context_id}"}}"#)
+    }
+
+    #[test]
+    fn test_utterance_chunks_share_one_context() {
+        let (websocket, mut ws_rx) = create_websocket();
+
+        websocket.speak("Hello there. ", false).unwrap();
+        websocket.speak("How are you today?", false).unwrap();
+        websocket.flush().unwrap();
+
+        let first = next_frame(&mut ws_rx);
+        let second = next_frame(&mut ws_rx);
+        let last = next_frame(&mut ws_rx);
+        let context_id = first["context_id"].as_str().unwrap();
+
+        assert_eq!(first["transcript"], "Hello there. ");
+        assert_eq!(first["continue"], true);
+        assert_eq!(first["model_id"], "sonic-3");
+        assert_eq!(first["output_format"]["container"], "raw");
+        assert_eq!(second["context_id"], context_id);
+        assert_eq!(second["transcript"], "How are you today?");
+        assert_eq!(second["continue"], true);
+        assert_eq!(last["context_id"], context_id);
+        assert_eq!(last["transcript"], "");
+        assert_eq!(last["continue"], false);
+        assert!(ws_rx.try_recv().is_err());
+
+        // Nothing left to finalize
+        websocket.flush().unwrap();
+        assert!(ws_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_flushing_speak_finalizes_context() {
+        let (websocket, mut ws_rx) = create_websocket();
+
+        websocket.speak("First reply.", true).unwrap();
+        websocket.speak("Second reply.", true).unwrap();
+        // Whitespace-only text only finalizes
+        websocket.speak("  ", false).unwrap();
+
+        let first = next_frame(&mut ws_rx);
+        let second = next_frame(&mut ws_rx);
+        assert_eq!(first["continue"], false);
+        assert_eq!(second["continue"], false);
+        assert_ne!(first["context_id"], second["context_id"]);
+        assert!(ws_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_completed_context_reports_audio_then_complete() {
+        let (websocket, mut ws_rx) = create_websocket();
+        websocket.speak("Hello there.", true).unwrap();
+        let context_id = next_frame(&mut ws_rx)["context_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let mut contexts = websocket.contexts.lock();
+        assert!(is_audio(contexts.handle_message(&chunk_frame(&context_id))));
+        assert!(
+            contexts
+                .handle_message(&format!(
+                    r#"{{"type":"timestamps","context_id":"{context_id}","word_timestamps":{{"words":["Hello"],"start":[0.0],"end":[0.3]}}}}"#
+                ))
+                .is_empty()
+        );
+        assert!(is_complete(
+            contexts.handle_message(&done_frame(&context_id))
+        ));
+    }
+
+    #[test]
+    fn test_cleared_context_is_cancelled_and_late_audio_dropped() {
+        let (websocket, mut ws_rx) = create_websocket();
+
+        // Agent reply interrupted mid-utterance
+        websocket
+            .speak("Let me check that for you. ", false)
+            .unwrap();
+        let interrupted = next_frame(&mut ws_rx)["context_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(is_audio(
+            websocket
+                .contexts
+                .lock()
+                .handle_message(&chunk_frame(&interrupted))
+        ));
+
+        websocket.clear().unwrap();
+        let cancel = next_frame(&mut ws_rx);
+        assert_eq!(
+            cancel,
+            serde_json::json!({"context_id": interrupted, "cancel": true})
+        );
+
+        // The next reply opens a fresh context
+        websocket.speak("Sure, go ahead.", true).unwrap();
+        let next = next_frame(&mut ws_rx);
+        let next_id = next["context_id"].as_str().unwrap().to_string();
+        assert_ne!(next_id, interrupted);
+        assert_eq!(next["continue"], false);
+
+        let mut contexts = websocket.contexts.lock();
+        // Audio already in flight for the cancelled context is dropped
+        assert!(
+            contexts
+                .handle_message(&chunk_frame(&interrupted))
+                .is_empty()
+        );
+        // Its final message does not complete the new utterance
+        assert!(
+            contexts
+                .handle_message(&done_frame(&interrupted))
+                .is_empty()
+        );
+        assert!(is_audio(contexts.handle_message(&chunk_frame(&next_id))));
+        assert!(is_complete(contexts.handle_message(&done_frame(&next_id))));
+        assert!(contexts.cancelled.is_empty());
+        assert!(contexts.active.is_empty());
+    }
+
+    #[test]
+    fn test_clear_without_contexts_sends_nothing() {
+        let (websocket, mut ws_rx) = create_websocket();
+        websocket.clear().unwrap();
+        assert!(ws_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_context_error_is_reported_per_context() {
+        let mut contexts = ContextTracker::default();
+        let failing = contexts.next_chunk(true);
+        let pending = contexts.next_chunk(true);
+
+        let events = contexts.handle_message(&format!(
+            r#"{{"type":"error","context_id":"{failing}","status_code":400,"done":true,"error":"Invalid voice"}}"#
+        ));
+        // Another context still has audio outstanding
+        assert!(matches!(
+            events.as_slice(),
+            [ContextEvent::Error(TTSError::ProviderError(message))] if message == "Invalid voice"
+        ));
+        assert!(is_complete(contexts.handle_message(&done_frame(&pending))));
+
+        // Errors without a context are still surfaced
+        let events = contexts.handle_message(
+            r#"{"type":"error","status_code":401,"done":true,"error":"Invalid API key"}"#,
+        );
+        assert!(matches!(
+            events.as_slice(),
+            [
+                ContextEvent::Error(TTSError::AuthenticationFailed(_)),
+                ContextEvent::Complete
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_voice_not_found_error() {
+        let mut contexts = ContextTracker::default();
+        let context = contexts.next_chunk(true);
+
+        let events = contexts.handle_message(&format!(
+            r#"{{"type":"error","context_id":"{context}","status_code":404,"done":true,"error":"Voice not found"}}"#
+        ));
+        assert!(matches!(
+            events.as_slice(),
+            [
+                ContextEvent::Error(TTSError::VoiceNotFound(_)),
+                ContextEvent::Complete
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_send_requires_connection() {
+        let config = TTSConfig::default();
+        let cartesia_config = CartesiaTTSConfig::from_base(config.clone());
+        let websocket =
+            CartesiaWebSocket::new(CartesiaRequestBuilder::new(config, cartesia_config));
+
+        assert!(!websocket.is_ready());
+        assert!(matches!(
+            websocket.speak("Hello", true),
+            Err(TTSError::ProviderNotReady(_))
+        ));
+    }
+}
@@ -3,14 +3,21 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use serde_json::json;
 
-use super::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSResult};
+use super::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSError, TTSResult};
 use super::provider::{PronunciationReplacer, TTSProvider, TTSRequestBuilder};
+use crate::core::credentials::{check_credentials, credential_check_client};
+use crate::core::providers::connection_budget::{
+    ConnectionPermit, DEEPGRAM_CONNECTION_BUDGET, connection_budgets,
+};
 use crate::utils::req_manager::ReqManager;
 use xxhash_rust::xxh3::xxh3_128;
 
 /// Deepgram TTS endpoint
 pub const DEEPGRAM_TTS_URL: &str = "https://api.deepgram.com/v1/speak";
 
+/// Deepgram REST API base URL, used for credential checks
+pub const DEEPGRAM_API_BASE_URL: &str = "https://api.deepgram.com";
+
 /// Deepgram-specific request builder
 #[derive(Clone)]
 struct DeepgramRequestBuilder {
@@ -108,9 +115,32 @@ pub struct DeepgramTTS {
     request_builder: DeepgramRequestBuilder,
     /// Precomputed config hash for caching
     config_hash: String,
+    /// Slot in the key's connection budget, shared with Deepgram STT, held while connected
+    connection_permit: Option<ConnectionPermit>,
 }
 
 impl DeepgramTTS {
+    /// Verify an API key with a lightweight authenticated request to `/v1/projects`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if Deepgram accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(DEEPGRAM_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/v1/projects", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .header("Authorization", format!("Token {api_key}")),
+        )
+        .await
+    }
+
     /// Create a new Deepgram TTS instance
     pub fn new(config: TTSConfig) -> TTSResult<Self> {
         let pronunciation_replacer = if !config.pronunciations.is_empty() {
@@ -127,6 +157,7 @@ impl DeepgramTTS {
             provider: TTSProvider::new()?,
             request_builder,
             config_hash: hash,
+            connection_permit: None,
         })
     }
 
@@ -153,13 +184,30 @@ impl BaseTTS for DeepgramTTS {
     }
 
     async fn connect(&mut self) -> TTSResult<()> {
-        self.provider
+        if self.connection_permit.is_none() {
+            let permit = connection_budgets()
+                .acquire(
+                    DEEPGRAM_CONNECTION_BUDGET,
+                    &self.request_builder.config.api_key,
+                )
+                .await
+                .map_err(|e| TTSError::ConnectionFailed(e.to_string()))?;
+            self.connection_permit = Some(permit);
+        }
+        let result = self
+            .provider
             .generic_connect_with_config(DEEPGRAM_TTS_URL, &self.request_builder.config)
-            .await
+            .await;
+        if result.is_err() {
+            self.connection_permit = None;
+        }
+        result
     }
 
     async fn disconnect(&mut self) -> TTSResult<()> {
-        self.provider.generic_disconnect().await
+        let result = self.provider.generic_disconnect().await;
+        self.connection_permit = None;
+        result
     }
 
     fn is_ready(&self) -> bool {
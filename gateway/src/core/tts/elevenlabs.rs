@@ -4,8 +4,9 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use super::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSResult};
+use super::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSError, TTSResult};
 use super::provider::{TTSProvider, TTSRequestBuilder};
+use crate::core::credentials::{check_credentials, credential_check_client};
 use crate::utils::req_manager::ReqManager;
 
 /// Voice settings for ElevenLabs TTS
@@ -42,6 +43,43 @@ impl Default for VoiceSettings {
 
 pub const ELEVENLABS_TTS_URL: &str = "https://api.elevenlabs.io/v1/text-to-speech";
 
+/// ElevenLabs REST API base URL, used for credential and voice checks
+pub const ELEVENLABS_API_BASE_URL: &str = "https://api.elevenlabs.io";
+
+/// Voice used when the config does not set one
+const ELEVENLABS_DEFAULT_VOICE_ID: &str = "21m00Tcm4TlvDq8ikWAM";
+
+/// Longest `previous_text`/`next_text` sent with a request, in characters
+///
+/// Context text counts toward the request size limits, and only the text
+/// next to the chunk affects its prosody.
+const ELEVENLABS_CONTEXT_MAX_CHARS: usize = 500;
+
+/// The last `max_chars` characters of `text`
+fn context_tail(text: &str, max_chars: usize) -> &str {
+    let skip = text.chars().count().saturating_sub(max_chars);
+    match text.char_indices().nth(skip) {
+        Some((start, _)) => &text[start..],
+        None => "",
+    }
+}
+
+/// The first `max_chars` characters of `text`
+fn context_head(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Whether an ElevenLabs error response reports an unknown voice
+///
+/// ElevenLabs answers `{"detail": {"status": "voice_not_found", ...}}` with
+/// 400 or 404; a bare 404 on a voice-scoped URL means the same.
+fn is_voice_not_found_response(status: u16, body: &str) -> bool {
+    status == 404 || (status == 400 && body.contains("voice_not_found"))
+}
+
 /// ElevenLabs-specific request builder
 #[derive(Clone)]
 struct ElevenLabsRequestBuilder {
@@ -52,8 +90,8 @@ struct ElevenLabsRequestBuilder {
 impl TTSRequestBuilder for ElevenLabsRequestBuilder {
     /// Build the ElevenLabs-specific HTTP request with URL, headers and body
     fn build_http_request(&self, client: &reqwest::Client, text: &str) -> reqwest::RequestBuilder {
-        // Forward to the context method without previous_text
-        self.build_http_request_with_context(client, text, None)
+        // Forward to the context method without surrounding text
+        self.build_http_request_with_context(client, text, None, None)
     }
 
     /// Build the ElevenLabs-specific HTTP request with context support
@@ -62,10 +100,14 @@ impl TTSRequestBuilder for ElevenLabsRequestBuilder {
         client: &reqwest::Client,
         text: &str,
         previous_text: Option<&str>,
+        next_text: Option<&str>,
     ) -> reqwest::RequestBuilder {
         // Get voice_id from config, required for ElevenLabs
-        let default_voice = "21m00Tcm4TlvDq8ikWAM".to_string();
-        let voice_id = self.config.voice_id.as_ref().unwrap_or(&default_voice);
+        let voice_id = self
+            .config
+            .voice_id
+            .as_deref()
+            .unwrap_or(ELEVENLABS_DEFAULT_VOICE_ID);
 
         // Build the URL with voice_id
         let url = format!("{ELEVENLABS_TTS_URL}/{voice_id}");
@@ -125,9 +167,12 @@ impl TTSRequestBuilder for ElevenLabsRequestBuilder {
             "voice_settings": self.voice_settings,
         });
 
-        // Add previous_text for context continuity if available
+        // Add the surrounding text for context continuity if available
         if let Some(prev) = previous_text {
-            body["previous_text"] = json!(prev);
+            body["previous_text"] = json!(context_tail(prev, ELEVENLABS_CONTEXT_MAX_CHARS));
+        }
+        if let Some(next) = next_text {
+            body["next_text"] = json!(context_head(next, ELEVENLABS_CONTEXT_MAX_CHARS));
         }
 
         // Add model_id if specified
@@ -161,6 +206,11 @@ impl TTSRequestBuilder for ElevenLabsRequestBuilder {
     fn get_config(&self) -> &TTSConfig {
         &self.config
     }
+
+    /// Detect `voice_not_found` responses
+    fn is_voice_not_found(&self, status: u16, body: &str) -> bool {
+        is_voice_not_found_response(status, body)
+    }
 }
 
 /// ElevenLabs TTS provider implementation using the ElevenLabs HTTP REST API
@@ -172,6 +222,60 @@ pub struct ElevenLabsTTS {
 }
 
 impl ElevenLabsTTS {
+    /// Verify an API key with a lightweight authenticated request to `/v1/user`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if ElevenLabs accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(ELEVENLABS_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/v1/user", base_url.trim_end_matches('/'));
+        check_credentials(client.get(url).header("xi-api-key", api_key)).await
+    }
+
+    /// Look up the configured voice via `/v1/voices/{voice_id}` at a custom API base URL.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the voice exists
+    /// * `Err(TTSError::VoiceNotFound)` if ElevenLabs does not know the voice
+    /// * `Err(TTSError)` for any other failure
+    pub async fn validate_voice_at(&self, base_url: &str) -> TTSResult<()> {
+        let config = &self.request_builder.config;
+        let voice_id = config
+            .voice_id
+            .as_deref()
+            .unwrap_or(ELEVENLABS_DEFAULT_VOICE_ID);
+        let client = credential_check_client().map_err(TTSError::InternalError)?;
+        let url = format!("{}/v1/voices/{voice_id}", base_url.trim_end_matches('/'));
+
+        let response = client
+            .get(url)
+            .header("xi-api-key", &config.api_key)
+            .send()
+            .await
+            .map_err(|e| TTSError::NetworkError(format!("Failed to reach ElevenLabs: {e}")))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        if is_voice_not_found_response(status.as_u16(), &body) {
+            Err(TTSError::VoiceNotFound(format!(
+                "ElevenLabs voice '{voice_id}' does not exist"
+            )))
+        } else {
+            Err(TTSError::ProviderError(format!(
+                "Voice lookup failed ({status}): {body}"
+            )))
+        }
+    }
+
     /// Create a new ElevenLabs TTS instance
     pub fn new(config: TTSConfig) -> TTSResult<Self> {
         // Validate required fields for ElevenLabs
@@ -266,6 +370,10 @@ impl BaseTTS for ElevenLabsTTS {
         self.provider.generic_remove_audio_callback()
     }
 
+    async fn validate_voice(&self) -> TTSResult<()> {
+        self.validate_voice_at(ELEVENLABS_API_BASE_URL).await
+    }
+
     fn get_provider_info(&self) -> serde_json::Value {
         serde_json::json!({
             "provider": "elevenlabs",
@@ -407,6 +515,20 @@ mod tests {
         assert_eq!(headers.get("accept").unwrap(), "audio/mpeg");
     }
 
+    #[test]
+    fn test_is_voice_not_found() {
+        let builder = ElevenLabsRequestBuilder {
+            config: TTSConfig::default(),
+            voice_settings: VoiceSettings::default(),
+        };
+
+        let body = r#"{"detail":{"status":"voice_not_found","message":"A voice with voice_id 'abc' was not found."}}"#;
+        assert!(builder.is_voice_not_found(404, body));
+        assert!(builder.is_voice_not_found(400, body));
+        assert!(!builder.is_voice_not_found(400, r#"{"detail":{"status":"invalid_text"}}"#));
+        assert!(!builder.is_voice_not_found(401, r#"{"detail":{"status":"invalid_api_key"}}"#));
+    }
+
     #[tokio::test]
     async fn test_elevenlabs_lifecycle() {
         let config = TTSConfig {
@@ -449,6 +571,7 @@ mod tests {
             &client,
             "Second utterance",
             Some("First utterance"),
+            None,
         );
 
         // Get the request as built
@@ -485,7 +608,8 @@ mod tests {
         let client = reqwest::Client::new();
 
         // Build request without previous_text
-        let request = builder.build_http_request_with_context(&client, "First utterance", None);
+        let request =
+            builder.build_http_request_with_context(&client, "First utterance", None, None);
 
         // Get the request as built
         let built_request = request.build().unwrap();
@@ -499,6 +623,72 @@ mod tests {
 
         assert_eq!(body_json["text"], "First utterance");
         assert!(body_json["previous_text"].is_null());
+        assert!(body_json["next_text"].is_null());
         assert!(body_json["model_id"].is_string());
     }
+
+    fn context_body(
+        builder: &ElevenLabsRequestBuilder,
+        text: &str,
+        previous_text: Option<&str>,
+        next_text: Option<&str>,
+    ) -> serde_json::Value {
+        let client = reqwest::Client::new();
+        let request = builder
+            .build_http_request_with_context(&client, text, previous_text, next_text)
+            .build()
+            .unwrap();
+        serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_context_hints_for_utterance_chunks() {
+        let builder = ElevenLabsRequestBuilder {
+            config: TTSConfig {
+                api_key: "test_key".to_string(),
+                ..Default::default()
+            },
+            voice_settings: VoiceSettings::default(),
+        };
+        let chunks = [
+            "Your order has shipped.",
+            "It should arrive on Tuesday.",
+            "Anything else?",
+        ];
+
+        let first = context_body(&builder, chunks[0], None, Some(chunks[1]));
+        assert!(first["previous_text"].is_null());
+        assert_eq!(first["next_text"], chunks[1]);
+
+        let middle = context_body(&builder, chunks[1], Some(chunks[0]), Some(chunks[2]));
+        assert_eq!(middle["text"], chunks[1]);
+        assert_eq!(middle["previous_text"], chunks[0]);
+        assert_eq!(middle["next_text"], chunks[2]);
+
+        let last = context_body(&builder, chunks[2], Some(chunks[1]), None);
+        assert_eq!(last["previous_text"], chunks[1]);
+        assert!(last["next_text"].is_null());
+    }
+
+    #[test]
+    fn test_context_hints_are_bounded() {
+        let builder = ElevenLabsRequestBuilder {
+            config: TTSConfig::default(),
+            voice_settings: VoiceSettings::default(),
+        };
+        let long = format!("{}é{}", "a".repeat(600), "b".repeat(600));
+
+        let body = context_body(&builder, "Chunk", Some(&long), Some(&long));
+        let previous = body["previous_text"].as_str().unwrap();
+        let next = body["next_text"].as_str().unwrap();
+        // The previous text keeps its end, the next text its start
+        assert_eq!(previous, "b".repeat(ELEVENLABS_CONTEXT_MAX_CHARS));
+        assert_eq!(next, "a".repeat(ELEVENLABS_CONTEXT_MAX_CHARS));
+
+        // Cuts land on character boundaries
+        assert_eq!(context_tail("aé", 1), "é");
+        assert_eq!(context_head("éa", 1), "é");
+        assert_eq!(context_tail("short", 10), "short");
+        assert_eq!(context_head("", 10), "");
+    }
 }
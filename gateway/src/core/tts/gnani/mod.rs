@@ -84,7 +84,12 @@ mod tests {
             request_timeout: Some(30),
             pronunciations: Vec::new(),
             request_pool_size: None,
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         }
     }
 
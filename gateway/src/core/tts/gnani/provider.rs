@@ -9,6 +9,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
+use crate::core::providers::headers::apply_custom_headers;
 use crate::core::tts::base::{
     AudioCallback, AudioData, BaseTTS, ConnectionState, TTSConfig, TTSError, TTSResult,
 };
@@ -119,14 +120,16 @@ impl GnaniTTS {
             "Gnani TTS synthesis request"
         );
 
-        let response = client
+        let request = client
             .post(config.endpoint())
             .header("token", &config.token)
             .header("accesskey", &config.access_key)
             .header("lang", config.language_code.as_str())
             .header("product", "tts")
             .header("Content-Type", "application/json")
-            .json(&request_body)
+            .json(&request_body);
+
+        let response = apply_custom_headers(request, &config.base.custom_headers)
             .send()
             .await
             .map_err(|e| TTSError::NetworkError(format!("Request failed: {}", e)))?;
@@ -349,7 +352,12 @@ mod tests {
             request_timeout: Some(30),
             pronunciations: Vec::new(),
             request_pool_size: None,
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         }
     }
 
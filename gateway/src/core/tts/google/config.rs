@@ -31,6 +31,22 @@
 use crate::core::tts::base::TTSConfig;
 use serde::{Deserialize, Serialize};
 
+/// `model` value that selects a Chirp 3 HD voice by its short name
+///
+/// With this model, `voice_id` may be just the voice ("Charon") and the full
+/// name ("en-US-Chirp3-HD-Charon") is built from the default language.
+pub const CHIRP3_HD_MODEL: &str = "chirp3-hd";
+
+/// Sample rate of Chirp 3 HD streaming audio (16-bit PCM, mono)
+pub const CHIRP3_HD_SAMPLE_RATE: u32 = 24_000;
+
+/// Whether `voice` names a Chirp 3 HD voice, e.g. "en-US-Chirp3-HD-Charon"
+///
+/// These voices are synthesized over the streaming API.
+pub fn is_chirp3_hd_voice(voice: &str) -> bool {
+    voice.to_ascii_lowercase().contains("-chirp3-hd-")
+}
+
 /// Audio encoding formats supported by Google Cloud Text-to-Speech API.
 ///
 /// This enum provides type-safe representation of Google's supported audio encodings,
@@ -303,6 +319,39 @@ impl GoogleTTSConfig {
         None
     }
 
+    /// Returns the Chirp 3 HD voice name when this config uses streaming synthesis.
+    ///
+    /// Streaming is selected by a Chirp 3 HD voice name in `voice_id` or
+    /// `model`, or by `model: chirp3-hd` with a short voice name in `voice_id`.
+    /// Returns `None` for every other voice, which use the REST API.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = GoogleTTSConfig::from_base_config(
+    ///     TTSConfig {
+    ///         model: "chirp3-hd".to_string(),
+    ///         voice_id: Some("Charon".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     "project-id".to_string(),
+    /// );
+    /// assert_eq!(config.streaming_voice_name().as_deref(), Some("en-US-Chirp3-HD-Charon"));
+    /// ```
+    pub fn streaming_voice_name(&self) -> Option<String> {
+        if let Some(name) = self.voice_name()
+            && is_chirp3_hd_voice(name)
+        {
+            return Some(name.to_string());
+        }
+
+        if !self.base.model.eq_ignore_ascii_case(CHIRP3_HD_MODEL) {
+            return None;
+        }
+        let voice = self.base.voice_id.as_deref().filter(|v| !v.is_empty())?;
+        Some(format!("{}-Chirp3-HD-{voice}", self.language_code))
+    }
+
     /// Returns the speaking rate, clamped to Google's valid range [0.25, 4.0].
     ///
     /// Returns `None` if no speaking rate is configured.
@@ -708,6 +757,45 @@ mod tests {
         assert_eq!(config.clamped_volume_gain(), None);
     }
 
+    #[test]
+    fn test_streaming_voice_name() {
+        let config = |voice_id: Option<&str>, model: &str| {
+            GoogleTTSConfig::from_base_config(
+                TTSConfig {
+                    voice_id: voice_id.map(str::to_string),
+                    model: model.to_string(),
+                    ..Default::default()
+                },
+                "test-project".to_string(),
+            )
+        };
+
+        assert_eq!(
+            config(Some("en-GB-Chirp3-HD-Kore"), "")
+                .streaming_voice_name()
+                .as_deref(),
+            Some("en-GB-Chirp3-HD-Kore")
+        );
+        assert_eq!(
+            config(None, "de-DE-Chirp3-HD-Puck")
+                .streaming_voice_name()
+                .as_deref(),
+            Some("de-DE-Chirp3-HD-Puck")
+        );
+        assert_eq!(
+            config(Some("Charon"), "chirp3-hd")
+                .streaming_voice_name()
+                .as_deref(),
+            Some("en-US-Chirp3-HD-Charon")
+        );
+        assert_eq!(
+            config(Some("en-US-Wavenet-D"), "").streaming_voice_name(),
+            None
+        );
+        assert_eq!(config(None, "chirp3-hd").streaming_voice_name(), None);
+        assert!(!is_chirp3_hd_voice("en-US-Chirp-HD-D"));
+    }
+
     #[test]
     fn test_serialization() {
         let config = GoogleTTSConfig {
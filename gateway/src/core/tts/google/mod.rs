@@ -8,6 +8,7 @@
 //! The module is organized into:
 //! - **config**: Google TTS-specific configuration (`GoogleTTSConfig`, `GoogleAudioEncoding`)
 //! - **provider**: Authentication wrapper (`TTSGoogleAuthClient`) and request builder (`GoogleRequestBuilder`)
+//! - **streaming**: Bidirectional streaming synthesis used for Chirp 3 HD voices
 //!
 //! # Usage
 //!
@@ -35,9 +36,13 @@
 
 mod config;
 mod provider;
+mod streaming;
 
 // Re-export configuration types
-pub use config::{GoogleAudioEncoding, GoogleTTSConfig};
+pub use config::{
+    CHIRP3_HD_MODEL, CHIRP3_HD_SAMPLE_RATE, GoogleAudioEncoding, GoogleTTSConfig,
+    is_chirp3_hd_voice,
+};
 
 // Re-export the auth wrapper for use by other modules
 pub use provider::TTSGoogleAuthClient;
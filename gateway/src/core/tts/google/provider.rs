@@ -40,11 +40,16 @@
 use crate::core::providers::google::{
     CredentialSource, GOOGLE_CLOUD_PLATFORM_SCOPE, GoogleAuthClient, GoogleError, TokenProvider,
 };
+use crate::core::providers::token;
 use crate::core::tts::base::TTSConfig;
 use crate::core::tts::base::TTSError;
 use crate::core::tts::provider::{PronunciationReplacer, TTSRequestBuilder};
+use crate::core::tts::segmentation::{SegmentationProfile, TextSegmenter};
 
-use super::config::GoogleTTSConfig;
+use super::config::{CHIRP3_HD_SAMPLE_RATE, GoogleTTSConfig};
+use super::streaming::{
+    GrpcStreamOpener, MAX_STREAM_INPUT_BYTES, StreamCommand, StreamingSession, build_config_request,
+};
 
 /// Google Cloud Text-to-Speech API endpoint.
 ///
@@ -89,9 +94,12 @@ pub(crate) fn google_error_to_tts(e: GoogleError) -> TTSError {
 /// The wrapper validates credentials during construction, catching configuration
 /// errors early with clear error messages rather than failing later during
 /// API calls.
+///
+/// Tokens are cached in a [`token::TokenProvider`] and refreshed ahead of
+/// expiry, so synthesis requests late in a long session stay authenticated.
 #[derive(Debug)]
 pub struct TTSGoogleAuthClient {
-    inner: GoogleAuthClient,
+    inner: token::TokenProvider,
 }
 
 impl TTSGoogleAuthClient {
@@ -124,10 +132,12 @@ impl TTSGoogleAuthClient {
         credential_source.validate().map_err(google_error_to_tts)?;
 
         // Create the inner client with the cloud platform scope
-        let inner = GoogleAuthClient::new(credential_source, &[GOOGLE_CLOUD_PLATFORM_SCOPE])
+        let client = GoogleAuthClient::new(credential_source, &[GOOGLE_CLOUD_PLATFORM_SCOPE])
             .map_err(google_error_to_tts)?;
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner: token::TokenProvider::new(client),
+        })
     }
 
     /// Creates a new auth client from an API key string.
@@ -173,8 +183,8 @@ impl TTSGoogleAuthClient {
 impl TokenProvider for TTSGoogleAuthClient {
     /// Retrieves a valid access token for Google Cloud TTS API.
     ///
-    /// The token is automatically refreshed when expired. This method delegates
-    /// to the inner `GoogleAuthClient` for actual token retrieval.
+    /// The token is cached and refreshed before it expires. Refreshes are
+    /// delegated to the inner `GoogleAuthClient`.
     ///
     /// Note: This returns `GoogleError` as required by the `TokenProvider` trait.
     /// Error conversion to `TTSError` should happen at the call site.
@@ -184,7 +194,10 @@ impl TokenProvider for TTSGoogleAuthClient {
     /// * `Ok(String)` - A valid OAuth2 access token
     /// * `Err(GoogleError)` - If token retrieval fails
     async fn get_token(&self) -> Result<String, GoogleError> {
-        self.inner.get_token().await
+        self.inner
+            .token()
+            .await
+            .map_err(|e| GoogleError::AuthenticationFailed(e.to_string()))
     }
 }
 
@@ -427,11 +440,14 @@ use base64::Engine;
 use serde::Deserialize;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, mpsc};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info};
 use xxhash_rust::xxh3::xxh3_128;
 
 use crate::core::cache::store::CacheStore;
+use crate::core::providers::google::{GOOGLE_TTS_ENDPOINT, create_grpc_channel};
+use crate::core::providers::headers::apply_custom_headers;
 use crate::core::tts::base::{AudioCallback, AudioData, BaseTTS, ConnectionState, TTSResult};
 use crate::utils::req_manager::ReqManager;
 
@@ -476,6 +492,11 @@ fn compute_google_tts_config_hash(config: &TTSConfig) -> String {
 ///
 /// This provider handles OAuth2 authentication, JSON parsing, base64 decoding,
 /// WAV header stripping for LINEAR16, and audio chunking for callbacks.
+///
+/// Chirp 3 HD voices are the exception: they use the gRPC streaming API
+/// instead (see [`GoogleTTSConfig::streaming_voice_name`]). Text is streamed
+/// to Google as it is spoken and audio is delivered as it arrives, always as
+/// 24kHz LINEAR16. Streamed audio is not cached.
 pub struct GoogleTTS {
     /// Base TTS configuration
     config: TTSConfig,
@@ -497,6 +518,12 @@ pub struct GoogleTTS {
     cache: Arc<RwLock<Option<Arc<CacheStore>>>>,
     /// Compiled pronunciation patterns for text replacement
     pronunciation_replacer: Option<PronunciationReplacer>,
+    /// Chirp 3 HD voice synthesized over the streaming API (None for REST voices)
+    streaming_voice: Option<String>,
+    /// Commands for the streaming session (set while connected in streaming mode)
+    stream_commands: Option<mpsc::UnboundedSender<StreamCommand>>,
+    /// Streaming session task
+    stream_handle: Option<JoinHandle<()>>,
 }
 
 impl GoogleTTS {
@@ -549,6 +576,7 @@ impl GoogleTTS {
 
         // Create Google-specific config from base config
         let google_config = GoogleTTSConfig::from_base_config(config.clone(), project_id);
+        let streaming_voice = google_config.streaming_voice_name();
 
         // Create pronunciation replacer if pronunciations are configured
         let pronunciation_replacer = if !config.pronunciations.is_empty() {
@@ -571,6 +599,45 @@ impl GoogleTTS {
             config_hash,
             cache: Arc::new(RwLock::new(None)),
             pronunciation_replacer,
+            streaming_voice,
+            stream_commands: None,
+            stream_handle: None,
+        })
+    }
+
+    /// Opens the gRPC channel and starts the streaming session.
+    async fn start_streaming(&mut self, voice_name: &str) -> TTSResult<()> {
+        let channel = create_grpc_channel(GOOGLE_TTS_ENDPOINT)
+            .await
+            .map_err(google_error_to_tts)?;
+        let opener = GrpcStreamOpener::new(
+            channel,
+            self.auth_client.clone(),
+            self.config.custom_headers.clone(),
+        );
+        let session = StreamingSession::new(
+            Arc::new(opener),
+            build_config_request(&self.google_config, voice_name),
+            self.audio_callback.clone(),
+            TextSegmenter::new(
+                SegmentationProfile::for_language(&self.google_config.language_code),
+                MAX_STREAM_INPUT_BYTES,
+            ),
+        );
+        let (commands, handle) = session.spawn();
+        self.stream_commands = Some(commands);
+        self.stream_handle = Some(handle);
+        info!(voice = %voice_name, "Google TTS streaming session started");
+        Ok(())
+    }
+
+    /// Sends a command to the streaming session.
+    fn send_stream_command(&self, command: StreamCommand) -> TTSResult<()> {
+        let commands = self.stream_commands.as_ref().ok_or_else(|| {
+            TTSError::ProviderNotReady("Google TTS streaming session not started".to_string())
+        })?;
+        commands.send(command).map_err(|_| {
+            TTSError::ConnectionFailed("Google TTS streaming session ended".to_string())
         })
     }
 
@@ -709,8 +776,13 @@ impl BaseTTS for GoogleTTS {
     }
 
     async fn connect(&mut self) -> TTSResult<()> {
-        // Google TTS is a stateless REST API - no persistent connection needed
-        // Just mark as connected and ready to accept requests
+        // Chirp 3 HD voices stream over gRPC; every other voice uses the
+        // stateless REST API and needs no persistent connection
+        if let Some(voice_name) = self.streaming_voice.clone()
+            && self.stream_commands.is_none()
+        {
+            self.start_streaming(&voice_name).await?;
+        }
         self.connected.store(true, Ordering::Release);
         info!("Google TTS provider ready");
         Ok(())
@@ -718,6 +790,10 @@ impl BaseTTS for GoogleTTS {
 
     async fn disconnect(&mut self) -> TTSResult<()> {
         self.connected.store(false, Ordering::Release);
+        self.stream_commands = None;
+        if let Some(handle) = self.stream_handle.take() {
+            handle.abort();
+        }
         *self.audio_callback.write().await = None;
         *self.cache.write().await = None;
         info!("Google TTS provider disconnected");
@@ -736,7 +812,7 @@ impl BaseTTS for GoogleTTS {
         }
     }
 
-    async fn speak(&mut self, text: &str, _flush: bool) -> TTSResult<()> {
+    async fn speak(&mut self, text: &str, flush: bool) -> TTSResult<()> {
         // Phase 1: Validation and Setup
         let text = text.trim();
         if text.is_empty() {
@@ -756,6 +832,15 @@ impl BaseTTS for GoogleTTS {
             text.to_string()
         };
 
+        // Streaming voices hand the text to the session, which delivers audio as it arrives
+        if self.streaming_voice.is_some() {
+            self.send_stream_command(StreamCommand::Text(processed_text))?;
+            if flush {
+                self.send_stream_command(StreamCommand::Flush)?;
+            }
+            return Ok(());
+        }
+
         // Phase 3: Cache Lookup
         let text_hash = format!("{:032x}", xxh3_128(processed_text.as_bytes()));
         let cache_key = format!("{}:{}", self.config_hash, text_hash);
@@ -801,14 +886,14 @@ impl BaseTTS for GoogleTTS {
             })?;
             let request =
                 request_builder.build_http_request(client_guard.client(), &processed_text);
-            request
+            apply_custom_headers(request, &self.config.custom_headers)
                 .send()
                 .await
                 .map_err(|e| TTSError::NetworkError(format!("HTTP request failed: {e}")))?
         } else {
             // Fallback to standalone client
             let request = request_builder.build_http_request(&self.http_client, &processed_text);
-            request
+            apply_custom_headers(request, &self.config.custom_headers)
                 .send()
                 .await
                 .map_err(|e| TTSError::NetworkError(format!("HTTP request failed: {e}")))?
@@ -867,13 +952,18 @@ impl BaseTTS for GoogleTTS {
     }
 
     async fn clear(&mut self) -> TTSResult<()> {
-        // Google TTS is stateless - no queued requests to cancel
-        // Just return Ok
+        // REST requests are stateless - only a streaming session has queued text
+        if self.stream_commands.is_some() {
+            self.send_stream_command(StreamCommand::Clear)?;
+        }
         Ok(())
     }
 
     async fn flush(&self) -> TTSResult<()> {
-        // No-op for Google TTS - requests are synchronous
+        // REST requests complete synchronously - only a streaming session buffers text
+        if self.stream_commands.is_some() {
+            self.send_stream_command(StreamCommand::Flush)?;
+        }
         Ok(())
     }
 
@@ -901,6 +991,19 @@ impl BaseTTS for GoogleTTS {
     }
 
     fn get_provider_info(&self) -> serde_json::Value {
+        if let Some(voice_name) = &self.streaming_voice {
+            return serde_json::json!({
+                "provider": "google",
+                "version": "1.0.0",
+                "api_type": "gRPC streaming",
+                "endpoint": GOOGLE_TTS_ENDPOINT,
+                "voice": voice_name,
+                "supported_formats": ["LINEAR16"],
+                "supported_sample_rates": [CHIRP3_HD_SAMPLE_RATE],
+                "documentation": "https://cloud.google.com/text-to-speech/docs/chirp3-hd"
+            });
+        }
+
         serde_json::json!({
             "provider": "google",
             "version": "1.0.0",
@@ -1114,7 +1217,12 @@ mod tests {
             request_timeout: Some(60),
             pronunciations: Vec::new(),
             request_pool_size: Some(4),
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         }
     }
 
@@ -0,0 +1,705 @@
+//! Streaming synthesis for Google Chirp 3 HD voices.
+//!
+//! Chirp 3 HD voices are synthesized over the bidirectional
+//! `StreamingSynthesize` gRPC method: the first request on a stream selects
+//! the voice, later requests carry text, and 24kHz LINEAR16 audio streams
+//! back while text is still being sent. Half-closing the request side ends
+//! the stream once the remaining audio has been returned.
+//!
+//! A stream only accepts a limited amount of input, so a
+//! [`StreamingSession`] splits text into sentences and rolls over to a new
+//! stream at a sentence boundary before the limit is reached. Audio from the
+//! next stream is only read after the previous stream has finished, so the
+//! callback receives audio in the order the text was spoken.
+
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use google_api_proto::google::cloud::texttospeech::v1::{
+    StreamingSynthesisInput, StreamingSynthesizeConfig, StreamingSynthesizeRequest,
+    StreamingSynthesizeResponse, VoiceSelectionParams, streaming_synthesis_input::InputSource,
+    streaming_synthesize_request::StreamingRequest, text_to_speech_client::TextToSpeechClient,
+};
+use tokio::sync::{RwLock, mpsc};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::core::providers::google::{GoogleError, TokenProvider};
+use crate::core::providers::headers::insert_custom_metadata;
+use crate::core::tts::base::{AudioCallback, AudioData, TTSError, TTSResult};
+use crate::core::tts::segmentation::TextSegmenter;
+
+use super::config::{CHIRP3_HD_SAMPLE_RATE, GoogleTTSConfig};
+use super::provider::{TTSGoogleAuthClient, google_error_to_tts};
+
+/// Text sent to one stream before rolling over to a new stream, in bytes.
+///
+/// Kept below Google's per-stream input limit so a full sentence always fits
+/// after the rollover check.
+pub(super) const MAX_STREAM_INPUT_BYTES: usize = 4_000;
+
+/// Requests buffered between the session and the gRPC request stream.
+const REQUEST_CHANNEL_SIZE: usize = 32;
+
+/// Audio format of streamed audio as reported in [`AudioData::format`].
+const STREAMING_AUDIO_FORMAT: &str = "linear16";
+
+type ResponseStream =
+    Pin<Box<dyn Stream<Item = Result<StreamingSynthesizeResponse, tonic::Status>> + Send>>;
+
+/// Builds the first request of a stream, selecting the voice.
+pub(super) fn build_config_request(
+    config: &GoogleTTSConfig,
+    voice_name: &str,
+) -> StreamingSynthesizeRequest {
+    let streaming_config = StreamingSynthesizeConfig {
+        voice: Some(VoiceSelectionParams {
+            language_code: config.language_code.clone(),
+            name: voice_name.to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    StreamingSynthesizeRequest {
+        streaming_request: Some(StreamingRequest::StreamingConfig(streaming_config)),
+    }
+}
+
+/// Builds a request carrying text to synthesize.
+pub(super) fn build_text_request(text: String) -> StreamingSynthesizeRequest {
+    StreamingSynthesizeRequest {
+        streaming_request: Some(StreamingRequest::Input(StreamingSynthesisInput {
+            input_source: Some(InputSource::Text(text)),
+        })),
+    }
+}
+
+/// Converts a response into audio for the callback.
+///
+/// Returns `None` for responses without audio.
+pub(super) fn response_audio(response: StreamingSynthesizeResponse) -> Option<AudioData> {
+    if response.audio_content.is_empty() {
+        return None;
+    }
+
+    let samples = response.audio_content.len() / 2;
+    Some(AudioData {
+        data: response.audio_content.to_vec(),
+        sample_rate: CHIRP3_HD_SAMPLE_RATE,
+        format: STREAMING_AUDIO_FORMAT.to_string(),
+        duration_ms: Some((samples as u64 * 1000 / u64::from(CHIRP3_HD_SAMPLE_RATE)) as u32),
+    })
+}
+
+/// Splits text into trimmed sentences of at most `segmenter.max_bytes()` bytes.
+///
+/// Sentences end at the script terminators [`TextSegmenter`] knows; an
+/// over-long sentence is cut at whitespace, a clause mark or a word boundary,
+/// never inside a grapheme cluster.
+pub(super) fn split_sentences(segmenter: &TextSegmenter, text: &str) -> Vec<String> {
+    segmenter
+        .split(text)
+        .into_iter()
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Opens `StreamingSynthesize` streams.
+///
+/// Abstracted so the session can be driven by recorded responses in tests.
+#[async_trait]
+pub(super) trait StreamOpener: Send + Sync {
+    /// Starts a stream that sends `requests` and returns its responses.
+    async fn open(
+        &self,
+        requests: mpsc::Receiver<StreamingSynthesizeRequest>,
+    ) -> TTSResult<ResponseStream>;
+}
+
+/// Opens streams over a shared gRPC channel with a current access token.
+pub(super) struct GrpcStreamOpener {
+    channel: tonic::transport::Channel,
+    auth_client: Arc<TTSGoogleAuthClient>,
+    custom_headers: HashMap<String, String>,
+}
+
+impl GrpcStreamOpener {
+    pub(super) fn new(
+        channel: tonic::transport::Channel,
+        auth_client: Arc<TTSGoogleAuthClient>,
+        custom_headers: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            channel,
+            auth_client,
+            custom_headers,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamOpener for GrpcStreamOpener {
+    async fn open(
+        &self,
+        mut requests: mpsc::Receiver<StreamingSynthesizeRequest>,
+    ) -> TTSResult<ResponseStream> {
+        // Each stream gets a current token; the provider refreshes it ahead of expiry
+        let token = self
+            .auth_client
+            .get_token()
+            .await
+            .map_err(google_error_to_tts)?;
+        let auth_value: tonic::metadata::MetadataValue<_> =
+            format!("Bearer {token}").parse().map_err(|_| {
+                TTSError::ConnectionFailed("Failed to parse authorization header".to_string())
+            })?;
+
+        let custom_headers = self.custom_headers.clone();
+        let mut client = TextToSpeechClient::with_interceptor(
+            self.channel.clone(),
+            move |mut req: tonic::Request<()>| {
+                insert_custom_metadata(req.metadata_mut(), &custom_headers);
+                req.metadata_mut()
+                    .insert("authorization", auth_value.clone());
+                Ok(req)
+            },
+        );
+
+        let request_stream = async_stream::stream! {
+            while let Some(request) = requests.recv().await {
+                yield request;
+            }
+        };
+
+        let response = client
+            .streaming_synthesize(request_stream)
+            .await
+            .map_err(|status| google_error_to_tts(GoogleError::categorize_grpc_error(status)))?;
+        Ok(Box::pin(response.into_inner()))
+    }
+}
+
+/// Commands sent from the provider to its streaming session.
+#[derive(Debug)]
+pub(super) enum StreamCommand {
+    /// Text to synthesize after everything queued before it
+    Text(String),
+    /// Finish the current stream and report completion once its audio is delivered
+    Flush,
+    /// Drop the current stream and all queued text
+    Clear,
+}
+
+/// Work queued behind the current stream.
+enum Pending {
+    Sentence(String),
+    Flush,
+}
+
+/// A stream that is being written to or drained.
+struct ActiveStream {
+    /// Request sender; `None` once the stream is half-closed
+    requests: Option<mpsc::Sender<StreamingSynthesizeRequest>>,
+    responses: ResponseStream,
+    input_bytes: usize,
+    /// Report completion when the stream ends
+    complete_on_end: bool,
+}
+
+impl ActiveStream {
+    fn close(&mut self) {
+        self.requests = None;
+    }
+}
+
+/// Feeds text into successive `StreamingSynthesize` streams.
+///
+/// The session runs as a task and is driven by [`StreamCommand`]s. It opens
+/// a stream when text arrives, rolls over to a new stream when the next
+/// sentence would exceed the segmenter's `max_bytes`, and calls `on_complete` once a
+/// flushed stream has returned all of its audio.
+pub(super) struct StreamingSession {
+    opener: Arc<dyn StreamOpener>,
+    config_request: StreamingSynthesizeRequest,
+    callback: Arc<RwLock<Option<Arc<dyn AudioCallback>>>>,
+    /// Splits text into sentences that fit a stream
+    segmenter: TextSegmenter,
+    active: Option<ActiveStream>,
+    pending: VecDeque<Pending>,
+}
+
+impl StreamingSession {
+    pub(super) fn new(
+        opener: Arc<dyn StreamOpener>,
+        config_request: StreamingSynthesizeRequest,
+        callback: Arc<RwLock<Option<Arc<dyn AudioCallback>>>>,
+        segmenter: TextSegmenter,
+    ) -> Self {
+        Self {
+            opener,
+            config_request,
+            callback,
+            segmenter,
+            active: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Runs the session until the command sender is dropped.
+    pub(super) fn spawn(self) -> (mpsc::UnboundedSender<StreamCommand>, JoinHandle<()>) {
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(self.run(command_rx));
+        (command_tx, handle)
+    }
+
+    async fn run(mut self, mut commands: mpsc::UnboundedReceiver<StreamCommand>) {
+        loop {
+            if let Err(e) = self.pump().await {
+                error!("Google TTS streaming failed: {}", e);
+                self.active = None;
+                self.pending.clear();
+                self.report_error(e).await;
+            }
+
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(StreamCommand::Text(text)) => {
+                        let sentences = split_sentences(&self.segmenter, &text);
+                        self.pending.extend(sentences.into_iter().map(Pending::Sentence));
+                    }
+                    Some(StreamCommand::Flush) => self.pending.push_back(Pending::Flush),
+                    Some(StreamCommand::Clear) => {
+                        debug!("Clearing Google TTS stream");
+                        self.active = None;
+                        self.pending.clear();
+                    }
+                    None => break,
+                },
+                response = next_response(&mut self.active) => self.on_response(response).await,
+            }
+        }
+        debug!("Google TTS streaming session ended");
+    }
+
+    /// Moves queued work into the current stream as far as possible.
+    async fn pump(&mut self) -> TTSResult<()> {
+        while let Some(next) = self.pending.front() {
+            match next {
+                Pending::Flush => {
+                    match self.active.as_mut() {
+                        Some(stream) => {
+                            stream.close();
+                            stream.complete_on_end = true;
+                        }
+                        None => self.complete().await,
+                    }
+                    self.pending.pop_front();
+                }
+                Pending::Sentence(sentence) => {
+                    let len = sentence.len();
+                    if let Some(stream) = self.active.as_mut() {
+                        if stream.requests.is_none() {
+                            // Wait for the previous stream to return its audio
+                            return Ok(());
+                        }
+                        if stream.input_bytes > 0
+                            && stream.input_bytes + len > self.segmenter.max_bytes()
+                        {
+                            debug!(
+                                input_bytes = stream.input_bytes,
+                                "Google TTS stream input limit reached, rolling over"
+                            );
+                            stream.close();
+                            return Ok(());
+                        }
+                    } else {
+                        self.active = Some(self.open().await?);
+                    }
+
+                    let Some(Pending::Sentence(sentence)) = self.pending.pop_front() else {
+                        unreachable!("front of the queue is a sentence");
+                    };
+                    let stream = self.active.as_mut().expect("stream opened above");
+                    let requests = stream.requests.as_ref().expect("stream is open");
+                    requests
+                        .send(build_text_request(sentence))
+                        .await
+                        .map_err(|_| {
+                            TTSError::ConnectionFailed(
+                                "Google TTS stream closed while sending text".to_string(),
+                            )
+                        })?;
+                    stream.input_bytes += len;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn open(&self) -> TTSResult<ActiveStream> {
+        let (requests, request_rx) = mpsc::channel(REQUEST_CHANNEL_SIZE);
+        requests
+            .send(self.config_request.clone())
+            .await
+            .map_err(|_| TTSError::InternalError("Request channel closed".to_string()))?;
+        let responses = self.opener.open(request_rx).await?;
+        info!("Opened Google TTS synthesis stream");
+        Ok(ActiveStream {
+            requests: Some(requests),
+            responses,
+            input_bytes: 0,
+            complete_on_end: false,
+        })
+    }
+
+    async fn on_response(
+        &mut self,
+        response: Option<Result<StreamingSynthesizeResponse, tonic::Status>>,
+    ) {
+        match response {
+            Some(Ok(response)) => {
+                if let Some(audio) = response_audio(response)
+                    && let Some(callback) = self.callback.read().await.clone()
+                {
+                    callback.on_audio(audio).await;
+                }
+            }
+            Some(Err(status)) => {
+                let e = google_error_to_tts(GoogleError::categorize_grpc_error(status));
+                error!("Google TTS stream error: {}", e);
+                self.active = None;
+                self.pending.clear();
+                self.report_error(e).await;
+            }
+            None => {
+                debug!("Google TTS synthesis stream finished");
+                if self
+                    .active
+                    .take()
+                    .is_some_and(|stream| stream.complete_on_end)
+                {
+                    self.complete().await;
+                }
+            }
+        }
+    }
+
+    async fn complete(&self) {
+        if let Some(callback) = self.callback.read().await.clone() {
+            callback.on_complete().await;
+        }
+    }
+
+    async fn report_error(&self, e: TTSError) {
+        if let Some(callback) = self.callback.read().await.clone() {
+            callback.on_error(e).await;
+        }
+    }
+}
+
+/// Next response of the active stream; never resolves without one.
+async fn next_response(
+    active: &mut Option<ActiveStream>,
+) -> Option<Result<StreamingSynthesizeResponse, tonic::Status>> {
+    match active {
+        Some(stream) => stream.responses.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use prost::Message;
+    use std::future::Future;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    use crate::core::tts::base::TTSConfig;
+    use crate::core::tts::segmentation::SegmentationProfile;
+
+    /// Response frames recorded from a Chirp 3 HD stream (protobuf wire format)
+    const RECORDED_FRAMES: [&str; 3] = [
+        // audio_content: 4 samples of LINEAR16
+        "0a080000fa0f0c08e3f4",
+        // audio_content: 2 samples
+        "0a04f7fe1101",
+        // empty response
+        "",
+    ];
+
+    fn recorded_frames() -> Vec<StreamingSynthesizeResponse> {
+        RECORDED_FRAMES
+            .iter()
+            .map(|frame| {
+                StreamingSynthesizeResponse::decode(hex::decode(frame).unwrap().as_slice()).unwrap()
+            })
+            .collect()
+    }
+
+    /// Answers every text request with the recorded frames
+    #[derive(Default)]
+    struct RecordedOpener {
+        /// Requests received by each opened stream
+        streams: Arc<Mutex<Vec<Vec<StreamingSynthesizeRequest>>>>,
+    }
+
+    #[async_trait]
+    impl StreamOpener for RecordedOpener {
+        async fn open(
+            &self,
+            mut requests: mpsc::Receiver<StreamingSynthesizeRequest>,
+        ) -> TTSResult<ResponseStream> {
+            let streams = self.streams.clone();
+            let index = {
+                let mut streams = streams.lock();
+                streams.push(Vec::new());
+                streams.len() - 1
+            };
+            let (response_tx, mut response_rx) = mpsc::unbounded_channel();
+            tokio::spawn(async move {
+                while let Some(request) = requests.recv().await {
+                    let is_text =
+                        matches!(request.streaming_request, Some(StreamingRequest::Input(_)));
+                    streams.lock()[index].push(request);
+                    if is_text {
+                        for frame in recorded_frames() {
+                            let _ = response_tx.send(Ok(frame));
+                        }
+                    }
+                }
+                // Request side half-closed: the stream ends after its audio
+            });
+            Ok(Box::pin(async_stream::stream! {
+                while let Some(response) = response_rx.recv().await {
+                    yield response;
+                }
+            }))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingCallback {
+        audio: Mutex<Vec<AudioData>>,
+        completions: Mutex<usize>,
+        errors: Mutex<Vec<String>>,
+        completed: Notify,
+    }
+
+    impl AudioCallback for RecordingCallback {
+        fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            Box::pin(async move { self.audio.lock().push(audio_data) })
+        }
+
+        fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            Box::pin(async move { self.errors.lock().push(error.to_string()) })
+        }
+
+        fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            Box::pin(async move {
+                *self.completions.lock() += 1;
+                self.completed.notify_one();
+            })
+        }
+    }
+
+    fn google_config(voice_id: &str) -> GoogleTTSConfig {
+        GoogleTTSConfig::from_base_config(
+            TTSConfig {
+                voice_id: Some(voice_id.to_string()),
+                ..Default::default()
+            },
+            "test-project".to_string(),
+        )
+    }
+
+    fn start_session(
+        max_stream_bytes: usize,
+    ) -> (
+        mpsc::UnboundedSender<StreamCommand>,
+        Arc<RecordingCallback>,
+        Arc<Mutex<Vec<Vec<StreamingSynthesizeRequest>>>>,
+    ) {
+        let opener = RecordedOpener::default();
+        let streams = opener.streams.clone();
+        let callback = Arc::new(RecordingCallback::default());
+        let registered: Arc<dyn AudioCallback> = callback.clone();
+        let config = google_config("en-US-Chirp3-HD-Charon");
+        let session = StreamingSession::new(
+            Arc::new(opener),
+            build_config_request(&config, "en-US-Chirp3-HD-Charon"),
+            Arc::new(RwLock::new(Some(registered))),
+            TextSegmenter::new(SegmentationProfile::Spaced, max_stream_bytes),
+        );
+        let (commands, _handle) = session.spawn();
+        (commands, callback, streams)
+    }
+
+    fn stream_texts(stream: &[StreamingSynthesizeRequest]) -> Vec<String> {
+        stream
+            .iter()
+            .filter_map(|request| match &request.streaming_request {
+                Some(StreamingRequest::Input(StreamingSynthesisInput {
+                    input_source: Some(InputSource::Text(text)),
+                })) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_config_request() {
+        let config = google_config("en-GB-Chirp3-HD-Kore");
+        let request = build_config_request(&config, "en-GB-Chirp3-HD-Kore");
+        let Some(StreamingRequest::StreamingConfig(streaming_config)) = request.streaming_request
+        else {
+            panic!("first request must carry the streaming config");
+        };
+        let voice = streaming_config.voice.unwrap();
+        assert_eq!(voice.name, "en-GB-Chirp3-HD-Kore");
+        assert_eq!(voice.language_code, "en-GB");
+    }
+
+    #[test]
+    fn test_recorded_frames_decode_to_audio() {
+        let frames = recorded_frames();
+        let audio: Vec<AudioData> = frames.into_iter().filter_map(response_audio).collect();
+        assert_eq!(audio.len(), 2, "empty responses carry no audio");
+        assert_eq!(audio[0].data, hex::decode("0000fa0f0c08e3f4").unwrap());
+        assert_eq!(audio[0].sample_rate, 24_000);
+        assert_eq!(audio[0].format, "linear16");
+        assert_eq!(audio[1].data.len(), 4);
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let split = |text, max_bytes| {
+            split_sentences(
+                &TextSegmenter::new(SegmentationProfile::Spaced, max_bytes),
+                text,
+            )
+        };
+        assert_eq!(
+            split("Hello there. How are you? Fine!", 100),
+            vec!["Hello there.", "How are you?", "Fine!"]
+        );
+        assert_eq!(split("  ", 100), Vec::<String>::new());
+        assert_eq!(
+            split("no punctuation at all", 100),
+            vec!["no punctuation at all"]
+        );
+
+        // Over-long sentences are split at whitespace
+        let sentences = split("one two three four five.", 10);
+        assert!(sentences.iter().all(|s| s.len() <= 10));
+        assert_eq!(sentences.join(" "), "one two three four five.");
+
+        // Multi-byte text is never split inside a character
+        let sentences = split("ééééé", 3);
+        assert!(sentences.iter().all(|s| s.len() <= 3));
+        assert_eq!(sentences.concat(), "ééééé");
+
+        // Japanese sentences end at full-width terminators
+        let japanese = TextSegmenter::new(SegmentationProfile::Cjk, 100);
+        assert_eq!(
+            split_sentences(&japanese, "はい。そうです！"),
+            vec!["はい。", "そうです！"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streams_text_and_completes_on_flush() {
+        let (commands, callback, streams) = start_session(MAX_STREAM_INPUT_BYTES);
+
+        commands
+            .send(StreamCommand::Text("Hello there. How are you?".to_string()))
+            .unwrap();
+        commands.send(StreamCommand::Flush).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), callback.completed.notified())
+            .await
+            .expect("flush should complete");
+
+        let streams = streams.lock();
+        assert_eq!(streams.len(), 1);
+        assert!(matches!(
+            streams[0][0].streaming_request,
+            Some(StreamingRequest::StreamingConfig(_))
+        ));
+        assert_eq!(stream_texts(&streams[0]), ["Hello there.", "How are you?"]);
+        // Two audio frames per sentence
+        assert_eq!(callback.audio.lock().len(), 4);
+        assert_eq!(*callback.completions.lock(), 1);
+        assert!(callback.errors.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rolls_to_new_stream_at_sentence_boundary() {
+        let (commands, callback, streams) = start_session(30);
+
+        let text = "The first sentence is here. The second one follows. And a third.";
+        commands
+            .send(StreamCommand::Text(text.to_string()))
+            .unwrap();
+        commands.send(StreamCommand::Flush).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), callback.completed.notified())
+            .await
+            .expect("flush should complete");
+
+        let streams = streams.lock();
+        assert_eq!(streams.len(), 3, "each sentence needs its own stream");
+        for stream in streams.iter() {
+            assert!(matches!(
+                stream[0].streaming_request,
+                Some(StreamingRequest::StreamingConfig(_))
+            ));
+            let texts = stream_texts(stream);
+            assert!(texts.iter().map(String::len).sum::<usize>() <= 30);
+        }
+        let sent: Vec<String> = streams.iter().flat_map(|s| stream_texts(s)).collect();
+        assert_eq!(sent.join(" "), text);
+
+        // Rolling over is invisible to the caller: one completion at the end
+        assert_eq!(callback.audio.lock().len(), 6);
+        assert_eq!(*callback.completions.lock(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_flush_without_text_completes_immediately() {
+        let (commands, callback, streams) = start_session(MAX_STREAM_INPUT_BYTES);
+
+        commands.send(StreamCommand::Flush).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), callback.completed.notified())
+            .await
+            .expect("flush should complete");
+        assert!(streams.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_clear_drops_current_stream() {
+        let (commands, callback, streams) = start_session(MAX_STREAM_INPUT_BYTES);
+
+        commands
+            .send(StreamCommand::Text("Interrupted speech.".to_string()))
+            .unwrap();
+        commands.send(StreamCommand::Clear).unwrap();
+        commands
+            .send(StreamCommand::Text("Next reply.".to_string()))
+            .unwrap();
+        commands.send(StreamCommand::Flush).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), callback.completed.notified())
+            .await
+            .expect("flush should complete");
+
+        let streams = streams.lock();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(stream_texts(&streams[1]), ["Next reply."]);
+        assert_eq!(*callback.completions.lock(), 1);
+    }
+}
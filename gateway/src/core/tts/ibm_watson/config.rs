@@ -25,7 +25,7 @@ use serde::{Deserialize, Serialize};
 // =============================================================================
 
 /// IBM Watson IAM authentication endpoint (shared with STT).
-pub const IBM_IAM_URL: &str = "https://iam.cloud.ibm.com/identity/token";
+pub use crate::core::providers::ibm::IBM_IAM_URL;
 
 /// Default IBM Watson TTS voice for English (US).
 pub const DEFAULT_VOICE: &str = "en-US_AllisonV3Voice";
@@ -628,7 +628,12 @@ impl Default for IbmWatsonTTSConfig {
                 request_timeout: Some(60),
                 pronunciations: Vec::new(),
                 request_pool_size: Some(4),
+                utterance_timeout: Some(10),
                 emotion_config: None,
+                custom_headers: Default::default(),
+                output_profile: None,
+                context_hints: None,
+                visemes: None,
             },
             region: IbmRegion::default(),
             instance_id: String::new(),
@@ -14,7 +14,7 @@
 //!
 //! IBM Watson TTS uses IAM token-based authentication:
 //! 1. API key is exchanged for a bearer token via IAM endpoint
-//! 2. Token is cached and automatically refreshed before expiration
+//! 2. Token is cached in a shared `TokenProvider` and refreshed at 80% of its lifetime
 //! 3. Token is included in Authorization header for all requests
 //!
 //! # Example
@@ -45,16 +45,18 @@
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use reqwest::Client;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
-use url::form_urlencoded;
 
-use super::config::{IBM_IAM_URL, IbmOutputFormat, IbmVoice, IbmWatsonTTSConfig, MAX_TEXT_LENGTH};
+use super::config::{IbmOutputFormat, IbmVoice, IbmWatsonTTSConfig, MAX_TEXT_LENGTH};
+use crate::core::providers::headers::apply_custom_headers;
+use crate::core::providers::ibm::iam_token_provider;
+use crate::core::providers::token::TokenProvider;
 use crate::core::stt::ibm_watson::IbmRegion;
 use crate::core::tts::base::{
     AudioCallback, AudioData, BaseTTS, ConnectionState, TTSConfig, TTSError, TTSResult,
@@ -64,86 +66,6 @@ use crate::utils::req_manager::ReqManager;
 /// IBM Watson TTS API base URL (for documentation purposes).
 pub const IBM_WATSON_TTS_URL: &str = "https://api.us-south.text-to-speech.watson.cloud.ibm.com";
 
-// =============================================================================
-// IAM Token Management
-// =============================================================================
-
-/// IAM token response from IBM Cloud.
-#[derive(Debug, Clone)]
-struct IamToken {
-    /// The access token
-    access_token: String,
-    /// Token expiration time
-    expires_at: Instant,
-}
-
-impl IamToken {
-    /// Check if the token is expired or about to expire (within 5 minutes).
-    fn is_expired(&self) -> bool {
-        self.expires_at <= Instant::now() + Duration::from_secs(300)
-    }
-}
-
-/// IAM token response from IBM Cloud API.
-#[derive(Debug, serde::Deserialize)]
-struct IamTokenResponse {
-    access_token: String,
-    #[serde(default)]
-    expires_in: u64,
-}
-
-/// Fetch IAM token from IBM Cloud.
-async fn fetch_iam_token(api_key: &str) -> TTSResult<IamToken> {
-    let client = Client::new();
-
-    // URL-encode the API key
-    let encoded_api_key: String = form_urlencoded::byte_serialize(api_key.as_bytes()).collect();
-
-    let response = client
-        .post(IBM_IAM_URL)
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .body(format!(
-            "grant_type=urn:ibm:params:oauth:grant-type:apikey&apikey={}",
-            encoded_api_key
-        ))
-        .send()
-        .await
-        .map_err(|e| TTSError::ConnectionFailed(format!("Failed to fetch IAM token: {}", e)))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(TTSError::ConnectionFailed(format!(
-            "IAM token request failed with status {}: {}",
-            status, body
-        )));
-    }
-
-    let token_response: IamTokenResponse = response
-        .json()
-        .await
-        .map_err(|e| TTSError::ConnectionFailed(format!("Failed to parse IAM token: {}", e)))?;
-
-    // Default to 1 hour if expires_in is not provided
-    let expires_in = if token_response.expires_in > 0 {
-        token_response.expires_in
-    } else {
-        3600
-    };
-
-    let expires_at = Instant::now() + Duration::from_secs(expires_in);
-
-    debug!(
-        "IAM token fetched successfully, expires in {} seconds",
-        expires_in
-    );
-
-    Ok(IamToken {
-        access_token: token_response.access_token,
-        expires_at,
-    })
-}
-
 // =============================================================================
 // IBM Watson TTS Provider
 // =============================================================================
@@ -165,8 +87,8 @@ pub struct IbmWatsonTTS {
     config: IbmWatsonTTSConfig,
     /// HTTP client for API requests
     client: Arc<RwLock<Option<Client>>>,
-    /// Cached IAM token
-    token: Arc<RwLock<Option<IamToken>>>,
+    /// IAM token provider for the configured API key
+    token_provider: Arc<TokenProvider>,
     /// Connection state
     connected: Arc<AtomicBool>,
     /// Audio callback
@@ -209,9 +131,9 @@ impl IbmWatsonTTS {
         };
 
         Ok(Self {
+            token_provider: iam_token_provider(&ibm_config.base.api_key),
             config: ibm_config,
             client: Arc::new(RwLock::new(None)),
-            token: Arc::new(RwLock::new(None)),
             connected: Arc::new(AtomicBool::new(false)),
             audio_callback: Arc::new(RwLock::new(None)),
             request_counter: Arc::new(AtomicU64::new(0)),
@@ -223,9 +145,9 @@ impl IbmWatsonTTS {
     /// Use this when you want full control over IBM-specific settings.
     pub fn new_from_ibm_config(config: IbmWatsonTTSConfig) -> TTSResult<Self> {
         Ok(Self {
+            token_provider: iam_token_provider(&config.base.api_key),
             config,
             client: Arc::new(RwLock::new(None)),
-            token: Arc::new(RwLock::new(None)),
             connected: Arc::new(AtomicBool::new(false)),
             audio_callback: Arc::new(RwLock::new(None)),
             request_counter: Arc::new(AtomicU64::new(0)),
@@ -278,25 +200,10 @@ impl IbmWatsonTTS {
 
     /// Get or refresh the IAM access token.
     async fn get_access_token(&self) -> TTSResult<String> {
-        // Check cached token
-        {
-            let token_guard = self.token.read().await;
-            if let Some(ref token) = *token_guard
-                && !token.is_expired()
-            {
-                return Ok(token.access_token.clone());
-            }
-        }
-
-        // Fetch new token
-        debug!("Fetching new IAM token...");
-        let new_token = fetch_iam_token(&self.config.base.api_key).await?;
-        let access_token = new_token.access_token.clone();
-
-        // Cache the token
-        *self.token.write().await = Some(new_token);
-
-        Ok(access_token)
+        self.token_provider
+            .token()
+            .await
+            .map_err(|e| TTSError::ConnectionFailed(format!("Failed to fetch IAM token: {}", e)))
     }
 
     /// Synthesize text to audio using IBM Watson TTS.
@@ -345,12 +252,14 @@ impl IbmWatsonTTS {
         let accept_header = self.config.accept_header();
 
         // Make the request
-        let response = client
+        let request = client
             .post(url)
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", "application/json")
             .header("Accept", accept_header)
-            .body(body)
+            .body(body);
+
+        let response = apply_custom_headers(request, &self.config.base.custom_headers)
             .send()
             .await
             .map_err(|e| {
@@ -373,8 +282,8 @@ impl IbmWatsonTTS {
             let error = match status.as_u16() {
                 400 => TTSError::InvalidConfiguration(format!("Bad request: {}", body)),
                 401 => {
-                    // Clear cached token so next request will refresh
-                    // Note: This is handled by caller through token refresh
+                    // Drop the rejected token so the next request fetches a new one
+                    self.token_provider.invalidate();
                     TTSError::ConnectionFailed(format!(
                         "Authentication failed - token may have expired: {}",
                         body
@@ -625,9 +534,6 @@ impl BaseTTS for IbmWatsonTTS {
         // Clear client
         *self.client.write().await = None;
 
-        // Clear token
-        *self.token.write().await = None;
-
         // Clear callback
         *self.audio_callback.write().await = None;
 
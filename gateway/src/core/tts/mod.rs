@@ -12,6 +12,8 @@ pub mod lmnt;
 pub mod openai;
 pub mod playht;
 pub mod provider;
+pub mod segmentation;
+pub mod telephony;
 
 pub use aws_polly::{
     AWS_POLLY_TTS_URL, AwsPollyTTS, AwsPollyTTSConfig, PollyEngine, PollyOutputFormat, PollyVoice,
@@ -19,8 +21,8 @@ pub use aws_polly::{
 };
 pub use azure::{AZURE_TTS_URL, AzureAudioEncoding, AzureTTS, AzureTTSConfig};
 pub use base::{
-    AudioCallback, AudioData, BaseTTS, BoxedTTS, ConnectionState, Pronunciation, TTSConfig,
-    TTSError, TTSFactory, TTSResult,
+    AudioCallback, AudioData, BaseTTS, BoxedTTS, ConnectionState, DEFAULT_UTTERANCE_TIMEOUT_SECS,
+    Pronunciation, TTSConfig, TTSError, TTSFactory, TTSResult, TTSStats, TTSTimingEvent,
 };
 pub use cartesia::{CARTESIA_TTS_URL, CartesiaTTS};
 pub use deepgram::{DEEPGRAM_TTS_URL, DeepgramTTS};
@@ -36,6 +38,11 @@ pub use playht::{
     PLAYHT_TTS_URL, PlayHtAudioFormat, PlayHtModel, PlayHtTts, PlayHtTtsConfig, PlayHtVoice,
 };
 pub use provider::{TTSProvider, TTSRequestBuilder};
+pub use segmentation::{DEFAULT_MAX_SEGMENT_BYTES, SegmentationProfile, TextSegmenter};
+pub use telephony::{
+    TELEPHONY_FORMAT, TELEPHONY_FRAME_BYTES, TELEPHONY_FRAME_MS, TELEPHONY_SAMPLE_RATE,
+    TTSOutputProfile, TelephonyFramer, telephony_config,
+};
 
 // Re-export Gnani.ai implementation
 pub use gnani::{GnaniGender, GnaniTTS, GnaniTTSConfig, GnaniTTSLanguage};
@@ -76,6 +83,21 @@ pub fn create_tts_provider(provider_type: &str, config: TTSConfig) -> TTSResult<
     crate::plugin::global_registry().create_tts(provider_type, config)
 }
 
+/// TTS providers given a pooled HTTP client, with their default API endpoint URLs
+const TTS_PROVIDER_URLS: [(&str, &str); 11] = [
+    ("deepgram", DEEPGRAM_TTS_URL),
+    ("elevenlabs", ELEVENLABS_TTS_URL),
+    ("google", GOOGLE_TTS_URL),
+    ("azure", AZURE_TTS_URL),
+    ("cartesia", CARTESIA_TTS_URL),
+    ("openai", OPENAI_TTS_URL),
+    ("aws-polly", AWS_POLLY_TTS_URL),
+    ("ibm-watson", IBM_WATSON_TTS_URL),
+    ("hume", HUME_TTS_STREAM_URL),
+    ("lmnt", LMNT_TTS_URL),
+    ("playht", PLAYHT_TTS_URL),
+];
+
 /// Returns a map of provider names to their default API endpoint URLs.
 ///
 /// Note: Azure uses regional endpoints. The URL returned here is for the
@@ -83,19 +105,20 @@ pub fn create_tts_provider(provider_type: &str, config: TTSConfig) -> TTSResult<
 /// Note: AWS Polly uses regional endpoints. The URL returned here is a template.
 /// Note: IBM Watson uses regional endpoints. The URL returned here is for us-south.
 pub fn get_tts_provider_urls() -> HashMap<String, String> {
-    let mut urls = HashMap::new();
-    urls.insert("deepgram".to_string(), DEEPGRAM_TTS_URL.to_string());
-    urls.insert("elevenlabs".to_string(), ELEVENLABS_TTS_URL.to_string());
-    urls.insert("google".to_string(), GOOGLE_TTS_URL.to_string());
-    urls.insert("azure".to_string(), AZURE_TTS_URL.to_string());
-    urls.insert("cartesia".to_string(), CARTESIA_TTS_URL.to_string());
-    urls.insert("openai".to_string(), OPENAI_TTS_URL.to_string());
-    urls.insert("aws-polly".to_string(), AWS_POLLY_TTS_URL.to_string());
-    urls.insert("ibm-watson".to_string(), IBM_WATSON_TTS_URL.to_string());
-    urls.insert("hume".to_string(), HUME_TTS_STREAM_URL.to_string());
-    urls.insert("lmnt".to_string(), LMNT_TTS_URL.to_string());
-    urls.insert("playht".to_string(), PLAYHT_TTS_URL.to_string());
-    urls
+    TTS_PROVIDER_URLS
+        .iter()
+        .map(|(provider, url)| (provider.to_string(), url.to_string()))
+        .collect()
+}
+
+/// Default API endpoint URL of `provider`, if it is given a pooled HTTP client
+///
+/// Same entries as [`get_tts_provider_urls`], without building the map.
+pub fn tts_provider_url(provider: &str) -> Option<&'static str> {
+    TTS_PROVIDER_URLS
+        .iter()
+        .find(|(name, _)| *name == provider)
+        .map(|(_, url)| *url)
 }
 
 #[cfg(test)]
@@ -104,12 +127,19 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_tts_provider() {
-        let config = TTSConfig::default();
+        let config = TTSConfig {
+            api_key: "test_key".to_string(),
+            ..Default::default()
+        };
         let result = create_tts_provider("deepgram", config);
         assert!(result.is_ok());
 
         let invalid_result = create_tts_provider("invalid", TTSConfig::default());
         assert!(invalid_result.is_err());
+
+        // Rejected by config validation before the provider is constructed
+        let missing_key = create_tts_provider("deepgram", TTSConfig::default());
+        assert!(missing_key.is_err());
     }
 
     #[tokio::test]
@@ -165,6 +195,17 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_tts_provider_url_matches_map() {
+        let urls = get_tts_provider_urls();
+        for (provider, url) in &urls {
+            assert_eq!(tts_provider_url(provider), Some(url.as_str()));
+        }
+        assert_eq!(tts_provider_url("unknown"), None);
+        // Aliases are not pooled
+        assert_eq!(tts_provider_url("polly"), None);
+    }
+
     #[test]
     fn test_get_tts_provider_urls_includes_azure() {
         let urls = get_tts_provider_urls();
@@ -41,7 +41,7 @@ mod config;
 mod provider;
 
 pub use config::{AudioOutputFormat, OpenAITTSModel, OpenAIVoice};
-pub use provider::{OPENAI_TTS_URL, OpenAITTS};
+pub use provider::{OPENAI_API_BASE_URL, OPENAI_TTS_URL, OpenAITTS};
 
 #[cfg(test)]
 mod tests {
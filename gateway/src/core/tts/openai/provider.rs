@@ -18,6 +18,7 @@ use serde_json::json;
 use xxhash_rust::xxh3::xxh3_128;
 
 use super::config::{AudioOutputFormat, OpenAITTSModel, OpenAIVoice};
+use crate::core::credentials::{check_credentials, credential_check_client};
 use crate::core::tts::base::{AudioCallback, BaseTTS, ConnectionState, TTSConfig, TTSResult};
 use crate::core::tts::provider::{PronunciationReplacer, TTSProvider, TTSRequestBuilder};
 use crate::utils::req_manager::ReqManager;
@@ -25,6 +26,9 @@ use crate::utils::req_manager::ReqManager;
 /// OpenAI TTS API endpoint
 pub const OPENAI_TTS_URL: &str = "https://api.openai.com/v1/audio/speech";
 
+/// OpenAI REST API base URL, used for credential checks
+pub const OPENAI_API_BASE_URL: &str = "https://api.openai.com";
+
 // =============================================================================
 // Request Builder
 // =============================================================================
@@ -160,6 +164,27 @@ pub struct OpenAITTS {
 }
 
 impl OpenAITTS {
+    /// Verify an API key with a lightweight authenticated request to `/v1/models`.
+    ///
+    /// # Returns
+    /// * `Ok(())` if OpenAI accepts the key
+    /// * `Err(String)` with a human-readable reason otherwise
+    pub async fn validate_credentials(api_key: &str) -> Result<(), String> {
+        Self::validate_credentials_at(OPENAI_API_BASE_URL, api_key).await
+    }
+
+    /// Same as [`Self::validate_credentials`] against a custom API base URL.
+    pub async fn validate_credentials_at(base_url: &str, api_key: &str) -> Result<(), String> {
+        let client = credential_check_client()?;
+        let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
+        check_credentials(
+            client
+                .get(url)
+                .header("Authorization", format!("Bearer {api_key}")),
+        )
+        .await
+    }
+
     /// Create a new OpenAI TTS instance
     pub fn new(config: TTSConfig) -> TTSResult<Self> {
         // Parse model from config
@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use futures_util::StreamExt;
 use tokio::sync::{Mutex, Notify, RwLock, mpsc};
@@ -8,13 +8,27 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
-use super::base::{AudioCallback, AudioData, ConnectionState, TTSConfig, TTSError, TTSResult};
+use super::base::{
+    AudioCallback, AudioData, ConnectionState, TTSConfig, TTSError, TTSResult, TTSStats,
+};
 use crate::core::cache::store::CacheStore;
+use crate::core::providers::headers::apply_custom_headers;
+use crate::core::providers::retry::{FailedAttempt, RetryPolicy, RetryReason, retry_after};
 use crate::utils::req_manager::{ReqManager, ReqManagerConfig};
 use regex::Regex;
 use std::time::Duration;
 use xxhash_rust::xxh3::xxh3_128;
 
+/// Retries for a speech request, or one chunk of an utterance, before any of
+/// its audio has been streamed. Kept short since a listener is waiting on the
+/// utterance.
+const TTS_RETRY_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 3,
+    initial_backoff: Duration::from_millis(250),
+    max_backoff: Duration::from_secs(2),
+    max_retry_after: Duration::from_secs(5),
+};
+
 /// Request entry for ordered processing
 struct RequestEntry {
     receiver: mpsc::Receiver<Result<Vec<u8>, TTSError>>,
@@ -22,12 +36,23 @@ struct RequestEntry {
     format: String,
     /// Sample rate for this specific request
     sample_rate: u32,
+    /// Cancellation token for this request's speak job
+    cancel_token: CancellationToken,
+    /// Maximum time to wait for the next audio chunk
+    utterance_timeout: Option<Duration>,
+    /// Utterance this request is a chunk of
+    utterance_id: u64,
 }
 
 /// A queued speak job containing all data needed to execute a TTS request
 struct SpeakJob {
     /// The trimmed text to synthesize
     text: String,
+    /// Whether the text was flushed, ending the utterance
+    ends_utterance: bool,
+    /// Utterance this text is a chunk of; unflushed texts share the id of the
+    /// flushed text that ends them
+    utterance_id: u64,
     /// The HTTP request builder (boxed trait object)
     request_builder: Box<dyn TTSRequestBuilderDyn>,
     /// The channel sender for streaming audio chunks
@@ -40,6 +65,17 @@ struct SpeakJob {
     cache_and_key: Option<(Arc<CacheStore>, String)>,
 }
 
+/// How a speak job ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobOutcome {
+    /// All of the chunk's audio was streamed
+    Delivered,
+    /// The chunk failed after its retries and the error was sent to the dispatcher
+    Failed,
+    /// The job was cancelled
+    Cancelled,
+}
+
 /// Trait object-safe version of TTSRequestBuilder for dynamic dispatch
 trait TTSRequestBuilderDyn: Send + Sync {
     fn build_http_request_with_context(
@@ -47,9 +83,11 @@ trait TTSRequestBuilderDyn: Send + Sync {
         client: &reqwest::Client,
         text: &str,
         previous_text: Option<&str>,
+        next_text: Option<&str>,
     ) -> reqwest::RequestBuilder;
     fn get_config(&self) -> &TTSConfig;
     fn get_pronunciation_replacer(&self) -> Option<&PronunciationReplacer>;
+    fn is_voice_not_found(&self, status: u16, body: &str) -> bool;
 }
 
 /// Blanket implementation to convert any TTSRequestBuilder to the trait object version
@@ -59,8 +97,15 @@ impl<T: TTSRequestBuilder> TTSRequestBuilderDyn for T {
         client: &reqwest::Client,
         text: &str,
         previous_text: Option<&str>,
+        next_text: Option<&str>,
     ) -> reqwest::RequestBuilder {
-        TTSRequestBuilder::build_http_request_with_context(self, client, text, previous_text)
+        TTSRequestBuilder::build_http_request_with_context(
+            self,
+            client,
+            text,
+            previous_text,
+            next_text,
+        )
     }
 
     fn get_config(&self) -> &TTSConfig {
@@ -70,6 +115,10 @@ impl<T: TTSRequestBuilder> TTSRequestBuilderDyn for T {
     fn get_pronunciation_replacer(&self) -> Option<&PronunciationReplacer> {
         TTSRequestBuilder::get_pronunciation_replacer(self)
     }
+
+    fn is_voice_not_found(&self, status: u16, body: &str) -> bool {
+        TTSRequestBuilder::is_voice_not_found(self, status, body)
+    }
 }
 
 /// Compiled pronunciation replacement patterns
@@ -133,6 +182,7 @@ pub trait TTSRequestBuilder: Send + Sync {
     /// * `client` - The HTTP client to use for building the request
     /// * `text` - The text to synthesize
     /// * `previous_text` - Optional previous text for context continuity
+    /// * `next_text` - Optional text queued after this chunk of the utterance
     ///
     /// # Returns
     /// A request builder ready to be sent
@@ -141,9 +191,10 @@ pub trait TTSRequestBuilder: Send + Sync {
         client: &reqwest::Client,
         text: &str,
         previous_text: Option<&str>,
+        next_text: Option<&str>,
     ) -> reqwest::RequestBuilder {
         // Default implementation ignores context
-        let _ = previous_text;
+        let _ = (previous_text, next_text);
         self.build_http_request(client, text)
     }
 
@@ -154,6 +205,18 @@ pub trait TTSRequestBuilder: Send + Sync {
     fn get_pronunciation_replacer(&self) -> Option<&PronunciationReplacer> {
         None
     }
+
+    /// Whether an error response means the configured voice does not exist
+    ///
+    /// Matching responses surface as `TTSError::VoiceNotFound` so the voice
+    /// manager can switch to a fallback voice. Defaults to `false`.
+    ///
+    /// # Arguments
+    /// * `status` - HTTP status code of the error response
+    /// * `body` - Error response body
+    fn is_voice_not_found(&self, _status: u16, _body: &str) -> bool {
+        false
+    }
 }
 
 /// Generic HTTP-based TTS provider implementation using ReqManager
@@ -188,6 +251,10 @@ pub struct TTSProvider {
     tts_config_hash: Arc<RwLock<Option<String>>>,
     /// Previous text for context continuity (session-scoped)
     previous_text: Arc<RwLock<Option<String>>>,
+    /// Utterance statistics
+    stats: Arc<RwLock<TTSStats>>,
+    /// Id of the utterance the next speak() text belongs to
+    next_utterance_id: Arc<AtomicU64>,
 }
 
 impl TTSProvider {
@@ -209,35 +276,48 @@ impl TTSProvider {
             cache: Arc::new(RwLock::new(None)),
             tts_config_hash: Arc::new(RwLock::new(None)),
             previous_text: Arc::new(RwLock::new(None)),
+            stats: Arc::new(RwLock::new(TTSStats::default())),
+            next_utterance_id: Arc::new(AtomicU64::new(0)),
         })
     }
 
     /// Generic send_request implementation that handles all the common logic
     /// Now accepts trait object for dynamic dispatch in the queue worker
+    #[allow(clippy::too_many_arguments)]
     async fn send_request_dyn(
         request_builder: &dyn TTSRequestBuilderDyn,
         req_manager: Arc<ReqManager>,
         text: String,
+        next_text: Option<String>,
         sender: mpsc::Sender<Result<Vec<u8>, TTSError>>,
         token: CancellationToken,
         cache_and_key: Option<(Arc<CacheStore>, String)>,
         previous_text_store: Arc<RwLock<Option<String>>>,
-    ) {
+        stats: Arc<RwLock<TTSStats>>,
+    ) -> JobOutcome {
         if token.is_cancelled() {
             let _ = sender
                 .send(Err(TTSError::InternalError("Cancelled".to_string())))
                 .await;
-            return;
+            return JobOutcome::Cancelled;
         }
 
-        // Get previous text for context (if available)
-        let previous_text = previous_text_store.read().await.clone();
-
         // Apply pronunciation replacements using precompiled regex patterns
-        let processed_text = if let Some(replacer) = request_builder.get_pronunciation_replacer() {
-            replacer.apply(&text)
+        let replacer = request_builder.get_pronunciation_replacer();
+        let processed_text = match replacer {
+            Some(replacer) => replacer.apply(&text),
+            None => text.clone(),
+        };
+
+        // Surrounding text for context continuity, unless disabled
+        let (previous_text, next_text) = if request_builder.get_config().context_hints_enabled() {
+            let next_text = next_text.map(|next| match replacer {
+                Some(replacer) => replacer.apply(&next),
+                None => next,
+            });
+            (previous_text_store.read().await.clone(), next_text)
         } else {
-            text.clone()
+            (None, None)
         };
 
         // Try cache first
@@ -255,6 +335,7 @@ impl TTSProvider {
                     );
                     if let Err(e) = sender.send(Ok(bytes.clone().to_vec())).await {
                         error!("Failed to send cached audio through channel: {:?}", e);
+                        return JobOutcome::Cancelled;
                     } else {
                         debug!(
                             "Successfully sent cached audio through channel for text: '{}' (receiver has read it)",
@@ -265,7 +346,7 @@ impl TTSProvider {
                         *previous_text_store.write().await = Some(processed_text.clone());
                         debug!("Updated previous_text on cache hit: '{}'", processed_text);
                     }
-                    return;
+                    return JobOutcome::Delivered;
                 }
                 Ok(None) => {
                     debug!("Cache miss for text: '{}'", processed_text);
@@ -286,194 +367,257 @@ impl TTSProvider {
                         "Failed to acquire client: {e}"
                     ))))
                     .await;
-                return;
+                return JobOutcome::Failed;
             }
         };
 
-        // Build request with provider-specific URL, headers and body using processed text
-        // Pass previous_text for context continuity (ElevenLabs uses this)
-        let request = request_builder.build_http_request_with_context(
-            client_guard.client(),
-            &processed_text,
-            previous_text.as_deref(),
-        );
-
-        // Send request
-        let response_result = request.send().await;
+        // Retry throttled and transient failures before any of this chunk's audio
+        // has been sent, honoring the provider's Retry-After within the policy's
+        // cap. A response cut off before any audio counts as a failed attempt too;
+        // once audio has been delivered a retry would repeat it, so the chunk fails.
         let config = request_builder.get_config();
+        let encoding = config.audio_format.as_deref().unwrap_or("linear16");
+        let sample_rate = config.sample_rate.unwrap_or(24000) as usize;
+        let (mut chunk_target_bytes, bytes_per_sample) = match encoding {
+            // Assume mono
+            "linear16" | "pcm" => ((sample_rate / 100) * 2, 2usize), // ~10ms
+            "mulaw" | "ulaw" | "alaw" => ((sample_rate / 100), 1usize),
+            _ => (0usize, 0usize),
+        };
 
-        match response_result {
-            Ok(response) => {
-                if !response.status().is_success() {
-                    info!("ERROR Response for text: {}", processed_text);
-                    let status = response.status();
+        // Guard against tiny sample rates
+        if chunk_target_bytes == 0
+            && matches!(encoding, "linear16" | "pcm" | "mulaw" | "ulaw" | "alaw")
+        {
+            chunk_target_bytes = (sample_rate.max(100) / 100) * bytes_per_sample.max(1);
+        }
 
-                    // Parse Retry-After header for rate limiting (429)
-                    let retry_after_secs = response
-                        .headers()
-                        .get("retry-after")
-                        .and_then(|v| v.to_str().ok())
-                        .and_then(|s| s.parse::<u64>().ok());
+        let mut retry = TTS_RETRY_POLICY.start(&config.provider);
+        let full_audio = 'attempt: loop {
+            // Build request with provider-specific URL, headers and body using processed text
+            // Pass the surrounding text for context continuity (ElevenLabs uses this)
+            let request = request_builder.build_http_request_with_context(
+                client_guard.client(),
+                &processed_text,
+                previous_text.as_deref(),
+                next_text.as_deref(),
+            );
+            let request = apply_custom_headers(request, &config.custom_headers);
 
-                    let error_body = response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| "Unknown error".to_string());
+            // Send request (abandoned if the job is cancelled, e.g. by the utterance timeout)
+            let response_result = tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("TTS request cancelled before response for text: '{}'", processed_text);
+                    return JobOutcome::Cancelled;
+                }
+                result = request.send() => result,
+            };
 
-                    // Handle specific HTTP status codes with appropriate error types
-                    let tts_error = match status.as_u16() {
-                        429 => {
-                            warn!(
-                                "TTS rate limited. Retry-After: {:?} seconds. Body: {}",
-                                retry_after_secs, error_body
-                            );
-                            TTSError::RateLimited {
-                                retry_after_secs,
-                                message: error_body,
-                            }
-                        }
-                        401 | 403 => {
-                            error!("TTS authentication failed ({}): {}", status, error_body);
-                            TTSError::AuthenticationFailed(format!(
-                                "API error ({status}): {error_body}"
-                            ))
-                        }
-                        _ => {
-                            error!("TTS API error ({}): {}", status, error_body);
-                            TTSError::ProviderError(format!("API error ({status}): {error_body}"))
-                        }
+            let failure = match response_result {
+                Ok(response) if response.status().is_success() => {
+                    let mut buffer: Vec<u8> = Vec::with_capacity(chunk_target_bytes.max(512));
+                    // Only allocate full_audio buffer if we're caching
+                    let mut full_audio: Option<Vec<u8>> = if cache_and_key.is_some() {
+                        Some(Vec::new())
+                    } else {
+                        None
                     };
+                    // Whether any of this attempt's audio reached the dispatcher
+                    let mut streamed = false;
+
+                    // Stream body in chunks
+                    let mut stream = response.bytes_stream();
+                    let stream_error = loop {
+                        let item = tokio::select! {
+                            _ = token.cancelled() => break None,
+                            item = stream.next() => item,
+                        };
+                        let Some(item) = item else {
+                            break None;
+                        };
+                        if token.is_cancelled() {
+                            break None;
+                        }
 
-                    let _ = sender.send(Err(tts_error)).await;
-                    return;
-                }
-
-                // Stream body in chunks
-                let encoding = config.audio_format.as_deref().unwrap_or("linear16");
-                let sample_rate = config.sample_rate.unwrap_or(24000) as usize;
-                let (mut chunk_target_bytes, bytes_per_sample) = match encoding {
-                    // Assume mono
-                    "linear16" | "pcm" => ((sample_rate / 100) * 2, 2usize), // ~10ms
-                    "mulaw" | "ulaw" | "alaw" => ((sample_rate / 100), 1usize),
-                    _ => (0usize, 0usize),
-                };
-
-                // Guard against tiny sample rates
-                if chunk_target_bytes == 0
-                    && matches!(encoding, "linear16" | "pcm" | "mulaw" | "ulaw" | "alaw")
-                {
-                    chunk_target_bytes = (sample_rate.max(100) / 100) * bytes_per_sample.max(1);
-                }
+                        match item {
+                            Ok(bytes) => {
+                                let mut incoming = bytes.as_ref();
 
-                let mut buffer: Vec<u8> = Vec::with_capacity(chunk_target_bytes.max(512));
-                // Only allocate full_audio buffer if we're caching
-                let mut full_audio: Option<Vec<u8>> = if cache_and_key.is_some() {
-                    Some(Vec::new())
-                } else {
-                    None
-                };
-
-                let mut stream = response.bytes_stream();
-                while let Some(item) = stream.next().await {
-                    if token.is_cancelled() {
-                        break;
-                    }
+                                if chunk_target_bytes == 0 {
+                                    // Non-PCM/containerized formats: forward chunks as-is
+                                    let chunk_vec = incoming.to_vec();
+                                    // Only accumulate if caching
+                                    if let Some(ref mut full) = full_audio {
+                                        full.extend_from_slice(&chunk_vec);
+                                    }
+                                    debug!(
+                                        "Sending audio chunk ({} bytes) - will wait for receiver...",
+                                        chunk_vec.len()
+                                    );
+                                    let _ = sender.send(Ok(chunk_vec)).await;
+                                    streamed = true;
+                                    debug!("Audio chunk sent and received");
+                                    continue;
+                                }
 
-                    match item {
-                        Ok(bytes) => {
-                            let mut incoming = bytes.as_ref();
+                                // PCM-like formats: aggregate into ~10ms chunks
+                                while !incoming.is_empty() {
+                                    let needed = chunk_target_bytes.saturating_sub(buffer.len());
+                                    let take = needed.min(incoming.len());
+                                    buffer.extend_from_slice(&incoming[..take]);
+                                    incoming = &incoming[take..];
+
+                                    if buffer.len() >= chunk_target_bytes {
+                                        // Take exactly chunk_target_bytes from buffer, leave any excess
+                                        let chunk: Vec<u8> =
+                                            buffer.drain(..chunk_target_bytes).collect();
+                                        // Only accumulate if caching
+                                        if let Some(ref mut full) = full_audio {
+                                            full.extend_from_slice(&chunk);
+                                        }
+                                        debug!(
+                                            "Sending PCM chunk ({} bytes) - will wait for receiver...",
+                                            chunk.len()
+                                        );
+                                        let _ = sender.send(Ok(chunk)).await;
+                                        streamed = true;
+                                        debug!("PCM chunk sent and received");
+                                    }
+                                }
+                            }
+                            Err(e) => break Some(e),
+                        }
+                    };
 
-                            if chunk_target_bytes == 0 {
-                                // Non-PCM/containerized formats: forward chunks as-is
-                                let chunk_vec = incoming.to_vec();
+                    match stream_error {
+                        None => {
+                            // Flush remainder
+                            if !buffer.is_empty() && !token.is_cancelled() {
                                 // Only accumulate if caching
                                 if let Some(ref mut full) = full_audio {
-                                    full.extend_from_slice(&chunk_vec);
+                                    full.extend_from_slice(&buffer);
                                 }
                                 debug!(
-                                    "Sending audio chunk ({} bytes) - will wait for receiver...",
-                                    chunk_vec.len()
+                                    "Sending final buffer ({} bytes) - will wait for receiver...",
+                                    buffer.len()
                                 );
-                                let _ = sender.send(Ok(chunk_vec)).await;
-                                debug!("Audio chunk sent and received");
-                                continue;
-                            }
-
-                            // PCM-like formats: aggregate into ~10ms chunks
-                            while !incoming.is_empty() {
-                                let needed = chunk_target_bytes.saturating_sub(buffer.len());
-                                let take = needed.min(incoming.len());
-                                buffer.extend_from_slice(&incoming[..take]);
-                                incoming = &incoming[take..];
-
-                                if buffer.len() >= chunk_target_bytes {
-                                    // Take exactly chunk_target_bytes from buffer, leave any excess
-                                    let chunk: Vec<u8> =
-                                        buffer.drain(..chunk_target_bytes).collect();
-                                    // Only accumulate if caching
-                                    if let Some(ref mut full) = full_audio {
-                                        full.extend_from_slice(&chunk);
-                                    }
-                                    debug!(
-                                        "Sending PCM chunk ({} bytes) - will wait for receiver...",
-                                        chunk.len()
-                                    );
-                                    let _ = sender.send(Ok(chunk)).await;
-                                    debug!("PCM chunk sent and received");
-                                }
+                                let _ = sender.send(Ok(buffer)).await;
+                                debug!("Final buffer sent and received");
                             }
+                            break 'attempt full_audio;
                         }
-                        Err(e) => {
+                        Some(e) => {
                             error!("Failed to read audio chunk: {}", e);
-                            let _ = sender
-                                .send(Err(TTSError::AudioGenerationFailed(format!(
-                                    "Failed to read audio: {e}"
-                                ))))
-                                .await;
-                            return;
+                            let error = TTSError::AudioGenerationFailed(format!(
+                                "Failed to read audio: {e}"
+                            ));
+                            if streamed {
+                                FailedAttempt::fatal(error)
+                            } else {
+                                FailedAttempt {
+                                    reason: RetryReason::from_reqwest(&e)
+                                        .or(Some(RetryReason::ServerError)),
+                                    error,
+                                    retry_after: None,
+                                }
+                            }
                         }
                     }
                 }
+                Ok(response) => {
+                    info!("ERROR Response for text: {}", processed_text);
+                    let status = response.status();
+                    let hint = retry_after(response.headers());
+                    let retry_after_secs = hint.map(|d| d.as_secs_f64().ceil() as u64);
+
+                    let error_body = response
+                        .text()
+                        .await
+                        .unwrap_or_else(|_| "Unknown error".to_string());
 
-                // Flush remainder
-                if !buffer.is_empty() && !token.is_cancelled() {
-                    // Only accumulate if caching
-                    if let Some(ref mut full) = full_audio {
-                        full.extend_from_slice(&buffer);
+                    if request_builder.is_voice_not_found(status.as_u16(), &error_body) {
+                        error!("TTS voice not found ({}): {}", status, error_body);
+                        let tts_error =
+                            TTSError::VoiceNotFound(format!("API error ({status}): {error_body}"));
+                        FailedAttempt::fatal(tts_error)
+                    } else {
+                        let reason = RetryReason::from_status(status, &error_body);
+
+                        // Handle specific HTTP status codes with appropriate error types
+                        let tts_error = match status.as_u16() {
+                            429 => {
+                                warn!(
+                                    "TTS rate limited. Retry-After: {:?} seconds. Body: {}",
+                                    retry_after_secs, error_body
+                                );
+                                TTSError::RateLimited {
+                                    retry_after_secs,
+                                    message: error_body,
+                                }
+                            }
+                            401 | 403 => {
+                                error!("TTS authentication failed ({}): {}", status, error_body);
+                                TTSError::AuthenticationFailed(format!(
+                                    "API error ({status}): {error_body}"
+                                ))
+                            }
+                            _ => {
+                                error!("TTS API error ({}): {}", status, error_body);
+                                TTSError::ProviderError(format!(
+                                    "API error ({status}): {error_body}"
+                                ))
+                            }
+                        };
+                        FailedAttempt {
+                            error: tts_error,
+                            reason,
+                            retry_after: hint,
+                        }
                     }
-                    debug!(
-                        "Sending final buffer ({} bytes) - will wait for receiver...",
-                        buffer.len()
-                    );
-                    let _ = sender.send(Ok(buffer)).await;
-                    debug!("Final buffer sent and received");
                 }
-
-                // Store the full audio in cache if provided
-                if let Some(((cache, key), full_audio)) = cache_and_key.zip(full_audio) {
-                    match cache.put(key, full_audio).await {
-                        Ok(_) => {}
-                        Err(e) => error!("Failed to cache TTS audio: {:?}", e),
+                Err(e) => {
+                    error!("HTTP request failed: {}", e);
+                    FailedAttempt {
+                        reason: RetryReason::from_reqwest(&e),
+                        error: TTSError::NetworkError(format!("Request failed: {e}")),
+                        retry_after: None,
                     }
                 }
+            };
 
-                // Update previous_text for context continuity on next request
-                // Only update if not cancelled and generation succeeded
-                if !token.is_cancelled() {
-                    *previous_text_store.write().await = Some(processed_text.clone());
-                    debug!(
-                        "Updated previous_text for next request: '{}'",
-                        processed_text
-                    );
+            let Some(delay) = retry.next_delay(failure.reason, failure.retry_after) else {
+                let _ = sender.send(Err(failure.error)).await;
+                return JobOutcome::Failed;
+            };
+            stats.write().await.chunk_retries += 1;
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("TTS request cancelled while waiting to retry: '{}'", processed_text);
+                    return JobOutcome::Cancelled;
                 }
+                _ = tokio::time::sleep(delay) => {}
             }
-            Err(e) => {
-                error!("HTTP request failed: {}", e);
-                let _ = sender
-                    .send(Err(TTSError::NetworkError(format!("Request failed: {e}"))))
-                    .await;
+        };
+
+        // Store the full audio in cache if provided
+        if let Some(((cache, key), full_audio)) = cache_and_key.zip(full_audio) {
+            match cache.put(key, full_audio).await {
+                Ok(_) => {}
+                Err(e) => error!("Failed to cache TTS audio: {:?}", e),
             }
         }
+
+        // Update previous_text for context continuity on next request
+        // Only update if not cancelled and generation succeeded
+        if token.is_cancelled() {
+            return JobOutcome::Cancelled;
+        }
+        *previous_text_store.write().await = Some(processed_text.clone());
+        debug!(
+            "Updated previous_text for next request: '{}'",
+            processed_text
+        );
+        JobOutcome::Delivered
     }
 
     /// Set the request manager for this instance
@@ -530,10 +674,13 @@ impl TTSProvider {
         let pending_notify = self.pending_notify.clone();
         let token = self.cancel_token.clone();
         let running_flag = self.dispatcher_running.clone();
+        let stats = self.stats.clone();
 
         let handle = tokio::spawn(async move {
             debug!("TTS dispatcher task started");
             running_flag.store(true, Ordering::Release);
+            // Utterance whose error was reported; its remaining chunks are dropped
+            let mut abandoned_utterance: Option<u64> = None;
 
             loop {
                 tokio::select! {
@@ -558,6 +705,18 @@ impl TTSProvider {
                             return;
                         };
 
+                        if abandoned_utterance == Some(entry.utterance_id) {
+                            debug!("TTS dispatcher skipping chunk of abandoned utterance {}", entry.utterance_id);
+                            entry.cancel_token.cancel();
+                            stats.write().await.chunks_abandoned += 1;
+                            if pending_queue.lock().await.is_empty()
+                                && let Some(cb) = audio_callback.read().await.clone()
+                            {
+                                cb.on_complete().await;
+                            }
+                            return;
+                        }
+
                         debug!("TTS dispatcher processing request from queue");
 
                         // Use the format and sample rate from this specific request
@@ -570,29 +729,58 @@ impl TTSProvider {
                         // Process all chunks from this request's receiver
                         debug!("TTS dispatcher waiting for audio chunks from receiver");
                         let mut chunk_count = 0;
-                        while let Some(result) = entry.receiver.recv().await {
+                        let mut audio_bytes = 0u64;
+                        let mut completed = true;
+                        loop {
+                            let next = match entry.utterance_timeout {
+                                Some(limit) => {
+                                    match tokio::time::timeout(limit, entry.receiver.recv()).await {
+                                        Ok(next) => next,
+                                        Err(_) => {
+                                            // Provider stalled: stop the job and salvage any audio
+                                            // that was produced before it stopped progressing
+                                            entry.cancel_token.cancel();
+                                            while let Ok(Ok(bytes)) = entry.receiver.try_recv() {
+                                                chunk_count += 1;
+                                                audio_bytes += bytes.len() as u64;
+                                                if let Some(cb) = cb_opt.as_ref() {
+                                                    let audio_data =
+                                                        Self::process_audio_chunk(bytes, &format, sample_rate);
+                                                    cb.on_audio(audio_data).await;
+                                                }
+                                            }
+
+                                            warn!(
+                                                "TTS utterance timed out after {:?} without audio ({} chunks delivered)",
+                                                limit, chunk_count
+                                            );
+                                            stats.write().await.utterance_timeouts += 1;
+                                            completed = false;
+                                            if let Some(cb) = cb_opt.as_ref() {
+                                                let err = TTSError::TimeoutError(format!(
+                                                    "No audio received for {}ms; delivered {} chunks before the provider stalled",
+                                                    limit.as_millis(),
+                                                    chunk_count
+                                                ));
+                                                cb.on_error(err).await;
+                                            }
+                                            break;
+                                        }
+                                    }
+                                }
+                                None => entry.receiver.recv().await,
+                            };
+                            let Some(result) = next else {
+                                break;
+                            };
+
                             match result {
                                 Ok(bytes) => {
                                     chunk_count += 1;
+                                    audio_bytes += bytes.len() as u64;
                                     debug!("TTS dispatcher received chunk #{}: {} bytes", chunk_count, bytes.len());
                                     if let Some(cb) = cb_opt.as_ref() {
-                                        let duration_ms = if matches!(
-                                            format.as_str(),
-                                            "linear16" | "pcm" | "mulaw" | "ulaw" | "alaw"
-                                        ) {
-                                            let bytes_per_sample = if matches!(format.as_str(), "linear16" | "pcm") { 2 } else { 1 };
-                                            let samples = (bytes.len() / bytes_per_sample) as u32;
-                                            Some((samples * 1000) / sample_rate)
-                                        } else {
-                                            None
-                                        };
-
-                                        let audio_data = AudioData {
-                                            data: bytes,
-                                            sample_rate,
-                                            format: format.to_string(),
-                                            duration_ms,
-                                        };
+                                        let audio_data = Self::process_audio_chunk(bytes, &format, sample_rate);
                                         debug!("TTS dispatcher calling audio callback with {} bytes", audio_data.data.len());
                                         cb.on_audio(audio_data).await;
                                         debug!("TTS dispatcher audio callback completed");
@@ -600,6 +788,9 @@ impl TTSProvider {
                                 }
                                 Err(err) => {
                                     error!("TTS dispatcher received error: {:?}", err);
+                                    completed = false;
+                                    // The rest of the utterance would leave a gap, so it is not played
+                                    abandoned_utterance = Some(entry.utterance_id);
                                     if let Some(cb) = cb_opt.as_ref() {
                                         cb.on_error(err).await;
                                     }
@@ -609,6 +800,16 @@ impl TTSProvider {
                         }
                         debug!("TTS dispatcher finished processing request with {} chunks", chunk_count);
 
+                        {
+                            let mut stats = stats.write().await;
+                            stats.total_audio_bytes += audio_bytes;
+                            if completed {
+                                stats.utterances_completed += 1;
+                            } else {
+                                stats.utterances_incomplete += 1;
+                            }
+                        }
+
                         // Only notify completion if this is the last request in the queue
                         // This ensures that multiple consecutive speak() calls only trigger
                         // one completion event after all audio has been generated
@@ -658,10 +859,13 @@ impl TTSProvider {
         let token = self.cancel_token.clone();
         let running_flag = self.queue_worker_running.clone();
         let previous_text_store = self.previous_text.clone();
+        let stats = self.stats.clone();
 
         let handle = tokio::spawn(async move {
             debug!("TTS queue worker task started");
             running_flag.store(true, Ordering::Release);
+            // Utterance with a chunk that failed after its retries
+            let mut abandoned_utterance: Option<u64> = None;
 
             loop {
                 tokio::select! {
@@ -689,6 +893,20 @@ impl TTSProvider {
                             return;
                         };
 
+                        // Later chunks of a failed utterance are not synthesized; dropping
+                        // the sender closes their channel for the dispatcher
+                        if abandoned_utterance == Some(job.utterance_id) {
+                            debug!("TTS queue worker skipping chunk of abandoned utterance: '{}'", job.text);
+                            return;
+                        }
+
+                        // The next chunk of the same utterance, if it is already queued
+                        let next_text = if job.ends_utterance {
+                            None
+                        } else {
+                            speak_queue.lock().await.front().map(|next| next.text.clone())
+                        };
+
                         debug!("TTS queue worker processing job for text: '{}'", job.text);
 
                         // Check if cancelled before starting
@@ -702,16 +920,22 @@ impl TTSProvider {
 
                         // Process the job by calling send_request_dyn
                         // This will block until all audio chunks are streamed through the sender
-                        Self::send_request_dyn(
+                        let outcome = Self::send_request_dyn(
                             job.request_builder.as_ref(),
                             job.req_manager,
                             job.text.clone(),
+                            next_text,
                             job.sender,
                             job.cancel_token,
                             job.cache_and_key,
                             previous_text_store.clone(),
+                            stats.clone(),
                         )
                         .await;
+                        if outcome == JobOutcome::Failed {
+                            abandoned_utterance = Some(job.utterance_id);
+                            stats.write().await.chunks_failed += 1;
+                        }
 
                         debug!("TTS queue worker finished processing job for text: '{}'", job.text);
                     } => {}
@@ -849,7 +1073,7 @@ impl TTSProvider {
         &mut self,
         request_builder: R,
         text: &str,
-        _flush: bool,
+        flush: bool,
     ) -> TTSResult<()> {
         if !self.is_ready() {
             return Err(TTSError::ProviderNotReady(
@@ -876,6 +1100,19 @@ impl TTSProvider {
             .clone()
             .unwrap_or_else(|| "linear16".to_string());
         let sample_rate = request_builder.get_config().sample_rate.unwrap_or(24000);
+        let utterance_timeout = request_builder
+            .get_config()
+            .utterance_timeout
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs);
+
+        // Per-job token so a stalled utterance can be abandoned without clearing the session
+        let job_token = self.cancel_token.child_token();
+
+        // A flushed text ends its utterance; the next text starts a new one
+        let utterance_id = self
+            .next_utterance_id
+            .fetch_add(u64::from(flush), Ordering::Relaxed);
 
         // IMPORTANT: Ensure dispatcher is running BEFORE enqueuing job
         // This is critical for cached audio which returns immediately
@@ -888,6 +1125,9 @@ impl TTSProvider {
                 receiver,
                 format: format.clone(),
                 sample_rate,
+                cancel_token: job_token.clone(),
+                utterance_timeout,
+                utterance_id,
             });
             debug!(
                 "Added request to pending queue for text: '{}', queue size: {}",
@@ -919,14 +1159,14 @@ impl TTSProvider {
             ));
         };
 
-        let token = self.cancel_token.clone();
-
         // Create SpeakJob and enqueue it
         let job = SpeakJob {
             text: text_trimmed.clone(),
+            ends_utterance: flush,
+            utterance_id,
             request_builder: Box::new(request_builder),
             sender,
-            cancel_token: token,
+            cancel_token: job_token,
             req_manager: req_mgr,
             cache_and_key,
         };
@@ -1002,6 +1242,9 @@ impl TTSProvider {
         // Clear previous_text context
         *self.previous_text.write().await = None;
 
+        // Text spoken after a clear starts a new utterance
+        self.next_utterance_id.fetch_add(1, Ordering::Relaxed);
+
         // Reset token for next cycle
         self.cancel_token = CancellationToken::new();
 
@@ -1078,6 +1321,11 @@ impl TTSProvider {
         }
     }
 
+    /// Get a snapshot of the utterance statistics
+    pub async fn get_stats(&self) -> TTSStats {
+        self.stats.read().await.clone()
+    }
+
     /// Set cache store for provider
     pub async fn set_cache(&mut self, cache: Arc<CacheStore>) {
         *self.cache.write().await = Some(cache);
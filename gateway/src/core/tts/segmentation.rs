@@ -0,0 +1,385 @@
+//! Sentence segmentation of text sent to TTS in chunks
+//!
+//! [`TextSegmenter`] cuts text into sentences that are each synthesized as
+//! one chunk. Sentences end after Latin (`.`, `!`, `?`), CJK (`。`, `！`,
+//! `？`) and Indic (`।`, `॥`) terminators or a newline, whatever the
+//! session's language, so mixed-script text is segmented too; closing quotes
+//! and brackets stay with their sentence. A sentence longer than the chunk
+//! limit is cut at the last whitespace or clause mark that fits, then at a
+//! UAX #29 word boundary, and never inside a grapheme cluster or after a
+//! virama.
+//!
+//! The [`SegmentationProfile`] chosen from the session's language decides
+//! what is preferred for those cuts: Chinese and Japanese have no spaces
+//! between words, so they are cut after clause marks (`、`, `，`) first.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Longest chunk cut from a sentence without a terminator, in bytes
+///
+/// Below the per-request text limit of every TTS provider, in bytes or
+/// characters.
+pub const DEFAULT_MAX_SEGMENT_BYTES: usize = 1_000;
+
+/// How text of a language is segmented
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SegmentationProfile {
+    /// Words separated by spaces (Latin, Cyrillic, Indic scripts, Korean, ...)
+    #[default]
+    Spaced,
+    /// Chinese and Japanese, written without spaces between words
+    Cjk,
+}
+
+impl SegmentationProfile {
+    /// Profile of a BCP-47 language code such as `hi-IN` or `ja-JP`
+    pub fn for_language(language: &str) -> Self {
+        let primary = language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" | "cmn" | "yue" | "wuu" | "ja" => Self::Cjk,
+            _ => Self::Spaced,
+        }
+    }
+}
+
+/// Splits text into sentences of at most `max_bytes` bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSegmenter {
+    profile: SegmentationProfile,
+    max_bytes: usize,
+}
+
+impl Default for TextSegmenter {
+    fn default() -> Self {
+        Self::new(SegmentationProfile::Spaced, DEFAULT_MAX_SEGMENT_BYTES)
+    }
+}
+
+impl TextSegmenter {
+    /// Segmenter cutting chunks of at most `max_bytes` (at least one byte)
+    pub fn new(profile: SegmentationProfile, max_bytes: usize) -> Self {
+        Self {
+            profile,
+            max_bytes: max_bytes.max(1),
+        }
+    }
+
+    /// Segmenter for a BCP-47 language code with the default chunk limit
+    pub fn for_language(language: &str) -> Self {
+        Self::new(
+            SegmentationProfile::for_language(language),
+            DEFAULT_MAX_SEGMENT_BYTES,
+        )
+    }
+
+    pub fn profile(&self) -> SegmentationProfile {
+        self.profile
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Split `text` into sentences of at most `max_bytes` bytes
+    ///
+    /// The segments are slices of `text` in order, so concatenated they give
+    /// `text` back; whitespace between sentences starts the next segment.
+    /// A single grapheme cluster wider than `max_bytes` is its own segment.
+    pub fn split<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut segments = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let end = self.sentence_end(rest).unwrap_or(rest.len());
+            let (mut sentence, tail) = rest.split_at(end);
+            while sentence.len() > self.max_bytes {
+                let (head, remainder) = sentence.split_at(self.split_point(sentence));
+                segments.push(head);
+                sentence = remainder;
+            }
+            if !sentence.is_empty() {
+                segments.push(sentence);
+            }
+            rest = tail;
+        }
+        segments
+    }
+
+    /// Byte offset just past the first sentence terminator in `text`
+    ///
+    /// `.`, `!` and `?` (with any closing quotes) only end a sentence once
+    /// the next character has arrived and is whitespace, or for CJK any
+    /// non-ASCII character, so decimals like "3.5" are not split
+    /// mid-stream. Full-width and Indic terminators and newlines end a
+    /// sentence immediately.
+    pub fn sentence_end(&self, text: &str) -> Option<usize> {
+        let mut chars = text.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let ascii = matches!(c, '.' | '!' | '?');
+            if !ascii && !is_terminator(c) {
+                continue;
+            }
+            let mut end = index + c.len_utf8();
+            while let Some(&(next_index, next)) = chars.peek()
+                && is_closing(next)
+            {
+                end = next_index + next.len_utf8();
+                chars.next();
+            }
+            let ends = !ascii
+                || chars.peek().is_some_and(|&(_, next)| {
+                    next.is_whitespace()
+                        || (self.profile == SegmentationProfile::Cjk && !next.is_ascii())
+                });
+            if ends {
+                return Some(end);
+            }
+        }
+        None
+    }
+
+    /// Where to cut `text`, which is longer than `max_bytes`
+    ///
+    /// Returns the best break within the limit: whitespace or a clause mark
+    /// (clause marks first for CJK), else the last word boundary, else the
+    /// last grapheme boundary not following a virama.
+    pub fn split_point(&self, text: &str) -> usize {
+        let mut limit = 0;
+        for (index, _) in text.grapheme_indices(true).skip(1) {
+            if index > self.max_bytes {
+                break;
+            }
+            if !text[..index].ends_with(is_virama) {
+                limit = index;
+            }
+        }
+        if limit == 0 {
+            // A single cluster wider than the limit
+            return text.graphemes(true).next().map_or(text.len(), str::len);
+        }
+
+        let whitespace = || {
+            if text[limit..].starts_with(char::is_whitespace) {
+                Some(limit)
+            } else {
+                text[..limit]
+                    .rfind(char::is_whitespace)
+                    .filter(|index| *index > 0)
+            }
+        };
+        let clause = || {
+            text[..limit]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| is_clause_mark(*c))
+                .map(|(index, c)| index + c.len_utf8())
+        };
+        let soft = match self.profile {
+            SegmentationProfile::Spaced => whitespace().or_else(clause),
+            SegmentationProfile::Cjk => clause().or_else(whitespace),
+        };
+        soft.or_else(|| {
+            text.split_word_bound_indices()
+                .map(|(index, _)| index)
+                .chain(std::iter::once(text.len()))
+                .take_while(|index| *index <= limit)
+                .filter(|index| *index > 0)
+                .last()
+        })
+        .unwrap_or(limit)
+    }
+}
+
+/// Terminators that end a sentence without looking ahead
+fn is_terminator(c: char) -> bool {
+    matches!(c, '\n' | '。' | '！' | '？' | '｡' | '．' | '।' | '॥')
+}
+
+/// Closing quotes and brackets kept with the sentence they end
+fn is_closing(c: char) -> bool {
+    matches!(
+        c,
+        '"' | '\'' | ')' | ']' | '”' | '’' | '」' | '』' | '）' | '】' | '》' | '〉'
+    )
+}
+
+/// Marks between clauses of a sentence
+fn is_clause_mark(c: char) -> bool {
+    matches!(c, ',' | ';' | ':' | '、' | '，' | '；' | '：' | '､')
+}
+
+/// Viramas of the Indic scripts; a cut after one would break a conjunct
+fn is_virama(c: char) -> bool {
+    matches!(
+        c,
+        '\u{094D}'
+            | '\u{09CD}'
+            | '\u{0A4D}'
+            | '\u{0ACD}'
+            | '\u{0B4D}'
+            | '\u{0BCD}'
+            | '\u{0C4D}'
+            | '\u{0CCD}'
+            | '\u{0D4D}'
+            | '\u{0DCA}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Language, text, chunk limit and the expected segments
+    type Case = (&'static str, &'static str, usize, &'static [&'static str]);
+
+    fn check(cases: &[Case]) {
+        for (language, text, max_bytes, expected) in cases {
+            let segmenter =
+                TextSegmenter::new(SegmentationProfile::for_language(language), *max_bytes);
+            let segments = segmenter.split(text);
+            assert_eq!(&segments, expected, "{language}: {text}");
+            assert_eq!(
+                segments.concat(),
+                *text,
+                "{language}: characters lost or reordered"
+            );
+            for segment in &segments {
+                assert!(
+                    segment.len() <= *max_bytes || segment.graphemes(true).count() == 1,
+                    "{language}: {segment:?} is longer than {max_bytes} bytes"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_language_profiles() {
+        assert_eq!(
+            SegmentationProfile::for_language("ja-JP"),
+            SegmentationProfile::Cjk
+        );
+        assert_eq!(
+            SegmentationProfile::for_language("zh_CN"),
+            SegmentationProfile::Cjk
+        );
+        assert_eq!(
+            SegmentationProfile::for_language("YUE"),
+            SegmentationProfile::Cjk
+        );
+        assert_eq!(
+            SegmentationProfile::for_language("hi-IN"),
+            SegmentationProfile::Spaced
+        );
+        assert_eq!(
+            SegmentationProfile::for_language("ko-KR"),
+            SegmentationProfile::Spaced
+        );
+        assert_eq!(
+            SegmentationProfile::for_language(""),
+            SegmentationProfile::Spaced
+        );
+    }
+
+    #[test]
+    fn test_hindi_segments() {
+        check(&[
+            (
+                "hi-IN",
+                "नमस्ते। आप कैसे हैं? मैं ठीक हूँ॥ धन्यवाद",
+                DEFAULT_MAX_SEGMENT_BYTES,
+                &["नमस्ते।", " आप कैसे हैं?", " मैं ठीक हूँ॥", " धन्यवाद"],
+            ),
+            // Over-long sentences are cut at whitespace
+            ("hi-IN", "क्षमा कीजिए", 20, &["क्षमा", " कीजिए"]),
+            // A word longer than the limit keeps its conjuncts whole
+            ("hi-IN", "स्वतंत्रता", 9, &["स्व", "तं", "त्र", "ता"]),
+        ]);
+    }
+
+    #[test]
+    fn test_japanese_segments() {
+        check(&[
+            (
+                "ja-JP",
+                "こんにちは。今日はいい天気ですね！「はい。」と答えた。",
+                DEFAULT_MAX_SEGMENT_BYTES,
+                &[
+                    "こんにちは。",
+                    "今日はいい天気ですね！",
+                    "「はい。」",
+                    "と答えた。",
+                ],
+            ),
+            // Over-long sentences are cut after clause marks
+            (
+                "ja-JP",
+                "東京、大阪、名古屋、福岡",
+                12,
+                &["東京、", "大阪、", "名古屋、", "福岡"],
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_chinese_segments() {
+        check(&[
+            (
+                "zh-CN",
+                "你好!我是小王。今天天气很好,我们去公园吧?好的",
+                DEFAULT_MAX_SEGMENT_BYTES,
+                &["你好!", "我是小王。", "今天天气很好,我们去公园吧?", "好的"],
+            ),
+            // Unsegmented text is cut between characters
+            (
+                "zh-CN",
+                "我们明天早上八点在公司门口见面",
+                12,
+                &["我们明天", "早上八点", "在公司门", "口见面"],
+            ),
+        ]);
+
+        // ASCII terminators before CJK text only end sentences for CJK languages
+        let spaced = TextSegmenter::for_language("en-US");
+        assert_eq!(spaced.split("你好!我是小王。"), vec!["你好!我是小王。"]);
+    }
+
+    #[test]
+    fn test_mixed_script_segments() {
+        check(&[
+            (
+                "en-US",
+                "Hello। नमस्ते. 你好。OK",
+                DEFAULT_MAX_SEGMENT_BYTES,
+                &["Hello।", " नमस्ते.", " 你好。", "OK"],
+            ),
+            (
+                "en-US",
+                "It costs 3.5 dollars. Thanks!\nBye",
+                DEFAULT_MAX_SEGMENT_BYTES,
+                &["It costs 3.5 dollars.", " Thanks!", "\n", "Bye"],
+            ),
+            (
+                "en-US",
+                "He said \"No.\" Then (he left.) OK",
+                DEFAULT_MAX_SEGMENT_BYTES,
+                &["He said \"No.\"", " Then (he left.)", " OK"],
+            ),
+            (
+                "en-US",
+                "one two three four five.",
+                10,
+                &["one two", " three", " four", " five."],
+            ),
+            (
+                "ja-JP",
+                "Tokyo, 東京です。",
+                9,
+                &["Tokyo,", " 東京", "です。"],
+            ),
+            ("en-US", "ééééé", 3, &["é", "é", "é", "é", "é"]),
+        ]);
+    }
+}
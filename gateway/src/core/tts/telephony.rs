@@ -0,0 +1,478 @@
+//! Telephony output profile
+//!
+//! SIP and PSTN bridges expect 8kHz G.711 μ-law audio in fixed 20ms frames.
+//! The telephony profile asks each TTS provider for the closest format it can
+//! produce natively (μ-law at 8kHz where available, otherwise raw PCM) and
+//! converts whatever arrives into 160-byte μ-law frames with
+//! [`TelephonyFramer`].
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::base::{AudioData, TTSConfig, TTSError};
+use crate::plugin::dispatch::{BuiltinTTSProvider, resolve_tts_provider};
+
+/// Sample rate of telephony audio (Hz)
+pub const TELEPHONY_SAMPLE_RATE: u32 = 8000;
+
+/// Duration of one telephony frame (ms)
+pub const TELEPHONY_FRAME_MS: u32 = 20;
+
+/// Size of one telephony frame in bytes (one byte per μ-law sample)
+pub const TELEPHONY_FRAME_BYTES: usize =
+    (TELEPHONY_SAMPLE_RATE * TELEPHONY_FRAME_MS / 1000) as usize;
+
+/// Audio format reported for telephony frames
+pub const TELEPHONY_FORMAT: &str = "mulaw";
+
+/// μ-law encoding of a zero sample, used to pad the last frame
+const MULAW_SILENCE: u8 = 0xFF;
+
+/// Output profile requested from the TTS pipeline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TTSOutputProfile {
+    /// Audio exactly as the provider produces it for `audio_format`/`sample_rate`
+    #[default]
+    Native,
+    /// 8kHz μ-law in 20ms (160-byte) frames
+    Telephony,
+}
+
+/// Resolve the provider configuration for the telephony profile
+///
+/// Requests the closest native format: μ-law at 8kHz from Azure and
+/// ElevenLabs, 8kHz PCM from Polly, 24kHz PCM from OpenAI, and 16-bit PCM at
+/// the configured rate from other built-in providers. Plugin providers must
+/// already be configured for PCM or μ-law output.
+///
+/// # Returns
+/// * `Ok(TTSConfig)` - Configuration to create the provider with
+/// * `Err(TTSError::InvalidConfiguration)` - The provider cannot produce
+///   audio the telephony profile can convert
+pub fn telephony_config(config: &TTSConfig) -> Result<TTSConfig, TTSError> {
+    let mut resolved = config.clone();
+    let (audio_format, sample_rate) = match resolve_tts_provider(&config.provider) {
+        Some(BuiltinTTSProvider::Azure) => ("mulaw", TELEPHONY_SAMPLE_RATE),
+        Some(BuiltinTTSProvider::ElevenLabs) => ("ulaw", TELEPHONY_SAMPLE_RATE),
+        Some(BuiltinTTSProvider::AwsPolly) => ("pcm", TELEPHONY_SAMPLE_RATE),
+        Some(BuiltinTTSProvider::OpenAI) => ("pcm", 24000),
+        Some(_) => ("linear16", config.sample_rate.unwrap_or(24000)),
+        None => {
+            let format = config.audio_format.as_deref().unwrap_or("linear16");
+            let sample_rate = config.sample_rate.unwrap_or(24000);
+            if !is_convertible(format, sample_rate) {
+                return Err(TTSError::InvalidConfiguration(format!(
+                    "TTS provider '{}' cannot produce telephony audio from '{format}' at {sample_rate}Hz; \
+                     configure linear16/pcm or 8kHz mulaw output",
+                    config.provider
+                )));
+            }
+            return Ok(resolved);
+        }
+    };
+    resolved.audio_format = Some(audio_format.to_string());
+    resolved.sample_rate = Some(sample_rate);
+    Ok(resolved)
+}
+
+/// Whether audio in `format` at `sample_rate` can be converted to telephony frames
+fn is_convertible(format: &str, sample_rate: u32) -> bool {
+    match format.to_ascii_lowercase().as_str() {
+        "linear16" | "pcm" => sample_rate > 0,
+        "mulaw" | "ulaw" => sample_rate == TELEPHONY_SAMPLE_RATE,
+        _ => false,
+    }
+}
+
+/// Encode a 16-bit PCM sample as G.711 μ-law
+pub fn linear_to_mulaw(sample: i16) -> u8 {
+    const BIAS: i32 = 0x84;
+    const CLIP: i32 = 32635;
+
+    let mut magnitude = sample as i32;
+    let sign = if magnitude < 0 {
+        magnitude = -magnitude;
+        0x80
+    } else {
+        0
+    };
+    magnitude = magnitude.min(CLIP) + BIAS;
+
+    let mut exponent = 7;
+    let mut mask = 0x4000;
+    while exponent > 0 && magnitude & mask == 0 {
+        exponent -= 1;
+        mask >>= 1;
+    }
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !((sign | (exponent << 4) | mantissa) as u8)
+}
+
+/// Streaming linear-interpolation resampler with a box low-pass filter
+///
+/// The filter averages over one output sample period when downsampling so
+/// content above the target Nyquist rate is attenuated before decimation.
+pub(crate) struct Resampler {
+    source_rate: u32,
+    /// Source samples per output sample
+    step: f64,
+    /// Position of the next output sample within `history`
+    position: f64,
+    /// Filtered source samples not yet consumed
+    history: Vec<f32>,
+    /// Low-pass window
+    window: std::collections::VecDeque<f32>,
+    window_sum: f32,
+    window_len: usize,
+}
+
+impl Resampler {
+    pub(crate) fn new(source_rate: u32, target_rate: u32) -> Self {
+        let step = source_rate as f64 / target_rate as f64;
+        Self {
+            source_rate,
+            step,
+            position: 0.0,
+            history: Vec::new(),
+            window: std::collections::VecDeque::new(),
+            window_sum: 0.0,
+            window_len: step.round().max(1.0) as usize,
+        }
+    }
+
+    pub(crate) fn process(&mut self, samples: &[i16], out: &mut Vec<i16>) {
+        for &sample in samples {
+            let sample = sample as f32;
+            if self.window_len > 1 {
+                self.window.push_back(sample);
+                self.window_sum += sample;
+                if self.window.len() > self.window_len
+                    && let Some(oldest) = self.window.pop_front()
+                {
+                    self.window_sum -= oldest;
+                }
+                self.history
+                    .push(self.window_sum / self.window.len() as f32);
+            } else {
+                self.history.push(sample);
+            }
+        }
+
+        while self.position + 1.0 < self.history.len() as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            let current = self.history[index];
+            let next = self.history[index + 1];
+            out.push(to_i16(current + (next - current) * fraction));
+            self.position += self.step;
+        }
+
+        let consumed = (self.position as usize).min(self.history.len());
+        self.history.drain(..consumed);
+        self.position -= consumed as f64;
+    }
+
+    /// Emit output for the samples held back for interpolation
+    pub(crate) fn flush(&mut self, out: &mut Vec<i16>) {
+        while self.position < self.history.len() as f64 {
+            out.push(to_i16(self.history[self.position as usize]));
+            self.position += self.step;
+        }
+        self.history.clear();
+        self.position = 0.0;
+        self.window.clear();
+        self.window_sum = 0.0;
+    }
+}
+
+fn to_i16(value: f32) -> i16 {
+    value.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+#[derive(Default)]
+struct FramerState {
+    resampler: Option<Resampler>,
+    /// Odd byte left over from a PCM chunk split mid-sample
+    pcm_carry: Option<u8>,
+    /// Encoded μ-law bytes not yet emitted as a frame
+    pending: Vec<u8>,
+}
+
+/// Converts TTS audio into 8kHz μ-law frames of exactly 20ms
+///
+/// Accepts 16-bit little-endian PCM at any sample rate and 8kHz μ-law in
+/// chunks of any size. Partial frames are held until more audio arrives or
+/// the utterance ends with [`flush`](Self::flush).
+#[derive(Default)]
+pub struct TelephonyFramer {
+    state: Mutex<FramerState>,
+}
+
+impl TelephonyFramer {
+    /// Create an empty framer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert a chunk of provider audio
+    ///
+    /// # Returns
+    /// * `Ok(frames)` - Complete 20ms frames (possibly none)
+    /// * `Err(TTSError::InvalidConfiguration)` - The chunk's format cannot be converted
+    pub fn push(&self, audio: &AudioData) -> Result<Vec<AudioData>, TTSError> {
+        let mut state = self.state.lock();
+        match audio.format.to_ascii_lowercase().as_str() {
+            "mulaw" | "ulaw" if audio.sample_rate == TELEPHONY_SAMPLE_RATE => {
+                state.pending.extend_from_slice(&audio.data);
+            }
+            "linear16" | "pcm" if audio.sample_rate > 0 => {
+                let mut bytes = Vec::with_capacity(audio.data.len() + 1);
+                bytes.extend(state.pcm_carry.take());
+                bytes.extend_from_slice(&audio.data);
+                if bytes.len() % 2 == 1 {
+                    state.pcm_carry = bytes.pop();
+                }
+                let samples: Vec<i16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                    .collect();
+
+                if audio.sample_rate == TELEPHONY_SAMPLE_RATE {
+                    state
+                        .pending
+                        .extend(samples.iter().map(|&s| linear_to_mulaw(s)));
+                } else {
+                    if state
+                        .resampler
+                        .as_ref()
+                        .is_none_or(|r| r.source_rate != audio.sample_rate)
+                    {
+                        state.resampler =
+                            Some(Resampler::new(audio.sample_rate, TELEPHONY_SAMPLE_RATE));
+                    }
+                    let mut resampled = Vec::with_capacity(samples.len());
+                    if let Some(resampler) = state.resampler.as_mut() {
+                        resampler.process(&samples, &mut resampled);
+                    }
+                    state
+                        .pending
+                        .extend(resampled.iter().map(|&s| linear_to_mulaw(s)));
+                }
+            }
+            format => {
+                return Err(TTSError::InvalidConfiguration(format!(
+                    "cannot convert {format} audio at {}Hz to telephony frames",
+                    audio.sample_rate
+                )));
+            }
+        }
+
+        let complete = state.pending.len() / TELEPHONY_FRAME_BYTES * TELEPHONY_FRAME_BYTES;
+        let frames = state
+            .pending
+            .drain(..complete)
+            .collect::<Vec<u8>>()
+            .chunks_exact(TELEPHONY_FRAME_BYTES)
+            .map(telephony_frame)
+            .collect();
+        Ok(frames)
+    }
+
+    /// End the utterance, padding any partial frame with silence
+    pub fn flush(&self) -> Option<AudioData> {
+        let mut state = self.state.lock();
+        state.pcm_carry = None;
+        if let Some(mut resampler) = state.resampler.take() {
+            let mut tail = Vec::new();
+            resampler.flush(&mut tail);
+            state
+                .pending
+                .extend(tail.iter().map(|&s| linear_to_mulaw(s)));
+        }
+        if state.pending.is_empty() {
+            return None;
+        }
+        let mut frame = std::mem::take(&mut state.pending);
+        frame.resize(TELEPHONY_FRAME_BYTES, MULAW_SILENCE);
+        Some(telephony_frame(&frame))
+    }
+
+    /// Drop buffered audio (e.g. after the caller barges in)
+    pub fn reset(&self) {
+        *self.state.lock() = FramerState::default();
+    }
+}
+
+fn telephony_frame(data: &[u8]) -> AudioData {
+    AudioData {
+        data: data.to_vec(),
+        sample_rate: TELEPHONY_SAMPLE_RATE,
+        format: TELEPHONY_FORMAT.to_string(),
+        duration_ms: Some(TELEPHONY_FRAME_MS),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm_chunk(samples: &[i16], sample_rate: u32) -> AudioData {
+        AudioData {
+            data: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+            sample_rate,
+            format: "linear16".to_string(),
+            duration_ms: None,
+        }
+    }
+
+    fn tts_config(provider: &str) -> TTSConfig {
+        TTSConfig {
+            provider: provider.to_string(),
+            audio_format: Some("mp3".to_string()),
+            sample_rate: Some(22050),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_telephony_config_requests_native_formats() {
+        let azure = telephony_config(&tts_config("microsoft-azure")).unwrap();
+        assert_eq!(azure.audio_format.as_deref(), Some("mulaw"));
+        assert_eq!(azure.sample_rate, Some(8000));
+
+        let elevenlabs = telephony_config(&tts_config("elevenlabs")).unwrap();
+        assert_eq!(elevenlabs.audio_format.as_deref(), Some("ulaw"));
+        assert_eq!(elevenlabs.sample_rate, Some(8000));
+
+        let polly = telephony_config(&tts_config("polly")).unwrap();
+        assert_eq!(polly.audio_format.as_deref(), Some("pcm"));
+        assert_eq!(polly.sample_rate, Some(8000));
+
+        let deepgram = telephony_config(&tts_config("deepgram")).unwrap();
+        assert_eq!(deepgram.audio_format.as_deref(), Some("linear16"));
+        assert_eq!(deepgram.sample_rate, Some(22050));
+    }
+
+    #[test]
+    fn test_telephony_config_checks_plugin_formats() {
+        assert!(telephony_config(&tts_config("my-plugin")).is_err());
+
+        let pcm = TTSConfig {
+            audio_format: Some("pcm".to_string()),
+            ..tts_config("my-plugin")
+        };
+        assert!(telephony_config(&pcm).is_ok());
+
+        let wideband_mulaw = TTSConfig {
+            audio_format: Some("mulaw".to_string()),
+            sample_rate: Some(16000),
+            ..tts_config("my-plugin")
+        };
+        assert!(telephony_config(&wideband_mulaw).is_err());
+    }
+
+    #[test]
+    fn test_linear_to_mulaw() {
+        assert_eq!(linear_to_mulaw(0), 0xFF);
+        assert_eq!(linear_to_mulaw(-1), 0x7F);
+        assert_eq!(linear_to_mulaw(i16::MAX), 0x80);
+        assert_eq!(linear_to_mulaw(i16::MIN), 0x00);
+        // Louder samples encode to smaller magnitudes (inverted bits)
+        assert!(linear_to_mulaw(1000) > linear_to_mulaw(8000));
+    }
+
+    #[test]
+    fn test_mulaw_input_is_reframed() {
+        let framer = TelephonyFramer::new();
+        let chunk = AudioData {
+            data: vec![0x55; 250],
+            sample_rate: 8000,
+            format: "ulaw".to_string(),
+            duration_ms: None,
+        };
+        let frames = framer.push(&chunk).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data, vec![0x55; TELEPHONY_FRAME_BYTES]);
+
+        // The remaining 90 bytes are padded with silence
+        let tail = framer.flush().unwrap();
+        assert_eq!(tail.data.len(), TELEPHONY_FRAME_BYTES);
+        assert_eq!(&tail.data[..90], &[0x55; 90][..]);
+        assert!(tail.data[90..].iter().all(|&b| b == MULAW_SILENCE));
+        assert!(framer.flush().is_none());
+    }
+
+    #[test]
+    fn test_pcm_split_mid_sample() {
+        let framer = TelephonyFramer::new();
+        let bytes: Vec<u8> = (0..320i16).flat_map(|s| (s * 10).to_le_bytes()).collect();
+        let (first, second) = bytes.split_at(101);
+        let mut frames = framer
+            .push(&AudioData {
+                data: first.to_vec(),
+                sample_rate: 8000,
+                format: "pcm".to_string(),
+                duration_ms: None,
+            })
+            .unwrap();
+        frames.extend(
+            framer
+                .push(&AudioData {
+                    data: second.to_vec(),
+                    sample_rate: 8000,
+                    format: "pcm".to_string(),
+                    duration_ms: None,
+                })
+                .unwrap(),
+        );
+        assert_eq!(frames.len(), 2);
+        let expected: Vec<u8> = (0..320i16).map(|s| linear_to_mulaw(s * 10)).collect();
+        let actual: Vec<u8> = frames.iter().flat_map(|f| f.data.clone()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_pcm_is_resampled_to_8khz() {
+        let framer = TelephonyFramer::new();
+        // One second of 24kHz audio in uneven chunks
+        let samples: Vec<i16> = (0..24000)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+        let mut frames = Vec::new();
+        for chunk in samples.chunks(1234) {
+            frames.extend(framer.push(&pcm_chunk(chunk, 24000)).unwrap());
+        }
+        frames.extend(framer.flush());
+
+        // 8000 samples = 50 frames of 20ms
+        assert_eq!(frames.len(), 50);
+        for frame in &frames {
+            assert_eq!(frame.data.len(), TELEPHONY_FRAME_BYTES);
+            assert_eq!(frame.sample_rate, TELEPHONY_SAMPLE_RATE);
+            assert_eq!(frame.format, TELEPHONY_FORMAT);
+            assert_eq!(frame.duration_ms, Some(TELEPHONY_FRAME_MS));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_format_is_rejected() {
+        let framer = TelephonyFramer::new();
+        let mp3 = AudioData {
+            data: vec![0; 100],
+            sample_rate: 44100,
+            format: "mp3".to_string(),
+            duration_ms: None,
+        };
+        assert!(framer.push(&mp3).is_err());
+    }
+
+    #[test]
+    fn test_reset_drops_partial_frame() {
+        let framer = TelephonyFramer::new();
+        framer.push(&pcm_chunk(&[100; 50], 8000)).unwrap();
+        framer.reset();
+        assert!(framer.flush().is_none());
+    }
+}
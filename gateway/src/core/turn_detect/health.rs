@@ -0,0 +1,234 @@
+//! Startup health of the turn detection model
+//!
+//! [`load_turn_detector`] loads and warms up the model once at startup and
+//! reports the outcome as a [`TurnDetectorHealth`], shown by `GET /readyz`.
+//! Sessions without a model decide end of turn with the silence-based
+//! fallback instead (see `voice_manager::turn_detection`).
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+#[cfg(feature = "turn-detect")]
+use tracing::{debug, warn};
+
+use crate::metrics::global_metrics;
+
+use super::TurnDetector;
+use super::config::TurnDetectorConfig;
+
+/// How a session decides that the user finished their turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TurnDetectionMode {
+    /// The ONNX turn detection model confirms the end of turn
+    Model,
+    /// Caller audio below an energy threshold for long enough ends the turn
+    SilenceVad,
+}
+
+impl TurnDetectionMode {
+    /// The mode's metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Model => "model",
+            Self::SilenceVad => "silence_vad",
+        }
+    }
+}
+
+/// Outcome of loading the turn detection model at startup
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TurnDetectorHealth {
+    /// Whether the gateway was built with the `turn-detect` feature
+    pub enabled: bool,
+    /// Whether the model loaded
+    pub model_loaded: bool,
+    /// Mode new sessions start in
+    pub mode: TurnDetectionMode,
+    /// Why the model failed to load
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Time taken to load and warm up the model (ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_ms: Option<u64>,
+}
+
+impl TurnDetectorHealth {
+    /// Health of a gateway built without the `turn-detect` feature
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            model_loaded: false,
+            mode: TurnDetectionMode::SilenceVad,
+            error: None,
+            load_ms: None,
+        }
+    }
+
+    /// Health after the model loaded in `load_ms`
+    pub fn loaded(load_ms: u64) -> Self {
+        Self {
+            enabled: true,
+            model_loaded: true,
+            mode: TurnDetectionMode::Model,
+            error: None,
+            load_ms: Some(load_ms),
+        }
+    }
+
+    /// Health after the model failed to load
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            enabled: true,
+            model_loaded: false,
+            mode: TurnDetectionMode::SilenceVad,
+            error: Some(error.into()),
+            load_ms: None,
+        }
+    }
+
+    /// Whether sessions run without the model the gateway was built for
+    pub fn is_degraded(&self) -> bool {
+        self.enabled && !self.model_loaded
+    }
+
+    fn record(&self) {
+        if self.enabled {
+            global_metrics().set_gauge(
+                "waav_turn_detection_model_loaded",
+                "Whether the turn detection model loaded at startup (1) or sessions fall back to silence detection (0)",
+                &[],
+                if self.model_loaded { 1.0 } else { 0.0 },
+            );
+        }
+    }
+}
+
+/// Load and warm up the turn detection model
+///
+/// Never fails: a missing model file, a load error or a load that takes
+/// longer than 30 seconds is reported in the returned health, and sessions
+/// fall back to silence-based end-of-turn detection.
+///
+/// # Arguments
+/// * `config` - Model location and inference settings
+///
+/// # Returns
+/// * `(Option<TurnDetector>, TurnDetectorHealth)` - The detector, if it loaded,
+///   and the outcome
+#[cfg(feature = "turn-detect")]
+pub async fn load_turn_detector(
+    config: TurnDetectorConfig,
+) -> (Option<TurnDetector>, TurnDetectorHealth) {
+    use std::time::{Duration, Instant};
+
+    info!("Initializing Turn Detector for speech completion detection");
+    let start = Instant::now();
+
+    // Add a timeout to prevent hanging forever during initialization
+    let init_timeout = Duration::from_secs(30);
+
+    let (detector, health) =
+        match tokio::time::timeout(init_timeout, TurnDetector::with_config(config)).await {
+            Ok(Ok(detector)) => {
+                info!("Turn Detector initialized in {:?}", start.elapsed());
+
+                // Warmup the model with sample inputs to ensure it's fully loaded
+                let warmup_start = Instant::now();
+                let warmup_timeout = Duration::from_secs(10);
+                match tokio::time::timeout(warmup_timeout, warmup_turn_detector(&detector)).await {
+                    Ok(()) => {
+                        info!(
+                            "Turn Detector warmup completed in {:?}",
+                            warmup_start.elapsed()
+                        );
+                    }
+                    Err(_) => {
+                        warn!("Turn Detector warmup timed out after {:?}", warmup_timeout);
+                    }
+                }
+
+                let total_elapsed = start.elapsed();
+                info!("Turn Detector fully ready in {:?}", total_elapsed);
+                let health = TurnDetectorHealth::loaded(total_elapsed.as_millis() as u64);
+                (Some(detector), health)
+            }
+            Ok(Err(e)) => {
+                warn!(
+                    "Failed to initialize Turn Detector: {:?}. \
+                    Falling back to silence-based detection.",
+                    e
+                );
+                (None, TurnDetectorHealth::failed(format!("{e:#}")))
+            }
+            Err(_) => {
+                warn!(
+                    "Turn Detector initialization timed out after {:?}. \
+                    Falling back to silence-based detection.",
+                    init_timeout
+                );
+                let error = format!("initialization timed out after {init_timeout:?}");
+                (None, TurnDetectorHealth::failed(error))
+            }
+        };
+    health.record();
+    (detector, health)
+}
+
+/// Load and warm up the turn detection model
+///
+/// Without the `turn-detect` feature there is no model to load; sessions use
+/// silence-based end-of-turn detection.
+#[cfg(not(feature = "turn-detect"))]
+pub async fn load_turn_detector(
+    config: TurnDetectorConfig,
+) -> (Option<TurnDetector>, TurnDetectorHealth) {
+    let _ = config;
+    info!("Turn detection feature disabled; using silence-based speech_final fallback logic");
+    let health = TurnDetectorHealth::disabled();
+    health.record();
+    (None, health)
+}
+
+/// Warmup the Turn Detector model with sample inputs
+#[cfg(feature = "turn-detect")]
+async fn warmup_turn_detector(detector: &TurnDetector) {
+    use std::time::Instant;
+
+    debug!("Starting Turn Detector warmup with sample inputs");
+
+    // Sample inputs that cover common speech patterns
+    let warmup_samples = [
+        "Hello",
+        "How are you?",
+        "What is the weather like today?",
+        "Thank you very much",
+        "I was wondering if",
+        "Can you help me with",
+        "That's great, thanks!",
+        "Let me think about it",
+        "I need to",
+    ];
+
+    // Run predictions on all samples to ensure model is fully loaded
+    for (i, sample) in warmup_samples.iter().enumerate() {
+        let start = Instant::now();
+        match detector.predict_end_of_turn(sample).await {
+            Ok(probability) => {
+                debug!(
+                    "Warmup sample {} ('{}...'): probability={:.3}, time={:?}",
+                    i + 1,
+                    &sample.chars().take(20).collect::<String>(),
+                    probability,
+                    start.elapsed()
+                );
+            }
+            Err(e) => {
+                warn!("Warmup sample {} failed: {:?}", i + 1, e);
+            }
+        }
+    }
+
+    debug!("Turn Detector warmup completed");
+}
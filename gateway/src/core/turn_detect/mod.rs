@@ -3,6 +3,7 @@ pub mod assets;
 pub mod config;
 #[cfg(feature = "turn-detect")]
 pub mod detector;
+pub mod health;
 #[cfg(feature = "turn-detect")]
 pub mod model_manager;
 #[cfg(feature = "turn-detect")]
@@ -12,6 +13,7 @@ pub mod tokenizer;
 mod stub;
 
 pub use config::TurnDetectorConfig;
+pub use health::{TurnDetectionMode, TurnDetectorHealth, load_turn_detector};
 
 #[cfg(feature = "turn-detect")]
 pub use detector::{TurnDetector, TurnDetectorBuilder};
@@ -0,0 +1,677 @@
+//! Pre-connection checks of STT and TTS configs
+//!
+//! Many misconfigurations, such as a missing ElevenLabs voice or a sample
+//! rate Cartesia does not accept, otherwise only fail once a session has
+//! started and the provider rejects the connection. [`STTConfig::validate`]
+//! and [`TTSConfig::validate`] check a config against the rules of its
+//! provider up front and report every problem at once. The provider
+//! factories run the same checks before constructing a provider.
+//!
+//! Built-in providers have their own rules below. Runtime-registered and
+//! dynamically loaded providers are checked against what their
+//! [`ProviderMetadata`] declares: sample rates, encodings and whether an
+//! API key is needed. Lists left empty are not checked.
+//!
+//! Models are checked separately when a session is set up, for built-in
+//! and runtime providers alike: [`STTConfig::normalize_model`] and
+//! [`TTSConfig::normalize_model`] map the model to its canonical name in
+//! the provider's known-model list and reject unlisted models, since most
+//! providers silently fall back to their default model instead.
+//!
+//! Credentials resolved at connect time (Google ADC, the AWS default
+//! credential chain for Transcribe and Polly) cannot be checked here.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::core::stt::{STTConfig, STTError};
+use crate::core::tts::google::{CHIRP3_HD_SAMPLE_RATE, GoogleTTSConfig};
+use crate::core::tts::{TTSConfig, TTSError};
+use crate::plugin::dispatch::{resolve_stt_provider, resolve_tts_provider};
+use crate::plugin::metadata::ProviderMetadata;
+use crate::plugin::{PluginRegistry, global_registry};
+
+/// Config field holding the provider API key
+const API_KEY_FIELD: &str = "api_key";
+
+/// A problem with one field of a provider config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConfigIssue {
+    /// Field the issue is about, e.g. `sample_rate` or `stt_config.voice_id`
+    pub field: String,
+    /// What is wrong with the field
+    pub message: String,
+}
+
+impl ConfigIssue {
+    /// Issue with `field`
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// The same issue with its field placed under `prefix`
+    pub fn prefixed(mut self, prefix: &str) -> Self {
+        self.field = format!("{prefix}.{}", self.field);
+        self
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Error message listing every issue
+pub fn config_issues_message(issues: &[ConfigIssue]) -> String {
+    let issues = issues
+        .iter()
+        .map(ConfigIssue::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("Invalid provider configuration: {issues}")
+}
+
+/// STT error for a config that failed validation
+///
+/// A config whose only problem is a missing key fails the way provider
+/// constructors always reported it, as an authentication error.
+pub(crate) fn stt_validation_error(issues: &[ConfigIssue]) -> STTError {
+    if issues.iter().all(|issue| issue.field == API_KEY_FIELD) {
+        let messages = issues
+            .iter()
+            .map(|issue| issue.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        STTError::AuthenticationFailed(messages)
+    } else {
+        STTError::ConfigurationError(config_issues_message(issues))
+    }
+}
+
+/// TTS error for a config that failed validation
+pub(crate) fn tts_validation_error(issues: &[ConfigIssue]) -> TTSError {
+    TTSError::InvalidConfiguration(config_issues_message(issues))
+}
+
+impl STTConfig {
+    /// Check this config against the rules of its provider
+    ///
+    /// Returns every issue found, or a single `provider` issue when the
+    /// provider is not registered.
+    pub fn validate(&self) -> Result<(), Vec<ConfigIssue>> {
+        global_registry().validate_stt_config(&self.provider, self)
+    }
+
+    /// Replace `model` by its canonical name among the provider's known models
+    ///
+    /// Names and aliases match case-insensitively. A model the provider does
+    /// not list is rejected with the closest known model as a suggestion,
+    /// unless `allow_unknown` is set. Unregistered providers are left to
+    /// [`validate`](Self::validate).
+    pub fn normalize_model(&mut self, allow_unknown: bool) -> Result<(), ConfigIssue> {
+        self.normalize_model_with(global_registry(), allow_unknown)
+    }
+
+    /// [`normalize_model`](Self::normalize_model) against the providers of `registry`
+    pub fn normalize_model_with(
+        &mut self,
+        registry: &PluginRegistry,
+        allow_unknown: bool,
+    ) -> Result<(), ConfigIssue> {
+        let id = resolve_stt_provider(&self.provider)
+            .map(|p| p.canonical_name().to_string())
+            .unwrap_or_else(|| self.provider.to_lowercase());
+        match registry.get_stt_metadata(&id) {
+            Some(metadata) => normalize_model(&metadata, &mut self.model, allow_unknown),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TTSConfig {
+    /// Check this config against the rules of its provider
+    ///
+    /// Returns every issue found, or a single `provider` issue when the
+    /// provider is not registered.
+    pub fn validate(&self) -> Result<(), Vec<ConfigIssue>> {
+        global_registry().validate_tts_config(&self.provider, self)
+    }
+
+    /// Replace `model` by its canonical name among the provider's known models
+    ///
+    /// See [`STTConfig::normalize_model`].
+    pub fn normalize_model(&mut self, allow_unknown: bool) -> Result<(), ConfigIssue> {
+        self.normalize_model_with(global_registry(), allow_unknown)
+    }
+
+    /// [`normalize_model`](Self::normalize_model) against the providers of `registry`
+    pub fn normalize_model_with(
+        &mut self,
+        registry: &PluginRegistry,
+        allow_unknown: bool,
+    ) -> Result<(), ConfigIssue> {
+        let id = resolve_tts_provider(&self.provider)
+            .map(|p| p.canonical_name().to_string())
+            .unwrap_or_else(|| self.provider.to_lowercase());
+        match registry.get_tts_metadata(&id) {
+            Some(metadata) => normalize_model(&metadata, &mut self.model, allow_unknown),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Issues with `config` for the STT provider registered as `provider`
+///
+/// `provider` is the canonical provider id. `metadata` is only consulted
+/// for providers that are not built in.
+pub fn stt_config_issues(
+    provider: &str,
+    config: &STTConfig,
+    metadata: Option<&ProviderMetadata>,
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    if config.sample_rate == 0 {
+        issues.push(ConfigIssue::new("sample_rate", "must be greater than 0"));
+    }
+    if config.channels == 0 {
+        issues.push(ConfigIssue::new("channels", "must be at least 1"));
+    }
+
+    if resolve_stt_provider(provider).is_none() {
+        if let Some(metadata) = metadata {
+            issues.extend(metadata_issues(
+                metadata,
+                &config.api_key,
+                Some(config.sample_rate),
+                Some(config.encoding.as_str()),
+            ));
+        }
+        return issues;
+    }
+
+    match provider {
+        "deepgram" | "openai" | "groq" | "ibm-watson" => {
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+        }
+        "microsoft-azure" => {
+            require_api_key(
+                &mut issues,
+                &config.api_key,
+                "Azure subscription key is required",
+            );
+        }
+        "elevenlabs" => {
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+            check_sample_rate_in(
+                &mut issues,
+                config.sample_rate,
+                crate::core::stt::elevenlabs::SUPPORTED_SAMPLE_RATES,
+            );
+        }
+        "cartesia" => {
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+            check_sample_rate_in(
+                &mut issues,
+                config.sample_rate,
+                crate::core::stt::cartesia::SUPPORTED_SAMPLE_RATES,
+            );
+        }
+        "assemblyai" => {
+            use crate::core::stt::assemblyai::{MAX_SAMPLE_RATE, MIN_SAMPLE_RATE};
+
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+            check_sample_rate_range(
+                &mut issues,
+                config.sample_rate,
+                MIN_SAMPLE_RATE,
+                MAX_SAMPLE_RATE,
+            );
+        }
+        "aws-transcribe" => {
+            use crate::core::stt::aws_transcribe::{MAX_SAMPLE_RATE, MIN_SAMPLE_RATE};
+
+            check_sample_rate_range(
+                &mut issues,
+                config.sample_rate,
+                MIN_SAMPLE_RATE,
+                MAX_SAMPLE_RATE,
+            );
+        }
+        // Google and Gnani authenticate with credential files resolved at connect
+        _ => {}
+    }
+
+    issues
+}
+
+/// Issues with `config` for the TTS provider registered as `provider`
+///
+/// `provider` is the canonical provider id. `metadata` is only consulted
+/// for providers that are not built in.
+pub fn tts_config_issues(
+    provider: &str,
+    config: &TTSConfig,
+    metadata: Option<&ProviderMetadata>,
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    if config.sample_rate == Some(0) {
+        issues.push(ConfigIssue::new("sample_rate", "must be greater than 0"));
+    }
+
+    if resolve_tts_provider(provider).is_none() {
+        if let Some(metadata) = metadata {
+            issues.extend(metadata_issues(
+                metadata,
+                &config.api_key,
+                config.sample_rate,
+                config.audio_format.as_deref(),
+            ));
+        }
+        return issues;
+    }
+
+    match provider {
+        "deepgram" | "openai" | "hume" | "lmnt" | "ibm-watson" => {
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+        }
+        "microsoft-azure" => {
+            require_api_key(
+                &mut issues,
+                &config.api_key,
+                "Azure subscription key is required",
+            );
+        }
+        "elevenlabs" | "playht" => {
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+            require_voice(&mut issues, config.voice_id.as_deref());
+        }
+        "cartesia" => {
+            require_api_key(&mut issues, &config.api_key, "API key is required");
+            if let Some(sample_rate) = config.sample_rate.filter(|rate| *rate > 0) {
+                check_sample_rate_in(
+                    &mut issues,
+                    sample_rate,
+                    crate::core::tts::cartesia::SUPPORTED_SAMPLE_RATES,
+                );
+            }
+        }
+        "google" => {
+            // Credentials are resolved at connect; Chirp 3 HD voices stream
+            // fixed-format audio
+            let google = GoogleTTSConfig::from_base_config(config.clone(), String::new());
+            if google.streaming_voice_name().is_some() {
+                if let Some(format) = config.audio_format.as_deref()
+                    && !matches!(format.to_lowercase().as_str(), "linear16" | "pcm")
+                {
+                    issues.push(ConfigIssue::new(
+                        "audio_format",
+                        format!("Chirp 3 HD voices stream linear16 audio only (got '{format}')"),
+                    ));
+                }
+                if let Some(sample_rate) = config.sample_rate.filter(|rate| *rate > 0) {
+                    check_sample_rate_in(&mut issues, sample_rate, &[CHIRP3_HD_SAMPLE_RATE]);
+                }
+            }
+        }
+        // Gnani uses credential files and Polly uses the AWS credential
+        // chain, both resolved at connect
+        _ => {}
+    }
+
+    issues
+}
+
+/// Issues against the lists a runtime-registered provider declares
+fn metadata_issues(
+    metadata: &ProviderMetadata,
+    api_key: &str,
+    sample_rate: Option<u32>,
+    encoding: Option<&str>,
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    if metadata.requires_api_key {
+        require_api_key(&mut issues, api_key, "API key is required");
+    }
+    if let Some(sample_rate) = sample_rate.filter(|rate| *rate > 0)
+        && !metadata.supported_sample_rates.is_empty()
+    {
+        check_sample_rate_in(&mut issues, sample_rate, &metadata.supported_sample_rates);
+    }
+    if let Some(encoding) = encoding
+        && !metadata.supported_encodings.is_empty()
+        && !metadata
+            .supported_encodings
+            .iter()
+            .any(|supported| supported.eq_ignore_ascii_case(encoding))
+    {
+        issues.push(ConfigIssue::new(
+            "encoding",
+            format!(
+                "'{encoding}' is not supported (supported: {})",
+                metadata.supported_encodings.join(", ")
+            ),
+        ));
+    }
+
+    issues
+}
+
+/// Replace `model` by its canonical name among the models `metadata` lists
+///
+/// Providers that list no models, and empty models (the provider default),
+/// are left alone. An unlisted model is an error naming the closest listed
+/// model, unless `allow_unknown` passes it through unchanged.
+fn normalize_model(
+    metadata: &ProviderMetadata,
+    model: &mut String,
+    allow_unknown: bool,
+) -> Result<(), ConfigIssue> {
+    if model.is_empty() || metadata.supported_models.is_empty() {
+        return Ok(());
+    }
+    if let Some(canonical) = metadata.resolve_model(model) {
+        *model = canonical.to_string();
+        return Ok(());
+    }
+    if allow_unknown {
+        return Ok(());
+    }
+
+    let message = match metadata.suggest_model(model) {
+        Some(suggestion) => format!(
+            "unknown {} model '{model}' (did you mean '{suggestion}'?); set allow_unknown_models to use it anyway",
+            metadata.name
+        ),
+        None => format!(
+            "unknown {} model '{model}' (known: {}); set allow_unknown_models to use it anyway",
+            metadata.name,
+            metadata.supported_models.join(", ")
+        ),
+    };
+    Err(ConfigIssue::new("model", message))
+}
+
+fn require_api_key(issues: &mut Vec<ConfigIssue>, api_key: &str, message: &str) {
+    if api_key.trim().is_empty() {
+        issues.push(ConfigIssue::new(API_KEY_FIELD, message));
+    }
+}
+
+fn require_voice(issues: &mut Vec<ConfigIssue>, voice_id: Option<&str>) {
+    if voice_id.is_none_or(|voice| voice.trim().is_empty()) {
+        issues.push(ConfigIssue::new("voice_id", "a voice is required"));
+    }
+}
+
+fn check_sample_rate_in(issues: &mut Vec<ConfigIssue>, sample_rate: u32, supported: &[u32]) {
+    if !supported.contains(&sample_rate) {
+        issues.push(ConfigIssue::new(
+            "sample_rate",
+            format!("{sample_rate} Hz is not supported (supported: {supported:?})"),
+        ));
+    }
+}
+
+fn check_sample_rate_range(issues: &mut Vec<ConfigIssue>, sample_rate: u32, min: u32, max: u32) {
+    if sample_rate > 0 && !(min..=max).contains(&sample_rate) {
+        issues.push(ConfigIssue::new(
+            "sample_rate",
+            format!("{sample_rate} Hz is outside the supported range ({min}-{max} Hz)"),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stt(provider: &str, api_key: &str, sample_rate: u32) -> STTConfig {
+        STTConfig {
+            provider: provider.to_string(),
+            api_key: api_key.to_string(),
+            sample_rate,
+            ..Default::default()
+        }
+    }
+
+    fn tts(provider: &str, api_key: &str) -> TTSConfig {
+        TTSConfig {
+            provider: provider.to_string(),
+            api_key: api_key.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn fields(issues: &[ConfigIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_api_key_providers() {
+        for provider in [
+            "deepgram",
+            "openai",
+            "groq",
+            "microsoft-azure",
+            "ibm-watson",
+        ] {
+            let issues = stt_config_issues(provider, &stt(provider, "", 16000), None);
+            assert_eq!(fields(&issues), vec!["api_key"], "{provider}");
+            assert!(stt_config_issues(provider, &stt(provider, "key", 16000), None).is_empty());
+        }
+        for provider in [
+            "deepgram",
+            "openai",
+            "hume",
+            "lmnt",
+            "microsoft-azure",
+            "ibm-watson",
+        ] {
+            let issues = tts_config_issues(provider, &tts(provider, " "), None);
+            assert_eq!(fields(&issues), vec!["api_key"], "{provider}");
+            assert!(tts_config_issues(provider, &tts(provider, "key"), None).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_sample_rate_set_providers() {
+        for provider in ["cartesia", "elevenlabs"] {
+            assert!(stt_config_issues(provider, &stt(provider, "key", 22050), None).is_empty());
+            let issues = stt_config_issues(provider, &stt(provider, "", 11025), None);
+            assert_eq!(
+                fields(&issues),
+                vec!["api_key", "sample_rate"],
+                "{provider}"
+            );
+        }
+
+        let mut config = tts("cartesia", "key");
+        config.sample_rate = Some(32000);
+        let issues = tts_config_issues("cartesia", &config, None);
+        assert_eq!(fields(&issues), vec!["sample_rate"]);
+        assert!(issues[0].message.contains("32000 Hz"));
+    }
+
+    #[test]
+    fn test_sample_rate_range_providers() {
+        for provider in ["assemblyai", "aws-transcribe"] {
+            assert!(stt_config_issues(provider, &stt(provider, "key", 8000), None).is_empty());
+            let issues = stt_config_issues(provider, &stt(provider, "key", 96000), None);
+            assert_eq!(fields(&issues), vec!["sample_rate"], "{provider}");
+        }
+        // AWS credentials come from the default chain, not the config
+        assert!(
+            stt_config_issues("aws-transcribe", &stt("aws-transcribe", "", 16000), None).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_voice_providers() {
+        for provider in ["elevenlabs", "playht"] {
+            let mut config = tts(provider, "");
+            config.voice_id = Some(String::new());
+            let issues = tts_config_issues(provider, &config, None);
+            assert_eq!(fields(&issues), vec!["api_key", "voice_id"], "{provider}");
+
+            let mut config = tts(provider, "key");
+            config.voice_id = Some("voice".to_string());
+            assert!(tts_config_issues(provider, &config, None).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_connect_time_credential_providers() {
+        for provider in ["google", "gnani"] {
+            assert!(stt_config_issues(provider, &stt(provider, "", 16000), None).is_empty());
+        }
+        for provider in ["google", "gnani", "aws-polly"] {
+            assert!(tts_config_issues(provider, &tts(provider, ""), None).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_google_streaming_voice_format() {
+        let mut config = tts("google", "");
+        config.voice_id = Some("en-US-Chirp3-HD-Charon".to_string());
+        assert!(tts_config_issues("google", &config, None).is_empty());
+
+        config.audio_format = Some("mp3".to_string());
+        config.sample_rate = Some(16000);
+        let issues = tts_config_issues("google", &config, None);
+        assert_eq!(fields(&issues), vec!["audio_format", "sample_rate"]);
+
+        // REST voices accept any format
+        config.voice_id = Some("en-US-Wavenet-D".to_string());
+        assert!(tts_config_issues("google", &config, None).is_empty());
+    }
+
+    #[test]
+    fn test_common_rules() {
+        let mut config = stt("deepgram", "key", 0);
+        config.channels = 0;
+        let issues = stt_config_issues("deepgram", &config, None);
+        assert_eq!(fields(&issues), vec!["sample_rate", "channels"]);
+
+        let mut config = tts("deepgram", "key");
+        config.sample_rate = Some(0);
+        assert_eq!(
+            fields(&tts_config_issues("deepgram", &config, None)),
+            vec!["sample_rate"]
+        );
+    }
+
+    #[test]
+    fn test_runtime_provider_uses_metadata() {
+        let metadata = ProviderMetadata::stt("custom", "Custom")
+            .with_models(["fast", "accurate"])
+            .with_sample_rates([8000, 16000])
+            .with_encodings(["linear16"])
+            .with_api_key_required(true);
+
+        let mut config = stt("custom", "", 44100);
+        config.encoding = "opus".to_string();
+        let issues = stt_config_issues("custom", &config, Some(&metadata));
+        assert_eq!(fields(&issues), vec!["api_key", "sample_rate", "encoding"]);
+
+        let mut config = stt("custom", "key", 16000);
+        config.model = "fast".to_string();
+        config.encoding = "LINEAR16".to_string();
+        assert!(stt_config_issues("custom", &config, Some(&metadata)).is_empty());
+
+        // Metadata without declared lists does not constrain the config
+        let bare = ProviderMetadata::stt("custom", "Custom");
+        assert!(stt_config_issues("custom", &STTConfig::default(), Some(&bare)).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_model() {
+        let metadata = ProviderMetadata::stt("custom", "Custom")
+            .with_models(["fast", "accurate"])
+            .with_model_alias("fast-latest", "fast");
+        let normalized = |model: &str, allow_unknown| {
+            let mut model = model.to_string();
+            normalize_model(&metadata, &mut model, allow_unknown).map(|()| model)
+        };
+
+        assert_eq!(normalized("Accurate", false).unwrap(), "accurate");
+        assert_eq!(normalized("FAST-LATEST", false).unwrap(), "fast");
+        assert_eq!(normalized("", false).unwrap(), "");
+
+        let issue = normalized("acurate", false).unwrap_err();
+        assert_eq!(issue.field, "model");
+        assert!(issue.message.contains("did you mean 'accurate'?"));
+        let issue = normalized("whisper-large", false).unwrap_err();
+        assert!(issue.message.contains("known: fast, accurate"));
+
+        // The escape hatch passes unlisted models through unchanged
+        assert_eq!(normalized("Turbo-2", true).unwrap(), "Turbo-2");
+
+        // Providers that list no models accept any
+        let mut model = "anything".to_string();
+        assert!(normalize_model(&ProviderMetadata::stt("bare", "Bare"), &mut model, false).is_ok());
+    }
+
+    #[test]
+    fn test_builtin_provider_models() {
+        let mut config = stt("deepgram", "key", 16000);
+        config.model = "nova3".to_string();
+        let issue = config.normalize_model(false).unwrap_err();
+        assert!(issue.message.contains("did you mean 'nova-3'?"));
+        assert!(config.normalize_model(true).is_ok());
+        assert_eq!(config.model, "nova3");
+
+        config.model = "Nova-2-PhoneCall".to_string();
+        config.normalize_model(false).unwrap();
+        assert_eq!(config.model, "nova-2-phonecall");
+        config.model = "nova-3-general".to_string();
+        config.normalize_model(false).unwrap();
+        assert_eq!(config.model, "nova-3");
+
+        let mut config = tts("elevenlabs", "key");
+        config.model = "Eleven_Flash_V2_5".to_string();
+        config.normalize_model(false).unwrap();
+        assert_eq!(config.model, "eleven_flash_v2_5");
+
+        // Providers without a model list are not checked
+        let mut config = stt("assemblyai", "key", 16000);
+        config.model = "anything".to_string();
+        assert!(config.normalize_model(false).is_ok());
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let key_only = vec![ConfigIssue::new("api_key", "API key is required")];
+        assert!(matches!(
+            stt_validation_error(&key_only),
+            STTError::AuthenticationFailed(msg) if msg == "API key is required"
+        ));
+
+        let issues = vec![
+            ConfigIssue::new("api_key", "API key is required"),
+            ConfigIssue::new("sample_rate", "must be greater than 0").prefixed("stt_config"),
+        ];
+        assert_eq!(
+            stt_validation_error(&issues).to_string(),
+            "Configuration error: Invalid provider configuration: api_key: API key is required; stt_config.sample_rate: must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_provider() {
+        let issues = stt("no-such-provider", "key", 16000)
+            .validate()
+            .unwrap_err();
+        assert_eq!(fields(&issues), vec!["provider"]);
+        assert!(stt("deepgram", "key", 16000).validate().is_ok());
+        assert!(tts("azure", "key").validate().is_ok());
+    }
+}
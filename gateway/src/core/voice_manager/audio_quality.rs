@@ -0,0 +1,538 @@
+//! Sanity checks on synthesized audio
+//!
+//! Providers occasionally return audio that is technically valid but unusable:
+//! hard-clipped, all digital silence, or so quiet it can't be heard. None of
+//! these surface as a provider error. [`AudioQualityChecker`] measures the
+//! 16-bit PCM audio of every utterance (the audio between two provider
+//! completions) and reports a [`TTSAudioQualityWarning`] when it crosses the
+//! thresholds in [`TTSAudioQualityConfig`], optionally asking the provider to
+//! synthesize the utterance once more.
+//!
+//! Compressed formats (μ-law, MP3, Opus, ...) are not checked: measuring them
+//! would mean decoding every utterance.
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::core::tts::{AudioData, BaseTTS};
+use crate::metrics::global_metrics;
+
+use super::callbacks::TTSAudioQualityCallback;
+
+/// Thresholds for the TTS audio sanity check
+///
+/// # Example JSON
+/// ```json
+/// {"max_clipped_fraction": 0.01, "min_rms_dbfs": -60.0, "retry": true}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct TTSAudioQualityConfig {
+    /// Whether synthesized audio is checked at all
+    pub enabled: bool,
+    /// Largest tolerated fraction of samples at full scale (0.0 - 1.0)
+    pub max_clipped_fraction: f32,
+    /// Largest tolerated fraction of samples that are exactly zero (0.0 - 1.0)
+    pub max_zero_fraction: f32,
+    /// Quietest tolerated RMS level of an utterance (dBFS)
+    pub min_rms_dbfs: f32,
+    /// Synthesize a failing utterance once more
+    pub retry: bool,
+}
+
+impl Default for TTSAudioQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_clipped_fraction: 0.01,
+            max_zero_fraction: 0.95,
+            min_rms_dbfs: -60.0,
+            retry: false,
+        }
+    }
+}
+
+/// Level reported for utterances without any signal (dBFS)
+const SILENCE_DBFS: f32 = -100.0;
+
+/// Utterances shorter than this are too short to judge
+const MIN_CHECKED_MS: u64 = 100;
+
+impl TTSAudioQualityConfig {
+    /// Validate the thresholds
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("max_clipped_fraction", self.max_clipped_fraction),
+            ("max_zero_fraction", self.max_zero_fraction),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("{name} must be between 0 and 1 (got {value})"));
+            }
+        }
+        if !(SILENCE_DBFS..=0.0).contains(&self.min_rms_dbfs) {
+            return Err(format!(
+                "min_rms_dbfs must be between {SILENCE_DBFS} and 0 (got {})",
+                self.min_rms_dbfs
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A threshold an utterance failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AudioQualityIssue {
+    /// Too many samples at full scale
+    Clipped,
+    /// Too many samples that are exactly zero
+    Silent,
+    /// RMS level below `min_rms_dbfs`
+    LowLevel,
+}
+
+impl AudioQualityIssue {
+    /// The issue's metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Clipped => "clipped",
+            Self::Silent => "silent",
+            Self::LowLevel => "low_level",
+        }
+    }
+}
+
+/// An utterance whose audio failed the sanity check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TTSAudioQualityWarning {
+    /// Thresholds the utterance failed
+    pub issues: Vec<AudioQualityIssue>,
+    /// Fraction of samples at full scale
+    pub clipped_fraction: f32,
+    /// Fraction of samples that are exactly zero
+    pub zero_fraction: f32,
+    /// RMS level of the utterance (dBFS)
+    pub rms_dbfs: f32,
+    /// Length of the checked audio (ms)
+    pub duration_ms: u64,
+    /// Text of the utterance, when known
+    pub text: String,
+    /// Whether the utterance is being synthesized once more
+    pub retrying: bool,
+    /// Whether this utterance was itself a retry
+    pub retried: bool,
+}
+
+/// Sample counts of the utterance being measured
+#[derive(Debug, Default)]
+struct UtteranceStats {
+    samples: u64,
+    clipped: u64,
+    zeros: u64,
+    sum_squares: f64,
+    sample_rate: u32,
+    /// Odd trailing byte of the previous chunk
+    carry: Option<u8>,
+}
+
+impl UtteranceStats {
+    fn push(&mut self, audio: &AudioData) {
+        self.sample_rate = audio.sample_rate;
+        let mut data = audio.data.as_slice();
+        if data.is_empty() {
+            return;
+        }
+        if let Some(low) = self.carry.take()
+            && let Some((&high, rest)) = data.split_first()
+        {
+            self.push_sample(i16::from_le_bytes([low, high]));
+            data = rest;
+        }
+        let mut samples = data.chunks_exact(2);
+        for sample in samples.by_ref() {
+            self.push_sample(i16::from_le_bytes([sample[0], sample[1]]));
+        }
+        self.carry = samples.remainder().first().copied();
+    }
+
+    fn push_sample(&mut self, sample: i16) {
+        self.samples += 1;
+        match sample {
+            0 => self.zeros += 1,
+            i16::MAX | i16::MIN => self.clipped += 1,
+            _ => {}
+        }
+        self.sum_squares += f64::from(sample) * f64::from(sample);
+    }
+
+    fn duration_ms(&self) -> u64 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        self.samples * 1000 / u64::from(self.sample_rate)
+    }
+
+    /// Compare the utterance against the thresholds
+    ///
+    /// Returns `None` for utterances too short to judge or that passed.
+    fn check(&self, config: &TTSAudioQualityConfig) -> Option<TTSAudioQualityWarning> {
+        let duration_ms = self.duration_ms();
+        if duration_ms < MIN_CHECKED_MS {
+            return None;
+        }
+
+        let samples = self.samples as f64;
+        let clipped_fraction = (self.clipped as f64 / samples) as f32;
+        let zero_fraction = (self.zeros as f64 / samples) as f32;
+        let rms = (self.sum_squares / samples).sqrt();
+        let rms_dbfs = if rms > 0.0 {
+            ((20.0 * (rms / 32768.0).log10()) as f32).max(SILENCE_DBFS)
+        } else {
+            SILENCE_DBFS
+        };
+
+        let mut issues = Vec::new();
+        if clipped_fraction > config.max_clipped_fraction {
+            issues.push(AudioQualityIssue::Clipped);
+        }
+        if zero_fraction > config.max_zero_fraction {
+            issues.push(AudioQualityIssue::Silent);
+        }
+        if rms_dbfs < config.min_rms_dbfs {
+            issues.push(AudioQualityIssue::LowLevel);
+        }
+        if issues.is_empty() {
+            return None;
+        }
+
+        Some(TTSAudioQualityWarning {
+            issues,
+            clipped_fraction,
+            zero_fraction,
+            rms_dbfs,
+            duration_ms,
+            text: String::new(),
+            retrying: false,
+            retried: false,
+        })
+    }
+}
+
+/// Whether the audio is 16-bit PCM that can be measured without decoding
+pub(super) fn is_pcm16(format: &str) -> bool {
+    matches!(
+        format.to_ascii_lowercase().as_str(),
+        "linear16" | "pcm" | "pcm16"
+    )
+}
+
+struct CheckerState {
+    stats: UtteranceStats,
+    /// Text sent since the provider last completed
+    texts: Vec<(String, bool)>,
+    /// Set while the provider re-synthesizes a failed utterance
+    retrying: bool,
+    /// Incremented whenever pending TTS is cleared, cancelling retries
+    generation: u64,
+}
+
+/// Measures synthesized audio per utterance and reports failures
+pub struct AudioQualityChecker {
+    config: TTSAudioQualityConfig,
+    provider: String,
+    tts: Arc<RwLock<Box<dyn BaseTTS>>>,
+    state: Mutex<CheckerState>,
+    /// Set once a compressed format has been logged as unchecked
+    skip_logged: AtomicBool,
+    callback: SyncRwLock<Option<TTSAudioQualityCallback>>,
+}
+
+impl AudioQualityChecker {
+    /// Create a checker for the provider behind `tts`
+    ///
+    /// # Arguments
+    /// * `config` - Thresholds and retry behaviour
+    /// * `provider` - Provider name, used as metric label
+    /// * `tts` - The VoiceManager's TTS provider slot, used for retries
+    pub fn new(
+        config: TTSAudioQualityConfig,
+        provider: impl Into<String>,
+        tts: Arc<RwLock<Box<dyn BaseTTS>>>,
+    ) -> Self {
+        Self {
+            config,
+            provider: provider.into(),
+            tts,
+            state: Mutex::new(CheckerState {
+                stats: UtteranceStats::default(),
+                texts: Vec::new(),
+                retrying: false,
+                generation: 0,
+            }),
+            skip_logged: AtomicBool::new(false),
+            callback: SyncRwLock::new(None),
+        }
+    }
+
+    /// Register the callback notified about failed utterances
+    pub fn set_callback(&self, callback: TTSAudioQualityCallback) {
+        *self.callback.write() = Some(callback);
+    }
+
+    /// Remember text sent to the provider, so a failed utterance can be retried
+    ///
+    /// Call while holding the TTS write lock, right before `speak()`.
+    pub fn record(&self, text: &str, flush: bool) {
+        self.state.lock().texts.push((text.to_string(), flush));
+    }
+
+    /// Add a chunk of provider audio to the current utterance
+    pub fn measure(&self, audio: &AudioData) {
+        if !is_pcm16(&audio.format) {
+            if !self.skip_logged.swap(true, Ordering::Relaxed) {
+                debug!(
+                    format = %audio.format,
+                    "Skipping TTS audio quality check for compressed audio"
+                );
+            }
+            return;
+        }
+        self.state.lock().stats.push(audio);
+    }
+
+    /// Close the current utterance after the provider completed
+    ///
+    /// Records the failure metric and, when configured, starts the retry.
+    ///
+    /// # Returns
+    /// * `Option<TTSAudioQualityWarning>` - The failure to report, if any
+    pub fn finish(self: &Arc<Self>) -> Option<TTSAudioQualityWarning> {
+        let mut state = self.state.lock();
+        let stats = std::mem::take(&mut state.stats);
+        let texts = std::mem::take(&mut state.texts);
+        let retried = std::mem::replace(&mut state.retrying, false);
+
+        let mut warning = stats.check(&self.config)?;
+        warning.text = texts
+            .iter()
+            .map(|(text, _)| text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        warning.retried = retried;
+        warning.retrying = self.config.retry && !retried && !texts.is_empty();
+
+        for issue in &warning.issues {
+            global_metrics().inc_counter(
+                "waav_tts_audio_quality_warnings_total",
+                "TTS utterances whose audio failed a quality check",
+                &[("provider", &self.provider), ("issue", issue.as_str())],
+            );
+        }
+        warn!(
+            provider = %self.provider,
+            issues = ?warning.issues,
+            clipped_fraction = warning.clipped_fraction,
+            zero_fraction = warning.zero_fraction,
+            rms_dbfs = warning.rms_dbfs,
+            retrying = warning.retrying,
+            "TTS audio failed quality check"
+        );
+
+        if warning.retrying {
+            state.retrying = true;
+            state.texts = texts.clone();
+            let generation = state.generation;
+            drop(state);
+            tokio::spawn(self.clone().resynthesize(generation, texts));
+        }
+        Some(warning)
+    }
+
+    /// Forget the current utterance and cancel a pending retry after the TTS
+    /// queue was cleared
+    pub fn clear(&self) {
+        let mut state = self.state.lock();
+        state.stats = UtteranceStats::default();
+        state.texts.clear();
+        state.retrying = false;
+        state.generation += 1;
+    }
+
+    /// Report a failed utterance to the registered callback
+    pub async fn notify(&self, warning: TTSAudioQualityWarning) {
+        let callback = self.callback.read().clone();
+        if let Some(callback) = callback {
+            callback(warning).await;
+        }
+    }
+
+    async fn resynthesize(self: Arc<Self>, generation: u64, texts: Vec<(String, bool)>) {
+        // Runs on its own task: providers may complete from inside `speak()`,
+        // while the caller still holds the TTS lock
+        let mut tts = self.tts.write().await;
+        if self.state.lock().generation != generation {
+            return;
+        }
+        for (text, flush) in texts {
+            if let Err(e) = tts.speak(&text, flush).await {
+                warn!("Failed to retry TTS utterance: {}", e);
+                self.state.lock().retrying = false;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm(samples: impl IntoIterator<Item = i16>) -> AudioData {
+        AudioData {
+            data: samples.into_iter().flat_map(i16::to_le_bytes).collect(),
+            sample_rate: 16000,
+            format: "linear16".to_string(),
+            duration_ms: None,
+        }
+    }
+
+    /// 500 ms of a 440 Hz tone at the given peak
+    fn tone(peak: f32) -> AudioData {
+        pcm((0..8000).map(|i| {
+            let phase = i as f32 * 440.0 * std::f32::consts::TAU / 16000.0;
+            (phase.sin() * peak) as i16
+        }))
+    }
+
+    fn check(audio: &[AudioData]) -> Option<TTSAudioQualityWarning> {
+        let mut stats = UtteranceStats::default();
+        for chunk in audio {
+            stats.push(chunk);
+        }
+        stats.check(&TTSAudioQualityConfig::default())
+    }
+
+    #[test]
+    fn test_normal_speech_passes() {
+        assert_eq!(check(&[tone(8000.0)]), None);
+    }
+
+    #[test]
+    fn test_clipped_audio_is_reported() {
+        // Overdriven tone, flattened at full scale
+        let clipped = pcm((0..8000).map(|i| {
+            let phase = i as f32 * 440.0 * std::f32::consts::TAU / 16000.0;
+            (phase.sin() * 65536.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        }));
+
+        let warning = check(&[clipped]).unwrap();
+        assert_eq!(warning.issues, vec![AudioQualityIssue::Clipped]);
+        assert!(warning.clipped_fraction > 0.5);
+        assert_eq!(warning.duration_ms, 500);
+    }
+
+    #[test]
+    fn test_silent_audio_is_reported() {
+        let warning = check(&[pcm(vec![0; 8000])]).unwrap();
+        assert_eq!(
+            warning.issues,
+            vec![AudioQualityIssue::Silent, AudioQualityIssue::LowLevel]
+        );
+        assert_eq!(warning.zero_fraction, 1.0);
+        assert_eq!(warning.rms_dbfs, SILENCE_DBFS);
+    }
+
+    #[test]
+    fn test_quiet_audio_is_reported() {
+        let warning = check(&[tone(16.0)]).unwrap();
+        assert_eq!(warning.issues, vec![AudioQualityIssue::LowLevel]);
+        assert!(warning.rms_dbfs < -60.0);
+    }
+
+    #[test]
+    fn test_short_utterances_are_not_judged() {
+        assert_eq!(check(&[pcm(vec![0; 800])]), None);
+    }
+
+    #[test]
+    fn test_samples_split_across_chunks() {
+        let audio = pcm(vec![i16::MAX; 8000]);
+        let (first, second) = audio.data.split_at(1001);
+        let chunks = [
+            AudioData {
+                data: first.to_vec(),
+                ..audio.clone()
+            },
+            AudioData {
+                data: second.to_vec(),
+                ..audio.clone()
+            },
+        ];
+
+        let warning = check(&chunks).unwrap();
+        assert_eq!(warning.clipped_fraction, 1.0);
+        assert_eq!(warning.duration_ms, 500);
+    }
+
+    #[test]
+    fn test_pcm_formats() {
+        assert!(is_pcm16("linear16"));
+        assert!(is_pcm16("PCM"));
+        assert!(!is_pcm16("mulaw"));
+        assert!(!is_pcm16("mp3"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(TTSAudioQualityConfig::default().validate().is_ok());
+
+        let config = TTSAudioQualityConfig {
+            max_clipped_fraction: 1.5,
+            ..Default::default()
+        };
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .contains("max_clipped_fraction")
+        );
+
+        let config = TTSAudioQualityConfig {
+            max_zero_fraction: -0.1,
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("max_zero_fraction"));
+
+        let config = TTSAudioQualityConfig {
+            min_rms_dbfs: 3.0,
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("min_rms_dbfs"));
+
+        let config = TTSAudioQualityConfig {
+            min_rms_dbfs: f32::NAN,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_deserializes_partial_json() {
+        let config: TTSAudioQualityConfig =
+            serde_json::from_str(r#"{"retry": true, "min_rms_dbfs": -50}"#).unwrap();
+        assert!(config.enabled);
+        assert!(config.retry);
+        assert_eq!(config.min_rms_dbfs, -50.0);
+        assert_eq!(config.max_zero_fraction, 0.95);
+    }
+}
@@ -6,10 +6,17 @@ use std::sync::Arc;
 
 use crate::core::{
     stt::{STTError, STTResult},
-    tts::{AudioCallback, AudioData, TTSError},
+    tts::{AudioCallback, AudioData, TTSError, TTSTimingEvent, TelephonyFramer},
 };
 
+use super::audio_quality::{AudioQualityChecker, TTSAudioQualityWarning};
+use super::filler::FillerPlayer;
+use super::preemption::{Preemption, PreemptionEvent};
+use super::sample_rate::SampleRateGuard;
 use super::state::InterruptionState;
+use super::tts_queue::{TTSQueue, TTSQueueFull};
+use super::turn_detection::TurnDetectionDegraded;
+use super::voice_fallback::VoiceFallback;
 
 /// Callback type for STT results
 pub type STTCallback =
@@ -23,6 +30,10 @@ pub type STTErrorCallback =
 pub type TTSAudioCallback =
     Arc<dyn Fn(AudioData) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
+/// Callback type for text accepted by the TTS provider
+pub type TTSTextCallback =
+    Arc<dyn Fn(String) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
 /// Callback type for TTS errors
 pub type TTSErrorCallback =
     Arc<dyn Fn(TTSError) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
@@ -35,30 +46,131 @@ pub type AudioClearCallback =
 pub type TTSCompleteCallback =
     Arc<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
+/// Callback type for viseme and word timing of synthesized audio
+pub type TTSTimingCallback =
+    Arc<dyn Fn(Vec<TTSTimingEvent>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Callback type for voice fallback notifications, called with the
+/// configured voice id and the fallback voice id
+pub type TTSVoiceFallbackCallback =
+    Arc<dyn Fn(String, String) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Callback type for notifications that the pending TTS queue was full
+pub type TTSQueueFullCallback =
+    Arc<dyn Fn(TTSQueueFull) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Callback type for TTS utterances whose audio failed the quality check
+pub type TTSAudioQualityCallback =
+    Arc<dyn Fn(TTSAudioQualityWarning) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Callback type for the start and end of system announcement pre-emptions
+pub type PreemptionCallback =
+    Arc<dyn Fn(PreemptionEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Callback type for sessions falling back from the turn detection model
+pub type TurnDetectionDegradedCallback =
+    Arc<dyn Fn(TurnDetectionDegraded) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
 /// Internal TTS callback implementation for the VoiceManager
+#[derive(Clone)]
 pub struct VoiceManagerTTSCallback {
     pub audio_callback: Option<TTSAudioCallback>,
     pub error_callback: Option<TTSErrorCallback>,
     pub interruption_state: Option<Arc<InterruptionState>>,
     pub complete_callback: Option<TTSCompleteCallback>,
+    /// Receives viseme and word timing from providers that report it
+    pub timing_callback: Option<TTSTimingCallback>,
+    /// Converts provider audio to 8kHz μ-law frames when the telephony profile is active
+    pub telephony: Option<Arc<TelephonyFramer>>,
+    /// Fixes audio the provider produced at another sample rate than requested
+    pub sample_rate_guard: Option<Arc<SampleRateGuard>>,
+    /// Switches to the fallback voice when the configured voice does not exist
+    pub voice_fallback: Option<Arc<VoiceFallback>>,
+    /// Whether this callback is registered on the fallback voice's provider
+    pub on_fallback_voice: bool,
+    /// Pending utterances, emptied when the provider finishes all queued text
+    pub tts_queue: Option<Arc<TTSQueue>>,
+    /// Checks each utterance for clipped, silent or inaudible audio
+    pub audio_quality: Option<Arc<AudioQualityChecker>>,
+    /// Filler to fade out when the reply's audio arrives
+    pub(super) filler: Option<Arc<FillerPlayer>>,
+    /// System announcements waiting for the provider to finish them
+    pub preemption: Option<Arc<Preemption>>,
 }
 
 impl AudioCallback for VoiceManagerTTSCallback {
     fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
         let callback = self.audio_callback.clone();
+        let error_callback = self.error_callback.clone();
+        let telephony = self.telephony.clone();
+        let filler = self.filler.clone();
+        let audio_data = match &self.sample_rate_guard {
+            Some(guard) => guard.correct(audio_data),
+            None => audio_data,
+        };
+
+        // Audio proves the configured voice exists
+        if let Some(fallback) = &self.voice_fallback
+            && !self.on_fallback_voice
+        {
+            fallback.confirm();
+        }
+        if let Some(checker) = &self.audio_quality {
+            checker.measure(&audio_data);
+        }
 
         Box::pin(async move {
-            // Call user callback if registered
-            if let Some(callback) = callback {
+            let Some(callback) = callback else {
+                return;
+            };
+
+            let mut audio_data = audio_data;
+            if let Some(filler) = filler {
+                filler.on_real_audio(&mut audio_data).await;
+            }
+
+            let Some(framer) = telephony else {
                 callback(audio_data).await;
+                return;
+            };
+
+            match framer.push(&audio_data) {
+                Ok(frames) => {
+                    for frame in frames {
+                        callback(frame).await;
+                    }
+                }
+                Err(e) => {
+                    if let Some(error_callback) = error_callback {
+                        error_callback(e).await;
+                    }
+                }
             }
         })
     }
 
     fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
         let callback = self.error_callback.clone();
+        if let Some(filler) = &self.filler {
+            filler.stop();
+        }
+
+        // A missing configured voice switches to the fallback voice instead
+        let error = match (&error, &self.voice_fallback) {
+            (TTSError::VoiceNotFound(_), Some(fallback)) if !self.on_fallback_voice => {
+                let fallback_callback = Arc::new(Self {
+                    on_fallback_voice: true,
+                    ..self.clone()
+                });
+                fallback.handle_voice_not_found(error, fallback_callback)
+            }
+            _ => Some(error),
+        };
 
         Box::pin(async move {
+            let Some(error) = error else {
+                return;
+            };
             if let Some(callback) = callback {
                 callback(error).await;
             }
@@ -68,8 +180,49 @@ impl AudioCallback for VoiceManagerTTSCallback {
     fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
         let interruption_state = self.interruption_state.clone();
         let complete_callback = self.complete_callback.clone();
+        let audio_callback = self.audio_callback.clone();
+        let telephony = self.telephony.clone();
+
+        if let Some(queue) = &self.tts_queue {
+            queue.complete();
+        }
+        if let Some(preemption) = &self.preemption {
+            preemption.complete();
+        }
+        if let Some(filler) = &self.filler {
+            filler.stop();
+        }
+        let audio_quality = self.audio_quality.clone();
+        let warning = audio_quality.as_ref().and_then(|checker| checker.finish());
+        let resampled_tail = self
+            .sample_rate_guard
+            .as_ref()
+            .and_then(|guard| guard.flush());
 
         Box::pin(async move {
+            // Emit the end of resampled audio and the padded final frame
+            // before reporting completion
+            if let Some(callback) = &audio_callback {
+                if let Some(tail) = resampled_tail {
+                    match &telephony {
+                        Some(framer) => {
+                            for frame in framer.push(&tail).unwrap_or_default() {
+                                callback(frame).await;
+                            }
+                        }
+                        None => callback(tail).await,
+                    }
+                }
+                if let Some(frame) = telephony.and_then(|framer| framer.flush()) {
+                    callback(frame).await;
+                }
+            }
+
+            // Report bad audio before the utterance counts as complete
+            if let (Some(checker), Some(warning)) = (audio_quality, warning) {
+                checker.notify(warning).await;
+            }
+
             // Mark as completed when TTS finishes
             if let Some(state) = interruption_state {
                 state
@@ -83,4 +236,16 @@ impl AudioCallback for VoiceManagerTTSCallback {
             }
         })
     }
+
+    fn on_timing(
+        &self,
+        events: Vec<TTSTimingEvent>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let callback = self.timing_callback.clone();
+        Box::pin(async move {
+            if let Some(callback) = callback {
+                callback(events).await;
+            }
+        })
+    }
 }
@@ -1,6 +1,20 @@
 //! Configuration types for the VoiceManager
 
-use crate::core::{stt::STTConfig, tts::TTSConfig};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "chaos")]
+use crate::core::chaos::SessionChaos;
+use crate::core::{
+    stt::{STTConfig, STTFailoverConfig, STTTurnRoutingConfig},
+    tts::TTSConfig,
+};
+use crate::plugin::{PluginRegistry, global_registry};
+
+use super::audio_quality::TTSAudioQualityConfig;
+use super::endpointing::AdaptiveEndpointingConfig;
+use super::preemption::DEFAULT_SYSTEM_SPEAK_MAX_CHARS;
+use super::tts_queue::TTSQueueLimit;
 
 /// Configuration for speech final timing control
 #[derive(Debug, Clone, Copy)]
@@ -10,6 +24,9 @@ pub struct SpeechFinalConfig {
     pub stt_speech_final_wait_ms: u64,
     /// Maximum time to wait for turn detection inference to complete (ms)
     pub turn_detection_inference_timeout_ms: u64,
+    /// Longest acceptable turn detection inference (ms); the session switches
+    /// to silence-based detection after a slower one
+    pub turn_detection_latency_budget_ms: u64,
     /// Hard upper bound timeout for any user utterance (ms)
     /// This guarantees that no utterance will wait longer than this value
     /// even if neither the STT provider nor turn detector fire
@@ -23,12 +40,16 @@ impl Default for SpeechFinalConfig {
         Self {
             stt_speech_final_wait_ms: 1800, // Wait 1.8s for real speech_final from STT
             turn_detection_inference_timeout_ms: 500, // 500ms max for model inference
+            turn_detection_latency_budget_ms: 200, // Degrade after a 200ms+ inference
             speech_final_hard_timeout_ms: 4000, // 4s hard upper bound for any utterance
             duplicate_window_ms: 500,       // 500ms duplicate prevention window
         }
     }
 }
 
+/// Default upper bound for a single provider `connect()` call
+pub const DEFAULT_PROVIDER_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Configuration for the VoiceManager
 #[derive(Debug, Clone)]
 pub struct VoiceManagerConfig {
@@ -38,6 +59,34 @@ pub struct VoiceManagerConfig {
     pub tts_config: TTSConfig,
     /// Configuration for speech final timing control
     pub speech_final_config: SpeechFinalConfig,
+    /// Adaptive end-of-turn silence threshold (disabled when `None`)
+    pub adaptive_endpointing: Option<AdaptiveEndpointingConfig>,
+    /// Maximum time to wait for each provider to connect on start
+    pub connect_timeout: Duration,
+    /// Voice to switch to when the configured TTS voice does not exist
+    ///
+    /// When unset, the configured voice is validated on start instead.
+    pub fallback_voice_id: Option<String>,
+    /// Cap on pending TTS utterances and what happens when it is reached
+    pub tts_queue_limit: TTSQueueLimit,
+    /// Send only the new text of partials that resend the sentence so far
+    pub dedupe_partials: bool,
+    /// Secondary STT provider to switch to when the primary fails
+    pub stt_failover: Option<STTFailoverConfig>,
+    /// Fast STT provider for turns expected to be confirmations or digits
+    pub stt_routing: Option<STTTurnRoutingConfig>,
+    /// Thresholds for flagging clipped, silent or inaudible TTS audio
+    pub tts_audio_quality: TTSAudioQualityConfig,
+    /// Longest text, in characters, accepted by a `system` priority speak
+    pub system_speak_max_chars: usize,
+    /// Whether a turn detection model should be available; without one the
+    /// session starts degraded to silence-based detection
+    pub turn_detector_expected: bool,
+    /// Registry the STT and TTS providers are created from
+    pub plugin_registry: Arc<PluginRegistry>,
+    /// Faults to inject into the primary STT and TTS providers
+    #[cfg(feature = "chaos")]
+    pub chaos: Option<Arc<SessionChaos>>,
 }
 
 impl VoiceManagerConfig {
@@ -47,6 +96,19 @@ impl VoiceManagerConfig {
             stt_config,
             tts_config,
             speech_final_config: SpeechFinalConfig::default(),
+            adaptive_endpointing: None,
+            connect_timeout: DEFAULT_PROVIDER_CONNECT_TIMEOUT,
+            fallback_voice_id: None,
+            tts_queue_limit: TTSQueueLimit::default(),
+            dedupe_partials: false,
+            stt_failover: None,
+            stt_routing: None,
+            tts_audio_quality: TTSAudioQualityConfig::default(),
+            system_speak_max_chars: DEFAULT_SYSTEM_SPEAK_MAX_CHARS,
+            turn_detector_expected: false,
+            plugin_registry: global_registry().clone(),
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
 
@@ -60,6 +122,92 @@ impl VoiceManagerConfig {
             stt_config,
             tts_config,
             speech_final_config,
+            adaptive_endpointing: None,
+            connect_timeout: DEFAULT_PROVIDER_CONNECT_TIMEOUT,
+            fallback_voice_id: None,
+            tts_queue_limit: TTSQueueLimit::default(),
+            dedupe_partials: false,
+            stt_failover: None,
+            stt_routing: None,
+            tts_audio_quality: TTSAudioQualityConfig::default(),
+            system_speak_max_chars: DEFAULT_SYSTEM_SPEAK_MAX_CHARS,
+            turn_detector_expected: false,
+            plugin_registry: global_registry().clone(),
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
+
+    /// Enable adaptive endpointing with the given bounds
+    pub fn with_adaptive_endpointing(mut self, config: AdaptiveEndpointingConfig) -> Self {
+        self.adaptive_endpointing = Some(config);
+        self
+    }
+
+    /// Set the maximum time to wait for each provider to connect
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Switch to `voice_id` if the configured TTS voice does not exist
+    pub fn with_fallback_voice_id(mut self, voice_id: impl Into<String>) -> Self {
+        self.fallback_voice_id = Some(voice_id.into());
+        self
+    }
+
+    /// Set the cap on pending TTS utterances
+    pub fn with_tts_queue_limit(mut self, limit: TTSQueueLimit) -> Self {
+        self.tts_queue_limit = limit;
+        self
+    }
+
+    /// Deduplicate partial text that LLM orchestrators resend on every token
+    pub fn with_dedupe_partials(mut self, enabled: bool) -> Self {
+        self.dedupe_partials = enabled;
+        self
+    }
+
+    /// Fail over to a secondary STT provider when the primary fails
+    pub fn with_stt_failover(mut self, failover: STTFailoverConfig) -> Self {
+        self.stt_failover = Some(failover);
+        self
+    }
+
+    /// Route confirmation and digit turns to a fast STT provider
+    pub fn with_stt_routing(mut self, routing: STTTurnRoutingConfig) -> Self {
+        self.stt_routing = Some(routing);
+        self
+    }
+
+    /// Set the thresholds for the TTS audio quality check
+    pub fn with_tts_audio_quality(mut self, config: TTSAudioQualityConfig) -> Self {
+        self.tts_audio_quality = config;
+        self
+    }
+
+    /// Set the longest text accepted by a `system` priority speak
+    pub fn with_system_speak_max_chars(mut self, max_chars: usize) -> Self {
+        self.system_speak_max_chars = max_chars;
+        self
+    }
+
+    /// Report a session without a turn detection model as degraded
+    pub fn with_turn_detector_expected(mut self, expected: bool) -> Self {
+        self.turn_detector_expected = expected;
+        self
+    }
+
+    /// Create the providers from `registry` instead of the global registry
+    pub fn with_plugin_registry(mut self, registry: Arc<PluginRegistry>) -> Self {
+        self.plugin_registry = registry;
+        self
+    }
+
+    /// Inject faults into the primary STT and TTS providers
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<SessionChaos>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
 }
@@ -0,0 +1,628 @@
+//! Adaptive endpointing
+//!
+//! Picks the end-of-turn silence threshold for each turn from what the caller
+//! has said so far. Short utterances ("yes", "no", "that's right") end after a
+//! short pause, longer ones get more room, and slow speakers get more room
+//! than fast ones.
+//!
+//! The threshold is pushed to providers that accept live endpointing updates
+//! (AssemblyAI). For other providers the gateway gates finals itself: once the
+//! caller has been silent for the threshold it asks the provider to finalize
+//! (Deepgram, ElevenLabs with `manual_commit`) or closes the turn with a
+//! speech_final of its own.
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::core::stt::{BaseSTT, STTResult};
+
+use super::state::SpeechFinalState;
+use super::stt_result::STTResultProcessor;
+
+/// Bounds and tuning for adaptive endpointing
+///
+/// # Example JSON
+/// ```json
+/// {"min_silence_ms": 300, "max_silence_ms": 1000}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct AdaptiveEndpointingConfig {
+    /// Shortest end-of-turn silence, used for short utterances (ms)
+    pub min_silence_ms: u32,
+    /// Longest end-of-turn silence, used for long utterances (ms)
+    pub max_silence_ms: u32,
+    /// Utterances with at most this many words use `min_silence_ms`
+    pub short_utterance_words: usize,
+    /// Utterances with at least this many words use `max_silence_ms`
+    pub long_utterance_words: usize,
+    /// Typical speech rate (words per second); slower speakers get a longer
+    /// threshold and faster speakers a shorter one
+    pub reference_words_per_second: f32,
+    /// Smallest threshold change worth pushing to the provider (ms)
+    pub min_update_delta_ms: u32,
+    /// Let the gateway end every utterance: providers with a manual commit
+    /// mode (ElevenLabs) stop endpointing on their own and commit when the
+    /// gateway's silence threshold is reached
+    pub manual_commit: bool,
+}
+
+impl Default for AdaptiveEndpointingConfig {
+    fn default() -> Self {
+        Self {
+            min_silence_ms: 300,
+            max_silence_ms: 1000,
+            short_utterance_words: 3,
+            long_utterance_words: 12,
+            reference_words_per_second: 2.5,
+            min_update_delta_ms: 50,
+            manual_commit: false,
+        }
+    }
+}
+
+/// Lower limit for `min_silence_ms`
+pub const MIN_ENDPOINTING_SILENCE_MS: u32 = 100;
+
+/// Upper limit for `max_silence_ms`
+pub const MAX_ENDPOINTING_SILENCE_MS: u32 = 5000;
+
+/// Limits on how far speech rate can scale the threshold
+const MIN_RATE_FACTOR: f32 = 0.75;
+const MAX_RATE_FACTOR: f32 = 1.5;
+
+/// Speech rate is only trusted once the turn is this long
+const MIN_RATE_SAMPLE: Duration = Duration::from_millis(500);
+
+impl AdaptiveEndpointingConfig {
+    /// Validate the bounds
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_silence_ms < MIN_ENDPOINTING_SILENCE_MS {
+            return Err(format!(
+                "min_silence_ms must be at least {MIN_ENDPOINTING_SILENCE_MS} (got {})",
+                self.min_silence_ms
+            ));
+        }
+        if self.max_silence_ms > MAX_ENDPOINTING_SILENCE_MS {
+            return Err(format!(
+                "max_silence_ms must be at most {MAX_ENDPOINTING_SILENCE_MS} (got {})",
+                self.max_silence_ms
+            ));
+        }
+        if self.min_silence_ms > self.max_silence_ms {
+            return Err(format!(
+                "min_silence_ms ({}) must not exceed max_silence_ms ({})",
+                self.min_silence_ms, self.max_silence_ms
+            ));
+        }
+        if self.short_utterance_words >= self.long_utterance_words {
+            return Err(format!(
+                "short_utterance_words ({}) must be less than long_utterance_words ({})",
+                self.short_utterance_words, self.long_utterance_words
+            ));
+        }
+        if !self.reference_words_per_second.is_finite() || self.reference_words_per_second <= 0.0 {
+            return Err("reference_words_per_second must be a positive number".to_string());
+        }
+        Ok(())
+    }
+
+    /// Threshold for an utterance of `words` words spoken over `elapsed`
+    pub fn threshold_for(&self, words: usize, elapsed: Duration) -> u32 {
+        let min = self.min_silence_ms as f32;
+        let max = self.max_silence_ms as f32;
+
+        // Interpolate between the bounds by utterance length
+        let base = if words <= self.short_utterance_words {
+            min
+        } else if words >= self.long_utterance_words {
+            max
+        } else {
+            let span = (self.long_utterance_words - self.short_utterance_words) as f32;
+            let position = (words - self.short_utterance_words) as f32 / span;
+            min + (max - min) * position
+        };
+
+        // Scale by speech rate once there is enough of the turn to measure it
+        let factor = if words >= 2 && elapsed >= MIN_RATE_SAMPLE {
+            let words_per_second = words as f32 / elapsed.as_secs_f32();
+            (self.reference_words_per_second / words_per_second)
+                .clamp(MIN_RATE_FACTOR, MAX_RATE_FACTOR)
+        } else {
+            1.0
+        };
+
+        (base * factor).round().clamp(min, max) as u32
+    }
+}
+
+/// Per-turn endpointing threshold controller
+///
+/// Feed every STT result to [`observe`](Self::observe); it returns the new
+/// threshold whenever it moves by at least `min_update_delta_ms`. A
+/// speech_final result ends the turn and resets the threshold to
+/// `max_silence_ms` so the start of the next turn is never clipped.
+#[derive(Debug, Clone)]
+pub struct AdaptiveEndpointing {
+    config: AdaptiveEndpointingConfig,
+    /// When the first words of the current turn arrived
+    turn_started: Option<Instant>,
+    /// Words in final (but not speech_final) results of the current turn
+    final_words: usize,
+    /// Words in the latest interim result
+    interim_words: usize,
+    /// Current threshold (ms)
+    threshold_ms: u32,
+    /// Last threshold reported to the caller (ms)
+    reported_ms: Option<u32>,
+}
+
+impl AdaptiveEndpointing {
+    /// Create a controller starting at `max_silence_ms`
+    pub fn new(config: AdaptiveEndpointingConfig) -> Self {
+        Self {
+            config,
+            turn_started: None,
+            final_words: 0,
+            interim_words: 0,
+            threshold_ms: config.max_silence_ms,
+            reported_ms: None,
+        }
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &AdaptiveEndpointingConfig {
+        &self.config
+    }
+
+    /// Current end-of-turn silence threshold (ms)
+    pub fn threshold_ms(&self) -> u32 {
+        self.threshold_ms
+    }
+
+    /// Words heard so far in the current turn
+    pub fn turn_words(&self) -> usize {
+        self.final_words + self.interim_words
+    }
+
+    /// Whether the latest words of the turn are still interim
+    pub fn has_pending_interim(&self) -> bool {
+        self.interim_words > 0
+    }
+
+    /// Whether the current turn has any words
+    pub fn in_turn(&self) -> bool {
+        self.turn_started.is_some()
+    }
+
+    /// Observe an STT result
+    ///
+    /// # Arguments
+    /// * `result` - Result from the STT provider
+    /// * `at` - When the result arrived
+    ///
+    /// # Returns
+    /// * `Some(threshold_ms)` - The threshold changed and should be applied
+    /// * `None` - Keep the current threshold
+    pub fn observe(&mut self, result: &STTResult, at: Instant) -> Option<u32> {
+        if result.is_speech_final {
+            self.reset();
+            return self.report();
+        }
+
+        let words = result.transcript.split_whitespace().count();
+        if words > 0 && self.turn_started.is_none() {
+            self.turn_started = Some(at);
+        }
+        if result.is_final {
+            self.final_words += words;
+            self.interim_words = 0;
+        } else {
+            self.interim_words = words;
+        }
+
+        let Some(started) = self.turn_started else {
+            return None;
+        };
+        self.threshold_ms = self
+            .config
+            .threshold_for(self.turn_words(), at.saturating_duration_since(started));
+        self.report()
+    }
+
+    /// End the current turn and return to `max_silence_ms`
+    pub fn reset(&mut self) {
+        self.turn_started = None;
+        self.final_words = 0;
+        self.interim_words = 0;
+        self.threshold_ms = self.config.max_silence_ms;
+    }
+
+    /// Report the threshold if it moved far enough from the last report
+    fn report(&mut self) -> Option<u32> {
+        let changed = match self.reported_ms {
+            None => true,
+            Some(reported) => {
+                reported.abs_diff(self.threshold_ms) >= self.config.min_update_delta_ms
+            }
+        };
+        if changed {
+            self.reported_ms = Some(self.threshold_ms);
+            Some(self.threshold_ms)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the STT provider accepts live endpointing updates
+const LIVE_UPDATES_UNKNOWN: u8 = 0;
+const LIVE_UPDATES_SUPPORTED: u8 = 1;
+const LIVE_UPDATES_UNSUPPORTED: u8 = 2;
+
+/// Applies adaptive endpointing to a VoiceManager's STT stream
+pub(super) struct EndpointingDriver {
+    controller: Mutex<AdaptiveEndpointing>,
+    stt: Arc<RwLock<Box<dyn BaseSTT>>>,
+    speech_final_state: Arc<SyncRwLock<SpeechFinalState>>,
+    live_updates: AtomicU8,
+    /// Set after a provider finalize; the next final result ends the turn
+    promote_next_final: AtomicBool,
+    /// Gateway silence timer for providers without live updates
+    silence_timer: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl EndpointingDriver {
+    pub(super) fn new(
+        config: AdaptiveEndpointingConfig,
+        stt: Arc<RwLock<Box<dyn BaseSTT>>>,
+        speech_final_state: Arc<SyncRwLock<SpeechFinalState>>,
+    ) -> Self {
+        Self {
+            controller: Mutex::new(AdaptiveEndpointing::new(config)),
+            stt,
+            speech_final_state,
+            live_updates: AtomicU8::new(LIVE_UPDATES_UNKNOWN),
+            promote_next_final: AtomicBool::new(false),
+            silence_timer: Mutex::new(None),
+        }
+    }
+
+    /// Observe a result before it is processed
+    ///
+    /// Promotes the final that answers a provider finalize to speech_final,
+    /// pushes threshold changes to the provider and (re)arms the gateway
+    /// silence timer when the provider cannot apply them itself.
+    pub(super) fn on_result(self: &Arc<Self>, result: &mut STTResult) {
+        // The first final after a finalize request closes the turn, even when
+        // the provider had nothing left to transcribe
+        if result.is_final
+            && self.promote_next_final.swap(false, Ordering::AcqRel)
+            && !result.is_speech_final
+            && self.controller.lock().in_turn()
+        {
+            debug!("Promoting finalized result to speech_final");
+            result.is_speech_final = true;
+        }
+
+        self.cancel_timer();
+
+        let (update, in_turn, threshold_ms) = {
+            let mut controller = self.controller.lock();
+            let update = controller.observe(result, Instant::now());
+            (update, controller.in_turn(), controller.threshold_ms())
+        };
+
+        if let Some(silence_ms) = update
+            && self.live_updates.load(Ordering::Acquire) != LIVE_UPDATES_UNSUPPORTED
+        {
+            self.push_threshold(silence_ms);
+        }
+
+        if in_turn
+            && !result.is_speech_final
+            && self.live_updates.load(Ordering::Acquire) != LIVE_UPDATES_SUPPORTED
+        {
+            self.arm_timer(threshold_ms);
+        }
+    }
+
+    /// Stop the silence timer
+    pub(super) fn cancel_timer(&self) {
+        if let Some(handle) = self.silence_timer.lock().take() {
+            handle.abort();
+        }
+    }
+
+    /// Push a threshold to the provider without blocking result delivery
+    fn push_threshold(self: &Arc<Self>, silence_ms: u32) {
+        let driver = self.clone();
+        tokio::spawn(async move {
+            let applied = {
+                let mut stt = driver.stt.write().await;
+                stt.set_end_of_turn_silence(silence_ms).await
+            };
+            match applied {
+                Ok(true) => {
+                    debug!("Provider end-of-turn silence set to {}ms", silence_ms);
+                    driver
+                        .live_updates
+                        .store(LIVE_UPDATES_SUPPORTED, Ordering::Release);
+                }
+                Ok(false) => {
+                    debug!("Provider has no live endpointing - gating finals in the gateway");
+                    driver
+                        .live_updates
+                        .store(LIVE_UPDATES_UNSUPPORTED, Ordering::Release);
+                }
+                Err(e) => warn!("Failed to update provider endpointing: {}", e),
+            }
+        });
+    }
+
+    /// Close the turn after `silence_ms` without new results
+    fn arm_timer(self: &Arc<Self>, silence_ms: u32) {
+        let driver = self.clone();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(silence_ms as u64)).await;
+            driver.on_silence(silence_ms).await;
+        });
+        *self.silence_timer.lock() = Some(handle);
+    }
+
+    /// The caller has been silent for the threshold
+    async fn on_silence(&self, silence_ms: u32) {
+        let finalized = {
+            let mut stt = self.stt.write().await;
+            stt.finalize().await
+        };
+        match finalized {
+            Ok(true) => {
+                debug!(
+                    "Silent for {}ms - asked provider to finalize the turn",
+                    silence_ms
+                );
+                self.promote_next_final.store(true, Ordering::Release);
+                return;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!("Failed to finalize STT turn: {}", e);
+                return;
+            }
+        }
+
+        // Without provider support, only close turns whose words are final
+        if self.controller.lock().has_pending_interim() {
+            return;
+        }
+        let (waiting, buffered_text) = {
+            let state = self.speech_final_state.read();
+            (
+                state.waiting_for_speech_final.load(Ordering::Acquire),
+                state.text_buffer.clone(),
+            )
+        };
+        if !waiting || buffered_text.is_empty() {
+            return;
+        }
+
+        debug!("Silent for {}ms - closing the turn", silence_ms);
+        self.controller.lock().reset();
+        let result = STTResult::new(String::new(), true, false, 1.0);
+        STTResultProcessor::fire_speech_final(
+            result,
+            buffered_text,
+            self.speech_final_state.clone(),
+            "adaptive_endpointing",
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interim(text: &str) -> STTResult {
+        STTResult::new(text.to_string(), false, false, 0.9)
+    }
+
+    fn final_result(text: &str) -> STTResult {
+        STTResult::new(text.to_string(), true, false, 0.9)
+    }
+
+    fn speech_final(text: &str) -> STTResult {
+        STTResult::new(text.to_string(), true, true, 0.9)
+    }
+
+    /// Feed `(offset_ms, result)` pairs and collect the reported thresholds
+    fn run_script(
+        config: AdaptiveEndpointingConfig,
+        script: &[(u64, STTResult)],
+    ) -> (AdaptiveEndpointing, Vec<Option<u32>>) {
+        let start = Instant::now();
+        let mut controller = AdaptiveEndpointing::new(config);
+        let reported = script
+            .iter()
+            .map(|(offset_ms, result)| {
+                controller.observe(result, start + Duration::from_millis(*offset_ms))
+            })
+            .collect();
+        (controller, reported)
+    }
+
+    #[test]
+    fn test_short_utterance_uses_min_threshold() {
+        let (controller, reported) = run_script(
+            AdaptiveEndpointingConfig::default(),
+            &[(0, interim("yes")), (200, final_result("yes"))],
+        );
+        assert_eq!(reported, vec![Some(300), None]);
+        assert_eq!(controller.threshold_ms(), 300);
+    }
+
+    #[test]
+    fn test_threshold_grows_with_utterance_length() {
+        // 2.5 words per second matches the reference rate: no rate scaling
+        let (controller, reported) = run_script(
+            AdaptiveEndpointingConfig::default(),
+            &[
+                (0, interim("I")),
+                (1200, interim("I would like")),
+                (2400, interim("I would like to book a")),
+                (3200, interim("I would like to book a table for")),
+                (
+                    4400,
+                    interim("I would like to book a table for two people at"),
+                ),
+                (
+                    5600,
+                    interim("I would like to book a table for two people at seven tonight please"),
+                ),
+            ],
+        );
+        // 1, 3, 6, 8, 11 and 14 words
+        assert_eq!(
+            reported,
+            vec![Some(300), None, Some(533), Some(689), Some(922), Some(1000)]
+        );
+        assert_eq!(controller.turn_words(), 14);
+        assert_eq!(controller.threshold_ms(), 1000);
+    }
+
+    #[test]
+    fn test_speech_rate_scales_threshold() {
+        let config = AdaptiveEndpointingConfig::default();
+        // 6 words: 533ms at the reference rate (2.4s)
+        assert_eq!(config.threshold_for(6, Duration::from_millis(2400)), 533);
+        // Slow speaker (1 word per second) gets the maximum 1.5x
+        assert_eq!(config.threshold_for(6, Duration::from_secs(6)), 800);
+        // Fast speaker (6 words per second) gets the minimum 0.75x
+        assert_eq!(config.threshold_for(6, Duration::from_secs(1)), 400);
+        // Scaling never leaves the bounds
+        assert_eq!(config.threshold_for(3, Duration::from_millis(500)), 300);
+        assert_eq!(config.threshold_for(11, Duration::from_secs(20)), 1000);
+        // Too little audio to measure rate
+        assert_eq!(config.threshold_for(6, Duration::from_millis(100)), 533);
+    }
+
+    #[test]
+    fn test_finals_accumulate_across_segments() {
+        let (controller, reported) = run_script(
+            AdaptiveEndpointingConfig::default(),
+            &[
+                (0, interim("my account number")),
+                (1200, final_result("My account number")),
+                (1600, interim("is four")),
+                (2400, final_result("is four five")),
+            ],
+        );
+        // 3 words, 3 final, 3 final + 2 interim (fast: 0.8x), 6 final
+        assert_eq!(reported, vec![Some(300), None, Some(364), Some(533)]);
+        assert!(!controller.has_pending_interim());
+        assert_eq!(controller.turn_words(), 6);
+    }
+
+    #[test]
+    fn test_speech_final_resets_to_max() {
+        let (controller, reported) = run_script(
+            AdaptiveEndpointingConfig::default(),
+            &[
+                (0, interim("no")),
+                (300, speech_final("No.")),
+                (2000, interim("actually")),
+            ],
+        );
+        assert_eq!(reported, vec![Some(300), Some(1000), Some(300)]);
+        assert!(controller.in_turn());
+    }
+
+    #[test]
+    fn test_small_changes_are_not_reported() {
+        let config = AdaptiveEndpointingConfig {
+            min_update_delta_ms: 200,
+            ..Default::default()
+        };
+        let (_, reported) = run_script(
+            config,
+            &[
+                (0, interim("one two three")),
+                (1600, interim("one two three four")),
+                (2000, interim("one two three four five")),
+                (2400, interim("one two three four five six")),
+            ],
+        );
+        // 378 and 456 are within 200ms of the reported 300
+        assert_eq!(reported, vec![Some(300), None, None, Some(533)]);
+    }
+
+    #[test]
+    fn test_empty_results_do_not_start_a_turn() {
+        let (controller, reported) = run_script(
+            AdaptiveEndpointingConfig::default(),
+            &[(0, interim("")), (100, interim("   "))],
+        );
+        assert_eq!(reported, vec![None, None]);
+        assert!(!controller.in_turn());
+        assert_eq!(controller.threshold_ms(), 1000);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(AdaptiveEndpointingConfig::default().validate().is_ok());
+
+        let inverted = AdaptiveEndpointingConfig {
+            min_silence_ms: 900,
+            max_silence_ms: 400,
+            ..Default::default()
+        };
+        assert!(inverted.validate().is_err());
+
+        let too_short = AdaptiveEndpointingConfig {
+            min_silence_ms: 50,
+            ..Default::default()
+        };
+        assert!(too_short.validate().is_err());
+
+        let too_long = AdaptiveEndpointingConfig {
+            max_silence_ms: MAX_ENDPOINTING_SILENCE_MS + 1,
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let words = AdaptiveEndpointingConfig {
+            short_utterance_words: 8,
+            long_utterance_words: 8,
+            ..Default::default()
+        };
+        assert!(words.validate().is_err());
+
+        let rate = AdaptiveEndpointingConfig {
+            reference_words_per_second: 0.0,
+            ..Default::default()
+        };
+        assert!(rate.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_deserialization_uses_defaults() {
+        let config: AdaptiveEndpointingConfig =
+            serde_json::from_str(r#"{"min_silence_ms": 250, "max_silence_ms": 1200}"#).unwrap();
+        assert_eq!(config.min_silence_ms, 250);
+        assert_eq!(config.max_silence_ms, 1200);
+        assert_eq!(config.short_utterance_words, 3);
+        assert_eq!(config.long_utterance_words, 12);
+    }
+}
@@ -1,6 +1,7 @@
 //! Error types for VoiceManager operations
 
 use crate::core::{stt::STTError, tts::TTSError};
+use crate::errors::provider_error::error_codes;
 
 /// Error types for VoiceManager operations
 #[derive(Debug, thiserror::Error)]
@@ -15,9 +16,29 @@ pub enum VoiceManagerError {
     ProviderNotReady(String),
     #[error("Callback registration error: {0}")]
     CallbackRegistrationError(String),
+    #[error("TTS queue full: {max_pending} utterances already pending")]
+    TTSQueueFull { max_pending: usize },
+    #[error("System announcement too long: limit is {max_chars} characters")]
+    SystemSpeakTooLong { max_chars: usize },
     #[error("Internal error: {0}")]
     InternalError(String),
 }
 
+impl VoiceManagerError {
+    /// Get the error code sent to clients with the sanitized message
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            VoiceManagerError::TTSError(e) => e.error_code(),
+            VoiceManagerError::STTError(e) => e.error_code(),
+            VoiceManagerError::TTSQueueFull { .. } => error_codes::TTS_QUEUE_FULL,
+            VoiceManagerError::SystemSpeakTooLong { .. } => error_codes::SPEAK_TOO_LONG,
+            VoiceManagerError::InitializationError(_)
+            | VoiceManagerError::ProviderNotReady(_)
+            | VoiceManagerError::CallbackRegistrationError(_)
+            | VoiceManagerError::InternalError(_) => error_codes::SESSION_ERROR,
+        }
+    }
+}
+
 /// Result type for VoiceManager operations
 pub type VoiceManagerResult<T> = Result<T, VoiceManagerError>;
@@ -0,0 +1,664 @@
+//! Filler audio while the first TTS audio of a reply is slow
+//!
+//! Callers start to notice silence after about 700ms without speech. With a
+//! [`FillerAudioConfig`], a pre-recorded asset (a breath, keyboard typing)
+//! starts playing when no TTS audio has arrived `threshold_ms` after a
+//! `speak` request and loops until the provider's audio arrives. The filler
+//! is then faded out over `fade_out_ms` with a linear gain ramp mixed into the
+//! start of the real audio, so the two cross-fade instead of cutting.
+//!
+//! Filler audio goes to its own callback (`VoiceManager::on_filler_audio`),
+//! not the TTS audio callback: it never counts as assistant speech, starts no
+//! turn, is not billed and never reaches the transcript. It never plays over
+//! real audio either:
+//!
+//! - the threshold counts from the end of audio that is still playing out
+//! - real audio arriving while a filler chunk is being delivered waits for it
+//! - `clear_tts()`, TTS errors and completions stop the filler outright
+//! - the filler stops by itself after `max_duration_ms`
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::config::is_valid_asset_name;
+use crate::core::tts::{AudioData, TelephonyFramer};
+
+use super::audio_quality::is_pcm16;
+use super::callbacks::TTSAudioCallback;
+
+/// Default time without TTS audio before the filler starts (ms)
+pub const DEFAULT_FILLER_THRESHOLD_MS: u64 = 700;
+
+/// Default length of the fade-out into the real audio (ms)
+pub const DEFAULT_FILLER_FADE_OUT_MS: u64 = 150;
+
+/// Default longest time the filler plays (ms)
+pub const DEFAULT_FILLER_MAX_DURATION_MS: u64 = 10_000;
+
+/// Length of each delivered filler chunk (ms)
+const FILLER_CHUNK_MS: u64 = 20;
+
+/// Filler played while waiting for the first TTS audio of a reply
+///
+/// # Example JSON
+/// ```json
+/// {"asset": "typing", "threshold_ms": 700, "fade_out_ms": 150}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FillerAudioConfig {
+    /// Name of a 16-bit mono WAV file in the greeting assets directory,
+    /// recorded at the session's output sample rate (`.wav` is optional)
+    #[cfg_attr(feature = "openapi", schema(example = "typing"))]
+    pub asset: String,
+    /// Time without TTS audio after a `speak` request before the filler
+    /// starts (100 - 5000 ms)
+    #[serde(default = "default_threshold_ms")]
+    pub threshold_ms: u64,
+    /// Length of the fade-out mixed into the real audio (0 - 1000 ms)
+    #[serde(default = "default_fade_out_ms")]
+    pub fade_out_ms: u64,
+    /// Longest time the filler plays, looping the asset (1000 - 60000 ms)
+    #[serde(default = "default_max_duration_ms")]
+    pub max_duration_ms: u64,
+}
+
+fn default_threshold_ms() -> u64 {
+    DEFAULT_FILLER_THRESHOLD_MS
+}
+
+fn default_fade_out_ms() -> u64 {
+    DEFAULT_FILLER_FADE_OUT_MS
+}
+
+fn default_max_duration_ms() -> u64 {
+    DEFAULT_FILLER_MAX_DURATION_MS
+}
+
+impl FillerAudioConfig {
+    /// Filler playing `asset` with the default timings
+    pub fn new(asset: impl Into<String>) -> Self {
+        Self {
+            asset: asset.into(),
+            threshold_ms: DEFAULT_FILLER_THRESHOLD_MS,
+            fade_out_ms: DEFAULT_FILLER_FADE_OUT_MS,
+            max_duration_ms: DEFAULT_FILLER_MAX_DURATION_MS,
+        }
+    }
+
+    /// Validate the asset name and timings
+    ///
+    /// # Returns
+    /// * `Ok(())` if the configuration is usable
+    /// * `Err(String)` describing the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_valid_asset_name(self.asset.trim()) {
+            return Err(format!(
+                "invalid filler asset name '{}': must be a file name without path separators",
+                self.asset
+            ));
+        }
+        if !(100..=5_000).contains(&self.threshold_ms) {
+            return Err(format!(
+                "threshold_ms must be between 100 and 5000, got {}",
+                self.threshold_ms
+            ));
+        }
+        if self.fade_out_ms > 1_000 {
+            return Err(format!(
+                "fade_out_ms must be at most 1000, got {}",
+                self.fade_out_ms
+            ));
+        }
+        if !(1_000..=60_000).contains(&self.max_duration_ms) {
+            return Err(format!(
+                "max_duration_ms must be between 1000 and 60000, got {}",
+                self.max_duration_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A filler asset loaded for playback
+#[derive(Debug, Clone)]
+pub struct FillerAudio {
+    config: FillerAudioConfig,
+    samples: Arc<[i16]>,
+    sample_rate: u32,
+}
+
+impl FillerAudio {
+    /// Filler playing `pcm`, 16-bit little-endian mono PCM at `sample_rate`
+    pub fn new(config: FillerAudioConfig, pcm: &[u8], sample_rate: u32) -> Result<Self, String> {
+        config.validate()?;
+        let samples: Arc<[i16]> = pcm
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        if samples.is_empty() {
+            return Err(format!("filler asset '{}' is empty", config.asset));
+        }
+        if sample_rate == 0 {
+            return Err("filler sample rate must not be 0".to_string());
+        }
+        Ok(Self {
+            config,
+            samples,
+            sample_rate,
+        })
+    }
+
+    /// The filler's configuration
+    pub fn config(&self) -> &FillerAudioConfig {
+        &self.config
+    }
+
+    /// Sample rate of the asset
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Sample `position` of the asset played in a loop
+    fn sample(&self, position: usize) -> i16 {
+        self.samples[position % self.samples.len()]
+    }
+
+    /// `count` samples from `position`, as 16-bit little-endian PCM
+    fn read(&self, position: usize, count: usize) -> Vec<u8> {
+        (position..position + count)
+            .flat_map(|i| self.sample(i).to_le_bytes())
+            .collect()
+    }
+
+    fn samples_for(&self, ms: u64) -> usize {
+        (self.sample_rate as u64 * ms / 1000) as usize
+    }
+}
+
+/// Filler playback counters of a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FillerStats {
+    /// Times the filler started playing
+    pub played: u64,
+    /// Times real audio arrived during the filler and cross-faded it out
+    pub crossfaded: u64,
+    /// Filler audio delivered, in milliseconds
+    pub filler_ms: u64,
+}
+
+/// Filler tail being mixed into the real audio
+struct Fade {
+    /// Next filler sample to mix in
+    position: usize,
+    /// Samples of the fade still to mix
+    remaining: usize,
+    /// Samples of the whole fade
+    total: usize,
+}
+
+#[derive(Default)]
+struct FillerState {
+    /// Bumped whenever the waiting or playing filler is cancelled
+    generation: u64,
+    /// Whether a `speak` request is waiting for its first audio
+    armed: bool,
+    /// Next sample of the playing filler, `None` while waiting
+    position: Option<usize>,
+    /// Fade-out still being mixed into real audio
+    fade: Option<Fade>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Plays the session's filler while the first TTS audio of a reply is slow
+pub(super) struct FillerPlayer {
+    audio: SyncRwLock<Option<FillerAudio>>,
+    callback: SyncRwLock<Option<TTSAudioCallback>>,
+    /// Whether filler chunks are framed as 8kHz μ-law
+    telephony: bool,
+    state: Mutex<FillerState>,
+    /// Held while a filler chunk is delivered, so real audio never overtakes it
+    delivery: tokio::sync::Mutex<()>,
+    /// When the real audio delivered so far has played out, ms since epoch
+    playout_end_ms: AtomicU64,
+    played: AtomicU64,
+    crossfaded: AtomicU64,
+    filler_ms: AtomicU64,
+}
+
+impl FillerPlayer {
+    pub(super) fn new(telephony: bool) -> Self {
+        Self {
+            audio: SyncRwLock::new(None),
+            callback: SyncRwLock::new(None),
+            telephony,
+            state: Mutex::new(FillerState::default()),
+            delivery: tokio::sync::Mutex::new(()),
+            playout_end_ms: AtomicU64::new(0),
+            played: AtomicU64::new(0),
+            crossfaded: AtomicU64::new(0),
+            filler_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Play `audio` from now on
+    pub(super) fn set_audio(&self, audio: FillerAudio) {
+        *self.audio.write() = Some(audio);
+    }
+
+    /// Deliver filler chunks to `callback`
+    pub(super) fn set_callback(&self, callback: TTSAudioCallback) {
+        *self.callback.write() = Some(callback);
+    }
+
+    /// Playback counters, or `None` without a filler
+    pub(super) fn stats(&self) -> Option<FillerStats> {
+        self.audio.read().as_ref()?;
+        Some(FillerStats {
+            played: self.played.load(Ordering::Relaxed),
+            crossfaded: self.crossfaded.load(Ordering::Relaxed),
+            filler_ms: self.filler_ms.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Start waiting for the first audio of a `speak` request
+    ///
+    /// Does nothing while a request is already waiting or the filler plays.
+    pub(super) fn arm(self: &Arc<Self>) {
+        let Some(audio) = self.audio.read().clone() else {
+            return;
+        };
+        let Some(callback) = self.callback.read().clone() else {
+            return;
+        };
+
+        let mut state = self.state.lock();
+        if state.armed {
+            return;
+        }
+        state.armed = true;
+        state.fade = None;
+        let generation = state.generation;
+        if let Some(task) = state.task.take() {
+            task.abort();
+        }
+        let threshold_ms = audio.config.threshold_ms;
+        let player = self.clone();
+        state.task = Some(tokio::spawn(async move {
+            player.run(generation, audio, callback, threshold_ms).await;
+        }));
+    }
+
+    /// Start the waiting filler without waiting out its threshold
+    ///
+    /// Real audio still playing out is waited for as usual.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a waiting filler was started early
+    pub(super) fn play_now(self: &Arc<Self>) -> bool {
+        let Some(audio) = self.audio.read().clone() else {
+            return false;
+        };
+        let Some(callback) = self.callback.read().clone() else {
+            return false;
+        };
+
+        let mut state = self.state.lock();
+        if !state.armed || state.position.is_some() {
+            return false;
+        }
+        state.generation += 1;
+        let generation = state.generation;
+        if let Some(task) = state.task.take() {
+            task.abort();
+        }
+        let player = self.clone();
+        state.task = Some(tokio::spawn(async move {
+            player.run(generation, audio, callback, 0).await;
+        }));
+        true
+    }
+
+    /// Stop the waiting or playing filler without a fade
+    pub(super) fn stop(&self) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        state.armed = false;
+        state.position = None;
+        state.fade = None;
+    }
+
+    /// Stop the filler and wait until a chunk being delivered has been
+    ///
+    /// Lets a clear of downstream buffers follow the filler's last chunk.
+    pub(super) async fn stop_and_drain(&self) {
+        self.stop();
+        let _delivery = self.delivery.lock().await;
+    }
+
+    /// Account for real audio about to be delivered
+    ///
+    /// Cancels the waiting filler, and cross-fades a playing one by mixing
+    /// its fading tail into `audio` when both are 16-bit PCM at the same rate.
+    pub(super) async fn on_real_audio(&self, audio: &mut AudioData) {
+        let now = now_ms();
+        let duration = audio_duration_ms(audio);
+        let _ = self
+            .playout_end_ms
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |end| {
+                Some(end.max(now) + duration)
+            });
+
+        let idle = {
+            let state = self.state.lock();
+            !state.armed && state.fade.is_none()
+        };
+        if idle {
+            return;
+        }
+        // A filler chunk in flight reaches the sink before this audio
+        let _delivery = self.delivery.lock().await;
+        let filler = self.audio.read().clone();
+        let mut state = self.state.lock();
+        if state.armed {
+            state.generation += 1;
+            state.armed = false;
+            if let (Some(position), Some(filler)) = (state.position.take(), &filler) {
+                self.crossfaded.fetch_add(1, Ordering::Relaxed);
+                debug!("TTS audio arrived, fading out filler");
+                let total = filler.samples_for(filler.config.fade_out_ms);
+                state.fade = (total > 0).then_some(Fade {
+                    position,
+                    remaining: total,
+                    total,
+                });
+            }
+        }
+
+        let Some(fade) = state.fade.as_mut() else {
+            return;
+        };
+        let Some(filler) = filler
+            .filter(|filler| is_pcm16(&audio.format) && audio.sample_rate == filler.sample_rate)
+        else {
+            // Formats that cannot be mixed cut the filler instead
+            state.fade = None;
+            return;
+        };
+        for bytes in audio.data.chunks_exact_mut(2) {
+            if fade.remaining == 0 {
+                break;
+            }
+            let gain = fade.remaining as f32 / fade.total as f32;
+            let real = i16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            let mixed = real + filler.sample(fade.position) as f32 * gain;
+            let mixed = mixed.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            bytes.copy_from_slice(&mixed.to_le_bytes());
+            fade.position += 1;
+            fade.remaining -= 1;
+        }
+        if fade.remaining == 0 {
+            state.fade = None;
+        }
+    }
+
+    /// Wait out `threshold_ms`, then play the filler until it is cancelled
+    async fn run(
+        &self,
+        generation: u64,
+        audio: FillerAudio,
+        callback: TTSAudioCallback,
+        threshold_ms: u64,
+    ) {
+        let armed_at = now_ms();
+        loop {
+            // Audio still playing out pushes the start back
+            let deadline = armed_at.max(self.playout_end_ms.load(Ordering::Acquire)) + threshold_ms;
+            let now = now_ms();
+            if now >= deadline {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(deadline - now)).await;
+            if self.state.lock().generation != generation {
+                return;
+            }
+        }
+        {
+            let mut state = self.state.lock();
+            if state.generation != generation {
+                return;
+            }
+            state.position = Some(0);
+        }
+        self.played.fetch_add(1, Ordering::Relaxed);
+        debug!(threshold_ms, "No TTS audio yet, playing filler");
+
+        let framer = self.telephony.then(TelephonyFramer::new);
+        let chunk_samples = audio.samples_for(FILLER_CHUNK_MS).max(1);
+        let max_duration = Duration::from_millis(audio.config.max_duration_ms);
+        let started = Instant::now();
+        let mut next = started;
+        while started.elapsed() < max_duration {
+            {
+                let _delivery = self.delivery.lock().await;
+                let chunk = {
+                    let mut state = self.state.lock();
+                    if state.generation != generation {
+                        return;
+                    }
+                    let Some(position) = state.position.as_mut() else {
+                        return;
+                    };
+                    let chunk = audio.read(*position, chunk_samples);
+                    *position += chunk_samples;
+                    chunk
+                };
+                let chunk = AudioData {
+                    data: chunk,
+                    sample_rate: audio.sample_rate,
+                    format: "linear16".to_string(),
+                    duration_ms: Some(FILLER_CHUNK_MS as u32),
+                };
+                match &framer {
+                    Some(framer) => match framer.push(&chunk) {
+                        Ok(frames) => {
+                            for frame in frames {
+                                callback(frame).await;
+                            }
+                        }
+                        Err(e) => {
+                            debug!("Failed to frame filler audio: {e}");
+                            break;
+                        }
+                    },
+                    None => callback(chunk).await,
+                }
+                self.filler_ms.fetch_add(FILLER_CHUNK_MS, Ordering::Relaxed);
+            }
+            // Paced in real time so the sink never buffers more than a chunk
+            next += Duration::from_millis(FILLER_CHUNK_MS);
+            tokio::time::sleep_until(next).await;
+        }
+
+        debug!("Filler stopped before any TTS audio arrived");
+        let mut state = self.state.lock();
+        if state.generation == generation {
+            state.armed = false;
+            state.position = None;
+        }
+    }
+}
+
+/// Playing time of a chunk of 16-bit PCM or 8-bit G.711 audio
+fn audio_duration_ms(audio: &AudioData) -> u64 {
+    if let Some(duration_ms) = audio.duration_ms {
+        return duration_ms as u64;
+    }
+    let bytes_per_sample = if matches!(audio.format.as_str(), "mulaw" | "ulaw" | "alaw") {
+        1
+    } else {
+        2
+    };
+    audio.data.len() as u64 * 1000 / (audio.sample_rate.max(1) as u64 * bytes_per_sample)
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm(samples: &[i16]) -> Vec<u8> {
+        samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+
+    fn samples(pcm: &[u8]) -> Vec<i16> {
+        pcm.chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(FillerAudioConfig::new("typing").validate().is_ok());
+        assert!(FillerAudioConfig::new("../typing").validate().is_err());
+        for config in [
+            FillerAudioConfig {
+                threshold_ms: 50,
+                ..FillerAudioConfig::new("typing")
+            },
+            FillerAudioConfig {
+                fade_out_ms: 2_000,
+                ..FillerAudioConfig::new("typing")
+            },
+            FillerAudioConfig {
+                max_duration_ms: 100,
+                ..FillerAudioConfig::new("typing")
+            },
+        ] {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+
+        let config: FillerAudioConfig = serde_json::from_str(r#"{"asset": "breath"}"#).unwrap();
+        assert_eq!(config, FillerAudioConfig::new("breath"));
+    }
+
+    #[test]
+    fn test_empty_asset_is_rejected() {
+        assert!(FillerAudio::new(FillerAudioConfig::new("typing"), &[], 16000).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_real_audio_cross_fades_playing_filler() {
+        let player = FillerPlayer::new(false);
+        let filler = FillerAudio::new(
+            FillerAudioConfig {
+                fade_out_ms: 1,
+                ..FillerAudioConfig::new("typing")
+            },
+            &pcm(&[1000; 4]),
+            16000,
+        )
+        .unwrap();
+        player.set_audio(filler);
+        {
+            let mut state = player.state.lock();
+            state.armed = true;
+            state.position = Some(2);
+        }
+
+        // 1ms at 16kHz fades over 16 samples, spanning two chunks
+        let mut first = AudioData {
+            data: pcm(&[0; 10]),
+            sample_rate: 16000,
+            format: "linear16".to_string(),
+            duration_ms: None,
+        };
+        player.on_real_audio(&mut first).await;
+        let faded = samples(&first.data);
+        assert_eq!(faded[0], 1000);
+        assert!(faded.windows(2).all(|pair| pair[0] > pair[1]), "{faded:?}");
+
+        let mut second = first.clone();
+        second.data = pcm(&[0; 10]);
+        player.on_real_audio(&mut second).await;
+        let rest = samples(&second.data);
+        assert!(
+            rest[..6].iter().all(|s| *s > 0 && *s < faded[9]),
+            "{rest:?}"
+        );
+        assert!(rest[6..].iter().all(|s| *s == 0), "{rest:?}");
+
+        let stats = player.stats().unwrap();
+        assert_eq!(stats.crossfaded, 1);
+        assert!(!player.state.lock().armed);
+    }
+
+    #[tokio::test]
+    async fn test_play_now_skips_threshold() {
+        let player = Arc::new(FillerPlayer::new(false));
+        player.set_audio(
+            FillerAudio::new(
+                FillerAudioConfig {
+                    threshold_ms: 5_000,
+                    ..FillerAudioConfig::new("typing")
+                },
+                &pcm(&[1000; 4]),
+                16000,
+            )
+            .unwrap(),
+        );
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        player.set_callback(Arc::new(move |chunk: AudioData| {
+            let _ = tx.send(chunk);
+            Box::pin(async {})
+        }));
+
+        // Nothing to start before a request waits for audio
+        assert!(!player.play_now());
+        player.arm();
+        assert!(player.play_now());
+        let chunk = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("filler should start before its threshold")
+            .unwrap();
+        assert_eq!(samples(&chunk.data)[0], 1000);
+        // Already playing
+        assert!(!player.play_now());
+        player.stop();
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_audio_cuts_filler() {
+        let player = FillerPlayer::new(false);
+        player.set_audio(
+            FillerAudio::new(FillerAudioConfig::new("typing"), &pcm(&[1000; 4]), 16000).unwrap(),
+        );
+        {
+            let mut state = player.state.lock();
+            state.armed = true;
+            state.position = Some(0);
+        }
+
+        let mut audio = AudioData {
+            data: pcm(&[0; 10]),
+            sample_rate: 24000,
+            format: "linear16".to_string(),
+            duration_ms: None,
+        };
+        player.on_real_audio(&mut audio).await;
+        assert_eq!(samples(&audio.data), vec![0; 10]);
+        assert!(player.state.lock().fade.is_none());
+    }
+}
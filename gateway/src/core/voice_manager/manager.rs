@@ -1,37 +1,154 @@
 //! Main VoiceManager implementation
 
 use bytes::Bytes;
-use parking_lot::RwLock as SyncRwLock;
+use parking_lot::{Mutex as SyncMutex, RwLock as SyncRwLock};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use tokio::sync::{Notify, RwLock};
 use tokio::time::Duration;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::core::cache::store::CacheStore;
 use crate::core::{
-    create_stt_provider, create_tts_provider,
     stt::{
-        BaseSTT, STTError, STTErrorCallback as ProviderSTTErrorCallback, STTResult,
-        STTResultCallback,
+        BaseSTT, FailoverSTT, STTError, STTErrorCallback as ProviderSTTErrorCallback,
+        STTFailoverUsage, STTResult, STTResultCallback, STTVadCallback, STTVadEvent,
+        SessionAudioClock, TurnRoutedSTT, TurnRouter, UtteranceKind,
+    },
+    tts::{
+        AudioData, BaseTTS, TELEPHONY_SAMPLE_RATE, TTSError, TTSTimingEvent, TelephonyFramer,
+        telephony_config,
     },
-    tts::{AudioData, BaseTTS, TTSError},
     turn_detect::TurnDetector,
 };
 
+#[cfg(feature = "chaos")]
+use crate::core::chaos::{ChaosSTT, ChaosTTS};
+
 use super::{
+    audio_quality::{AudioQualityChecker, TTSAudioQualityWarning, is_pcm16},
     callbacks::{
-        AudioClearCallback, STTCallback, STTErrorCallback, TTSAudioCallback, TTSCompleteCallback,
-        TTSErrorCallback, VoiceManagerTTSCallback,
+        AudioClearCallback, PreemptionCallback, STTCallback, STTErrorCallback, TTSAudioCallback,
+        TTSCompleteCallback, TTSErrorCallback, TTSQueueFullCallback, TTSTextCallback,
+        TTSTimingCallback, TurnDetectionDegradedCallback, VoiceManagerTTSCallback,
     },
     config::VoiceManagerConfig,
+    endpointing::EndpointingDriver,
     errors::{VoiceManagerError, VoiceManagerResult},
+    filler::{FillerAudio, FillerPlayer, FillerStats},
+    preemption::{Hold, PausedUtterance, Preemption, PreemptionEvent, Resume, SpeakPriority},
+    sample_rate::SampleRateGuard,
     state::{InterruptionState, SpeechFinalState},
     stt_result::{STTProcessingConfig, STTResultProcessor},
+    text_dedup::{Deduplicated, PartialTextDedup, TextDedupStats},
+    tts_queue::{Admission, TTSQueue, TTSQueueFull, TTSQueueStats},
+    turn_detection::{TurnDetectionDegraded, TurnDetectionMonitor, TurnDetectionStats},
+    voice_fallback::VoiceFallback,
 };
 
+/// Callbacks registered with the STT provider
+#[derive(Clone, Default)]
+struct STTProviderCallbacks {
+    result: Option<STTResultCallback>,
+    error: Option<ProviderSTTErrorCallback>,
+    vad: Option<STTVadCallback>,
+}
+
+/// Handles for sending text to the TTS provider, shared with the task that
+/// resumes speech paused by a system announcement
+#[derive(Clone)]
+struct TTSSender {
+    tts: Arc<RwLock<Box<dyn BaseTTS>>>,
+    tts_queue: Arc<TTSQueue>,
+    voice_fallback: Option<Arc<VoiceFallback>>,
+    audio_quality: Option<Arc<AudioQualityChecker>>,
+    telephony: Option<Arc<TelephonyFramer>>,
+    sample_rate_guard: Option<Arc<SampleRateGuard>>,
+    filler: Arc<FillerPlayer>,
+    text_callback: Arc<SyncRwLock<Option<TTSTextCallback>>>,
+}
+
+impl TTSSender {
+    /// Send text to the TTS provider, enforcing the pending utterance cap
+    ///
+    /// When the cap is reached, the `reject` policy fails with
+    /// `VoiceManagerError::TTSQueueFull` and the `drop_oldest` policy clears the
+    /// provider and re-sends every pending utterance except the oldest normal
+    /// one before `text`. Either way the queue-full callback is notified.
+    async fn send(
+        &self,
+        text: &str,
+        flush: bool,
+        priority: SpeakPriority,
+    ) -> VoiceManagerResult<()> {
+        let mut tts = self.tts.write().await;
+        let dropped = match self.tts_queue.admit_with_priority(text, flush, priority) {
+            Admission::Accepted => None,
+            Admission::Rejected => {
+                drop(tts);
+                let max_pending = self.tts_queue.limit().max_pending;
+                warn!(max_pending, "TTS queue full, rejecting utterance");
+                self.tts_queue.notify_full(text.to_string()).await;
+                return Err(VoiceManagerError::TTSQueueFull { max_pending });
+            }
+            Admission::DroppedOldest { dropped, retained } => {
+                debug!("TTS queue full, dropping oldest utterance");
+                // Providers only clear their whole queue, so the rest is sent again
+                tts.clear().await.map_err(VoiceManagerError::TTSError)?;
+                if let Some(fallback) = &self.voice_fallback {
+                    fallback.clear();
+                }
+                if let Some(checker) = &self.audio_quality {
+                    checker.clear();
+                }
+                if let Some(framer) = &self.telephony {
+                    framer.reset();
+                }
+                if let Some(guard) = &self.sample_rate_guard {
+                    guard.reset();
+                }
+                for (retained_text, retained_flush) in &retained {
+                    if let Some(fallback) = &self.voice_fallback {
+                        fallback.record(retained_text, *retained_flush);
+                    }
+                    if let Some(checker) = &self.audio_quality {
+                        checker.record(retained_text, *retained_flush);
+                    }
+                    tts.speak(retained_text, *retained_flush)
+                        .await
+                        .map_err(VoiceManagerError::TTSError)?;
+                }
+                Some(dropped)
+            }
+        };
+
+        if let Some(fallback) = &self.voice_fallback {
+            fallback.record(text, flush);
+        }
+        if let Some(checker) = &self.audio_quality {
+            checker.record(text, flush);
+        }
+        if let Err(e) = tts.speak(text, flush).await {
+            self.tts_queue.revoke_last();
+            return Err(VoiceManagerError::TTSError(e));
+        }
+        drop(tts);
+        self.filler.arm();
+
+        let text_callback = self.text_callback.read().clone();
+        if let Some(callback) = text_callback {
+            callback(text.to_string()).await;
+        }
+        if let Some(dropped) = dropped {
+            self.tts_queue.notify_full(dropped).await;
+        }
+
+        Ok(())
+    }
+}
+
 /// VoiceManager provides a unified interface for managing STT and TTS providers
 /// Optimized for extreme low-latency with lock-free atomics and pre-allocated buffers
 pub struct VoiceManager {
@@ -45,6 +162,14 @@ pub struct VoiceManager {
     tts_error_callback: Arc<SyncRwLock<Option<TTSErrorCallback>>>,
     audio_clear_callback: Arc<SyncRwLock<Option<AudioClearCallback>>>,
     tts_complete_callback: Arc<SyncRwLock<Option<TTSCompleteCallback>>>,
+    tts_timing_callback: Arc<SyncRwLock<Option<TTSTimingCallback>>>,
+    tts_text_callback: Arc<SyncRwLock<Option<TTSTextCallback>>>,
+
+    // Callbacks as registered with the STT provider, kept for re-registration on reconnect
+    stt_provider_callbacks: SyncRwLock<STTProviderCallbacks>,
+
+    // Session-relative timeline of the audio sent to the STT provider
+    audio_clock: Arc<SyncMutex<SessionAudioClock>>,
 
     // Speech final timing control - using parking_lot for faster access
     speech_final_state: Arc<SyncRwLock<SpeechFinalState>>,
@@ -52,14 +177,53 @@ pub struct VoiceManager {
     // Turn detection for better end-of-speech detection
     turn_detector: Option<Arc<RwLock<TurnDetector>>>,
 
+    // Turn detection mode, metrics and the silence-based fallback
+    turn_detection: Arc<TurnDetectionMonitor>,
+
+    // Adaptive end-of-turn silence threshold (None when disabled)
+    endpointing: Option<Arc<EndpointingDriver>>,
+
+    // 8kHz μ-law framing for the telephony output profile (None when native)
+    telephony: Option<Arc<TelephonyFramer>>,
+
+    // Corrects TTS audio at another sample rate than requested (None without a rate)
+    sample_rate_guard: Option<Arc<SampleRateGuard>>,
+
+    // Switches to the fallback voice when the configured one is missing (None when unset)
+    voice_fallback: Option<Arc<VoiceFallback>>,
+
+    // Pending TTS utterances, capped by `VoiceManagerConfig::tts_queue_limit`
+    tts_queue: Arc<TTSQueue>,
+
+    // Flags clipped, silent or inaudible TTS audio (None when disabled)
+    audio_quality: Option<Arc<AudioQualityChecker>>,
+
+    // Plays the filler asset while the first TTS audio is slow (idle until configured)
+    filler: Arc<FillerPlayer>,
+
+    // Strips resent prefixes from partial text (None unless `dedupe_partials`)
+    text_dedup: Option<PartialTextDedup>,
+
+    // Audio sent to each STT provider (None unless `stt_failover` is set)
+    stt_failover_usage: Option<Arc<STTFailoverUsage>>,
+
+    // Per-turn STT routing (None unless `stt_routing` is set)
+    stt_router: Option<Arc<TurnRouter>>,
+
     // Interruption control - mostly lock-free with atomics
     interruption_state: Arc<InterruptionState>,
 
+    // System announcements and the speech they paused
+    preemption: Arc<Preemption>,
+
     // Configuration
     config: VoiceManagerConfig,
 
     // Notification for audio clear completion instead of sleep
     clear_notify: Arc<Notify>,
+
+    // Incremented on every successful clear so in-flight injected audio stops
+    clear_generation: AtomicUsize,
 }
 
 impl VoiceManager {
@@ -98,44 +262,169 @@ impl VoiceManager {
         config: VoiceManagerConfig,
         turn_detector: Option<Arc<RwLock<TurnDetector>>>,
     ) -> VoiceManagerResult<Self> {
-        let tts = create_tts_provider(&config.tts_config.provider, config.tts_config.clone())
+        // The telephony profile asks the provider for its closest native format
+        let (provider_tts_config, telephony) = if config.tts_config.is_telephony() {
+            let resolved =
+                telephony_config(&config.tts_config).map_err(VoiceManagerError::TTSError)?;
+            (resolved, Some(Arc::new(TelephonyFramer::new())))
+        } else {
+            (config.tts_config.clone(), None)
+        };
+        let registry = &config.plugin_registry;
+        let tts = registry
+            .create_tts(&provider_tts_config.provider, provider_tts_config.clone())
             .map_err(VoiceManagerError::TTSError)?;
-        let stt = create_stt_provider(&config.stt_config.provider, config.stt_config.clone())
+        let stt = registry
+            .create_stt(&config.stt_config.provider, config.stt_config.clone())
             .map_err(VoiceManagerError::STTError)?;
 
+        // Faults go into the primary providers, beneath failover
+        #[cfg(feature = "chaos")]
+        let (stt, tts): (Box<dyn BaseSTT>, Box<dyn BaseTTS>) = match &config.chaos {
+            Some(chaos) => (
+                Box::new(ChaosSTT::wrap(stt, chaos.clone())),
+                Box::new(ChaosTTS::wrap(tts, chaos.clone())),
+            ),
+            None => (stt, tts),
+        };
+
+        let (stt, stt_failover_usage): (Box<dyn BaseSTT>, _) = match &config.stt_failover {
+            Some(failover) => {
+                let secondary = registry
+                    .create_stt(&failover.secondary.provider, failover.secondary.clone())
+                    .map_err(VoiceManagerError::STTError)?;
+                let stt = FailoverSTT::with_providers(stt, secondary, failover.warm_standby)
+                    .with_connect_timeout(config.connect_timeout);
+                let usage = stt.usage();
+                (Box::new(stt), Some(usage))
+            }
+            None => (stt, None),
+        };
+        let (stt, stt_router): (Box<dyn BaseSTT>, _) = match &config.stt_routing {
+            Some(routing) => {
+                let fast = registry
+                    .create_stt(&routing.fast.provider, routing.fast.clone())
+                    .map_err(VoiceManagerError::STTError)?;
+                let stt = TurnRoutedSTT::with_providers(stt, fast)
+                    .with_connect_timeout(config.connect_timeout);
+                let router = stt.router();
+                (Box::new(stt), Some(router))
+            }
+            None => (stt, None),
+        };
+        if let Some(endpointing) = &config.adaptive_endpointing {
+            endpointing.validate().map_err(|e| {
+                VoiceManagerError::InitializationError(format!(
+                    "Invalid adaptive endpointing configuration: {e}"
+                ))
+            })?;
+        }
+        config.tts_audio_quality.validate().map_err(|e| {
+            VoiceManagerError::InitializationError(format!(
+                "Invalid TTS audio quality configuration: {e}"
+            ))
+        })?;
+
         // Pre-allocate string buffers with reasonable capacity
         const TEXT_BUFFER_CAPACITY: usize = 1024;
         let text_buffer = String::with_capacity(TEXT_BUFFER_CAPACITY);
 
+        let stt = Arc::new(RwLock::new(stt));
+        let speech_final_state = Arc::new(SyncRwLock::new(SpeechFinalState {
+            text_buffer,
+            turn_detection_handle: None,
+            hard_timeout_handle: None,
+            waiting_for_speech_final: AtomicBool::new(false),
+            user_callback: None,
+            turn_detection_last_fired_ms: AtomicUsize::new(0),
+            last_forced_text: String::with_capacity(1024),
+            segment_start_ms: AtomicUsize::new(0),
+            hard_timeout_deadline_ms: AtomicUsize::new(0),
+        }));
+        let endpointing = config.adaptive_endpointing.map(|endpointing| {
+            Arc::new(EndpointingDriver::new(
+                endpointing,
+                stt.clone(),
+                speech_final_state.clone(),
+            ))
+        });
+
+        let tts = Arc::new(RwLock::new(tts));
+        let voice_fallback = config
+            .fallback_voice_id
+            .clone()
+            .filter(|fallback| provider_tts_config.voice_id.as_ref() != Some(fallback))
+            .map(|fallback| {
+                Arc::new(VoiceFallback::new(
+                    &provider_tts_config,
+                    fallback,
+                    config.connect_timeout,
+                    tts.clone(),
+                    config.plugin_registry.clone(),
+                ))
+            });
+        let tts_queue = Arc::new(TTSQueue::new(config.tts_queue_limit));
+        let sample_rate_guard = provider_tts_config.sample_rate.map(|requested| {
+            Arc::new(SampleRateGuard::new(
+                provider_tts_config.provider.clone(),
+                requested,
+            ))
+        });
+        let audio_quality = config.tts_audio_quality.enabled.then(|| {
+            Arc::new(AudioQualityChecker::new(
+                config.tts_audio_quality,
+                provider_tts_config.provider.clone(),
+                tts.clone(),
+            ))
+        });
+        let turn_detection = Arc::new(TurnDetectionMonitor::new(
+            turn_detector.is_some(),
+            config.turn_detector_expected,
+            Duration::from_millis(config.speech_final_config.turn_detection_latency_budget_ms),
+            &config.stt_config.encoding,
+        ));
+        let audio_clock = Arc::new(SyncMutex::new(SessionAudioClock::new(
+            config.stt_config.sample_rate,
+            config.stt_config.channels,
+            &config.stt_config.encoding,
+        )));
+
         Ok(Self {
-            tts: Arc::new(RwLock::new(tts)),
-            stt: Arc::new(RwLock::new(stt)),
+            tts,
+            stt,
             stt_callback: Arc::new(SyncRwLock::new(None)),
             stt_error_callback: Arc::new(SyncRwLock::new(None)),
             tts_audio_callback: Arc::new(SyncRwLock::new(None)),
             tts_error_callback: Arc::new(SyncRwLock::new(None)),
             audio_clear_callback: Arc::new(SyncRwLock::new(None)),
             tts_complete_callback: Arc::new(SyncRwLock::new(None)),
-            speech_final_state: Arc::new(SyncRwLock::new(SpeechFinalState {
-                text_buffer,
-                turn_detection_handle: None,
-                hard_timeout_handle: None,
-                waiting_for_speech_final: AtomicBool::new(false),
-                user_callback: None,
-                turn_detection_last_fired_ms: AtomicUsize::new(0),
-                last_forced_text: String::with_capacity(1024),
-                segment_start_ms: AtomicUsize::new(0),
-                hard_timeout_deadline_ms: AtomicUsize::new(0),
-            })),
+            tts_timing_callback: Arc::new(SyncRwLock::new(None)),
+            tts_text_callback: Arc::new(SyncRwLock::new(None)),
+            stt_provider_callbacks: SyncRwLock::new(STTProviderCallbacks::default()),
+            audio_clock,
+            speech_final_state,
             turn_detector,
+            turn_detection,
+            endpointing,
+            filler: Arc::new(FillerPlayer::new(telephony.is_some())),
+            telephony,
+            sample_rate_guard,
+            voice_fallback,
+            tts_queue,
+            audio_quality,
+            text_dedup: config.dedupe_partials.then(PartialTextDedup::new),
+            stt_failover_usage,
+            stt_router,
             interruption_state: Arc::new(InterruptionState {
                 allow_interruption: AtomicBool::new(true),
                 non_interruptible_until_ms: AtomicUsize::new(0),
                 current_sample_rate: AtomicU32::new(24000),
                 is_completed: AtomicBool::new(true), // Start as completed
             }),
+            preemption: Arc::new(Preemption::new()),
             config,
             clear_notify: Arc::new(Notify::new()),
+            clear_generation: AtomicUsize::new(0),
         })
     }
 
@@ -178,29 +467,52 @@ impl VoiceManager {
     /// # }
     /// ```
     pub async fn start(&self) -> VoiceManagerResult<()> {
+        let connect_timeout = self.config.connect_timeout;
+
         // Connect STT provider
         {
             let mut stt = self.stt.write().await;
-            stt.connect().await.map_err(VoiceManagerError::STTError)?;
+            if self
+                .config
+                .adaptive_endpointing
+                .is_some_and(|endpointing| endpointing.manual_commit)
+            {
+                match stt.set_manual_commit(true).await {
+                    Ok(true) => debug!("STT provider commits utterances on gateway endpointing"),
+                    Ok(false) => {
+                        debug!("STT provider has no manual commit - keeping its endpointing")
+                    }
+                    Err(e) => warn!("Failed to enable manual commit: {}", e),
+                }
+            }
+            stt.connect_with_timeout(connect_timeout)
+                .await
+                .map_err(VoiceManagerError::STTError)?;
         }
 
         // Connect TTS provider
         {
             let mut tts = self.tts.write().await;
-            tts.connect().await.map_err(VoiceManagerError::TTSError)?;
+            tts.connect_with_timeout(connect_timeout)
+                .await
+                .map_err(VoiceManagerError::TTSError)?;
+
+            // Without a fallback voice, a missing voice would fail every utterance
+            if self.voice_fallback.is_none() {
+                match tts.validate_voice().await {
+                    Ok(()) => {}
+                    Err(e @ TTSError::VoiceNotFound(_)) => {
+                        return Err(VoiceManagerError::TTSError(e));
+                    }
+                    Err(e) => warn!("Skipping TTS voice validation: {}", e),
+                }
+            }
         }
 
         // Set up internal TTS callback - using parking_lot for faster access
         {
             let mut tts = self.tts.write().await;
-            let tts_callback = Arc::new(VoiceManagerTTSCallback {
-                audio_callback: self.tts_audio_callback.read().clone(),
-                error_callback: self.tts_error_callback.read().clone(),
-                interruption_state: Some(self.interruption_state.clone()),
-                complete_callback: self.tts_complete_callback.read().clone(),
-            });
-
-            tts.on_audio(tts_callback)
+            tts.on_audio(self.tts_callback())
                 .map_err(VoiceManagerError::TTSError)?;
         }
 
@@ -226,6 +538,12 @@ impl VoiceManager {
     /// # }
     /// ```
     pub async fn stop(&self) -> VoiceManagerResult<()> {
+        if let Some(endpointing) = &self.endpointing {
+            endpointing.cancel_timer();
+        }
+        self.preemption.reset();
+        self.filler.stop();
+
         // Cancel any pending speech final timer
         {
             let mut state = self.speech_final_state.write();
@@ -326,11 +644,98 @@ impl VoiceManager {
     /// # }
     /// ```
     pub async fn receive_audio(&self, audio: Bytes) -> VoiceManagerResult<()> {
+        let len = audio.len();
+        self.turn_detection.observe_audio(&audio);
         // Send audio to STT provider (zero-copy pass-through)
         let mut stt = self.stt.write().await;
         stt.send_audio(audio)
             .await
             .map_err(VoiceManagerError::STTError)?;
+        self.audio_clock.lock().record_audio(len);
+        Ok(())
+    }
+
+    /// Reconnect the STT provider, replaying audio it may not have transcribed
+    ///
+    /// Registered callbacks carry over to the new connection. `replay` is
+    /// audio that was already passed to [`receive_audio`](Self::receive_audio)
+    /// and is sent again, in order, before any new audio; transcript timings
+    /// account for it so they keep increasing across the reconnect.
+    ///
+    /// # Arguments
+    /// * `replay` - Most recent audio to send again to the new connection
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn reconnect_stt(&self, replay: &[Bytes]) -> VoiceManagerResult<()> {
+        let mut stt = self.stt.write().await;
+        if let Err(e) = stt.disconnect().await {
+            warn!("Failed to disconnect STT provider before reconnect: {e}");
+        }
+        stt.connect_with_timeout(self.config.connect_timeout)
+            .await
+            .map_err(VoiceManagerError::STTError)?;
+
+        let callbacks = self.stt_provider_callbacks.read().clone();
+        if let Some(callback) = callbacks.result {
+            stt.on_result(callback)
+                .await
+                .map_err(VoiceManagerError::STTError)?;
+        }
+        if let Some(callback) = callbacks.error {
+            stt.on_error(callback)
+                .await
+                .map_err(VoiceManagerError::STTError)?;
+        }
+        if let Some(callback) = callbacks.vad {
+            stt.on_vad_event(callback)
+                .await
+                .map_err(VoiceManagerError::STTError)?;
+        }
+
+        let replayed = replay.iter().map(Bytes::len).sum();
+        self.audio_clock.lock().stream_restarted(replayed);
+        for chunk in replay {
+            stt.send_audio(chunk.clone())
+                .await
+                .map_err(VoiceManagerError::STTError)?;
+        }
+
+        debug!(replayed_bytes = replayed, "STT provider reconnected");
+        Ok(())
+    }
+
+    /// Reconnect the TTS provider, dropping utterances it has not finished
+    ///
+    /// Registered callbacks carry over to the new connection. Pending text
+    /// and unplayed audio are discarded as on [`clear_tts`](Self::clear_tts),
+    /// so the audio clear callback runs, but neither a non-interruptible
+    /// utterance nor a `system` announcement blocks it.
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn reconnect_tts(&self) -> VoiceManagerResult<()> {
+        {
+            let mut tts = self.tts.write().await;
+            if let Err(e) = tts.disconnect().await {
+                warn!("Failed to disconnect TTS provider before reconnect: {e}");
+            }
+            tts.connect_with_timeout(self.config.connect_timeout)
+                .await
+                .map_err(VoiceManagerError::TTSError)?;
+            tts.on_audio(self.tts_callback())
+                .map_err(VoiceManagerError::TTSError)?;
+        }
+
+        self.discard_pending_tts().await?;
+        if let Some(dedup) = &self.text_dedup {
+            dedup.reset();
+        }
+        // A stalled announcement would otherwise keep the queue paused
+        self.preemption.reset();
+        self.interruption_state.reset();
+
+        debug!("TTS provider reconnected");
         Ok(())
     }
 
@@ -362,15 +767,13 @@ impl VoiceManager {
     /// # }
     /// ```
     pub async fn speak(&self, text: &str, flush: bool) -> VoiceManagerResult<()> {
-        // Send text to TTS provider
+        if let Some(result) = self
+            .hold_during_preemption(text, flush, None, SpeakPriority::Normal)
+            .await
         {
-            let mut tts = self.tts.write().await;
-            tts.speak(text, flush)
-                .await
-                .map_err(VoiceManagerError::TTSError)?;
+            return result;
         }
-
-        Ok(())
+        self.send_partial(text, flush).await
     }
 
     /// Send text to the TTS provider with interruption control
@@ -388,46 +791,351 @@ impl VoiceManager {
         flush: bool,
         allow_interruption: bool,
     ) -> VoiceManagerResult<()> {
-        // Update interruption state
+        self.speak_with_priority(text, flush, allow_interruption, SpeakPriority::Normal)
+            .await
+    }
+
+    /// Send text to the TTS provider with interruption control and a priority
+    ///
+    /// `high` text is exempt from the pending utterance cap. `system` text is
+    /// an announcement: it stops the current utterance, pauses the rest of the
+    /// queue and plays without barge-in, always flushed. Once it has played,
+    /// the paused utterances are re-sent in order, the interrupted one from its
+    /// beginning. Speech requested in the meantime is held and sent after them.
+    ///
+    /// # Arguments
+    /// * `text` - Text to synthesize
+    /// * `flush` - Whether to immediately flush and start processing the text
+    /// * `allow_interruption` - Whether this audio can be interrupted by STT or clear commands;
+    ///   ignored for `system` text
+    /// * `priority` - Priority of the text
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error; `system` text longer than
+    ///   `VoiceManagerConfig::system_speak_max_chars` fails with
+    ///   `VoiceManagerError::SystemSpeakTooLong`
+    pub async fn speak_with_priority(
+        &self,
+        text: &str,
+        flush: bool,
+        allow_interruption: bool,
+        priority: SpeakPriority,
+    ) -> VoiceManagerResult<()> {
+        if priority == SpeakPriority::System {
+            return self.announce(text).await;
+        }
+        if let Some(result) = self
+            .hold_during_preemption(text, flush, Some(allow_interruption), priority)
+            .await
+        {
+            return result;
+        }
+
         self.interruption_state
-            .allow_interruption
-            .store(allow_interruption, Ordering::Release);
+            .begin_utterance(allow_interruption, self.output_sample_rate());
+        match priority {
+            SpeakPriority::Normal => self.send_partial(text, flush).await,
+            _ => self.tts_sender().send(text, flush, priority).await,
+        }
+    }
 
-        if !allow_interruption {
-            // Update sample rate from TTS config
-            if let Some(sample_rate) = self.config.tts_config.sample_rate {
-                self.interruption_state
-                    .current_sample_rate
-                    .store(sample_rate, Ordering::Release);
+    /// Hold text requested while a system announcement pre-empts the queue
+    ///
+    /// # Returns
+    /// * `Option<VoiceManagerResult<()>>` - `None` when no pre-emption is active
+    async fn hold_during_preemption(
+        &self,
+        text: &str,
+        flush: bool,
+        allow_interruption: Option<bool>,
+        priority: SpeakPriority,
+    ) -> Option<VoiceManagerResult<()>> {
+        let limit = self.tts_queue.limit();
+        let utterance = PausedUtterance {
+            text: text.to_string(),
+            flush,
+            allow_interruption,
+            priority,
+        };
+        match self.preemption.hold(utterance, limit) {
+            Hold::NotActive => None,
+            Hold::Held => {
+                debug!("System announcement playing, holding utterance");
+                Some(Ok(()))
             }
+            Hold::Rejected => {
+                let max_pending = limit.max_pending;
+                warn!(
+                    max_pending,
+                    "TTS queue full during system announcement, rejecting utterance"
+                );
+                self.tts_queue.record_rejected();
+                self.tts_queue.notify_full(text.to_string()).await;
+                Some(Err(VoiceManagerError::TTSQueueFull { max_pending }))
+            }
+            Hold::DroppedOldest(dropped) => {
+                debug!("TTS queue full during system announcement, dropping oldest utterance");
+                self.tts_queue.record_dropped();
+                self.tts_queue.notify_full(dropped).await;
+                Some(Ok(()))
+            }
+        }
+    }
+
+    /// Speak a system announcement, pre-empting the current utterance
+    async fn announce(&self, text: &str) -> VoiceManagerResult<()> {
+        let max_chars = self.config.system_speak_max_chars;
+        if text.chars().count() > max_chars {
+            return Err(VoiceManagerError::SystemSpeakTooLong { max_chars });
+        }
+
+        // Hold new speech first so nothing slips in between pausing and clearing
+        let started = self.preemption.begin(
+            self.interruption_state
+                .allow_interruption
+                .load(Ordering::Acquire),
+        );
+        if started {
+            self.spawn_resume();
+        }
+        let interrupted = self
+            .tts_queue
+            .take_pending()
+            .into_iter()
+            .map(|(text, flush, priority)| PausedUtterance {
+                text,
+                flush,
+                allow_interruption: None,
+                priority,
+            })
+            .collect();
+        let paused = self.preemption.pause(interrupted);
+        if let Err(e) = self.discard_pending_tts().await {
+            // Let the resume task send the paused speech again
+            self.preemption.complete();
+            return Err(e);
+        }
+        if let Some(dedup) = &self.text_dedup {
+            dedup.reset();
+        }
+
+        self.interruption_state
+            .begin_utterance(false, self.output_sample_rate());
+        if started {
+            debug!(paused, "System announcement pre-empting TTS queue");
+            self.preemption
+                .notify(PreemptionEvent::Started {
+                    text: text.to_string(),
+                    paused,
+                })
+                .await;
+        }
+
+        let result = self
+            .tts_sender()
+            .send(text, true, SpeakPriority::System)
+            .await;
+        if result.is_err() {
+            self.preemption.complete();
+        }
+        result
+    }
+
+    /// Resume the paused speech once the announcements have played out
+    fn spawn_resume(&self) {
+        let preemption = self.preemption.clone();
+        let interruption_state = self.interruption_state.clone();
+        let sender = self.tts_sender();
+        let sample_rate = self.output_sample_rate();
+
+        let task = tokio::spawn(async move {
+            let mut resumed = 0;
+            loop {
+                preemption.wait_generated().await;
+
+                // The announcement is non-interruptible until its audio has played
+                loop {
+                    let until_ms = interruption_state
+                        .non_interruptible_until_ms
+                        .load(Ordering::Acquire);
+                    let now_ms = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as usize;
+                    if until_ms <= now_ms {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis((until_ms - now_ms) as u64)).await;
+                }
+                if !preemption.start_resume() {
+                    continue;
+                }
+
+                loop {
+                    match preemption.next_paused() {
+                        Resume::Next(utterance) => {
+                            let allow_interruption = utterance
+                                .allow_interruption
+                                .unwrap_or_else(|| preemption.allow_interruption());
+                            interruption_state.begin_utterance(allow_interruption, sample_rate);
+                            match sender
+                                .send(&utterance.text, utterance.flush, utterance.priority)
+                                .await
+                            {
+                                Ok(()) => resumed += 1,
+                                Err(e) => warn!("Failed to resume paused utterance: {}", e),
+                            }
+                        }
+                        Resume::Wait => break,
+                        Resume::Done => {
+                            debug!(resumed, "System announcement played, TTS queue resumed");
+                            preemption.notify(PreemptionEvent::Ended { resumed }).await;
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        self.preemption.set_resume_task(task);
+    }
+
+    /// Send text to the TTS provider, dropping resent partials when enabled
+    ///
+    /// A partial that revises earlier text of the utterance discards the
+    /// pending text and audio before the revised text is sent in full.
+    async fn send_partial(&self, text: &str, flush: bool) -> VoiceManagerResult<()> {
+        let Some(dedup) = &self.text_dedup else {
+            return self.send_to_tts(text, flush).await;
+        };
+        match dedup.process(text, flush) {
+            Deduplicated::Append(new_text) => self.send_to_tts(&new_text, flush).await,
+            Deduplicated::Skip if flush => self.flush_tts().await,
+            Deduplicated::Skip => Ok(()),
+            Deduplicated::Replace(full_text) => {
+                debug!("TTS partial revised earlier text, replacing the pending utterance");
+                self.discard_pending_tts().await?;
+                self.send_to_tts(&full_text, flush).await
+            }
+        }
+    }
+
+    /// Send normal priority text to the TTS provider, enforcing the pending utterance cap
+    async fn send_to_tts(&self, text: &str, flush: bool) -> VoiceManagerResult<()> {
+        self.tts_sender()
+            .send(text, flush, SpeakPriority::Normal)
+            .await
+    }
+
+    /// Handles for sending text to the TTS provider
+    fn tts_sender(&self) -> TTSSender {
+        TTSSender {
+            tts: self.tts.clone(),
+            tts_queue: self.tts_queue.clone(),
+            voice_fallback: self.voice_fallback.clone(),
+            audio_quality: self.audio_quality.clone(),
+            telephony: self.telephony.clone(),
+            sample_rate_guard: self.sample_rate_guard.clone(),
+            filler: self.filler.clone(),
+            text_callback: self.tts_text_callback.clone(),
+        }
+    }
 
-            // Get current timestamp in milliseconds
+    /// Play pre-synthesized audio through the TTS output path
+    ///
+    /// The audio is delivered to the registered TTS audio callback in chunks, so it
+    /// reaches the same sinks as synthesized speech (LiveKit or WebSocket). It
+    /// honors interruption control and `clear_tts()`, and fires the TTS completion
+    /// callback once all chunks have been delivered.
+    ///
+    /// # Arguments
+    /// * `pcm` - 16-bit little-endian mono PCM audio
+    /// * `sample_rate` - Sample rate of `pcm` in Hz
+    /// * `allow_interruption` - Whether this audio can be interrupted by STT or clear commands
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn play_audio(
+        &self,
+        pcm: Vec<u8>,
+        sample_rate: u32,
+        allow_interruption: bool,
+    ) -> VoiceManagerResult<()> {
+        // ~100ms at 16kHz, 16-bit mono; even so chunks hold whole samples
+        const PLAY_AUDIO_CHUNK_BYTES: usize = 3200;
+
+        let callback = self.tts_audio_callback.read().clone().ok_or_else(|| {
+            VoiceManagerError::InternalError("TTS audio callback not registered".to_string())
+        })?;
+
+        self.interruption_state
+            .allow_interruption
+            .store(allow_interruption, Ordering::Release);
+        let output_sample_rate = if self.telephony.is_some() {
+            TELEPHONY_SAMPLE_RATE
+        } else {
+            sample_rate
+        };
+        self.interruption_state
+            .current_sample_rate
+            .store(output_sample_rate, Ordering::Release);
+        if !allow_interruption {
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_millis() as usize;
-
-            // Initialize non_interruptible_until_ms to current time
-            // The actual duration will be calculated as TTS chunks arrive
             self.interruption_state
                 .non_interruptible_until_ms
                 .store(now, Ordering::Release);
-
-            // Mark as not completed since new audio is starting
-            self.interruption_state
-                .is_completed
-                .store(false, Ordering::SeqCst);
+        }
+        // Mark as in progress; the audio callback wrapper adds each chunk's duration
+        self.interruption_state
+            .is_completed
+            .store(false, Ordering::SeqCst);
+
+        let chunks: Vec<AudioData> = if self.telephony.is_some() {
+            // A dedicated framer keeps injected audio out of the synthesis stream
+            let framer = TelephonyFramer::new();
+            let mut frames = framer
+                .push(&AudioData {
+                    data: pcm,
+                    sample_rate,
+                    format: "linear16".to_string(),
+                    duration_ms: None,
+                })
+                .map_err(VoiceManagerError::TTSError)?;
+            frames.extend(framer.flush());
+            frames
         } else {
-            // For interruptible audio, just reset to defaults
-            self.interruption_state.reset();
+            pcm.chunks(PLAY_AUDIO_CHUNK_BYTES)
+                .map(|chunk| AudioData {
+                    data: chunk.to_vec(),
+                    sample_rate,
+                    format: "linear16".to_string(),
+                    duration_ms: Some(
+                        (chunk.len() as u64 * 1000 / (sample_rate.max(1) as u64 * 2)) as u32,
+                    ),
+                })
+                .collect()
+        };
+
+        let generation = self.clear_generation.load(Ordering::Acquire);
+
+        for mut chunk in chunks {
+            if self.clear_generation.load(Ordering::Acquire) != generation {
+                debug!("Injected audio interrupted by clear");
+                return Ok(());
+            }
+
+            self.filler.on_real_audio(&mut chunk).await;
+            callback(chunk).await;
         }
 
-        // Send text to TTS provider
-        {
-            let mut tts = self.tts.write().await;
-            tts.speak(text, flush)
-                .await
-                .map_err(VoiceManagerError::TTSError)?;
+        self.interruption_state
+            .is_completed
+            .store(true, Ordering::SeqCst);
+        let complete_callback = self.tts_complete_callback.read().clone();
+        if let Some(complete_callback) = complete_callback {
+            complete_callback().await;
         }
 
         Ok(())
@@ -438,7 +1146,7 @@ impl VoiceManager {
     /// # Returns
     /// * `bool` - True if interruption is currently blocked
     pub async fn is_interruption_blocked(&self) -> bool {
-        !self.interruption_state.can_interrupt()
+        self.preemption.is_announcing() || !self.interruption_state.can_interrupt()
     }
 
     /// Clear any queued text from the TTS provider and audio buffers
@@ -449,26 +1157,20 @@ impl VoiceManager {
     /// # Returns
     /// * `VoiceManagerResult<()>` - Success or error
     pub async fn clear_tts(&self) -> VoiceManagerResult<()> {
-        // Check if we're allowed to clear
-        if !self.interruption_state.can_interrupt() {
+        // Check if we're allowed to clear; system announcements are never cleared
+        if self.is_interruption_blocked().await {
             // Not allowed to interrupt yet
             return Ok(());
         }
 
         debug!("Starting audio clearing process");
 
-        // Clear TTS text queue
-        let mut tts = self.tts.write().await;
-        tts.clear().await.map_err(VoiceManagerError::TTSError)?;
-        drop(tts); // Release the lock
-
-        // Call audio clear callback to clear any audio buffers (e.g., LiveKit)
-        {
-            let callback_opt = self.audio_clear_callback.read().clone();
-            if let Some(callback) = callback_opt {
-                callback().await;
-            }
+        self.discard_pending_tts().await?;
+        if let Some(dedup) = &self.text_dedup {
+            dedup.reset();
         }
+        // Speech still waiting to be resumed after an announcement goes too
+        self.preemption.discard_paused();
 
         // Notify any waiters that the clear operation is complete
         // This wakes up coroutines waiting on clear_notify.notified()
@@ -490,6 +1192,40 @@ impl VoiceManager {
         Ok(())
     }
 
+    /// Drop queued TTS text and any audio not yet played
+    async fn discard_pending_tts(&self) -> VoiceManagerResult<()> {
+        // Stop any in-flight injected audio before clearing downstream buffers
+        self.clear_generation.fetch_add(1, Ordering::AcqRel);
+        self.filler.stop_and_drain().await;
+
+        // Clear TTS text queue
+        let mut tts = self.tts.write().await;
+        tts.clear().await.map_err(VoiceManagerError::TTSError)?;
+        if let Some(fallback) = &self.voice_fallback {
+            fallback.clear();
+        }
+        if let Some(checker) = &self.audio_quality {
+            checker.clear();
+        }
+        self.tts_queue.clear();
+        drop(tts); // Release the lock
+
+        // Drop any partial telephony frame from the interrupted utterance
+        if let Some(framer) = &self.telephony {
+            framer.reset();
+        }
+        if let Some(guard) = &self.sample_rate_guard {
+            guard.reset();
+        }
+
+        // Call audio clear callback to clear any audio buffers (e.g., LiveKit)
+        let callback_opt = self.audio_clear_callback.read().clone();
+        if let Some(callback) = callback_opt {
+            callback().await;
+        }
+        Ok(())
+    }
+
     /// Flush the TTS provider to process queued text immediately
     ///
     /// # Returns
@@ -529,7 +1265,17 @@ impl VoiceManager {
     where
         F: Fn(STTResult) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
     {
-        let callback = Arc::new(callback);
+        // Place every delivered result, including forced speech_final results,
+        // on the session audio clock
+        let audio_clock = self.audio_clock.clone();
+        let stt_router = self.stt_router.clone();
+        let callback: STTCallback = Arc::new(move |mut result: STTResult| {
+            result.timing = Some(audio_clock.lock().anchor(&result));
+            if let Some(router) = &stt_router {
+                router.on_transcript(&result);
+            }
+            callback(result)
+        });
 
         // Store the callback for later use - using parking_lot for faster access
         {
@@ -547,6 +1293,7 @@ impl VoiceManager {
         let speech_final_state_clone = self.speech_final_state.clone();
         let interruption_state_clone = self.interruption_state.clone();
         let turn_detector_clone = self.turn_detector.clone();
+        let endpointing_clone = self.endpointing.clone();
 
         // Create STT processor with configured timeouts from VoiceManagerConfig
         let processing_config = STTProcessingConfig::new(
@@ -557,7 +1304,8 @@ impl VoiceManager {
             self.config.speech_final_config.speech_final_hard_timeout_ms,
             self.config.speech_final_config.duplicate_window_ms,
         );
-        let stt_processor = STTResultProcessor::new(processing_config);
+        let stt_processor =
+            STTResultProcessor::new(processing_config).with_monitor(self.turn_detection.clone());
 
         let wrapper_callback: STTResultCallback = Arc::new(move |result| {
             // Clone Arc references per invocation (lightweight operation)
@@ -566,6 +1314,7 @@ impl VoiceManager {
             let interruption_state = interruption_state_clone.clone();
             let turn_detector = turn_detector_clone.clone();
             let stt_processor = stt_processor.clone();
+            let endpointing = endpointing_clone.clone();
 
             Box::pin(async move {
                 // Fast synchronous check for interruption - execute before any async ops
@@ -574,6 +1323,12 @@ impl VoiceManager {
                     return;
                 }
 
+                // Adapt the end-of-turn threshold to what has been said so far
+                let mut result = result;
+                if let Some(endpointing) = &endpointing {
+                    endpointing.on_result(&mut result);
+                }
+
                 // Process result with timing control - now non-blocking for result delivery
                 let processed_result = stt_processor
                     .process_result(result, speech_final_state, turn_detector)
@@ -590,10 +1345,11 @@ impl VoiceManager {
         // Register callback with STT provider
         {
             let mut stt = self.stt.write().await;
-            stt.on_result(wrapper_callback)
+            stt.on_result(wrapper_callback.clone())
                 .await
                 .map_err(VoiceManagerError::STTError)?;
         }
+        self.stt_provider_callbacks.write().result = Some(wrapper_callback);
 
         Ok(())
     }
@@ -652,14 +1408,40 @@ impl VoiceManager {
         // Register callback with STT provider
         {
             let mut stt = self.stt.write().await;
-            stt.on_error(wrapper_callback)
+            stt.on_error(wrapper_callback.clone())
                 .await
                 .map_err(VoiceManagerError::STTError)?;
         }
+        self.stt_provider_callbacks.write().error = Some(wrapper_callback);
 
         Ok(())
     }
 
+    /// Register a callback for STT voice activity events
+    ///
+    /// Events are forwarded as-is, ahead of the transcripts for the same
+    /// speech, so they can drive barge-in.
+    ///
+    /// # Arguments
+    /// * `callback` - Callback function to handle VAD events
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<bool>` - Whether the STT provider reports VAD events
+    pub async fn on_stt_vad_event<F>(&self, callback: F) -> VoiceManagerResult<bool>
+    where
+        F: Fn(STTVadEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        let callback: STTVadCallback = Arc::new(callback);
+
+        let mut stt = self.stt.write().await;
+        let supported = stt
+            .on_vad_event(callback.clone())
+            .await
+            .map_err(VoiceManagerError::STTError)?;
+        self.stt_provider_callbacks.write().vad = Some(callback);
+        Ok(supported)
+    }
+
     /// Register a callback for TTS audio data
     ///
     /// # Arguments
@@ -719,8 +1501,14 @@ impl VoiceManager {
                 if !int_state.allow_interruption.load(Ordering::Acquire) {
                     // Calculate actual audio duration from audio data
                     // For PCM/linear16: bytes / (sample_rate * bytes_per_sample * channels)
-                    // Assuming 16-bit audio (2 bytes per sample) and mono (1 channel)
-                    let bytes_per_sample = 2;
+                    // Assuming mono (1 channel) and 16-bit audio (2 bytes per sample),
+                    // except G.711 telephony frames which are 1 byte per sample
+                    let bytes_per_sample =
+                        if matches!(audio_data.format.as_str(), "mulaw" | "ulaw" | "alaw") {
+                            1
+                        } else {
+                            2
+                        };
                     let channels = 1;
                     let sample_rate = int_state.current_sample_rate.load(Ordering::Acquire);
 
@@ -746,23 +1534,12 @@ impl VoiceManager {
         });
 
         // Store callback and release lock before await
-        let audio_callback = {
-            let mut tts_audio_callback = self.tts_audio_callback.write();
-            *tts_audio_callback = Some(wrapper_callback.clone());
-            tts_audio_callback.clone()
-        };
+        *self.tts_audio_callback.write() = Some(wrapper_callback);
 
         // Update the internal TTS callback
         {
             let mut tts = self.tts.write().await;
-            let tts_callback = Arc::new(VoiceManagerTTSCallback {
-                audio_callback,
-                error_callback: self.tts_error_callback.read().clone(),
-                interruption_state: Some(self.interruption_state.clone()),
-                complete_callback: self.tts_complete_callback.read().clone(),
-            });
-
-            tts.on_audio(tts_callback)
+            tts.on_audio(self.tts_callback())
                 .map_err(VoiceManagerError::TTSError)?;
         }
 
@@ -781,23 +1558,12 @@ impl VoiceManager {
         F: Fn(TTSError) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
     {
         // Store callback and then release lock before await
-        let error_callback = {
-            let mut tts_error_callback = self.tts_error_callback.write();
-            *tts_error_callback = Some(Arc::new(callback));
-            tts_error_callback.clone()
-        };
+        *self.tts_error_callback.write() = Some(Arc::new(callback));
 
         // Update the internal TTS callback
         {
             let mut tts = self.tts.write().await;
-            let tts_callback = Arc::new(VoiceManagerTTSCallback {
-                audio_callback: self.tts_audio_callback.read().clone(),
-                error_callback,
-                interruption_state: Some(self.interruption_state.clone()),
-                complete_callback: self.tts_complete_callback.read().clone(),
-            });
-
-            tts.on_audio(tts_callback)
+            tts.on_audio(self.tts_callback())
                 .map_err(VoiceManagerError::TTSError)?;
         }
 
@@ -890,23 +1656,326 @@ impl VoiceManager {
 
         // Update the TTS provider's callback to include completion callback
         let mut tts = self.tts.write().await;
-        let audio_callback = self.tts_audio_callback.read().clone();
-        let error_callback = self.tts_error_callback.read().clone();
-        let complete_callback = self.tts_complete_callback.read().clone();
+        tts.on_audio(self.tts_callback())
+            .map_err(VoiceManagerError::TTSError)?;
 
-        let callback = Arc::new(VoiceManagerTTSCallback {
-            audio_callback,
-            error_callback,
-            interruption_state: Some(self.interruption_state.clone()),
-            complete_callback,
-        });
+        Ok(())
+    }
+
+    /// Register a callback for viseme and word timing of synthesized audio
+    ///
+    /// Only providers configured with `TTSConfig::visemes` report timing.
+    /// Offsets are relative to the start of the current utterance's audio,
+    /// which ends with the `on_tts_complete` callback.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function called with each batch of timing events
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_tts_timing<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(Vec<TTSTimingEvent>) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        *self.tts_timing_callback.write() = Some(Arc::new(callback));
 
-        tts.on_audio(callback)
+        let mut tts = self.tts.write().await;
+        tts.on_audio(self.tts_callback())
             .map_err(VoiceManagerError::TTSError)?;
 
         Ok(())
     }
 
+    /// Register a callback for switches to the fallback voice
+    ///
+    /// The callback fires once, after the configured voice turned out not to
+    /// exist and the TTS provider was replaced by one using
+    /// `VoiceManagerConfig::fallback_voice_id`. It receives the configured
+    /// voice id and the fallback voice id. Without a fallback voice the
+    /// callback is never invoked.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call after switching voices
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_tts_voice_fallback<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(String, String) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        if let Some(fallback) = &self.voice_fallback {
+            fallback.set_callback(Arc::new(callback));
+        }
+        Ok(())
+    }
+
+    /// Register a callback for text accepted by the TTS provider
+    ///
+    /// The callback receives the text of every `speak` call once the provider
+    /// has queued it, including text spoken by the agent bridge.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call with the queued text
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_tts_text<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(String) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        *self.tts_text_callback.write() = Some(Arc::new(callback));
+        Ok(())
+    }
+
+    /// Register a callback for speak requests that hit the pending TTS queue cap
+    ///
+    /// The callback receives the applied policy, the cap, and the text of the
+    /// rejected (`reject`) or dropped (`drop_oldest`) utterance.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call when the cap is hit
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_tts_queue_full<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(TTSQueueFull) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        let callback: TTSQueueFullCallback = Arc::new(callback);
+        self.tts_queue.set_callback(callback);
+        Ok(())
+    }
+
+    /// Register a callback for the start and end of system announcement pre-emptions
+    ///
+    /// The callback receives `PreemptionEvent::Started` when a `system` speak
+    /// pauses the queue, and `PreemptionEvent::Ended` once the announcement has
+    /// played and the paused utterances were re-sent.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call with the pre-emption event
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_preemption<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(PreemptionEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        let callback: PreemptionCallback = Arc::new(callback);
+        self.preemption.set_callback(callback);
+        Ok(())
+    }
+
+    /// Whether a system announcement is pre-empting the TTS queue
+    ///
+    /// True from the `system` speak until the paused utterances were re-sent.
+    pub fn is_preempted(&self) -> bool {
+        self.preemption.is_active()
+    }
+
+    /// Register a callback for TTS utterances whose audio failed the quality check
+    ///
+    /// The callback fires once the provider completed an utterance whose 16-bit
+    /// PCM audio was clipped, silent or below the RMS floor set by
+    /// `VoiceManagerConfig::tts_audio_quality`, before the completion callback.
+    /// With the check disabled the callback is never invoked.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call with the measured failure
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_tts_audio_quality_warning<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(TTSAudioQualityWarning) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        if let Some(checker) = &self.audio_quality {
+            checker.set_callback(Arc::new(callback));
+        }
+        Ok(())
+    }
+
+    /// Register a callback for filler audio
+    ///
+    /// Filler chunks configured with [`set_filler_audio`](Self::set_filler_audio)
+    /// go to this callback instead of the TTS audio callback, so they bypass
+    /// interruption timing and never count as synthesized speech. Without a
+    /// registered callback no filler plays.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call with each filler chunk
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_filler_audio<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(AudioData) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static,
+    {
+        self.filler.set_callback(Arc::new(callback));
+        Ok(())
+    }
+
+    /// Play `filler` whenever the first TTS audio of a reply is slow
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Error unless the filler matches the TTS output
+    ///   format: 16-bit PCM at the output sample rate, or any rate with the
+    ///   telephony profile
+    pub fn set_filler_audio(&self, filler: FillerAudio) -> VoiceManagerResult<()> {
+        if self.telephony.is_none() {
+            let format = self.config.tts_config.audio_format.as_deref();
+            if format.is_some_and(|format| !is_pcm16(format)) {
+                return Err(VoiceManagerError::InitializationError(format!(
+                    "filler audio requires 16-bit PCM TTS output, not {}",
+                    format.unwrap_or_default()
+                )));
+            }
+            let output_sample_rate = self.output_sample_rate().unwrap_or(24000);
+            if filler.sample_rate() != output_sample_rate {
+                return Err(VoiceManagerError::InitializationError(format!(
+                    "filler audio is {}Hz but the TTS output is {output_sample_rate}Hz",
+                    filler.sample_rate()
+                )));
+            }
+        }
+        self.filler.set_audio(filler);
+        Ok(())
+    }
+
+    /// Get how often filler audio played and was cross-faded
+    ///
+    /// # Returns
+    /// * `Option<FillerStats>` - Filler counters, or `None` without filler audio
+    pub fn filler_stats(&self) -> Option<FillerStats> {
+        self.filler.stats()
+    }
+
+    /// Start the filler now for a `speak` request still waiting for audio,
+    /// instead of after its threshold
+    ///
+    /// # Returns
+    /// * `bool` - Whether the filler started; `false` without filler audio,
+    ///   without a waiting request, or when it is already playing
+    pub fn play_filler_now(&self) -> bool {
+        self.filler.play_now()
+    }
+
+    /// Register a callback for the session falling back from the turn detection model
+    ///
+    /// The callback fires once per session, when an inference exceeds
+    /// `SpeechFinalConfig::turn_detection_latency_budget_ms` or times out.
+    /// A session started without the model it expected
+    /// (`VoiceManagerConfig::turn_detector_expected`) is already degraded, and
+    /// the callback is invoked right away.
+    ///
+    /// # Arguments
+    /// * `callback` - Async function to call with the reason for the fallback
+    ///
+    /// # Returns
+    /// * `VoiceManagerResult<()>` - Success or error
+    pub async fn on_turn_detection_degraded<F>(&self, callback: F) -> VoiceManagerResult<()>
+    where
+        F: Fn(TurnDetectionDegraded) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let callback: TurnDetectionDegradedCallback = Arc::new(callback);
+        self.turn_detection.set_callback(callback.clone());
+        if let Some(degraded) = self.turn_detection.initial_degradation() {
+            callback(degraded).await;
+        }
+        Ok(())
+    }
+
+    /// Get how the session decides end of turn and the model's inference counters
+    ///
+    /// # Returns
+    /// * `TurnDetectionStats` - Active mode, degradation reason and counters
+    pub fn turn_detection_stats(&self) -> TurnDetectionStats {
+        self.turn_detection.stats()
+    }
+
+    /// Get the pending TTS queue depth and cap counters
+    ///
+    /// # Returns
+    /// * `TTSQueueStats` - Pending utterances, the cap, and rejected/dropped counts
+    pub fn tts_queue_stats(&self) -> TTSQueueStats {
+        self.tts_queue.stats()
+    }
+
+    /// Get how much resent partial text was dropped or corrected
+    ///
+    /// # Returns
+    /// * `Option<TextDedupStats>` - Dedup counters, or `None` unless `dedupe_partials` is set
+    pub fn text_dedup_stats(&self) -> Option<TextDedupStats> {
+        self.text_dedup.as_ref().map(|dedup| dedup.stats())
+    }
+
+    /// Get the audio sent to the primary and secondary STT providers
+    ///
+    /// # Returns
+    /// * `Option<Arc<STTFailoverUsage>>` - Live counters, or `None` unless `stt_failover` is set
+    pub fn stt_failover_usage(&self) -> Option<Arc<STTFailoverUsage>> {
+        self.stt_failover_usage.clone()
+    }
+
+    /// Get the per-turn STT routing state
+    ///
+    /// # Returns
+    /// * `Option<Arc<TurnRouter>>` - Routing state, or `None` unless `stt_routing` is set
+    pub fn stt_router(&self) -> Option<Arc<TurnRouter>> {
+        self.stt_router.clone()
+    }
+
+    /// Route the next user turn by what the caller is expected to say
+    ///
+    /// Confirmation and digit turns go to the fast STT provider, freeform
+    /// turns to the configured one. A hint sent mid-utterance applies once
+    /// the utterance ends.
+    ///
+    /// # Returns
+    /// * `bool` - Whether per-turn STT routing is configured
+    pub fn expect_utterance(&self, kind: UtteranceKind) -> bool {
+        match &self.stt_router {
+            Some(router) => {
+                router.expect(kind);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Build the internal TTS callback from the registered user callbacks
+    ///
+    /// Call while holding the TTS lock so the callback matches the provider's voice.
+    fn tts_callback(&self) -> Arc<VoiceManagerTTSCallback> {
+        Arc::new(VoiceManagerTTSCallback {
+            audio_callback: self.tts_audio_callback.read().clone(),
+            error_callback: self.tts_error_callback.read().clone(),
+            interruption_state: Some(self.interruption_state.clone()),
+            complete_callback: self.tts_complete_callback.read().clone(),
+            timing_callback: self.tts_timing_callback.read().clone(),
+            telephony: self.telephony.clone(),
+            sample_rate_guard: self.sample_rate_guard.clone(),
+            on_fallback_voice: self
+                .voice_fallback
+                .as_ref()
+                .is_some_and(|fallback| fallback.is_pinned()),
+            voice_fallback: self.voice_fallback.clone(),
+            tts_queue: Some(self.tts_queue.clone()),
+            audio_quality: self.audio_quality.clone(),
+            filler: Some(self.filler.clone()),
+            preemption: Some(self.preemption.clone()),
+        })
+    }
+
     /// Get the current configuration
     ///
     /// # Returns
@@ -915,6 +1984,18 @@ impl VoiceManager {
         &self.config
     }
 
+    /// Get the sample rate of audio delivered to the TTS audio callback
+    ///
+    /// # Returns
+    /// * `Option<u32>` - 8000 for the telephony profile, otherwise the configured TTS sample rate
+    pub fn output_sample_rate(&self) -> Option<u32> {
+        if self.telephony.is_some() {
+            Some(TELEPHONY_SAMPLE_RATE)
+        } else {
+            self.config.tts_config.sample_rate
+        }
+    }
+
     /// Check if STT provider is ready
     ///
     /// # Returns
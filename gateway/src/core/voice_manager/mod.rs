@@ -11,9 +11,29 @@
 //! - **Real-time Processing**: Optimized for low-latency voice processing
 //! - **Speech Final Timing Control**: Multi-tier fallback mechanism for delayed `is_speech_final` signals:
 //!   - Primary: Wait for STT provider's `speech_final` signal (default: 2s)
-//!   - Secondary: ML-based turn detection as intelligent fallback (default: 100ms inference timeout)
+//!   - Secondary: ML-based turn detection as intelligent fallback (default: 100ms inference timeout),
+//!     or silence-based detection on the caller audio without a model or once an inference
+//!     exceeds `turn_detection_latency_budget_ms` (see [`turn_detection`])
 //!   - Tertiary: Hard timeout guarantee (default: 5s) - ensures no utterance waits indefinitely
 //!   - All timeouts are configurable through `SpeechFinalConfig`
+//! - **Adaptive Endpointing**: Optional per-turn end-of-turn silence threshold that shortens
+//!   for short utterances and fast speakers (`AdaptiveEndpointingConfig`)
+//! - **Voice Fallback**: Optional fallback voice used for the rest of the session when the
+//!   configured TTS voice does not exist (`VoiceManagerConfig::fallback_voice_id`)
+//! - **TTS Queue Limit**: Per-session cap on pending TTS utterances that either rejects new
+//!   text or drops the oldest utterance (`VoiceManagerConfig::tts_queue_limit`)
+//! - **Speak Priorities**: `high` speech bypasses the TTS queue cap and `system` announcements
+//!   pre-empt the current utterance without barge-in, pausing and then resuming the queue
+//!   (`VoiceManager::speak_with_priority`)
+//! - **Partial Deduplication**: Optional stripping of partial text that LLM orchestrators
+//!   resend on every token (`VoiceManagerConfig::dedupe_partials`)
+//! - **TTS Audio Quality Check**: Per-utterance detection of clipped, silent or inaudible PCM
+//!   audio from the provider, with an optional single retry (`VoiceManagerConfig::tts_audio_quality`)
+//! - **Filler Audio**: Optional looped asset played while the first TTS audio of a reply is
+//!   slow, cross-faded out when the real audio arrives (`VoiceManager::set_filler_audio`)
+//! - **Sample Rate Correction**: TTS audio the provider produced at another rate than requested
+//!   is relabelled from its WAV/MP3 header, and raw PCM resampled to the requested rate
+//!   (`SampleRateGuard`)
 //! - **Error Handling**: Comprehensive error handling with proper error propagation
 //! - **Callback System**: Event-driven architecture for handling results
 //! - **Thread Safety**: Safe concurrent access using Arc<RwLock<>>
@@ -39,6 +59,8 @@
 //!         punctuation: true,
 //!         encoding: "linear16".to_string(),
 //!         model: "nova-3".to_string(),
+//!         custom_headers: Default::default(),
+//!         certificate_path: None,
 //!     };
 //!     let tts_config = TTSConfig {
 //!         provider: "deepgram".to_string(),
@@ -145,6 +167,7 @@
 //!     let speech_final_config = SpeechFinalConfig {
 //!         stt_speech_final_wait_ms: 3000,             // Wait 3s for STT provider
 //!         turn_detection_inference_timeout_ms: 150,    // 150ms for turn detector
+//!         turn_detection_latency_budget_ms: 100,       // Silence detection after a slower inference
 //!         speech_final_hard_timeout_ms: 8000,          // 8s hard upper bound
 //!         duplicate_window_ms: 1000,                   // 1s duplicate prevention
 //!     };
@@ -289,20 +312,46 @@
 //! }
 //! ```
 
+pub mod audio_quality;
 pub mod callbacks;
 pub mod config;
+pub mod endpointing;
 pub mod errors;
+pub mod filler;
 pub mod manager;
+pub mod preemption;
+pub mod sample_rate;
 pub mod state;
 pub mod stt_result;
+pub mod text_dedup;
+pub mod tts_queue;
+pub mod turn_detection;
+pub mod voice_fallback;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export commonly used items
+pub use audio_quality::{AudioQualityIssue, TTSAudioQualityConfig, TTSAudioQualityWarning};
 pub use callbacks::{
-    AudioClearCallback, STTCallback, STTErrorCallback, TTSAudioCallback, TTSErrorCallback,
+    AudioClearCallback, PreemptionCallback, STTCallback, STTErrorCallback, TTSAudioCallback,
+    TTSAudioQualityCallback, TTSErrorCallback, TTSQueueFullCallback, TTSTextCallback,
+    TTSTimingCallback, TTSVoiceFallbackCallback, TurnDetectionDegradedCallback,
 };
-pub use config::{SpeechFinalConfig, VoiceManagerConfig};
+pub use config::{DEFAULT_PROVIDER_CONNECT_TIMEOUT, SpeechFinalConfig, VoiceManagerConfig};
+pub use endpointing::{AdaptiveEndpointing, AdaptiveEndpointingConfig};
 pub use errors::{VoiceManagerError, VoiceManagerResult};
+pub use filler::{
+    DEFAULT_FILLER_FADE_OUT_MS, DEFAULT_FILLER_MAX_DURATION_MS, DEFAULT_FILLER_THRESHOLD_MS,
+    FillerAudio, FillerAudioConfig, FillerStats,
+};
 pub use manager::VoiceManager;
+pub use preemption::{DEFAULT_SYSTEM_SPEAK_MAX_CHARS, PreemptionEvent, SpeakPriority};
+pub use sample_rate::SampleRateGuard;
+pub use text_dedup::{PartialTextDedup, TextDedupStats};
+pub use tts_queue::{
+    DEFAULT_MAX_PENDING_UTTERANCES, TTSQueueFull, TTSQueueLimit, TTSQueuePolicy, TTSQueueStats,
+};
+pub use turn_detection::{
+    TurnDetectionDegraded, TurnDetectionDegradedReason, TurnDetectionMonitor, TurnDetectionStats,
+};
@@ -0,0 +1,471 @@
+//! Speak priorities and pre-emption by system announcements
+//!
+//! Compliance announcements ("this call is recorded") have to play even when
+//! the bot is mid-sentence. A `system` priority speak stops the current
+//! utterance, pauses everything that was still queued on the TTS provider,
+//! speaks the announcement without barge-in and then resumes the paused
+//! utterances in their original order. Speech requested while an
+//! announcement is playing is held and resumed after the paused utterances.
+//!
+//! Providers cannot resume mid-utterance, so the utterance that was playing
+//! when the announcement started is spoken again from its beginning.
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use super::callbacks::PreemptionCallback;
+use super::tts_queue::{TTSQueueLimit, TTSQueuePolicy};
+
+/// Default cap on the length of a `system` priority speak, in characters
+pub const DEFAULT_SYSTEM_SPEAK_MAX_CHARS: usize = 500;
+
+/// Priority of a `speak` request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SpeakPriority {
+    /// Queued behind earlier speech and subject to the pending utterance cap
+    #[default]
+    Normal,
+    /// Queued behind earlier speech but exempt from the pending utterance cap;
+    /// never dropped by the `drop_oldest` policy
+    High,
+    /// Pre-empts the current utterance, pauses the queue until it has played
+    /// and cannot be interrupted by barge-in
+    System,
+}
+
+impl SpeakPriority {
+    /// The priority's wire name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::System => "system",
+        }
+    }
+}
+
+impl fmt::Display for SpeakPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SpeakPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "normal" => Ok(Self::Normal),
+            "high" => Ok(Self::High),
+            "system" => Ok(Self::System),
+            other => Err(format!(
+                "Invalid speak priority '{other}': expected 'normal', 'high' or 'system'"
+            )),
+        }
+    }
+}
+
+/// Start or end of a system announcement pre-emption
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreemptionEvent {
+    /// A `system` speak stopped the current utterance and paused the queue
+    Started {
+        /// Text of the announcement
+        text: String,
+        /// Utterances paused until the announcement has played
+        paused: usize,
+    },
+    /// The announcement has played and the paused utterances were re-sent
+    Ended {
+        /// Utterances re-sent to the TTS provider
+        resumed: usize,
+    },
+}
+
+/// An utterance waiting for an announcement to finish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct PausedUtterance {
+    pub text: String,
+    pub flush: bool,
+    /// `None` keeps the interruption setting from before the announcement
+    pub allow_interruption: Option<bool>,
+    pub priority: SpeakPriority,
+}
+
+/// Outcome of offering an utterance to the pre-emption state
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Hold {
+    /// No announcement is in progress; send the utterance as usual
+    NotActive,
+    /// The utterance is held until the announcement has played
+    Held,
+    /// Too many normal utterances are held; the utterance was not held
+    Rejected,
+    /// The oldest held normal utterance was dropped to make room
+    DroppedOldest(String),
+}
+
+/// Next step of the task resuming the paused utterances
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Resume {
+    /// Re-send this utterance
+    Next(PausedUtterance),
+    /// Another announcement started; wait for it to play first
+    Wait,
+    /// Nothing is left to resume; the pre-emption is over
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Phase {
+    #[default]
+    Idle,
+    /// Announcements are queued or playing; other speech is held
+    Announcing,
+    /// Announcements have played; paused utterances are being re-sent
+    Resuming,
+}
+
+#[derive(Default)]
+struct PreemptionState {
+    phase: Phase,
+    /// Announcements sent since the provider last reported completion
+    outstanding: usize,
+    /// Whether speech was interruptible before the first announcement
+    allow_interruption: bool,
+    paused: VecDeque<PausedUtterance>,
+    resume_task: Option<JoinHandle<()>>,
+}
+
+/// Tracks system announcements and the utterances they paused
+#[derive(Default)]
+pub struct Preemption {
+    state: Mutex<PreemptionState>,
+    generated: Notify,
+    callback: SyncRwLock<Option<PreemptionCallback>>,
+}
+
+impl Preemption {
+    /// Create an idle pre-emption state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether an announcement is queued or still playing
+    pub fn is_announcing(&self) -> bool {
+        self.state.lock().phase == Phase::Announcing
+    }
+
+    /// Whether an announcement is in progress or paused speech is being resumed
+    pub fn is_active(&self) -> bool {
+        self.state.lock().phase != Phase::Idle
+    }
+
+    /// Number of utterances waiting for the announcement to finish
+    pub fn paused(&self) -> usize {
+        self.state.lock().paused.len()
+    }
+
+    /// Start an announcement; speech requested from now on is held
+    ///
+    /// `allow_interruption` is the setting of the speech being pre-empted,
+    /// restored for paused speech that did not choose its own.
+    ///
+    /// # Returns
+    /// * `bool` - False if an earlier announcement's pre-emption is still active
+    pub(super) fn begin(&self, allow_interruption: bool) -> bool {
+        let mut state = self.state.lock();
+        let started = state.phase == Phase::Idle;
+        if started {
+            state.allow_interruption = allow_interruption;
+        }
+        state.phase = Phase::Announcing;
+        state.outstanding += 1;
+        started
+    }
+
+    /// Interruption setting of the speech the pre-emption paused
+    pub(super) fn allow_interruption(&self) -> bool {
+        self.state.lock().allow_interruption
+    }
+
+    /// Pause the utterances the announcement interrupted ahead of those held
+    ///
+    /// # Returns
+    /// * `usize` - Number of utterances now paused
+    pub(super) fn pause(&self, interrupted: Vec<PausedUtterance>) -> usize {
+        let mut state = self.state.lock();
+        for utterance in interrupted.into_iter().rev() {
+            state.paused.push_front(utterance);
+        }
+        state.paused.len()
+    }
+
+    /// Keep the task that resumes the paused utterances, aborting on reset
+    pub(super) fn set_resume_task(&self, task: JoinHandle<()>) {
+        if let Some(previous) = self.state.lock().resume_task.replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// Hold an utterance requested while a pre-emption is active
+    ///
+    /// Normal utterances are capped at the queue's `max_pending`; the policy
+    /// decides whether the new or the oldest held one is discarded.
+    pub(super) fn hold(&self, utterance: PausedUtterance, limit: TTSQueueLimit) -> Hold {
+        let mut state = self.state.lock();
+        if state.phase == Phase::Idle {
+            return Hold::NotActive;
+        }
+        if utterance.priority != SpeakPriority::Normal {
+            state.paused.push_back(utterance);
+            return Hold::Held;
+        }
+
+        let normal = state
+            .paused
+            .iter()
+            .filter(|paused| paused.priority == SpeakPriority::Normal)
+            .count();
+        if normal < limit.max_pending.max(1) {
+            state.paused.push_back(utterance);
+            return Hold::Held;
+        }
+        match limit.policy {
+            TTSQueuePolicy::Reject => Hold::Rejected,
+            TTSQueuePolicy::DropOldest => {
+                let Some(oldest) = state
+                    .paused
+                    .iter()
+                    .position(|paused| paused.priority == SpeakPriority::Normal)
+                else {
+                    return Hold::Rejected;
+                };
+                let dropped = state.paused.remove(oldest).map(|paused| paused.text);
+                state.paused.push_back(utterance);
+                Hold::DroppedOldest(dropped.unwrap_or_default())
+            }
+        }
+    }
+
+    /// The TTS provider finished all queued text, including the announcements
+    pub(super) fn complete(&self) {
+        let mut state = self.state.lock();
+        if state.phase == Phase::Announcing && state.outstanding > 0 {
+            state.outstanding = 0;
+            drop(state);
+            self.generated.notify_one();
+        }
+    }
+
+    /// Wait until the provider has generated every announcement sent so far
+    pub(super) async fn wait_generated(&self) {
+        loop {
+            let notified = self.generated.notified();
+            if self.state.lock().outstanding == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Switch to resuming once no announcement is waiting on the provider
+    ///
+    /// # Returns
+    /// * `bool` - False if another announcement was sent in the meantime
+    pub(super) fn start_resume(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.outstanding > 0 {
+            return false;
+        }
+        state.phase = Phase::Resuming;
+        true
+    }
+
+    /// Take the next paused utterance to re-send
+    pub(super) fn next_paused(&self) -> Resume {
+        let mut state = self.state.lock();
+        if state.phase == Phase::Announcing {
+            return Resume::Wait;
+        }
+        match state.paused.pop_front() {
+            Some(utterance) => Resume::Next(utterance),
+            None => {
+                state.phase = Phase::Idle;
+                state.resume_task = None;
+                Resume::Done
+            }
+        }
+    }
+
+    /// Drop the held utterances after barge-in cleared the resumed speech
+    pub(super) fn discard_paused(&self) {
+        let mut state = self.state.lock();
+        if state.phase == Phase::Resuming {
+            state.paused.clear();
+        }
+    }
+
+    /// Abort any pending resume and forget all paused speech
+    pub(super) fn reset(&self) {
+        let mut state = self.state.lock();
+        if let Some(task) = state.resume_task.take() {
+            task.abort();
+        }
+        *state = PreemptionState::default();
+    }
+
+    /// Register the callback fired when a pre-emption starts or ends
+    pub fn set_callback(&self, callback: PreemptionCallback) {
+        *self.callback.write() = Some(callback);
+    }
+
+    /// Report the start or end of a pre-emption
+    pub(super) async fn notify(&self, event: PreemptionEvent) {
+        let callback = self.callback.read().clone();
+        if let Some(callback) = callback {
+            callback(event).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(text: &str, priority: SpeakPriority) -> PausedUtterance {
+        PausedUtterance {
+            text: text.to_string(),
+            flush: true,
+            allow_interruption: None,
+            priority,
+        }
+    }
+
+    fn limit(max_pending: usize, policy: TTSQueuePolicy) -> TTSQueueLimit {
+        TTSQueueLimit {
+            max_pending,
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_hold_only_while_active() {
+        let preemption = Preemption::new();
+        let reject = limit(2, TTSQueuePolicy::Reject);
+        assert_eq!(
+            preemption.hold(utterance("one", SpeakPriority::Normal), reject),
+            Hold::NotActive
+        );
+
+        assert!(preemption.begin(true));
+        assert_eq!(
+            preemption.pause(vec![utterance("interrupted", SpeakPriority::Normal)]),
+            1
+        );
+        assert!(preemption.is_announcing());
+        assert_eq!(
+            preemption.hold(utterance("two", SpeakPriority::Normal), reject),
+            Hold::Held
+        );
+        assert_eq!(
+            preemption.hold(utterance("three", SpeakPriority::Normal), reject),
+            Hold::Rejected
+        );
+        assert_eq!(
+            preemption.hold(utterance("urgent", SpeakPriority::High), reject),
+            Hold::Held
+        );
+        assert_eq!(preemption.paused(), 3);
+    }
+
+    #[test]
+    fn test_hold_drop_oldest_keeps_high() {
+        let preemption = Preemption::new();
+        preemption.begin(true);
+        preemption.pause(vec![
+            utterance("urgent", SpeakPriority::High),
+            utterance("one", SpeakPriority::Normal),
+        ]);
+        assert_eq!(
+            preemption.hold(
+                utterance("two", SpeakPriority::Normal),
+                limit(1, TTSQueuePolicy::DropOldest)
+            ),
+            Hold::DroppedOldest("one".to_string())
+        );
+        assert_eq!(
+            preemption.next_paused(),
+            Resume::Wait,
+            "nothing resumes while announcing"
+        );
+    }
+
+    #[test]
+    fn test_resume_order_and_nested_announcement() {
+        let preemption = Preemption::new();
+        let reject = limit(5, TTSQueuePolicy::Reject);
+        assert!(preemption.begin(false));
+        preemption.pause(vec![utterance("one", SpeakPriority::Normal)]);
+        preemption.hold(utterance("two", SpeakPriority::Normal), reject);
+        // A second announcement extends the running pre-emption
+        assert!(!preemption.begin(true));
+        assert!(!preemption.allow_interruption());
+        assert_eq!(
+            preemption.pause(vec![utterance("zero", SpeakPriority::Normal)]),
+            3
+        );
+
+        assert!(!preemption.start_resume());
+        preemption.complete();
+        assert!(preemption.start_resume());
+        assert!(!preemption.is_announcing());
+        assert!(preemption.is_active());
+
+        let mut resumed = Vec::new();
+        while let Resume::Next(utterance) = preemption.next_paused() {
+            resumed.push(utterance.text);
+        }
+        assert_eq!(resumed, vec!["zero", "one", "two"]);
+        assert!(!preemption.is_active());
+    }
+
+    #[test]
+    fn test_discard_paused_only_after_announcement() {
+        let preemption = Preemption::new();
+        preemption.begin(true);
+        preemption.pause(vec![utterance("one", SpeakPriority::Normal)]);
+        preemption.discard_paused();
+        assert_eq!(preemption.paused(), 1);
+
+        preemption.complete();
+        preemption.start_resume();
+        preemption.discard_paused();
+        assert_eq!(preemption.next_paused(), Resume::Done);
+
+        preemption.begin(true);
+        preemption.reset();
+        assert!(!preemption.is_active());
+    }
+
+    #[test]
+    fn test_priority_parsing() {
+        assert_eq!(
+            "System".parse::<SpeakPriority>().unwrap(),
+            SpeakPriority::System
+        );
+        assert!("urgent".parse::<SpeakPriority>().is_err());
+        assert_eq!(
+            serde_json::to_string(&SpeakPriority::High).unwrap(),
+            "\"high\""
+        );
+        assert_eq!(SpeakPriority::default(), SpeakPriority::Normal);
+    }
+}
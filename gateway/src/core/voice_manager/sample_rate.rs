@@ -0,0 +1,200 @@
+//! Correction of TTS audio produced at another sample rate than requested
+//!
+//! Providers occasionally synthesize at a different rate than the one asked
+//! for (Azure returns 16kHz audio for some voices and regions when 24kHz was
+//! requested), and audio played at the wrong rate sounds too fast or too slow.
+//! [`SampleRateGuard`] takes the actual rate from WAV and MP3 headers, or the
+//! rate the provider reported for raw PCM, and fixes each chunk's
+//! `sample_rate` and duration. Raw PCM is also resampled to the requested
+//! rate, since nothing downstream of it (WebSocket binary frames, the LiveKit
+//! track) carries a rate; container audio keeps its own, which clients read
+//! from the header.
+
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+use crate::core::session::container_sample_rate;
+use crate::core::tts::{AudioData, telephony::Resampler};
+
+#[derive(Default)]
+struct GuardState {
+    /// Rate from the last WAV or MP3 header, for chunks without one
+    container_rate: Option<u32>,
+    /// Resampler for raw PCM, with the rate it converts from and the chunks' format
+    resampler: Option<(u32, String, Resampler)>,
+    /// Odd byte left over from a PCM chunk split mid-sample
+    pcm_carry: Option<u8>,
+}
+
+/// Corrects TTS audio whose sample rate differs from the requested one
+pub struct SampleRateGuard {
+    provider: String,
+    /// Sample rate requested from the provider
+    requested: u32,
+    /// Set once the mismatch has been logged for the session
+    warned: AtomicBool,
+    state: Mutex<GuardState>,
+}
+
+impl SampleRateGuard {
+    /// Create a guard for audio requested from `provider` at `requested` Hz
+    pub fn new(provider: impl Into<String>, requested: u32) -> Self {
+        Self {
+            provider: provider.into(),
+            requested,
+            warned: AtomicBool::new(false),
+            state: Mutex::new(GuardState::default()),
+        }
+    }
+
+    /// The chunk labelled with its actual sample rate, and raw PCM resampled
+    /// to the requested rate
+    pub fn correct(&self, mut audio: AudioData) -> AudioData {
+        let format = audio.format.to_ascii_lowercase();
+        let mut state = self.state.lock();
+
+        let actual = match format.as_str() {
+            "wav" | "mp3" => {
+                if let Some(rate) = container_sample_rate(&format, &audio.data) {
+                    state.container_rate = Some(rate);
+                }
+                state.container_rate.unwrap_or(audio.sample_rate)
+            }
+            _ => audio.sample_rate,
+        };
+        if actual == 0 || self.requested == 0 {
+            return audio;
+        }
+        if actual != audio.sample_rate {
+            audio.duration_ms = audio
+                .duration_ms
+                .map(|ms| (ms as u64 * audio.sample_rate as u64 / actual as u64) as u32);
+            audio.sample_rate = actual;
+        }
+        if actual == self.requested {
+            return audio;
+        }
+
+        if !self.warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                provider = %self.provider,
+                format = %format,
+                requested_sample_rate = self.requested,
+                actual_sample_rate = actual,
+                "TTS provider returned audio at a different sample rate than requested"
+            );
+        }
+        if matches!(format.as_str(), "linear16" | "pcm") {
+            audio = self.resample(&mut state, audio);
+        }
+        audio
+    }
+
+    /// Resample a raw PCM chunk to the requested rate
+    fn resample(&self, state: &mut GuardState, audio: AudioData) -> AudioData {
+        let mut bytes = Vec::with_capacity(audio.data.len() + 1);
+        bytes.extend(state.pcm_carry.take());
+        bytes.extend_from_slice(&audio.data);
+        if bytes.len() % 2 == 1 {
+            state.pcm_carry = bytes.pop();
+        }
+        let samples: Vec<i16> = bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+
+        if state
+            .resampler
+            .as_ref()
+            .is_none_or(|(rate, _, _)| *rate != audio.sample_rate)
+        {
+            state.resampler = Some((
+                audio.sample_rate,
+                audio.format.clone(),
+                Resampler::new(audio.sample_rate, self.requested),
+            ));
+        }
+        let mut resampled = Vec::with_capacity(
+            samples.len() * self.requested as usize / audio.sample_rate as usize + 1,
+        );
+        if let Some((_, _, resampler)) = state.resampler.as_mut() {
+            resampler.process(&samples, &mut resampled);
+        }
+        self.pcm_chunk(&resampled, audio.format)
+    }
+
+    /// Resampled audio held back for interpolation, at the end of an utterance
+    pub fn flush(&self) -> Option<AudioData> {
+        let mut state = self.state.lock();
+        state.pcm_carry = None;
+        let (_, format, mut resampler) = state.resampler.take()?;
+        let mut tail = Vec::new();
+        resampler.flush(&mut tail);
+        (!tail.is_empty()).then(|| self.pcm_chunk(&tail, format))
+    }
+
+    /// Drop partial state from an interrupted utterance
+    pub fn reset(&self) {
+        let mut state = self.state.lock();
+        state.resampler = None;
+        state.pcm_carry = None;
+    }
+
+    fn pcm_chunk(&self, samples: &[i16], format: String) -> AudioData {
+        AudioData {
+            data: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+            sample_rate: self.requested,
+            format,
+            duration_ms: Some((samples.len() as u64 * 1000 / self.requested as u64) as u32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm(samples: usize, sample_rate: u32) -> AudioData {
+        AudioData {
+            data: vec![0x10; samples * 2],
+            sample_rate,
+            format: "linear16".to_string(),
+            duration_ms: Some((samples as u64 * 1000 / sample_rate as u64) as u32),
+        }
+    }
+
+    #[test]
+    fn test_matching_audio_is_untouched() {
+        let guard = SampleRateGuard::new("azure", 24000);
+        let audio = guard.correct(pcm(2400, 24000));
+        assert_eq!(audio.data.len(), 4800);
+        assert_eq!(audio.sample_rate, 24000);
+        assert!(guard.flush().is_none());
+    }
+
+    #[test]
+    fn test_pcm_at_other_rate_is_resampled() {
+        let guard = SampleRateGuard::new("openai", 16000);
+        let audio = guard.correct(pcm(2400, 24000));
+        assert_eq!(audio.sample_rate, 16000);
+        assert_eq!(audio.format, "linear16");
+        let tail = guard.flush().map_or(0, |tail| tail.data.len() / 2);
+        assert_eq!(audio.data.len() / 2 + tail, 1600);
+        assert!(guard.warned.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_odd_byte_carries_to_next_chunk() {
+        let guard = SampleRateGuard::new("openai", 8000);
+        let mut first = pcm(100, 16000);
+        first.data.push(0x10);
+        let first = guard.correct(first);
+        let second = guard.correct(AudioData {
+            data: vec![0x10; 199],
+            ..pcm(0, 16000)
+        });
+        let tail = guard.flush().map_or(0, |tail| tail.data.len() / 2);
+        assert_eq!((first.data.len() + second.data.len()) / 2 + tail, 100);
+    }
+}
@@ -66,6 +66,36 @@ impl InterruptionState {
         false
     }
 
+    /// Set up interruption control for a new utterance
+    ///
+    /// Non-interruptible audio is blocked from now until the duration of its
+    /// chunks has played; `sample_rate` is the rate those chunks arrive at.
+    pub fn begin_utterance(&self, allow_interruption: bool, sample_rate: Option<u32>) {
+        self.allow_interruption
+            .store(allow_interruption, Ordering::Release);
+
+        if !allow_interruption {
+            if let Some(sample_rate) = sample_rate {
+                self.current_sample_rate
+                    .store(sample_rate, Ordering::Release);
+            }
+
+            // The actual duration will be calculated as TTS chunks arrive
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as usize;
+            self.non_interruptible_until_ms
+                .store(now, Ordering::Release);
+
+            // Mark as not completed since new audio is starting
+            self.is_completed.store(false, Ordering::SeqCst);
+        } else {
+            // For interruptible audio, just reset to defaults
+            self.reset();
+        }
+    }
+
     /// Reset interruption state to defaults
     pub fn reset(&self) {
         self.allow_interruption.store(true, Ordering::Release);
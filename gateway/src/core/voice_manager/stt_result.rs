@@ -5,12 +5,13 @@ use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant};
 use tracing::{debug, info};
 
 use crate::core::{stt::STTResult, turn_detect::TurnDetector};
 
 use super::state::SpeechFinalState;
+use super::turn_detection::{TurnDecision, TurnDetectionMonitor};
 
 /// Configuration for STT result processing
 #[derive(Clone, Copy)]
@@ -60,11 +61,23 @@ impl STTProcessingConfig {
 #[derive(Clone)]
 pub struct STTResultProcessor {
     config: STTProcessingConfig,
+    monitor: Arc<TurnDetectionMonitor>,
 }
 
 impl STTResultProcessor {
+    /// Create a processor that treats the caller's audio as always silent
     pub fn new(config: STTProcessingConfig) -> Self {
-        Self { config }
+        let budget = Duration::from_millis(config.turn_detection_inference_timeout_ms);
+        Self {
+            config,
+            monitor: Arc::new(TurnDetectionMonitor::new(true, false, budget, "")),
+        }
+    }
+
+    /// Use `monitor` for the turn detection mode, metrics and caller silence
+    pub fn with_monitor(mut self, monitor: Arc<TurnDetectionMonitor>) -> Self {
+        self.monitor = monitor;
+        self
     }
 
     /// Process an STT result with timing control
@@ -267,6 +280,13 @@ impl STTResultProcessor {
                 is_final: true,
                 is_speech_final: false,
                 confidence: 1.0,
+                redactions: Vec::new(),
+                start: None,
+                end: None,
+                words: Vec::new(),
+                timing: None,
+                turn_id: None,
+                detected_language: None,
             };
 
             Self::fire_speech_final(
@@ -283,7 +303,8 @@ impl STTResultProcessor {
     ///
     /// Voice AI Best Practice Logic:
     /// 1. Wait for STT provider to send real speech_final (they see the audio stream)
-    /// 2. If STT is silent and text hasn't changed, run turn detection to confirm
+    /// 2. If STT is silent and text hasn't changed, run turn detection to confirm,
+    ///    or without a usable model wait for the caller's audio to go silent
     /// 3. Only fire artificial speech_final if turn detection confirms turn is complete
     fn create_detection_task(
         &self,
@@ -294,6 +315,7 @@ impl STTResultProcessor {
     ) -> JoinHandle<()> {
         let stt_wait_ms = self.config.stt_speech_final_wait_ms;
         let inference_timeout_ms = self.config.turn_detection_inference_timeout_ms;
+        let monitor = self.monitor.clone();
 
         tokio::spawn(async move {
             // PHASE 1: Wait for STT provider to send real speech_final
@@ -316,7 +338,8 @@ impl STTResultProcessor {
             }
 
             // PHASE 2: STT didn't send speech_final - verify with turn detection
-            let detection_method = if let Some(detector) = turn_detector {
+            let detector = turn_detector.filter(|_| monitor.uses_model());
+            let detection_method = if let Some(detector) = detector {
                 // Check if text buffer has changed (new transcripts arrived)
                 let current_text = {
                     let state = speech_final_state.read();
@@ -337,12 +360,25 @@ impl STTResultProcessor {
                     "STT silent for {}ms, running turn detection to confirm",
                     stt_wait_ms
                 );
+                let started = Instant::now();
                 let turn_result =
                     tokio::time::timeout(Duration::from_millis(inference_timeout_ms), async {
                         let detector_guard = detector.read().await;
                         detector_guard.is_turn_complete(&current_text).await
                     })
                     .await;
+                let decision = match &turn_result {
+                    Ok(Ok(true)) => TurnDecision::Complete,
+                    Ok(Ok(false)) => TurnDecision::Incomplete,
+                    Ok(Err(_)) => TurnDecision::Error,
+                    Err(_) => TurnDecision::Timeout,
+                };
+                if let Some(degraded) = monitor.record_inference(started.elapsed(), decision) {
+                    // Notify from its own task so a newer transcript cancelling
+                    // this one can't drop the event
+                    let monitor = monitor.clone();
+                    tokio::spawn(async move { monitor.notify(degraded).await });
+                }
 
                 match turn_result {
                     Ok(Ok(true)) => {
@@ -374,9 +410,32 @@ impl STTResultProcessor {
                     }
                 }
             } else {
-                // No turn detector - fire based on silence duration alone
-                info!("No turn detector - firing after {}ms silence", stt_wait_ms);
-                "no_detector_timeout"
+                // No usable turn detector - fire once the caller's audio has gone quiet.
+                // The hard timeout still fires if the caller never does.
+                loop {
+                    let remaining = monitor.silence_remaining();
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    debug!(
+                        "Caller audio not silent yet - checking again in {:?}",
+                        remaining
+                    );
+                    tokio::time::sleep(remaining).await;
+                    let waiting = speech_final_state
+                        .read()
+                        .waiting_for_speech_final
+                        .load(Ordering::Acquire);
+                    if !waiting {
+                        return;
+                    }
+                }
+                monitor.record_silence_turn();
+                info!(
+                    "No turn detector - firing after {}ms without transcripts and silent audio",
+                    stt_wait_ms
+                );
+                "silence_vad"
             };
 
             // PHASE 3: Fire artificial speech_final
@@ -386,7 +445,7 @@ impl STTResultProcessor {
     }
 
     /// Fire a forced speech_final event
-    async fn fire_speech_final(
+    pub(super) async fn fire_speech_final(
         _result: STTResult,
         buffered_text: String,
         speech_final_state: Arc<SyncRwLock<SpeechFinalState>>,
@@ -432,6 +491,13 @@ impl STTResultProcessor {
                 is_final: true,
                 is_speech_final: true,
                 confidence: 1.0,
+                redactions: Vec::new(),
+                start: None,
+                end: None,
+                words: Vec::new(),
+                timing: None,
+                turn_id: None,
+                detected_language: None,
             };
 
             info!("Forcing speech_final via {}", detection_method);
@@ -562,6 +628,13 @@ mod tests {
             is_final: true,
             is_speech_final: false,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         // Process the result - should trigger turn detection and hard timeout
@@ -630,6 +703,13 @@ mod tests {
             is_final: true,
             is_speech_final: false,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor
@@ -645,6 +725,13 @@ mod tests {
             is_final: true,
             is_speech_final: true,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor
@@ -704,6 +791,13 @@ mod tests {
             is_final: true,
             is_speech_final: false,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor.process_result(result1, state.clone(), None).await;
@@ -717,6 +811,13 @@ mod tests {
             is_final: true,
             is_speech_final: false,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor.process_result(result2, state.clone(), None).await;
@@ -768,6 +869,13 @@ mod tests {
             is_final: true,
             is_speech_final: false,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor.process_result(result, state.clone(), None).await;
@@ -785,6 +893,13 @@ mod tests {
             is_final: true,
             is_speech_final: true,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor
@@ -804,6 +919,13 @@ mod tests {
             is_final: true,
             is_speech_final: false,
             confidence: 0.95,
+            redactions: Vec::new(),
+            start: None,
+            end: None,
+            words: Vec::new(),
+            timing: None,
+            turn_id: None,
+            detected_language: None,
         };
 
         processor.process_result(result2, state.clone(), None).await;
@@ -944,3 +944,69 @@ async fn test_hard_timeout_observability() {
     // The implementation emits: tracing::warn!("Hard timeout fired after {}ms - forcing speech_final...")
     // This allows SREs to create alerts on fallback frequency.
 }
+
+#[tokio::test]
+async fn test_play_audio_delivers_through_tts_callbacks() {
+    let config = VoiceManagerConfig::new(
+        STTConfig {
+            provider: "deepgram".to_string(),
+            api_key: "test_key".to_string(),
+            ..Default::default()
+        },
+        TTSConfig {
+            provider: "deepgram".to_string(),
+            api_key: "test_key".to_string(),
+            ..Default::default()
+        },
+    );
+    let voice_manager = VoiceManager::new(config, None).unwrap();
+
+    // Without an audio callback there is nowhere to send the audio
+    assert!(
+        voice_manager
+            .play_audio(vec![0u8; 100], 16000, true)
+            .await
+            .is_err()
+    );
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let chunks = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicBool::new(false));
+
+    let received_clone = received.clone();
+    let chunks_clone = chunks.clone();
+    voice_manager
+        .on_tts_audio(move |audio| {
+            let received = received_clone.clone();
+            let chunks = chunks_clone.clone();
+            Box::pin(async move {
+                assert_eq!(audio.sample_rate, 16000);
+                received.fetch_add(audio.data.len(), Ordering::SeqCst);
+                chunks.fetch_add(1, Ordering::SeqCst);
+            })
+        })
+        .await
+        .unwrap();
+
+    let completed_clone = completed.clone();
+    voice_manager
+        .on_tts_complete(move || {
+            let completed = completed_clone.clone();
+            Box::pin(async move {
+                completed.store(true, Ordering::SeqCst);
+            })
+        })
+        .await
+        .unwrap();
+
+    voice_manager
+        .play_audio(vec![0u8; 8000], 16000, false)
+        .await
+        .unwrap();
+
+    assert_eq!(received.load(Ordering::SeqCst), 8000);
+    assert_eq!(chunks.load(Ordering::SeqCst), 3);
+    assert!(completed.load(Ordering::SeqCst));
+    // 250ms of non-interruptible audio was just queued
+    assert!(voice_manager.is_interruption_blocked().await);
+}
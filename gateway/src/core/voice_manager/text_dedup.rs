@@ -0,0 +1,211 @@
+//! Deduplication of partial text that LLM orchestrators resend
+//!
+//! Some orchestrators send the whole partial sentence on every token
+//! ("Hel", "Hell", "Hello") instead of only the new characters. Passed
+//! straight to `speak(flush=false)`, every partial is queued on the provider
+//! and the audio stutters. [`PartialTextDedup`] remembers the text sent for
+//! the current utterance and turns each partial into what is actually new:
+//!
+//! - A partial that extends the sent text only sends the new suffix
+//! - A partial that shares at least one whole word with the sent text but
+//!   then diverges is a revision; the pending utterance is replaced
+//! - A partial with no word in common starts a new segment and is sent as-is
+//!
+//! The utterance ends with `flush=true` or when TTS is cleared.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Counters of deduplicated and corrected text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TextDedupStats {
+    /// Characters not sent again because an earlier partial already had them
+    pub deduplicated_chars: u64,
+    /// Characters of already-sent text that a later partial revised
+    pub corrected_chars: u64,
+    /// Partials that revised earlier text and replaced the pending utterance
+    pub replacements: u64,
+}
+
+/// What to send to the provider for a partial
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Deduplicated {
+    /// Send this text
+    Append(String),
+    /// Nothing new to send
+    Skip,
+    /// Discard the pending utterance and send this text in full
+    Replace(String),
+}
+
+#[derive(Default)]
+struct DedupState {
+    sent: String,
+    stats: TextDedupStats,
+}
+
+/// Tracks the text of the current utterance to strip resent prefixes
+#[derive(Default)]
+pub struct PartialTextDedup {
+    state: Mutex<DedupState>,
+}
+
+impl PartialTextDedup {
+    /// Create a deduplicator with no text sent yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Work out what to send for `text`; `flush` ends the utterance
+    pub(super) fn process(&self, text: &str, flush: bool) -> Deduplicated {
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+
+        let outcome = if let Some(suffix) = text.strip_prefix(state.sent.as_str()) {
+            state.stats.deduplicated_chars += state.sent.chars().count() as u64;
+            if suffix.is_empty() {
+                Deduplicated::Skip
+            } else {
+                Deduplicated::Append(suffix.to_string())
+            }
+        } else {
+            let common = common_prefix_len(&state.sent, text);
+            if text[..common].contains(char::is_whitespace) {
+                state.stats.corrected_chars += state.sent[common..].chars().count() as u64;
+                state.stats.replacements += 1;
+                Deduplicated::Replace(text.to_string())
+            } else {
+                Deduplicated::Append(text.to_string())
+            }
+        };
+
+        state.sent.clear();
+        if !flush {
+            state.sent.push_str(text);
+        }
+        outcome
+    }
+
+    /// Forget the current utterance, e.g. after TTS was cleared
+    pub fn reset(&self) {
+        self.state.lock().sent.clear();
+    }
+
+    /// Deduplication counters since the session started
+    pub fn stats(&self) -> TextDedupStats {
+        self.state.lock().stats
+    }
+}
+
+/// Length in bytes of the longest common prefix, on a char boundary
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| a.len().min(b.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn append(text: &str) -> Deduplicated {
+        Deduplicated::Append(text.to_string())
+    }
+
+    #[test]
+    fn test_prefix_resend_sends_only_suffix() {
+        let dedup = PartialTextDedup::new();
+        let sent: Vec<_> = [
+            "Hel",
+            "Hell",
+            "Hello",
+            "Hello,",
+            "Hello, wor",
+            "Hello, world",
+        ]
+        .into_iter()
+        .map(|partial| dedup.process(partial, false))
+        .collect();
+
+        assert_eq!(
+            sent,
+            vec![
+                append("Hel"),
+                append("l"),
+                append("o"),
+                append(","),
+                append(" wor"),
+                append("ld"),
+            ]
+        );
+        let stats = dedup.stats();
+        assert_eq!(stats.deduplicated_chars, 3 + 4 + 5 + 6 + 10);
+        assert_eq!(stats.corrected_chars, 0);
+    }
+
+    #[test]
+    fn test_repeated_partial_is_skipped() {
+        let dedup = PartialTextDedup::new();
+        dedup.process("Hello", false);
+        assert_eq!(dedup.process("Hello", false), Deduplicated::Skip);
+    }
+
+    #[test]
+    fn test_mid_sentence_revision_replaces() {
+        let dedup = PartialTextDedup::new();
+        dedup.process("I can book a table for", false);
+        assert_eq!(
+            dedup.process("I can book a room for", false),
+            Deduplicated::Replace("I can book a room for".to_string())
+        );
+        // Later partials extend the revised text
+        assert_eq!(
+            dedup.process("I can book a room for two", false),
+            append(" two")
+        );
+
+        let stats = dedup.stats();
+        assert_eq!(stats.replacements, 1);
+        assert_eq!(stats.corrected_chars, "table for".len() as u64);
+    }
+
+    #[test]
+    fn test_unrelated_text_starts_new_segment() {
+        let dedup = PartialTextDedup::new();
+        dedup.process("Hello.", false);
+        // Shares only "H", not a whole word
+        assert_eq!(dedup.process("How are", false), append("How are"));
+        assert_eq!(dedup.process("How are you?", false), append(" you?"));
+        assert_eq!(dedup.stats().replacements, 0);
+    }
+
+    #[test]
+    fn test_flush_ends_utterance() {
+        let dedup = PartialTextDedup::new();
+        dedup.process("Hi", false);
+        assert_eq!(dedup.process("Hi there", true), append(" there"));
+        // The next utterance may repeat the previous one's text
+        assert_eq!(dedup.process("Hi", false), append("Hi"));
+    }
+
+    #[test]
+    fn test_reset_forgets_sent_text() {
+        let dedup = PartialTextDedup::new();
+        dedup.process("Good morning", false);
+        dedup.reset();
+        assert_eq!(dedup.process("Good morning", false), append("Good morning"));
+    }
+
+    #[test]
+    fn test_multibyte_revision() {
+        let dedup = PartialTextDedup::new();
+        dedup.process("Très bien, café", false);
+        assert_eq!(
+            dedup.process("Très bien, thé", false),
+            Deduplicated::Replace("Très bien, thé".to_string())
+        );
+        assert_eq!(dedup.stats().corrected_chars, 4);
+    }
+}
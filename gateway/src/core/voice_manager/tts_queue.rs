@@ -0,0 +1,365 @@
+//! Per-session cap on pending TTS utterances
+//!
+//! Every `speak` call queues an utterance on the TTS provider, and a client
+//! that sends text faster than it can be spoken grows that queue without
+//! bound. [`TTSQueue`] tracks the utterances sent since the provider last
+//! reported completion and enforces [`TTSQueueLimit`] on them, either
+//! rejecting new text or dropping the oldest pending utterance. `high`
+//! priority utterances are exempt from the cap and are never dropped.
+//!
+//! The cap is enforced by the VoiceManager in front of [`BaseTTS`](crate::core::tts::BaseTTS),
+//! so it applies the same way to built-in providers and dynamic plugins.
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::callbacks::TTSQueueFullCallback;
+use super::preemption::SpeakPriority;
+
+/// Default maximum number of pending TTS utterances per session
+pub const DEFAULT_MAX_PENDING_UTTERANCES: usize = 5;
+
+/// What to do with a `speak` request when the pending TTS queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TTSQueuePolicy {
+    /// Reject the new utterance and keep the queue as-is
+    #[default]
+    Reject,
+    /// Drop the oldest pending utterance to make room for the new one
+    DropOldest,
+}
+
+impl TTSQueuePolicy {
+    /// The policy's configuration name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::DropOldest => "drop_oldest",
+        }
+    }
+}
+
+impl fmt::Display for TTSQueuePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TTSQueuePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "drop_oldest" => Ok(Self::DropOldest),
+            other => Err(format!(
+                "Invalid TTS queue policy '{other}': expected 'reject' or 'drop_oldest'"
+            )),
+        }
+    }
+}
+
+/// Cap on pending TTS utterances
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TTSQueueLimit {
+    /// Maximum number of utterances waiting on the TTS provider
+    pub max_pending: usize,
+    /// What to do with new text once `max_pending` is reached
+    pub policy: TTSQueuePolicy,
+}
+
+impl Default for TTSQueueLimit {
+    fn default() -> Self {
+        Self {
+            max_pending: DEFAULT_MAX_PENDING_UTTERANCES,
+            policy: TTSQueuePolicy::default(),
+        }
+    }
+}
+
+/// Snapshot of the pending TTS queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TTSQueueStats {
+    /// Utterances sent to the provider that have not completed yet
+    pub pending: usize,
+    /// Configured cap on `pending`
+    pub max_pending: usize,
+    /// Utterances rejected because the queue was full
+    pub rejected: u64,
+    /// Utterances dropped to make room for newer ones
+    pub dropped: u64,
+}
+
+/// Details passed to the queue-full callback
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TTSQueueFull {
+    /// Policy that was applied
+    pub policy: TTSQueuePolicy,
+    /// Configured cap on pending utterances
+    pub max_pending: usize,
+    /// Text of the utterance that was rejected or dropped
+    pub text: String,
+}
+
+/// Outcome of admitting an utterance to the queue
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Admission {
+    /// The utterance fits under the cap
+    Accepted,
+    /// The queue is full and the utterance was not queued
+    Rejected,
+    /// The oldest normal utterance was dropped; `retained` lists the pending
+    /// utterances that must be re-sent before the new one
+    DroppedOldest {
+        dropped: String,
+        retained: Vec<(String, bool)>,
+    },
+}
+
+/// Tracks pending TTS utterances and enforces a [`TTSQueueLimit`]
+pub struct TTSQueue {
+    limit: TTSQueueLimit,
+    pending: Mutex<VecDeque<(String, bool, SpeakPriority)>>,
+    rejected: AtomicU64,
+    dropped: AtomicU64,
+    callback: SyncRwLock<Option<TTSQueueFullCallback>>,
+}
+
+impl TTSQueue {
+    /// Create an empty queue; a `max_pending` of 0 is treated as 1
+    pub fn new(limit: TTSQueueLimit) -> Self {
+        Self {
+            limit: TTSQueueLimit {
+                max_pending: limit.max_pending.max(1),
+                ..limit
+            },
+            pending: Mutex::new(VecDeque::new()),
+            rejected: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            callback: SyncRwLock::new(None),
+        }
+    }
+
+    /// The enforced limit
+    pub fn limit(&self) -> TTSQueueLimit {
+        self.limit
+    }
+
+    /// Admit a normal priority utterance, applying the policy when the queue is full
+    ///
+    /// Call while holding the TTS lock so admission order matches send order.
+    pub(super) fn admit(&self, text: &str, flush: bool) -> Admission {
+        self.admit_with_priority(text, flush, SpeakPriority::Normal)
+    }
+
+    /// Admit an utterance; only normal priority utterances are capped
+    ///
+    /// The `drop_oldest` policy drops the oldest normal utterance and rejects
+    /// the new one if every pending utterance has a higher priority.
+    /// Call while holding the TTS lock so admission order matches send order.
+    pub(super) fn admit_with_priority(
+        &self,
+        text: &str,
+        flush: bool,
+        priority: SpeakPriority,
+    ) -> Admission {
+        let mut pending = self.pending.lock();
+        if priority != SpeakPriority::Normal || pending.len() < self.limit.max_pending {
+            pending.push_back((text.to_string(), flush, priority));
+            return Admission::Accepted;
+        }
+
+        let oldest_normal = pending
+            .iter()
+            .position(|(_, _, priority)| *priority == SpeakPriority::Normal);
+        match (self.limit.policy, oldest_normal) {
+            (TTSQueuePolicy::DropOldest, Some(oldest)) => {
+                let (dropped, _, _) = pending.remove(oldest).unwrap_or_default();
+                let retained = pending
+                    .iter()
+                    .map(|(text, flush, _)| (text.clone(), *flush))
+                    .collect();
+                pending.push_back((text.to_string(), flush, priority));
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                Admission::DroppedOldest { dropped, retained }
+            }
+            _ => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                Admission::Rejected
+            }
+        }
+    }
+
+    /// Empty the queue, returning the pending utterances oldest first
+    pub(super) fn take_pending(&self) -> Vec<(String, bool, SpeakPriority)> {
+        self.pending.lock().drain(..).collect()
+    }
+
+    /// Forget the most recently admitted utterance after the provider refused it
+    pub(super) fn revoke_last(&self) {
+        self.pending.lock().pop_back();
+    }
+
+    /// Count an utterance rejected outside of `admit`
+    pub(super) fn record_rejected(&self) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count an utterance dropped outside of `admit`
+    pub(super) fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The provider finished all queued text
+    pub fn complete(&self) {
+        self.pending.lock().clear();
+    }
+
+    /// Queued text was cleared
+    pub fn clear(&self) {
+        self.pending.lock().clear();
+    }
+
+    /// Current queue depth and counters
+    pub fn stats(&self) -> TTSQueueStats {
+        TTSQueueStats {
+            pending: self.pending.lock().len(),
+            max_pending: self.limit.max_pending,
+            rejected: self.rejected.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Register the callback fired whenever the cap is hit
+    pub fn set_callback(&self, callback: TTSQueueFullCallback) {
+        *self.callback.write() = Some(callback);
+    }
+
+    /// Report that `text` was rejected or dropped
+    pub(super) async fn notify_full(&self, text: String) {
+        let callback = self.callback.read().clone();
+        if let Some(callback) = callback {
+            callback(TTSQueueFull {
+                policy: self.limit.policy,
+                max_pending: self.limit.max_pending,
+                text,
+            })
+            .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_pending: usize, policy: TTSQueuePolicy) -> TTSQueue {
+        TTSQueue::new(TTSQueueLimit {
+            max_pending,
+            policy,
+        })
+    }
+
+    #[test]
+    fn test_reject_policy_keeps_queue() {
+        let queue = queue(2, TTSQueuePolicy::Reject);
+        assert_eq!(queue.admit("one", false), Admission::Accepted);
+        assert_eq!(queue.admit("two", false), Admission::Accepted);
+        assert_eq!(queue.admit("three", true), Admission::Rejected);
+
+        let stats = queue.stats();
+        assert_eq!(stats.pending, 2);
+        assert_eq!(stats.max_pending, 2);
+        assert_eq!(stats.rejected, 1);
+        assert_eq!(stats.dropped, 0);
+    }
+
+    #[test]
+    fn test_drop_oldest_policy_replaces_head() {
+        let queue = queue(2, TTSQueuePolicy::DropOldest);
+        queue.admit("one", false);
+        queue.admit("two", true);
+        assert_eq!(
+            queue.admit("three", false),
+            Admission::DroppedOldest {
+                dropped: "one".to_string(),
+                retained: vec![("two".to_string(), true)],
+            }
+        );
+
+        let stats = queue.stats();
+        assert_eq!(stats.pending, 2);
+        assert_eq!(stats.rejected, 0);
+        assert_eq!(stats.dropped, 1);
+    }
+
+    #[test]
+    fn test_high_priority_bypasses_cap_and_is_never_dropped() {
+        let queue = queue(1, TTSQueuePolicy::DropOldest);
+        queue.admit_with_priority("urgent", true, SpeakPriority::High);
+        queue.admit("one", false);
+        assert_eq!(
+            queue.admit_with_priority("urgent again", true, SpeakPriority::High),
+            Admission::Accepted
+        );
+        assert_eq!(
+            queue.admit("two", true),
+            Admission::DroppedOldest {
+                dropped: "one".to_string(),
+                retained: vec![
+                    ("urgent".to_string(), true),
+                    ("urgent again".to_string(), true)
+                ],
+            }
+        );
+
+        let only_high = queue(1, TTSQueuePolicy::DropOldest);
+        only_high.admit_with_priority("urgent", true, SpeakPriority::High);
+        only_high.admit_with_priority("urgent again", true, SpeakPriority::High);
+        assert_eq!(only_high.admit("one", true), Admission::Rejected);
+        assert_eq!(only_high.take_pending().len(), 2);
+        assert_eq!(only_high.stats().pending, 0);
+    }
+
+    #[test]
+    fn test_complete_and_clear_empty_queue() {
+        let queue = queue(1, TTSQueuePolicy::Reject);
+        queue.admit("one", true);
+        queue.complete();
+        assert_eq!(queue.admit("two", true), Admission::Accepted);
+        queue.clear();
+        assert_eq!(queue.stats().pending, 0);
+    }
+
+    #[test]
+    fn test_revoke_last_and_zero_limit() {
+        let queue = queue(0, TTSQueuePolicy::Reject);
+        assert_eq!(queue.limit().max_pending, 1);
+        queue.admit("one", true);
+        queue.revoke_last();
+        assert_eq!(queue.admit("two", true), Admission::Accepted);
+    }
+
+    #[test]
+    fn test_policy_parsing() {
+        assert_eq!(
+            "drop_oldest".parse::<TTSQueuePolicy>().unwrap(),
+            TTSQueuePolicy::DropOldest
+        );
+        assert_eq!(
+            " Reject ".parse::<TTSQueuePolicy>().unwrap(),
+            TTSQueuePolicy::Reject
+        );
+        assert!("newest".parse::<TTSQueuePolicy>().is_err());
+        assert_eq!(
+            serde_json::to_string(&TTSQueuePolicy::DropOldest).unwrap(),
+            "\"drop_oldest\""
+        );
+    }
+}
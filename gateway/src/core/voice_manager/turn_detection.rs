@@ -0,0 +1,456 @@
+//! Turn detection metrics and the silence-based fallback
+//!
+//! When the STT provider does not end a turn itself, the turn detection
+//! model confirms the end of turn from the buffered transcript. A session
+//! without the model, or whose model takes longer than the per-inference
+//! latency budget, degrades to a simple energy detector instead: the turn
+//! ends once the caller's audio has stayed below [`SILENCE_VAD_THRESHOLD_DBFS`]
+//! for [`SILENCE_VAD_MIN_SILENCE_MS`]. A session never switches back to the
+//! model once degraded.
+//!
+//! [`TurnDetectionMonitor`] records every decision in the process metrics,
+//! keeps the per-session [`TurnDetectionStats`], and reports the switch as a
+//! [`TurnDetectionDegraded`] event.
+//!
+//! Only 16-bit PCM caller audio is measured. With other encodings the caller
+//! always counts as silent, which ends the turn once the STT provider has
+//! been quiet for `stt_speech_final_wait_ms`.
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::core::turn_detect::TurnDetectionMode;
+use crate::metrics::global_metrics;
+
+use super::callbacks::TurnDetectionDegradedCallback;
+
+/// Level below which caller audio counts as silence (dBFS)
+pub const SILENCE_VAD_THRESHOLD_DBFS: f64 = -40.0;
+
+/// Silence that ends a turn in silence VAD mode (ms)
+pub const SILENCE_VAD_MIN_SILENCE_MS: u64 = 300;
+
+/// Why a session stopped using the turn detection model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TurnDetectionDegradedReason {
+    /// The model failed to load at startup
+    ModelUnavailable,
+    /// An inference took longer than the latency budget
+    LatencyBudgetExceeded,
+}
+
+impl TurnDetectionDegradedReason {
+    /// The reason's metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ModelUnavailable => "model_unavailable",
+            Self::LatencyBudgetExceeded => "latency_budget_exceeded",
+        }
+    }
+}
+
+/// A session switched from the model to silence VAD
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TurnDetectionDegraded {
+    /// Why the model is no longer used
+    pub reason: TurnDetectionDegradedReason,
+    /// Duration of the inference that exceeded the budget (ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inference_ms: Option<u64>,
+    /// Configured latency budget per inference (ms)
+    pub budget_ms: u64,
+}
+
+/// Result of one end-of-turn check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnDecision {
+    /// The turn is complete
+    Complete,
+    /// The user may continue speaking
+    Incomplete,
+    /// The model failed
+    Error,
+    /// The model did not answer within the inference timeout
+    Timeout,
+}
+
+impl TurnDecision {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Complete => "complete",
+            Self::Incomplete => "incomplete",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
+/// Turn detection counters of one session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TurnDetectionStats {
+    /// How end of turn is currently decided
+    pub mode: TurnDetectionMode,
+    /// Why the session degraded to silence VAD, if it did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub degraded_reason: Option<TurnDetectionDegradedReason>,
+    /// Model inferences run
+    pub inferences: u64,
+    /// Inferences that confirmed the end of turn
+    pub model_complete: u64,
+    /// Inferences that judged the turn incomplete
+    pub model_incomplete: u64,
+    /// Inferences that failed or timed out
+    pub model_failed: u64,
+    /// Total time spent in inference (ms)
+    pub inference_ms_total: u64,
+    /// Slowest inference (ms)
+    pub inference_ms_max: u64,
+    /// Turns ended by silence VAD
+    pub silence_vad_turns: u64,
+}
+
+/// Silence tracking of the caller audio
+struct EnergyVad {
+    /// Whether caller audio is 16-bit PCM that can be measured
+    measurable: bool,
+    /// When the caller's audio was last above the threshold
+    last_voiced: Option<Instant>,
+}
+
+/// Per-session turn detection mode, metrics and energy detector
+pub struct TurnDetectionMonitor {
+    has_model: bool,
+    latency_budget: Duration,
+    degraded: Mutex<Option<TurnDetectionDegradedReason>>,
+    vad: Mutex<EnergyVad>,
+    inferences: AtomicU64,
+    model_complete: AtomicU64,
+    model_incomplete: AtomicU64,
+    model_failed: AtomicU64,
+    inference_ms_total: AtomicU64,
+    inference_ms_max: AtomicU64,
+    silence_vad_turns: AtomicU64,
+    callback: SyncRwLock<Option<TurnDetectionDegradedCallback>>,
+}
+
+impl TurnDetectionMonitor {
+    /// Create the monitor of one session
+    ///
+    /// # Arguments
+    /// * `has_model` - Whether the session has a turn detection model
+    /// * `model_expected` - Whether a model should have loaded; a session
+    ///   without one starts degraded
+    /// * `latency_budget` - Longest acceptable inference
+    /// * `stt_encoding` - Encoding of the caller audio
+    pub fn new(
+        has_model: bool,
+        model_expected: bool,
+        latency_budget: Duration,
+        stt_encoding: &str,
+    ) -> Self {
+        let degraded = (!has_model && model_expected).then(|| {
+            Self::count_degraded(TurnDetectionDegradedReason::ModelUnavailable);
+            TurnDetectionDegradedReason::ModelUnavailable
+        });
+        Self {
+            has_model,
+            latency_budget,
+            degraded: Mutex::new(degraded),
+            vad: Mutex::new(EnergyVad {
+                measurable: matches!(
+                    stt_encoding.to_ascii_lowercase().as_str(),
+                    "linear16" | "pcm" | "pcm16"
+                ),
+                last_voiced: None,
+            }),
+            inferences: AtomicU64::new(0),
+            model_complete: AtomicU64::new(0),
+            model_incomplete: AtomicU64::new(0),
+            model_failed: AtomicU64::new(0),
+            inference_ms_total: AtomicU64::new(0),
+            inference_ms_max: AtomicU64::new(0),
+            silence_vad_turns: AtomicU64::new(0),
+            callback: SyncRwLock::new(None),
+        }
+    }
+
+    /// Register the callback notified when the session degrades
+    pub fn set_callback(&self, callback: TurnDetectionDegradedCallback) {
+        *self.callback.write() = Some(callback);
+    }
+
+    /// The degradation the session started with, if any
+    pub fn initial_degradation(&self) -> Option<TurnDetectionDegraded> {
+        (*self.degraded.lock() == Some(TurnDetectionDegradedReason::ModelUnavailable)).then(|| {
+            TurnDetectionDegraded {
+                reason: TurnDetectionDegradedReason::ModelUnavailable,
+                inference_ms: None,
+                budget_ms: self.budget_ms(),
+            }
+        })
+    }
+
+    /// Whether the model may still be used
+    pub fn uses_model(&self) -> bool {
+        self.has_model && self.degraded.lock().is_none()
+    }
+
+    /// Measure a frame of caller audio
+    pub fn observe_audio(&self, audio: &[u8]) {
+        let mut vad = self.vad.lock();
+        if !vad.measurable {
+            return;
+        }
+        let samples = audio.len() / 2;
+        if samples == 0 {
+            return;
+        }
+        let sum_squares: f64 = audio
+            .chunks_exact(2)
+            .map(|pair| {
+                let sample = f64::from(i16::from_le_bytes([pair[0], pair[1]])) / 32768.0;
+                sample * sample
+            })
+            .sum();
+        let rms = (sum_squares / samples as f64).sqrt();
+        if rms > 0.0 && 20.0 * rms.log10() >= SILENCE_VAD_THRESHOLD_DBFS {
+            vad.last_voiced = Some(Instant::now());
+        }
+    }
+
+    /// How much longer the caller has to stay silent to end the turn
+    ///
+    /// # Returns
+    /// * `Duration` - Zero once the caller has been silent for
+    ///   [`SILENCE_VAD_MIN_SILENCE_MS`]
+    pub fn silence_remaining(&self) -> Duration {
+        let min_silence = Duration::from_millis(SILENCE_VAD_MIN_SILENCE_MS);
+        match self.vad.lock().last_voiced {
+            Some(last_voiced) => min_silence.saturating_sub(last_voiced.elapsed()),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Count a turn ended by silence VAD
+    pub fn record_silence_turn(&self) {
+        self.silence_vad_turns.fetch_add(1, Ordering::Relaxed);
+        Self::count_decision(TurnDetectionMode::SilenceVad, TurnDecision::Complete);
+    }
+
+    /// Record a model inference and degrade when it exceeded the budget
+    ///
+    /// # Returns
+    /// * `Option<TurnDetectionDegraded>` - The degradation, the first time an
+    ///   inference is over budget
+    pub fn record_inference(
+        &self,
+        elapsed: Duration,
+        decision: TurnDecision,
+    ) -> Option<TurnDetectionDegraded> {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        self.inferences.fetch_add(1, Ordering::Relaxed);
+        self.inference_ms_total
+            .fetch_add(elapsed_ms, Ordering::Relaxed);
+        self.inference_ms_max
+            .fetch_max(elapsed_ms, Ordering::Relaxed);
+        match decision {
+            TurnDecision::Complete => &self.model_complete,
+            TurnDecision::Incomplete => &self.model_incomplete,
+            TurnDecision::Error | TurnDecision::Timeout => &self.model_failed,
+        }
+        .fetch_add(1, Ordering::Relaxed);
+
+        Self::count_decision(TurnDetectionMode::Model, decision);
+        global_metrics().add_counter(
+            "waav_turn_detection_inference_seconds_total",
+            "Time spent in turn detection model inference",
+            &[],
+            elapsed.as_secs_f64(),
+        );
+
+        if elapsed <= self.latency_budget && decision != TurnDecision::Timeout {
+            return None;
+        }
+        let mut degraded = self.degraded.lock();
+        if degraded.is_some() {
+            return None;
+        }
+        *degraded = Some(TurnDetectionDegradedReason::LatencyBudgetExceeded);
+        Self::count_degraded(TurnDetectionDegradedReason::LatencyBudgetExceeded);
+        warn!(
+            inference_ms = elapsed_ms,
+            budget_ms = self.budget_ms(),
+            "Turn detection inference exceeded its latency budget; \
+             falling back to silence detection for the rest of the session"
+        );
+        Some(TurnDetectionDegraded {
+            reason: TurnDetectionDegradedReason::LatencyBudgetExceeded,
+            inference_ms: Some(elapsed_ms),
+            budget_ms: self.budget_ms(),
+        })
+    }
+
+    /// Notify the registered callback about a degradation
+    pub async fn notify(&self, degraded: TurnDetectionDegraded) {
+        let callback = self.callback.read().clone();
+        if let Some(callback) = callback {
+            callback(degraded).await;
+        }
+    }
+
+    /// Current counters
+    pub fn stats(&self) -> TurnDetectionStats {
+        let degraded_reason = *self.degraded.lock();
+        TurnDetectionStats {
+            mode: if self.has_model && degraded_reason.is_none() {
+                TurnDetectionMode::Model
+            } else {
+                TurnDetectionMode::SilenceVad
+            },
+            degraded_reason,
+            inferences: self.inferences.load(Ordering::Relaxed),
+            model_complete: self.model_complete.load(Ordering::Relaxed),
+            model_incomplete: self.model_incomplete.load(Ordering::Relaxed),
+            model_failed: self.model_failed.load(Ordering::Relaxed),
+            inference_ms_total: self.inference_ms_total.load(Ordering::Relaxed),
+            inference_ms_max: self.inference_ms_max.load(Ordering::Relaxed),
+            silence_vad_turns: self.silence_vad_turns.load(Ordering::Relaxed),
+        }
+    }
+
+    fn budget_ms(&self) -> u64 {
+        self.latency_budget.as_millis() as u64
+    }
+
+    fn count_decision(mode: TurnDetectionMode, decision: TurnDecision) {
+        global_metrics().inc_counter(
+            "waav_turn_detection_decisions_total",
+            "End-of-turn checks by detection mode and outcome",
+            &[("mode", mode.as_str()), ("decision", decision.as_str())],
+        );
+    }
+
+    fn count_degraded(reason: TurnDetectionDegradedReason) {
+        global_metrics().inc_counter(
+            "waav_turn_detection_degraded_total",
+            "Sessions that fell back from the turn detection model to silence detection",
+            &[("reason", reason.as_str())],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm(sample: i16, samples: usize) -> Vec<u8> {
+        std::iter::repeat_n(sample, samples)
+            .flat_map(i16::to_le_bytes)
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_expected_model_starts_degraded() {
+        let monitor =
+            TurnDetectionMonitor::new(false, true, Duration::from_millis(200), "linear16");
+        assert!(!monitor.uses_model());
+        let degraded = monitor.initial_degradation().unwrap();
+        assert_eq!(
+            degraded.reason,
+            TurnDetectionDegradedReason::ModelUnavailable
+        );
+        assert_eq!(degraded.budget_ms, 200);
+        assert_eq!(monitor.stats().mode, TurnDetectionMode::SilenceVad);
+
+        // Without the turn-detect feature no model is expected
+        let monitor =
+            TurnDetectionMonitor::new(false, false, Duration::from_millis(200), "linear16");
+        assert!(monitor.initial_degradation().is_none());
+        assert_eq!(monitor.stats().mode, TurnDetectionMode::SilenceVad);
+    }
+
+    #[test]
+    fn test_slow_inference_degrades_once() {
+        let monitor = TurnDetectionMonitor::new(true, true, Duration::from_millis(200), "linear16");
+        assert!(monitor.initial_degradation().is_none());
+        assert!(
+            monitor
+                .record_inference(Duration::from_millis(50), TurnDecision::Incomplete)
+                .is_none()
+        );
+        assert!(monitor.uses_model());
+
+        let degraded = monitor
+            .record_inference(Duration::from_millis(340), TurnDecision::Complete)
+            .unwrap();
+        assert_eq!(
+            degraded.reason,
+            TurnDetectionDegradedReason::LatencyBudgetExceeded
+        );
+        assert_eq!(degraded.inference_ms, Some(340));
+        assert!(!monitor.uses_model());
+        assert!(
+            monitor
+                .record_inference(Duration::from_millis(500), TurnDecision::Complete)
+                .is_none()
+        );
+
+        let stats = monitor.stats();
+        assert_eq!(stats.mode, TurnDetectionMode::SilenceVad);
+        assert_eq!(
+            stats.degraded_reason,
+            Some(TurnDetectionDegradedReason::LatencyBudgetExceeded)
+        );
+        assert_eq!(stats.inferences, 3);
+        assert_eq!(stats.model_complete, 2);
+        assert_eq!(stats.model_incomplete, 1);
+        assert_eq!(stats.inference_ms_total, 890);
+        assert_eq!(stats.inference_ms_max, 500);
+    }
+
+    #[test]
+    fn test_inference_timeout_degrades() {
+        let monitor = TurnDetectionMonitor::new(true, true, Duration::from_millis(200), "linear16");
+        let degraded = monitor
+            .record_inference(Duration::from_millis(150), TurnDecision::Timeout)
+            .unwrap();
+        assert_eq!(
+            degraded.reason,
+            TurnDetectionDegradedReason::LatencyBudgetExceeded
+        );
+        assert_eq!(monitor.stats().model_failed, 1);
+    }
+
+    #[test]
+    fn test_silence_remaining_follows_caller_audio() {
+        let monitor =
+            TurnDetectionMonitor::new(false, false, Duration::from_millis(200), "linear16");
+        assert_eq!(monitor.silence_remaining(), Duration::ZERO);
+
+        monitor.observe_audio(&pcm(8000, 160));
+        assert!(monitor.silence_remaining() > Duration::ZERO);
+        assert!(monitor.silence_remaining() <= Duration::from_millis(SILENCE_VAD_MIN_SILENCE_MS));
+
+        // Quiet audio does not extend the turn
+        let monitor =
+            TurnDetectionMonitor::new(false, false, Duration::from_millis(200), "linear16");
+        monitor.observe_audio(&pcm(30, 160));
+        monitor.observe_audio(&pcm(0, 160));
+        assert_eq!(monitor.silence_remaining(), Duration::ZERO);
+
+        monitor.record_silence_turn();
+        assert_eq!(monitor.stats().silence_vad_turns, 1);
+    }
+
+    #[test]
+    fn test_compressed_audio_is_not_measured() {
+        let monitor = TurnDetectionMonitor::new(false, false, Duration::from_millis(200), "mulaw");
+        monitor.observe_audio(&pcm(8000, 160));
+        assert_eq!(monitor.silence_remaining(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,229 @@
+//! Fallback voice for TTS voices that no longer exist
+//!
+//! When the configured voice has been deleted, every synthesis fails with
+//! `TTSError::VoiceNotFound`. [`VoiceFallback`] replaces the TTS provider with
+//! one using the fallback voice on the first such error, replays the text that
+//! the missing voice never spoke, and keeps the fallback for the rest of the
+//! session.
+
+use parking_lot::{Mutex, RwLock as SyncRwLock};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, error, warn};
+
+use crate::core::tts::{AudioCallback, BaseTTS, TTSConfig, TTSError};
+use crate::plugin::PluginRegistry;
+
+use super::callbacks::TTSVoiceFallbackCallback;
+
+/// Which voice the TTS provider is speaking with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FallbackStage {
+    /// The configured voice
+    Original,
+    /// Replacing the provider with one using the fallback voice
+    Switching,
+    /// The fallback voice, for the rest of the session
+    Pinned,
+    /// Switching failed; voice errors are reported as-is
+    Failed,
+}
+
+struct FallbackState {
+    stage: FallbackStage,
+    /// Text sent while the configured voice is unconfirmed, replayed after switching
+    unconfirmed: Vec<(String, bool)>,
+}
+
+/// Switches a session to its fallback voice when the configured voice is missing
+pub struct VoiceFallback {
+    original_voice_id: String,
+    fallback_voice_id: String,
+    /// Provider configuration with the fallback voice
+    fallback_config: TTSConfig,
+    connect_timeout: Duration,
+    tts: Arc<RwLock<Box<dyn BaseTTS>>>,
+    /// Registry the fallback provider is created from
+    registry: Arc<PluginRegistry>,
+    /// Set once the configured voice produced audio
+    voice_confirmed: AtomicBool,
+    state: Mutex<FallbackState>,
+    callback: SyncRwLock<Option<TTSVoiceFallbackCallback>>,
+}
+
+impl VoiceFallback {
+    /// Create the fallback for the provider behind `tts`
+    ///
+    /// # Arguments
+    /// * `provider_config` - Configuration the current provider was created with
+    /// * `fallback_voice_id` - Voice to switch to
+    /// * `connect_timeout` - Upper bound for connecting the fallback provider
+    /// * `tts` - The VoiceManager's TTS provider slot
+    /// * `registry` - Registry the current provider was created from
+    pub fn new(
+        provider_config: &TTSConfig,
+        fallback_voice_id: String,
+        connect_timeout: Duration,
+        tts: Arc<RwLock<Box<dyn BaseTTS>>>,
+        registry: Arc<PluginRegistry>,
+    ) -> Self {
+        let fallback_config = TTSConfig {
+            voice_id: Some(fallback_voice_id.clone()),
+            ..provider_config.clone()
+        };
+
+        Self {
+            original_voice_id: provider_config.voice_id.clone().unwrap_or_default(),
+            fallback_voice_id,
+            fallback_config,
+            connect_timeout,
+            tts,
+            registry,
+            voice_confirmed: AtomicBool::new(false),
+            state: Mutex::new(FallbackState {
+                stage: FallbackStage::Original,
+                unconfirmed: Vec::new(),
+            }),
+            callback: SyncRwLock::new(None),
+        }
+    }
+
+    /// Register the callback notified after switching to the fallback voice
+    pub fn set_callback(&self, callback: TTSVoiceFallbackCallback) {
+        *self.callback.write() = Some(callback);
+    }
+
+    /// Whether the TTS provider speaks with the fallback voice
+    ///
+    /// Call while holding the TTS lock so the answer matches the provider.
+    pub fn is_pinned(&self) -> bool {
+        self.state.lock().stage == FallbackStage::Pinned
+    }
+
+    /// Remember text sent to the provider until the configured voice is confirmed
+    ///
+    /// Call while holding the TTS write lock, right before `speak()`.
+    pub fn record(&self, text: &str, flush: bool) {
+        let mut state = self.state.lock();
+        let keep = match state.stage {
+            FallbackStage::Original => !self.voice_confirmed.load(Ordering::Acquire),
+            FallbackStage::Switching => true,
+            FallbackStage::Pinned | FallbackStage::Failed => false,
+        };
+        if keep {
+            state.unconfirmed.push((text.to_string(), flush));
+        }
+    }
+
+    /// Mark the configured voice as existing once it produced audio
+    pub fn confirm(&self) {
+        if !self.voice_confirmed.swap(true, Ordering::AcqRel) {
+            let mut state = self.state.lock();
+            if state.stage == FallbackStage::Original {
+                state.unconfirmed.clear();
+            }
+        }
+    }
+
+    /// Forget recorded text after the TTS queue was cleared
+    pub fn clear(&self) {
+        self.state.lock().unconfirmed.clear();
+    }
+
+    /// Handle `TTSError::VoiceNotFound` from the provider using the configured voice
+    ///
+    /// The first error starts the switch to the fallback voice. Errors for
+    /// requests still in flight on the replaced provider are swallowed.
+    ///
+    /// # Arguments
+    /// * `error` - The voice error, reported through `callback` if switching fails
+    /// * `callback` - Audio callback to register on the fallback provider
+    ///
+    /// # Returns
+    /// * `Option<TTSError>` - The error when it should still be reported
+    pub fn handle_voice_not_found(
+        self: &Arc<Self>,
+        error: TTSError,
+        callback: Arc<dyn AudioCallback>,
+    ) -> Option<TTSError> {
+        let mut state = self.state.lock();
+        match state.stage {
+            FallbackStage::Original => {
+                state.stage = FallbackStage::Switching;
+                drop(state);
+                tokio::spawn(self.clone().switch(error, callback));
+                None
+            }
+            FallbackStage::Switching | FallbackStage::Pinned => None,
+            FallbackStage::Failed => Some(error),
+        }
+    }
+
+    async fn switch(self: Arc<Self>, error: TTSError, callback: Arc<dyn AudioCallback>) {
+        warn!(
+            "TTS voice '{}' not found ({}), switching to fallback voice '{}'",
+            self.original_voice_id, error, self.fallback_voice_id
+        );
+
+        let mut tts = self.tts.write().await;
+        let result = async {
+            let mut provider = self
+                .registry
+                .create_tts(&self.fallback_config.provider, self.fallback_config.clone())?;
+            provider.connect_with_timeout(self.connect_timeout).await?;
+            provider.on_audio(callback.clone())?;
+            Ok::<_, TTSError>(provider)
+        }
+        .await;
+
+        let mut previous = match result {
+            Ok(provider) => std::mem::replace(&mut *tts, provider),
+            Err(e) => {
+                error!(
+                    "Failed to switch to fallback voice '{}': {}",
+                    self.fallback_voice_id, e
+                );
+                {
+                    let mut state = self.state.lock();
+                    state.stage = FallbackStage::Failed;
+                    state.unconfirmed.clear();
+                }
+                drop(tts);
+                callback.on_error(error).await;
+                return;
+            }
+        };
+
+        let replay = {
+            let mut state = self.state.lock();
+            state.stage = FallbackStage::Pinned;
+            std::mem::take(&mut state.unconfirmed)
+        };
+        let mut replay_errors = Vec::new();
+        for (text, flush) in replay {
+            if let Err(e) = tts.speak(&text, flush).await {
+                replay_errors.push(e);
+            }
+        }
+        drop(tts);
+
+        for e in replay_errors {
+            callback.on_error(e).await;
+        }
+
+        if let Err(e) = previous.disconnect().await {
+            debug!("Failed to disconnect replaced TTS provider: {}", e);
+        }
+
+        let notify = self.callback.read().clone();
+        if let Some(notify) = notify {
+            notify(
+                self.original_voice_id.clone(),
+                self.fallback_voice_id.clone(),
+            )
+            .await;
+        }
+    }
+}
@@ -256,6 +256,15 @@ impl DAGCompiler {
                 }
                 Arc::new(node)
             }
+            // IPC endpoints connect over Unix domain sockets
+            #[cfg(not(unix))]
+            NodeType::IpcEndpoint { .. } => {
+                return Err(DAGError::InvalidNodeConfig {
+                    node_id: def.id.clone(),
+                    error: "ipc_endpoint nodes use Unix domain sockets, which this platform does not support; use an http_endpoint or grpc_endpoint node instead".to_string(),
+                });
+            }
+            #[cfg(unix)]
             NodeType::IpcEndpoint { shm_name, input_format, output_format } => {
                 // Use try_new() for proper error handling with input validation
                 let mut node = IpcEndpointNode::try_new(&def.id, shm_name)?;
@@ -487,6 +496,30 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    #[cfg(not(unix))]
+    fn test_ipc_endpoint_rejected_without_unix_sockets() {
+        let compiler = DAGCompiler::new();
+        let mut dag = DAGDefinition::new("test-dag", "Test DAG");
+        dag.add_node(NodeDefinition::new("input", NodeType::AudioInput));
+        dag.add_node(NodeDefinition::new("whisper", NodeType::IpcEndpoint {
+            shm_name: "waav_whisper".to_string(),
+            input_format: None,
+            output_format: None,
+        }));
+        dag.add_edge(EdgeDefinition::new("input", "whisper"));
+        dag.with_entry("input");
+        dag.add_exit("whisper");
+
+        match compiler.compile(dag) {
+            Err(DAGError::InvalidNodeConfig { node_id, error }) => {
+                assert_eq!(node_id, "whisper");
+                assert!(error.contains("Unix domain sockets"), "{error}");
+            }
+            other => panic!("unexpected result: {:?}", other.err()),
+        }
+    }
+
     #[test]
     fn test_api_key_routing() {
         let compiler = DAGCompiler::new();
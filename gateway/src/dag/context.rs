@@ -10,6 +10,8 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 
+use crate::plugin::{PluginRegistry, global_registry};
+
 /// Context passed through DAG execution
 ///
 /// This context is cloned for each branch during Split operations but shares
@@ -54,6 +56,8 @@ pub mod resource_keys {
     pub const VOICE_MANAGER: &str = "voice_manager";
     /// Key prefix for realtime providers (format: "realtime_provider:{provider_name}")
     pub const REALTIME_PROVIDER_PREFIX: &str = "realtime_provider:";
+    /// Key for the PluginRegistry provider and processor nodes create from
+    pub const PLUGIN_REGISTRY: &str = "plugin_registry";
 }
 
 impl DAGContext {
@@ -149,6 +153,14 @@ impl DAGContext {
         self.external_resources.contains_key(key)
     }
 
+    /// Registry nodes create providers and processors from
+    ///
+    /// Falls back to the [`global_registry`] when no registry resource is set.
+    pub fn plugin_registry(&self) -> Arc<PluginRegistry> {
+        self.get_resource_as::<PluginRegistry>(resource_keys::PLUGIN_REGISTRY)
+            .unwrap_or_else(|| global_registry().clone())
+    }
+
     /// Set the execution deadline
     pub fn with_deadline(mut self, deadline: Instant) -> Self {
         self.deadline = Some(deadline);
@@ -452,4 +464,17 @@ mod tests {
         ctx.cancel_token.cancel();
         assert!(branch_ctx.is_cancelled());
     }
+
+    #[test]
+    fn test_plugin_registry_resource() {
+        let mut ctx = DAGContext::new("stream-123");
+        assert!(Arc::ptr_eq(&ctx.plugin_registry(), global_registry()));
+
+        let registry = Arc::new(PluginRegistry::new_isolated());
+        ctx.set_resource(resource_keys::PLUGIN_REGISTRY, registry.clone());
+        assert!(Arc::ptr_eq(
+            &ctx.clone_for_branch().plugin_registry(),
+            &registry
+        ));
+    }
 }
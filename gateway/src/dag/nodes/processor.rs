@@ -9,7 +9,7 @@ use tracing::{debug, warn, info};
 use super::{DAGNode, DAGData, NodeCapability};
 use crate::dag::context::DAGContext;
 use crate::dag::error::{DAGError, DAGResult};
-use crate::plugin::{global_registry, capabilities::AudioFormat};
+use crate::plugin::capabilities::AudioFormat;
 
 /// Plugin-based processor node
 ///
@@ -152,7 +152,7 @@ impl DAGNode for ProcessorNode {
 
 impl ProcessorNode {
     /// Process audio data through the plugin registry
-    async fn process_audio(&self, input: DAGData, _ctx: &mut DAGContext) -> DAGResult<DAGData> {
+    async fn process_audio(&self, input: DAGData, ctx: &mut DAGContext) -> DAGResult<DAGData> {
         // Extract audio data from input
         let audio_bytes = match &input {
             DAGData::Audio(bytes) => bytes.clone(),
@@ -172,7 +172,7 @@ impl ProcessorNode {
         let format = self.get_audio_format();
 
         // Try to create the processor from the registry
-        let registry = global_registry();
+        let registry = ctx.plugin_registry();
 
         if !registry.has_audio_processor(&self.plugin_id) {
             warn!(
@@ -191,7 +191,7 @@ impl DAGNode for STTProviderNode {
         );
 
         // Get STT provider from registry
-        let registry = crate::plugin::global_registry();
+        let registry = ctx.plugin_registry();
 
         // Build STT configuration
         let stt_config = crate::core::stt::STTConfig {
@@ -551,7 +551,7 @@ impl DAGNode for TTSProviderNode {
         );
 
         // Get TTS provider from registry
-        let registry = crate::plugin::global_registry();
+        let registry = ctx.plugin_registry();
 
         // Build TTS configuration
         let tts_config = crate::core::tts::TTSConfig {
@@ -888,7 +888,7 @@ impl DAGNode for RealtimeProviderNode {
         };
 
         // Get realtime provider from registry
-        let registry = crate::plugin::global_registry();
+        let registry = ctx.plugin_registry();
 
         // Build realtime configuration
         let realtime_config = RealtimeConfig {
@@ -3,30 +3,90 @@
 //! This module provides OpenAPI specification generation for the WaaV Gateway API.
 //! It is only compiled when the `openapi` feature is enabled.
 //! The spec can be generated via CLI command: `cargo run --features openapi -- openapi -o docs/openapi.yaml`
+//!
+//! Every router in [`crate::routes`] documents the paths it mounts with its own
+//! `*RoutesDoc`; [`spec`] merges them into [`ApiDoc`], which carries the API
+//! info, shared schemas and tags. Routers behind a cargo feature contribute
+//! their paths only when the feature is compiled in and selected as an
+//! [`ApiFeature`].
+
+use std::fmt;
+use std::str::FromStr;
 
 use utoipa::OpenApi;
 
-use crate::core::tts::Pronunciation;
+use crate::agents::AgentProfile;
+use crate::build_info::BuildInfo;
+use crate::config::{
+    AdminScope, FeatureFlagConfig, FeatureFlags, LoadSheddingConfig, ProviderVoice, VoiceProfile,
+};
+use crate::core::providers::connection_budget::ConnectionBudgetStatus;
+use crate::core::providers::credential_health::{CredentialHealthStatus, CredentialState};
+use crate::core::redaction::RedactionSource;
+use crate::core::session::{
+    AudioDirection, BargeInMode, ConsentConfig, ConsentSource, ConsentState, EchoGuardConfig,
+    LatencyBudgetAction, LatencyBudgetConfig, LatencyStage, PendingAudioPolicy,
+    PipelineWatchdogConfig, RetryBudgetConfig, RetryPriority, WatchdogStage,
+};
+use crate::core::stt::{RedactedSpan, TranscriptTiming, UtteranceKind, WordTiming};
+use crate::core::tts::{Pronunciation, TTSOutputProfile};
+use crate::core::turn_detect::{TurnDetectionMode, TurnDetectorHealth};
+use crate::core::validation::ConfigIssue;
+use crate::core::voice_manager::{
+    AdaptiveEndpointingConfig, AudioQualityIssue, FillerAudioConfig, TTSAudioQualityConfig,
+    TurnDetectionDegradedReason,
+};
 use crate::handlers::{
-    api::HealthResponse,
+    admin_audit::AdminAuditResponse,
+    agents::{AgentProfileEntry, AgentProfilesResponse},
+    api::{
+        HealthResponse, ProviderHealthResponse, ReadinessResponse, SelfTestReadiness,
+        VersionResponse,
+    },
+    feature_flags::FeatureFlagsResponse,
     livekit::{
         ListRoomsResponse, MuteParticipantRequest, MuteParticipantResponse, ParticipantInfo,
         RemoveParticipantErrorResponse, RemoveParticipantRequest, RemoveParticipantResponse,
         RoomDetailsResponse, RoomInfo, TokenRequest, TokenResponse,
     },
+    providers::{ProvidersResponse, ValidateCredentialsRequest, ValidateCredentialsResponse},
+    reconciliation::ReconciliationResponse,
+    replay::ReplayJobsResponse,
+    session_artifacts::SessionArtifactsResponse,
+    session_events::MonitorKeyResponse,
     sip::{
         DeleteSipHooksRequest, SIPTransferErrorResponse, SIPTransferRequest, SIPTransferResponse,
         SipHookEntry, SipHooksErrorResponse, SipHooksRequest, SipHooksResponse,
     },
     speak::SpeakRequest,
     voices::Voice,
+    webhooks::PendingWebhooksResponse,
     ws::{
-        config::{LiveKitWebSocketConfig, STTWebSocketConfig, TTSWebSocketConfig},
+        config::{
+            LiveKitWebSocketConfig, STTFailoverWebSocketConfig, STTRoutingWebSocketConfig,
+            STTWebSocketConfig, TTSWebSocketConfig,
+        },
+        heartbeat::HeartbeatMode,
         messages::{IncomingMessage, OutgoingMessage, ParticipantDisconnectedInfo, UnifiedMessage},
+        redaction::RedactionState,
     },
 };
+use crate::plugin::DynamicPluginInfo;
+use crate::plugin::{ModelPrice, ProviderPrices};
+use crate::recording_upload::RecordingUploadStatus;
+use crate::replay::{
+    ReplayJobInfo, ReplayJobStatus, ReplayReport, ReplayRequest, ReplaySessionConfig, ReplaySpeed,
+};
+use crate::selftest::{SelfTestReport, SelfTestStatus};
+use crate::session_export::{ArtifactKind, ArtifactLinks};
+use crate::state::{AdminAuditEntry, CallEndReason, LoadSheddingStatus, ShedReason};
+use crate::usage::ReconciliationSummary;
+use crate::webhooks::{DropReason, DroppedWebhook, QueuedWebhook, WebhookBacklog, WebhookReplay};
 
 /// OpenAPI documentation structure
+///
+/// Holds everything but the paths, which the routers contribute; use [`spec`]
+/// for the complete document.
 #[derive(OpenApi)]
 #[openapi(
     info(
@@ -42,24 +102,26 @@ use crate::handlers::{
         (url = "https://api.waav.ai", description = "Production API"),
         (url = "http://localhost:3001", description = "Local development")
     ),
-    paths(
-        crate::handlers::api::health_check,
-        crate::handlers::voices::list_voices,
-        crate::handlers::speak::speak_handler,
-        crate::handlers::livekit::generate_token,
-        crate::handlers::livekit::list_rooms,
-        crate::handlers::livekit::get_room_details,
-        crate::handlers::livekit::remove_participant,
-        crate::handlers::livekit::mute_participant,
-        crate::handlers::recording::download_recording,
-        crate::handlers::sip::list_sip_hooks,
-        crate::handlers::sip::update_sip_hooks,
-        crate::handlers::sip::delete_sip_hooks,
-        crate::handlers::sip::sip_transfer,
-    ),
     components(schemas(
         // REST API types
         HealthResponse,
+        ReadinessResponse,
+        SelfTestReadiness,
+        TurnDetectorHealth,
+        TurnDetectionMode,
+        SelfTestReport,
+        SelfTestStatus,
+        ProviderHealthResponse,
+        CredentialHealthStatus,
+        CredentialState,
+        ConnectionBudgetStatus,
+        VersionResponse,
+        BuildInfo,
+        DynamicPluginInfo,
+        MonitorKeyResponse,
+        SessionArtifactsResponse,
+        ArtifactLinks,
+        ArtifactKind,
         Voice,
         SpeakRequest,
         TokenRequest,
@@ -84,25 +146,110 @@ use crate::handlers::{
         SIPTransferRequest,
         SIPTransferResponse,
         SIPTransferErrorResponse,
+        // Provider types
+        ProvidersResponse,
+        VoiceProfile,
+        ProviderVoice,
+        ProviderPrices,
+        ModelPrice,
+        ValidateCredentialsRequest,
+        ValidateCredentialsResponse,
+        // Agent profile types
+        AgentProfile,
+        AgentProfileEntry,
+        AgentProfilesResponse,
+        // Load shedding types
+        LoadSheddingConfig,
+        LoadSheddingStatus,
+        ShedReason,
+        // Recording upload types
+        RecordingUploadStatus,
+        // Feature flag types
+        FeatureFlagConfig,
+        FeatureFlags,
+        FeatureFlagsResponse,
+        // Recording replay types
+        ReplayRequest,
+        ReplaySessionConfig,
+        ReplaySpeed,
+        ReplayJobInfo,
+        ReplayJobStatus,
+        ReplayJobsResponse,
+        ReplayReport,
+        // Admin audit types
+        AdminAuditResponse,
+        AdminAuditEntry,
+        AdminScope,
+        // Usage reconciliation types
+        ReconciliationResponse,
+        ReconciliationSummary,
+        // Webhook queue types
+        PendingWebhooksResponse,
+        WebhookBacklog,
+        QueuedWebhook,
+        DroppedWebhook,
+        DropReason,
+        WebhookReplay,
         // WebSocket message types
         IncomingMessage,
         OutgoingMessage,
         UnifiedMessage,
         ParticipantDisconnectedInfo,
+        CallEndReason,
+        AudioDirection,
+        HeartbeatMode,
+        RedactionState,
+        RedactionSource,
+        ConsentState,
+        ConsentSource,
+        RedactedSpan,
+        TranscriptTiming,
+        WordTiming,
+        UtteranceKind,
+        ConfigIssue,
         // Configuration types
         STTWebSocketConfig,
+        STTFailoverWebSocketConfig,
+        STTRoutingWebSocketConfig,
         TTSWebSocketConfig,
         LiveKitWebSocketConfig,
         Pronunciation,
+        AdaptiveEndpointingConfig,
+        TTSAudioQualityConfig,
+        AudioQualityIssue,
+        FillerAudioConfig,
+        TurnDetectionDegradedReason,
+        BargeInMode,
+        EchoGuardConfig,
+        LatencyBudgetConfig,
+        LatencyBudgetAction,
+        LatencyStage,
+        PipelineWatchdogConfig,
+        WatchdogStage,
+        ConsentConfig,
+        PendingAudioPolicy,
+        RetryBudgetConfig,
+        RetryPriority,
+        TTSOutputProfile,
     )),
     modifiers(&SecurityAddon),
     tags(
-        (name = "health", description = "Health check endpoints"),
+        (name = "health", description = "Health, readiness, metrics and version endpoints"),
         (name = "voices", description = "TTS voice management"),
         (name = "tts", description = "Text-to-speech synthesis"),
         (name = "livekit", description = "LiveKit room and token management"),
         (name = "recordings", description = "Recording download operations"),
+        (name = "sessions", description = "Observing active sessions"),
         (name = "sip", description = "SIP webhook configuration management"),
+        (name = "providers", description = "Provider discovery and administration"),
+        (name = "admin", description = "Admin audit log (admin only)"),
+        (name = "agents", description = "Agent profile management (admin only)"),
+        (name = "load_shedding", description = "Load shedding thresholds (admin only)"),
+        (name = "feature_flags", description = "Session feature flags (admin only)"),
+        (name = "replay", description = "Recording replay jobs (admin only)"),
+        (name = "usage", description = "Usage reconciliation (admin only)"),
+        (name = "webhooks", description = "Outbound webhook queue (admin only)"),
+        (name = "plugins", description = "HTTP routes served by plugins"),
         (name = "websocket", description = "WebSocket API for real-time communication")
     )
 )]
@@ -132,26 +279,201 @@ impl utoipa::Modify for SecurityAddon {
     }
 }
 
+/// Endpoint group compiled in behind a cargo feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiFeature {
+    /// DAG templates and validation (`dag-routing`)
+    DagRouting,
+    /// Chaos fault injection (`chaos`)
+    Chaos,
+}
+
+impl ApiFeature {
+    /// All feature-gated endpoint groups
+    pub const ALL: [ApiFeature; 2] = [ApiFeature::DagRouting, ApiFeature::Chaos];
+
+    /// Cargo feature name
+    pub fn name(self) -> &'static str {
+        match self {
+            ApiFeature::DagRouting => "dag-routing",
+            ApiFeature::Chaos => "chaos",
+        }
+    }
+
+    /// Whether the feature is compiled into this binary
+    pub fn is_compiled(self) -> bool {
+        match self {
+            ApiFeature::DagRouting => cfg!(feature = "dag-routing"),
+            ApiFeature::Chaos => cfg!(feature = "chaos"),
+        }
+    }
+
+    /// The features compiled into this binary
+    pub fn compiled() -> Vec<ApiFeature> {
+        Self::ALL.into_iter().filter(|f| f.is_compiled()).collect()
+    }
+
+    /// Parse a comma-separated feature list, as given to `--include-features`
+    ///
+    /// # Returns
+    /// * `Ok(features)` if every name is a known, compiled-in feature
+    /// * `Err(String)` naming the first unknown or missing feature
+    pub fn parse_list(list: &str) -> Result<Vec<ApiFeature>, String> {
+        let mut features = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let feature: ApiFeature = name.parse()?;
+            if !feature.is_compiled() {
+                return Err(format!(
+                    "feature '{name}' is not compiled in; rebuild with --features {name}"
+                ));
+            }
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+        Ok(features)
+    }
+
+    fn openapi(self) -> Option<utoipa::openapi::OpenApi> {
+        match self {
+            #[cfg(feature = "dag-routing")]
+            ApiFeature::DagRouting => Some(crate::routes::api::DagRoutesDoc::openapi()),
+            #[cfg(not(feature = "dag-routing"))]
+            ApiFeature::DagRouting => None,
+            #[cfg(feature = "chaos")]
+            ApiFeature::Chaos => Some(crate::routes::admin::ChaosRoutesDoc::openapi()),
+            #[cfg(not(feature = "chaos"))]
+            ApiFeature::Chaos => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ApiFeature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.name() == s)
+            .ok_or_else(|| {
+                let known: Vec<_> = Self::ALL.iter().map(|f| f.name()).collect();
+                format!(
+                    "unknown feature '{s}' (expected one of: {})",
+                    known.join(", ")
+                )
+            })
+    }
+}
+
+/// Build the OpenAPI spec of every router plus the selected feature groups
+///
+/// Features that are not compiled into this binary are skipped.
+pub fn spec(features: &[ApiFeature]) -> utoipa::openapi::OpenApi {
+    use crate::routes::{admin, api, downloads, public, realtime, webhooks, ws};
+
+    let mut doc = ApiDoc::openapi();
+    for routes in [
+        public::PublicRoutesDoc::openapi(),
+        api::ApiRoutesDoc::openapi(),
+        admin::AdminRoutesDoc::openapi(),
+        ws::WsRoutesDoc::openapi(),
+        realtime::RealtimeRoutesDoc::openapi(),
+        webhooks::WebhookRoutesDoc::openapi(),
+        downloads::DownloadRoutesDoc::openapi(),
+    ] {
+        doc.merge(routes);
+    }
+    for feature in features {
+        if let Some(routes) = feature.openapi() {
+            doc.merge(routes);
+        }
+    }
+    doc
+}
+
 /// Get OpenAPI spec as YAML string
 ///
 /// This is used for the CLI export command to generate docs/openapi.yaml
-pub fn spec_yaml() -> Result<String, serde_yaml::Error> {
-    serde_yaml::to_string(&ApiDoc::openapi())
+pub fn spec_yaml(features: &[ApiFeature]) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(&spec(features))
 }
 
 /// Get OpenAPI spec as JSON string
-pub fn spec_json() -> Result<String, serde_json::Error> {
-    serde_json::to_string_pretty(&ApiDoc::openapi())
+pub fn spec_json(features: &[ApiFeature]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&spec(features))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    use axum::Router;
+
+    use crate::routes;
+    use crate::state::AppState;
+
+    /// Mounted paths deliberately left out of the spec
+    const UNDOCUMENTED_PATHS: &[&str] = &[
+        // The plugin route with an empty path; documented as /plugins/{plugin_id}/{path}
+        "/plugins/{plugin_id}",
+        // The development console's static page and assets, not an API
+        "/console",
+        "/console/",
+        "/console/{file_name}",
+    ];
+
+    /// DAG stubs answering 501 when the `dag-routing` feature is off
+    const DAG_STUB_PATHS: &[&str] = &[
+        "/dag/templates",
+        "/dag/templates/{template_name}",
+        "/dag/validate",
+    ];
+
+    /// Every router main.rs mounts, without middleware
+    fn gateway_router() -> Router<Arc<AppState>> {
+        let router = routes::public::create_public_router();
+        #[cfg(feature = "dev-console")]
+        let router = router.merge(routes::console::create_console_router());
+        router
+            .merge(routes::webhooks::create_webhook_router())
+            .merge(routes::downloads::create_download_router())
+            .merge(routes::api::create_api_router())
+            .merge(routes::admin::create_admin_router())
+            .merge(routes::ws::create_ws_router())
+            .merge(routes::realtime::create_realtime_router())
+    }
+
+    /// Paths mounted on a router, in OpenAPI notation
+    ///
+    /// axum does not expose its routes, but its `Debug` output lists every
+    /// mounted path as a quoted string. Wildcards (`{*key}`) are written as
+    /// plain parameters (`{key}`), as in the spec.
+    fn mounted_paths(router: &Router<Arc<AppState>>) -> BTreeSet<String> {
+        let debug = format!("{router:?}");
+        debug
+            .match_indices("\"/")
+            .filter_map(|(start, _)| {
+                let path = &debug[start + 1..];
+                path.find('"').map(|end| &path[..end])
+            })
+            .filter(|path| !path.contains("__private__axum"))
+            .map(|path| path.replace("{*", "{"))
+            .collect()
+    }
+
     #[test]
     fn test_openapi_spec_generation() {
         // Ensure the OpenAPI spec can be generated without errors
-        let spec = ApiDoc::openapi();
+        let spec = spec(&ApiFeature::compiled());
         assert_eq!(spec.info.title, "WaaV Gateway API");
         assert_eq!(spec.info.version, "0.1.0");
     }
@@ -159,7 +481,7 @@ mod tests {
     #[test]
     fn test_yaml_export() {
         // Ensure YAML export works
-        let yaml = spec_yaml();
+        let yaml = spec_yaml(&ApiFeature::compiled());
         assert!(yaml.is_ok());
         let yaml_str = yaml.unwrap();
         assert!(yaml_str.contains("WaaV Gateway API"));
@@ -168,9 +490,72 @@ mod tests {
     #[test]
     fn test_json_export() {
         // Ensure JSON export works
-        let json = spec_json();
+        let json = spec_json(&ApiFeature::compiled());
         assert!(json.is_ok());
         let json_str = json.unwrap();
         assert!(json_str.contains("WaaV Gateway API"));
     }
+
+    #[test]
+    fn test_spec_covers_every_mounted_route() {
+        let mounted = mounted_paths(&gateway_router());
+        // Guard against the Debug output changing shape and matching nothing
+        assert!(mounted.contains("/ws"), "no routes found: {mounted:?}");
+        assert!(mounted.contains("/downloads/{key}"));
+
+        let mut excluded = UNDOCUMENTED_PATHS.to_vec();
+        if !cfg!(feature = "dag-routing") {
+            excluded.extend_from_slice(DAG_STUB_PATHS);
+        }
+        let spec = spec(&ApiFeature::compiled());
+        let missing: Vec<_> = mounted
+            .iter()
+            .filter(|path| !excluded.contains(&path.as_str()))
+            .filter(|path| !spec.paths.paths.contains_key(path.as_str()))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "mounted routes missing from the OpenAPI spec: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn test_feature_groups_follow_selection() {
+        let base = spec(&[]);
+        assert!(!base.paths.paths.contains_key("/admin/chaos"));
+        assert!(!base.paths.paths.contains_key("/dag/validate"));
+        assert!(base.paths.paths.contains_key("/metrics"));
+        assert!(base.paths.paths.contains_key("/admin/reconciliation"));
+
+        let full = spec(&ApiFeature::compiled());
+        assert_eq!(
+            full.paths.paths.contains_key("/admin/chaos"),
+            cfg!(feature = "chaos")
+        );
+        assert_eq!(
+            full.paths.paths.contains_key("/dag/validate"),
+            cfg!(feature = "dag-routing")
+        );
+    }
+
+    #[test]
+    fn test_parse_feature_list() {
+        assert_eq!(ApiFeature::parse_list(""), Ok(vec![]));
+        assert!(
+            ApiFeature::parse_list("metrics")
+                .unwrap_err()
+                .contains("unknown feature 'metrics'")
+        );
+
+        let chaos = ApiFeature::parse_list("chaos, chaos");
+        if cfg!(feature = "chaos") {
+            assert_eq!(chaos, Ok(vec![ApiFeature::Chaos]));
+        } else {
+            assert!(chaos.unwrap_err().contains("not compiled in"));
+        }
+        assert_eq!(
+            "dag-routing".parse::<ApiFeature>(),
+            Ok(ApiFeature::DagRouting)
+        );
+    }
 }
@@ -0,0 +1,93 @@
+//! One finished session and its enriched transcript
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::session::{
+    SpeakerSegment, SpeechActivity, TranscriptEntry, annotate_speakers, diarize,
+};
+use crate::state::SessionMetadata;
+
+/// Enriched transcript stored next to a recording
+pub const TRANSCRIPT_EXPORT_FILE: &str = "transcript.json";
+
+/// Webhook event announcing an enriched transcript
+pub const TRANSCRIPT_ENRICHED_EVENT: &str = "transcript.enriched";
+
+/// A finished session waiting to be enriched
+#[derive(Debug, Clone)]
+pub struct EnrichmentJob {
+    /// Stream ID of the session
+    pub session_id: String,
+    /// Tenant the session belongs to
+    pub auth_id: Option<String>,
+    /// Transcript exported before the session was closed
+    pub transcript: Vec<TranscriptEntry>,
+    /// Speech timeline recorded by the session
+    pub activity: SpeechActivity,
+    /// The session's metadata when the job was queued
+    pub metadata: SessionMetadata,
+}
+
+/// Transcript of a finished session labelled with speakers
+///
+/// Stored as [`TRANSCRIPT_EXPORT_FILE`] and sent as the body of the
+/// `transcript.enriched` webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnrichedTranscript {
+    /// Always `transcript.enriched`
+    pub event: String,
+    /// Unique ID of this event (`X-WaaV-Event-Id`)
+    pub event_id: String,
+    /// Stream ID of the session
+    pub session_id: String,
+    /// Tenant the session belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_id: Option<String>,
+    /// Object key of the stored transcript, when it was stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_key: Option<String>,
+    /// Entries labelled by this pass
+    pub annotated_entries: usize,
+    /// Speech segments found in the session, in time order
+    pub segments: Vec<SpeakerSegment>,
+    /// The full transcript with speakers
+    pub transcript: Vec<TranscriptEntry>,
+    /// When the transcript was enriched (Unix ms)
+    pub enriched_at: u64,
+    /// The session's metadata, including the entries the gateway recorded
+    #[serde(default, skip_serializing_if = "SessionMetadata::is_empty")]
+    pub metadata: SessionMetadata,
+}
+
+/// Diarize a finished session and label its transcript
+pub fn enrich(job: EnrichmentJob) -> EnrichedTranscript {
+    let EnrichmentJob {
+        session_id,
+        auth_id,
+        mut transcript,
+        activity,
+        metadata,
+    } = job;
+
+    let segments = diarize(&activity);
+    let annotated_entries = annotate_speakers(&mut transcript, &segments);
+
+    EnrichedTranscript {
+        event: TRANSCRIPT_ENRICHED_EVENT.to_string(),
+        event_id: Uuid::new_v4().to_string(),
+        session_id,
+        auth_id,
+        transcript_key: None,
+        annotated_entries,
+        segments,
+        transcript,
+        enriched_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        metadata,
+    }
+}
@@ -0,0 +1,24 @@
+//! # Transcript Enrichment
+//!
+//! With [`TranscriptEnrichmentConfig`](crate::config::TranscriptEnrichmentConfig)
+//! set, voice sessions record their [speech activity](crate::core::session::SpeechActivity)
+//! and each finished session is queued for a background worker, which:
+//!
+//! 1. diarizes the session into bot and caller segments and labels the
+//!    transcript entries that have no `speaker` yet
+//! 2. stores the result as `transcript.json` next to the session's
+//!    recording (`{prefix}/{auth_id}/{stream_id}/transcript.json`) when a
+//!    recording bucket is configured
+//! 3. POSTs it as a `transcript.enriched` event to the configured webhook,
+//!    signed with the `X-WaaV-Signature` headers used for SIP hook
+//!    forwarding
+//!
+//! Sessions finishing while the queue is full are not enriched.
+
+mod job;
+mod workers;
+
+pub use job::{
+    EnrichedTranscript, EnrichmentJob, TRANSCRIPT_ENRICHED_EVENT, TRANSCRIPT_EXPORT_FILE, enrich,
+};
+pub use workers::{EnrichmentError, EnrichmentWorkers};
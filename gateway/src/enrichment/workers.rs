@@ -0,0 +1,354 @@
+//! Background workers that enrich finished sessions
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use object_store::{ObjectStore, path::Path as ObjectPath};
+use thiserror::Error;
+use tokio::sync::{Mutex, mpsc};
+use tracing::{debug, info, warn};
+
+use super::job::{EnrichedTranscript, EnrichmentJob, TRANSCRIPT_EXPORT_FILE, enrich};
+use crate::config::TranscriptEnrichmentConfig;
+use crate::handlers::recording::session_object_key;
+use crate::utils::webhook_signing::generate_webhook_signature;
+use crate::webhooks::{ENRICHMENT_DESTINATION, WebhookDispatcher};
+
+/// Timeout for a `transcript.enriched` webhook request
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors raised while queueing or delivering an enriched transcript
+#[derive(Debug, Error)]
+pub enum EnrichmentError {
+    #[error("Transcript enrichment queue is full")]
+    QueueFull,
+
+    #[error("Transcript enrichment workers have stopped")]
+    Stopped,
+
+    #[error("Failed to serialize enriched transcript: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("Transcript webhook failed: {0}")]
+    Webhook(String),
+}
+
+/// Queue of finished sessions and the workers draining it
+///
+/// Dropping the last handle lets the workers finish the queued jobs and
+/// exit.
+pub struct EnrichmentWorkers {
+    queue: mpsc::Sender<EnrichmentJob>,
+}
+
+impl EnrichmentWorkers {
+    /// Start the workers of a validated configuration
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Arguments
+    /// * `config` - Pool sizes and webhook
+    /// * `store` - Recording bucket the transcripts are stored in, if any
+    /// * `prefix` - Key prefix of the recordings in the bucket
+    /// * `webhook_queue` - Durable queue the events are delivered through,
+    ///   as the `enrichment` destination, instead of being POSTed inline
+    ///
+    /// # Errors
+    /// Returns `EnrichmentError::Webhook` if the HTTP client cannot be built
+    pub fn start(
+        config: &TranscriptEnrichmentConfig,
+        store: Option<Arc<dyn ObjectStore>>,
+        prefix: Option<String>,
+        webhook_queue: Option<Arc<WebhookDispatcher>>,
+    ) -> Result<Self, EnrichmentError> {
+        let webhook = match (&config.webhook_url, &config.webhook_secret) {
+            (Some(url), Some(secret)) => {
+                let client = reqwest::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()
+                    .map_err(|e| EnrichmentError::Webhook(e.to_string()))?;
+                if let Some(queue) = &webhook_queue {
+                    queue.register(ENRICHMENT_DESTINATION, url, secret);
+                }
+                Some(EnrichmentWebhook {
+                    client,
+                    url: url.clone(),
+                    secret: secret.clone(),
+                    queue: webhook_queue,
+                })
+            }
+            _ => None,
+        };
+        let output = Arc::new(EnrichmentOutput {
+            store,
+            prefix,
+            webhook,
+        });
+
+        let (queue, jobs) = mpsc::channel(config.queue_size);
+        let jobs = Arc::new(Mutex::new(jobs));
+        for _ in 0..config.workers {
+            tokio::spawn(run_worker(jobs.clone(), output.clone()));
+        }
+
+        Ok(Self { queue })
+    }
+
+    /// Queue a finished session
+    ///
+    /// # Errors
+    /// Returns `EnrichmentError::QueueFull` if `queue_size` sessions are
+    /// already waiting; the session is not enriched
+    pub fn submit(&self, job: EnrichmentJob) -> Result<(), EnrichmentError> {
+        self.queue.try_send(job).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => EnrichmentError::QueueFull,
+            mpsc::error::TrySendError::Closed(_) => EnrichmentError::Stopped,
+        })
+    }
+}
+
+/// Take jobs off the shared queue until every sender is gone
+async fn run_worker(
+    jobs: Arc<Mutex<mpsc::Receiver<EnrichmentJob>>>,
+    output: Arc<EnrichmentOutput>,
+) {
+    loop {
+        let Some(job) = jobs.lock().await.recv().await else {
+            break;
+        };
+        let session_id = job.session_id.clone();
+        let enriched = enrich(job);
+        info!(
+            session_id = %session_id,
+            segments = enriched.segments.len(),
+            annotated_entries = enriched.annotated_entries,
+            "Transcript enriched"
+        );
+        if let Err(e) = output.deliver(enriched).await {
+            warn!(session_id = %session_id, "Failed to deliver enriched transcript: {}", e);
+        }
+    }
+    debug!("Transcript enrichment worker stopped");
+}
+
+/// Where enriched transcripts go
+struct EnrichmentOutput {
+    store: Option<Arc<dyn ObjectStore>>,
+    prefix: Option<String>,
+    webhook: Option<EnrichmentWebhook>,
+}
+
+impl EnrichmentOutput {
+    /// Store the transcript, then announce it
+    ///
+    /// A transcript that cannot be stored is still sent, without a
+    /// `transcript_key`.
+    async fn deliver(&self, mut enriched: EnrichedTranscript) -> Result<(), EnrichmentError> {
+        if let Some(store) = &self.store {
+            let key = session_object_key(
+                self.prefix.as_ref(),
+                enriched.auth_id.as_deref(),
+                &enriched.session_id,
+                TRANSCRIPT_EXPORT_FILE,
+            );
+            enriched.transcript_key = Some(key.clone());
+            let body = serde_json::to_vec(&enriched)?;
+            if let Err(e) = store
+                .put(&ObjectPath::from(key.as_str()), body.into())
+                .await
+            {
+                warn!(key = %key, "Failed to store enriched transcript: {}", e);
+                enriched.transcript_key = None;
+            }
+        }
+
+        if let Some(webhook) = &self.webhook {
+            let payload = serde_json::to_string(&enriched)?;
+            webhook
+                .post(&enriched.event_id, &enriched.session_id, payload)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Webhook `transcript.enriched` events are POSTed to
+struct EnrichmentWebhook {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+    /// Durable queue the events are handed to instead, if configured
+    queue: Option<Arc<WebhookDispatcher>>,
+}
+
+impl EnrichmentWebhook {
+    async fn post(
+        &self,
+        event_id: &str,
+        session_id: &str,
+        payload: String,
+    ) -> Result<(), EnrichmentError> {
+        if let Some(queue) = &self.queue {
+            queue
+                .enqueue(ENRICHMENT_DESTINATION, event_id, Some(session_id), payload)
+                .await;
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let signing_headers =
+            generate_webhook_signature(&self.secret, timestamp, event_id, &payload)
+                .map_err(EnrichmentError::Webhook)?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        for (key, value) in signing_headers {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| EnrichmentError::Webhook(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(EnrichmentError::Webhook(format!(
+                "{} responded with {}",
+                self.url, status
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::realtime::TranscriptRole;
+    use crate::core::session::{Speaker, SpeechActivity, SpeechInterval, TranscriptEntry};
+    use crate::state::SessionMetadata;
+    use axum::{Router, http::HeaderMap, routing::post};
+    use object_store::memory::InMemory;
+
+    const CALL_START: u64 = 100_000;
+
+    /// A call where the bot greets for two seconds and the caller answers
+    /// for one and a half, with the STT provider not diarizing
+    fn synthetic_call() -> EnrichmentJob {
+        let loud = |ms: u64| (1000..3000).contains(&ms) || (4000..5500).contains(&ms);
+        let activity = SpeechActivity {
+            started_at: CALL_START,
+            input_levels_db: (0..300)
+                .map(|frame| if loud(frame * 20) { -20.0 } else { -90.0 })
+                .collect(),
+            bot_speech: vec![SpeechInterval {
+                start_ms: CALL_START + 1000,
+                end_ms: CALL_START + 3000,
+            }],
+            truncated: false,
+        };
+        let entry = |role, text: &str, offset: u64| TranscriptEntry {
+            timestamp: CALL_START + offset,
+            ..TranscriptEntry::new(role, text, None)
+        };
+        EnrichmentJob {
+            session_id: "stream-1".to_string(),
+            auth_id: Some("tenant-a".to_string()),
+            transcript: vec![
+                entry(TranscriptRole::Assistant, "Hello, how can I help?", 1000),
+                entry(TranscriptRole::User, "I'd like to book a table", 5600),
+            ],
+            activity,
+            metadata: SessionMetadata::from([("customer_id".to_string(), "c-123".to_string())]),
+        }
+    }
+
+    /// Local webhook receiver that forwards each request
+    async fn webhook_receiver() -> (String, mpsc::UnboundedReceiver<(HeaderMap, String)>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/transcripts",
+            post(move |headers: HeaderMap, body: String| {
+                let tx = tx.clone();
+                async move {
+                    let _ = tx.send((headers, body));
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}/transcripts"), rx)
+    }
+
+    #[test]
+    fn test_enrich_labels_synthetic_call() {
+        let enriched = enrich(synthetic_call());
+
+        assert_eq!(enriched.event, "transcript.enriched");
+        assert_eq!(enriched.annotated_entries, 2);
+        let speakers: Vec<_> = enriched.segments.iter().map(|s| s.speaker).collect();
+        assert_eq!(speakers, [Speaker::Bot, Speaker::Caller]);
+        assert_eq!(enriched.transcript[0].speaker, Some(Speaker::Bot));
+        assert_eq!(enriched.transcript[1].speaker, Some(Speaker::Caller));
+        assert_eq!(enriched.metadata["customer_id"], "c-123");
+    }
+
+    #[tokio::test]
+    async fn test_workers_store_and_post_enriched_transcript() {
+        let store = Arc::new(InMemory::new());
+        let (url, mut requests) = webhook_receiver().await;
+        let config = TranscriptEnrichmentConfig {
+            webhook_url: Some(url),
+            webhook_secret: Some("transcript-signing-secret".to_string()),
+            ..Default::default()
+        };
+        let workers = EnrichmentWorkers::start(
+            &config,
+            Some(store.clone()),
+            Some("calls/".to_string()),
+            None,
+        )
+        .unwrap();
+
+        workers.submit(synthetic_call()).unwrap();
+
+        let (headers, body) = tokio::time::timeout(Duration::from_secs(5), requests.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let posted: EnrichedTranscript = serde_json::from_str(&body).unwrap();
+        assert_eq!(posted.session_id, "stream-1");
+        assert_eq!(
+            posted.transcript_key.as_deref(),
+            Some("calls/tenant-a/stream-1/transcript.json")
+        );
+        assert_eq!(posted.transcript[1].speaker, Some(Speaker::Caller));
+        assert_eq!(posted.metadata["customer_id"], "c-123");
+        assert_eq!(headers["X-WaaV-Event-Id"], posted.event_id.as_str());
+        assert!(
+            headers["X-WaaV-Signature"]
+                .to_str()
+                .unwrap()
+                .starts_with("v1=")
+        );
+
+        // The stored export is the payload that was posted
+        let stored = store
+            .get(&ObjectPath::from("calls/tenant-a/stream-1/transcript.json"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        let stored: EnrichedTranscript = serde_json::from_slice(&stored).unwrap();
+        assert_eq!(stored, posted);
+    }
+}
@@ -12,6 +12,8 @@ pub mod error_codes {
     pub const AUTH_SERVICE_UNAVAILABLE: &str = "auth_service_unavailable";
     pub const AUTH_SERVICE_ERROR: &str = "auth_service_error";
     pub const UNAUTHORIZED: &str = "unauthorized";
+    pub const FORBIDDEN: &str = "forbidden";
+    pub const MISSING_SCOPE: &str = "missing_scope";
     pub const JWT_SIGNING_ERROR: &str = "jwt_signing_error";
     pub const CONFIG_ERROR: &str = "config_error";
 }
@@ -39,6 +41,14 @@ pub enum AuthError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    /// Authenticated, but not permitted to access the resource (e.g., admin endpoints)
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    /// Authenticated, but the client does not hold the scope the route requires
+    #[error("Forbidden: missing scope {0}")]
+    MissingScope(String),
+
     /// JWT signing operation failed
     #[error("JWT signing error: {0}")]
     JwtSigningError(String),
@@ -65,6 +75,8 @@ impl AuthError {
             AuthError::AuthServiceUnavailable(_) => error_codes::AUTH_SERVICE_UNAVAILABLE,
             AuthError::AuthServiceError(_, _) => error_codes::AUTH_SERVICE_ERROR,
             AuthError::Unauthorized(_) => error_codes::UNAUTHORIZED,
+            AuthError::Forbidden(_) => error_codes::FORBIDDEN,
+            AuthError::MissingScope(_) => error_codes::MISSING_SCOPE,
             AuthError::JwtSigningError(_) => error_codes::JWT_SIGNING_ERROR,
             AuthError::ConfigError(_) => error_codes::CONFIG_ERROR,
             AuthError::HttpError(_) => error_codes::AUTH_SERVICE_UNAVAILABLE,
@@ -77,6 +89,7 @@ impl AuthError {
         match self {
             AuthError::MissingAuthHeader | AuthError::InvalidAuthHeader => StatusCode::UNAUTHORIZED,
             AuthError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AuthError::Forbidden(_) | AuthError::MissingScope(_) => StatusCode::FORBIDDEN,
             AuthError::AuthServiceUnavailable(_) | AuthError::HttpError(_) => {
                 StatusCode::SERVICE_UNAVAILABLE
             }
@@ -106,6 +119,12 @@ impl AuthError {
             AuthError::Unauthorized(msg) => {
                 tracing::warn!("Unauthorized: {}", msg);
             }
+            AuthError::Forbidden(msg) => {
+                tracing::warn!("Forbidden: {}", msg);
+            }
+            AuthError::MissingScope(scope) => {
+                tracing::warn!("Forbidden: missing scope {}", scope);
+            }
             AuthError::AuthServiceError(code, msg) => {
                 tracing::warn!("Auth service error ({}): {}", code, msg);
             }
@@ -139,12 +158,16 @@ impl IntoResponse for AuthError {
         let error_message = self.to_string();
 
         // Response format: {"error": "error_code", "message": "human readable message"}
-        let body = Json(json!({
+        // A missing scope is also named in a `scope` field
+        let mut body = json!({
             "error": error_code,
             "message": error_message
-        }));
+        });
+        if let AuthError::MissingScope(scope) = &self {
+            body["scope"] = json!(scope);
+        }
 
-        (status, body).into_response()
+        (status, Json(body)).into_response()
     }
 }
 
@@ -193,6 +216,10 @@ mod tests {
             AuthError::ConfigError("test".to_string()).status_code(),
             StatusCode::INTERNAL_SERVER_ERROR
         );
+        assert_eq!(
+            AuthError::Forbidden("test".to_string()).status_code(),
+            StatusCode::FORBIDDEN
+        );
     }
 
     #[test]
@@ -350,6 +377,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_into_response_missing_scope() {
+        use http_body_util::BodyExt;
+
+        let error = AuthError::MissingScope("admin:write".to_string());
+        let response = error.into_response();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let body_bytes = tokio_test::block_on(async {
+            response.into_body().collect().await.unwrap().to_bytes()
+        });
+        let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+
+        assert_eq!(body_json["error"], "missing_scope");
+        assert_eq!(body_json["message"], "Forbidden: missing scope admin:write");
+        assert_eq!(body_json["scope"], "admin:write");
+    }
+
     #[test]
     fn test_into_response_config_error() {
         use http_body_util::BodyExt;
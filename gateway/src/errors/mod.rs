@@ -1,2 +1,3 @@
 pub mod app_error;
 pub mod auth_error;
+pub mod provider_error;
@@ -0,0 +1,475 @@
+//! The log of one connection
+
+use std::fmt::Write;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use super::FlightRecorder;
+use crate::metrics::global_metrics;
+
+/// Format version written in the header of every log
+pub const FLIGHT_LOG_VERSION: u32 = 1;
+
+/// Replacement of scrubbed transcript text
+pub const SCRUBBED: &str = "[scrubbed]";
+
+/// Replacement of redacted secrets
+const REDACTED: &str = "[redacted]";
+
+/// Fields holding secrets, redacted at any depth
+const SECRET_FIELDS: &[&str] = &["api_key", "token"];
+
+/// Fields whose values are all secrets (header name to value maps)
+const SECRET_MAPS: &[&str] = &["custom_headers"];
+
+/// Fields holding what was said, scrubbed with `scrub_transcripts`
+const TEXT_FIELDS: &[&str] = &["transcript", "text"];
+
+/// Message types whose `message` field is chat text
+const CHAT_TYPES: &[&str] = &["message", "send_message"];
+
+/// First line of a log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlightLogHeader {
+    /// Format version ([`FLIGHT_LOG_VERSION`])
+    pub flight_recorder: u32,
+    /// Stream ID of the session
+    pub session_id: String,
+    /// When the connection started (Unix ms); entry times are relative to it
+    pub started_at: u64,
+}
+
+/// What an entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Text message from the client
+    Inbound,
+    /// Message sent to the client
+    Outbound,
+    /// Session config resolved by the gateway
+    Config,
+    /// Reason the connection was closed
+    Close,
+    /// Entries left out once the log reached its size cap
+    Truncated,
+}
+
+impl EntryKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Inbound => "inbound",
+            Self::Outbound => "outbound",
+            Self::Config => "config",
+            Self::Close => "close",
+            Self::Truncated => "truncated",
+        }
+    }
+}
+
+/// One line of a log after the header
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlightEntry {
+    /// Milliseconds since the connection started
+    pub t: u64,
+    pub kind: EntryKind,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+}
+
+/// Log of one connection, buffered until it is written to the cache
+pub struct FlightLog {
+    recorder: Arc<FlightRecorder>,
+    buffer: Mutex<LogBuffer>,
+}
+
+struct LogBuffer {
+    /// Cleared once the session discards its log or the connection ends
+    recording: bool,
+    session_id: Option<String>,
+    started_at: u64,
+    /// Entry lines, each ending with a newline
+    entries: String,
+    /// Entries left out since the log reached its size cap
+    dropped: u64,
+    /// Time of the last entry left out
+    dropped_t: u64,
+    /// Size of `entries` and `dropped` when the log was last written
+    flushed: (usize, u64),
+}
+
+impl FlightLog {
+    pub(super) fn new(recorder: Arc<FlightRecorder>, started_at: u64) -> Self {
+        Self {
+            recorder,
+            buffer: Mutex::new(LogBuffer {
+                recording: true,
+                session_id: None,
+                started_at,
+                entries: String::new(),
+                dropped: 0,
+                dropped_t: 0,
+                flushed: (0, 0),
+            }),
+        }
+    }
+
+    /// Record an entry now
+    pub fn record(&self, kind: EntryKind, data: Value) {
+        self.record_at(now_ms(), kind, data);
+    }
+
+    /// Record an entry that happened at `at_ms` (Unix ms)
+    ///
+    /// Secrets in `data` are redacted, and transcript text scrubbed if
+    /// configured. Once the log has reached its size cap the entry, and
+    /// every one after it, is only counted.
+    pub fn record_at(&self, at_ms: u64, kind: EntryKind, mut data: Value) {
+        scrub(&mut data, self.recorder.config().scrub_transcripts);
+
+        let mut buffer = self.buffer.lock();
+        if !buffer.recording {
+            return;
+        }
+        let t = at_ms.saturating_sub(buffer.started_at);
+        let Ok(line) = serde_json::to_string(&FlightEntry { t, kind, data }) else {
+            return;
+        };
+
+        let cap = self.recorder.config().max_bytes_per_session;
+        if buffer.dropped > 0 || buffer.entries.len() + line.len() + 1 > cap {
+            if buffer.dropped == 0 {
+                warn!(
+                    session_id = ?buffer.session_id,
+                    max_bytes = cap,
+                    "Flight log reached its size cap; later entries are left out"
+                );
+                global_metrics().inc_counter(
+                    "waav_flight_logs_truncated_total",
+                    "Session flight logs that reached their size cap",
+                    &[],
+                );
+            }
+            buffer.dropped += 1;
+            buffer.dropped_t = t;
+            return;
+        }
+        buffer.entries.push_str(&line);
+        buffer.entries.push('\n');
+    }
+
+    /// Store the log under the session's stream ID from now on
+    ///
+    /// The first call starts writing the log to the cache periodically. A
+    /// connection switching to another stream ID writes the log so far
+    /// under the previous ID and starts a new log.
+    pub async fn attach(self: &Arc<Self>, session_id: &str) {
+        let (first, previous) = {
+            let mut buffer = self.buffer.lock();
+            if !buffer.recording || buffer.session_id.as_deref() == Some(session_id) {
+                return;
+            }
+            let previous = match &buffer.session_id {
+                Some(previous) => {
+                    let contents = buffer.contents(previous);
+                    let previous = previous.clone();
+                    buffer.restart(now_ms());
+                    Some((previous, contents))
+                }
+                None => None,
+            };
+            let first = previous.is_none();
+            buffer.session_id = Some(session_id.to_string());
+            (first, previous)
+        };
+
+        if let Some((previous, contents)) = previous {
+            self.recorder.store(&previous, contents).await;
+        }
+        if first {
+            spawn_flusher(
+                Arc::downgrade(self),
+                Duration::from_secs(self.recorder.config().flush_interval_secs),
+            );
+        }
+    }
+
+    /// Stop recording and drop what was recorded; for sessions that did
+    /// not opt in
+    pub fn discard(&self) {
+        let mut buffer = self.buffer.lock();
+        if buffer.session_id.is_none() {
+            buffer.recording = false;
+            buffer.entries = String::new();
+        }
+    }
+
+    /// Whether entries are still being recorded
+    pub fn is_recording(&self) -> bool {
+        self.buffer.lock().recording
+    }
+
+    /// Write the log to the cache if it changed since the last write
+    pub async fn flush(&self) {
+        let pending = {
+            let mut buffer = self.buffer.lock();
+            let changed = buffer.flushed != (buffer.entries.len(), buffer.dropped);
+            match buffer.session_id.clone() {
+                Some(session_id) if changed => {
+                    buffer.flushed = (buffer.entries.len(), buffer.dropped);
+                    let contents = buffer.contents(&session_id);
+                    Some((session_id, contents))
+                }
+                _ => None,
+            }
+        };
+        if let Some((session_id, contents)) = pending {
+            self.recorder.store(&session_id, contents).await;
+        }
+    }
+
+    /// Write the log one last time and stop recording; called when the
+    /// connection ends
+    pub async fn finish(&self) {
+        self.flush().await;
+        self.buffer.lock().recording = false;
+    }
+
+    /// The log as it would be stored now, if a session is attached
+    pub fn contents(&self) -> Option<Bytes> {
+        let buffer = self.buffer.lock();
+        let session_id = buffer.session_id.as_deref()?;
+        Some(buffer.contents(session_id))
+    }
+}
+
+impl LogBuffer {
+    /// Header, entries and, if entries were left out, the `truncated` entry
+    fn contents(&self, session_id: &str) -> Bytes {
+        let header = FlightLogHeader {
+            flight_recorder: FLIGHT_LOG_VERSION,
+            session_id: session_id.to_string(),
+            started_at: self.started_at,
+        };
+        let mut contents = serde_json::to_string(&header).unwrap_or_default();
+        contents.push('\n');
+        contents.push_str(&self.entries);
+        if self.dropped > 0 {
+            let truncated = FlightEntry {
+                t: self.dropped_t,
+                kind: EntryKind::Truncated,
+                data: serde_json::json!({ "dropped": self.dropped }),
+            };
+            if let Ok(line) = serde_json::to_string(&truncated) {
+                let _ = writeln!(contents, "{line}");
+            }
+        }
+        Bytes::from(contents)
+    }
+
+    /// Empty the log for a new session starting at `now_ms`
+    fn restart(&mut self, now_ms: u64) {
+        self.started_at = now_ms;
+        self.entries = String::new();
+        self.dropped = 0;
+        self.dropped_t = 0;
+        self.flushed = (0, 0);
+    }
+}
+
+/// Write the log every `interval` until the connection ends
+fn spawn_flusher(log: Weak<FlightLog>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let Some(log) = log.upgrade() else {
+                break;
+            };
+            if !log.is_recording() {
+                break;
+            }
+            log.flush().await;
+        }
+    });
+}
+
+/// Redact secrets in `data`, and with `transcripts` replace what was said
+fn scrub(data: &mut Value, transcripts: bool) {
+    let chat = data
+        .get("type")
+        .and_then(Value::as_str)
+        .is_some_and(|kind| CHAT_TYPES.contains(&kind));
+    scrub_value(data, transcripts, chat);
+}
+
+fn scrub_value(value: &mut Value, transcripts: bool, chat: bool) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                let name = name.as_str();
+                if SECRET_FIELDS.contains(&name) {
+                    if field.as_str().is_some_and(|secret| !secret.is_empty()) {
+                        *field = Value::String(REDACTED.to_string());
+                    }
+                } else if SECRET_MAPS.contains(&name) {
+                    if let Value::Object(map) = field {
+                        map.values_mut()
+                            .for_each(|secret| *secret = Value::String(REDACTED.to_string()));
+                    }
+                } else if transcripts
+                    && (TEXT_FIELDS.contains(&name) || (chat && name == "message"))
+                    && field.is_string()
+                {
+                    *field = Value::String(SCRUBBED.to_string());
+                } else {
+                    scrub_value(field, transcripts, chat);
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| scrub_value(item, transcripts, chat)),
+        _ => {}
+    }
+}
+
+/// Current time in milliseconds since epoch
+pub(super) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FlightRecorderConfig;
+    use crate::core::cache::store::{CacheConfig, CacheStore};
+    use serde_json::json;
+
+    async fn recorder(config: FlightRecorderConfig) -> Arc<FlightRecorder> {
+        let cache = CacheStore::from_config(CacheConfig::Memory {
+            max_entries: 100,
+            max_size_bytes: None,
+            ttl_seconds: None,
+        })
+        .await
+        .unwrap();
+        Arc::new(FlightRecorder::new(config, Arc::new(cache)))
+    }
+
+    fn lines(log: &FlightLog) -> Vec<Value> {
+        let contents = log.contents().unwrap();
+        std::str::from_utf8(&contents)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_secrets_redacted_and_transcripts_scrubbed() {
+        let config = FlightRecorderConfig {
+            scrub_transcripts: true,
+            ..Default::default()
+        };
+        let recorder = recorder(config).await;
+        let log = Arc::new(FlightLog::new(recorder.clone(), 1_000));
+        log.record_at(
+            1_000,
+            EntryKind::Inbound,
+            json!({"type": "auth", "token": "secret-token"}),
+        );
+        log.record_at(
+            1_200,
+            EntryKind::Inbound,
+            json!({"type": "config", "stt_config": {"provider": "deepgram", "api_key": "dg-key",
+                "custom_headers": {"X-Api-Key": "abc"}}}),
+        );
+        log.record_at(
+            1_500,
+            EntryKind::Outbound,
+            json!({"type": "stt_result", "transcript": "my card number", "is_final": true}),
+        );
+        log.record_at(
+            1_600,
+            EntryKind::Inbound,
+            json!({"type": "send_message", "message": "hello", "topic": "chat"}),
+        );
+        log.attach("session-1").await;
+
+        let lines = lines(&log);
+        assert_eq!(
+            lines[0],
+            json!({"flight_recorder": 1, "session_id": "session-1", "started_at": 1_000})
+        );
+        assert_eq!(lines[1]["data"]["token"], "[redacted]");
+        assert_eq!(lines[2]["t"], 200);
+        assert_eq!(lines[2]["data"]["stt_config"]["api_key"], "[redacted]");
+        assert_eq!(
+            lines[2]["data"]["stt_config"]["custom_headers"]["X-Api-Key"],
+            "[redacted]"
+        );
+        assert_eq!(lines[2]["data"]["stt_config"]["provider"], "deepgram");
+        assert_eq!(lines[3]["data"]["transcript"], SCRUBBED);
+        assert_eq!(lines[3]["data"]["is_final"], true);
+        assert_eq!(lines[4]["data"]["message"], SCRUBBED);
+        assert_eq!(lines[4]["data"]["topic"], "chat");
+
+        log.finish().await;
+        assert!(recorder.load("session-1").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_size_cap_truncates_log() {
+        let config = FlightRecorderConfig {
+            max_bytes_per_session: 200,
+            ..Default::default()
+        };
+        let log = Arc::new(FlightLog::new(recorder(config).await, 0));
+        log.attach("session-1").await;
+        for t in 0..10 {
+            log.record_at(
+                t * 100,
+                EntryKind::Outbound,
+                json!({"type": "tts_playback_complete", "timestamp": t}),
+            );
+        }
+
+        let lines = lines(&log);
+        let kept = lines.len() - 2;
+        assert!(kept > 0 && kept < 10);
+        assert_eq!(
+            lines.last().unwrap(),
+            &json!({"t": 900, "kind": "truncated", "data": {"dropped": 10 - kept}})
+        );
+        let entries = lines[1..=kept]
+            .iter()
+            .map(|line| line.to_string().len() + 1)
+            .sum::<usize>();
+        assert!(entries <= 200);
+    }
+
+    #[tokio::test]
+    async fn test_discarded_log_records_nothing() {
+        let log = Arc::new(FlightLog::new(
+            recorder(FlightRecorderConfig::default()).await,
+            0,
+        ));
+        log.record_at(10, EntryKind::Inbound, json!({"type": "config"}));
+        log.discard();
+        log.record_at(20, EntryKind::Outbound, json!({"type": "ready"}));
+        log.attach("session-1").await;
+        assert!(!log.is_recording());
+        assert!(log.contents().is_none());
+    }
+}
@@ -0,0 +1,94 @@
+//! # Session Flight Recorder
+//!
+//! With [`FlightRecorderConfig`] set, `/ws` sessions whose config message
+//! has `"flight_recorder": true` keep a log of what happened to them:
+//!
+//! 1. every text message the client sent (`inbound`) and every message the
+//!    gateway sent back (`outbound`), which includes the events the session
+//!    published to its observers, provider state changes such as
+//!    `pipeline_recovered` or `voice_fallback`, and errors
+//! 2. the session config the gateway resolved (`config`), with its secrets
+//!    redacted
+//! 3. the reason the connection was closed (`close`)
+//!
+//! Audio frames are not recorded.
+//!
+//! The log is JSON lines: a header with the session ID and the Unix time
+//! the connection started, then one entry per line with its time in
+//! milliseconds since that start. It is buffered per connection and written
+//! to the cache backend under `flight:{stream_id}` every
+//! `flush_interval_secs` and when the session ends, where it stays for
+//! `retention_secs`. `GET /admin/sessions/{stream_id}/flight_log` downloads
+//! it and `waav-gateway session replay <file>` renders it as a timeline.
+//!
+//! A log stops growing at `max_bytes_per_session` and ends with a
+//! `truncated` entry counting what was left out. API keys, auth tokens and
+//! custom header values are always redacted; `scrub_transcripts` replaces
+//! transcript, speak and chat text as well.
+
+mod log;
+mod render;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use tracing::{debug, warn};
+
+pub use log::{EntryKind, FLIGHT_LOG_VERSION, FlightEntry, FlightLog, FlightLogHeader, SCRUBBED};
+pub use render::{FlightLogError, ParsedFlightLog, RenderOptions, parse_flight_log, render};
+
+use crate::config::FlightRecorderConfig;
+use crate::core::cache::store::{CacheStore, Result as CacheResult};
+
+/// Cache key prefix of the session logs
+const LOG_KEY_PREFIX: &str = "flight:";
+
+/// Cache key a session's log is stored under
+pub fn flight_log_key(session_id: &str) -> String {
+    format!("{LOG_KEY_PREFIX}{session_id}")
+}
+
+/// Starts the logs of connections and stores them in the cache backend
+pub struct FlightRecorder {
+    config: FlightRecorderConfig,
+    cache: Arc<CacheStore>,
+}
+
+impl FlightRecorder {
+    /// Create the recorder of a validated configuration
+    pub fn new(config: FlightRecorderConfig, cache: Arc<CacheStore>) -> Self {
+        Self { config, cache }
+    }
+
+    pub fn config(&self) -> &FlightRecorderConfig {
+        &self.config
+    }
+
+    /// Start the log of a new connection
+    ///
+    /// Entries are buffered until the session [attaches](FlightLog::attach)
+    /// its stream ID, and dropped if it [discards](FlightLog::discard) them.
+    pub fn begin(self: &Arc<Self>) -> Arc<FlightLog> {
+        Arc::new(FlightLog::new(self.clone(), log::now_ms()))
+    }
+
+    /// The stored log of a session, if any
+    pub async fn load(&self, session_id: &str) -> CacheResult<Option<Bytes>> {
+        self.cache.get(flight_log_key(session_id)).await
+    }
+
+    /// Write a session's log to the cache, replacing what was stored before
+    async fn store(&self, session_id: &str, contents: Bytes) {
+        let size = contents.len();
+        let ttl = Duration::from_secs(self.config.retention_secs);
+        match self
+            .cache
+            .put_with_ttl(flight_log_key(session_id), contents, ttl)
+            .await
+        {
+            Ok(()) => debug!(session_id = %session_id, size, "Stored flight log"),
+            Err(e) => warn!(session_id = %session_id, "Failed to store flight log: {}", e),
+        }
+    }
+}
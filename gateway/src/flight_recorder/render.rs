@@ -0,0 +1,191 @@
+//! Rendering of stored logs for `waav-gateway session replay`
+
+use std::fmt::Write;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use super::log::{EntryKind, FLIGHT_LOG_VERSION, FlightEntry, FlightLogHeader};
+
+/// Errors raised while reading a log
+#[derive(Debug, Error)]
+pub enum FlightLogError {
+    #[error("Flight log is empty")]
+    Empty,
+
+    #[error("Invalid flight log header: {0}")]
+    InvalidHeader(serde_json::Error),
+
+    #[error("Unsupported flight log version {0} (expected {FLIGHT_LOG_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid flight log entry on line {line}: {source}")]
+    InvalidEntry {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+/// A log read back from its JSON lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFlightLog {
+    pub header: FlightLogHeader,
+    /// Entries in the order they were recorded
+    pub entries: Vec<FlightEntry>,
+}
+
+/// How a log is rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Print the outbound messages as the text frames the gateway sent,
+    /// one per line, instead of the timeline
+    pub frames: bool,
+}
+
+/// Read a log from its JSON lines
+///
+/// # Errors
+/// Returns an error if the header is missing, invalid or of another version,
+/// or if an entry cannot be read
+pub fn parse_flight_log(contents: &str) -> Result<ParsedFlightLog, FlightLogError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines.next().ok_or(FlightLogError::Empty)?;
+    let header: FlightLogHeader =
+        serde_json::from_str(header).map_err(FlightLogError::InvalidHeader)?;
+    if header.flight_recorder != FLIGHT_LOG_VERSION {
+        return Err(FlightLogError::UnsupportedVersion(header.flight_recorder));
+    }
+
+    let entries = lines
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|source| FlightLogError::InvalidEntry {
+                line: index + 1,
+                source,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ParsedFlightLog { header, entries })
+}
+
+/// Render a log
+///
+/// The timeline has one line per entry: its time since the connection
+/// started, its kind, the message type and the rest of the message as
+/// compact JSON. `timestamp` fields are shown relative to the start as
+/// well. The output only depends on the log, so rendering a log again
+/// gives the same text.
+pub fn render(log: &ParsedFlightLog, options: RenderOptions) -> String {
+    let mut output = String::new();
+    if options.frames {
+        for entry in &log.entries {
+            if entry.kind == EntryKind::Outbound {
+                let _ = writeln!(output, "{}", entry.data);
+            }
+        }
+        return output;
+    }
+
+    let _ = writeln!(
+        output,
+        "session {} (started at {} Unix ms, {} entries)",
+        log.header.session_id,
+        log.header.started_at,
+        log.entries.len()
+    );
+    for entry in &log.entries {
+        let mut data = entry.data.clone();
+        let message_type = match (entry.kind, &mut data) {
+            (EntryKind::Inbound | EntryKind::Outbound, Value::Object(fields)) => fields
+                .remove("type")
+                .and_then(|kind| kind.as_str().map(str::to_string)),
+            _ => None,
+        };
+        if let Some(timestamp) = data.get_mut("timestamp")
+            && let Some(unix_ms) = timestamp.as_u64()
+            && unix_ms >= log.header.started_at
+        {
+            *timestamp = Value::String(offset(unix_ms - log.header.started_at));
+        }
+
+        let mut line = format!(
+            "{:>10}  {:<9} {}",
+            offset(entry.t),
+            entry.kind.as_str(),
+            message_type.as_deref().unwrap_or("-")
+        );
+        let empty = data.is_null() || data.as_object().is_some_and(|fields| fields.is_empty());
+        if !empty {
+            let _ = write!(line, " {data}");
+        }
+        let _ = writeln!(output, "{line}");
+    }
+    output
+}
+
+/// `+S.mmms`
+fn offset(ms: u64) -> String {
+    format!("+{}.{:03}s", ms / 1000, ms % 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = r#"{"flight_recorder":1,"session_id":"call-42","started_at":1700000000000}
+{"t":3,"kind":"inbound","data":{"type":"config","audio":true,"stt_config":{"provider":"deepgram","api_key":"[redacted]"}}}
+{"t":412,"kind":"config","data":{"barge_in":"immediate","stt":{"provider":"deepgram"}}}
+{"t":415,"kind":"outbound","data":{"type":"ready","stream_id":"call-42"}}
+{"t":2250,"kind":"outbound","data":{"type":"stt_result","transcript":"hello","is_final":true}}
+{"t":3100,"kind":"outbound","data":{"type":"tts_playback_complete","timestamp":1700000003099}}
+{"t":64020,"kind":"close","data":{"code":1000,"reason":"Client closed"}}
+"#;
+
+    #[test]
+    fn test_render_timeline() {
+        let log = parse_flight_log(LOG).unwrap();
+        assert_eq!(log.header.session_id, "call-42");
+        assert_eq!(log.entries.len(), 6);
+        assert_eq!(
+            render(&log, RenderOptions::default()),
+            "session call-42 (started at 1700000000000 Unix ms, 6 entries)\n\
+             \x20  +0.003s  inbound   config {\"audio\":true,\"stt_config\":{\"api_key\":\"[redacted]\",\"provider\":\"deepgram\"}}\n\
+             \x20  +0.412s  config    - {\"barge_in\":\"immediate\",\"stt\":{\"provider\":\"deepgram\"}}\n\
+             \x20  +0.415s  outbound  ready {\"stream_id\":\"call-42\"}\n\
+             \x20  +2.250s  outbound  stt_result {\"is_final\":true,\"transcript\":\"hello\"}\n\
+             \x20  +3.100s  outbound  tts_playback_complete {\"timestamp\":\"+3.099s\"}\n\
+             \x20 +64.020s  close     - {\"code\":1000,\"reason\":\"Client closed\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_render_frames() {
+        let log = parse_flight_log(LOG).unwrap();
+        assert_eq!(
+            render(&log, RenderOptions { frames: true }),
+            "{\"stream_id\":\"call-42\",\"type\":\"ready\"}\n\
+             {\"is_final\":true,\"transcript\":\"hello\",\"type\":\"stt_result\"}\n\
+             {\"timestamp\":1700000003099,\"type\":\"tts_playback_complete\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_logs() {
+        assert!(matches!(parse_flight_log(""), Err(FlightLogError::Empty)));
+        assert!(matches!(
+            parse_flight_log("{\"t\":1}"),
+            Err(FlightLogError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            parse_flight_log(r#"{"flight_recorder":9,"session_id":"s","started_at":0}"#),
+            Err(FlightLogError::UnsupportedVersion(9))
+        ));
+        let err = parse_flight_log(
+            "{\"flight_recorder\":1,\"session_id\":\"s\",\"started_at\":0}\n{\"t\":1,\"kind\":\"bogus\"}",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
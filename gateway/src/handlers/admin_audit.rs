@@ -0,0 +1,42 @@
+//! Admin audit log endpoint
+//!
+//! Serves the most recent mutating admin calls, each with the acting client
+//! id and response status. The log is kept in memory and lost on restart;
+//! the same entries are written to the `audit` tracing target.
+
+use axum::{Json, extract::State};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::state::{AdminAuditEntry, AppState};
+
+/// Recorded admin calls
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AdminAuditResponse {
+    /// Most recent mutating admin calls, oldest first
+    pub entries: Vec<AdminAuditEntry>,
+}
+
+/// Get the audit log of mutating admin calls
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/audit",
+        responses(
+            (status = 200, description = "Mutating admin calls", body = AdminAuditResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "admin"
+    )
+)]
+pub async fn get_admin_audit(State(state): State<Arc<AppState>>) -> Json<AdminAuditResponse> {
+    Json(AdminAuditResponse {
+        entries: state.admin_audit.entries(),
+    })
+}
@@ -0,0 +1,347 @@
+//! Agent profile administration endpoints
+//!
+//! Admin-only endpoints for managing the agent profiles clients select with
+//! `{"type": "config", "agent": "..."}`. Profiles defined in the application
+//! config are listed but cannot be changed here.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::agents::{AgentProfile, AgentProfileError, AgentStoreError};
+use crate::handlers::realtime::messages::RealtimeSessionConfig;
+use crate::handlers::ws::messages::IncomingMessage;
+use crate::state::AppState;
+
+/// An agent profile as returned by the admin API
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AgentProfileEntry {
+    #[serde(flatten)]
+    pub profile: AgentProfile,
+    /// Whether the profile comes from the application config (read-only)
+    pub read_only: bool,
+}
+
+/// Response listing all agent profiles
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AgentProfilesResponse {
+    /// Config profiles first, then profiles created through this API
+    pub agents: Vec<AgentProfileEntry>,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({"error": message.into()}))).into_response()
+}
+
+/// Check that the profile's sections parse as config messages
+///
+/// Catches typos in field values when the profile is saved rather than when
+/// the first client selects it.
+fn check_sections(profile: &AgentProfile) -> Result<(), String> {
+    if let Some(session) = &profile.session {
+        let mut fields = session.clone();
+        fields.insert("type".to_string(), Value::String("config".to_string()));
+        let msg: IncomingMessage = serde_json::from_value(Value::Object(fields))
+            .map_err(|e| format!("Invalid session configuration: {e}"))?;
+        msg.validate_size()
+            .map_err(|e| format!("Invalid session configuration: {e}"))?;
+    }
+    if let Some(realtime) = &profile.realtime {
+        serde_json::from_value::<RealtimeSessionConfig>(Value::Object(realtime.clone()))
+            .map_err(|e| format!("Invalid realtime configuration: {e}"))?;
+    }
+    Ok(())
+}
+
+async fn save_profile(state: &AppState, profile: AgentProfile) -> Response {
+    if let Err(e) = profile.validate() {
+        return error_response(StatusCode::BAD_REQUEST, e.to_string());
+    }
+    if let Err(e) = check_sections(&profile) {
+        return error_response(StatusCode::BAD_REQUEST, e);
+    }
+
+    let name = profile.name.clone();
+    let mut profiles = state.agent_profiles.write().await;
+    match profiles.upsert(profile.clone()).await {
+        Ok(created) => {
+            info!(agent = %name, created, "Saved agent profile");
+            let status = if created {
+                StatusCode::CREATED
+            } else {
+                StatusCode::OK
+            };
+            let entry = AgentProfileEntry {
+                profile,
+                read_only: false,
+            };
+            (status, Json(entry)).into_response()
+        }
+        Err(AgentStoreError::Profile(e @ AgentProfileError::Protected(_))) => {
+            error_response(StatusCode::METHOD_NOT_ALLOWED, e.to_string())
+        }
+        Err(AgentStoreError::Profile(e)) => error_response(StatusCode::BAD_REQUEST, e.to_string()),
+        Err(e) => {
+            error!(agent = %name, error = %e, "Failed to save agent profile");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to save agent profile: {e}"),
+            )
+        }
+    }
+}
+
+/// List agent profiles
+///
+/// Returns every profile, including those scoped to specific clients.
+/// Requires admin privileges when authentication is enabled.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/agents",
+        responses(
+            (status = 200, description = "List of agent profiles", body = AgentProfilesResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "agents"
+    )
+)]
+pub async fn list_agents(State(state): State<Arc<AppState>>) -> Response {
+    let profiles = state.agent_profiles.read().await;
+    let agents = profiles
+        .list()
+        .map(|profile| AgentProfileEntry {
+            profile: profile.clone(),
+            read_only: profiles.is_protected(&profile.name),
+        })
+        .collect();
+    (StatusCode::OK, Json(AgentProfilesResponse { agents })).into_response()
+}
+
+/// Get an agent profile
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/agents/{name}",
+        params(
+            ("name" = String, Path, description = "Agent profile name", example = "support-bot-en")
+        ),
+        responses(
+            (status = 200, description = "Agent profile", body = AgentProfileEntry),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope"),
+            (status = 404, description = "Agent profile not found")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "agents"
+    )
+)]
+pub async fn get_agent(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    let profiles = state.agent_profiles.read().await;
+    match profiles.get(&name) {
+        Some(profile) => {
+            let entry = AgentProfileEntry {
+                profile: profile.clone(),
+                read_only: profiles.is_protected(&name),
+            };
+            (StatusCode::OK, Json(entry)).into_response()
+        }
+        None => error_response(StatusCode::NOT_FOUND, format!("Agent '{name}' not found")),
+    }
+}
+
+/// Create an agent profile
+///
+/// The profile is persisted in the cache directory and available to new
+/// sessions immediately. Fails with 409 if a profile with the same name exists.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/admin/agents",
+        request_body = AgentProfile,
+        responses(
+            (status = 201, description = "Agent profile created", body = AgentProfileEntry),
+            (status = 400, description = "Invalid agent profile"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope"),
+            (status = 409, description = "Agent profile already exists"),
+            (status = 500, description = "Failed to persist agent profile")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "agents"
+    )
+)]
+pub async fn create_agent(
+    State(state): State<Arc<AppState>>,
+    Json(profile): Json<AgentProfile>,
+) -> Response {
+    if state
+        .agent_profiles
+        .read()
+        .await
+        .get(&profile.name)
+        .is_some()
+    {
+        return error_response(
+            StatusCode::CONFLICT,
+            format!("Agent '{}' already exists", profile.name),
+        );
+    }
+    save_profile(&state, profile).await
+}
+
+/// Create or replace an agent profile
+///
+/// The profile name is taken from the path. Profiles defined in the
+/// application config cannot be replaced.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        put,
+        path = "/admin/agents/{name}",
+        params(
+            ("name" = String, Path, description = "Agent profile name", example = "support-bot-en")
+        ),
+        request_body = AgentProfile,
+        responses(
+            (status = 200, description = "Agent profile replaced", body = AgentProfileEntry),
+            (status = 201, description = "Agent profile created", body = AgentProfileEntry),
+            (status = 400, description = "Invalid agent profile"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope"),
+            (status = 405, description = "Agent profile is defined in the application config"),
+            (status = 500, description = "Failed to persist agent profile")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "agents"
+    )
+)]
+pub async fn update_agent(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(mut profile): Json<AgentProfile>,
+) -> Response {
+    profile.name = name;
+    save_profile(&state, profile).await
+}
+
+/// Delete an agent profile
+///
+/// Sessions already configured from the profile are not affected.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        delete,
+        path = "/admin/agents/{name}",
+        params(
+            ("name" = String, Path, description = "Agent profile name", example = "support-bot-en")
+        ),
+        responses(
+            (status = 204, description = "Agent profile deleted"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope"),
+            (status = 404, description = "Agent profile not found"),
+            (status = 405, description = "Agent profile is defined in the application config"),
+            (status = 500, description = "Failed to persist agent profiles")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "agents"
+    )
+)]
+pub async fn delete_agent(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    let mut profiles = state.agent_profiles.write().await;
+    match profiles.remove(&name).await {
+        Ok(true) => {
+            info!(agent = %name, "Deleted agent profile");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("Agent '{name}' not found")),
+        Err(AgentStoreError::Profile(e)) => {
+            error_response(StatusCode::METHOD_NOT_ALLOWED, e.to_string())
+        }
+        Err(e) => {
+            error!(agent = %name, error = %e, "Failed to delete agent profile");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete agent profile: {e}"),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(session: Value) -> AgentProfile {
+        AgentProfile {
+            name: "support-bot-en".to_string(),
+            description: None,
+            clients: vec![],
+            overridable: vec![],
+            session: session.as_object().cloned(),
+            realtime: None,
+        }
+    }
+
+    #[test]
+    fn test_agent_profile_entry_serialization() {
+        let entry = AgentProfileEntry {
+            profile: profile(json!({"audio": false})),
+            read_only: true,
+        };
+
+        let json = serde_json::to_value(&entry).expect("Failed to serialize");
+        assert_eq!(json["name"], "support-bot-en");
+        assert_eq!(json["session"]["audio"], false);
+        assert_eq!(json["read_only"], true);
+    }
+
+    #[test]
+    fn test_check_sections_accepts_valid_session() {
+        let profile = profile(json!({
+            "tts_config": {"provider": "deepgram", "model": "aura-asteria-en"}
+        }));
+        assert!(check_sections(&profile).is_ok());
+    }
+
+    #[test]
+    fn test_check_sections_rejects_invalid_values() {
+        let profile = profile(json!({"tts_config": {"provider": 42}}));
+        let err = check_sections(&profile).unwrap_err();
+        assert!(err.starts_with("Invalid session configuration"), "{err}");
+
+        let mut realtime = profile.clone();
+        realtime.session = None;
+        realtime.realtime = json!({"temperature": "warm"}).as_object().cloned();
+        let err = check_sections(&realtime).unwrap_err();
+        assert!(err.starts_with("Invalid realtime configuration"), "{err}");
+    }
+}
@@ -1,6 +1,21 @@
-use axum::{http::StatusCode, response::Json};
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json},
+};
 use serde::{Deserialize, Serialize};
 
+use crate::build_info::BuildInfo;
+use crate::core::providers::connection_budget::ConnectionBudgetStatus;
+use crate::core::providers::credential_health::CredentialHealthStatus;
+use crate::core::turn_detect::TurnDetectorHealth;
+use crate::metrics::{METRICS_CONTENT_TYPE, global_metrics};
+use crate::recording_upload::RecordingUploadStatus;
+use crate::selftest::SelfTestReport;
+use crate::state::{AppState, LoadSheddingStatus};
+
 /// Health check response
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -28,3 +43,175 @@ pub async fn health_check() -> Result<Json<HealthResponse>, StatusCode> {
         status: "OK".to_string(),
     }))
 }
+
+/// Readiness check response
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`
+    #[cfg_attr(feature = "openapi", schema(example = "ready"))]
+    pub status: String,
+    /// Latest provider self-test result (omitted when the self-test is off)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selftest: Option<SelfTestReadiness>,
+    /// Current load and shedding state (omitted when load shedding is off)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_shedding: Option<LoadSheddingStatus>,
+    /// Whether the turn detection model loaded at startup. A failed load does
+    /// not affect readiness: sessions fall back to silence-based detection.
+    pub turn_detection: TurnDetectorHealth,
+    /// Caller audio recordings not uploaded in full yet (omitted when
+    /// recording upload is off). Pending uploads do not affect readiness.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recording_uploads: Option<RecordingUploadStatus>,
+}
+
+/// Provider self-test detail in the readiness response
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SelfTestReadiness {
+    /// Whether a passing self-test is required for readiness
+    pub required: bool,
+    /// Latest self-test report
+    #[serde(flatten)]
+    pub report: SelfTestReport,
+}
+
+/// Readiness check handler
+/// Returns 503 while a required provider self-test is pending or failing,
+/// or while new sessions are being shed
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/readyz",
+        responses(
+            (status = 200, description = "Server is ready", body = ReadinessResponse),
+            (status = 503, description = "A required provider self-test has not passed or new sessions are being shed", body = ReadinessResponse)
+        ),
+        tag = "health"
+    )
+)]
+pub async fn readiness_check(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let selftest = state.selftest.latest().map(|report| SelfTestReadiness {
+        required: state.selftest.is_required(),
+        report,
+    });
+    let load_shedding = if state.load_shedder.config().is_enabled() {
+        Some(state.load_shedding_status().await)
+    } else {
+        None
+    };
+    let recording_uploads = match &state.recording_uploads {
+        Some(uploads) => Some(uploads.status().await),
+        None => None,
+    };
+    let ready = !state.selftest.blocks_readiness()
+        && !load_shedding.as_ref().is_some_and(|status| status.shedding);
+
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (
+        code,
+        Json(ReadinessResponse {
+            status: status.to_string(),
+            selftest,
+            load_shedding,
+            turn_detection: state.core_state.turn_detector_health().clone(),
+            recording_uploads,
+        }),
+    )
+}
+
+/// Metrics handler
+/// Returns the gateway metrics in the Prometheus text format
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/metrics",
+        responses(
+            (status = 200, description = "Prometheus metrics", body = String, content_type = "text/plain")
+        ),
+        tag = "health"
+    )
+)]
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    // Refresh the load gauges so they are current at scrape time
+    state.load_shedding_status().await;
+    if let Some(uploads) = &state.recording_uploads {
+        uploads.status().await;
+    }
+    (
+        [(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)],
+        global_metrics().render(),
+    )
+}
+
+/// Provider credential health response
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProviderHealthResponse {
+    /// Every credential a token has been requested for, by provider and key fingerprint
+    pub credentials: Vec<CredentialHealthStatus>,
+    /// Open connections of every API key that connected, by provider and key fingerprint
+    pub connection_budgets: Vec<ConnectionBudgetStatus>,
+}
+
+/// Provider credential health handler
+/// Returns the cached health of provider credentials that use token exchange
+/// and the connection budget usage of each API key
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/health/providers",
+        responses(
+            (status = 200, description = "Provider credential health", body = ProviderHealthResponse)
+        ),
+        tag = "health"
+    )
+)]
+pub async fn provider_health(State(state): State<Arc<AppState>>) -> Json<ProviderHealthResponse> {
+    Json(ProviderHealthResponse {
+        credentials: state.credential_health.snapshot(),
+        connection_budgets: state.connection_budgets.snapshot(),
+    })
+}
+
+/// Version response
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VersionResponse {
+    #[serde(flatten)]
+    pub build: BuildInfo,
+    /// Seconds since the gateway started
+    #[cfg_attr(feature = "openapi", schema(example = 86400))]
+    pub uptime_secs: u64,
+}
+
+/// Version handler
+/// Returns the build, enabled features, loaded dynamic plugins and uptime
+/// of this gateway node
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/version",
+        responses(
+            (status = 200, description = "Gateway version and build details", body = VersionResponse)
+        ),
+        tag = "health"
+    )
+)]
+pub async fn version(State(state): State<Arc<AppState>>) -> Json<VersionResponse> {
+    Json(VersionResponse {
+        build: BuildInfo::collect(&state.plugin_registry),
+        uptime_secs: state.started_at.elapsed().as_secs(),
+    })
+}
@@ -0,0 +1,77 @@
+//! Chaos fault injection endpoints
+//!
+//! Admin-only endpoints for choosing which faults are injected into new
+//! sessions and reading back what was injected into recent ones. Only built
+//! with the `chaos` feature, and refused unless `chaos.enabled: true` is set
+//! in the server config. See [`crate::core::chaos`].
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::core::chaos::ChaosFaults;
+use crate::state::AppState;
+
+/// Get the current faults and the faults injected into recent sessions
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/chaos",
+        responses(
+            (status = 200, description = "Current and recently injected faults", body = crate::core::chaos::ChaosStatus),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "chaos"
+    )
+)]
+pub async fn get_chaos(State(state): State<Arc<AppState>>) -> Response {
+    (StatusCode::OK, Json(state.chaos.status())).into_response()
+}
+
+/// Replace the faults injected into new sessions
+///
+/// Sending `{}` stops injecting faults. Answers `409 Conflict` when chaos is
+/// not enabled in the server config.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        put,
+        path = "/admin/chaos",
+        request_body = ChaosFaults,
+        responses(
+            (status = 200, description = "Faults updated", body = crate::core::chaos::ChaosStatus),
+            (status = 400, description = "Invalid faults"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:chaos scope"),
+            (status = 409, description = "Chaos is not enabled in the server config")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "chaos"
+    )
+)]
+pub async fn update_chaos(
+    State(state): State<Arc<AppState>>,
+    Json(faults): Json<ChaosFaults>,
+) -> Response {
+    if let Err(e) = state.chaos.update(faults) {
+        let status = if state.chaos.is_enabled() {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::CONFLICT
+        };
+        return (status, Json(json!({"error": e}))).into_response();
+    }
+    (StatusCode::OK, Json(state.chaos.status())).into_response()
+}
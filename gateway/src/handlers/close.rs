@@ -0,0 +1,142 @@
+//! WebSocket close codes
+//!
+//! Every server-initiated close of a `/ws` or `/realtime` connection carries
+//! one of the application close codes below (4000-4999, reserved for
+//! applications by RFC 6455) with the reason's name as the close reason.
+//! Because some clients cannot read close frames, the same code is also sent
+//! in a final JSON error message right before the close frame.
+
+use axum::extract::ws::{CloseFrame, Message};
+
+/// Why the server closed a WebSocket connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CloseReason {
+    /// The client closed the connection or the session ended normally
+    Normal,
+    /// The client sent a frame the server could not process
+    ProtocolViolation,
+    /// Authentication was missing, invalid or not configured
+    AuthFailed,
+    /// No messages were received for too long
+    IdleTimeout,
+    /// The session reached its maximum duration
+    MaxDuration,
+    /// The client exceeded its usage quota
+    QuotaExceeded,
+    /// An administrator terminated the session
+    AdminTerminated,
+    /// A provider failed and the session cannot continue
+    ProviderFailure,
+    /// The server is shutting down
+    ShuttingDown,
+    /// The LiveKit participant the session served left the room
+    ParticipantLeft,
+    /// The client stopped answering heartbeat pings
+    HeartbeatTimeout,
+    /// The outbound call the session placed ended before it was answered
+    CallNotAnswered,
+    /// The caller denied recording consent or did not answer the prompt
+    ConsentNotGiven,
+}
+
+impl CloseReason {
+    /// Every close reason, in code order
+    pub const ALL: [CloseReason; 13] = [
+        Self::Normal,
+        Self::ProtocolViolation,
+        Self::AuthFailed,
+        Self::IdleTimeout,
+        Self::MaxDuration,
+        Self::QuotaExceeded,
+        Self::AdminTerminated,
+        Self::ProviderFailure,
+        Self::ShuttingDown,
+        Self::ParticipantLeft,
+        Self::HeartbeatTimeout,
+        Self::CallNotAnswered,
+        Self::ConsentNotGiven,
+    ];
+
+    /// WebSocket close code
+    pub const fn code(self) -> u16 {
+        match self {
+            Self::Normal => 1000,
+            Self::ProtocolViolation => 4000,
+            Self::AuthFailed => 4001,
+            Self::IdleTimeout => 4002,
+            Self::MaxDuration => 4003,
+            Self::QuotaExceeded => 4004,
+            Self::AdminTerminated => 4005,
+            Self::ProviderFailure => 4006,
+            Self::ShuttingDown => 4007,
+            Self::ParticipantLeft => 4008,
+            Self::HeartbeatTimeout => 4009,
+            Self::CallNotAnswered => 4010,
+            Self::ConsentNotGiven => 4011,
+        }
+    }
+
+    /// Machine-readable name, used as the close reason and the error `code`
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::ProtocolViolation => "protocol_violation",
+            Self::AuthFailed => "auth_failed",
+            Self::IdleTimeout => "idle_timeout",
+            Self::MaxDuration => "max_duration",
+            Self::QuotaExceeded => "quota_exceeded",
+            Self::AdminTerminated => "admin_terminated",
+            Self::ProviderFailure => "provider_failure",
+            Self::ShuttingDown => "shutting_down",
+            Self::ParticipantLeft => "participant_left",
+            Self::HeartbeatTimeout => "heartbeat_timeout",
+            Self::CallNotAnswered => "call_not_answered",
+            Self::ConsentNotGiven => "consent_not_given",
+        }
+    }
+
+    /// The close frame for this reason
+    pub fn close_message(self) -> Message {
+        Message::Close(Some(CloseFrame {
+            code: self.code(),
+            reason: self.as_str().into(),
+        }))
+    }
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_are_unique_application_codes() {
+        let codes: HashSet<u16> = CloseReason::ALL.iter().map(|r| r.code()).collect();
+        assert_eq!(codes.len(), CloseReason::ALL.len());
+        let names: HashSet<&str> = CloseReason::ALL.iter().map(|r| r.as_str()).collect();
+        assert_eq!(names.len(), CloseReason::ALL.len());
+
+        for reason in CloseReason::ALL {
+            if reason != CloseReason::Normal {
+                assert!((4000..5000).contains(&reason.code()), "{reason}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_close_message() {
+        match CloseReason::IdleTimeout.close_message() {
+            Message::Close(Some(frame)) => {
+                assert_eq!(frame.code, 4002);
+                assert_eq!(frame.reason.as_str(), "idle_timeout");
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Development console
+//!
+//! Serves the single-page console at `/console`: static HTML, CSS and
+//! JavaScript embedded in the binary. The page connects to `/ws` with an API
+//! secret like any other client, so it needs nothing the public protocol does
+//! not offer. Only built with the `dev-console` feature, and answers
+//! `404 Not Found` unless `console.enabled: true` is set in the server config.
+
+use axum::{
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+/// Embedded console assets: file name, content type and contents
+const ASSETS: &[(&str, &str, &[u8])] = &[
+    (
+        "index.html",
+        "text/html; charset=utf-8",
+        include_bytes!("../../assets/console/index.html"),
+    ),
+    (
+        "console.js",
+        "text/javascript; charset=utf-8",
+        include_bytes!("../../assets/console/console.js"),
+    ),
+    (
+        "capture-worklet.js",
+        "text/javascript; charset=utf-8",
+        include_bytes!("../../assets/console/capture-worklet.js"),
+    ),
+    (
+        "console.css",
+        "text/css; charset=utf-8",
+        include_bytes!("../../assets/console/console.css"),
+    ),
+];
+
+/// Serve the console page
+pub async fn console_index(State(state): State<Arc<AppState>>) -> Response {
+    serve_asset(&state, "index.html")
+}
+
+/// Serve a script or stylesheet of the console
+pub async fn console_asset(
+    State(state): State<Arc<AppState>>,
+    Path(file_name): Path<String>,
+) -> Response {
+    serve_asset(&state, &file_name)
+}
+
+fn serve_asset(state: &AppState, file_name: &str) -> Response {
+    if !state.config.console.enabled {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let Some((_, content_type, body)) = ASSETS.iter().find(|(name, ..)| *name == file_name) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    (
+        [
+            (header::CONTENT_TYPE, *content_type),
+            // The assets change with the binary; always fetch the current ones
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        *body,
+    )
+        .into_response()
+}
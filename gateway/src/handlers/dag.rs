@@ -22,6 +22,21 @@ use crate::dag::{
 
 /// List available DAG templates
 #[cfg(feature = "dag-routing")]
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/dag/templates",
+        responses(
+            (status = 200, description = "Registered DAG templates", body = ListTemplatesResponse),
+            (status = 401, description = "Unauthorized")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "dag"
+    )
+)]
 pub async fn list_templates(
     State(_state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
@@ -62,6 +77,23 @@ pub async fn list_templates(
 
 /// Validate a DAG definition
 #[cfg(feature = "dag-routing")]
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/dag/validate",
+        request_body = ValidateDAGRequest,
+        responses(
+            (status = 200, description = "Validation result", body = ValidateDAGResponse),
+            (status = 400, description = "DAG definition could not be parsed", body = ValidateDAGResponse),
+            (status = 401, description = "Unauthorized")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "dag"
+    )
+)]
 pub async fn validate_dag(
     State(_state): State<Arc<AppState>>,
     Json(request): Json<ValidateDAGRequest>,
@@ -136,6 +168,25 @@ pub async fn validate_dag(
 
 /// Get a specific DAG template
 #[cfg(feature = "dag-routing")]
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/dag/templates/{template_name}",
+        params(
+            ("template_name" = String, Path, description = "Template name")
+        ),
+        responses(
+            (status = 200, description = "The template definition"),
+            (status = 401, description = "Unauthorized"),
+            (status = 404, description = "Template not found")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "dag"
+    )
+)]
 pub async fn get_template(
     State(_state): State<Arc<AppState>>,
     axum::extract::Path(template_name): axum::extract::Path<String>,
@@ -182,12 +233,14 @@ pub async fn get_template(
 // Request/Response types
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ListTemplatesResponse {
     pub templates: Vec<TemplateInfo>,
     pub count: usize,
 }
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TemplateInfo {
     pub name: String,
     pub version: String,
@@ -195,11 +248,14 @@ pub struct TemplateInfo {
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ValidateDAGRequest {
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub dag: serde_json::Value,
 }
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ValidateDAGResponse {
     pub valid: bool,
     pub errors: Vec<String>,
@@ -0,0 +1,104 @@
+//! Feature flag administration endpoints
+//!
+//! Admin-only endpoints for inspecting and replacing the session feature
+//! flags without a restart. New flags apply to sessions configured after
+//! the change; open sessions keep the flags they were configured with.
+//! Changes are not written back to the config file.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::config::FeatureFlagConfig;
+use crate::state::AppState;
+
+/// Feature flag configurations in effect
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeatureFlagsResponse {
+    /// Flag configurations by name
+    pub flags: BTreeMap<String, FeatureFlagConfig>,
+    /// Configured flags that no session component checks
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown_flags: Vec<String>,
+}
+
+/// Get the feature flag configurations
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/feature_flags",
+        responses(
+            (status = 200, description = "Feature flag configurations", body = FeatureFlagsResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "feature_flags"
+    )
+)]
+pub async fn get_feature_flags(State(state): State<Arc<AppState>>) -> Response {
+    let flags = state.feature_flags.flags();
+    let unknown_flags = crate::config::unknown_feature_flags(&flags)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    (
+        StatusCode::OK,
+        Json(FeatureFlagsResponse {
+            flags,
+            unknown_flags,
+        }),
+    )
+        .into_response()
+}
+
+/// Replace every feature flag configuration
+///
+/// Flags missing from the body are removed and resolve as disabled; sending
+/// `{}` clears all flags. Unknown flag names are accepted and listed in
+/// `unknown_flags`.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        put,
+        path = "/admin/feature_flags",
+        request_body = BTreeMap<String, FeatureFlagConfig>,
+        responses(
+            (status = 200, description = "Feature flags replaced", body = FeatureFlagsResponse),
+            (status = 400, description = "Invalid feature flag"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "feature_flags"
+    )
+)]
+pub async fn update_feature_flags(
+    State(state): State<Arc<AppState>>,
+    Json(flags): Json<BTreeMap<String, FeatureFlagConfig>>,
+) -> Response {
+    match state.feature_flags.update(flags) {
+        Ok(unknown_flags) => (
+            StatusCode::OK,
+            Json(FeatureFlagsResponse {
+                flags: state.feature_flags.flags(),
+                unknown_flags,
+            }),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(json!({"error": e}))).into_response(),
+    }
+}
@@ -0,0 +1,82 @@
+//! Session flight log download
+//!
+//! Admin-only endpoint serving the flight log a session left in the cache
+//! backend, for rendering with `waav-gateway session replay <file>`.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::handlers::recording::is_valid_stream_id;
+use crate::state::AppState;
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+/// Download the flight log of a session
+///
+/// The log of a session still running holds what was recorded up to its
+/// last periodic write.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/sessions/{stream_id}/flight_log",
+        params(
+            ("stream_id" = String, Path, description = "Session stream identifier")
+        ),
+        responses(
+            (status = 200, description = "The session's flight log as JSON lines", content_type = "application/x-ndjson", body = String),
+            (status = 400, description = "Invalid stream_id format"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope"),
+            (status = 404, description = "Flight recorder not configured or no log stored for the session"),
+            (status = 503, description = "The cache backend could not be read")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "sessions"
+    )
+)]
+pub async fn get_flight_log(
+    State(state): State<Arc<AppState>>,
+    Path(stream_id): Path<String>,
+) -> Response {
+    let Some(recorder) = &state.flight_recorder else {
+        return error_response(StatusCode::NOT_FOUND, "Flight recorder not configured");
+    };
+    if !is_valid_stream_id(&stream_id) {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid stream_id format");
+    }
+
+    match recorder.load(&stream_id).await {
+        Ok(Some(contents)) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/x-ndjson"),
+            )],
+            contents,
+        )
+            .into_response(),
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            &format!("No flight log stored for session: {stream_id}"),
+        ),
+        Err(e) => {
+            warn!(stream_id = %stream_id, "Failed to read flight log: {}", e);
+            error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Failed to read the flight log from the cache",
+            )
+        }
+    }
+}
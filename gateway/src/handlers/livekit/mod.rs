@@ -31,7 +31,8 @@ pub use rooms::{
 pub use token::{TokenRequest, TokenResponse, generate_token};
 pub use webhook::{
     SIPHookEvent, SIPHookParticipant, SIPHookRoom, SipForwardingError, extract_sip_attributes,
-    get_sip_host_header, handle_livekit_webhook, parse_sip_domain, webhook_error_to_status,
+    get_sip_host_header, handle_livekit_webhook, parse_sip_domain, sip_call_signal,
+    webhook_error_to_status,
 };
 
 // Re-export utoipa-generated path types for OpenAPI spec generation
@@ -41,3 +42,5 @@ pub use participants::{__path_mute_participant, __path_remove_participant};
 pub use rooms::{__path_get_room_details, __path_list_rooms};
 #[cfg(feature = "openapi")]
 pub use token::__path_generate_token;
+#[cfg(feature = "openapi")]
+pub use webhook::__path_handle_livekit_webhook;
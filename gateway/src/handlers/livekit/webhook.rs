@@ -1,7 +1,8 @@
 //! LiveKit webhook handler
 //!
 //! This module handles incoming LiveKit webhook events, validates their signatures,
-//! and forwards SIP-related events to configured downstream webhooks.
+//! forwards SIP-related events to configured downstream webhooks and reports the
+//! progress of outbound SIP calls to the sessions waiting on them.
 
 use axum::{
     extract::State,
@@ -9,13 +10,11 @@ use axum::{
     response::Json,
 };
 use bytes::Bytes;
-use hmac::{Hmac, Mac};
 use livekit_api::access_token::TokenVerifier;
 use livekit_api::webhooks::{WebhookError, WebhookReceiver};
-use livekit_protocol::WebhookEvent;
+use livekit_protocol::{WebhookEvent, participant_info};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use sha2::Sha256;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
@@ -23,11 +22,10 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
 use crate::AppState;
-use crate::state::SipHooksState;
+use crate::state::{CallSignal, SIP_CALL_STATUS_ATTRIBUTE, SipHooksState};
 use crate::utils::req_manager::ReqManager;
 use crate::utils::url_validation::validate_webhook_url;
-
-type HmacSha256 = Hmac<Sha256>;
+use crate::utils::webhook_signing::generate_webhook_signature;
 
 /// Participant information for SIP webhook events.
 ///
@@ -72,6 +70,9 @@ pub struct SIPHookEvent {
     pub room_prefix: String,
     /// SIP host/domain that matched the webhook configuration
     pub sip_host: String,
+    /// Client metadata of the session bound to this room (omitted when empty)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
 }
 
 /// Categorizes SIP webhook forwarding failures by severity and expected action.
@@ -251,6 +252,24 @@ impl SipForwardingError {
 ///
 /// The endpoint is unauthenticated (no JWT middleware) because LiveKit
 /// authenticates via signed webhook payloads using the Authorization header.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/livekit/webhook",
+        request_body(content = String, description = "LiveKit webhook event (JSON)", content_type = "application/webhook+json"),
+        params(
+            ("Authorization" = String, Header, description = "LiveKit-signed JWT covering the payload hash")
+        ),
+        responses(
+            (status = 200, description = "Event accepted"),
+            (status = 400, description = "Payload is not valid UTF-8"),
+            (status = 401, description = "Missing or invalid webhook signature"),
+            (status = 503, description = "LiveKit webhooks not configured")
+        ),
+        tag = "livekit"
+    )
+)]
 pub async fn handle_livekit_webhook(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -300,6 +319,17 @@ pub async fn handle_livekit_webhook(
         webhook_error_to_status(&e)
     })?;
 
+    // Outbound calls advance as their SIP participant joins and leaves
+    if let Some((room, participant, signal)) = sip_call_signal(&event)
+        && let Some(call_state) = state.sip_calls.observe(room, Some(participant), signal)
+    {
+        info!(
+            room = %room,
+            state = call_state.as_str(),
+            "Outbound SIP call progressed"
+        );
+    }
+
     // Step 6: Forward event to SIP-specific webhook if applicable (non-blocking)
     // Short-circuit: Only spawn forwarding task if SIP config exists and has hooks.
     // This ensures pure non-SIP deployments don't incur unnecessary background tasks
@@ -384,6 +414,34 @@ pub fn extract_sip_attributes(
         .collect()
 }
 
+/// Outbound call progress reported by a webhook event.
+///
+/// `participant_joined` events of SIP participants carry the call's
+/// `sip.callStatus`; a SIP participant leaving ends its call.
+///
+/// # Returns
+/// * `Some((room, identity, signal))` - The call's room, its SIP participant and the progress
+/// * `None` - The event is not about a SIP participant
+pub fn sip_call_signal(event: &WebhookEvent) -> Option<(&str, &str, CallSignal)> {
+    let room = event.room.as_ref()?;
+    let participant = event.participant.as_ref()?;
+    let is_sip = participant_info::Kind::try_from(participant.kind)
+        .is_ok_and(|kind| kind == participant_info::Kind::Sip)
+        || participant
+            .attributes
+            .contains_key(SIP_CALL_STATUS_ATTRIBUTE);
+    if !is_sip {
+        return None;
+    }
+
+    let signal = match event.event.as_str() {
+        "participant_joined" => CallSignal::from_attributes(&participant.attributes)?,
+        "participant_left" => CallSignal::left(&participant.attributes),
+        _ => return None,
+    };
+    Some((&room.name, &participant.identity, signal))
+}
+
 /// Maps WebhookError to appropriate HTTP status code.
 ///
 /// Returns:
@@ -522,59 +580,6 @@ pub fn parse_sip_domain(header: &str) -> Option<String> {
     None
 }
 
-/// Generates an HMAC-SHA256 signature for webhook payload authentication.
-///
-/// Creates a canonical string `v1:{timestamp}:{event_id}:{payload}` and signs it
-/// with HMAC-SHA256. Returns signing headers for inclusion in webhook requests.
-///
-/// # Arguments
-/// * `secret` - The signing secret (hook-level or global)
-/// * `timestamp` - Unix timestamp in seconds
-/// * `event_id` - The LiveKit event ID
-/// * `payload` - The JSON payload string
-///
-/// # Returns
-/// * `Ok(headers)` - Map of signing headers (X-WaaV-Signature, X-WaaV-Timestamp, etc.)
-/// * `Err(String)` - If HMAC initialization fails
-///
-/// # Security
-/// - Uses HMAC-SHA256 for signature generation
-/// - Canonical string format prevents replay attacks when combined with timestamp validation
-/// - Secrets are never logged or exposed in error messages
-fn generate_webhook_signature(
-    secret: &str,
-    timestamp: u64,
-    event_id: &str,
-    payload: &str,
-) -> Result<HashMap<String, String>, String> {
-    // Build canonical string: v1:{timestamp}:{event_id}:{payload}
-    let canonical_string = format!("v1:{}:{}:{}", timestamp, event_id, payload);
-
-    // Initialize HMAC-SHA256
-    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
-        .map_err(|e| format!("HMAC initialization failed: {}", e))?;
-
-    // Compute signature
-    mac.update(canonical_string.as_bytes());
-    let result = mac.finalize();
-    let signature_bytes = result.into_bytes();
-
-    // Encode as hex
-    let signature_hex = hex::encode(signature_bytes);
-
-    // Build signing headers
-    let mut headers = HashMap::new();
-    headers.insert(
-        "X-WaaV-Signature".to_string(),
-        format!("v1={}", signature_hex),
-    );
-    headers.insert("X-WaaV-Timestamp".to_string(), timestamp.to_string());
-    headers.insert("X-WaaV-Event-Id".to_string(), event_id.to_string());
-    headers.insert("X-WaaV-Signature-Version".to_string(), "v1".to_string());
-
-    Ok(headers)
-}
-
 /// Forwards a LiveKit webhook event to a SIP-specific downstream webhook.
 ///
 /// Only forwards `participant_joined` events. Extracts the SIP domain from the
@@ -700,6 +705,10 @@ async fn forward_to_sip_hook(
         to_phone_number: to_phone_number.clone(),
         room_prefix: room_prefix.clone(),
         sip_host: domain.clone(),
+        metadata: state
+            .session_store
+            .metadata_for_room(&room.name)
+            .unwrap_or_default(),
     };
 
     // Step 10: Serialize payload
@@ -908,6 +917,7 @@ mod tests {
             to_phone_number: "+0987654321".to_string(),
             room_prefix: "sip-".to_string(),
             sip_host: "example.com".to_string(),
+            metadata: HashMap::new(),
         };
 
         // Serialize to JSON
@@ -935,6 +945,32 @@ mod tests {
         assert_eq!(parsed.as_object().unwrap().len(), 6);
     }
 
+    #[test]
+    fn test_sip_hook_event_serialization_with_metadata() {
+        let event = SIPHookEvent {
+            participant: SIPHookParticipant {
+                name: "SIP User".to_string(),
+                identity: "sip-user-123".to_string(),
+                sid: "PA_abc123".to_string(),
+            },
+            room: SIPHookRoom {
+                name: "sip-room-456".to_string(),
+                sid: "RM_xyz789".to_string(),
+            },
+            from_phone_number: "+1234567890".to_string(),
+            to_phone_number: "+0987654321".to_string(),
+            room_prefix: "sip-".to_string(),
+            sip_host: "example.com".to_string(),
+            metadata: HashMap::from([("customer_id".to_string(), "c-123".to_string())]),
+        };
+
+        let json = serde_json::to_string(&event).expect("Failed to serialize SIPHookEvent");
+        let parsed: Value = serde_json::from_str(&json).expect("Failed to parse JSON");
+
+        assert_eq!(parsed["metadata"]["customer_id"], "c-123");
+        assert_eq!(parsed.as_object().unwrap().len(), 7);
+    }
+
     #[test]
     fn test_sip_hook_event_deserialization() {
         // Create JSON payload
@@ -968,6 +1004,7 @@ mod tests {
         assert_eq!(event.to_phone_number, "+1234567890");
         assert_eq!(event.room_prefix, "call-");
         assert_eq!(event.sip_host, "test.example.org");
+        assert!(event.metadata.is_empty());
     }
 
     #[test]
@@ -0,0 +1,69 @@
+//! Load shedding administration endpoints
+//!
+//! Admin-only endpoints for inspecting the current load and replacing the
+//! load shedding thresholds without a restart. Changes apply to the next
+//! session request and are not written back to the config file.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::config::LoadSheddingConfig;
+use crate::state::AppState;
+
+/// Get the current load and load shedding thresholds
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/load_shedding",
+        responses(
+            (status = 200, description = "Current load and thresholds", body = crate::state::LoadSheddingStatus),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "load_shedding"
+    )
+)]
+pub async fn get_load_shedding(State(state): State<Arc<AppState>>) -> Response {
+    (StatusCode::OK, Json(state.load_shedding_status().await)).into_response()
+}
+
+/// Replace the load shedding thresholds
+///
+/// Omitted thresholds are turned off; sending `{}` disables load shedding.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        put,
+        path = "/admin/load_shedding",
+        request_body = LoadSheddingConfig,
+        responses(
+            (status = 200, description = "Thresholds replaced", body = crate::state::LoadSheddingStatus),
+            (status = 400, description = "Invalid thresholds"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "load_shedding"
+    )
+)]
+pub async fn update_load_shedding(
+    State(state): State<Arc<AppState>>,
+    Json(config): Json<LoadSheddingConfig>,
+) -> Response {
+    if let Err(e) = state.load_shedder.update_config(config) {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": e}))).into_response();
+    }
+    (StatusCode::OK, Json(state.load_shedding_status().await)).into_response()
+}
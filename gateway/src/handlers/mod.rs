@@ -1,24 +1,56 @@
 //! HTTP and WebSocket request handlers
 //!
 //! This module organizes all API handlers into logical groups:
+//! - `admin_audit` - Audit log of mutating admin calls (admin)
+//! - `agents` - Agent profile management (admin)
 //! - `api` - Health check endpoint
+//! - `chaos` - Fault injection for resilience testing (admin, `chaos` feature)
+//! - `close` - WebSocket close codes shared by `/ws` and `/realtime`
+//! - `console` - Browser console for local development (`dev-console` feature)
 //! - `dag` - DAG template management and validation
+//! - `feature_flags` - Session feature flag management (admin)
+//! - `flight_logs` - Session flight log download (admin)
 //! - `livekit` - LiveKit token generation and webhook handling
+//! - `plugin_routes` - HTTP routes served by plugins
+//! - `providers` - Provider credential validation (admin)
 //! - `realtime` - Realtime audio-to-audio WebSocket (OpenAI Realtime API)
+//! - `reconciliation` - Usage reconciliation summaries (admin)
 //! - `recording` - Recording download endpoint
+//! - `replay` - Recording replay jobs (admin)
+//! - `session_artifacts` - Session artifact download URLs
+//! - `session_events` - Server-Sent Events stream of a session's events
 //! - `sip` - SIP hooks management and call transfer
 //! - `speak` - Text-to-speech REST API
+//! - `strict_config` - Unknown field checks for config messages
 //! - `voices` - Voice listing endpoint
 //! - `ws` - WebSocket real-time voice processing
 
+pub mod admin_audit;
+pub mod agents;
 pub mod api;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod close;
+#[cfg(feature = "dev-console")]
+pub mod console;
 pub mod dag;
+pub mod feature_flags;
+pub mod flight_logs;
 pub mod livekit;
+pub mod load_shedding;
+pub mod plugin_routes;
+pub mod providers;
 pub mod realtime;
+pub mod reconciliation;
 pub mod recording;
+pub mod replay;
+pub mod session_artifacts;
+pub mod session_events;
 pub mod sip;
 pub mod speak;
+pub mod strict_config;
 pub mod voices;
+pub mod webhooks;
 pub mod ws;
 
 // Re-export commonly used handlers for convenient access
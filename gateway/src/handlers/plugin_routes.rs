@@ -0,0 +1,253 @@
+//! HTTP routes served by plugins
+//!
+//! `/plugins/{plugin_id}/{*path}` forwards requests to the plugin's HTTP
+//! handler (the `handle_http` slot of the plugin API), so a plugin can expose
+//! a small config or diagnostics surface without changes to the gateway.
+//!
+//! Before a request crosses into the plugin, the gateway checks that the
+//! plugin serves routes (404), that the client holds the `plugin:{plugin_id}`
+//! scope (403) and that the body fits `plugins.http_max_body_bytes` (413).
+//! The plugin then has `plugins.http_timeout_ms` to answer (504). Plugin
+//! failures and unusable responses are reported as 502.
+//!
+//! A plugin handles at most `plugins.http_max_concurrency` requests at once
+//! (503 beyond that). A call that timed out keeps its blocking thread and its
+//! slot until the plugin returns, so a hanging handler can't exhaust the
+//! blocking pool the rest of the gateway relies on.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::{Path, Request, State},
+    http::{HeaderName, HeaderValue, StatusCode, header::CONTENT_LENGTH},
+    response::{IntoResponse, Response},
+};
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use crate::auth::{Auth, filter_headers};
+use crate::config::ServerConfig;
+use crate::plugin::capabilities::{PluginHttpRequest, PluginHttpResponse};
+use crate::state::AppState;
+
+/// Header carrying the authenticated client id to the plugin
+///
+/// Client-supplied `x-waav-*` headers are stripped, so plugins can trust it.
+pub const CLIENT_ID_HEADER: &str = "x-waav-client-id";
+
+/// Path parameters of a plugin route
+#[derive(Debug, Deserialize)]
+#[cfg_attr(
+    feature = "openapi",
+    derive(utoipa::IntoParams),
+    into_params(parameter_in = Path)
+)]
+pub struct PluginRouteParams {
+    /// Plugin the request is routed to
+    pub plugin_id: String,
+    /// Path below `/plugins/{plugin_id}`, without the leading `/`
+    #[serde(default)]
+    pub path: String,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({"error": message.into()}))).into_response()
+}
+
+/// Whether a client holds the `plugin:{plugin_id}` scope
+///
+/// Without required authentication every client holds it, as with admin
+/// endpoints.
+fn holds_plugin_scope(config: &ServerConfig, auth: &Auth, plugin_id: &str) -> bool {
+    !config.auth_required
+        || auth
+            .id
+            .as_deref()
+            .is_some_and(|id| config.can_access_plugin(plugin_id, id))
+}
+
+/// Plugin route handler
+///
+/// Checks the request against the scope and body limits, then dispatches it
+/// to the HTTP handler of `plugin_id` and returns the plugin's response.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        method(get, post, put, patch, delete),
+        path = "/plugins/{plugin_id}/{path}",
+        responses(
+            (status = 200, description = "The plugin's response; status, headers and body are chosen by the plugin"),
+            (status = 403, description = "Client does not hold the plugin:{plugin_id} scope"),
+            (status = 404, description = "Plugin does not serve HTTP routes"),
+            (status = 413, description = "Request body exceeds plugins.http_max_body_bytes"),
+            (status = 502, description = "Plugin failed or returned an unusable response"),
+            (status = 503, description = "Plugin is already handling plugins.http_max_concurrency requests"),
+            (status = 504, description = "Plugin did not answer within plugins.http_timeout_ms")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "plugins"
+    )
+)]
+pub async fn plugin_route(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<Auth>,
+    Path(params): Path<PluginRouteParams>,
+    request: Request,
+) -> Response {
+    let config = &state.config;
+    let plugin_id = params.plugin_id.as_str();
+    let Some(handler) = state.plugin_registry.get_http_handler(plugin_id) else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            format!("Plugin '{plugin_id}' does not serve HTTP routes"),
+        );
+    };
+    if !holds_plugin_scope(config, &auth, plugin_id) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            format!("Requires the plugin:{plugin_id} scope"),
+        );
+    }
+
+    let max_body_bytes = config.plugins.http_max_body_bytes;
+    let too_large = || {
+        error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Request body exceeds {max_body_bytes} bytes"),
+        )
+    };
+
+    let (parts, body) = request.into_parts();
+    let declared_length = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared_length.is_some_and(|length| length > max_body_bytes as u64) {
+        return too_large();
+    }
+    let body = match Limited::new(body, max_body_bytes).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => return too_large(),
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read request body: {e}"),
+            );
+        }
+    };
+
+    let mut headers: Vec<(String, String)> = filter_headers(&parts.headers).into_iter().collect();
+    if let Some(id) = &auth.id {
+        headers.push((CLIENT_ID_HEADER.to_string(), id.clone()));
+    }
+    let plugin_request = PluginHttpRequest {
+        method: parts.method.to_string(),
+        path: format!("/{}", params.path),
+        query: parts.uri.query().unwrap_or_default().to_string(),
+        headers,
+        body,
+    };
+    debug!(
+        plugin_id = %plugin_id,
+        method = %plugin_request.method,
+        path = %plugin_request.path,
+        "Dispatching plugin HTTP request"
+    );
+
+    let permits = state
+        .plugin_http_permits
+        .entry(plugin_id.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(config.plugins.http_max_concurrency)))
+        .clone();
+    let Ok(permit) = permits.try_acquire_owned() else {
+        warn!(
+            plugin_id = %plugin_id,
+            max_concurrency = config.plugins.http_max_concurrency,
+            "Plugin HTTP handler is at its concurrency limit"
+        );
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "Plugin is already handling {} requests",
+                config.plugins.http_max_concurrency
+            ),
+        );
+    };
+
+    // The plugin call can't be cancelled: on timeout it keeps running on its
+    // blocking thread and its response is discarded. The permit is only
+    // released once the call returns.
+    let timeout = Duration::from_millis(config.plugins.http_timeout_ms);
+    let call = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        handler(plugin_request)
+    });
+    match tokio::time::timeout(timeout, call).await {
+        Ok(Ok(Ok(response))) => into_response(plugin_id, response),
+        Ok(Ok(Err(e))) => {
+            warn!(plugin_id = %plugin_id, error = %e, "Plugin HTTP handler failed");
+            error_response(StatusCode::BAD_GATEWAY, format!("Plugin error: {e}"))
+        }
+        Ok(Err(e)) => {
+            warn!(plugin_id = %plugin_id, error = %e, "Plugin HTTP handler panicked");
+            error_response(StatusCode::BAD_GATEWAY, "Plugin handler panicked")
+        }
+        Err(_) => {
+            warn!(
+                plugin_id = %plugin_id,
+                timeout_ms = config.plugins.http_timeout_ms,
+                "Plugin HTTP handler timed out"
+            );
+            error_response(
+                StatusCode::GATEWAY_TIMEOUT,
+                format!(
+                    "Plugin did not respond within {} ms",
+                    config.plugins.http_timeout_ms
+                ),
+            )
+        }
+    }
+}
+
+/// Convert a plugin's response, dropping headers that aren't valid HTTP
+fn into_response(plugin_id: &str, response: PluginHttpResponse) -> Response {
+    let Ok(status) = StatusCode::from_u16(response.status) else {
+        warn!(
+            plugin_id = %plugin_id,
+            status = response.status,
+            "Plugin returned an invalid HTTP status"
+        );
+        return error_response(
+            StatusCode::BAD_GATEWAY,
+            format!("Plugin returned invalid status {}", response.status),
+        );
+    };
+
+    let mut http_response = Response::new(Body::from(response.body));
+    *http_response.status_mut() = status;
+    for (name, value) in response.headers {
+        match (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::try_from(value.as_str()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                http_response.headers_mut().append(name, value);
+            }
+            _ => debug!(
+                plugin_id = %plugin_id,
+                header = %name,
+                "Dropping invalid plugin response header"
+            ),
+        }
+    }
+    http_response
+}
@@ -0,0 +1,218 @@
+//! Provider discovery and administration endpoints
+//!
+//! `GET /providers` lists the providers and voice profiles sessions can use,
+//! with the price of each provider's models.
+//! The admin-only credential check validates provider keys before they are
+//! stored or rolled out to clients.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config::VoiceProfile;
+use crate::core::credentials::ResembleCredentials;
+use crate::core::stt::{deepgram::DeepgramSTT, groq::GroqSTT, openai::OpenAISTT};
+use crate::core::tts::{
+    cartesia::CartesiaTTS, deepgram::DeepgramTTS, elevenlabs::ElevenLabsTTS, openai::OpenAITTS,
+};
+use crate::plugin::{ProviderPrices, pricing::registry_prices};
+use crate::state::AppState;
+
+/// Providers and voice profiles available to sessions
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProvidersResponse {
+    /// STT provider names
+    #[cfg_attr(feature = "openapi", schema(example = json!(["deepgram", "google"])))]
+    pub stt: Vec<String>,
+    /// TTS provider names
+    #[cfg_attr(feature = "openapi", schema(example = json!(["deepgram", "elevenlabs"])))]
+    pub tts: Vec<String>,
+    /// Realtime provider names
+    #[cfg_attr(feature = "openapi", schema(example = json!(["openai"])))]
+    pub realtime: Vec<String>,
+    /// Voice profiles by name, selected with `tts_config.voice_profile`
+    pub voice_profiles: BTreeMap<String, VoiceProfile>,
+    /// Per-model STT and TTS prices by provider name
+    #[serde(default)]
+    pub pricing: ProviderPrices,
+}
+
+/// List providers and voice profiles
+///
+/// Returns the registered STT, TTS and realtime providers, the server's
+/// voice profiles with the voice each TTS provider uses for them, and the
+/// known price of each STT and TTS model.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/providers",
+        responses(
+            (status = 200, description = "Available providers and voice profiles", body = ProvidersResponse),
+            (status = 401, description = "Unauthorized")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "providers"
+    )
+)]
+pub async fn list_providers(State(state): State<Arc<AppState>>) -> Json<ProvidersResponse> {
+    let registry = &state.plugin_registry;
+    let sorted = |mut names: Vec<String>| {
+        names.sort();
+        names
+    };
+    Json(ProvidersResponse {
+        stt: sorted(registry.get_stt_provider_names()),
+        tts: sorted(registry.get_tts_provider_names()),
+        realtime: sorted(registry.get_realtime_provider_names()),
+        voice_profiles: state.config.voice_profiles.clone(),
+        pricing: registry_prices(registry),
+    })
+}
+
+/// Request body for credential validation
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ValidateCredentialsRequest {
+    /// Provider API key to check
+    #[cfg_attr(feature = "openapi", schema(example = "sk-..."))]
+    pub api_key: String,
+}
+
+/// Result of a credential validation
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ValidateCredentialsResponse {
+    /// Whether the provider accepted the API key
+    pub valid: bool,
+    /// Reason the key was rejected (only present when `valid` is false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = "Invalid API key"))]
+    pub error: Option<String>,
+}
+
+/// Providers that support credential validation, as `(type, name)` pairs.
+pub const CREDENTIAL_VALIDATION_PROVIDERS: &[(&str, &str)] = &[
+    ("stt", "deepgram"),
+    ("stt", "groq"),
+    ("stt", "openai"),
+    ("tts", "cartesia"),
+    ("tts", "deepgram"),
+    ("tts", "elevenlabs"),
+    ("tts", "openai"),
+    ("tts", "resemble"),
+];
+
+/// Run the provider's credential check.
+///
+/// Returns `None` when the provider does not support credential validation.
+async fn run_validation(
+    provider_type: &str,
+    name: &str,
+    api_key: &str,
+) -> Option<Result<(), String>> {
+    let result = match (provider_type, name) {
+        ("stt", "deepgram") => DeepgramSTT::validate_credentials(api_key).await,
+        ("stt", "groq") => GroqSTT::validate_credentials(api_key).await,
+        ("stt", "openai") => OpenAISTT::validate_credentials(api_key).await,
+        ("tts", "cartesia") => CartesiaTTS::validate_credentials(api_key).await,
+        ("tts", "deepgram") => DeepgramTTS::validate_credentials(api_key).await,
+        ("tts", "elevenlabs") => ElevenLabsTTS::validate_credentials(api_key).await,
+        ("tts", "openai") => OpenAITTS::validate_credentials(api_key).await,
+        ("tts", "resemble") => ResembleCredentials::validate_credentials(api_key).await,
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Validate a provider API key
+///
+/// Performs a lightweight authenticated request against the provider (for
+/// example listing models or projects) and reports whether the key was
+/// accepted. Requires admin privileges when authentication is enabled.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/providers/{provider_type}/{name}/validate_credentials",
+        params(
+            ("provider_type" = String, Path, description = "Provider type (`stt` or `tts`)", example = "tts"),
+            ("name" = String, Path, description = "Provider name", example = "deepgram")
+        ),
+        request_body = ValidateCredentialsRequest,
+        responses(
+            (status = 200, description = "Validation completed", body = ValidateCredentialsResponse),
+            (status = 400, description = "Missing api_key"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope"),
+            (status = 404, description = "Provider does not support credential validation")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "providers"
+    )
+)]
+pub async fn validate_credentials(
+    State(_state): State<Arc<AppState>>,
+    Path((provider_type, name)): Path<(String, String)>,
+    Json(request): Json<ValidateCredentialsRequest>,
+) -> Response {
+    let provider_type = provider_type.to_lowercase();
+    let name = name.to_lowercase();
+
+    if request.api_key.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "api_key must not be empty"})),
+        )
+            .into_response();
+    }
+
+    let Some(result) = run_validation(&provider_type, &name, request.api_key.trim()).await else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": format!(
+                    "Credential validation is not supported for {provider_type} provider '{name}'"
+                )
+            })),
+        )
+            .into_response();
+    };
+
+    let response = match result {
+        Ok(()) => {
+            info!(provider_type = %provider_type, provider = %name, "Provider credentials valid");
+            ValidateCredentialsResponse {
+                valid: true,
+                error: None,
+            }
+        }
+        Err(error) => {
+            warn!(
+                provider_type = %provider_type,
+                provider = %name,
+                error = %error,
+                "Provider credentials rejected"
+            );
+            ValidateCredentialsResponse {
+                valid: false,
+                error: Some(error),
+            }
+        }
+    };
+
+    (StatusCode::OK, Json(response)).into_response()
+}
@@ -15,20 +15,31 @@ use axum::{
     response::Response,
 };
 use futures::{SinkExt, StreamExt};
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tokio::{select, time::Duration};
 use tracing::{debug, error, info, warn};
 
+use crate::agents::{AgentProfileError, AgentSection};
 use crate::auth::Auth;
+use crate::config::FeatureFlags;
 use crate::core::realtime::{
     BaseRealtime, RealtimeAudioData, RealtimeConfig, RealtimeError, TranscriptResult,
-    TranscriptRole, create_realtime_provider, get_supported_realtime_providers,
+    TranscriptRole,
 };
-use crate::state::AppState;
+use crate::core::session::{ProviderModel, SessionUsage};
+use crate::handlers::close::CloseReason;
+use crate::handlers::strict_config::unknown_fields_message;
+use crate::middleware::{ClientIp, ConnectionGuard};
+use crate::plugin::dispatch::{BuiltinRealtimeProvider, resolve_realtime_provider};
+use crate::state::{AppState, SessionStore};
+use crate::usage::{UsageRecord, UsageRecorder, UsageTermination};
 
 use super::messages::{
     RealtimeIncomingMessage, RealtimeMessageRoute, RealtimeOutgoingMessage, RealtimeSessionConfig,
+    unknown_session_fields,
 };
 
 /// Optimized channel buffer size for audio workloads
@@ -55,35 +66,64 @@ const DEFAULT_MODEL: &str = "gpt-4o-realtime-preview";
 /// * `ws` - The WebSocket upgrade request from Axum
 /// * `state` - Application state containing configuration
 /// * `auth` - Auth context from middleware
+/// * `client_ip` - Optional client IP from connection limit middleware for releasing connection
 ///
 /// # Returns
 /// * `Response` - HTTP response that upgrades the connection to WebSocket
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/realtime",
+        responses(
+            (status = 101, description = "Switching to the WebSocket protocol for audio-to-audio streaming"),
+            (status = 401, description = "Unauthorized"),
+            (status = 429, description = "Connection limit reached"),
+            (status = 503, description = "Server is shedding load")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "websocket"
+    )
+)]
 pub async fn realtime_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
     Extension(auth): Extension<Auth>,
+    client_ip: Option<Extension<ClientIp>>,
 ) -> Response {
     info!(
         auth_id = ?auth.id,
         "Realtime WebSocket connection upgrade requested"
     );
 
+    let ip = client_ip.map(|Extension(ClientIp(ip))| ip);
     ws.max_frame_size(MAX_WS_FRAME_SIZE)
         .max_message_size(MAX_WS_MESSAGE_SIZE)
-        .on_upgrade(move |socket| handle_realtime_socket(socket, state, auth))
+        .on_upgrade(move |socket| handle_realtime_socket(socket, state, auth, ip))
 }
 
 /// Handle the realtime WebSocket connection
-async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, auth: Auth) {
+async fn handle_realtime_socket(
+    socket: WebSocket,
+    app_state: Arc<AppState>,
+    auth: Auth,
+    client_ip: Option<IpAddr>,
+) {
     info!(auth_id = ?auth.id, "Realtime WebSocket connection established");
 
+    // Release the connection slot taken by the connection limit middleware
+    // when the session ends
+    let _connection_guard = client_ip.map(|ip| ConnectionGuard::new(app_state.clone(), ip));
+
     let (mut sender, mut receiver) = socket.split();
     let (message_tx, mut message_rx) = mpsc::channel::<RealtimeMessageRoute>(CHANNEL_BUFFER_SIZE);
 
     // Sender task for outgoing messages
-    let sender_task = tokio::spawn(async move {
+    let mut sender_task = tokio::spawn(async move {
         while let Some(route) = message_rx.recv().await {
-            let should_close = matches!(route, RealtimeMessageRoute::Close);
+            let should_close = matches!(route, RealtimeMessageRoute::Close(_));
 
             let result = match route {
                 RealtimeMessageRoute::Outgoing(message) => match serde_json::to_string(&message) {
@@ -94,9 +134,9 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
                     }
                 },
                 RealtimeMessageRoute::Audio(data) => sender.send(Message::Binary(data)).await,
-                RealtimeMessageRoute::Close => {
-                    info!("Closing realtime WebSocket connection");
-                    sender.send(Message::Close(None)).await
+                RealtimeMessageRoute::Close(reason) => {
+                    info!(%reason, "Closing realtime WebSocket connection");
+                    sender.send(reason.close_message()).await
                 }
             };
 
@@ -114,6 +154,12 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
     // State for the realtime session
     let mut realtime_provider: Option<Box<dyn BaseRealtime>> = None;
     let mut session_id: Option<String> = None;
+    let mut usage = RealtimeUsageGuard {
+        recorder: app_state.usage_recorder.clone(),
+        client_id: auth.id.clone(),
+        sessions: app_state.session_store.clone(),
+        current: None,
+    };
 
     // How often we check if the connection is stale
     let processing_timeout = Duration::from_secs(30);
@@ -143,6 +189,7 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
                             msg,
                             &mut realtime_provider,
                             &mut session_id,
+                            &mut usage,
                             &message_tx,
                             &app_state,
                         ).await;
@@ -153,14 +200,12 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
                     }
                     Some(Err(e)) => {
                         warn!("Realtime WebSocket error: {}", e);
-                        let _ = message_tx
-                            .send(RealtimeMessageRoute::Outgoing(
-                                RealtimeOutgoingMessage::Error {
-                                    code: Some("websocket_error".to_string()),
-                                    message: format!("WebSocket error: {e}"),
-                                },
-                            ))
-                            .await;
+                        close_connection(
+                            &message_tx,
+                            CloseReason::ProtocolViolation,
+                            format!("WebSocket error: {e}"),
+                        )
+                        .await;
                         break;
                     }
                     None => {
@@ -176,14 +221,12 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
                         "Realtime WebSocket connection idle for {}s, closing stale connection",
                         last_activity.elapsed().as_secs()
                     );
-                    let _ = message_tx
-                        .send(RealtimeMessageRoute::Outgoing(
-                            RealtimeOutgoingMessage::Error {
-                                code: Some("idle_timeout".to_string()),
-                                message: "Connection closed due to inactivity".to_string(),
-                            },
-                        ))
-                        .await;
+                    close_connection(
+                        &message_tx,
+                        CloseReason::IdleTimeout,
+                        "Connection closed due to inactivity",
+                    )
+                    .await;
                     break;
                 }
                 debug!("Realtime WebSocket connection idle check - still active");
@@ -191,8 +234,17 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
         }
     }
 
-    // Cleanup
-    sender_task.abort();
+    // Flush queued messages and close; ignored if a close was already sent
+    let _ = message_tx
+        .send(RealtimeMessageRoute::Close(CloseReason::Normal))
+        .await;
+    if tokio::time::timeout(Duration::from_millis(500), &mut sender_task)
+        .await
+        .is_err()
+    {
+        warn!("Realtime sender task did not complete within timeout");
+        sender_task.abort();
+    }
 
     // Disconnect realtime provider if connected
     if let Some(mut provider) = realtime_provider
@@ -201,15 +253,37 @@ async fn handle_realtime_socket(socket: WebSocket, app_state: Arc<AppState>, aut
         error!("Failed to disconnect realtime provider: {:?}", e);
     }
 
+    // The record reads the session's metadata, so it is written first
+    usage.finish().await;
+
+    if let Some(session_id) = &session_id {
+        app_state.session_store.remove(session_id);
+    }
+
     info!("Realtime WebSocket connection terminated");
 }
 
+/// Send the final error message and close the connection
+async fn close_connection(
+    message_tx: &mpsc::Sender<RealtimeMessageRoute>,
+    reason: CloseReason,
+    message: impl Into<String>,
+) {
+    let _ = message_tx
+        .send(RealtimeMessageRoute::Outgoing(
+            RealtimeOutgoingMessage::close_error(reason, message),
+        ))
+        .await;
+    let _ = message_tx.send(RealtimeMessageRoute::Close(reason)).await;
+}
+
 /// Process incoming WebSocket message
 #[inline(always)]
 async fn process_realtime_message(
     msg: Message,
     realtime_provider: &mut Option<Box<dyn BaseRealtime>>,
     session_id: &mut Option<String>,
+    usage: &mut RealtimeUsageGuard,
     message_tx: &mpsc::Sender<RealtimeMessageRoute>,
     app_state: &Arc<AppState>,
 ) -> bool {
@@ -247,10 +321,35 @@ async fn process_realtime_message(
                 return true;
             }
 
+            // Strict config: reject session configs with unknown fields
+            if let RealtimeIncomingMessage::Config(config)
+            | RealtimeIncomingMessage::UpdateSession(config) = &incoming_msg
+                && config
+                    .strict_config
+                    .unwrap_or(app_state.config.strict_config)
+                && let Ok(value) = serde_json::from_str::<serde_json::Value>(&text)
+            {
+                let unknown = unknown_session_fields(&value);
+                if !unknown.is_empty() {
+                    let message = unknown_fields_message(&unknown);
+                    warn!("Rejected realtime config: {}", message);
+                    let _ = message_tx
+                        .send(RealtimeMessageRoute::Outgoing(
+                            RealtimeOutgoingMessage::Error {
+                                code: Some("validation_error".to_string()),
+                                message,
+                            },
+                        ))
+                        .await;
+                    return true;
+                }
+            }
+
             handle_realtime_incoming(
                 incoming_msg,
                 realtime_provider,
                 session_id,
+                usage,
                 message_tx,
                 app_state,
             )
@@ -303,12 +402,37 @@ async fn handle_realtime_incoming(
     msg: RealtimeIncomingMessage,
     realtime_provider: &mut Option<Box<dyn BaseRealtime>>,
     session_id: &mut Option<String>,
+    usage: &mut RealtimeUsageGuard,
     message_tx: &mpsc::Sender<RealtimeMessageRoute>,
     app_state: &Arc<AppState>,
 ) -> bool {
     match msg {
         RealtimeIncomingMessage::Config(config) => {
-            handle_config(config, realtime_provider, session_id, message_tx, app_state).await
+            let config =
+                match resolve_agent_config(config, usage.client_id.as_deref(), app_state).await {
+                    Ok(config) => config,
+                    Err(message) => {
+                        warn!("Agent profile resolution failed: {}", message);
+                        let _ = message_tx
+                            .send(RealtimeMessageRoute::Outgoing(
+                                RealtimeOutgoingMessage::Error {
+                                    code: Some("invalid_agent".to_string()),
+                                    message,
+                                },
+                            ))
+                            .await;
+                        return true;
+                    }
+                };
+            handle_config(
+                config,
+                realtime_provider,
+                session_id,
+                usage,
+                message_tx,
+                app_state,
+            )
+            .await
         }
         RealtimeIncomingMessage::Text { text } => {
             if let Some(provider) = realtime_provider
@@ -407,23 +531,86 @@ async fn handle_realtime_incoming(
     }
 }
 
+/// Expand a config that names an agent profile
+///
+/// Configs without `agent` are returned unchanged. Otherwise the profile's
+/// realtime section, visible to `client_id`, is resolved with the config's
+/// `overrides` and `metadata`.
+async fn resolve_agent_config(
+    config: RealtimeSessionConfig,
+    client_id: Option<&str>,
+    app_state: &AppState,
+) -> Result<RealtimeSessionConfig, String> {
+    let Some(agent) = config.agent.clone() else {
+        return Ok(config);
+    };
+
+    // The profile supplies the configuration; changes go through `overrides`
+    let sent = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+    if let Some(field) = sent.as_object().and_then(|fields| {
+        fields
+            .iter()
+            .find(|(key, value)| {
+                !value.is_null()
+                    && !matches!(
+                        key.as_str(),
+                        "agent" | "overrides" | "metadata" | "strict_config"
+                    )
+            })
+            .map(|(key, _)| key.clone())
+    }) {
+        return Err(AgentProfileError::FieldWithAgent { field }.to_string());
+    }
+
+    let metadata: Option<serde_json::Map<String, serde_json::Value>> =
+        config.metadata.map(|metadata| {
+            metadata
+                .into_iter()
+                .map(|(key, value)| (key, serde_json::Value::String(value)))
+                .collect()
+        });
+
+    let fields = {
+        let profiles = app_state.agent_profiles.read().await;
+        let profile = profiles
+            .lookup(&agent, client_id)
+            .map_err(|e| e.to_string())?;
+        profile
+            .resolve(
+                AgentSection::Realtime,
+                config.overrides.as_ref(),
+                metadata.as_ref(),
+            )
+            .map_err(|e| e.to_string())?
+    };
+
+    let resolved: RealtimeSessionConfig = serde_json::from_value(serde_json::Value::Object(fields))
+        .map_err(|e| format!("Invalid configuration for agent '{agent}': {e}"))?;
+    RealtimeIncomingMessage::Config(resolved.clone())
+        .validate_size()
+        .map_err(|e| format!("Invalid configuration for agent '{agent}': {e}"))?;
+
+    info!(agent = %agent, "Resolved realtime configuration from agent profile");
+    Ok(resolved)
+}
+
 /// Handle config message - create and connect provider
 async fn handle_config(
     config: RealtimeSessionConfig,
     realtime_provider: &mut Option<Box<dyn BaseRealtime>>,
     session_id: &mut Option<String>,
+    usage: &mut RealtimeUsageGuard,
     message_tx: &mpsc::Sender<RealtimeMessageRoute>,
     app_state: &Arc<AppState>,
 ) -> bool {
     let provider_name = config.provider.as_deref().unwrap_or(DEFAULT_PROVIDER);
     let model = config.model.as_deref().unwrap_or(DEFAULT_MODEL);
 
-    // Validate provider
-    let supported = get_supported_realtime_providers();
-    if !supported
-        .iter()
-        .any(|p| p.eq_ignore_ascii_case(provider_name))
-    {
+    // Validate provider against the registry so plugin providers are accepted
+    let registry = &app_state.plugin_registry;
+    if !registry.has_realtime_provider(provider_name) {
+        let mut supported = registry.get_realtime_provider_names();
+        supported.sort();
         let _ = message_tx
             .send(RealtimeMessageRoute::Outgoing(
                 RealtimeOutgoingMessage::Error {
@@ -438,14 +625,7 @@ async fn handle_config(
         return true;
     }
 
-    // Get API key from config based on provider
-    let api_key = match provider_name.to_lowercase().as_str() {
-        "openai" => app_state.config.openai_api_key.clone(),
-        "hume" => app_state.config.hume_api_key.clone(),
-        _ => None,
-    };
-
-    let Some(api_key) = api_key else {
+    let Some(api_key) = realtime_api_key(app_state, provider_name) else {
         let _ = message_tx
             .send(RealtimeMessageRoute::Outgoing(
                 RealtimeOutgoingMessage::Error {
@@ -461,14 +641,16 @@ async fn handle_config(
     let realtime_config = build_realtime_config(api_key, &config);
 
     // Create provider
-    let mut provider = match create_realtime_provider(provider_name, realtime_config) {
+    let mut provider = match registry.create_realtime(provider_name, realtime_config) {
         Ok(p) => p,
         Err(e) => {
             let _ = message_tx
                 .send(RealtimeMessageRoute::Outgoing(
                     RealtimeOutgoingMessage::Error {
                         code: Some("provider_error".to_string()),
-                        message: format!("Failed to create provider: {e}"),
+                        message: app_state
+                            .error_sanitizer
+                            .sanitize("provider_error", &format!("Failed to create provider: {e}")),
                     },
                 ))
                 .await;
@@ -510,15 +692,17 @@ async fn handle_config(
         .ok();
 
     let tx_clone = message_tx.clone();
+    let error_sanitizer = app_state.error_sanitizer.clone();
     provider
         .on_error(Arc::new(move |error: RealtimeError| {
             let tx = tx_clone.clone();
+            let message = error_sanitizer.sanitize("provider_error", &error.to_string());
             Box::pin(async move {
                 let _ = tx
                     .send(RealtimeMessageRoute::Outgoing(
                         RealtimeOutgoingMessage::Error {
                             code: Some("provider_error".to_string()),
-                            message: error.to_string(),
+                            message,
                         },
                     ))
                     .await;
@@ -589,12 +773,15 @@ async fn handle_config(
 
     // Connect to provider
     info!("Connecting to {} realtime provider", provider_name);
-    if let Err(e) = provider.connect().await {
+    let connect_timeout = Duration::from_secs(app_state.config.provider_connect_timeout_secs);
+    if let Err(e) = provider.connect_with_timeout(connect_timeout).await {
         let _ = message_tx
             .send(RealtimeMessageRoute::Outgoing(
                 RealtimeOutgoingMessage::Error {
                     code: Some("connection_error".to_string()),
-                    message: format!("Failed to connect: {e}"),
+                    message: app_state
+                        .error_sanitizer
+                        .sanitize("connection_error", &format!("Failed to connect: {e}")),
                 },
             ))
             .await;
@@ -603,10 +790,20 @@ async fn handle_config(
 
     // Generate session ID
     let new_session_id = uuid::Uuid::new_v4().to_string();
-    *session_id = Some(new_session_id.clone());
+    let previous = session_id.replace(new_session_id.clone());
+
+    // Register session metadata in the shared session store
+    app_state
+        .session_store
+        .register(&new_session_id, config.metadata.clone().unwrap_or_default());
 
-    // Store provider
+    // Store provider; the replaced session's usage record is written with
+    // its metadata before the entry is dropped
     *realtime_provider = Some(provider);
+    usage.start(&new_session_id, provider_name, model);
+    if let Some(previous) = previous {
+        app_state.session_store.remove(&previous);
+    }
 
     // Send session created message
     let _ = message_tx
@@ -673,6 +870,118 @@ async fn handle_session_update(
     true
 }
 
+/// Writes the usage record of the connection's realtime session
+///
+/// A session replaced by a new config gets a `reconfigured` record, normal
+/// teardown calls `finish`, and if the handler panics or is cancelled first
+/// `Drop` writes the record with `termination: aborted`.
+struct RealtimeUsageGuard {
+    recorder: Option<Arc<UsageRecorder>>,
+    client_id: Option<String>,
+    /// Registry the session's metadata is read from
+    sessions: Arc<SessionStore>,
+    /// Session ID and usage of the connected session
+    current: Option<(String, SessionUsage)>,
+}
+
+impl RealtimeUsageGuard {
+    /// Start metering a newly connected session
+    fn start(&mut self, session_id: &str, provider: &str, model: &str) {
+        if self.recorder.is_none() {
+            return;
+        }
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let usage = SessionUsage {
+            started_at,
+            ended_at: None,
+            stt: None,
+            stt_audio_seconds: 0.0,
+            stt_secondary: None,
+            stt_secondary_audio_seconds: 0.0,
+            stt_fast: None,
+            stt_fast_audio_seconds: 0.0,
+            stt_turns: Vec::new(),
+            tts: None,
+            tts_characters: 0,
+            tts_audio_seconds: 0.0,
+            tts_utterances: Vec::new(),
+            realtime: Some(ProviderModel {
+                provider: provider.to_string(),
+                model: model.to_string(),
+            }),
+            turns: 0,
+            turn_ids: Vec::new(),
+            feature_flags: FeatureFlags::default(),
+            consent: None,
+        };
+        if let Some(record) = self.take_record(UsageTermination::Reconfigured)
+            && let Some(recorder) = &self.recorder
+        {
+            recorder.record_detached(record);
+        }
+        self.current = Some((session_id.to_string(), usage));
+    }
+
+    /// Write the record of the current session at normal teardown
+    async fn finish(&mut self) {
+        if let Some(record) = self.take_record(UsageTermination::Closed)
+            && let Some(recorder) = &self.recorder
+        {
+            // Sink failures are logged by the recorder
+            let _ = recorder.record(&record).await;
+        }
+    }
+
+    fn take_record(&mut self, termination: UsageTermination) -> Option<UsageRecord> {
+        let (session_id, usage) = self.current.take()?;
+        let metadata = self.sessions.metadata(&session_id).unwrap_or_default();
+        Some(
+            UsageRecord::new(
+                session_id,
+                self.client_id.clone(),
+                &usage,
+                termination,
+                None,
+            )
+            .with_metadata(metadata),
+        )
+    }
+}
+
+impl Drop for RealtimeUsageGuard {
+    fn drop(&mut self) {
+        if let Some(record) = self.take_record(UsageTermination::Aborted)
+            && let Some(recorder) = &self.recorder
+        {
+            warn!(session_id = %record.session_id, "Writing usage record for aborted session");
+            recorder.record_detached(record);
+        }
+    }
+}
+
+/// API key for a realtime provider
+///
+/// Built-in providers use the server's credentials and return `None` when
+/// none are configured. Plugin providers manage their own credentials; they
+/// get `plugins.providers.<name>.api_key` when set and an empty key otherwise.
+fn realtime_api_key(app_state: &AppState, provider_name: &str) -> Option<String> {
+    match resolve_realtime_provider(provider_name) {
+        Some(BuiltinRealtimeProvider::OpenAI) => app_state.config.openai_api_key.clone(),
+        Some(BuiltinRealtimeProvider::Hume) => app_state.config.hume_api_key.clone(),
+        None => Some(
+            app_state
+                .config
+                .plugins
+                .provider_api_key(provider_name)
+                .unwrap_or_default()
+                .to_string(),
+        ),
+    }
+}
+
 /// Build RealtimeConfig from session config
 fn build_realtime_config(api_key: String, config: &RealtimeSessionConfig) -> RealtimeConfig {
     use crate::core::realtime::{InputTranscriptionConfig, TurnDetectionConfig};
@@ -727,6 +1036,10 @@ fn build_realtime_config(api_key: String, config: &RealtimeSessionConfig) -> Rea
 
     RealtimeConfig {
         api_key,
+        provider: config
+            .provider
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PROVIDER.to_string()),
         model: config
             .model
             .clone()
@@ -775,6 +1088,20 @@ mod tests {
         assert_eq!(realtime_config.temperature, Some(0.8));
     }
 
+    #[test]
+    fn test_build_realtime_config_provider() {
+        let realtime_config =
+            build_realtime_config(String::new(), &RealtimeSessionConfig::default());
+        assert_eq!(realtime_config.provider, DEFAULT_PROVIDER);
+
+        let session_config = RealtimeSessionConfig {
+            provider: Some("mock-realtime".to_string()),
+            ..Default::default()
+        };
+        let realtime_config = build_realtime_config(String::new(), &session_config);
+        assert_eq!(realtime_config.provider, "mock-realtime");
+    }
+
     #[test]
     fn test_default_provider() {
         assert_eq!(DEFAULT_PROVIDER, "openai");
@@ -7,6 +7,11 @@
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
+use crate::agents::MAX_AGENT_NAME_LENGTH;
+use crate::handlers::close::CloseReason;
+use crate::handlers::strict_config::{UnknownField, collect_unknown_fields};
+use crate::state::{SessionMetadata, SessionMetadataError, validate_session_metadata};
+
 /// Maximum allowed size for instructions (100 KB)
 pub const MAX_INSTRUCTIONS_SIZE: usize = 100 * 1024;
 
@@ -74,7 +79,8 @@ pub enum RealtimeIncomingMessage {
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RealtimeSessionConfig {
-    /// Provider to use (e.g., "openai")
+    /// Provider to use (e.g., "openai"); any registered realtime provider,
+    /// including ones from dynamic plugins, is accepted
     #[serde(default)]
     pub provider: Option<String>,
 
@@ -128,6 +134,29 @@ pub struct RealtimeSessionConfig {
     /// Output audio format override
     #[serde(default)]
     pub output_audio_format: Option<String>,
+
+    /// Client metadata attached to the session (string values only).
+    /// Limited to 10 entries and 2 KB in total. Only honored on `config`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub metadata: Option<SessionMetadata>,
+
+    /// Reject this message if it contains fields the server does not know,
+    /// instead of ignoring them (default: the server's `strict_config`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_config: Option<bool>,
+
+    /// Agent profile to configure the session from. Only honored on `config`.
+    /// The profile supplies every other field; only `metadata`,
+    /// `strict_config` and `overrides` may be sent alongside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// Field-level overrides of the agent profile, applied as a JSON merge
+    /// patch. Each field must be listed as overridable by the profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub overrides: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 /// Turn detection configuration
@@ -264,6 +293,17 @@ pub enum RealtimeOutgoingMessage {
         message: String,
     },
 
+    /// Final error sent before the server closes the connection
+    #[serde(rename = "error")]
+    CloseError {
+        /// Machine-readable close reason (e.g. `idle_timeout`)
+        code: String,
+        /// WebSocket close code of the following close frame
+        close_code: u16,
+        /// Error message
+        message: String,
+    },
+
     /// Connection closing
     #[serde(rename = "closing")]
     Closing {
@@ -282,8 +322,19 @@ pub enum RealtimeMessageRoute {
     Outgoing(RealtimeOutgoingMessage),
     /// Binary audio data
     Audio(Bytes),
-    /// Close connection
-    Close,
+    /// Close the connection with an application close code
+    Close(CloseReason),
+}
+
+impl RealtimeOutgoingMessage {
+    /// The final error message announcing a close
+    pub fn close_error(reason: CloseReason, message: impl Into<String>) -> Self {
+        Self::CloseError {
+            code: reason.as_str().to_string(),
+            close_code: reason.code(),
+            message: message.into(),
+        }
+    }
 }
 
 // =============================================================================
@@ -301,6 +352,10 @@ pub enum RealtimeValidationError {
     FunctionResultTooLarge { size: usize, max: usize },
     /// Invalid provider
     InvalidProvider { provider: String },
+    /// Session metadata violates a size limit
+    InvalidMetadata(SessionMetadataError),
+    /// Agent profile name exceeds maximum allowed size
+    AgentNameTooLarge { size: usize, max: usize },
 }
 
 impl std::fmt::Display for RealtimeValidationError {
@@ -326,6 +381,14 @@ impl std::fmt::Display for RealtimeValidationError {
             Self::InvalidProvider { provider } => {
                 write!(f, "Invalid provider: {}", provider)
             }
+            Self::InvalidMetadata(e) => write!(f, "{}", e),
+            Self::AgentNameTooLarge { size, max } => {
+                write!(
+                    f,
+                    "Agent name too large: {} bytes (max: {} bytes)",
+                    size, max
+                )
+            }
         }
     }
 }
@@ -347,6 +410,18 @@ impl RealtimeIncomingMessage {
                         });
                     }
                 }
+                if let Some(metadata) = &config.metadata {
+                    validate_session_metadata(metadata)
+                        .map_err(RealtimeValidationError::InvalidMetadata)?;
+                }
+                if let Some(agent) = &config.agent
+                    && agent.len() > MAX_AGENT_NAME_LENGTH
+                {
+                    return Err(RealtimeValidationError::AgentNameTooLarge {
+                        size: agent.len(),
+                        max: MAX_AGENT_NAME_LENGTH,
+                    });
+                }
             }
             RealtimeIncomingMessage::Text { text } => {
                 let size = text.len();
@@ -376,6 +451,17 @@ impl RealtimeIncomingMessage {
     }
 }
 
+/// Fields of a JSON `config` or `update_session` message the server does not
+/// know, at any depth
+pub fn unknown_session_fields(message: &serde_json::Value) -> Vec<UnknownField> {
+    let Some(object) = message.as_object() else {
+        return Vec::new();
+    };
+    let mut fields = object.clone();
+    fields.remove("type");
+    collect_unknown_fields::<RealtimeSessionConfig>(&serde_json::Value::Object(fields), "")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -489,6 +575,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_metadata_deserialization() {
+        let json = r#"{"type": "config", "metadata": {"customer_id": "c-123"}}"#;
+        let msg: RealtimeIncomingMessage = serde_json::from_str(json).expect("Should deserialize");
+        match &msg {
+            RealtimeIncomingMessage::Config(config) => {
+                let metadata = config
+                    .metadata
+                    .as_ref()
+                    .expect("metadata should be present");
+                assert_eq!(metadata.get("customer_id"), Some(&"c-123".to_string()));
+            }
+            _ => panic!("Expected Config variant"),
+        }
+        assert!(msg.validate_size().is_ok());
+    }
+
+    #[test]
+    fn test_validation_metadata_exceeds_limit() {
+        let config = RealtimeSessionConfig {
+            metadata: Some(
+                (0..10)
+                    .map(|i| (format!("key{i}"), "v".repeat(250)))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let msg = RealtimeIncomingMessage::Config(config);
+        let err = msg.validate_size().unwrap_err();
+        match err {
+            RealtimeValidationError::InvalidMetadata(SessionMetadataError::TooLarge { .. }) => {}
+            _ => panic!("Expected InvalidMetadata error"),
+        }
+    }
+
     #[test]
     fn test_validation_text_exceeds_limit() {
         let msg = RealtimeIncomingMessage::Text {
@@ -531,4 +652,34 @@ mod tests {
             _ => panic!("Expected Config variant"),
         }
     }
+
+    #[test]
+    fn test_unknown_session_fields() {
+        let message = serde_json::json!({
+            "type": "update_session",
+            "voice": "alloy",
+            "temprature": 0.7,
+            "tools": [{
+                "type": "function",
+                "function": {"name": "lookup", "paramters": {"type": "object"}}
+            }],
+            "metadata": {"anything": "goes"}
+        });
+        let mut reported: Vec<String> = unknown_session_fields(&message)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        reported.sort();
+        assert_eq!(
+            reported,
+            vec![
+                "'temprature' (did you mean 'temperature'?)",
+                "'tools[0].function.paramters' (did you mean 'parameters'?)",
+            ]
+        );
+
+        let message =
+            serde_json::json!({"type": "config", "provider": "openai", "strict_config": true});
+        assert!(unknown_session_fields(&message).is_empty());
+    }
 }
@@ -40,3 +40,7 @@ mod handler;
 pub mod messages;
 
 pub use handler::realtime_handler;
+
+// Re-export utoipa-generated path type for OpenAPI spec generation
+#[cfg(feature = "openapi")]
+pub use handler::__path_realtime_handler;
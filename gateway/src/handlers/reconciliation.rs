@@ -0,0 +1,69 @@
+//! Usage reconciliation endpoint
+//!
+//! Admin-only endpoint serving the daily TTS reconciliation summaries per
+//! provider, for comparing our usage counts with provider invoices.
+//! Summaries are kept in memory for the last 31 days and lost on restart;
+//! the outliers themselves are in the JSON-lines report.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::usage::{EXPECTED_CHARS_PER_SECOND, ReconciliationSummary};
+
+/// Daily reconciliation summaries
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReconciliationResponse {
+    /// Speaking rate the expected audio length is computed with
+    pub expected_chars_per_second: f64,
+    /// Summaries per day (UTC) and TTS provider, oldest day first
+    pub days: Vec<ReconciliationSummary>,
+}
+
+/// Get the daily TTS reconciliation summaries
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/reconciliation",
+        responses(
+            (status = 200, description = "Daily reconciliation summaries", body = ReconciliationResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope"),
+            (status = 404, description = "Usage reconciliation not configured")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "usage"
+    )
+)]
+pub async fn get_reconciliation(State(state): State<Arc<AppState>>) -> Response {
+    let days = state
+        .usage_recorder
+        .as_ref()
+        .and_then(|recorder| recorder.reconciliation_summaries());
+    match days {
+        Some(days) => (
+            StatusCode::OK,
+            Json(ReconciliationResponse {
+                expected_chars_per_second: EXPECTED_CHARS_PER_SECOND,
+                days,
+            }),
+        )
+            .into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Usage reconciliation not configured"})),
+        )
+            .into_response(),
+    }
+}
@@ -14,7 +14,7 @@ use crate::state::AppState;
 
 const CONTENT_TYPE: &str = "audio/ogg";
 
-fn is_valid_stream_id(stream_id: &str) -> bool {
+pub(crate) fn is_valid_stream_id(stream_id: &str) -> bool {
     !stream_id.is_empty() && !stream_id.contains("..") && !stream_id.contains('/')
 }
 
@@ -29,20 +29,33 @@ fn build_recording_object_key(
     prefix: Option<&String>,
     auth_id: Option<&str>,
     stream_id: &str,
+) -> String {
+    session_object_key(prefix, auth_id, stream_id, "audio.ogg")
+}
+
+/// Build the object key of a file stored next to a session's recording
+///
+/// Uses the same tenant-scoped layout as the recording itself, so
+/// `{prefix}/{auth_id}/{stream_id}/{file}` or `{prefix}/{stream_id}/{file}`.
+pub(crate) fn session_object_key(
+    prefix: Option<&String>,
+    auth_id: Option<&str>,
+    stream_id: &str,
+    file: &str,
 ) -> String {
     let normalized_prefix = prefix
         .map(|p| p.trim().trim_end_matches('/'))
         .filter(|p| !p.is_empty());
 
     match (normalized_prefix, auth_id) {
-        // No prefix, no auth_id: stream_id/audio.ogg
-        (None, None) => format!("{}/audio.ogg", stream_id),
-        // No prefix, with auth_id: auth_id/stream_id/audio.ogg
-        (None, Some(auth)) => format!("{}/{}/audio.ogg", auth, stream_id),
-        // With prefix, no auth_id: prefix/stream_id/audio.ogg
-        (Some(prefix), None) => format!("{}/{}/audio.ogg", prefix, stream_id),
-        // With prefix, with auth_id: prefix/auth_id/stream_id/audio.ogg
-        (Some(prefix), Some(auth)) => format!("{}/{}/{}/audio.ogg", prefix, auth, stream_id),
+        // No prefix, no auth_id: stream_id/file
+        (None, None) => format!("{}/{}", stream_id, file),
+        // No prefix, with auth_id: auth_id/stream_id/file
+        (None, Some(auth)) => format!("{}/{}/{}", auth, stream_id, file),
+        // With prefix, no auth_id: prefix/stream_id/file
+        (Some(prefix), None) => format!("{}/{}/{}", prefix, stream_id, file),
+        // With prefix, with auth_id: prefix/auth_id/stream_id/file
+        (Some(prefix), Some(auth)) => format!("{}/{}/{}/{}", prefix, auth, stream_id, file),
     }
 }
 
@@ -0,0 +1,163 @@
+//! Recording replay administration endpoints
+//!
+//! Admin-only endpoints for replaying a stored recording through a new
+//! provider configuration in the background. Jobs are kept in memory only and
+//! are lost on restart.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::replay::{ReplayJobInfo, ReplayRequest, spawn_replay_job};
+use crate::state::AppState;
+
+/// Response listing all replay jobs
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReplayJobsResponse {
+    /// Active and recently finished jobs, oldest first
+    pub jobs: Vec<ReplayJobInfo>,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({"error": message.into()}))).into_response()
+}
+
+/// List replay jobs
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/replay",
+        responses(
+            (status = 200, description = "List of replay jobs", body = ReplayJobsResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "replay"
+    )
+)]
+pub async fn list_replay_jobs(State(state): State<Arc<AppState>>) -> Response {
+    let jobs = state.replay_jobs.list();
+    (StatusCode::OK, Json(ReplayJobsResponse { jobs })).into_response()
+}
+
+/// Start replaying a stored recording
+///
+/// The job is queued until one of the `replay_max_concurrent_jobs` slots is
+/// free. Poll `GET /admin/replay/{job_id}` for its report.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/admin/replay",
+        request_body = ReplayRequest,
+        responses(
+            (status = 202, description = "Replay job queued", body = ReplayJobInfo),
+            (status = 400, description = "Invalid replay request"),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope"),
+            (status = 503, description = "Recording storage not configured")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "replay"
+    )
+)]
+pub async fn create_replay_job(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ReplayRequest>,
+) -> Response {
+    if let Err(e) = request.validate() {
+        return error_response(StatusCode::BAD_REQUEST, e);
+    }
+    if state.object_store.is_none() {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Recording storage not configured",
+        );
+    }
+    let info = spawn_replay_job(state, request);
+    (StatusCode::ACCEPTED, Json(info)).into_response()
+}
+
+/// Get a replay job and, once completed, its report
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/replay/{job_id}",
+        params(
+            ("job_id" = String, Path, description = "Replay job id")
+        ),
+        responses(
+            (status = 200, description = "Replay job", body = ReplayJobInfo),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope"),
+            (status = 404, description = "Replay job not found")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "replay"
+    )
+)]
+pub async fn get_replay_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Response {
+    match state.replay_jobs.get(&job_id) {
+        Some(info) => (StatusCode::OK, Json(info)).into_response(),
+        None => error_response(
+            StatusCode::NOT_FOUND,
+            format!("Replay job '{job_id}' not found"),
+        ),
+    }
+}
+
+/// Cancel a queued or running replay job, or delete a finished one
+///
+/// Returns the job as it was before the request. A cancelled job reports
+/// `cancelled` once its session has closed.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        delete,
+        path = "/admin/replay/{job_id}",
+        params(
+            ("job_id" = String, Path, description = "Replay job id")
+        ),
+        responses(
+            (status = 200, description = "Replay job cancelled or deleted", body = ReplayJobInfo),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope"),
+            (status = 404, description = "Replay job not found")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "replay"
+    )
+)]
+pub async fn cancel_replay_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Response {
+    match state.replay_jobs.cancel(&job_id) {
+        Some(info) => (StatusCode::OK, Json(info)).into_response(),
+        None => error_response(
+            StatusCode::NOT_FOUND,
+            format!("Replay job '{job_id}' not found"),
+        ),
+    }
+}
@@ -0,0 +1,201 @@
+//! Session artifact download URLs
+//!
+//! `GET /sessions/{stream_id}/artifacts` lists fresh download URLs of a
+//! finished session's exported artifacts, scoped to the caller's tenant.
+//! Without a recording bucket, the URLs point at `GET /downloads/{key}`,
+//! which needs no bearer token: it serves the object only while the URL's
+//! `expires` time has not passed and its `sig` matches the export's
+//! dedicated signing key.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use object_store::{Error as ObjectStoreError, path::Path as ObjectPath};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::auth::Auth;
+use crate::handlers::recording::is_valid_stream_id;
+use crate::session_export::{ArtifactKind, ArtifactLinks, DownloadUrlError, verify_download};
+use crate::state::AppState;
+
+/// Download URLs of a session's artifacts
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionArtifactsResponse {
+    /// Session stream identifier
+    pub session_id: String,
+    /// URLs of the stored artifacts and when they expire
+    #[serde(flatten)]
+    pub links: ArtifactLinks,
+}
+
+/// Query parameters of a signed download URL
+#[derive(Debug, Deserialize)]
+#[cfg_attr(
+    feature = "openapi",
+    derive(utoipa::IntoParams),
+    into_params(parameter_in = Query)
+)]
+pub struct DownloadQuery {
+    /// When the URL stops working (Unix seconds)
+    pub expires: u64,
+    /// Hex HMAC-SHA256 signature of the URL
+    pub sig: String,
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+/// List download URLs of a finished session's artifacts
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/sessions/{stream_id}/artifacts",
+        params(
+            ("stream_id" = String, Path, description = "Session stream identifier", example = "550e8400-e29b-41d4-a716-446655440000")
+        ),
+        responses(
+            (status = 200, description = "Download URLs of the session's artifacts", body = SessionArtifactsResponse),
+            (status = 400, description = "Invalid stream_id format"),
+            (status = 404, description = "No artifacts stored for the session"),
+            (status = 503, description = "Session export not configured")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "recordings"
+    )
+)]
+pub async fn list_session_artifacts(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<Auth>,
+    Path(stream_id): Path<String>,
+) -> Response {
+    let Some(exporter) = &state.session_export else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Session export not configured",
+        );
+    };
+    if !is_valid_stream_id(&stream_id) {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid stream_id format");
+    }
+
+    let links = exporter
+        .artifact_links(auth.id.as_deref(), &stream_id, &[])
+        .await;
+    if links.artifacts.is_empty() {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            &format!("No artifacts found for session: {stream_id}"),
+        );
+    }
+
+    Json(SessionArtifactsResponse {
+        session_id: stream_id,
+        links,
+    })
+    .into_response()
+}
+
+/// Download an artifact through a signed gateway URL
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/downloads/{key}",
+        params(
+            ("key" = String, Path, description = "Object key of the artifact")
+        ),
+        responses(
+            (status = 200, description = "The artifact"),
+            (status = 403, description = "Invalid signature"),
+            (status = 404, description = "Artifact not found"),
+            (status = 410, description = "Download URL expired"),
+            (status = 503, description = "Artifact storage unavailable")
+        ),
+        tag = "recordings"
+    )
+)]
+pub async fn download_artifact(
+    State(state): State<Arc<AppState>>,
+    Path(key): Path<String>,
+    Query(query): Query<DownloadQuery>,
+) -> Response {
+    // Only gateway-served exports hand out gateway URLs
+    let Some((exporter, signing_key)) = state
+        .session_export
+        .as_ref()
+        .and_then(|exporter| Some((exporter, exporter.gateway_signing_key()?)))
+    else {
+        return error_response(StatusCode::NOT_FOUND, "Not found");
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    match verify_download(signing_key, &key, query.expires, &query.sig, now) {
+        Ok(()) => {}
+        Err(DownloadUrlError::Expired) => {
+            info!(key = %key, "Expired artifact download URL");
+            return error_response(StatusCode::GONE, "Download URL has expired");
+        }
+        Err(DownloadUrlError::InvalidSignature) => {
+            warn!(key = %key, "Artifact download URL with invalid signature");
+            return error_response(StatusCode::FORBIDDEN, "Invalid download URL signature");
+        }
+    }
+
+    // Signed keys always name an artifact file; anything else was not signed by us
+    let Some(kind) = key
+        .rsplit('/')
+        .next()
+        .and_then(ArtifactKind::from_file_name)
+    else {
+        return error_response(StatusCode::NOT_FOUND, "Not found");
+    };
+    let Ok(path) = ObjectPath::parse(&key) else {
+        return error_response(StatusCode::NOT_FOUND, "Not found");
+    };
+
+    let body = match exporter.store().get(&path).await {
+        Ok(result) => result.bytes().await,
+        Err(ObjectStoreError::NotFound { .. }) => {
+            return error_response(StatusCode::NOT_FOUND, "Artifact not found");
+        }
+        Err(e) => Err(e),
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(key = %key, "Failed to read session artifact: {}", e);
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Failed to read artifact from storage",
+            );
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(kind.content_type()),
+    );
+    if let Ok(disposition) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", kind.file_name()))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    (StatusCode::OK, headers, body).into_response()
+}
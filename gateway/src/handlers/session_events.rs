@@ -0,0 +1,454 @@
+//! Server-Sent Events stream of a session's events
+//!
+//! `GET /sessions/{stream_id}/events` streams the JSON messages a `/ws`
+//! session sends to its client (transcripts, VAD events, errors, playback
+//! completion) without audio. Each SSE event carries the message type as its
+//! event name and a per-session sequence number as its id, so a client that
+//! reconnects with `Last-Event-ID` is replayed what it missed.
+//!
+//! Holders of the `monitor:audio` scope may add `?audio=true` to also receive
+//! the session's audio as `monitor_audio` events. The audio is encrypted
+//! with a per-session key that `POST /sessions/{stream_id}/monitor-key`
+//! hands out once; see [`crate::state::decrypt_monitor_frame`].
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::auth::Auth;
+use crate::config::ServerConfig;
+use crate::state::{AppState, MonitorAudioFrame, MonitorKeyError, ObservedEvent, SubscribeError};
+
+/// Interval between heartbeat comments
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Header SSE clients send when reconnecting
+const LAST_EVENT_ID: &str = "last-event-id";
+
+/// Cipher of monitor audio frames
+pub const MONITOR_AUDIO_ALGORITHM: &str = "XChaCha20-Poly1305";
+
+/// Query parameters of the event stream
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(
+    feature = "openapi",
+    derive(utoipa::IntoParams),
+    into_params(parameter_in = Query)
+)]
+pub struct SessionEventsQuery {
+    /// Also stream encrypted `monitor_audio` events (requires `monitor:audio`)
+    #[serde(default)]
+    pub audio: bool,
+}
+
+/// Key that decrypts a session's monitor audio
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonitorKeyResponse {
+    /// Session stream identifier
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "550e8400-e29b-41d4-a716-446655440000")
+    )]
+    pub stream_id: String,
+    /// Cipher of the monitor audio frames
+    #[cfg_attr(feature = "openapi", schema(example = "XChaCha20-Poly1305"))]
+    pub algorithm: &'static str,
+    /// Base64-encoded 32-byte key
+    pub key: String,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({"error": message.into()}))).into_response()
+}
+
+/// Whether a client may observe a session owned by `owner`
+///
+/// Admins may observe any session; other clients only their own. Without
+/// authentication, only sessions opened without authentication are visible.
+fn can_observe(config: &ServerConfig, auth: &Auth, owner: Option<&str>) -> bool {
+    match auth.id.as_deref() {
+        Some(id) => config.is_admin_id(id) || owner == Some(id),
+        None => owner.is_none(),
+    }
+}
+
+/// Whether a client holds the `monitor:audio` scope
+///
+/// Without required authentication every client holds it, as with admin
+/// endpoints.
+fn holds_monitor_audio(config: &ServerConfig, auth: &Auth) -> bool {
+    !config.auth_required
+        || auth
+            .id
+            .as_deref()
+            .is_some_and(|id| config.can_monitor_audio(id))
+}
+
+fn parse_last_event_id(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn to_sse_event(event: ObservedEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.event_type)
+        .data(event.data)
+}
+
+/// Monitor audio frames carry no id: they are not replayed on resume
+fn to_audio_event(frame: MonitorAudioFrame) -> Event {
+    let data = json!({
+        "direction": frame.direction,
+        "counter": frame.counter,
+        "sample_rate": frame.sample_rate,
+        "channels": frame.channels,
+        "data": BASE64_STANDARD.encode(&frame.ciphertext),
+    });
+    Event::default()
+        .event("monitor_audio")
+        .data(data.to_string())
+}
+
+/// Stream a session's events
+///
+/// Streams transcripts and other session events as Server-Sent Events until
+/// the session ends. Send `Last-Event-ID` when reconnecting to receive the
+/// buffered events published since that id. A heartbeat comment is sent
+/// every 15 seconds. With `audio=true`, holders of the `monitor:audio` scope
+/// also receive the session's audio, encrypted with the key from
+/// `POST /sessions/{stream_id}/monitor-key`.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/sessions/{stream_id}/events",
+        params(
+            ("stream_id" = String, Path, description = "Session stream identifier", example = "550e8400-e29b-41d4-a716-446655440000"),
+            ("Last-Event-ID" = Option<u64>, Header, description = "Id of the last event received before reconnecting")
+        ),
+        responses(
+            (status = 200, description = "Event stream", content_type = "text/event-stream", body = String),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Audio requested without the monitor:audio scope"),
+            (status = 404, description = "Session not found"),
+            (status = 429, description = "Session has the maximum number of observers")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "sessions"
+    )
+)]
+pub async fn stream_session_events(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<Auth>,
+    Path(stream_id): Path<String>,
+    Query(query): Query<SessionEventsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    // Sessions of other clients are reported as missing
+    let visible = state
+        .session_events
+        .owner(&stream_id)
+        .is_some_and(|owner| can_observe(&state.config, &auth, owner.as_deref()));
+    if !visible {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            SubscribeError::NotFound(stream_id).to_string(),
+        );
+    }
+
+    if query.audio && !holds_monitor_audio(&state.config, &auth) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Monitor audio requires the monitor:audio scope",
+        );
+    }
+
+    let last_event_id = parse_last_event_id(&headers);
+    let subscription = match state.session_events.subscribe(&stream_id, last_event_id) {
+        Ok(subscription) => subscription,
+        Err(e @ SubscribeError::NotFound(_)) => {
+            return error_response(StatusCode::NOT_FOUND, e.to_string());
+        }
+        Err(e @ SubscribeError::TooManyObservers { .. }) => {
+            warn!(stream_id = %stream_id, "Rejected session observer: {}", e);
+            return error_response(StatusCode::TOO_MANY_REQUESTS, e.to_string());
+        }
+    };
+
+    let mut audio = if query.audio {
+        match state.session_events.subscribe_audio(&stream_id) {
+            Ok(receiver) => Some(receiver),
+            Err(e) => return error_response(StatusCode::NOT_FOUND, e.to_string()),
+        }
+    } else {
+        None
+    };
+
+    info!(
+        stream_id = %stream_id,
+        client_id = ?auth.id,
+        last_event_id = ?last_event_id,
+        replayed = subscription.replay.len(),
+        audio = query.audio,
+        "Session observer connected"
+    );
+
+    let stream = async_stream::stream! {
+        let mut subscription = subscription;
+        for event in std::mem::take(&mut subscription.replay) {
+            yield Ok::<_, Infallible>(to_sse_event(event));
+        }
+        loop {
+            tokio::select! {
+                result = subscription.receiver.recv() => match result {
+                    Ok(event) => yield Ok(to_sse_event(event)),
+                    // Skipped events remain visible as a gap in the ids
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!(stream_id = %stream_id, skipped, "Session observer lagged");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                Some(result) = async {
+                    match audio.as_mut() {
+                        Some(receiver) => Some(receiver.recv().await),
+                        None => None,
+                    }
+                } => match result {
+                    Ok(frame) => yield Ok(to_audio_event(frame)),
+                    // Skipped frames show up as a gap in the counters
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!(stream_id = %stream_id, skipped, "Monitor audio observer lagged");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        debug!(stream_id = %stream_id, "Session ended, closing observer stream");
+    };
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL))
+        .into_response()
+}
+
+/// Retrieve a session's monitor audio key
+///
+/// Returns the key that decrypts the session's `monitor_audio` events. Each
+/// session's key is handed out only once; later requests get `409 Conflict`.
+/// Requires the `monitor:audio` scope and access to the session.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/sessions/{stream_id}/monitor-key",
+        params(
+            ("stream_id" = String, Path, description = "Session stream identifier", example = "550e8400-e29b-41d4-a716-446655440000")
+        ),
+        responses(
+            (status = 200, description = "Monitor audio key", body = MonitorKeyResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the monitor:audio scope"),
+            (status = 404, description = "Session not found"),
+            (status = 409, description = "Key already retrieved")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "sessions"
+    )
+)]
+pub async fn retrieve_monitor_key(
+    State(state): State<Arc<AppState>>,
+    Extension(auth): Extension<Auth>,
+    Path(stream_id): Path<String>,
+) -> Response {
+    let visible = state
+        .session_events
+        .owner(&stream_id)
+        .is_some_and(|owner| can_observe(&state.config, &auth, owner.as_deref()));
+    if !visible {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            MonitorKeyError::NotFound(stream_id).to_string(),
+        );
+    }
+    if !holds_monitor_audio(&state.config, &auth) {
+        warn!(stream_id = %stream_id, client_id = ?auth.id, "Monitor key denied");
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Monitor audio requires the monitor:audio scope",
+        );
+    }
+
+    match state.session_events.take_monitor_key(&stream_id) {
+        Ok(key) => {
+            info!(stream_id = %stream_id, client_id = ?auth.id, "Monitor key retrieved");
+            Json(MonitorKeyResponse {
+                stream_id,
+                algorithm: MONITOR_AUDIO_ALGORITHM,
+                key: BASE64_STANDARD.encode(key),
+            })
+            .into_response()
+        }
+        Err(e @ MonitorKeyError::NotFound(_)) => {
+            error_response(StatusCode::NOT_FOUND, e.to_string())
+        }
+        Err(e @ MonitorKeyError::AlreadyRetrieved(_)) => {
+            warn!(stream_id = %stream_id, client_id = ?auth.id, "Monitor key requested again");
+            error_response(StatusCode::CONFLICT, e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn test_config(admin_ids: &[&str], monitor_audio_ids: &[&str]) -> ServerConfig {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect();
+        ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            tls: None,
+            livekit_url: "ws://localhost:7880".to_string(),
+            livekit_public_url: "http://localhost:7880".to_string(),
+            livekit_api_key: None,
+            livekit_api_secret: None,
+            deepgram_api_key: None,
+            elevenlabs_api_key: None,
+            google_credentials: None,
+            azure_speech_subscription_key: None,
+            azure_speech_region: None,
+            cartesia_api_key: None,
+            openai_api_key: None,
+            assemblyai_api_key: None,
+            hume_api_key: None,
+            lmnt_api_key: None,
+            groq_api_key: None,
+            playht_api_key: None,
+            playht_user_id: None,
+            ibm_watson_api_key: None,
+            ibm_watson_instance_id: None,
+            ibm_watson_region: None,
+            aws_access_key_id: None,
+            aws_secret_access_key: None,
+            aws_region: None,
+            gnani_token: None,
+            gnani_access_key: None,
+            gnani_certificate_path: None,
+            recording_s3_bucket: None,
+            recording_s3_region: None,
+            recording_s3_endpoint: None,
+            recording_s3_access_key: None,
+            recording_s3_secret_key: None,
+            recording_s3_prefix: None,
+            cache_path: None,
+            cache_ttl_seconds: None,
+            auth_service_url: None,
+            auth_signing_key_path: None,
+            auth_api_secrets: Vec::new(),
+            auth_timeout_seconds: 5,
+            auth_required: true,
+            auth_admin_ids: ids(admin_ids),
+            auth_monitor_audio_ids: ids(monitor_audio_ids),
+            auth_admin_scopes: Default::default(),
+            sip: None,
+            cors_allowed_origins: None,
+            rate_limit_requests_per_second: 60,
+            rate_limit_burst_size: 10,
+            max_websocket_connections: None,
+            max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
+            plugins: Default::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_last_event_id() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_last_event_id(&headers), None);
+        headers.insert(LAST_EVENT_ID, HeaderValue::from_static(" 42 "));
+        assert_eq!(parse_last_event_id(&headers), Some(42));
+        headers.insert(LAST_EVENT_ID, HeaderValue::from_static("abc"));
+        assert_eq!(parse_last_event_id(&headers), None);
+    }
+
+    #[test]
+    fn test_can_observe() {
+        let config = test_config(&["ops"], &[]);
+        let project1 = Auth::new("project1");
+
+        assert!(can_observe(&config, &project1, Some("project1")));
+        assert!(!can_observe(&config, &project1, Some("project2")));
+        assert!(!can_observe(&config, &project1, None));
+        assert!(can_observe(&config, &Auth::new("OPS"), Some("project2")));
+
+        // Without authentication only unauthenticated sessions are visible
+        assert!(can_observe(&config, &Auth::empty(), None));
+        assert!(!can_observe(&config, &Auth::empty(), Some("project1")));
+    }
+
+    #[test]
+    fn test_holds_monitor_audio() {
+        let mut config = test_config(&["ops"], &["supervisor"]);
+
+        assert!(holds_monitor_audio(&config, &Auth::new("Supervisor")));
+        assert!(holds_monitor_audio(&config, &Auth::new("ops")));
+        assert!(!holds_monitor_audio(&config, &Auth::new("project1")));
+        assert!(!holds_monitor_audio(&config, &Auth::empty()));
+
+        config.auth_required = false;
+        assert!(holds_monitor_audio(&config, &Auth::empty()));
+    }
+}
@@ -17,7 +17,11 @@ const DEFAULT_SPEAK_TIMEOUT_SECS: u64 = 30;
 /// This prevents DoS attacks via very long text inputs
 const MAX_TEXT_LENGTH: usize = 10 * 1024;
 
-use crate::core::tts::{AudioCallback, AudioData, TTSError, create_tts_provider};
+use crate::core::tts::{
+    AudioCallback, AudioData, TELEPHONY_FORMAT, TELEPHONY_SAMPLE_RATE, TTSError, TelephonyFramer,
+    telephony_config,
+};
+use crate::core::validation::config_issues_message;
 use crate::handlers::ws::config::TTSWebSocketConfig;
 use crate::state::AppState;
 
@@ -156,7 +160,7 @@ impl AudioCallback for AudioCollector {
                     ("x-sample-rate" = u32, description = "Sample rate in Hz")
                 )
             ),
-            (status = 400, description = "Invalid request (empty text)"),
+            (status = 400, description = "Invalid request (empty text or invalid provider config)"),
             (status = 500, description = "TTS synthesis failed")
         ),
         security(
@@ -235,7 +239,59 @@ pub async fn speak_handler(
     };
 
     // Convert WebSocket config to full TTSConfig with API key
-    let tts_config = request.tts_config.to_tts_config(api_key);
+    let mut tts_config = request.tts_config.to_tts_config(api_key);
+    if let Err(issue) = request
+        .tts_config
+        .apply_voice_profile(&state.config.voice_profiles, &mut tts_config)
+        .and_then(|()| {
+            tts_config.normalize_model_with(
+                &state.plugin_registry,
+                request.tts_config.allow_unknown_models,
+            )
+        })
+    {
+        let issues = vec![issue];
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": config_issues_message(&issues),
+                "issues": issues,
+            })),
+        )
+            .into_response();
+    }
+
+    // The telephony profile asks the provider for its closest native format
+    let tts_config = if tts_config.is_telephony() {
+        match telephony_config(&tts_config) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": e.to_string()
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    } else {
+        tts_config
+    };
+
+    if let Err(issues) = state
+        .plugin_registry
+        .validate_tts_config(&tts_config.provider, &tts_config)
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": config_issues_message(&issues),
+                "issues": issues,
+            })),
+        )
+            .into_response();
+    }
 
     // Apply pronunciation replacements
     let mut processed_text = request.text.clone();
@@ -244,7 +300,10 @@ pub async fn speak_handler(
     }
 
     // Create TTS provider
-    let mut tts_provider = match create_tts_provider(&tts_config.provider, tts_config.clone()) {
+    let mut tts_provider = match state
+        .plugin_registry
+        .create_tts(&tts_config.provider, tts_config.clone())
+    {
         Ok(provider) => provider,
         Err(e) => {
             error!("Failed to create TTS provider: {:?}", e);
@@ -342,6 +401,25 @@ pub async fn speak_handler(
         }
     };
 
+    // Convert to contiguous 8kHz μ-law for the telephony profile
+    let (audio_data, format, sample_rate) = if tts_config.is_telephony() {
+        match encode_telephony(audio_data, format, sample_rate) {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Telephony conversion error: {:?}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({
+                        "error": format!("Telephony conversion error: {}", e)
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    } else {
+        (audio_data, format, sample_rate)
+    };
+
     info!(
         "TTS synthesis successful - {} bytes, format: {}, sample_rate: {}",
         audio_data.len(),
@@ -378,3 +456,21 @@ pub async fn speak_handler(
     )
         .into_response()
 }
+
+/// Convert synthesized audio to 8kHz μ-law, padded to whole 20ms frames
+fn encode_telephony(
+    data: Vec<u8>,
+    format: String,
+    sample_rate: u32,
+) -> Result<(Vec<u8>, String, u32), TTSError> {
+    let framer = TelephonyFramer::new();
+    let mut frames = framer.push(&AudioData {
+        data,
+        sample_rate,
+        format,
+        duration_ms: None,
+    })?;
+    frames.extend(framer.flush());
+    let audio = frames.into_iter().flat_map(|frame| frame.data).collect();
+    Ok((audio, TELEPHONY_FORMAT.to_string(), TELEPHONY_SAMPLE_RATE))
+}
@@ -0,0 +1,320 @@
+//! Strict checking of session config messages
+//!
+//! Config messages are parsed leniently: serde ignores fields it does not
+//! know, so a typo like `"porvider"` silently falls back to the default.
+//! With `strict_config` enabled (server-wide or per session), a config
+//! message is deserialized a second time through [`collect_unknown_fields`],
+//! which reports every field the target types did not declare, with the
+//! closest known field name as a suggestion.
+//!
+//! Structs are checked against the field list serde passes to
+//! `deserialize_struct`. Free-form maps, enum payloads and flattened fields
+//! are not checked.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::{Map, Value};
+
+use crate::utils::did_you_mean::suggest;
+
+/// A field in a config message that the server does not know
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField {
+    /// Dotted path of the field, e.g. `stt_config.porvider`
+    pub path: String,
+    /// Known field at the same level with the closest name
+    pub suggestion: Option<String>,
+}
+
+impl UnknownField {
+    /// Unknown field `name` at `path`, suggesting the closest of `known`
+    pub fn new(path: &str, name: &str, known: &[&str]) -> Self {
+        Self {
+            path: join_path(path, name),
+            suggestion: suggest(name, known).map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.suggestion {
+            Some(suggestion) => write!(f, "'{}' (did you mean '{}'?)", self.path, suggestion),
+            None => write!(f, "'{}'", self.path),
+        }
+    }
+}
+
+/// Error message listing every unknown field
+pub fn unknown_fields_message(unknown: &[UnknownField]) -> String {
+    let fields = unknown
+        .iter()
+        .map(UnknownField::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("Unknown fields in config message: {fields}")
+}
+
+/// Fields of `value` that `T` does not declare, prefixed with `path`
+///
+/// Type errors are ignored; the lenient parse has already reported them.
+pub fn collect_unknown_fields<T: DeserializeOwned>(value: &Value, path: &str) -> Vec<UnknownField> {
+    let unknown = RefCell::new(Vec::new());
+    let _ = T::deserialize(Checked {
+        value,
+        path: path.to_string(),
+        unknown: &unknown,
+    });
+    unknown.into_inner()
+}
+
+/// Fields of a JSON object missing from `known`, without looking deeper
+pub fn unknown_keys(object: &Map<String, Value>, path: &str, known: &[&str]) -> Vec<UnknownField> {
+    object
+        .keys()
+        .filter(|key| !known.contains(&key.as_str()))
+        .map(|key| UnknownField::new(path, key, known))
+        .collect()
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+/// Deserializer over a JSON value that records fields structs do not declare
+struct Checked<'a, 'de> {
+    value: &'de Value,
+    path: String,
+    unknown: &'a RefCell<Vec<UnknownField>>,
+}
+
+impl<'de> Checked<'_, 'de> {
+    fn visit_object<V: Visitor<'de>>(
+        self,
+        object: &'de Map<String, Value>,
+        visitor: V,
+    ) -> Result<V::Value, serde_json::Error> {
+        visitor.visit_map(CheckedMap {
+            entries: object.iter(),
+            pending: None,
+            path: self.path,
+            unknown: self.unknown,
+        })
+    }
+
+    fn visit_array<V: Visitor<'de>>(
+        self,
+        items: &'de [Value],
+        visitor: V,
+    ) -> Result<V::Value, serde_json::Error> {
+        visitor.visit_seq(CheckedSeq {
+            items: items.iter().enumerate(),
+            path: self.path,
+            unknown: self.unknown,
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Checked<'_, 'de> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Object(object) => self.visit_object(object, visitor),
+            Value::Array(items) => self.visit_array(items, visitor),
+            value => de::Deserializer::deserialize_any(value, visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Object(object) => {
+                self.unknown
+                    .borrow_mut()
+                    .extend(unknown_keys(object, &self.path, fields));
+                self.visit_object(object, visitor)
+            }
+            value => value.deserialize_struct(name, fields, visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+        ignored_any
+    }
+}
+
+struct CheckedMap<'a, 'de> {
+    entries: serde_json::map::Iter<'de>,
+    pending: Option<(&'de String, &'de Value)>,
+    path: String,
+    unknown: &'a RefCell<Vec<UnknownField>>,
+}
+
+impl<'de> MapAccess<'de> for CheckedMap<'_, 'de> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.pending = Some((key, value));
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, value) = self
+            .pending
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(Checked {
+            value,
+            path: join_path(&self.path, key),
+            unknown: self.unknown,
+        })
+    }
+}
+
+struct CheckedSeq<'a, 'de> {
+    items: std::iter::Enumerate<std::slice::Iter<'de, Value>>,
+    path: String,
+    unknown: &'a RefCell<Vec<UnknownField>>,
+}
+
+impl<'de> SeqAccess<'de> for CheckedSeq<'_, 'de> {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some((index, value)) = self.items.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(Checked {
+            value,
+            path: format!("{}[{}]", self.path, index),
+            unknown: self.unknown,
+        })
+        .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Inner {
+        provider: String,
+        #[serde(default)]
+        sample_rate: Option<u32>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Outer {
+        stt_config: Option<Inner>,
+        #[serde(default)]
+        tools: Vec<Inner>,
+        #[serde(default)]
+        metadata: HashMap<String, String>,
+    }
+
+    fn paths(unknown: &[UnknownField]) -> Vec<&str> {
+        unknown.iter().map(|field| field.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_nested_unknown_fields_are_all_reported() {
+        let value = json!({
+            "stt_config": {"porvider": "deepgram", "sample_rate": 16000, "langauge": "en"},
+            "tools": [{"provider": "a"}, {"provider": "b", "sampel_rate": 8000}],
+            "metadata": {"anything": "goes"},
+            "stt_confg": {}
+        });
+        let mut unknown = collect_unknown_fields::<Outer>(&value, "");
+        unknown.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            paths(&unknown),
+            vec![
+                "stt_confg",
+                "stt_config.langauge",
+                "stt_config.porvider",
+                "tools[1].sampel_rate",
+            ]
+        );
+        assert_eq!(unknown[0].suggestion.as_deref(), Some("stt_config"));
+        assert_eq!(unknown[1].suggestion, None);
+        assert_eq!(unknown[2].suggestion.as_deref(), Some("provider"));
+        assert_eq!(unknown[3].suggestion.as_deref(), Some("sample_rate"));
+    }
+
+    #[test]
+    fn test_known_fields_pass() {
+        let value = json!({"stt_config": {"provider": "deepgram"}, "tools": []});
+        assert!(collect_unknown_fields::<Outer>(&value, "").is_empty());
+        assert!(collect_unknown_fields::<Inner>(&json!(null), "stt_config").is_empty());
+    }
+
+    #[test]
+    fn test_message() {
+        let unknown = collect_unknown_fields::<Inner>(
+            &json!({"porvider": "deepgram", "xyz": 1}),
+            "stt_config",
+        );
+        assert_eq!(
+            unknown_fields_message(&unknown),
+            "Unknown fields in config message: 'stt_config.porvider' (did you mean 'provider'?), 'stt_config.xyz'"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Outbound webhook queue endpoints
+//!
+//! Admin-only endpoints for inspecting the durable webhook queue and
+//! retrying its events without waiting for their backoff, e.g. once a
+//! receiver is back after an outage. Dropped events are queued again by a
+//! replay.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::webhooks::{WebhookBacklog, WebhookQueueError, WebhookReplay};
+
+/// Queued and dropped webhook events
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PendingWebhooksResponse {
+    /// Backlog of each registered destination
+    pub destinations: Vec<WebhookBacklog>,
+}
+
+/// Query parameters of a replay
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(
+    feature = "openapi",
+    derive(utoipa::IntoParams),
+    into_params(parameter_in = Query)
+)]
+pub struct ReplayWebhooksQuery {
+    /// Only replay this destination (`usage`, `session_export` or `enrichment`)
+    pub destination: Option<String>,
+}
+
+fn not_configured() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Webhook queue not configured"})),
+    )
+        .into_response()
+}
+
+/// List the queued and dropped webhook events
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/admin/webhooks/pending",
+        responses(
+            (status = 200, description = "Queued and dropped events per destination", body = PendingWebhooksResponse),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:read scope"),
+            (status = 404, description = "Webhook queue not configured")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "webhooks"
+    )
+)]
+pub async fn get_pending_webhooks(State(state): State<Arc<AppState>>) -> Response {
+    let Some(queue) = &state.webhook_queue else {
+        return not_configured();
+    };
+    let destinations = queue.backlog().await;
+    (
+        StatusCode::OK,
+        Json(PendingWebhooksResponse { destinations }),
+    )
+        .into_response()
+}
+
+/// Retry queued webhook events now and queue dropped ones again
+///
+/// Replayed events start over with no failed attempts.
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        post,
+        path = "/admin/webhooks/replay",
+        params(ReplayWebhooksQuery),
+        responses(
+            (status = 200, description = "Events replayed", body = WebhookReplay),
+            (status = 401, description = "Unauthorized"),
+            (status = 403, description = "Missing the admin:write scope"),
+            (status = 404, description = "Webhook queue not configured or unknown destination")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "webhooks"
+    )
+)]
+pub async fn replay_webhooks(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReplayWebhooksQuery>,
+) -> Response {
+    let Some(queue) = &state.webhook_queue else {
+        return not_configured();
+    };
+    match queue.replay(query.destination.as_deref()).await {
+        Ok(replay) => (StatusCode::OK, Json(replay)).into_response(),
+        Err(e @ WebhookQueueError::UnknownDestination(_)) => {
+            (StatusCode::NOT_FOUND, Json(json!({"error": e.to_string()}))).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,116 @@
+//! Agent profile resolution for config messages
+//!
+//! A config message with `agent` set is expanded into the full config
+//! message the profile describes before it is handled like any other.
+
+use serde_json::{Map, Value};
+use tracing::info;
+
+use crate::agents::{AgentProfileError, AgentSection};
+use crate::state::AppState;
+
+use super::messages::IncomingMessage;
+
+/// Expand a config message that names an agent profile
+///
+/// Messages without `agent` are returned unchanged. Otherwise the profile
+/// visible to `client_id` is resolved with the message's `overrides` and
+/// `metadata`, and the result is parsed and validated as a config message.
+///
+/// # Returns
+/// * `Ok(IncomingMessage)` - The resolved config message (without `agent`)
+/// * `Err(String)` - Error message for the client: an unknown agent (listing
+///   the profiles the client may use), a field sent alongside `agent`, an
+///   override the profile does not allow, or an invalid resolved config
+pub async fn resolve_agent_config(
+    msg: IncomingMessage,
+    client_id: Option<&str>,
+    app_state: &AppState,
+) -> Result<IncomingMessage, String> {
+    let IncomingMessage::Config {
+        stream_id,
+        audio_disabled,
+        stt_config,
+        tts_config,
+        livekit,
+        dag_config,
+        agent_config,
+        metadata,
+        greeting,
+        audio_levels,
+        heartbeat,
+        agent: Some(agent),
+        overrides,
+        dry_run,
+        flight_recorder,
+        ..
+    } = msg
+    else {
+        return Ok(msg);
+    };
+
+    // The profile supplies the configuration; changes go through `overrides`
+    // so they can be checked against what the profile allows
+    for (field, is_set) in [
+        ("audio_disabled", audio_disabled.is_some()),
+        ("stt_config", stt_config.is_some()),
+        ("tts_config", tts_config.is_some()),
+        ("livekit", livekit.is_some()),
+        ("dag_config", dag_config.is_some()),
+        ("agent_config", agent_config.is_some()),
+        ("greeting", greeting.is_some()),
+    ] {
+        if is_set {
+            return Err(AgentProfileError::FieldWithAgent {
+                field: field.to_string(),
+            }
+            .to_string());
+        }
+    }
+
+    let metadata: Option<Map<String, Value>> = metadata.map(|metadata| {
+        metadata
+            .into_iter()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect()
+    });
+
+    let mut fields = {
+        let profiles = app_state.agent_profiles.read().await;
+        let profile = profiles
+            .lookup(&agent, client_id)
+            .map_err(|e| e.to_string())?;
+        profile
+            .resolve(AgentSection::Session, overrides.as_ref(), metadata.as_ref())
+            .map_err(|e| e.to_string())?
+    };
+
+    fields.insert("type".to_string(), Value::String("config".to_string()));
+    if let Some(stream_id) = stream_id {
+        fields.insert("stream_id".to_string(), Value::String(stream_id));
+    }
+    if let Some(audio_levels) = audio_levels {
+        fields.insert("audio_levels".to_string(), Value::Bool(audio_levels));
+    }
+    if let Some(heartbeat) = heartbeat {
+        fields.insert(
+            "heartbeat".to_string(),
+            Value::String(heartbeat.as_str().to_string()),
+        );
+    }
+    if let Some(dry_run) = dry_run {
+        fields.insert("dry_run".to_string(), Value::Bool(dry_run));
+    }
+    if let Some(flight_recorder) = flight_recorder {
+        fields.insert("flight_recorder".to_string(), Value::Bool(flight_recorder));
+    }
+
+    let resolved: IncomingMessage = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| format!("Invalid configuration for agent '{agent}': {e}"))?;
+    resolved
+        .validate_size()
+        .map_err(|e| format!("Invalid configuration for agent '{agent}': {e}"))?;
+
+    info!(agent = %agent, "Resolved session configuration from agent profile");
+    Ok(resolved)
+}
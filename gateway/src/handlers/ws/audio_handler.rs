@@ -5,16 +5,27 @@
 //! - Routing audio through STT (Speech-to-Text) providers
 //! - Managing TTS (Text-to-Speech) synthesis requests
 //! - Handling audio clear/interruption commands
+//! - Injecting pre-synthesized audio (`play_audio`) into the TTS output path
 
+use base64::prelude::*;
 use bytes::Bytes;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
 use tracing::{debug, error, info, warn};
 
-use crate::core::voice_manager::VoiceManager;
+use crate::core::session::{AudioDirection, Session, SessionError};
+use crate::core::sink_audio::{SinkAudioFormat, is_pcm16};
+use crate::core::stt::UtteranceKind;
+use crate::core::voice_manager::{SpeakPriority, VoiceManagerError};
+use crate::errors::provider_error::ErrorSanitizer;
+use crate::state::SessionEventBus;
 
 use super::{
     messages::{MessageRoute, OutgoingMessage},
+    play_audio::{
+        MAX_PLAY_AUDIO_INLINE_SIZE, MAX_PLAY_AUDIO_SIZE, PendingPlayAudio, PlayAudioError,
+        PlayAudioFormat, check_session_budget, decode_play_audio,
+    },
     state::ConnectionState,
 };
 
@@ -28,12 +39,16 @@ pub const MAX_AUDIO_FRAME_SIZE: usize = 5 * 1024 * 1024;
 /// Handle incoming audio data with zero-copy optimizations
 ///
 /// Processes raw audio data received from WebSocket clients and forwards it
-/// to the configured STT provider for transcription.
+/// to the configured STT provider for transcription. The audio is also
+/// published to observers receiving the session's monitor audio and written
+/// to the caller recording, both of which get silence while a
+/// [redaction](crate::core::redaction) window is open.
 ///
 /// # Arguments
 /// * `audio_data` - Raw audio bytes received from the client
-/// * `state` - Connection state containing voice manager and configuration
+/// * `state` - Connection state containing the voice session and configuration
 /// * `message_tx` - Channel for sending response messages back to the client
+/// * `observers` - Event bus of the session's observers
 ///
 /// # Returns
 /// * `bool` - true to continue processing, false to terminate connection
@@ -47,6 +62,7 @@ pub async fn handle_audio_message(
     audio_data: Bytes,
     state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
+    observers: &SessionEventBus,
 ) -> bool {
     let audio_len = audio_data.len();
     debug!("Processing audio data: {} bytes", audio_len);
@@ -68,8 +84,8 @@ pub async fn handle_audio_message(
         return true;
     }
 
-    // Fast path: read lock to check state and get voice manager
-    let voice_manager = {
+    // Fast path: read lock to check state and get the voice session
+    let session = {
         let state_guard = state.read().await;
 
         // Check if audio processing is enabled (atomic read, no lock overhead)
@@ -84,8 +100,34 @@ pub async fn handle_audio_message(
             return true;
         }
 
-        match &state_guard.voice_manager {
-            Some(vm) => vm.clone(),
+        match &state_guard.session {
+            Some(session) => {
+                if let Some(stream_id) = &state_guard.stream_id {
+                    // Caller audio is in the format of the STT config
+                    let (channels, pcm16) = session
+                        .effective_config()
+                        .stt
+                        .as_ref()
+                        .map_or((1, true), |stt| (stt.channels, is_pcm16(&stt.encoding)));
+                    // Encoded audio has no all-zero silence; redacted frames are dropped
+                    if pcm16 || !state_guard.redaction.is_active() {
+                        observers.publish_audio(
+                            stream_id,
+                            AudioDirection::In,
+                            SinkAudioFormat {
+                                sample_rate: session.input_sample_rate(),
+                                channels,
+                            },
+                            pcm16,
+                            &state_guard.redaction.redact(&audio_data),
+                        );
+                    }
+                }
+                if let Some(recording) = &state_guard.input_recording {
+                    recording.write(&state_guard.redaction.redact(&audio_data));
+                }
+                session.clone()
+            }
             None => {
                 let _ = message_tx
                     .send(MessageRoute::Outgoing(OutgoingMessage::Error {
@@ -103,7 +145,7 @@ pub async fn handle_audio_message(
 
     // Send audio to STT provider with zero-copy optimization
     // Bytes type provides O(1) cloning via reference counting
-    if let Err(e) = voice_manager.receive_audio(audio_data).await {
+    if let Err(e) = session.push_audio(audio_data).await {
         error!("Failed to process audio: {}", e);
         let _ = message_tx
             .send(MessageRoute::Outgoing(OutgoingMessage::Error {
@@ -118,39 +160,49 @@ pub async fn handle_audio_message(
 /// Handle text-to-speech synthesis request
 ///
 /// Processes speak commands to synthesize text into audio using the configured
-/// TTS provider. Supports queuing, flushing, and interruption control.
+/// TTS provider. Supports queuing, flushing, interruption control and
+/// priorities, with `system` speech pre-empting the queue.
 ///
 /// # Arguments
 /// * `text` - Text to synthesize into speech
 /// * `flush` - Whether to clear the TTS queue before speaking (default: true)
 /// * `allow_interruption` - Whether this audio can be interrupted (default: true)
-/// * `state` - Connection state containing voice manager
+/// * `turn_id` - Turn the speech answers (default: the latest user turn)
+/// * `priority` - Priority of the speech (default: normal)
+/// * `state` - Connection state containing the voice session
 /// * `message_tx` - Channel for sending response messages
+/// * `error_sanitizer` - Sanitizes provider errors before they reach the client
 ///
 /// # Returns
 /// * `bool` - true to continue processing, false to terminate connection
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_speak_message(
     text: String,
     flush: Option<bool>,
     allow_interruption: Option<bool>,
+    turn_id: Option<String>,
+    priority: Option<SpeakPriority>,
     state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
+    error_sanitizer: &ErrorSanitizer,
 ) -> bool {
     // Default flush to true for backward compatibility
     let should_flush = flush.unwrap_or(true);
     // Default allow_interruption to true for backward compatibility
     let allow_interruption = allow_interruption.unwrap_or(true);
+    let priority = priority.unwrap_or_default();
 
     debug!(
-        "Processing speak command: {} chars (flush: {}, allow_interruption: {})",
+        "Processing speak command: {} chars (flush: {}, allow_interruption: {}, priority: {})",
         text.len(),
         should_flush,
-        allow_interruption
+        allow_interruption,
+        priority
     );
 
-    // Fast path: read lock to check state and get voice manager
-    let voice_manager = match get_voice_manager_if_audio_enabled(state, message_tx).await {
-        Some(vm) => vm,
+    // Fast path: read lock to check state and get the voice session
+    let session = match get_session_if_audio_enabled(state, message_tx).await {
+        Some(session) => session,
         None => return true,
     };
 
@@ -159,24 +211,44 @@ pub async fn handle_speak_message(
         should_flush, allow_interruption, text
     );
 
-    // Send text to TTS provider with flush and allow_interruption parameters
-    if let Err(e) = voice_manager
-        .speak_with_interruption(&text, should_flush, allow_interruption)
-        .await
-    {
-        error!("Failed to synthesize speech: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to synthesize speech: {e}"),
-            }))
-            .await;
-    } else {
-        debug!(
-            "Speech synthesis started for: {} chars (flush: {}, allow_interruption: {})",
-            text.len(),
+    // Send text to TTS provider with flush, allow_interruption and priority parameters
+    let result = session
+        .speak_with_priority(
+            &text,
             should_flush,
-            allow_interruption
-        );
+            allow_interruption,
+            turn_id.as_deref(),
+            priority,
+        )
+        .await;
+    match result {
+        // The client is told through the tts.queue_full event
+        Err(SessionError::VoiceManager(VoiceManagerError::TTSQueueFull { max_pending })) => {
+            warn!(
+                "Speak rejected: {} TTS utterances already pending",
+                max_pending
+            );
+        }
+        Err(e) => {
+            let code = e.error_code();
+            error!(code, "Failed to synthesize speech");
+            let error = OutgoingMessage::provider_error(
+                error_sanitizer,
+                code,
+                &format!("Failed to synthesize speech: {e}"),
+                turn_id,
+            );
+            let _ = message_tx.send(MessageRoute::Outgoing(error)).await;
+        }
+        Ok(turn_id) => {
+            debug!(
+                "Speech synthesis started for: {} chars (flush: {}, allow_interruption: {}, turn: {:?})",
+                text.len(),
+                should_flush,
+                allow_interruption,
+                turn_id
+            );
+        }
     }
 
     true
@@ -188,7 +260,7 @@ pub async fn handle_speak_message(
 /// audio playback settings.
 ///
 /// # Arguments
-/// * `state` - Connection state containing voice and LiveKit managers
+/// * `state` - Connection state containing the voice session and LiveKit client
 /// * `message_tx` - Channel for sending response messages
 ///
 /// # Returns
@@ -199,14 +271,14 @@ pub async fn handle_clear_message(
 ) -> bool {
     debug!("Processing clear command");
 
-    // Fast path: read lock to get both managers
-    let (voice_manager, livekit_client) = {
+    // Fast path: read lock to get the session and LiveKit client
+    let (session, livekit_client) = {
         let state_guard = state.read().await;
 
-        // Check if audio processing is enabled for voice manager operations
-        let vm = if state_guard.is_audio_enabled() {
-            match &state_guard.voice_manager {
-                Some(vm) => Some(vm.clone()),
+        // Check if audio processing is enabled for session operations
+        let session = if state_guard.is_audio_enabled() {
+            match &state_guard.session {
+                Some(session) => Some(session.clone()),
                 None => {
                     let _ = message_tx
                         .send(MessageRoute::Outgoing(OutgoingMessage::Error {
@@ -218,39 +290,30 @@ pub async fn handle_clear_message(
                 }
             }
         } else {
-            // Audio is disabled, so voice manager operations are not available
+            // Audio is disabled, so session operations are not available
             None
         };
 
-        let lk = state_guard.livekit_client.clone();
-        (vm, lk)
+        (session, state_guard.livekit_client.clone())
     };
 
-    // Check if we're in a non-interruptible state
-    let is_blocked = if let Some(ref vm) = voice_manager {
-        vm.is_interruption_blocked().await
-    } else {
-        false
-    };
-
-    if is_blocked {
-        debug!("Clear command ignored - currently in non-interruptible audio playback");
-        return true;
-    }
-
-    // Clear TTS provider and audio buffers (only if audio is enabled)
-    // Note: The VoiceManager's clear_tts() will automatically call the audio_clear_callback
-    // which clears the LiveKit audio buffer, so we don't need to do it separately
-    if let Some(vm) = voice_manager {
-        if let Err(e) = vm.clear_tts().await {
-            error!("Failed to clear TTS provider: {}", e);
-            let _ = message_tx
-                .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                    message: format!("Failed to clear TTS provider: {e}"),
-                }))
-                .await;
-        } else {
-            debug!("Successfully cleared TTS and audio buffers");
+    // Interrupt the session: cancels the agent reply, clears TTS and emits
+    // AudioCleared, which the event forwarder turns into a LiveKit clear
+    if let Some(session) = session {
+        match session.interrupt().await {
+            Ok(true) => debug!("Successfully cleared TTS and audio buffers"),
+            Ok(false) => {
+                debug!("Clear command ignored - currently in non-interruptible audio playback");
+                return true;
+            }
+            Err(e) => {
+                error!("Failed to clear TTS provider: {}", e);
+                let _ = message_tx
+                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                        message: format!("Failed to clear TTS provider: {e}"),
+                    }))
+                    .await;
+            }
         }
     } else {
         debug!("Audio processing disabled - skipping TTS provider clear");
@@ -274,21 +337,291 @@ pub async fn handle_clear_message(
     true
 }
 
-/// Helper function to get voice manager if audio is enabled
+/// Handle expect command
+///
+/// Routes the caller's next turn to the fast or the premium STT provider.
+/// Without `routing` in the STT config the hint is ignored.
+///
+/// # Arguments
+/// * `kind` - What the caller is expected to say next
+/// * `state` - Connection state holding the session
+/// * `message_tx` - Channel for sending error messages
+///
+/// # Returns
+/// * `bool` - Always true to continue processing
+pub async fn handle_expect_message(
+    kind: UtteranceKind,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+) -> bool {
+    let session = state.read().await.session.clone();
+    let Some(session) = session else {
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: "Voice manager not configured. Send config message with audio=true first."
+                    .to_string(),
+            }))
+            .await;
+        return true;
+    };
+
+    if !session.expect_utterance(kind) {
+        debug!(?kind, "Ignoring expect hint - STT routing not configured");
+    }
+    true
+}
+
+/// Handle a `play_audio` request
+///
+/// Validates the format, sample rate and size limits, then either plays the
+/// inline base64 clip or starts collecting `size` bytes of binary frames.
+/// Playback goes through the session's TTS output path, so it reaches
+/// LiveKit (and room recordings) or the WebSocket exactly like synthesized speech.
+///
+/// # Arguments
+/// * `format` - Audio format ("wav", "linear16" or "pcm")
+/// * `sample_rate` - Sample rate of the audio; must match the session TTS sample rate
+/// * `audio` - Optional base64-encoded clip
+/// * `size` - Optional size of the binary frames that follow
+/// * `allow_interruption` - Whether this audio can be interrupted (default: true)
+/// * `state` - Connection state containing the voice session
+/// * `message_tx` - Channel for sending response messages
+///
+/// # Returns
+/// * `bool` - true to continue processing, false to terminate connection
+pub async fn handle_play_audio_message(
+    format: String,
+    sample_rate: u32,
+    audio: Option<String>,
+    size: Option<usize>,
+    allow_interruption: Option<bool>,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+) -> bool {
+    let allow_interruption = allow_interruption.unwrap_or(true);
+
+    debug!(
+        "Processing play_audio command (format: {}, sample_rate: {}, inline: {}, size: {:?})",
+        format,
+        sample_rate,
+        audio.is_some(),
+        size
+    );
+
+    let session = match get_session_if_audio_enabled(state, message_tx).await {
+        Some(session) => session,
+        None => return true,
+    };
+
+    let result = match PlayAudioFormat::parse(&format) {
+        None => Err(PlayAudioError::UnsupportedFormat(format)),
+        Some(format) => {
+            let output_sample_rate = session.output_sample_rate();
+            if sample_rate != output_sample_rate {
+                Err(PlayAudioError::SampleRateMismatch {
+                    audio: sample_rate,
+                    output: output_sample_rate,
+                })
+            } else {
+                match (audio, size) {
+                    (Some(encoded), None) => decode_inline_audio(&encoded, state)
+                        .await
+                        .and_then(|data| decode_play_audio(format, sample_rate, data))
+                        .map(|pcm| {
+                            spawn_play_audio(
+                                session,
+                                pcm,
+                                sample_rate,
+                                allow_interruption,
+                                message_tx,
+                            )
+                        }),
+                    (None, Some(size)) => {
+                        start_play_audio_transfer(
+                            format,
+                            sample_rate,
+                            allow_interruption,
+                            size,
+                            state,
+                        )
+                        .await
+                    }
+                    _ => Err(PlayAudioError::InvalidSource),
+                }
+            }
+        }
+    };
+
+    if let Err(e) = result {
+        warn!("Rejected play_audio request: {}", e);
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: e.to_string(),
+            }))
+            .await;
+    }
+
+    true
+}
+
+/// Handle a binary frame belonging to a pending `play_audio` transfer
+///
+/// Appends the frame to the pending buffer and starts playback once the
+/// declared size has been received. On overrun the transfer is dropped.
+///
+/// # Returns
+/// * `bool` - true to continue processing, false to terminate connection
+pub async fn handle_play_audio_frame(
+    frame: Bytes,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+) -> bool {
+    let completed = {
+        let mut state_guard = state.write().await;
+        let Some(pending) = state_guard.pending_play_audio.as_mut() else {
+            return true;
+        };
+
+        match pending.push(&frame) {
+            Ok(false) => return true,
+            Ok(true) => (
+                state_guard.pending_play_audio.take(),
+                state_guard.session.clone(),
+            ),
+            Err(e) => {
+                state_guard.pending_play_audio = None;
+                drop(state_guard);
+                warn!("Dropped play_audio transfer: {}", e);
+                let _ = message_tx
+                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                        message: e.to_string(),
+                    }))
+                    .await;
+                return true;
+            }
+        }
+    };
+
+    let (Some(pending), Some(session)) = completed else {
+        return true;
+    };
+
+    let format = pending.format;
+    let sample_rate = pending.sample_rate;
+    let allow_interruption = pending.allow_interruption;
+
+    match decode_play_audio(format, sample_rate, pending.into_data()) {
+        Ok(pcm) => spawn_play_audio(session, pcm, sample_rate, allow_interruption, message_tx),
+        Err(e) => {
+            warn!("Rejected play_audio data: {}", e);
+            let _ = message_tx
+                .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                    message: e.to_string(),
+                }))
+                .await;
+        }
+    }
+
+    true
+}
+
+/// Decode an inline base64 clip and charge it to the session budget
+async fn decode_inline_audio(
+    encoded: &str,
+    state: &Arc<RwLock<ConnectionState>>,
+) -> Result<Vec<u8>, PlayAudioError> {
+    let data = BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|e| PlayAudioError::InvalidBase64(e.to_string()))?;
+    if data.len() > MAX_PLAY_AUDIO_INLINE_SIZE {
+        return Err(PlayAudioError::TooLarge {
+            size: data.len(),
+            max: MAX_PLAY_AUDIO_INLINE_SIZE,
+        });
+    }
+
+    let mut state_guard = state.write().await;
+    check_session_budget(state_guard.play_audio_bytes, data.len())?;
+    state_guard.play_audio_bytes += data.len();
+    Ok(data)
+}
+
+/// Start collecting binary frames for a `play_audio` transfer
+async fn start_play_audio_transfer(
+    format: PlayAudioFormat,
+    sample_rate: u32,
+    allow_interruption: bool,
+    size: usize,
+    state: &Arc<RwLock<ConnectionState>>,
+) -> Result<(), PlayAudioError> {
+    if size == 0 || size > MAX_PLAY_AUDIO_SIZE {
+        return Err(PlayAudioError::TooLarge {
+            size,
+            max: MAX_PLAY_AUDIO_SIZE,
+        });
+    }
+
+    let mut state_guard = state.write().await;
+    if state_guard.is_play_audio_pending() {
+        return Err(PlayAudioError::TransferInProgress);
+    }
+    check_session_budget(state_guard.play_audio_bytes, size)?;
+    state_guard.play_audio_bytes += size;
+    state_guard.pending_play_audio = Some(PendingPlayAudio::new(
+        format,
+        sample_rate,
+        allow_interruption,
+        size,
+    ));
+    Ok(())
+}
+
+/// Play decoded PCM in the background so `clear` can interrupt it
+fn spawn_play_audio(
+    session: Arc<Session>,
+    pcm: Vec<u8>,
+    sample_rate: u32,
+    allow_interruption: bool,
+    message_tx: &mpsc::Sender<MessageRoute>,
+) {
+    let message_tx = message_tx.clone();
+    info!(
+        "Playing injected audio: {} bytes at {} Hz (allow_interruption: {})",
+        pcm.len(),
+        sample_rate,
+        allow_interruption
+    );
+
+    tokio::spawn(async move {
+        if let Err(e) = session
+            .play_audio(pcm, sample_rate, allow_interruption)
+            .await
+        {
+            error!("Failed to play injected audio: {}", e);
+            let _ = message_tx
+                .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                    message: format!("Failed to play audio: {e}"),
+                }))
+                .await;
+        }
+    });
+}
+
+/// Helper function to get the voice session if audio is enabled
 ///
-/// Checks if audio processing is enabled and returns the voice manager if available.
-/// Sends appropriate error messages if audio is disabled or voice manager is not configured.
+/// Checks if audio processing is enabled and returns the session if available.
+/// Sends appropriate error messages if audio is disabled or the session is not configured.
 ///
 /// # Arguments
 /// * `state` - Connection state to check
 /// * `message_tx` - Channel for sending error messages
 ///
 /// # Returns
-/// * `Option<Arc<VoiceManager>>` - Voice manager if available, None otherwise
-async fn get_voice_manager_if_audio_enabled(
+/// * `Option<Arc<Session>>` - Voice session if available, None otherwise
+async fn get_session_if_audio_enabled(
     state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
-) -> Option<Arc<VoiceManager>> {
+) -> Option<Arc<Session>> {
     let state_guard = state.read().await;
 
     // Check if audio processing is enabled (atomic read, no lock overhead)
@@ -302,8 +635,8 @@ async fn get_voice_manager_if_audio_enabled(
         return None;
     }
 
-    match &state_guard.voice_manager {
-        Some(vm) => Some(vm.clone()),
+    match &state_guard.session {
+        Some(session) => Some(session.clone()),
         None => {
             let _ = message_tx
                 .send(MessageRoute::Outgoing(OutgoingMessage::Error {
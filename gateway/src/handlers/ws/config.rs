@@ -3,16 +3,26 @@
 //! This module contains all configuration-related types for WebSocket connections,
 //! including STT, TTS, and LiveKit configurations without API keys.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use xxhash_rust::xxh3::xxh3_128;
 
 use crate::{
+    config::{VoiceProfile, resolve_voice_profile},
     core::{
         emotion::{DeliveryStyle, Emotion, EmotionConfig, EmotionIntensity},
-        stt::STTConfig,
-        tts::{Pronunciation, TTSConfig},
+        session::{
+            BargeInConfirmationConfig, BargeInMode, ConsentConfig, EchoGuardConfig,
+            LatencyBudgetConfig, PipelineWatchdogConfig, RetryBudgetConfig,
+            TranscriptFormattingConfig,
+        },
+        stt::{STTConfig, STTFailoverConfig, STTTurnRoutingConfig},
+        tts::{Pronunciation, TTSConfig, TTSOutputProfile},
+        validation::ConfigIssue,
+        voice_manager::{AdaptiveEndpointingConfig, FillerAudioConfig, TTSAudioQualityConfig},
     },
-    livekit::LiveKitConfig,
+    livekit::{AudioPacingConfig, LiveKitConfig},
 };
 
 /// DAG configuration for WebSocket messages
@@ -58,7 +68,10 @@ pub struct STTWebSocketConfig {
     /// Provider name (e.g., "deepgram")
     #[cfg_attr(feature = "openapi", schema(example = "deepgram"))]
     pub provider: String,
-    /// Language code for transcription (e.g., "en-US", "es-ES")
+    /// Language code for transcription (e.g., "en-US", "es-ES"). Empty or
+    /// omitted leaves it to the provider, or for inbound SIP calls to the
+    /// configured SIP language pack
+    #[serde(default)]
     #[cfg_attr(feature = "openapi", schema(example = "en-US"))]
     pub language: String,
     /// Sample rate of the audio in Hz
@@ -76,9 +89,58 @@ pub struct STTWebSocketConfig {
     /// Model to use for transcription
     #[cfg_attr(feature = "openapi", schema(example = "nova-2"))]
     pub model: String,
+    /// Accept models missing from the provider's known-model list (e.g.
+    /// models released after this gateway version); also covers the
+    /// failover and routing providers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_unknown_models: bool,
     /// Optional API key for this provider (overrides server config)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    /// Adapt the end-of-turn silence threshold to each utterance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive_endpointing: Option<AdaptiveEndpointingConfig>,
+    /// Interrupt TTS output when the caller starts speaking
+    /// ("on_vad", "on_interim" or "off"; default "off")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub barge_in: Option<BargeInMode>,
+    /// Only interrupt once the caller's speech reaches a minimum number of
+    /// words or characters with enough confidence within a time window;
+    /// without it, barge-in is immediate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub barge_in_confirmation: Option<BargeInConfirmationConfig>,
+    /// Keep the assistant's own audio, echoed back through the caller's mic,
+    /// from interrupting it: stricter barge-in thresholds while TTS plays and
+    /// dropping transcripts that repeat the spoken text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo_guard: Option<EchoGuardConfig>,
+    /// Restore sentence case, drop filler words and write numbers as digits
+    /// in transcripts, consistently across providers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatting: Option<TranscriptFormattingConfig>,
+    /// Deadlines from the end of the caller's speech to the first reply
+    /// audio, and what to do when a turn misses them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_budget: Option<LatencyBudgetConfig>,
+    /// Reconnect the STT or TTS provider when it stops responding while the
+    /// caller is speaking or speech is pending
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog: Option<PipelineWatchdogConfig>,
+    /// Hold the caller's audio from STT until they consent to the call being
+    /// processed (a `consent` message or DTMF digit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consent: Option<ConsentConfig>,
+    /// Bound the provider reconnects of the session, with STT reconnects
+    /// taking precedence over TTS retries and voice-list refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<RetryBudgetConfig>,
+    /// Secondary provider to switch to when this one fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover: Option<STTFailoverWebSocketConfig>,
+    /// Fast provider for turns the client expects to be short (see the
+    /// `expect` message)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<STTRoutingWebSocketConfig>,
 }
 
 impl STTWebSocketConfig {
@@ -99,6 +161,97 @@ impl STTWebSocketConfig {
             punctuation: self.punctuation,
             encoding: self.encoding.clone(),
             model: self.model.clone(),
+            custom_headers: Default::default(),
+            certificate_path: None,
+        }
+    }
+}
+
+/// Secondary STT provider for WebSocket messages (with optional API key)
+///
+/// The secondary transcribes the same audio as the primary, so it shares the
+/// primary's language and audio format.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct STTFailoverWebSocketConfig {
+    /// Provider name (e.g., "assemblyai")
+    #[cfg_attr(feature = "openapi", schema(example = "assemblyai"))]
+    pub provider: String,
+    /// Model to use for transcription; empty for the provider default
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(example = "universal-streaming"))]
+    pub model: String,
+    /// Optional API key for this provider (overrides server config)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Keep the secondary connected and transcribing alongside the primary,
+    /// so it takes over without losing words. Both providers bill for the
+    /// whole session.
+    #[serde(default)]
+    pub warm_standby: bool,
+}
+
+impl STTFailoverWebSocketConfig {
+    /// Convert to a failover config for the given primary STT config
+    ///
+    /// # Arguments
+    /// * `primary` - Configuration of the primary provider
+    /// * `api_key` - The API key to use for the secondary provider
+    ///
+    /// # Returns
+    /// * `STTFailoverConfig` - Secondary provider and failover mode
+    pub fn to_failover_config(&self, primary: &STTConfig, api_key: String) -> STTFailoverConfig {
+        STTFailoverConfig {
+            secondary: STTConfig {
+                provider: self.provider.clone(),
+                api_key,
+                model: self.model.clone(),
+                custom_headers: Default::default(),
+                ..primary.clone()
+            },
+            warm_standby: self.warm_standby,
+        }
+    }
+}
+
+/// Fast STT provider for WebSocket messages (with optional API key)
+///
+/// Turns announced as confirmations or digits are transcribed by this
+/// provider instead of the one in `stt_config`. It shares the primary's
+/// language and audio format.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct STTRoutingWebSocketConfig {
+    /// Provider name (e.g., "deepgram")
+    #[cfg_attr(feature = "openapi", schema(example = "deepgram"))]
+    pub provider: String,
+    /// Model to use for transcription; empty for the provider default
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(example = "base"))]
+    pub model: String,
+    /// Optional API key for this provider (overrides server config)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+impl STTRoutingWebSocketConfig {
+    /// Convert to a turn routing config for the given primary STT config
+    ///
+    /// # Arguments
+    /// * `primary` - Configuration of the premium provider
+    /// * `api_key` - The API key to use for the fast provider
+    ///
+    /// # Returns
+    /// * `STTTurnRoutingConfig` - Fast provider configuration
+    pub fn to_routing_config(&self, primary: &STTConfig, api_key: String) -> STTTurnRoutingConfig {
+        STTTurnRoutingConfig {
+            fast: STTConfig {
+                provider: self.provider.clone(),
+                api_key,
+                model: self.model.clone(),
+                custom_headers: Default::default(),
+                ..primary.clone()
+            },
         }
     }
 }
@@ -130,9 +283,41 @@ pub struct LiveKitWebSocketConfig {
     ///   are in this list will be processed; others will be ignored
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub listen_participants: Vec<String>,
+    /// Duration of each published TTS audio frame in milliseconds (defaults to 20)
+    ///
+    /// Provider audio is re-chunked into frames of this size and published at a
+    /// steady pace. Must be a multiple of 10 between 10 and 100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = 20))]
+    pub audio_frame_ms: Option<u32>,
+    /// TTS audio buffered before playback starts or resumes, in milliseconds (defaults to 120)
+    ///
+    /// Absorbs provider jitter at the cost of start-up latency. At most 2000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = 120))]
+    pub audio_low_watermark_ms: Option<u32>,
+    /// The room's SIP participant is an outbound call being placed
+    ///
+    /// STT, usage and the greeting wait until the call is answered, and
+    /// `call_state` messages report its progress. Until then the server's
+    /// early media asset (`sip.outbound`) is played, and a call left
+    /// unanswered is abandoned.
+    #[serde(default)]
+    pub outbound_call: bool,
 }
 
 impl LiveKitWebSocketConfig {
+    /// Output pacing for published TTS audio, with defaults for unset fields
+    pub fn output_pacing(&self) -> AudioPacingConfig {
+        let defaults = AudioPacingConfig::default();
+        AudioPacingConfig {
+            frame_duration_ms: self.audio_frame_ms.unwrap_or(defaults.frame_duration_ms),
+            low_watermark_ms: self
+                .audio_low_watermark_ms
+                .unwrap_or(defaults.low_watermark_ms),
+        }
+    }
+
     /// Convert WebSocket LiveKit config to full LiveKit config with audio parameters
     ///
     /// # Arguments
@@ -161,6 +346,7 @@ impl LiveKitWebSocketConfig {
             enable_noise_filter: cfg!(feature = "noise-filter"),
             // Pass through the participant filter list
             listen_participants: self.listen_participants.clone(),
+            output_pacing: self.output_pacing(),
         }
     }
 }
@@ -190,9 +376,17 @@ pub struct TTSWebSocketConfig {
     /// Request timeout in seconds
     #[cfg_attr(feature = "openapi", schema(example = 60))]
     pub request_timeout: Option<u64>,
+    /// Per-utterance synthesis timeout in seconds (time without audio progress)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = 10))]
+    pub utterance_timeout: Option<u64>,
     /// Model to use for TTS
     #[cfg_attr(feature = "openapi", schema(example = "aura-asteria-en"))]
     pub model: String,
+    /// Accept models missing from the provider's known-model list (e.g.
+    /// models released after this gateway version)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_unknown_models: bool,
     /// Pronunciation replacements to apply before TTS
     #[serde(default)]
     pub pronunciations: Vec<Pronunciation>,
@@ -241,6 +435,60 @@ pub struct TTSWebSocketConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "openapi", schema(example = "warm, friendly, inviting"))]
     pub emotion_description: Option<String>,
+
+    /// Output profile for synthesized audio.
+    ///
+    /// `telephony` delivers 8kHz μ-law audio in 20ms (160-byte) frames for SIP/PSTN
+    /// bridges, overriding `audio_format` and `sample_rate`. Not supported with LiveKit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = "telephony"))]
+    pub output_profile: Option<TTSOutputProfile>,
+
+    /// Send the text around each chunk of an utterance to providers that use it
+    /// for smoother prosody (ElevenLabs `previous_text`/`next_text`).
+    ///
+    /// Defaults to `true`; set `false` to synthesize every chunk independently.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = true))]
+    pub context_hints: Option<bool>,
+
+    /// Send `tts_visemes` messages with viseme and word timing for avatar
+    /// lip-sync (Azure and plugins that report timing; default `false`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = true))]
+    pub visemes: Option<bool>,
+
+    /// Treat `speak` text without `flush` as partials that may resend the
+    /// sentence so far, and send only what is new.
+    ///
+    /// For LLM orchestrators that send "Hel", "Hell", "Hello" instead of
+    /// deltas. A partial that revises earlier text replaces the pending utterance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = true))]
+    pub dedupe_partials: Option<bool>,
+
+    /// Thresholds for flagging clipped, silent or inaudible synthesized audio.
+    ///
+    /// The check runs with default thresholds unless disabled with
+    /// `{"enabled": false}`. Only PCM output (`linear16`/`pcm`) is checked.
+    /// Failing utterances are reported as `tts.audio_quality_warning`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_quality: Option<TTSAudioQualityConfig>,
+
+    /// Audio asset played while the first synthesized audio of a reply is slow.
+    ///
+    /// Loaded from the server's greeting assets directory; the filler fades
+    /// out into the real audio and is never part of the transcript.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filler: Option<FillerAudioConfig>,
+
+    /// Server voice profile to use instead of `voice_id`.
+    ///
+    /// The profile's voice for `provider` sets the voice, its model if it
+    /// has one, and the speaking rate unless `speaking_rate` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = "warm-female-en"))]
+    pub voice_profile: Option<String>,
 }
 
 impl TTSWebSocketConfig {
@@ -271,8 +519,42 @@ impl TTSWebSocketConfig {
             request_timeout: self.request_timeout.or(defaults.request_timeout),
             pronunciations: self.pronunciations.clone(),
             request_pool_size: defaults.request_pool_size,
+            utterance_timeout: self.utterance_timeout.or(defaults.utterance_timeout),
             emotion_config,
+            custom_headers: Default::default(),
+            output_profile: self.output_profile,
+            context_hints: self.context_hints,
+            visemes: self.visemes,
+        }
+    }
+
+    /// Set the voice of `provider` from the selected voice profile, if any
+    ///
+    /// The client's `speaking_rate` takes precedence over the profile's.
+    ///
+    /// # Returns
+    /// * `Ok(())` - No profile was selected, or its voice was applied
+    /// * `Err(ConfigIssue)` - The profile is unknown, has no voice for the
+    ///   provider, or was combined with `voice_id`
+    pub fn apply_voice_profile(
+        &self,
+        profiles: &BTreeMap<String, VoiceProfile>,
+        tts_config: &mut TTSConfig,
+    ) -> Result<(), ConfigIssue> {
+        const FIELD: &str = "tts_config.voice_profile";
+        let Some(name) = &self.voice_profile else {
+            return Ok(());
+        };
+        if self.voice_id.is_some() {
+            return Err(ConfigIssue::new(FIELD, "cannot be combined with voice_id"));
+        }
+        let voice = resolve_voice_profile(profiles, name, &self.provider)
+            .map_err(|e| ConfigIssue::new(FIELD, e))?;
+        voice.apply(tts_config);
+        if let Some(rate) = self.speaking_rate {
+            tts_config.speaking_rate = Some(rate);
         }
+        Ok(())
     }
 
     /// Extract emotion configuration from WebSocket config.
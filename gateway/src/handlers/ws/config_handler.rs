@@ -5,26 +5,48 @@
 
 use base64::{Engine as _, engine::general_purpose};
 use bytes::Bytes;
+use livekit_protocol::participant_info;
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
-use tokio::time::{Duration, timeout};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{RwLock, mpsc, oneshot, watch};
+use tokio::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
+    config::{FeatureFlags, GreetingConfig, SipCallInfo, SipOutboundConfig},
     core::{
-        stt::STTResult,
-        tts::AudioData,
-        voice_manager::{VoiceManager, VoiceManagerConfig},
+        agent_bridge::AgentBridgeConfig,
+        session::{
+            AudioDirection, EffectiveSessionConfig, Session, SessionEvent, SessionPipelineBuilder,
+            load_audio_asset,
+        },
+        sink_audio::{SinkAudioFormat, is_pcm16},
+        stt::{STTConfig, STTFailoverConfig, STTTurnRoutingConfig},
+        template::{TemplateContext, contains_template, render_templates},
+        tts::{AudioData, TTSConfig, TTSOutputProfile, telephony_config},
+        validation::ConfigIssue,
+        voice_manager::TTSQueueLimit,
     },
-    livekit::LiveKitClient,
-    state::AppState,
+    errors::provider_error::ErrorSanitizer,
+    flight_recorder::EntryKind,
+    handlers::close::CloseReason,
+    livekit::{DtmfCallback, DtmfEvent, LiveKitClient},
+    plugin::PluginRegistry,
+    recording_upload::RecordingUpload,
+    state::{
+        AppState, CallSignal, CallState, SessionEventBus, SessionMetadata, SessionStore,
+        insert_session_metadata,
+    },
+    usage::{UsageRecord, UsageTermination},
 };
 
 #[cfg(feature = "dag-routing")]
 use crate::dag::{
-    compiler::DAGCompiler,
-    context::DAGContext,
+    compiler::{CompiledDAG, DAGCompiler},
+    context::{DAGContext, resource_keys},
     definition::DAGDefinition,
     executor::DAGExecutor,
     global_templates,
@@ -35,7 +57,11 @@ use super::{
         DAGWebSocketConfig, LiveKitWebSocketConfig, STTWebSocketConfig, TTSWebSocketConfig,
         compute_tts_config_hash,
     },
+    consent,
     messages::{MessageRoute, OutgoingMessage, ParticipantDisconnectedInfo, UnifiedMessage},
+    processor::close_connection,
+    redaction,
+    stages::{EventAction, session_event_action},
     state::ConnectionState,
 };
 
@@ -67,7 +93,9 @@ fn resolve_stream_id(stream_id: Option<String>) -> String {
 /// - TTS (Text-to-Speech) provider initialization
 /// - LiveKit client connection (optional)
 /// - DAG routing initialization (optional, when dag_config provided)
+/// - Agent bridge setup (optional, when agent_config provided)
 /// - Callback registration for audio routing
+/// - Greeting playback (optional, per-session or server default)
 ///
 /// # Arguments
 /// * `stream_id` - Optional unique identifier for the WebSocket session
@@ -76,6 +104,12 @@ fn resolve_stream_id(stream_id: Option<String>) -> String {
 /// * `tts_ws_config` - TTS provider configuration
 /// * `livekit_ws_config` - Optional LiveKit configuration
 /// * `dag_ws_config` - Optional DAG routing configuration
+/// * `agent_config` - Optional LLM agent bridge configuration
+/// * `metadata` - Client metadata to attach to the session (already validated)
+/// * `greeting` - Optional greeting overriding the server default (already validated)
+/// * `audio_levels` - Send `audio_level` messages for caller and TTS audio
+/// * `dry_run` - Only resolve and report the session config (see [`handle_dry_run`])
+/// * `flight_recorder` - Keep the session's flight log (if the flight recorder is configured)
 /// * `state` - Connection state to update
 /// * `message_tx` - Channel for sending response messages
 /// * `app_state` - Application state containing API keys
@@ -86,10 +120,16 @@ fn resolve_stream_id(stream_id: Option<String>) -> String {
 pub async fn handle_config_message(
     stream_id: Option<String>,
     audio: Option<bool>,
-    stt_ws_config: Option<STTWebSocketConfig>,
-    tts_ws_config: Option<TTSWebSocketConfig>,
+    mut stt_ws_config: Option<STTWebSocketConfig>,
+    mut tts_ws_config: Option<TTSWebSocketConfig>,
     livekit_ws_config: Option<LiveKitWebSocketConfig>,
     dag_ws_config: Option<DAGWebSocketConfig>,
+    mut agent_config: Option<AgentBridgeConfig>,
+    mut metadata: SessionMetadata,
+    mut greeting: Option<GreetingConfig>,
+    audio_levels: bool,
+    dry_run: bool,
+    flight_recorder: bool,
     state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
     app_state: &Arc<AppState>,
@@ -112,44 +152,241 @@ pub async fn handle_config_message(
         return true;
     }
 
-    // Store audio_enabled flag in connection state
+    // LiveKit publishes PCM tracks; μ-law telephony frames are for WebSocket bridges
+    if livekit_ws_config.is_some()
+        && tts_ws_config
+            .as_ref()
+            .is_some_and(|tts| tts.output_profile == Some(TTSOutputProfile::Telephony))
+    {
+        let error_msg = "TTS output_profile 'telephony' is not supported with LiveKit".to_string();
+        error!("{}", error_msg);
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: error_msg,
+            }))
+            .await;
+        return true;
+    }
+
+    if let Some(livekit) = &livekit_ws_config
+        && let Err(e) = livekit.output_pacing().validate()
+    {
+        let error_msg = format!("Invalid LiveKit config: {e}");
+        error!("{}", error_msg);
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: error_msg,
+            }))
+            .await;
+        return true;
+    }
+
+    // Flags are bucketed by client so a client sees the same rollout in
+    // every session; without auth each stream is bucketed on its own
+    let auth_id = state.read().await.auth.id.clone();
+    let feature_flags = app_state
+        .feature_flags
+        .resolve(auth_id.as_deref().unwrap_or(&stream_id));
+
+    // Inbound SIP calls that leave the language open start in the language
+    // pack their call details select
+    if audio_enabled
+        && let Some(livekit) = &livekit_ws_config
+        && let Some(stt) = stt_ws_config.as_mut()
+        && stt.language.is_empty()
     {
+        apply_sip_language_pack(
+            &livekit.room_name,
+            stt,
+            tts_ws_config.as_mut(),
+            &mut metadata,
+            app_state,
+        )
+        .await;
+    }
+
+    // Templated fields are evaluated once, against the final metadata
+    if let Err(issue) = render_session_templates(
+        &stream_id,
+        livekit_ws_config.as_ref(),
+        &mut stt_ws_config,
+        &mut tts_ws_config,
+        &mut agent_config,
+        &mut greeting,
+        &metadata,
+        app_state,
+    )
+    .await
+    {
+        warn!(field = %issue.field, "Rejecting session config template: {}", issue.message);
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::config_error(vec![
+                issue,
+            ])))
+            .await;
+        return true;
+    }
+
+    if dry_run {
+        return handle_dry_run(
+            stream_id,
+            audio_enabled,
+            stt_ws_config.as_ref(),
+            tts_ws_config.as_ref(),
+            dag_ws_config.as_ref(),
+            agent_config.as_ref(),
+            metadata,
+            audio_levels,
+            feature_flags,
+            message_tx,
+            app_state,
+        )
+        .await;
+    }
+
+    // Store audio_enabled flag in connection state
+    let (previous_stream_id, previous_metadata) = {
         let mut state_guard = state.write().await;
         state_guard.set_audio_enabled(audio_enabled);
-        state_guard.stream_id = Some(stream_id.clone());
-    }
+        // A re-sent config may switch stream_id; drop the stale session entry
+        let previous_stream_id = state_guard.stream_id.replace(stream_id.clone());
+        // Kept for the usage record of a session this config replaces
+        let previous_metadata = previous_stream_id
+            .as_ref()
+            .and_then(|previous| app_state.session_store.metadata(previous))
+            .unwrap_or_default();
+        if let Some(previous) = &previous_stream_id
+            && *previous != stream_id
+        {
+            app_state.session_store.remove(previous);
+            app_state.session_events.close(previous);
+        }
+        (previous_stream_id, previous_metadata)
+    };
     debug!(stream_id = %stream_id, "Stored stream_id in connection state");
 
-    // Initialize voice manager if audio is enabled
-    let voice_manager = if audio_enabled {
-        match initialize_voice_manager(
+    // Register session metadata so webhooks and recordings can pick it up
+    app_state
+        .session_store
+        .register(&stream_id, metadata.clone());
+    app_state.session_events.open(&stream_id, auth_id);
+
+    // Sessions that opt in keep their flight log under the stream_id
+    let flight_log = state.read().await.flight_log.clone();
+    match flight_log {
+        Some(log) if flight_recorder => log.attach(&stream_id).await,
+        Some(log) => log.discard(),
+        None if flight_recorder => debug!(
+            stream_id = %stream_id,
+            "flight_recorder requested, but the flight recorder is not configured"
+        ),
+        None => {}
+    }
+
+    // Initialize the voice session if audio is enabled
+    let session = if audio_enabled {
+        match initialize_session(
+            &stream_id,
             stt_ws_config.as_ref().unwrap(),
             tts_ws_config.as_ref().unwrap(),
+            agent_config.as_ref(),
+            audio_levels,
+            &feature_flags,
             app_state,
             message_tx,
         )
         .await
         {
-            Some(vm) => {
-                // Store in connection state
+            Some(session) => {
+                // Store in connection state, stopping any agent reply from a previous config
                 let mut state_guard = state.write().await;
-                state_guard.voice_manager = Some(vm.clone());
-                Some(vm)
+                // A new stream or audio format starts a new caller recording
+                let keep_recording = previous_stream_id.as_deref() == Some(stream_id.as_str())
+                    && state_guard
+                        .input_recording
+                        .as_ref()
+                        .is_some_and(|recording| recording.format() == input_format(&session));
+                if !keep_recording {
+                    state_guard.input_recording = start_input_recording(
+                        &session,
+                        state_guard.auth.id.as_deref(),
+                        &stream_id,
+                        app_state,
+                    );
+                }
+                if let Some(previous) = state_guard.session.replace(session.clone()) {
+                    if let Some(bridge) = previous.agent_bridge() {
+                        bridge.cancel();
+                    }
+                    // The replaced session gets its own usage record
+                    if let Some(recorder) = &app_state.usage_recorder {
+                        recorder.record_detached(
+                            UsageRecord::new(
+                                previous_stream_id.unwrap_or_else(|| stream_id.clone()),
+                                state_guard.auth.id.clone(),
+                                &previous.usage(),
+                                UsageTermination::Reconfigured,
+                                None,
+                            )
+                            .with_metadata(previous_metadata),
+                        );
+                    }
+                }
+                Some(session)
             }
             None => return true,
         }
     } else {
-        info!("Audio processing disabled - skipping voice manager initialization");
+        info!("Audio processing disabled - skipping voice session initialization");
+        if agent_config.is_some() {
+            warn!("agent_config ignored because audio processing is disabled");
+        }
         None
     };
 
-    // Register early TTS callback for cached audio
-    if let Some(ref vm) = voice_manager {
-        register_early_tts_callback(vm, message_tx).await;
+    // Forward session events; TTS audio switches to LiveKit once it is connected
+    if let Some(ref session) = session {
+        spawn_session_event_forwarder(session, state, message_tx, app_state, &stream_id);
     }
 
+    // The resolved config goes in the flight log, secrets redacted
+    if let (Some(session), Some(log)) = (&session, &state.read().await.flight_log)
+        && let Ok(config) = serde_json::to_value(session.effective_config())
+    {
+        log.record(EntryKind::Config, config);
+    }
+
+    // Resolve the greeting: the session's own greeting overrides the server default
+    let greeting = session
+        .as_ref()
+        .and(greeting.or_else(|| app_state.config.greeting.clone()))
+        .filter(|greeting| greeting.source().is_some());
+
+    // With LiveKit, greet once the caller's audio track appears
+    let (greeting_track_tx, greeting_track_rx) =
+        if greeting.is_some() && livekit_ws_config.is_some() {
+            let (tx, rx) = oneshot::channel();
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+
+    // An outbound call holds STT and usage, and the greeting, until it is answered
+    let outbound_call = livekit_ws_config
+        .as_ref()
+        .is_some_and(|livekit| livekit.outbound_call);
+    if outbound_call && let Some(session) = &session {
+        session.hold();
+    }
+    let (answered_tx, answered_rx) = if greeting.is_some() && outbound_call {
+        let (tx, rx) = oneshot::channel();
+        (Some(tx), Some(rx))
+    } else {
+        (None, None)
+    };
+
     // Initialize LiveKit client if configured
-    let (livekit_client, livekit_room_name, waav_identity, waav_name) =
+    let (livekit_room_name, waav_identity, waav_name) =
         if let Some(mut livekit_ws_config) = livekit_ws_config {
             // Normalize room name with auth prefix for tenant isolation
             {
@@ -169,51 +406,57 @@ pub async fn handle_config_message(
                 state_guard.auth.id.clone()
             };
 
+            // Redact recordings while a SIP caller keys in digits, and take
+            // their recording consent from the keypad
+            let on_redaction_dtmf = app_state
+                .config
+                .recording_redaction
+                .dtmf
+                .then(|| redaction::dtmf_callback(state, message_tx, app_state));
+            let on_consent_dtmf = session.as_ref().and_then(|session| {
+                session
+                    .effective_config()
+                    .consent
+                    .as_ref()
+                    .map(|config| consent::dtmf_callback(session, config))
+            });
+            let on_dtmf = chain_dtmf_callbacks(on_consent_dtmf, on_redaction_dtmf);
+
             match initialize_livekit_client(
                 livekit_ws_config,
                 tts_ws_config.as_ref(),
                 &app_state.config.livekit_url,
-                voice_manager.as_ref(),
+                session.as_ref(),
                 message_tx,
                 app_state.livekit_room_handler.as_ref(),
                 &stream_id,
                 auth_id.as_deref(),
+                &metadata,
+                greeting_track_tx,
+                on_dtmf,
+                &app_state.error_sanitizer,
             )
             .await
             {
                 Some((client, operation_queue, room_name, egress_id, identity, name)) => {
+                    app_state
+                        .session_store
+                        .set_livekit_room(&stream_id, &room_name);
                     // Store in connection state
                     let mut state_guard = state.write().await;
-                    state_guard.livekit_client = Some(client.clone());
+                    state_guard.livekit_client = Some(client);
                     state_guard.livekit_operation_queue = operation_queue;
                     state_guard.livekit_room_name = Some(room_name.clone());
                     state_guard.livekit_local_identity = Some(identity.clone());
                     state_guard.recording_egress_id = egress_id;
-                    (Some(client), Some(room_name), Some(identity), Some(name))
+                    (Some(room_name), Some(identity), Some(name))
                 }
                 None => return true,
             }
         } else {
-            (None, None, None, None)
+            (None, None, None)
         };
 
-    // Register final TTS callback with LiveKit routing
-    if let Some(ref vm) = voice_manager {
-        let operation_queue = if let Some(ref _client) = livekit_client {
-            let state_guard = state.read().await;
-            state_guard.livekit_operation_queue.clone()
-        } else {
-            None
-        };
-        register_final_tts_callback(
-            vm,
-            livekit_client.as_ref(),
-            operation_queue.as_ref(),
-            message_tx,
-        )
-        .await;
-    }
-
     // Initialize DAG routing if configured
     #[cfg(feature = "dag-routing")]
     let dag_enabled = if let Some(dag_config) = dag_ws_config {
@@ -222,6 +465,7 @@ pub async fn handle_config_message(
             &stream_id,
             state,
             message_tx,
+            &app_state.plugin_registry,
         )
         .await
         {
@@ -266,6 +510,7 @@ pub async fn handle_config_message(
             livekit_url: Some(app_state.config.livekit_public_url.clone()),
             waav_participant_identity: waav_identity,
             waav_participant_name: waav_name,
+            feature_flags,
         }))
         .await;
 
@@ -277,9 +522,165 @@ pub async fn handle_config_message(
         "Connection configured and ready"
     );
 
+    if outbound_call && let Some(room_name) = livekit_room_name {
+        spawn_call_watcher(
+            session.clone(),
+            room_name,
+            answered_tx,
+            message_tx,
+            app_state,
+        );
+    }
+
+    if let (Some(session), Some(greeting)) = (session, greeting) {
+        spawn_greeting(
+            session,
+            greeting,
+            stream_id,
+            greeting_track_rx,
+            answered_rx,
+            message_tx,
+            app_state,
+        );
+    }
+
     true
 }
 
+/// Answer a config message sent with `dry_run: true`
+///
+/// Resolves the session through the same builder setup as a real session and
+/// runs its validation and feature-flag resolution, then reports the effective
+/// configuration in a `dry_run` message. No provider is created, LiveKit is
+/// not joined and the connection state and session registry are left
+/// untouched, so the client can send another config afterwards. Errors are
+/// reported exactly as a real session start reports them.
+#[allow(clippy::too_many_arguments)]
+async fn handle_dry_run(
+    stream_id: String,
+    audio_enabled: bool,
+    stt_ws_config: Option<&STTWebSocketConfig>,
+    tts_ws_config: Option<&TTSWebSocketConfig>,
+    dag_ws_config: Option<&DAGWebSocketConfig>,
+    agent_config: Option<&AgentBridgeConfig>,
+    metadata: SessionMetadata,
+    audio_levels: bool,
+    feature_flags: FeatureFlags,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    app_state: &Arc<AppState>,
+) -> bool {
+    let mut warnings = Vec::new();
+
+    let config = match (audio_enabled, stt_ws_config, tts_ws_config) {
+        (true, Some(stt_ws_config), Some(tts_ws_config)) => {
+            let builder = match session_builder(
+                stt_ws_config,
+                tts_ws_config,
+                agent_config,
+                audio_levels,
+                &feature_flags,
+                app_state,
+            ) {
+                Ok(builder) => builder,
+                Err(message) => {
+                    let _ = message_tx.send(MessageRoute::Outgoing(message)).await;
+                    return true;
+                }
+            };
+            match builder.dry_run() {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    error!("Dry run rejected session config: {}", e);
+                    let _ = message_tx
+                        .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                            message: e.to_string(),
+                        }))
+                        .await;
+                    return true;
+                }
+            }
+        }
+        _ => {
+            if agent_config.is_some() {
+                warnings.push(
+                    "agent_config is ignored because audio processing is disabled".to_string(),
+                );
+            }
+            None
+        }
+    };
+    if let Some(config) = &config {
+        warnings.extend(language_warnings(config, &app_state.plugin_registry));
+    }
+
+    #[cfg(feature = "dag-routing")]
+    if let Some(dag_config) = dag_ws_config
+        && let Err(e) = compile_dag(dag_config)
+    {
+        error!("Dry run rejected DAG config: {}", e);
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: format!("DAG initialization failed: {}", e),
+            }))
+            .await;
+        return true;
+    }
+    #[cfg(not(feature = "dag-routing"))]
+    if dag_ws_config.is_some() {
+        warnings.push(
+            "dag_config is ignored because DAG routing is not enabled in this build".to_string(),
+        );
+    }
+
+    info!(
+        stream_id = %stream_id,
+        warnings = warnings.len(),
+        "Dry run resolved session config"
+    );
+    let _ = message_tx
+        .send(MessageRoute::Outgoing(OutgoingMessage::DryRun {
+            stream_id,
+            config,
+            feature_flags,
+            metadata,
+            warnings,
+        }))
+        .await;
+    true
+}
+
+/// Warnings for STT languages their providers do not declare
+///
+/// Providers that declare no languages accept any; a declared code also
+/// covers its regional variants (`en` covers `en-US`).
+fn language_warnings(config: &EffectiveSessionConfig, registry: &PluginRegistry) -> Vec<String> {
+    [
+        ("stt", config.stt.as_ref()),
+        ("stt_failover", config.stt_failover.as_ref()),
+        ("stt_routing", config.stt_routing.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(section, stt)| {
+        let stt = stt.filter(|stt| !stt.language.is_empty())?;
+        let metadata = registry.get_stt_metadata(&stt.provider)?;
+        let primary = stt.language.split(['-', '_']).next().unwrap_or_default();
+        let declared = metadata.supported_languages.is_empty()
+            || metadata.supported_languages.iter().any(|language| {
+                language.eq_ignore_ascii_case(&stt.language)
+                    || language.eq_ignore_ascii_case(primary)
+            });
+        (!declared).then(|| {
+            format!(
+                "{section}.language '{}' is not among the languages {} declares ({})",
+                stt.language,
+                stt.provider,
+                metadata.supported_languages.join(", ")
+            )
+        })
+    })
+    .collect()
+}
+
 /// Validate audio configurations are present
 async fn validate_audio_configs(
     stt_config: &Option<STTWebSocketConfig>,
@@ -311,460 +712,688 @@ async fn validate_audio_configs(
     true
 }
 
-/// Initialize voice manager with STT and TTS providers
-async fn initialize_voice_manager(
-    stt_ws_config: &STTWebSocketConfig,
-    tts_ws_config: &TTSWebSocketConfig,
+/// Apply the language pack of the SIP call in a LiveKit room
+///
+/// The call details are read from the room's SIP participant. The pack sets
+/// the STT language, and the TTS voice when the session chose none; its name
+/// and the rule that chose it are recorded in the session metadata as
+/// `language_pack` and `language_pack_source` when they fit within the
+/// metadata limits. Rooms without a SIP participant, and lookups that fail,
+/// leave the session as configured.
+async fn apply_sip_language_pack(
+    room_name: &str,
+    stt: &mut STTWebSocketConfig,
+    tts: Option<&mut TTSWebSocketConfig>,
+    metadata: &mut SessionMetadata,
     app_state: &Arc<AppState>,
-    message_tx: &mpsc::Sender<MessageRoute>,
-) -> Option<Arc<VoiceManager>> {
+) {
+    let Some(sip) = app_state
+        .config
+        .sip
+        .as_ref()
+        .filter(|sip| !sip.language_routing.is_empty())
+    else {
+        return;
+    };
+    let Some(attributes) = sip_caller_attributes(room_name, app_state).await else {
+        return;
+    };
+
+    let call = SipCallInfo::from_attributes(&attributes);
+    let Some((name, pack, source)) = sip.language_routing.resolve(&call) else {
+        return;
+    };
     info!(
-        "Initializing voice manager with STT provider: {} and TTS provider: {}",
-        stt_ws_config.provider, tts_ws_config.provider
+        room = %room_name,
+        language_pack = %name,
+        source = source.as_str(),
+        "Starting SIP call in language pack"
     );
 
-    // Get API keys - prefer client-provided keys, fall back to server config
-    let stt_api_key = if let Some(ref client_key) = stt_ws_config.api_key {
-        if !client_key.is_empty() {
-            info!(
-                "Using client-provided API key for STT provider: {}",
-                stt_ws_config.provider
-            );
-            client_key.clone()
-        } else {
-            match app_state.config.get_api_key(&stt_ws_config.provider) {
-                Ok(key) => key,
-                Err(error_msg) => {
-                    error!("{}", error_msg);
-                    let _ = message_tx
-                        .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                            message: error_msg,
-                        }))
-                        .await;
-                    return None;
-                }
-            }
+    stt.language = pack.stt_language.clone();
+    if let Some(tts) = tts
+        && tts.voice_id.is_none()
+        && tts.voice_profile.is_none()
+    {
+        tts.voice_id = pack.tts_voice_id.clone();
+    }
+    // The client may already use the whole metadata budget, in which case
+    // the pack is applied without being recorded
+    for (key, value) in [
+        ("language_pack", name),
+        ("language_pack_source", source.as_str()),
+    ] {
+        if let Err(e) = insert_session_metadata(metadata, key, value.to_string()) {
+            warn!(room = %room_name, key, "Language pack not recorded in session metadata: {}", e);
         }
-    } else {
-        match app_state.config.get_api_key(&stt_ws_config.provider) {
-            Ok(key) => key,
-            Err(error_msg) => {
-                error!("{}", error_msg);
-                let _ = message_tx
-                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: error_msg,
-                    }))
-                    .await;
-                return None;
-            }
+    }
+}
+
+/// Attributes of the SIP caller in a LiveKit room, if the room has one
+async fn sip_caller_attributes(
+    room_name: &str,
+    app_state: &Arc<AppState>,
+) -> Option<HashMap<String, String>> {
+    let room_handler = app_state.livekit_room_handler.as_ref()?;
+    let participants = match room_handler.list_participants(room_name).await {
+        Ok(participants) => participants,
+        Err(e) => {
+            warn!(room = %room_name, "Failed to look up SIP caller: {}", e);
+            return None;
         }
     };
+    participants
+        .into_iter()
+        .find(|p| {
+            participant_info::Kind::try_from(p.kind)
+                .is_ok_and(|kind| kind == participant_info::Kind::Sip)
+        })
+        .map(|caller| caller.attributes)
+}
 
-    let tts_api_key = if let Some(ref client_key) = tts_ws_config.api_key {
-        if !client_key.is_empty() {
-            info!(
-                "Using client-provided API key for TTS provider: {}",
-                tts_ws_config.provider
-            );
-            client_key.clone()
-        } else {
-            match app_state.config.get_api_key(&tts_ws_config.provider) {
-                Ok(key) => key,
-                Err(error_msg) => {
-                    error!("{}", error_msg);
-                    let _ = message_tx
-                        .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                            message: error_msg,
-                        }))
-                        .await;
-                    return None;
-                }
-            }
-        }
-    } else {
-        match app_state.config.get_api_key(&tts_ws_config.provider) {
-            Ok(key) => key,
-            Err(error_msg) => {
-                error!("{}", error_msg);
-                let _ = message_tx
-                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: error_msg,
-                    }))
-                    .await;
-                return None;
-            }
-        }
+/// Evaluate the `{{ }}` templates of a session config
+///
+/// See [`crate::core::template`] for the expression grammar. The SIP caller
+/// is only looked up when the config holds a template.
+#[allow(clippy::too_many_arguments)]
+async fn render_session_templates(
+    stream_id: &str,
+    livekit_ws_config: Option<&LiveKitWebSocketConfig>,
+    stt_ws_config: &mut Option<STTWebSocketConfig>,
+    tts_ws_config: &mut Option<TTSWebSocketConfig>,
+    agent_config: &mut Option<AgentBridgeConfig>,
+    greeting: &mut Option<GreetingConfig>,
+    metadata: &SessionMetadata,
+    app_state: &Arc<AppState>,
+) -> Result<(), ConfigIssue> {
+    let templated = [
+        serde_json::to_value(&*stt_ws_config),
+        serde_json::to_value(&*tts_ws_config),
+        serde_json::to_value(&*agent_config),
+        serde_json::to_value(&*greeting),
+    ]
+    .iter()
+    .any(|value| value.as_ref().is_ok_and(contains_template));
+    if !templated {
+        return Ok(());
+    }
+
+    let sip_attributes = match livekit_ws_config {
+        Some(livekit) => sip_caller_attributes(&livekit.room_name, app_state).await,
+        None => None,
+    };
+    let context = TemplateContext {
+        metadata: metadata.clone(),
+        sip_attributes,
+        stream_id: stream_id.to_string(),
     };
 
-    // Create full configs with API keys
-    let stt_config = stt_ws_config.to_stt_config(stt_api_key);
-    let tts_config = tts_ws_config.to_tts_config(tts_api_key);
+    render_config_templates("stt_config", stt_ws_config, &context)?;
+    render_config_templates("tts_config", tts_ws_config, &context)?;
+    render_config_templates("agent_config", agent_config, &context)?;
+    render_config_templates("greeting", greeting, &context)
+}
 
-    // Create voice manager configuration with default speech final settings
-    let voice_config = VoiceManagerConfig::new(stt_config.clone(), tts_config.clone());
+/// Evaluate the templates of one config section in place
+fn render_config_templates<T: Serialize + DeserializeOwned>(
+    field: &str,
+    config: &mut Option<T>,
+    context: &TemplateContext,
+) -> Result<(), ConfigIssue> {
+    let Some(current) = config.as_ref() else {
+        return Ok(());
+    };
+    let mut value =
+        serde_json::to_value(current).map_err(|e| ConfigIssue::new(field, e.to_string()))?;
+    if !contains_template(&value) {
+        return Ok(());
+    }
+    render_templates(field, &mut value, context)?;
+    let rendered = serde_json::from_value(value)
+        .map_err(|e| ConfigIssue::new(field, format!("invalid after evaluating templates: {e}")))?;
+    *config = Some(rendered);
+    Ok(())
+}
 
-    let turn_detector = app_state.core_state.get_turn_detector();
+/// Build the voice session with STT and TTS providers
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(feature = "chaos"), allow(unused_variables))]
+async fn initialize_session(
+    stream_id: &str,
+    stt_ws_config: &STTWebSocketConfig,
+    tts_ws_config: &TTSWebSocketConfig,
+    agent_config: Option<&AgentBridgeConfig>,
+    audio_levels: bool,
+    feature_flags: &FeatureFlags,
+    app_state: &Arc<AppState>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+) -> Option<Arc<Session>> {
+    let builder = match session_builder(
+        stt_ws_config,
+        tts_ws_config,
+        agent_config,
+        audio_levels,
+        feature_flags,
+        app_state,
+    ) {
+        Ok(builder) => builder,
+        Err(message) => {
+            let _ = message_tx.send(MessageRoute::Outgoing(message)).await;
+            return None;
+        }
+    };
+    #[cfg(feature = "chaos")]
+    let builder = builder.chaos(app_state.chaos.session(stream_id));
 
-    // Create voice manager
-    let voice_manager = match VoiceManager::new(voice_config, turn_detector) {
-        Ok(vm) => Arc::new(vm),
+    match builder.build().await {
+        Ok(session) => Some(Arc::new(session)),
         Err(e) => {
-            error!("Failed to create voice manager: {}", e);
+            let code = e.error_code();
+            error!(code, "Failed to initialize voice session");
             let _ = message_tx
-                .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                    message: format!("Failed to create voice manager: {e}"),
-                }))
+                .send(MessageRoute::Outgoing(OutgoingMessage::provider_error(
+                    &app_state.error_sanitizer,
+                    code,
+                    &e.to_string(),
+                    None,
+                )))
                 .await;
-            return None;
+            None
+        }
+    }
+}
+
+/// Resolve the session builder for a config message
+///
+/// Resolves the provider credentials, validates the provider configs and
+/// applies the server's session settings. Real setups and dry runs both go
+/// through here, so a dry run resolves exactly what a session would use.
+///
+/// # Returns
+/// * `Ok(SessionPipelineBuilder)` - Builder ready to connect the providers
+/// * `Err(OutgoingMessage)` - Error message for the client
+fn session_builder(
+    stt_ws_config: &STTWebSocketConfig,
+    tts_ws_config: &TTSWebSocketConfig,
+    agent_config: Option<&AgentBridgeConfig>,
+    audio_levels: bool,
+    feature_flags: &FeatureFlags,
+    app_state: &Arc<AppState>,
+) -> Result<SessionPipelineBuilder, OutgoingMessage> {
+    info!(
+        "Initializing voice session with STT provider: {} and TTS provider: {}",
+        stt_ws_config.provider, tts_ws_config.provider
+    );
+
+    // Get API keys - prefer client-provided keys, fall back to server config
+    let stt_api_key = resolve_api_key(
+        stt_ws_config.api_key.as_deref(),
+        &stt_ws_config.provider,
+        "STT",
+        app_state,
+    )?;
+    let tts_api_key = resolve_api_key(
+        tts_ws_config.api_key.as_deref(),
+        &tts_ws_config.provider,
+        "TTS",
+        app_state,
+    )?;
+
+    // Create full configs with API keys
+    let mut stt_config = stt_ws_config.to_stt_config(stt_api_key);
+    stt_config.certificate_path = app_state.config.gnani_certificate_path.clone();
+    let mut tts_config = tts_ws_config.to_tts_config(tts_api_key);
+    let voice_profile_issue = tts_ws_config
+        .apply_voice_profile(&app_state.config.voice_profiles, &mut tts_config)
+        .err();
+
+    // The secondary STT provider's key is resolved like the primary's
+    let mut stt_failover = match &stt_ws_config.failover {
+        Some(failover) => {
+            let api_key = resolve_api_key(
+                failover.api_key.as_deref(),
+                &failover.provider,
+                "STT failover",
+                app_state,
+            )?;
+            Some(failover.to_failover_config(&stt_config, api_key))
         }
+        None => None,
     };
 
-    // Set up TTS cache
-    let cfg_hash = compute_tts_config_hash(&tts_config);
-    if let Err(e) = voice_manager
-        .set_tts_cache(app_state.cache(), Some(cfg_hash))
-        .await
-    {
-        error!("Failed to set TTS cache: {}", e);
-    }
+    // So is the key of the fast provider used for routed turns
+    let mut stt_routing = match &stt_ws_config.routing {
+        Some(routing) => {
+            let api_key = resolve_api_key(
+                routing.api_key.as_deref(),
+                &routing.provider,
+                "STT routing",
+                app_state,
+            )?;
+            Some(routing.to_routing_config(&stt_config, api_key))
+        }
+        None => None,
+    };
 
-    // Start voice manager
-    if let Err(e) = voice_manager.start().await {
-        error!("Failed to start voice manager: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to start voice manager: {e}"),
-            }))
-            .await;
-        return None;
+    // Report every provider config problem before any connection is attempted
+    let registry = &app_state.plugin_registry;
+    let mut issues = config_issues(
+        registry,
+        &stt_config,
+        stt_failover.as_ref(),
+        stt_routing.as_ref(),
+        &tts_config,
+    );
+    issues.extend(voice_profile_issue);
+    issues.extend(normalize_models(
+        registry,
+        &mut stt_config,
+        stt_failover.as_mut(),
+        stt_routing.as_mut(),
+        &mut tts_config,
+        stt_ws_config.allow_unknown_models,
+        tts_ws_config.allow_unknown_models,
+    ));
+    if !issues.is_empty() {
+        warn!("Rejecting session config with {} issue(s)", issues.len());
+        return Err(OutgoingMessage::config_error(issues));
     }
 
-    // Set up STT result callback
-    if !register_stt_callback(&voice_manager, message_tx).await {
-        return None;
-    }
+    // Cached audio is what the provider produces, so key it by the resolved
+    // telephony format. This also rejects providers that cannot serve the profile.
+    let cfg_hash = if tts_config.is_telephony() {
+        match telephony_config(&tts_config) {
+            Ok(resolved) => compute_tts_config_hash(&resolved),
+            Err(e) => {
+                error!("{}", e);
+                return Err(OutgoingMessage::Error {
+                    message: e.to_string(),
+                });
+            }
+        }
+    } else {
+        compute_tts_config_hash(&tts_config)
+    };
 
-    // Set up STT error callback - critical for propagating streaming errors
-    if !register_stt_error_callback(&voice_manager, message_tx).await {
-        return None;
+    let mut builder = SessionPipelineBuilder::new()
+        .plugin_registry(app_state.plugin_registry.clone())
+        .stt(stt_config)
+        .tts(tts_config)
+        .turn_detector(app_state.core_state.get_turn_detector())
+        .turn_detector_expected(app_state.core_state.turn_detector_health().enabled)
+        .tts_cache(app_state.cache(), Some(cfg_hash))
+        .connect_timeout(Duration::from_secs(
+            app_state.config.provider_connect_timeout_secs,
+        ))
+        .ready_timeout(Duration::from_secs(PROVIDER_READY_TIMEOUT_SECS))
+        .audio_levels(audio_levels)
+        .feature_flags(feature_flags.clone())
+        .transcript_buffer(app_state.config.transcript_buffer, Some(app_state.cache()))
+        .speech_activity(app_state.transcript_enrichment.is_some());
+    if let Some(config) = agent_config {
+        builder = builder.agent(config.clone());
     }
-
-    // Set up TTS error callback
-    if !register_tts_error_callback(&voice_manager, message_tx).await {
-        return None;
+    if let Some(endpointing) = stt_ws_config.adaptive_endpointing {
+        builder = builder.adaptive_endpointing(endpointing);
     }
-
-    // Set up TTS completion callback
-    if !register_tts_complete_callback(&voice_manager, message_tx).await {
-        return None;
+    if let Some(mode) = stt_ws_config.barge_in {
+        builder = builder.barge_in(mode);
     }
-
-    // Wait for providers to be ready
-    if !wait_for_providers_ready(&voice_manager, message_tx).await {
-        return None;
+    if let Some(confirmation) = stt_ws_config.barge_in_confirmation {
+        builder = builder.barge_in_confirmation(confirmation);
     }
-
-    Some(voice_manager)
-}
-
-/// Register STT result callback
-async fn register_stt_callback(
-    voice_manager: &Arc<VoiceManager>,
-    message_tx: &mpsc::Sender<MessageRoute>,
-) -> bool {
-    let message_tx_clone = message_tx.clone();
-    if let Err(e) = voice_manager
-        .on_stt_result(move |result: STTResult| {
-            let message_tx = message_tx_clone.clone();
-            Box::pin(async move {
-                let msg = OutgoingMessage::STTResult {
-                    transcript: result.transcript,
-                    is_final: result.is_final,
-                    is_speech_final: result.is_speech_final,
-                    confidence: result.confidence,
-                };
-                let _ = message_tx.send(MessageRoute::Outgoing(msg)).await;
-            })
-        })
-        .await
+    if let Some(echo_guard) = stt_ws_config.echo_guard {
+        builder = builder.echo_guard(echo_guard);
+    }
+    if let Some(formatting) = &stt_ws_config.formatting {
+        builder = builder.transcript_formatting(formatting.clone());
+    }
+    if let Some(latency_budget) = &stt_ws_config.latency_budget {
+        builder = builder.latency_budget(latency_budget.clone());
+    }
+    if let Some(watchdog) = stt_ws_config.watchdog {
+        builder = builder.watchdog(watchdog);
+    }
+    if let Some(consent) = &stt_ws_config.consent {
+        builder = builder.consent(consent.clone());
+    }
+    if let Some(retry_budget) = stt_ws_config.retry_budget {
+        builder = builder.retry_budget(retry_budget);
+    }
+    if let Some(failover) = stt_failover {
+        builder = builder.stt_failover(failover);
+    }
+    if let Some(routing) = stt_routing {
+        builder = builder.stt_routing(routing);
+    }
+    if let Some(voice_id) = app_state
+        .config
+        .tts_fallback_voices
+        .get(&tts_ws_config.provider)
     {
-        error!("Failed to set up STT callback: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to set up STT callback: {e}"),
-            }))
-            .await;
-        return false;
+        builder = builder.fallback_voice(voice_id.clone());
     }
-    true
+    builder = builder.tts_queue_limit(TTSQueueLimit {
+        max_pending: app_state.config.tts_max_pending_utterances,
+        policy: app_state.config.tts_queue_policy,
+    });
+    builder = builder.system_speak_max_chars(app_state.config.tts_system_speak_max_chars);
+    builder = builder.dedupe_partials(tts_ws_config.dedupe_partials.unwrap_or(false));
+    if let Some(audio_quality) = tts_ws_config.audio_quality {
+        builder = builder.tts_audio_quality(audio_quality);
+    }
+    if let Some(filler) = &tts_ws_config.filler {
+        builder = builder.filler_audio(
+            filler.clone(),
+            app_state.config.greeting_assets_dir.clone(),
+        );
+    }
+    Ok(builder)
 }
 
-/// Register STT error callback to propagate streaming errors to clients
-async fn register_stt_error_callback(
-    voice_manager: &Arc<VoiceManager>,
-    message_tx: &mpsc::Sender<MessageRoute>,
-) -> bool {
-    let message_tx_clone = message_tx.clone();
-    if let Err(e) = voice_manager
-        .on_stt_error(move |error| {
-            let message_tx = message_tx_clone.clone();
-            Box::pin(async move {
-                let msg = OutgoingMessage::Error {
-                    message: format!("STT streaming error: {error}"),
-                };
-                let _ = message_tx.send(MessageRoute::Outgoing(msg)).await;
-            })
-        })
-        .await
-    {
-        error!("Failed to set up STT error callback: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to set up STT error callback: {e}"),
-            }))
-            .await;
-        return false;
+/// API key for a provider: the client's key when it sent one, else the server's
+fn resolve_api_key(
+    client_key: Option<&str>,
+    provider: &str,
+    role: &str,
+    app_state: &AppState,
+) -> Result<String, OutgoingMessage> {
+    if let Some(client_key) = client_key.filter(|key| !key.is_empty()) {
+        info!(
+            "Using client-provided API key for {} provider: {}",
+            role, provider
+        );
+        return Ok(client_key.to_string());
     }
-    true
+    app_state.config.get_api_key(provider).map_err(|message| {
+        error!("{}", message);
+        OutgoingMessage::Error { message }
+    })
 }
 
-/// Register TTS error callback
-async fn register_tts_error_callback(
-    voice_manager: &Arc<VoiceManager>,
-    message_tx: &mpsc::Sender<MessageRoute>,
-) -> bool {
-    let message_tx_clone = message_tx.clone();
-    if let Err(e) = voice_manager
-        .on_tts_error(move |error| {
-            let message_tx = message_tx_clone.clone();
-            Box::pin(async move {
-                let msg = OutgoingMessage::Error {
-                    message: format!("TTS error: {error}"),
-                };
-                let _ = message_tx.send(MessageRoute::Outgoing(msg)).await;
-            })
-        })
-        .await
-    {
-        error!("Failed to set up TTS error callback: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to set up TTS error callback: {e}"),
-            }))
-            .await;
-        return false;
-    }
-    true
+/// Validation issues of the provider configs, prefixed with their section
+fn config_issues(
+    registry: &PluginRegistry,
+    stt_config: &STTConfig,
+    stt_failover: Option<&STTFailoverConfig>,
+    stt_routing: Option<&STTTurnRoutingConfig>,
+    tts_config: &TTSConfig,
+) -> Vec<ConfigIssue> {
+    let validate_stt = |config: &STTConfig| {
+        registry
+            .validate_stt_config(&config.provider, config)
+            .err()
+            .unwrap_or_default()
+    };
+    let stt_issues = validate_stt(stt_config);
+    let failover_issues = stt_failover
+        .map(|failover| validate_stt(&failover.secondary))
+        .unwrap_or_default();
+    let routing_issues = stt_routing
+        .map(|routing| validate_stt(&routing.fast))
+        .unwrap_or_default();
+    let tts_issues = registry
+        .validate_tts_config(&tts_config.provider, tts_config)
+        .err()
+        .unwrap_or_default();
+    stt_issues
+        .into_iter()
+        .map(|issue| issue.prefixed("stt_config"))
+        .chain(
+            failover_issues
+                .into_iter()
+                .map(|issue| issue.prefixed("stt_config.failover")),
+        )
+        .chain(
+            routing_issues
+                .into_iter()
+                .map(|issue| issue.prefixed("stt_config.routing")),
+        )
+        .chain(
+            tts_issues
+                .into_iter()
+                .map(|issue| issue.prefixed("tts_config")),
+        )
+        .collect()
 }
 
-/// Register TTS completion callback to send WebSocket notifications
-///
-/// This callback is invoked by the TTS provider's dispatcher after all audio
-/// chunks for a given `speak()` command have been generated and sent.
-///
-/// # Arguments
-/// * `voice_manager` - VoiceManager instance to register callback with
-/// * `message_tx` - Channel for sending WebSocket messages
+/// Map the provider configs' models to their canonical names
 ///
-/// # Returns
-/// * `bool` - true on success, false on error (triggers connection termination)
-async fn register_tts_complete_callback(
-    voice_manager: &Arc<VoiceManager>,
-    message_tx: &mpsc::Sender<MessageRoute>,
-) -> bool {
-    let message_tx_clone = message_tx.clone();
-
-    if let Err(e) = voice_manager
-        .on_tts_complete(move || {
-            let message_tx = message_tx_clone.clone();
-            Box::pin(async move {
-                // Calculate timestamp when completion occurred
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u64;
-
-                // Send completion message to WebSocket client
-                let msg = OutgoingMessage::TTSPlaybackComplete { timestamp };
-
-                // Ignore send errors - client may have disconnected
-                let _ = message_tx.send(MessageRoute::Outgoing(msg)).await;
-
-                debug!(
-                    "TTS playback completion event sent at timestamp {}",
-                    timestamp
-                );
-            })
+/// Models the providers do not list are reported, prefixed with their
+/// section, unless the client allows unknown models for that side.
+fn normalize_models(
+    registry: &PluginRegistry,
+    stt_config: &mut STTConfig,
+    stt_failover: Option<&mut STTFailoverConfig>,
+    stt_routing: Option<&mut STTTurnRoutingConfig>,
+    tts_config: &mut TTSConfig,
+    allow_unknown_stt: bool,
+    allow_unknown_tts: bool,
+) -> Vec<ConfigIssue> {
+    let stt_configs = [
+        ("stt_config", Some(stt_config)),
+        (
+            "stt_config.failover",
+            stt_failover.map(|failover| &mut failover.secondary),
+        ),
+        (
+            "stt_config.routing",
+            stt_routing.map(|routing| &mut routing.fast),
+        ),
+    ];
+    stt_configs
+        .into_iter()
+        .filter_map(|(section, config)| {
+            config?
+                .normalize_model_with(registry, allow_unknown_stt)
+                .err()
+                .map(|issue| issue.prefixed(section))
         })
-        .await
-    {
-        error!("Failed to set up TTS completion callback: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to set up TTS completion callback: {e}"),
-            }))
-            .await;
-        return false;
-    }
-
-    true
+        .chain(
+            tts_config
+                .normalize_model_with(registry, allow_unknown_tts)
+                .err()
+                .map(|issue| issue.prefixed("tts_config")),
+        )
+        .collect()
 }
 
-/// Wait for voice providers to become ready
-async fn wait_for_providers_ready(
-    voice_manager: &Arc<VoiceManager>,
+/// Forward session events to the WebSocket client
+///
+/// TTS audio goes to LiveKit once a LiveKit client is connected and to the
+/// WebSocket otherwise. The route is read from the connection state for each
+/// chunk, so audio produced before LiveKit is ready (e.g. cached audio)
+/// still reaches the client. JSON messages are also published to the
+/// session's observers.
+fn spawn_session_event_forwarder(
+    session: &Arc<Session>,
+    state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
-) -> bool {
-    let ready_timeout = Duration::from_secs(PROVIDER_READY_TIMEOUT_SECS);
-    let ready_check = async {
-        while !voice_manager.is_ready().await {
-            tokio::time::sleep(Duration::from_millis(50)).await;
-        }
+    app_state: &Arc<AppState>,
+    stream_id: &str,
+) {
+    let Some(mut events) = session.take_events() else {
+        warn!("Session events already taken - not forwarding to WebSocket");
+        return;
     };
-
-    if timeout(ready_timeout, ready_check).await.is_err() {
-        error!("Timeout waiting for voice providers to be ready");
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: "Timeout waiting for voice providers to be ready".to_string(),
-            }))
+    let state = state.clone();
+    let message_tx = message_tx.clone();
+    let observers = app_state.session_events.clone();
+    let sessions = app_state.session_store.clone();
+    let error_sanitizer = app_state.error_sanitizer.clone();
+    let stream_id = stream_id.to_string();
+
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            forward_session_event(
+                event,
+                &state,
+                &message_tx,
+                &observers,
+                &sessions,
+                &error_sanitizer,
+                &stream_id,
+            )
             .await;
-        return false;
-    }
+        }
+        debug!("Session event stream ended");
+    });
+}
 
-    true
+/// One callback calling `first`, then `second`, for every DTMF digit
+fn chain_dtmf_callbacks(
+    first: Option<DtmfCallback>,
+    second: Option<DtmfCallback>,
+) -> Option<DtmfCallback> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(Arc::new(move |event: DtmfEvent| {
+            first(event.clone());
+            second(event);
+        })),
+        (first, second) => first.or(second),
+    }
 }
 
-/// Register early TTS audio callback for cached audio
-async fn register_early_tts_callback(
-    voice_manager: &Arc<VoiceManager>,
+/// Route one session event to the client
+async fn forward_session_event(
+    event: SessionEvent,
+    state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
+    observers: &SessionEventBus,
+    sessions: &SessionStore,
+    error_sanitizer: &ErrorSanitizer,
+    stream_id: &str,
 ) {
-    let message_tx_for_early_tts = message_tx.clone();
-
-    if let Err(e) = voice_manager
-        .on_tts_audio(move |audio_data: AudioData| {
-            let message_tx = message_tx_for_early_tts.clone();
-
-            Box::pin(async move {
-                debug!(
-                    "Early TTS audio callback triggered: {} bytes",
-                    audio_data.data.len()
+    if let SessionEvent::Consent(record) = &event {
+        consent::consent_decided(record, state, message_tx, sessions, stream_id).await;
+    }
+    match session_event_action(event, error_sanitizer) {
+        EventAction::Send(msg) => {
+            // Slow links get fewer level updates, paced by the heartbeat RTT
+            if let OutgoingMessage::AudioLevel { direction, .. } = &msg
+                && let Some(heartbeat) = &state.read().await.heartbeat
+                && !heartbeat.pace_audio_level(*direction, Instant::now())
+            {
+                return;
+            }
+            // Levels would crowd everything else out of the replay buffer
+            if !matches!(msg, OutgoingMessage::AudioLevel { .. })
+                && let Ok(value) = serde_json::to_value(&msg)
+            {
+                observers.publish(stream_id, &value);
+            }
+            // Ignore send errors - client may have disconnected
+            let _ = message_tx.send(MessageRoute::Outgoing(msg)).await;
+        }
+        EventAction::Audio(audio_data) => {
+            // TTS audio is mono; monitors get silence while recordings are redacted
+            let pcm16 = is_pcm16(&audio_data.format);
+            let redaction = state.read().await.redaction.clone();
+            if pcm16 || !redaction.is_active() {
+                observers.publish_audio(
+                    stream_id,
+                    AudioDirection::Out,
+                    SinkAudioFormat::mono(audio_data.sample_rate),
+                    pcm16,
+                    &redaction.redact(&audio_data.data),
                 );
-
-                // Send audio as binary data to WebSocket
-                let audio_bytes = Bytes::from(audio_data.data);
-                let _ = message_tx.send(MessageRoute::Binary(audio_bytes)).await;
-            })
-        })
-        .await
-    {
-        warn!("Failed to register early TTS audio callback: {:?}", e);
+            }
+            send_tts_audio(audio_data, state, message_tx).await
+        }
+        EventAction::ClearLiveKitAudio => clear_livekit_audio(state).await,
+        EventAction::Ignore => {}
     }
 }
 
-/// Register final TTS audio callback with LiveKit routing
-async fn register_final_tts_callback(
-    voice_manager: &Arc<VoiceManager>,
-    livekit_client: Option<&Arc<RwLock<LiveKitClient>>>,
-    operation_queue: Option<&crate::livekit::OperationQueue>,
+/// Send TTS audio to LiveKit, falling back to the WebSocket
+async fn send_tts_audio(
+    audio_data: AudioData,
+    state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
 ) {
-    let message_tx_for_tts = message_tx.clone();
-    let livekit_client_for_tts = livekit_client.cloned();
-    let operation_queue_for_tts = operation_queue.cloned();
-
-    if let Err(e) = voice_manager
-        .on_tts_audio(move |audio_data: AudioData| {
-            let message_tx = message_tx_for_tts.clone();
-            let livekit_client = livekit_client_for_tts.clone();
-            let operation_queue = operation_queue_for_tts.clone();
-
-            Box::pin(async move {
-                let mut sent_to_livekit = false;
-
-                // Try to send to LiveKit using operation queue if available
-                if let Some(queue) = operation_queue {
-                    let (tx, rx) = tokio::sync::oneshot::channel();
-                    if queue
-                        .queue(crate::livekit::LiveKitOperation::SendAudio {
-                            audio_data: audio_data.data.clone(),
-                            response_tx: tx,
-                        })
-                        .await
-                        .is_ok()
-                    {
-                        match rx.await {
-                            Ok(Ok(())) => {
-                                debug!(
-                                    "TTS audio successfully sent to LiveKit via queue: {} bytes",
-                                    audio_data.data.len()
-                                );
-                                sent_to_livekit = true;
-                            }
-                            Ok(Err(e)) => {
-                                error!("Failed to send TTS audio to LiveKit: {:?}", e);
-                            }
-                            Err(_) => {
-                                error!("Operation worker disconnected while sending TTS audio");
-                            }
-                        }
-                    }
-                } else if let Some(livekit_client_arc) = &livekit_client {
-                    // Fallback to lock-based approach
-                    match tokio::time::timeout(
-                        tokio::time::Duration::from_millis(LIVEKIT_LOCK_TIMEOUT_MS),
-                        livekit_client_arc.write()
-                    ).await {
-                        Ok(client) => {
-                            // Check if LiveKit is connected before attempting to send
-                            if client.is_connected() {
-                                match client.send_tts_audio(audio_data.data.clone()).await {
-                                    Ok(()) => {
-                                        debug!(
-                                            "TTS audio successfully sent to LiveKit: {} bytes",
-                                            audio_data.data.len()
-                                        );
-                                        sent_to_livekit = true;
-                                    }
-                                    Err(e) => {
-                                        error!("Failed to send TTS audio to LiveKit: {:?}", e);
-                                    }
-                                }
-                            } else {
-                                debug!("LiveKit client not connected, falling back to WebSocket");
-                            }
-                        }
-                        Err(_) => {
-                            error!(
-                                "Failed to acquire LiveKit lock within {}ms timeout, falling back to WebSocket",
-                                LIVEKIT_LOCK_TIMEOUT_MS
-                            );
-                        }
-                    }
-                }
+    let (livekit_client, operation_queue) = {
+        let state_guard = state.read().await;
+        (
+            state_guard.livekit_client.clone(),
+            state_guard.livekit_operation_queue.clone(),
+        )
+    };
+
+    let mut sent_to_livekit = false;
 
-                // Fall back to WebSocket if LiveKit is not available or failed
-                if !sent_to_livekit {
+    // Try to send to LiveKit using operation queue if available
+    if let Some(queue) = operation_queue {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        if queue
+            .queue(crate::livekit::LiveKitOperation::SendAudio {
+                audio_data: audio_data.data.clone(),
+                response_tx: tx,
+            })
+            .await
+            .is_ok()
+        {
+            match rx.await {
+                Ok(Ok(())) => {
                     debug!(
-                        "Sending TTS audio to WebSocket client: {} bytes",
+                        "TTS audio successfully sent to LiveKit via queue: {} bytes",
                         audio_data.data.len()
                     );
-                    let audio_bytes = Bytes::from(audio_data.data);
-                    if let Err(e) = message_tx.send(MessageRoute::Binary(audio_bytes)).await {
-                        error!("Failed to send TTS audio to WebSocket: {:?}", e);
-                    }
+                    sent_to_livekit = true;
                 }
-            })
-        })
+                Ok(Err(e)) => {
+                    error!("Failed to send TTS audio to LiveKit: {:?}", e);
+                }
+                Err(_) => {
+                    error!("Operation worker disconnected while sending TTS audio");
+                }
+            }
+        }
+    } else if let Some(livekit_client_arc) = &livekit_client {
+        // Fallback to lock-based approach
+        match tokio::time::timeout(
+            tokio::time::Duration::from_millis(LIVEKIT_LOCK_TIMEOUT_MS),
+            livekit_client_arc.write(),
+        )
         .await
-    {
-        error!("Failed to set up TTS audio callback: {}", e);
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: format!("Failed to set up TTS audio callback: {e}"),
-            }))
-            .await;
+        {
+            Ok(client) => {
+                // Check if LiveKit is connected before attempting to send
+                if client.is_connected() {
+                    match client.send_tts_audio(audio_data.data.clone()).await {
+                        Ok(()) => {
+                            debug!(
+                                "TTS audio successfully sent to LiveKit: {} bytes",
+                                audio_data.data.len()
+                            );
+                            sent_to_livekit = true;
+                        }
+                        Err(e) => {
+                            error!("Failed to send TTS audio to LiveKit: {:?}", e);
+                        }
+                    }
+                } else {
+                    debug!("LiveKit client not connected, falling back to WebSocket");
+                }
+            }
+            Err(_) => {
+                error!(
+                    "Failed to acquire LiveKit lock within {}ms timeout, falling back to WebSocket",
+                    LIVEKIT_LOCK_TIMEOUT_MS
+                );
+            }
+        }
+    }
+
+    // Fall back to WebSocket if LiveKit is not available or failed
+    if !sent_to_livekit {
+        debug!(
+            "Sending TTS audio to WebSocket client: {} bytes",
+            audio_data.data.len()
+        );
+        let audio_bytes = Bytes::from(audio_data.data);
+        if let Err(e) = message_tx.send(MessageRoute::Binary(audio_bytes)).await {
+            error!("Failed to send TTS audio to WebSocket: {:?}", e);
+        }
     }
 }
 
@@ -774,11 +1403,15 @@ async fn initialize_livekit_client(
     livekit_ws_config: LiveKitWebSocketConfig,
     tts_config: Option<&TTSWebSocketConfig>,
     livekit_url: &str,
-    voice_manager: Option<&Arc<VoiceManager>>,
+    session: Option<&Arc<Session>>,
     message_tx: &mpsc::Sender<MessageRoute>,
     room_handler: Option<&Arc<crate::livekit::room_handler::LiveKitRoomHandler>>,
     stream_id: &str,
     auth_id: Option<&str>,
+    metadata: &SessionMetadata,
+    greeting_track_tx: Option<oneshot::Sender<()>>,
+    on_dtmf: Option<DtmfCallback>,
+    error_sanitizer: &Arc<ErrorSanitizer>,
 ) -> Option<(
     Arc<RwLock<LiveKitClient>>,
     Option<crate::livekit::OperationQueue>,
@@ -857,7 +1490,7 @@ async fn initialize_livekit_client(
     // Start recording if requested
     let egress_id = if livekit_ws_config.enable_recording {
         match room_handler
-            .setup_room_recording(&livekit_ws_config.room_name, auth_id, stream_id)
+            .setup_room_recording(&livekit_ws_config.room_name, auth_id, stream_id, metadata)
             .await
         {
             Ok(id) => {
@@ -886,13 +1519,22 @@ async fn initialize_livekit_client(
         sample_rate: Some(24000), // Default sample rate for LiveKit
         connection_timeout: None,
         request_timeout: None,
+        utterance_timeout: None,
         model: "".to_string(),
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         api_key: None, // No client-provided key for default config
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let tts_config_for_livekit = tts_config.unwrap_or(&default_tts_config);
@@ -901,8 +1543,8 @@ async fn initialize_livekit_client(
     let mut livekit_client = LiveKitClient::new(livekit_config);
 
     // Set up audio callback to forward to STT processing
-    if let Some(vm) = voice_manager {
-        setup_livekit_audio_callback(&mut livekit_client, vm, message_tx);
+    if let Some(session) = session {
+        setup_livekit_audio_callback(&mut livekit_client, session, message_tx, error_sanitizer);
     }
 
     // Set up data callback
@@ -911,6 +1553,16 @@ async fn initialize_livekit_client(
     // Set up participant disconnect callback
     setup_livekit_disconnect_callback(&mut livekit_client, message_tx);
 
+    // Signal the pending greeting when the first audio track appears
+    if let Some(track_tx) = greeting_track_tx {
+        setup_livekit_audio_track_callback(&mut livekit_client, track_tx);
+    }
+
+    // Open redaction windows for DTMF digits
+    if let Some(on_dtmf) = on_dtmf {
+        livekit_client.set_dtmf_callback(move |event| on_dtmf(event));
+    }
+
     // Connect to LiveKit room
     if let Err(e) = livekit_client.connect().await {
         error!("Failed to connect to LiveKit room: {:?}", e);
@@ -930,11 +1582,6 @@ async fn initialize_livekit_client(
 
     let livekit_client_arc = Arc::new(RwLock::new(livekit_client));
 
-    // Register audio clear callback with VoiceManager
-    if let Some(vm) = voice_manager {
-        register_audio_clear_callback(vm, &livekit_client_arc, operation_queue.as_ref()).await;
-    }
-
     info!("LiveKit client connected and ready");
     Some((
         livekit_client_arc,
@@ -946,18 +1593,52 @@ async fn initialize_livekit_client(
     ))
 }
 
+/// Format of the caller audio a session receives, as set by its STT config
+fn input_format(session: &Session) -> SinkAudioFormat {
+    SinkAudioFormat {
+        sample_rate: session.input_sample_rate(),
+        channels: session
+            .effective_config()
+            .stt
+            .as_ref()
+            .map_or(1, |stt| stt.channels),
+    }
+}
+
+/// Start recording a session's caller audio, if recording upload is configured
+///
+/// Only 16-bit PCM is recorded; sessions receiving other encodings get no
+/// recording.
+fn start_input_recording(
+    session: &Session,
+    auth_id: Option<&str>,
+    stream_id: &str,
+    app_state: &AppState,
+) -> Option<RecordingUpload> {
+    let uploader = app_state.recording_uploads.as_ref()?;
+    let stt = session.effective_config().stt.as_ref();
+    if stt.is_some_and(|stt| !is_pcm16(&stt.encoding)) {
+        debug!(stream_id = %stream_id, "Caller audio is not 16-bit PCM; not recording it");
+        return None;
+    }
+    Some(uploader.begin(auth_id, stream_id, input_format(session)))
+}
+
 /// Set up LiveKit audio callback to forward audio to STT
 fn setup_livekit_audio_callback(
     livekit_client: &mut LiveKitClient,
-    voice_manager: &Arc<VoiceManager>,
+    session: &Arc<Session>,
     message_tx: &mpsc::Sender<MessageRoute>,
+    error_sanitizer: &Arc<ErrorSanitizer>,
 ) {
-    let voice_manager_clone = voice_manager.clone();
+    let session_clone = session.clone();
     let message_tx_clone = message_tx.clone();
+    let error_sanitizer = error_sanitizer.clone();
 
     livekit_client.set_audio_callback(move |audio_data: Vec<u8>| {
-        let voice_manager = voice_manager_clone.clone();
+        let session = session_clone.clone();
         let message_tx = message_tx_clone.clone();
+        let error_sanitizer = error_sanitizer.clone();
 
         // Direct processing - spawn lightweight task for async processing
         tokio::spawn(async move {
@@ -965,12 +1646,16 @@ fn setup_livekit_audio_callback(
 
             // Forward LiveKit audio to the same STT processing pipeline
             // Convert Vec<u8> to Bytes - O(1) ownership transfer, no copy
-            if let Err(e) = voice_manager.receive_audio(audio_data.into()).await {
-                error!("Failed to process LiveKit audio: {:?}", e);
+            if let Err(e) = session.push_audio(audio_data.into()).await {
+                let code = e.error_code();
+                error!(code, "Failed to process LiveKit audio");
                 let _ = message_tx
-                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: format!("Failed to process LiveKit audio: {e:?}"),
-                    }))
+                    .send(MessageRoute::Outgoing(OutgoingMessage::provider_error(
+                        &error_sanitizer,
+                        code,
+                        &format!("Failed to process LiveKit audio: {e}"),
+                        None,
+                    )))
                     .await;
             }
         });
@@ -1065,11 +1750,294 @@ fn setup_livekit_disconnect_callback(
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
             // Close the WebSocket connection to trigger cleanup
-            let _ = message_tx.send(MessageRoute::Close).await;
+            close_connection(
+                &message_tx,
+                CloseReason::ParticipantLeft,
+                "LiveKit participant left the room",
+            )
+            .await;
         });
     });
 }
 
+/// Set up LiveKit audio track callback to signal the first subscribed audio track
+fn setup_livekit_audio_track_callback(
+    livekit_client: &mut LiveKitClient,
+    track_tx: oneshot::Sender<()>,
+) {
+    let track_tx = parking_lot::Mutex::new(Some(track_tx));
+
+    livekit_client.set_audio_track_callback(move |track_event| {
+        if let Some(tx) = track_tx.lock().take() {
+            debug!(
+                "Audio track {} from {} is ready for the greeting",
+                track_event.track_sid, track_event.participant_identity
+            );
+            let _ = tx.send(());
+        }
+    });
+}
+
+/// Play the session greeting once the caller can hear it
+///
+/// The greeting plays at most once per `stream_id`: a re-sent config or a
+/// client resuming the session skips it. With LiveKit it waits for the first
+/// remote audio track (e.g. the SIP participant joining the room), and for
+/// an outbound call until the callee answers.
+fn spawn_greeting(
+    session: Arc<Session>,
+    greeting: GreetingConfig,
+    stream_id: String,
+    track_ready: Option<oneshot::Receiver<()>>,
+    answered: Option<oneshot::Receiver<()>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    app_state: &Arc<AppState>,
+) {
+    if !app_state.session_store.claim_greeting(&stream_id) {
+        info!(stream_id = %stream_id, "Greeting already played for this session - skipping");
+        return;
+    }
+
+    let message_tx = message_tx.clone();
+    let app_state = app_state.clone();
+
+    tokio::spawn(async move {
+        // The sender is dropped with the LiveKit client if no track ever appears
+        if let Some(track_ready) = track_ready
+            && track_ready.await.is_err()
+        {
+            debug!(stream_id = %stream_id, "LiveKit closed before an audio track appeared - greeting skipped");
+            app_state.session_store.release_greeting(&stream_id);
+            return;
+        }
+
+        // The sender is dropped if the outbound call ends unanswered
+        if let Some(answered) = answered
+            && answered.await.is_err()
+        {
+            debug!(stream_id = %stream_id, "Outbound call was not answered - greeting skipped");
+            app_state.session_store.release_greeting(&stream_id);
+            return;
+        }
+
+        let assets_dir = app_state.config.greeting_assets_dir.as_deref();
+        if let Err(e) = session.play_greeting(&greeting, assets_dir).await {
+            let code = e.error_code();
+            error!(stream_id = %stream_id, code, "Failed to play greeting");
+            app_state.session_store.release_greeting(&stream_id);
+            let _ = message_tx
+                .send(MessageRoute::Outgoing(OutgoingMessage::provider_error(
+                    &app_state.error_sanitizer,
+                    code,
+                    &format!("Failed to play greeting: {e}"),
+                    None,
+                )))
+                .await;
+        }
+    });
+}
+
+/// Follow the outbound call a session placed until it is answered or ends
+///
+/// Each state the call reaches is sent as a `call_state` message, and the
+/// early media asset plays while it rings. Once the call is answered the
+/// early media is cleared, the session's hold is released and `answered`
+/// fires. A call that ends unanswered is abandoned: its SIP participant is
+/// removed from the room and the connection is closed.
+fn spawn_call_watcher(
+    session: Option<Arc<Session>>,
+    room_name: String,
+    answered: Option<oneshot::Sender<()>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    app_state: &Arc<AppState>,
+) {
+    let mut call_state = app_state.sip_calls.track(&room_name);
+    let message_tx = message_tx.clone();
+    let app_state = app_state.clone();
+    let outbound = app_state
+        .config
+        .sip
+        .as_ref()
+        .map(|sip| sip.outbound.clone())
+        .unwrap_or_default();
+
+    tokio::spawn(async move {
+        let poller = spawn_call_status_poller(&room_name, &outbound, &app_state);
+        let deadline = Instant::now() + Duration::from_secs(outbound.no_answer_timeout_secs);
+        let mut early_media = None;
+        let mut state = *call_state.borrow_and_update();
+        // None when the connection closed while the call was pending
+        let outcome = loop {
+            let _ = message_tx
+                .send(MessageRoute::Outgoing(OutgoingMessage::call_state(
+                    state,
+                    now_ms(),
+                )))
+                .await;
+            if state == CallState::EarlyMedia
+                && early_media.is_none()
+                && let Some(session) = &session
+            {
+                early_media = Some(play_early_media(session, &outbound, &room_name, &app_state));
+            }
+            if !state.is_pending() {
+                break Some(state);
+            }
+
+            tokio::select! {
+                next = app_state.sip_calls.progress(&room_name, &mut call_state, deadline) => {
+                    state = next;
+                }
+                _ = message_tx.closed() => break None,
+            }
+        };
+
+        if let Some(poller) = poller {
+            poller.abort();
+        }
+        if let Some(early_media) = &early_media {
+            early_media.abort();
+        }
+        let participant = app_state.sip_calls.participant(&room_name);
+        app_state.sip_calls.remove(&room_name);
+
+        match outcome {
+            Some(CallState::Ended(reason)) => {
+                info!(
+                    room = %room_name,
+                    reason = reason.as_str(),
+                    "Outbound call ended before it was answered"
+                );
+                if let (Some(room_handler), Some(participant)) =
+                    (app_state.livekit_room_handler.as_ref(), participant)
+                    && let Err(e) = room_handler
+                        .remove_participant(&room_name, &participant)
+                        .await
+                {
+                    debug!(room = %room_name, "SIP participant already gone: {}", e);
+                }
+                close_connection(
+                    &message_tx,
+                    CloseReason::CallNotAnswered,
+                    format!("Outbound call was not answered ({})", reason.as_str()),
+                )
+                .await;
+            }
+            Some(_) => {
+                info!(room = %room_name, "Outbound call answered");
+                if let Some(session) = &session {
+                    if early_media.is_some()
+                        && let Err(e) = session.interrupt().await
+                    {
+                        warn!(room = %room_name, "Failed to clear early media: {}", e);
+                    }
+                    session.release_hold();
+                }
+                if let Some(answered) = answered {
+                    let _ = answered.send(());
+                }
+            }
+            None => {
+                debug!(room = %room_name, "Connection closed before the outbound call was answered")
+            }
+        }
+    });
+}
+
+/// Poll a room's SIP participant for the status of its outbound call
+///
+/// LiveKit webhooks only report participants joining and leaving, so the
+/// ringing and the answer of a call are seen by reading the participant's
+/// `sip.callStatus`. Polling stops with the call watcher.
+fn spawn_call_status_poller(
+    room_name: &str,
+    outbound: &SipOutboundConfig,
+    app_state: &Arc<AppState>,
+) -> Option<tokio::task::AbortHandle> {
+    let room_handler = app_state.livekit_room_handler.clone()?;
+    let room_name = room_name.to_string();
+    let interval = Duration::from_millis(outbound.status_poll_interval_ms);
+    let app_state = app_state.clone();
+
+    let task = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut seen = false;
+        loop {
+            ticker.tick().await;
+            let participants = match room_handler.list_participants(&room_name).await {
+                Ok(participants) => participants,
+                Err(e) => {
+                    debug!(room = %room_name, "Failed to poll outbound call status: {}", e);
+                    continue;
+                }
+            };
+            let callee = participants.into_iter().find(|p| {
+                participant_info::Kind::try_from(p.kind)
+                    .is_ok_and(|kind| kind == participant_info::Kind::Sip)
+            });
+            let (participant, signal) = match callee {
+                Some(callee) => {
+                    seen = true;
+                    match CallSignal::from_attributes(&callee.attributes) {
+                        Some(signal) => (Some(callee.identity), signal),
+                        None => continue,
+                    }
+                }
+                // The callee was in the room and is gone
+                None if seen => (None, CallSignal::HungUp(None)),
+                None => continue,
+            };
+            app_state
+                .sip_calls
+                .observe(&room_name, participant.as_deref(), signal);
+        }
+    });
+    Some(task.abort_handle())
+}
+
+/// Play the early media asset of outbound calls, if one is configured
+fn play_early_media(
+    session: &Arc<Session>,
+    outbound: &SipOutboundConfig,
+    room_name: &str,
+    app_state: &Arc<AppState>,
+) -> tokio::task::AbortHandle {
+    let session = session.clone();
+    let asset = outbound.early_media_asset.clone();
+    let assets_dir = app_state.config.greeting_assets_dir.clone();
+    let room_name = room_name.to_string();
+
+    let task = tokio::spawn(async move {
+        let Some(asset) = asset else {
+            return;
+        };
+        let Some(assets_dir) = assets_dir else {
+            warn!(room = %room_name, "early_media_asset requires a greeting assets directory");
+            return;
+        };
+        let sample_rate = session.output_sample_rate();
+        let result = match load_audio_asset(&assets_dir, &asset, sample_rate).await {
+            Ok(pcm) => session
+                .play_audio(pcm, sample_rate, true)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!(room = %room_name, "Failed to play early media: {}", e);
+        }
+    });
+    task.abort_handle()
+}
+
+/// Current Unix time in milliseconds
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 /// Wait for LiveKit audio source to become available
 async fn wait_for_livekit_audio(livekit_client: &LiveKitClient) {
     let mut wait_count = 0;
@@ -1098,104 +2066,69 @@ async fn wait_for_livekit_audio(livekit_client: &LiveKitClient) {
     }
 }
 
-/// Register audio clear callback with VoiceManager for LiveKit integration
-async fn register_audio_clear_callback(
-    voice_manager: &Arc<VoiceManager>,
-    livekit_client: &Arc<RwLock<LiveKitClient>>,
-    operation_queue: Option<&crate::livekit::OperationQueue>,
-) {
+/// Clear buffered LiveKit audio after the session drops queued TTS
+async fn clear_livekit_audio(state: &Arc<RwLock<ConnectionState>>) {
+    let (livekit_client, operation_queue) = {
+        let state_guard = state.read().await;
+        (
+            state_guard.livekit_client.clone(),
+            state_guard.livekit_operation_queue.clone(),
+        )
+    };
+
     if let Some(queue) = operation_queue {
         // Use operation queue for non-blocking clear
-        let queue_clone = queue.clone();
-
-        if let Err(e) = voice_manager
-            .on_audio_clear(move || {
-                let queue = queue_clone.clone();
-                Box::pin(async move {
-                    let (tx, rx) = tokio::sync::oneshot::channel();
-                    if let Err(e) = queue
-                        .queue(crate::livekit::LiveKitOperation::ClearAudio { response_tx: tx })
-                        .await
-                    {
-                        warn!("Failed to queue clear audio operation: {:?}", e);
-                    } else {
-                        // Wait for the operation to complete
-                        match rx.await {
-                            Ok(Ok(())) => {
-                                debug!("Cleared LiveKit audio buffer during interruption");
-                            }
-                            Ok(Err(e)) => {
-                                warn!("Failed to clear LiveKit audio buffer: {:?}", e);
-                            }
-                            Err(_) => {
-                                warn!("Operation worker disconnected during audio clear");
-                            }
-                        }
-                    }
-                })
-            })
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        if let Err(e) = queue
+            .queue(crate::livekit::LiveKitOperation::ClearAudio { response_tx: tx })
             .await
         {
-            warn!("Failed to register audio clear callback: {:?}", e);
+            warn!("Failed to queue clear audio operation: {:?}", e);
+        } else {
+            // Wait for the operation to complete
+            match rx.await {
+                Ok(Ok(())) => {
+                    debug!("Cleared LiveKit audio buffer during interruption");
+                }
+                Ok(Err(e)) => {
+                    warn!("Failed to clear LiveKit audio buffer: {:?}", e);
+                }
+                Err(_) => {
+                    warn!("Operation worker disconnected during audio clear");
+                }
+            }
         }
-    } else {
+    } else if let Some(livekit) = livekit_client {
         // Fallback to lock-based approach
-        let livekit_client_clone = livekit_client.clone();
-
-        if let Err(e) = voice_manager
-            .on_audio_clear(move || {
-                let livekit = livekit_client_clone.clone();
-                Box::pin(async move {
-                    // Use try_write to avoid blocking in callback
-                    // If we can't get the lock, it's okay - audio will be cleared eventually
-                    match livekit.try_write() {
-                        Ok(client) => {
-                            if let Err(e) = client.clear_audio().await {
-                                warn!("Failed to clear LiveKit audio buffer: {:?}", e);
-                            } else {
-                                debug!("Cleared LiveKit audio buffer during interruption");
-                            }
-                        }
-                        Err(_) => {
-                            debug!("LiveKit client busy during audio clear - skipping");
-                        }
-                    }
-                })
-            })
-            .await
-        {
-            warn!("Failed to register audio clear callback: {:?}", e);
+        // Use try_write to avoid blocking the event forwarder
+        // If we can't get the lock, it's okay - audio will be cleared eventually
+        match livekit.try_write() {
+            Ok(client) => {
+                if let Err(e) = client.clear_audio().await {
+                    warn!("Failed to clear LiveKit audio buffer: {:?}", e);
+                } else {
+                    debug!("Cleared LiveKit audio buffer during interruption");
+                }
+            }
+            Err(_) => {
+                debug!("LiveKit client busy during audio clear - skipping");
+            }
         }
     }
 }
 
-/// Initialize DAG routing for the connection
-///
-/// Compiles a DAG from template or inline definition and sets up the executor.
-/// The DAG will route audio through its nodes instead of direct STT→TTS flow.
-///
-/// # Arguments
-/// * `dag_config` - DAG configuration from WebSocket message
-/// * `stream_id` - Session identifier for the DAG context
-/// * `state` - Connection state to store compiled DAG
-/// * `message_tx` - Channel for sending error messages
+/// Load the DAG of a session config from its inline definition or template and compile it
 ///
 /// # Returns
-/// * `Ok(true)` - DAG successfully initialized and enabled
-/// * `Ok(false)` - DAG not configured or disabled
-/// * `Err(String)` - DAG initialization failed
+/// * `Ok(Some(CompiledDAG))` - The compiled DAG
+/// * `Ok(None)` - The config names no DAG
+/// * `Err(String)` - The definition is invalid, unknown or does not compile
 #[cfg(feature = "dag-routing")]
-async fn initialize_dag_routing(
-    dag_config: &DAGWebSocketConfig,
-    stream_id: &str,
-    state: &Arc<RwLock<ConnectionState>>,
-    _message_tx: &mpsc::Sender<MessageRoute>,
-) -> Result<bool, String> {
+fn compile_dag(dag_config: &DAGWebSocketConfig) -> Result<Option<CompiledDAG>, String> {
     // Get DAG definition from template or inline
     let dag_definition: DAGDefinition = if let Some(ref def) = dag_config.definition {
         // Parse inline definition
-        serde_json::from_value(def.clone())
-            .map_err(|e| format!("Invalid DAG definition: {}", e))?
+        serde_json::from_value(def.clone()).map_err(|e| format!("Invalid DAG definition: {}", e))?
     } else if let Some(ref template_name) = dag_config.template {
         // Load from template registry
         let templates = global_templates();
@@ -1203,8 +2136,7 @@ async fn initialize_dag_routing(
             .get(template_name)
             .ok_or_else(|| format!("DAG template '{}' not found", template_name))?
     } else {
-        // No DAG specified
-        return Ok(false);
+        return Ok(None);
     };
 
     info!(
@@ -1217,14 +2149,45 @@ async fn initialize_dag_routing(
 
     // Compile the DAG
     let compiler = DAGCompiler::new();
-    let compiled_dag = compiler
+    compiler
         .compile(dag_definition)
-        .map_err(|e| format!("DAG compilation failed: {}", e))?;
+        .map(Some)
+        .map_err(|e| format!("DAG compilation failed: {}", e))
+}
+
+/// Initialize DAG routing for the connection
+///
+/// Compiles a DAG from template or inline definition and sets up the executor.
+/// The DAG will route audio through its nodes instead of direct STT→TTS flow.
+///
+/// # Arguments
+/// * `dag_config` - DAG configuration from WebSocket message
+/// * `stream_id` - Session identifier for the DAG context
+/// * `state` - Connection state to store compiled DAG
+/// * `message_tx` - Channel for sending error messages
+/// * `plugin_registry` - Registry the DAG's nodes create providers from
+///
+/// # Returns
+/// * `Ok(true)` - DAG successfully initialized and enabled
+/// * `Ok(false)` - DAG not configured or disabled
+/// * `Err(String)` - DAG initialization failed
+#[cfg(feature = "dag-routing")]
+async fn initialize_dag_routing(
+    dag_config: &DAGWebSocketConfig,
+    stream_id: &str,
+    state: &Arc<RwLock<ConnectionState>>,
+    _message_tx: &mpsc::Sender<MessageRoute>,
+    plugin_registry: &Arc<PluginRegistry>,
+) -> Result<bool, String> {
+    let Some(compiled_dag) = compile_dag(dag_config)? else {
+        // No DAG specified
+        return Ok(false);
+    };
 
     let compiled_dag = Arc::new(compiled_dag);
 
     // Create DAG context with auth info from connection state
-    let dag_context = {
+    let mut dag_context = {
         let conn_state = state.read().await;
         DAGContext::with_auth(
             stream_id.to_string(),
@@ -1232,6 +2195,7 @@ async fn initialize_dag_routing(
             conn_state.auth.id.clone(),
         )
     };
+    dag_context.set_resource(resource_keys::PLUGIN_REGISTRY, plugin_registry.clone());
 
     // Apply timeout if specified
     let dag_context = if let Some(timeout_ms) = dag_config.timeout_ms {
@@ -0,0 +1,231 @@
+//! Recording consent of a WebSocket session
+//!
+//! Sessions configured with `stt_config.consent` hold the caller's audio from
+//! STT until the caller consents, with the client's `consent` message or a
+//! DTMF digit from a SIP caller. Every decision is written to the audit log
+//! and counted. A denial or an unanswered prompt plays the session's goodbye
+//! and closes the connection with [`CloseReason::ConsentNotGiven`], which
+//! ends the call.
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::sync::{RwLock, mpsc};
+use tracing::{debug, info, warn};
+
+use crate::core::session::{ConsentConfig, ConsentRecord, ConsentSource, ConsentState, Session};
+use crate::handlers::close::CloseReason;
+use crate::livekit::{DtmfCallback, DtmfEvent};
+use crate::metrics::global_metrics;
+use crate::state::SessionStore;
+
+use super::{
+    messages::{MessageRoute, OutgoingMessage},
+    processor::close_connection,
+    state::ConnectionState,
+};
+
+/// Longest wait for the goodbye to finish playing before closing
+const GOODBYE_MAX_WAIT: Duration = Duration::from_secs(15);
+
+/// How often to check whether the goodbye finished playing
+const GOODBYE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Session metadata key holding the consent state
+pub const CONSENT_METADATA_KEY: &str = "recording_consent";
+
+/// Session metadata key holding when consent was decided (Unix ms)
+pub const CONSENT_AT_METADATA_KEY: &str = "recording_consent_at";
+
+/// Handle the client's `consent` message
+///
+/// The first decision counts; a message arriving after it is answered with
+/// the session's current consent. Sessions without a consent gate answer with
+/// an error.
+///
+/// # Arguments
+/// * `granted` - Whether the caller consented
+/// * `state` - Connection state containing the voice session
+/// * `message_tx` - Channel for sending response messages
+///
+/// # Returns
+/// * `bool` - true to continue processing
+pub async fn handle_consent_message(
+    granted: bool,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+) -> bool {
+    let session = state.read().await.session.clone();
+    let Some(session) = session else {
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: "Voice manager not configured. Send config message with audio=true first."
+                    .to_string(),
+            }))
+            .await;
+        return true;
+    };
+
+    let Some(current) = session.consent() else {
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
+                message: "Recording consent is not configured for this session".to_string(),
+            }))
+            .await;
+        return true;
+    };
+
+    match session
+        .set_consent(granted, ConsentSource::Client, None)
+        .await
+    {
+        // The session's consent event answers the client
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            debug!(
+                state = current.state.as_str(),
+                "Recording consent already decided"
+            );
+            let _ = message_tx
+                .send(MessageRoute::Outgoing(consent_message(current)))
+                .await;
+        }
+        Err(e) => {
+            warn!("Failed to send held audio after consent: {}", e);
+        }
+    }
+    true
+}
+
+/// Callback deciding consent from DTMF digits in the session's room
+///
+/// Digits other than the configured grant and deny digits are ignored. The
+/// digit itself is never logged.
+pub(super) fn dtmf_callback(session: &Arc<Session>, config: &ConsentConfig) -> DtmfCallback {
+    let session: Weak<Session> = Arc::downgrade(session);
+    let config = config.clone();
+
+    Arc::new(move |event: DtmfEvent| {
+        let Some(granted) = event
+            .digit
+            .as_deref()
+            .and_then(|digit| config.digit_decision(digit))
+        else {
+            return;
+        };
+        let Some(session) = session.upgrade() else {
+            return;
+        };
+
+        tokio::spawn(async move {
+            if let Err(e) = session
+                .set_consent(granted, ConsentSource::Dtmf, event.participant_identity)
+                .await
+            {
+                warn!("Failed to send held audio after consent: {}", e);
+            }
+        });
+    })
+}
+
+/// Record a consent decision of the session and, unless consent was granted,
+/// end the call politely
+///
+/// The decision goes into the session's metadata, so webhooks carry it, when
+/// it fits within the metadata limits.
+/// Called for the session's `Consent` event, before it is forwarded to the
+/// client.
+pub(super) async fn consent_decided(
+    record: &ConsentRecord,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    sessions: &SessionStore,
+    stream_id: &str,
+) {
+    // The client may already use the whole metadata budget; the decision
+    // still reaches the audit log, usage record and export
+    let mut entries = vec![(CONSENT_METADATA_KEY, record.state.as_str().to_string())];
+    if let Some(timestamp) = record.timestamp {
+        entries.push((CONSENT_AT_METADATA_KEY, timestamp.to_string()));
+    }
+    for (key, value) in entries {
+        if let Err(e) = sessions.set_metadata(stream_id, key, value) {
+            warn!(stream_id, key, "Consent left out of metadata: {}", e);
+        }
+    }
+
+    let (auth_id, session) = {
+        let state_guard = state.read().await;
+        (state_guard.auth.id.clone(), state_guard.session.clone())
+    };
+
+    info!(
+        target: "audit",
+        stream_id,
+        client_id = ?auth_id,
+        state = record.state.as_str(),
+        source = record.source.map(ConsentSource::as_str),
+        participant = ?record.participant,
+        timestamp = ?record.timestamp,
+        "Recording consent"
+    );
+    global_metrics().inc_counter(
+        "waav_recording_consent_total",
+        "Recording consent decisions",
+        &[("state", record.state.as_str())],
+    );
+
+    if record.state == ConsentState::Granted {
+        return;
+    }
+    let Some(session) = session else {
+        return;
+    };
+    let goodbye = session
+        .effective_config()
+        .consent
+        .as_ref()
+        .and_then(|config| config.goodbye.clone());
+    let reason = match record.state {
+        ConsentState::Denied => "Recording consent was denied",
+        _ => "Recording consent was not given in time",
+    };
+    let message_tx = message_tx.clone();
+
+    tokio::spawn(async move {
+        if let Some(goodbye) = goodbye {
+            say_goodbye(&session, &goodbye).await;
+        }
+        close_connection(&message_tx, CloseReason::ConsentNotGiven, reason).await;
+    });
+}
+
+/// Play the goodbye and wait until it finished, up to [`GOODBYE_MAX_WAIT`]
+async fn say_goodbye(session: &Session, goodbye: &str) {
+    if let Err(e) = session.speak_with_interruption(goodbye, true, false).await {
+        warn!("Failed to play consent goodbye: {}", e);
+        return;
+    }
+    let played = tokio::time::timeout(GOODBYE_MAX_WAIT, async {
+        loop {
+            tokio::time::sleep(GOODBYE_POLL_INTERVAL).await;
+            if !session.is_interruption_blocked().await {
+                break;
+            }
+        }
+    })
+    .await;
+    if played.is_err() {
+        debug!("Consent goodbye still playing - closing anyway");
+    }
+}
+
+/// The `consent` message for a consent record
+fn consent_message(record: ConsentRecord) -> OutgoingMessage {
+    OutgoingMessage::Consent {
+        state: record.state,
+        source: record.source,
+        participant: record.participant,
+        timestamp: record.timestamp,
+    }
+}
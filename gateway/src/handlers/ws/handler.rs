@@ -11,21 +11,33 @@ use axum::{
     },
     response::Response,
 };
-use futures::{SinkExt, StreamExt};
+use futures::{Sink, SinkExt, Stream, StreamExt};
 use std::net::IpAddr;
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
-use tokio::{select, time::Duration};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{RwLock, mpsc, oneshot};
+use tokio::{
+    select,
+    time::{Duration, Instant},
+};
 use tracing::{debug, error, info, warn};
 
 use crate::auth::Auth;
-use crate::middleware::ClientIp;
-use crate::state::AppState;
+use crate::enrichment::EnrichmentJob;
+use crate::flight_recorder::{EntryKind, FlightLog};
+use crate::handlers::close::CloseReason;
+use crate::metrics::global_metrics;
+use crate::middleware::{ClientIp, ConnectionGuard};
+use crate::session_export::FinishedSession;
+use crate::state::{AppState, SessionStore};
+use crate::usage::{UsageRecord, UsageRecorder, UsageTermination};
 
 use super::{
-    audio_handler::handle_audio_message,
-    messages::{IncomingMessage, MessageRoute, OutgoingMessage},
-    processor::handle_incoming_message,
+    audio_handler::{handle_audio_message, handle_play_audio_frame},
+    heartbeat::{ConnectionHeartbeat, HeartbeatAction},
+    messages::{MessageRoute, OutgoingMessage},
+    processor::{close_connection, handle_incoming_message},
+    stages::{InboundFrame, decode_frame, encode_route, is_heartbeat_answer},
     state::ConnectionState,
 };
 
@@ -42,10 +54,8 @@ const MAX_WS_FRAME_SIZE: usize = 10 * 1024 * 1024;
 /// This limits the total message size (can be multiple frames)
 const MAX_WS_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
 
-/// Maximum text message size before deserialization (1 MB)
-/// This prevents JSON parsing attacks with extremely large payloads
-/// Binary audio messages use MAX_WS_MESSAGE_SIZE instead
-const MAX_TEXT_MESSAGE_SIZE: usize = 1024 * 1024;
+/// Longest wait to queue the close of a connection reaped by the heartbeat
+const HEARTBEAT_CLOSE_TIMEOUT: Duration = Duration::from_millis(500);
 
 /// WebSocket voice processing handler
 ///
@@ -60,6 +70,23 @@ const MAX_TEXT_MESSAGE_SIZE: usize = 1024 * 1024;
 ///
 /// # Returns
 /// * `Response` - HTTP response that upgrades the connection to WebSocket
+#[cfg_attr(
+    feature = "openapi",
+    utoipa::path(
+        get,
+        path = "/ws",
+        responses(
+            (status = 101, description = "Switching to the WebSocket protocol; messages are described by IncomingMessage and OutgoingMessage"),
+            (status = 401, description = "Unauthorized"),
+            (status = 429, description = "Connection limit reached"),
+            (status = 503, description = "Server is shedding load")
+        ),
+        security(
+            ("bearer_auth" = [])
+        ),
+        tag = "websocket"
+    )
+)]
 pub async fn ws_voice_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
@@ -91,33 +118,61 @@ pub async fn ws_voice_handler(
 
 /// Handle WebSocket voice connection with optimized performance
 ///
-/// This function manages the entire WebSocket session for voice processing,
-/// including message routing, resource management, and graceful cleanup.
+/// Splits the socket and runs it with [`run_voice_connection`].
 ///
 /// # Arguments
 /// * `socket` - The established WebSocket connection
 /// * `app_state` - Application state containing shared resources
 /// * `auth` - Auth context for tenant isolation (room name prefixing)
 /// * `client_ip` - Optional client IP for connection limit tracking (releases on disconnect)
+async fn handle_voice_socket(
+    socket: WebSocket,
+    app_state: Arc<AppState>,
+    auth: Auth,
+    client_ip: Option<IpAddr>,
+) {
+    debug!("Splitting socket into sender and receiver");
+    let (sender, receiver) = socket.split();
+    run_voice_connection(sender, receiver, app_state, auth, client_ip).await;
+}
+
+/// Run a voice connection over any transport speaking `/ws` messages
+///
+/// This function manages the entire session for voice processing,
+/// including message routing, resource management, and graceful cleanup.
+/// Besides WebSocket clients it drives sessions fed by other transports,
+/// such as RTMP ingest, which translate their input into `/ws` messages.
+///
+/// # Arguments
+/// * `sender` - Sink for outgoing messages
+/// * `receiver` - Stream of incoming messages; the session ends with it
+/// * `app_state` - Application state containing shared resources
+/// * `auth` - Auth context for tenant isolation (room name prefixing)
+/// * `client_ip` - Optional client IP for connection limit tracking (releases on disconnect)
 ///
 /// # Lifecycle
-/// 1. Split socket into sender/receiver for bidirectional communication
-/// 2. Set up message routing channels with optimized buffer sizes
-/// 3. Spawn sender task for outgoing messages
-/// 4. Process incoming messages in a loop
-/// 5. Clean up resources on connection close (including connection limit release)
+/// 1. Set up message routing channels with optimized buffer sizes
+/// 2. Spawn sender task for outgoing messages
+/// 3. Process incoming messages in a loop
+/// 4. Clean up resources on connection close (including connection limit release)
 ///
 /// # Performance Optimizations
 /// - Large channel buffer (1024) for reduced contention
 /// - RwLock for connection state (frequent reads, rare writes)
 /// - Timeout handling for stale connection detection
-async fn handle_voice_socket(
-    socket: WebSocket,
+pub(crate) async fn run_voice_connection<S, R, E>(
+    mut sender: S,
+    mut receiver: R,
     app_state: Arc<AppState>,
     auth: Auth,
     client_ip: Option<IpAddr>,
-) {
-    debug!("handle_voice_socket started");
+) where
+    S: Sink<Message> + Unpin + Send + 'static,
+    S::Error: std::fmt::Display,
+    R: Stream<Item = Result<Message, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    debug!("run_voice_connection started");
     info!(
         auth_id = ?auth.id,
         pending = %auth.pending,
@@ -127,21 +182,36 @@ async fn handle_voice_socket(
 
     // Create a connection guard that will release the connection when dropped
     // This ensures the connection is released even if the function panics
-    let _connection_guard = client_ip.map(|ip| ConnectionGuard {
-        app_state: app_state.clone(),
-        ip,
-    });
+    let _connection_guard = client_ip.map(|ip| ConnectionGuard::new(app_state.clone(), ip));
 
-    debug!("Splitting socket into sender and receiver");
-    // Split the socket into sender and receiver
-    let (mut sender, mut receiver) = socket.split();
-    debug!("Socket split completed");
+    // Heartbeat pings reap half-open connections long before TCP notices
+    let heartbeat = Arc::new(ConnectionHeartbeat::new(
+        app_state.config.ws_heartbeat,
+        Instant::now(),
+    ));
 
     // Connection state with RwLock for rare writes, frequent reads
     // Initialize with auth context for room name normalization
-    let state = Arc::new(RwLock::new(ConnectionState::with_auth(auth.clone())));
+    let mut connection_state = ConnectionState::with_auth(auth.clone());
+    connection_state.heartbeat = Some(heartbeat.clone());
+    // Buffered until the config message opts the session in (or not)
+    let flight_log = app_state
+        .flight_recorder
+        .as_ref()
+        .map(|recorder| recorder.begin());
+    connection_state.flight_log = flight_log.clone();
+    let state = Arc::new(RwLock::new(connection_state));
+
+    // Write the session's usage record at teardown, or from Drop if the
+    // handler panics or is cancelled before teardown finishes
+    let usage_guard = app_state.usage_recorder.clone().map(|recorder| UsageGuard {
+        recorder,
+        state: state.clone(),
+        sessions: app_state.session_store.clone(),
+        finished: false,
+    });
 
-    let (message_tx, mut message_rx) = mpsc::channel::<MessageRoute>(CHANNEL_BUFFER_SIZE);
+    let (message_tx, message_rx) = mpsc::channel::<MessageRoute>(CHANNEL_BUFFER_SIZE);
 
     // If authentication is pending, send AuthRequired notification immediately
     // This informs browser clients they need to send an auth message first
@@ -149,78 +219,26 @@ async fn handle_voice_socket(
         info!("Auth pending - sending auth_required notification");
         let auth_required_msg =
             serde_json::to_string(&OutgoingMessage::AuthRequired).unwrap_or_default();
-        if let Err(e) = sender.send(Message::Text(auth_required_msg.into())).await {
+        let frame = Message::Text(auth_required_msg.into());
+        if let Some(log) = &flight_log {
+            record_frame(log, &frame);
+        }
+        if let Err(e) = sender.send(frame).await {
             error!("Failed to send auth_required message: {}", e);
             return;
         }
     }
 
     // Create shutdown channel for graceful sender task termination
-    let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
     // Spawn task to handle outgoing messages - simple and direct for low latency
-    let sender_task = tokio::spawn(async move {
-        loop {
-            select! {
-                route_opt = message_rx.recv() => {
-                    let Some(route) = route_opt else {
-                        // Channel closed, exit gracefully
-                        break;
-                    };
-                    let should_close = matches!(route, MessageRoute::Close);
-
-                    let result = match route {
-                        MessageRoute::Outgoing(message) => {
-                            // Direct serialization and send - no batching for low latency
-                            match serde_json::to_string(&message) {
-                                Ok(json_str) => sender.send(Message::Text(json_str.into())).await,
-                                Err(e) => {
-                                    error!("Failed to serialize outgoing message: {}", e);
-                                    continue;
-                                }
-                            }
-                        }
-                        MessageRoute::Binary(data) => sender.send(Message::Binary(data)).await,
-                        MessageRoute::Close => {
-                            info!("Closing WebSocket connection");
-                            sender.send(Message::Close(None)).await
-                        }
-                    };
-
-                    if let Err(e) = result {
-                        error!("Failed to send WebSocket message: {}", e);
-                        break;
-                    }
-
-                    // If we sent a Close message, break the loop
-                    if should_close {
-                        break;
-                    }
-                }
-                _ = &mut shutdown_rx => {
-                    // Graceful shutdown requested - drain remaining messages
-                    while let Ok(route) = message_rx.try_recv() {
-                        let result = match route {
-                            MessageRoute::Outgoing(message) => {
-                                match serde_json::to_string(&message) {
-                                    Ok(json_str) => sender.send(Message::Text(json_str.into())).await,
-                                    Err(_) => continue,
-                                }
-                            }
-                            MessageRoute::Binary(data) => sender.send(Message::Binary(data)).await,
-                            MessageRoute::Close => sender.send(Message::Close(None)).await,
-                        };
-                        if result.is_err() {
-                            break;
-                        }
-                    }
-                    // Send close frame for clean WebSocket termination
-                    let _ = sender.send(Message::Close(None)).await;
-                    break;
-                }
-            }
-        }
-    });
+    let sender_task = tokio::spawn(forward_routes(
+        sender,
+        message_rx,
+        shutdown_rx,
+        flight_log.clone(),
+    ));
 
     // Timeout for checking idle connections
     // This determines how often we check if the connection is stale
@@ -243,11 +261,13 @@ async fn handle_voice_socket(
     loop {
         select! {
             msg_result = receiver.next() => {
-                // Update activity time on any message
-                last_activity = std::time::Instant::now();
-
                 match msg_result {
                     Some(Ok(msg)) => {
+                        // Heartbeat answers show the client is alive, not active
+                        if !is_heartbeat_answer(&msg) {
+                            last_activity = std::time::Instant::now();
+                        }
+
                         let continue_processing = process_message(
                             msg,
                             &state,
@@ -261,9 +281,11 @@ async fn handle_voice_socket(
                     }
                     Some(Err(e)) => {
                         warn!("WebSocket error: {}", e);
-                        let _ = message_tx.send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                            message: format!("WebSocket error: {e}"),
-                        })).await;
+                        close_connection(
+                            &message_tx,
+                            CloseReason::ProtocolViolation,
+                            format!("WebSocket error: {e}"),
+                        ).await;
                         break;
                     }
                     None => {
@@ -272,6 +294,40 @@ async fn handle_voice_socket(
                     }
                 }
             }
+            _ = heartbeat_due(&heartbeat) => {
+                match heartbeat.poll(Instant::now(), now_ms()) {
+                    HeartbeatAction::Ping { ts } => {
+                        // A full queue means the client is not reading; the ping is missed
+                        let _ = message_tx.try_send(heartbeat.ping_route(ts));
+                    }
+                    HeartbeatAction::Reap { missed } => {
+                        let stream_id = state.read().await.stream_id.clone();
+                        warn!(
+                            stream_id = ?stream_id,
+                            missed,
+                            "WebSocket client stopped answering heartbeat pings, closing connection"
+                        );
+                        global_metrics().inc_counter(
+                            "waav_ws_heartbeat_timeouts_total",
+                            "WebSocket connections closed for unanswered heartbeat pings",
+                            &[],
+                        );
+                        // The client is likely gone; do not wait on a full outbound queue
+                        let _ = tokio::time::timeout(
+                            HEARTBEAT_CLOSE_TIMEOUT,
+                            close_connection(
+                                &message_tx,
+                                CloseReason::HeartbeatTimeout,
+                                format!(
+                                    "No answer to {missed} consecutive heartbeat pings; reconnect with the same stream_id to resume"
+                                ),
+                            ),
+                        ).await;
+                        break;
+                    }
+                    HeartbeatAction::Wait => {}
+                }
+            }
             _ = tokio::time::sleep(processing_timeout) => {
                 // Check if connection has been idle too long
                 if last_activity.elapsed() > idle_timeout {
@@ -279,9 +335,11 @@ async fn handle_voice_socket(
                         "WebSocket connection idle for {}s, closing stale connection",
                         last_activity.elapsed().as_secs()
                     );
-                    let _ = message_tx.send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: "Connection closed due to inactivity".to_string(),
-                    })).await;
+                    close_connection(
+                        &message_tx,
+                        CloseReason::IdleTimeout,
+                        "Connection closed due to inactivity",
+                    ).await;
                     break;
                 }
                 debug!(
@@ -306,16 +364,22 @@ async fn handle_voice_socket(
     }
 
     // Snapshot state before cleanup so we can drop the read lock before awaiting
-    let (voice_manager, livekit_client, recording_egress_id, room_name) = {
+    let (session, livekit_client, recording_egress_id, room_name, stream_id) = {
         let state_guard = state.read().await;
         (
-            state_guard.voice_manager.clone(),
+            state_guard.session.clone(),
             state_guard.livekit_client.clone(),
             state_guard.recording_egress_id.clone(),
             state_guard.livekit_room_name.clone(),
+            state_guard.stream_id.clone(),
         )
     };
 
+    // Stop any in-flight LLM reply before tearing down TTS
+    if let Some(bridge) = session.as_ref().and_then(|s| s.agent_bridge()) {
+        bridge.cancel();
+    }
+
     // Disconnect LiveKit first to stop inbound audio before tearing down STT/TTS
     if let Some(livekit_client) = livekit_client {
         // Try to get write lock with timeout for cleanup
@@ -331,26 +395,84 @@ async fn handle_voice_socket(
         }
     }
 
-    // Now stop the voice manager after audio sources are quiet
-    if let Some(voice_manager) = voice_manager {
-        match voice_manager.stop().await {
+    // Export the transcript for enrichment before closing discards spilled entries
+    let mut enrichment_job = None;
+    if let (Some(session), Some(stream_id), Some(_)) =
+        (&session, &stream_id, &app_state.transcript_enrichment)
+        && let Some(activity) = session.speech_activity()
+    {
+        enrichment_job = Some(EnrichmentJob {
+            session_id: stream_id.clone(),
+            auth_id: state.read().await.auth.id.clone(),
+            transcript: session.transcript().await,
+            activity,
+            metadata: app_state
+                .session_store
+                .metadata(stream_id)
+                .unwrap_or_default(),
+        });
+    }
+
+    // Keep what the session export needs before closing the session
+    let mut finished_session = None;
+    if let (Some(session), Some(stream_id), Some(_)) =
+        (&session, &stream_id, &app_state.session_export)
+    {
+        let (auth_id, redactions) = {
+            let state = state.read().await;
+            (state.auth.id.clone(), state.redaction.spans(now_ms()))
+        };
+        finished_session = Some(FinishedSession {
+            session_id: stream_id.clone(),
+            auth_id,
+            started_at: session.usage().started_at,
+            transcript: session.transcript().await,
+            usage: None,
+            recorded: false,
+            redactions,
+            consent: session.consent(),
+            metadata: app_state
+                .session_store
+                .metadata(stream_id)
+                .unwrap_or_default(),
+        });
+    }
+
+    // Now close the voice session after audio sources are quiet
+    if let Some(session) = session {
+        match session.close().await {
             Ok(_) => {}
-            Err(e) => error!("Failed to stop voice manager: {}", e),
+            Err(e) => error!("Failed to close voice session: {}", e),
         }
     }
 
+    // No more caller audio arrives; upload the rest of its recording
+    if let Some(recording) = state.write().await.input_recording.take() {
+        recording.finish();
+    }
+
     // Stop recording if it was started
+    let mut recording_bytes = None;
     if let (Some(egress_id), Some(room_handler)) =
         (&recording_egress_id, &app_state.livekit_room_handler)
     {
         info!("Stopping recording with egress ID: {}", egress_id);
-        if let Err(e) = room_handler.stop_room_recording(egress_id).await {
-            error!("Failed to stop room recording: {:?}", e);
-        } else {
-            info!("Recording stopped successfully");
+        match room_handler.stop_room_recording(egress_id).await {
+            Ok(bytes) => {
+                info!("Recording stopped successfully");
+                recording_bytes = bytes;
+            }
+            Err(e) => error!("Failed to stop room recording: {:?}", e),
         }
     }
 
+    // Diarize the session in the background once the recording is final
+    if let (Some(job), Some(workers)) = (enrichment_job, &app_state.transcript_enrichment)
+        && let Err(e) = workers.submit(job)
+    {
+        warn!("Transcript enrichment skipped: {}", e);
+    }
+
     // Delete room if it exists
     if let (Some(room), Some(room_handler)) = (&room_name, &app_state.livekit_room_handler) {
         info!("Deleting LiveKit room: {}", room);
@@ -361,13 +483,140 @@ async fn handle_voice_socket(
         }
     }
 
+    // The session is closed and the recording stopped, so its usage is final
+    let usage = match usage_guard {
+        Some(usage_guard) => usage_guard.finish(recording_bytes).await,
+        None => None,
+    };
+
+    // Store the session's artifacts and announce them in the background
+    if let (Some(mut finished), Some(exporter)) = (finished_session, &app_state.session_export) {
+        finished.recorded = recording_bytes.is_some();
+        finished.usage = match usage {
+            Some(usage) => Some(usage),
+            None => usage_record(
+                &*state.read().await,
+                &app_state.session_store,
+                UsageTermination::Closed,
+                recording_bytes,
+            ),
+        };
+        exporter.export_detached(finished);
+    }
+
+    // Drop the session from the registry once all outbound sinks are done
+    if let Some(stream_id) = &stream_id {
+        app_state.session_store.remove(stream_id);
+        app_state.session_events.close(stream_id);
+    }
+
+    // Every message has been sent; store the session's final log
+    if let Some(log) = flight_log {
+        log.finish().await;
+    }
+
     info!("WebSocket voice connection terminated");
 }
 
+/// Encode and send routed messages until the channel closes or shutdown
+///
+/// Runs as the connection's sender task. On shutdown, messages still queued
+/// are drained before the close frame is sent. Text and close frames are
+/// recorded in the flight log, if the connection has one.
+///
+/// # Arguments
+/// * `sender` - The WebSocket sink
+/// * `message_rx` - Routed messages from the handlers
+/// * `shutdown_rx` - Signalled once the receive loop has ended
+/// * `flight_log` - The connection's flight log
+pub(super) async fn forward_routes<S>(
+    mut sender: S,
+    mut message_rx: mpsc::Receiver<MessageRoute>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+    flight_log: Option<Arc<FlightLog>>,
+) where
+    S: Sink<Message> + Unpin,
+    S::Error: std::fmt::Display,
+{
+    loop {
+        select! {
+            route_opt = message_rx.recv() => {
+                let Some(route) = route_opt else {
+                    // Channel closed, exit gracefully
+                    break;
+                };
+                let should_close = matches!(route, MessageRoute::Close(_));
+                if should_close {
+                    info!("Closing WebSocket connection");
+                }
+
+                // Direct serialization and send - no batching for low latency
+                let frame = match encode_route(route) {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        error!("Failed to serialize outgoing message: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Some(log) = &flight_log {
+                    record_frame(log, &frame);
+                }
+                if let Err(e) = sender.send(frame).await {
+                    error!("Failed to send WebSocket message: {}", e);
+                    break;
+                }
+
+                // If we sent a Close message, break the loop
+                if should_close {
+                    break;
+                }
+            }
+            _ = &mut shutdown_rx => {
+                // Graceful shutdown requested - drain remaining messages
+                while let Ok(route) = message_rx.try_recv() {
+                    let closing = matches!(route, MessageRoute::Close(_));
+                    let Ok(frame) = encode_route(route) else {
+                        continue;
+                    };
+                    if let Some(log) = &flight_log {
+                        record_frame(log, &frame);
+                    }
+                    if sender.send(frame).await.is_err() || closing {
+                        return;
+                    }
+                }
+                // Send close frame for clean WebSocket termination
+                let frame = CloseReason::Normal.close_message();
+                if let Some(log) = &flight_log {
+                    record_frame(log, &frame);
+                }
+                let _ = sender.send(frame).await;
+                break;
+            }
+        }
+    }
+}
+
+/// Record a text or close frame sent to the client in its flight log
+fn record_frame(log: &FlightLog, frame: &Message) {
+    match frame {
+        Message::Text(text) => {
+            let data = serde_json::from_str(text.as_str()).unwrap_or_else(|_| text.as_str().into());
+            log.record(EntryKind::Outbound, data);
+        }
+        Message::Close(Some(close)) => log.record(
+            EntryKind::Close,
+            serde_json::json!({"code": close.code, "reason": close.reason.as_str()}),
+        ),
+        _ => {}
+    }
+}
+
 /// Process incoming WebSocket message with optimizations
 ///
-/// Routes different message types to appropriate handlers and manages
-/// the connection lifecycle based on message processing results.
+/// Decodes the frame and routes it to the appropriate handler, managing
+/// the connection lifecycle based on the result.
 ///
 /// # Arguments
 /// * `msg` - The WebSocket message to process
@@ -383,96 +632,134 @@ async fn handle_voice_socket(
 /// - Fast JSON parsing with pre-validation
 /// - Zero-copy audio data handling where possible
 #[inline(always)]
-async fn process_message(
+pub(super) async fn process_message(
     msg: Message,
     state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
     app_state: &Arc<AppState>,
 ) -> bool {
-    match msg {
-        Message::Text(text) => {
-            debug!("Received text message: {} bytes", text.len());
-
-            // Pre-deserialization size check to prevent JSON parsing attacks
-            if text.len() > MAX_TEXT_MESSAGE_SIZE {
-                warn!(
-                    size = text.len(),
-                    max = MAX_TEXT_MESSAGE_SIZE,
-                    "Text message exceeds maximum size before deserialization"
-                );
-                let _ = message_tx
-                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: format!(
-                            "Message too large: {} bytes (max {} bytes)",
-                            text.len(),
-                            MAX_TEXT_MESSAGE_SIZE
-                        ),
-                    }))
-                    .await;
-                return true;
-            }
-
-            // Fast path JSON parsing with pre-validation
-            let incoming_msg: IncomingMessage = match serde_json::from_str(&text) {
-                Ok(msg) => msg,
-                Err(e) => {
-                    error!("Failed to parse incoming message: {}", e);
-                    let _ = message_tx
-                        .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                            message: format!("Invalid message format: {e}"),
-                        }))
-                        .await;
-                    return true;
-                }
-            };
-
-            // Validate message field sizes to prevent resource exhaustion
-            if let Err(e) = incoming_msg.validate_size() {
-                warn!("Message validation failed: {}", e);
-                let _ = message_tx
-                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: e.to_string(),
-                    }))
-                    .await;
-                return true;
-            }
+    // Record the client's messages before they are handled
+    if let Message::Text(text) = &msg
+        && let Some(log) = &state.read().await.flight_log
+    {
+        let data = serde_json::from_str(text.as_str())
+            .unwrap_or_else(|_| serde_json::json!({ "invalid_json_bytes": text.len() }));
+        log.record(EntryKind::Inbound, data);
+    }
 
+    match decode_frame(msg, app_state.config.strict_config) {
+        InboundFrame::Control(incoming_msg) => {
             handle_incoming_message(incoming_msg, state, message_tx, app_state).await
         }
-        Message::Binary(data) => {
-            debug!("Received binary message: {} bytes", data.len());
+        InboundFrame::Binary(data) => {
+            // Binary frames belong to a pending play_audio transfer if one is open
+            if state.read().await.is_play_audio_pending() {
+                return handle_play_audio_frame(data, state, message_tx).await;
+            }
 
             // Handle binary audio data with zero-copy optimization
-            handle_audio_message(data, state, message_tx).await
+            handle_audio_message(data, state, message_tx, &app_state.session_events).await
         }
-        Message::Ping(_data) => {
-            debug!("Received ping message");
-            // Ping/Pong is handled automatically by axum
+        InboundFrame::Rejected(error) => {
+            let _ = message_tx.send(MessageRoute::Outgoing(error)).await;
             true
         }
-        Message::Pong(_) => {
-            debug!("Received pong message");
+        InboundFrame::Pong(payload) => {
+            if let Some(heartbeat) = &state.read().await.heartbeat {
+                heartbeat.on_pong_frame(&payload, Instant::now());
+            }
             true
         }
-        Message::Close(_) => {
-            info!("WebSocket connection closed by client");
-            false
-        }
+        // Pings are answered automatically by axum
+        InboundFrame::Ignored => true,
+        InboundFrame::Close => false,
     }
 }
 
-/// Guard struct that releases a connection slot when dropped
+/// Resolve when the connection's heartbeat is due (never when disabled)
+async fn heartbeat_due(heartbeat: &ConnectionHeartbeat) {
+    match heartbeat.next_deadline() {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Current time in milliseconds since epoch, used to stamp heartbeat pings
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Guard that writes the connection's usage record
 ///
-/// This implements RAII pattern to ensure connection slots are always released,
-/// even if the WebSocket handler panics or encounters errors.
-struct ConnectionGuard {
-    app_state: Arc<AppState>,
-    ip: IpAddr,
+/// Normal teardown calls `finish` once the session is closed and recording
+/// stopped. If the handler panics or its task is cancelled first, `Drop`
+/// writes the record with `termination: aborted` instead, so every session
+/// is accounted for.
+struct UsageGuard {
+    recorder: Arc<UsageRecorder>,
+    state: Arc<RwLock<ConnectionState>>,
+    /// Registry the session's metadata is read from
+    sessions: Arc<SessionStore>,
+    finished: bool,
+}
+
+impl UsageGuard {
+    /// Write the record, returning it for the session export
+    async fn finish(mut self, recording_bytes: Option<u64>) -> Option<UsageRecord> {
+        self.finished = true;
+        let record = usage_record(
+            &*self.state.read().await,
+            &self.sessions,
+            UsageTermination::Closed,
+            recording_bytes,
+        )?;
+        // Sink failures are logged by the recorder
+        let _ = self.recorder.record(&record).await;
+        Some(record)
+    }
 }
 
-impl Drop for ConnectionGuard {
+impl Drop for UsageGuard {
     fn drop(&mut self) {
-        debug!(ip = %self.ip, "Releasing connection slot");
-        self.app_state.release_connection(self.ip);
+        if self.finished {
+            return;
+        }
+        let Ok(state) = self.state.try_read() else {
+            warn!("Connection state locked during aborted teardown - usage record not written");
+            return;
+        };
+        if let Some(record) = usage_record(&state, &self.sessions, UsageTermination::Aborted, None)
+        {
+            warn!(session_id = %record.session_id, "Writing usage record for aborted session");
+            self.recorder.record_detached(record);
+        }
     }
 }
+
+/// Build the usage record of the connection's session, if one was configured
+///
+/// Called before the session is removed from `sessions`, so the record
+/// carries the session's final metadata.
+fn usage_record(
+    state: &ConnectionState,
+    sessions: &SessionStore,
+    termination: UsageTermination,
+    recording_bytes: Option<u64>,
+) -> Option<UsageRecord> {
+    let session = state.session.as_ref()?;
+    let session_id = state.stream_id.clone()?;
+    let metadata = sessions.metadata(&session_id).unwrap_or_default();
+    Some(
+        UsageRecord::new(
+            session_id,
+            state.auth.id.clone(),
+            &session.usage(),
+            termination,
+            recording_bytes,
+        )
+        .with_metadata(metadata),
+    )
+}
@@ -0,0 +1,408 @@
+//! Connection heartbeat
+//!
+//! The handler pings the client every `interval_ms` and measures the round
+//! trip of each answer. A ping that is not answered within `timeout_ms` is
+//! missed; after `max_missed` consecutive misses the connection is reaped
+//! with [`CloseReason::HeartbeatTimeout`](crate::handlers::close::CloseReason).
+//!
+//! Pings are WebSocket ping frames by default, which every WebSocket client
+//! answers on its own. Clients that want to see the heartbeat (browsers
+//! cannot observe ping frames) configure `heartbeat: "json"` and answer
+//! `{"type": "ping", "ts": ...}` with `{"type": "pong", "ts": ...}`.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::config::WsHeartbeatConfig;
+use crate::core::session::AudioDirection;
+
+use super::messages::{MessageRoute, OutgoingMessage};
+
+/// Interval of `audio_level` messages, which pacing never shortens
+const AUDIO_LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How the server pings the client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum HeartbeatMode {
+    /// WebSocket ping frames, answered by the client's WebSocket stack
+    #[default]
+    Frame,
+    /// `ping` messages the client answers with `pong` messages
+    Json,
+}
+
+impl HeartbeatMode {
+    /// Name used in config messages
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Frame => "frame",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// What the handler does when the heartbeat is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeartbeatAction {
+    /// Nothing is due yet
+    Wait,
+    /// Send a ping stamped with `ts` (milliseconds since epoch)
+    Ping { ts: u64 },
+    /// Close the connection: `missed` consecutive pings went unanswered
+    Reap { missed: u32 },
+}
+
+/// Heartbeat counters and round-trip times of a connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeartbeatStats {
+    /// Round-trip time of the last answered ping
+    pub last_rtt_ms: Option<u64>,
+    /// Smoothed round-trip time (1/8 weight per sample, as TCP's SRTT)
+    pub smoothed_rtt_ms: Option<u64>,
+    /// Pings sent
+    pub pings_sent: u64,
+    /// Pings answered in time
+    pub pongs_received: u64,
+    /// Pings that went unanswered
+    pub missed_pongs: u64,
+}
+
+/// Heartbeat state of one WebSocket connection
+///
+/// Shared between the receive loop, which polls it, the message handlers,
+/// which pass it the client's pongs, and the session event forwarder, which
+/// paces `audio_level` messages by the measured round trip.
+pub struct ConnectionHeartbeat {
+    config: WsHeartbeatConfig,
+    state: Mutex<HeartbeatState>,
+}
+
+struct HeartbeatState {
+    mode: HeartbeatMode,
+    next_ping_at: Instant,
+    /// Timestamp and send time of the unanswered ping
+    outstanding: Option<(u64, Instant)>,
+    /// Consecutive missed pings
+    missed: u32,
+    smoothed_rtt: Option<Duration>,
+    stats: HeartbeatStats,
+    /// When the last `audio_level` message of each direction was sent
+    last_level: [Option<Instant>; 2],
+}
+
+impl ConnectionHeartbeat {
+    /// Create the heartbeat of a connection opened at `now`
+    pub fn new(config: WsHeartbeatConfig, now: Instant) -> Self {
+        Self {
+            config,
+            state: Mutex::new(HeartbeatState {
+                mode: HeartbeatMode::default(),
+                next_ping_at: now + Duration::from_millis(config.interval_ms),
+                outstanding: None,
+                missed: 0,
+                smoothed_rtt: None,
+                stats: HeartbeatStats::default(),
+                last_level: [None; 2],
+            }),
+        }
+    }
+
+    /// How pings are sent
+    pub fn mode(&self) -> HeartbeatMode {
+        self.state.lock().mode
+    }
+
+    /// Switch how pings are sent; an outstanding ping is still accepted
+    pub fn set_mode(&self, mode: HeartbeatMode) {
+        self.state.lock().mode = mode;
+    }
+
+    /// When [`poll`](Self::poll) has something to do, or None when disabled
+    pub fn next_deadline(&self) -> Option<Instant> {
+        if !self.config.enabled {
+            return None;
+        }
+        let state = self.state.lock();
+        Some(match state.outstanding {
+            Some((_, sent_at)) => sent_at + Duration::from_millis(self.config.timeout_ms),
+            None => state.next_ping_at,
+        })
+    }
+
+    /// Expire the outstanding ping and send the next one when they are due
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    /// * `ts` - Timestamp for a new ping (milliseconds since epoch)
+    pub fn poll(&self, now: Instant, ts: u64) -> HeartbeatAction {
+        if !self.config.enabled {
+            return HeartbeatAction::Wait;
+        }
+        let mut state = self.state.lock();
+
+        if let Some((_, sent_at)) = state.outstanding
+            && now >= sent_at + Duration::from_millis(self.config.timeout_ms)
+        {
+            state.outstanding = None;
+            state.missed += 1;
+            state.stats.missed_pongs += 1;
+            debug!(missed = state.missed, "Heartbeat ping unanswered");
+            if state.missed >= self.config.max_missed {
+                return HeartbeatAction::Reap {
+                    missed: state.missed,
+                };
+            }
+        }
+
+        if state.outstanding.is_none() && now >= state.next_ping_at {
+            state.outstanding = Some((ts, now));
+            state.next_ping_at = now + Duration::from_millis(self.config.interval_ms);
+            state.stats.pings_sent += 1;
+            return HeartbeatAction::Ping { ts };
+        }
+
+        HeartbeatAction::Wait
+    }
+
+    /// The message carrying the ping stamped `ts`, in the connection's mode
+    pub fn ping_route(&self, ts: u64) -> MessageRoute {
+        let state = self.state.lock();
+        match state.mode {
+            HeartbeatMode::Frame => MessageRoute::Ping(Bytes::copy_from_slice(&ts.to_be_bytes())),
+            HeartbeatMode::Json => MessageRoute::Outgoing(OutgoingMessage::Ping {
+                ts,
+                rtt_ms: state.stats.smoothed_rtt_ms,
+            }),
+        }
+    }
+
+    /// Record a `pong` message echoing `ts`
+    ///
+    /// # Returns
+    /// * `bool` - Whether it answered the outstanding ping
+    pub fn on_pong(&self, ts: u64, now: Instant) -> bool {
+        let mut state = self.state.lock();
+        let Some((expected, sent_at)) = state.outstanding else {
+            return false;
+        };
+        if ts != expected {
+            return false;
+        }
+
+        let rtt = now.saturating_duration_since(sent_at);
+        let smoothed = match state.smoothed_rtt {
+            Some(smoothed) if rtt > smoothed => smoothed + (rtt - smoothed) / 8,
+            Some(smoothed) => smoothed - (smoothed - rtt) / 8,
+            None => rtt,
+        };
+        state.outstanding = None;
+        state.missed = 0;
+        state.smoothed_rtt = Some(smoothed);
+        state.stats.pongs_received += 1;
+        state.stats.last_rtt_ms = Some(rtt.as_millis() as u64);
+        state.stats.smoothed_rtt_ms = Some(smoothed.as_millis() as u64);
+        true
+    }
+
+    /// Record a WebSocket pong frame
+    ///
+    /// Pongs that do not carry one of our 8-byte timestamps (e.g. unsolicited
+    /// pongs) are ignored.
+    pub fn on_pong_frame(&self, payload: &[u8], now: Instant) -> bool {
+        match <[u8; 8]>::try_from(payload) {
+            Ok(ts) => self.on_pong(u64::from_be_bytes(ts), now),
+            Err(_) => false,
+        }
+    }
+
+    /// Smoothed round-trip time, once a ping was answered
+    pub fn rtt(&self) -> Option<Duration> {
+        self.state.lock().smoothed_rtt
+    }
+
+    /// Counters and round-trip times so far
+    pub fn stats(&self) -> HeartbeatStats {
+        self.state.lock().stats
+    }
+
+    /// Whether an `audio_level` message for `direction` should be sent now
+    ///
+    /// Levels are sent at their normal interval while the round trip is
+    /// shorter. On slower links at most one level per direction is sent per
+    /// round trip, so meters do not crowd out transcripts and audio.
+    pub fn pace_audio_level(&self, direction: AudioDirection, now: Instant) -> bool {
+        let mut state = self.state.lock();
+        let Some(rtt) = state.smoothed_rtt.filter(|rtt| *rtt > AUDIO_LEVEL_INTERVAL) else {
+            return true;
+        };
+        let slot = match direction {
+            AudioDirection::In => 0,
+            AudioDirection::Out => 1,
+        };
+        if let Some(last) = state.last_level[slot]
+            && now.saturating_duration_since(last) < rtt
+        {
+            return false;
+        }
+        state.last_level[slot] = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WsHeartbeatConfig {
+        WsHeartbeatConfig {
+            enabled: true,
+            interval_ms: 1_000,
+            timeout_ms: 500,
+            max_missed: 2,
+        }
+    }
+
+    #[test]
+    fn test_pings_at_interval_and_measures_rtt() {
+        let start = Instant::now();
+        let heartbeat = ConnectionHeartbeat::new(config(), start);
+        assert_eq!(heartbeat.poll(start, 1), HeartbeatAction::Wait);
+        assert_eq!(
+            heartbeat.next_deadline(),
+            Some(start + Duration::from_secs(1))
+        );
+
+        let sent = start + Duration::from_secs(1);
+        assert_eq!(heartbeat.poll(sent, 42), HeartbeatAction::Ping { ts: 42 });
+        assert_eq!(
+            heartbeat.next_deadline(),
+            Some(sent + Duration::from_millis(500))
+        );
+
+        // Only the outstanding ping's timestamp is accepted
+        assert!(!heartbeat.on_pong(41, sent + Duration::from_millis(80)));
+        assert!(heartbeat.on_pong(42, sent + Duration::from_millis(80)));
+        assert_eq!(heartbeat.rtt(), Some(Duration::from_millis(80)));
+
+        let stats = heartbeat.stats();
+        assert_eq!(stats.pings_sent, 1);
+        assert_eq!(stats.pongs_received, 1);
+        assert_eq!(stats.last_rtt_ms, Some(80));
+        assert_eq!(stats.smoothed_rtt_ms, Some(80));
+
+        // The next sample moves the smoothed RTT by an eighth of the difference
+        let sent = start + Duration::from_secs(2);
+        assert_eq!(heartbeat.poll(sent, 43), HeartbeatAction::Ping { ts: 43 });
+        assert!(heartbeat.on_pong_frame(&43u64.to_be_bytes(), sent + Duration::from_millis(160)));
+        assert_eq!(heartbeat.stats().last_rtt_ms, Some(160));
+        assert_eq!(heartbeat.stats().smoothed_rtt_ms, Some(90));
+    }
+
+    #[test]
+    fn test_reaps_after_consecutive_misses() {
+        let start = Instant::now();
+        let heartbeat = ConnectionHeartbeat::new(config(), start);
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(
+            heartbeat.poll(at(1_000), 1),
+            HeartbeatAction::Ping { ts: 1 }
+        );
+        // The first miss alone does not reap; the next ping goes out on time
+        assert_eq!(heartbeat.poll(at(1_500), 2), HeartbeatAction::Wait);
+        assert_eq!(
+            heartbeat.poll(at(2_000), 2),
+            HeartbeatAction::Ping { ts: 2 }
+        );
+
+        // An answer resets the count of consecutive misses
+        assert!(heartbeat.on_pong(2, at(2_100)));
+        assert_eq!(
+            heartbeat.poll(at(3_000), 3),
+            HeartbeatAction::Ping { ts: 3 }
+        );
+        assert_eq!(heartbeat.poll(at(3_500), 4), HeartbeatAction::Wait);
+        assert_eq!(
+            heartbeat.poll(at(4_000), 4),
+            HeartbeatAction::Ping { ts: 4 }
+        );
+
+        // A late answer to an expired ping does not count
+        assert!(!heartbeat.on_pong(3, at(4_100)));
+        assert_eq!(
+            heartbeat.poll(at(4_500), 5),
+            HeartbeatAction::Reap { missed: 2 }
+        );
+        assert_eq!(heartbeat.stats().missed_pongs, 3);
+    }
+
+    #[test]
+    fn test_disabled_heartbeat_never_pings() {
+        let start = Instant::now();
+        let heartbeat = ConnectionHeartbeat::new(
+            WsHeartbeatConfig {
+                enabled: false,
+                ..config()
+            },
+            start,
+        );
+        assert_eq!(heartbeat.next_deadline(), None);
+        assert_eq!(
+            heartbeat.poll(start + Duration::from_secs(60), 1),
+            HeartbeatAction::Wait
+        );
+    }
+
+    #[test]
+    fn test_ping_route_follows_mode() {
+        let start = Instant::now();
+        let heartbeat = ConnectionHeartbeat::new(config(), start);
+        assert_eq!(heartbeat.mode(), HeartbeatMode::Frame);
+        match heartbeat.ping_route(7) {
+            MessageRoute::Ping(payload) => assert_eq!(payload.as_ref(), &7u64.to_be_bytes()),
+            _ => panic!("expected a ping frame"),
+        }
+
+        heartbeat.set_mode(HeartbeatMode::Json);
+        match heartbeat.ping_route(7) {
+            MessageRoute::Outgoing(OutgoingMessage::Ping { ts, rtt_ms }) => {
+                assert_eq!(ts, 7);
+                assert_eq!(rtt_ms, None);
+            }
+            _ => panic!("expected a ping message"),
+        }
+
+        // Pong frames that are not our timestamps are ignored
+        assert!(!heartbeat.on_pong_frame(b"hello", start));
+    }
+
+    #[test]
+    fn test_audio_levels_paced_by_rtt() {
+        let start = Instant::now();
+        let heartbeat = ConnectionHeartbeat::new(config(), start);
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // Without a measurement or on a fast link every level is sent
+        assert!(heartbeat.pace_audio_level(AudioDirection::In, start));
+        assert!(heartbeat.pace_audio_level(AudioDirection::In, start));
+
+        // At 400ms RTT one level per direction is sent per round trip
+        assert_eq!(
+            heartbeat.poll(at(1_000), 1),
+            HeartbeatAction::Ping { ts: 1 }
+        );
+        assert!(heartbeat.on_pong(1, at(1_400)));
+        assert!(heartbeat.pace_audio_level(AudioDirection::In, at(1_400)));
+        assert!(heartbeat.pace_audio_level(AudioDirection::Out, at(1_400)));
+        assert!(!heartbeat.pace_audio_level(AudioDirection::In, at(1_500)));
+        assert!(!heartbeat.pace_audio_level(AudioDirection::In, at(1_700)));
+        assert!(heartbeat.pace_audio_level(AudioDirection::In, at(1_800)));
+    }
+}
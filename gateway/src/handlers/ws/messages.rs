@@ -27,6 +27,34 @@ pub const MAX_STREAM_ID_SIZE: usize = 256;
 /// JWTs and API keys should not exceed this
 pub const MAX_AUTH_TOKEN_SIZE: usize = 4 * 1024;
 
+/// Maximum allowed size for a client-provided turn_id (128 bytes)
+pub const MAX_TURN_ID_SIZE: usize = 128;
+
+use crate::agents::MAX_AGENT_NAME_LENGTH;
+use crate::config::{FeatureFlags, GreetingConfig};
+use crate::core::agent_bridge::AgentBridgeConfig;
+use crate::core::redaction::RedactionSource;
+use crate::core::session::{
+    AudioDirection, BargeInSuppressionReason, ConsentSource, ConsentState, EffectiveSessionConfig,
+    LatencyStage, WatchdogStage,
+};
+use crate::core::stt::{RedactedSpan, TranscriptTiming, UtteranceKind};
+use crate::core::tts::TTSTimingEvent;
+use crate::core::validation::{ConfigIssue, config_issues_message};
+use crate::core::voice_manager::{
+    AudioQualityIssue, SpeakPriority, TTSQueuePolicy, TurnDetectionDegradedReason,
+};
+use crate::errors::provider_error::ErrorSanitizer;
+use crate::handlers::close::CloseReason;
+use crate::handlers::strict_config::{UnknownField, collect_unknown_fields, unknown_keys};
+use crate::state::{
+    CallEndReason, CallState, SessionMetadata, SessionMetadataError, validate_session_metadata,
+};
+
+use super::heartbeat::HeartbeatMode;
+use super::play_audio::{MAX_PLAY_AUDIO_INLINE_SIZE, MAX_PLAY_AUDIO_SIZE};
+use super::redaction::RedactionState;
+
 use super::config::{
     DAGWebSocketConfig, LiveKitWebSocketConfig, STTWebSocketConfig, TTSWebSocketConfig,
     default_allow_interruption, default_audio_enabled,
@@ -73,6 +101,56 @@ pub enum IncomingMessage {
         /// When configured, audio flows through the DAG instead of direct STT→TTS
         #[serde(skip_serializing_if = "Option::is_none")]
         dag_config: Option<DAGWebSocketConfig>,
+        /// Optional agent bridge configuration (requires audio=true).
+        /// When set, each completed user turn is sent to an OpenAI-compatible
+        /// chat completions endpoint and the streamed reply is spoken via TTS.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        agent_config: Option<AgentBridgeConfig>,
+        /// Optional client metadata attached to the session (string values only).
+        /// Forwarded to outbound webhooks and stored as S3 object metadata/tags
+        /// on recordings. Limited to 10 entries and 2 KB in total.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+        metadata: Option<SessionMetadata>,
+        /// Optional greeting spoken when the session is ready (requires audio=true).
+        /// Overrides the server default greeting; an empty greeting disables it.
+        /// Not replayed when a client resumes a session with the same stream_id.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        greeting: Option<GreetingConfig>,
+        /// Send `audio_level` messages with caller and TTS audio levels about
+        /// every 100ms, for level meters (default: false)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        audio_levels: Option<bool>,
+        /// How the server sends heartbeat pings: "frame" (WebSocket ping
+        /// frames, the default) or "json" (`ping` messages answered with `pong`)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        heartbeat: Option<HeartbeatMode>,
+        /// Reject this message if it contains fields the server does not know,
+        /// instead of ignoring them (default: the server's `strict_config`)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        strict_config: Option<bool>,
+        /// Optional agent profile to configure the session from.
+        /// The profile supplies every other field; only `stream_id`, `metadata`,
+        /// `audio_levels`, `heartbeat`, `strict_config`, `overrides`, `dry_run`
+        /// and `flight_recorder` may be sent alongside it.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "openapi", schema(example = "support-bot-en"))]
+        agent: Option<String>,
+        /// Field-level overrides of the agent profile, applied as a JSON merge
+        /// patch (e.g. `{"tts_config": {"voice_id": "..."}}`). Each field must
+        /// be listed as overridable by the profile.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+        overrides: Option<serde_json::Map<String, serde_json::Value>>,
+        /// Validate the config and reply with a `dry_run` message describing
+        /// the session it would start, without connecting any provider or
+        /// starting the session (default: false)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dry_run: Option<bool>,
+        /// Keep a flight log of the session for `session replay`, when the
+        /// server's flight recorder is configured (default: false)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        flight_recorder: Option<bool>,
     },
     #[serde(rename = "speak")]
     Speak {
@@ -87,9 +165,64 @@ pub enum IncomingMessage {
             skip_serializing_if = "Option::is_none"
         )]
         allow_interruption: Option<bool>,
+        /// Turn this speech answers, echoed on its TTS messages and errors.
+        /// Defaults to the turn of the latest user transcript.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+        /// Priority of the speech (default: "normal"). "high" is exempt from
+        /// the pending TTS queue cap; "system" pre-empts the current speech,
+        /// cannot be interrupted and resumes the paused speech afterwards.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        priority: Option<SpeakPriority>,
     },
     #[serde(rename = "clear")]
     Clear,
+    /// Announce what the caller is expected to say in their next turn.
+    ///
+    /// With `routing` in the STT config, confirmation and digit turns are
+    /// transcribed by the fast provider and freeform turns by the configured
+    /// one. A hint sent while the caller is speaking applies to the next turn.
+    ///
+    /// # Example
+    /// ```json
+    /// {"type": "expect", "kind": "digits"}
+    /// ```
+    #[serde(rename = "expect")]
+    Expect {
+        /// "confirmation", "digits" or "freeform"
+        kind: UtteranceKind,
+    },
+    /// Play pre-synthesized audio through the TTS output path.
+    ///
+    /// Audio is either sent inline as base64 in `audio` (small clips) or as
+    /// binary frames immediately following this message, totalling `size` bytes.
+    /// While a transfer is pending, binary frames are not forwarded to STT.
+    ///
+    /// # Example
+    /// ```json
+    /// {"type": "play_audio", "format": "wav", "sample_rate": 16000, "size": 32044}
+    /// ```
+    #[serde(rename = "play_audio")]
+    PlayAudio {
+        /// Audio format: "wav" (16-bit mono PCM) or "linear16"/"pcm" (raw 16-bit mono PCM)
+        #[cfg_attr(feature = "openapi", schema(example = "wav"))]
+        format: String,
+        /// Sample rate of the audio in Hz. Must match the session's TTS sample rate.
+        #[cfg_attr(feature = "openapi", schema(example = 16000))]
+        sample_rate: u32,
+        /// Base64-encoded audio for small clips (max 512 KB decoded)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        audio: Option<String>,
+        /// Total size in bytes of the binary frames that follow (max 5 MB)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        size: Option<usize>,
+        /// Allow this audio to be interrupted
+        #[serde(
+            default = "default_allow_interruption",
+            skip_serializing_if = "Option::is_none"
+        )]
+        allow_interruption: Option<bool>,
+    },
     #[serde(rename = "send_message")]
     SendMessage {
         /// Message content
@@ -147,6 +280,48 @@ pub enum IncomingMessage {
         #[cfg_attr(feature = "openapi", schema(example = "sk_test_my_api_key_123456"))]
         token: String,
     },
+    /// Answer to a heartbeat `ping` message, echoing its `ts`.
+    ///
+    /// # Example
+    /// ```json
+    /// {"type": "pong", "ts": 1700000000000}
+    /// ```
+    #[serde(rename = "pong")]
+    Pong {
+        /// Timestamp of the ping being answered
+        ts: u64,
+    },
+    /// Open or close a recording redaction window.
+    ///
+    /// While a window is open the session's caller recording and monitor
+    /// audio hold silence, and the window is listed in the session's exports.
+    /// The server answers with a `redaction` message.
+    ///
+    /// # Example
+    /// ```json
+    /// {"type": "redaction", "state": "start"}
+    /// ```
+    #[serde(rename = "redaction")]
+    Redaction {
+        /// "start" or "stop"
+        state: RedactionState,
+    },
+    /// Record the caller's answer to the recording consent prompt.
+    ///
+    /// Only for sessions configured with `stt_config.consent`; the caller's
+    /// audio is held from STT until consent is granted. The first decision
+    /// counts, whether it came from this message or a DTMF digit. The server
+    /// answers with a `consent` message.
+    ///
+    /// # Example
+    /// ```json
+    /// {"type": "consent", "granted": true}
+    /// ```
+    #[serde(rename = "consent")]
+    Consent {
+        /// Whether the caller consented
+        granted: bool,
+    },
     /// Custom message type for plugin-defined message handlers.
     ///
     /// This variant allows plugins to define and handle their own message types
@@ -231,6 +406,29 @@ pub enum OutgoingMessage {
         /// Optional display name of the AI agent participant
         #[serde(skip_serializing_if = "Option::is_none")]
         waav_participant_name: Option<String>,
+        /// Feature flags resolved for this session (omitted when none are configured)
+        #[serde(skip_serializing_if = "FeatureFlags::is_empty")]
+        feature_flags: FeatureFlags,
+    },
+    /// Reply to a config message sent with `dry_run: true`
+    ///
+    /// Nothing was started; the client may send another config.
+    #[serde(rename = "dry_run")]
+    DryRun {
+        /// Stream ID the session would use
+        stream_id: String,
+        /// Effective configuration of the voice session, with API keys
+        /// redacted (omitted when audio is disabled)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+        config: Option<EffectiveSessionConfig>,
+        /// Feature flags resolved for the session
+        feature_flags: FeatureFlags,
+        /// Session metadata, including a SIP language pack chosen for the call
+        #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+        metadata: SessionMetadata,
+        /// Settings that were accepted but will not take effect as sent
+        warnings: Vec<String>,
     },
     #[serde(rename = "stt_result")]
     STTResult {
@@ -242,6 +440,33 @@ pub enum OutgoingMessage {
         is_speech_final: bool,
         /// Confidence score (0.0 to 1.0)
         confidence: f32,
+        /// Spans of `transcript` the provider redacted (omitted when none)
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        redactions: Vec<RedactedSpan>,
+        /// Position of the transcript on the session timeline, with wall-clock
+        /// (UTC) times; keeps increasing across STT provider reconnects
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timing: Option<TranscriptTiming>,
+        /// Server-generated ID of the user turn, shared by every transcript
+        /// up to and including the one with `is_speech_final`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+    },
+    /// The STT provider detected the start of user speech
+    ///
+    /// Sent ahead of the first transcript for the speech.
+    #[serde(rename = "speech_started")]
+    SpeechStarted {
+        /// Offset into the audio stream in seconds, when reported
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timestamp: Option<f64>,
+    },
+    /// The STT provider detected the end of a user utterance
+    #[serde(rename = "utterance_end")]
+    UtteranceEnd {
+        /// End of the last recognized word in seconds, when reported
+        #[serde(skip_serializing_if = "Option::is_none")]
+        last_word_end: Option<f64>,
     },
     #[serde(rename = "message")]
     Message {
@@ -253,17 +478,315 @@ pub enum OutgoingMessage {
         /// Information about the participant who disconnected
         participant: ParticipantDisconnectedInfo,
     },
+    /// Outbound call progress
+    ///
+    /// Sent for sessions configured with `livekit.outbound_call` each time
+    /// the call reaches a new state.
+    #[serde(rename = "call_state")]
+    CallState {
+        /// "dialing", "early_media", "answered" or "ended"
+        state: String,
+        /// Why the call ended, with `state: "ended"`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<CallEndReason>,
+        /// Timestamp of the change (milliseconds since epoch)
+        timestamp: u64,
+    },
+    /// Recording redaction window opened or closed
+    ///
+    /// Sent when the client's `redaction` message or DTMF digits open a
+    /// window, and when it closes.
+    #[serde(rename = "redaction")]
+    Redaction {
+        /// "start" or "stop"
+        state: RedactionState,
+        /// What opened or closed the window: "client" or "dtmf"
+        source: RedactionSource,
+        /// Timestamp of the change (milliseconds since epoch)
+        timestamp: u64,
+    },
+    /// Greeting playback notification
+    ///
+    /// Sent once per session when the greeting has been queued for playback.
+    #[serde(rename = "greeting.played")]
+    GreetingPlayed {
+        /// What was played ("text" or "asset")
+        source: String,
+        /// Asset name when an asset was played
+        #[serde(skip_serializing_if = "Option::is_none")]
+        asset: Option<String>,
+        /// Timestamp when the greeting was queued (milliseconds since epoch)
+        timestamp: u64,
+    },
+    /// TTS voice fallback notification
+    ///
+    /// Sent once when the configured voice does not exist and the server's
+    /// fallback voice for the provider is used for the rest of the session.
+    #[serde(rename = "tts.voice_fallback")]
+    VoiceFallback {
+        /// Configured voice id that was not found
+        from_voice_id: String,
+        /// Fallback voice id now in use
+        to_voice_id: String,
+        /// Turn of the speech being synthesized when the voice was switched
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+    },
+    /// TTS queue limit notification
+    ///
+    /// Sent when a `speak` command finds the session's pending TTS queue full.
+    /// With the `reject` policy `text` is the rejected speak text; with
+    /// `drop_oldest` it is the oldest pending utterance, which was dropped.
+    #[serde(rename = "tts.queue_full")]
+    TTSQueueFull {
+        /// Policy that was applied ("reject" or "drop_oldest")
+        policy: TTSQueuePolicy,
+        /// Maximum number of pending utterances per session
+        max_pending: usize,
+        /// Text that was rejected or dropped
+        text: String,
+    },
+    /// System announcement pre-emption started
+    ///
+    /// Sent when a `speak` with `priority: "system"` stopped the current
+    /// speech. The interrupted and queued speech is paused until the
+    /// announcement has played; `tts.preemption_ended` follows.
+    #[serde(rename = "tts.preemption_started")]
+    TTSPreemptionStarted {
+        /// Text of the announcement
+        text: String,
+        /// Number of utterances paused
+        paused: usize,
+    },
+    /// System announcement pre-emption ended
+    ///
+    /// Sent once the announcement has played and the paused speech was sent
+    /// to the TTS provider again. The interrupted utterance restarts from its
+    /// beginning.
+    #[serde(rename = "tts.preemption_ended")]
+    TTSPreemptionEnded {
+        /// Number of utterances resumed
+        resumed: usize,
+    },
+    /// TTS audio quality warning
+    ///
+    /// Sent when the provider's audio for an utterance was clipped, mostly
+    /// digital silence, or below the RMS floor. Only PCM output is checked.
+    /// With `retrying` the utterance is synthesized once more; its audio
+    /// follows the audio that failed the check.
+    #[serde(rename = "tts.audio_quality_warning")]
+    TTSAudioQualityWarning {
+        /// Thresholds the utterance failed ("clipped", "silent", "low_level")
+        issues: Vec<AudioQualityIssue>,
+        /// Fraction of samples at full scale
+        clipped_fraction: f32,
+        /// Fraction of samples that are exactly zero
+        zero_fraction: f32,
+        /// RMS level of the utterance in dBFS (-100 for silence)
+        rms_dbfs: f32,
+        /// Length of the checked audio in milliseconds
+        duration_ms: u64,
+        /// Text of the utterance
+        text: String,
+        /// Whether the utterance is being synthesized once more
+        retrying: bool,
+        /// Whether the checked audio was itself a retry
+        retried: bool,
+        /// Turn of the checked utterance
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+    },
+    /// Turn detection degraded
+    ///
+    /// Sent once per session when the end of the user's turn is no longer
+    /// decided by the turn detection model: at session start when the model
+    /// failed to load, or when an inference took longer than the latency
+    /// budget. From then on `speech_final` is sent after the caller's audio
+    /// stays below an energy threshold for a short silence.
+    #[serde(rename = "turn_detection.degraded")]
+    TurnDetectionDegraded {
+        /// Why the model is no longer used ("model_unavailable" or
+        /// "latency_budget_exceeded")
+        reason: TurnDetectionDegradedReason,
+        /// Duration of the inference that exceeded the budget (ms)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        inference_ms: Option<u64>,
+        /// Per-inference latency budget (ms)
+        budget_ms: u64,
+    },
+    /// Latency budget exceeded
+    ///
+    /// Sent when a stage of a turn runs past its deadline, for sessions
+    /// configured with a `latency_budget` whose actions include `event`.
+    #[serde(rename = "latency_budget.exceeded")]
+    LatencyBudgetExceeded {
+        /// User turn the reply answers
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+        /// Stage that exceeded its deadline ("stt_final", "agent",
+        /// "tts_first_byte" or "total")
+        stage: LatencyStage,
+        /// Time the stage had taken when it was checked (ms)
+        elapsed_ms: u64,
+        /// Deadline of the stage (ms)
+        deadline_ms: u64,
+        /// Whether a mitigation was applied
+        mitigated: bool,
+    },
+    /// Recording consent decided
+    ///
+    /// Sent for sessions configured with `stt_config.consent` when the caller
+    /// granted or denied consent, or the prompt timed out. After a denial or
+    /// timeout the session plays its goodbye and closes with code 4011.
+    #[serde(rename = "consent")]
+    Consent {
+        /// "granted", "denied" or "timed_out"
+        state: ConsentState,
+        /// What decided: "client", "dtmf" or "timeout"
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source: Option<ConsentSource>,
+        /// Identity of the participant whose DTMF digit decided
+        #[serde(skip_serializing_if = "Option::is_none")]
+        participant: Option<String>,
+        /// Timestamp of the decision (milliseconds since epoch)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timestamp: Option<u64>,
+    },
+    /// Pipeline recovered
+    ///
+    /// Sent when the session's watchdog reconnected a provider that stopped
+    /// responding, for sessions configured with a `watchdog`. After a TTS
+    /// recovery the pending speech is gone, as after `clear`.
+    #[serde(rename = "pipeline.recovered")]
+    PipelineRecovered {
+        /// Stage whose provider was reconnected ("stt" or "tts")
+        stage: WatchdogStage,
+        /// Time the stage had gone without an answer (ms)
+        stalled_ms: u64,
+        /// Unanswered caller audio sent again to the STT provider (bytes)
+        replayed_bytes: usize,
+        /// Recoveries of the session so far, including this one
+        recoveries: u32,
+    },
+    /// Barge-in suppressed
+    ///
+    /// Sent for sessions configured with `barge_in_confirmation` when a
+    /// transcript did not interrupt TTS output because the caller's speech
+    /// was not confirmed yet. Useful for tuning the thresholds.
+    #[serde(rename = "barge_in.suppressed")]
+    BargeInSuppressed {
+        /// Text of the rejected transcript
+        transcript: String,
+        /// Confidence the STT provider reported for it
+        confidence: f32,
+        /// Whether the transcript was an interim result
+        interim: bool,
+        /// Words of confident speech heard in the confirmation window
+        words: usize,
+        /// Why barge-in was held back ("low_confidence" or "too_short")
+        reason: BargeInSuppressionReason,
+    },
+    /// Audio level notification
+    ///
+    /// Sent about every 100ms per direction while audio flows, when the
+    /// session was configured with `audio_levels: true`.
+    #[serde(rename = "audio_level")]
+    AudioLevel {
+        /// Audio path: "in" for caller audio, "out" for TTS audio
+        direction: AudioDirection,
+        /// RMS level over the last 100ms in dBFS (-100 for silence)
+        rms_db: f32,
+        /// Peak sample level over the last 100ms in dBFS
+        peak_db: f32,
+    },
+    /// TTS playback start notification
+    ///
+    /// Sent before the first audio of each utterance.
+    #[serde(rename = "tts_playback_started")]
+    TTSPlaybackStarted {
+        /// Turn the speech belongs to
+        turn_id: String,
+        /// Timestamp of the first audio (milliseconds since epoch)
+        timestamp: u64,
+    },
     /// TTS playback completion notification
     #[serde(rename = "tts_playback_complete")]
     TTSPlaybackComplete {
         /// Timestamp when completion occurred (milliseconds since epoch)
         timestamp: u64,
+        /// Turn the speech belonged to
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+    },
+    /// Viseme and word timing of the utterance being synthesized
+    ///
+    /// Sent when `tts_config.visemes` is enabled and the provider reports
+    /// timing. Offsets are in milliseconds from the start of the utterance's
+    /// audio, which ends with `tts_playback_complete`.
+    #[serde(rename = "tts_visemes")]
+    TTSVisemes {
+        /// Viseme and word events sorted by offset
+        events: Vec<TTSTimingEvent>,
+        /// Turn the speech belongs to
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
     },
     #[serde(rename = "error")]
     Error {
         /// Error message
         message: String,
     },
+    /// Provider or agent error during a turn
+    ///
+    /// Serialized as an `error` message with the ID of the turn it affected.
+    #[serde(rename = "error")]
+    TurnError {
+        /// Error message
+        message: String,
+        /// Turn the error belongs to
+        turn_id: String,
+    },
+    /// Error reported by an STT, TTS or LLM provider
+    ///
+    /// Serialized as an `error` message. The provider's message is stripped
+    /// of secrets, URLs and markup and truncated; `code` identifies the kind
+    /// of failure independently of the provider's wording.
+    #[serde(rename = "error")]
+    ProviderError {
+        /// Machine-readable error code (e.g. `stt_authentication_failed`)
+        code: String,
+        /// Sanitized error message
+        message: String,
+        /// Turn the error belongs to
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+    },
+    /// Final error sent before the server closes the connection
+    ///
+    /// Serialized as an `error` message with the close code, for clients
+    /// that cannot read close frames.
+    #[serde(rename = "error")]
+    CloseError {
+        /// Machine-readable close reason (e.g. `idle_timeout`)
+        code: String,
+        /// WebSocket close code of the following close frame
+        close_code: u16,
+        /// Error message
+        message: String,
+    },
+    /// A session config that failed provider validation
+    ///
+    /// Serialized as an `error` message carrying every issue found, so
+    /// clients can fix the config in one round trip.
+    #[serde(rename = "error")]
+    ConfigError {
+        /// Machine-readable error code (`invalid_config`)
+        code: String,
+        /// Error message summarizing the issues
+        message: String,
+        /// Each invalid field and what is wrong with it
+        issues: Vec<ConfigIssue>,
+    },
     /// SIP transfer specific error
     ///
     /// This message is sent when a SIP transfer operation fails.
@@ -291,6 +814,20 @@ pub enum OutgoingMessage {
     /// Client must send an `auth` message before any other commands.
     #[serde(rename = "auth_required")]
     AuthRequired,
+    /// Heartbeat ping
+    ///
+    /// Sent every heartbeat interval to connections configured with
+    /// `heartbeat: "json"`. The client answers with a `pong` echoing `ts`;
+    /// connections that leave several pings in a row unanswered are closed
+    /// with code 4009.
+    #[serde(rename = "ping")]
+    Ping {
+        /// Server time the ping was sent (milliseconds since epoch)
+        ts: u64,
+        /// Smoothed round-trip time of earlier pings, once one was answered
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rtt_ms: Option<u64>,
+    },
     /// Plugin response message
     ///
     /// Generic response from a plugin handler for custom messages.
@@ -309,7 +846,59 @@ pub enum OutgoingMessage {
 pub enum MessageRoute {
     Outgoing(OutgoingMessage),
     Binary(Bytes),
-    Close,
+    /// WebSocket ping frame with a heartbeat payload
+    Ping(Bytes),
+    /// Close the connection with an application close code
+    Close(CloseReason),
+}
+
+impl OutgoingMessage {
+    /// The `call_state` message for a state an outbound call reached
+    pub fn call_state(state: CallState, timestamp: u64) -> Self {
+        Self::CallState {
+            state: state.as_str().to_string(),
+            reason: state.end_reason(),
+            timestamp,
+        }
+    }
+
+    /// The error message for a session config that failed validation
+    pub fn config_error(issues: Vec<ConfigIssue>) -> Self {
+        Self::ConfigError {
+            code: "invalid_config".to_string(),
+            message: config_issues_message(&issues),
+            issues,
+        }
+    }
+
+    /// The final error message announcing a close
+    pub fn close_error(reason: CloseReason, message: impl Into<String>) -> Self {
+        Self::CloseError {
+            code: reason.as_str().to_string(),
+            close_code: reason.code(),
+            message: message.into(),
+        }
+    }
+
+    /// The error message for a failure reported by a provider
+    ///
+    /// # Arguments
+    /// * `sanitizer` - Removes secrets, URLs and markup from `message`
+    /// * `code` - Error code from the provider error taxonomy
+    /// * `message` - Error message, including the provider's own text
+    /// * `turn_id` - Turn the error belongs to, if any
+    pub fn provider_error(
+        sanitizer: &ErrorSanitizer,
+        code: &str,
+        message: &str,
+        turn_id: Option<String>,
+    ) -> Self {
+        Self::ProviderError {
+            code: code.to_string(),
+            message: sanitizer.sanitize(code, message),
+            turn_id,
+        }
+    }
 }
 
 /// Error type for message validation failures
@@ -325,6 +914,16 @@ pub enum MessageValidationError {
     StreamIdTooLarge { size: usize, max: usize },
     /// Auth token exceeds maximum allowed size
     AuthTokenTooLarge { size: usize, max: usize },
+    /// Session metadata violates a size limit
+    InvalidMetadata(SessionMetadataError),
+    /// play_audio clip exceeds maximum allowed size
+    PlayAudioTooLarge { size: usize, max: usize },
+    /// Greeting configuration is invalid
+    InvalidGreeting(String),
+    /// Agent profile name exceeds maximum allowed size
+    AgentNameTooLarge { size: usize, max: usize },
+    /// Speak turn_id exceeds maximum allowed size
+    TurnIdTooLarge { size: usize, max: usize },
 }
 
 impl std::fmt::Display for MessageValidationError {
@@ -365,6 +964,25 @@ impl std::fmt::Display for MessageValidationError {
                     size, max
                 )
             }
+            Self::InvalidMetadata(e) => write!(f, "{}", e),
+            Self::PlayAudioTooLarge { size, max } => {
+                write!(
+                    f,
+                    "play_audio clip too large: {} bytes (max: {} bytes)",
+                    size, max
+                )
+            }
+            Self::InvalidGreeting(e) => write!(f, "Invalid greeting: {}", e),
+            Self::AgentNameTooLarge { size, max } => {
+                write!(
+                    f,
+                    "Agent name too large: {} bytes (max: {} bytes)",
+                    size, max
+                )
+            }
+            Self::TurnIdTooLarge { size, max } => {
+                write!(f, "turn_id too large: {} bytes (max: {} bytes)", size, max)
+            }
         }
     }
 }
@@ -379,12 +997,15 @@ impl IncomingMessage {
     /// * `Err(MessageValidationError)` if any field exceeds its limit
     ///
     /// # Limits
-    /// * Speak text: 100 KB
+    /// * Speak text: 100 KB, turn_id: 128 bytes
     /// * SendMessage message: 50 KB
     /// * SIP transfer_to: 256 bytes
+    /// * Config metadata: 10 entries, 2 KB total
+    /// * Config agent name: 64 bytes
+    /// * play_audio: 512 KB inline (decoded), 5 MB via binary frames
     pub fn validate_size(&self) -> Result<(), MessageValidationError> {
         match self {
-            IncomingMessage::Speak { text, .. } => {
+            IncomingMessage::Speak { text, turn_id, .. } => {
                 let size = text.len();
                 if size > MAX_SPEAK_TEXT_SIZE {
                     return Err(MessageValidationError::SpeakTextTooLarge {
@@ -392,6 +1013,14 @@ impl IncomingMessage {
                         max: MAX_SPEAK_TEXT_SIZE,
                     });
                 }
+                if let Some(turn_id) = turn_id
+                    && turn_id.len() > MAX_TURN_ID_SIZE
+                {
+                    return Err(MessageValidationError::TurnIdTooLarge {
+                        size: turn_id.len(),
+                        max: MAX_TURN_ID_SIZE,
+                    });
+                }
             }
             IncomingMessage::SendMessage { message, .. } => {
                 let size = message.len();
@@ -411,7 +1040,13 @@ impl IncomingMessage {
                     });
                 }
             }
-            IncomingMessage::Config { stream_id, .. } => {
+            IncomingMessage::Config {
+                stream_id,
+                metadata,
+                greeting,
+                agent,
+                ..
+            } => {
                 // Validate stream_id if provided
                 if let Some(id) = stream_id {
                     let size = id.len();
@@ -422,6 +1057,26 @@ impl IncomingMessage {
                         });
                     }
                 }
+                // Validate session metadata if provided
+                if let Some(metadata) = metadata {
+                    validate_session_metadata(metadata)
+                        .map_err(MessageValidationError::InvalidMetadata)?;
+                }
+                // Validate greeting if provided
+                if let Some(greeting) = greeting {
+                    greeting
+                        .validate()
+                        .map_err(MessageValidationError::InvalidGreeting)?;
+                }
+                // Validate agent profile name length
+                if let Some(agent) = agent
+                    && agent.len() > MAX_AGENT_NAME_LENGTH
+                {
+                    return Err(MessageValidationError::AgentNameTooLarge {
+                        size: agent.len(),
+                        max: MAX_AGENT_NAME_LENGTH,
+                    });
+                }
             }
             IncomingMessage::Auth { token } => {
                 // Validate auth token length
@@ -433,7 +1088,29 @@ impl IncomingMessage {
                     });
                 }
             }
-            IncomingMessage::Clear => {}
+            IncomingMessage::Clear
+            | IncomingMessage::Expect { .. }
+            | IncomingMessage::Pong { .. }
+            | IncomingMessage::Redaction { .. }
+            | IncomingMessage::Consent { .. } => {}
+            IncomingMessage::PlayAudio { audio, size, .. } => {
+                // Check the inline clip before decoding (base64 expands 3 bytes to 4)
+                if let Some(encoded) = audio {
+                    let decoded_size = encoded.len() / 4 * 3;
+                    if decoded_size > MAX_PLAY_AUDIO_INLINE_SIZE {
+                        return Err(MessageValidationError::PlayAudioTooLarge {
+                            size: decoded_size,
+                            max: MAX_PLAY_AUDIO_INLINE_SIZE,
+                        });
+                    }
+                }
+                if let Some(size) = size.filter(|size| *size > MAX_PLAY_AUDIO_SIZE) {
+                    return Err(MessageValidationError::PlayAudioTooLarge {
+                        size,
+                        max: MAX_PLAY_AUDIO_SIZE,
+                    });
+                }
+            }
             IncomingMessage::Custom {
                 message_type,
                 payload,
@@ -459,6 +1136,51 @@ impl IncomingMessage {
     }
 }
 
+/// Fields of a config message, including its `type` tag
+const CONFIG_FIELDS: &[&str] = &[
+    "type",
+    "stream_id",
+    "audio",
+    "audio_disabled",
+    "stt_config",
+    "tts_config",
+    "livekit",
+    "dag_config",
+    "agent_config",
+    "metadata",
+    "greeting",
+    "audio_levels",
+    "heartbeat",
+    "strict_config",
+    "agent",
+    "overrides",
+    "dry_run",
+    "flight_recorder",
+];
+
+/// Fields of a JSON config message the server does not know, at any depth
+///
+/// `metadata` and `overrides` are free-form and not checked.
+pub fn unknown_config_fields(message: &serde_json::Value) -> Vec<UnknownField> {
+    let Some(object) = message.as_object() else {
+        return Vec::new();
+    };
+    let mut unknown = unknown_keys(object, "", CONFIG_FIELDS);
+    for (field, value) in object {
+        let nested = match field.as_str() {
+            "stt_config" => collect_unknown_fields::<STTWebSocketConfig>(value, field),
+            "tts_config" => collect_unknown_fields::<TTSWebSocketConfig>(value, field),
+            "livekit" => collect_unknown_fields::<LiveKitWebSocketConfig>(value, field),
+            "dag_config" => collect_unknown_fields::<DAGWebSocketConfig>(value, field),
+            "agent_config" => collect_unknown_fields::<AgentBridgeConfig>(value, field),
+            "greeting" => collect_unknown_fields::<GreetingConfig>(value, field),
+            _ => continue,
+        };
+        unknown.extend(nested);
+    }
+    unknown
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,6 +1195,7 @@ mod tests {
             livekit_url: Some("ws://localhost:7880".to_string()),
             waav_participant_identity: Some("waav-ai".to_string()),
             waav_participant_name: Some("WaaV AI".to_string()),
+            feature_flags: Default::default(),
         };
 
         let json = serde_json::to_string(&ready).expect("Should serialize");
@@ -490,6 +1213,7 @@ mod tests {
             livekit_url: None,
             waav_participant_identity: None,
             waav_participant_name: None,
+            feature_flags: Default::default(),
         };
 
         let json = serde_json::to_string(&ready).expect("Should serialize");
@@ -500,6 +1224,29 @@ mod tests {
         assert!(!json.contains("livekit_url"));
         assert!(!json.contains("waav_participant_identity"));
         assert!(!json.contains("waav_participant_name"));
+        assert!(!json.contains("feature_flags"));
+    }
+
+    #[test]
+    fn test_ready_message_with_feature_flags() {
+        let ready = OutgoingMessage::Ready {
+            stream_id: "flagged-stream".to_string(),
+            livekit_room_name: None,
+            livekit_url: None,
+            waav_participant_identity: None,
+            waav_participant_name: None,
+            feature_flags: FeatureFlags::from(std::collections::BTreeMap::from([
+                ("adaptive_endpointing".to_string(), false),
+                ("echo_guard".to_string(), true),
+            ])),
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&ready).expect("Should serialize");
+
+        assert_eq!(
+            json["feature_flags"],
+            serde_json::json!({"adaptive_endpointing": false, "echo_guard": true})
+        );
     }
 
     #[test]
@@ -512,6 +1259,7 @@ mod tests {
             livekit_url: None,
             waav_participant_identity: None,
             waav_participant_name: None,
+            feature_flags: Default::default(),
         };
 
         let json = serde_json::to_string(&ready).expect("Should serialize");
@@ -527,6 +1275,7 @@ mod tests {
             livekit_url: None,
             waav_participant_identity: None,
             waav_participant_name: None,
+            feature_flags: Default::default(),
         };
 
         let json = serde_json::to_string(&ready).expect("Should serialize");
@@ -647,6 +1396,8 @@ mod tests {
             text,
             flush: None,
             allow_interruption: None,
+            turn_id: None,
+            priority: None,
         };
         assert!(msg.validate_size().is_ok());
     }
@@ -658,6 +1409,8 @@ mod tests {
             text,
             flush: None,
             allow_interruption: None,
+            turn_id: None,
+            priority: None,
         };
         let err = msg.validate_size().unwrap_err();
         match err {
@@ -669,6 +1422,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_speak_turn_id() {
+        let msg: IncomingMessage =
+            serde_json::from_str(r#"{"type": "speak", "text": "Hi", "turn_id": "turn-7"}"#)
+                .unwrap();
+        assert!(matches!(
+            &msg,
+            IncomingMessage::Speak { turn_id: Some(id), .. } if id == "turn-7"
+        ));
+        assert!(msg.validate_size().is_ok());
+
+        let msg = IncomingMessage::Speak {
+            text: "Hi".to_string(),
+            flush: None,
+            allow_interruption: None,
+            turn_id: Some("t".repeat(MAX_TURN_ID_SIZE + 1)),
+            priority: None,
+        };
+        assert!(matches!(
+            msg.validate_size(),
+            Err(MessageValidationError::TurnIdTooLarge { .. })
+        ));
+    }
+
     #[test]
     fn test_send_message_within_limit() {
         let message = "b".repeat(MAX_MESSAGE_CONTENT_SIZE);
@@ -730,16 +1507,576 @@ mod tests {
             stt_config: None,
             tts_config: None,
             livekit: None,
+            dag_config: None,
+            agent_config: None,
+            metadata: None,
+            greeting: None,
+            audio_levels: None,
+            heartbeat: None,
+            strict_config: None,
+            agent: None,
+            overrides: None,
+            dry_run: None,
+            flight_recorder: None,
         };
         assert!(msg.validate_size().is_ok());
     }
 
+    #[test]
+    fn test_config_message_metadata_deserialization() {
+        let json = r#"{"type": "config", "audio": false, "metadata": {"customer_id": "c-123"}}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).expect("Should deserialize");
+        match &msg {
+            IncomingMessage::Config { metadata, .. } => {
+                let metadata = metadata.as_ref().expect("metadata should be present");
+                assert_eq!(metadata.get("customer_id"), Some(&"c-123".to_string()));
+            }
+            _ => panic!("Expected Config variant"),
+        }
+        assert!(msg.validate_size().is_ok());
+    }
+
+    #[test]
+    fn test_config_message_metadata_rejects_non_string_values() {
+        let json = r#"{"type": "config", "metadata": {"count": 3}}"#;
+        assert!(serde_json::from_str::<IncomingMessage>(json).is_err());
+    }
+
+    #[test]
+    fn test_config_message_metadata_too_large() {
+        let metadata: SessionMetadata = (0..10)
+            .map(|i| (format!("key{i}"), "v".repeat(250)))
+            .collect();
+        let msg = IncomingMessage::Config {
+            stream_id: None,
+            audio: Some(false),
+            audio_disabled: None,
+            stt_config: None,
+            tts_config: None,
+            livekit: None,
+            dag_config: None,
+            agent_config: None,
+            metadata: Some(metadata),
+            greeting: None,
+            audio_levels: None,
+            heartbeat: None,
+            strict_config: None,
+            agent: None,
+            overrides: None,
+            dry_run: None,
+            flight_recorder: None,
+        };
+        let err = msg.validate_size().unwrap_err();
+        assert!(matches!(
+            err,
+            MessageValidationError::InvalidMetadata(SessionMetadataError::TooLarge { .. })
+        ));
+        assert!(err.to_string().contains("Session metadata too large"));
+    }
+
+    #[test]
+    fn test_config_message_greeting_validation() {
+        let json =
+            r#"{"type": "config", "audio": false, "greeting": {"text": "Hi", "asset": "welcome"}}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        let err = msg.validate_size().unwrap_err();
+        assert!(matches!(err, MessageValidationError::InvalidGreeting(_)));
+        assert!(err.to_string().starts_with("Invalid greeting"));
+
+        let json = r#"{"type": "config", "audio": false, "greeting": {"asset": "welcome"}}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        assert!(msg.validate_size().is_ok());
+    }
+
+    #[test]
+    fn test_greeting_played_serialization() {
+        let msg = OutgoingMessage::GreetingPlayed {
+            source: "asset".to_string(),
+            asset: Some("welcome".to_string()),
+            timestamp: 1700000000000,
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "greeting.played");
+        assert_eq!(json["source"], "asset");
+        assert_eq!(json["asset"], "welcome");
+    }
+
+    #[test]
+    fn test_call_state_serialization() {
+        let json = serde_json::to_value(OutgoingMessage::call_state(
+            CallState::EarlyMedia,
+            1700000000000,
+        ))
+        .unwrap();
+        assert_eq!(json["type"], "call_state");
+        assert_eq!(json["state"], "early_media");
+        assert!(json.get("reason").is_none());
+
+        let json = serde_json::to_value(OutgoingMessage::call_state(
+            CallState::Ended(CallEndReason::NoAnswer),
+            1700000000000,
+        ))
+        .unwrap();
+        assert_eq!(json["state"], "ended");
+        assert_eq!(json["reason"], "no_answer");
+    }
+
+    #[test]
+    fn test_config_audio_levels() {
+        let json = r#"{"type": "config", "audio": false, "audio_levels": true}"#;
+        match serde_json::from_str(json).unwrap() {
+            IncomingMessage::Config { audio_levels, .. } => assert_eq!(audio_levels, Some(true)),
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_heartbeat_and_pong() {
+        let json = r#"{"type": "config", "audio": false, "heartbeat": "json"}"#;
+        match serde_json::from_str(json).unwrap() {
+            IncomingMessage::Config { heartbeat, .. } => {
+                assert_eq!(heartbeat, Some(HeartbeatMode::Json))
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+        assert!(
+            serde_json::from_str::<IncomingMessage>(r#"{"type": "config", "heartbeat": "tcp"}"#)
+                .is_err()
+        );
+
+        match serde_json::from_str(r#"{"type": "pong", "ts": 1700000000000}"#).unwrap() {
+            IncomingMessage::Pong { ts } => assert_eq!(ts, 1_700_000_000_000),
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_config_fields_nested() {
+        let message = serde_json::json!({
+            "type": "config",
+            "stream_id": "s-1",
+            "stt_config": {
+                "provider": "deepgram",
+                "language": "en-US",
+                "sample_rate": 16000,
+                "channels": 1,
+                "punctuation": true,
+                "encoding": "linear16",
+                "model": "nova-2",
+                "echo_gaurd": {},
+                "adaptive_endpointing": {"min_silence_ms": 300, "max_silense_ms": 1200}
+            },
+            "livekit": {"room_name": "room", "enable_recording": true, "colour": "blue"},
+            "metadata": {"anything": "goes"},
+            "overrides": {"tts_config": {"voice_id": "v"}},
+            "audio_level": true
+        });
+        // Unknown fields are ignored by the regular parse
+        assert!(serde_json::from_value::<IncomingMessage>(message.clone()).is_ok());
+
+        let unknown = unknown_config_fields(&message);
+        let mut reported: Vec<String> = unknown.iter().map(ToString::to_string).collect();
+        reported.sort();
+        assert_eq!(
+            reported,
+            vec![
+                "'audio_level' (did you mean 'audio_levels'?)",
+                "'livekit.colour'",
+                "'stt_config.adaptive_endpointing.max_silense_ms' (did you mean 'max_silence_ms'?)",
+                "'stt_config.echo_gaurd' (did you mean 'echo_guard'?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_config_fields_known_message() {
+        let message = serde_json::json!({
+            "type": "config",
+            "audio": true,
+            "strict_config": true,
+            "livekit": {"room_name": "room", "enable_recording": true},
+            "greeting": {"text": "Hello"}
+        });
+        assert!(unknown_config_fields(&message).is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_config_parsing() {
+        let message = serde_json::json!({"type": "config", "audio": false, "dry_run": true});
+        assert!(unknown_config_fields(&message).is_empty());
+        match serde_json::from_value::<IncomingMessage>(message).unwrap() {
+            IncomingMessage::Config { dry_run, .. } => assert_eq!(dry_run, Some(true)),
+            _ => panic!("expected a config message"),
+        }
+    }
+
+    #[test]
+    fn test_config_error_serialization() {
+        let msg = OutgoingMessage::config_error(vec![
+            ConfigIssue::new("api_key", "API key is required").prefixed("tts_config"),
+            ConfigIssue::new("sample_rate", "11025 Hz is not supported").prefixed("stt_config"),
+        ]);
+        let json: serde_json::Value = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "invalid_config");
+        assert_eq!(
+            json["message"],
+            "Invalid provider configuration: tts_config.api_key: API key is required; \
+             stt_config.sample_rate: 11025 Hz is not supported"
+        );
+        assert_eq!(json["issues"][0]["field"], "tts_config.api_key");
+        assert_eq!(json["issues"][1]["message"], "11025 Hz is not supported");
+    }
+
+    #[test]
+    fn test_audio_level_serialization() {
+        let msg = OutgoingMessage::AudioLevel {
+            direction: AudioDirection::In,
+            rms_db: -23.4,
+            peak_db: -12.0,
+        };
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"type":"audio_level","direction":"in","rms_db":-23.4,"peak_db":-12.0}"#
+        );
+    }
+
+    #[test]
+    fn test_voice_fallback_serialization() {
+        let msg = OutgoingMessage::VoiceFallback {
+            from_voice_id: "deleted-voice".to_string(),
+            to_voice_id: "backup-voice".to_string(),
+            turn_id: None,
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "tts.voice_fallback");
+        assert_eq!(json["from_voice_id"], "deleted-voice");
+        assert_eq!(json["to_voice_id"], "backup-voice");
+        assert!(json.get("turn_id").is_none());
+    }
+
+    #[test]
+    fn test_turn_messages_serialization() {
+        let started = OutgoingMessage::TTSPlaybackStarted {
+            turn_id: "turn-1".to_string(),
+            timestamp: 1_700_000_000_000,
+        };
+        assert_eq!(
+            serde_json::to_value(&started).unwrap(),
+            serde_json::json!({
+                "type": "tts_playback_started",
+                "turn_id": "turn-1",
+                "timestamp": 1_700_000_000_000u64
+            })
+        );
+
+        let error = OutgoingMessage::TurnError {
+            message: "TTS error: boom".to_string(),
+            turn_id: "turn-1".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "type": "error",
+                "message": "TTS error: boom",
+                "turn_id": "turn-1"
+            })
+        );
+    }
+
+    #[test]
+    fn test_tts_queue_full_serialization() {
+        let msg = OutgoingMessage::TTSQueueFull {
+            policy: TTSQueuePolicy::DropOldest,
+            max_pending: 5,
+            text: "First sentence.".to_string(),
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "tts.queue_full");
+        assert_eq!(json["policy"], "drop_oldest");
+        assert_eq!(json["max_pending"], 5);
+        assert_eq!(json["text"], "First sentence.");
+    }
+
+    #[test]
+    fn test_speak_priority() {
+        let msg: IncomingMessage =
+            serde_json::from_str(r#"{"type": "speak", "text": "Hi", "priority": "system"}"#)
+                .unwrap();
+        assert!(matches!(
+            msg,
+            IncomingMessage::Speak {
+                priority: Some(SpeakPriority::System),
+                ..
+            }
+        ));
+        assert!(
+            serde_json::from_str::<IncomingMessage>(
+                r#"{"type": "speak", "text": "Hi", "priority": "urgent"}"#
+            )
+            .is_err()
+        );
+
+        let started = serde_json::to_value(OutgoingMessage::TTSPreemptionStarted {
+            text: "This call is recorded.".to_string(),
+            paused: 2,
+        })
+        .unwrap();
+        assert_eq!(started["type"], "tts.preemption_started");
+        assert_eq!(started["text"], "This call is recorded.");
+        assert_eq!(started["paused"], 2);
+
+        let ended =
+            serde_json::to_value(OutgoingMessage::TTSPreemptionEnded { resumed: 2 }).unwrap();
+        assert_eq!(ended["type"], "tts.preemption_ended");
+        assert_eq!(ended["resumed"], 2);
+    }
+
+    #[test]
+    fn test_tts_audio_quality_warning_serialization() {
+        let msg = OutgoingMessage::TTSAudioQualityWarning {
+            issues: vec![AudioQualityIssue::Silent, AudioQualityIssue::LowLevel],
+            clipped_fraction: 0.0,
+            zero_fraction: 1.0,
+            rms_dbfs: -100.0,
+            duration_ms: 500,
+            text: "Hello there.".to_string(),
+            retrying: true,
+            retried: false,
+            turn_id: Some("turn-1".to_string()),
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "tts.audio_quality_warning");
+        assert_eq!(json["issues"], serde_json::json!(["silent", "low_level"]));
+        assert_eq!(json["zero_fraction"], 1.0);
+        assert_eq!(json["rms_dbfs"], -100.0);
+        assert_eq!(json["retrying"], true);
+        assert_eq!(json["turn_id"], "turn-1");
+    }
+
+    #[test]
+    fn test_provider_error_never_forwards_secrets() {
+        let sanitizer = ErrorSanitizer::new(80).with_secrets(["dg-3f9a0c6e2b7d41f5".to_string()]);
+        let body = "STT streaming error: Authentication failed: 401 from \
+            https://stt.internal:8443/v1/listen?token=dg-3f9a0c6e2b7d41f5 \
+            <html><body><h1>Unauthorized</h1><p>Authorization: Token dg-3f9a0c6e2b7d41f5</p></body></html>";
+        let msg = OutgoingMessage::provider_error(
+            &sanitizer,
+            "stt_authentication_failed",
+            body,
+            Some("turn-1".to_string()),
+        );
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "stt_authentication_failed");
+        assert_eq!(json["turn_id"], "turn-1");
+        let message = json["message"].as_str().unwrap();
+        assert!(!message.contains("3f9a0c6e"), "{message}");
+        assert!(!message.contains("stt.internal") && !message.contains('<'));
+        assert!(message.chars().count() <= 80);
+        assert!(message.starts_with("STT streaming error: Authentication failed"));
+
+        let json = serde_json::to_value(OutgoingMessage::provider_error(
+            &sanitizer,
+            "tts_timeout",
+            "TTS error: Timeout error: no audio for 30s",
+            None,
+        ))
+        .unwrap();
+        assert_eq!(
+            json["message"],
+            "TTS error: Timeout error: no audio for 30s"
+        );
+        assert!(json.get("turn_id").is_none());
+    }
+
+    #[test]
+    fn test_turn_detection_degraded_serialization() {
+        let json = serde_json::to_value(OutgoingMessage::TurnDetectionDegraded {
+            reason: TurnDetectionDegradedReason::LatencyBudgetExceeded,
+            inference_ms: Some(340),
+            budget_ms: 200,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "turn_detection.degraded");
+        assert_eq!(json["reason"], "latency_budget_exceeded");
+        assert_eq!(json["inference_ms"], 340);
+        assert_eq!(json["budget_ms"], 200);
+
+        let json = serde_json::to_value(OutgoingMessage::TurnDetectionDegraded {
+            reason: TurnDetectionDegradedReason::ModelUnavailable,
+            inference_ms: None,
+            budget_ms: 200,
+        })
+        .unwrap();
+        assert_eq!(json["reason"], "model_unavailable");
+        assert!(json.get("inference_ms").is_none());
+    }
+
+    #[test]
+    fn test_latency_budget_exceeded_serialization() {
+        let json = serde_json::to_value(OutgoingMessage::LatencyBudgetExceeded {
+            turn_id: Some("turn-1".to_string()),
+            stage: LatencyStage::Agent,
+            elapsed_ms: 640,
+            deadline_ms: 600,
+            mitigated: true,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "latency_budget.exceeded");
+        assert_eq!(json["turn_id"], "turn-1");
+        assert_eq!(json["stage"], "agent");
+        assert_eq!(json["elapsed_ms"], 640);
+        assert_eq!(json["deadline_ms"], 600);
+        assert_eq!(json["mitigated"], true);
+    }
+
+    #[test]
+    fn test_consent_messages() {
+        let msg: IncomingMessage =
+            serde_json::from_str(r#"{"type": "consent", "granted": true}"#).unwrap();
+        assert!(matches!(msg, IncomingMessage::Consent { granted: true }));
+
+        let json = serde_json::to_value(OutgoingMessage::Consent {
+            state: ConsentState::TimedOut,
+            source: Some(ConsentSource::Timeout),
+            participant: None,
+            timestamp: Some(1700000000000),
+        })
+        .unwrap();
+        assert_eq!(json["type"], "consent");
+        assert_eq!(json["state"], "timed_out");
+        assert_eq!(json["source"], "timeout");
+        assert!(json.get("participant").is_none());
+        assert_eq!(json["timestamp"], 1700000000000u64);
+    }
+
+    #[test]
+    fn test_pipeline_recovered_serialization() {
+        let json = serde_json::to_value(OutgoingMessage::PipelineRecovered {
+            stage: WatchdogStage::Stt,
+            stalled_ms: 10250,
+            replayed_bytes: 160000,
+            recoveries: 1,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "pipeline.recovered");
+        assert_eq!(json["stage"], "stt");
+        assert_eq!(json["stalled_ms"], 10250);
+        assert_eq!(json["replayed_bytes"], 160000);
+        assert_eq!(json["recoveries"], 1);
+    }
+
+    #[test]
+    fn test_ping_serialization() {
+        let json = serde_json::to_value(OutgoingMessage::Ping {
+            ts: 1_700_000_000_000,
+            rtt_ms: None,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "ping", "ts": 1_700_000_000_000u64})
+        );
+
+        let json = serde_json::to_value(OutgoingMessage::Ping {
+            ts: 1_700_000_000_000,
+            rtt_ms: Some(42),
+        })
+        .unwrap();
+        assert_eq!(json["rtt_ms"], 42);
+    }
+
+    #[test]
+    fn test_barge_in_suppressed_serialization() {
+        let json = serde_json::to_value(OutgoingMessage::BargeInSuppressed {
+            transcript: "uh".to_string(),
+            confidence: 0.25,
+            interim: true,
+            words: 0,
+            reason: BargeInSuppressionReason::LowConfidence,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "barge_in.suppressed");
+        assert_eq!(json["transcript"], "uh");
+        assert_eq!(json["confidence"], 0.25);
+        assert_eq!(json["interim"], true);
+        assert_eq!(json["words"], 0);
+        assert_eq!(json["reason"], "low_confidence");
+    }
+
     #[test]
     fn test_clear_message_validation_passes() {
         let msg = IncomingMessage::Clear;
         assert!(msg.validate_size().is_ok());
     }
 
+    #[test]
+    fn test_expect_message_parsing() {
+        let msg: IncomingMessage =
+            serde_json::from_str(r#"{"type": "expect", "kind": "confirmation"}"#).unwrap();
+        assert!(matches!(
+            msg,
+            IncomingMessage::Expect {
+                kind: UtteranceKind::Confirmation
+            }
+        ));
+        assert!(msg.validate_size().is_ok());
+        assert!(serde_json::from_str::<IncomingMessage>(r#"{"type": "expect"}"#).is_err());
+        assert!(
+            serde_json::from_str::<IncomingMessage>(r#"{"type": "expect", "kind": "yes"}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_play_audio_message_parsing_and_validation() {
+        let json =
+            r#"{"type": "play_audio", "format": "wav", "sample_rate": 16000, "size": 32044}"#;
+        let msg: IncomingMessage = serde_json::from_str(json).unwrap();
+        match &msg {
+            IncomingMessage::PlayAudio {
+                format,
+                sample_rate,
+                audio,
+                size,
+                allow_interruption,
+            } => {
+                assert_eq!(format, "wav");
+                assert_eq!(*sample_rate, 16000);
+                assert!(audio.is_none());
+                assert_eq!(*size, Some(32044));
+                assert_eq!(*allow_interruption, Some(true));
+            }
+            _ => panic!("Expected PlayAudio message"),
+        }
+        assert!(msg.validate_size().is_ok());
+
+        let msg = IncomingMessage::PlayAudio {
+            format: "linear16".to_string(),
+            sample_rate: 16000,
+            audio: None,
+            size: Some(MAX_PLAY_AUDIO_SIZE + 1),
+            allow_interruption: None,
+        };
+        assert!(matches!(
+            msg.validate_size(),
+            Err(MessageValidationError::PlayAudioTooLarge { .. })
+        ));
+
+        let msg = IncomingMessage::PlayAudio {
+            format: "linear16".to_string(),
+            sample_rate: 16000,
+            audio: Some("A".repeat((MAX_PLAY_AUDIO_INLINE_SIZE / 3 + 1) * 4)),
+            size: None,
+            allow_interruption: None,
+        };
+        assert!(matches!(
+            msg.validate_size(),
+            Err(MessageValidationError::PlayAudioTooLarge { .. })
+        ));
+    }
+
     #[test]
     fn test_validation_error_display() {
         let err = MessageValidationError::SpeakTextTooLarge {
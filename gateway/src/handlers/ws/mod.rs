@@ -1,6 +1,7 @@
 //! # WebSocket Voice Handler Module
 //!
-//! This module provides a WebSocket interface for real-time voice processing using the VoiceManager.
+//! This module provides a WebSocket interface for real-time voice processing on top of a
+//! [`Session`](crate::core::session::Session) built with `SessionPipelineBuilder`.
 //! It supports STT (Speech-to-Text) and TTS (Text-to-Speech) operations through simple WebSocket messages.
 //!
 //! ## WebSocket API
@@ -18,17 +19,25 @@
 //! - `{"type": "config", "audio": true, "stt_config": {...}, "tts_config": {...}, "livekit": {...}}` - Initialize voice providers (without API keys) and optionally connect to LiveKit
 //! - `{"type": "speak", "text": "Hello world", "flush": true, "allow_interruption": true}` - Synthesize speech from text (flush and allow_interruption are optional, both default to true)
 //! - `{"type": "clear"}` - Clear pending TTS audio and clear queue (ignored if allow_interruption=false until audio finishes)
+//! - `{"type": "play_audio", "format": "wav", "sample_rate": 16000, "size": 32044}` - Play pre-synthesized audio through the TTS output path, sent as `size` bytes of binary frames (or inline base64 `audio`)
 //! - `{"type": "send_message", "message": "Hello LiveKit!", "role": "user", "topic": "chat"}` - Send custom text message through LiveKit (topic is optional)
 //! - `{"type": "sip_transfer", "transfer_to": "+1234567890"}` - Transfer active SIP call to another phone number
-//! - **Binary messages** - Raw audio data for transcription
+//! - `{"type": "pong", "ts": 1234567890}` - Answer a heartbeat `ping` (with `heartbeat: "json"` in the config)
+//! - `{"type": "redaction", "state": "start"}` - Silence the session's recordings until `"state": "stop"` (e.g. while the caller reads out a card number)
+//! - `{"type": "consent", "granted": true}` - Record the caller's recording consent (with `stt_config.consent`); their audio reaches STT only after consent
+//! - **Binary messages** - Raw audio data for transcription (or `play_audio` data while a transfer is pending)
 //!
 //! **Outgoing Messages:**
 //! - `{"type": "ready"}` - Voice providers are ready for use
 //! - `{"type": "stt_result", "transcript": "...", "is_final": true, "confidence": 0.95}` - STT result
 //! - `{"type": "message", "message": {...}}` - Unified message from various sources (LiveKit, etc.)
 //! - `{"type": "participant_disconnected", "participant": {...}}` - LiveKit participant disconnected from room
+//! - `{"type": "call_state", "state": "answered", "timestamp": 1234567890}` - Outbound call progress (with `livekit.outbound_call`)
+//! - `{"type": "redaction", "state": "start", "source": "dtmf", "timestamp": 1234567890}` - A recording redaction window opened or closed
+//! - `{"type": "consent", "state": "granted", "source": "dtmf", "timestamp": 1234567890}` - Recording consent was granted, denied or timed out
 //! - `{"type": "tts_playback_complete", "timestamp": 1234567890}` - TTS audio generation completed
 //! - `{"type": "error", "message": "error description"}` - Error occurred
+//! - `{"type": "ping", "ts": 1234567890, "rtt_ms": 42}` - Heartbeat ping (with `heartbeat: "json"`; WebSocket ping frames otherwise)
 //! - **Binary messages** - Raw TTS audio data (optimized for performance)
 //!
 //! **Unified Message Structure:**
@@ -416,16 +425,24 @@
 //!
 //! All errors are sent back to the client as JSON messages with `type: "error"`.
 
+pub mod agent_profile;
 pub mod audio_handler;
 pub mod command_handler;
 pub mod config;
 pub mod config_handler;
+pub mod consent;
 pub mod error;
 pub mod handler;
+pub mod heartbeat;
 pub mod messages;
+pub mod play_audio;
 pub mod processor;
+pub mod redaction;
+pub mod stages;
 pub mod state;
 
+#[cfg(test)]
+mod protocol_tests;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,324 @@
+//! Pre-synthesized audio injection for WebSocket sessions
+//!
+//! Clients that already have audio (a cached clip, a third-party TTS) can send a
+//! `play_audio` message and have the gateway play it through the same output path
+//! as synthesized speech. Audio is sent either inline as base64 (small clips) or
+//! as binary frames following the message, totalling the declared `size`.
+//!
+//! This module holds the format parsing, size limits and the buffer used while
+//! binary frames are being collected.
+
+/// Maximum decoded size of a single `play_audio` clip (5 MB)
+/// Matches the per-frame audio limit for incoming audio
+pub const MAX_PLAY_AUDIO_SIZE: usize = 5 * 1024 * 1024;
+
+/// Maximum decoded size of an inline base64 `play_audio` clip (512 KB)
+/// Keeps the encoded JSON message well under the text message limit
+pub const MAX_PLAY_AUDIO_INLINE_SIZE: usize = 512 * 1024;
+
+/// Maximum total `play_audio` bytes accepted per session (50 MB)
+pub const MAX_PLAY_AUDIO_SESSION_BYTES: usize = 50 * 1024 * 1024;
+
+/// Size of the canonical RIFF/WAVE header preamble (`RIFF` + size + `WAVE`)
+const WAV_PREAMBLE_SIZE: usize = 12;
+
+/// Container format of injected audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayAudioFormat {
+    /// RIFF/WAVE file containing 16-bit mono PCM
+    Wav,
+    /// Raw 16-bit little-endian mono PCM
+    Linear16,
+}
+
+impl PlayAudioFormat {
+    /// Parse a client-supplied format name (case-insensitive)
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "wav" => Some(Self::Wav),
+            "linear16" | "pcm" => Some(Self::Linear16),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when a `play_audio` request cannot be played
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PlayAudioError {
+    #[error("Unsupported play_audio format '{0}' (supported: wav, linear16, pcm)")]
+    UnsupportedFormat(String),
+    #[error(
+        "play_audio sample_rate {audio} Hz does not match the session output sample rate {output} Hz"
+    )]
+    SampleRateMismatch { audio: u32, output: u32 },
+    #[error("play_audio requires either 'audio' (base64) or 'size' (binary frames), not both")]
+    InvalidSource,
+    #[error("play_audio clip too large: {size} bytes (max: {max} bytes)")]
+    TooLarge { size: usize, max: usize },
+    #[error("play_audio session limit exceeded: {used} + {requested} bytes (max: {max} bytes)")]
+    SessionLimitExceeded {
+        used: usize,
+        requested: usize,
+        max: usize,
+    },
+    #[error("A play_audio transfer is already in progress")]
+    TransferInProgress,
+    #[error(
+        "Received more play_audio data than declared: {received} bytes (declared: {expected} bytes)"
+    )]
+    SizeMismatch { received: usize, expected: usize },
+    #[error("Invalid base64 audio: {0}")]
+    InvalidBase64(String),
+    #[error("Invalid WAV audio: {0}")]
+    InvalidWav(String),
+    #[error("PCM audio must contain whole 16-bit samples")]
+    InvalidPcm,
+}
+
+/// Check a clip against the per-session byte budget.
+///
+/// # Returns
+/// * `Ok(())` if `requested` more bytes fit within `MAX_PLAY_AUDIO_SESSION_BYTES`
+pub fn check_session_budget(used: usize, requested: usize) -> Result<(), PlayAudioError> {
+    if used.saturating_add(requested) > MAX_PLAY_AUDIO_SESSION_BYTES {
+        return Err(PlayAudioError::SessionLimitExceeded {
+            used,
+            requested,
+            max: MAX_PLAY_AUDIO_SESSION_BYTES,
+        });
+    }
+    Ok(())
+}
+
+/// Convert injected audio to raw 16-bit mono PCM.
+///
+/// WAV input must be uncompressed 16-bit mono PCM at `sample_rate`; the header
+/// is validated and stripped. Raw PCM input is passed through.
+pub fn decode_play_audio(
+    format: PlayAudioFormat,
+    sample_rate: u32,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, PlayAudioError> {
+    let pcm = match format {
+        PlayAudioFormat::Linear16 => data,
+        PlayAudioFormat::Wav => extract_wav_pcm(&data, sample_rate)?.to_vec(),
+    };
+
+    if pcm.len() % 2 != 0 {
+        return Err(PlayAudioError::InvalidPcm);
+    }
+    Ok(pcm)
+}
+
+/// Validate a WAV file and return its PCM data chunk.
+fn extract_wav_pcm(data: &[u8], sample_rate: u32) -> Result<&[u8], PlayAudioError> {
+    if data.len() < WAV_PREAMBLE_SIZE || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(PlayAudioError::InvalidWav(
+            "missing RIFF/WAVE header".to_string(),
+        ));
+    }
+
+    let mut offset = WAV_PREAMBLE_SIZE;
+    let mut format_checked = false;
+
+    while offset + 8 <= data.len() {
+        let chunk_id = &data[offset..offset + 4];
+        let chunk_size = u32::from_le_bytes([
+            data[offset + 4],
+            data[offset + 5],
+            data[offset + 6],
+            data[offset + 7],
+        ]) as usize;
+        let body_start = offset + 8;
+        let body_end = body_start.saturating_add(chunk_size).min(data.len());
+        let body = &data[body_start..body_end];
+
+        match chunk_id {
+            b"fmt " => {
+                if body.len() < 16 {
+                    return Err(PlayAudioError::InvalidWav(
+                        "fmt chunk too short".to_string(),
+                    ));
+                }
+                let audio_format = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let wav_sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                let bits_per_sample = u16::from_le_bytes([body[14], body[15]]);
+
+                if audio_format != 1 || bits_per_sample != 16 || channels != 1 {
+                    return Err(PlayAudioError::InvalidWav(format!(
+                        "expected 16-bit mono PCM, got format={audio_format} channels={channels} bits={bits_per_sample}"
+                    )));
+                }
+                if wav_sample_rate != sample_rate {
+                    return Err(PlayAudioError::InvalidWav(format!(
+                        "header sample rate {wav_sample_rate} Hz does not match declared {sample_rate} Hz"
+                    )));
+                }
+                format_checked = true;
+            }
+            b"data" => {
+                if !format_checked {
+                    return Err(PlayAudioError::InvalidWav(
+                        "data chunk before fmt chunk".to_string(),
+                    ));
+                }
+                return Ok(body);
+            }
+            _ => {}
+        }
+
+        // Chunks are word-aligned
+        offset = body_start.saturating_add(chunk_size + (chunk_size & 1));
+    }
+
+    Err(PlayAudioError::InvalidWav("missing data chunk".to_string()))
+}
+
+/// A `play_audio` transfer waiting for its binary frames
+#[derive(Debug)]
+pub struct PendingPlayAudio {
+    pub format: PlayAudioFormat,
+    pub sample_rate: u32,
+    pub allow_interruption: bool,
+    expected: usize,
+    buffer: Vec<u8>,
+}
+
+impl PendingPlayAudio {
+    pub fn new(
+        format: PlayAudioFormat,
+        sample_rate: u32,
+        allow_interruption: bool,
+        expected: usize,
+    ) -> Self {
+        Self {
+            format,
+            sample_rate,
+            allow_interruption,
+            expected,
+            buffer: Vec::with_capacity(expected),
+        }
+    }
+
+    /// Append a binary frame.
+    ///
+    /// # Returns
+    /// * `Ok(true)` once the declared size has been received
+    /// * `Ok(false)` while more frames are expected
+    /// * `Err(SizeMismatch)` if the frame overruns the declared size
+    pub fn push(&mut self, frame: &[u8]) -> Result<bool, PlayAudioError> {
+        let received = self.buffer.len() + frame.len();
+        if received > self.expected {
+            return Err(PlayAudioError::SizeMismatch {
+                received,
+                expected: self.expected,
+            });
+        }
+        self.buffer.extend_from_slice(frame);
+        Ok(self.buffer.len() == self.expected)
+    }
+
+    /// Consume the transfer, returning the collected bytes.
+    pub fn into_data(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(sample_rate: u32, channels: u16, pcm: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + pcm.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&(sample_rate * 2 * channels as u32).to_le_bytes());
+        out.extend_from_slice(&(2 * channels).to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(pcm.len() as u32).to_le_bytes());
+        out.extend_from_slice(pcm);
+        out
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(PlayAudioFormat::parse("wav"), Some(PlayAudioFormat::Wav));
+        assert_eq!(
+            PlayAudioFormat::parse("PCM"),
+            Some(PlayAudioFormat::Linear16)
+        );
+        assert_eq!(
+            PlayAudioFormat::parse("linear16"),
+            Some(PlayAudioFormat::Linear16)
+        );
+        assert_eq!(PlayAudioFormat::parse("mp3"), None);
+    }
+
+    #[test]
+    fn test_decode_wav_strips_header() {
+        let pcm = [1u8, 2, 3, 4];
+        let decoded = decode_play_audio(PlayAudioFormat::Wav, 16000, wav(16000, 1, &pcm)).unwrap();
+        assert_eq!(decoded, pcm);
+    }
+
+    #[test]
+    fn test_decode_wav_rejects_mismatch_and_stereo() {
+        let pcm = [0u8; 4];
+        assert!(matches!(
+            decode_play_audio(PlayAudioFormat::Wav, 16000, wav(24000, 1, &pcm)),
+            Err(PlayAudioError::InvalidWav(_))
+        ));
+        assert!(matches!(
+            decode_play_audio(PlayAudioFormat::Wav, 16000, wav(16000, 2, &pcm)),
+            Err(PlayAudioError::InvalidWav(_))
+        ));
+        assert!(matches!(
+            decode_play_audio(PlayAudioFormat::Wav, 16000, pcm.to_vec()),
+            Err(PlayAudioError::InvalidWav(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_pcm_requires_whole_samples() {
+        assert_eq!(
+            decode_play_audio(PlayAudioFormat::Linear16, 16000, vec![0u8; 3]),
+            Err(PlayAudioError::InvalidPcm)
+        );
+        assert_eq!(
+            decode_play_audio(PlayAudioFormat::Linear16, 16000, vec![0u8; 4]).unwrap(),
+            vec![0u8; 4]
+        );
+    }
+
+    #[test]
+    fn test_pending_play_audio_collects_frames() {
+        let mut pending = PendingPlayAudio::new(PlayAudioFormat::Linear16, 16000, true, 6);
+        assert_eq!(pending.push(&[0, 1, 2, 3]), Ok(false));
+        assert_eq!(
+            pending.push(&[4, 5, 6]),
+            Err(PlayAudioError::SizeMismatch {
+                received: 7,
+                expected: 6
+            })
+        );
+        assert_eq!(pending.push(&[4, 5]), Ok(true));
+        assert_eq!(pending.into_data(), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_session_budget() {
+        assert!(check_session_budget(0, MAX_PLAY_AUDIO_SESSION_BYTES).is_ok());
+        assert!(matches!(
+            check_session_budget(MAX_PLAY_AUDIO_SESSION_BYTES, 1),
+            Err(PlayAudioError::SessionLimitExceeded { .. })
+        ));
+    }
+}
@@ -5,21 +5,46 @@
 
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
+use tokio::time::Instant;
 use tracing::{debug, info, warn};
 
 use crate::auth::{Auth, match_api_secret_id};
+use crate::handlers::close::CloseReason;
 use crate::plugin::capabilities::{WSContext, WSResponse};
-use crate::plugin::global_registry;
 use crate::state::AppState;
 
 use super::{
-    audio_handler::{handle_clear_message, handle_speak_message},
+    agent_profile::resolve_agent_config,
+    audio_handler::{
+        handle_clear_message, handle_expect_message, handle_play_audio_message,
+        handle_speak_message,
+    },
     command_handler::{handle_send_message, handle_sip_transfer},
     config_handler::handle_config_message,
+    consent::handle_consent_message,
     messages::{IncomingMessage, MessageRoute, OutgoingMessage},
+    redaction::handle_redaction_message,
+    stages::{is_allowed_before_auth, resolve_audio_flag},
     state::ConnectionState,
 };
 
+/// Send the final error message and close the connection
+///
+/// The error carries the close reason's code so clients that cannot read
+/// close frames still learn why the connection ended.
+pub(super) async fn close_connection(
+    message_tx: &mpsc::Sender<MessageRoute>,
+    reason: CloseReason,
+    message: impl Into<String>,
+) {
+    let _ = message_tx
+        .send(MessageRoute::Outgoing(OutgoingMessage::close_error(
+            reason, message,
+        )))
+        .await;
+    let _ = message_tx.send(MessageRoute::Close(reason)).await;
+}
+
 /// Process incoming WebSocket message based on its type
 ///
 /// This is the main message router that delegates to specialized handlers
@@ -50,20 +75,38 @@ pub async fn handle_incoming_message(
         let conn_state = state.read().await;
         if conn_state.auth.is_pending() {
             // Only allow Auth messages when auth is pending
-            if !matches!(msg, IncomingMessage::Auth { .. }) {
+            if !is_allowed_before_auth(&msg) {
                 warn!("Received non-auth message while auth is pending, rejecting");
-                let _ = message_tx
-                    .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                        message: "Authentication required. Send auth message first.".to_string(),
-                    }))
-                    .await;
                 // Close connection for security
-                let _ = message_tx.send(MessageRoute::Close).await;
+                close_connection(
+                    message_tx,
+                    CloseReason::AuthFailed,
+                    "Authentication required. Send auth message first.",
+                )
+                .await;
                 return false;
             }
         }
     }
 
+    // Expand config messages that name an agent profile
+    let msg = match msg {
+        msg @ IncomingMessage::Config { agent: Some(_), .. } => {
+            let client_id = state.read().await.auth.id.clone();
+            match resolve_agent_config(msg, client_id.as_deref(), app_state).await {
+                Ok(resolved) => resolved,
+                Err(message) => {
+                    warn!("Failed to resolve agent profile: {}", message);
+                    let _ = message_tx
+                        .send(MessageRoute::Outgoing(OutgoingMessage::Error { message }))
+                        .await;
+                    return true;
+                }
+            }
+        }
+        msg => msg,
+    };
+
     match msg {
         // Handle first-message authentication for browser clients
         IncomingMessage::Auth { token } => {
@@ -77,30 +120,24 @@ pub async fn handle_incoming_message(
             tts_config,
             livekit,
             dag_config,
+            agent_config,
+            metadata,
+            greeting,
+            audio_levels,
+            heartbeat,
+            dry_run,
+            flight_recorder,
+            ..
         } => {
             // Handle backward compatibility for audio_disabled field
-            // Priority: audio field takes precedence if explicitly set
-            // If only audio_disabled is set, invert it to get audio value
-            let resolved_audio = if audio.is_some() {
-                // Explicit audio field set - use it directly
-                if audio_disabled.is_some() {
-                    warn!(
-                        "Both 'audio' and 'audio_disabled' fields present in config. \
-                         Using 'audio' value. 'audio_disabled' is deprecated."
-                    );
-                }
-                audio
-            } else if let Some(disabled) = audio_disabled {
-                // Legacy audio_disabled field - invert and warn
-                warn!(
-                    "'audio_disabled' is deprecated. Use 'audio: {}' instead.",
-                    !disabled
-                );
-                Some(!disabled)
-            } else {
-                // Neither set - use default
-                None
-            };
+            let resolved_audio = resolve_audio_flag(audio, audio_disabled);
+
+            if let Some(mode) = heartbeat
+                && let Some(connection_heartbeat) = &state.read().await.heartbeat
+            {
+                debug!(?mode, "Heartbeat mode set by config");
+                connection_heartbeat.set_mode(mode);
+            }
 
             handle_config_message(
                 stream_id,
@@ -109,6 +146,12 @@ pub async fn handle_incoming_message(
                 tts_config,
                 livekit,
                 dag_config,
+                agent_config,
+                metadata.unwrap_or_default(),
+                greeting,
+                audio_levels.unwrap_or(false),
+                dry_run.unwrap_or(false),
+                flight_recorder.unwrap_or(false),
                 state,
                 message_tx,
                 app_state,
@@ -119,8 +162,41 @@ pub async fn handle_incoming_message(
             text,
             flush,
             allow_interruption,
-        } => handle_speak_message(text, flush, allow_interruption, state, message_tx).await,
+            turn_id,
+            priority,
+        } => {
+            handle_speak_message(
+                text,
+                flush,
+                allow_interruption,
+                turn_id,
+                priority,
+                state,
+                message_tx,
+                &app_state.error_sanitizer,
+            )
+            .await
+        }
         IncomingMessage::Clear => handle_clear_message(state, message_tx).await,
+        IncomingMessage::Expect { kind } => handle_expect_message(kind, state, message_tx).await,
+        IncomingMessage::PlayAudio {
+            format,
+            sample_rate,
+            audio,
+            size,
+            allow_interruption,
+        } => {
+            handle_play_audio_message(
+                format,
+                sample_rate,
+                audio,
+                size,
+                allow_interruption,
+                state,
+                message_tx,
+            )
+            .await
+        }
         IncomingMessage::SendMessage {
             message,
             role,
@@ -130,6 +206,20 @@ pub async fn handle_incoming_message(
         IncomingMessage::SIPTransfer { transfer_to } => {
             handle_sip_transfer(transfer_to, state, message_tx, app_state).await
         }
+        IncomingMessage::Pong { ts } => {
+            if let Some(heartbeat) = &state.read().await.heartbeat
+                && !heartbeat.on_pong(ts, Instant::now())
+            {
+                debug!(ts, "Ignoring pong that answers no outstanding ping");
+            }
+            true
+        }
+        IncomingMessage::Redaction { state: redaction } => {
+            handle_redaction_message(redaction, state, message_tx, app_state).await
+        }
+        IncomingMessage::Consent { granted } => {
+            handle_consent_message(granted, state, message_tx).await
+        }
         IncomingMessage::Custom {
             message_type,
             payload,
@@ -160,12 +250,12 @@ async fn handle_auth_message(
     // Validate token against configured API secrets
     if !app_state.config.has_api_secret_auth() {
         warn!("First-message auth attempted but API secret auth not configured");
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: "API secret authentication not configured".to_string(),
-            }))
-            .await;
-        let _ = message_tx.send(MessageRoute::Close).await;
+        close_connection(
+            message_tx,
+            CloseReason::AuthFailed,
+            "API secret authentication not configured",
+        )
+        .await;
         return false;
     }
 
@@ -190,13 +280,13 @@ async fn handle_auth_message(
         true
     } else {
         warn!("First-message authentication failed: invalid token");
-        let _ = message_tx
-            .send(MessageRoute::Outgoing(OutgoingMessage::Error {
-                message: "Invalid authentication token".to_string(),
-            }))
-            .await;
         // Close connection on auth failure
-        let _ = message_tx.send(MessageRoute::Close).await;
+        close_connection(
+            message_tx,
+            CloseReason::AuthFailed,
+            "Invalid authentication token",
+        )
+        .await;
         false
     }
 }
@@ -221,10 +311,9 @@ async fn handle_custom_message(
     payload: serde_json::Value,
     state: &Arc<RwLock<ConnectionState>>,
     message_tx: &mpsc::Sender<MessageRoute>,
-    _app_state: &Arc<AppState>,
+    app_state: &Arc<AppState>,
 ) -> bool {
-    let registry = global_registry();
-    let handlers = registry.get_ws_handlers(&message_type);
+    let handlers = app_state.plugin_registry.get_ws_handlers(&message_type);
 
     if handlers.is_empty() {
         debug!(
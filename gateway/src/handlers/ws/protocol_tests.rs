@@ -0,0 +1,672 @@
+//! Snapshot tests for the WebSocket protocol
+//!
+//! Each case feeds a scripted sequence of inbound frames through
+//! `process_message` and the sender task, using mock STT/TTS providers, and
+//! asserts the exact outbound frames. The snapshots define current behavior.
+
+use async_trait::async_trait;
+use axum::extract::ws::Message;
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::channel::mpsc::UnboundedReceiver;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, mpsc, oneshot};
+
+use crate::ServerConfig;
+use crate::agents::AgentProfile;
+use crate::auth::Auth;
+use crate::config::PluginConfig;
+use crate::core::stt::{
+    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback,
+};
+use crate::core::tts::{
+    AudioCallback, AudioData, BaseTTS, ConnectionState as TTSConnectionState, TTSConfig, TTSResult,
+};
+use crate::core::validation::ConfigIssue;
+use crate::handlers::close::CloseReason;
+use crate::plugin::{PluginRegistry, ProviderMetadata};
+use crate::state::AppState;
+
+use super::handler::{forward_routes, process_message};
+use super::messages::{IncomingMessage, MessageRoute, OutgoingMessage};
+use super::state::ConnectionState;
+
+const MOCK_PROVIDER: &str = "ws-protocol-mock";
+
+/// How long the outbound stream must stay quiet before a step is complete
+const SETTLE_TIMEOUT: Duration = Duration::from_millis(200);
+
+const CONFIG: &str = r#"{"type":"config","stream_id":"snapshot","stt_config":{"provider":"ws-protocol-mock","language":"en-US","sample_rate":16000,"channels":1,"punctuation":true,"encoding":"linear16","model":"mock","api_key":"test-key"},"tts_config":{"provider":"ws-protocol-mock","voice_id":"mock-voice","model":"mock","api_key":"test-key"}}"#;
+const READY: &str = r#"{"type":"ready","stream_id":"snapshot","livekit_url":""}"#;
+const PLAYBACK_COMPLETE: &str = r#"{"type":"tts_playback_complete","timestamp":0}"#;
+const AUDIO_DISABLED: &str = r#"{"type":"error","message":"Audio processing is disabled. Send config message with audio=true first."}"#;
+
+/// STT provider that transcribes audio bytes as UTF-8 text
+struct MockSTT {
+    config: STTConfig,
+    connected: bool,
+    result_callback: Option<STTResultCallback>,
+}
+
+#[async_trait]
+impl BaseSTT for MockSTT {
+    fn new(config: STTConfig) -> Result<Self, STTError> {
+        Ok(Self {
+            config,
+            connected: false,
+            result_callback: None,
+        })
+    }
+
+    async fn connect(&mut self) -> Result<(), STTError> {
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), STTError> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.connected
+    }
+
+    async fn send_audio(&mut self, audio_data: Bytes) -> Result<(), STTError> {
+        if let Some(callback) = &self.result_callback {
+            let transcript = String::from_utf8_lossy(&audio_data).into_owned();
+            callback(STTResult::new(transcript, true, true, 1.0)).await;
+        }
+        Ok(())
+    }
+
+    async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+        self.result_callback = Some(callback);
+        Ok(())
+    }
+
+    async fn on_error(&mut self, _callback: STTErrorCallback) -> Result<(), STTError> {
+        Ok(())
+    }
+
+    fn get_config(&self) -> Option<&STTConfig> {
+        Some(&self.config)
+    }
+
+    async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn get_provider_info(&self) -> &'static str {
+        "WebSocket protocol mock STT"
+    }
+}
+
+/// TTS provider that synthesizes text as its UTF-8 bytes
+struct MockTTS {
+    state: TTSConnectionState,
+    callback: Option<Arc<dyn AudioCallback>>,
+}
+
+#[async_trait]
+impl BaseTTS for MockTTS {
+    fn new(_config: TTSConfig) -> TTSResult<Self> {
+        Ok(Self {
+            state: TTSConnectionState::Disconnected,
+            callback: None,
+        })
+    }
+
+    async fn connect(&mut self) -> TTSResult<()> {
+        self.state = TTSConnectionState::Connected;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> TTSResult<()> {
+        self.state = TTSConnectionState::Disconnected;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        matches!(self.state, TTSConnectionState::Connected)
+    }
+
+    fn get_connection_state(&self) -> TTSConnectionState {
+        self.state.clone()
+    }
+
+    async fn speak(&mut self, text: &str, _flush: bool) -> TTSResult<()> {
+        if let Some(callback) = &self.callback {
+            callback
+                .on_audio(AudioData {
+                    data: text.as_bytes().to_vec(),
+                    sample_rate: 16000,
+                    format: "linear16".to_string(),
+                    duration_ms: Some(100),
+                })
+                .await;
+            callback.on_complete().await;
+        }
+        Ok(())
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        Ok(())
+    }
+
+    async fn flush(&self) -> TTSResult<()> {
+        Ok(())
+    }
+
+    fn on_audio(&mut self, callback: Arc<dyn AudioCallback>) -> TTSResult<()> {
+        self.callback = Some(callback);
+        Ok(())
+    }
+
+    fn remove_audio_callback(&mut self) -> TTSResult<()> {
+        self.callback = None;
+        Ok(())
+    }
+}
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(
+        MOCK_PROVIDER,
+        Arc::new(|config| Ok(Box::new(MockSTT::new(config)?) as Box<dyn BaseSTT>)),
+        ProviderMetadata::stt(MOCK_PROVIDER, "WebSocket Protocol Mock STT").with_models(["mock"]),
+    );
+    registry.register_tts(
+        MOCK_PROVIDER,
+        Arc::new(|config| Ok(Box::new(MockTTS::new(config)?) as Box<dyn BaseTTS>)),
+        ProviderMetadata::tts(MOCK_PROVIDER, "WebSocket Protocol Mock TTS"),
+    );
+    registry
+}
+
+/// A scripted protocol exchange
+struct Case {
+    name: &'static str,
+    /// Whether the connection starts with first-message auth pending
+    auth_pending: bool,
+    inbound: Vec<Message>,
+    outbound: Vec<String>,
+}
+
+fn text(json: &str) -> Message {
+    Message::Text(json.into())
+}
+
+fn binary(data: &'static [u8]) -> Message {
+    Message::Binary(Bytes::from_static(data))
+}
+
+fn speak(text_to_speak: &str) -> Message {
+    text(&format!(r#"{{"type":"speak","text":"{text_to_speak}"}}"#))
+}
+
+fn error(message: &str) -> String {
+    serde_json::to_string(&OutgoingMessage::Error {
+        message: message.to_string(),
+    })
+    .unwrap()
+}
+
+/// The error sent for a config with an invalid field
+fn config_error(field: &str, message: &str) -> String {
+    serde_json::to_string(&OutgoingMessage::config_error(vec![ConfigIssue::new(
+        field, message,
+    )]))
+    .unwrap()
+}
+
+/// `CONFIG` asking for STT model `model`
+fn config_with_stt_model(model: &str, allow_unknown_models: bool, dry_run: bool) -> String {
+    let mut config: serde_json::Value = serde_json::from_str(CONFIG).unwrap();
+    config["stt_config"]["model"] = model.into();
+    if allow_unknown_models {
+        config["stt_config"]["allow_unknown_models"] = true.into();
+    }
+    if dry_run {
+        config["dry_run"] = true.into();
+    }
+    config.to_string()
+}
+
+/// The final error sent before the server closes the connection
+fn close_error(reason: CloseReason, message: &str) -> String {
+    serde_json::to_string(&OutgoingMessage::close_error(reason, message)).unwrap()
+}
+
+/// A close frame with the reason's code
+fn closed(reason: CloseReason) -> String {
+    format!("close:{}:{}", reason.code(), reason.as_str())
+}
+
+/// The error sent for a text frame that does not parse as a message
+fn invalid_format(raw: &str) -> String {
+    let e = serde_json::from_str::<IncomingMessage>(raw).unwrap_err();
+    error(&format!("Invalid message format: {e}"))
+}
+
+/// Render an outbound frame; playback timestamps are masked
+fn render(frame: Message) -> String {
+    match frame {
+        Message::Text(text) => mask_timestamp(text.as_str()),
+        Message::Binary(data) => format!("binary:{}", String::from_utf8_lossy(&data)),
+        Message::Close(Some(frame)) => format!("close:{}:{}", frame.code, frame.reason.as_str()),
+        Message::Close(None) => "close".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn mask_timestamp(text: &str) -> String {
+    const KEY: &str = "\"timestamp\":";
+    let Some(start) = text.find(KEY).map(|i| i + KEY.len()) else {
+        return text.to_string();
+    };
+    let digits = text[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len() - start);
+    format!("{}0{}", &text[..start], &text[start + digits..])
+}
+
+/// Collect outbound frames until the stream is quiet or closed
+async fn settle(frames: &mut UnboundedReceiver<Message>, outbound: &mut Vec<String>) {
+    while let Ok(Some(frame)) = tokio::time::timeout(SETTLE_TIMEOUT, frames.next()).await {
+        outbound.push(render(frame));
+    }
+}
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: test_agents(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Agent profiles: one with the same setup as `CONFIG`, one scoped to another client
+fn test_agents() -> Vec<AgentProfile> {
+    let mut session: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(CONFIG).unwrap();
+    session.remove("type");
+    session.remove("stream_id");
+
+    vec![
+        AgentProfile {
+            name: "snapshot-agent".to_string(),
+            description: None,
+            clients: vec![],
+            overridable: vec!["tts_config.voice_id".to_string()],
+            session: Some(session.clone()),
+            realtime: None,
+        },
+        AgentProfile {
+            name: "private-agent".to_string(),
+            description: None,
+            clients: vec!["another-client".to_string()],
+            overridable: vec![],
+            session: Some(session),
+            realtime: None,
+        },
+    ]
+}
+
+/// Run one case through the handler stages and return the outbound frames
+async fn run(case: &Case) -> Vec<String> {
+    let app_state = AppState::with_plugin_registry(test_config(), mock_registry()).await;
+    let auth = if case.auth_pending {
+        Auth::pending()
+    } else {
+        Auth::empty()
+    };
+    let state = Arc::new(RwLock::new(ConnectionState::with_auth(auth)));
+
+    let (message_tx, message_rx) = mpsc::channel::<MessageRoute>(64);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let (sink, mut frames) = futures::channel::mpsc::unbounded::<Message>();
+    let sender_task = tokio::spawn(forward_routes(sink, message_rx, shutdown_rx, None));
+
+    let mut outbound = Vec::new();
+    for msg in case.inbound.iter().cloned() {
+        let keep_open = process_message(msg, &state, &message_tx, &app_state).await;
+        settle(&mut frames, &mut outbound).await;
+        if !keep_open {
+            break;
+        }
+    }
+
+    let _ = shutdown_tx.send(());
+    let _ = sender_task.await;
+    settle(&mut frames, &mut outbound).await;
+
+    let session = state.write().await.session.take();
+    if let Some(session) = session {
+        let _ = session.close().await;
+    }
+    outbound
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            name: "start",
+            auth_pending: false,
+            inbound: vec![text(CONFIG)],
+            outbound: vec![READY.to_string(), closed(CloseReason::Normal)],
+        },
+        Case {
+            name: "unknown model",
+            auth_pending: false,
+            inbound: vec![
+                text(&config_with_stt_model("mokc", false, false)),
+                text(&config_with_stt_model("mokc", false, true)),
+                // Models not listed yet are accepted on request
+                text(&config_with_stt_model("mock-2", true, false)),
+            ],
+            outbound: vec![
+                config_error(
+                    "stt_config.model",
+                    "unknown ws-protocol-mock model 'mokc' (did you mean 'mock'?); set allow_unknown_models to use it anyway",
+                ),
+                config_error(
+                    "stt_config.model",
+                    "unknown ws-protocol-mock model 'mokc' (did you mean 'mock'?); set allow_unknown_models to use it anyway",
+                ),
+                READY.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "start without stt config",
+            auth_pending: false,
+            inbound: vec![text(
+                r#"{"type":"config","stream_id":"snapshot","tts_config":{"provider":"ws-protocol-mock","model":"mock","api_key":"test-key"}}"#,
+            )],
+            outbound: vec![
+                error("STT configuration is required when audio=true"),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "start without audio",
+            auth_pending: false,
+            inbound: vec![
+                text(r#"{"type":"config","stream_id":"snapshot","audio":false}"#),
+                speak("Hello"),
+            ],
+            outbound: vec![
+                READY.to_string(),
+                AUDIO_DISABLED.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "start from agent",
+            auth_pending: false,
+            inbound: vec![
+                text(
+                    r#"{"type":"config","stream_id":"snapshot","agent":"snapshot-agent","overrides":{"tts_config":{"voice_id":"other-voice"}}}"#,
+                ),
+                speak("Hello"),
+            ],
+            outbound: vec![
+                READY.to_string(),
+                "binary:Hello".to_string(),
+                PLAYBACK_COMPLETE.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "unknown agent",
+            auth_pending: false,
+            inbound: vec![
+                text(r#"{"type":"config","agent":"private-agent"}"#),
+                text(r#"{"type":"config","agent":"missing"}"#),
+            ],
+            outbound: vec![
+                error("Unknown agent 'private-agent'. Available agents: snapshot-agent"),
+                error("Unknown agent 'missing'. Available agents: snapshot-agent"),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "agent override not allowed",
+            auth_pending: false,
+            inbound: vec![
+                text(
+                    r#"{"type":"config","agent":"snapshot-agent","overrides":{"tts_config":{"provider":"deepgram"}}}"#,
+                ),
+                text(
+                    r#"{"type":"config","agent":"snapshot-agent","greeting":{"text":"Hi"}}"#,
+                ),
+            ],
+            outbound: vec![
+                error(
+                    "Field 'tts_config.provider' cannot be overridden for agent 'snapshot-agent'. Overridable fields: tts_config.voice_id",
+                ),
+                error("Field 'greeting' cannot be set alongside 'agent'; use 'overrides' instead"),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "audio before config",
+            auth_pending: false,
+            inbound: vec![binary(b"hello")],
+            outbound: vec![AUDIO_DISABLED.to_string(), closed(CloseReason::Normal)],
+        },
+        Case {
+            name: "transcript",
+            auth_pending: false,
+            inbound: vec![
+                text(CONFIG),
+                binary(b"hello world"),
+                text(r#"{"type":"ping"}"#),
+            ],
+            outbound: vec![
+                READY.to_string(),
+                r#"{"type":"stt_result","transcript":"hello world","is_final":true,"is_speech_final":true,"confidence":1.0}"#.to_string(),
+                invalid_format(r#"{"type":"ping"}"#),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "echo of spoken text",
+            auth_pending: false,
+            inbound: vec![
+                text(
+                    r#"{"type":"config","stream_id":"snapshot","stt_config":{"provider":"ws-protocol-mock","language":"en-US","sample_rate":16000,"channels":1,"punctuation":true,"encoding":"linear16","model":"mock","api_key":"test-key","echo_guard":{}},"tts_config":{"provider":"ws-protocol-mock","voice_id":"mock-voice","model":"mock","api_key":"test-key"}}"#,
+                ),
+                speak("How can I help you?"),
+                binary(b"how can I help"),
+                binary(b"cancel my order"),
+            ],
+            outbound: vec![
+                READY.to_string(),
+                "binary:How can I help you?".to_string(),
+                PLAYBACK_COMPLETE.to_string(),
+                r#"{"type":"stt_result","transcript":"cancel my order","is_final":true,"is_speech_final":true,"confidence":1.0}"#.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "speak",
+            auth_pending: false,
+            inbound: vec![
+                text(CONFIG),
+                speak("Hello"),
+                text(r#"{"type":"speak","text":"How are you?","flush":false}"#),
+            ],
+            outbound: vec![
+                READY.to_string(),
+                "binary:Hello".to_string(),
+                PLAYBACK_COMPLETE.to_string(),
+                "binary:How are you?".to_string(),
+                PLAYBACK_COMPLETE.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "interrupt",
+            auth_pending: false,
+            inbound: vec![
+                text(CONFIG),
+                speak("Hello"),
+                text(r#"{"type":"clear"}"#),
+                speak("Again"),
+            ],
+            outbound: vec![
+                READY.to_string(),
+                "binary:Hello".to_string(),
+                PLAYBACK_COMPLETE.to_string(),
+                "binary:Again".to_string(),
+                PLAYBACK_COMPLETE.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "error",
+            auth_pending: false,
+            inbound: vec![
+                text("not json"),
+                text(r#"{"type":"speak"}"#),
+                speak("Hello"),
+                text(r#"{"type":"clear"}"#),
+            ],
+            outbound: vec![
+                invalid_format("not json"),
+                invalid_format(r#"{"type":"speak"}"#),
+                AUDIO_DISABLED.to_string(),
+                closed(CloseReason::Normal),
+            ],
+        },
+        Case {
+            name: "auth required",
+            auth_pending: true,
+            inbound: vec![speak("Hello"), speak("Hello")],
+            outbound: vec![
+                close_error(
+                    CloseReason::AuthFailed,
+                    "Authentication required. Send auth message first.",
+                ),
+                closed(CloseReason::AuthFailed),
+            ],
+        },
+        Case {
+            name: "auth not configured",
+            auth_pending: true,
+            inbound: vec![text(r#"{"type":"auth","token":"secret"}"#), speak("Hello")],
+            outbound: vec![
+                close_error(
+                    CloseReason::AuthFailed,
+                    "API secret authentication not configured",
+                ),
+                closed(CloseReason::AuthFailed),
+            ],
+        },
+        Case {
+            name: "close",
+            auth_pending: false,
+            inbound: vec![text(CONFIG), Message::Close(None), speak("Hello")],
+            outbound: vec![READY.to_string(), closed(CloseReason::Normal)],
+        },
+    ]
+}
+
+#[tokio::test]
+async fn test_protocol_snapshots() {
+    for case in cases() {
+        assert_eq!(run(&case).await, case.outbound, "case: {}", case.name);
+    }
+}
+
+#[test]
+fn test_mask_timestamp() {
+    assert_eq!(
+        mask_timestamp(r#"{"type":"tts_playback_complete","timestamp":1700000000000}"#),
+        PLAYBACK_COMPLETE
+    );
+    assert_eq!(mask_timestamp(READY), READY);
+}
@@ -0,0 +1,293 @@
+//! Recording redaction windows of a WebSocket session
+//!
+//! A window is opened by the client's `redaction` message, or by DTMF digits
+//! from a SIP caller when `recording_redaction.dtmf` is set. Every opening
+//! and closing is written to the audit log, counted, announced to the client
+//! and the session's observers with a `redaction` message and, with
+//! `recording_redaction.mute_egress_tracks`, applied to the room recording by
+//! muting the callers' LiveKit audio tracks.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use livekit_protocol::{TrackType, participant_info};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{RwLock, mpsc};
+use tracing::{debug, info, warn};
+
+use crate::core::redaction::RedactionSource;
+use crate::livekit::{DtmfCallback, DtmfEvent};
+use crate::metrics::global_metrics;
+use crate::state::AppState;
+
+use super::{
+    messages::{MessageRoute, OutgoingMessage},
+    state::ConnectionState,
+};
+
+/// Whether a redaction window opens or closes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionState {
+    Start,
+    Stop,
+}
+
+impl RedactionState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+        }
+    }
+}
+
+/// Handle the client's `redaction` message
+///
+/// Starting a window DTMF digits opened keeps it open until the client
+/// stops it. The client gets a `redaction` message with the window's state
+/// either way.
+///
+/// # Arguments
+/// * `redaction_state` - Whether to open or close the window
+/// * `state` - Connection state holding the session's redaction windows
+/// * `message_tx` - Channel for sending response messages
+/// * `app_state` - Application state with the redaction config and room handler
+///
+/// # Returns
+/// * `bool` - true to continue processing
+pub async fn handle_redaction_message(
+    redaction_state: RedactionState,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    app_state: &Arc<AppState>,
+) -> bool {
+    let redaction = state.read().await.redaction.clone();
+    let now = now_ms();
+    let changed = match redaction_state {
+        RedactionState::Start => redaction.start(RedactionSource::Client, now),
+        RedactionState::Stop => redaction.stop(now).is_some(),
+    };
+
+    if changed {
+        redaction_changed(
+            redaction_state,
+            RedactionSource::Client,
+            now,
+            state,
+            message_tx,
+            app_state,
+        )
+        .await;
+    } else {
+        debug!(
+            state = redaction_state.as_str(),
+            "Redaction window already in the requested state"
+        );
+        let current = if redaction.is_active() {
+            RedactionState::Start
+        } else {
+            RedactionState::Stop
+        };
+        let _ = message_tx
+            .send(MessageRoute::Outgoing(OutgoingMessage::Redaction {
+                state: current,
+                source: RedactionSource::Client,
+                timestamp: now,
+            }))
+            .await;
+    }
+    true
+}
+
+/// Callback opening a redaction window for DTMF digits from the session's room
+///
+/// The window closes `recording_redaction.dtmf_idle_ms` after the last digit,
+/// unless the client took it over in the meantime.
+pub(super) fn dtmf_callback(
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    app_state: &Arc<AppState>,
+) -> DtmfCallback {
+    let state = state.clone();
+    let message_tx = message_tx.clone();
+    let app_state = app_state.clone();
+    let idle = Duration::from_millis(app_state.config.recording_redaction.dtmf_idle_ms);
+
+    Arc::new(move |event: DtmfEvent| {
+        let state = state.clone();
+        let message_tx = message_tx.clone();
+        let app_state = app_state.clone();
+
+        tokio::spawn(async move {
+            let redaction = state.read().await.redaction.clone();
+            let (opened, generation) = redaction.dtmf_digit(event.timestamp);
+            if opened {
+                redaction_changed(
+                    RedactionState::Start,
+                    RedactionSource::Dtmf,
+                    event.timestamp,
+                    &state,
+                    &message_tx,
+                    &app_state,
+                )
+                .await;
+            }
+
+            tokio::time::sleep(idle).await;
+            let now = now_ms();
+            if redaction.dtmf_idle(generation, now).is_some() {
+                redaction_changed(
+                    RedactionState::Stop,
+                    RedactionSource::Dtmf,
+                    now,
+                    &state,
+                    &message_tx,
+                    &app_state,
+                )
+                .await;
+            }
+        });
+    })
+}
+
+/// Record, announce and apply a window opening or closing
+async fn redaction_changed(
+    redaction_state: RedactionState,
+    source: RedactionSource,
+    timestamp: u64,
+    state: &Arc<RwLock<ConnectionState>>,
+    message_tx: &mpsc::Sender<MessageRoute>,
+    app_state: &Arc<AppState>,
+) {
+    let (stream_id, auth_id) = {
+        let state_guard = state.read().await;
+        (state_guard.stream_id.clone(), state_guard.auth.id.clone())
+    };
+
+    info!(
+        target: "audit",
+        stream_id = ?stream_id,
+        client_id = ?auth_id,
+        state = redaction_state.as_str(),
+        source = source.as_str(),
+        timestamp,
+        "Recording redaction"
+    );
+    if redaction_state == RedactionState::Start {
+        global_metrics().inc_counter(
+            "waav_recording_redactions_total",
+            "Recording redaction windows opened",
+            &[("source", source.as_str())],
+        );
+    }
+
+    let message = OutgoingMessage::Redaction {
+        state: redaction_state,
+        source,
+        timestamp,
+    };
+    if let Some(stream_id) = &stream_id
+        && let Ok(value) = serde_json::to_value(&message)
+    {
+        app_state.session_events.publish(stream_id, &value);
+    }
+    let _ = message_tx.send(MessageRoute::Outgoing(message)).await;
+
+    if app_state.config.recording_redaction.mute_egress_tracks {
+        mute_caller_tracks(redaction_state == RedactionState::Start, state, app_state).await;
+    }
+}
+
+/// Mute the callers' audio tracks in the session's room, or unmute the
+/// tracks muted for the window
+///
+/// Only rooms being recorded are touched. The gateway's own participant is
+/// left alone, as are tracks the caller muted themselves.
+async fn mute_caller_tracks(
+    muted: bool,
+    state: &Arc<RwLock<ConnectionState>>,
+    app_state: &Arc<AppState>,
+) {
+    let Some(room_handler) = &app_state.livekit_room_handler else {
+        return;
+    };
+    let (room_name, local_identity) = {
+        let state_guard = state.read().await;
+        if state_guard.recording_egress_id.is_none() {
+            return;
+        }
+        let Some(room_name) = state_guard.livekit_room_name.clone() else {
+            return;
+        };
+        (room_name, state_guard.livekit_local_identity.clone())
+    };
+
+    if !muted {
+        let tracks = std::mem::take(&mut state.write().await.redaction_muted_tracks);
+        for (identity, track_sid) in tracks {
+            if let Err(e) = room_handler
+                .mute_participant_track(&room_name, &identity, &track_sid, false)
+                .await
+            {
+                warn!(
+                    room = %room_name,
+                    track = %track_sid,
+                    "Failed to unmute track after redaction: {}",
+                    e
+                );
+            }
+        }
+        return;
+    }
+
+    let participants = match room_handler.list_participants(&room_name).await {
+        Ok(participants) => participants,
+        Err(e) => {
+            warn!(room = %room_name, "Failed to list participants for redaction: {}", e);
+            return;
+        }
+    };
+    let mut tracks = Vec::new();
+    for participant in participants {
+        let caller = Some(&participant.identity) != local_identity.as_ref()
+            && participant_info::Kind::try_from(participant.kind).is_ok_and(|kind| {
+                matches!(
+                    kind,
+                    participant_info::Kind::Standard | participant_info::Kind::Sip
+                )
+            });
+        if !caller {
+            continue;
+        }
+        for track in &participant.tracks {
+            let audio =
+                TrackType::try_from(track.r#type).is_ok_and(|kind| kind == TrackType::Audio);
+            if track.muted || !audio {
+                continue;
+            }
+            match room_handler
+                .mute_participant_track(&room_name, &participant.identity, &track.sid, true)
+                .await
+            {
+                Ok(_) => tracks.push((participant.identity.clone(), track.sid.clone())),
+                Err(e) => warn!(
+                    room = %room_name,
+                    track = %track.sid,
+                    "Failed to mute track for redaction: {}",
+                    e
+                ),
+            }
+        }
+    }
+    state.write().await.redaction_muted_tracks.extend(tracks);
+}
+
+/// Current time in milliseconds since epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
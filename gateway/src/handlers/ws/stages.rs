@@ -0,0 +1,738 @@
+//! Stages of the WebSocket session handler
+//!
+//! Every frame passes through the same stages:
+//!
+//! 1. **Decode** - [`decode_frame`] turns a WebSocket frame into an [`InboundFrame`]
+//! 2. **Session command** - [`handle_incoming_message`](super::processor::handle_incoming_message)
+//!    applies control messages to the connection, using [`is_allowed_before_auth`]
+//!    and [`resolve_audio_flag`]
+//! 3. **Pipeline action** - [`session_event_action`] decides what each session
+//!    event produces for the client
+//! 4. **Encode** - [`encode_route`] turns a [`MessageRoute`] into a WebSocket frame
+//!
+//! The functions here are pure; the handlers own all state and I/O.
+
+use axum::extract::ws::Message;
+use bytes::Bytes;
+use tracing::{debug, error, info, warn};
+
+use crate::config::GreetingSource;
+use crate::core::{session::SessionEvent, stt::STTVadEvent, tts::AudioData};
+use crate::errors::provider_error::ErrorSanitizer;
+use crate::handlers::strict_config::unknown_fields_message;
+
+use super::messages::{IncomingMessage, MessageRoute, OutgoingMessage, unknown_config_fields};
+
+/// Maximum text message size before deserialization (1 MB)
+/// This prevents JSON parsing attacks with extremely large payloads
+/// Binary audio messages use the WebSocket message size limit instead
+pub const MAX_TEXT_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// A decoded inbound WebSocket frame
+#[derive(Debug)]
+pub enum InboundFrame {
+    /// A valid control message
+    Control(IncomingMessage),
+    /// Binary audio (or `play_audio` data while a transfer is pending)
+    Binary(Bytes),
+    /// A frame that is answered with an error; the connection stays open
+    Rejected(OutgoingMessage),
+    /// Pong frame, possibly answering a heartbeat ping
+    Pong(Bytes),
+    /// Ping frames, answered by axum
+    Ignored,
+    /// The client closed the connection
+    Close,
+}
+
+/// Decode stage: parse and validate one inbound frame
+///
+/// Text frames are size-checked before deserialization, then parsed as
+/// [`IncomingMessage`] and validated field by field. Config messages are
+/// also checked for unknown fields when their `strict_config` (or
+/// `strict_default` if unset) is enabled.
+pub fn decode_frame(msg: Message, strict_default: bool) -> InboundFrame {
+    match msg {
+        Message::Text(text) => {
+            debug!("Received text message: {} bytes", text.len());
+
+            // Pre-deserialization size check to prevent JSON parsing attacks
+            if text.len() > MAX_TEXT_MESSAGE_SIZE {
+                warn!(
+                    size = text.len(),
+                    max = MAX_TEXT_MESSAGE_SIZE,
+                    "Text message exceeds maximum size before deserialization"
+                );
+                return InboundFrame::Rejected(OutgoingMessage::Error {
+                    message: format!(
+                        "Message too large: {} bytes (max {} bytes)",
+                        text.len(),
+                        MAX_TEXT_MESSAGE_SIZE
+                    ),
+                });
+            }
+
+            let incoming_msg: IncomingMessage = match serde_json::from_str(&text) {
+                Ok(msg) => msg,
+                Err(e) => {
+                    error!("Failed to parse incoming message: {}", e);
+                    return InboundFrame::Rejected(OutgoingMessage::Error {
+                        message: format!("Invalid message format: {e}"),
+                    });
+                }
+            };
+
+            // Validate message field sizes to prevent resource exhaustion
+            if let Err(e) = incoming_msg.validate_size() {
+                warn!("Message validation failed: {}", e);
+                return InboundFrame::Rejected(OutgoingMessage::Error {
+                    message: e.to_string(),
+                });
+            }
+
+            if let IncomingMessage::Config { strict_config, .. } = &incoming_msg
+                && strict_config.unwrap_or(strict_default)
+                && let Err(message) = check_unknown_config_fields(&text)
+            {
+                warn!("Rejected config message: {}", message);
+                return InboundFrame::Rejected(OutgoingMessage::Error { message });
+            }
+
+            InboundFrame::Control(incoming_msg)
+        }
+        Message::Binary(data) => {
+            debug!("Received binary message: {} bytes", data.len());
+            InboundFrame::Binary(data)
+        }
+        Message::Ping(_) => {
+            debug!("Received ping message");
+            InboundFrame::Ignored
+        }
+        Message::Pong(data) => {
+            debug!("Received pong message");
+            InboundFrame::Pong(data)
+        }
+        Message::Close(_) => {
+            info!("WebSocket connection closed by client");
+            InboundFrame::Close
+        }
+    }
+}
+
+/// Longest text frame checked for a heartbeat `pong` message
+const MAX_PONG_MESSAGE_SIZE: usize = 64;
+
+/// Whether `msg` only answers a heartbeat ping
+///
+/// Such frames do not count as activity for the idle timeout. Only short
+/// text frames are parsed, so other messages are not decoded twice.
+pub fn is_heartbeat_answer(msg: &Message) -> bool {
+    match msg {
+        Message::Pong(_) => true,
+        Message::Text(text) if text.len() <= MAX_PONG_MESSAGE_SIZE => matches!(
+            serde_json::from_str::<IncomingMessage>(text),
+            Ok(IncomingMessage::Pong { .. })
+        ),
+        _ => false,
+    }
+}
+
+/// Strict config check: list every unknown field of a config message
+fn check_unknown_config_fields(text: &str) -> Result<(), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Invalid message format: {e}"))?;
+    let unknown = unknown_config_fields(&value);
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(unknown_fields_message(&unknown))
+    }
+}
+
+/// Whether `msg` may be processed while first-message auth is pending
+///
+/// Heartbeat pongs are accepted so unauthenticated connections are not
+/// reaped while the client is still authenticating.
+pub fn is_allowed_before_auth(msg: &IncomingMessage) -> bool {
+    matches!(
+        msg,
+        IncomingMessage::Auth { .. } | IncomingMessage::Pong { .. }
+    )
+}
+
+/// Resolve the `audio` flag of a config message
+///
+/// `audio` takes precedence; the deprecated `audio_disabled` field is
+/// inverted when it is the only one set. `None` means the default applies.
+pub fn resolve_audio_flag(audio: Option<bool>, audio_disabled: Option<bool>) -> Option<bool> {
+    if audio.is_some() {
+        if audio_disabled.is_some() {
+            warn!(
+                "Both 'audio' and 'audio_disabled' fields present in config. \
+                 Using 'audio' value. 'audio_disabled' is deprecated."
+            );
+        }
+        audio
+    } else if let Some(disabled) = audio_disabled {
+        warn!(
+            "'audio_disabled' is deprecated. Use 'audio: {}' instead.",
+            !disabled
+        );
+        Some(!disabled)
+    } else {
+        None
+    }
+}
+
+/// What a session event produces for the client
+#[derive(Debug)]
+pub enum EventAction {
+    /// Send a JSON message
+    Send(OutgoingMessage),
+    /// Send TTS audio to LiveKit, or to the WebSocket as a binary frame
+    Audio(AudioData),
+    /// Drop audio already queued on LiveKit
+    ClearLiveKitAudio,
+    /// Nothing to send
+    Ignore,
+}
+
+/// Pipeline action stage: map a session event to its client-facing action
+///
+/// Provider errors are passed through `sanitizer` so that credentials and
+/// raw provider responses never reach the client.
+pub fn session_event_action(event: SessionEvent, sanitizer: &ErrorSanitizer) -> EventAction {
+    let msg = match event {
+        SessionEvent::Transcript(result) => OutgoingMessage::STTResult {
+            transcript: result.transcript,
+            is_final: result.is_final,
+            is_speech_final: result.is_speech_final,
+            confidence: result.confidence,
+            redactions: result.redactions,
+            timing: result.timing,
+            turn_id: result.turn_id,
+        },
+        SessionEvent::Vad(STTVadEvent::SpeechStarted { timestamp }) => {
+            OutgoingMessage::SpeechStarted { timestamp }
+        }
+        SessionEvent::Vad(STTVadEvent::UtteranceEnd { last_word_end }) => {
+            OutgoingMessage::UtteranceEnd { last_word_end }
+        }
+        SessionEvent::SttError { error, turn_id } => OutgoingMessage::provider_error(
+            sanitizer,
+            error.error_code(),
+            &format!("STT streaming error: {error}"),
+            turn_id,
+        ),
+        SessionEvent::TtsError { error, turn_id } => OutgoingMessage::provider_error(
+            sanitizer,
+            error.error_code(),
+            &format!("TTS error: {error}"),
+            turn_id,
+        ),
+        SessionEvent::VoiceFallback {
+            from_voice_id,
+            to_voice_id,
+            turn_id,
+        } => OutgoingMessage::VoiceFallback {
+            from_voice_id,
+            to_voice_id,
+            turn_id,
+        },
+        SessionEvent::TtsQueueFull {
+            policy,
+            max_pending,
+            text,
+        } => OutgoingMessage::TTSQueueFull {
+            policy,
+            max_pending,
+            text,
+        },
+        SessionEvent::PreemptionStarted { text, paused } => {
+            OutgoingMessage::TTSPreemptionStarted { text, paused }
+        }
+        SessionEvent::PreemptionEnded { resumed } => {
+            OutgoingMessage::TTSPreemptionEnded { resumed }
+        }
+        SessionEvent::TtsAudioQualityWarning { warning, turn_id } => {
+            OutgoingMessage::TTSAudioQualityWarning {
+                issues: warning.issues,
+                clipped_fraction: warning.clipped_fraction,
+                zero_fraction: warning.zero_fraction,
+                rms_dbfs: warning.rms_dbfs,
+                duration_ms: warning.duration_ms,
+                text: warning.text,
+                retrying: warning.retrying,
+                retried: warning.retried,
+                turn_id,
+            }
+        }
+        SessionEvent::TurnDetectionDegraded(degraded) => OutgoingMessage::TurnDetectionDegraded {
+            reason: degraded.reason,
+            inference_ms: degraded.inference_ms,
+            budget_ms: degraded.budget_ms,
+        },
+        SessionEvent::LatencyBudgetExceeded(exceeded) => OutgoingMessage::LatencyBudgetExceeded {
+            turn_id: exceeded.turn_id,
+            stage: exceeded.stage,
+            elapsed_ms: exceeded.elapsed_ms,
+            deadline_ms: exceeded.deadline_ms,
+            mitigated: exceeded.mitigated,
+        },
+        SessionEvent::Consent(record) => OutgoingMessage::Consent {
+            state: record.state,
+            source: record.source,
+            participant: record.participant,
+            timestamp: record.timestamp,
+        },
+        SessionEvent::PipelineRecovered(recovered) => OutgoingMessage::PipelineRecovered {
+            stage: recovered.stage,
+            stalled_ms: recovered.stalled_ms,
+            replayed_bytes: recovered.replayed_bytes,
+            recoveries: recovered.recoveries,
+        },
+        SessionEvent::BargeInSuppressed(suppressed) => OutgoingMessage::BargeInSuppressed {
+            transcript: suppressed.transcript,
+            confidence: suppressed.confidence,
+            interim: suppressed.interim,
+            words: suppressed.words,
+            reason: suppressed.reason,
+        },
+        SessionEvent::AgentError { error, turn_id } => OutgoingMessage::provider_error(
+            sanitizer,
+            error.error_code(),
+            &error.to_string(),
+            turn_id,
+        ),
+        SessionEvent::SpeechStarted { turn_id, timestamp } => {
+            OutgoingMessage::TTSPlaybackStarted { turn_id, timestamp }
+        }
+        SessionEvent::SpeechComplete { timestamp, turn_id } => {
+            debug!(
+                "TTS playback completion event sent at timestamp {}",
+                timestamp
+            );
+            OutgoingMessage::TTSPlaybackComplete { timestamp, turn_id }
+        }
+        SessionEvent::TtsTiming { events, turn_id } => {
+            OutgoingMessage::TTSVisemes { events, turn_id }
+        }
+        SessionEvent::Audio(audio_data) => return EventAction::Audio(audio_data),
+        SessionEvent::AudioLevel(level) => OutgoingMessage::AudioLevel {
+            direction: level.direction,
+            rms_db: level.rms_db,
+            peak_db: level.peak_db,
+        },
+        SessionEvent::AudioCleared => return EventAction::ClearLiveKitAudio,
+        SessionEvent::GreetingPlayed { source, timestamp } => {
+            let (source, asset) = match source {
+                GreetingSource::Text(_) => ("text", None),
+                GreetingSource::Asset(name) => ("asset", Some(name)),
+            };
+            OutgoingMessage::GreetingPlayed {
+                source: source.to_string(),
+                asset,
+                timestamp,
+            }
+        }
+        // WebSocket sessions are always STT + TTS pipelines
+        SessionEvent::RealtimeTranscript(_)
+        | SessionEvent::RealtimeAudio(_)
+        | SessionEvent::RealtimeError(_) => return EventAction::Ignore,
+    };
+
+    EventAction::Send(msg)
+}
+
+/// Encode stage: turn a routed message into a WebSocket frame
+pub fn encode_route(route: MessageRoute) -> Result<Message, serde_json::Error> {
+    match route {
+        MessageRoute::Outgoing(message) => {
+            serde_json::to_string(&message).map(|json_str| Message::Text(json_str.into()))
+        }
+        MessageRoute::Binary(data) => Ok(Message::Binary(data)),
+        MessageRoute::Ping(data) => Ok(Message::Ping(data)),
+        MessageRoute::Close(reason) => Ok(reason.close_message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session::{
+        BargeInSuppressed, BargeInSuppressionReason, ConsentRecord, ConsentSource, ConsentState,
+        LatencyBudgetExceeded, LatencyStage, PipelineRecovered, WatchdogStage,
+    };
+    use crate::core::stt::{STTError, STTResult};
+    use crate::core::tts::{TTSError, TTSTimingEvent};
+    use crate::core::voice_manager::{
+        AudioQualityIssue, TTSAudioQualityWarning, TTSQueuePolicy, TurnDetectionDegraded,
+        TurnDetectionDegradedReason,
+    };
+    use crate::handlers::close::CloseReason;
+
+    #[test]
+    fn test_decode_frame_rejects_oversized_text() {
+        let text = "x".repeat(MAX_TEXT_MESSAGE_SIZE + 1);
+        match decode_frame(Message::Text(text.into()), false) {
+            InboundFrame::Rejected(OutgoingMessage::Error { message }) => {
+                assert!(message.starts_with("Message too large"));
+            }
+            other => panic!("unexpected frame: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_frame_variants() {
+        assert!(matches!(
+            decode_frame(Message::Text(r#"{"type": "clear"}"#.into()), false),
+            InboundFrame::Control(IncomingMessage::Clear)
+        ));
+        assert!(matches!(
+            decode_frame(Message::Text("not json".into()), false),
+            InboundFrame::Rejected(OutgoingMessage::Error { .. })
+        ));
+        assert!(matches!(
+            decode_frame(Message::Binary(Bytes::from_static(b"\x00\x01")), false),
+            InboundFrame::Binary(data) if data.len() == 2
+        ));
+        assert!(matches!(
+            decode_frame(Message::Ping(Bytes::new()), false),
+            InboundFrame::Ignored
+        ));
+        assert!(matches!(
+            decode_frame(Message::Pong(Bytes::from_static(b"\x00\x01")), false),
+            InboundFrame::Pong(data) if data.len() == 2
+        ));
+        assert!(matches!(
+            decode_frame(Message::Close(None), false),
+            InboundFrame::Close
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_strict_config() {
+        let typo = r#"{"type": "config", "audio": false, "livekit": {"room_name": "room", "enable_recordings": true}}"#;
+
+        // Lenient by default: the unknown field is ignored
+        assert!(matches!(
+            decode_frame(Message::Text(typo.into()), false),
+            InboundFrame::Control(IncomingMessage::Config { .. })
+        ));
+
+        // Strict server default
+        match decode_frame(Message::Text(typo.into()), true) {
+            InboundFrame::Rejected(OutgoingMessage::Error { message }) => assert_eq!(
+                message,
+                "Unknown fields in config message: 'livekit.enable_recordings' (did you mean 'enable_recording'?)"
+            ),
+            other => panic!("unexpected frame: {other:?}"),
+        }
+
+        // The session's own setting takes precedence
+        let strict = r#"{"type": "config", "audio": false, "strict_config": true, "audoi": true}"#;
+        assert!(matches!(
+            decode_frame(Message::Text(strict.into()), false),
+            InboundFrame::Rejected(OutgoingMessage::Error { message }) if message.contains("'audoi' (did you mean 'audio'?)")
+        ));
+        let lenient =
+            r#"{"type": "config", "audio": false, "strict_config": false, "audoi": true}"#;
+        assert!(matches!(
+            decode_frame(Message::Text(lenient.into()), true),
+            InboundFrame::Control(_)
+        ));
+
+        // Other messages are not checked
+        assert!(matches!(
+            decode_frame(
+                Message::Text(r#"{"type": "clear", "extra": 1}"#.into()),
+                true
+            ),
+            InboundFrame::Control(IncomingMessage::Clear)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_audio_flag() {
+        assert_eq!(resolve_audio_flag(Some(false), Some(false)), Some(false));
+        assert_eq!(resolve_audio_flag(None, Some(true)), Some(false));
+        assert_eq!(resolve_audio_flag(None, Some(false)), Some(true));
+        assert_eq!(resolve_audio_flag(None, None), None);
+    }
+
+    #[test]
+    fn test_event_action() {
+        let action = event_action(SessionEvent::Transcript(STTResult::new(
+            "hello".to_string(),
+            true,
+            false,
+            0.9,
+        )));
+        assert!(matches!(
+            action,
+            EventAction::Send(OutgoingMessage::STTResult { ref transcript, .. }) if transcript == "hello"
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::AudioCleared),
+            EventAction::ClearLiveKitAudio
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::TtsQueueFull {
+                policy: TTSQueuePolicy::Reject,
+                max_pending: 5,
+                text: "Hello".to_string(),
+            }),
+            EventAction::Send(OutgoingMessage::TTSQueueFull { max_pending: 5, .. })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::PreemptionStarted {
+                text: "This call is recorded.".to_string(),
+                paused: 1,
+            }),
+            EventAction::Send(OutgoingMessage::TTSPreemptionStarted { paused: 1, .. })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::PreemptionEnded { resumed: 1 }),
+            EventAction::Send(OutgoingMessage::TTSPreemptionEnded { resumed: 1 })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::TtsAudioQualityWarning {
+                warning: TTSAudioQualityWarning {
+                    issues: vec![AudioQualityIssue::Clipped],
+                    clipped_fraction: 0.4,
+                    zero_fraction: 0.0,
+                    rms_dbfs: -3.0,
+                    duration_ms: 800,
+                    text: "Hello".to_string(),
+                    retrying: false,
+                    retried: false,
+                },
+                turn_id: Some("turn-1".to_string()),
+            }),
+            EventAction::Send(OutgoingMessage::TTSAudioQualityWarning {
+                duration_ms: 800,
+                turn_id: Some(ref turn_id),
+                ..
+            }) if turn_id == "turn-1"
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::TurnDetectionDegraded(TurnDetectionDegraded {
+                reason: TurnDetectionDegradedReason::ModelUnavailable,
+                inference_ms: None,
+                budget_ms: 200,
+            })),
+            EventAction::Send(OutgoingMessage::TurnDetectionDegraded {
+                reason: TurnDetectionDegradedReason::ModelUnavailable,
+                budget_ms: 200,
+                ..
+            })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::LatencyBudgetExceeded(LatencyBudgetExceeded {
+                turn_id: Some("turn-1".to_string()),
+                stage: LatencyStage::TtsFirstByte,
+                elapsed_ms: 310,
+                deadline_ms: 300,
+                mitigated: true,
+            })),
+            EventAction::Send(OutgoingMessage::LatencyBudgetExceeded {
+                stage: LatencyStage::TtsFirstByte,
+                mitigated: true,
+                ..
+            })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::PipelineRecovered(PipelineRecovered {
+                stage: WatchdogStage::Tts,
+                stalled_ms: 10100,
+                replayed_bytes: 0,
+                recoveries: 2,
+            })),
+            EventAction::Send(OutgoingMessage::PipelineRecovered {
+                stage: WatchdogStage::Tts,
+                recoveries: 2,
+                ..
+            })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::Consent(ConsentRecord {
+                state: ConsentState::Denied,
+                source: Some(ConsentSource::Dtmf),
+                participant: Some("sip_caller".to_string()),
+                timestamp: Some(1700000000000),
+            })),
+            EventAction::Send(OutgoingMessage::Consent {
+                state: ConsentState::Denied,
+                source: Some(ConsentSource::Dtmf),
+                ..
+            })
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::BargeInSuppressed(BargeInSuppressed {
+                transcript: "wait".to_string(),
+                confidence: 0.9,
+                interim: true,
+                words: 1,
+                reason: BargeInSuppressionReason::TooShort,
+            })),
+            EventAction::Send(OutgoingMessage::BargeInSuppressed {
+                words: 1,
+                reason: BargeInSuppressionReason::TooShort,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_session_event_action_keeps_turn_ids() {
+        let mut result = STTResult::new("hello".to_string(), true, true, 0.9);
+        result.turn_id = Some("turn-1".to_string());
+        assert!(matches!(
+            event_action(SessionEvent::Transcript(result)),
+            EventAction::Send(OutgoingMessage::STTResult { turn_id: Some(ref id), .. }) if id == "turn-1"
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::SpeechComplete {
+                timestamp: 1,
+                turn_id: Some("turn-1".to_string()),
+            }),
+            EventAction::Send(OutgoingMessage::TTSPlaybackComplete { turn_id: Some(ref id), .. }) if id == "turn-1"
+        ));
+
+        // Errors are tagged when the turn is known
+        let error = || TTSError::ProviderError("boom".to_string());
+        assert!(matches!(
+            event_action(SessionEvent::TtsError {
+                error: error(),
+                turn_id: Some("turn-2".to_string()),
+            }),
+            EventAction::Send(OutgoingMessage::ProviderError { turn_id: Some(ref id), ref code, .. })
+                if id == "turn-2" && code == "tts_provider_error"
+        ));
+        assert!(matches!(
+            event_action(SessionEvent::TtsError {
+                error: error(),
+                turn_id: None,
+            }),
+            EventAction::Send(OutgoingMessage::ProviderError { turn_id: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_provider_errors_are_sanitized() {
+        let key = "dg_live_9f8e7d6c5b4a39281706";
+        let sanitizer = ErrorSanitizer::new(80).with_secrets([key.to_string()]);
+        let error = STTError::AuthenticationFailed(format!(
+            "401 Unauthorized: invalid credentials for key {key} (Authorization: Token {key})"
+        ));
+
+        match session_event_action(
+            SessionEvent::SttError {
+                error,
+                turn_id: None,
+            },
+            &sanitizer,
+        ) {
+            EventAction::Send(OutgoingMessage::ProviderError { code, message, .. }) => {
+                assert_eq!(code, "stt_authentication_failed");
+                assert!(!message.contains("9f8e7d6c"), "{message}");
+                assert!(message.chars().count() <= 80, "{message}");
+                assert!(message.starts_with("STT streaming error"), "{message}");
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_encode_route() {
+        match encode_route(MessageRoute::Outgoing(OutgoingMessage::Error {
+            message: "boom".to_string(),
+        }))
+        .unwrap()
+        {
+            Message::Text(text) => {
+                assert_eq!(text.as_str(), r#"{"type":"error","message":"boom"}"#)
+            }
+            other => panic!("unexpected frame: {other:?}"),
+        }
+        match encode_route(MessageRoute::Close(CloseReason::AuthFailed)).unwrap() {
+            Message::Close(Some(frame)) => {
+                assert_eq!(frame.code, 4001);
+                assert_eq!(frame.reason.as_str(), "auth_failed");
+            }
+            other => panic!("unexpected frame: {other:?}"),
+        }
+        match encode_route(MessageRoute::Outgoing(OutgoingMessage::close_error(
+            CloseReason::IdleTimeout,
+            "Connection closed due to inactivity",
+        )))
+        .unwrap()
+        {
+            Message::Text(text) => assert_eq!(
+                text.as_str(),
+                r#"{"type":"error","code":"idle_timeout","close_code":4002,"message":"Connection closed due to inactivity"}"#
+            ),
+            other => panic!("unexpected frame: {other:?}"),
+        }
+        match encode_route(MessageRoute::Ping(Bytes::from_static(b"ts"))).unwrap() {
+            Message::Ping(payload) => assert_eq!(payload.as_ref(), b"ts"),
+            other => panic!("unexpected frame: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_tts_timing_is_sent_as_tts_visemes() {
+        let events = vec![
+            TTSTimingEvent::Viseme {
+                offset_ms: 50,
+                viseme_id: 21,
+            },
+            TTSTimingEvent::Word {
+                offset_ms: 50,
+                duration_ms: 320,
+                text: "Hello".to_string(),
+            },
+        ];
+        let EventAction::Send(message) = event_action(SessionEvent::TtsTiming {
+            events,
+            turn_id: Some("turn-1".to_string()),
+        }) else {
+            panic!("timing should be sent");
+        };
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "type": "tts_visemes",
+                "events": [
+                    {"kind": "viseme", "offset_ms": 50, "viseme_id": 21},
+                    {"kind": "word", "offset_ms": 50, "duration_ms": 320, "text": "Hello"}
+                ],
+                "turn_id": "turn-1"
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_heartbeat_answer() {
+        assert!(is_heartbeat_answer(&Message::Pong(Bytes::new())));
+        assert!(is_heartbeat_answer(&Message::Text(
+            r#"{"type":"pong","ts":1700000000000}"#.into()
+        )));
+        assert!(!is_heartbeat_answer(&Message::Text(
+            r#"{"type":"clear"}"#.into()
+        )));
+        assert!(!is_heartbeat_answer(&Message::Ping(Bytes::new())));
+        assert!(!is_heartbeat_answer(&Message::Binary(Bytes::from_static(
+            b"\x00\x01"
+        ))));
+    }
+
+    #[test]
+    fn test_allowed_before_auth() {
+        assert!(is_allowed_before_auth(&IncomingMessage::Auth {
+            token: "token".to_string()
+        }));
+        assert!(is_allowed_before_auth(&IncomingMessage::Pong { ts: 1 }));
+        assert!(!is_allowed_before_auth(&IncomingMessage::Clear));
+    }
+}
@@ -9,10 +9,14 @@ use std::sync::{
 };
 use tokio::sync::RwLock;
 
+use super::heartbeat::ConnectionHeartbeat;
+use super::play_audio::PendingPlayAudio;
 use crate::{
     auth::Auth,
-    core::voice_manager::VoiceManager,
+    core::{redaction::RecordingRedaction, session::Session},
+    flight_recorder::FlightLog,
     livekit::{LiveKitClient, operations::OperationQueue},
+    recording_upload::RecordingUpload,
 };
 
 #[cfg(feature = "dag-routing")]
@@ -25,7 +29,8 @@ use crate::dag::{compiler::CompiledDAG, context::DAGContext, executor::DAGExecut
 /// - RwLock for managers - fast reads, rare writes (only during config)
 /// - Optimized for the common case: many reads, few writes
 pub struct ConnectionState {
-    pub voice_manager: Option<Arc<VoiceManager>>,
+    /// Voice session (providers, turn detection, agent bridge) when audio is enabled
+    pub session: Option<Arc<Session>>,
     pub livekit_client: Option<Arc<RwLock<LiveKitClient>>>,
     /// Operation queue for non-blocking LiveKit operations
     pub livekit_operation_queue: Option<OperationQueue>,
@@ -41,6 +46,20 @@ pub struct ConnectionState {
     pub recording_egress_id: Option<String>,
     /// Auth context for this connection (used for room name normalization)
     pub auth: Auth,
+    /// `play_audio` transfer collecting binary frames (binary frames bypass STT while set)
+    pub pending_play_audio: Option<PendingPlayAudio>,
+    /// Total `play_audio` bytes accepted on this connection
+    pub play_audio_bytes: usize,
+    /// Recording of the caller audio (if recording upload is configured)
+    pub input_recording: Option<RecordingUpload>,
+    /// Heartbeat pings and round-trip times (set by the connection handler)
+    pub heartbeat: Option<Arc<ConnectionHeartbeat>>,
+    /// Redaction windows of this session's recordings
+    pub redaction: Arc<RecordingRedaction>,
+    /// LiveKit tracks muted for the open redaction window (participant identity, track SID)
+    pub redaction_muted_tracks: Vec<(String, String)>,
+    /// Flight log of the connection (if the flight recorder is configured)
+    pub flight_log: Option<Arc<FlightLog>>,
 
     // DAG routing state (feature-gated)
     /// Compiled DAG for this connection
@@ -66,7 +85,7 @@ impl Default for ConnectionState {
 impl ConnectionState {
     pub fn new() -> Self {
         Self {
-            voice_manager: None,
+            session: None,
             livekit_client: None,
             livekit_operation_queue: None,
             audio_enabled: AtomicBool::new(false),
@@ -75,6 +94,13 @@ impl ConnectionState {
             livekit_local_identity: None,
             recording_egress_id: None,
             auth: Auth::empty(),
+            pending_play_audio: None,
+            play_audio_bytes: 0,
+            input_recording: None,
+            heartbeat: None,
+            redaction: Arc::new(RecordingRedaction::new()),
+            redaction_muted_tracks: Vec::new(),
+            flight_log: None,
             #[cfg(feature = "dag-routing")]
             compiled_dag: None,
             #[cfg(feature = "dag-routing")]
@@ -89,7 +115,7 @@ impl ConnectionState {
     /// Create a new ConnectionState with the given Auth context
     pub fn with_auth(auth: Auth) -> Self {
         Self {
-            voice_manager: None,
+            session: None,
             livekit_client: None,
             livekit_operation_queue: None,
             audio_enabled: AtomicBool::new(false),
@@ -98,6 +124,13 @@ impl ConnectionState {
             livekit_local_identity: None,
             recording_egress_id: None,
             auth,
+            pending_play_audio: None,
+            play_audio_bytes: 0,
+            input_recording: None,
+            heartbeat: None,
+            redaction: Arc::new(RecordingRedaction::new()),
+            redaction_muted_tracks: Vec::new(),
+            flight_log: None,
             #[cfg(feature = "dag-routing")]
             compiled_dag: None,
             #[cfg(feature = "dag-routing")]
@@ -119,6 +152,11 @@ impl ConnectionState {
         self.audio_enabled.store(enabled, Ordering::Relaxed);
     }
 
+    /// Check if binary frames belong to a pending `play_audio` transfer
+    pub fn is_play_audio_pending(&self) -> bool {
+        self.pending_play_audio.is_some()
+    }
+
     /// Check if DAG routing is enabled
     #[cfg(feature = "dag-routing")]
     pub fn is_dag_enabled(&self) -> bool {
@@ -146,7 +184,7 @@ mod tests {
     fn test_connection_state_new() {
         let state = ConnectionState::new();
         assert!(state.stream_id.is_none());
-        assert!(state.voice_manager.is_none());
+        assert!(state.session.is_none());
         assert!(state.livekit_client.is_none());
     }
 
@@ -1,6 +1,8 @@
 use base64::{Engine as _, engine::general_purpose};
 use bytes::Bytes;
 
+use crate::core::stt::TranscriptTiming;
+
 use super::config::{LiveKitWebSocketConfig, STTWebSocketConfig, TTSWebSocketConfig};
 use super::messages::{
     IncomingMessage, MessageRoute, OutgoingMessage, ParticipantDisconnectedInfo, UnifiedMessage,
@@ -18,6 +20,18 @@ fn test_ws_config_serialization() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "nova-3".to_string(),
+        allow_unknown_models: false,
+        adaptive_endpointing: None,
+        barge_in: None,
+        barge_in_confirmation: None,
+        echo_guard: None,
+        formatting: None,
+        latency_budget: None,
+        watchdog: None,
+        consent: None,
+        retry_budget: None,
+        failover: None,
+        routing: None,
     };
 
     let json = serde_json::to_string(&stt_ws_config).unwrap();
@@ -35,12 +49,21 @@ fn test_ws_config_serialization() {
         sample_rate: Some(22050),
         connection_timeout: Some(30),
         request_timeout: Some(60),
+        utterance_timeout: None,
         model: "".to_string(), // Model is in Voice ID for Deepgram
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let json = serde_json::to_string(&tts_ws_config).unwrap();
@@ -65,6 +88,18 @@ fn test_incoming_message_serialization() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "nova-3".to_string(),
+            allow_unknown_models: false,
+            adaptive_endpointing: None,
+            barge_in: None,
+            barge_in_confirmation: None,
+            echo_guard: None,
+            formatting: None,
+            latency_budget: None,
+            watchdog: None,
+            consent: None,
+            retry_budget: None,
+            failover: None,
+            routing: None,
         }),
         tts_config: Some(TTSWebSocketConfig {
             api_key: None,
@@ -75,14 +110,34 @@ fn test_incoming_message_serialization() {
             sample_rate: Some(22050),
             connection_timeout: Some(30),
             request_timeout: Some(60),
+            utterance_timeout: None,
             model: "".to_string(), // Model is in Voice ID for Deepgram
+            allow_unknown_models: false,
             pronunciations: Vec::new(),
             emotion: None,
             emotion_intensity: None,
             delivery_style: None,
             emotion_description: None,
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
+            dedupe_partials: None,
+            audio_quality: None,
+            filler: None,
+            voice_profile: None,
         }),
         livekit: None,
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
@@ -94,6 +149,8 @@ fn test_incoming_message_serialization() {
         text: "Hello world".to_string(),
         flush: Some(true),
         allow_interruption: Some(true),
+        turn_id: None,
+        priority: None,
     };
 
     let json = serde_json::to_string(&speak_msg).unwrap();
@@ -106,6 +163,8 @@ fn test_incoming_message_serialization() {
         text: "Hello world".to_string(),
         flush: None,
         allow_interruption: None,
+        turn_id: None,
+        priority: None,
     };
 
     let json = serde_json::to_string(&speak_msg_no_flush).unwrap();
@@ -121,6 +180,7 @@ fn test_incoming_message_serialization() {
         text,
         flush,
         allow_interruption,
+        ..
     } = parsed
     {
         assert_eq!(text, "Hello world");
@@ -135,6 +195,8 @@ fn test_incoming_message_serialization() {
         text: "Do not interrupt me".to_string(),
         flush: Some(true),
         allow_interruption: Some(false),
+        turn_id: None,
+        priority: None,
     };
 
     let json = serde_json::to_string(&speak_msg_no_interruption).unwrap();
@@ -149,6 +211,7 @@ fn test_incoming_message_serialization() {
         text,
         flush,
         allow_interruption,
+        ..
     } = parsed
     {
         assert_eq!(text, "Hello");
@@ -289,6 +352,7 @@ fn test_outgoing_message_serialization() {
         livekit_url: None,
         waav_participant_identity: None,
         waav_participant_name: None,
+        feature_flags: Default::default(),
     };
     let json = serde_json::to_string(&ready_msg).unwrap();
     assert!(json.contains("\"type\":\"ready\""));
@@ -300,6 +364,7 @@ fn test_outgoing_message_serialization() {
         livekit_url: Some("ws://localhost:7880".to_string()),
         waav_participant_identity: Some("waav-ai".to_string()),
         waav_participant_name: Some("WaaV AI".to_string()),
+        feature_flags: Default::default(),
     };
     let json_with_livekit = serde_json::to_string(&ready_msg_with_livekit).unwrap();
     assert!(json_with_livekit.contains("\"type\":\"ready\""));
@@ -308,18 +373,59 @@ fn test_outgoing_message_serialization() {
     assert!(json_with_livekit.contains("\"waav_participant_identity\":\"waav-ai\""));
     assert!(json_with_livekit.contains("\"waav_participant_name\":\"WaaV AI\""));
 
+    // Test VAD messages
+    let speech_started = OutgoingMessage::SpeechStarted {
+        timestamp: Some(9.54),
+    };
+    assert_eq!(
+        serde_json::to_string(&speech_started).unwrap(),
+        r#"{"type":"speech_started","timestamp":9.54}"#
+    );
+    let utterance_end = OutgoingMessage::UtteranceEnd {
+        last_word_end: None,
+    };
+    assert_eq!(
+        serde_json::to_string(&utterance_end).unwrap(),
+        r#"{"type":"utterance_end"}"#
+    );
+
     // Test STT result message
     let stt_msg = OutgoingMessage::STTResult {
         transcript: "Hello world".to_string(),
         is_final: true,
         is_speech_final: true,
         confidence: 0.95,
+        redactions: Vec::new(),
+        timing: None,
+        turn_id: None,
     };
 
     let json = serde_json::to_string(&stt_msg).unwrap();
     assert!(json.contains("\"type\":\"stt_result\""));
     assert!(json.contains("Hello world"));
     assert!(json.contains("0.95"));
+    assert!(!json.contains("timing"));
+
+    let timed_msg = OutgoingMessage::STTResult {
+        transcript: "Hello world".to_string(),
+        is_final: true,
+        is_speech_final: true,
+        confidence: 0.95,
+        redactions: Vec::new(),
+        timing: Some(TranscriptTiming {
+            start: 1.5,
+            end: 2.25,
+            start_utc_ms: 1_700_000_001_500,
+            end_utc_ms: 1_700_000_002_250,
+            words: Vec::new(),
+        }),
+        turn_id: Some("turn-1".to_string()),
+    };
+    let json: serde_json::Value = serde_json::to_value(&timed_msg).unwrap();
+    assert_eq!(json["timing"]["start"], 1.5);
+    assert_eq!(json["timing"]["end_utc_ms"], 1_700_000_002_250_u64);
+    assert!(json["timing"].get("words").is_none());
+    assert_eq!(json["turn_id"], "turn-1");
 
     // Test error message
     let error_msg = OutgoingMessage::Error {
@@ -355,6 +461,18 @@ fn test_stt_ws_config_conversion() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "nova-3".to_string(),
+        allow_unknown_models: false,
+        adaptive_endpointing: None,
+        barge_in: None,
+        barge_in_confirmation: None,
+        echo_guard: None,
+        formatting: None,
+        latency_budget: None,
+        watchdog: None,
+        consent: None,
+        retry_budget: None,
+        failover: None,
+        routing: None,
     };
 
     let api_key = "test_api_key".to_string();
@@ -368,6 +486,64 @@ fn test_stt_ws_config_conversion() {
     assert!(stt_config.punctuation);
 }
 
+#[test]
+fn test_stt_ws_config_failover_conversion() {
+    let stt_ws_config: STTWebSocketConfig = serde_json::from_value(serde_json::json!({
+        "provider": "deepgram",
+        "language": "en-US",
+        "sample_rate": 8000,
+        "channels": 1,
+        "punctuation": true,
+        "encoding": "mulaw",
+        "model": "nova-3",
+        "failover": {"provider": "assemblyai", "warm_standby": true}
+    }))
+    .unwrap();
+
+    let failover = stt_ws_config.failover.as_ref().unwrap();
+    assert!(failover.api_key.is_none());
+    let stt_config = stt_ws_config.to_stt_config("primary_key".to_string());
+    let failover = failover.to_failover_config(&stt_config, "secondary_key".to_string());
+
+    assert!(failover.warm_standby);
+    let secondary = failover.secondary;
+    assert_eq!(secondary.provider, "assemblyai");
+    assert_eq!(secondary.api_key, "secondary_key");
+    assert_eq!(secondary.model, "");
+    // Same audio as the primary
+    assert_eq!(secondary.language, "en-US");
+    assert_eq!(secondary.sample_rate, 8000);
+    assert_eq!(secondary.encoding, "mulaw");
+}
+
+#[test]
+fn test_stt_ws_config_routing_conversion() {
+    let stt_ws_config: STTWebSocketConfig = serde_json::from_value(serde_json::json!({
+        "provider": "deepgram",
+        "language": "en-US",
+        "sample_rate": 8000,
+        "channels": 1,
+        "punctuation": true,
+        "encoding": "mulaw",
+        "model": "nova-3",
+        "routing": {"provider": "deepgram", "model": "base", "api_key": "fast_key"}
+    }))
+    .unwrap();
+
+    let routing = stt_ws_config.routing.as_ref().unwrap();
+    let stt_config = stt_ws_config.to_stt_config("primary_key".to_string());
+    let fast = routing
+        .to_routing_config(&stt_config, routing.api_key.clone().unwrap())
+        .fast;
+
+    assert_eq!(fast.provider, "deepgram");
+    assert_eq!(fast.api_key, "fast_key");
+    assert_eq!(fast.model, "base");
+    // Same audio as the primary
+    assert_eq!(fast.sample_rate, 8000);
+    assert_eq!(fast.encoding, "mulaw");
+}
+
 #[test]
 fn test_tts_ws_config_conversion_with_all_values() {
     let tts_ws_config = TTSWebSocketConfig {
@@ -379,12 +555,21 @@ fn test_tts_ws_config_conversion_with_all_values() {
         sample_rate: Some(22050),
         connection_timeout: Some(60),
         request_timeout: Some(120),
+        utterance_timeout: None,
         model: "".to_string(), // Model is in Voice ID for Deepgram
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let api_key = "test_api_key".to_string();
@@ -411,12 +596,21 @@ fn test_tts_ws_config_conversion_with_defaults() {
         sample_rate: None,
         connection_timeout: None,
         request_timeout: None,
+        utterance_timeout: None,
         model: "".to_string(), // Model is in Voice ID for Deepgram
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let api_key = "test_api_key".to_string();
@@ -442,6 +636,9 @@ fn test_livekit_ws_config_serialization() {
         waav_participant_identity: Some("waav-ai".to_string()),
         waav_participant_name: Some("WaaV AI".to_string()),
         listen_participants: vec![],
+        audio_frame_ms: None,
+        audio_low_watermark_ms: None,
+        outbound_call: false,
     };
 
     let json = serde_json::to_string(&livekit_config).unwrap();
@@ -461,6 +658,9 @@ fn test_livekit_ws_config_conversion() {
         waav_participant_identity: None,
         waav_participant_name: None,
         listen_participants: vec![],
+        audio_frame_ms: None,
+        audio_low_watermark_ms: None,
+        outbound_call: false,
     };
 
     let tts_ws_config = TTSWebSocketConfig {
@@ -472,12 +672,21 @@ fn test_livekit_ws_config_conversion() {
         sample_rate: Some(22050),
         connection_timeout: Some(30),
         request_timeout: Some(60),
+        utterance_timeout: None,
         model: "".to_string(),
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let livekit_url = "wss://test-livekit.com".to_string();
@@ -503,6 +712,9 @@ fn test_livekit_config_with_empty_listen_participants() {
         waav_participant_identity: None,
         waav_participant_name: None,
         listen_participants: vec![],
+        audio_frame_ms: None,
+        audio_low_watermark_ms: None,
+        outbound_call: false,
     };
 
     let tts_ws_config = TTSWebSocketConfig {
@@ -514,12 +726,21 @@ fn test_livekit_config_with_empty_listen_participants() {
         sample_rate: Some(22050),
         connection_timeout: Some(30),
         request_timeout: Some(60),
+        utterance_timeout: None,
         model: "".to_string(),
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let livekit_config = livekit_ws_config.to_livekit_config(
@@ -542,6 +763,9 @@ fn test_livekit_config_with_listen_participants() {
         waav_participant_identity: None,
         waav_participant_name: None,
         listen_participants: vec!["user-123".to_string(), "user-456".to_string()],
+        audio_frame_ms: None,
+        audio_low_watermark_ms: None,
+        outbound_call: false,
     };
 
     let tts_ws_config = TTSWebSocketConfig {
@@ -553,12 +777,21 @@ fn test_livekit_config_with_listen_participants() {
         sample_rate: Some(22050),
         connection_timeout: Some(30),
         request_timeout: Some(60),
+        utterance_timeout: None,
         model: "".to_string(),
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let livekit_config = livekit_ws_config.to_livekit_config(
@@ -592,6 +825,9 @@ fn test_livekit_ws_config_serialization_with_listen_participants() {
         waav_participant_identity: None,
         waav_participant_name: None,
         listen_participants: vec!["user-1".to_string(), "user-2".to_string()],
+        audio_frame_ms: None,
+        audio_low_watermark_ms: None,
+        outbound_call: false,
     };
 
     let json = serde_json::to_string(&config).unwrap();
@@ -606,6 +842,9 @@ fn test_livekit_ws_config_serialization_omits_empty_listen_participants() {
         waav_participant_identity: None,
         waav_participant_name: None,
         listen_participants: vec![],
+        audio_frame_ms: None,
+        audio_low_watermark_ms: None,
+        outbound_call: false,
     };
 
     let json = serde_json::to_string(&config).unwrap();
@@ -627,6 +866,24 @@ fn test_livekit_ws_config_deserialization_with_listen_participants() {
     assert!(config.listen_participants.contains(&"user-2".to_string()));
 }
 
+#[test]
+fn test_livekit_ws_config_output_pacing() {
+    let json = r#"{
+        "room_name": "test-room",
+        "audio_frame_ms": 40
+    }"#;
+
+    let config: LiveKitWebSocketConfig = serde_json::from_str(json).unwrap();
+    let pacing = config.output_pacing();
+    assert_eq!(pacing.frame_duration_ms, 40);
+    // Unset watermark falls back to the default
+    assert_eq!(pacing.low_watermark_ms, 120);
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains("\"audio_frame_ms\":40"));
+    assert!(!json.contains("audio_low_watermark_ms"));
+}
+
 #[test]
 fn test_livekit_ws_config_deserialization_without_listen_participants() {
     let json = r#"{
@@ -669,6 +926,18 @@ fn test_incoming_message_config_with_livekit() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "nova-3".to_string(),
+            allow_unknown_models: false,
+            adaptive_endpointing: None,
+            barge_in: None,
+            barge_in_confirmation: None,
+            echo_guard: None,
+            formatting: None,
+            latency_budget: None,
+            watchdog: None,
+            consent: None,
+            retry_budget: None,
+            failover: None,
+            routing: None,
         }),
         tts_config: Some(TTSWebSocketConfig {
             api_key: None,
@@ -679,12 +948,21 @@ fn test_incoming_message_config_with_livekit() {
             sample_rate: Some(22050),
             connection_timeout: Some(30),
             request_timeout: Some(60),
+            utterance_timeout: None,
             model: "".to_string(),
+            allow_unknown_models: false,
             pronunciations: Vec::new(),
             emotion: None,
             emotion_intensity: None,
             delivery_style: None,
             emotion_description: None,
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
+            dedupe_partials: None,
+            audio_quality: None,
+            filler: None,
+            voice_profile: None,
         }),
         livekit: Some(LiveKitWebSocketConfig {
             room_name: "test-room".to_string(),
@@ -692,7 +970,21 @@ fn test_incoming_message_config_with_livekit() {
             waav_participant_identity: None,
             waav_participant_name: None,
             listen_participants: vec![],
+            audio_frame_ms: None,
+            audio_low_watermark_ms: None,
+            outbound_call: false,
         }),
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
@@ -716,6 +1008,18 @@ fn test_incoming_message_config_without_livekit() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "nova-3".to_string(),
+            allow_unknown_models: false,
+            adaptive_endpointing: None,
+            barge_in: None,
+            barge_in_confirmation: None,
+            echo_guard: None,
+            formatting: None,
+            latency_budget: None,
+            watchdog: None,
+            consent: None,
+            retry_budget: None,
+            failover: None,
+            routing: None,
         }),
         tts_config: Some(TTSWebSocketConfig {
             api_key: None,
@@ -726,14 +1030,34 @@ fn test_incoming_message_config_without_livekit() {
             sample_rate: Some(22050),
             connection_timeout: Some(30),
             request_timeout: Some(60),
+            utterance_timeout: None,
             model: "".to_string(),
+            allow_unknown_models: false,
             pronunciations: Vec::new(),
             emotion: None,
             emotion_intensity: None,
             delivery_style: None,
             emotion_description: None,
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
+            dedupe_partials: None,
+            audio_quality: None,
+            filler: None,
+            voice_profile: None,
         }),
         livekit: None,
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
@@ -989,12 +1313,21 @@ fn test_tts_ws_config_conversion_mixed_values() {
         sample_rate: None, // Should use default
         connection_timeout: Some(45),
         request_timeout: None, // Should use default
+        utterance_timeout: None,
         model: "".to_string(), // Model is in Voice ID for Deepgram
+        allow_unknown_models: false,
         pronunciations: Vec::new(),
         emotion: None,
         emotion_intensity: None,
         delivery_style: None,
         emotion_description: None,
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
+        dedupe_partials: None,
+        audio_quality: None,
+        filler: None,
+        voice_profile: None,
     };
 
     let api_key = "test_api_key".to_string();
@@ -1010,6 +1343,90 @@ fn test_tts_ws_config_conversion_mixed_values() {
     assert_eq!(tts_config.request_timeout, Some(60)); // Default
 }
 
+#[test]
+fn test_tts_ws_config_output_profile() {
+    let tts_ws_config: TTSWebSocketConfig = serde_json::from_str(
+        r#"{"provider": "elevenlabs", "model": "", "output_profile": "telephony"}"#,
+    )
+    .unwrap();
+    let tts_config = tts_ws_config.to_tts_config("key".to_string());
+    assert!(tts_config.is_telephony());
+
+    let tts_ws_config: TTSWebSocketConfig =
+        serde_json::from_str(r#"{"provider": "elevenlabs", "model": ""}"#).unwrap();
+    assert!(
+        !tts_ws_config
+            .to_tts_config("key".to_string())
+            .is_telephony()
+    );
+
+    let invalid = serde_json::from_str::<TTSWebSocketConfig>(
+        r#"{"provider": "elevenlabs", "model": "", "output_profile": "pstn"}"#,
+    );
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_tts_ws_config_voice_profile_follows_provider() {
+    let profiles: std::collections::BTreeMap<String, crate::config::VoiceProfile> =
+        serde_yaml::from_str(
+            r#"
+warm:
+  voices:
+    elevenlabs:
+      voice_id: "21m00Tcm4TlvDq8ikWAM"
+      model: eleven_flash_v2_5
+    azure:
+      voice_id: en-US-JennyNeural
+      speaking_rate: 1.1
+"#,
+        )
+        .unwrap();
+    let resolve = |json: &str| {
+        let tts_ws_config: TTSWebSocketConfig = serde_json::from_str(json).unwrap();
+        let mut tts_config = tts_ws_config.to_tts_config("key".to_string());
+        tts_ws_config
+            .apply_voice_profile(&profiles, &mut tts_config)
+            .map(|()| tts_config)
+    };
+
+    // The same profile picks each provider's own voice
+    let tts_config =
+        resolve(r#"{"provider": "elevenlabs", "model": "", "voice_profile": "warm"}"#).unwrap();
+    assert_eq!(tts_config.voice_id.as_deref(), Some("21m00Tcm4TlvDq8ikWAM"));
+    assert_eq!(tts_config.model, "eleven_flash_v2_5");
+    assert_eq!(tts_config.speaking_rate, Some(1.0));
+
+    let tts_config =
+        resolve(r#"{"provider": "microsoft-azure", "model": "", "voice_profile": "warm"}"#)
+            .unwrap();
+    assert_eq!(tts_config.voice_id.as_deref(), Some("en-US-JennyNeural"));
+    assert_eq!(tts_config.speaking_rate, Some(1.1));
+
+    // The client's speaking rate wins over the profile's
+    let tts_config = resolve(
+        r#"{"provider": "azure", "model": "", "voice_profile": "warm", "speaking_rate": 0.9}"#,
+    )
+    .unwrap();
+    assert_eq!(tts_config.speaking_rate, Some(0.9));
+
+    let issue =
+        resolve(r#"{"provider": "deepgram", "model": "", "voice_profile": "warm"}"#).unwrap_err();
+    assert_eq!(issue.field, "tts_config.voice_profile");
+    assert!(issue.message.contains("no voice for provider 'deepgram'"));
+
+    let issue = resolve(
+        r#"{"provider": "elevenlabs", "model": "", "voice_profile": "warm", "voice_id": "adam"}"#,
+    )
+    .unwrap_err();
+    assert!(issue.message.contains("voice_id"));
+
+    // Without a profile the config is left alone
+    let tts_config =
+        resolve(r#"{"provider": "deepgram", "model": "", "voice_id": "aura-luna-en"}"#).unwrap();
+    assert_eq!(tts_config.voice_id.as_deref(), Some("aura-luna-en"));
+}
+
 #[test]
 fn test_config_message_without_livekit_routing() {
     // Test that configuration without LiveKit creates proper routing logic
@@ -1026,6 +1443,18 @@ fn test_config_message_without_livekit_routing() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "nova-3".to_string(),
+            allow_unknown_models: false,
+            adaptive_endpointing: None,
+            barge_in: None,
+            barge_in_confirmation: None,
+            echo_guard: None,
+            formatting: None,
+            latency_budget: None,
+            watchdog: None,
+            consent: None,
+            retry_budget: None,
+            failover: None,
+            routing: None,
         }),
         tts_config: Some(TTSWebSocketConfig {
             api_key: None,
@@ -1036,14 +1465,34 @@ fn test_config_message_without_livekit_routing() {
             sample_rate: Some(22050),
             connection_timeout: Some(30),
             request_timeout: Some(60),
+            utterance_timeout: None,
             model: "".to_string(),
+            allow_unknown_models: false,
             pronunciations: Vec::new(),
             emotion: None,
             emotion_intensity: None,
             delivery_style: None,
             emotion_description: None,
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
+            dedupe_partials: None,
+            audio_quality: None,
+            filler: None,
+            voice_profile: None,
         }),
         livekit: None, // No LiveKit configuration
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
@@ -1078,6 +1527,18 @@ fn test_config_message_with_livekit_routing() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "nova-3".to_string(),
+            allow_unknown_models: false,
+            adaptive_endpointing: None,
+            barge_in: None,
+            barge_in_confirmation: None,
+            echo_guard: None,
+            formatting: None,
+            latency_budget: None,
+            watchdog: None,
+            consent: None,
+            retry_budget: None,
+            failover: None,
+            routing: None,
         }),
         tts_config: Some(TTSWebSocketConfig {
             api_key: None,
@@ -1088,12 +1549,21 @@ fn test_config_message_with_livekit_routing() {
             sample_rate: Some(22050),
             connection_timeout: Some(30),
             request_timeout: Some(60),
+            utterance_timeout: None,
             model: "".to_string(),
+            allow_unknown_models: false,
             pronunciations: Vec::new(),
             emotion: None,
             emotion_intensity: None,
             delivery_style: None,
             emotion_description: None,
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
+            dedupe_partials: None,
+            audio_quality: None,
+            filler: None,
+            voice_profile: None,
         }),
         livekit: Some(LiveKitWebSocketConfig {
             room_name: "test-room".to_string(),
@@ -1101,7 +1571,21 @@ fn test_config_message_with_livekit_routing() {
             waav_participant_identity: None,
             waav_participant_name: None,
             listen_participants: vec![],
+            audio_frame_ms: None,
+            audio_low_watermark_ms: None,
+            outbound_call: false,
         }),
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
@@ -1289,7 +1773,21 @@ fn test_config_message_audio_disabled() {
             waav_participant_identity: None,
             waav_participant_name: None,
             listen_participants: vec![],
+            audio_frame_ms: None,
+            audio_low_watermark_ms: None,
+            outbound_call: false,
         }),
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
@@ -1337,6 +1835,18 @@ fn test_config_message_audio_default() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "nova-3".to_string(),
+            allow_unknown_models: false,
+            adaptive_endpointing: None,
+            barge_in: None,
+            barge_in_confirmation: None,
+            echo_guard: None,
+            formatting: None,
+            latency_budget: None,
+            watchdog: None,
+            consent: None,
+            retry_budget: None,
+            failover: None,
+            routing: None,
         }),
         tts_config: Some(TTSWebSocketConfig {
             api_key: None,
@@ -1347,14 +1857,34 @@ fn test_config_message_audio_default() {
             sample_rate: Some(22050),
             connection_timeout: Some(30),
             request_timeout: Some(60),
+            utterance_timeout: None,
             model: "".to_string(),
+            allow_unknown_models: false,
             pronunciations: Vec::new(),
             emotion: None,
             emotion_intensity: None,
             delivery_style: None,
             emotion_description: None,
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
+            dedupe_partials: None,
+            audio_quality: None,
+            filler: None,
+            voice_profile: None,
         }),
         livekit: None,
+        dag_config: None,
+        agent_config: None,
+        metadata: None,
+        greeting: None,
+        audio_levels: None,
+        heartbeat: None,
+        strict_config: None,
+        agent: None,
+        overrides: None,
+        dry_run: None,
+        flight_recorder: None,
     };
 
     let json = serde_json::to_string(&config_msg).unwrap();
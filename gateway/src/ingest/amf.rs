@@ -0,0 +1,282 @@
+//! AMF0 encoding of RTMP command messages
+//!
+//! Only the types publishers send in `connect`, `createStream`, `publish`
+//! and metadata messages are decoded; the rest are rejected.
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use super::IngestError;
+
+const NUMBER: u8 = 0x00;
+const BOOLEAN: u8 = 0x01;
+const STRING: u8 = 0x02;
+const OBJECT: u8 = 0x03;
+const NULL: u8 = 0x05;
+const UNDEFINED: u8 = 0x06;
+const ECMA_ARRAY: u8 = 0x08;
+const OBJECT_END: u8 = 0x09;
+const STRICT_ARRAY: u8 = 0x0A;
+const DATE: u8 = 0x0B;
+const LONG_STRING: u8 = 0x0C;
+
+/// Deepest nesting of objects and arrays accepted
+const MAX_DEPTH: usize = 16;
+
+/// A decoded AMF0 value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Amf0Value {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    /// Objects and ECMA arrays, in wire order
+    Object(Vec<(String, Amf0Value)>),
+    Array(Vec<Amf0Value>),
+    Null,
+    Undefined,
+}
+
+impl Amf0Value {
+    /// The string, if this is one
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The number, if this is one
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Build an object from `(key, value)` pairs
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Amf0Value)>) -> Self {
+        Self::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// Append the encoded value to `out`
+    pub fn encode(&self, out: &mut BytesMut) {
+        match self {
+            Self::Number(n) => {
+                out.put_u8(NUMBER);
+                out.put_f64(*n);
+            }
+            Self::Boolean(b) => {
+                out.put_u8(BOOLEAN);
+                out.put_u8(u8::from(*b));
+            }
+            Self::String(s) => {
+                if s.len() > u16::MAX as usize {
+                    out.put_u8(LONG_STRING);
+                    out.put_u32(s.len() as u32);
+                } else {
+                    out.put_u8(STRING);
+                    out.put_u16(s.len() as u16);
+                }
+                out.put_slice(s.as_bytes());
+            }
+            Self::Object(fields) => {
+                out.put_u8(OBJECT);
+                for (key, value) in fields {
+                    out.put_u16(key.len() as u16);
+                    out.put_slice(key.as_bytes());
+                    value.encode(out);
+                }
+                out.put_u16(0);
+                out.put_u8(OBJECT_END);
+            }
+            Self::Array(items) => {
+                out.put_u8(STRICT_ARRAY);
+                out.put_u32(items.len() as u32);
+                for item in items {
+                    item.encode(out);
+                }
+            }
+            Self::Null => out.put_u8(NULL),
+            Self::Undefined => out.put_u8(UNDEFINED),
+        }
+    }
+}
+
+/// Decode every value in a command or data message payload
+pub fn decode_all(mut payload: &[u8]) -> Result<Vec<Amf0Value>, IngestError> {
+    let mut values = Vec::new();
+    while payload.has_remaining() {
+        values.push(decode_value(&mut payload, 0)?);
+    }
+    Ok(values)
+}
+
+/// Encode `values` back to back, as a command message payload
+pub fn encode_all(values: &[Amf0Value]) -> BytesMut {
+    let mut out = BytesMut::new();
+    for value in values {
+        value.encode(&mut out);
+    }
+    out
+}
+
+fn decode_value(buf: &mut &[u8], depth: usize) -> Result<Amf0Value, IngestError> {
+    if depth > MAX_DEPTH {
+        return Err(malformed("values nested too deeply"));
+    }
+    let marker = take_u8(buf)?;
+    match marker {
+        NUMBER => {
+            need(buf, 8)?;
+            Ok(Amf0Value::Number(buf.get_f64()))
+        }
+        BOOLEAN => Ok(Amf0Value::Boolean(take_u8(buf)? != 0)),
+        STRING => {
+            need(buf, 2)?;
+            let len = buf.get_u16() as usize;
+            Ok(Amf0Value::String(take_string(buf, len)?))
+        }
+        LONG_STRING => {
+            need(buf, 4)?;
+            let len = buf.get_u32() as usize;
+            Ok(Amf0Value::String(take_string(buf, len)?))
+        }
+        OBJECT => decode_properties(buf, depth).map(Amf0Value::Object),
+        ECMA_ARRAY => {
+            // The count is advisory; the properties end with an end marker
+            need(buf, 4)?;
+            buf.advance(4);
+            decode_properties(buf, depth).map(Amf0Value::Object)
+        }
+        STRICT_ARRAY => {
+            need(buf, 4)?;
+            let count = buf.get_u32() as usize;
+            // Every value takes at least one byte
+            if count > buf.remaining() {
+                return Err(malformed("array longer than its message"));
+            }
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(decode_value(buf, depth + 1)?);
+            }
+            Ok(Amf0Value::Array(items))
+        }
+        DATE => {
+            // Milliseconds since the epoch followed by an unused time zone
+            need(buf, 10)?;
+            let millis = buf.get_f64();
+            buf.advance(2);
+            Ok(Amf0Value::Number(millis))
+        }
+        NULL => Ok(Amf0Value::Null),
+        UNDEFINED => Ok(Amf0Value::Undefined),
+        other => Err(malformed(&format!("unsupported AMF0 type 0x{other:02x}"))),
+    }
+}
+
+fn decode_properties(
+    buf: &mut &[u8],
+    depth: usize,
+) -> Result<Vec<(String, Amf0Value)>, IngestError> {
+    let mut fields = Vec::new();
+    loop {
+        need(buf, 2)?;
+        let len = buf.get_u16() as usize;
+        if len == 0 {
+            if take_u8(buf)? != OBJECT_END {
+                return Err(malformed("object key is empty"));
+            }
+            return Ok(fields);
+        }
+        let key = take_string(buf, len)?;
+        let value = decode_value(buf, depth + 1)?;
+        fields.push((key, value));
+    }
+}
+
+fn take_u8(buf: &mut &[u8]) -> Result<u8, IngestError> {
+    need(buf, 1)?;
+    Ok(buf.get_u8())
+}
+
+fn take_string(buf: &mut &[u8], len: usize) -> Result<String, IngestError> {
+    need(buf, len)?;
+    let s = String::from_utf8_lossy(&buf[..len]).into_owned();
+    buf.advance(len);
+    Ok(s)
+}
+
+fn need(buf: &[u8], len: usize) -> Result<(), IngestError> {
+    if buf.len() < len {
+        return Err(malformed("value cut short"));
+    }
+    Ok(())
+}
+
+fn malformed(reason: &str) -> IngestError {
+    IngestError::Protocol(format!("malformed AMF0: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_round_trip() {
+        let values = vec![
+            Amf0Value::String("connect".to_string()),
+            Amf0Value::Number(1.0),
+            Amf0Value::object([
+                ("app", Amf0Value::String("live".to_string())),
+                ("fpad", Amf0Value::Boolean(false)),
+                ("audioCodecs", Amf0Value::Number(3575.0)),
+            ]),
+            Amf0Value::Null,
+            Amf0Value::Array(vec![Amf0Value::Undefined]),
+        ];
+        let encoded = encode_all(&values);
+        assert_eq!(decode_all(&encoded).unwrap(), values);
+    }
+
+    #[test]
+    fn test_ecma_array_decodes_as_object() {
+        // onMetaData as ffmpeg sends it: ECMA array with a count of 1
+        let mut payload = vec![ECMA_ARRAY, 0, 0, 0, 1, 0, 12];
+        payload.extend_from_slice(b"audiocodecid");
+        payload.push(NUMBER);
+        payload.extend_from_slice(&10.0f64.to_be_bytes());
+        payload.extend_from_slice(&[0, 0, OBJECT_END]);
+
+        let values = decode_all(&payload).unwrap();
+        assert_eq!(
+            values,
+            vec![Amf0Value::object([(
+                "audiocodecid",
+                Amf0Value::Number(10.0)
+            )])]
+        );
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_hostile_input() {
+        let encoded = encode_all(&[Amf0Value::String("publish".to_string())]);
+        assert!(decode_all(&encoded[..encoded.len() - 1]).is_err());
+
+        // A strict array claiming four billion items
+        assert!(decode_all(&[STRICT_ARRAY, 0xFF, 0xFF, 0xFF, 0xFF]).is_err());
+
+        // Objects nested past the depth limit
+        let mut nested = Vec::new();
+        for _ in 0..=MAX_DEPTH + 1 {
+            nested.extend_from_slice(&[OBJECT, 0, 1, b'a']);
+        }
+        assert!(decode_all(&nested).is_err());
+
+        // AMF3 switch marker
+        assert!(decode_all(&[0x11]).is_err());
+    }
+}
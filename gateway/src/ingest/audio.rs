@@ -0,0 +1,315 @@
+//! FLV audio decoding
+//!
+//! Turns the payload of RTMP audio messages (FLV audio tags) into 16-bit
+//! little-endian mono PCM at the session's STT sample rate. Linear PCM and
+//! G.711 are decoded here; AAC-LC goes through symphonia.
+
+use bytes::Bytes;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_AAC, CodecParameters, Decoder, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::Packet;
+use tracing::debug;
+
+use crate::core::tts::telephony::Resampler;
+
+use super::IngestError;
+
+/// FLV `SoundFormat` values
+const FORMAT_PCM_NATIVE: u8 = 0;
+const FORMAT_PCM_LE: u8 = 3;
+const FORMAT_ALAW: u8 = 7;
+const FORMAT_MULAW: u8 = 8;
+const FORMAT_AAC: u8 = 10;
+
+/// Sample rates of the FLV `SoundRate` field
+const FLV_RATES: [u32; 4] = [5512, 11025, 22050, 44100];
+
+/// Samples per AAC frame, used as the packet duration
+const AAC_FRAME_SAMPLES: u64 = 1024;
+
+/// Decodes the audio tags of one published stream
+pub struct AudioDecoder {
+    target_rate: u32,
+    /// Resampler and the source rate it was built for
+    resampler: Option<(u32, Resampler)>,
+    aac: Option<Box<dyn Decoder>>,
+    aac_timestamp: u64,
+}
+
+impl AudioDecoder {
+    /// Decoder producing PCM at `target_rate` Hz
+    pub fn new(target_rate: u32) -> Self {
+        Self {
+            target_rate,
+            resampler: None,
+            aac: None,
+            aac_timestamp: 0,
+        }
+    }
+
+    /// Decode one audio tag
+    ///
+    /// # Returns
+    /// * `Ok(Bytes)` - 16-bit little-endian mono PCM at the target rate;
+    ///   empty for AAC sequence headers and frames the resampler holds back
+    /// * `Err(IngestError::UnsupportedCodec)` - MP3, Speex, Nellymoser, ADPCM
+    ///   or an AAC profile symphonia cannot decode
+    pub fn decode(&mut self, tag: &[u8]) -> Result<Bytes, IngestError> {
+        let Some((&header, data)) = tag.split_first() else {
+            return Ok(Bytes::new());
+        };
+        let format = header >> 4;
+        let rate = FLV_RATES[usize::from((header >> 2) & 0x03)];
+        let sixteen_bit = header & 0x02 != 0;
+        let channels = usize::from(header & 0x01) + 1;
+
+        let (rate, channels, samples) = match format {
+            FORMAT_PCM_NATIVE | FORMAT_PCM_LE => {
+                let samples = if sixteen_bit {
+                    data.chunks_exact(2)
+                        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+                        .collect()
+                } else {
+                    data.iter().map(|&b| (i16::from(b) - 128) << 8).collect()
+                };
+                (rate, channels, samples)
+            }
+            // G.711 in FLV is always 8kHz, whatever the rate field says
+            FORMAT_ALAW => (
+                8000,
+                channels,
+                data.iter().map(|&b| alaw_to_linear(b)).collect(),
+            ),
+            FORMAT_MULAW => (
+                8000,
+                channels,
+                data.iter().map(|&b| mulaw_to_linear(b)).collect(),
+            ),
+            FORMAT_AAC => match self.decode_aac(data)? {
+                Some(decoded) => decoded,
+                None => return Ok(Bytes::new()),
+            },
+            other => {
+                return Err(IngestError::UnsupportedCodec(
+                    format_name(other).to_string(),
+                ));
+            }
+        };
+
+        let mono = downmix(&samples, channels);
+        Ok(to_le_bytes(&self.resample(rate, &mono)))
+    }
+
+    /// PCM still held by the resampler, once the stream has ended
+    pub fn flush(&mut self) -> Bytes {
+        let mut out = Vec::new();
+        if let Some((_, resampler)) = self.resampler.as_mut() {
+            resampler.flush(&mut out);
+        }
+        to_le_bytes(&out)
+    }
+
+    /// Decode an AAC packet; None for sequence headers and skipped frames
+    fn decode_aac(&mut self, data: &[u8]) -> Result<Option<(u32, usize, Vec<i16>)>, IngestError> {
+        let Some((&packet_type, payload)) = data.split_first() else {
+            return Ok(None);
+        };
+
+        // Packet type 0 carries the AudioSpecificConfig
+        if packet_type == 0 {
+            let params = CodecParameters::new()
+                .for_codec(CODEC_TYPE_AAC)
+                .with_extra_data(payload.to_vec().into_boxed_slice())
+                .clone();
+            let decoder = symphonia::default::get_codecs()
+                .make(&params, &DecoderOptions::default())
+                .map_err(|e| IngestError::UnsupportedCodec(format!("AAC: {e}")))?;
+            self.aac = Some(decoder);
+            return Ok(None);
+        }
+
+        let Some(decoder) = self.aac.as_mut() else {
+            return Err(IngestError::Decode(
+                "AAC frame before its sequence header".to_string(),
+            ));
+        };
+        let packet = Packet::new_from_slice(0, self.aac_timestamp, AAC_FRAME_SAMPLES, payload);
+        self.aac_timestamp += AAC_FRAME_SAMPLES;
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let mut buffer = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                Ok(Some((
+                    spec.rate,
+                    spec.channels.count(),
+                    buffer.samples().to_vec(),
+                )))
+            }
+            // A corrupt frame is dropped; the next one decodes on its own
+            Err(SymphoniaError::DecodeError(e)) => {
+                debug!("Skipping undecodable AAC frame: {}", e);
+                Ok(None)
+            }
+            Err(e) => Err(IngestError::Decode(format!("AAC: {e}"))),
+        }
+    }
+
+    fn resample(&mut self, source_rate: u32, samples: &[i16]) -> Vec<i16> {
+        if source_rate == self.target_rate {
+            return samples.to_vec();
+        }
+        let mut out = Vec::with_capacity(
+            samples.len() * self.target_rate as usize / source_rate as usize + 1,
+        );
+        // Drain the old resampler if the publisher changed its rate
+        if let Some((rate, resampler)) = self.resampler.as_mut()
+            && *rate != source_rate
+        {
+            resampler.flush(&mut out);
+            self.resampler = None;
+        }
+        let (_, resampler) = self
+            .resampler
+            .get_or_insert_with(|| (source_rate, Resampler::new(source_rate, self.target_rate)));
+        resampler.process(samples, &mut out);
+        out
+    }
+}
+
+/// Average interleaved channels into one
+fn downmix(samples: &[i16], channels: usize) -> Vec<i16> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| {
+            let sum: i32 = frame.iter().map(|&s| i32::from(s)).sum();
+            (sum / channels as i32) as i16
+        })
+        .collect()
+}
+
+fn to_le_bytes(samples: &[i16]) -> Bytes {
+    samples
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+/// Decode one G.711 μ-law byte
+fn mulaw_to_linear(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = i32::from(byte & 0x0F);
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if byte & 0x80 != 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+/// Decode one G.711 A-law byte
+fn alaw_to_linear(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = i32::from(byte & 0x0F);
+    let magnitude = if exponent == 0 {
+        (mantissa << 4) + 8
+    } else {
+        ((mantissa << 4) + 0x108) << (exponent - 1)
+    };
+    if byte & 0x80 != 0 {
+        magnitude as i16
+    } else {
+        -magnitude as i16
+    }
+}
+
+/// Name of an FLV `SoundFormat` for error messages
+fn format_name(format: u8) -> &'static str {
+    match format {
+        1 => "ADPCM",
+        2 | 14 => "MP3",
+        4..=6 => "Nellymoser",
+        9 => "enhanced RTMP audio",
+        11 => "Speex",
+        _ => "unknown FLV sound format",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tts::telephony::linear_to_mulaw;
+
+    /// FLV header byte for 16-bit PCM LE at 44.1kHz
+    const PCM_44K_MONO: u8 = (FORMAT_PCM_LE << 4) | (3 << 2) | 0x02;
+
+    fn samples(bytes: &[u8]) -> Vec<i16> {
+        bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_pcm_passes_through_at_target_rate() {
+        let mut decoder = AudioDecoder::new(44_100);
+        let mut tag = vec![PCM_44K_MONO];
+        for sample in [0i16, 1000, -1000, i16::MAX] {
+            tag.extend_from_slice(&sample.to_le_bytes());
+        }
+        let pcm = decoder.decode(&tag).unwrap();
+        assert_eq!(samples(&pcm), vec![0, 1000, -1000, i16::MAX]);
+    }
+
+    #[test]
+    fn test_stereo_is_downmixed_and_resampled() {
+        let mut decoder = AudioDecoder::new(22_050);
+        let mut tag = vec![PCM_44K_MONO | 0x01];
+        for _ in 0..4410 {
+            tag.extend_from_slice(&2000i16.to_le_bytes());
+            tag.extend_from_slice(&0i16.to_le_bytes());
+        }
+        let mut pcm = decoder.decode(&tag).unwrap().to_vec();
+        pcm.extend_from_slice(&decoder.flush());
+        let out = samples(&pcm);
+        // 100ms of 44.1kHz stereo is 2205 mono samples at 22.05kHz
+        assert!((2200..=2210).contains(&out.len()), "{}", out.len());
+        assert!(out[10..].iter().all(|&s| s == 1000));
+    }
+
+    #[test]
+    fn test_g711_decodes_at_8khz() {
+        let mut decoder = AudioDecoder::new(8000);
+        let values = [0i16, 100, -100, 8000, -8000, 30000];
+        let mut tag = vec![FORMAT_MULAW << 4];
+        tag.extend(values.iter().map(|&v| linear_to_mulaw(v)));
+        let decoded = samples(&decoder.decode(&tag).unwrap());
+        for (value, decoded) in values.iter().zip(&decoded) {
+            let tolerance = (i32::from(*value).abs() / 16).max(8);
+            assert!((i32::from(*value) - i32::from(*decoded)).abs() <= tolerance);
+        }
+
+        assert_eq!(alaw_to_linear(0xD5), 8);
+        assert_eq!(alaw_to_linear(0x55), -8);
+        assert_eq!(alaw_to_linear(0xAA), 32256);
+    }
+
+    #[test]
+    fn test_unsupported_codecs_are_named() {
+        let mut decoder = AudioDecoder::new(16_000);
+        let err = decoder.decode(&[0x2F, 0xFF, 0xFB]).unwrap_err();
+        assert!(matches!(err, IngestError::UnsupportedCodec(ref name) if name == "MP3"));
+        assert!(matches!(
+            decoder.decode(&[0xAF, 0x01, 0x21]),
+            Err(IngestError::Decode(_))
+        ));
+    }
+}
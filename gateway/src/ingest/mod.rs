@@ -0,0 +1,154 @@
+//! # Broadcast Audio Ingest
+//!
+//! With the `ingest` cargo feature and an
+//! [`IngestConfig`](crate::config::IngestConfig), the gateway runs an RTMP
+//! listener next to its HTTP server so broadcast encoders (OBS, ffmpeg,
+//! hardware encoders) can publish straight to it for live captioning and
+//! monitoring:
+//!
+//! 1. the publisher connects to `rtmp://<host>:<port>/<app>/<key>` and its
+//!    stream key is looked up in the configured streams; unknown keys are
+//!    refused with `NetStream.Publish.BadName`
+//! 2. a voice session is opened as if the stream's `client_id` had sent
+//!    `{"type": "config", "agent": ..., "stream_id": ...}` on `/ws`, so the
+//!    agent profile configures it and the session's events, transcripts,
+//!    exports and usage records behave like any other session
+//! 3. once the session is ready the publish is accepted; audio messages are
+//!    decoded (linear PCM, G.711 or AAC-LC), downmixed and resampled to the
+//!    profile's STT sample rate and fed to the session; video is discarded
+//! 4. the session closes when the publisher unpublishes or disconnects, and
+//!    the RTMP connection is closed when the session ends
+//!
+//! SRT is not supported; encoders that only speak SRT can be relayed through
+//! ffmpeg (`ffmpeg -i srt://... -c:a aac -f flv rtmp://...`).
+
+mod amf;
+mod audio;
+mod rtmp;
+mod session;
+
+use std::collections::HashSet;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::config::IngestConfig;
+use crate::metrics::global_metrics;
+use crate::state::AppState;
+
+/// Errors of an ingest connection
+#[derive(Debug, thiserror::Error)]
+pub enum IngestError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("RTMP protocol error: {0}")]
+    Protocol(String),
+    #[error("Unsupported audio codec: {0}")]
+    UnsupportedCodec(String),
+    #[error("Audio decoding failed: {0}")]
+    Decode(String),
+}
+
+/// State shared by the connections of one listener
+struct IngestShared {
+    config: IngestConfig,
+    app_state: Arc<AppState>,
+    /// Session IDs being published
+    active: Mutex<HashSet<String>>,
+}
+
+/// RTMP ingest listener
+pub struct RtmpIngest {
+    listener: TcpListener,
+    shared: Arc<IngestShared>,
+}
+
+impl RtmpIngest {
+    /// Bind the listener to `config.rtmp_address`
+    pub async fn bind(config: IngestConfig, app_state: Arc<AppState>) -> io::Result<Self> {
+        let listener = TcpListener::bind(&config.rtmp_address).await?;
+        Ok(Self {
+            listener,
+            shared: Arc::new(IngestShared {
+                config,
+                app_state,
+                active: Mutex::new(HashSet::new()),
+            }),
+        })
+    }
+
+    /// The address the listener is bound to
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept publishers until the task is dropped
+    pub async fn run(self) {
+        info!(
+            address = ?self.listener.local_addr().ok(),
+            streams = self.shared.config.streams.len(),
+            "RTMP ingest listening"
+        );
+        loop {
+            let (stream, peer) = match self.listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!("Failed to accept RTMP connection: {}", e);
+                    continue;
+                }
+            };
+            let shared = self.shared.clone();
+            tokio::spawn(async move {
+                if let Err(e) = session::serve(stream, peer, shared).await {
+                    warn!(peer = %peer, "RTMP connection ended: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Claim of a session ID while its stream is published
+struct ActiveStream {
+    shared: Arc<IngestShared>,
+    session_id: String,
+}
+
+impl ActiveStream {
+    /// Claim `session_id`, or say why the publish is refused
+    fn claim(shared: &Arc<IngestShared>, session_id: &str) -> Result<Self, &'static str> {
+        let mut active = shared.active.lock();
+        if active.contains(session_id) {
+            return Err("already publishing");
+        }
+        if active.len() >= shared.config.max_streams {
+            return Err("ingest is at its stream limit");
+        }
+        active.insert(session_id.to_string());
+        record_active(active.len());
+        Ok(Self {
+            shared: shared.clone(),
+            session_id: session_id.to_string(),
+        })
+    }
+}
+
+impl Drop for ActiveStream {
+    fn drop(&mut self) {
+        let mut active = self.shared.active.lock();
+        active.remove(&self.session_id);
+        record_active(active.len());
+    }
+}
+
+fn record_active(count: usize) {
+    global_metrics().set_gauge(
+        "waav_ingest_active_streams",
+        "RTMP streams being published",
+        &[],
+        count as f64,
+    );
+}
@@ -0,0 +1,483 @@
+//! RTMP handshake and chunk stream
+//!
+//! Implements the server side of the simple (unsigned) handshake, which
+//! OBS, ffmpeg and hardware encoders accept when publishing, and the chunk
+//! stream that carries RTMP messages in both directions.
+
+use std::collections::HashMap;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::IngestError;
+
+/// RTMP protocol version sent in C0/S0
+pub const RTMP_VERSION: u8 = 3;
+
+/// Size of the C1/S1 and C2/S2 handshake packets
+pub const HANDSHAKE_SIZE: usize = 1536;
+
+/// Chunk size both directions start with
+pub const DEFAULT_CHUNK_SIZE: usize = 128;
+
+/// Largest message accepted from a publisher
+///
+/// Audio messages are a few kilobytes; video keyframes can be larger but
+/// are discarded, so this only has to bound memory.
+pub const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Most chunk streams a publisher may interleave
+const MAX_CHUNK_STREAMS: usize = 64;
+
+/// Timestamp field value announcing an extended timestamp
+const EXTENDED_TIMESTAMP: u32 = 0xFF_FFFF;
+
+/// RTMP message type IDs
+pub mod message_type {
+    pub const SET_CHUNK_SIZE: u8 = 1;
+    pub const ABORT: u8 = 2;
+    pub const ACKNOWLEDGEMENT: u8 = 3;
+    pub const USER_CONTROL: u8 = 4;
+    pub const WINDOW_ACK_SIZE: u8 = 5;
+    pub const SET_PEER_BANDWIDTH: u8 = 6;
+    pub const AUDIO: u8 = 8;
+    pub const VIDEO: u8 = 9;
+    pub const COMMAND_AMF3: u8 = 17;
+    pub const DATA_AMF0: u8 = 18;
+    pub const COMMAND_AMF0: u8 = 20;
+}
+
+/// A complete RTMP message
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtmpMessage {
+    pub type_id: u8,
+    /// Message stream ID (0 for connection-level messages)
+    pub stream_id: u32,
+    /// Timestamp in milliseconds
+    pub timestamp: u32,
+    pub payload: Bytes,
+}
+
+impl RtmpMessage {
+    /// A connection-level control message
+    pub fn control(type_id: u8, payload: impl Into<Bytes>) -> Self {
+        Self {
+            type_id,
+            stream_id: 0,
+            timestamp: 0,
+            payload: payload.into(),
+        }
+    }
+}
+
+/// Run the server side of the handshake
+///
+/// Reads C0 and C1, answers with S0, S1 and S2 (an echo of C1), then reads
+/// C2, which is not checked.
+pub async fn accept_handshake<S>(stream: &mut S) -> Result<(), IngestError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let version = stream.read_u8().await?;
+    if version != RTMP_VERSION {
+        return Err(IngestError::Protocol(format!(
+            "unsupported RTMP version {version}"
+        )));
+    }
+    let mut c1 = vec![0u8; HANDSHAKE_SIZE];
+    stream.read_exact(&mut c1).await?;
+
+    let mut response = Vec::with_capacity(1 + 2 * HANDSHAKE_SIZE);
+    response.push(RTMP_VERSION);
+    // S1: time and zero fields, then random bytes
+    response.extend_from_slice(&[0u8; 8]);
+    while response.len() < 1 + HANDSHAKE_SIZE {
+        response.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+    }
+    response.truncate(1 + HANDSHAKE_SIZE);
+    response.extend_from_slice(&c1);
+    stream.write_all(&response).await?;
+    stream.flush().await?;
+
+    let mut c2 = c1;
+    stream.read_exact(&mut c2).await?;
+    Ok(())
+}
+
+/// Header state of one inbound chunk stream
+#[derive(Default)]
+struct ChunkStream {
+    timestamp: u32,
+    delta: u32,
+    length: usize,
+    type_id: u8,
+    stream_id: u32,
+    extended: bool,
+    /// Payload of the message being reassembled
+    partial: BytesMut,
+}
+
+/// Reassembles messages from the inbound chunk stream
+///
+/// `Set Chunk Size` and `Abort` are applied here and not returned.
+pub struct ChunkReader {
+    chunk_size: usize,
+    streams: HashMap<u32, ChunkStream>,
+    bytes_read: u64,
+}
+
+impl Default for ChunkReader {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            streams: HashMap::new(),
+            bytes_read: 0,
+        }
+    }
+}
+
+impl ChunkReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bytes read from the connection, handshake excluded
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Read chunks until a message is complete
+    pub async fn read_message<R>(&mut self, reader: &mut R) -> Result<RtmpMessage, IngestError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        loop {
+            if let Some(message) = self.read_chunk(reader).await? {
+                match message.type_id {
+                    message_type::SET_CHUNK_SIZE => {
+                        let size = read_u32(&message.payload)? & 0x7FFF_FFFF;
+                        if size == 0 {
+                            return Err(IngestError::Protocol("chunk size of 0".to_string()));
+                        }
+                        self.chunk_size = size as usize;
+                    }
+                    message_type::ABORT => {
+                        let csid = read_u32(&message.payload)?;
+                        if let Some(stream) = self.streams.get_mut(&csid) {
+                            stream.partial.clear();
+                        }
+                    }
+                    _ => return Ok(message),
+                }
+            }
+        }
+    }
+
+    async fn read_chunk<R>(&mut self, reader: &mut R) -> Result<Option<RtmpMessage>, IngestError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let first = self.read_u8(reader).await?;
+        let fmt = first >> 6;
+        let csid = match u32::from(first & 0x3F) {
+            0 => 64 + u32::from(self.read_u8(reader).await?),
+            1 => {
+                let low = self.read_u8(reader).await?;
+                let high = self.read_u8(reader).await?;
+                64 + u32::from(low) + (u32::from(high) << 8)
+            }
+            csid => csid,
+        };
+
+        if !self.streams.contains_key(&csid) {
+            if fmt != 0 {
+                return Err(IngestError::Protocol(format!(
+                    "chunk stream {csid} starts without a full header"
+                )));
+            }
+            if self.streams.len() >= MAX_CHUNK_STREAMS {
+                return Err(IngestError::Protocol("too many chunk streams".to_string()));
+            }
+        }
+
+        let mut header = [0u8; 11];
+        let header_len = [11, 7, 3, 0][fmt as usize];
+        self.read_exact(reader, &mut header[..header_len]).await?;
+
+        let stream = self.streams.entry(csid).or_default();
+        let starts_message = stream.partial.is_empty();
+        if fmt < 3 {
+            let field = u24(&header[0..3]);
+            stream.extended = field == EXTENDED_TIMESTAMP;
+            if fmt == 0 {
+                stream.timestamp = field;
+                stream.delta = 0;
+            } else {
+                stream.delta = field;
+            }
+            if fmt < 2 {
+                stream.length = u24(&header[3..6]) as usize;
+                stream.type_id = header[6];
+            }
+            if fmt == 0 {
+                stream.stream_id =
+                    u32::from_le_bytes([header[7], header[8], header[9], header[10]]);
+            }
+        }
+        let extended = stream.extended;
+        let length = stream.length;
+        if length > MAX_MESSAGE_SIZE {
+            return Err(IngestError::Protocol(format!(
+                "message of {length} bytes exceeds {MAX_MESSAGE_SIZE}"
+            )));
+        }
+
+        if extended {
+            let mut field = [0u8; 4];
+            self.read_exact(reader, &mut field).await?;
+            let value = u32::from_be_bytes(field);
+            let stream = self.streams.get_mut(&csid).expect("chunk stream exists");
+            if fmt == 0 {
+                stream.timestamp = value;
+            } else if fmt < 3 {
+                stream.delta = value;
+            }
+        }
+
+        let stream = self.streams.get_mut(&csid).expect("chunk stream exists");
+        if starts_message && fmt != 0 {
+            stream.timestamp = stream.timestamp.wrapping_add(stream.delta);
+        }
+        let Some(remaining) = length.checked_sub(stream.partial.len()) else {
+            return Err(IngestError::Protocol(format!(
+                "chunk stream {csid} shrank a message mid-transfer"
+            )));
+        };
+        let take = remaining.min(self.chunk_size);
+        let mut body = vec![0u8; take];
+        self.read_exact(reader, &mut body).await?;
+
+        let stream = self.streams.get_mut(&csid).expect("chunk stream exists");
+        stream.partial.extend_from_slice(&body);
+        if stream.partial.len() < length {
+            return Ok(None);
+        }
+        Ok(Some(RtmpMessage {
+            type_id: stream.type_id,
+            stream_id: stream.stream_id,
+            timestamp: stream.timestamp,
+            payload: stream.partial.split().freeze(),
+        }))
+    }
+
+    async fn read_u8<R: AsyncRead + Unpin>(&mut self, reader: &mut R) -> Result<u8, IngestError> {
+        let byte = reader.read_u8().await?;
+        self.bytes_read += 1;
+        Ok(byte)
+    }
+
+    async fn read_exact<R: AsyncRead + Unpin>(
+        &mut self,
+        reader: &mut R,
+        buf: &mut [u8],
+    ) -> Result<(), IngestError> {
+        reader.read_exact(buf).await?;
+        self.bytes_read += buf.len() as u64;
+        Ok(())
+    }
+}
+
+/// Split messages into chunks of the outbound chunk size
+pub struct ChunkWriter {
+    chunk_size: usize,
+}
+
+impl Default for ChunkWriter {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl ChunkWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `chunk_size` for the messages after this one
+    ///
+    /// The peer must have been sent `Set Chunk Size` first.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size.max(1);
+    }
+
+    /// Encode `message` on chunk stream `csid` (2-63)
+    ///
+    /// The first chunk carries a full header, the rest type 3 headers.
+    pub fn encode(&self, csid: u8, message: &RtmpMessage) -> BytesMut {
+        let csid = csid.clamp(2, 63);
+        let mut out = BytesMut::with_capacity(message.payload.len() + 16);
+        let extended = message.timestamp >= EXTENDED_TIMESTAMP;
+        out.put_u8(csid);
+        put_u24(&mut out, message.timestamp.min(EXTENDED_TIMESTAMP));
+        put_u24(&mut out, message.payload.len() as u32);
+        out.put_u8(message.type_id);
+        out.put_u32_le(message.stream_id);
+        if extended {
+            out.put_u32(message.timestamp);
+        }
+        for (index, chunk) in message.payload.chunks(self.chunk_size).enumerate() {
+            if index > 0 {
+                out.put_u8(0xC0 | csid);
+                if extended {
+                    out.put_u32(message.timestamp);
+                }
+            }
+            out.put_slice(chunk);
+        }
+        out
+    }
+
+    /// Encode and write `message` on chunk stream `csid`
+    pub async fn write<W>(
+        &self,
+        writer: &mut W,
+        csid: u8,
+        message: &RtmpMessage,
+    ) -> Result<(), IngestError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        writer.write_all(&self.encode(csid, message)).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+fn u24(bytes: &[u8]) -> u32 {
+    (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2])
+}
+
+fn put_u24(out: &mut BytesMut, value: u32) {
+    out.put_slice(&value.to_be_bytes()[1..]);
+}
+
+fn read_u32(payload: &[u8]) -> Result<u32, IngestError> {
+    payload
+        .get(..4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| IngestError::Protocol("control message cut short".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(type_id: u8, len: usize) -> RtmpMessage {
+        RtmpMessage {
+            type_id,
+            stream_id: 1,
+            timestamp: 40,
+            payload: (0..len).map(|i| i as u8).collect::<Vec<_>>().into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_messages_round_trip_across_chunks() {
+        let writer = ChunkWriter::new();
+        let audio = message(message_type::AUDIO, 1000);
+        let command = message(message_type::COMMAND_AMF0, 20);
+
+        let mut wire = BytesMut::new();
+        wire.extend_from_slice(&writer.encode(4, &audio));
+        wire.extend_from_slice(&writer.encode(3, &command));
+
+        let mut reader = ChunkReader::new();
+        let mut input = &wire[..];
+        assert_eq!(reader.read_message(&mut input).await.unwrap(), audio);
+        assert_eq!(reader.read_message(&mut input).await.unwrap(), command);
+        assert_eq!(reader.bytes_read(), wire.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_set_chunk_size_applies_to_later_chunks() {
+        let mut writer = ChunkWriter::new();
+        let set_chunk_size =
+            RtmpMessage::control(message_type::SET_CHUNK_SIZE, 4096u32.to_be_bytes().to_vec());
+        let mut wire = writer.encode(2, &set_chunk_size);
+        writer.set_chunk_size(4096);
+        let audio = message(message_type::AUDIO, 3000);
+        wire.extend_from_slice(&writer.encode(4, &audio));
+
+        let mut reader = ChunkReader::new();
+        let mut input = &wire[..];
+        assert_eq!(reader.read_message(&mut input).await.unwrap(), audio);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_headers_add_timestamp_delta() {
+        // fmt 0 audio at t=100, then fmt 2 (delta 23) and fmt 3 reusing it
+        let mut wire = BytesMut::new();
+        wire.put_u8(4);
+        put_u24(&mut wire, 100);
+        put_u24(&mut wire, 2);
+        wire.put_u8(message_type::AUDIO);
+        wire.put_u32_le(1);
+        wire.put_slice(&[0xAF, 0x01]);
+        wire.put_u8(0x80 | 4);
+        put_u24(&mut wire, 23);
+        wire.put_slice(&[0xAF, 0x02]);
+        wire.put_u8(0xC0 | 4);
+        wire.put_slice(&[0xAF, 0x03]);
+
+        let mut reader = ChunkReader::new();
+        let mut input = &wire[..];
+        let timestamps: Vec<u32> = [
+            reader.read_message(&mut input).await.unwrap(),
+            reader.read_message(&mut input).await.unwrap(),
+            reader.read_message(&mut input).await.unwrap(),
+        ]
+        .iter()
+        .map(|m| m.timestamp)
+        .collect();
+        assert_eq!(timestamps, vec![100, 123, 146]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_and_headerless_streams() {
+        let mut oversized = BytesMut::new();
+        oversized.put_u8(4);
+        put_u24(&mut oversized, 0);
+        put_u24(&mut oversized, (MAX_MESSAGE_SIZE + 1) as u32);
+        oversized.put_u8(message_type::VIDEO);
+        oversized.put_u32_le(1);
+        let mut input = &oversized[..];
+        assert!(ChunkReader::new().read_message(&mut input).await.is_err());
+
+        let headerless = [0xC0 | 4, 0xAF];
+        let mut input = &headerless[..];
+        assert!(ChunkReader::new().read_message(&mut input).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handshake_echoes_c1() {
+        let (mut client, mut server) = tokio::io::duplex(8192);
+        let server_task = tokio::spawn(async move { accept_handshake(&mut server).await });
+
+        let c1: Vec<u8> = (0..HANDSHAKE_SIZE).map(|i| (i % 251) as u8).collect();
+        client.write_u8(RTMP_VERSION).await.unwrap();
+        client.write_all(&c1).await.unwrap();
+
+        let mut s0s1s2 = vec![0u8; 1 + 2 * HANDSHAKE_SIZE];
+        client.read_exact(&mut s0s1s2).await.unwrap();
+        assert_eq!(s0s1s2[0], RTMP_VERSION);
+        assert_eq!(&s0s1s2[1 + HANDSHAKE_SIZE..], &c1[..]);
+
+        client
+            .write_all(&s0s1s2[1..1 + HANDSHAKE_SIZE])
+            .await
+            .unwrap();
+        server_task.await.unwrap().unwrap();
+    }
+}
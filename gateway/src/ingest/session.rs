@@ -0,0 +1,693 @@
+//! One RTMP publisher and the voice session it feeds
+//!
+//! The connection answers the commands publishers send before `publish`,
+//! then opens a voice session through the `/ws` connection loop: a config
+//! message naming the agent profile, followed by binary PCM frames. The
+//! publish is only accepted once the session is ready, so encoders that
+//! wait for `NetStream.Publish.Start` send no audio before it can be used.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::ws::Message;
+use futures::channel::mpsc as frame_channel;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::agents::AgentSection;
+use crate::auth::Auth;
+use crate::handlers::ws::handler::run_voice_connection;
+use crate::metrics::global_metrics;
+use crate::state::AppState;
+
+use super::amf::{self, Amf0Value};
+use super::audio::AudioDecoder;
+use super::rtmp::{ChunkReader, ChunkWriter, RtmpMessage, accept_handshake, message_type};
+use super::{ActiveStream, IngestError, IngestShared};
+
+/// Longest wait for the client's handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Acknowledgement window and peer bandwidth announced on `connect`
+const WINDOW_SIZE: u32 = 2_500_000;
+
+/// Chunk size of the messages sent to publishers
+const OUT_CHUNK_SIZE: usize = 4096;
+
+/// Message stream handed out by `createStream`
+const PUBLISH_STREAM_ID: u32 = 1;
+
+/// Chunk streams of outbound control and command messages
+const CONTROL_CSID: u8 = 2;
+const COMMAND_CSID: u8 = 3;
+
+/// Messages buffered between the socket reader and the connection
+const INBOUND_BUFFER: usize = 64;
+
+/// PCM frames buffered ahead of the session
+const FRAME_BUFFER: usize = 64;
+
+type Inbound = Result<(RtmpMessage, u64), IngestError>;
+type FrameSender = frame_channel::Sender<Result<Message, Infallible>>;
+type EventReceiver = frame_channel::UnboundedReceiver<Message>;
+
+/// Serve one RTMP connection until it or its session ends
+pub(super) async fn serve(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    shared: Arc<IngestShared>,
+) -> Result<(), IngestError> {
+    stream.set_nodelay(true)?;
+    tokio::time::timeout(HANDSHAKE_TIMEOUT, accept_handshake(&mut stream))
+        .await
+        .map_err(|_| IngestError::Protocol("handshake timed out".to_string()))??;
+    debug!(peer = %peer, "RTMP handshake complete");
+
+    // Reading in its own task keeps partially read messages safe from the
+    // connection's select loop
+    let (read_half, write_half) = stream.into_split();
+    let (inbound_tx, inbound_rx) = mpsc::channel(INBOUND_BUFFER);
+    let reader = tokio::spawn(read_messages(read_half, inbound_tx));
+
+    let mut connection = Connection {
+        writer: write_half,
+        chunks: ChunkWriter::new(),
+        inbound: inbound_rx,
+        ack_window: None,
+        acknowledged: 0,
+        shared,
+        peer,
+    };
+    let result = connection.run().await;
+    reader.abort();
+    result
+}
+
+/// Forward reassembled messages with the byte count read so far
+async fn read_messages(mut reader: OwnedReadHalf, tx: mpsc::Sender<Inbound>) {
+    let mut chunks = ChunkReader::new();
+    loop {
+        let result = chunks
+            .read_message(&mut reader)
+            .await
+            .map(|message| (message, chunks.bytes_read()));
+        let failed = result.is_err();
+        if tx.send(result).await.is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// A command message: name, transaction ID and the arguments after the
+/// command object
+struct Command {
+    name: String,
+    transaction: f64,
+    args: Vec<Amf0Value>,
+}
+
+impl Command {
+    fn parse(message: &RtmpMessage) -> Result<Self, IngestError> {
+        // AMF3 commands start with a format byte, then use AMF0 encoding
+        let payload = match message.type_id {
+            message_type::COMMAND_AMF3 => message.payload.get(1..).unwrap_or_default(),
+            _ => &message.payload[..],
+        };
+        let mut values = amf::decode_all(payload)?.into_iter();
+        let name = values
+            .next()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .ok_or_else(|| IngestError::Protocol("command without a name".to_string()))?;
+        let transaction = values
+            .next()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0);
+        // Skip the command object (null for stream commands)
+        values.next();
+        Ok(Self {
+            name,
+            transaction,
+            args: values.collect(),
+        })
+    }
+}
+
+/// Why a publish was refused, sent back in `onStatus`
+struct Rejection {
+    code: &'static str,
+    description: String,
+}
+
+impl Rejection {
+    fn bad_name(description: impl Into<String>) -> Self {
+        Self {
+            code: "NetStream.Publish.BadName",
+            description: description.into(),
+        }
+    }
+
+    fn denied(description: impl Into<String>) -> Self {
+        Self {
+            code: "NetStream.Publish.Denied",
+            description: description.into(),
+        }
+    }
+}
+
+/// The session a publish was bound to
+struct Binding {
+    session_id: String,
+    agent: String,
+    client_id: Option<String>,
+    metadata: BTreeMap<String, String>,
+    sample_rate: u32,
+    /// Keeps the session ID claimed while the stream is published
+    _active: ActiveStream,
+}
+
+struct Connection {
+    writer: OwnedWriteHalf,
+    chunks: ChunkWriter,
+    inbound: mpsc::Receiver<Inbound>,
+    /// Acknowledgement window the publisher announced
+    ack_window: Option<u64>,
+    /// Byte count last acknowledged
+    acknowledged: u64,
+    shared: Arc<IngestShared>,
+    peer: SocketAddr,
+}
+
+impl Connection {
+    async fn run(&mut self) -> Result<(), IngestError> {
+        let timeout = Duration::from_millis(self.shared.config.session_timeout_ms);
+        let stream_name = loop {
+            let received = tokio::time::timeout(timeout, self.inbound.recv())
+                .await
+                .map_err(|_| {
+                    IngestError::Protocol(
+                        "no publish request within the session timeout".to_string(),
+                    )
+                })?;
+            let Some(message) = self.receive(received).await? else {
+                return Ok(());
+            };
+            if let Some(stream_name) = self.handle_setup(message).await? {
+                break stream_name;
+            }
+        };
+        self.publish(&stream_name).await
+    }
+
+    /// Unpack a received message and acknowledge its bytes; None once the
+    /// publisher has gone
+    async fn receive(
+        &mut self,
+        received: Option<Inbound>,
+    ) -> Result<Option<RtmpMessage>, IngestError> {
+        let (message, bytes_read) = match received {
+            None => return Ok(None),
+            Some(Err(IngestError::Io(e))) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return Ok(None);
+            }
+            Some(Err(e)) => return Err(e),
+            Some(Ok(received)) => received,
+        };
+
+        if let Some(window) = self.ack_window
+            && bytes_read - self.acknowledged >= window
+        {
+            self.acknowledged = bytes_read;
+            let sequence = (bytes_read as u32).to_be_bytes().to_vec();
+            self.send(
+                CONTROL_CSID,
+                RtmpMessage::control(message_type::ACKNOWLEDGEMENT, sequence),
+            )
+            .await?;
+        }
+        if message.type_id == message_type::WINDOW_ACK_SIZE
+            && let Some(window) = message.payload.get(..4)
+        {
+            let window = u32::from_be_bytes([window[0], window[1], window[2], window[3]]);
+            self.ack_window = Some(u64::from(window.max(1)));
+        }
+        Ok(Some(message))
+    }
+
+    /// Answer a command sent before `publish`
+    ///
+    /// # Returns
+    /// * `Ok(Some(name))` - The publisher asked to publish stream `name`
+    async fn handle_setup(&mut self, message: RtmpMessage) -> Result<Option<String>, IngestError> {
+        if !matches!(
+            message.type_id,
+            message_type::COMMAND_AMF0 | message_type::COMMAND_AMF3
+        ) {
+            return Ok(None);
+        }
+        let command = Command::parse(&message)?;
+        debug!(peer = %self.peer, command = %command.name, "RTMP command");
+        match command.name.as_str() {
+            "connect" => self.accept_connect(command.transaction).await?,
+            "createStream" => {
+                self.send_result(
+                    command.transaction,
+                    Amf0Value::Number(f64::from(PUBLISH_STREAM_ID)),
+                )
+                .await?
+            }
+            "publish" => {
+                let name = command
+                    .args
+                    .first()
+                    .and_then(Amf0Value::as_str)
+                    .unwrap_or_default();
+                return Ok(Some(name.to_string()));
+            }
+            // releaseStream, FCPublish and the like need no more than an answer
+            _ if command.transaction != 0.0 => {
+                self.send_result(command.transaction, Amf0Value::Undefined)
+                    .await?
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    async fn accept_connect(&mut self, transaction: f64) -> Result<(), IngestError> {
+        self.send(
+            CONTROL_CSID,
+            RtmpMessage::control(
+                message_type::WINDOW_ACK_SIZE,
+                WINDOW_SIZE.to_be_bytes().to_vec(),
+            ),
+        )
+        .await?;
+        // Limit type 2: dynamic
+        let mut bandwidth = WINDOW_SIZE.to_be_bytes().to_vec();
+        bandwidth.push(2);
+        self.send(
+            CONTROL_CSID,
+            RtmpMessage::control(message_type::SET_PEER_BANDWIDTH, bandwidth),
+        )
+        .await?;
+        self.send(
+            CONTROL_CSID,
+            RtmpMessage::control(
+                message_type::SET_CHUNK_SIZE,
+                (OUT_CHUNK_SIZE as u32).to_be_bytes().to_vec(),
+            ),
+        )
+        .await?;
+        self.chunks.set_chunk_size(OUT_CHUNK_SIZE);
+
+        self.send_command(
+            0,
+            &[
+                Amf0Value::String("_result".to_string()),
+                Amf0Value::Number(transaction),
+                Amf0Value::object([
+                    ("fmsVer", Amf0Value::String("FMS/3,0,1,123".to_string())),
+                    ("capabilities", Amf0Value::Number(31.0)),
+                ]),
+                Amf0Value::object([
+                    ("level", Amf0Value::String("status".to_string())),
+                    (
+                        "code",
+                        Amf0Value::String("NetConnection.Connect.Success".to_string()),
+                    ),
+                    (
+                        "description",
+                        Amf0Value::String("Connection succeeded.".to_string()),
+                    ),
+                    ("objectEncoding", Amf0Value::Number(0.0)),
+                ]),
+            ],
+        )
+        .await
+    }
+
+    /// Bind the publish to a session and feed it until either side ends
+    async fn publish(&mut self, stream_name: &str) -> Result<(), IngestError> {
+        let binding = match self.bind(stream_name).await {
+            Ok(binding) => binding,
+            Err(rejection) => {
+                count_publish("rejected");
+                warn!(
+                    peer = %self.peer,
+                    reason = %rejection.description,
+                    "Refused RTMP publish"
+                );
+                return self
+                    .on_status("error", rejection.code, &rejection.description)
+                    .await;
+            }
+        };
+        info!(
+            peer = %self.peer,
+            session_id = %binding.session_id,
+            agent = %binding.agent,
+            sample_rate = binding.sample_rate,
+            "RTMP publish bound to session"
+        );
+
+        let auth = binding
+            .client_id
+            .clone()
+            .map(Auth::new)
+            .unwrap_or_else(Auth::empty);
+        let (mut frames, frames_rx) = frame_channel::channel(FRAME_BUFFER);
+        let (events_tx, mut events) = frame_channel::unbounded();
+        let session = run_voice_connection(
+            events_tx,
+            frames_rx,
+            self.shared.app_state.clone(),
+            auth,
+            None,
+        );
+        let feed = async {
+            let result = self.feed(&binding, &mut frames, &mut events).await;
+            // Ending the frames closes the session; drain it until its teardown is done
+            drop(frames);
+            while events.next().await.is_some() {}
+            result
+        };
+        let ((), result) = tokio::join!(session, feed);
+        info!(session_id = %binding.session_id, "RTMP stream ended");
+        result
+    }
+
+    /// Resolve the stream key, agent profile and session ID of a publish
+    async fn bind(&self, stream_name: &str) -> Result<Binding, Rejection> {
+        let (key, query) = stream_name.split_once('?').unwrap_or((stream_name, ""));
+        let Some(stream) = self.shared.config.stream(key) else {
+            return Err(Rejection::bad_name("Unknown stream key"));
+        };
+
+        let mut agent = stream.agent.clone();
+        let mut session_id = stream.name.clone();
+        for (name, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            match name.as_ref() {
+                "agent" => agent = value.into_owned(),
+                "stream_id" => session_id = value.into_owned(),
+                _ => {}
+            }
+        }
+
+        let sample_rate =
+            stt_sample_rate(&self.shared.app_state, &agent, stream.client_id.as_deref())
+                .await
+                .map_err(Rejection::bad_name)?;
+        let active = ActiveStream::claim(&self.shared, &session_id)
+            .map_err(|reason| Rejection::denied(format!("Stream '{session_id}': {reason}")))?;
+
+        Ok(Binding {
+            session_id,
+            agent,
+            client_id: stream.client_id.clone(),
+            metadata: stream.metadata.clone(),
+            sample_rate,
+            _active: active,
+        })
+    }
+
+    /// Configure the session, accept the publish and forward its audio
+    async fn feed(
+        &mut self,
+        binding: &Binding,
+        frames: &mut FrameSender,
+        events: &mut EventReceiver,
+    ) -> Result<(), IngestError> {
+        // Configure the session from the agent profile, as a /ws client would
+        let mut config = json!({
+            "type": "config",
+            "agent": binding.agent,
+            "stream_id": binding.session_id,
+        });
+        if !binding.metadata.is_empty() {
+            config["metadata"] = json!(binding.metadata);
+        }
+        if frames
+            .send(Ok(Message::Text(config.to_string().into())))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        let timeout = Duration::from_millis(self.shared.config.session_timeout_ms);
+        let ready = tokio::time::timeout(timeout, wait_until_ready(frames, events))
+            .await
+            .unwrap_or_else(
+                |_| Err("Session was not ready within the session timeout".to_string()),
+            );
+        if let Err(reason) = ready {
+            count_publish("failed");
+            warn!(
+                session_id = %binding.session_id,
+                reason = %reason,
+                "RTMP publish failed to start a session"
+            );
+            return self
+                .on_status("error", "NetStream.Publish.Denied", &reason)
+                .await;
+        }
+        count_publish("started");
+
+        // Stream Begin, then accept the publish
+        let mut stream_begin = vec![0, 0];
+        stream_begin.extend_from_slice(&PUBLISH_STREAM_ID.to_be_bytes());
+        self.send(
+            CONTROL_CSID,
+            RtmpMessage::control(message_type::USER_CONTROL, stream_begin),
+        )
+        .await?;
+        self.on_status(
+            "status",
+            "NetStream.Publish.Start",
+            &format!("Publishing to session {}", binding.session_id),
+        )
+        .await?;
+
+        let mut decoder = AudioDecoder::new(binding.sample_rate);
+        loop {
+            tokio::select! {
+                received = self.inbound.recv() => {
+                    let Some(message) = self.receive(received).await? else {
+                        info!(session_id = %binding.session_id, "RTMP publisher disconnected");
+                        break;
+                    };
+                    match message.type_id {
+                        message_type::AUDIO => match decoder.decode(&message.payload) {
+                            Ok(pcm) if pcm.is_empty() => {}
+                            Ok(pcm) => {
+                                if frames.send(Ok(Message::Binary(pcm))).await.is_err() {
+                                    return Ok(());
+                                }
+                            }
+                            Err(IngestError::Decode(e)) => {
+                                debug!("Skipping RTMP audio message: {}", e);
+                            }
+                            Err(e) => {
+                                warn!(session_id = %binding.session_id, "{}", e);
+                                self.on_status("error", "NetStream.Failed", &e.to_string()).await?;
+                                return Err(e);
+                            }
+                        },
+                        message_type::COMMAND_AMF0 | message_type::COMMAND_AMF3 => {
+                            let command = Command::parse(&message)?;
+                            if matches!(
+                                command.name.as_str(),
+                                "FCUnpublish" | "deleteStream" | "closeStream"
+                            ) {
+                                info!(session_id = %binding.session_id, "RTMP publisher unpublished");
+                                break;
+                            }
+                            if command.transaction != 0.0 {
+                                self.send_result(command.transaction, Amf0Value::Undefined).await?;
+                            }
+                        }
+                        // Video and stream metadata are not used
+                        message_type::VIDEO | message_type::DATA_AMF0 => {}
+                        _ => {}
+                    }
+                }
+                event = events.next() => match event {
+                    Some(Message::Ping(payload)) => {
+                        let _ = frames.send(Ok(Message::Pong(payload))).await;
+                    }
+                    Some(Message::Text(text)) => log_session_error(&binding.session_id, &text),
+                    Some(Message::Close(_)) | None => {
+                        info!(session_id = %binding.session_id, "Session ended, closing RTMP connection");
+                        return Ok(());
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let rest = decoder.flush();
+        if !rest.is_empty() {
+            let _ = frames.send(Ok(Message::Binary(rest))).await;
+        }
+        Ok(())
+    }
+
+    async fn on_status(
+        &mut self,
+        level: &str,
+        code: &str,
+        description: &str,
+    ) -> Result<(), IngestError> {
+        self.send_command(
+            PUBLISH_STREAM_ID,
+            &[
+                Amf0Value::String("onStatus".to_string()),
+                Amf0Value::Number(0.0),
+                Amf0Value::Null,
+                Amf0Value::object([
+                    ("level", Amf0Value::String(level.to_string())),
+                    ("code", Amf0Value::String(code.to_string())),
+                    ("description", Amf0Value::String(description.to_string())),
+                ]),
+            ],
+        )
+        .await
+    }
+
+    async fn send_result(&mut self, transaction: f64, value: Amf0Value) -> Result<(), IngestError> {
+        self.send_command(
+            0,
+            &[
+                Amf0Value::String("_result".to_string()),
+                Amf0Value::Number(transaction),
+                Amf0Value::Null,
+                value,
+            ],
+        )
+        .await
+    }
+
+    async fn send_command(
+        &mut self,
+        stream_id: u32,
+        values: &[Amf0Value],
+    ) -> Result<(), IngestError> {
+        let message = RtmpMessage {
+            type_id: message_type::COMMAND_AMF0,
+            stream_id,
+            timestamp: 0,
+            payload: amf::encode_all(values).freeze(),
+        };
+        self.send(COMMAND_CSID, message).await
+    }
+
+    async fn send(&mut self, csid: u8, message: RtmpMessage) -> Result<(), IngestError> {
+        self.chunks.write(&mut self.writer, csid, &message).await
+    }
+}
+
+/// Wait for the session's `ready` message, answering heartbeat pings
+///
+/// # Returns
+/// * `Err(String)` - The session's error, e.g. an invalid agent profile
+async fn wait_until_ready(
+    frames: &mut FrameSender,
+    events: &mut EventReceiver,
+) -> Result<(), String> {
+    while let Some(event) = events.next().await {
+        match event {
+            Message::Text(text) => {
+                let Ok(message) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                match message["type"].as_str() {
+                    Some("ready") => return Ok(()),
+                    Some("error") => {
+                        return Err(message["message"]
+                            .as_str()
+                            .unwrap_or("Session configuration failed")
+                            .to_string());
+                    }
+                    _ => {}
+                }
+            }
+            Message::Ping(payload) => {
+                let _ = frames.send(Ok(Message::Pong(payload))).await;
+            }
+            Message::Close(frame) => {
+                return Err(frame
+                    .map(|frame| frame.reason.to_string())
+                    .unwrap_or_else(|| "Session closed".to_string()));
+            }
+            _ => {}
+        }
+    }
+    Err("Session closed".to_string())
+}
+
+/// Log errors the session reports while the stream is published
+fn log_session_error(session_id: &str, text: &str) {
+    if let Ok(message) = serde_json::from_str::<Value>(text)
+        && message["type"] == "error"
+    {
+        warn!(
+            session_id = %session_id,
+            error = %message["message"].as_str().unwrap_or_default(),
+            "Ingest session reported an error"
+        );
+    }
+}
+
+/// STT sample rate of the session `agent` configures for `client_id`
+///
+/// Also checks the profile transcribes mono 16-bit PCM, which is what the
+/// ingest feeds it.
+async fn stt_sample_rate(
+    app_state: &AppState,
+    agent: &str,
+    client_id: Option<&str>,
+) -> Result<u32, String> {
+    let profiles = app_state.agent_profiles.read().await;
+    let session = profiles
+        .lookup(agent, client_id)
+        .map_err(|e| e.to_string())?
+        .resolve(AgentSection::Session, None, None)
+        .map_err(|e| e.to_string())?;
+    let Some(stt) = session.get("stt_config") else {
+        return Err(format!("Agent '{agent}' has no stt_config"));
+    };
+
+    if let Some(encoding) = stt.get("encoding").and_then(Value::as_str)
+        && !matches!(encoding, "linear16" | "pcm")
+    {
+        return Err(format!(
+            "Agent '{agent}' expects {encoding} audio; ingest sends linear16"
+        ));
+    }
+    if stt.get("channels").and_then(Value::as_u64).unwrap_or(1) != 1 {
+        return Err(format!(
+            "Agent '{agent}' expects multichannel audio; ingest sends mono"
+        ));
+    }
+    stt.get("sample_rate")
+        .and_then(Value::as_u64)
+        .and_then(|rate| u32::try_from(rate).ok())
+        .filter(|rate| *rate > 0)
+        .ok_or_else(|| format!("Agent '{agent}' has no stt_config.sample_rate"))
+}
+
+fn count_publish(result: &'static str) {
+    global_metrics().inc_counter(
+        "waav_ingest_publishes_total",
+        "RTMP publish requests by result",
+        &[("result", result)],
+    );
+}
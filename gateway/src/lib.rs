@@ -1,18 +1,33 @@
+pub mod agents;
 pub mod auth;
+pub mod bench;
+pub mod build_info;
 pub mod config;
 pub mod core;
 #[cfg(feature = "dag-routing")]
 pub mod dag;
 pub mod docs;
+pub mod enrichment;
 pub mod errors;
+pub mod flight_recorder;
 pub mod handlers;
+#[cfg(feature = "ingest")]
+pub mod ingest;
 pub mod init;
 pub mod livekit;
+pub mod metrics;
 pub mod middleware;
 pub mod plugin;
+pub mod recording_upload;
+pub mod replay;
 pub mod routes;
+pub mod selftest;
+pub mod session_export;
+pub mod shutdown;
 pub mod state;
+pub mod usage;
 pub mod utils;
+pub mod webhooks;
 
 // Re-export commonly used items for convenience
 pub use config::ServerConfig;
@@ -13,10 +13,15 @@ use livekit::track::{LocalAudioTrack, LocalTrack, TrackSource};
 use livekit::webrtc::audio_source::native::NativeAudioSource;
 use livekit::webrtc::prelude::{AudioFrame, AudioSourceOptions, RtcAudioSource};
 use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::time::MissedTickBehavior;
 use tracing::{debug, error, info, warn};
 
-use super::{LiveKitClient, LiveKitConfig, LiveKitOperation, operation_worker::OperationContext};
+use super::{
+    AudioPacer, LiveKitClient, LiveKitConfig, LiveKitOperation, QueueStats,
+    operation_worker::OperationContext,
+};
 use crate::AppError;
+use crate::metrics::global_metrics;
 
 impl LiveKitClient {
     pub(super) async fn setup_audio_publishing(&mut self) -> Result<(), AppError> {
@@ -81,48 +86,24 @@ impl LiveKitClient {
                 self.has_audio_source_atomic
                     .store(true, std::sync::atomic::Ordering::Release);
 
-                // Spawn a lightweight task to drain queued audio without blocking
-                let audio_queue_clone = Arc::clone(&self.audio_queue);
-                let audio_source_clone = Arc::clone(&audio_source);
-                let sample_rate = self.config.sample_rate;
-                let channels = self.config.channels;
-
-                let handle = tokio::spawn(async move {
-                    // Extract all queued data to avoid holding the lock during async operations
-                    let queued_data: Vec<Vec<u8>> = {
-                        let mut queue = audio_queue_clone.lock().await;
-                        if !queue.is_empty() {
-                            info!(
-                                "Draining {} queued audio messages after track publish",
-                                queue.len()
-                            );
-                            queue.drain(..).collect()
-                        } else {
-                            Vec::new()
-                        }
-                    };
-
-                    // Process the drained audio without holding any locks
-                    for audio_data in queued_data {
-                        // Use convert_audio_to_frame_ref to avoid allocations
-                        match LiveKitClient::convert_audio_to_frame_ref(
-                            &audio_data,
-                            sample_rate,
-                            channels,
-                        ) {
-                            Ok(audio_frame) => {
-                                if let Err(e) = audio_source_clone.capture_frame(&audio_frame).await
-                                {
-                                    error!("Failed to send queued audio frame: {:?}", e);
-                                    // Don't re-queue on error to avoid infinite loop
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to convert queued audio frame: {:?}", e);
-                            }
-                        }
-                    }
-                });
+                // Audio queued before the track existed goes through the pacer like the rest
+                let queued =
+                    Self::drain_queue_into_pacer(&self.audio_queue, &self.audio_pacer).await;
+                if queued > 0 {
+                    info!(
+                        "Moved {} queued audio messages to the pacer after track publish",
+                        queued
+                    );
+                }
+
+                // The pacing task reads the current source each tick, so it outlives reconnects
+                let handle = Self::spawn_pacing_task(
+                    Arc::clone(&self.audio_pacer),
+                    Arc::clone(&self.audio_source),
+                    Arc::clone(&self.stats),
+                    self.config.sample_rate,
+                    self.config.channels,
+                );
 
                 // Track the spawn handle for lifecycle management
                 self.active_streams.lock().await.push(handle);
@@ -162,6 +143,7 @@ impl LiveKitClient {
                 audio_data,
                 &self.audio_queue,
                 &self.audio_source,
+                &self.audio_pacer,
                 &self.is_connected,
                 &self.config,
             )
@@ -195,6 +177,7 @@ impl LiveKitClient {
                 warn!("No audio source available - nothing to clear");
             }
 
+            self.audio_pacer.lock().await.clear();
             self.audio_queue.lock().await.clear();
             debug!("Cleared audio queue");
             Ok(())
@@ -204,6 +187,7 @@ impl LiveKitClient {
     pub(super) async fn process_clear_audio(
         audio_queue: &Arc<Mutex<VecDeque<Vec<u8>>>>,
         audio_source: &Arc<Mutex<Option<Arc<NativeAudioSource>>>>,
+        audio_pacer: &Arc<Mutex<AudioPacer>>,
         is_connected: &Arc<Mutex<bool>>,
     ) -> Result<(), AppError> {
         if !*is_connected.lock().await {
@@ -219,6 +203,7 @@ impl LiveKitClient {
             warn!("No audio source available - nothing to clear");
         }
 
+        audio_pacer.lock().await.clear();
         audio_queue.lock().await.clear();
         debug!("Cleared audio queue");
 
@@ -229,6 +214,7 @@ impl LiveKitClient {
         audio_data: Vec<u8>,
         audio_queue: &Arc<Mutex<VecDeque<Vec<u8>>>>,
         audio_source: &Arc<Mutex<Option<Arc<NativeAudioSource>>>>,
+        audio_pacer: &Arc<Mutex<AudioPacer>>,
         is_connected: &Arc<Mutex<bool>>,
         config: &LiveKitConfig,
     ) -> Result<(), AppError> {
@@ -238,97 +224,29 @@ impl LiveKitClient {
             ));
         }
 
-        // Clone the Arc<NativeAudioSource> out of the mutex to avoid holding lock during await
-        let source = {
-            let guard = audio_source.lock().await;
-            guard.as_ref().cloned()
-        };
-
-        if let Some(source) = source {
-            // Try to convert and send without holding any locks
-            match Self::convert_audio_to_frame_ref(&audio_data, config.sample_rate, config.channels)
-            {
-                Ok(audio_frame) => {
-                    // Try up to 3 times with the current frame before queuing
-                    let mut retry_count = 0;
-                    const MAX_RETRIES: usize = 3;
-
-                    loop {
-                        match source.capture_frame(&audio_frame).await {
-                            Ok(()) => {
-                                debug!("Successfully sent audio frame directly");
-
-                                // Try to drain any queued audio now that we have capacity
-                                let queued_data: Vec<Vec<u8>> = {
-                                    let mut queue = audio_queue.lock().await;
-                                    if !queue.is_empty() && queue.len() <= 5 {
-                                        // Drain a few items to reduce latency
-                                        let drain_count = queue.len().min(5);
-                                        queue.drain(..drain_count).collect()
-                                    } else {
-                                        Vec::new()
-                                    }
-                                };
-
-                                // Process drained items without holding locks
-                                for data in queued_data {
-                                    if let Ok(frame) = Self::convert_audio_to_frame_ref(
-                                        &data,
-                                        config.sample_rate,
-                                        config.channels,
-                                    ) {
-                                        let _ = source.capture_frame(&frame).await;
-                                    }
-                                }
-
-                                return Ok(());
-                            }
-                            Err(e) if retry_count < MAX_RETRIES => {
-                                retry_count += 1;
-                                debug!(
-                                    "Retry {}/{} for frame capture after error: {:?}",
-                                    retry_count, MAX_RETRIES, e
-                                );
-                                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
-                            }
-                            Err(e) => {
-                                error!(
-                                    "Failed to capture frame after {} retries: {:?}, queuing",
-                                    MAX_RETRIES, e
-                                );
-                                // Apply bounded queue with backpressure - drop oldest if queue is too large
-                                let mut queue = audio_queue.lock().await;
-                                if queue.len() >= super::MAX_AUDIO_QUEUE_SIZE {
-                                    // Drop the oldest audio frame to prevent unbounded latency
-                                    match queue.pop_front() {
-                                        Some(_dropped) => {
-                                            warn!(
-                                                "Audio queue full, dropping oldest frame to prevent latency spike"
-                                            );
-                                        }
-                                        None => warn!(
-                                            "Audio queue full but nothing to drop; queue state may be inconsistent"
-                                        ),
-                                    }
-                                }
-                                queue.push_back(audio_data);
-                                debug!(
-                                    "Queued audio data for later processing, queue size: {}",
-                                    queue.len()
-                                );
-                                return Ok(());
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    // Conversion failure is likely a format issue, not transient
-                    error!("Failed to convert audio frame: {:?}", e);
-                    return Err(AppError::InternalServerError(format!(
-                        "Audio format error: {e:?}"
-                    )));
-                }
+        if audio_source.lock().await.is_some() {
+            // Reject malformed audio here; the pacing task can't report errors back
+            let bytes_per_sample = 2 * config.channels.max(1) as usize;
+            if !audio_data.len().is_multiple_of(bytes_per_sample) {
+                error!(
+                    "Invalid audio data length {} for {} channel 16-bit PCM",
+                    audio_data.len(),
+                    config.channels
+                );
+                return Err(AppError::InternalServerError(format!(
+                    "Audio format error: {} bytes is not a whole number of {}-channel 16-bit samples",
+                    audio_data.len(),
+                    config.channels
+                )));
             }
+
+            let mut pacer = audio_pacer.lock().await;
+            pacer.push(&audio_data);
+            debug!(
+                "Buffered audio for pacing, {} bytes pending",
+                pacer.buffered_bytes()
+            );
+            return Ok(());
         }
 
         // No audio source yet, queue for when track is ready with bounded queue
@@ -353,6 +271,79 @@ impl LiveKitClient {
         Ok(())
     }
 
+    /// Move audio queued while no source existed into the pacer
+    pub(super) async fn drain_queue_into_pacer(
+        audio_queue: &Arc<Mutex<VecDeque<Vec<u8>>>>,
+        audio_pacer: &Arc<Mutex<AudioPacer>>,
+    ) -> usize {
+        let queued: Vec<Vec<u8>> = audio_queue.lock().await.drain(..).collect();
+        let mut pacer = audio_pacer.lock().await;
+        for audio_data in &queued {
+            pacer.push(audio_data);
+        }
+        queued.len()
+    }
+
+    /// Publish one pacer frame per frame period to the current audio source.
+    ///
+    /// The source is looked up on every tick rather than captured, so the task
+    /// keeps running when a reconnect swaps in a new source.
+    pub(super) fn spawn_pacing_task(
+        audio_pacer: Arc<Mutex<AudioPacer>>,
+        audio_source: Arc<Mutex<Option<Arc<NativeAudioSource>>>>,
+        stats: Arc<Mutex<QueueStats>>,
+        sample_rate: u32,
+        channels: u16,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let period = audio_pacer.lock().await.frame_duration();
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut reported_underruns = 0;
+
+            loop {
+                interval.tick().await;
+
+                // Hold frames in the pacer until there is somewhere to send them
+                let Some(source) = audio_source.lock().await.clone() else {
+                    continue;
+                };
+
+                let (frame, underruns) = {
+                    let mut pacer = audio_pacer.lock().await;
+                    (pacer.tick(), pacer.underruns())
+                };
+
+                if underruns > reported_underruns {
+                    let new_underruns = underruns - reported_underruns;
+                    reported_underruns = underruns;
+                    debug!("TTS audio underrun, {} total", underruns);
+                    stats.lock().await.audio_underruns += new_underruns;
+                    global_metrics().add_counter(
+                        "waav_livekit_audio_underruns_total",
+                        "Gaps in published TTS audio after playback started",
+                        &[],
+                        new_underruns as f64,
+                    );
+                }
+
+                let Some(frame) = frame else {
+                    continue;
+                };
+                match Self::convert_audio_to_frame_ref(&frame, sample_rate, channels) {
+                    Ok(audio_frame) => {
+                        if let Err(e) = source.capture_frame(&audio_frame).await {
+                            error!("Failed to capture paced audio frame: {:?}", e);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to convert paced audio frame: {:?}", e);
+                    }
+                }
+            }
+        })
+    }
+
     pub(super) fn convert_audio_to_frame_ref(
         audio_data: &[u8],
         sample_rate: u32,
@@ -515,51 +506,16 @@ impl LiveKitClient {
                         *ctx.audio_source.lock().await = Some(new_audio_source.clone());
                         *ctx.local_track_publication.lock().await = Some(publication);
 
-                        // Spawn a task to drain queued audio just like in initial setup
-                        let audio_queue_clone = Arc::clone(&ctx.audio_queue);
-                        let audio_source_clone = Arc::clone(&new_audio_source);
-                        let sample_rate = ctx.config.sample_rate;
-                        let channels = ctx.config.channels;
-                        let active_streams_clone = Arc::clone(&ctx.active_streams);
-
-                        let handle = tokio::spawn(async move {
-                            // Extract all queued data to avoid holding the lock during async operations
-                            let queued_data: Vec<Vec<u8>> = {
-                                let mut queue = audio_queue_clone.lock().await;
-                                if !queue.is_empty() {
-                                    info!(
-                                        "Draining {} queued audio messages after reconnect",
-                                        queue.len()
-                                    );
-                                    queue.drain(..).collect()
-                                } else {
-                                    Vec::new()
-                                }
-                            };
-
-                            // Process the drained audio without holding any locks
-                            for audio_data in queued_data {
-                                match Self::convert_audio_to_frame_ref(
-                                    &audio_data,
-                                    sample_rate,
-                                    channels,
-                                ) {
-                                    Ok(audio_frame) => {
-                                        if let Err(e) =
-                                            audio_source_clone.capture_frame(&audio_frame).await
-                                        {
-                                            error!("Failed to send queued audio frame: {:?}", e);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("Failed to convert queued audio to frame: {:?}", e);
-                                    }
-                                }
-                            }
-                        });
-
-                        // Track the spawn handle for lifecycle management
-                        active_streams_clone.lock().await.push(handle);
+                        // Audio queued while the source was down resumes through the pacer,
+                        // whose task picks up the new source on its next tick
+                        let queued =
+                            Self::drain_queue_into_pacer(&ctx.audio_queue, &ctx.audio_pacer).await;
+                        if queued > 0 {
+                            info!(
+                                "Moved {} queued audio messages to the pacer after reconnect",
+                                queued
+                            );
+                        }
 
                         info!("Successfully reconnected and re-published audio track");
 
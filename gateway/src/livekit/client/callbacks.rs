@@ -6,7 +6,7 @@
 
 use std::sync::Arc;
 
-use super::{DataMessage, LiveKitClient, ParticipantDisconnectEvent};
+use super::{AudioTrackEvent, DataMessage, DtmfEvent, LiveKitClient, ParticipantDisconnectEvent};
 
 impl LiveKitClient {
     /// Set the callback function for handling incoming audio chunks
@@ -86,4 +86,26 @@ impl LiveKitClient {
     {
         self.participant_disconnect_callback = Some(Arc::new(callback));
     }
+
+    /// Register a callback to handle remote audio tracks being subscribed.
+    ///
+    /// The callback fires once per audio track that passes the
+    /// `listen_participants` filter, e.g. when a SIP caller's audio appears.
+    pub fn set_audio_track_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(AudioTrackEvent) + Send + Sync + 'static,
+    {
+        self.audio_track_callback = Some(Arc::new(callback));
+    }
+
+    /// Register a callback to handle DTMF digits sent by SIP participants.
+    ///
+    /// The callback fires once per digit, whatever the `listen_participants`
+    /// filter says, so keypad entry is noticed for every caller in the room.
+    pub fn set_dtmf_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(DtmfEvent) + Send + Sync + 'static,
+    {
+        self.dtmf_callback = Some(Arc::new(callback));
+    }
 }
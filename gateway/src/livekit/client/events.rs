@@ -12,8 +12,9 @@ use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
 use super::{
-    AudioCallback, DataCallback, DataMessage, LiveKitClient, LiveKitConfig,
-    ParticipantDisconnectCallback, ParticipantDisconnectEvent,
+    AudioCallback, AudioTrackCallback, AudioTrackEvent, DataCallback, DataMessage, DtmfCallback,
+    DtmfEvent, LiveKitClient, LiveKitConfig, ParticipantDisconnectCallback,
+    ParticipantDisconnectEvent,
 };
 use crate::AppError;
 #[cfg(feature = "noise-filter")]
@@ -25,6 +26,8 @@ impl LiveKitClient {
             let audio_callback = self.audio_callback.clone();
             let data_callback = self.data_callback.clone();
             let participant_disconnect_callback = self.participant_disconnect_callback.clone();
+            let audio_track_callback = self.audio_track_callback.clone();
+            let dtmf_callback = self.dtmf_callback.clone();
             let active_streams = Arc::clone(&self.active_streams);
             let is_connected = Arc::clone(&self.is_connected);
             let config = self.config.clone();
@@ -36,6 +39,8 @@ impl LiveKitClient {
                         &audio_callback,
                         &data_callback,
                         &participant_disconnect_callback,
+                        &audio_track_callback,
+                        &dtmf_callback,
                         &active_streams,
                         &is_connected,
                         &config,
@@ -55,11 +60,14 @@ impl LiveKitClient {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn restart_event_handler(
         mut room_events: tokio::sync::mpsc::UnboundedReceiver<RoomEvent>,
         audio_callback: &Option<AudioCallback>,
         data_callback: &Option<DataCallback>,
         participant_disconnect_callback: &Option<ParticipantDisconnectCallback>,
+        audio_track_callback: &Option<AudioTrackCallback>,
+        dtmf_callback: &Option<DtmfCallback>,
         active_streams: &Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
         is_connected: &Arc<Mutex<bool>>,
         config: &LiveKitConfig,
@@ -67,6 +75,8 @@ impl LiveKitClient {
         let audio_callback = audio_callback.clone();
         let data_callback = data_callback.clone();
         let participant_disconnect_callback = participant_disconnect_callback.clone();
+        let audio_track_callback = audio_track_callback.clone();
+        let dtmf_callback = dtmf_callback.clone();
         let active_streams = Arc::clone(active_streams);
         let is_connected = Arc::clone(is_connected);
         let config = config.clone();
@@ -79,6 +89,8 @@ impl LiveKitClient {
                     &audio_callback,
                     &data_callback,
                     &participant_disconnect_callback,
+                    &audio_track_callback,
+                    &dtmf_callback,
                     &active_streams,
                     &is_connected,
                     &config,
@@ -92,11 +104,14 @@ impl LiveKitClient {
         });
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_room_event(
         event: RoomEvent,
         audio_callback: &Option<AudioCallback>,
         data_callback: &Option<DataCallback>,
         participant_disconnect_callback: &Option<ParticipantDisconnectCallback>,
+        audio_track_callback: &Option<AudioTrackCallback>,
+        dtmf_callback: &Option<DtmfCallback>,
         active_streams: &Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
         is_connected: &Arc<Mutex<bool>>,
         config: &LiveKitConfig,
@@ -133,6 +148,17 @@ impl LiveKitClient {
                             participant.identity()
                         );
 
+                        if let Some(callback) = audio_track_callback {
+                            callback(AudioTrackEvent {
+                                participant_identity: participant.identity().to_string(),
+                                track_sid: publication.sid().to_string(),
+                                timestamp: std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .unwrap_or_default()
+                                    .as_millis() as u64,
+                            });
+                        }
+
                         if let Some(callback) = audio_callback {
                             let callback_clone = Arc::clone(callback);
                             let rtc_track = audio_track.rtc_track();
@@ -308,6 +334,24 @@ impl LiveKitClient {
                     debug!("No data callback set, ignoring data message");
                 }
             }
+            RoomEvent::SipDTMFReceived {
+                participant, digit, ..
+            } => {
+                // Never log the digit; callers key in card numbers
+                let participant_identity = participant.map(|p| p.identity().to_string());
+                debug!("DTMF digit received from {:?}", participant_identity);
+
+                if let Some(callback) = dtmf_callback {
+                    callback(DtmfEvent {
+                        participant_identity,
+                        digit,
+                        timestamp: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_millis() as u64,
+                    });
+                }
+            }
             _ => {
                 debug!("Received other room event: {:?}", event);
             }
@@ -26,6 +26,7 @@ use tokio::sync::{Mutex, mpsc};
 use tracing::warn;
 
 pub(crate) use super::operations::{LiveKitOperation, OperationQueue, QueueStats};
+pub(crate) use super::pacing::AudioPacer;
 pub(crate) use super::types::LiveKitConfig;
 
 /// Callback type for handling incoming audio chunks.
@@ -37,6 +38,12 @@ pub type DataCallback = Arc<dyn Fn(DataMessage) + Send + Sync>;
 /// Callback type for handling participant disconnection events.
 pub type ParticipantDisconnectCallback = Arc<dyn Fn(ParticipantDisconnectEvent) + Send + Sync>;
 
+/// Callback type for handling remote audio track subscriptions.
+pub type AudioTrackCallback = Arc<dyn Fn(AudioTrackEvent) + Send + Sync>;
+
+/// Callback type for handling DTMF digits received from SIP participants.
+pub type DtmfCallback = Arc<dyn Fn(DtmfEvent) + Send + Sync>;
+
 /// LiveKit data message structure.
 #[derive(Debug, Clone)]
 pub struct DataMessage {
@@ -63,6 +70,41 @@ pub struct ParticipantDisconnectEvent {
     pub timestamp: u64,
 }
 
+/// LiveKit remote audio track subscription event structure.
+#[derive(Debug, Clone)]
+pub struct AudioTrackEvent {
+    /// The participant identity who published the track.
+    pub participant_identity: String,
+    /// The track SID.
+    pub track_sid: String,
+    /// Timestamp when the track was subscribed.
+    pub timestamp: u64,
+}
+
+/// LiveKit SIP DTMF event structure.
+///
+/// Callers key in card numbers and PINs: the digit must never be logged or
+/// stored.
+#[derive(Clone)]
+pub struct DtmfEvent {
+    /// The SIP participant who sent the digit (if known).
+    pub participant_identity: Option<String>,
+    /// The key pressed ("0"-"9", "*" or "#"), if LiveKit reported it.
+    pub digit: Option<String>,
+    /// Timestamp when the digit was received.
+    pub timestamp: u64,
+}
+
+impl std::fmt::Debug for DtmfEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DtmfEvent")
+            .field("participant_identity", &self.participant_identity)
+            .field("digit", &self.digit.as_ref().map(|_| "[redacted]"))
+            .field("timestamp", &self.timestamp)
+            .finish()
+    }
+}
+
 /// Reliable data channel threshold (200 MB) to handle bursty payloads without premature backpressure.
 pub(crate) const RELIABLE_BUFFER_THRESHOLD_BYTES: u64 = 200 * 1024 * 1024;
 
@@ -79,6 +121,8 @@ pub struct LiveKitClient {
     pub(crate) audio_callback: Option<AudioCallback>,
     pub(crate) data_callback: Option<DataCallback>,
     pub(crate) participant_disconnect_callback: Option<ParticipantDisconnectCallback>,
+    pub(crate) audio_track_callback: Option<AudioTrackCallback>,
+    pub(crate) dtmf_callback: Option<DtmfCallback>,
     pub(crate) active_streams: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
     pub(crate) is_connected: Arc<Mutex<bool>>,
     // Atomic flags for fast status queries
@@ -89,6 +133,8 @@ pub struct LiveKitClient {
     pub(crate) local_track_publication: Arc<Mutex<Option<LocalTrackPublication>>>,
     pub(crate) _audio_buffer_pool: Arc<Mutex<Vec<Vec<u8>>>>,
     pub(crate) audio_queue: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    /// Re-chunks provider audio into fixed frames for the pacing task
+    pub(crate) audio_pacer: Arc<Mutex<AudioPacer>>,
     pub(crate) operation_queue: Option<OperationQueue>,
     pub(crate) operation_worker_handle: Option<tokio::task::JoinHandle<()>>,
     pub(crate) event_handler_handle: Option<tokio::task::JoinHandle<()>>,
@@ -131,6 +177,8 @@ impl LiveKitClient {
     pub fn new(config: LiveKitConfig) -> Self {
         let buffer_capacity =
             ((config.sample_rate as usize * config.channels as usize * 2) / 100).max(4096);
+        let audio_pacer =
+            AudioPacer::new(config.output_pacing, config.sample_rate, config.channels);
 
         Self {
             config,
@@ -139,6 +187,8 @@ impl LiveKitClient {
             audio_callback: None,
             data_callback: None,
             participant_disconnect_callback: None,
+            audio_track_callback: None,
+            dtmf_callback: None,
             active_streams: Arc::new(Mutex::new(Vec::new())),
             is_connected: Arc::new(Mutex::new(false)),
             is_connected_atomic: Arc::new(AtomicBool::new(false)),
@@ -152,6 +202,7 @@ impl LiveKitClient {
                     .collect(),
             )),
             audio_queue: Arc::new(Mutex::new(VecDeque::new())),
+            audio_pacer: Arc::new(Mutex::new(audio_pacer)),
             operation_queue: None,
             operation_worker_handle: None,
             event_handler_handle: None,
@@ -175,6 +226,16 @@ impl LiveKitClient {
         self.audio_queue.lock().await.len()
     }
 
+    #[cfg(test)]
+    pub(crate) async fn get_paced_bytes(&self) -> usize {
+        self.audio_pacer.lock().await.buffered_bytes()
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn pace_queued_audio(&self) -> usize {
+        Self::drain_queue_into_pacer(&self.audio_queue, &self.audio_pacer).await
+    }
+
     #[cfg(test)]
     pub(crate) async fn set_connected(&self, connected: bool) {
         use std::sync::atomic::Ordering;
@@ -12,8 +12,8 @@ use tokio::sync::Mutex;
 use tracing::{info, warn};
 
 use super::{
-    AudioCallback, DataCallback, LiveKitClient, LiveKitConfig, LiveKitOperation, OperationQueue,
-    ParticipantDisconnectCallback,
+    AudioCallback, AudioPacer, AudioTrackCallback, DataCallback, DtmfCallback, LiveKitClient,
+    LiveKitConfig, LiveKitOperation, OperationQueue, ParticipantDisconnectCallback,
 };
 use crate::AppError;
 
@@ -21,6 +21,7 @@ use crate::AppError;
 pub(super) struct OperationContext {
     pub(super) room: Arc<Mutex<Option<Room>>>,
     pub(super) audio_queue: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    pub(super) audio_pacer: Arc<Mutex<AudioPacer>>,
     pub(super) audio_source: Arc<Mutex<Option<Arc<NativeAudioSource>>>>,
     pub(super) is_connected: Arc<Mutex<bool>>,
     pub(super) config: LiveKitConfig,
@@ -29,6 +30,8 @@ pub(super) struct OperationContext {
     pub(super) audio_callback: Option<AudioCallback>,
     pub(super) data_callback: Option<DataCallback>,
     pub(super) participant_disconnect_callback: Option<ParticipantDisconnectCallback>,
+    pub(super) audio_track_callback: Option<AudioTrackCallback>,
+    pub(super) dtmf_callback: Option<DtmfCallback>,
 }
 
 impl LiveKitClient {
@@ -39,6 +42,7 @@ impl LiveKitClient {
         let context = OperationContext {
             room: Arc::clone(&self.room),
             audio_queue: Arc::clone(&self.audio_queue),
+            audio_pacer: Arc::clone(&self.audio_pacer),
             audio_source: Arc::clone(&self.audio_source),
             is_connected: Arc::clone(&self.is_connected),
             config: self.config.clone(),
@@ -47,6 +51,8 @@ impl LiveKitClient {
             audio_callback: self.audio_callback.clone(),
             data_callback: self.data_callback.clone(),
             participant_disconnect_callback: self.participant_disconnect_callback.clone(),
+            audio_track_callback: self.audio_track_callback.clone(),
+            dtmf_callback: self.dtmf_callback.clone(),
         };
         let stats = Arc::clone(&self.stats);
 
@@ -130,6 +136,7 @@ impl LiveKitClient {
                     audio_data,
                     &ctx.audio_queue,
                     &ctx.audio_source,
+                    &ctx.audio_pacer,
                     &ctx.is_connected,
                     &ctx.config,
                 )
@@ -174,6 +181,7 @@ impl LiveKitClient {
                 let result = LiveKitClient::process_clear_audio(
                     &ctx.audio_queue,
                     &ctx.audio_source,
+                    &ctx.audio_pacer,
                     &ctx.is_connected,
                 )
                 .await;
@@ -201,6 +209,8 @@ impl LiveKitClient {
                             &ctx.audio_callback,
                             &ctx.data_callback,
                             &ctx.participant_disconnect_callback,
+                            &ctx.audio_track_callback,
+                            &ctx.dtmf_callback,
                             &ctx.active_streams,
                             &ctx.is_connected,
                             &ctx.config,
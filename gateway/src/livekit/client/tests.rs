@@ -2,6 +2,7 @@
 
 use super::*;
 use crate::AppError;
+use crate::livekit::AudioPacingConfig;
 use tokio::time::{Duration, timeout};
 
 fn create_test_config() -> LiveKitConfig {
@@ -13,6 +14,7 @@ fn create_test_config() -> LiveKitConfig {
         channels: 1,
         enable_noise_filter: cfg!(feature = "noise-filter"),
         listen_participants: vec![],
+        output_pacing: AudioPacingConfig::default(),
     }
 }
 
@@ -72,6 +74,12 @@ async fn test_livekit_client_callback_registration() {
 
     client.set_participant_disconnect_callback(|_event| {});
     assert!(client.participant_disconnect_callback.is_some());
+
+    client.set_audio_track_callback(|_event| {});
+    assert!(client.audio_track_callback.is_some());
+
+    client.set_dtmf_callback(|_event| {});
+    assert!(client.dtmf_callback.is_some());
 }
 
 #[tokio::test]
@@ -229,6 +237,25 @@ async fn test_livekit_client_audio_queue_drain_success() {
     assert_eq!(client.get_audio_queue_len().await, 0);
 }
 
+#[tokio::test]
+async fn test_livekit_client_queued_audio_moves_to_pacer() {
+    let config = create_test_config();
+    let client = LiveKitClient::new(config);
+    client.set_connected(true).await;
+
+    // Irregular chunks queue up until the track is published
+    for len in [960, 4, 7_200] {
+        assert!(client.send_tts_audio(vec![0u8; len]).await.is_ok());
+    }
+    assert_eq!(client.pace_queued_audio().await, 3);
+    assert_eq!(client.get_audio_queue_len().await, 0);
+    assert_eq!(client.get_paced_bytes().await, 8_164);
+
+    // Clearing also drops audio waiting in the pacer
+    assert!(client.clear_audio().await.is_ok());
+    assert_eq!(client.get_paced_bytes().await, 0);
+}
+
 #[tokio::test]
 async fn test_livekit_client_message_send_with_serializable_struct() {
     use serde::{Deserialize, Serialize};
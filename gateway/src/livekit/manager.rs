@@ -179,7 +179,7 @@ impl LiveKitManager {
     ///
     /// # Examples
     /// ```rust,no_run
-    /// # use waav_gateway::livekit::{LiveKitManager, LiveKitConfig};
+    /// # use waav_gateway::livekit::{AudioPacingConfig, LiveKitManager, LiveKitConfig};
     /// # use std::sync::Arc;
     /// # use tokio;
     /// # #[tokio::main]
@@ -192,6 +192,7 @@ impl LiveKitManager {
     ///     channels: 1,
     ///     enable_noise_filter: cfg!(feature = "noise-filter"),
     ///     listen_participants: vec![],
+    ///     output_pacing: AudioPacingConfig::default(),
     /// };
     ///
     /// let mut manager = LiveKitManager::new();
@@ -269,6 +270,7 @@ impl Drop for LiveKitManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::livekit::AudioPacingConfig;
     use tokio::time::{Duration, timeout};
 
     #[tokio::test]
@@ -302,6 +304,7 @@ mod tests {
             channels: 1,
             enable_noise_filter: cfg!(feature = "noise-filter"),
             listen_participants: vec![],
+            output_pacing: AudioPacingConfig::default(),
         };
 
         // Try to initialize (this will fail with mock config, but we can still test the clear_audio logic)
@@ -346,6 +349,7 @@ mod tests {
             channels: 1,
             enable_noise_filter: cfg!(feature = "noise-filter"),
             listen_participants: vec![],
+            output_pacing: AudioPacingConfig::default(),
         };
 
         // Try initialization (will fail with mock config)
@@ -18,7 +18,7 @@
 //! ## Usage Example
 //!
 //! ```rust,no_run
-//! use waav_gateway::livekit::{LiveKitConfig, LiveKitManager};
+//! use waav_gateway::livekit::{AudioPacingConfig, LiveKitConfig, LiveKitManager};
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -31,6 +31,7 @@
 //!         channels: 1,
 //!         enable_noise_filter: cfg!(feature = "noise-filter"),
 //!         listen_participants: vec![],
+//!         output_pacing: AudioPacingConfig::default(),
 //!     };
 //!
 //!     // Create and initialize manager
@@ -69,14 +70,19 @@
 mod client;
 mod manager;
 pub mod operations;
+mod pacing;
 pub mod room_handler;
 pub mod sip_handler;
 mod types;
 
 // Re-export public types and traits
-pub use client::{AudioCallback, DataCallback, DataMessage, LiveKitClient};
+pub use client::{
+    AudioCallback, AudioTrackCallback, AudioTrackEvent, DataCallback, DataMessage, DtmfCallback,
+    DtmfEvent, LiveKitClient,
+};
 pub use manager::LiveKitManager;
 pub use operations::{LiveKitOperation, OperationQueue};
+pub use pacing::{AudioPacer, AudioPacingConfig};
 pub use room_handler::LiveKitRoomHandler;
 pub use sip_handler::{DispatchConfig, LiveKitSipHandler, TrunkConfig};
 pub use types::{
@@ -111,6 +117,7 @@ mod tests {
             channels: 1,
             enable_noise_filter: cfg!(feature = "noise-filter"),
             listen_participants: vec![],
+            output_pacing: AudioPacingConfig::default(),
         };
 
         assert_eq!(config.url, "wss://test.example.com");
@@ -147,6 +147,8 @@ pub struct QueueStats {
     pub message_operations: u64,
     pub average_latency_ms: u64,
     pub max_latency_ms: u64,
+    /// Gaps in paced TTS audio after playback started
+    pub audio_underruns: u64,
 }
 
 impl QueueStats {
@@ -189,14 +191,15 @@ impl QueueStats {
 
     pub fn log_stats(&self) {
         debug!(
-            "Queue stats - Total: {}, Success: {}, Failed: {}, Audio: {}, Messages: {}, Avg latency: {}ms, Max latency: {}ms",
+            "Queue stats - Total: {}, Success: {}, Failed: {}, Audio: {}, Messages: {}, Avg latency: {}ms, Max latency: {}ms, Audio underruns: {}",
             self.total_operations,
             self.successful_operations,
             self.failed_operations,
             self.audio_operations,
             self.message_operations,
             self.average_latency_ms,
-            self.max_latency_ms
+            self.max_latency_ms,
+            self.audio_underruns
         );
     }
 }
@@ -289,6 +292,7 @@ mod tests {
         assert_eq!(stats.message_operations, 0);
         assert_eq!(stats.average_latency_ms, 0);
         assert_eq!(stats.max_latency_ms, 0);
+        assert_eq!(stats.audio_underruns, 0);
     }
 
     #[test]
@@ -0,0 +1,365 @@
+//! Output pacing for published TTS audio
+//!
+//! TTS providers deliver audio in whatever chunk sizes their stream produces,
+//! from a few milliseconds to several seconds. Handing those chunks straight
+//! to the track source makes publishing bursty, so [`AudioPacer`] buffers
+//! provider audio and hands out fixed-duration frames, one per pacing tick.
+//!
+//! Playback starts once the low watermark is buffered. If the buffer runs dry
+//! mid-playback the pacer waits for more audio; when it arrives, that gap is
+//! counted as an underrun and the pacer rebuffers to the watermark. If no audio
+//! arrives within the watermark duration the utterance is treated as finished,
+//! and any partial tail is padded with silence so every frame has the same size.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Default duration of each published frame
+pub const DEFAULT_FRAME_DURATION_MS: u32 = 20;
+
+/// Default audio buffered before playback starts
+pub const DEFAULT_LOW_WATERMARK_MS: u32 = 120;
+
+/// Shortest frame WebRTC audio sources accept
+pub const MIN_FRAME_DURATION_MS: u32 = 10;
+
+/// Longest configurable frame; longer frames defeat the point of pacing
+pub const MAX_FRAME_DURATION_MS: u32 = 100;
+
+/// Upper bound on the low watermark, matching the audio queue's two seconds
+pub const MAX_LOW_WATERMARK_MS: u32 = 2_000;
+
+/// Frame size and startup buffering for published audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioPacingConfig {
+    /// Duration of each published frame in milliseconds (multiple of 10)
+    pub frame_duration_ms: u32,
+    /// Audio buffered before playback starts or resumes, in milliseconds
+    pub low_watermark_ms: u32,
+}
+
+impl Default for AudioPacingConfig {
+    fn default() -> Self {
+        Self {
+            frame_duration_ms: DEFAULT_FRAME_DURATION_MS,
+            low_watermark_ms: DEFAULT_LOW_WATERMARK_MS,
+        }
+    }
+}
+
+impl AudioPacingConfig {
+    /// Check the frame duration and watermark are usable
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_FRAME_DURATION_MS..=MAX_FRAME_DURATION_MS).contains(&self.frame_duration_ms)
+            || !self.frame_duration_ms.is_multiple_of(10)
+        {
+            return Err(format!(
+                "audio_frame_ms must be a multiple of 10 between {MIN_FRAME_DURATION_MS} and {MAX_FRAME_DURATION_MS}, got {}",
+                self.frame_duration_ms
+            ));
+        }
+        if self.low_watermark_ms > MAX_LOW_WATERMARK_MS {
+            return Err(format!(
+                "audio_low_watermark_ms must be at most {MAX_LOW_WATERMARK_MS}, got {}",
+                self.low_watermark_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PacerState {
+    /// Waiting for the low watermark before playing
+    Buffering,
+    /// Emitting a frame per tick
+    Playing,
+    /// Ran dry during playback; counts ticks without new audio
+    Starved { idle_ticks: u32 },
+}
+
+/// Re-chunks 16-bit PCM into fixed-size frames emitted one per tick
+#[derive(Debug)]
+pub struct AudioPacer {
+    frame_duration: Duration,
+    frame_bytes: usize,
+    watermark_bytes: usize,
+    /// Ticks without new audio before a dry or under-watermark buffer is played out
+    hold_ticks: u32,
+    buffer: VecDeque<u8>,
+    state: PacerState,
+    idle_ticks: u32,
+    underruns: u64,
+}
+
+impl AudioPacer {
+    /// Create a pacer for interleaved 16-bit PCM at `sample_rate` and `channels`
+    pub fn new(config: AudioPacingConfig, sample_rate: u32, channels: u16) -> Self {
+        let bytes_per_ms = |ms: u32| {
+            let samples = (sample_rate as u64 * ms as u64 / 1000) as usize;
+            samples * channels.max(1) as usize * 2
+        };
+        let frame_ms = config.frame_duration_ms.max(MIN_FRAME_DURATION_MS);
+        let frame_bytes = bytes_per_ms(frame_ms).max(2);
+
+        Self {
+            frame_duration: Duration::from_millis(frame_ms as u64),
+            frame_bytes,
+            // Starting below one frame would stall on the very first tick
+            watermark_bytes: bytes_per_ms(config.low_watermark_ms).max(frame_bytes),
+            hold_ticks: config.low_watermark_ms.div_ceil(frame_ms).max(1),
+            buffer: VecDeque::new(),
+            state: PacerState::Buffering,
+            idle_ticks: 0,
+            underruns: 0,
+        }
+    }
+
+    /// Time between ticks
+    pub fn frame_duration(&self) -> Duration {
+        self.frame_duration
+    }
+
+    /// Size in bytes of every emitted frame
+    pub fn frame_bytes(&self) -> usize {
+        self.frame_bytes
+    }
+
+    /// Bytes waiting to be emitted
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of mid-playback gaps since creation
+    pub fn underruns(&self) -> u64 {
+        self.underruns
+    }
+
+    /// Buffer provider audio
+    pub fn push(&mut self, audio: &[u8]) {
+        if audio.is_empty() {
+            return;
+        }
+        if matches!(self.state, PacerState::Starved { .. }) {
+            self.underruns += 1;
+            self.state = PacerState::Buffering;
+        }
+        self.idle_ticks = 0;
+        self.buffer.extend(audio);
+    }
+
+    /// Drop buffered audio, e.g. when the client interrupts playback
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.state = PacerState::Buffering;
+        self.idle_ticks = 0;
+    }
+
+    /// Advance one frame period, returning the frame to publish if any
+    pub fn tick(&mut self) -> Option<Vec<u8>> {
+        match self.state {
+            PacerState::Buffering => {
+                self.idle_ticks = self.idle_ticks.saturating_add(1);
+                // Utterances shorter than the watermark still get played
+                let ready = !self.buffer.is_empty()
+                    && (self.buffer.len() >= self.watermark_bytes
+                        || self.idle_ticks > self.hold_ticks);
+                if !ready {
+                    return None;
+                }
+                self.state = PacerState::Playing;
+                self.tick()
+            }
+            PacerState::Playing => {
+                if self.buffer.len() >= self.frame_bytes {
+                    return Some(self.buffer.drain(..self.frame_bytes).collect());
+                }
+                self.state = PacerState::Starved { idle_ticks: 1 };
+                None
+            }
+            PacerState::Starved { idle_ticks } => {
+                if idle_ticks < self.hold_ticks {
+                    self.state = PacerState::Starved {
+                        idle_ticks: idle_ticks + 1,
+                    };
+                    return None;
+                }
+                // Playback finished; pad out whatever tail is left
+                self.state = PacerState::Buffering;
+                self.idle_ticks = 0;
+                if self.buffer.is_empty() {
+                    return None;
+                }
+                let mut frame: Vec<u8> = self.buffer.drain(..).collect();
+                frame.resize(self.frame_bytes, 0);
+                Some(frame)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 24_000;
+    // 20ms at 24kHz mono 16-bit
+    const FRAME: usize = 960;
+
+    fn pacer() -> AudioPacer {
+        AudioPacer::new(AudioPacingConfig::default(), RATE, 1)
+    }
+
+    fn ms(duration: u32) -> Vec<u8> {
+        vec![1; (RATE * duration / 1000) as usize * 2]
+    }
+
+    /// Tick until the pacer has nothing more to emit
+    fn drain(pacer: &mut AudioPacer) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        for _ in 0..1_000 {
+            if let Some(frame) = pacer.tick() {
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    #[test]
+    fn test_default_frame_size() {
+        let pacer = pacer();
+        assert_eq!(pacer.frame_bytes(), FRAME);
+
+        let stereo = AudioPacer::new(
+            AudioPacingConfig {
+                frame_duration_ms: 10,
+                low_watermark_ms: 0,
+            },
+            48_000,
+            2,
+        );
+        assert_eq!(stereo.frame_bytes(), 1_920);
+    }
+
+    #[test]
+    fn test_waits_for_low_watermark() {
+        let mut pacer = pacer();
+        pacer.push(&ms(100));
+        assert!(pacer.tick().is_none());
+
+        pacer.push(&ms(20));
+        assert_eq!(pacer.tick().map(|f| f.len()), Some(FRAME));
+    }
+
+    #[test]
+    fn test_irregular_chunks_produce_constant_frames() {
+        let mut pacer = pacer();
+        let total: usize = [3_000, 7, 200, 33, 1, 450, 13]
+            .into_iter()
+            .map(|duration| {
+                let chunk = ms(duration);
+                pacer.push(&chunk);
+                chunk.len()
+            })
+            .sum();
+        // Odd-sized chunk that is not a whole number of milliseconds
+        pacer.push(&[1; 302]);
+
+        let frames = drain(&mut pacer);
+        assert!(frames.iter().all(|f| f.len() == FRAME));
+        assert_eq!(frames.len(), (total + 302).div_ceil(FRAME));
+        assert_eq!(pacer.underruns(), 0);
+        assert_eq!(pacer.buffered_bytes(), 0);
+    }
+
+    #[test]
+    fn test_interleaved_pushes_and_ticks() {
+        let mut pacer = pacer();
+        let chunks = [200, 5, 3_000, 15, 40, 1, 1, 120];
+        let mut frames = Vec::new();
+
+        for duration in chunks {
+            pacer.push(&ms(duration));
+            for _ in 0..3 {
+                frames.extend(pacer.tick());
+            }
+        }
+        frames.extend(drain(&mut pacer));
+
+        assert!(!frames.is_empty());
+        assert!(frames.iter().all(|f| f.len() == FRAME));
+    }
+
+    #[test]
+    fn test_short_utterance_below_watermark_plays() {
+        let mut pacer = pacer();
+        pacer.push(&ms(50));
+
+        let frames = drain(&mut pacer);
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|f| f.len() == FRAME));
+        // The last frame is padded with silence
+        assert_eq!(frames[2][FRAME / 2 - 1], 1);
+        assert_eq!(frames[2][FRAME / 2], 0);
+    }
+
+    #[test]
+    fn test_mid_playback_gap_counts_underrun() {
+        let mut pacer = pacer();
+        pacer.push(&ms(200));
+        let played = (0..10).filter_map(|_| pacer.tick()).count();
+        assert_eq!(played, 10);
+
+        // Buffer is dry, and the provider is late with the next chunk
+        assert!(pacer.tick().is_none());
+        assert!(pacer.tick().is_none());
+        pacer.push(&ms(200));
+        assert_eq!(pacer.underruns(), 1);
+
+        // Rebuffers to the watermark before resuming
+        assert!(pacer.tick().is_some());
+        assert_eq!(drain(&mut pacer).len(), 9);
+        assert_eq!(pacer.underruns(), 1);
+    }
+
+    #[test]
+    fn test_finished_utterance_is_not_an_underrun() {
+        let mut pacer = pacer();
+        pacer.push(&ms(200));
+        assert_eq!(drain(&mut pacer).len(), 10);
+
+        // Next utterance after the hold window
+        pacer.push(&ms(200));
+        assert_eq!(drain(&mut pacer).len(), 10);
+        assert_eq!(pacer.underruns(), 0);
+    }
+
+    #[test]
+    fn test_clear_drops_buffered_audio() {
+        let mut pacer = pacer();
+        pacer.push(&ms(500));
+        assert!(pacer.tick().is_some());
+
+        pacer.clear();
+        assert_eq!(pacer.buffered_bytes(), 0);
+        assert!(drain(&mut pacer).is_empty());
+        assert_eq!(pacer.underruns(), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(AudioPacingConfig::default().validate().is_ok());
+
+        let bad_frame = AudioPacingConfig {
+            frame_duration_ms: 15,
+            ..Default::default()
+        };
+        assert!(bad_frame.validate().is_err());
+
+        let bad_watermark = AudioPacingConfig {
+            low_watermark_ms: 5_000,
+            ..Default::default()
+        };
+        assert!(bad_watermark.validate().is_err());
+    }
+}
@@ -37,12 +37,17 @@
 //! }
 //! ```
 
+use std::collections::HashMap;
+
 use livekit_api::access_token::{AccessToken, SIPGrants, VideoGrants};
 use livekit_api::services::egress::{EgressClient, EgressOutput, RoomCompositeOptions};
 use livekit_api::services::room::{CreateRoomOptions, RoomClient};
 use livekit_protocol as proto;
+use tracing::warn;
 
 use super::types::LiveKitError;
+use crate::config::SinkAudioPolicy;
+use crate::state::insert_session_metadata;
 
 /// Configuration for S3 recording uploads
 #[derive(Debug, Clone)]
@@ -62,6 +67,8 @@ pub struct RecordingConfig {
     /// - With auth_id: `{prefix}/{auth_id}/{stream_id}/audio.ogg`
     /// - Without auth_id: `{prefix}/{stream_id}/audio.ogg`
     pub prefix: String,
+    /// Sample rate and Opus bitrate egress records with
+    pub audio: SinkAudioPolicy,
 }
 
 /// Build the full recording file path from prefix, optional auth_id, and stream_id.
@@ -86,6 +93,46 @@ fn build_recording_filepath(prefix: &str, auth_id: Option<&str>, stream_id: &str
     }
 }
 
+/// Build the S3 tagging string (URL-encoded query format) for recording objects.
+///
+/// Keys are sorted so the tag set is deterministic across uploads.
+/// Returns an empty string when there is no metadata, which leaves the object untagged.
+fn build_recording_tagging(metadata: &HashMap<String, String>) -> String {
+    let mut entries: Vec<_> = metadata.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    for (key, value) in entries {
+        serializer.append_pair(key, value);
+    }
+    serializer.finish()
+}
+
+/// Apply a recording audio policy to the egress options and object metadata
+///
+/// The policy is recorded in the metadata (`recording_sample_rate`,
+/// `recording_opus_bitrate_kbps`) so stored recordings say how they were
+/// encoded, as far as the S3 metadata and tag limits leave room next to the
+/// session metadata. Settings the policy leaves unset keep the egress
+/// defaults.
+fn apply_recording_audio_policy(
+    policy: &SinkAudioPolicy,
+    options: &mut RoomCompositeOptions,
+    metadata: &mut HashMap<String, String>,
+) {
+    if let Some(rate) = policy.sample_rate {
+        options.encoding.audio_frequency = rate as i32;
+    }
+    if let Some(kbps) = policy.opus_bitrate_kbps {
+        options.encoding.audio_bitrate = kbps as i32;
+    }
+    for (key, value) in policy.metadata("recording") {
+        if let Err(e) = insert_session_metadata(metadata, &key, value) {
+            warn!(key = %key, "Recording audio policy not recorded in object metadata: {}", e);
+        }
+    }
+}
+
 /// Handler for LiveKit room management and token generation
 ///
 /// This struct provides methods to create LiveKit rooms and generate JWT tokens
@@ -387,6 +434,8 @@ impl LiveKitRoomHandler {
     /// * `room_name` - Name of the LiveKit room to record
     /// * `auth_id` - Optional tenant/client ID for scoping recordings
     /// * `stream_id` - Unique identifier for the recording stream
+    /// * `metadata` - Session metadata applied to the uploaded object as S3
+    ///   user metadata and object tags, along with the recording audio policy
     ///
     /// # Returns
     /// * `Result<String, LiveKitError>` - Egress ID for the started recording or error
@@ -419,6 +468,7 @@ impl LiveKitRoomHandler {
     ///         access_key: "access_key".to_string(),
     ///         secret_key: "secret_key".to_string(),
     ///         prefix: "recordings/prod".to_string(),
+    ///         audio: Default::default(),
     ///     }),
     /// )?;
     ///
@@ -428,6 +478,7 @@ impl LiveKitRoomHandler {
     ///         "my-room",
     ///         Some("project1"),
     ///         "550e8400-e29b-41d4-a716-446655440000",
+    ///         &Default::default(),
     ///     )
     ///     .await?;
     /// # Ok(())
@@ -438,12 +489,21 @@ impl LiveKitRoomHandler {
         room_name: &str,
         auth_id: Option<&str>,
         stream_id: &str,
+        metadata: &HashMap<String, String>,
     ) -> Result<String, LiveKitError> {
         // Validate that recording configuration is present
         let config = self.recording_config.as_ref().ok_or_else(|| {
             LiveKitError::ConnectionFailed("Recording configuration not provided".to_string())
         })?;
 
+        // Configure room composite options for audio-only recording
+        let mut options = RoomCompositeOptions {
+            audio_only: true,
+            ..Default::default()
+        };
+        let mut metadata = metadata.clone();
+        apply_recording_audio_policy(&config.audio, &mut options, &mut metadata);
+
         // Create S3 upload configuration
         let s3_upload = proto::S3Upload {
             bucket: config.bucket.clone(),
@@ -452,6 +512,8 @@ impl LiveKitRoomHandler {
             access_key: config.access_key.clone(),
             secret: config.secret_key.clone(),
             force_path_style: true,
+            tagging: build_recording_tagging(&metadata),
+            metadata,
             ..Default::default()
         };
 
@@ -466,12 +528,6 @@ impl LiveKitRoomHandler {
             output: Some(proto::encoded_file_output::Output::S3(s3_upload)),
         };
 
-        // Configure room composite options for audio-only recording
-        let options = RoomCompositeOptions {
-            audio_only: true,
-            ..Default::default()
-        };
-
         // Start the room composite egress
         let egress_info = self
             .egress_client
@@ -490,7 +546,8 @@ impl LiveKitRoomHandler {
     /// * `egress_id` - The egress ID returned from setup_room_recording
     ///
     /// # Returns
-    /// * `Result<(), LiveKitError>` - Success or error
+    /// * `Result<Option<u64>, LiveKitError>` - Size in bytes of the recorded
+    ///   files as reported by egress when it stopped (`None` if none reported)
     ///
     /// # Example
     /// ```rust,no_run
@@ -508,15 +565,17 @@ impl LiveKitRoomHandler {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn stop_room_recording(&self, egress_id: &str) -> Result<(), LiveKitError> {
-        self.egress_client
+    pub async fn stop_room_recording(&self, egress_id: &str) -> Result<Option<u64>, LiveKitError> {
+        let egress_info = self
+            .egress_client
             .stop_egress(egress_id)
             .await
             .map_err(|e| {
                 LiveKitError::ConnectionFailed(format!("Failed to stop room recording: {e}"))
             })?;
 
-        Ok(())
+        let size: i64 = egress_info.file_results.iter().map(|file| file.size).sum();
+        Ok((size > 0).then_some(size as u64))
     }
 
     /// List participants in a LiveKit room
@@ -728,6 +787,10 @@ impl LiveKitRoomHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::{
+        MAX_SESSION_METADATA_ENTRIES, MAX_SESSION_METADATA_SIZE, MAX_SESSION_METADATA_VALUE_SIZE,
+        session_metadata_size, validate_session_metadata,
+    };
 
     #[test]
     fn test_room_handler_creation() {
@@ -847,6 +910,7 @@ mod tests {
             access_key: "access_key".to_string(),
             secret_key: "secret_key".to_string(),
             prefix: "recordings/test".to_string(),
+            audio: Default::default(),
         };
 
         let handler_with_recording = LiveKitRoomHandler::new(
@@ -926,6 +990,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_recording_tagging_empty_metadata() {
+        assert_eq!(build_recording_tagging(&HashMap::new()), "");
+    }
+
+    #[test]
+    fn test_recording_tagging_sorted_and_encoded() {
+        let metadata = HashMap::from([
+            ("tenant".to_string(), "acme corp".to_string()),
+            ("customer_id".to_string(), "c-1&2".to_string()),
+        ]);
+        assert_eq!(
+            build_recording_tagging(&metadata),
+            "customer_id=c-1%262&tenant=acme+corp"
+        );
+    }
+
+    #[test]
+    fn test_recording_audio_policy_applied() {
+        let mut options = RoomCompositeOptions {
+            audio_only: true,
+            ..Default::default()
+        };
+        let defaults = options.encoding.clone();
+        let mut metadata = HashMap::from([("agent".to_string(), "support".to_string())]);
+        apply_recording_audio_policy(&SinkAudioPolicy::default(), &mut options, &mut metadata);
+        assert_eq!(options.encoding.audio_frequency, defaults.audio_frequency);
+        assert_eq!(options.encoding.audio_bitrate, defaults.audio_bitrate);
+        assert_eq!(metadata.len(), 1);
+
+        let policy = SinkAudioPolicy {
+            sample_rate: Some(8000),
+            mono: false,
+            opus_bitrate_kbps: Some(16),
+        };
+        apply_recording_audio_policy(&policy, &mut options, &mut metadata);
+        assert_eq!(options.encoding.audio_frequency, 8000);
+        assert_eq!(options.encoding.audio_bitrate, 16);
+        assert_eq!(metadata["recording_sample_rate"], "8000");
+        assert_eq!(metadata["recording_opus_bitrate_kbps"], "16");
+        assert_eq!(
+            build_recording_tagging(&metadata),
+            "agent=support&recording_opus_bitrate_kbps=16&recording_sample_rate=8000"
+        );
+    }
+
+    #[test]
+    fn test_recording_audio_policy_with_client_at_metadata_limit() {
+        let policy = SinkAudioPolicy {
+            sample_rate: Some(8000),
+            mono: false,
+            opus_bitrate_kbps: Some(16),
+        };
+
+        // Every entry taken: the policy is applied but not recorded
+        let mut options = RoomCompositeOptions::default();
+        let mut metadata: HashMap<_, _> = (0..MAX_SESSION_METADATA_ENTRIES)
+            .map(|i| (format!("key{i}"), "value".to_string()))
+            .collect();
+        apply_recording_audio_policy(&policy, &mut options, &mut metadata);
+        assert_eq!(options.encoding.audio_frequency, 8000);
+        assert_eq!(metadata.len(), MAX_SESSION_METADATA_ENTRIES);
+        assert!(validate_session_metadata(&metadata).is_ok());
+        let tagging = build_recording_tagging(&metadata);
+        assert_eq!(tagging.split('&').count(), MAX_SESSION_METADATA_ENTRIES);
+
+        // Bytes for the sample rate left: only entries that fit are recorded
+        let mut metadata = HashMap::new();
+        for i in 0..8 {
+            metadata.insert(
+                format!("key{i}"),
+                "v".repeat(MAX_SESSION_METADATA_VALUE_SIZE),
+            );
+        }
+        let free = "recording_sample_rate".len() + "8000".len();
+        let fill =
+            MAX_SESSION_METADATA_SIZE - session_metadata_size(&metadata) - "pad".len() - free;
+        metadata.insert("pad".to_string(), "v".repeat(fill));
+        apply_recording_audio_policy(&policy, &mut options, &mut metadata);
+        assert_eq!(metadata["recording_sample_rate"], "8000");
+        assert!(!metadata.contains_key("recording_opus_bitrate_kbps"));
+        assert_eq!(session_metadata_size(&metadata), MAX_SESSION_METADATA_SIZE);
+        assert!(validate_session_metadata(&metadata).is_ok());
+    }
+
     #[tokio::test]
     async fn test_setup_room_recording_without_config() {
         let handler = LiveKitRoomHandler::new(
@@ -937,7 +1086,7 @@ mod tests {
         .unwrap();
 
         let result = handler
-            .setup_room_recording("test-room", None, "stream-123")
+            .setup_room_recording("test-room", None, "stream-123", &HashMap::new())
             .await;
         assert!(result.is_err());
         assert!(
@@ -959,6 +1108,7 @@ mod tests {
             access_key: "access_key".to_string(),
             secret_key: "secret_key".to_string(),
             prefix: "recordings/test".to_string(),
+            audio: Default::default(),
         };
 
         let handler = LiveKitRoomHandler::new(
@@ -972,7 +1122,7 @@ mod tests {
         // This will fail at the API call stage, but that's expected since we don't have a real server
         // We're just validating that the configuration is accepted
         let result = handler
-            .setup_room_recording("test-room", Some("project1"), "stream-123")
+            .setup_room_recording("test-room", Some("project1"), "stream-123", &HashMap::new())
             .await;
 
         // We expect an error because there's no real LiveKit server, but it shouldn't be a config error
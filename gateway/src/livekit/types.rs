@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::pacing::AudioPacingConfig;
+
 /// LiveKit configuration for connecting to a room
 #[derive(Debug, Clone)]
 pub struct LiveKitConfig {
@@ -20,6 +22,8 @@ pub struct LiveKitConfig {
     /// If empty, all participants' audio and data will be processed.
     /// If populated, only participants in this list will be processed.
     pub listen_participants: Vec<String>,
+    /// Frame size and startup buffering for published TTS audio
+    pub output_pacing: AudioPacingConfig,
 }
 
 /// LiveKit connection status
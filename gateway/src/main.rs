@@ -1,3 +1,5 @@
+use std::future::IntoFuture;
+use std::io::Read;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
@@ -6,7 +8,7 @@ use std::fs;
 
 use tracing::info;
 
-use axum::{Router, middleware};
+use axum::middleware;
 use axum_server::tls_rustls::RustlsConfig;
 use clap::{Parser, Subcommand};
 use http::{
@@ -14,23 +16,32 @@ use http::{
     header::{AUTHORIZATION, CONTENT_TYPE},
 };
 use tokio::net::TcpListener;
-use tower_governor::{
-    GovernorLayer, governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor,
-};
+use tower_governor::{GovernorLayer, governor::GovernorConfigBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::set_header::SetResponseHeaderLayer;
+use zeroize::Zeroizing;
 
 use anyhow::anyhow;
 
 use waav_gateway::{
-    ServerConfig, global_registry, init,
-    middleware::{auth_middleware, connection_limit_middleware},
-    routes,
+    ServerConfig, bench,
+    build_info::BuildInfo,
+    config::{
+        CONFIG_MASTER_KEY_ENV, CONFIG_MASTER_KEY_FILE_ENV, ENCRYPTED_TAG, MasterKey, encrypt_value,
+    },
+    flight_recorder, global_registry, init,
+    middleware::{
+        ClientIpKeyExtractor, admin_auth_middleware, auth_middleware, connection_limit_middleware,
+        load_shedding_middleware,
+    },
+    plugin::check_registry_pricing,
+    replay, routes, selftest,
+    shutdown::{DRAIN_TIMEOUT, ShutdownSignal},
     state::AppState,
 };
 
 #[cfg(feature = "plugins-dynamic")]
-use waav_gateway::plugin::DynamicPluginLoader;
+use waav_gateway::plugin::{DynamicPluginLoader, PluginLoadStatus, PluginStorageManager};
 
 /// WaaV Gateway - Real-time voice processing server
 #[derive(Parser, Debug)]
@@ -51,6 +62,24 @@ enum Commands {
     /// Initialize turn detection models
     Init,
 
+    /// Load-test a running gateway with synthetic sessions
+    Bench(bench::BenchOptions),
+
+    /// Replay a recording through a new provider configuration
+    Replay(replay::ReplayOptions),
+
+    /// Work with configuration files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Inspect recorded sessions
+    Session {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
+
     /// Generate OpenAPI specification
     #[cfg(feature = "openapi")]
     Openapi {
@@ -61,6 +90,39 @@ enum Commands {
         /// Output file path (prints to stdout if not specified)
         #[arg(short = 'o', long = "output")]
         output: Option<PathBuf>,
+
+        /// Comma-separated feature-gated endpoint groups to document
+        /// (e.g. `dag-routing,chaos`; defaults to every feature compiled in)
+        #[arg(long = "include-features", value_name = "FEATURES")]
+        include_features: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Encrypt a value for an `!encrypted` entry of a config file
+    ///
+    /// The master key is read from CONFIG_MASTER_KEY or CONFIG_MASTER_KEY_FILE.
+    EncryptValue {
+        /// Value to encrypt; read from stdin when omitted, which keeps it
+        /// out of the shell history
+        value: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SessionCommands {
+    /// Render a session's flight log as a timeline
+    ///
+    /// Logs are downloaded from `GET /admin/sessions/{stream_id}/flight_log`.
+    Replay {
+        /// Flight log file (JSON lines)
+        file: PathBuf,
+
+        /// Print the outbound messages as the text frames the gateway sent,
+        /// one per line (keys sorted), for diffing against a client's log
+        #[arg(long)]
+        frames: bool,
     },
 }
 
@@ -88,18 +150,89 @@ async fn main() -> anyhow::Result<()> {
                 init::run().await?;
                 return Ok(());
             }
+            Commands::Bench(options) => {
+                let json = options.json;
+                let report = bench::run(options).await?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print!("{report}");
+                }
+                return Ok(());
+            }
+            Commands::Replay(options) => {
+                let config = if let Some(config_path) = &cli.config {
+                    ServerConfig::from_file(config_path).map_err(|e| anyhow!(e.to_string()))?
+                } else {
+                    ServerConfig::from_env().map_err(|e| anyhow!(e.to_string()))?
+                };
+                let report = replay::run(&options, &config, global_registry()).await?;
+                if options.json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print!("{report}");
+                }
+                return Ok(());
+            }
+            Commands::Config {
+                command: ConfigCommands::EncryptValue { value },
+            } => {
+                let key = MasterKey::from_env()?.ok_or_else(|| {
+                    anyhow!(
+                        "Set {} or {} to the base64 master key",
+                        CONFIG_MASTER_KEY_ENV,
+                        CONFIG_MASTER_KEY_FILE_ENV
+                    )
+                })?;
+                let value = match value {
+                    Some(value) => Zeroizing::new(value),
+                    None => {
+                        let mut input = Zeroizing::new(String::new());
+                        std::io::stdin().read_to_string(&mut input)?;
+                        let trimmed = input.strip_suffix('\n').unwrap_or(&input);
+                        Zeroizing::new(trimmed.strip_suffix('\r').unwrap_or(trimmed).to_string())
+                    }
+                };
+                println!("!{} \"{}\"", ENCRYPTED_TAG, encrypt_value(&key, &value));
+                return Ok(());
+            }
+            Commands::Session {
+                command: SessionCommands::Replay { file, frames },
+            } => {
+                let contents = std::fs::read_to_string(&file)
+                    .map_err(|e| anyhow!("Failed to read {}: {}", file.display(), e))?;
+                let log = flight_recorder::parse_flight_log(&contents)?;
+                print!(
+                    "{}",
+                    flight_recorder::render(&log, flight_recorder::RenderOptions { frames })
+                );
+                return Ok(());
+            }
             #[cfg(feature = "openapi")]
-            Commands::Openapi { format, output } => {
+            Commands::Openapi {
+                format,
+                output,
+                include_features,
+            } => {
+                use waav_gateway::docs::openapi::{ApiFeature, spec_json, spec_yaml};
+
                 // Validate format
                 if format != "yaml" && format != "json" {
                     anyhow::bail!("Invalid format '{}'. Must be 'yaml' or 'json'", format);
                 }
 
+                // Select the feature-gated endpoint groups to document
+                let features = match include_features {
+                    Some(list) => ApiFeature::parse_list(&list)
+                        .map_err(|e| anyhow!("Invalid --include-features: {}", e))?,
+                    None => ApiFeature::compiled(),
+                };
+
                 // Generate the spec in the requested format
                 let spec_content = match format.as_str() {
-                    "yaml" => waav_gateway::docs::openapi::spec_yaml()
+                    "yaml" => spec_yaml(&features)
                         .map_err(|e| anyhow!("Failed to generate OpenAPI YAML: {}", e))?,
-                    "json" => waav_gateway::docs::openapi::spec_json()
+                    "json" => spec_json(&features)
                         .map_err(|e| anyhow!("Failed to generate OpenAPI JSON: {}", e))?,
                     _ => unreachable!(),
                 };
@@ -130,48 +263,104 @@ async fn main() -> anyhow::Result<()> {
     // Initialize the plugin registry (including built-in plugins)
     let registry = global_registry();
 
-    // Load dynamic plugins if the feature is enabled and a plugin directory is configured
+    // Load dynamic plugins if the feature is enabled and plugin directories are configured
     #[cfg(feature = "plugins-dynamic")]
     {
-        if config.plugins.enabled {
-            if let Some(ref plugin_dir) = config.plugins.plugin_dir {
-                info!("Loading dynamic plugins from: {}", plugin_dir.display());
-                let mut loader = DynamicPluginLoader::new();
-                match loader.load_all_from_directory(plugin_dir, registry) {
-                    Ok(count) => {
-                        if count > 0 {
-                            info!("Loaded {} dynamic plugin(s)", count);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to load dynamic plugins: {}", e);
+        let plugin_dirs = config.plugins.plugin_directories();
+        if config.plugins.enabled && !plugin_dirs.is_empty() {
+            info!("Loading dynamic plugins from: {:?}", plugin_dirs);
+            let mut loader = DynamicPluginLoader::new()
+                .with_require_exact_abi(config.plugins.require_exact_abi)
+                .with_init_timeout(std::time::Duration::from_secs(
+                    config.plugins.init_timeout_secs,
+                ))
+                .with_conflict_policy(config.plugins.conflict_policy)
+                .with_allow_override(config.plugins.allow_override);
+            if let Some(data_dir) = &config.plugins.data_dir {
+                loader = loader.with_storage(PluginStorageManager::new(
+                    data_dir,
+                    config.plugins.storage_quota_bytes,
+                ));
+            }
+            match loader
+                .load_all_from_directories(&plugin_dirs, registry)
+                .await
+            {
+                Ok(reports) => {
+                    let count = reports
+                        .iter()
+                        .filter(|report| report.status == PluginLoadStatus::Loaded)
+                        .count();
+                    if count > 0 {
+                        info!("Loaded {} dynamic plugin(s)", count);
                     }
+                    loader.spawn_storage_quota_checks(std::time::Duration::from_secs(
+                        config.plugins.storage_check_interval_secs,
+                    ));
+                    // The registry shuts the plugins down when the server stops
+                    loader.hand_over_to(registry);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to load dynamic plugins: {}", e);
                 }
             }
         }
     }
-    // Suppress unused variable warning when plugins-dynamic feature is not enabled
-    let _ = registry;
+    for mismatch in check_registry_pricing(registry) {
+        tracing::warn!("Provider pricing out of date: {}", mismatch);
+    }
+    println!("{}", BuildInfo::collect(registry));
 
     let address = config.address();
     let tls_config = config.tls.clone();
     let is_tls_enabled = config.is_tls_enabled();
     let rate_limit_rps = config.rate_limit_requests_per_second;
     let rate_limit_burst = config.rate_limit_burst_size;
+    let rate_limit_key = ClientIpKeyExtractor::new(&config.trusted_proxies);
     let cors_origins = config.cors_allowed_origins.clone();
     println!("Starting server on {address}");
 
     // Create application state
     let app_state = AppState::new(config).await;
 
+    // Run the provider self-test in the background (if configured)
+    selftest::spawn_selftest(app_state.clone());
+
+    // Accept RTMP publishers next to the HTTP server (if configured)
+    #[cfg(feature = "ingest")]
+    if let Some(ingest) = app_state.config.ingest.clone() {
+        let listener = waav_gateway::ingest::RtmpIngest::bind(ingest, app_state.clone()).await?;
+        println!("RTMP ingest listening on {}", listener.local_addr()?);
+        tokio::spawn(listener.run());
+    }
+    #[cfg(not(feature = "ingest"))]
+    if app_state.config.ingest.is_some() {
+        tracing::warn!(
+            "ingest is configured but this gateway was built without the `ingest` feature"
+        );
+    }
+
     // Create protected API routes with authentication middleware
     let protected_routes = routes::api::create_api_router().layer(middleware::from_fn_with_state(
         app_state.clone(),
         auth_middleware,
     ));
 
-    // Create WebSocket routes with connection limit and auth middleware
-    // Layer order (outer to inner): connection_limit -> auth -> handler
+    // Create admin routes (auth + admin id check)
+    // Layer order (outer to inner): auth -> admin -> handler
+    let admin_routes = routes::admin::create_admin_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            admin_auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ));
+
+    // Create WebSocket routes with load shedding, connection limit and auth middleware
+    // Layer order (outer to inner): load_shedding -> connection_limit -> auth -> handler
+    // - load_shedding_middleware: Rejects new sessions while over a load watermark
     // - connection_limit_middleware: Enforces max connections (global and per-IP)
     // - auth_middleware: Validates auth token (when enabled) or sets empty context
     let ws_routes = routes::ws::create_ws_router()
@@ -182,10 +371,14 @@ async fn main() -> anyhow::Result<()> {
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             connection_limit_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            load_shedding_middleware,
         ));
 
     // Create Realtime WebSocket routes for audio-to-audio streaming (OpenAI Realtime API)
-    // Also uses connection limit middleware for capacity management
+    // Also uses load shedding and connection limit middleware for capacity management
     let realtime_routes = routes::realtime::create_realtime_router()
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
@@ -194,23 +387,31 @@ async fn main() -> anyhow::Result<()> {
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             connection_limit_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            load_shedding_middleware,
         ));
 
     // Create webhook routes (no auth - uses LiveKit signature verification)
     let webhook_routes = routes::webhooks::create_webhook_router();
 
-    // Create public health check route (no auth)
-    let public_routes = Router::new().route(
-        "/",
-        axum::routing::get(waav_gateway::handlers::api::health_check),
-    );
+    // Create signed artifact download routes (no auth - URLs carry their own signature)
+    let download_routes = routes::downloads::create_download_router();
+
+    // Create public health, readiness, metrics and version routes (no auth)
+    let public_routes = routes::public::create_public_router();
+
+    // Create the development console routes (no auth - the page authenticates on /ws)
+    #[cfg(feature = "dev-console")]
+    let public_routes = public_routes.merge(routes::console::create_console_router());
 
     // Configure rate limiting (disabled when rate >= 100000 for performance testing)
     let governor_layer = if rate_limit_rps < 100000 {
         let governor_config = GovernorConfigBuilder::default()
             .per_second(rate_limit_rps as u64)
             .burst_size(rate_limit_burst)
-            .key_extractor(SmartIpKeyExtractor)
+            .key_extractor(rate_limit_key)
             .finish()
             .expect("Failed to build rate limiter config");
         Some(GovernorLayer::new(governor_config))
@@ -289,10 +490,14 @@ async fn main() -> anyhow::Result<()> {
             http::HeaderValue::from_static("DENY"),
         ));
 
-    // Combine all routes: public + webhook + protected + websocket + realtime
+    let plugin_registry = app_state.plugin_registry.clone();
+
+    // Combine all routes: public + webhook + download + protected + admin + websocket + realtime
     let app = public_routes
         .merge(webhook_routes)
+        .merge(download_routes)
         .merge(protected_routes)
+        .merge(admin_routes)
         .merge(ws_routes)
         .merge(realtime_routes)
         .with_state(app_state)
@@ -305,6 +510,9 @@ async fn main() -> anyhow::Result<()> {
         .parse()
         .map_err(|e| anyhow!("Invalid server address '{}': {}", address, e))?;
 
+    let shutdown = ShutdownSignal::install()
+        .map_err(|e| anyhow!("Failed to install shutdown signal handlers: {}", e))?;
+
     // Start server with or without TLS
     if is_tls_enabled {
         let tls = tls_config.expect("TLS config must be present when TLS is enabled");
@@ -323,7 +531,16 @@ async fn main() -> anyhow::Result<()> {
 
         println!("Server listening on https://{} (TLS enabled)", socket_addr);
 
+        let handle = axum_server::Handle::new();
+        let drain = handle.clone();
+        tokio::spawn(async move {
+            let signal = shutdown.recv().await;
+            println!("Received {signal}, shutting down");
+            drain.graceful_shutdown(Some(DRAIN_TIMEOUT));
+        });
+
         axum_server::bind_rustls(socket_addr, rustls_config)
+            .handle(handle)
             .serve(app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .map_err(|e| anyhow!("TLS server error: {}", e))?;
@@ -331,12 +548,34 @@ async fn main() -> anyhow::Result<()> {
         println!("Server listening on http://{}", socket_addr);
 
         let listener = TcpListener::bind(&socket_addr).await?;
-        axum::serve(
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        // Resolves once open connections had DRAIN_TIMEOUT to finish
+        let drained = tokio::spawn(async move {
+            let signal = shutdown.recv().await;
+            println!("Received {signal}, shutting down");
+            let _ = stop_tx.send(());
+            tokio::time::sleep(DRAIN_TIMEOUT).await;
+        });
+        let server = axum::serve(
             listener,
             app.into_make_service_with_connect_info::<SocketAddr>(),
         )
-        .await?;
+        .with_graceful_shutdown(async move {
+            let _ = stop_rx.await;
+        })
+        .into_future();
+
+        tokio::select! {
+            result = server => result?,
+            _ = drained => {
+                tracing::warn!(
+                    "Connections still open after {}s, closing them",
+                    DRAIN_TIMEOUT.as_secs()
+                );
+            }
+        }
     }
 
+    plugin_registry.shutdown();
     Ok(())
 }
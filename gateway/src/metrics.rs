@@ -0,0 +1,185 @@
+//! # Metrics
+//!
+//! Process-wide gauges and counters exposed at `GET /metrics` in the
+//! Prometheus text exposition format. Components record into
+//! [`global_metrics`] by metric name; each metric keeps one value per label
+//! set and is rendered with its `# HELP` and `# TYPE` lines.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+use parking_lot::RwLock;
+
+/// Content type of the rendered metrics
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Prometheus metric type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Value that only increases
+    Counter,
+    /// Value that can go up and down
+    Gauge,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+        }
+    }
+}
+
+/// A metric name with its values keyed by rendered label set
+struct MetricFamily {
+    help: &'static str,
+    kind: MetricKind,
+    samples: BTreeMap<String, f64>,
+}
+
+/// Registry of named metrics
+#[derive(Default)]
+pub struct MetricsRegistry {
+    families: RwLock<BTreeMap<&'static str, MetricFamily>>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a gauge to `value`
+    pub fn set_gauge(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.update(name, help, MetricKind::Gauge, labels, |sample| {
+            *sample = value
+        });
+    }
+
+    /// Add `value` to a counter
+    pub fn add_counter(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.update(name, help, MetricKind::Counter, labels, |sample| {
+            *sample += value
+        });
+    }
+
+    /// Increment a counter by one
+    pub fn inc_counter(&self, name: &'static str, help: &'static str, labels: &[(&str, &str)]) {
+        self.add_counter(name, help, labels, 1.0);
+    }
+
+    /// Current value of a metric, None if it has not been recorded
+    pub fn get(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.families
+            .read()
+            .get(name)
+            .and_then(|family| family.samples.get(&render_labels(labels)).copied())
+    }
+
+    /// Render every metric in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, family) in self.families.read().iter() {
+            let _ = writeln!(out, "# HELP {name} {}", family.help);
+            let _ = writeln!(out, "# TYPE {name} {}", family.kind.as_str());
+            for (labels, value) in &family.samples {
+                let _ = writeln!(out, "{name}{labels} {value}");
+            }
+        }
+        out
+    }
+
+    fn update(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        apply: impl FnOnce(&mut f64),
+    ) {
+        let mut families = self.families.write();
+        let family = families.entry(name).or_insert_with(|| MetricFamily {
+            help,
+            kind,
+            samples: BTreeMap::new(),
+        });
+        apply(family.samples.entry(render_labels(labels)).or_insert(0.0));
+    }
+}
+
+/// Render a label set as `{name="value",...}`, empty when there are no labels
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let rendered: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect();
+    format!("{{{}}}", rendered.join(","))
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+static GLOBAL_METRICS: OnceLock<MetricsRegistry> = OnceLock::new();
+
+/// The process-wide metrics registry
+pub fn global_metrics() -> &'static MetricsRegistry {
+    GLOBAL_METRICS.get_or_init(MetricsRegistry::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gauges_and_counters() {
+        let metrics = MetricsRegistry::new();
+        metrics.set_gauge("test_gauge", "A gauge", &[], 2.5);
+        metrics.set_gauge("test_gauge", "A gauge", &[], 1.0);
+        metrics.inc_counter("test_total", "A counter", &[("result", "ok")]);
+        metrics.inc_counter("test_total", "A counter", &[("result", "ok")]);
+        metrics.inc_counter("test_total", "A counter", &[("result", "error")]);
+
+        assert_eq!(metrics.get("test_gauge", &[]), Some(1.0));
+        assert_eq!(metrics.get("test_total", &[("result", "ok")]), Some(2.0));
+        assert_eq!(metrics.get("test_total", &[("result", "error")]), Some(1.0));
+        assert_eq!(metrics.get("missing", &[]), None);
+    }
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = MetricsRegistry::new();
+        metrics.set_gauge("test_gauge", "A gauge", &[], 0.5);
+        metrics.inc_counter("test_total", "A counter", &[("provider", "say \"hi\"")]);
+
+        assert_eq!(
+            metrics.render(),
+            "# HELP test_gauge A gauge\n\
+             # TYPE test_gauge gauge\n\
+             test_gauge 0.5\n\
+             # HELP test_total A counter\n\
+             # TYPE test_total counter\n\
+             test_total{provider=\"say \\\"hi\\\"\"} 1\n"
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use crate::auth::{Auth, filter_headers, match_api_secret_id};
 use crate::errors::auth_error::AuthError;
+use crate::middleware::client_ip::request_client_ip;
+use crate::routes::admin::required_scope;
 use crate::state::AppState;
 use axum::{
     body::Body,
-    extract::{Request, State},
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
     middleware::Next,
     response::Response,
 };
@@ -86,9 +89,10 @@ pub async fn auth_middleware(
         return Ok(next.run(request).await);
     }
 
-    // Extract request method and path for logging
+    // Extract request method, path and client address for logging
     let request_method = request.method().to_string();
     let request_path = request.uri().path().to_string();
+    let client_ip = request_client_ip(&request, &state.config.trusted_proxies);
 
     tracing::debug!(
         method = %request_method,
@@ -106,6 +110,7 @@ pub async fn auth_middleware(
             if request_path == "/ws" {
                 tracing::info!(
                     path = %request_path,
+                    client_ip = ?client_ip,
                     "WebSocket connection without token, enabling first-message auth"
                 );
                 request.extensions_mut().insert(Auth::pending());
@@ -124,6 +129,7 @@ pub async fn auth_middleware(
             tracing::info!(
                 method = %request_method,
                 path = %request_path,
+                client_ip = ?client_ip,
                 auth_id = %secret_id,
                 "API secret authentication successful"
             );
@@ -133,6 +139,7 @@ pub async fn auth_middleware(
             tracing::warn!(
                 method = %request_method,
                 path = %request_path,
+                client_ip = ?client_ip,
                 "API secret authentication failed: token mismatch"
             );
             return Err(AuthError::Unauthorized("Invalid API secret".to_string()));
@@ -184,6 +191,7 @@ pub async fn auth_middleware(
                 tracing::info!(
                     method = %request_method,
                     path = %request_path,
+                    client_ip = ?client_ip,
                     auth_id = ?auth.id,
                     "JWT authentication successful"
                 );
@@ -199,6 +207,7 @@ pub async fn auth_middleware(
                 tracing::warn!(
                     method = %request_method,
                     path = %request_path,
+                    client_ip = ?client_ip,
                     error = %e,
                     "JWT authentication failed"
                 );
@@ -213,6 +222,70 @@ pub async fn auth_middleware(
     }
 }
 
+/// Admin authorization middleware for privileged routes
+///
+/// Must be layered inside [`auth_middleware`] so the `Auth` extension is
+/// already populated. Each route requires the scope given by
+/// [`required_scope`]; when authentication is disabled every request is
+/// allowed. Otherwise the authenticated client id must hold that scope, or
+/// the request is refused with 403 naming the missing scope.
+///
+/// Every call needing a mutating scope is recorded in the admin audit log
+/// with the acting client id, including refused calls.
+///
+/// # Returns
+/// * `Result<Response, AuthError>` - The response from the next handler or 403 Forbidden
+pub async fn admin_auth_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched| matched.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let scope = required_scope(request.method(), &route);
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let auth_id = request
+        .extensions()
+        .get::<Auth>()
+        .and_then(|auth| auth.id.clone());
+
+    let allowed = !state.config.auth_required
+        || auth_id
+            .as_deref()
+            .is_some_and(|id| state.config.has_admin_scope(id, scope));
+    if !allowed {
+        tracing::warn!(
+            path = %path,
+            client_ip = ?request_client_ip(&request, &state.config.trusted_proxies),
+            auth_id = ?auth_id,
+            scope = %scope,
+            "Admin authorization failed"
+        );
+        if scope.is_mutating() {
+            state.admin_audit.record(
+                auth_id,
+                scope,
+                &method,
+                &path,
+                StatusCode::FORBIDDEN.as_u16(),
+            );
+        }
+        return Err(AuthError::MissingScope(scope.to_string()));
+    }
+
+    let response = next.run(request).await;
+    if scope.is_mutating() {
+        state
+            .admin_audit
+            .record(auth_id, scope, &method, &path, response.status().as_u16());
+    }
+    Ok(response)
+}
+
 /// Helper function to create a test request with authorization header
 #[cfg(test)]
 pub fn create_test_request_with_auth(token: &str, body: &str) -> Request {
@@ -0,0 +1,217 @@
+//! Client IP resolution behind trusted proxies
+//!
+//! Rate limiting, connection limits and authentication logs all key on the
+//! address resolved here, so a client cannot dodge one of them with a forged
+//! header that another ignores.
+//!
+//! Forwarded headers are only honored when the connection comes from a
+//! trusted proxy (`security.trusted_proxies`). `X-Forwarded-For` is then
+//! read from the right: each trusted hop is skipped and the first untrusted
+//! address is the client, so entries a client prepends itself are never
+//! used. Without `X-Forwarded-For`, `X-Real-IP` is used. Connections from
+//! anywhere else are keyed on their socket address.
+
+use axum::{extract::ConnectInfo, http::HeaderMap, http::Request};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tower_governor::{GovernorError, key_extractor::KeyExtractor};
+
+use crate::config::IpNetwork;
+
+/// Resolve the client address of a connection from `peer`
+pub fn resolve_client_ip(
+    peer: IpAddr,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpNetwork],
+) -> IpAddr {
+    let trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !trusted(&peer) {
+        return peer;
+    }
+
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    if hops.is_empty() {
+        return headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(peer);
+    }
+
+    let mut client = peer;
+    for hop in hops.iter().rev() {
+        // Nothing left of a malformed hop can be trusted
+        let Ok(ip) = hop.parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !trusted(&ip) {
+            break;
+        }
+    }
+    client
+}
+
+/// Client address of a request served with connect info
+///
+/// Returns `None` when the server was not started with
+/// `into_make_service_with_connect_info`, as in tests.
+pub fn request_client_ip<B>(request: &Request<B>, trusted_proxies: &[IpNetwork]) -> Option<IpAddr> {
+    let ConnectInfo(addr) = request.extensions().get::<ConnectInfo<SocketAddr>>()?;
+    Some(resolve_client_ip(
+        addr.ip(),
+        request.headers(),
+        trusted_proxies,
+    ))
+}
+
+/// Rate limiting key: the client address resolved by [`resolve_client_ip`]
+#[derive(Debug, Clone)]
+pub struct ClientIpKeyExtractor {
+    trusted_proxies: Arc<[IpNetwork]>,
+}
+
+impl ClientIpKeyExtractor {
+    /// Key requests on their client address behind `trusted_proxies`
+    pub fn new(trusted_proxies: &[IpNetwork]) -> Self {
+        Self {
+            trusted_proxies: trusted_proxies.into(),
+        }
+    }
+}
+
+impl KeyExtractor for ClientIpKeyExtractor {
+    type Key = IpAddr;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        request_client_ip(req, &self.trusted_proxies).ok_or(GovernorError::UnableToExtractKey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted() -> Vec<IpNetwork> {
+        vec![
+            "10.0.0.0/8".parse().unwrap(),
+            "192.168.0.1".parse().unwrap(),
+        ]
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_forwarded_headers() {
+        let headers = headers(&[
+            ("x-forwarded-for", "203.0.113.9"),
+            ("x-real-ip", "203.0.113.10"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(ip("198.51.100.4"), &headers, &trusted()),
+            ip("198.51.100.4")
+        );
+        // No trusted proxies: headers are never honored
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &headers, &[]),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn test_trusted_peer_uses_forwarded_client() {
+        let forwarded = headers(&[("x-forwarded-for", "203.0.113.9")]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &forwarded, &trusted()),
+            ip("203.0.113.9")
+        );
+
+        let real_ip = headers(&[("x-real-ip", "203.0.113.10")]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &real_ip, &trusted()),
+            ip("203.0.113.10")
+        );
+
+        // Neither header: the proxy itself is the client
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &HeaderMap::new(), &trusted()),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn test_chained_proxies_use_right_most_untrusted_hop() {
+        // The client forged the first entry; the LB appended the real address,
+        // then an internal proxy appended the LB's
+        let chained = headers(&[(
+            "x-forwarded-for",
+            "1.2.3.4, 203.0.113.9, 192.168.0.1, 10.4.0.7",
+        )]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &chained, &trusted()),
+            ip("203.0.113.9")
+        );
+
+        // Separate header lines are one list
+        let split = headers(&[
+            ("x-forwarded-for", "1.2.3.4, 203.0.113.9"),
+            ("x-forwarded-for", "10.4.0.7"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &split, &trusted()),
+            ip("203.0.113.9")
+        );
+
+        // Only trusted hops: the left-most one
+        let internal = headers(&[("x-forwarded-for", "10.9.9.9, 10.4.0.7")]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &internal, &trusted()),
+            ip("10.9.9.9")
+        );
+    }
+
+    #[test]
+    fn test_malformed_hop_stops_the_walk() {
+        let malformed = headers(&[("x-forwarded-for", "203.0.113.9, unknown, 10.4.0.7")]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.2"), &malformed, &trusted()),
+            ip("10.4.0.7")
+        );
+    }
+
+    #[test]
+    fn test_key_extractor_uses_connect_info() {
+        let extractor = ClientIpKeyExtractor::new(&trusted());
+        let mut request = Request::builder()
+            .header("x-forwarded-for", "203.0.113.9")
+            .body(())
+            .unwrap();
+        assert!(extractor.extract(&request).is_err());
+
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 40000))));
+        assert_eq!(extractor.extract(&request).unwrap(), ip("203.0.113.9"));
+
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([198, 51, 100, 4], 40000))));
+        assert_eq!(extractor.extract(&request).unwrap(), ip("198.51.100.4"));
+    }
+}
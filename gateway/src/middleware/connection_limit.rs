@@ -4,6 +4,9 @@
 //! - Global maximum WebSocket connections
 //! - Per-IP connection limits
 //!
+//! Clients are counted by the address [`resolve_client_ip`] resolves, so
+//! forwarded headers only count behind `security.trusted_proxies`.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -28,6 +31,7 @@ use axum::{
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
+use super::client_ip::resolve_client_ip;
 use crate::state::{AppState, ConnectionLimitError};
 
 /// Extension type to carry the client IP through to the handler
@@ -35,6 +39,29 @@ use crate::state::{AppState, ConnectionLimitError};
 #[derive(Clone, Debug)]
 pub struct ClientIp(pub IpAddr);
 
+/// Guard that releases a connection slot when dropped
+///
+/// This implements RAII pattern to ensure connection slots are always released,
+/// even if the WebSocket handler panics or encounters errors.
+pub struct ConnectionGuard {
+    app_state: Arc<AppState>,
+    ip: IpAddr,
+}
+
+impl ConnectionGuard {
+    /// Hold the slot acquired for `ip` by the middleware
+    pub fn new(app_state: Arc<AppState>, ip: IpAddr) -> Self {
+        Self { app_state, ip }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        tracing::debug!(ip = %self.ip, "Releasing connection slot");
+        self.app_state.release_connection(self.ip);
+    }
+}
+
 /// Middleware that enforces connection limits for WebSocket connections.
 ///
 /// This middleware:
@@ -53,19 +80,12 @@ pub async fn connection_limit_middleware(
     next: Next,
 ) -> Response {
     // Only apply limits to WebSocket upgrade requests
-    let is_ws_upgrade = request
-        .headers()
-        .get("upgrade")
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v.eq_ignore_ascii_case("websocket"))
-        .unwrap_or(false);
-
-    if !is_ws_upgrade {
+    if !is_websocket_upgrade(&request) {
         // Not a WebSocket upgrade, pass through
         return next.run(request).await;
     }
 
-    let client_ip = addr.ip();
+    let client_ip = resolve_client_ip(addr.ip(), request.headers(), &state.config.trusted_proxies);
 
     // Try to acquire a connection slot
     match state.try_acquire_connection(client_ip) {
@@ -101,6 +121,16 @@ pub async fn connection_limit_middleware(
     }
 }
 
+/// Whether the request asks to upgrade to a WebSocket
+pub(crate) fn is_websocket_upgrade(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get("upgrade")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,13 +193,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: Some(10),
             max_connections_per_ip: 3,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let state = AppState::new(config).await;
@@ -258,13 +321,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: Some(5), // Global limit of 5
             max_connections_per_ip: 10,         // Per-IP limit higher than global
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let state = AppState::new(config).await;
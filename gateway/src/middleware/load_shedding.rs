@@ -0,0 +1,86 @@
+//! Load shedding middleware for new WebSocket sessions
+//!
+//! Turns away WebSocket upgrade requests with `503 Service Unavailable` while
+//! the gateway is over one of its load shedding watermarks. Sessions that are
+//! already open are not affected; only new upgrades are checked.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use axum::Router;
+//! use waav_gateway::middleware::load_shedding_middleware;
+//!
+//! let app = Router::new()
+//!     .route("/ws", get(websocket_handler))
+//!     .layer(axum::middleware::from_fn_with_state(
+//!         state.clone(),
+//!         load_shedding_middleware,
+//!     ));
+//! ```
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Request, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::connection_limit::is_websocket_upgrade;
+use crate::state::{AppState, ShedReason};
+
+/// Body of a shed request
+#[derive(Debug, Serialize)]
+struct LoadShedResponse {
+    error: &'static str,
+    reason: ShedReason,
+    detail: String,
+    retry_after_secs: u64,
+}
+
+/// Middleware that rejects new WebSocket sessions while the gateway is overloaded.
+///
+/// Returns 503 Service Unavailable with a `Retry-After` header and a JSON body
+/// naming the exceeded threshold. Requests that are not WebSocket upgrades,
+/// and all requests while no threshold is exceeded, pass through.
+pub async fn load_shedding_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !is_websocket_upgrade(&request) {
+        return next.run(request).await;
+    }
+
+    let status = state.load_shedding_status().await;
+    let Some(reason) = status.reason else {
+        return next.run(request).await;
+    };
+
+    let detail = status.detail.unwrap_or_default();
+    let retry_after_secs = status.thresholds.retry_after_secs;
+    tracing::warn!(
+        reason = reason.as_str(),
+        detail = %detail,
+        "Rejecting new session: load shedding"
+    );
+    state.load_shedder.record_rejection(reason);
+
+    let mut response = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(LoadShedResponse {
+            error: "Server overloaded. Please try again later.",
+            reason,
+            detail,
+            retry_after_secs,
+        }),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
@@ -1,6 +1,10 @@
 pub mod auth;
+pub mod client_ip;
 pub mod connection_limit;
+pub mod load_shedding;
 
 // Re-export middleware functions
-pub use auth::auth_middleware;
-pub use connection_limit::{ClientIp, connection_limit_middleware};
+pub use auth::{admin_auth_middleware, auth_middleware};
+pub use client_ip::{ClientIpKeyExtractor, request_client_ip, resolve_client_ip};
+pub use connection_limit::{ClientIp, ConnectionGuard, connection_limit_middleware};
+pub use load_shedding::load_shedding_middleware;
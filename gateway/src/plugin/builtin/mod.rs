@@ -42,6 +42,34 @@ fn deepgram_stt_metadata() -> ProviderMetadata {
             "punctuation",
         ])
         .with_languages(["en", "es", "fr", "de", "it", "pt", "nl", "ja", "ko", "zh"])
+        .with_models([
+            "nova-3",
+            "nova-3-medical",
+            "nova-2",
+            "nova-2-general",
+            "nova-2-meeting",
+            "nova-2-phonecall",
+            "nova-2-finance",
+            "nova-2-conversationalai",
+            "nova-2-voicemail",
+            "nova-2-video",
+            "nova-2-medical",
+            "nova-2-drivethru",
+            "nova-2-automotive",
+            "nova-2-atc",
+            "enhanced",
+            "base",
+            "whisper",
+        ])
+        .with_model_alias("nova-3-general", "nova-3")
+        .with_pricing_key("nova-3-medical", "deepgram:nova-3")
+        .with_pricing_key("nova-2-finance", "deepgram:nova-2")
+        .with_pricing_key("nova-2-conversationalai", "deepgram:nova-2")
+        .with_pricing_key("nova-2-voicemail", "deepgram:nova-2")
+        .with_pricing_key("nova-2-video", "deepgram:nova-2")
+        .with_pricing_key("nova-2-drivethru", "deepgram:nova-2")
+        .with_pricing_key("nova-2-automotive", "deepgram:nova-2")
+        .with_pricing_key("nova-2-atc", "deepgram:nova-2")
 }
 
 fn google_stt_metadata() -> ProviderMetadata {
@@ -80,7 +108,7 @@ fn openai_stt_metadata() -> ProviderMetadata {
     ProviderMetadata::stt("openai", "OpenAI Whisper")
         .with_description("OpenAI Whisper API for speech recognition")
         .with_features(["word-timestamps", "translation"])
-        .with_models(["whisper-1"])
+        .with_models(["whisper-1", "gpt-4o-transcribe", "gpt-4o-mini-transcribe"])
 }
 
 fn assemblyai_stt_metadata() -> ProviderMetadata {
@@ -157,6 +185,23 @@ fn elevenlabs_tts_metadata() -> ProviderMetadata {
     ProviderMetadata::tts("elevenlabs", "ElevenLabs TTS")
         .with_description("High-quality voice synthesis with emotion control")
         .with_features(["streaming", "voice-cloning", "emotion-control"])
+        .with_models([
+            "eleven_v3",
+            "eleven_multilingual_v2",
+            "eleven_multilingual_v1",
+            "eleven_monolingual_v1",
+            "eleven_turbo_v2_5",
+            "eleven_turbo_v2",
+            "eleven_flash_v2_5",
+            "eleven_flash_v2",
+        ])
+        .with_pricing_key("eleven_v3", "elevenlabs:eleven_multilingual_v2")
+        .with_pricing_key(
+            "eleven_multilingual_v1",
+            "elevenlabs:eleven_multilingual_v2",
+        )
+        .with_pricing_key("eleven_turbo_v2", "elevenlabs:eleven_turbo_v2_5")
+        .with_pricing_key("eleven_flash_v2", "elevenlabs:eleven_flash_v2_5")
 }
 
 fn google_tts_metadata() -> ProviderMetadata {
@@ -492,11 +537,11 @@ inventory::submit! {
 
 #[cfg(test)]
 mod tests {
-    use crate::plugin::registry::global_registry;
+    use crate::plugin::registry::PluginRegistry;
 
     #[test]
     fn test_builtin_stt_providers_registered() {
-        let registry = global_registry();
+        let registry = PluginRegistry::new_isolated();
 
         // All 11 STT providers should be registered
         assert!(registry.has_stt_provider("deepgram"));
@@ -515,7 +560,7 @@ mod tests {
 
     #[test]
     fn test_builtin_tts_providers_registered() {
-        let registry = global_registry();
+        let registry = PluginRegistry::new_isolated();
 
         // All 12 TTS providers should be registered
         assert!(registry.has_tts_provider("deepgram"));
@@ -534,7 +579,7 @@ mod tests {
 
     #[test]
     fn test_builtin_realtime_providers_registered() {
-        let registry = global_registry();
+        let registry = PluginRegistry::new_isolated();
 
         // Both realtime providers should be registered
         assert!(registry.has_realtime_provider("openai"));
@@ -544,7 +589,7 @@ mod tests {
 
     #[test]
     fn test_provider_aliases() {
-        let registry = global_registry();
+        let registry = PluginRegistry::new_isolated();
 
         // Test STT aliases
         assert!(registry.has_stt_provider("azure")); // alias for microsoft-azure
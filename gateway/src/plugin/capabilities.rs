@@ -288,6 +288,32 @@ pub enum WSError {
     PermissionDenied(String),
 }
 
+/// HTTP request routed to a plugin under `/plugins/{plugin_id}`
+#[derive(Debug, Clone)]
+pub struct PluginHttpRequest {
+    /// Request method (e.g., "GET")
+    pub method: String,
+    /// Path below `/plugins/{plugin_id}`, starting with `/`
+    pub path: String,
+    /// Raw query string without the leading `?`
+    pub query: String,
+    /// Request headers as (lowercase name, value) pairs
+    pub headers: Vec<(String, String)>,
+    /// Request body
+    pub body: Bytes,
+}
+
+/// HTTP response returned by a plugin
+#[derive(Debug, Clone)]
+pub struct PluginHttpResponse {
+    /// Status code
+    pub status: u16,
+    /// Response headers as (name, value) pairs
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    pub body: Bytes,
+}
+
 /// Auth strategy capability
 ///
 /// Implement this trait to add custom authentication strategies.
@@ -7,10 +7,33 @@
 //!
 //! The dynamic loader:
 //! 1. Scans configured plugin directories for plugin libraries
-//! 2. Validates ABI compatibility using `abi_stable`
+//! 2. Validates ABI compatibility using `abi_stable` and the plugin's reported
+//!    plugin API version
 //! 3. Checks gateway version requirements
 //! 4. Registers loaded plugins with the existing `PluginRegistry`
 //!
+//! At startup, plugins are loaded and initialized concurrently (bounded by
+//! `with_max_parallel_loads`), each within `with_init_timeout`. A plugin that
+//! fails or times out is skipped without holding up the others; successful
+//! plugins are registered afterwards, in discovery order.
+//!
+//! # Plugin ID Conflicts
+//!
+//! Directories are searched in the order given to
+//! `load_all_from_directories`, and libraries within a directory in path
+//! order. When several successfully loaded libraries declare the same plugin
+//! ID, `with_conflict_policy` decides which one is registered: the one from
+//! the last directory (the default), the one with the highest semver version,
+//! or none at all, failing the whole load. A plugin ID naming a built-in
+//! provider is refused unless `with_allow_override` is set.
+//!
+//! # Plugin Storage
+//!
+//! With `with_storage`, each plugin gets a private directory named after its
+//! ID, passed to its `init` function together with the storage quota. After
+//! a load in which every plugin reported its ID, directories of plugins that
+//! are no longer installed are removed.
+//!
 //! # Safety
 //!
 //! Plugin loading involves unsafe operations. The loader provides:
@@ -24,20 +47,32 @@
 //! Plugins must follow the naming pattern:
 //! - Linux: `libwaav_plugin_<name>.so`
 //! - macOS: `libwaav_plugin_<name>.dylib`
-//! - Windows: `waav_plugin_<name>.dll`
+//! - Windows: `waav_plugin_<name>.dll`, matched case-insensitively
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use abi_stable::library::{LibraryError, RootModule};
+use abi_stable::sabi_types::VersionStrings;
 use waav_plugin_api::{
-    FFIConfig, PluginCapabilityType, PluginManifest, PluginModule_Ref,
-    RealtimeProvider, STTProvider, TTSProvider,
+    FFIConfig, FFIHttpHeader, FFIHttpRequest, FFIHttpResponse, PLUGIN_API_VERSION,
+    PluginCapabilityType, PluginManifest, PluginModule_Ref, RealtimeProvider, STTProvider,
+    TTSProvider,
 };
 
+use tokio::sync::Semaphore;
+
+use super::capabilities::{PluginHttpRequest, PluginHttpResponse};
+use super::dispatch::{is_builtin_realtime, is_builtin_stt, is_builtin_tts};
 use super::metadata::ProviderMetadata;
-use super::registry::{PluginRegistry, RealtimeFactoryFn, STTFactoryFn, TTSFactoryFn};
+use super::registry::{
+    PluginHttpHandlerFn, PluginRegistry, RealtimeFactoryFn, STTFactoryFn, TTSFactoryFn,
+};
+use super::storage::{PluginStorageManager, spawn_quota_checks};
+use crate::config::PluginConflictPolicy;
 use crate::core::realtime::{RealtimeConfig, RealtimeError};
 use crate::core::stt::{STTConfig, STTError};
 use crate::core::tts::{TTSConfig, TTSError};
@@ -62,6 +97,60 @@ pub enum PluginLoadError {
 
     #[error("Plugin manifest invalid: {0}")]
     ManifestInvalid(String),
+
+    #[error("ABI mismatch: plugin built against plugin API {plugin}, gateway uses {host}")]
+    AbiMismatch { plugin: String, host: String },
+
+    #[error("Plugin '{0}' is already loaded")]
+    DuplicatePlugin(String),
+
+    #[error("Plugin '{id}' is declared by several libraries: {}", .paths.join(", "))]
+    ConflictingPlugins { id: String, paths: Vec<String> },
+
+    #[error("Plugin '{0}' shadows a built-in provider; set plugins.allow_override to replace it")]
+    ShadowsBuiltin(String),
+}
+
+/// Default number of plugins loaded and initialized at the same time
+pub const DEFAULT_MAX_PARALLEL_LOADS: usize = 4;
+
+/// Default time a plugin may take to load and initialize
+pub const DEFAULT_INIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of loading one plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginLoadStatus {
+    /// Loaded, initialized and registered
+    Loaded,
+    /// Loading or initialization failed
+    Failed(String),
+    /// Did not finish within the init timeout
+    TimedOut,
+    /// Lost a plugin ID conflict to the library at the given path
+    Superseded(String),
+}
+
+impl fmt::Display for PluginLoadStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Loaded => f.write_str("loaded"),
+            Self::Failed(error) => write!(f, "failed: {error}"),
+            Self::TimedOut => f.write_str("timed out"),
+            Self::Superseded(path) => write!(f, "superseded by {path}"),
+        }
+    }
+}
+
+/// Result of loading one plugin candidate at startup
+#[derive(Debug, Clone)]
+pub struct PluginLoadReport {
+    /// Plugin name from the library filename
+    pub name: String,
+    /// Plugin version from its manifest, if it got that far
+    pub version: Option<String>,
+    /// Time spent loading and initializing
+    pub load_time: Duration,
+    pub status: PluginLoadStatus,
 }
 
 impl From<LibraryError> for PluginLoadError {
@@ -70,6 +159,61 @@ impl From<LibraryError> for PluginLoadError {
     }
 }
 
+/// Config constraints a plugin declares through `provider_capabilities`
+#[derive(Debug, Default, serde::Deserialize)]
+struct PluginProviderCapabilities {
+    #[serde(default)]
+    stt: Option<ProviderConstraints>,
+    #[serde(default)]
+    tts: Option<ProviderConstraints>,
+}
+
+/// Constraints on the configs one of a plugin's providers accepts
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct ProviderConstraints {
+    models: Vec<String>,
+    model_aliases: BTreeMap<String, String>,
+    sample_rates: Vec<u32>,
+    encodings: Vec<String>,
+    requires_api_key: bool,
+}
+
+impl ProviderConstraints {
+    /// Record the constraints in provider metadata, where config validation reads them
+    fn apply(&self, metadata: ProviderMetadata) -> ProviderMetadata {
+        let mut metadata = metadata
+            .with_models(self.models.iter().cloned())
+            .with_sample_rates(self.sample_rates.iter().copied())
+            .with_encodings(self.encodings.iter().cloned())
+            .with_api_key_required(self.requires_api_key);
+        metadata.model_aliases.extend(self.model_aliases.clone());
+        metadata
+    }
+}
+
+/// Read the config constraints a plugin module declares
+///
+/// Plugins without the export, or with unparseable JSON, get no
+/// constraints and their configs go to the factory unchecked.
+fn provider_capabilities(module: PluginModule_Ref, plugin_id: &str) -> PluginProviderCapabilities {
+    let Some(abi_stable::std_types::ROption::RSome(capabilities_fn)) =
+        module.provider_capabilities()
+    else {
+        return PluginProviderCapabilities::default();
+    };
+
+    let json = capabilities_fn();
+    serde_json::from_str(json.as_str()).unwrap_or_else(|e| {
+        tracing::warn!(
+            plugin_id = %plugin_id,
+            error = %e,
+            "Ignoring invalid provider capabilities JSON"
+        );
+        PluginProviderCapabilities::default()
+    })
+}
+
 /// Information about a discovered plugin candidate
 #[derive(Debug, Clone)]
 pub struct PluginCandidate {
@@ -112,14 +256,157 @@ impl LoadedPlugin {
     }
 }
 
+/// Compatibility checks and initialization applied to every plugin
+///
+/// Cloned into the blocking tasks that load plugins concurrently.
+#[derive(Debug, Clone)]
+struct PluginInitializer {
+    /// Gateway version for compatibility checking
+    gateway_version: semver::Version,
+    /// Treat any plugin ABI version mismatch as a load error
+    require_exact_abi: bool,
+    /// Storage directories provisioned for plugins, if configured
+    storage: Option<Arc<PluginStorageManager>>,
+}
+
+impl PluginInitializer {
+    /// Check a loaded module's versions and initialize the plugin
+    fn initialize(
+        &self,
+        module: PluginModule_Ref,
+        path: &Path,
+    ) -> Result<LoadedPlugin, PluginLoadError> {
+        // Check the ABI version before calling into the plugin
+        self.check_abi_compatibility(module, path)?;
+
+        // Get manifest
+        let manifest = (module.manifest())();
+
+        // Validate manifest
+        if manifest.id.is_empty() {
+            return Err(PluginLoadError::ManifestInvalid(
+                "Plugin ID cannot be empty".into(),
+            ));
+        }
+
+        // Check gateway version compatibility
+        self.check_version_compatibility(&manifest)?;
+
+        // Initialize the plugin, with its storage directory if configured
+        let config = match &self.storage {
+            Some(storage) => storage
+                .provision(manifest.id.as_str())
+                .map_err(|e| {
+                    PluginLoadError::InitializationError(format!(
+                        "cannot provision plugin storage: {e}"
+                    ))
+                })?
+                .to_ffi(),
+            None => FFIConfig::default(),
+        };
+        let init_result = (module.init())(&config as *const _);
+
+        if let abi_stable::std_types::RResult::RErr(e) = init_result {
+            return Err(PluginLoadError::InitializationError(e.to_string()));
+        }
+
+        // Note: abi_stable's load_from_file keeps the library alive internally
+        // by intentionally leaking it. This is a deliberate design choice for
+        // FFI safety. The library cannot be unloaded at runtime.
+        Ok(LoadedPlugin {
+            module,
+            manifest,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Check the plugin's ABI version against the gateway's
+    ///
+    /// Only reads the version the plugin exported; no plugin function is
+    /// called. Mismatches are fatal when `require_exact_abi` is set.
+    fn check_abi_compatibility(
+        &self,
+        module: PluginModule_Ref,
+        path: &Path,
+    ) -> Result<(), PluginLoadError> {
+        let plugin_version = module.plugin_abi_version();
+        if plugin_version == Some(PLUGIN_API_VERSION) {
+            return Ok(());
+        }
+
+        let plugin = plugin_version
+            .as_ref()
+            .map(format_version)
+            .unwrap_or_else(|| "unknown".to_string());
+        let host = format_version(&PLUGIN_API_VERSION);
+
+        if self.require_exact_abi {
+            return Err(PluginLoadError::AbiMismatch { plugin, host });
+        }
+
+        if module.is_compatible_with_host() {
+            tracing::debug!(
+                path = %path.display(),
+                plugin_abi_version = %plugin,
+                host_abi_version = %host,
+                "Plugin ABI version differs from gateway but is compatible"
+            );
+        } else {
+            tracing::warn!(
+                path = %path.display(),
+                plugin_abi_version = %plugin,
+                host_abi_version = %host,
+                "Plugin ABI version is incompatible with the gateway; the plugin may crash. \
+                 Rebuild it against the gateway's waav-plugin-api, or set \
+                 plugins.require_exact_abi to refuse such plugins"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Check if a plugin is compatible with the current gateway version
+    fn check_version_compatibility(&self, manifest: &PluginManifest) -> Result<(), PluginLoadError> {
+        let version_req_str = manifest.gateway_version_req.as_str();
+
+        // Empty requirement means any version is acceptable
+        if version_req_str.is_empty() {
+            return Ok(());
+        }
+
+        let version_req = semver::VersionReq::parse(version_req_str).map_err(|e| {
+            PluginLoadError::ManifestInvalid(format!(
+                "Invalid gateway version requirement '{}': {}",
+                version_req_str, e
+            ))
+        })?;
+
+        if !version_req.matches(&self.gateway_version) {
+            return Err(PluginLoadError::VersionIncompatible {
+                required: version_req_str.to_string(),
+                actual: self.gateway_version.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
 /// Dynamic Plugin Loader
 ///
 /// Discovers, loads, and registers plugins from filesystem directories.
 pub struct DynamicPluginLoader {
     /// Currently loaded plugins (keyed by plugin ID)
     loaded_plugins: HashMap<String, LoadedPlugin>,
-    /// Gateway version for compatibility checking
-    gateway_version: semver::Version,
+    initializer: PluginInitializer,
+    /// Time each plugin may take to load and initialize at startup
+    init_timeout: Duration,
+    /// Number of plugins loaded at the same time at startup
+    max_parallel_loads: usize,
+    /// Which library wins when several declare the same plugin ID
+    conflict_policy: PluginConflictPolicy,
+    /// Let plugins replace built-in providers of the same name
+    allow_override: bool,
 }
 
 impl DynamicPluginLoader {
@@ -131,14 +418,66 @@ impl DynamicPluginLoader {
 
         Self {
             loaded_plugins: HashMap::new(),
-            gateway_version,
+            initializer: PluginInitializer {
+                gateway_version,
+                require_exact_abi: false,
+                storage: None,
+            },
+            init_timeout: DEFAULT_INIT_TIMEOUT,
+            max_parallel_loads: DEFAULT_MAX_PARALLEL_LOADS,
+            conflict_policy: PluginConflictPolicy::default(),
+            allow_override: false,
         }
     }
 
+    /// Refuse to load plugins whose ABI version differs from the gateway's
+    ///
+    /// By default incompatible plugins are loaded with a warning.
+    pub fn with_require_exact_abi(mut self, require_exact_abi: bool) -> Self {
+        self.initializer.require_exact_abi = require_exact_abi;
+        self
+    }
+
+    /// Time each plugin may take to load and initialize in
+    /// `load_all_from_directory` before it is marked as timed out
+    pub fn with_init_timeout(mut self, init_timeout: Duration) -> Self {
+        self.init_timeout = init_timeout;
+        self
+    }
+
+    /// Number of plugins `load_all_from_directory` loads at the same time
+    pub fn with_max_parallel_loads(mut self, max_parallel_loads: usize) -> Self {
+        self.max_parallel_loads = max_parallel_loads.max(1);
+        self
+    }
+
+    /// How to pick between libraries declaring the same plugin ID
+    pub fn with_conflict_policy(mut self, conflict_policy: PluginConflictPolicy) -> Self {
+        self.conflict_policy = conflict_policy;
+        self
+    }
+
+    /// Register plugins whose ID names a built-in provider, replacing it
+    ///
+    /// By default such plugins are refused.
+    pub fn with_allow_override(mut self, allow_override: bool) -> Self {
+        self.allow_override = allow_override;
+        self
+    }
+
+    /// Provision a storage directory for each plugin, passed to its `init`
+    ///
+    /// Without storage, plugins are initialized with an empty config.
+    pub fn with_storage(mut self, storage: PluginStorageManager) -> Self {
+        self.initializer.storage = Some(Arc::new(storage));
+        self
+    }
+
     /// Discover plugin candidates in a directory
     ///
-    /// Scans the directory for files matching the plugin naming convention.
-    /// Does not load the plugins, only identifies candidates.
+    /// Scans the directory for files matching the plugin naming convention,
+    /// then its subdirectories, each in path order. Does not load the
+    /// plugins, only identifies candidates.
     pub fn discover(&self, plugin_dir: &Path) -> Result<Vec<PluginCandidate>, PluginLoadError> {
         let mut candidates = Vec::new();
 
@@ -166,6 +505,9 @@ impl DynamicPluginLoader {
             }
         }
 
+        candidates.sort_by(|a, b| a.path.cmp(&b.path));
+        let top_level = candidates.len();
+
         // Also scan subdirectories (one level deep)
         for entry in std::fs::read_dir(plugin_dir)? {
             let entry = entry?;
@@ -187,6 +529,8 @@ impl DynamicPluginLoader {
             }
         }
 
+        candidates[top_level..].sort_by(|a, b| a.path.cmp(&b.path));
+
         tracing::info!(
             count = candidates.len(),
             path = %plugin_dir.display(),
@@ -215,7 +559,21 @@ impl DynamicPluginLoader {
         #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
         let (prefix, suffix) = ("libwaav_plugin_", ".so");
 
-        if filename.starts_with(prefix) && filename.ends_with(suffix) {
+        let has_affixes = if cfg!(target_os = "windows") {
+            // File names are case-insensitive on Windows (`WAAV_PLUGIN_X.DLL`)
+            let ends_with = filename
+                .len()
+                .checked_sub(suffix.len())
+                .and_then(|start| filename.get(start..))
+                .is_some_and(|end| end.eq_ignore_ascii_case(suffix));
+            ends_with
+                && filename
+                    .get(..prefix.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        } else {
+            filename.starts_with(prefix) && filename.ends_with(suffix)
+        };
+        if has_affixes {
             let name_start = prefix.len();
             let name_end = filename.len() - suffix.len();
             if name_end > name_start {
@@ -237,37 +595,7 @@ impl DynamicPluginLoader {
 
         // Load the library using abi_stable
         let module = PluginModule_Ref::load_from_file(&candidate.path)?;
-
-        // Get manifest
-        let manifest = (module.manifest())();
-
-        // Validate manifest
-        if manifest.id.is_empty() {
-            return Err(PluginLoadError::ManifestInvalid(
-                "Plugin ID cannot be empty".into(),
-            ));
-        }
-
-        // Check gateway version compatibility
-        self.check_version_compatibility(&manifest)?;
-
-        // Initialize the plugin
-        let config = FFIConfig::default();
-        let init_result = (module.init())(&config as *const _);
-
-        if let abi_stable::std_types::RResult::RErr(e) = init_result {
-            return Err(PluginLoadError::InitializationError(e.to_string()));
-        }
-
-        // Store the loaded plugin
-        // Note: abi_stable's load_from_file keeps the library alive internally
-        // by intentionally leaking it. This is a deliberate design choice for
-        // FFI safety. The library cannot be unloaded at runtime.
-        let plugin = LoadedPlugin {
-            module,
-            manifest,
-            path: candidate.path.clone(),
-        };
+        let plugin = self.initializer.initialize(module, &candidate.path)?;
 
         let id = plugin.id().to_string();
         self.loaded_plugins.insert(id.clone(), plugin);
@@ -281,49 +609,24 @@ impl DynamicPluginLoader {
         Ok(self.loaded_plugins.get(&id).unwrap())
     }
 
-    /// Check if a plugin is compatible with the current gateway version
-    fn check_version_compatibility(&self, manifest: &PluginManifest) -> Result<(), PluginLoadError> {
-        let version_req_str = manifest.gateway_version_req.as_str();
-
-        // Empty requirement means any version is acceptable
-        if version_req_str.is_empty() {
-            return Ok(());
-        }
-
-        let version_req = semver::VersionReq::parse(version_req_str).map_err(|e| {
-            PluginLoadError::ManifestInvalid(format!(
-                "Invalid gateway version requirement '{}': {}",
-                version_req_str, e
-            ))
-        })?;
-
-        if !version_req.matches(&self.gateway_version) {
-            return Err(PluginLoadError::VersionIncompatible {
-                required: version_req_str.to_string(),
-                actual: self.gateway_version.to_string(),
-            });
-        }
-
-        Ok(())
-    }
-
     /// Register a loaded plugin with the gateway's plugin registry
     pub fn register_plugin(&self, plugin: &LoadedPlugin, registry: &PluginRegistry) {
         let manifest = plugin.manifest();
         let module = plugin.module;
+        let constraints = provider_capabilities(module, manifest.id.as_str());
 
         for cap in manifest.capabilities.iter() {
             match cap {
                 PluginCapabilityType::STT => {
                     // create_stt is a required field, returns ROption directly
                     if let abi_stable::std_types::ROption::RSome(create_fn) = module.create_stt() {
-                        self.register_stt_factory(manifest, create_fn, registry);
+                        self.register_stt_factory(manifest, create_fn, constraints.stt.as_ref(), registry);
                     }
                 }
                 PluginCapabilityType::TTS => {
                     // create_tts is a suffix field, returns Option<ROption>
                     if let Some(abi_stable::std_types::ROption::RSome(create_fn)) = module.create_tts() {
-                        self.register_tts_factory(manifest, create_fn, registry);
+                        self.register_tts_factory(manifest, create_fn, constraints.tts.as_ref(), registry);
                     }
                 }
                 PluginCapabilityType::Realtime => {
@@ -341,6 +644,13 @@ impl DynamicPluginLoader {
                 }
             }
         }
+
+        // handle_http is a suffix field, absent from plugins built before it
+        if let Some(abi_stable::std_types::ROption::RSome(handle_fn)) = module.handle_http() {
+            self.register_http_handler(manifest, handle_fn, registry);
+        }
+
+        registry.record_dynamic_plugin(manifest.id.as_str(), manifest.version.as_str());
     }
 
     /// Create and register an STT factory for a dynamic plugin
@@ -348,6 +658,7 @@ impl DynamicPluginLoader {
         &self,
         manifest: &PluginManifest,
         create_fn: extern "C" fn(*const FFIConfig) -> abi_stable::std_types::RResult<STTProvider, abi_stable::std_types::RString>,
+        constraints: Option<&ProviderConstraints>,
         registry: &PluginRegistry,
     ) {
         let plugin_id = manifest.id.to_string();
@@ -376,9 +687,12 @@ impl DynamicPluginLoader {
         });
 
         // Create metadata
-        let metadata = ProviderMetadata::stt(&plugin_id, &plugin_name)
+        let mut metadata = ProviderMetadata::stt(&plugin_id, &plugin_name)
             .with_feature("streaming")  // Assume streaming capability
             .with_description(manifest.description.to_string());
+        if let Some(constraints) = constraints {
+            metadata = constraints.apply(metadata);
+        }
 
         registry.register_stt(&plugin_id, factory, metadata);
 
@@ -393,6 +707,7 @@ impl DynamicPluginLoader {
         &self,
         manifest: &PluginManifest,
         create_fn: extern "C" fn(*const FFIConfig) -> abi_stable::std_types::RResult<TTSProvider, abi_stable::std_types::RString>,
+        constraints: Option<&ProviderConstraints>,
         registry: &PluginRegistry,
     ) {
         let plugin_id = manifest.id.to_string();
@@ -416,9 +731,12 @@ impl DynamicPluginLoader {
             }
         });
 
-        let metadata = ProviderMetadata::tts(&plugin_id, &plugin_name)
+        let mut metadata = ProviderMetadata::tts(&plugin_id, &plugin_name)
             .with_feature("streaming")
             .with_description(manifest.description.to_string());
+        if let Some(constraints) = constraints {
+            metadata = constraints.apply(metadata);
+        }
 
         registry.register_tts(&plugin_id, factory, metadata);
 
@@ -467,48 +785,368 @@ impl DynamicPluginLoader {
         );
     }
 
+    /// Register the handler for a dynamic plugin's `/plugins/{plugin_id}/...` routes
+    fn register_http_handler(
+        &self,
+        manifest: &PluginManifest,
+        handle_fn: extern "C" fn(*const FFIHttpRequest) -> abi_stable::std_types::RResult<FFIHttpResponse, abi_stable::std_types::RString>,
+        registry: &PluginRegistry,
+    ) {
+        let plugin_id = manifest.id.to_string();
+
+        let handler: PluginHttpHandlerFn = Arc::new(move |request: PluginHttpRequest| {
+            let ffi_request = FFIHttpRequest {
+                method: request.method.into(),
+                path: request.path.into(),
+                query: request.query.into(),
+                headers: request
+                    .headers
+                    .into_iter()
+                    .map(|(name, value)| FFIHttpHeader::new(name, value))
+                    .collect(),
+                body: request.body.to_vec().into(),
+            };
+
+            match handle_fn(&ffi_request as *const _) {
+                abi_stable::std_types::RResult::ROk(response) => Ok(PluginHttpResponse {
+                    status: response.status,
+                    headers: response
+                        .headers
+                        .into_iter()
+                        .map(|header| (header.name.into_string(), header.value.into_string()))
+                        .collect(),
+                    body: response.body.into_vec().into(),
+                }),
+                abi_stable::std_types::RResult::RErr(e) => Err(e.into_string()),
+            }
+        });
+
+        registry.register_http_handler(&plugin_id, handler);
+
+        tracing::info!(
+            plugin_id = %plugin_id,
+            "Registered dynamic plugin HTTP routes"
+        );
+    }
+
     /// Load all plugins from a directory and register them
     ///
-    /// This is the main entry point for dynamic plugin loading.
-    /// Failed plugins are logged but don't prevent other plugins from loading.
-    pub fn load_all_from_directory(
+    /// Shorthand for `load_all_from_directories` with a single directory.
+    pub async fn load_all_from_directory(
         &mut self,
         plugin_dir: &Path,
         registry: &PluginRegistry,
-    ) -> Result<usize, PluginLoadError> {
-        let candidates = self.discover(plugin_dir)?;
-        let mut loaded_ids: Vec<String> = Vec::new();
+    ) -> Result<Vec<PluginLoadReport>, PluginLoadError> {
+        self.load_all_from_directories(&[plugin_dir.to_path_buf()], registry)
+            .await
+    }
+
+    /// Load all plugins from the given directories and register them
+    ///
+    /// This is the main entry point for dynamic plugin loading. Plugins are
+    /// loaded and initialized concurrently; one that fails or exceeds the
+    /// init timeout is reported and skipped without delaying the others.
+    /// Plugin ID conflicts are then resolved by the conflict policy, with
+    /// later directories counting as later. Returns one report per discovered
+    /// plugin, in discovery order, and logs them as a summary table.
+    ///
+    /// Fails without registering anything when the policy is `error` and two
+    /// libraries declare the same plugin ID.
+    pub async fn load_all_from_directories(
+        &mut self,
+        plugin_dirs: &[PathBuf],
+        registry: &PluginRegistry,
+    ) -> Result<Vec<PluginLoadReport>, PluginLoadError> {
+        let mut candidates = Vec::new();
+        for plugin_dir in plugin_dirs {
+            candidates.extend(self.discover(plugin_dir)?);
+        }
+        let reports = self
+            .load_candidates(candidates, registry, |path| {
+                Ok(PluginModule_Ref::load_from_file(path)?)
+            })
+            .await?;
+
+        let loaded = reports
+            .iter()
+            .filter(|report| report.status == PluginLoadStatus::Loaded)
+            .count();
+        tracing::info!(
+            loaded,
+            failed = reports.len() - loaded,
+            directories = ?plugin_dirs,
+            "Dynamic plugin loading complete\n{}",
+            format_summary(&reports)
+        );
+
+        Ok(reports)
+    }
+
+    /// Load and initialize candidates concurrently, then register the
+    /// successful ones in discovery order
+    ///
+    /// `open` loads a candidate's library; tests substitute in-process
+    /// modules. Registration happens only after every load has finished or
+    /// timed out and plugin ID conflicts are resolved, so the registry and
+    /// `loaded_plugins` are only ever touched from this task.
+    async fn load_candidates<F>(
+        &mut self,
+        candidates: Vec<PluginCandidate>,
+        registry: &PluginRegistry,
+        open: F,
+    ) -> Result<Vec<PluginLoadReport>, PluginLoadError>
+    where
+        F: Fn(&Path) -> Result<PluginModule_Ref, PluginLoadError> + Send + Sync + 'static,
+    {
+        let open = Arc::new(open);
+        let permits = Arc::new(Semaphore::new(self.max_parallel_loads));
+        let init_timeout = self.init_timeout;
+
+        let loads = candidates.iter().map(|candidate| {
+            let open = open.clone();
+            let permits = permits.clone();
+            let initializer = self.initializer.clone();
+            let path = candidate.path.clone();
+            let name = candidate.name.clone();
+
+            async move {
+                let _permit = permits.acquire_owned().await;
+                let started = Instant::now();
+                // A timed out init cannot be cancelled; its blocking thread
+                // runs on and the plugin is discarded if it ever completes
+                let task = tokio::task::spawn_blocking(move || {
+                    tracing::info!(path = %path.display(), name = %name, "Loading plugin");
+                    let module = open(&path)?;
+                    initializer.initialize(module, &path)
+                });
+                let result = match tokio::time::timeout(init_timeout, task).await {
+                    Ok(Ok(result)) => result.map_err(Some),
+                    Ok(Err(e)) => Err(Some(PluginLoadError::InitializationError(format!(
+                        "load task failed: {e}"
+                    )))),
+                    Err(_) => Err(None),
+                };
+                (result, started.elapsed())
+            }
+        });
+        let outcomes = futures::future::join_all(loads).await;
+
+        let loaded: Vec<_> = outcomes
+            .iter()
+            .map(|(result, _)| result.as_ref().ok())
+            .collect();
+        let superseded = self.resolve_conflicts(&loaded)?;
 
-        // First pass: load all plugins
-        for candidate in candidates {
-            match self.load(&candidate) {
+        let mut reports = Vec::with_capacity(candidates.len());
+        for (index, (candidate, (result, load_time))) in
+            candidates.into_iter().zip(outcomes).enumerate()
+        {
+            let mut report = PluginLoadReport {
+                name: candidate.name,
+                version: None,
+                load_time,
+                status: PluginLoadStatus::Loaded,
+            };
+
+            match result {
                 Ok(plugin) => {
-                    loaded_ids.push(plugin.id().to_string());
+                    report.version = Some(plugin.manifest().version.to_string());
+                    let id = plugin.id().to_string();
+                    let refused = if let Some(winner) = superseded.get(&index) {
+                        report.status = PluginLoadStatus::Superseded(winner.clone());
+                        None
+                    } else if self.loaded_plugins.contains_key(&id) {
+                        Some(PluginLoadError::DuplicatePlugin(id.clone()))
+                    } else if shadows_builtin(plugin.manifest()) && !self.allow_override {
+                        Some(PluginLoadError::ShadowsBuiltin(id.clone()))
+                    } else {
+                        None
+                    };
+
+                    if let Some(e) = refused {
+                        tracing::warn!(
+                            path = %candidate.path.display(),
+                            error = %e,
+                            "Failed to load plugin"
+                        );
+                        report.status = PluginLoadStatus::Failed(e.to_string());
+                    } else if report.status == PluginLoadStatus::Loaded {
+                        if shadows_builtin(plugin.manifest()) {
+                            tracing::warn!(
+                                plugin_id = %id,
+                                path = %candidate.path.display(),
+                                "Dynamic plugin overrides built-in provider"
+                            );
+                        }
+                        self.register_plugin(&plugin, registry);
+                        tracing::info!(
+                            plugin_id = %id,
+                            path = %candidate.path.display(),
+                            "Successfully loaded plugin"
+                        );
+                        self.loaded_plugins.insert(id, plugin);
+                    }
                 }
-                Err(e) => {
+                Err(Some(e)) => {
                     tracing::warn!(
                         path = %candidate.path.display(),
                         error = %e,
                         "Failed to load plugin"
                     );
+                    report.status = PluginLoadStatus::Failed(e.to_string());
+                }
+                Err(None) => {
+                    tracing::warn!(
+                        path = %candidate.path.display(),
+                        timeout_ms = init_timeout.as_millis() as u64,
+                        "Plugin did not initialize in time"
+                    );
+                    report.status = PluginLoadStatus::TimedOut;
                 }
             }
+
+            reports.push(report);
         }
 
-        // Second pass: register all loaded plugins
-        for id in &loaded_ids {
-            if let Some(plugin) = self.loaded_plugins.get(id) {
-                self.register_plugin(plugin, registry);
+        self.remove_unused_storage(&reports);
+        Ok(reports)
+    }
+
+    /// Remove the storage of plugins that are no longer installed
+    ///
+    /// Skipped when a plugin failed or timed out, since its ID, and so its
+    /// storage directory, is unknown.
+    fn remove_unused_storage(&self, reports: &[PluginLoadReport]) {
+        let Some(storage) = &self.initializer.storage else {
+            return;
+        };
+        let all_known = reports.iter().all(|report| {
+            matches!(
+                report.status,
+                PluginLoadStatus::Loaded | PluginLoadStatus::Superseded(_)
+            )
+        });
+        if !all_known {
+            tracing::debug!("Keeping plugin storage directories, not every plugin loaded");
+            return;
+        }
+
+        let plugin_ids: HashSet<&str> = self.loaded_plugins.keys().map(String::as_str).collect();
+        if let Err(e) = storage.remove_unused(&plugin_ids) {
+            tracing::warn!(
+                data_dir = %storage.data_dir().display(),
+                error = %e,
+                "Failed to remove storage of uninstalled plugins"
+            );
+        }
+    }
+
+    /// Delete files of loaded plugins whose storage is over the quota
+    pub fn enforce_storage_quotas(&self) {
+        let Some(storage) = &self.initializer.storage else {
+            return;
+        };
+        for plugin_id in self.loaded_plugins.keys() {
+            if let Err(e) = storage.enforce_quota(plugin_id) {
+                tracing::warn!(
+                    plugin_id = %plugin_id,
+                    error = %e,
+                    "Failed to check plugin storage quota"
+                );
             }
         }
+    }
 
-        tracing::info!(
-            loaded = loaded_ids.len(),
-            directory = %plugin_dir.display(),
-            "Dynamic plugin loading complete"
-        );
+    /// Check the storage quotas of the loaded plugins every `interval`
+    ///
+    /// Returns `None` when no storage is configured.
+    pub fn spawn_storage_quota_checks(
+        &self,
+        interval: Duration,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let storage = self.initializer.storage.clone()?;
+        Some(spawn_quota_checks(
+            storage,
+            self.loaded_plugin_ids(),
+            interval,
+        ))
+    }
+
+    /// Pick one library per plugin ID among the loaded candidates
+    ///
+    /// `loaded` holds the successfully initialized plugin of each candidate,
+    /// in discovery order. Returns the path of the winning library for every
+    /// candidate that lost a conflict, keyed by candidate index.
+    fn resolve_conflicts(
+        &self,
+        loaded: &[Option<&LoadedPlugin>],
+    ) -> Result<HashMap<usize, String>, PluginLoadError> {
+        // BTreeMap keeps the order conflicts are reported in deterministic
+        let mut by_id: BTreeMap<&str, Vec<(usize, &LoadedPlugin)>> = BTreeMap::new();
+        for (index, plugin) in loaded.iter().enumerate() {
+            if let Some(plugin) = plugin {
+                by_id.entry(plugin.id()).or_default().push((index, *plugin));
+            }
+        }
+
+        let mut superseded = HashMap::new();
+        for (id, plugins) in by_id.into_iter().filter(|(_, plugins)| plugins.len() > 1) {
+            let paths: Vec<String> = plugins
+                .iter()
+                .map(|(_, plugin)| plugin.path().display().to_string())
+                .collect();
+
+            let (winner, reason) = match self.conflict_policy {
+                PluginConflictPolicy::Error => {
+                    let e = PluginLoadError::ConflictingPlugins {
+                        id: id.to_string(),
+                        paths,
+                    };
+                    tracing::error!(policy = %self.conflict_policy, error = %e, "Plugin ID conflict");
+                    return Err(e);
+                }
+                PluginConflictPolicy::LastDirWins => (
+                    plugins[plugins.len() - 1],
+                    "found in the last directory".to_string(),
+                ),
+                PluginConflictPolicy::HighestVersion => {
+                    // max_by_key returns the last maximum, so ties go to the
+                    // later directory and unparseable versions lose
+                    let winner = *plugins
+                        .iter()
+                        .max_by_key(|(_, plugin)| {
+                            semver::Version::parse(plugin.manifest().version.as_str()).ok()
+                        })
+                        .expect("conflicts involve at least two plugins");
+                    let reason = format!("highest version {}", winner.1.manifest().version);
+                    (winner, reason)
+                }
+            };
 
-        Ok(loaded_ids.len())
+            let winner_path = winner.1.path().display().to_string();
+            tracing::info!(
+                plugin_id = %id,
+                policy = %self.conflict_policy,
+                winner = %winner_path,
+                reason = %reason,
+                candidates = ?paths,
+                "Resolved plugin ID conflict"
+            );
+            for (index, plugin) in plugins {
+                if index != winner.0 {
+                    tracing::warn!(
+                        plugin_id = %id,
+                        path = %plugin.path().display(),
+                        version = %plugin.manifest().version,
+                        winner = %winner_path,
+                        "Skipping plugin superseded by another library with the same ID"
+                    );
+                    superseded.insert(index, winner_path.clone());
+                }
+            }
+        }
+
+        Ok(superseded)
     }
 
     /// Get a list of currently loaded plugin IDs
@@ -524,28 +1162,100 @@ impl DynamicPluginLoader {
     /// will remain loaded until the process exits. To reload plugins, restart
     /// the gateway.
     pub fn shutdown_all(&mut self) {
-        for (id, plugin) in self.loaded_plugins.drain() {
-            tracing::debug!(
-                plugin_id = %id,
-                path = %plugin.path.display(),
-                "Shutting down plugin"
-            );
-
-            // Call shutdown function for plugin cleanup
-            let result = (plugin.module.shutdown())();
+        for (_, plugin) in self.loaded_plugins.drain() {
+            shutdown_plugin(plugin);
+        }
+    }
 
-            if let abi_stable::std_types::RResult::RErr(e) = result {
-                tracing::warn!(
-                    plugin_id = %id,
-                    error = %e.as_str(),
-                    "Plugin shutdown returned error"
-                );
+    /// Leave the loaded plugins to `registry`, which shuts them down in
+    /// [`PluginRegistry::shutdown`]
+    ///
+    /// Dropping the loader shuts its plugins down right away, while the
+    /// factories it registered are still in use; a loader whose plugins
+    /// should outlive it hands them over instead.
+    pub fn hand_over_to(mut self, registry: &PluginRegistry) {
+        let plugins = std::mem::take(&mut self.loaded_plugins);
+        if plugins.is_empty() {
+            return;
+        }
+        registry.on_shutdown(Box::new(move || {
+            for (_, plugin) in plugins {
+                shutdown_plugin(plugin);
             }
+        }));
+    }
+}
+
+/// Call a plugin's shutdown function
+///
+/// The library stays loaded (by design): abi_stable leaks it for FFI
+/// safety, and the memory is freed when the process exits.
+fn shutdown_plugin(plugin: LoadedPlugin) {
+    tracing::debug!(
+        plugin_id = %plugin.id(),
+        path = %plugin.path.display(),
+        "Shutting down plugin"
+    );
+
+    if let abi_stable::std_types::RResult::RErr(e) = (plugin.module.shutdown())() {
+        tracing::warn!(
+            plugin_id = %plugin.id(),
+            error = %e.as_str(),
+            "Plugin shutdown returned error"
+        );
+    }
+}
 
-            // Note: Library remains loaded (by design) - abi_stable leaks it
-            // for FFI safety. The memory will be freed when process exits.
+/// Whether a plugin's ID names a built-in provider of a capability it offers
+fn shadows_builtin(manifest: &PluginManifest) -> bool {
+    let id = manifest.id.as_str();
+    manifest.capabilities.iter().any(|cap| match cap {
+        PluginCapabilityType::STT => is_builtin_stt(id),
+        PluginCapabilityType::TTS => is_builtin_tts(id),
+        PluginCapabilityType::Realtime => is_builtin_realtime(id),
+        PluginCapabilityType::WSHandler => false,
+    })
+}
+
+/// Render load reports as a table with one row per plugin
+fn format_summary(reports: &[PluginLoadReport]) -> String {
+    let rows: Vec<[String; 4]> = reports
+        .iter()
+        .map(|report| {
+            [
+                report.name.clone(),
+                report.version.clone().unwrap_or_else(|| "-".to_string()),
+                format!("{}ms", report.load_time.as_millis()),
+                report.status.to_string(),
+            ]
+        })
+        .collect();
+
+    let header = ["plugin", "version", "load time", "status"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
         }
     }
+
+    let mut table = String::new();
+    for row in std::iter::once(header.map(String::from)).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// Format plugin API version strings as `major.minor.patch`
+fn format_version(version: &VersionStrings) -> String {
+    format!("{}.{}.{}", version.major, version.minor, version.patch)
 }
 
 impl Default for DynamicPluginLoader {
@@ -589,6 +1299,27 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn test_extract_plugin_name_windows() {
+        let loader = DynamicPluginLoader::new();
+
+        assert_eq!(
+            loader.extract_plugin_name(Path::new(r"C:\ProgramData\WaaV\plugins\waav_plugin_custom.dll")),
+            Some("custom".to_string())
+        );
+        assert_eq!(
+            loader.extract_plugin_name(Path::new("WAAV_PLUGIN_My_Stt.DLL")),
+            Some("My_Stt".to_string())
+        );
+        assert_eq!(
+            loader.extract_plugin_name(Path::new("libwaav_plugin_custom.so")),
+            None
+        );
+        assert_eq!(loader.extract_plugin_name(Path::new("waav_plugin_.dll")), None);
+        assert_eq!(loader.extract_plugin_name(Path::new("other.dll")), None);
+    }
+
     #[test]
     fn test_version_compatibility() {
         let loader = DynamicPluginLoader::new();
@@ -596,17 +1327,714 @@ mod tests {
         // Valid version requirements
         let manifest = PluginManifest::new("test", "Test", "1.0.0")
             .with_gateway_version(">=1.0.0");
-        assert!(loader.check_version_compatibility(&manifest).is_ok());
+        assert!(loader.initializer.check_version_compatibility(&manifest).is_ok());
 
         // Empty version requirement (always valid)
         let manifest = PluginManifest::new("test", "Test", "1.0.0")
             .with_gateway_version("");
-        assert!(loader.check_version_compatibility(&manifest).is_ok());
+        assert!(loader.initializer.check_version_compatibility(&manifest).is_ok());
 
         // Invalid version requirement syntax
         let manifest = PluginManifest::new("test", "Test", "1.0.0")
             .with_gateway_version("not-a-version");
-        assert!(loader.check_version_compatibility(&manifest).is_err());
+        assert!(loader.initializer.check_version_compatibility(&manifest).is_err());
+    }
+
+    extern "C" fn mock_manifest() -> PluginManifest {
+        PluginManifest::new("mock", "Mock", "1.0.0")
+    }
+
+    extern "C" fn mock_init(_config: *const FFIConfig) -> waav_plugin_api::FFIResult {
+        panic!("init must not be called by the ABI check");
+    }
+
+    extern "C" fn mock_shutdown() -> waav_plugin_api::FFIResult {
+        waav_plugin_api::ffi_ok()
+    }
+
+    /// Mock plugin module reporting the given ABI version
+    fn mock_module(abi_version: VersionStrings) -> PluginModule_Ref {
+        use abi_stable::prefix_type::PrefixTypeTrait;
+
+        waav_plugin_api::PluginModule {
+            manifest: mock_manifest,
+            init: mock_init,
+            shutdown: mock_shutdown,
+            create_stt: abi_stable::std_types::ROption::RNone,
+            create_tts: abi_stable::std_types::ROption::RNone,
+            create_realtime: abi_stable::std_types::ROption::RNone,
+            abi_version,
+            provider_capabilities: abi_stable::std_types::ROption::RNone,
+            handle_http: abi_stable::std_types::ROption::RNone,
+        }
+        .leak_into_prefix()
+    }
+
+    #[test]
+    fn test_abi_check_matching_version() {
+        let module = mock_module(PLUGIN_API_VERSION);
+        let path = Path::new("libwaav_plugin_mock.so");
+
+        let loader = DynamicPluginLoader::new();
+        assert!(loader.initializer.check_abi_compatibility(module, path).is_ok());
+
+        let loader = DynamicPluginLoader::new().with_require_exact_abi(true);
+        assert!(loader.initializer.check_abi_compatibility(module, path).is_ok());
+    }
+
+    #[test]
+    fn test_abi_check_mismatched_version() {
+        let module = mock_module(VersionStrings::new("99.0.0"));
+        assert!(!module.is_compatible_with_host());
+        let path = Path::new("libwaav_plugin_mock.so");
+
+        // Mismatch is only a warning by default
+        let loader = DynamicPluginLoader::new();
+        assert!(loader.initializer.check_abi_compatibility(module, path).is_ok());
+
+        let loader = DynamicPluginLoader::new().with_require_exact_abi(true);
+        match loader.initializer.check_abi_compatibility(module, path) {
+            Err(PluginLoadError::AbiMismatch { plugin, host }) => {
+                assert_eq!(plugin, "99.0.0");
+                assert_eq!(host, format_version(&PLUGIN_API_VERSION));
+            }
+            other => panic!("expected AbiMismatch, got {:?}", other.err()),
+        }
+    }
+
+    extern "C" fn capabilities_json() -> abi_stable::std_types::RString {
+        r#"{
+            "stt": {"models": ["fast", "accurate"], "model_aliases": {"fast-latest": "fast"}},
+            "tts": {"sample_rates": [16000, 24000], "encodings": ["wav"], "requires_api_key": true}
+        }"#
+        .into()
+    }
+
+    extern "C" fn capabilities_invalid() -> abi_stable::std_types::RString {
+        "not json".into()
+    }
+
+    fn capabilities_module(
+        capabilities: extern "C" fn() -> abi_stable::std_types::RString,
+    ) -> PluginModule_Ref {
+        use abi_stable::prefix_type::PrefixTypeTrait;
+
+        waav_plugin_api::PluginModule {
+            manifest: mock_manifest,
+            init: mock_init,
+            shutdown: mock_shutdown,
+            create_stt: abi_stable::std_types::ROption::RNone,
+            create_tts: abi_stable::std_types::ROption::RNone,
+            create_realtime: abi_stable::std_types::ROption::RNone,
+            abi_version: PLUGIN_API_VERSION,
+            provider_capabilities: abi_stable::std_types::ROption::RSome(capabilities),
+            handle_http: abi_stable::std_types::ROption::RNone,
+        }
+        .leak_into_prefix()
+    }
+
+    #[test]
+    fn test_provider_capabilities_become_metadata() {
+        let capabilities = provider_capabilities(capabilities_module(capabilities_json), "mock");
+
+        let metadata = capabilities
+            .stt
+            .unwrap()
+            .apply(ProviderMetadata::stt("mock", "Mock"));
+        assert_eq!(metadata.supported_models, vec!["fast", "accurate"]);
+        assert_eq!(metadata.resolve_model("Fast-Latest"), Some("fast"));
+
+        let metadata = capabilities
+            .tts
+            .unwrap()
+            .apply(ProviderMetadata::tts("mock", "Mock"));
+        assert_eq!(metadata.supported_sample_rates, vec![16000, 24000]);
+        assert_eq!(metadata.supported_encodings, vec!["wav"]);
+        assert!(metadata.supported_models.is_empty());
+        assert!(metadata.requires_api_key);
+    }
+
+    #[test]
+    fn test_missing_or_invalid_provider_capabilities() {
+        let capabilities = provider_capabilities(mock_module(PLUGIN_API_VERSION), "mock");
+        assert!(capabilities.stt.is_none() && capabilities.tts.is_none());
+
+        let capabilities = provider_capabilities(capabilities_module(capabilities_invalid), "mock");
+        assert!(capabilities.stt.is_none() && capabilities.tts.is_none());
+    }
+
+    extern "C" fn http_echo(
+        request: *const FFIHttpRequest,
+    ) -> abi_stable::std_types::RResult<FFIHttpResponse, abi_stable::std_types::RString> {
+        let request = unsafe { &*request };
+        if request.path.as_str() == "/fail" {
+            return abi_stable::std_types::RResult::RErr("cache unavailable".into());
+        }
+        let body = format!("{} {}?{}", request.method, request.path, request.query);
+        abi_stable::std_types::RResult::ROk(
+            FFIHttpResponse::new(201)
+                .with_header(
+                    "x-plugin",
+                    request.header("x-request-id").unwrap_or_default(),
+                )
+                .with_body(body.into_bytes()),
+        )
+    }
+
+    #[test]
+    fn test_register_plugin_http_handler() {
+        use abi_stable::prefix_type::PrefixTypeTrait;
+
+        let module = waav_plugin_api::PluginModule {
+            manifest: mock_manifest,
+            init: mock_init,
+            shutdown: mock_shutdown,
+            create_stt: abi_stable::std_types::ROption::RNone,
+            create_tts: abi_stable::std_types::ROption::RNone,
+            create_realtime: abi_stable::std_types::ROption::RNone,
+            abi_version: PLUGIN_API_VERSION,
+            provider_capabilities: abi_stable::std_types::ROption::RNone,
+            handle_http: abi_stable::std_types::ROption::RSome(http_echo),
+        }
+        .leak_into_prefix();
+        let plugin = LoadedPlugin {
+            module,
+            manifest: mock_manifest(),
+            path: PathBuf::from("libwaav_plugin_mock.so"),
+        };
+        let registry = PluginRegistry::new();
+        DynamicPluginLoader::new().register_plugin(&plugin, &registry);
+
+        let handler = registry.get_http_handler("mock").unwrap();
+        let request = |path: &str| PluginHttpRequest {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            query: "force=true".to_string(),
+            headers: vec![("x-request-id".to_string(), "req-1".to_string())],
+            body: Default::default(),
+        };
+        let response = handler(request("/cache/flush")).unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(
+            response.headers,
+            vec![("x-plugin".to_string(), "req-1".to_string())]
+        );
+        assert_eq!(response.body.as_ref(), b"POST /cache/flush?force=true");
+
+        assert_eq!(handler(request("/fail")).unwrap_err(), "cache unavailable");
+    }
+
+    static COUNTED_SHUTDOWNS: std::sync::atomic::AtomicUsize =
+        std::sync::atomic::AtomicUsize::new(0);
+
+    extern "C" fn counted_shutdown() -> waav_plugin_api::FFIResult {
+        COUNTED_SHUTDOWNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        waav_plugin_api::ffi_ok()
+    }
+
+    #[test]
+    fn test_handed_over_plugins_shut_down_with_registry() {
+        use abi_stable::prefix_type::PrefixTypeTrait;
+        use std::sync::atomic::Ordering;
+
+        let module = waav_plugin_api::PluginModule {
+            manifest: mock_manifest,
+            init: mock_init,
+            shutdown: counted_shutdown,
+            create_stt: abi_stable::std_types::ROption::RNone,
+            create_tts: abi_stable::std_types::ROption::RNone,
+            create_realtime: abi_stable::std_types::ROption::RNone,
+            abi_version: PLUGIN_API_VERSION,
+            provider_capabilities: abi_stable::std_types::ROption::RNone,
+            handle_http: abi_stable::std_types::ROption::RSome(http_echo),
+        }
+        .leak_into_prefix();
+        let plugin = LoadedPlugin {
+            module,
+            manifest: mock_manifest(),
+            path: PathBuf::from("libwaav_plugin_mock.so"),
+        };
+        let registry = PluginRegistry::new();
+        let mut loader = DynamicPluginLoader::new();
+        loader.register_plugin(&plugin, &registry);
+        loader.loaded_plugins.insert("mock".to_string(), plugin);
+
+        // Dropping the loader leaves the plugin running
+        loader.hand_over_to(&registry);
+        assert_eq!(COUNTED_SHUTDOWNS.load(Ordering::SeqCst), 0);
+        assert!(registry.get_http_handler("mock").is_some());
+
+        registry.shutdown();
+        assert_eq!(COUNTED_SHUTDOWNS.load(Ordering::SeqCst), 1);
+        assert!(registry.get_http_handler("mock").is_none());
+    }
+
+    extern "C" fn fixture_init_ok(_config: *const FFIConfig) -> waav_plugin_api::FFIResult {
+        waav_plugin_api::ffi_ok()
+    }
+
+    extern "C" fn fixture_init_slow(_config: *const FFIConfig) -> waav_plugin_api::FFIResult {
+        std::thread::sleep(Duration::from_secs(1));
+        waav_plugin_api::ffi_ok()
+    }
+
+    extern "C" fn fixture_init_err(_config: *const FFIConfig) -> waav_plugin_api::FFIResult {
+        waav_plugin_api::ffi_err("missing credentials")
+    }
+
+    extern "C" fn fixture_manifest_fast_a() -> PluginManifest {
+        PluginManifest::new("fast_a", "Fast A", "1.0.0")
+    }
+
+    extern "C" fn fixture_manifest_fast_b() -> PluginManifest {
+        PluginManifest::new("fast_b", "Fast B", "2.1.0")
+    }
+
+    extern "C" fn fixture_manifest_slow() -> PluginManifest {
+        PluginManifest::new("slow", "Slow", "0.3.0")
+    }
+
+    extern "C" fn fixture_manifest_broken() -> PluginManifest {
+        PluginManifest::new("broken", "Broken", "1.0.0")
+    }
+
+    /// In-process plugin module with the given manifest and init functions
+    fn fixture_module(
+        manifest: extern "C" fn() -> PluginManifest,
+        init: extern "C" fn(*const FFIConfig) -> waav_plugin_api::FFIResult,
+    ) -> PluginModule_Ref {
+        use abi_stable::prefix_type::PrefixTypeTrait;
+
+        waav_plugin_api::PluginModule {
+            manifest,
+            init,
+            shutdown: mock_shutdown,
+            create_stt: abi_stable::std_types::ROption::RNone,
+            create_tts: abi_stable::std_types::ROption::RNone,
+            create_realtime: abi_stable::std_types::ROption::RNone,
+            abi_version: PLUGIN_API_VERSION,
+            provider_capabilities: abi_stable::std_types::ROption::RNone,
+            handle_http: abi_stable::std_types::ROption::RNone,
+        }
+        .leak_into_prefix()
+    }
+
+    /// Open fixture plugins by candidate name instead of from disk
+    fn open_fixture(path: &Path) -> Result<PluginModule_Ref, PluginLoadError> {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        match name {
+            "fast_a" => Ok(fixture_module(fixture_manifest_fast_a, fixture_init_ok)),
+            "fast_b" => Ok(fixture_module(fixture_manifest_fast_b, fixture_init_ok)),
+            "slow" => Ok(fixture_module(fixture_manifest_slow, fixture_init_slow)),
+            "broken" => Ok(fixture_module(fixture_manifest_broken, fixture_init_err)),
+            // A second library exporting an already loaded plugin ID
+            "fast_a_copy" => Ok(fixture_module(fixture_manifest_fast_a, fixture_init_ok)),
+            _ => Err(PluginLoadError::LibraryError(format!(
+                "no fixture named {name}"
+            ))),
+        }
+    }
+
+    fn candidates(names: &[&str]) -> Vec<PluginCandidate> {
+        names
+            .iter()
+            .map(|name| PluginCandidate {
+                path: PathBuf::from(format!("{name}.so")),
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_slow_plugin_times_out_without_blocking_others() {
+        let mut loader = DynamicPluginLoader::new()
+            .with_init_timeout(Duration::from_millis(200))
+            .with_max_parallel_loads(2);
+        let registry = PluginRegistry::new();
+
+        let started = Instant::now();
+        let reports = loader
+            .load_candidates(
+                candidates(&["slow", "fast_a", "broken", "fast_b", "fast_a_copy"]),
+                &registry,
+                open_fixture,
+            )
+            .await
+            .unwrap();
+        // The slow plugin's init is still sleeping, but loading moved on
+        assert!(started.elapsed() < Duration::from_millis(900));
+
+        let statuses: Vec<_> = reports.iter().map(|r| r.status.clone()).collect();
+        assert_eq!(statuses[0], PluginLoadStatus::TimedOut);
+        // The later library with the same ID wins by default
+        assert_eq!(
+            statuses[1],
+            PluginLoadStatus::Superseded("fast_a_copy.so".to_string())
+        );
+        assert!(
+            matches!(&statuses[2], PluginLoadStatus::Failed(e) if e.contains("missing credentials"))
+        );
+        assert_eq!(statuses[3], PluginLoadStatus::Loaded);
+        assert_eq!(statuses[4], PluginLoadStatus::Loaded);
+
+        assert_eq!(reports[0].version, None);
+        assert_eq!(reports[3].version.as_deref(), Some("2.1.0"));
+
+        let mut ids = loader.loaded_plugin_ids();
+        ids.sort();
+        assert_eq!(ids, vec!["fast_a", "fast_b"]);
+
+        let summary = format_summary(&reports);
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("plugin"));
+        assert!(lines[1].starts_with("slow") && lines[1].ends_with("timed out"));
+        assert!(lines[2].ends_with("superseded by fast_a_copy.so"));
+        assert!(lines[4].contains("2.1.0") && lines[4].ends_with("loaded"));
+    }
+
+    static CONCURRENT_INITS: std::sync::atomic::AtomicUsize =
+        std::sync::atomic::AtomicUsize::new(0);
+
+    /// Succeeds only if another init is running at the same time
+    extern "C" fn fixture_init_rendezvous(_config: *const FFIConfig) -> waav_plugin_api::FFIResult {
+        use std::sync::atomic::Ordering;
+
+        CONCURRENT_INITS.fetch_add(1, Ordering::SeqCst);
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while Instant::now() < deadline {
+            if CONCURRENT_INITS.load(Ordering::SeqCst) >= 2 {
+                return waav_plugin_api::ffi_ok();
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        waav_plugin_api::ffi_err("ran alone")
+    }
+
+    #[tokio::test]
+    async fn test_plugins_initialize_in_parallel() {
+        let mut loader = DynamicPluginLoader::new().with_max_parallel_loads(2);
+        let registry = PluginRegistry::new();
+
+        let reports = loader
+            .load_candidates(candidates(&["fast_a", "fast_b"]), &registry, |path| {
+                let module = if path == Path::new("fast_a.so") {
+                    fixture_module(fixture_manifest_fast_a, fixture_init_rendezvous)
+                } else {
+                    fixture_module(fixture_manifest_fast_b, fixture_init_rendezvous)
+                };
+                Ok(module)
+            })
+            .await
+            .unwrap();
+
+        for report in &reports {
+            assert_eq!(report.status, PluginLoadStatus::Loaded, "{}", report.name);
+        }
+    }
+
+    extern "C" fn fixture_manifest_resemble_old() -> PluginManifest {
+        PluginManifest::new("resemble-tts", "Resemble", "1.4.0")
+            .with_capability(PluginCapabilityType::TTS)
+    }
+
+    extern "C" fn fixture_manifest_resemble_new() -> PluginManifest {
+        PluginManifest::new("resemble-tts", "Resemble", "2.0.0")
+            .with_capability(PluginCapabilityType::TTS)
+    }
+
+    extern "C" fn fixture_manifest_resemble_unversioned() -> PluginManifest {
+        PluginManifest::new("resemble-tts", "Resemble", "nightly")
+            .with_capability(PluginCapabilityType::TTS)
+    }
+
+    extern "C" fn fixture_manifest_deepgram() -> PluginManifest {
+        PluginManifest::new("deepgram", "Deepgram (patched)", "1.0.0")
+            .with_capability(PluginCapabilityType::STT)
+    }
+
+    /// The newer resemble-tts build sits in the earlier directory, so
+    /// `last_dir_wins` and `highest_version` pick different libraries
+    fn resemble_candidates() -> Vec<PluginCandidate> {
+        [
+            "plugins/resemble",
+            "plugins-extra/resemble",
+            "plugins-nightly/resemble",
+        ]
+        .into_iter()
+        .map(|path| PluginCandidate {
+            path: PathBuf::from(format!("{path}.so")),
+            name: "resemble".to_string(),
+        })
+        .collect()
+    }
+
+    fn open_resemble(path: &Path) -> Result<PluginModule_Ref, PluginLoadError> {
+        let manifest = if path.starts_with("plugins") {
+            fixture_manifest_resemble_new
+        } else if path.starts_with("plugins-extra") {
+            fixture_manifest_resemble_old
+        } else {
+            fixture_manifest_resemble_unversioned
+        };
+        Ok(fixture_module(manifest, fixture_init_ok))
+    }
+
+    async fn load_resemble(
+        policy: PluginConflictPolicy,
+        candidates: Vec<PluginCandidate>,
+    ) -> (
+        Result<Vec<PluginLoadReport>, PluginLoadError>,
+        PluginRegistry,
+    ) {
+        let mut loader = DynamicPluginLoader::new().with_conflict_policy(policy);
+        let registry = PluginRegistry::new();
+        let reports = loader
+            .load_candidates(candidates, &registry, open_resemble)
+            .await;
+        (reports, registry)
+    }
+
+    #[tokio::test]
+    async fn test_conflict_policy_last_dir_wins() {
+        let (reports, registry) =
+            load_resemble(PluginConflictPolicy::LastDirWins, resemble_candidates()).await;
+        let reports = reports.unwrap();
+
+        let winner = "plugins-nightly/resemble.so".to_string();
+        assert_eq!(
+            reports[0].status,
+            PluginLoadStatus::Superseded(winner.clone())
+        );
+        assert_eq!(reports[1].status, PluginLoadStatus::Superseded(winner));
+        assert_eq!(reports[2].status, PluginLoadStatus::Loaded);
+
+        let plugins = registry.dynamic_plugins();
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].version, "nightly");
+    }
+
+    #[tokio::test]
+    async fn test_conflict_policy_highest_version() {
+        let (reports, registry) =
+            load_resemble(PluginConflictPolicy::HighestVersion, resemble_candidates()).await;
+        let reports = reports.unwrap();
+
+        // 2.0.0 beats 1.4.0 from a later directory and the unparseable
+        // "nightly" from the last one
+        let winner = "plugins/resemble.so".to_string();
+        assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+        assert_eq!(
+            reports[1].status,
+            PluginLoadStatus::Superseded(winner.clone())
+        );
+        assert_eq!(reports[2].status, PluginLoadStatus::Superseded(winner));
+
+        let plugins = registry.dynamic_plugins();
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].version, "2.0.0");
+
+        // Equal versions go to the later directory
+        let mut candidates = resemble_candidates();
+        candidates[1].path = PathBuf::from("plugins/resemble-copy.so");
+        candidates.truncate(2);
+        let (reports, _) = load_resemble(PluginConflictPolicy::HighestVersion, candidates).await;
+        let reports = reports.unwrap();
+        assert_eq!(
+            reports[0].status,
+            PluginLoadStatus::Superseded("plugins/resemble-copy.so".to_string())
+        );
+        assert_eq!(reports[1].status, PluginLoadStatus::Loaded);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_policy_error_refuses_to_load() {
+        let mut candidates = resemble_candidates();
+        candidates.extend(self::candidates(&["fast_a"]));
+        let mut loader =
+            DynamicPluginLoader::new().with_conflict_policy(PluginConflictPolicy::Error);
+        let registry = PluginRegistry::new();
+
+        let result = loader
+            .load_candidates(candidates, &registry, |path| {
+                if path == Path::new("fast_a.so") {
+                    open_fixture(path)
+                } else {
+                    open_resemble(path)
+                }
+            })
+            .await;
+        match result {
+            Err(PluginLoadError::ConflictingPlugins { id, paths }) => {
+                assert_eq!(id, "resemble-tts");
+                assert_eq!(
+                    paths,
+                    vec![
+                        "plugins/resemble.so",
+                        "plugins-extra/resemble.so",
+                        "plugins-nightly/resemble.so"
+                    ]
+                );
+            }
+            other => panic!("expected ConflictingPlugins, got {:?}", other.err()),
+        }
+
+        // Nothing is registered, not even the plugins without a conflict
+        assert!(registry.dynamic_plugins().is_empty());
+        assert!(loader.loaded_plugin_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plugin_shadowing_builtin_requires_allow_override() {
+        let open_deepgram =
+            |_: &Path| Ok(fixture_module(fixture_manifest_deepgram, fixture_init_ok));
+
+        let mut loader = DynamicPluginLoader::new();
+        let registry = PluginRegistry::new();
+        let reports = loader
+            .load_candidates(candidates(&["deepgram"]), &registry, open_deepgram)
+            .await
+            .unwrap();
+        assert!(matches!(
+            &reports[0].status,
+            PluginLoadStatus::Failed(e) if e.contains("shadows a built-in provider")
+        ));
+        assert!(registry.dynamic_plugins().is_empty());
+
+        let mut loader = DynamicPluginLoader::new().with_allow_override(true);
+        let reports = loader
+            .load_candidates(candidates(&["deepgram"]), &registry, open_deepgram)
+            .await
+            .unwrap();
+        assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+        assert_eq!(registry.dynamic_plugins()[0].id, "deepgram");
+    }
+
+    /// Writes a file to the storage directory passed to `init`
+    extern "C" fn fixture_init_storage(config: *const FFIConfig) -> waav_plugin_api::FFIResult {
+        let init = unsafe { waav_plugin_api::PluginInitConfig::from_ffi(config) };
+        let Some(storage) = init.storage() else {
+            return waav_plugin_api::ffi_err("no storage provisioned");
+        };
+        match storage.write("voices.json", b"[]") {
+            Ok(()) => waav_plugin_api::ffi_ok(),
+            Err(e) => waav_plugin_api::ffi_err(e.to_string()),
+        }
+    }
+
+    fn write_file(path: &Path, len: usize, age: Duration) {
+        std::fs::write(path, vec![0u8; len]).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - age)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_plugin_storage_quota_enforced() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut loader = DynamicPluginLoader::new()
+            .with_storage(PluginStorageManager::new(data_dir.path(), 1000));
+        let registry = PluginRegistry::new();
+
+        let reports = loader
+            .load_candidates(candidates(&["fast_a"]), &registry, |_: &Path| {
+                Ok(fixture_module(
+                    fixture_manifest_fast_a,
+                    fixture_init_storage,
+                ))
+            })
+            .await
+            .unwrap();
+        assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+
+        let plugin_dir = data_dir.path().join("fast_a");
+        assert!(plugin_dir.join("voices.json").is_file());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&plugin_dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        // Within the quota nothing is touched
+        write_file(&plugin_dir.join("old.bin"), 600, Duration::from_secs(3600));
+        loader.enforce_storage_quotas();
+        assert!(plugin_dir.join("old.bin").is_file());
+
+        // Over it, the least recently modified files go first
+        std::fs::create_dir(plugin_dir.join("models")).unwrap();
+        write_file(&plugin_dir.join("models/new.bin"), 600, Duration::ZERO);
+        loader.enforce_storage_quotas();
+        assert!(!plugin_dir.join("old.bin").exists());
+        assert!(plugin_dir.join("models/new.bin").is_file());
+        assert!(plugin_dir.join("voices.json").is_file());
+    }
+
+    #[tokio::test]
+    async fn test_storage_of_removed_plugin_is_cleaned_up() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let removed = data_dir.path().join("removed-plugin");
+        std::fs::create_dir(&removed).unwrap();
+        std::fs::write(removed.join("cache.bin"), b"stale").unwrap();
+        let storage = || PluginStorageManager::new(data_dir.path(), 1000);
+        let registry = PluginRegistry::new();
+
+        // A failed plugin might be the owner, so nothing is removed
+        let mut loader = DynamicPluginLoader::new().with_storage(storage());
+        loader
+            .load_candidates(candidates(&["fast_a", "broken"]), &registry, open_fixture)
+            .await
+            .unwrap();
+        assert!(removed.join("cache.bin").is_file());
+        assert!(data_dir.path().join("broken").is_dir());
+
+        let mut loader = DynamicPluginLoader::new().with_storage(storage());
+        loader
+            .load_candidates(
+                candidates(&["fast_a"]),
+                &PluginRegistry::new(),
+                open_fixture,
+            )
+            .await
+            .unwrap();
+        assert!(!removed.exists());
+        assert!(!data_dir.path().join("broken").exists());
+        assert!(data_dir.path().join("fast_a").is_dir());
+    }
+
+    #[test]
+    fn test_discover_in_path_order() {
+        let loader = DynamicPluginLoader::new();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        let file = |dir: &Path, name: &str| {
+            let path = dir.join(format!(
+                "{}waav_plugin_{name}{}",
+                std::env::consts::DLL_PREFIX,
+                std::env::consts::DLL_SUFFIX
+            ));
+            std::fs::write(&path, b"").unwrap();
+        };
+        for name in ["zeta", "alpha", "mid"] {
+            file(temp_dir.path(), name);
+        }
+        file(&nested, "beta");
+
+        let names: Vec<_> = loader
+            .discover(temp_dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|candidate| candidate.name)
+            .collect();
+        assert_eq!(names, vec!["alpha", "mid", "zeta", "beta"]);
     }
 
     #[test]
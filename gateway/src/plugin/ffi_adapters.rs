@@ -17,15 +17,33 @@
 //! Callbacks are stored using `TypeErasedCallback` which preserves the original type's
 //! drop function. When the adapter is dropped, each callback is freed using its correct
 //! type, preventing heap corruption from size/alignment mismatches.
+//!
+//! Registering a callback again replaces the stored value for that slot. The old value
+//! is freed only after the plugin's `set_*_callback` function has returned; plugins
+//! that keep callbacks in a `CallbackRegistry` wait there until no invocation still
+//! uses the old `user_data`.
+//!
+//! # STT Recovery
+//!
+//! An STT plugin that loses its upstream connection reports a recoverable
+//! [`ErrorCode`] through its error callback. The STT adapter then buffers the
+//! session's audio, calls `disconnect` and `connect` up to three times with
+//! backoff, calls the plugin's optional `notify_recovered`, and sends the
+//! buffered audio. Only when every attempt fails does the session see an error.
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tracing::{info, warn};
 use waav_plugin_api::{
-    ErrorCallbackFn, FFISTTResult, FFIAudioData, FFITranscriptResult, FFIRealtimeAudio,
+    ErrorCallbackFn, ErrorCode, FFISTTResult, FFIAudioData, FFITranscriptResult, FFIRealtimeAudio,
     RealtimeAudioCallbackFn, RealtimeProvider, RealtimeTranscriptCallbackFn,
-    STTProvider, STTResultCallbackFn, TTSAudioCallbackFn, TTSProvider,
-    CompleteCallbackFn,
+    STTProvider, STTResultCallbackFn, TTSAudioCallbackFn, TTSProvider, TTSTimingCallbackFn,
+    CompleteCallbackFn, ToolCallCallbackFn,
 };
 
 // =============================================================================
@@ -90,27 +108,36 @@ unsafe impl Sync for TypeErasedCallback {}
 /// This replaces the broken `CallbackPointers` struct that incorrectly freed
 /// memory using `Box<()>`. This new implementation preserves type information
 /// through stored drop functions, ensuring correct memory deallocation.
+///
+/// Each callback slot holds one value; storing into a slot again hands the
+/// previous value back so the caller can free it once the plugin has
+/// switched over to the new `user_data`.
 struct CallbackStorage {
-    /// Type-erased callbacks that will be properly freed on drop
-    callbacks: Vec<TypeErasedCallback>,
+    /// Type-erased callbacks by slot, properly freed on drop
+    callbacks: HashMap<&'static str, TypeErasedCallback>,
 }
 
 impl CallbackStorage {
     fn new() -> Self {
         Self {
-            callbacks: Vec::new(),
+            callbacks: HashMap::new(),
         }
     }
 
-    /// Store a callback and return its raw pointer for FFI use.
+    /// Store a callback for `slot` and return its raw pointer for FFI use.
     ///
     /// The callback will be properly freed when this `CallbackStorage` is dropped,
     /// using the correct type information to ensure proper memory deallocation.
-    fn store<T: 'static>(&mut self, value: T) -> *mut () {
+    /// The value previously stored for `slot` is returned instead of dropped:
+    /// keep it until the plugin's `set_*_callback` call has returned.
+    fn store<T: 'static>(
+        &mut self,
+        slot: &'static str,
+        value: T,
+    ) -> (*mut (), Option<TypeErasedCallback>) {
         let callback = TypeErasedCallback::new(value);
         let ptr = callback.as_ptr();
-        self.callbacks.push(callback);
-        ptr
+        (ptr, self.callbacks.insert(slot, callback))
     }
 
     /// Clear all stored callbacks, freeing their memory properly.
@@ -123,39 +150,211 @@ impl CallbackStorage {
 use crate::core::realtime::{
     BaseRealtime, ConnectionState as RealtimeConnectionState, RealtimeAudioData, RealtimeConfig,
     RealtimeError, RealtimeResult, TranscriptResult, TranscriptRole,
-    TranscriptCallback, AudioOutputCallback, RealtimeErrorCallback,
+    TranscriptCallback, AudioOutputCallback, RealtimeErrorCallback, FunctionCallRequest,
     FunctionCallCallback, SpeechEventCallback, ResponseDoneCallback, ReconnectionCallback,
 };
 use crate::core::stt::{BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback};
+use crate::metrics::global_metrics;
 use crate::core::tts::{
     AudioCallback, AudioData, BaseTTS, ConnectionState as TTSConnectionState, TTSConfig, TTSError,
-    TTSResult as TTSOpResult,
+    TTSResult as TTSOpResult, TTSTimingEvent,
 };
 
 // =============================================================================
 // STT Adapter
 // =============================================================================
 
-/// Adapter that wraps an FFI STT provider and implements BaseSTT.
-pub struct FFISTTAdapter {
+/// Reconnect attempts after a recoverable plugin error before it is reported
+const STT_RECOVERY_MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first reconnect attempt, doubled after each failed attempt
+const STT_RECOVERY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Audio buffered while a plugin reconnects (10 seconds of 16kHz 16-bit mono).
+/// The oldest chunks are dropped beyond it.
+const STT_RECOVERY_MAX_BUFFER_BYTES: usize = 320_000;
+
+/// Audio held back while an STT plugin reconnects after a recoverable error.
+#[derive(Default)]
+struct STTRecoveryBuffer {
+    /// A reconnect is in progress; audio is buffered instead of sent
+    recovering: bool,
+    /// Buffered audio, oldest first
+    chunks: VecDeque<Bytes>,
+    /// Total length of `chunks`
+    bytes: usize,
+}
+
+impl STTRecoveryBuffer {
+    /// Buffer a chunk, dropping the oldest audio beyond the limit.
+    fn push(&mut self, chunk: Bytes) {
+        self.bytes += chunk.len();
+        self.chunks.push_back(chunk);
+        while self.bytes > STT_RECOVERY_MAX_BUFFER_BYTES {
+            let Some(dropped) = self.chunks.pop_front() else {
+                break;
+            };
+            self.bytes -= dropped.len();
+        }
+    }
+
+    /// Put chunks that could not be sent back in front of the buffer.
+    fn requeue(&mut self, chunks: impl DoubleEndedIterator<Item = Bytes>) {
+        for chunk in chunks.rev() {
+            self.bytes += chunk.len();
+            self.chunks.push_front(chunk);
+        }
+    }
+
+    /// Take all buffered audio.
+    fn take(&mut self) -> VecDeque<Bytes> {
+        self.bytes = 0;
+        std::mem::take(&mut self.chunks)
+    }
+
+    /// Stop recovering and drop the buffered audio.
+    fn reset(&mut self) {
+        self.recovering = false;
+        self.take();
+    }
+}
+
+/// Provider and callbacks of an STT adapter, shared with its recovery task.
+///
+/// `provider` is declared before `callback_storage`, so the plugin's handle
+/// is dropped before the callbacks it may still invoke are freed, whichever
+/// owner lets go last.
+struct FFISTTShared {
     /// The underlying FFI provider
     provider: Mutex<STTProvider>,
+    /// Type-safe callback storage with proper cleanup on drop
+    callback_storage: Mutex<CallbackStorage>,
+}
+
+impl FFISTTShared {
+    /// Reconnect the plugin and send the audio buffered during the outage.
+    ///
+    /// Audio arriving meanwhile is buffered behind it, so chunks reach the
+    /// plugin in order. Recovery ends once the buffer is empty.
+    fn reconnect(&self, recovery: &Mutex<STTRecoveryBuffer>) -> Result<(), String> {
+        let mut provider = self.provider.lock().unwrap();
+        // The connection is already lost; failing to close it changes nothing
+        let _ = provider.disconnect();
+        provider.connect().into_result().map_err(|e| e.to_string())?;
+
+        let buffered_len = recovery.lock().unwrap().bytes;
+        provider
+            .notify_recovered(buffered_len)
+            .into_result()
+            .map_err(|e| e.to_string())?;
+
+        loop {
+            let mut chunks = {
+                let mut buffer = recovery.lock().unwrap();
+                if buffer.chunks.is_empty() {
+                    buffer.recovering = false;
+                    return Ok(());
+                }
+                buffer.take()
+            };
+            while let Some(chunk) = chunks.pop_front() {
+                if let abi_stable::std_types::RResult::RErr(e) = provider.send_audio(&chunk) {
+                    chunks.push_front(chunk);
+                    recovery.lock().unwrap().requeue(chunks.into_iter());
+                    return Err(e.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// `user_data` of an STT plugin's error callback.
+struct STTErrorContext {
+    /// The session's error callback, for fatal errors
+    callback: STTErrorCallback,
+    /// Provider to reconnect on recoverable errors
+    shared: Weak<FFISTTShared>,
+    recovery: Arc<Mutex<STTRecoveryBuffer>>,
+    /// Runtime the recovery task runs on; plugins may report from their own threads
+    runtime: tokio::runtime::Handle,
+}
+
+/// Reconnect an STT plugin after a recoverable error, with bounded retries.
+///
+/// Reports a connection error to the session once every attempt failed. Ends
+/// early when the adapter is dropped or disconnected meanwhile.
+async fn recover_stt_provider(
+    shared: Weak<FFISTTShared>,
+    recovery: Arc<Mutex<STTRecoveryBuffer>>,
+    callback: STTErrorCallback,
+    reason: String,
+) {
+    warn!(%reason, "STT plugin lost its connection, reconnecting");
+    let mut backoff = STT_RECOVERY_BACKOFF;
+    let mut last_error = reason;
+
+    for attempt in 1..=STT_RECOVERY_MAX_ATTEMPTS {
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+        if !recovery.lock().unwrap().recovering {
+            return;
+        }
+        let result = shared.reconnect(&recovery);
+        global_metrics().inc_counter(
+            "waav_plugin_stt_recoveries_total",
+            "Reconnects of dynamic STT plugins after recoverable errors",
+            &[("result", if result.is_ok() { "ok" } else { "error" })],
+        );
+        match result {
+            Ok(()) => {
+                info!(attempt, "STT plugin reconnected");
+                return;
+            }
+            Err(e) => {
+                warn!(attempt, error = %e, "STT plugin reconnect failed");
+                last_error = e;
+            }
+        }
+    }
+
+    recovery.lock().unwrap().reset();
+    callback(STTError::ConnectionFailed(format!(
+        "Reconnecting failed after {STT_RECOVERY_MAX_ATTEMPTS} attempts: {last_error}"
+    )))
+    .await;
+}
+
+/// Adapter that wraps an FFI STT provider and implements BaseSTT.
+///
+/// Errors the plugin reports with a recoverable [`ErrorCode`] do not reach
+/// the session: the adapter buffers audio, reconnects the plugin in the
+/// background and sends the buffered audio once it is back.
+pub struct FFISTTAdapter {
+    /// Provider and callbacks, shared with the recovery task
+    shared: Arc<FFISTTShared>,
+    /// Audio held back while the plugin reconnects
+    recovery: Arc<Mutex<STTRecoveryBuffer>>,
     /// Stored config for get_config
     config: Mutex<Option<STTConfig>>,
     /// Current connection state
     connected: Mutex<bool>,
-    /// Type-safe callback storage with proper cleanup on drop
-    callback_storage: Mutex<CallbackStorage>,
 }
 
 impl FFISTTAdapter {
     /// Create a new STT adapter wrapping the given FFI provider.
     pub fn new(provider: STTProvider) -> Self {
         Self {
-            provider: Mutex::new(provider),
+            shared: Arc::new(FFISTTShared {
+                provider: Mutex::new(provider),
+                callback_storage: Mutex::new(CallbackStorage::new()),
+            }),
+            recovery: Arc::new(Mutex::new(STTRecoveryBuffer::default())),
             config: Mutex::new(None),
             connected: Mutex::new(false),
-            callback_storage: Mutex::new(CallbackStorage::new()),
         }
     }
 }
@@ -173,7 +372,7 @@ impl BaseSTT for FFISTTAdapter {
 
     async fn connect(&mut self) -> Result<(), STTError> {
         let result = {
-            let mut provider = self.provider.lock().unwrap();
+            let mut provider = self.shared.provider.lock().unwrap();
             provider.connect()
         };
 
@@ -189,8 +388,11 @@ impl BaseSTT for FFISTTAdapter {
     }
 
     async fn disconnect(&mut self) -> Result<(), STTError> {
+        // A reconnect in progress must not bring the provider back
+        self.recovery.lock().unwrap().reset();
+
         let result = {
-            let mut provider = self.provider.lock().unwrap();
+            let mut provider = self.shared.provider.lock().unwrap();
             provider.disconnect()
         };
 
@@ -206,19 +408,33 @@ impl BaseSTT for FFISTTAdapter {
     }
 
     fn is_ready(&self) -> bool {
-        let provider = self.provider.lock().unwrap();
+        let provider = self.shared.provider.lock().unwrap();
         provider.is_ready()
     }
 
     async fn send_audio(&mut self, audio_data: Bytes) -> Result<(), STTError> {
+        {
+            let mut buffer = self.recovery.lock().unwrap();
+            if buffer.recovering {
+                buffer.push(audio_data);
+                return Ok(());
+            }
+        }
+
         let result = {
-            let mut provider = self.provider.lock().unwrap();
+            let mut provider = self.shared.provider.lock().unwrap();
             provider.send_audio(&audio_data)
         };
 
         match result {
             abi_stable::std_types::RResult::ROk(()) => Ok(()),
             abi_stable::std_types::RResult::RErr(e) => {
+                // The plugin reported a recoverable error while taking this chunk
+                let mut buffer = self.recovery.lock().unwrap();
+                if buffer.recovering {
+                    buffer.push(audio_data);
+                    return Ok(());
+                }
                 Err(STTError::AudioProcessingError(e.to_string()))
             }
         }
@@ -226,7 +442,12 @@ impl BaseSTT for FFISTTAdapter {
 
     async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
         // Store callback with type-safe cleanup
-        let user_data = self.callback_storage.lock().unwrap().store(callback);
+        let (user_data, replaced) = self
+            .shared
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("result", callback);
 
         extern "C" fn stt_result_callback(result: *const FFISTTResult, user_data: *mut ()) {
             if result.is_null() || user_data.is_null() {
@@ -240,6 +461,13 @@ impl BaseSTT for FFISTTAdapter {
                     is_final: ffi_result.is_final,
                     is_speech_final: ffi_result.is_speech_final,
                     confidence: ffi_result.confidence,
+                    redactions: Vec::new(),
+                    start: None,
+                    end: None,
+                    words: Vec::new(),
+                    timing: None,
+                    turn_id: None,
+                    detected_language: None,
                 };
 
                 let callback = &*(user_data as *const STTResultCallback);
@@ -252,17 +480,30 @@ impl BaseSTT for FFISTTAdapter {
             func: stt_result_callback,
         };
 
-        let mut provider = self.provider.lock().unwrap();
+        let mut provider = self.shared.provider.lock().unwrap();
         // Get the vtable function and call it with handle reference
         let set_callback = provider.vtable.set_result_callback;
         set_callback(&mut provider.handle, callback_fn, user_data);
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
 
         Ok(())
     }
 
     async fn on_error(&mut self, callback: STTErrorCallback) -> Result<(), STTError> {
+        let context = STTErrorContext {
+            callback,
+            shared: Arc::downgrade(&self.shared),
+            recovery: self.recovery.clone(),
+            runtime: tokio::runtime::Handle::current(),
+        };
         // Store callback with type-safe cleanup
-        let user_data = self.callback_storage.lock().unwrap().store(callback);
+        let (user_data, replaced) = self
+            .shared
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("error", context);
 
         extern "C" fn stt_error_callback(
             error_code: u32,
@@ -275,19 +516,36 @@ impl BaseSTT for FFISTTAdapter {
 
             unsafe {
                 let msg = &*message;
-                let error = match error_code {
-                    1 => STTError::ConnectionFailed(msg.to_string()),
-                    2 => STTError::AuthenticationFailed(msg.to_string()),
-                    3 => STTError::ConfigurationError(msg.to_string()),
-                    4 => STTError::ProviderError(msg.to_string()),
-                    5 => STTError::NetworkError(msg.to_string()),
-                    6 => STTError::AudioProcessingError(msg.to_string()),
+                let context = &*(user_data as *const STTErrorContext);
+
+                if ErrorCode::from_u32(error_code).is_recoverable() {
+                    // Only the first report of an outage starts a reconnect
+                    let already_recovering =
+                        std::mem::replace(&mut context.recovery.lock().unwrap().recovering, true);
+                    if !already_recovering {
+                        context.runtime.spawn(recover_stt_provider(
+                            context.shared.clone(),
+                            context.recovery.clone(),
+                            context.callback.clone(),
+                            msg.to_string(),
+                        ));
+                    }
+                    return;
+                }
+
+                let error = match ErrorCode::from_u32(error_code) {
+                    ErrorCode::AuthenticationFailed => {
+                        STTError::AuthenticationFailed(msg.to_string())
+                    }
+                    ErrorCode::ConfigurationError => STTError::ConfigurationError(msg.to_string()),
+                    ErrorCode::AudioProcessingError | ErrorCode::InvalidInput => {
+                        STTError::AudioProcessingError(msg.to_string())
+                    }
                     _ => STTError::ProviderError(msg.to_string()),
                 };
 
-                let callback = &*(user_data as *const STTErrorCallback);
-                let future = callback(error);
-                tokio::spawn(future);
+                let future = (context.callback)(error);
+                context.runtime.spawn(future);
             }
         }
 
@@ -295,9 +553,11 @@ impl BaseSTT for FFISTTAdapter {
             func: stt_error_callback,
         };
 
-        let mut provider = self.provider.lock().unwrap();
+        let mut provider = self.shared.provider.lock().unwrap();
         let set_callback = provider.vtable.set_error_callback;
         set_callback(&mut provider.handle, callback_fn, user_data);
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
 
         Ok(())
     }
@@ -445,7 +705,11 @@ impl BaseTTS for FFITTSAdapter {
 
     fn on_audio(&mut self, callback: Arc<dyn AudioCallback>) -> TTSOpResult<()> {
         // Store callback with type-safe cleanup
-        let user_data = self.callback_storage.lock().unwrap().store(callback);
+        let (user_data, replaced) = self
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("tts", callback);
 
         extern "C" fn tts_audio_callback(audio: *const FFIAudioData, user_data: *mut ()) {
             if audio.is_null() || user_data.is_null() {
@@ -502,6 +766,29 @@ impl BaseTTS for FFITTSAdapter {
             }
         }
 
+        extern "C" fn tts_timing_callback(
+            events: *const abi_stable::std_types::RString,
+            user_data: *mut (),
+        ) {
+            if events.is_null() || user_data.is_null() {
+                return;
+            }
+
+            unsafe {
+                let events = match serde_json::from_str::<Vec<TTSTimingEvent>>((*events).as_str()) {
+                    Ok(events) => events,
+                    Err(e) => {
+                        warn!("Ignoring invalid TTS timing from plugin: {}", e);
+                        return;
+                    }
+                };
+
+                let callback = &*(user_data as *const Arc<dyn AudioCallback>);
+                let future = callback.on_timing(events);
+                tokio::spawn(future);
+            }
+        }
+
         let audio_callback_fn = TTSAudioCallbackFn {
             func: tts_audio_callback,
         };
@@ -521,6 +808,14 @@ impl BaseTTS for FFITTSAdapter {
         set_audio(&mut provider.handle, audio_callback_fn, user_data);
         set_error(&mut provider.handle, error_callback_fn, user_data);
         set_complete(&mut provider.handle, complete_callback_fn, user_data);
+        provider.set_timing_callback(
+            TTSTimingCallbackFn {
+                func: tts_timing_callback,
+            },
+            user_data,
+        );
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
 
         Ok(())
     }
@@ -547,7 +842,35 @@ unsafe impl Sync for FFITTSAdapter {}
 // Realtime Adapter
 // =============================================================================
 
+/// Sender feeding values from FFI callbacks to an async callback.
+type CallbackSender<T> = tokio::sync::mpsc::UnboundedSender<T>;
+
+/// Deliver values from plugin callbacks to `callback` in the order they arrive.
+///
+/// Plugins may call back from their own threads, outside the Tokio runtime,
+/// so the FFI trampolines only push onto an unbounded channel. A task on the
+/// current runtime awaits `callback` for each value in turn, which keeps audio
+/// chunks and transcripts ordered. The task ends once the sender, owned by the
+/// adapter's callback storage, is dropped.
+fn callback_queue<T: Send + 'static>(
+    callback: Arc<dyn Fn(T) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>,
+) -> RealtimeResult<CallbackSender<T>> {
+    let runtime = tokio::runtime::Handle::try_current().map_err(|_| {
+        RealtimeError::ProviderError("Realtime callbacks require a Tokio runtime".into())
+    })?;
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    runtime.spawn(async move {
+        while let Some(value) = rx.recv().await {
+            callback(value).await;
+        }
+    });
+    Ok(tx)
+}
+
 /// Adapter that wraps an FFI Realtime provider and implements BaseRealtime.
+///
+/// Callbacks registered on the adapter are delivered through
+/// [`callback_queue`], so plugins may invoke them from any thread.
 pub struct FFIRealtimeAdapter {
     provider: Mutex<RealtimeProvider>,
     config: Mutex<Option<RealtimeConfig>>,
@@ -692,8 +1015,13 @@ impl BaseRealtime for FFIRealtimeAdapter {
     }
 
     fn on_transcript(&mut self, callback: TranscriptCallback) -> RealtimeResult<()> {
-        // Store callback with type-safe cleanup
-        let user_data = self.callback_storage.lock().unwrap().store(callback);
+        // Store the queue sender with type-safe cleanup
+        let sender = callback_queue(callback)?;
+        let (user_data, replaced) = self
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("transcript", sender);
 
         extern "C" fn realtime_transcript_callback(
             result: *const FFITranscriptResult,
@@ -718,9 +1046,8 @@ impl BaseRealtime for FFIRealtimeAdapter {
                     item_id: None,
                 };
 
-                let callback = &*(user_data as *const TranscriptCallback);
-                let future = callback(rust_result);
-                tokio::spawn(future);
+                let sender = &*(user_data as *const CallbackSender<TranscriptResult>);
+                let _ = sender.send(rust_result);
             }
         }
 
@@ -731,13 +1058,20 @@ impl BaseRealtime for FFIRealtimeAdapter {
         let mut provider = self.provider.lock().unwrap();
         let set_callback = provider.vtable.set_transcript_callback;
         set_callback(&mut provider.handle, callback_fn, user_data);
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
 
         Ok(())
     }
 
     fn on_audio(&mut self, callback: AudioOutputCallback) -> RealtimeResult<()> {
-        // Store callback with type-safe cleanup
-        let user_data = self.callback_storage.lock().unwrap().store(callback);
+        // Store the queue sender with type-safe cleanup
+        let sender = callback_queue(callback)?;
+        let (user_data, replaced) = self
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("audio", sender);
 
         extern "C" fn realtime_audio_callback(audio: *const FFIRealtimeAudio, user_data: *mut ()) {
             if audio.is_null() || user_data.is_null() {
@@ -753,9 +1087,8 @@ impl BaseRealtime for FFIRealtimeAdapter {
                     response_id: None,
                 };
 
-                let callback = &*(user_data as *const AudioOutputCallback);
-                let future = callback(rust_audio);
-                tokio::spawn(future);
+                let sender = &*(user_data as *const CallbackSender<RealtimeAudioData>);
+                let _ = sender.send(rust_audio);
             }
         }
 
@@ -766,13 +1099,20 @@ impl BaseRealtime for FFIRealtimeAdapter {
         let mut provider = self.provider.lock().unwrap();
         let set_callback = provider.vtable.set_audio_callback;
         set_callback(&mut provider.handle, callback_fn, user_data);
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
 
         Ok(())
     }
 
     fn on_error(&mut self, callback: RealtimeErrorCallback) -> RealtimeResult<()> {
-        // Store callback with type-safe cleanup
-        let user_data = self.callback_storage.lock().unwrap().store(callback);
+        // Store the queue sender with type-safe cleanup
+        let sender = callback_queue(callback)?;
+        let (user_data, replaced) = self
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("error", sender);
 
         extern "C" fn realtime_error_callback(
             error_code: u32,
@@ -792,9 +1132,8 @@ impl BaseRealtime for FFIRealtimeAdapter {
                     _ => RealtimeError::ProviderError(msg.to_string()),
                 };
 
-                let callback = &*(user_data as *const RealtimeErrorCallback);
-                let future = callback(error);
-                tokio::spawn(future);
+                let sender = &*(user_data as *const CallbackSender<RealtimeError>);
+                let _ = sender.send(error);
             }
         }
 
@@ -805,12 +1144,54 @@ impl BaseRealtime for FFIRealtimeAdapter {
         let mut provider = self.provider.lock().unwrap();
         let set_callback = provider.vtable.set_error_callback;
         set_callback(&mut provider.handle, callback_fn, user_data);
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
 
         Ok(())
     }
 
-    fn on_function_call(&mut self, _callback: FunctionCallCallback) -> RealtimeResult<()> {
-        // FFI plugins don't support function calls yet
+    fn on_function_call(&mut self, callback: FunctionCallCallback) -> RealtimeResult<()> {
+        // Store the queue sender with type-safe cleanup
+        let sender = callback_queue(callback)?;
+        let (user_data, replaced) = self
+            .callback_storage
+            .lock()
+            .unwrap()
+            .store("tool_call", sender);
+
+        extern "C" fn realtime_tool_call_callback(
+            call_json: *const abi_stable::std_types::RString,
+            user_data: *mut (),
+        ) {
+            if call_json.is_null() || user_data.is_null() {
+                return;
+            }
+
+            unsafe {
+                let json = &*call_json;
+                let request: FunctionCallRequest = match serde_json::from_str(json.as_str()) {
+                    Ok(request) => request,
+                    Err(e) => {
+                        tracing::warn!("Ignoring malformed tool call from FFI plugin: {}", e);
+                        return;
+                    }
+                };
+
+                let sender = &*(user_data as *const CallbackSender<FunctionCallRequest>);
+                let _ = sender.send(request);
+            }
+        }
+
+        let callback_fn = ToolCallCallbackFn {
+            func: realtime_tool_call_callback,
+        };
+
+        let mut provider = self.provider.lock().unwrap();
+        let set_callback = provider.vtable.set_tool_call_callback;
+        set_callback(&mut provider.handle, callback_fn, user_data);
+        // The plugin no longer uses the previous user_data once set returns
+        drop(replaced);
+
         Ok(())
     }
 
@@ -834,11 +1215,25 @@ impl BaseRealtime for FFIRealtimeAdapter {
         Ok(())
     }
 
-    async fn submit_function_result(&mut self, _call_id: &str, _result: &str) -> RealtimeResult<()> {
-        // FFI plugins don't support function results yet
-        Err(RealtimeError::ProviderError(
-            "Function results not supported by FFI plugins".into(),
-        ))
+    async fn submit_function_result(&mut self, call_id: &str, result: &str) -> RealtimeResult<()> {
+        let response: abi_stable::std_types::RString = serde_json::json!({
+            "call_id": call_id,
+            "result": result,
+        })
+        .to_string()
+        .into();
+
+        let result = {
+            let mut provider = self.provider.lock().unwrap();
+            provider.send_tool_response(&response)
+        };
+
+        match result {
+            abi_stable::std_types::RResult::ROk(()) => Ok(()),
+            abi_stable::std_types::RResult::RErr(e) => {
+                Err(RealtimeError::ProviderError(e.to_string()))
+            }
+        }
     }
 
     fn get_provider_info(&self) -> serde_json::Value {
@@ -870,4 +1265,108 @@ mod tests {
         assert_send_sync::<FFITTSAdapter>();
         assert_send_sync::<FFIRealtimeAdapter>();
     }
+
+    #[test]
+    fn test_callback_storage_hands_back_replaced_value() {
+        use waav_plugin_api::CallbackRegistry;
+
+        extern "C" fn noop_complete(_user_data: *mut ()) {}
+
+        let mut storage = CallbackStorage::new();
+        let registry = CallbackRegistry::<CompleteCallbackFn>::new();
+        let callback = CompleteCallbackFn {
+            func: noop_complete,
+        };
+        let first = Arc::new(1u32);
+        let second = Arc::new(2u32);
+
+        let (user_data, replaced) = storage.store("complete", first.clone());
+        assert!(replaced.is_none());
+        registry.set(callback, user_data);
+        let (_, other) = storage.store("error", second.clone());
+        assert!(other.is_none());
+
+        // The first value stays alive until the plugin has switched over
+        let (user_data, replaced) = storage.store("complete", second.clone());
+        assert_eq!(Arc::strong_count(&first), 2);
+        registry.set(callback, user_data);
+        drop(replaced);
+        assert_eq!(Arc::strong_count(&first), 1);
+        assert_eq!(
+            registry.invoke(|_, user_data| unsafe { **(user_data as *const Arc<u32>) }),
+            Some(2)
+        );
+
+        drop(storage);
+        assert_eq!(Arc::strong_count(&second), 1);
+    }
+
+    fn recording_callback(
+        tx: tokio::sync::mpsc::UnboundedSender<u32>,
+    ) -> Arc<dyn Fn(u32) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync> {
+        Arc::new(move |value| {
+            let tx = tx.clone();
+            Box::pin(async move {
+                let _ = tx.send(value);
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_callback_queue_preserves_order_from_plugin_threads() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let sender = callback_queue(recording_callback(tx)).unwrap();
+
+        // Plugin threads are not Tokio worker threads
+        std::thread::spawn(move || {
+            for value in 0..100 {
+                sender.send(value).unwrap();
+            }
+        })
+        .join()
+        .unwrap();
+
+        for value in 0..100 {
+            assert_eq!(rx.recv().await, Some(value));
+        }
+        // The queue task ends once the sender is gone
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[test]
+    fn test_callback_queue_requires_runtime() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        assert!(matches!(
+            callback_queue(recording_callback(tx)),
+            Err(RealtimeError::ProviderError(_))
+        ));
+    }
+
+    #[test]
+    fn test_stt_recovery_buffer_keeps_order_within_limit() {
+        let mut buffer = STTRecoveryBuffer::default();
+        let chunk = |byte: u8| Bytes::from(vec![byte; STT_RECOVERY_MAX_BUFFER_BYTES / 4]);
+
+        for byte in 0..6 {
+            buffer.push(chunk(byte));
+        }
+        // The two oldest chunks were dropped to stay within the limit
+        assert_eq!(buffer.bytes, STT_RECOVERY_MAX_BUFFER_BYTES);
+        let mut chunks = buffer.take();
+        assert_eq!(chunks.iter().map(|c| c[0]).collect::<Vec<_>>(), [2, 3, 4, 5]);
+        assert_eq!(buffer.bytes, 0);
+
+        // Unsent chunks go back in front of audio buffered meanwhile
+        buffer.push(chunk(6));
+        chunks.pop_front();
+        buffer.requeue(chunks.into_iter());
+        let order: Vec<u8> = buffer.take().iter().map(|c| c[0]).collect();
+        assert_eq!(order, [3, 4, 5, 6]);
+
+        buffer.recovering = true;
+        buffer.push(chunk(7));
+        buffer.reset();
+        assert!(!buffer.recovering);
+        assert!(buffer.chunks.is_empty());
+    }
 }
@@ -177,10 +177,10 @@ mod tests {
 
     #[test]
     fn test_macro_registered_provider() {
-        use crate::plugin::global_registry;
+        use crate::plugin::PluginRegistry;
 
         // The macro should have registered the provider
-        let registry = global_registry();
+        let registry = PluginRegistry::new_isolated();
         assert!(
             registry.has_stt_provider("test-macro-stt"),
             "Macro-registered provider should be in registry"
@@ -4,7 +4,9 @@
 //! including version information, capabilities, and configuration requirements.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::utils::did_you_mean::suggest;
 
 /// Plugin manifest containing metadata about a plugin
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -128,6 +130,32 @@ pub struct ProviderMetadata {
     #[serde(default)]
     pub supported_models: Vec<String>,
 
+    /// Other names of supported models, mapped to their canonical name
+    #[serde(default)]
+    pub model_aliases: BTreeMap<String, String>,
+
+    /// Pricing table keys of models priced under another key
+    ///
+    /// Maps a model to its `"provider:model"` key in [`crate::config::pricing`].
+    /// Models without an entry are priced under `"{name}:{model}"`.
+    #[serde(default)]
+    pub pricing_keys: BTreeMap<String, String>,
+
+    /// Accepted audio sample rates in Hz (empty means any)
+    #[serde(default)]
+    pub supported_sample_rates: Vec<u32>,
+
+    /// Accepted audio encodings or formats (empty means any)
+    #[serde(default)]
+    pub supported_encodings: Vec<String>,
+
+    /// Whether configs must carry an API key
+    ///
+    /// Checked before creating runtime-registered providers; built-in
+    /// providers have their own validation rules.
+    #[serde(default)]
+    pub requires_api_key: bool,
+
     /// Provider features (e.g., "streaming", "word-timestamps", "speaker-diarization")
     #[serde(default)]
     pub features: HashSet<String>,
@@ -225,6 +253,79 @@ impl ProviderMetadata {
         self.supported_models = models.into_iter().map(Into::into).collect();
         self
     }
+
+    /// Accept `alias` as another name of `model`
+    pub fn with_model_alias(mut self, alias: impl Into<String>, model: impl Into<String>) -> Self {
+        self.model_aliases.insert(alias.into(), model.into());
+        self
+    }
+
+    /// Canonical name of `model` among the supported models
+    ///
+    /// Matches names and aliases case-insensitively. Returns None when
+    /// the model is not listed.
+    pub fn resolve_model(&self, model: &str) -> Option<&str> {
+        self.supported_models
+            .iter()
+            .find(|supported| supported.eq_ignore_ascii_case(model))
+            .or_else(|| {
+                self.model_aliases
+                    .iter()
+                    .find(|(alias, _)| alias.eq_ignore_ascii_case(model))
+                    .map(|(_, canonical)| canonical)
+            })
+            .map(String::as_str)
+    }
+
+    /// The supported model closest to an unlisted `model`, if it looks like a typo
+    pub fn suggest_model(&self, model: &str) -> Option<&str> {
+        let model = model.to_lowercase();
+        let candidates: Vec<String> = self
+            .supported_models
+            .iter()
+            .chain(self.model_aliases.keys())
+            .map(|name| name.to_lowercase())
+            .collect();
+        let known: Vec<&str> = candidates.iter().map(String::as_str).collect();
+        let closest = suggest(&model, &known)?;
+        self.resolve_model(closest)
+    }
+
+    /// Price `model` under `key` of the pricing tables
+    pub fn with_pricing_key(mut self, model: impl Into<String>, key: impl Into<String>) -> Self {
+        self.pricing_keys.insert(model.into(), key.into());
+        self
+    }
+
+    /// Pricing table key of `model`
+    pub fn pricing_key(&self, model: &str) -> String {
+        self.pricing_keys
+            .get(model)
+            .cloned()
+            .unwrap_or_else(|| format!("{}:{}", self.name, model))
+            .to_lowercase()
+    }
+
+    /// Set accepted sample rates
+    pub fn with_sample_rates(mut self, sample_rates: impl IntoIterator<Item = u32>) -> Self {
+        self.supported_sample_rates = sample_rates.into_iter().collect();
+        self
+    }
+
+    /// Set accepted encodings
+    pub fn with_encodings(
+        mut self,
+        encodings: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.supported_encodings = encodings.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether configs must carry an API key
+    pub fn with_api_key_required(mut self, required: bool) -> Self {
+        self.requires_api_key = required;
+        self
+    }
 }
 
 /// Provider type enumeration
@@ -272,6 +373,26 @@ mod tests {
         assert_eq!(metadata.supported_languages.len(), 3);
     }
 
+    #[test]
+    fn test_model_lookup() {
+        let metadata = ProviderMetadata::stt("deepgram", "Deepgram Nova-3")
+            .with_models(["nova-3", "nova-2", "nova-2-phonecall"])
+            .with_model_alias("nova-3-general", "nova-3");
+
+        assert_eq!(metadata.resolve_model("nova-2"), Some("nova-2"));
+        assert_eq!(metadata.resolve_model("Nova-3"), Some("nova-3"));
+        assert_eq!(metadata.resolve_model("NOVA-3-GENERAL"), Some("nova-3"));
+        assert_eq!(metadata.resolve_model("nova3"), None);
+
+        assert_eq!(metadata.suggest_model("nova3"), Some("nova-3"));
+        assert_eq!(
+            metadata.suggest_model("Nova-2-Phonecal"),
+            Some("nova-2-phonecall")
+        );
+        assert_eq!(metadata.suggest_model("nova-3-genral"), Some("nova-3"));
+        assert_eq!(metadata.suggest_model("whisper"), None);
+    }
+
     #[test]
     fn test_plugin_manifest() {
         let manifest = PluginManifest::new("my-plugin", "My Plugin", "1.0.0")
@@ -48,10 +48,15 @@
 //!
 //! ## Using the Registry
 //!
+//! Handlers create providers through the registry their `AppState` owns;
+//! [`global_registry`] is the default it is built with.
+//!
 //! ```ignore
-//! use waav_gateway::plugin::global_registry;
+//! let provider = app_state.plugin_registry.create_stt("deepgram", config)?;
 //!
-//! let provider = global_registry().create_stt("deepgram", config)?;
+//! // Tests register mocks into a registry of their own
+//! let registry = Arc::new(PluginRegistry::new_isolated());
+//! let app_state = AppState::with_plugin_registry(config, registry).await;
 //! ```
 
 pub mod builtin;
@@ -62,6 +67,7 @@ pub mod lifecycle;
 #[macro_use]
 pub mod macros;
 pub mod metadata;
+pub mod pricing;
 pub mod registry;
 
 // Dynamic plugin loading (feature-gated)
@@ -69,6 +75,8 @@ pub mod registry;
 pub mod dynamic_loader;
 #[cfg(feature = "plugins-dynamic")]
 pub mod ffi_adapters;
+#[cfg(feature = "plugins-dynamic")]
+pub mod storage;
 
 // Re-exports for convenience
 pub use capabilities::{
@@ -78,13 +86,19 @@ pub use capabilities::{
 pub use isolation::{PluginError, call_plugin_safely};
 pub use lifecycle::{PluginHealth, PluginLifecycle, PluginState};
 pub use metadata::{PluginManifest, ProviderMetadata};
-pub use registry::{PluginRegistry, global_registry};
+pub use pricing::{ModelPrice, PricingMismatch, ProviderPrices, check_registry_pricing};
+pub use registry::{DynamicPluginInfo, PluginRegistry, global_registry};
 
 // Dynamic loader re-exports (feature-gated)
 #[cfg(feature = "plugins-dynamic")]
-pub use dynamic_loader::{DynamicPluginLoader, LoadedPlugin, PluginCandidate, PluginLoadError};
+pub use dynamic_loader::{
+    DEFAULT_INIT_TIMEOUT, DEFAULT_MAX_PARALLEL_LOADS, DynamicPluginLoader, LoadedPlugin,
+    PluginCandidate, PluginLoadError, PluginLoadReport, PluginLoadStatus,
+};
 #[cfg(feature = "plugins-dynamic")]
 pub use ffi_adapters::{FFIRealtimeAdapter, FFISTTAdapter, FFITTSAdapter};
+#[cfg(feature = "plugins-dynamic")]
+pub use storage::PluginStorageManager;
 
 /// Prelude module for convenient imports
 ///
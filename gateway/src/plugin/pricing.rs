@@ -0,0 +1,390 @@
+//! Provider metadata checked against the pricing tables
+//!
+//! Models are listed in [`ProviderMetadata`] and priced in
+//! [`crate::config::pricing`], which are maintained separately. The checker
+//! reports listed models without a price, and prices of models or providers
+//! no metadata lists, so cost estimates do not silently go missing.
+//! [`priced_models`] gives the prices of a provider's models for discovery.
+//!
+//! A pricing key (`"provider:model"`) belongs to a provider when its
+//! metadata references the key, or when the key's provider part is the
+//! provider's name or one of its aliases (`_` and `-` are treated alike, so
+//! `aws_polly` prices belong to `aws-polly`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use super::metadata::{ProviderMetadata, ProviderType};
+use super::registry::PluginRegistry;
+use crate::config::{ModelPricing, stt_pricing_table, tts_pricing_table};
+
+/// Pricing table: prices by `"provider:model"` key
+pub type PricingTable = HashMap<&'static str, ModelPricing>;
+
+/// Price of one of a provider's models
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ModelPrice {
+    /// Model name
+    #[cfg_attr(feature = "openapi", schema(example = "whisper-large-v3-turbo"))]
+    pub model: String,
+    /// USD per hour of audio, for models priced by audio time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(example = 0.04))]
+    pub price_per_hour: Option<f64>,
+    /// USD per 1000 characters of text, for models priced by text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_1k_chars: Option<f64>,
+    /// Pricing notes (e.g., minimum billing)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl ModelPrice {
+    fn new(model: impl Into<String>, pricing: &ModelPricing) -> Self {
+        let per_character = pricing.is_per_character();
+        Self {
+            model: model.into(),
+            price_per_hour: (!per_character).then(|| pricing.to_per_hour()),
+            price_per_1k_chars: per_character.then(|| pricing.to_per_1k_chars()),
+            notes: pricing.notes.map(str::to_string),
+        }
+    }
+}
+
+/// Per-model prices of the registered providers, by provider name
+///
+/// Providers without any known price are left out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProviderPrices {
+    /// Prices of STT models
+    pub stt: BTreeMap<String, Vec<ModelPrice>>,
+    /// Prices of TTS models
+    pub tts: BTreeMap<String, Vec<ModelPrice>>,
+}
+
+/// A disagreement between provider metadata and a pricing table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PricingMismatch {
+    /// A model listed in a provider's metadata has no price
+    UnpricedModel {
+        provider_type: ProviderType,
+        provider: String,
+        model: String,
+        key: String,
+    },
+    /// A price belongs to a provider whose metadata does not list the model
+    UnlistedModel {
+        provider_type: ProviderType,
+        provider: String,
+        key: String,
+    },
+    /// A price belongs to no registered provider
+    UnknownProvider {
+        provider_type: ProviderType,
+        key: String,
+    },
+}
+
+impl fmt::Display for PricingMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnpricedModel {
+                provider_type,
+                provider,
+                model,
+                key,
+            } => write!(
+                f,
+                "{provider_type} provider '{provider}' lists model '{model}' but the pricing table has no '{key}'; its costs will not be estimated"
+            ),
+            Self::UnlistedModel {
+                provider_type,
+                provider,
+                key,
+            } => write!(
+                f,
+                "{provider_type} pricing table has '{key}' but provider '{provider}' does not list the model"
+            ),
+            Self::UnknownProvider { provider_type, key } => write!(
+                f,
+                "{provider_type} pricing table has '{key}' but no such provider is registered"
+            ),
+        }
+    }
+}
+
+fn same_provider(a: &str, b: &str) -> bool {
+    a.replace('_', "-")
+        .eq_ignore_ascii_case(&b.replace('_', "-"))
+}
+
+/// Whether pricing `key` belongs to the provider of `metadata`
+fn owns_key(metadata: &ProviderMetadata, key: &str) -> bool {
+    if metadata
+        .pricing_keys
+        .values()
+        .any(|reference| reference.eq_ignore_ascii_case(key))
+    {
+        return true;
+    }
+    let Some((provider, _)) = key.split_once(':') else {
+        return false;
+    };
+    std::iter::once(&metadata.name)
+        .chain(&metadata.aliases)
+        .any(|name| same_provider(name, provider))
+}
+
+/// Model of `metadata` priced under `key`, which it owns
+fn model_of(metadata: &ProviderMetadata, key: &str) -> String {
+    metadata
+        .pricing_keys
+        .iter()
+        .find(|(_, reference)| reference.eq_ignore_ascii_case(key))
+        .map(|(model, _)| model.clone())
+        .unwrap_or_else(|| {
+            key.split_once(':')
+                .map_or(key, |(_, model)| model)
+                .to_string()
+        })
+}
+
+/// Check the metadata of `providers` against their pricing `table`
+///
+/// Providers that list no models accept any model, so only their prices'
+/// provider is checked.
+///
+/// # Returns
+/// * Every mismatch found, listed models first
+pub fn check_pricing_table(
+    provider_type: ProviderType,
+    providers: &[ProviderMetadata],
+    table: &PricingTable,
+) -> Vec<PricingMismatch> {
+    let mut mismatches = Vec::new();
+    for metadata in providers {
+        for model in &metadata.supported_models {
+            let key = metadata.pricing_key(model);
+            if !table.contains_key(key.as_str()) {
+                mismatches.push(PricingMismatch::UnpricedModel {
+                    provider_type,
+                    provider: metadata.name.clone(),
+                    model: model.clone(),
+                    key,
+                });
+            }
+        }
+    }
+
+    let mut keys: Vec<&str> = table.keys().copied().collect();
+    keys.sort_unstable();
+    for key in keys {
+        match providers.iter().find(|metadata| owns_key(metadata, key)) {
+            None => mismatches.push(PricingMismatch::UnknownProvider {
+                provider_type,
+                key: key.to_string(),
+            }),
+            Some(metadata)
+                if !metadata.supported_models.is_empty()
+                    && !metadata
+                        .supported_models
+                        .iter()
+                        .any(|model| metadata.pricing_key(model) == key) =>
+            {
+                mismatches.push(PricingMismatch::UnlistedModel {
+                    provider_type,
+                    provider: metadata.name.clone(),
+                    key: key.to_string(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    mismatches
+}
+
+/// Prices of the models `metadata` offers
+///
+/// Providers that list their models get the price of each listed model that
+/// has one; providers accepting any model get every price they own.
+pub fn priced_models(metadata: &ProviderMetadata, table: &PricingTable) -> Vec<ModelPrice> {
+    if !metadata.supported_models.is_empty() {
+        return metadata
+            .supported_models
+            .iter()
+            .filter_map(|model| {
+                let pricing = table.get(metadata.pricing_key(model).as_str())?;
+                Some(ModelPrice::new(model, pricing))
+            })
+            .collect();
+    }
+
+    let mut prices: Vec<ModelPrice> = table
+        .iter()
+        .filter(|(key, _)| owns_key(metadata, key))
+        .map(|(key, pricing)| ModelPrice::new(model_of(metadata, key), pricing))
+        .collect();
+    prices.sort_by(|a, b| a.model.cmp(&b.model));
+    prices
+}
+
+fn stt_metadata(registry: &PluginRegistry) -> Vec<ProviderMetadata> {
+    registry
+        .get_stt_provider_names()
+        .iter()
+        .filter_map(|name| registry.get_stt_metadata(name))
+        .collect()
+}
+
+fn tts_metadata(registry: &PluginRegistry) -> Vec<ProviderMetadata> {
+    registry
+        .get_tts_provider_names()
+        .iter()
+        .filter_map(|name| registry.get_tts_metadata(name))
+        .collect()
+}
+
+/// Check the registered STT and TTS providers against the pricing tables
+pub fn check_registry_pricing(registry: &PluginRegistry) -> Vec<PricingMismatch> {
+    let mut mismatches = check_pricing_table(
+        ProviderType::STT,
+        &stt_metadata(registry),
+        stt_pricing_table(),
+    );
+    mismatches.extend(check_pricing_table(
+        ProviderType::TTS,
+        &tts_metadata(registry),
+        tts_pricing_table(),
+    ));
+    mismatches
+}
+
+/// Per-model prices of the registered STT and TTS providers
+pub fn registry_prices(registry: &PluginRegistry) -> ProviderPrices {
+    let prices = |providers: Vec<ProviderMetadata>, table: &PricingTable| {
+        providers
+            .into_iter()
+            .filter_map(|metadata| {
+                let prices = priced_models(&metadata, table);
+                (!prices.is_empty()).then_some((metadata.name, prices))
+            })
+            .collect()
+    };
+    ProviderPrices {
+        stt: prices(stt_metadata(registry), stt_pricing_table()),
+        tts: prices(tts_metadata(registry), tts_pricing_table()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PricingUnit;
+    use crate::plugin::PluginRegistry;
+
+    fn fixture_table() -> PricingTable {
+        let mut table = HashMap::new();
+        table.insert("acme:fast", ModelPricing::new(0.5, PricingUnit::PerHour));
+        table.insert(
+            "acme:legacy",
+            ModelPricing::new(1.0, PricingUnit::PerMinute),
+        );
+        table.insert(
+            "open_voice:default",
+            ModelPricing::new(4.0, PricingUnit::Per1MChars),
+        );
+        table.insert("ghost:model", ModelPricing::new(1.0, PricingUnit::PerHour));
+        table
+    }
+
+    #[test]
+    fn test_mismatched_fixture_is_caught() {
+        let providers = vec![
+            // "accurate" is listed but not priced; "legacy" is priced but not listed
+            ProviderMetadata::stt("acme", "Acme STT").with_models(["fast", "accurate"]),
+            // Accepts any model; its prices are owned through the `_`/`-` spelling
+            ProviderMetadata::stt("open-voice", "Open Voice"),
+        ];
+        let mismatches = check_pricing_table(ProviderType::STT, &providers, &fixture_table());
+
+        assert_eq!(
+            mismatches,
+            vec![
+                PricingMismatch::UnpricedModel {
+                    provider_type: ProviderType::STT,
+                    provider: "acme".to_string(),
+                    model: "accurate".to_string(),
+                    key: "acme:accurate".to_string(),
+                },
+                PricingMismatch::UnlistedModel {
+                    provider_type: ProviderType::STT,
+                    provider: "acme".to_string(),
+                    key: "acme:legacy".to_string(),
+                },
+                PricingMismatch::UnknownProvider {
+                    provider_type: ProviderType::STT,
+                    key: "ghost:model".to_string(),
+                },
+            ]
+        );
+        assert!(mismatches[0].to_string().contains("acme:accurate"));
+    }
+
+    #[test]
+    fn test_pricing_key_references() {
+        let providers = vec![
+            ProviderMetadata::stt("acme", "Acme STT")
+                .with_models(["fast", "accurate", "legacy"])
+                .with_pricing_key("accurate", "acme:fast"),
+            ProviderMetadata::stt("open-voice", "Open Voice"),
+            ProviderMetadata::stt("spectre", "Spectre")
+                .with_models(["model-v2"])
+                .with_pricing_key("model-v2", "ghost:model"),
+        ];
+        assert!(check_pricing_table(ProviderType::STT, &providers, &fixture_table()).is_empty());
+    }
+
+    #[test]
+    fn test_priced_models() {
+        let table = fixture_table();
+        let acme = ProviderMetadata::stt("acme", "Acme STT").with_models(["fast", "accurate"]);
+        let prices = priced_models(&acme, &table);
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[0].model, "fast");
+        assert_eq!(prices[0].price_per_hour, Some(0.5));
+        assert_eq!(prices[0].price_per_1k_chars, None);
+
+        let open_voice = ProviderMetadata::tts("open-voice", "Open Voice");
+        let prices = priced_models(&open_voice, &table);
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[0].model, "default");
+        assert_eq!(prices[0].price_per_1k_chars, Some(0.004));
+        assert_eq!(prices[0].price_per_hour, None);
+    }
+
+    #[test]
+    fn test_builtin_providers_match_pricing() {
+        let registry = PluginRegistry::new_isolated();
+        let mismatches = check_registry_pricing(&registry);
+        assert!(
+            mismatches.is_empty(),
+            "{}",
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+
+        let prices = registry_prices(&registry);
+        assert!(
+            prices.stt["groq"]
+                .iter()
+                .any(|price| price.model == "distil-whisper-large-v3-en")
+        );
+        assert!(prices.tts.contains_key("aws-polly"));
+    }
+}
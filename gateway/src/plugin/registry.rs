@@ -10,6 +10,15 @@
 //! All providers (built-in and runtime-registered) are stored in the same maps
 //! for simplicity and consistent performance.
 //!
+//! # Ownership
+//!
+//! Each [`AppState`](crate::state::AppState) owns the registry its sessions
+//! create providers from. The binary uses the process-wide
+//! [`global_registry`], which also backs the free provider factories such as
+//! `create_stt_provider`; tests and embedders that need providers of their
+//! own use [`PluginRegistry::new_isolated`] and
+//! `AppState::with_plugin_registry`.
+//!
 //! # Usage
 //!
 //! ```ignore
@@ -20,22 +29,29 @@
 //! ```
 
 use dashmap::DashMap;
+use parking_lot::Mutex;
 use serde_json::Value;
 use std::any::TypeId;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 
 use super::capabilities::{
-    RealtimeCapability, STTCapability, TTSCapability, WSContext, WSError, WSResponse,
+    PluginHttpRequest, PluginHttpResponse, RealtimeCapability, STTCapability, TTSCapability,
+    WSContext, WSError, WSResponse,
 };
 use super::dispatch::{resolve_realtime_provider, resolve_stt_provider, resolve_tts_provider};
 use super::isolation::call_plugin_preserving_error;
 use super::lifecycle::PluginEntry;
 use super::metadata::ProviderMetadata;
+use crate::core::providers::headers::validate_custom_headers;
 use crate::core::realtime::{BaseRealtime, RealtimeConfig, RealtimeError, RealtimeResult};
 use crate::core::stt::{BaseSTT, STTConfig, STTError};
 use crate::core::tts::{BaseTTS, TTSConfig, TTSResult};
+use crate::core::validation::{
+    ConfigIssue, stt_config_issues, stt_validation_error, tts_config_issues, tts_validation_error,
+};
 
 /// Factory function type for STT providers
 pub type STTFactoryFn = Arc<dyn Fn(STTConfig) -> Result<Box<dyn BaseSTT>, STTError> + Send + Sync>;
@@ -63,6 +79,16 @@ pub type WSHandlerFn = Arc<
         + Sync,
 >;
 
+/// Handler function type for a plugin's `/plugins/{plugin_id}/...` routes
+///
+/// Called on a blocking thread. Returns the plugin's response, or an error
+/// message if the plugin failed to handle the request.
+pub type PluginHttpHandlerFn =
+    Arc<dyn Fn(PluginHttpRequest) -> Result<PluginHttpResponse, String> + Send + Sync>;
+
+/// Cleanup run once by [`PluginRegistry::shutdown`]
+pub type ShutdownHookFn = Box<dyn FnOnce() + Send>;
+
 /// Metadata function type for deferred metadata creation
 pub type MetadataFn = fn() -> ProviderMetadata;
 
@@ -75,6 +101,16 @@ pub type TTSFactoryPtr = fn(TTSConfig) -> TTSResult<Box<dyn BaseTTS>>;
 /// Factory function pointer type for Realtime providers (non-Arc version for PluginConstructor)
 pub type RealtimeFactoryPtr = fn(RealtimeConfig) -> RealtimeResult<Box<dyn BaseRealtime>>;
 
+/// ID and version of a dynamic plugin loaded at runtime
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DynamicPluginInfo {
+    /// Plugin ID from its manifest
+    pub id: String,
+    /// Plugin version from its manifest
+    pub version: String,
+}
+
 /// Plugin constructor for inventory-based registration
 ///
 /// Uses function pointers to defer non-const operations (metadata creation)
@@ -164,14 +200,20 @@ inventory::collect!(PluginConstructor);
 /// The registry maintains indexes of all registered plugins and provides
 /// methods to create provider instances by name.
 pub struct PluginRegistry {
-    /// STT provider factories indexed by provider ID
-    stt_factories: DashMap<String, (STTFactoryFn, ProviderMetadata)>,
+    /// STT provider factories indexed by provider ID and alias
+    ///
+    /// Aliases share the metadata of their provider.
+    stt_factories: DashMap<String, (STTFactoryFn, Arc<ProviderMetadata>)>,
 
-    /// TTS provider factories indexed by provider ID
-    tts_factories: DashMap<String, (TTSFactoryFn, ProviderMetadata)>,
+    /// TTS provider factories indexed by provider ID and alias
+    ///
+    /// Aliases share the metadata of their provider.
+    tts_factories: DashMap<String, (TTSFactoryFn, Arc<ProviderMetadata>)>,
 
-    /// Realtime provider factories indexed by provider ID
-    realtime_factories: DashMap<String, (RealtimeFactoryFn, ProviderMetadata)>,
+    /// Realtime provider factories indexed by provider ID and alias
+    ///
+    /// Aliases share the metadata of their provider.
+    realtime_factories: DashMap<String, (RealtimeFactoryFn, Arc<ProviderMetadata>)>,
 
     /// WebSocket message handlers indexed by message type
     ws_handlers: DashMap<String, Vec<WSHandlerFn>>,
@@ -181,6 +223,19 @@ pub struct PluginRegistry {
 
     /// Plugin entries for lifecycle management
     plugin_entries: DashMap<String, PluginEntry>,
+
+    /// Versions of the dynamic plugins loaded at runtime, by plugin ID
+    dynamic_plugins: DashMap<String, String>,
+
+    /// HTTP handlers of plugins serving routes, by plugin ID
+    http_handlers: DashMap<String, PluginHttpHandlerFn>,
+
+    /// Cleanup of the plugins whose code the registry calls into, in
+    /// registration order
+    shutdown_hooks: Mutex<Vec<ShutdownHookFn>>,
+
+    /// Set once [`Self::shutdown`] ran
+    shut_down: AtomicBool,
 }
 
 impl PluginRegistry {
@@ -193,9 +248,130 @@ impl PluginRegistry {
             ws_handlers: DashMap::new(),
             capability_index: DashMap::new(),
             plugin_entries: DashMap::new(),
+            dynamic_plugins: DashMap::new(),
+            http_handlers: DashMap::new(),
+            shutdown_hooks: Mutex::new(Vec::new()),
+            shut_down: AtomicBool::new(false),
         }
     }
 
+    /// Create a registry holding the built-in providers only
+    ///
+    /// Unlike [`global_registry`], every call returns a new registry, so
+    /// providers registered in one are never seen by another.
+    pub fn new_isolated() -> Self {
+        let registry = Self::new();
+        registry.register_builtins();
+        registry
+    }
+
+    /// Register every provider submitted with `inventory::submit!`
+    ///
+    /// These are the built-in providers and those of the `register_*_plugin!`
+    /// macros.
+    pub fn register_builtins(&self) {
+        for constructor in inventory::iter::<PluginConstructor> {
+            // Get metadata (deferred creation)
+            let metadata = constructor.metadata();
+
+            // Register STT factory if present
+            if let Some(factory) = constructor.create_stt {
+                let factory_arc: STTFactoryFn = Arc::new(factory);
+                self.register_stt(
+                    constructor.provider_id,
+                    factory_arc.clone(),
+                    metadata.clone(),
+                );
+
+                // Also register aliases
+                for alias in constructor.aliases {
+                    self.register_stt(alias, factory_arc.clone(), metadata.clone());
+                }
+            }
+
+            // Register TTS factory if present
+            if let Some(factory) = constructor.create_tts {
+                let factory_arc: TTSFactoryFn = Arc::new(factory);
+                self.register_tts(
+                    constructor.provider_id,
+                    factory_arc.clone(),
+                    metadata.clone(),
+                );
+
+                for alias in constructor.aliases {
+                    self.register_tts(alias, factory_arc.clone(), metadata.clone());
+                }
+            }
+
+            // Register Realtime factory if present
+            if let Some(factory) = constructor.create_realtime {
+                let factory_arc: RealtimeFactoryFn = Arc::new(factory);
+                self.register_realtime(
+                    constructor.provider_id,
+                    factory_arc.clone(),
+                    metadata.clone(),
+                );
+
+                for alias in constructor.aliases {
+                    self.register_realtime(alias, factory_arc.clone(), metadata.clone());
+                }
+            }
+        }
+    }
+
+    /// Run `hook` when the registry shuts down
+    ///
+    /// Used by the dynamic plugin loader to shut down the plugins whose
+    /// factories it registered. On a registry that already shut down, `hook`
+    /// runs right away.
+    pub fn on_shutdown(&self, hook: ShutdownHookFn) {
+        let mut hooks = self.shutdown_hooks.lock();
+        if self.is_shut_down() {
+            drop(hooks);
+            hook();
+            return;
+        }
+        hooks.push(hook);
+    }
+
+    /// Unregister every provider and handler, then run the shutdown hooks
+    ///
+    /// Factories are dropped before the plugins behind them are shut down,
+    /// so no provider is created from a plugin that already cleaned up.
+    /// Providers created earlier keep working until they are dropped; shared
+    /// libraries stay mapped until the process exits, since unloading Rust
+    /// code with live destructors and statics is not safe. Calling this more
+    /// than once has no further effect.
+    pub fn shutdown(&self) {
+        let hooks = {
+            let mut hooks = self.shutdown_hooks.lock();
+            if self.shut_down.swap(true, Ordering::AcqRel) {
+                return;
+            }
+            std::mem::take(&mut *hooks)
+        };
+
+        self.stt_factories.clear();
+        self.tts_factories.clear();
+        self.realtime_factories.clear();
+        self.ws_handlers.clear();
+        self.http_handlers.clear();
+        self.capability_index.clear();
+        self.plugin_entries.clear();
+        self.dynamic_plugins.clear();
+
+        // Plugins are shut down in the reverse order they were loaded in
+        for hook in hooks.into_iter().rev() {
+            hook();
+        }
+        tracing::debug!("Plugin registry shut down");
+    }
+
+    /// Whether [`Self::shutdown`] ran
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::Acquire)
+    }
+
     /// Register an STT provider factory
     pub fn register_stt(
         &self,
@@ -206,13 +382,16 @@ impl PluginRegistry {
         let id = provider_id.to_lowercase();
 
         // Register by primary name
+        let metadata = Arc::new(metadata);
         self.stt_factories
-            .insert(id.clone(), (factory.clone(), metadata.clone()));
+            .insert(id.clone(), (factory.clone(), Arc::clone(&metadata)));
 
         // Register by aliases
         for alias in &metadata.aliases {
-            self.stt_factories
-                .insert(alias.to_lowercase(), (factory.clone(), metadata.clone()));
+            self.stt_factories.insert(
+                alias.to_lowercase(),
+                (factory.clone(), Arc::clone(&metadata)),
+            );
         }
 
         // Update capability index
@@ -240,12 +419,15 @@ impl PluginRegistry {
     ) {
         let id = provider_id.to_lowercase();
 
+        let metadata = Arc::new(metadata);
         self.tts_factories
-            .insert(id.clone(), (factory.clone(), metadata.clone()));
+            .insert(id.clone(), (factory.clone(), Arc::clone(&metadata)));
 
         for alias in &metadata.aliases {
-            self.tts_factories
-                .insert(alias.to_lowercase(), (factory.clone(), metadata.clone()));
+            self.tts_factories.insert(
+                alias.to_lowercase(),
+                (factory.clone(), Arc::clone(&metadata)),
+            );
         }
 
         self.capability_index
@@ -271,12 +453,15 @@ impl PluginRegistry {
     ) {
         let id = provider_id.to_lowercase();
 
+        let metadata = Arc::new(metadata);
         self.realtime_factories
-            .insert(id.clone(), (factory.clone(), metadata.clone()));
+            .insert(id.clone(), (factory.clone(), Arc::clone(&metadata)));
 
         for alias in &metadata.aliases {
-            self.realtime_factories
-                .insert(alias.to_lowercase(), (factory.clone(), metadata.clone()));
+            self.realtime_factories.insert(
+                alias.to_lowercase(),
+                (factory.clone(), Arc::clone(&metadata)),
+            );
         }
 
         self.capability_index
@@ -299,12 +484,16 @@ impl PluginRegistry {
     /// Uses PHF for O(1) guaranteed lookup of built-in providers with automatic
     /// alias resolution. Falls back to DashMap for runtime-registered providers.
     /// The call is wrapped in panic isolation to prevent plugin panics from
-    /// crashing the gateway.
+    /// crashing the gateway. Custom headers and the config itself (see
+    /// [`crate::core::validation`]) are validated before the factory is
+    /// invoked.
     pub fn create_stt(
         &self,
         provider: &str,
         config: STTConfig,
     ) -> Result<Box<dyn BaseSTT>, STTError> {
+        validate_custom_headers(&config.custom_headers).map_err(STTError::ConfigurationError)?;
+
         // Use PHF for O(1) canonical name resolution (handles aliases + case insensitivity)
         // Falls back to lowercase for runtime-registered providers
         let id = resolve_stt_provider(provider)
@@ -320,8 +509,15 @@ impl PluginRegistry {
         })?;
 
         let factory = factory_entry.0.clone();
+        let issues = stt_config_issues(&id, &config, Some(factory_entry.1.as_ref()));
         drop(factory_entry); // Release lock before calling factory
 
+        // Rejected configs never reach the provider, so they are not
+        // recorded as plugin errors
+        if !issues.is_empty() {
+            return Err(stt_validation_error(&issues));
+        }
+
         // Call with panic isolation, preserving original error type
         let result = call_plugin_preserving_error(
             std::panic::AssertUnwindSafe(|| factory(config)),
@@ -343,7 +539,11 @@ impl PluginRegistry {
     ///
     /// Uses PHF for O(1) guaranteed lookup of built-in providers with automatic
     /// alias resolution. Falls back to DashMap for runtime-registered providers.
+    /// The config is validated before the factory is invoked.
     pub fn create_tts(&self, provider: &str, config: TTSConfig) -> TTSResult<Box<dyn BaseTTS>> {
+        validate_custom_headers(&config.custom_headers)
+            .map_err(crate::core::tts::TTSError::InvalidConfiguration)?;
+
         // Use PHF for O(1) canonical name resolution (handles aliases + case insensitivity)
         let id = resolve_tts_provider(provider)
             .map(|p| p.canonical_name().to_string())
@@ -358,8 +558,13 @@ impl PluginRegistry {
         })?;
 
         let factory = factory_entry.0.clone();
+        let issues = tts_config_issues(&id, &config, Some(factory_entry.1.as_ref()));
         drop(factory_entry);
 
+        if !issues.is_empty() {
+            return Err(tts_validation_error(&issues));
+        }
+
         let result = call_plugin_preserving_error(
             std::panic::AssertUnwindSafe(|| factory(config)),
             |panic_msg| {
@@ -447,21 +652,75 @@ impl PluginRegistry {
     pub fn get_stt_metadata(&self, provider: &str) -> Option<ProviderMetadata> {
         self.stt_factories
             .get(&provider.to_lowercase())
-            .map(|entry| entry.1.clone())
+            .map(|entry| entry.1.as_ref().clone())
     }
 
     /// Get provider metadata by name
     pub fn get_tts_metadata(&self, provider: &str) -> Option<ProviderMetadata> {
         self.tts_factories
             .get(&provider.to_lowercase())
-            .map(|entry| entry.1.clone())
+            .map(|entry| entry.1.as_ref().clone())
+    }
+
+    /// Check an STT config against the rules of `provider`
+    ///
+    /// Runs the same checks as [`Self::create_stt`] without creating the
+    /// provider. An unknown provider is reported as a `provider` issue.
+    pub fn validate_stt_config(
+        &self,
+        provider: &str,
+        config: &STTConfig,
+    ) -> Result<(), Vec<ConfigIssue>> {
+        let id = resolve_stt_provider(provider)
+            .map(|p| p.canonical_name().to_string())
+            .unwrap_or_else(|| provider.to_lowercase());
+
+        let issues = match self.stt_factories.get(&id) {
+            Some(entry) => stt_config_issues(&id, config, Some(entry.1.as_ref())),
+            None => vec![ConfigIssue::new(
+                "provider",
+                format!("unknown STT provider '{provider}'"),
+            )],
+        };
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Check a TTS config against the rules of `provider`
+    ///
+    /// Runs the same checks as [`Self::create_tts`] without creating the
+    /// provider. An unknown provider is reported as a `provider` issue.
+    pub fn validate_tts_config(
+        &self,
+        provider: &str,
+        config: &TTSConfig,
+    ) -> Result<(), Vec<ConfigIssue>> {
+        let id = resolve_tts_provider(provider)
+            .map(|p| p.canonical_name().to_string())
+            .unwrap_or_else(|| provider.to_lowercase());
+
+        let issues = match self.tts_factories.get(&id) {
+            Some(entry) => tts_config_issues(&id, config, Some(entry.1.as_ref())),
+            None => vec![ConfigIssue::new(
+                "provider",
+                format!("unknown TTS provider '{provider}'"),
+            )],
+        };
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
     }
 
     /// Get provider metadata by name
     pub fn get_realtime_metadata(&self, provider: &str) -> Option<ProviderMetadata> {
         self.realtime_factories
             .get(&provider.to_lowercase())
-            .map(|entry| entry.1.clone())
+            .map(|entry| entry.1.as_ref().clone())
     }
 
     /// Check if an STT provider is registered
@@ -512,6 +771,42 @@ impl PluginRegistry {
         self.get_realtime_provider_names().len()
     }
 
+    /// Record a dynamic plugin whose providers were registered
+    pub fn record_dynamic_plugin(&self, plugin_id: &str, version: &str) {
+        self.dynamic_plugins
+            .insert(plugin_id.to_string(), version.to_string());
+    }
+
+    /// Dynamic plugins loaded at runtime, sorted by ID
+    pub fn dynamic_plugins(&self) -> Vec<DynamicPluginInfo> {
+        let mut plugins: Vec<_> = self
+            .dynamic_plugins
+            .iter()
+            .map(|entry| DynamicPluginInfo {
+                id: entry.key().clone(),
+                version: entry.value().clone(),
+            })
+            .collect();
+        plugins.sort_by(|a, b| a.id.cmp(&b.id));
+        plugins
+    }
+
+    /// Register the handler for a plugin's `/plugins/{plugin_id}/...` routes
+    ///
+    /// Replaces any handler previously registered for the plugin.
+    pub fn register_http_handler(&self, plugin_id: &str, handler: PluginHttpHandlerFn) {
+        self.http_handlers.insert(plugin_id.to_string(), handler);
+
+        tracing::debug!(plugin_id = %plugin_id, "Registered plugin HTTP handler");
+    }
+
+    /// Get the HTTP handler of a plugin, if it serves routes
+    pub fn get_http_handler(&self, plugin_id: &str) -> Option<PluginHttpHandlerFn> {
+        self.http_handlers
+            .get(plugin_id)
+            .map(|handler| handler.clone())
+    }
+
     /// Register a WebSocket message handler
     ///
     /// Multiple handlers can be registered for the same message type.
@@ -576,7 +871,10 @@ impl PluginRegistry {
         plugin_id: &str,
         _config: serde_json::Value,
     ) -> Result<Box<dyn crate::dag::nodes::AudioProcessor>, String> {
-        Err(format!("Audio processor '{}' not found - audio processor plugins are not yet implemented", plugin_id))
+        Err(format!(
+            "Audio processor '{}' not found - audio processor plugins are not yet implemented",
+            plugin_id
+        ))
     }
 }
 
@@ -586,13 +884,26 @@ impl Default for PluginRegistry {
     }
 }
 
+impl std::fmt::Debug for PluginRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginRegistry")
+            .field("stt_providers", &self.stt_provider_count())
+            .field("tts_providers", &self.tts_provider_count())
+            .field("realtime_providers", &self.realtime_provider_count())
+            .field("shut_down", &self.is_shut_down())
+            .finish_non_exhaustive()
+    }
+}
+
 /// Global registry instance
-static GLOBAL_REGISTRY: OnceLock<PluginRegistry> = OnceLock::new();
+static GLOBAL_REGISTRY: OnceLock<Arc<PluginRegistry>> = OnceLock::new();
 
 /// Get the global plugin registry
 ///
 /// The registry is lazily initialized on first access and populated
-/// with all plugins registered via `inventory::submit!`.
+/// with all plugins registered via `inventory::submit!`. It is the registry
+/// of `AppState::new` and of the free provider factories; cloning the `Arc`
+/// shares it.
 ///
 /// # Example
 ///
@@ -601,58 +912,9 @@ static GLOBAL_REGISTRY: OnceLock<PluginRegistry> = OnceLock::new();
 ///
 /// let stt = global_registry().create_stt("deepgram", config)?;
 /// ```
-pub fn global_registry() -> &'static PluginRegistry {
+pub fn global_registry() -> &'static Arc<PluginRegistry> {
     GLOBAL_REGISTRY.get_or_init(|| {
-        let registry = PluginRegistry::new();
-
-        // Register all plugins discovered via inventory
-        for constructor in inventory::iter::<PluginConstructor> {
-            // Get metadata (deferred creation)
-            let metadata = constructor.metadata();
-
-            // Register STT factory if present
-            if let Some(factory) = constructor.create_stt {
-                let factory_arc: STTFactoryFn = Arc::new(factory);
-                registry.register_stt(
-                    constructor.provider_id,
-                    factory_arc.clone(),
-                    metadata.clone(),
-                );
-
-                // Also register aliases
-                for alias in constructor.aliases {
-                    registry.register_stt(alias, factory_arc.clone(), metadata.clone());
-                }
-            }
-
-            // Register TTS factory if present
-            if let Some(factory) = constructor.create_tts {
-                let factory_arc: TTSFactoryFn = Arc::new(factory);
-                registry.register_tts(
-                    constructor.provider_id,
-                    factory_arc.clone(),
-                    metadata.clone(),
-                );
-
-                for alias in constructor.aliases {
-                    registry.register_tts(alias, factory_arc.clone(), metadata.clone());
-                }
-            }
-
-            // Register Realtime factory if present
-            if let Some(factory) = constructor.create_realtime {
-                let factory_arc: RealtimeFactoryFn = Arc::new(factory);
-                registry.register_realtime(
-                    constructor.provider_id,
-                    factory_arc.clone(),
-                    metadata.clone(),
-                );
-
-                for alias in constructor.aliases {
-                    registry.register_realtime(alias, factory_arc.clone(), metadata.clone());
-                }
-            }
-        }
+        let registry = PluginRegistry::new_isolated();
 
         tracing::info!(
             stt_count = registry.stt_provider_count(),
@@ -661,7 +923,7 @@ pub fn global_registry() -> &'static PluginRegistry {
             "Plugin registry initialized"
         );
 
-        registry
+        Arc::new(registry)
     })
 }
 
@@ -755,6 +1017,92 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_registry_dynamic_plugins_sorted_by_id() {
+        let registry = PluginRegistry::new();
+        assert!(registry.dynamic_plugins().is_empty());
+
+        registry.record_dynamic_plugin("zeta-tts", "0.2.0");
+        registry.record_dynamic_plugin("alpha-stt", "1.0.0");
+        assert_eq!(
+            registry.dynamic_plugins(),
+            vec![
+                DynamicPluginInfo {
+                    id: "alpha-stt".to_string(),
+                    version: "1.0.0".to_string(),
+                },
+                DynamicPluginInfo {
+                    id: "zeta-tts".to_string(),
+                    version: "0.2.0".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registry_http_handlers() {
+        let registry = PluginRegistry::new();
+        assert!(registry.get_http_handler("test-stt").is_none());
+
+        registry.register_http_handler(
+            "test-stt",
+            Arc::new(|request: PluginHttpRequest| {
+                Ok(PluginHttpResponse {
+                    status: 200,
+                    headers: Vec::new(),
+                    body: request.path.into(),
+                })
+            }),
+        );
+
+        let handler = registry.get_http_handler("test-stt").unwrap();
+        let response = handler(PluginHttpRequest {
+            method: "GET".to_string(),
+            path: "/voices".to_string(),
+            query: String::new(),
+            headers: Vec::new(),
+            body: Default::default(),
+        })
+        .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.as_ref(), b"/voices");
+        assert!(registry.get_http_handler("other").is_none());
+    }
+
+    #[test]
+    fn test_registry_rejects_invalid_custom_headers() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let registry = PluginRegistry::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
+        let factory: STTFactoryFn = Arc::new(move |_| {
+            calls_clone.fetch_add(1, Ordering::SeqCst);
+            Err(STTError::ProviderError("factory called".to_string()))
+        });
+        registry.register_stt(
+            "test-headers",
+            factory,
+            ProviderMetadata::stt("test-headers", "Test Headers Provider"),
+        );
+
+        let mut config = STTConfig::default();
+        config
+            .custom_headers
+            .insert("X-Proxy".to_string(), "a\r\nX-Injected: 1".to_string());
+        let result = registry.create_stt("test-headers", config);
+        assert!(matches!(result, Err(STTError::ConfigurationError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let mut config = STTConfig::default();
+        config
+            .custom_headers
+            .insert("X-Proxy".to_string(), "token".to_string());
+        let result = registry.create_stt("test-headers", config);
+        assert!(matches!(result, Err(STTError::ProviderError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_registry_records_success_after_call() {
         let registry = PluginRegistry::new();
@@ -794,8 +1142,8 @@ mod tests {
 
     #[test]
     fn test_registry_phf_alias_resolution() {
-        // Use the global registry which has real providers
-        let registry = global_registry();
+        // An isolated registry has the real providers
+        let registry = PluginRegistry::new_isolated();
 
         // Test that aliases resolve to the same provider as canonical names
         // STT: "azure" should resolve to "microsoft-azure"
@@ -826,8 +1174,8 @@ mod tests {
 
     #[test]
     fn test_registry_records_success_on_success() {
-        // Use the global registry which has real providers
-        let registry = global_registry();
+        // An isolated registry has the real providers
+        let registry = PluginRegistry::new_isolated();
 
         // Get initial state
         let initial_call_count = registry
@@ -851,4 +1199,61 @@ mod tests {
             "Either call_count or error_count should have incremented"
         );
     }
+
+    #[test]
+    fn test_isolated_registries_do_not_share_providers() {
+        let first = PluginRegistry::new_isolated();
+        let second = PluginRegistry::new_isolated();
+        assert!(first.has_stt_provider("deepgram"));
+        assert_eq!(first.stt_provider_count(), second.stt_provider_count());
+
+        let factory: STTFactoryFn =
+            Arc::new(|_| Err(STTError::ConfigurationError("test".to_string())));
+        first.register_stt(
+            "isolated-stt",
+            factory,
+            ProviderMetadata::stt("isolated-stt", "Isolated STT"),
+        );
+        first.record_dynamic_plugin("isolated-stt", "1.0.0");
+
+        assert!(first.has_stt_provider("isolated-stt"));
+        assert!(!second.has_stt_provider("isolated-stt"));
+        assert!(!global_registry().has_stt_provider("isolated-stt"));
+        assert!(second.dynamic_plugins().is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_unregisters_before_running_hooks() {
+        let registry = Arc::new(PluginRegistry::new_isolated());
+        registry.record_dynamic_plugin("test-plugin", "0.1.0");
+
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let hook_registry = Arc::downgrade(&registry);
+        let hook_runs = runs.clone();
+        registry.on_shutdown(Box::new(move || {
+            // The plugin's providers are gone by the time it shuts down
+            let registry = hook_registry.upgrade().unwrap();
+            assert_eq!(registry.stt_provider_count(), 0);
+            hook_runs.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        registry.shutdown();
+        registry.shutdown();
+        assert!(registry.is_shut_down());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(registry.tts_provider_count(), 0);
+        assert!(registry.dynamic_plugins().is_empty());
+        assert!(
+            registry
+                .create_stt("deepgram", STTConfig::default())
+                .is_err()
+        );
+
+        // Hooks added after the shutdown run right away
+        let late_runs = runs.clone();
+        registry.on_shutdown(Box::new(move || {
+            late_runs.fetch_add(1, Ordering::SeqCst);
+        }));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
 }
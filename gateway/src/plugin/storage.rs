@@ -0,0 +1,206 @@
+//! Dynamic Plugin Storage
+//!
+//! Plugins that cache models or voice lists need somewhere to write that
+//! works on read-only container filesystems. When `plugins.data_dir` is set,
+//! the loader creates one private subdirectory per plugin ID in it and passes
+//! its path and the storage quota to the plugin's `init` function (see
+//! `waav_plugin_api::PluginInitConfig`).
+//!
+//! The quota is enforced by periodic checks: a plugin directory over its
+//! quota loses its least recently modified files until it fits again.
+//! Directories of plugins that are no longer installed are removed after
+//! plugins are loaded.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::task::JoinHandle;
+use waav_plugin_api::PluginInitConfig;
+
+/// Storage directories of dynamic plugins under `plugins.data_dir`
+#[derive(Debug, Clone)]
+pub struct PluginStorageManager {
+    /// Directory holding one subdirectory per plugin ID
+    data_dir: PathBuf,
+    /// Most bytes each plugin may keep
+    quota_bytes: u64,
+}
+
+impl PluginStorageManager {
+    /// Storage under `data_dir`, limited to `quota_bytes` per plugin
+    pub fn new(data_dir: impl Into<PathBuf>, quota_bytes: u64) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            quota_bytes,
+        }
+    }
+
+    /// Directory holding the plugins' storage directories
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Most bytes each plugin may keep
+    pub fn quota_bytes(&self) -> u64 {
+        self.quota_bytes
+    }
+
+    /// Storage directory of a plugin
+    ///
+    /// # Errors
+    /// Returns an error if the plugin ID cannot be used as a directory name
+    pub fn plugin_dir(&self, plugin_id: &str) -> io::Result<PathBuf> {
+        let valid = !plugin_id.is_empty()
+            && plugin_id != "."
+            && plugin_id != ".."
+            && plugin_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("plugin ID '{plugin_id}' cannot be used as a storage directory name"),
+            ));
+        }
+        Ok(self.data_dir.join(plugin_id))
+    }
+
+    /// Create a plugin's storage directory, readable only by the gateway's
+    /// user, and return the config passed to the plugin's `init`
+    pub fn provision(&self, plugin_id: &str) -> io::Result<PluginInitConfig> {
+        let dir = self.plugin_dir(plugin_id)?;
+        std::fs::create_dir_all(&dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(PluginInitConfig {
+            storage_dir: Some(dir.display().to_string()),
+            storage_quota_bytes: Some(self.quota_bytes),
+        })
+    }
+
+    /// Delete a plugin's least recently modified files while its storage is
+    /// over the quota
+    ///
+    /// # Returns
+    /// The number of bytes deleted
+    pub fn enforce_quota(&self, plugin_id: &str) -> io::Result<u64> {
+        let dir = self.plugin_dir(plugin_id)?;
+        let mut files = Vec::new();
+        match collect_files(&dir, &mut files) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            result => result?,
+        }
+
+        let mut used: u64 = files.iter().map(|(_, len, _)| len).sum();
+        if used <= self.quota_bytes {
+            return Ok(0);
+        }
+        let over_quota = used;
+
+        files.sort_by_key(|(_, _, modified)| *modified);
+        let mut freed = 0;
+        for (path, len, _) in files {
+            if used <= self.quota_bytes {
+                break;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    used -= len;
+                    freed += len;
+                }
+                // The plugin may have deleted or replaced it meanwhile
+                Err(e) if e.kind() == io::ErrorKind::NotFound => used -= len,
+                Err(e) => return Err(e),
+            }
+        }
+
+        tracing::warn!(
+            plugin_id = %plugin_id,
+            used_bytes = over_quota,
+            quota_bytes = self.quota_bytes,
+            freed_bytes = freed,
+            "Plugin storage over quota, deleted least recently modified files"
+        );
+        Ok(freed)
+    }
+
+    /// Remove the storage directories of plugins not in `plugin_ids`
+    ///
+    /// # Returns
+    /// The plugin IDs whose storage was removed
+    pub fn remove_unused(&self, plugin_ids: &HashSet<&str>) -> io::Result<Vec<String>> {
+        let entries = match std::fs::read_dir(&self.data_dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            result => result?,
+        };
+
+        let mut removed = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if plugin_ids.contains(name.as_str()) {
+                continue;
+            }
+            std::fs::remove_dir_all(entry.path())?;
+            tracing::info!(plugin_id = %name, "Removed storage of uninstalled plugin");
+            removed.push(name);
+        }
+        removed.sort();
+        Ok(removed)
+    }
+}
+
+/// Check the quota of every plugin in `plugin_ids` every `interval`
+pub fn spawn_quota_checks(
+    storage: Arc<PluginStorageManager>,
+    plugin_ids: Vec<String>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let storage = storage.clone();
+            let plugin_ids = plugin_ids.clone();
+            let _ = tokio::task::spawn_blocking(move || {
+                for plugin_id in &plugin_ids {
+                    if let Err(e) = storage.enforce_quota(plugin_id) {
+                        tracing::warn!(
+                            plugin_id = %plugin_id,
+                            error = %e,
+                            "Failed to check plugin storage quota"
+                        );
+                    }
+                }
+            })
+            .await;
+        }
+    })
+}
+
+/// Every file below `dir` with its size and modification time
+fn collect_files(dir: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.path(), meta.len(), modified));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//! # Recording Upload
+//!
+//! With [`RecordingUploadConfig`](crate::config::RecordingUploadConfig) set
+//! and a recording bucket configured, the caller audio every voice session
+//! receives over its WebSocket is recorded as `input.wav` next to the
+//! session's recording (`{prefix}/{auth_id}/{stream_id}/input.wav`):
+//!
+//! 1. the audio is cut into parts of `part_size_bytes`, and each part is
+//!    written to `spill_dir` before it is uploaded as a part of an S3
+//!    multipart upload
+//! 2. a part that fails is retried with exponential backoff; once its
+//!    attempts run out, the rest of the session only spills to disk
+//! 3. when the session ends, the WAV header is written into the first part,
+//!    the parts S3 does not have yet are uploaded and the upload is
+//!    completed; its spill folder is removed once S3 has the file
+//! 4. a background reconciler retries uploads that did not complete, on
+//!    startup and every `reconcile_interval_secs`, so a recording survives
+//!    an S3 outage or a gateway restart
+//!
+//! Only 16-bit PCM audio is recorded. Pending uploads are reported by
+//! `GET /readyz` and as `waav_recording_upload*` metrics.
+
+mod spill;
+mod uploader;
+
+pub use uploader::{
+    RecordingUpload, RecordingUploadError, RecordingUploadStatus, RecordingUploader,
+};
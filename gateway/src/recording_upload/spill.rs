@@ -0,0 +1,129 @@
+//! Parts of unfinished uploads kept on local disk
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// File describing an upload in its spill folder
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// State of an upload, saved next to its parts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct UploadManifest {
+    /// Object key the recording is uploaded to
+    pub key: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// ID of the multipart upload, once created
+    pub upload_id: Option<String>,
+    /// Every part spilled so far, in order
+    pub parts: Vec<SpilledPart>,
+    /// Whether the recording has ended, so no parts are added
+    pub finished: bool,
+}
+
+impl UploadManifest {
+    pub fn new(key: String, sample_rate: u32, channels: u16) -> Self {
+        Self {
+            key,
+            sample_rate,
+            channels,
+            upload_id: None,
+            parts: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Number of parts S3 does not have yet
+    pub fn pending_parts(&self) -> usize {
+        self.parts
+            .iter()
+            .filter(|part| part.content_id.is_none())
+            .count()
+    }
+
+    /// Size of the recording in bytes, WAV header included
+    pub fn total_len(&self) -> usize {
+        self.parts.iter().map(|part| part.len).sum()
+    }
+}
+
+/// A part written to disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct SpilledPart {
+    pub len: usize,
+    /// Content ID S3 returned for the part, once uploaded
+    pub content_id: Option<String>,
+}
+
+/// Folder holding one subfolder per unfinished upload
+#[derive(Debug, Clone)]
+pub(super) struct SpillDir {
+    root: PathBuf,
+}
+
+impl SpillDir {
+    /// Use `root`, creating it if needed
+    pub fn create(root: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    fn upload_dir(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    fn part_path(&self, name: &str, index: usize) -> PathBuf {
+        self.upload_dir(name).join(format!("part-{index:05}.bin"))
+    }
+
+    /// Names of the uploads with a manifest
+    pub async fn list(&self) -> io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.root).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.path().join(MANIFEST_FILE).is_file()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    pub async fn load(&self, name: &str) -> io::Result<UploadManifest> {
+        let data = tokio::fs::read(self.upload_dir(name).join(MANIFEST_FILE)).await?;
+        serde_json::from_slice(&data).map_err(io::Error::other)
+    }
+
+    /// Save the manifest, replacing the previous one atomically
+    pub async fn save(&self, name: &str, manifest: &UploadManifest) -> io::Result<()> {
+        let dir = self.upload_dir(name);
+        tokio::fs::create_dir_all(&dir).await?;
+        let tmp = dir.join(format!("{MANIFEST_FILE}.tmp"));
+        tokio::fs::write(
+            &tmp,
+            serde_json::to_vec(manifest).map_err(io::Error::other)?,
+        )
+        .await?;
+        tokio::fs::rename(&tmp, dir.join(MANIFEST_FILE)).await
+    }
+
+    pub async fn write_part(&self, name: &str, index: usize, data: &[u8]) -> io::Result<()> {
+        tokio::fs::create_dir_all(self.upload_dir(name)).await?;
+        tokio::fs::write(self.part_path(name, index), data).await
+    }
+
+    pub async fn read_part(&self, name: &str, index: usize) -> io::Result<Vec<u8>> {
+        tokio::fs::read(self.part_path(name, index)).await
+    }
+
+    /// Remove an upload with its parts
+    pub async fn remove(&self, name: &str) -> io::Result<()> {
+        tokio::fs::remove_dir_all(self.upload_dir(name)).await
+    }
+}
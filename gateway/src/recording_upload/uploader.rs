@@ -0,0 +1,615 @@
+//! Multipart uploads of caller audio and the reconciler finishing them
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use object_store::PutPayload;
+use object_store::multipart::{MultipartStore, PartId};
+use object_store::path::Path as ObjectPath;
+use parking_lot::Mutex;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
+
+use super::spill::{SpillDir, SpilledPart, UploadManifest};
+use crate::config::RecordingUploadConfig;
+use crate::core::sink_audio::SinkAudioFormat;
+use crate::handlers::recording::session_object_key;
+use crate::metrics::global_metrics;
+use crate::session_export::ArtifactKind;
+
+/// Size of the WAV header at the start of the first part
+const WAV_HEADER_LEN: usize = 44;
+
+/// Errors raised while starting the uploader or uploading a recording
+#[derive(Debug, Error)]
+pub enum RecordingUploadError {
+    #[error("Recording upload spill_dir is not set")]
+    NoSpillDir,
+
+    #[error("Recording spill directory error: {0}")]
+    Spill(#[from] std::io::Error),
+
+    #[error("Recording upload failed: {0}")]
+    Store(#[from] object_store::Error),
+}
+
+/// Recordings that S3 does not have in full yet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecordingUploadStatus {
+    /// Recordings with parts on disk, including those of running sessions
+    pub pending_uploads: usize,
+    /// Parts on disk that are not uploaded yet
+    pub pending_parts: usize,
+    /// Pending recordings of ended sessions, left to the reconciler
+    pub stalled_uploads: usize,
+}
+
+/// Starts the recording uploads of sessions and reconciles unfinished ones
+///
+/// Dropping the uploader stops the reconciler; uploads of running sessions
+/// carry on.
+pub struct RecordingUploader {
+    inner: Arc<UploaderInner>,
+    reconciler: AbortHandle,
+}
+
+impl RecordingUploader {
+    /// Start the reconciler of a validated configuration
+    ///
+    /// Must be called from within a Tokio runtime. The first reconcile pass
+    /// runs right away, resuming the uploads a previous run left behind.
+    ///
+    /// # Arguments
+    /// * `config` - Spill directory, part size and retry policy
+    /// * `store` - Recording bucket the recordings are uploaded to
+    /// * `prefix` - Key prefix of the recordings in the bucket
+    ///
+    /// # Errors
+    /// Returns `RecordingUploadError::NoSpillDir` without a spill directory,
+    /// or `RecordingUploadError::Spill` if it cannot be created
+    pub fn start(
+        config: &RecordingUploadConfig,
+        store: Arc<dyn MultipartStore>,
+        prefix: Option<String>,
+    ) -> Result<Self, RecordingUploadError> {
+        let spill_dir = config
+            .spill_dir
+            .as_deref()
+            .ok_or(RecordingUploadError::NoSpillDir)?;
+        let inner = Arc::new(UploaderInner {
+            store,
+            spill: SpillDir::create(spill_dir)?,
+            prefix,
+            part_size: config.part_size_bytes,
+            max_part_attempts: config.max_part_attempts,
+            retry_base_delay: Duration::from_millis(config.retry_base_delay_ms),
+            retry_max_delay: Duration::from_millis(config.retry_max_delay_ms),
+            active: Mutex::new(HashSet::new()),
+            reconciling: tokio::sync::Mutex::new(()),
+        });
+
+        let interval = Duration::from_secs(config.reconcile_interval_secs);
+        let reconciler = tokio::spawn({
+            let inner = inner.clone();
+            async move {
+                let mut ticks = tokio::time::interval(interval);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    ticks.tick().await;
+                    inner.reconcile().await;
+                }
+            }
+        })
+        .abort_handle();
+
+        Ok(Self { inner, reconciler })
+    }
+
+    /// Start recording the caller audio of a session
+    ///
+    /// # Arguments
+    /// * `auth_id` - Tenant of the session, if any
+    /// * `stream_id` - The session's stream ID
+    /// * `format` - Format of the 16-bit PCM written to the recording
+    pub fn begin(
+        &self,
+        auth_id: Option<&str>,
+        stream_id: &str,
+        format: SinkAudioFormat,
+    ) -> RecordingUpload {
+        let key = session_object_key(
+            self.inner.prefix.as_ref(),
+            auth_id,
+            stream_id,
+            ArtifactKind::InputAudio.file_name(),
+        );
+        let name = uuid::Uuid::new_v4().to_string();
+        self.inner.active.lock().insert(name.clone());
+
+        let (commands, received) = mpsc::unbounded_channel();
+        let manifest = UploadManifest::new(key, format.sample_rate, format.channels);
+        tokio::spawn(self.inner.clone().run_upload(name, manifest, received));
+
+        RecordingUpload {
+            format,
+            part_size: self.inner.part_size,
+            buffer: Mutex::new(vec![0; WAV_HEADER_LEN]),
+            commands,
+        }
+    }
+
+    /// Retry the uploads of ended sessions that did not complete
+    pub async fn reconcile(&self) {
+        self.inner.reconcile().await;
+    }
+
+    /// Recordings not uploaded in full yet; also refreshes their gauges
+    pub async fn status(&self) -> RecordingUploadStatus {
+        self.inner.status().await
+    }
+}
+
+impl Drop for RecordingUploader {
+    fn drop(&mut self) {
+        self.reconciler.abort();
+    }
+}
+
+/// Recording of one session's caller audio
+///
+/// Dropping the recording ends it: the rest of the audio is uploaded and the
+/// upload completed in the background.
+pub struct RecordingUpload {
+    format: SinkAudioFormat,
+    part_size: usize,
+    /// Audio of the part being filled; the first part starts with room for
+    /// the WAV header
+    buffer: Mutex<Vec<u8>>,
+    commands: mpsc::UnboundedSender<UploadCommand>,
+}
+
+impl RecordingUpload {
+    /// Format of the recorded audio
+    pub fn format(&self) -> SinkAudioFormat {
+        self.format
+    }
+
+    /// Append 16-bit PCM in the recording's format
+    pub fn write(&self, pcm: &[u8]) {
+        let mut buffer = self.buffer.lock();
+        buffer.extend_from_slice(pcm);
+        while buffer.len() >= self.part_size {
+            let rest = buffer.split_off(self.part_size);
+            let part = std::mem::replace(&mut *buffer, rest);
+            let _ = self.commands.send(UploadCommand::Part(part));
+        }
+    }
+
+    /// End the recording and upload the rest in the background
+    pub fn finish(self) {
+        drop(self);
+    }
+}
+
+impl Drop for RecordingUpload {
+    fn drop(&mut self) {
+        let rest = std::mem::take(self.buffer.get_mut());
+        let _ = self.commands.send(UploadCommand::Finish(rest));
+    }
+}
+
+/// Audio handed from a recording to its upload task
+enum UploadCommand {
+    /// A full part
+    Part(Vec<u8>),
+    /// The last, possibly empty, part
+    Finish(Vec<u8>),
+}
+
+/// State shared by the uploader, the upload tasks and the reconciler
+struct UploaderInner {
+    store: Arc<dyn MultipartStore>,
+    spill: SpillDir,
+    prefix: Option<String>,
+    part_size: usize,
+    max_part_attempts: u32,
+    retry_base_delay: Duration,
+    retry_max_delay: Duration,
+    /// Uploads a session task is still working on
+    active: Mutex<HashSet<String>>,
+    /// Held during a reconcile pass so passes do not overlap
+    reconciling: tokio::sync::Mutex<()>,
+}
+
+impl UploaderInner {
+    /// Spill and upload the parts of one recording until it ends
+    ///
+    /// The first part is uploaded last, once its WAV header is known. After
+    /// a part runs out of attempts the remaining parts are only spilled.
+    async fn run_upload(
+        self: Arc<Self>,
+        name: String,
+        mut manifest: UploadManifest,
+        mut commands: mpsc::UnboundedReceiver<UploadCommand>,
+    ) {
+        let mut uploading = true;
+        while let Some(command) = commands.recv().await {
+            let (data, last) = match command {
+                UploadCommand::Part(data) => (data, false),
+                UploadCommand::Finish(data) => (data, true),
+            };
+            if !data.is_empty() {
+                let index = manifest.parts.len();
+                if let Err(e) = self.spill.write_part(&name, index, &data).await {
+                    warn!(key = %manifest.key, "Failed to spill recording part {}: {}", index, e);
+                }
+                manifest.parts.push(SpilledPart {
+                    len: data.len(),
+                    content_id: None,
+                });
+                if uploading
+                    && index > 0
+                    && !last
+                    && let Err(e) = self.upload_part(&mut manifest, index, data).await
+                {
+                    warn!(key = %manifest.key, "Recording part upload failed; spilling the rest: {}", e);
+                    uploading = false;
+                }
+            }
+            manifest.finished = last;
+            self.save(&name, &manifest).await;
+            if last {
+                break;
+            }
+        }
+
+        manifest.finished = true;
+        self.complete_and_remove(&name, manifest).await;
+        self.active.lock().remove(&name);
+    }
+
+    /// Complete every ended upload that no session task is working on
+    async fn reconcile(&self) {
+        let _pass = self.reconciling.lock().await;
+        let names = match self.spill.list().await {
+            Ok(names) => names,
+            Err(e) => {
+                warn!("Failed to list recording uploads: {}", e);
+                return;
+            }
+        };
+        for name in names {
+            if self.active.lock().contains(&name) {
+                continue;
+            }
+            let mut manifest = match self.spill.load(&name).await {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    warn!(upload = %name, "Failed to read recording upload: {}", e);
+                    continue;
+                }
+            };
+            // Nobody writes to an upload left behind by a previous run
+            if !manifest.finished {
+                manifest.finished = true;
+                self.save(&name, &manifest).await;
+            }
+            self.complete_and_remove(&name, manifest).await;
+        }
+        self.status().await;
+    }
+
+    async fn status(&self) -> RecordingUploadStatus {
+        let mut status = RecordingUploadStatus::default();
+        for name in self.spill.list().await.unwrap_or_default() {
+            let Ok(manifest) = self.spill.load(&name).await else {
+                continue;
+            };
+            status.pending_uploads += 1;
+            status.pending_parts += manifest.pending_parts();
+            if !self.active.lock().contains(&name) {
+                status.stalled_uploads += 1;
+            }
+        }
+
+        let metrics = global_metrics();
+        metrics.set_gauge(
+            "waav_recording_uploads_pending",
+            "Recordings with parts on disk that S3 does not have in full",
+            &[],
+            status.pending_uploads as f64,
+        );
+        metrics.set_gauge(
+            "waav_recording_upload_parts_pending",
+            "Recording parts on disk that are not uploaded yet",
+            &[],
+            status.pending_parts as f64,
+        );
+        status
+    }
+
+    /// Complete an ended upload, removing its spill folder once S3 has it
+    async fn complete_and_remove(&self, name: &str, mut manifest: UploadManifest) {
+        match self.complete(name, &mut manifest).await {
+            Ok(()) => {
+                info!(key = %manifest.key, parts = manifest.parts.len(), "Recording uploaded");
+                global_metrics().inc_counter(
+                    "waav_recording_uploads_completed_total",
+                    "Recordings uploaded in full",
+                    &[],
+                );
+                if let Err(e) = self.spill.remove(name).await {
+                    warn!(upload = %name, "Failed to remove uploaded recording parts: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!(key = %manifest.key, "Recording upload left for the reconciler: {}", e);
+            }
+        }
+    }
+
+    /// Upload the parts S3 does not have, then complete the upload
+    async fn complete(
+        &self,
+        name: &str,
+        manifest: &mut UploadManifest,
+    ) -> Result<(), RecordingUploadError> {
+        for index in 0..manifest.parts.len() {
+            if manifest.parts[index].content_id.is_some() {
+                continue;
+            }
+            let mut data = self.spill.read_part(name, index).await?;
+            if index == 0 && data.len() >= WAV_HEADER_LEN {
+                let data_len = manifest.total_len() - WAV_HEADER_LEN;
+                data[..WAV_HEADER_LEN].copy_from_slice(&wav_header(
+                    manifest.sample_rate,
+                    manifest.channels,
+                    data_len,
+                ));
+            }
+            let uploaded = self.upload_part(manifest, index, data).await;
+            self.save(name, manifest).await;
+            uploaded?;
+        }
+
+        let Some(upload_id) = manifest.upload_id.clone() else {
+            return Ok(());
+        };
+        let parts = manifest
+            .parts
+            .iter()
+            .map(|part| PartId {
+                content_id: part.content_id.clone().unwrap_or_default(),
+            })
+            .collect();
+        self.store
+            .complete_multipart(&ObjectPath::from(manifest.key.as_str()), &upload_id, parts)
+            .await?;
+        Ok(())
+    }
+
+    /// Upload one part, creating the multipart upload first if needed
+    async fn upload_part(
+        &self,
+        manifest: &mut UploadManifest,
+        index: usize,
+        data: Vec<u8>,
+    ) -> Result<(), RecordingUploadError> {
+        let path = ObjectPath::from(manifest.key.as_str());
+        let upload_id = match &manifest.upload_id {
+            Some(upload_id) => upload_id.clone(),
+            None => {
+                let upload_id = self.store.create_multipart(&path).await?;
+                manifest.upload_id = Some(upload_id.clone());
+                upload_id
+            }
+        };
+
+        let payload = PutPayload::from(data);
+        let mut attempt = 1;
+        loop {
+            match self
+                .store
+                .put_part(&path, &upload_id, index, payload.clone())
+                .await
+            {
+                Ok(part) => {
+                    manifest.parts[index].content_id = Some(part.content_id);
+                    return Ok(());
+                }
+                Err(e) => {
+                    global_metrics().inc_counter(
+                        "waav_recording_upload_part_failures_total",
+                        "Failed attempts to upload a recording part",
+                        &[],
+                    );
+                    if attempt >= self.max_part_attempts {
+                        return Err(e.into());
+                    }
+                    let delay = self.retry_delay(attempt);
+                    debug!(key = %manifest.key, part = index, attempt, "Retrying recording part in {:?}: {}", delay, e);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Delay after failed attempt `attempt`, doubling up to the maximum
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        self.retry_base_delay
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.retry_max_delay)
+    }
+
+    async fn save(&self, name: &str, manifest: &UploadManifest) {
+        if let Err(e) = self.spill.save(name, manifest).await {
+            warn!(key = %manifest.key, "Failed to save recording upload state: {}", e);
+        }
+    }
+}
+
+/// Header of a 16-bit PCM WAV file with `data_len` bytes of audio
+fn wav_header(sample_rate: u32, channels: u16, data_len: usize) -> [u8; WAV_HEADER_LEN] {
+    let data_len = u32::try_from(data_len).unwrap_or(u32::MAX - 36);
+    let block_align = channels * 2;
+    let mut header = [0; WAV_HEADER_LEN];
+    header[0..4].copy_from_slice(b"RIFF");
+    header[4..8].copy_from_slice(&(36 + data_len).to_le_bytes());
+    header[8..12].copy_from_slice(b"WAVE");
+    header[12..16].copy_from_slice(b"fmt ");
+    header[16..20].copy_from_slice(&16u32.to_le_bytes());
+    header[20..22].copy_from_slice(&1u16.to_le_bytes());
+    header[22..24].copy_from_slice(&channels.to_le_bytes());
+    header[24..28].copy_from_slice(&sample_rate.to_le_bytes());
+    header[28..32].copy_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+    header[32..34].copy_from_slice(&block_align.to_le_bytes());
+    header[34..36].copy_from_slice(&16u16.to_le_bytes());
+    header[36..40].copy_from_slice(b"data");
+    header[40..44].copy_from_slice(&data_len.to_le_bytes());
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_trait::async_trait;
+    use object_store::memory::InMemory;
+    use object_store::{MultipartId, ObjectStore, PutResult};
+
+    use super::*;
+    use crate::config::MIN_RECORDING_PART_SIZE;
+
+    /// In-memory bucket whose part uploads fail while `failing` is set
+    #[derive(Debug, Default)]
+    struct FlakyStore {
+        inner: InMemory,
+        failing: AtomicBool,
+    }
+
+    #[async_trait]
+    impl MultipartStore for FlakyStore {
+        async fn create_multipart(&self, path: &ObjectPath) -> object_store::Result<MultipartId> {
+            self.inner.create_multipart(path).await
+        }
+
+        async fn put_part(
+            &self,
+            path: &ObjectPath,
+            id: &MultipartId,
+            part_idx: usize,
+            data: PutPayload,
+        ) -> object_store::Result<PartId> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(object_store::Error::Generic {
+                    store: "flaky",
+                    source: "connection reset".into(),
+                });
+            }
+            self.inner.put_part(path, id, part_idx, data).await
+        }
+
+        async fn complete_multipart(
+            &self,
+            path: &ObjectPath,
+            id: &MultipartId,
+            parts: Vec<PartId>,
+        ) -> object_store::Result<PutResult> {
+            self.inner.complete_multipart(path, id, parts).await
+        }
+
+        async fn abort_multipart(
+            &self,
+            path: &ObjectPath,
+            id: &MultipartId,
+        ) -> object_store::Result<()> {
+            self.inner.abort_multipart(path, id).await
+        }
+    }
+
+    fn config(spill_dir: &std::path::Path) -> RecordingUploadConfig {
+        RecordingUploadConfig {
+            spill_dir: Some(spill_dir.to_path_buf()),
+            max_part_attempts: 2,
+            retry_base_delay_ms: 1,
+            retry_max_delay_ms: 2,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_resumes_after_outage_and_restart() {
+        let spill_dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(FlakyStore::default());
+        let uploader = RecordingUploader::start(
+            &config(spill_dir.path()),
+            store.clone(),
+            Some("recordings".to_string()),
+        )
+        .unwrap();
+
+        // Two full parts reach S3 in time; the outage hits the rest
+        let recording = uploader.begin(Some("tenant"), "stream-1", SinkAudioFormat::mono(16000));
+        let chunk: Vec<u8> = (0..4000u16)
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        let before_outage = 2 * MIN_RECORDING_PART_SIZE / chunk.len() + 1;
+        for _ in 0..before_outage {
+            recording.write(&chunk);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        store.failing.store(true, Ordering::SeqCst);
+        for _ in 0..3 {
+            recording.write(&chunk);
+        }
+        let written = (before_outage + 3) * chunk.len();
+        recording.finish();
+
+        let mut status = uploader.status().await;
+        for _ in 0..200 {
+            if status.stalled_uploads == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            status = uploader.status().await;
+        }
+        assert_eq!(status.pending_uploads, 1);
+        assert_eq!(status.stalled_uploads, 1);
+        assert_eq!(status.pending_parts, 2, "the first and the last part");
+
+        // The gateway restarts once S3 is back
+        drop(uploader);
+        store.failing.store(false, Ordering::SeqCst);
+        let uploader = RecordingUploader::start(
+            &config(spill_dir.path()),
+            store.clone(),
+            Some("recordings".to_string()),
+        )
+        .unwrap();
+        uploader.reconcile().await;
+        assert_eq!(uploader.status().await, RecordingUploadStatus::default());
+
+        let wav = store
+            .inner
+            .get(&ObjectPath::from("recordings/tenant/stream-1/input.wav"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(wav.len(), WAV_HEADER_LEN + written);
+        let reader = hound::WavReader::new(std::io::Cursor::new(wav.to_vec())).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.spec().channels, 1);
+        let samples: Vec<i16> = reader.into_samples().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), written / 2);
+        assert_eq!(&samples[..3], &[0, 1, 2]);
+        assert_eq!(samples[samples.len() - 1], 3999);
+    }
+}
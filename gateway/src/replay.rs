@@ -0,0 +1,1036 @@
+//! # Recording Replay
+//!
+//! Re-runs the input audio of a recorded session through a new provider
+//! configuration, to check how a provider change would have transcribed real
+//! calls. `waav-gateway replay` replays a local file or an object from the
+//! recording bucket once; `POST /admin/replay` runs the same replay as a
+//! background job on a running gateway.
+//!
+//! The audio is fed through a fresh session built with
+//! [`SessionPipelineBuilder`], either at real-time pace or as fast as the
+//! providers accept it, and the final transcripts are joined into one
+//! transcript. When a reference transcript is available (given explicitly or
+//! stored as `transcript.txt` next to the recording), the report includes the
+//! word error rate against it.
+//!
+//! Recordings must be 16-bit PCM WAV; multi-channel audio is mixed down to
+//! mono. Ogg/Opus recordings from LiveKit egress need converting first, e.g.
+//! with `ffmpeg -i audio.ogg -ac 1 -ar 16000 audio.wav`.
+//!
+//! Replay sessions open their own provider connections and never use the
+//! shared TTS request pools of live sessions. A gateway runs at most
+//! `replay_max_concurrent_jobs` jobs at once; further jobs wait queued.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use object_store::{Error as ObjectStoreError, ObjectStore, path::Path as ObjectPath};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::time::{Interval, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::config::ServerConfig;
+use crate::core::session::{Session, SessionError, SessionEvent, SessionPipelineBuilder};
+use crate::core::stt::STTConfig;
+use crate::handlers::ws::config::{STTWebSocketConfig, TTSWebSocketConfig};
+use crate::plugin::PluginRegistry;
+use crate::plugin::dispatch::{resolve_stt_provider, resolve_tts_provider};
+use crate::selftest::{normalized_words, provider_api_key, word_edit_distance};
+use crate::state::{AppState, recording_object_store};
+
+/// Audio pushed to the session per `push_audio` call
+const CHUNK_DURATION_MS: u64 = 100;
+
+/// Silence sent after the recording so providers finalize the last utterance
+const TRAILING_SILENCE_MS: u64 = 1000;
+
+/// How long the session may go without events after the audio ends before
+/// the transcript is considered complete
+const TRANSCRIPT_SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Distinct STT errors kept for the report
+const MAX_ERROR_SAMPLES: usize = 10;
+
+/// Finished jobs kept for `GET /admin/replay`; older ones are forgotten
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// Reference transcript stored next to a recording
+pub const REFERENCE_TRANSCRIPT_FILE: &str = "transcript.txt";
+
+/// Pace at which recorded audio is fed to the session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ReplaySpeed {
+    /// As fast as it was recorded, like a live caller
+    #[default]
+    Realtime,
+    /// As fast as the providers accept it
+    Max,
+}
+
+impl ReplaySpeed {
+    /// Wire name of the speed
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Realtime => "realtime",
+            Self::Max => "max",
+        }
+    }
+}
+
+/// Providers a recording is replayed through
+///
+/// Same shape as the `stt_config` and `tts_config` of a WebSocket config
+/// message, including optional API keys. The audio format of `stt_config` is
+/// replaced by the recording's. The TTS provider is connected but never
+/// speaks.
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReplaySessionConfig {
+    pub stt_config: STTWebSocketConfig,
+    pub tts_config: TTSWebSocketConfig,
+}
+
+/// Error that stops a replay
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("Invalid replay options: {0}")]
+    InvalidOptions(String),
+    #[error("Failed to load recording {recording}: {message}")]
+    Recording { recording: String, message: String },
+    #[error("Recording storage not configured")]
+    StorageUnavailable,
+    #[error("Missing provider credentials: {0}")]
+    Credentials(String),
+    #[error("Session failed: {0}")]
+    Session(#[from] SessionError),
+    #[error("Failed to write report to {path}: {message}")]
+    Output { path: PathBuf, message: String },
+    #[error("Replay cancelled")]
+    Cancelled,
+}
+
+/// 16-bit little-endian mono PCM of a recording
+#[derive(Debug, Clone)]
+pub struct ReplayAudio {
+    pub pcm: Bytes,
+    pub sample_rate: u32,
+}
+
+impl ReplayAudio {
+    /// Decode a 16-bit integer PCM WAV file, mixing its channels down to mono
+    pub fn from_wav(data: &[u8]) -> Result<Self, String> {
+        if data.starts_with(b"OggS") {
+            return Err(
+                "Ogg recordings are not supported, convert to 16-bit PCM WAV first".to_string(),
+            );
+        }
+        let reader =
+            hound::WavReader::new(std::io::Cursor::new(data)).map_err(|e| e.to_string())?;
+        let spec = reader.spec();
+        if spec.bits_per_sample != 16 || spec.sample_format != hound::SampleFormat::Int {
+            return Err(format!(
+                "must be 16-bit PCM (got {}-bit {:?})",
+                spec.bits_per_sample, spec.sample_format
+            ));
+        }
+
+        let samples = reader
+            .into_samples::<i16>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        let channels = usize::from(spec.channels.max(1));
+        let mut pcm = Vec::with_capacity(samples.len() / channels * 2);
+        for frame in samples.chunks_exact(channels) {
+            let sum: i32 = frame.iter().copied().map(i32::from).sum();
+            let sample = (sum / channels as i32) as i16;
+            pcm.extend_from_slice(&sample.to_le_bytes());
+        }
+        if pcm.is_empty() {
+            return Err("contains no audio".to_string());
+        }
+        Ok(Self {
+            pcm: pcm.into(),
+            sample_rate: spec.sample_rate,
+        })
+    }
+
+    /// Length of the audio in seconds
+    pub fn duration_secs(&self) -> f64 {
+        self.pcm.len() as f64 / 2.0 / self.sample_rate as f64
+    }
+
+    /// Bytes of audio in one chunk
+    fn chunk_len(&self) -> usize {
+        (self.sample_rate as u64 * CHUNK_DURATION_MS / 1000 * 2).max(2) as usize
+    }
+
+    /// Silence appended after the recording
+    fn trailing_silence(&self) -> Bytes {
+        let len = self.sample_rate as u64 * TRAILING_SILENCE_MS / 1000 * 2;
+        Bytes::from(vec![0u8; len as usize])
+    }
+}
+
+/// A recording loaded for replay
+#[derive(Debug, Clone)]
+pub struct ReplayRecording {
+    /// File path or object key the recording was loaded from
+    pub source: String,
+    pub audio: ReplayAudio,
+    /// Transcript to compare the replay with
+    pub reference: Option<String>,
+}
+
+impl ReplayRecording {
+    /// Load a WAV file and the `transcript.txt` next to it, if there is one
+    pub async fn from_file(path: &Path) -> Result<Self, ReplayError> {
+        let source = path.display().to_string();
+        let error = |message: String| ReplayError::Recording {
+            recording: source.clone(),
+            message,
+        };
+        let data = tokio::fs::read(path)
+            .await
+            .map_err(|e| error(e.to_string()))?;
+        let audio = ReplayAudio::from_wav(&data).map_err(error)?;
+
+        let reference_path = path.with_file_name(REFERENCE_TRANSCRIPT_FILE);
+        let reference = match tokio::fs::read_to_string(&reference_path).await {
+            Ok(text) => Some(text.trim().to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(error(format!("failed to read reference transcript: {e}"))),
+        };
+        Ok(Self {
+            source,
+            audio,
+            reference,
+        })
+    }
+
+    /// Load a WAV object and the `transcript.txt` next to it, if there is one
+    pub async fn from_object_store(
+        store: &dyn ObjectStore,
+        key: &str,
+    ) -> Result<Self, ReplayError> {
+        let error = |message: String| ReplayError::Recording {
+            recording: key.to_string(),
+            message,
+        };
+        let path = ObjectPath::parse(key).map_err(|e| error(e.to_string()))?;
+        let data = match store.get(&path).await {
+            Ok(result) => result.bytes().await.map_err(|e| error(e.to_string()))?,
+            Err(ObjectStoreError::NotFound { .. }) => return Err(error("not found".to_string())),
+            Err(e) => return Err(error(e.to_string())),
+        };
+        let audio = ReplayAudio::from_wav(&data).map_err(error)?;
+
+        let reference_key = match path.as_ref().rsplit_once('/') {
+            Some((dir, _)) => format!("{dir}/{REFERENCE_TRANSCRIPT_FILE}"),
+            None => REFERENCE_TRANSCRIPT_FILE.to_string(),
+        };
+        let reference_path = ObjectPath::parse(reference_key).map_err(|e| error(e.to_string()))?;
+        let reference = match store.get(&reference_path).await {
+            Ok(result) => {
+                let text = result
+                    .bytes()
+                    .await
+                    .map_err(|e| error(format!("failed to read reference transcript: {e}")))?;
+                Some(String::from_utf8_lossy(&text).trim().to_string())
+            }
+            Err(ObjectStoreError::NotFound { .. }) => None,
+            Err(e) => return Err(error(format!("failed to read reference transcript: {e}"))),
+        };
+        Ok(Self {
+            source: key.to_string(),
+            audio,
+            reference,
+        })
+    }
+}
+
+/// Result of a replay
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReplayReport {
+    /// File path or object key of the recording
+    pub recording: String,
+    /// STT provider the audio was transcribed with
+    pub stt_provider: String,
+    /// STT model the audio was transcribed with
+    pub stt_model: String,
+    pub speed: ReplaySpeed,
+    /// Length of the recording in seconds
+    pub audio_secs: f64,
+    /// Wall-clock time of the replay in seconds
+    pub elapsed_secs: f64,
+    /// Final transcripts joined with spaces
+    pub transcript: String,
+    /// Final transcripts received
+    pub final_results: usize,
+    /// Transcript the replay was compared with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Word error rate against the reference (0.0 is a perfect match)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wer: Option<f64>,
+    /// First distinct STT errors
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Recording:  {}", self.recording)?;
+        writeln!(f, "STT:        {} ({})", self.stt_provider, self.stt_model)?;
+        writeln!(
+            f,
+            "Replayed:   {:.1}s of audio in {:.1}s ({})",
+            self.audio_secs,
+            self.elapsed_secs,
+            self.speed.as_str()
+        )?;
+        writeln!(f, "Transcript: {}", self.transcript)?;
+        match (&self.reference, self.wer) {
+            (Some(reference), Some(wer)) => {
+                writeln!(f, "Reference:  {reference}")?;
+                writeln!(f, "WER:        {:.1}%", wer * 100.0)?;
+            }
+            _ => writeln!(f, "WER:        n/a (no reference transcript)")?,
+        }
+        writeln!(f, "STT errors: {}", self.errors.len())?;
+        for error in &self.errors {
+            writeln!(f, "  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Word error rate of a transcript against a reference transcript
+///
+/// Both are lowercased and stripped of punctuation. The rate is the word edit
+/// distance divided by the number of reference words, so insertions can push
+/// it above 1.0. Against an empty reference, an empty transcript scores 0.0
+/// and anything else 1.0.
+pub fn word_error_rate(reference: &str, transcript: &str) -> f64 {
+    let reference = normalized_words(reference);
+    let transcript = normalized_words(transcript);
+    if reference.is_empty() {
+        return if transcript.is_empty() { 0.0 } else { 1.0 };
+    }
+    word_edit_distance(&reference, &transcript) as f64 / reference.len() as f64
+}
+
+/// Replay a recording through a fresh session
+///
+/// The session's providers are created from `registry`. Returns
+/// [`ReplayError::Cancelled`] soon after `cancel` fires. The session is
+/// closed either way.
+pub async fn replay(
+    recording: &ReplayRecording,
+    session_config: &ReplaySessionConfig,
+    speed: ReplaySpeed,
+    server: &ServerConfig,
+    registry: &Arc<PluginRegistry>,
+    cancel: &CancellationToken,
+) -> Result<ReplayReport, ReplayError> {
+    let started = Instant::now();
+    let audio = &recording.audio;
+    let stt_ws = &session_config.stt_config;
+    let tts_ws = &session_config.tts_config;
+
+    let stt_api_key = api_key(
+        server,
+        &stt_ws.provider,
+        stt_ws.api_key.as_deref(),
+        resolve_stt_provider(&stt_ws.provider).is_some(),
+    )?;
+    let tts_api_key = api_key(
+        server,
+        &tts_ws.provider,
+        tts_ws.api_key.as_deref(),
+        resolve_tts_provider(&tts_ws.provider).is_some(),
+    )?;
+    let stt_config = STTConfig {
+        sample_rate: audio.sample_rate,
+        channels: 1,
+        encoding: "linear16".to_string(),
+        ..stt_ws.to_stt_config(stt_api_key)
+    };
+
+    let mut builder = SessionPipelineBuilder::new()
+        .plugin_registry(registry.clone())
+        .stt(stt_config.clone())
+        .tts(tts_ws.to_tts_config(tts_api_key))
+        .connect_timeout(Duration::from_secs(server.provider_connect_timeout_secs));
+    if let Some(failover) = &stt_ws.failover {
+        let api_key = api_key(
+            server,
+            &failover.provider,
+            failover.api_key.as_deref(),
+            resolve_stt_provider(&failover.provider).is_some(),
+        )?;
+        builder = builder.stt_failover(failover.to_failover_config(&stt_config, api_key));
+    }
+    if let Some(endpointing) = stt_ws.adaptive_endpointing {
+        builder = builder.adaptive_endpointing(endpointing);
+    }
+
+    let session = tokio::select! {
+        _ = cancel.cancelled() => return Err(ReplayError::Cancelled),
+        session = builder.build() => session?,
+    };
+    let result = feed(&session, audio, speed, cancel).await;
+    let _ = session.close().await;
+    let collected = result?;
+
+    let transcript = collected.finals.join(" ");
+    let wer = recording
+        .reference
+        .as_deref()
+        .map(|reference| word_error_rate(reference, &transcript));
+    Ok(ReplayReport {
+        recording: recording.source.clone(),
+        stt_provider: stt_ws.provider.clone(),
+        stt_model: stt_ws.model.clone(),
+        speed,
+        audio_secs: audio.duration_secs(),
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        final_results: collected.finals.len(),
+        transcript,
+        reference: recording.reference.clone(),
+        wer,
+        errors: collected.errors,
+    })
+}
+
+/// Client-supplied API key, or the gateway's key for the provider
+fn api_key(
+    server: &ServerConfig,
+    provider: &str,
+    client_key: Option<&str>,
+    builtin: bool,
+) -> Result<String, ReplayError> {
+    match client_key.filter(|key| !key.is_empty()) {
+        Some(key) => Ok(key.to_string()),
+        None => provider_api_key(server, provider, builtin).map_err(ReplayError::Credentials),
+    }
+}
+
+/// Final transcripts and STT errors from the session's events
+#[derive(Default)]
+struct Collected {
+    finals: Vec<String>,
+    errors: Vec<String>,
+}
+
+impl Collected {
+    fn handle(&mut self, event: SessionEvent) {
+        match event {
+            SessionEvent::Transcript(result) if result.is_final => {
+                let text = result.transcript.trim();
+                if !text.is_empty() {
+                    self.finals.push(text.to_string());
+                }
+            }
+            SessionEvent::SttError { error, .. } => {
+                let message = error.to_string();
+                if self.errors.len() < MAX_ERROR_SAMPLES && !self.errors.contains(&message) {
+                    self.errors.push(message);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Stream the audio and trailing silence, then wait for the transcript to settle
+async fn feed(
+    session: &Session,
+    audio: &ReplayAudio,
+    speed: ReplaySpeed,
+    cancel: &CancellationToken,
+) -> Result<Collected, ReplayError> {
+    let mut events = session
+        .take_events()
+        .expect("events of a new session are not taken yet");
+    let mut collected = Collected::default();
+
+    let chunk_len = audio.chunk_len();
+    let silence = audio.trailing_silence();
+    let mut chunks = chunks(&audio.pcm, chunk_len).chain(chunks(&silence, chunk_len));
+    let mut pacer = (speed == ReplaySpeed::Realtime).then(|| {
+        let mut interval = tokio::time::interval(Duration::from_millis(CHUNK_DURATION_MS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    });
+    let mut events_open = true;
+
+    // Events are drained between chunks so providers never wait on a full
+    // event buffer while audio is being pushed
+    loop {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(ReplayError::Cancelled),
+            event = events.recv(), if events_open => match event {
+                Some(event) => collected.handle(event),
+                None => events_open = false,
+            },
+            _ = next_tick(&mut pacer) => {
+                let Some(chunk) = chunks.next() else { break };
+                session.push_audio(chunk).await?;
+            }
+        }
+    }
+
+    while events_open {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(ReplayError::Cancelled),
+            event = tokio::time::timeout(TRANSCRIPT_SETTLE_TIME, events.recv()) => match event {
+                Ok(Some(event)) => collected.handle(event),
+                Ok(None) | Err(_) => break,
+            },
+        }
+    }
+    Ok(collected)
+}
+
+fn chunks(audio: &Bytes, chunk_len: usize) -> impl Iterator<Item = Bytes> + '_ {
+    (0..audio.len())
+        .step_by(chunk_len)
+        .map(move |start| audio.slice(start..(start + chunk_len).min(audio.len())))
+}
+
+/// Wait for the next real-time tick; immediate at max speed
+async fn next_tick(pacer: &mut Option<Interval>) {
+    if let Some(interval) = pacer {
+        interval.tick().await;
+    }
+}
+
+/// Options of `waav-gateway replay`
+#[derive(Debug, Clone, clap::Args)]
+pub struct ReplayOptions {
+    /// 16-bit PCM WAV recording to replay
+    #[arg(long, required_unless_present = "s3_key", conflicts_with = "s3_key")]
+    pub file: Option<PathBuf>,
+
+    /// Object key of a 16-bit PCM WAV recording in the recording bucket
+    #[arg(long = "s3-key")]
+    pub s3_key: Option<String>,
+
+    /// JSON file with the `stt_config` and `tts_config` to replay through
+    #[arg(long = "session-config")]
+    pub session_config: PathBuf,
+
+    /// Pace at which the audio is fed to the providers
+    #[arg(long, value_enum, default_value_t = ReplaySpeed::Realtime)]
+    pub speed: ReplaySpeed,
+
+    /// Reference transcript file (default: `transcript.txt` next to the recording)
+    #[arg(long)]
+    pub reference: Option<PathBuf>,
+
+    /// Also write the report as JSON to this file
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+
+    /// Print the report as JSON instead of text
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Run `waav-gateway replay`
+///
+/// Loads the recording and session config, replays the recording with the
+/// providers of `registry` and writes the report to `output` when one is
+/// given. Ctrl-C cancels the replay.
+pub async fn run(
+    options: &ReplayOptions,
+    server: &ServerConfig,
+    registry: &Arc<PluginRegistry>,
+) -> Result<ReplayReport, ReplayError> {
+    let session_config = tokio::fs::read(&options.session_config)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|data| {
+            serde_json::from_slice::<ReplaySessionConfig>(&data).map_err(|e| e.to_string())
+        })
+        .map_err(|e| {
+            ReplayError::InvalidOptions(format!(
+                "session config {}: {e}",
+                options.session_config.display()
+            ))
+        })?;
+
+    let mut recording = match (&options.file, &options.s3_key) {
+        (Some(path), None) => ReplayRecording::from_file(path).await?,
+        (None, Some(key)) => {
+            let (store, _) =
+                recording_object_store(server).ok_or(ReplayError::StorageUnavailable)?;
+            ReplayRecording::from_object_store(store.as_ref(), key).await?
+        }
+        _ => {
+            return Err(ReplayError::InvalidOptions(
+                "exactly one of --file and --s3-key is required".to_string(),
+            ));
+        }
+    };
+    if let Some(path) = &options.reference {
+        let reference = tokio::fs::read_to_string(path).await.map_err(|e| {
+            ReplayError::InvalidOptions(format!("reference {}: {e}", path.display()))
+        })?;
+        recording.reference = Some(reference.trim().to_string());
+    }
+
+    let cancel = CancellationToken::new();
+    let ctrl_c = {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+            }
+        })
+    };
+    let result = replay(
+        &recording,
+        &session_config,
+        options.speed,
+        server,
+        registry,
+        &cancel,
+    )
+    .await;
+    ctrl_c.abort();
+    let report = result?;
+
+    if let Some(path) = &options.output {
+        let output_error = |message: String| ReplayError::Output {
+            path: path.clone(),
+            message,
+        };
+        let json = serde_json::to_vec_pretty(&report).map_err(|e| output_error(e.to_string()))?;
+        tokio::fs::write(path, json)
+            .await
+            .map_err(|e| output_error(e.to_string()))?;
+    }
+    Ok(report)
+}
+
+/// State of a replay job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayJobStatus {
+    /// Waiting for a free replay slot
+    Queued,
+    /// Replaying the recording
+    Running,
+    /// Finished with a report
+    Completed,
+    /// Stopped by an error
+    Failed,
+    /// Stopped through `DELETE /admin/replay/{job_id}`
+    Cancelled,
+}
+
+impl ReplayJobStatus {
+    /// Whether the job has stopped
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Request body of `POST /admin/replay`
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReplayRequest {
+    /// Object key of a 16-bit PCM WAV recording in the recording bucket
+    pub s3_key: String,
+    /// Providers to replay the recording through
+    pub session: ReplaySessionConfig,
+    /// Pace at which the audio is fed to the providers (default `realtime`)
+    #[serde(default)]
+    pub speed: ReplaySpeed,
+    /// Transcript to compare with instead of the `transcript.txt` stored next
+    /// to the recording
+    #[serde(default)]
+    pub reference_transcript: Option<String>,
+}
+
+impl ReplayRequest {
+    /// Check the object key before the job is queued
+    pub fn validate(&self) -> Result<(), String> {
+        if self.s3_key.trim().is_empty() {
+            return Err("s3_key must not be empty".to_string());
+        }
+        ObjectPath::parse(&self.s3_key)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid s3_key: {e}"))
+    }
+}
+
+/// A replay job as reported by the admin API
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReplayJobInfo {
+    pub id: String,
+    pub status: ReplayJobStatus,
+    /// Object key of the recording
+    pub s3_key: String,
+    /// STT provider the recording is replayed through
+    pub stt_provider: String,
+    pub speed: ReplaySpeed,
+    /// Unix timestamp (seconds) at which the job was created
+    pub created_at: u64,
+    /// Unix timestamp (seconds) at which the job finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    /// Result of a completed job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ReplayReport>,
+    /// Why the job failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct ReplayJob {
+    /// Creation order, for listing
+    seq: u64,
+    info: RwLock<ReplayJobInfo>,
+    cancel: CancellationToken,
+}
+
+impl ReplayJob {
+    fn finish(&self, result: Result<ReplayReport, ReplayError>) {
+        let mut info = self.info.write();
+        match result {
+            Ok(report) => {
+                info.status = ReplayJobStatus::Completed;
+                info.report = Some(report);
+            }
+            Err(ReplayError::Cancelled) => info.status = ReplayJobStatus::Cancelled,
+            Err(e) => {
+                info.status = ReplayJobStatus::Failed;
+                info.error = Some(e.to_string());
+            }
+        }
+        info.finished_at = Some(unix_now());
+    }
+}
+
+/// Replay jobs of a gateway
+///
+/// A job waits for one of the `max_concurrent` slots before it builds its
+/// session, so replays never hold more provider connections than configured.
+pub struct ReplayJobs {
+    jobs: DashMap<String, Arc<ReplayJob>>,
+    slots: Semaphore,
+    next_seq: AtomicU64,
+}
+
+impl ReplayJobs {
+    /// Create an empty job list running at most `max_concurrent` jobs at once
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            jobs: DashMap::new(),
+            slots: Semaphore::new(max_concurrent),
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// All known jobs, oldest first
+    pub fn list(&self) -> Vec<ReplayJobInfo> {
+        let mut jobs: Vec<(u64, ReplayJobInfo)> = self
+            .jobs
+            .iter()
+            .map(|job| (job.seq, job.info.read().clone()))
+            .collect();
+        jobs.sort_unstable_by_key(|(seq, _)| *seq);
+        jobs.into_iter().map(|(_, info)| info).collect()
+    }
+
+    /// A job by id
+    pub fn get(&self, id: &str) -> Option<ReplayJobInfo> {
+        self.jobs.get(id).map(|job| job.info.read().clone())
+    }
+
+    /// Cancel a queued or running job, or forget a finished one
+    ///
+    /// Returns the job as it was before the call, or None for an unknown id.
+    /// A cancelled job reports `cancelled` once its session has closed.
+    pub fn cancel(&self, id: &str) -> Option<ReplayJobInfo> {
+        let job = self.jobs.get(id)?.clone();
+        let info = job.info.read().clone();
+        if info.status.is_finished() {
+            self.jobs.remove(id);
+        } else {
+            job.cancel.cancel();
+        }
+        Some(info)
+    }
+
+    fn insert(&self, request: &ReplayRequest) -> Arc<ReplayJob> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let job = Arc::new(ReplayJob {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            info: RwLock::new(ReplayJobInfo {
+                id: id.clone(),
+                status: ReplayJobStatus::Queued,
+                s3_key: request.s3_key.clone(),
+                stt_provider: request.session.stt_config.provider.clone(),
+                speed: request.speed,
+                created_at: unix_now(),
+                finished_at: None,
+                report: None,
+                error: None,
+            }),
+            cancel: CancellationToken::new(),
+        });
+        self.jobs.insert(id, job.clone());
+        job
+    }
+
+    /// Forget the oldest finished jobs beyond `MAX_FINISHED_JOBS`
+    fn prune(&self) {
+        let mut finished: Vec<(u64, String)> = self
+            .jobs
+            .iter()
+            .filter(|job| job.info.read().status.is_finished())
+            .map(|job| (job.seq, job.key().clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort_unstable();
+        let excess = finished.len() - MAX_FINISHED_JOBS;
+        for (_, id) in finished.into_iter().take(excess) {
+            self.jobs.remove(&id);
+        }
+    }
+}
+
+/// Start a replay job in the background
+///
+/// The recording is read from the recording bucket once the job gets a slot.
+/// Returns the queued job.
+pub fn spawn_replay_job(app_state: Arc<AppState>, request: ReplayRequest) -> ReplayJobInfo {
+    let job = app_state.replay_jobs.insert(&request);
+    let info = job.info.read().clone();
+    info!(
+        job_id = %info.id,
+        s3_key = %info.s3_key,
+        stt_provider = %info.stt_provider,
+        "Replay job queued"
+    );
+
+    tokio::spawn(async move {
+        let result = run_job(&app_state, &job, request).await;
+        match &result {
+            Ok(report) => info!(
+                job_id = %job.info.read().id,
+                wer = ?report.wer,
+                elapsed_secs = report.elapsed_secs,
+                "Replay job completed"
+            ),
+            Err(ReplayError::Cancelled) => {
+                info!(job_id = %job.info.read().id, "Replay job cancelled")
+            }
+            Err(e) => warn!(job_id = %job.info.read().id, error = %e, "Replay job failed"),
+        }
+        job.finish(result);
+        app_state.replay_jobs.prune();
+    });
+    info
+}
+
+async fn run_job(
+    app_state: &AppState,
+    job: &ReplayJob,
+    request: ReplayRequest,
+) -> Result<ReplayReport, ReplayError> {
+    let _slot = tokio::select! {
+        _ = job.cancel.cancelled() => return Err(ReplayError::Cancelled),
+        slot = app_state.replay_jobs.slots.acquire() => {
+            slot.expect("replay slots are never closed")
+        }
+    };
+    job.info.write().status = ReplayJobStatus::Running;
+
+    let store = app_state
+        .object_store
+        .as_ref()
+        .ok_or(ReplayError::StorageUnavailable)?;
+    let mut recording = ReplayRecording::from_object_store(store.as_ref(), &request.s3_key).await?;
+    if let Some(reference) = request.reference_transcript {
+        recording.reference = Some(reference.trim().to_string());
+    }
+    replay(
+        &recording,
+        &request.session,
+        request.speed,
+        &app_state.config,
+        &app_state.plugin_registry,
+        &job.cancel,
+    )
+    .await
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(channels: u16, samples: &[i16]) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    fn request(s3_key: &str) -> ReplayRequest {
+        serde_json::from_value(serde_json::json!({
+            "s3_key": s3_key,
+            "session": {
+                "stt_config": {
+                    "provider": "deepgram",
+                    "language": "en-US",
+                    "sample_rate": 16000,
+                    "channels": 1,
+                    "punctuation": true,
+                    "encoding": "linear16",
+                    "model": "nova-3"
+                },
+                "tts_config": {"provider": "deepgram", "model": "aura-asteria-en"}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_word_error_rate() {
+        let reference = "I'd like to move my appointment to Friday";
+        assert_eq!(
+            word_error_rate(reference, "i'd like to move my appointment to friday."),
+            0.0
+        );
+        // One substitution out of eight words
+        let wer = word_error_rate(reference, "I'd like to move my appointment to Sunday");
+        assert!((wer - 1.0 / 8.0).abs() < 1e-9);
+        // Two insertions
+        let wer = word_error_rate(
+            reference,
+            "I'd like to move my appointment to Friday please thanks",
+        );
+        assert!((wer - 2.0 / 8.0).abs() < 1e-9);
+        assert_eq!(word_error_rate(reference, ""), 1.0);
+        assert_eq!(word_error_rate("", ""), 0.0);
+        assert_eq!(word_error_rate("", "hello"), 1.0);
+    }
+
+    #[test]
+    fn test_wav_decoding() {
+        let audio = ReplayAudio::from_wav(&wav(1, &[1, -2, 3])).unwrap();
+        assert_eq!(audio.sample_rate, 16000);
+        assert_eq!(&audio.pcm[..], &[1, 0, 254, 255, 3, 0]);
+
+        // Stereo frames are averaged
+        let audio = ReplayAudio::from_wav(&wav(2, &[100, 300, -50, -150])).unwrap();
+        assert_eq!(
+            &audio.pcm[..],
+            &[200i16.to_le_bytes(), (-100i16).to_le_bytes()].concat()[..]
+        );
+
+        let err = ReplayAudio::from_wav(b"OggS\0\x02rest of an opus stream").unwrap_err();
+        assert!(err.contains("Ogg"));
+        assert!(ReplayAudio::from_wav(&wav(1, &[])).is_err());
+        assert!(ReplayAudio::from_wav(b"not audio").is_err());
+    }
+
+    #[test]
+    fn test_request_validation() {
+        assert!(request("recordings/call-1/audio.wav").validate().is_ok());
+        assert!(request("").validate().is_err());
+        assert!(request("recordings/../secrets").validate().is_err());
+    }
+
+    #[test]
+    fn test_job_bookkeeping() {
+        let jobs = ReplayJobs::new(1);
+        let first = jobs.insert(&request("a.wav"));
+        let second = jobs.insert(&request("b.wav"));
+        let first_id = first.info.read().id.clone();
+        let second_id = second.info.read().id.clone();
+
+        let listed: Vec<String> = jobs.list().into_iter().map(|job| job.id).collect();
+        assert_eq!(listed, vec![first_id.clone(), second_id.clone()]);
+        assert_eq!(jobs.get(&first_id).unwrap().status, ReplayJobStatus::Queued);
+
+        // Cancelling an active job only signals it
+        let cancelled = jobs.cancel(&first_id).unwrap();
+        assert_eq!(cancelled.status, ReplayJobStatus::Queued);
+        assert!(first.cancel.is_cancelled());
+        first.finish(Err(ReplayError::Cancelled));
+        assert_eq!(
+            jobs.get(&first_id).unwrap().status,
+            ReplayJobStatus::Cancelled
+        );
+
+        // Cancelling a finished job forgets it
+        jobs.cancel(&first_id).unwrap();
+        assert!(jobs.get(&first_id).is_none());
+        assert!(jobs.cancel("unknown").is_none());
+
+        second.finish(Err(ReplayError::StorageUnavailable));
+        let failed = jobs.get(&second_id).unwrap();
+        assert_eq!(failed.status, ReplayJobStatus::Failed);
+        assert_eq!(
+            failed.error.as_deref(),
+            Some("Recording storage not configured")
+        );
+        assert!(failed.finished_at.is_some());
+    }
+
+    #[test]
+    fn test_prune_keeps_recent_finished_jobs() {
+        let jobs = ReplayJobs::new(1);
+        let active = jobs.insert(&request("active.wav"));
+        let mut finished = Vec::new();
+        for i in 0..MAX_FINISHED_JOBS + 2 {
+            let job = jobs.insert(&request(&format!("{i}.wav")));
+            job.finish(Err(ReplayError::Cancelled));
+            finished.push(job.info.read().id.clone());
+        }
+
+        jobs.prune();
+        assert_eq!(jobs.list().len(), MAX_FINISHED_JOBS + 1);
+        assert!(jobs.get(&active.info.read().id).is_some());
+        assert!(jobs.get(&finished[0]).is_none());
+        assert!(jobs.get(&finished[1]).is_none());
+        assert!(jobs.get(&finished[2]).is_some());
+    }
+}
@@ -0,0 +1,175 @@
+use axum::{
+    Router,
+    http::Method,
+    routing::{get, post},
+};
+use std::sync::Arc;
+use tower_http::trace::TraceLayer;
+
+use crate::config::AdminScope;
+use crate::handlers::{
+    admin_audit, agents, feature_flags, flight_logs, load_shedding, providers, reconciliation,
+    replay, webhooks,
+};
+use crate::state::AppState;
+
+/// Create the admin router for privileged endpoints
+///
+/// Note: Both `auth_middleware` and `admin_auth_middleware` should be applied
+/// in main.rs, with `auth_middleware` as the outer layer. The scope each
+/// route requires is given by [`required_scope`].
+pub fn create_admin_router() -> Router<Arc<AppState>> {
+    let router = Router::new()
+        .route("/admin/audit", get(admin_audit::get_admin_audit))
+        .route(
+            "/providers/{provider_type}/{name}/validate_credentials",
+            post(providers::validate_credentials),
+        )
+        .route(
+            "/admin/agents",
+            get(agents::list_agents).post(agents::create_agent),
+        )
+        .route(
+            "/admin/agents/{name}",
+            get(agents::get_agent)
+                .put(agents::update_agent)
+                .delete(agents::delete_agent),
+        )
+        .route(
+            "/admin/load_shedding",
+            get(load_shedding::get_load_shedding).put(load_shedding::update_load_shedding),
+        )
+        .route(
+            "/admin/feature_flags",
+            get(feature_flags::get_feature_flags).put(feature_flags::update_feature_flags),
+        )
+        .route(
+            "/admin/replay",
+            get(replay::list_replay_jobs).post(replay::create_replay_job),
+        )
+        .route(
+            "/admin/replay/{job_id}",
+            get(replay::get_replay_job).delete(replay::cancel_replay_job),
+        )
+        .route(
+            "/admin/reconciliation",
+            get(reconciliation::get_reconciliation),
+        )
+        .route(
+            "/admin/webhooks/pending",
+            get(webhooks::get_pending_webhooks),
+        )
+        .route("/admin/webhooks/replay", post(webhooks::replay_webhooks))
+        .route(
+            "/admin/sessions/{stream_id}/flight_log",
+            get(flight_logs::get_flight_log),
+        );
+
+    #[cfg(feature = "chaos")]
+    let router = router.route(
+        "/admin/chaos",
+        get(crate::handlers::chaos::get_chaos).put(crate::handlers::chaos::update_chaos),
+    );
+
+    router.layer(TraceLayer::new_for_http())
+}
+
+/// Scope an admin route requires
+///
+/// `route` is the matched route pattern, e.g. `/admin/agents/{name}`. Reads
+/// need `admin:read`; so does validating provider credentials, which changes
+/// nothing. Changing fault injection needs `admin:chaos`, and every other
+/// call, including routes not listed here, needs `admin:write`.
+pub fn required_scope(method: &Method, route: &str) -> AdminScope {
+    match (method, route) {
+        (&Method::GET | &Method::HEAD, _) => AdminScope::Read,
+        (&Method::POST, "/providers/{provider_type}/{name}/validate_credentials") => {
+            AdminScope::Read
+        }
+        (_, "/admin/chaos") => AdminScope::Chaos,
+        _ => AdminScope::Write,
+    }
+}
+
+/// OpenAPI paths served by [`create_admin_router`]
+///
+/// The chaos endpoints are documented by [`ChaosRoutesDoc`].
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    admin_audit::get_admin_audit,
+    providers::validate_credentials,
+    agents::list_agents,
+    agents::get_agent,
+    agents::create_agent,
+    agents::update_agent,
+    agents::delete_agent,
+    load_shedding::get_load_shedding,
+    load_shedding::update_load_shedding,
+    feature_flags::get_feature_flags,
+    feature_flags::update_feature_flags,
+    replay::list_replay_jobs,
+    replay::create_replay_job,
+    replay::get_replay_job,
+    replay::cancel_replay_job,
+    reconciliation::get_reconciliation,
+    webhooks::get_pending_webhooks,
+    webhooks::replay_webhooks,
+    flight_logs::get_flight_log,
+))]
+pub struct AdminRoutesDoc;
+
+/// OpenAPI paths of the chaos fault injection endpoints (`chaos` feature)
+#[cfg(all(feature = "openapi", feature = "chaos"))]
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(crate::handlers::chaos::get_chaos, crate::handlers::chaos::update_chaos),
+    components(schemas(
+        crate::core::chaos::ChaosFaults,
+        crate::core::chaos::ChaosStatus,
+    )),
+    tags((name = "chaos", description = "Fault injection for resilience testing (admin only)"))
+)]
+pub struct ChaosRoutesDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope_per_route() {
+        let cases = [
+            (Method::GET, "/admin/audit", AdminScope::Read),
+            (
+                Method::POST,
+                "/providers/{provider_type}/{name}/validate_credentials",
+                AdminScope::Read,
+            ),
+            (Method::GET, "/admin/agents", AdminScope::Read),
+            (Method::POST, "/admin/agents", AdminScope::Write),
+            (Method::GET, "/admin/agents/{name}", AdminScope::Read),
+            (Method::PUT, "/admin/agents/{name}", AdminScope::Write),
+            (Method::DELETE, "/admin/agents/{name}", AdminScope::Write),
+            (Method::GET, "/admin/load_shedding", AdminScope::Read),
+            (Method::PUT, "/admin/load_shedding", AdminScope::Write),
+            (Method::GET, "/admin/feature_flags", AdminScope::Read),
+            (Method::PUT, "/admin/feature_flags", AdminScope::Write),
+            (Method::GET, "/admin/replay", AdminScope::Read),
+            (Method::POST, "/admin/replay", AdminScope::Write),
+            (Method::GET, "/admin/replay/{job_id}", AdminScope::Read),
+            (Method::DELETE, "/admin/replay/{job_id}", AdminScope::Write),
+            (Method::GET, "/admin/reconciliation", AdminScope::Read),
+            (
+                Method::GET,
+                "/admin/sessions/{stream_id}/flight_log",
+                AdminScope::Read,
+            ),
+            (Method::GET, "/admin/chaos", AdminScope::Read),
+            (Method::PUT, "/admin/chaos", AdminScope::Chaos),
+            (Method::POST, "/admin/unknown", AdminScope::Write),
+        ];
+        for (method, route, scope) in cases {
+            assert_eq!(required_scope(&method, route), scope, "{method} {route}");
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use axum::{
     Router,
-    routing::{delete, get, post},
+    routing::{any, delete, get, post},
 };
 use tower_http::trace::TraceLayer;
 
-use crate::handlers::{dag, livekit, recording, sip, speak, voices};
+use crate::handlers::{
+    dag, livekit, plugin_routes, providers, recording, session_artifacts, session_events, sip,
+    speak, voices,
+};
 use crate::state::AppState;
 use std::sync::Arc;
 
@@ -17,12 +20,25 @@ pub fn create_api_router() -> Router<Arc<AppState>> {
         .route("/voices", get(voices::list_voices))
         .route("/voices/clone", post(voices::clone_voice))
         .route("/speak", post(speak::speak_handler))
+        .route("/providers", get(providers::list_providers))
         .route("/livekit/token", post(livekit::generate_token))
         .route("/livekit/rooms", get(livekit::list_rooms))
         .route("/livekit/rooms/{room_name}", get(livekit::get_room_details))
         .route("/livekit/participant", delete(livekit::remove_participant))
         .route("/livekit/participant/mute", post(livekit::mute_participant))
         .route("/recording/{stream_id}", get(recording::download_recording))
+        .route(
+            "/sessions/{stream_id}/events",
+            get(session_events::stream_session_events),
+        )
+        .route(
+            "/sessions/{stream_id}/artifacts",
+            get(session_artifacts::list_session_artifacts),
+        )
+        .route(
+            "/sessions/{stream_id}/monitor-key",
+            post(session_events::retrieve_monitor_key),
+        )
         // SIP hooks management
         .route(
             "/sip/hooks",
@@ -36,5 +52,55 @@ pub fn create_api_router() -> Router<Arc<AppState>> {
         .route("/dag/templates", get(dag::list_templates))
         .route("/dag/templates/{template_name}", get(dag::get_template))
         .route("/dag/validate", post(dag::validate_dag))
+        // Routes served by plugins, gated by the plugin:{plugin_id} scope
+        .route("/plugins/{plugin_id}", any(plugin_routes::plugin_route))
+        .route(
+            "/plugins/{plugin_id}/{*path}",
+            any(plugin_routes::plugin_route),
+        )
         .layer(TraceLayer::new_for_http())
 }
+
+/// OpenAPI paths served by [`create_api_router`]
+///
+/// The DAG endpoints are documented by [`DagRoutesDoc`]; without the
+/// `dag-routing` feature they are stubs answering `501 Not Implemented`.
+/// `/plugins/{plugin_id}` is the same plugin route with an empty path.
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    voices::list_voices,
+    voices::clone_voice,
+    speak::speak_handler,
+    providers::list_providers,
+    livekit::generate_token,
+    livekit::list_rooms,
+    livekit::get_room_details,
+    livekit::remove_participant,
+    livekit::mute_participant,
+    recording::download_recording,
+    session_events::stream_session_events,
+    session_artifacts::list_session_artifacts,
+    session_events::retrieve_monitor_key,
+    sip::list_sip_hooks,
+    sip::update_sip_hooks,
+    sip::delete_sip_hooks,
+    sip::sip_transfer,
+    plugin_routes::plugin_route,
+))]
+pub struct ApiRoutesDoc;
+
+/// OpenAPI paths of the DAG routing endpoints (`dag-routing` feature)
+#[cfg(all(feature = "openapi", feature = "dag-routing"))]
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(dag::list_templates, dag::get_template, dag::validate_dag),
+    components(schemas(
+        dag::ListTemplatesResponse,
+        dag::TemplateInfo,
+        dag::ValidateDAGRequest,
+        dag::ValidateDAGResponse,
+    )),
+    tags((name = "dag", description = "DAG routing templates and validation"))
+)]
+pub struct DagRoutesDoc;
@@ -0,0 +1,16 @@
+use axum::{Router, routing::get};
+use std::sync::Arc;
+
+use crate::handlers::console;
+use crate::state::AppState;
+
+/// Create the router for the development console
+///
+/// The console is a static page whose client authenticates on `/ws`, so
+/// this router should be merged without the auth middleware.
+pub fn create_console_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/console", get(console::console_index))
+        .route("/console/", get(console::console_index))
+        .route("/console/{file_name}", get(console::console_asset))
+}
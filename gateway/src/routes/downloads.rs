@@ -0,0 +1,25 @@
+use axum::{Router, routing::get};
+use std::sync::Arc;
+use tower_http::trace::TraceLayer;
+
+use crate::handlers::session_artifacts;
+use crate::state::AppState;
+
+/// Create the router for signed artifact downloads
+///
+/// Download URLs carry their own expiring signature in place of a bearer
+/// token, so this router should be merged without the auth middleware.
+pub fn create_download_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/downloads/{*key}",
+            get(session_artifacts::download_artifact),
+        )
+        .layer(TraceLayer::new_for_http())
+}
+
+/// OpenAPI paths served by [`create_download_router`]
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(session_artifacts::download_artifact))]
+pub struct DownloadRoutesDoc;
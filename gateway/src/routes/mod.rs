@@ -1,4 +1,9 @@
+pub mod admin;
 pub mod api;
+#[cfg(feature = "dev-console")]
+pub mod console;
+pub mod downloads;
+pub mod public;
 pub mod realtime;
 pub mod webhooks;
 pub mod ws;
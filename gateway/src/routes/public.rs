@@ -0,0 +1,30 @@
+use axum::{Router, routing::get};
+use std::sync::Arc;
+
+use crate::handlers::api;
+use crate::state::AppState;
+
+/// Create the router for public health, readiness, metrics and version endpoints
+///
+/// These routes are probed by load balancers and monitoring, so this router
+/// should be merged without the auth middleware.
+pub fn create_public_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(api::health_check))
+        .route("/readyz", get(api::readiness_check))
+        .route("/metrics", get(api::metrics))
+        .route("/health/providers", get(api::provider_health))
+        .route("/version", get(api::version))
+}
+
+/// OpenAPI paths served by [`create_public_router`]
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    api::health_check,
+    api::readiness_check,
+    api::metrics,
+    api::provider_health,
+    api::version,
+))]
+pub struct PublicRoutesDoc;
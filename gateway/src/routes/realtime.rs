@@ -49,3 +49,9 @@ pub fn create_realtime_router() -> Router<Arc<AppState>> {
         .route("/realtime", get(realtime_handler))
         .layer(TraceLayer::new_for_http())
 }
+
+/// OpenAPI paths served by [`create_realtime_router`]
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(crate::handlers::realtime::realtime_handler))]
+pub struct RealtimeRoutesDoc;
@@ -15,3 +15,9 @@ pub fn create_webhook_router() -> Router<Arc<AppState>> {
         .route("/livekit/webhook", post(livekit::handle_livekit_webhook))
         .layer(TraceLayer::new_for_http())
 }
+
+/// OpenAPI paths served by [`create_webhook_router`]
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(livekit::handle_livekit_webhook))]
+pub struct WebhookRoutesDoc;
@@ -31,3 +31,9 @@ pub fn create_ws_router() -> Router<Arc<AppState>> {
         .route("/ws", get(ws::ws_voice_handler))
         .layer(TraceLayer::new_for_http())
 }
+
+/// OpenAPI paths served by [`create_ws_router`]
+#[cfg(feature = "openapi")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(ws::handler::ws_voice_handler))]
+pub struct WsRoutesDoc;
@@ -0,0 +1,623 @@
+//! # Provider Self-Test
+//!
+//! Checks that the configured STT and TTS providers work end to end: the
+//! canary phrase from [`SelfTestConfig`] is synthesized with the TTS provider,
+//! the audio is transcribed with the STT provider, and the transcript is
+//! compared with the phrase by word-level similarity.
+//!
+//! The test runs once at startup and, with `interval_secs`, periodically after
+//! that. Each result is logged, recorded in the `waav_selftest_*` metrics and
+//! reported by `GET /readyz`. A failed run only makes the gateway unready when
+//! `required` is set; a run skipped because a provider has no credentials
+//! never does.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use parking_lot::RwLock;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::config::{SelfTestConfig, ServerConfig};
+use crate::core::stt::{STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback};
+use crate::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError};
+use crate::metrics::global_metrics;
+use crate::plugin::PluginRegistry;
+use crate::plugin::dispatch::{resolve_stt_provider, resolve_tts_provider};
+use crate::state::AppState;
+
+/// Audio streamed to the STT provider per `send_audio` call
+const CHUNK_DURATION_MS: usize = 100;
+
+/// Silence sent after the canary audio so providers finalize the utterance
+const TRAILING_SILENCE_MS: usize = 1000;
+
+/// How long the transcript may go without new final results before it is
+/// considered complete
+const TRANSCRIPT_SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Outcome of a self-test run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestStatus {
+    /// The first run has not finished yet
+    Pending,
+    /// The transcript matched the canary phrase
+    Passed,
+    /// A provider failed or the transcript did not match
+    Failed,
+    /// A provider has no credentials configured
+    Skipped,
+}
+
+impl SelfTestStatus {
+    /// Wire name of the status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+/// Result of the latest self-test run
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SelfTestReport {
+    /// Outcome of the run
+    pub status: SelfTestStatus,
+    /// STT provider the canary audio was transcribed with
+    pub stt_provider: String,
+    /// TTS provider the canary phrase was synthesized with
+    pub tts_provider: String,
+    /// Transcript returned by the STT provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
+    /// Word-level similarity between the phrase and the transcript (0.0 to 1.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// Why the run failed or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// How long the run took in milliseconds
+    pub duration_ms: u64,
+    /// Unix timestamp (seconds) at which the run finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+impl SelfTestReport {
+    fn new(config: &SelfTestConfig, status: SelfTestStatus) -> Self {
+        Self {
+            status,
+            stt_provider: config.stt_provider.clone(),
+            tts_provider: config.tts_provider.clone(),
+            transcript: None,
+            similarity: None,
+            message: None,
+            duration_ms: 0,
+            finished_at: None,
+        }
+    }
+
+    fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Holds the self-test configuration and the latest report
+pub struct SelfTestMonitor {
+    config: Option<SelfTestConfig>,
+    latest: RwLock<Option<SelfTestReport>>,
+}
+
+impl SelfTestMonitor {
+    /// Create a monitor; the self-test is disabled when `config` is None
+    pub fn new(config: Option<SelfTestConfig>) -> Self {
+        let latest = config
+            .as_ref()
+            .map(|config| SelfTestReport::new(config, SelfTestStatus::Pending));
+        Self {
+            config,
+            latest: RwLock::new(latest),
+        }
+    }
+
+    /// Self-test configuration, None when the self-test is disabled
+    pub fn config(&self) -> Option<&SelfTestConfig> {
+        self.config.as_ref()
+    }
+
+    /// Whether a passing run is required for readiness
+    pub fn is_required(&self) -> bool {
+        self.config.as_ref().is_some_and(|config| config.required)
+    }
+
+    /// Latest report (pending until the first run finishes), None when disabled
+    pub fn latest(&self) -> Option<SelfTestReport> {
+        self.latest.read().clone()
+    }
+
+    /// Whether the self-test currently keeps the gateway from being ready
+    ///
+    /// Only a required self-test blocks readiness, and only while its first
+    /// run is pending or after a failed run.
+    pub fn blocks_readiness(&self) -> bool {
+        self.is_required()
+            && self.latest.read().as_ref().is_some_and(|report| {
+                matches!(
+                    report.status,
+                    SelfTestStatus::Pending | SelfTestStatus::Failed
+                )
+            })
+    }
+
+    /// Log a finished run, update the metrics and make it the latest report
+    pub fn record(&self, report: SelfTestReport) {
+        let message = report.message.as_deref().unwrap_or_default();
+        match report.status {
+            SelfTestStatus::Passed => tracing::info!(
+                stt_provider = %report.stt_provider,
+                tts_provider = %report.tts_provider,
+                similarity = report.similarity.unwrap_or_default(),
+                duration_ms = report.duration_ms,
+                "Provider self-test passed"
+            ),
+            SelfTestStatus::Failed if self.is_required() => tracing::error!(
+                stt_provider = %report.stt_provider,
+                tts_provider = %report.tts_provider,
+                transcript = report.transcript.as_deref().unwrap_or_default(),
+                "Provider self-test failed, gateway is not ready: {message}"
+            ),
+            SelfTestStatus::Failed => tracing::warn!(
+                stt_provider = %report.stt_provider,
+                tts_provider = %report.tts_provider,
+                transcript = report.transcript.as_deref().unwrap_or_default(),
+                "Provider self-test failed: {message}"
+            ),
+            SelfTestStatus::Skipped | SelfTestStatus::Pending => {
+                tracing::info!("Provider self-test skipped: {message}")
+            }
+        }
+
+        record_metrics(&report);
+        *self.latest.write() = Some(report);
+    }
+}
+
+fn record_metrics(report: &SelfTestReport) {
+    let metrics = global_metrics();
+    metrics.inc_counter(
+        "waav_selftest_runs_total",
+        "Provider self-test runs by result",
+        &[("result", report.status.as_str())],
+    );
+    metrics.set_gauge(
+        "waav_selftest_passed",
+        "Whether the latest provider self-test passed (1) or not (0)",
+        &[],
+        if report.status == SelfTestStatus::Passed {
+            1.0
+        } else {
+            0.0
+        },
+    );
+    metrics.set_gauge(
+        "waav_selftest_duration_seconds",
+        "Duration of the latest provider self-test",
+        &[],
+        report.duration_ms as f64 / 1000.0,
+    );
+    if let Some(similarity) = report.similarity {
+        metrics.set_gauge(
+            "waav_selftest_similarity",
+            "Transcript similarity of the latest provider self-test",
+            &[],
+            similarity,
+        );
+    }
+    if let Some(finished_at) = report.finished_at {
+        metrics.set_gauge(
+            "waav_selftest_last_run_timestamp_seconds",
+            "Unix time the latest provider self-test finished",
+            &[],
+            finished_at as f64,
+        );
+    }
+}
+
+/// Start the self-test in the background if it is configured
+///
+/// Runs once immediately and then every `interval_secs`, recording each
+/// result in `app_state.selftest`.
+pub fn spawn_selftest(app_state: Arc<AppState>) -> Option<JoinHandle<()>> {
+    let config = app_state.selftest.config()?.clone();
+    Some(tokio::spawn(async move {
+        loop {
+            let report = run_selftest(&config, &app_state).await;
+            app_state.selftest.record(report);
+            match config.interval() {
+                Some(interval) => tokio::time::sleep(interval).await,
+                None => break,
+            }
+        }
+    }))
+}
+
+/// Run the self-test once
+///
+/// Never fails: provider errors, timeouts and a mismatching transcript are
+/// reported as [`SelfTestStatus::Failed`], missing credentials as
+/// [`SelfTestStatus::Skipped`]. Providers are created through the state's
+/// plugin registry.
+pub async fn run_selftest(config: &SelfTestConfig, app_state: &AppState) -> SelfTestReport {
+    let started = Instant::now();
+    let mut report = match tokio::time::timeout(config.timeout(), check(config, app_state)).await {
+        Ok(report) => report,
+        Err(_) => SelfTestReport::new(config, SelfTestStatus::Failed)
+            .with_message(format!("Timed out after {}s", config.timeout_secs)),
+    };
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    report.finished_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|now| now.as_secs());
+    report
+}
+
+async fn check(config: &SelfTestConfig, app_state: &AppState) -> SelfTestReport {
+    let server = &app_state.config;
+    let registry = &app_state.plugin_registry;
+    let tts_builtin = resolve_tts_provider(&config.tts_provider).is_some();
+    let tts_api_key = match provider_api_key(server, &config.tts_provider, tts_builtin) {
+        Ok(api_key) => api_key,
+        Err(e) => {
+            return SelfTestReport::new(config, SelfTestStatus::Skipped)
+                .with_message(format!("No credentials for TTS provider: {e}"));
+        }
+    };
+    let stt_builtin = resolve_stt_provider(&config.stt_provider).is_some();
+    let stt_api_key = match provider_api_key(server, &config.stt_provider, stt_builtin) {
+        Ok(api_key) => api_key,
+        Err(e) => {
+            return SelfTestReport::new(config, SelfTestStatus::Skipped)
+                .with_message(format!("No credentials for STT provider: {e}"));
+        }
+    };
+    let connect_timeout = Duration::from_secs(server.provider_connect_timeout_secs);
+
+    let (audio, sample_rate) =
+        match synthesize(registry, config, tts_api_key, connect_timeout).await {
+            Ok(synthesized) if !synthesized.0.is_empty() => synthesized,
+            Ok(_) => {
+                return SelfTestReport::new(config, SelfTestStatus::Failed)
+                    .with_message("TTS provider returned no audio");
+            }
+            Err(e) => {
+                return SelfTestReport::new(config, SelfTestStatus::Failed)
+                    .with_message(format!("Synthesis failed: {e}"));
+            }
+        };
+
+    let transcript = match transcribe(
+        registry,
+        config,
+        stt_api_key,
+        connect_timeout,
+        &audio,
+        sample_rate,
+    )
+    .await
+    {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            return SelfTestReport::new(config, SelfTestStatus::Failed)
+                .with_message(format!("Transcription failed: {e}"));
+        }
+    };
+
+    let similarity = transcript_similarity(&config.phrase, &transcript);
+    let mut report = if similarity >= config.min_similarity {
+        SelfTestReport::new(config, SelfTestStatus::Passed)
+    } else {
+        SelfTestReport::new(config, SelfTestStatus::Failed).with_message(format!(
+            "Transcript does not match the canary phrase (similarity {similarity:.2}, minimum {:.2})",
+            config.min_similarity
+        ))
+    };
+    report.transcript = Some(transcript);
+    report.similarity = Some(similarity);
+    report
+}
+
+/// API key for a provider from the server config
+///
+/// Built-in providers use their configured key and are skipped without one;
+/// plugin providers use `plugins.providers.<name>.api_key`, which is optional.
+pub(crate) fn provider_api_key(
+    server: &ServerConfig,
+    provider: &str,
+    builtin: bool,
+) -> Result<String, String> {
+    if builtin {
+        server.get_api_key(provider)
+    } else {
+        Ok(server
+            .plugins
+            .provider_api_key(provider)
+            .unwrap_or_default()
+            .to_string())
+    }
+}
+
+/// Events from the TTS provider while the canary phrase is synthesized
+enum CanaryEvent {
+    Audio(AudioData),
+    Error(TTSError),
+    Complete,
+}
+
+/// Audio callback forwarding TTS events to the self-test
+struct CanaryAudio {
+    events: mpsc::UnboundedSender<CanaryEvent>,
+}
+
+impl AudioCallback for CanaryAudio {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = self.events.send(CanaryEvent::Audio(audio_data));
+        Box::pin(std::future::ready(()))
+    }
+
+    fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = self.events.send(CanaryEvent::Error(error));
+        Box::pin(std::future::ready(()))
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = self.events.send(CanaryEvent::Complete);
+        Box::pin(std::future::ready(()))
+    }
+}
+
+/// Synthesize the canary phrase, returning the audio and its sample rate
+async fn synthesize(
+    registry: &PluginRegistry,
+    config: &SelfTestConfig,
+    api_key: String,
+    connect_timeout: Duration,
+) -> Result<(Vec<u8>, u32), String> {
+    let defaults = TTSConfig::default();
+    let tts_config = TTSConfig {
+        provider: config.tts_provider.clone(),
+        api_key,
+        voice_id: config.tts_voice.clone().or(defaults.voice_id.clone()),
+        model: config.tts_model.clone().unwrap_or_default(),
+        audio_format: Some("linear16".to_string()),
+        sample_rate: Some(config.sample_rate),
+        ..defaults
+    };
+
+    let mut tts = registry
+        .create_tts(&config.tts_provider, tts_config)
+        .map_err(|e| e.to_string())?;
+    tts.connect_with_timeout(connect_timeout)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+    tts.on_audio(Arc::new(CanaryAudio { events: events_tx }))
+        .map_err(|e| e.to_string())?;
+
+    let mut audio = Vec::new();
+    let mut sample_rate = config.sample_rate;
+    let result = match tts.speak(&config.phrase, true).await {
+        Ok(()) => loop {
+            match events_rx.recv().await {
+                Some(CanaryEvent::Audio(chunk)) => {
+                    sample_rate = chunk.sample_rate;
+                    audio.extend_from_slice(&chunk.data);
+                }
+                Some(CanaryEvent::Error(e)) => break Err(e.to_string()),
+                Some(CanaryEvent::Complete) | None => break Ok(()),
+            }
+        },
+        Err(e) => Err(e.to_string()),
+    };
+
+    let _ = tts.disconnect().await;
+    result.map(|()| (audio, sample_rate))
+}
+
+/// Stream the canary audio to the STT provider and collect the final transcript
+async fn transcribe(
+    registry: &PluginRegistry,
+    config: &SelfTestConfig,
+    api_key: String,
+    connect_timeout: Duration,
+    audio: &[u8],
+    sample_rate: u32,
+) -> Result<String, String> {
+    let defaults = STTConfig::default();
+    let stt_config = STTConfig {
+        provider: config.stt_provider.clone(),
+        api_key,
+        language: config.language.clone(),
+        sample_rate,
+        channels: 1,
+        encoding: "linear16".to_string(),
+        model: config.stt_model.clone().unwrap_or(defaults.model.clone()),
+        ..defaults
+    };
+
+    let mut stt = registry
+        .create_stt(&config.stt_provider, stt_config)
+        .map_err(|e| e.to_string())?;
+
+    let (results_tx, mut results_rx) = mpsc::unbounded_channel::<Result<String, String>>();
+    let errors_tx = results_tx.clone();
+    let result_callback: STTResultCallback = Arc::new(move |result: STTResult| {
+        let results_tx = results_tx.clone();
+        Box::pin(async move {
+            if result.is_final {
+                let _ = results_tx.send(Ok(result.transcript));
+            }
+        }) as Pin<Box<dyn Future<Output = ()> + Send>>
+    });
+    let error_callback: STTErrorCallback = Arc::new(move |error: STTError| {
+        let errors_tx = errors_tx.clone();
+        Box::pin(async move {
+            let _ = errors_tx.send(Err(error.to_string()));
+        }) as Pin<Box<dyn Future<Output = ()> + Send>>
+    });
+    stt.on_result(result_callback)
+        .await
+        .map_err(|e| e.to_string())?;
+    stt.on_error(error_callback)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    stt.connect_with_timeout(connect_timeout)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 16-bit mono PCM
+    let bytes_per_ms = sample_rate as usize * 2 / 1000;
+    let chunk_size = (bytes_per_ms * CHUNK_DURATION_MS).max(2);
+    let silence = vec![0u8; bytes_per_ms * TRAILING_SILENCE_MS];
+    for chunk in audio.chunks(chunk_size).chain(silence.chunks(chunk_size)) {
+        if let Err(e) = stt.send_audio(Bytes::copy_from_slice(chunk)).await {
+            let _ = stt.disconnect().await;
+            return Err(e.to_string());
+        }
+    }
+    let _ = stt.finalize().await;
+
+    let mut transcript = String::new();
+    let result = loop {
+        match tokio::time::timeout(TRANSCRIPT_SETTLE_TIME, results_rx.recv()).await {
+            Ok(Some(Ok(text))) => {
+                let text = text.trim();
+                if !text.is_empty() {
+                    if !transcript.is_empty() {
+                        transcript.push(' ');
+                    }
+                    transcript.push_str(text);
+                }
+                if transcript_similarity(&config.phrase, &transcript) >= config.min_similarity {
+                    break Ok(());
+                }
+            }
+            Ok(Some(Err(e))) => break Err(e),
+            Ok(None) => break Ok(()),
+            // Settled once results stop arriving; keep waiting for the first one
+            Err(_) if !transcript.is_empty() => break Ok(()),
+            Err(_) => {}
+        }
+    };
+
+    let _ = stt.disconnect().await;
+    result.map(|()| transcript)
+}
+
+/// Word-level similarity between the expected phrase and a transcript
+///
+/// Both are lowercased and stripped of punctuation, then compared by word
+/// edit distance: `1.0 - distance / max(word count)`. Two empty texts are
+/// identical.
+pub fn transcript_similarity(expected: &str, transcript: &str) -> f64 {
+    let expected = normalized_words(expected);
+    let transcript = normalized_words(transcript);
+    let longest = expected.len().max(transcript.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    1.0 - word_edit_distance(&expected, &transcript) as f64 / longest as f64
+}
+
+/// Levenshtein distance over words, computed one row at a time
+pub(crate) fn word_edit_distance(expected: &[String], transcript: &[String]) -> usize {
+    let mut previous: Vec<usize> = (0..=transcript.len()).collect();
+    for (i, expected_word) in expected.iter().enumerate() {
+        let mut current = vec![i + 1; transcript.len() + 1];
+        for (j, transcript_word) in transcript.iter().enumerate() {
+            let substitution = previous[j] + usize::from(expected_word != transcript_word);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[transcript.len()]
+}
+
+/// Lowercased words of a text with punctuation removed
+pub(crate) fn normalized_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_similarity() {
+        let phrase = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(transcript_similarity(phrase, phrase), 1.0);
+        assert_eq!(
+            transcript_similarity(phrase, "the quick brown fox, jumps over the lazy dog."),
+            1.0
+        );
+        // One substituted word out of nine
+        let similarity =
+            transcript_similarity(phrase, "The quick brown box jumps over the lazy dog");
+        assert!((similarity - 8.0 / 9.0).abs() < 1e-9);
+        // One missing word
+        let similarity = transcript_similarity(phrase, "The quick brown fox jumps over lazy dog");
+        assert!((similarity - 8.0 / 9.0).abs() < 1e-9);
+
+        assert_eq!(transcript_similarity(phrase, ""), 0.0);
+        assert_eq!(transcript_similarity("", ""), 1.0);
+        assert!(transcript_similarity(phrase, "something else entirely") < 0.2);
+    }
+
+    #[test]
+    fn test_monitor_readiness() {
+        let monitor = SelfTestMonitor::new(None);
+        assert!(monitor.latest().is_none());
+        assert!(!monitor.blocks_readiness());
+
+        let optional = SelfTestConfig::new("deepgram", "deepgram");
+        let monitor = SelfTestMonitor::new(Some(optional.clone()));
+        assert_eq!(monitor.latest().unwrap().status, SelfTestStatus::Pending);
+        monitor.record(SelfTestReport::new(&optional, SelfTestStatus::Failed));
+        assert!(!monitor.blocks_readiness());
+
+        let required = SelfTestConfig {
+            required: true,
+            ..optional
+        };
+        let monitor = SelfTestMonitor::new(Some(required.clone()));
+        assert!(monitor.blocks_readiness());
+        monitor.record(SelfTestReport::new(&required, SelfTestStatus::Failed));
+        assert!(monitor.blocks_readiness());
+        monitor.record(SelfTestReport::new(&required, SelfTestStatus::Passed));
+        assert!(!monitor.blocks_readiness());
+        monitor.record(SelfTestReport::new(&required, SelfTestStatus::Skipped));
+        assert!(!monitor.blocks_readiness());
+    }
+}
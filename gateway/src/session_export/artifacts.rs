@@ -0,0 +1,259 @@
+//! The files exported for a session
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::realtime::TranscriptRole;
+use crate::core::session::{SPOKEN_MS_PER_WORD, Speaker, TranscriptEntry};
+
+/// Shortest time a subtitle cue is shown (ms)
+const MIN_CUE_MS: u64 = 1000;
+
+/// Longest time a subtitle cue is shown (ms)
+const MAX_CUE_MS: u64 = 10_000;
+
+/// A file stored in a session's folder, next to its recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// Audio recording of the room (`audio.ogg`)
+    Recording,
+    /// The bot's TTS audio on its own (`tts.ogg`)
+    TtsTrack,
+    /// The caller audio the gateway received (`input.wav`)
+    InputAudio,
+    /// The session's transcript as JSON (`transcript.json`)
+    TranscriptJson,
+    /// The session's transcript as SubRip subtitles (`transcript.srt`)
+    TranscriptSrt,
+    /// The session's transcript as WebVTT subtitles (`transcript.vtt`)
+    TranscriptVtt,
+    /// The session's usage record (`usage.json`)
+    Usage,
+    /// The stretches the session's recordings were redacted for (`redactions.json`)
+    Redactions,
+}
+
+impl ArtifactKind {
+    /// Every artifact, in the order they are listed
+    pub const ALL: [ArtifactKind; 8] = [
+        ArtifactKind::Recording,
+        ArtifactKind::TtsTrack,
+        ArtifactKind::InputAudio,
+        ArtifactKind::TranscriptJson,
+        ArtifactKind::TranscriptSrt,
+        ArtifactKind::TranscriptVtt,
+        ArtifactKind::Usage,
+        ArtifactKind::Redactions,
+    ];
+
+    /// Name of the artifact's file in the session's folder
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ArtifactKind::Recording => "audio.ogg",
+            ArtifactKind::TtsTrack => "tts.ogg",
+            ArtifactKind::InputAudio => "input.wav",
+            ArtifactKind::TranscriptJson => "transcript.json",
+            ArtifactKind::TranscriptSrt => "transcript.srt",
+            ArtifactKind::TranscriptVtt => "transcript.vtt",
+            ArtifactKind::Usage => "usage.json",
+            ArtifactKind::Redactions => "redactions.json",
+        }
+    }
+
+    /// Content type the artifact is downloaded with
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ArtifactKind::Recording | ArtifactKind::TtsTrack => "audio/ogg",
+            ArtifactKind::InputAudio => "audio/wav",
+            ArtifactKind::TranscriptJson | ArtifactKind::Usage | ArtifactKind::Redactions => {
+                "application/json"
+            }
+            ArtifactKind::TranscriptSrt => "application/x-subrip",
+            ArtifactKind::TranscriptVtt => "text/vtt",
+        }
+    }
+
+    /// The artifact stored under `file_name`, if any
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.file_name() == file_name)
+    }
+}
+
+/// Render a transcript as SubRip subtitles
+///
+/// Cue times are relative to `origin_ms`, the Unix time the session (and
+/// its recording) started. Each entry is shown from when it was recorded
+/// until the next entry, for at most [`MAX_CUE_MS`] and no less than
+/// [`MIN_CUE_MS`] unless the next entry follows sooner. Entries with a
+/// language are labelled with it (`User (hi): ...`).
+pub fn render_srt(transcript: &[TranscriptEntry], origin_ms: u64) -> String {
+    let mut srt = String::new();
+    for (index, (start, end, entry)) in cues(transcript, origin_ms).into_iter().enumerate() {
+        let _ = write!(
+            srt,
+            "{}\n{} --> {}\n{}",
+            index + 1,
+            cue_time(start, ','),
+            cue_time(end, ','),
+            speaker_label(entry),
+        );
+        if let Some(language) = &entry.language {
+            let _ = write!(srt, " ({language})");
+        }
+        let _ = write!(srt, ": {}\n\n", entry.text.trim());
+    }
+    srt
+}
+
+/// Render a transcript as WebVTT subtitles
+///
+/// Cues are timed as in [`render_srt`]. The speaker is a voice span and an
+/// entry's language a language span (`<v User><lang hi>...</lang>`).
+pub fn render_vtt(transcript: &[TranscriptEntry], origin_ms: u64) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for (index, (start, end, entry)) in cues(transcript, origin_ms).into_iter().enumerate() {
+        let text = vtt_escape(entry.text.trim());
+        let _ = write!(
+            vtt,
+            "{}\n{} --> {}\n<v {}>",
+            index + 1,
+            cue_time(start, '.'),
+            cue_time(end, '.'),
+            speaker_label(entry),
+        );
+        let _ = match &entry.language {
+            Some(language) => write!(vtt, "<lang {}>{text}</lang>\n\n", vtt_escape(language)),
+            None => write!(vtt, "{text}\n\n"),
+        };
+    }
+    vtt
+}
+
+/// Start, end (ms from `origin_ms`) and entry of each subtitle cue
+fn cues(transcript: &[TranscriptEntry], origin_ms: u64) -> Vec<(u64, u64, &TranscriptEntry)> {
+    let mut cues = Vec::new();
+    let mut entries = transcript
+        .iter()
+        .filter(|entry| !entry.text.trim().is_empty())
+        .peekable();
+    while let Some(entry) = entries.next() {
+        let start = entry.timestamp.saturating_sub(origin_ms);
+        let words = entry.text.split_whitespace().count() as u64;
+        let mut end = start + (words * SPOKEN_MS_PER_WORD).clamp(MIN_CUE_MS, MAX_CUE_MS);
+        if let Some(next) = entries.peek() {
+            let next_start = next.timestamp.saturating_sub(origin_ms);
+            if next_start > start {
+                end = end.min(next_start);
+            }
+        }
+        cues.push((start, end, entry));
+    }
+    cues
+}
+
+/// `HH:MM:SS,mmm` (SubRip) or `HH:MM:SS.mmm` (WebVTT)
+fn cue_time(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Escape the characters WebVTT cue text reserves
+fn vtt_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Diarized speaker of the entry, or its role
+fn speaker_label(entry: &TranscriptEntry) -> &'static str {
+    match (entry.speaker, entry.role) {
+        (Some(Speaker::Bot), _) => "Bot",
+        (Some(Speaker::Caller), _) => "Caller",
+        (None, TranscriptRole::User) => "User",
+        (None, TranscriptRole::Assistant) => "Assistant",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(role: TranscriptRole, text: &str, timestamp: u64) -> TranscriptEntry {
+        TranscriptEntry {
+            timestamp,
+            ..TranscriptEntry::new(role, text, None)
+        }
+    }
+
+    #[test]
+    fn test_artifact_file_names() {
+        for kind in ArtifactKind::ALL {
+            assert_eq!(ArtifactKind::from_file_name(kind.file_name()), Some(kind));
+        }
+        assert_eq!(ArtifactKind::from_file_name("secrets.env"), None);
+        assert_eq!(
+            serde_json::to_string(&ArtifactKind::TranscriptSrt).unwrap(),
+            "\"transcript_srt\""
+        );
+    }
+
+    #[test]
+    fn test_render_srt() {
+        let origin = 1_700_000_000_000;
+        let transcript = vec![
+            entry(TranscriptRole::User, "Hi there", origin + 1_200),
+            entry(TranscriptRole::Assistant, " ", origin + 1_500),
+            entry(
+                TranscriptRole::Assistant,
+                "Hello, how can I help you today?",
+                origin + 1_800,
+            ),
+            entry(TranscriptRole::User, "I need a refund", origin + 3_661_000),
+        ];
+        let srt = render_srt(&transcript, origin);
+        assert_eq!(
+            srt,
+            "1\n00:00:01,200 --> 00:00:01,800\nUser: Hi there\n\n\
+             2\n00:00:01,800 --> 00:00:04,600\nAssistant: Hello, how can I help you today?\n\n\
+             3\n01:01:01,000 --> 01:01:02,600\nUser: I need a refund\n\n"
+        );
+
+        let mut labelled = transcript[0].clone();
+        labelled.speaker = Some(Speaker::Caller);
+        assert!(render_srt(&[labelled], origin).contains("Caller: Hi there"));
+        assert_eq!(render_srt(&[], origin), "");
+
+        let spoken = transcript[3].clone().with_language("hi");
+        assert!(render_srt(&[spoken], origin).contains("User (hi): I need a refund"));
+    }
+
+    #[test]
+    fn test_render_vtt() {
+        let origin = 1_700_000_000_000;
+        let transcript = vec![
+            entry(TranscriptRole::User, "kal meeting hai", origin + 1_200).with_language("hi"),
+            entry(
+                TranscriptRole::Assistant,
+                "Sure, 3 < 4 & noon?",
+                origin + 3_000,
+            ),
+        ];
+        assert_eq!(
+            render_vtt(&transcript, origin),
+            "WEBVTT\n\n\
+             1\n00:00:01.200 --> 00:00:02.400\n<v User><lang hi>kal meeting hai</lang>\n\n\
+             2\n00:00:03.000 --> 00:00:05.000\n<v Assistant>Sure, 3 &lt; 4 &amp; noon?\n\n"
+        );
+        assert_eq!(render_vtt(&[], origin), "WEBVTT\n\n");
+    }
+}
@@ -0,0 +1,772 @@
+//! Export of finished sessions and their download URLs
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::http::Method;
+use object_store::aws::AmazonS3;
+use object_store::local::LocalFileSystem;
+use object_store::signer::Signer;
+use object_store::{Error as ObjectStoreError, ObjectStore, path::Path as ObjectPath};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::artifacts::{ArtifactKind, render_srt, render_vtt};
+use super::signing::gateway_download_url;
+use crate::config::SessionExportConfig;
+use crate::core::redaction::RedactionWindow;
+use crate::core::session::{
+    ConsentRecord, LanguageSegment, LanguageTalkTime, TranscriptEntry, language_segments,
+    language_talk_time,
+};
+use crate::handlers::recording::session_object_key;
+use crate::state::SessionMetadata;
+use crate::usage::UsageRecord;
+use crate::utils::webhook_signing::generate_webhook_signature;
+use crate::webhooks::{SESSION_EXPORT_DESTINATION, WebhookDispatcher};
+
+/// Webhook event listing a finished session's artifacts
+pub const SESSION_ENDED_EVENT: &str = "session.ended";
+
+/// Timeout for a `session.ended` webhook request
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors raised while exporting a session
+#[derive(Debug, Error)]
+pub enum SessionExportError {
+    #[error("Session export storage unavailable: {0}")]
+    Storage(String),
+
+    #[error("Failed to serialize session export: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("Session webhook failed: {0}")]
+    Webhook(String),
+}
+
+/// What a finished session leaves to export
+#[derive(Debug, Clone)]
+pub struct FinishedSession {
+    /// Stream ID of the session
+    pub session_id: String,
+    /// Tenant the session belongs to
+    pub auth_id: Option<String>,
+    /// When the session and its recording started (Unix ms)
+    pub started_at: u64,
+    /// Transcript exported before the session was closed
+    pub transcript: Vec<TranscriptEntry>,
+    /// The session's usage record, if one was made
+    pub usage: Option<UsageRecord>,
+    /// Whether the session's recording was stopped and stored
+    pub recorded: bool,
+    /// Stretches the session's recordings were redacted for
+    pub redactions: Vec<RedactionWindow>,
+    /// The caller's recording consent, for sessions with a consent gate
+    pub consent: Option<ConsentRecord>,
+    /// The session's metadata as it stood when the session ended
+    pub metadata: SessionMetadata,
+}
+
+/// Download URLs of a session's artifacts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ArtifactLinks {
+    /// When the URLs stop working (Unix seconds)
+    pub expires_at: u64,
+    /// URL of each stored artifact
+    pub artifacts: BTreeMap<ArtifactKind, String>,
+}
+
+/// Body of the `session.ended` webhook
+///
+/// # Example JSON
+/// ```json
+/// {
+///   "event": "session.ended",
+///   "event_id": "0d9c4f7e-...",
+///   "session_id": "call-1234",
+///   "auth_id": "project1",
+///   "ended_at": 1700000065000,
+///   "metadata": { "customer_id": "c-123", "language_pack": "es" },
+///   "expires_at": 1700003665,
+///   "artifacts": {
+///     "recording": "https://bucket.s3.amazonaws.com/project1/call-1234/audio.ogg?X-Amz-...",
+///     "transcript_json": "https://...",
+///     "transcript_srt": "https://...",
+///     "transcript_vtt": "https://...",
+///     "usage": "https://..."
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionEnded {
+    /// Always `session.ended`
+    pub event: String,
+    /// Unique ID of this event (`X-WaaV-Event-Id`)
+    pub event_id: String,
+    /// Stream ID of the session
+    pub session_id: String,
+    /// Tenant the session belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_id: Option<String>,
+    /// When the session was exported (Unix ms)
+    pub ended_at: u64,
+    /// The session's metadata, including the entries the gateway recorded
+    #[serde(default, skip_serializing_if = "SessionMetadata::is_empty")]
+    pub metadata: SessionMetadata,
+    /// Download URLs of the session's artifacts
+    #[serde(flatten)]
+    pub links: ArtifactLinks,
+}
+
+/// Transcript stored as `transcript.json`
+///
+/// The enriched transcript stored by transcript enrichment has the same
+/// `session_id` and `transcript` fields.
+#[derive(Serialize)]
+struct ExportedTranscript<'a> {
+    session_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth_id: Option<&'a str>,
+    transcript: &'a [TranscriptEntry],
+    /// Consecutive user entries in one language
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    language_segments: Vec<LanguageSegment>,
+    /// Talk time per language of the user entries
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    languages: Vec<LanguageTalkTime>,
+    /// Stretches the recordings hold silence for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recording_redactions: Vec<RedactionWindow>,
+    /// The caller's recording consent and when it was decided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recording_consent: Option<ConsentRecord>,
+}
+
+/// Redaction windows stored as `redactions.json`
+#[derive(Serialize)]
+struct ExportedRedactions<'a> {
+    session_id: &'a str,
+    /// When the session and its recording started (Unix ms)
+    started_at: u64,
+    redactions: &'a [RedactionWindow],
+}
+
+/// How download URLs are made
+enum UrlSigner {
+    /// Pre-signed URLs of the recording bucket
+    S3(AmazonS3),
+    /// Gateway URLs signed with the export's signing key
+    Gateway {
+        base_url: String,
+        signing_key: String,
+    },
+}
+
+/// Stores finished sessions' artifacts and hands out their download URLs
+pub struct SessionExporter {
+    store: Arc<dyn ObjectStore>,
+    signer: UrlSigner,
+    prefix: Option<String>,
+    url_expiry: Duration,
+    /// `transcript.json` is stored by the transcript enrichment workers
+    transcript_from_enrichment: bool,
+    webhook: Option<ExportWebhook>,
+}
+
+impl SessionExporter {
+    /// Create the exporter of a validated configuration
+    ///
+    /// # Arguments
+    /// * `config` - Expiry, local storage, signing key and webhook
+    /// * `recording_s3` - Recording bucket, used instead of `local_dir` when present
+    /// * `prefix` - Key prefix of the recordings
+    /// * `enrichment_enabled` - Whether transcript enrichment stores `transcript.json`
+    ///
+    /// # Errors
+    /// Returns `SessionExportError::Storage` if there is neither a bucket nor
+    /// a usable `local_dir`, and `SessionExportError::Webhook` if the HTTP
+    /// client cannot be built
+    pub fn new(
+        config: &SessionExportConfig,
+        recording_s3: Option<AmazonS3>,
+        prefix: Option<String>,
+        enrichment_enabled: bool,
+    ) -> Result<Self, SessionExportError> {
+        let (store, signer, transcript_from_enrichment): (Arc<dyn ObjectStore>, _, _) =
+            match (recording_s3, &config.local_dir) {
+                (Some(s3), _) => (Arc::new(s3.clone()), UrlSigner::S3(s3), enrichment_enabled),
+                (None, Some(dir)) => {
+                    let (Some(base_url), Some(signing_key)) =
+                        (&config.public_base_url, &config.signing_key)
+                    else {
+                        return Err(SessionExportError::Storage(
+                            "local_dir needs public_base_url and signing_key".to_string(),
+                        ));
+                    };
+                    std::fs::create_dir_all(dir).map_err(|e| {
+                        SessionExportError::Storage(format!("{}: {e}", dir.display()))
+                    })?;
+                    let store = LocalFileSystem::new_with_prefix(dir)
+                        .map_err(|e| SessionExportError::Storage(e.to_string()))?;
+                    let signer = UrlSigner::Gateway {
+                        base_url: base_url.clone(),
+                        signing_key: signing_key.clone(),
+                    };
+                    // Enrichment only stores transcripts in the recording bucket
+                    (Arc::new(store), signer, false)
+                }
+                (None, None) => {
+                    return Err(SessionExportError::Storage(
+                        "no recording bucket or local_dir configured".to_string(),
+                    ));
+                }
+            };
+
+        let webhook = match (&config.webhook_url, &config.webhook_secret) {
+            (Some(url), Some(secret)) => {
+                let client = reqwest::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()
+                    .map_err(|e| SessionExportError::Webhook(e.to_string()))?;
+                Some(ExportWebhook {
+                    client,
+                    url: url.clone(),
+                    secret: secret.clone(),
+                    queue: None,
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            store,
+            signer,
+            prefix,
+            url_expiry: Duration::from_secs(config.url_expiry_secs),
+            transcript_from_enrichment,
+            webhook,
+        })
+    }
+
+    /// Deliver `session.ended` events through the durable webhook queue
+    ///
+    /// Events are queued for the `session_export` destination instead of
+    /// being POSTed inline. Does nothing without a webhook.
+    pub fn with_webhook_queue(mut self, queue: Arc<WebhookDispatcher>) -> Self {
+        if let Some(webhook) = &mut self.webhook {
+            queue.register(SESSION_EXPORT_DESTINATION, &webhook.url, &webhook.secret);
+            webhook.queue = Some(queue);
+        }
+        self
+    }
+
+    /// Store where the artifacts are kept
+    pub fn store(&self) -> &Arc<dyn ObjectStore> {
+        &self.store
+    }
+
+    /// Key gateway download URLs are signed with, when artifacts are served
+    /// by the gateway
+    pub fn gateway_signing_key(&self) -> Option<&str> {
+        match &self.signer {
+            UrlSigner::Gateway { signing_key, .. } => Some(signing_key),
+            UrlSigner::S3(_) => None,
+        }
+    }
+
+    /// Store a finished session's artifacts and announce them
+    ///
+    /// An artifact that cannot be stored is left out of the event.
+    ///
+    /// # Errors
+    /// Returns an error if the event cannot be serialized or the webhook
+    /// fails; the artifacts are stored either way
+    pub async fn export(
+        &self,
+        session: FinishedSession,
+    ) -> Result<SessionEnded, SessionExportError> {
+        let FinishedSession {
+            session_id,
+            auth_id,
+            started_at,
+            transcript,
+            usage,
+            recorded,
+            redactions,
+            consent,
+            metadata,
+        } = session;
+
+        let mut stored = Vec::new();
+        if recorded {
+            stored.push(ArtifactKind::Recording);
+        }
+        if self.transcript_from_enrichment {
+            stored.push(ArtifactKind::TranscriptJson);
+        } else {
+            let body = serde_json::to_vec(&ExportedTranscript {
+                session_id: &session_id,
+                auth_id: auth_id.as_deref(),
+                transcript: &transcript,
+                language_segments: language_segments(&transcript),
+                languages: language_talk_time(&transcript),
+                recording_redactions: redactions.clone(),
+                recording_consent: consent,
+            })?;
+            self.put(
+                auth_id.as_deref(),
+                &session_id,
+                ArtifactKind::TranscriptJson,
+                body,
+                &mut stored,
+            )
+            .await;
+        }
+        let srt = render_srt(&transcript, started_at).into_bytes();
+        self.put(
+            auth_id.as_deref(),
+            &session_id,
+            ArtifactKind::TranscriptSrt,
+            srt,
+            &mut stored,
+        )
+        .await;
+        let vtt = render_vtt(&transcript, started_at).into_bytes();
+        self.put(
+            auth_id.as_deref(),
+            &session_id,
+            ArtifactKind::TranscriptVtt,
+            vtt,
+            &mut stored,
+        )
+        .await;
+        if let Some(usage) = &usage {
+            let body = serde_json::to_vec(usage)?;
+            self.put(
+                auth_id.as_deref(),
+                &session_id,
+                ArtifactKind::Usage,
+                body,
+                &mut stored,
+            )
+            .await;
+        }
+        if !redactions.is_empty() {
+            let body = serde_json::to_vec(&ExportedRedactions {
+                session_id: &session_id,
+                started_at,
+                redactions: &redactions,
+            })?;
+            self.put(
+                auth_id.as_deref(),
+                &session_id,
+                ArtifactKind::Redactions,
+                body,
+                &mut stored,
+            )
+            .await;
+        }
+
+        let links = self
+            .artifact_links(auth_id.as_deref(), &session_id, &stored)
+            .await;
+        let event = SessionEnded {
+            event: SESSION_ENDED_EVENT.to_string(),
+            event_id: Uuid::new_v4().to_string(),
+            session_id,
+            auth_id,
+            ended_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            metadata,
+            links,
+        };
+
+        if let Some(webhook) = &self.webhook {
+            let payload = serde_json::to_string(&event)?;
+            webhook
+                .post(&event.event_id, &event.session_id, payload)
+                .await?;
+        }
+        Ok(event)
+    }
+
+    /// Export a finished session from a spawned task
+    pub fn export_detached(self: &Arc<Self>, session: FinishedSession) {
+        let exporter = self.clone();
+        tokio::spawn(async move {
+            let session_id = session.session_id.clone();
+            match exporter.export(session).await {
+                Ok(event) => info!(
+                    session_id = %session_id,
+                    artifacts = event.links.artifacts.len(),
+                    "Session exported"
+                ),
+                Err(e) => warn!(session_id = %session_id, "Failed to export session: {}", e),
+            }
+        });
+    }
+
+    /// Fresh download URLs of the artifacts stored for a session
+    ///
+    /// # Arguments
+    /// * `auth_id` - Tenant the session belongs to
+    /// * `session_id` - Stream ID of the session
+    /// * `stored` - Artifacts known to be stored; the others are looked up
+    pub async fn artifact_links(
+        &self,
+        auth_id: Option<&str>,
+        session_id: &str,
+        stored: &[ArtifactKind],
+    ) -> ArtifactLinks {
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            + self.url_expiry.as_secs();
+
+        let mut artifacts = BTreeMap::new();
+        for kind in ArtifactKind::ALL {
+            let key = self.object_key(auth_id, session_id, kind);
+            let path = ObjectPath::from(key.as_str());
+            if !stored.contains(&kind) {
+                match self.store.head(&path).await {
+                    Ok(_) => {}
+                    Err(ObjectStoreError::NotFound { .. }) => continue,
+                    Err(e) => {
+                        warn!(key = %key, "Failed to look up session artifact: {}", e);
+                        continue;
+                    }
+                }
+            }
+            match self.download_url(&key, &path, expires_at).await {
+                Ok(url) => {
+                    artifacts.insert(kind, url);
+                }
+                Err(e) => warn!(key = %key, "Failed to sign artifact URL: {}", e),
+            }
+        }
+        ArtifactLinks {
+            expires_at,
+            artifacts,
+        }
+    }
+
+    fn object_key(&self, auth_id: Option<&str>, session_id: &str, kind: ArtifactKind) -> String {
+        session_object_key(self.prefix.as_ref(), auth_id, session_id, kind.file_name())
+    }
+
+    async fn put(
+        &self,
+        auth_id: Option<&str>,
+        session_id: &str,
+        kind: ArtifactKind,
+        body: Vec<u8>,
+        stored: &mut Vec<ArtifactKind>,
+    ) {
+        let key = self.object_key(auth_id, session_id, kind);
+        match self
+            .store
+            .put(&ObjectPath::from(key.as_str()), body.into())
+            .await
+        {
+            Ok(_) => stored.push(kind),
+            Err(e) => warn!(key = %key, "Failed to store session artifact: {}", e),
+        }
+    }
+
+    async fn download_url(
+        &self,
+        key: &str,
+        path: &ObjectPath,
+        expires_at: u64,
+    ) -> Result<String, String> {
+        match &self.signer {
+            UrlSigner::S3(s3) => s3
+                .signed_url(Method::GET, path, self.url_expiry)
+                .await
+                .map(String::from)
+                .map_err(|e| e.to_string()),
+            UrlSigner::Gateway {
+                base_url,
+                signing_key,
+            } => gateway_download_url(base_url, signing_key, key, expires_at)
+                .ok_or_else(|| format!("invalid public_base_url '{base_url}'")),
+        }
+    }
+}
+
+/// Webhook `session.ended` events are POSTed to
+struct ExportWebhook {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+    /// Durable queue the events are handed to instead, if configured
+    queue: Option<Arc<WebhookDispatcher>>,
+}
+
+impl ExportWebhook {
+    async fn post(
+        &self,
+        event_id: &str,
+        session_id: &str,
+        payload: String,
+    ) -> Result<(), SessionExportError> {
+        if let Some(queue) = &self.queue {
+            queue
+                .enqueue(
+                    SESSION_EXPORT_DESTINATION,
+                    event_id,
+                    Some(session_id),
+                    payload,
+                )
+                .await;
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let signing_headers =
+            generate_webhook_signature(&self.secret, timestamp, event_id, &payload)
+                .map_err(SessionExportError::Webhook)?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        for (key, value) in signing_headers {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| SessionExportError::Webhook(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SessionExportError::Webhook(format!(
+                "{} responded with {}",
+                self.url, status
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::realtime::TranscriptRole;
+    use crate::core::session::{ConsentSource, ConsentState};
+    use crate::session_export::verify_download;
+
+    const KEY: &str = "a-dedicated-key-of-at-least-32-characters";
+
+    fn local_exporter(dir: &std::path::Path) -> SessionExporter {
+        let config = SessionExportConfig {
+            local_dir: Some(dir.to_path_buf()),
+            public_base_url: Some("https://voice.example.com".to_string()),
+            signing_key: Some(KEY.to_string()),
+            ..Default::default()
+        };
+        config.validate().unwrap();
+        SessionExporter::new(&config, None, Some("recordings".to_string()), true).unwrap()
+    }
+
+    fn finished() -> FinishedSession {
+        FinishedSession {
+            session_id: "call-1".to_string(),
+            auth_id: Some("project1".to_string()),
+            started_at: 1_700_000_000_000,
+            transcript: vec![TranscriptEntry {
+                timestamp: 1_700_000_001_000,
+                ..TranscriptEntry::new(TranscriptRole::User, "Hello", None)
+            }],
+            usage: None,
+            recorded: false,
+            redactions: Vec::new(),
+            consent: None,
+            metadata: SessionMetadata::new(),
+        }
+    }
+
+    #[test]
+    fn test_exporter_needs_storage() {
+        let config = SessionExportConfig::default();
+        assert!(matches!(
+            SessionExporter::new(&config, None, None, false),
+            Err(SessionExportError::Storage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_export_stores_artifacts_and_signs_gateway_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let exporter = local_exporter(dir.path());
+        assert_eq!(exporter.gateway_signing_key(), Some(KEY));
+
+        let event = exporter.export(finished()).await.unwrap();
+        assert_eq!(event.event, SESSION_ENDED_EVENT);
+        assert_eq!(event.auth_id.as_deref(), Some("project1"));
+        // Enrichment only writes to the recording bucket, so the export
+        // stores transcript.json itself; no recording or usage was made
+        assert_eq!(
+            event.links.artifacts.keys().copied().collect::<Vec<_>>(),
+            vec![
+                ArtifactKind::TranscriptJson,
+                ArtifactKind::TranscriptSrt,
+                ArtifactKind::TranscriptVtt
+            ]
+        );
+
+        let srt =
+            std::fs::read_to_string(dir.path().join("recordings/project1/call-1/transcript.srt"))
+                .unwrap();
+        assert!(srt.contains("00:00:01,000 --> 00:00:02,000\nUser: Hello"));
+
+        let url = url::Url::parse(&event.links.artifacts[&ArtifactKind::TranscriptSrt]).unwrap();
+        assert_eq!(
+            url.path(),
+            "/downloads/recordings/project1/call-1/transcript.srt"
+        );
+        let query: BTreeMap<_, _> = url.query_pairs().into_owned().collect();
+        let expires: u64 = query["expires"].parse().unwrap();
+        assert_eq!(expires, event.links.expires_at);
+        assert!(
+            verify_download(
+                KEY,
+                "recordings/project1/call-1/transcript.srt",
+                expires,
+                &query["sig"],
+                expires - 1
+            )
+            .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_lists_redactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let exporter = local_exporter(dir.path());
+        let window = RedactionWindow {
+            start_ms: 1_700_000_002_000,
+            end_ms: 1_700_000_009_000,
+            source: crate::core::redaction::RedactionSource::Dtmf,
+        };
+        let event = exporter
+            .export(FinishedSession {
+                redactions: vec![window],
+                ..finished()
+            })
+            .await
+            .unwrap();
+        assert!(
+            event
+                .links
+                .artifacts
+                .contains_key(&ArtifactKind::Redactions)
+        );
+
+        let folder = dir.path().join("recordings/project1/call-1");
+        let redactions: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(folder.join("redactions.json")).unwrap())
+                .unwrap();
+        assert_eq!(redactions["started_at"], 1_700_000_000_000u64);
+        assert_eq!(redactions["redactions"][0]["source"], "dtmf");
+        let transcript: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(folder.join("transcript.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            transcript["recording_redactions"][0]["end_ms"],
+            1_700_000_009_000u64
+        );
+        assert!(transcript.get("recording_consent").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_export_records_consent() {
+        let dir = tempfile::tempdir().unwrap();
+        let exporter = local_exporter(dir.path());
+        exporter
+            .export(FinishedSession {
+                consent: Some(ConsentRecord {
+                    state: ConsentState::Granted,
+                    source: Some(ConsentSource::Dtmf),
+                    participant: Some("sip_caller".to_string()),
+                    timestamp: Some(1_700_000_003_000),
+                }),
+                ..finished()
+            })
+            .await
+            .unwrap();
+
+        let folder = dir.path().join("recordings/project1/call-1");
+        let transcript: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(folder.join("transcript.json")).unwrap())
+                .unwrap();
+        assert_eq!(transcript["recording_consent"]["state"], "granted");
+        assert_eq!(transcript["recording_consent"]["source"], "dtmf");
+        assert_eq!(
+            transcript["recording_consent"]["timestamp"],
+            1_700_000_003_000u64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_carries_session_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let exporter = local_exporter(dir.path());
+
+        let event = exporter.export(finished()).await.unwrap();
+        let json = serde_json::to_value(&event).unwrap();
+        assert!(json.get("metadata").is_none());
+
+        let metadata = SessionMetadata::from([
+            ("customer_id".to_string(), "c-123".to_string()),
+            ("recording_consent".to_string(), "granted".to_string()),
+        ]);
+        let event = exporter
+            .export(FinishedSession {
+                metadata: metadata.clone(),
+                ..finished()
+            })
+            .await
+            .unwrap();
+        assert_eq!(event.metadata, metadata);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["metadata"]["customer_id"], "c-123");
+        assert_eq!(json["metadata"]["recording_consent"], "granted");
+        let parsed: SessionEnded = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[tokio::test]
+    async fn test_artifact_links_list_stored_artifacts_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let exporter = local_exporter(dir.path());
+        let links = exporter
+            .artifact_links(Some("project1"), "call-1", &[])
+            .await;
+        assert!(links.artifacts.is_empty());
+
+        exporter.export(finished()).await.unwrap();
+        let links = exporter
+            .artifact_links(Some("project1"), "call-1", &[])
+            .await;
+        assert_eq!(links.artifacts.len(), 2);
+        // Another tenant's session of the same name has nothing
+        let links = exporter
+            .artifact_links(Some("project2"), "call-1", &[])
+            .await;
+        assert!(links.artifacts.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+//! # Session Export
+//!
+//! With [`SessionExportConfig`](crate::config::SessionExportConfig) set,
+//! every finished voice session is exported in the background:
+//!
+//! 1. its transcript (`transcript.json`, `transcript.srt` and
+//!    `transcript.vtt`), usage record (`usage.json`) and, if its recordings
+//!    were [redacted](crate::core::redaction), the redacted stretches
+//!    (`redactions.json`) are stored next to the session's recording
+//!    (`{prefix}/{auth_id}/{stream_id}/...`), in the recording bucket or,
+//!    without one, in the configured `local_dir`
+//! 2. a download URL expiring after `url_expiry_secs` is made for each of
+//!    the session's [artifacts](ArtifactKind): pre-signed S3 URLs for the
+//!    bucket, gateway URLs (`GET /downloads/{key}`) signed with the
+//!    dedicated `signing_key` for `local_dir`
+//! 3. the URLs are POSTed as a `session.ended` event to the configured
+//!    webhook, signed with the `X-WaaV-Signature` headers used for SIP hook
+//!    forwarding
+//!
+//! `GET /sessions/{stream_id}/artifacts` hands out fresh URLs later on.
+//! Artifacts that were not stored for a session are left out; the gateway
+//! records no separate TTS track itself, so `tts_track` is only listed when
+//! a `tts.ogg` was stored in the session's folder by other means, and
+//! `input_audio` once [recording upload](crate::recording_upload) has
+//! completed the session's `input.wav`.
+//! With transcript enrichment configured, `transcript.json` is the labelled
+//! transcript the enrichment worker stores shortly after the session ends.
+
+mod artifacts;
+mod exporter;
+mod signing;
+
+pub use artifacts::{ArtifactKind, render_srt, render_vtt};
+pub use exporter::{
+    ArtifactLinks, FinishedSession, SESSION_ENDED_EVENT, SessionEnded, SessionExportError,
+    SessionExporter,
+};
+pub use signing::{
+    DOWNLOAD_ROUTE_PREFIX, DownloadUrlError, gateway_download_url, sign_download, verify_download,
+};
@@ -0,0 +1,162 @@
+//! Signed, expiring gateway download URLs
+//!
+//! A gateway URL names the object it downloads and carries an `expires`
+//! Unix time (seconds) and a `sig` query parameter: the hex HMAC-SHA256 of
+//! `v1:{expires}:{object_key}` under the export's dedicated signing key.
+//! `GET /downloads/{object_key}` serves the object only while the URL has
+//! not expired and the signature matches.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Path the download route is served under
+pub const DOWNLOAD_ROUTE_PREFIX: &str = "downloads";
+
+/// Why a download URL was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum DownloadUrlError {
+    #[error("Download URL has expired")]
+    Expired,
+
+    #[error("Download URL signature is invalid")]
+    InvalidSignature,
+}
+
+fn mac(signing_key: &str, object_key: &str, expires: u64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(signing_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("v1:{expires}:{object_key}").as_bytes());
+    mac
+}
+
+/// Signature of a download URL for `object_key` valid until `expires`
+pub fn sign_download(signing_key: &str, object_key: &str, expires: u64) -> String {
+    hex::encode(
+        mac(signing_key, object_key, expires)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+/// Check a download URL's signature and expiry
+///
+/// The signature is compared in constant time.
+///
+/// # Arguments
+/// * `signing_key` - Key the URL was signed with
+/// * `object_key` - Object the URL downloads
+/// * `expires` - The URL's `expires` parameter (Unix seconds)
+/// * `signature` - The URL's `sig` parameter
+/// * `now` - Current Unix time in seconds
+pub fn verify_download(
+    signing_key: &str,
+    object_key: &str,
+    expires: u64,
+    signature: &str,
+    now: u64,
+) -> Result<(), DownloadUrlError> {
+    let signature = hex::decode(signature).map_err(|_| DownloadUrlError::InvalidSignature)?;
+    mac(signing_key, object_key, expires)
+        .verify_slice(&signature)
+        .map_err(|_| DownloadUrlError::InvalidSignature)?;
+    // Only a genuine expiry is reported as expired
+    if now >= expires {
+        return Err(DownloadUrlError::Expired);
+    }
+    Ok(())
+}
+
+/// Gateway URL downloading `object_key` until `expires`
+///
+/// Returns `None` if `base_url` is not a valid base URL.
+pub fn gateway_download_url(
+    base_url: &str,
+    signing_key: &str,
+    object_key: &str,
+    expires: u64,
+) -> Option<String> {
+    let mut url = url::Url::parse(base_url).ok()?;
+    url.path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .push(DOWNLOAD_ROUTE_PREFIX)
+        .extend(object_key.split('/'));
+    url.query_pairs_mut()
+        .append_pair("expires", &expires.to_string())
+        .append_pair("sig", &sign_download(signing_key, object_key, expires));
+    Some(url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "a-dedicated-key-of-at-least-32-characters";
+    const OBJECT: &str = "recordings/project1/call-1/transcript.srt";
+
+    #[test]
+    fn test_valid_signature_is_accepted_until_expiry() {
+        let sig = sign_download(KEY, OBJECT, 2_000);
+        assert_eq!(verify_download(KEY, OBJECT, 2_000, &sig, 1_999), Ok(()));
+        assert_eq!(
+            verify_download(KEY, OBJECT, 2_000, &sig, 2_000),
+            Err(DownloadUrlError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_tampered_urls_are_rejected() {
+        let sig = sign_download(KEY, OBJECT, 2_000);
+        // Extended expiry
+        assert_eq!(
+            verify_download(KEY, OBJECT, 9_000, &sig, 1_000),
+            Err(DownloadUrlError::InvalidSignature)
+        );
+        // Another tenant's object
+        assert_eq!(
+            verify_download(
+                KEY,
+                "recordings/project2/call-1/transcript.srt",
+                2_000,
+                &sig,
+                1_000
+            ),
+            Err(DownloadUrlError::InvalidSignature)
+        );
+        // Another key, a flipped digit, and no hex at all
+        let other = sign_download("another-key-of-at-least-32-characters", OBJECT, 2_000);
+        assert!(verify_download(KEY, OBJECT, 2_000, &other, 1_000).is_err());
+        let mut flipped = sig.clone().into_bytes();
+        flipped[0] = if flipped[0] == b'0' { b'1' } else { b'0' };
+        let flipped = String::from_utf8(flipped).unwrap();
+        assert!(verify_download(KEY, OBJECT, 2_000, &flipped, 1_000).is_err());
+        assert!(verify_download(KEY, OBJECT, 2_000, "not-hex", 1_000).is_err());
+        // A tampered, expired URL is not reported as merely expired
+        assert_eq!(
+            verify_download(KEY, OBJECT, 2_000, &other, 5_000),
+            Err(DownloadUrlError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_gateway_download_url() {
+        let url = gateway_download_url(
+            "https://voice.example.com/gateway/",
+            KEY,
+            "project 1/call-1/audio.ogg",
+            2_000,
+        )
+        .unwrap();
+        let sig = sign_download(KEY, "project 1/call-1/audio.ogg", 2_000);
+        assert_eq!(
+            url,
+            format!(
+                "https://voice.example.com/gateway/downloads/project%201/call-1/audio.ogg?expires=2000&sig={sig}"
+            )
+        );
+        assert!(gateway_download_url("not a url", KEY, OBJECT, 2_000).is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! Graceful shutdown on termination signals
+//!
+//! The server stops accepting connections when the process is asked to
+//! terminate, gives open connections [`DRAIN_TIMEOUT`] to finish and then
+//! shuts the plugins down. The signals are:
+//!
+//! - Unix: `SIGINT` (Ctrl+C) and `SIGTERM`
+//! - Windows: Ctrl+C, Ctrl+Break, and the console close and system shutdown
+//!   events (closing the console window, or Windows shutting down while the
+//!   gateway runs under a console host or service wrapper)
+//!
+//! Handlers are installed by [`ShutdownSignal::install`] before the server
+//! starts, so a signal that arrives during startup is not lost.
+
+use std::io;
+use std::time::Duration;
+
+/// How long open connections may take to finish after a shutdown signal
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Installed handlers for the signals that shut the gateway down
+pub struct ShutdownSignal {
+    #[cfg(unix)]
+    interrupt: tokio::signal::unix::Signal,
+    #[cfg(unix)]
+    terminate: tokio::signal::unix::Signal,
+    #[cfg(windows)]
+    ctrl_c: tokio::signal::windows::CtrlC,
+    #[cfg(windows)]
+    ctrl_break: tokio::signal::windows::CtrlBreak,
+    #[cfg(windows)]
+    ctrl_close: tokio::signal::windows::CtrlClose,
+    #[cfg(windows)]
+    ctrl_shutdown: tokio::signal::windows::CtrlShutdown,
+}
+
+impl ShutdownSignal {
+    /// Install the handlers
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    /// Returns an error if the operating system refuses a handler
+    #[cfg(unix)]
+    pub fn install() -> io::Result<Self> {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    /// Install the handlers
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    /// Returns an error if the operating system refuses a handler
+    #[cfg(windows)]
+    pub fn install() -> io::Result<Self> {
+        use tokio::signal::windows::{ctrl_break, ctrl_c, ctrl_close, ctrl_shutdown};
+
+        Ok(Self {
+            ctrl_c: ctrl_c()?,
+            ctrl_break: ctrl_break()?,
+            ctrl_close: ctrl_close()?,
+            ctrl_shutdown: ctrl_shutdown()?,
+        })
+    }
+
+    /// Install the handlers
+    ///
+    /// Only Ctrl+C is handled on this platform.
+    #[cfg(not(any(unix, windows)))]
+    pub fn install() -> io::Result<Self> {
+        Ok(Self {})
+    }
+
+    /// Wait for the first signal and return its name
+    #[cfg(unix)]
+    pub async fn recv(mut self) -> &'static str {
+        tokio::select! {
+            _ = self.interrupt.recv() => "SIGINT",
+            _ = self.terminate.recv() => "SIGTERM",
+        }
+    }
+
+    /// Wait for the first signal and return its name
+    #[cfg(windows)]
+    pub async fn recv(mut self) -> &'static str {
+        tokio::select! {
+            _ = self.ctrl_c.recv() => "Ctrl+C",
+            _ = self.ctrl_break.recv() => "Ctrl+Break",
+            _ = self.ctrl_close.recv() => "console close",
+            _ = self.ctrl_shutdown.recv() => "system shutdown",
+        }
+    }
+
+    /// Wait for the first signal and return its name
+    #[cfg(not(any(unix, windows)))]
+    pub async fn recv(self) -> &'static str {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => "Ctrl+C",
+            Err(_) => std::future::pending().await,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Audit log of mutating admin calls
+//!
+//! Every admin call that needs `admin:write` or `admin:chaos` is recorded
+//! with the acting client id and the response status, whether it was allowed
+//! or refused. Entries are written to the `audit` tracing target and the
+//! most recent ones are kept in memory for `GET /admin/audit`.
+
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::config::AdminScope;
+
+/// Audit entries kept in memory; older entries are dropped
+pub const ADMIN_AUDIT_LOG_SIZE: usize = 1_000;
+
+/// A mutating admin call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AdminAuditEntry {
+    /// Time of the call in milliseconds since epoch
+    pub timestamp_ms: u64,
+    /// Authenticated client id; absent when authentication is disabled
+    pub client_id: Option<String>,
+    /// Scope the route requires
+    pub scope: AdminScope,
+    pub method: String,
+    /// Request path, e.g. `/admin/agents/support`
+    pub path: String,
+    /// Response status; 403 when the client lacked the scope
+    pub status: u16,
+}
+
+/// Most recent mutating admin calls
+pub struct AdminAuditLog {
+    entries: Mutex<VecDeque<AdminAuditEntry>>,
+    capacity: usize,
+}
+
+impl Default for AdminAuditLog {
+    fn default() -> Self {
+        Self::new(ADMIN_AUDIT_LOG_SIZE)
+    }
+}
+
+impl AdminAuditLog {
+    /// Create a log keeping the last `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity.min(ADMIN_AUDIT_LOG_SIZE))),
+            capacity,
+        }
+    }
+
+    /// Record a call, stamping it with the current time
+    pub fn record(
+        &self,
+        client_id: Option<String>,
+        scope: AdminScope,
+        method: &str,
+        path: &str,
+        status: u16,
+    ) {
+        let entry = AdminAuditEntry {
+            timestamp_ms: now_ms(),
+            client_id,
+            scope,
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+        };
+        tracing::info!(
+            target: "audit",
+            client_id = ?entry.client_id,
+            scope = %entry.scope,
+            method = %entry.method,
+            path = %entry.path,
+            status = entry.status,
+            "Admin call"
+        );
+
+        let mut entries = self.entries.lock();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        if self.capacity > 0 {
+            entries.push_back(entry);
+        }
+    }
+
+    /// Recorded entries, oldest first
+    pub fn entries(&self) -> Vec<AdminAuditEntry> {
+        self.entries.lock().iter().cloned().collect()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_most_recent_entries() {
+        let log = AdminAuditLog::new(2);
+        log.record(
+            Some("ops".to_string()),
+            AdminScope::Write,
+            "PUT",
+            "/admin/a",
+            200,
+        );
+        log.record(None, AdminScope::Write, "DELETE", "/admin/b", 204);
+        log.record(
+            Some("sre".to_string()),
+            AdminScope::Chaos,
+            "PUT",
+            "/admin/chaos",
+            403,
+        );
+
+        let entries = log.entries();
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/admin/b", "/admin/chaos"]);
+        assert_eq!(entries[1].client_id.as_deref(), Some("sre"));
+        assert_eq!(entries[1].scope, AdminScope::Chaos);
+        assert_eq!(entries[1].status, 403);
+        assert!(entries[1].timestamp_ms > 0);
+    }
+}
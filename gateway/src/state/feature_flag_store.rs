@@ -0,0 +1,117 @@
+//! Runtime feature flag configuration
+//!
+//! [`FeatureFlagStore`] holds the flag configurations that new sessions are
+//! resolved against. `PUT /admin/feature_flags` replaces them without a
+//! restart; sessions that are already open keep the flags they resolved.
+
+use std::collections::BTreeMap;
+
+use parking_lot::RwLock;
+
+use crate::config::{FeatureFlagConfig, FeatureFlags, unknown_feature_flags};
+
+/// Feature flag configurations, replaceable while the server is running
+pub struct FeatureFlagStore {
+    flags: RwLock<BTreeMap<String, FeatureFlagConfig>>,
+}
+
+impl FeatureFlagStore {
+    /// Create a store with the configured flags
+    pub fn new(flags: BTreeMap<String, FeatureFlagConfig>) -> Self {
+        Self {
+            flags: RwLock::new(flags),
+        }
+    }
+
+    /// Flag configurations in effect
+    pub fn flags(&self) -> BTreeMap<String, FeatureFlagConfig> {
+        self.flags.read().clone()
+    }
+
+    /// Resolve the flags for a new session
+    ///
+    /// # Arguments
+    /// * `client_key` - Authenticated client ID, or the stream ID when auth is off
+    pub fn resolve(&self, client_key: &str) -> FeatureFlags {
+        FeatureFlags::resolve(&self.flags.read(), client_key)
+    }
+
+    /// Replace every flag configuration
+    ///
+    /// # Returns
+    /// * `Ok(Vec<String>)` - Names of flags that no session component checks
+    ///
+    /// # Errors
+    /// Returns the validation error and keeps the current flags if a flag
+    /// is unnamed or its rollout is out of range
+    pub fn update(
+        &self,
+        flags: BTreeMap<String, FeatureFlagConfig>,
+    ) -> Result<Vec<String>, String> {
+        for (name, flag) in &flags {
+            if name.trim().is_empty() {
+                return Err("feature flag names must not be empty".to_string());
+            }
+            flag.validate().map_err(|e| format!("{name}: {e}"))?;
+        }
+        let unknown: Vec<String> = unknown_feature_flags(&flags)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if !unknown.is_empty() {
+            tracing::warn!(unknown = ?unknown, "Feature flags updated with unknown names");
+        }
+        tracing::info!(
+            flags = ?flags.keys().collect::<Vec<_>>(),
+            "Feature flags updated"
+        );
+        *self.flags.write() = flags;
+        Ok(unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_applies_to_new_resolutions() {
+        let store = FeatureFlagStore::new(BTreeMap::new());
+        let before = store.resolve("project-1");
+        assert!(before.is_empty());
+
+        let unknown = store
+            .update(BTreeMap::from([
+                (
+                    "echo_guard".to_string(),
+                    FeatureFlagConfig {
+                        default: true,
+                        ..Default::default()
+                    },
+                ),
+                ("new_chunker".to_string(), FeatureFlagConfig::default()),
+            ]))
+            .unwrap();
+        assert_eq!(unknown, vec!["new_chunker"]);
+        assert!(store.resolve("project-1").is_enabled("echo_guard"));
+        // Flags resolved earlier are unaffected
+        assert!(!before.is_enabled("echo_guard"));
+    }
+
+    #[test]
+    fn test_invalid_update_keeps_flags() {
+        let flags = BTreeMap::from([("echo_guard".to_string(), FeatureFlagConfig::default())]);
+        let store = FeatureFlagStore::new(flags.clone());
+
+        let invalid = BTreeMap::from([(
+            "echo_guard".to_string(),
+            FeatureFlagConfig {
+                rollout_percent: Some(-5.0),
+                ..Default::default()
+            },
+        )]);
+        let err = store.update(invalid).unwrap_err();
+        assert!(err.starts_with("echo_guard: rollout_percent"));
+        assert_eq!(store.flags(), flags);
+    }
+}
@@ -0,0 +1,306 @@
+//! Load shedding for new sessions
+//!
+//! [`LoadShedder`] compares the current load against the configured
+//! watermarks and decides whether a new WebSocket or realtime session should
+//! be turned away. It tracks event-loop lag itself with a sampling task; the
+//! active session count and provider pool saturation are supplied by
+//! [`AppState::load_shedding_status`](super::AppState::load_shedding_status).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::config::LoadSheddingConfig;
+use crate::metrics::global_metrics;
+
+/// How often the event-loop lag is sampled
+const LAG_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Why new sessions are being shed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ShedReason {
+    /// Too many sessions are open
+    ActiveSessions,
+    /// A provider request pool is close to full
+    PoolSaturation,
+    /// The runtime is falling behind its timers
+    EventLoopLag,
+}
+
+impl ShedReason {
+    /// Wire name of the reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ActiveSessions => "active_sessions",
+            Self::PoolSaturation => "pool_saturation",
+            Self::EventLoopLag => "event_loop_lag",
+        }
+    }
+}
+
+/// Current load and whether new sessions are being shed
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoadSheddingStatus {
+    /// Whether new sessions are currently rejected
+    pub shedding: bool,
+    /// First threshold that is exceeded (omitted when not shedding)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ShedReason>,
+    /// Human-readable description of the exceeded threshold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Open WebSocket and realtime sessions
+    pub active_sessions: usize,
+    /// Fraction of busy slots in the fullest provider request pool (0.0 to 1.0)
+    pub pool_saturation: f64,
+    /// Recent event-loop lag in milliseconds
+    pub event_loop_lag_ms: u64,
+    /// Thresholds in effect
+    pub thresholds: LoadSheddingConfig,
+}
+
+/// Decides whether new sessions are shed
+///
+/// The thresholds sit behind a lock so they can be replaced while the server
+/// is running.
+pub struct LoadShedder {
+    config: RwLock<LoadSheddingConfig>,
+    event_loop_lag_us: AtomicU64,
+}
+
+impl LoadShedder {
+    /// Create a shedder with the given thresholds
+    pub fn new(config: LoadSheddingConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            event_loop_lag_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Thresholds in effect
+    pub fn config(&self) -> LoadSheddingConfig {
+        self.config.read().clone()
+    }
+
+    /// Replace the thresholds
+    ///
+    /// # Errors
+    /// Returns the validation error and keeps the current thresholds if the
+    /// new ones are out of range
+    pub fn update_config(&self, config: LoadSheddingConfig) -> Result<(), String> {
+        config.validate()?;
+        tracing::info!(
+            max_active_sessions = ?config.max_active_sessions,
+            max_pool_saturation = ?config.max_pool_saturation,
+            max_event_loop_lag_ms = ?config.max_event_loop_lag_ms,
+            retry_after_secs = config.retry_after_secs,
+            "Load shedding thresholds updated"
+        );
+        *self.config.write() = config;
+        Ok(())
+    }
+
+    /// Recent event-loop lag
+    pub fn event_loop_lag(&self) -> Duration {
+        Duration::from_micros(self.event_loop_lag_us.load(Ordering::Relaxed))
+    }
+
+    /// Record one lag sample
+    ///
+    /// The reported lag holds the highest recent sample and halves on every
+    /// quieter sample, so a single stall is visible for a few sample periods
+    /// instead of being overwritten by the next on-time tick.
+    pub fn record_event_loop_lag(&self, sample: Duration) {
+        let sample_us = u64::try_from(sample.as_micros()).unwrap_or(u64::MAX);
+        let lag = &self.event_loop_lag_us;
+        let _ = lag.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |previous| {
+            Some(sample_us.max(previous / 2))
+        });
+    }
+
+    /// Sample the event-loop lag in the background
+    ///
+    /// The task measures how late a short sleep wakes up. It stops once the
+    /// shedder is dropped.
+    pub fn spawn_lag_monitor(self: &Arc<Self>) -> JoinHandle<()> {
+        let shedder: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let started = Instant::now();
+                tokio::time::sleep(LAG_SAMPLE_INTERVAL).await;
+                let Some(shedder) = shedder.upgrade() else {
+                    break;
+                };
+                shedder
+                    .record_event_loop_lag(started.elapsed().saturating_sub(LAG_SAMPLE_INTERVAL));
+            }
+        })
+    }
+
+    /// Compare the current load against the thresholds
+    ///
+    /// Also refreshes the load gauges exported at `/metrics`.
+    pub fn assess(&self, active_sessions: usize, pool_saturation: f64) -> LoadSheddingStatus {
+        let thresholds = self.config();
+        let lag_ms = u64::try_from(self.event_loop_lag().as_millis()).unwrap_or(u64::MAX);
+
+        let exceeded = if let Some(max) = thresholds
+            .max_active_sessions
+            .filter(|max| active_sessions >= *max)
+        {
+            Some((
+                ShedReason::ActiveSessions,
+                format!("{active_sessions} active sessions (limit {max})"),
+            ))
+        } else if let Some(max) = thresholds
+            .max_pool_saturation
+            .filter(|max| pool_saturation >= *max)
+        {
+            Some((
+                ShedReason::PoolSaturation,
+                format!("provider pool saturation {pool_saturation:.2} (limit {max:.2})"),
+            ))
+        } else {
+            thresholds
+                .max_event_loop_lag_ms
+                .filter(|max| lag_ms >= *max)
+                .map(|max| {
+                    (
+                        ShedReason::EventLoopLag,
+                        format!("event loop lag {lag_ms}ms (limit {max}ms)"),
+                    )
+                })
+        };
+        let (reason, detail) = exceeded.unzip();
+
+        let metrics = global_metrics();
+        metrics.set_gauge(
+            "waav_load_shedding_active",
+            "1 while new sessions are being shed",
+            &[],
+            if reason.is_some() { 1.0 } else { 0.0 },
+        );
+        metrics.set_gauge(
+            "waav_active_sessions",
+            "Open WebSocket and realtime sessions",
+            &[],
+            active_sessions as f64,
+        );
+        metrics.set_gauge(
+            "waav_provider_pool_saturation",
+            "Fraction of busy slots in the fullest provider request pool",
+            &[],
+            pool_saturation,
+        );
+        metrics.set_gauge(
+            "waav_event_loop_lag_seconds",
+            "Recent event loop lag",
+            &[],
+            self.event_loop_lag().as_secs_f64(),
+        );
+
+        LoadSheddingStatus {
+            shedding: reason.is_some(),
+            reason,
+            detail,
+            active_sessions,
+            pool_saturation,
+            event_loop_lag_ms: lag_ms,
+            thresholds,
+        }
+    }
+
+    /// Count a rejected session
+    pub fn record_rejection(&self, reason: ShedReason) {
+        global_metrics().inc_counter(
+            "waav_load_shed_rejections_total",
+            "New sessions rejected by load shedding",
+            &[("reason", reason.as_str())],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shedder() -> LoadShedder {
+        LoadShedder::new(LoadSheddingConfig {
+            max_active_sessions: Some(10),
+            max_pool_saturation: Some(0.9),
+            max_event_loop_lag_ms: Some(100),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_disabled_never_sheds() {
+        let shedder = LoadShedder::new(LoadSheddingConfig::default());
+        shedder.record_event_loop_lag(Duration::from_secs(5));
+        let status = shedder.assess(100_000, 1.0);
+        assert!(!status.shedding);
+        assert_eq!(status.reason, None);
+    }
+
+    #[test]
+    fn test_sheds_at_watermark() {
+        let shedder = shedder();
+        assert!(!shedder.assess(9, 0.5).shedding);
+
+        let status = shedder.assess(10, 0.5);
+        assert!(status.shedding);
+        assert_eq!(status.reason, Some(ShedReason::ActiveSessions));
+        assert_eq!(
+            status.detail.as_deref(),
+            Some("10 active sessions (limit 10)")
+        );
+
+        let status = shedder.assess(1, 0.95);
+        assert_eq!(status.reason, Some(ShedReason::PoolSaturation));
+
+        shedder.record_event_loop_lag(Duration::from_millis(150));
+        let status = shedder.assess(1, 0.0);
+        assert_eq!(status.reason, Some(ShedReason::EventLoopLag));
+        assert_eq!(status.event_loop_lag_ms, 150);
+    }
+
+    #[test]
+    fn test_event_loop_lag_decays() {
+        let shedder = shedder();
+        shedder.record_event_loop_lag(Duration::from_millis(400));
+        shedder.record_event_loop_lag(Duration::from_millis(1));
+        assert_eq!(shedder.event_loop_lag(), Duration::from_millis(200));
+        shedder.record_event_loop_lag(Duration::from_millis(1));
+        shedder.record_event_loop_lag(Duration::from_millis(1));
+        assert_eq!(shedder.event_loop_lag(), Duration::from_millis(50));
+        assert!(!shedder.assess(0, 0.0).shedding);
+    }
+
+    #[test]
+    fn test_update_config() {
+        let shedder = shedder();
+        assert!(shedder.assess(10, 0.0).shedding);
+
+        shedder
+            .update_config(LoadSheddingConfig {
+                max_active_sessions: Some(20),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(!shedder.assess(10, 0.0).shedding);
+
+        let invalid = LoadSheddingConfig {
+            max_active_sessions: Some(0),
+            ..Default::default()
+        };
+        assert!(shedder.update_config(invalid).is_err());
+        assert_eq!(shedder.config().max_active_sessions, Some(20));
+    }
+}
@@ -1,20 +1,66 @@
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 
+use crate::agents::AgentProfileStore;
 use crate::auth::AuthClient;
 use crate::config::ServerConfig;
 use crate::core::CoreState;
 use crate::core::cache::store::CacheStore;
+#[cfg(feature = "chaos")]
+use crate::core::chaos::ChaosController;
+use crate::core::providers::connection_budget::{
+    ConnectionBudgets, DEEPGRAM_CONNECTION_BUDGET, connection_budgets,
+};
+use crate::core::providers::credential_health::{CredentialHealthCache, credential_health};
+use crate::enrichment::EnrichmentWorkers;
+use crate::errors::provider_error::ErrorSanitizer;
+use crate::flight_recorder::FlightRecorder;
 use crate::livekit::room_handler::{LiveKitRoomHandler, RecordingConfig};
 use crate::livekit::sip_handler::{DispatchConfig, LiveKitSipHandler, TrunkConfig};
+use crate::plugin::{PluginRegistry, global_registry};
+use crate::recording_upload::RecordingUploader;
+use crate::replay::ReplayJobs;
+use crate::selftest::SelfTestMonitor;
+use crate::session_export::SessionExporter;
+use crate::usage::UsageRecorder;
 use crate::utils::req_manager::ReqManager;
+use crate::webhooks::WebhookDispatcher;
 use dashmap::DashMap;
 use object_store::ObjectStore;
-use object_store::aws::AmazonS3Builder;
-
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use tokio::sync::{RwLock, Semaphore};
+
+mod admin_audit;
+mod feature_flag_store;
+mod load_shedder;
+mod monitor_audio;
+mod session_events;
+mod session_store;
+mod sip_calls;
 mod sip_hooks_state;
 
+pub use admin_audit::{ADMIN_AUDIT_LOG_SIZE, AdminAuditEntry, AdminAuditLog};
+pub use feature_flag_store::FeatureFlagStore;
+pub use load_shedder::{LoadShedder, LoadSheddingStatus, ShedReason};
+pub use monitor_audio::{
+    MONITOR_KEY_LEN, MONITOR_NONCE_LEN, MonitorAudioCipher, MonitorAudioError, MonitorAudioFrame,
+    decrypt_monitor_frame, monitor_nonce,
+};
+pub use session_events::{
+    MAX_SESSION_OBSERVERS, MONITOR_AUDIO_BUFFER_SIZE, MonitorKeyError, ObservedEvent,
+    SESSION_EVENT_REPLAY_SIZE, SessionEventBus, SubscribeError, Subscription,
+};
+pub use session_store::{
+    MAX_SESSION_METADATA_ENTRIES, MAX_SESSION_METADATA_KEY_SIZE, MAX_SESSION_METADATA_SIZE,
+    MAX_SESSION_METADATA_VALUE_SIZE, SessionEntry, SessionMetadata, SessionMetadataError,
+    SessionStore, insert_session_metadata, session_metadata_size, validate_session_metadata,
+};
+pub use sip_calls::{
+    CallEndReason, CallSignal, CallState, SIP_CALL_STATUS_ATTRIBUTE,
+    SIP_CALL_STATUS_CODE_ATTRIBUTE, SipCallRegistry,
+};
 pub use sip_hooks_state::SipHooksState;
 
 /// Application state that can be shared across handlers
@@ -23,6 +69,8 @@ pub struct AppState {
     pub config: ServerConfig,
     /// Core layer state that holds shared resources, such as TTS request managers
     pub core_state: Arc<CoreState>,
+    /// Providers and plugin handlers sessions and routes are served by
+    pub plugin_registry: Arc<PluginRegistry>,
     /// LiveKit room handler for room and token management
     pub livekit_room_handler: Option<Arc<LiveKitRoomHandler>>,
     /// Object store client for recording retrieval
@@ -37,10 +85,66 @@ pub struct AppState {
     pub active_ws_connections: Arc<AtomicUsize>,
     /// Connection count per IP address (for per-IP limit enforcement)
     pub connections_per_ip: Arc<DashMap<IpAddr, AtomicUsize>>,
+    /// Permits for calls into plugin HTTP handlers, per plugin ID
+    pub plugin_http_permits: Arc<DashMap<String, Arc<Semaphore>>>,
+    /// Registry of active sessions and their client-supplied metadata
+    pub session_store: Arc<SessionStore>,
+    /// Per-session event streams for observers
+    pub session_events: Arc<SessionEventBus>,
+    /// Progress of the outbound SIP calls sessions are waiting on
+    pub sip_calls: Arc<SipCallRegistry>,
+    /// Writes a usage record when each session ends (if usage records are configured)
+    pub usage_recorder: Option<Arc<UsageRecorder>>,
+    /// Diarizes finished sessions in the background (if transcript enrichment is configured)
+    pub transcript_enrichment: Option<Arc<EnrichmentWorkers>>,
+    /// Stores and announces finished sessions' artifacts (if session export is configured)
+    pub session_export: Option<Arc<SessionExporter>>,
+    /// Durable queue outbound webhooks are delivered through (if the webhook queue is configured)
+    pub webhook_queue: Option<Arc<WebhookDispatcher>>,
+    /// Logs of the sessions that opt in (if the flight recorder is configured)
+    pub flight_recorder: Option<Arc<FlightRecorder>>,
+    /// Uploads sessions' caller audio to the recording bucket (if recording upload is configured)
+    pub recording_uploads: Option<Arc<RecordingUploader>>,
+    /// Agent profiles from the config and the admin API
+    pub agent_profiles: Arc<RwLock<AgentProfileStore>>,
+    /// Latest provider self-test result (if the self-test is configured)
+    pub selftest: Arc<SelfTestMonitor>,
+    /// Decides whether new sessions are turned away under load
+    pub load_shedder: Arc<LoadShedder>,
+    /// Feature flags that new sessions are resolved against
+    pub feature_flags: Arc<FeatureFlagStore>,
+    /// Shared tokens and cached rejections of provider credentials
+    pub credential_health: Arc<CredentialHealthCache>,
+    /// Open provider connections per API key, limited where configured
+    pub connection_budgets: Arc<ConnectionBudgets>,
+    /// Recording replay jobs started through `POST /admin/replay`
+    pub replay_jobs: Arc<ReplayJobs>,
+    /// Strips secrets and markup from provider errors before they reach clients
+    pub error_sanitizer: Arc<ErrorSanitizer>,
+    /// Mutating admin calls, served by `GET /admin/audit`
+    pub admin_audit: Arc<AdminAuditLog>,
+    /// Faults injected into sessions for resilience testing (`/admin/chaos`)
+    #[cfg(feature = "chaos")]
+    pub chaos: Arc<ChaosController>,
+    /// When the state was created, for the uptime reported by `GET /version`
+    pub started_at: Instant,
 }
 
 impl AppState {
+    /// Create the state with the process-wide [`global_registry`]
     pub async fn new(config: ServerConfig) -> Arc<Self> {
+        Self::with_plugin_registry(config, global_registry().clone()).await
+    }
+
+    /// Create the state with its own plugin registry
+    ///
+    /// Providers registered in `plugin_registry`, e.g. one from
+    /// [`PluginRegistry::new_isolated`], are only seen by this state, which
+    /// lets several gateways or tests run in one process.
+    pub async fn with_plugin_registry(
+        config: ServerConfig,
+        plugin_registry: Arc<PluginRegistry>,
+    ) -> Arc<Self> {
         let core_state = CoreState::new(&config).await;
 
         // Initialize LiveKit room handler if API keys are available
@@ -68,6 +172,7 @@ impl AppState {
                     access_key: access_key.clone(),
                     secret_key: secret_key.clone(),
                     prefix: config.recording_s3_prefix.clone().unwrap_or_default(),
+                    audio: config.audio_sinks.recording,
                 })
             } else {
                 None
@@ -90,57 +195,13 @@ impl AppState {
         };
 
         // Initialize object store for recording downloads if all credentials are provided
-        let (object_store, recording_bucket) = if let (
-            Some(bucket),
-            Some(region),
-            Some(endpoint),
-            Some(access_key),
-            Some(secret_key),
-        ) = (
-            &config.recording_s3_bucket,
-            &config.recording_s3_region,
-            &config.recording_s3_endpoint,
-            &config.recording_s3_access_key,
-            &config.recording_s3_secret_key,
-        ) {
-            let mut builder = AmazonS3Builder::new()
-                .with_bucket_name(bucket)
-                .with_region(region)
-                .with_endpoint(endpoint)
-                .with_access_key_id(access_key)
-                .with_secret_access_key(secret_key);
-
-            if endpoint.starts_with("http://") {
-                builder = builder.with_allow_http(true);
-            }
-
-            match builder.build() {
-                Ok(store) => {
-                    tracing::info!(
-                        "Recording object store initialized for bucket={} endpoint={}",
-                        bucket,
-                        endpoint
-                    );
-                    (
-                        Some(Arc::new(store) as Arc<dyn ObjectStore>),
-                        Some(bucket.clone()),
-                    )
-                }
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to initialize recording object store for bucket={} endpoint={}: {:?}",
-                        bucket,
-                        endpoint,
-                        e
-                    );
-                    tracing::info!("Recording downloads will be unavailable");
-                    (None, None)
-                }
-            }
-        } else {
-            tracing::info!("Recording storage not configured; recording downloads disabled");
-            (None, None)
-        };
+        let recording_s3 = recording_s3_store(&config);
+        let object_store = recording_s3
+            .clone()
+            .map(|store| Arc::new(store) as Arc<dyn ObjectStore>);
+        let recording_bucket = object_store
+            .as_ref()
+            .and(config.recording_s3_bucket.clone());
 
         // Initialize auth client if JWT-based auth is configured
         // Note: API secret auth doesn't need a client - it's handled directly in middleware
@@ -269,9 +330,166 @@ impl AppState {
             None
         };
 
+        // Without a working queue, webhooks are POSTed inline
+        let webhook_queue = match &config.webhook_queue {
+            Some(queue) => match WebhookDispatcher::start(queue, core_state.cache.clone()) {
+                Ok(dispatcher) => {
+                    tracing::info!(
+                        max_pending = queue.max_pending_per_destination,
+                        "Webhook queue enabled"
+                    );
+                    Some(Arc::new(dispatcher))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to start the webhook queue: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // Usage records are opt-in; a sink that cannot be created disables them
+        let usage_recorder = match &config.usage {
+            Some(usage) => match UsageRecorder::new(usage) {
+                Ok(recorder) => {
+                    tracing::info!(sink = %usage.sink, "Usage records enabled");
+                    let recorder = match &webhook_queue {
+                        Some(queue) => recorder.with_webhook_queue(queue.clone()),
+                        None => recorder,
+                    };
+                    Some(Arc::new(recorder))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to initialize usage recorder: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let transcript_enrichment = match &config.transcript_enrichment {
+            Some(enrichment) => match EnrichmentWorkers::start(
+                enrichment,
+                object_store.clone(),
+                config.recording_s3_prefix.clone(),
+                webhook_queue.clone(),
+            ) {
+                Ok(workers) => {
+                    tracing::info!(
+                        workers = enrichment.workers,
+                        "Transcript enrichment enabled"
+                    );
+                    Some(Arc::new(workers))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to start transcript enrichment: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // Caller audio recording needs the recording bucket for its uploads
+        let recording_uploads = match (&config.recording_upload, &recording_s3) {
+            (Some(upload), Some(store)) => match RecordingUploader::start(
+                upload,
+                Arc::new(store.clone()),
+                config.recording_s3_prefix.clone(),
+            ) {
+                Ok(uploader) => {
+                    tracing::info!(
+                        part_size_bytes = upload.part_size_bytes,
+                        "Caller audio recording enabled"
+                    );
+                    Some(Arc::new(uploader))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to start recording uploads: {}", e);
+                    None
+                }
+            },
+            (Some(_), None) => {
+                tracing::warn!(
+                    "recording_upload is set, but recording storage is unavailable; caller audio is not recorded"
+                );
+                None
+            }
+            (None, _) => None,
+        };
+
+        let session_export = match &config.session_export {
+            Some(export) => match SessionExporter::new(
+                export,
+                recording_s3,
+                config.recording_s3_prefix.clone(),
+                transcript_enrichment.is_some(),
+            ) {
+                Ok(exporter) => {
+                    tracing::info!(
+                        url_expiry_secs = export.url_expiry_secs,
+                        "Session export enabled"
+                    );
+                    let exporter = match &webhook_queue {
+                        Some(queue) => exporter.with_webhook_queue(queue.clone()),
+                        None => exporter,
+                    };
+                    Some(Arc::new(exporter))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to initialize session export: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let agent_profiles =
+            AgentProfileStore::new(&config.agents, config.cache_path.as_deref()).await;
+
+        let selftest = Arc::new(SelfTestMonitor::new(config.selftest.clone()));
+
+        let load_shedder = Arc::new(LoadShedder::new(
+            config.load_shedding.clone().unwrap_or_default(),
+        ));
+        load_shedder.spawn_lag_monitor();
+
+        let replay_jobs = Arc::new(ReplayJobs::new(config.replay_max_concurrent_jobs));
+
+        let feature_flags = Arc::new(FeatureFlagStore::new(config.feature_flags.clone()));
+
+        let connection_budgets = connection_budgets().clone();
+        connection_budgets.configure(
+            DEEPGRAM_CONNECTION_BUDGET,
+            config.deepgram_connection_budget,
+        );
+
+        let error_sanitizer = Arc::new(
+            ErrorSanitizer::new(config.provider_error_max_chars)
+                .with_secrets(config.provider_secrets()),
+        );
+
+        #[cfg(feature = "chaos")]
+        let chaos = Arc::new(ChaosController::new(config.chaos));
+        let flight_recorder = config.flight_recorder.map(|recorder| {
+            tracing::info!(
+                max_bytes = recorder.max_bytes_per_session,
+                retention_secs = recorder.retention_secs,
+                "Session flight recorder enabled"
+            );
+            Arc::new(FlightRecorder::new(recorder, core_state.cache.clone()))
+        });
+
+        #[cfg(not(feature = "chaos"))]
+        if config.chaos.enabled {
+            tracing::warn!(
+                "chaos.enabled is set, but this build has no chaos feature; ignoring it"
+            );
+        }
+
         Arc::new(Self {
             config,
             core_state,
+            plugin_registry,
             livekit_room_handler,
             object_store,
             recording_bucket,
@@ -279,6 +497,30 @@ impl AppState {
             auth_client,
             active_ws_connections: Arc::new(AtomicUsize::new(0)),
             connections_per_ip: Arc::new(DashMap::new()),
+            plugin_http_permits: Arc::new(DashMap::new()),
+            session_store: Arc::new(SessionStore::new()),
+            session_events: Arc::new(SessionEventBus::with_monitor_policy(
+                config.audio_sinks.monitor,
+            )),
+            sip_calls: Arc::new(SipCallRegistry::new()),
+            usage_recorder,
+            transcript_enrichment,
+            session_export,
+            webhook_queue,
+            flight_recorder,
+            recording_uploads,
+            agent_profiles: Arc::new(RwLock::new(agent_profiles)),
+            selftest,
+            load_shedder,
+            feature_flags,
+            credential_health: credential_health().clone(),
+            connection_budgets,
+            replay_jobs,
+            error_sanitizer,
+            admin_audit: Arc::new(AdminAuditLog::default()),
+            #[cfg(feature = "chaos")]
+            chaos,
+            started_at: Instant::now(),
         })
     }
 
@@ -297,6 +539,13 @@ impl AppState {
         self.active_ws_connections.load(Ordering::Relaxed)
     }
 
+    /// Current load and whether new sessions are being shed
+    pub async fn load_shedding_status(&self) -> LoadSheddingStatus {
+        let pool_saturation = self.core_state.tts_pool_saturation().await;
+        self.load_shedder
+            .assess(self.ws_connection_count(), pool_saturation)
+    }
+
     /// Get the number of connections from a specific IP address
     pub fn ip_connection_count(&self, ip: &IpAddr) -> usize {
         self.connections_per_ip
@@ -378,6 +627,66 @@ impl AppState {
     }
 }
 
+/// Object store client and bucket name for the configured recording storage
+///
+/// Returns None when the recording S3 settings are incomplete or the client
+/// cannot be created.
+pub fn recording_object_store(config: &ServerConfig) -> Option<(Arc<dyn ObjectStore>, String)> {
+    let store = recording_s3_store(config)?;
+    let bucket = config.recording_s3_bucket.clone()?;
+    Some((Arc::new(store) as Arc<dyn ObjectStore>, bucket))
+}
+
+/// S3 client of the configured recording storage
+///
+/// Returns None when the recording S3 settings are incomplete or the client
+/// cannot be created. Unlike [`recording_object_store`], the client can
+/// pre-sign download URLs.
+pub fn recording_s3_store(config: &ServerConfig) -> Option<AmazonS3> {
+    let (Some(bucket), Some(region), Some(endpoint), Some(access_key), Some(secret_key)) = (
+        &config.recording_s3_bucket,
+        &config.recording_s3_region,
+        &config.recording_s3_endpoint,
+        &config.recording_s3_access_key,
+        &config.recording_s3_secret_key,
+    ) else {
+        tracing::info!("Recording storage not configured; recording downloads disabled");
+        return None;
+    };
+
+    let mut builder = AmazonS3Builder::new()
+        .with_bucket_name(bucket)
+        .with_region(region)
+        .with_endpoint(endpoint)
+        .with_access_key_id(access_key)
+        .with_secret_access_key(secret_key);
+
+    if endpoint.starts_with("http://") {
+        builder = builder.with_allow_http(true);
+    }
+
+    match builder.build() {
+        Ok(store) => {
+            tracing::info!(
+                "Recording object store initialized for bucket={} endpoint={}",
+                bucket,
+                endpoint
+            );
+            Some(store)
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to initialize recording object store for bucket={} endpoint={}: {:?}",
+                bucket,
+                endpoint,
+                e
+            );
+            tracing::info!("Recording downloads will be unavailable");
+            None
+        }
+    }
+}
+
 /// Error type for connection limit checks
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionLimitError {
@@ -438,13 +747,46 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None, // No SIP config
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // We can't actually call AppState::new in a sync test, but we can verify
@@ -501,19 +843,54 @@ mod tests {
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: false,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: Some(SipConfig {
                 room_prefix: "sip-".to_string(),
                 allowed_addresses: vec!["192.168.1.0/24".to_string()],
                 hooks: vec![],
                 hook_secret: None,
                 naming_prefix: "waav".to_string(),
+                language_routing: Default::default(),
+                outbound: Default::default(),
             }),
             cors_allowed_origins: None,
             rate_limit_requests_per_second: 60,
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: crate::config::PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         // Verify that SIP config is present but credentials are missing
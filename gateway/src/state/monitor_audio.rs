@@ -0,0 +1,224 @@
+//! Encryption of the audio sent to session observers
+//!
+//! Monitor audio is encrypted with XChaCha20-Poly1305 under a key generated
+//! for each session. The key is handed out once, over
+//! `POST /sessions/{stream_id}/monitor-key`, so observers that only see the
+//! event stream cannot listen in.
+//!
+//! Every frame of a session takes the next value of a 64-bit counter, shared
+//! by both directions. The 24-byte nonce is 16 zero bytes followed by the
+//! counter in big-endian, and the direction (`in` or `out`) is authenticated
+//! as associated data. A key is never reused across sessions, so the counter
+//! alone keeps nonces unique.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use parking_lot::Mutex;
+
+use crate::core::session::AudioDirection;
+use crate::core::sink_audio::SinkAudioFormat;
+
+/// Length of a monitor audio key in bytes
+pub const MONITOR_KEY_LEN: usize = 32;
+
+/// Length of a monitor audio nonce in bytes
+pub const MONITOR_NONCE_LEN: usize = 24;
+
+/// Error returned when a monitor audio frame cannot be decrypted
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MonitorAudioError {
+    /// Wrong key, counter or direction, or the frame was modified
+    #[error("Monitor audio frame {counter} failed authentication")]
+    Decrypt { counter: u64 },
+}
+
+/// One encrypted frame of monitor audio
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorAudioFrame {
+    pub direction: AudioDirection,
+    /// Frame counter the nonce was derived from
+    pub counter: u64,
+    /// Sample rate of the audio
+    pub sample_rate: u32,
+    /// Number of interleaved channels of the audio
+    pub channels: u16,
+    /// Encrypted audio followed by the 16-byte authentication tag
+    pub ciphertext: Vec<u8>,
+}
+
+/// Nonce of the frame with `counter`
+pub fn monitor_nonce(counter: u64) -> [u8; MONITOR_NONCE_LEN] {
+    let mut nonce = [0u8; MONITOR_NONCE_LEN];
+    nonce[MONITOR_NONCE_LEN - 8..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+fn associated_data(direction: AudioDirection) -> &'static [u8] {
+    match direction {
+        AudioDirection::In => b"in",
+        AudioDirection::Out => b"out",
+    }
+}
+
+/// Decrypt a monitor audio frame with the session's key
+///
+/// Reference implementation for observers; returns the session audio.
+pub fn decrypt_monitor_frame(
+    key: &[u8; MONITOR_KEY_LEN],
+    direction: AudioDirection,
+    counter: u64,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, MonitorAudioError> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = monitor_nonce(counter);
+    cipher
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: ciphertext,
+                aad: associated_data(direction),
+            },
+        )
+        .map_err(|_| MonitorAudioError::Decrypt { counter })
+}
+
+/// Per-session monitor audio key and frame counter
+pub struct MonitorAudioCipher {
+    cipher: XChaCha20Poly1305,
+    /// Key until an observer has retrieved it
+    key: Mutex<Option<[u8; MONITOR_KEY_LEN]>>,
+    next_counter: AtomicU64,
+}
+
+impl Default for MonitorAudioCipher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonitorAudioCipher {
+    /// Create a cipher with a freshly generated random key
+    pub fn new() -> Self {
+        let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+        let mut bytes = [0u8; MONITOR_KEY_LEN];
+        bytes.copy_from_slice(&key);
+        Self {
+            cipher: XChaCha20Poly1305::new(&key),
+            key: Mutex::new(Some(bytes)),
+            next_counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Hand out the key; `None` once it has been retrieved
+    pub fn take_key(&self) -> Option<[u8; MONITOR_KEY_LEN]> {
+        self.key.lock().take()
+    }
+
+    /// Encrypt one chunk of audio as the next frame
+    pub fn encrypt(
+        &self,
+        direction: AudioDirection,
+        format: SinkAudioFormat,
+        pcm: &[u8],
+    ) -> MonitorAudioFrame {
+        let counter = self.next_counter.fetch_add(1, Ordering::Relaxed);
+        let nonce = monitor_nonce(counter);
+        // Encrypting into a Vec only fails on allocation limits
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: pcm,
+                    aad: associated_data(direction),
+                },
+            )
+            .expect("XChaCha20-Poly1305 encryption failed");
+        MonitorAudioFrame {
+            direction,
+            counter,
+            sample_rate: format.sample_rate,
+            channels: format.channels,
+            ciphertext,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = MonitorAudioCipher::new();
+        let key = cipher.take_key().unwrap();
+        let pcm: Vec<u8> = (0..640).map(|i| (i % 251) as u8).collect();
+
+        let first = cipher.encrypt(AudioDirection::In, SinkAudioFormat::mono(16000), &pcm);
+        let second = cipher.encrypt(
+            AudioDirection::Out,
+            SinkAudioFormat::mono(24000),
+            &pcm[..320],
+        );
+        assert_eq!((first.counter, second.counter), (0, 1));
+        assert_eq!(first.ciphertext.len(), pcm.len() + 16);
+        assert_ne!(&first.ciphertext[..pcm.len()], pcm.as_slice());
+
+        assert_eq!(
+            decrypt_monitor_frame(&key, first.direction, first.counter, &first.ciphertext).unwrap(),
+            pcm
+        );
+        assert_eq!(
+            decrypt_monitor_frame(&key, second.direction, second.counter, &second.ciphertext)
+                .unwrap(),
+            &pcm[..320]
+        );
+    }
+
+    #[test]
+    fn test_key_is_retrievable_once() {
+        let cipher = MonitorAudioCipher::new();
+        assert!(cipher.take_key().is_some());
+        assert!(cipher.take_key().is_none());
+        // Encryption continues after the key was handed out
+        cipher.encrypt(AudioDirection::In, SinkAudioFormat::mono(16000), &[0; 4]);
+    }
+
+    #[test]
+    fn test_sessions_have_distinct_keys() {
+        let a = MonitorAudioCipher::new().take_key().unwrap();
+        let b = MonitorAudioCipher::new().take_key().unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let cipher = MonitorAudioCipher::new();
+        let key = cipher.take_key().unwrap();
+        let frame = cipher.encrypt(
+            AudioDirection::Out,
+            SinkAudioFormat::mono(24000),
+            &[1, 2, 3, 4],
+        );
+
+        // Wrong counter, wrong direction, modified ciphertext, wrong key
+        assert_eq!(
+            decrypt_monitor_frame(&key, frame.direction, 1, &frame.ciphertext),
+            Err(MonitorAudioError::Decrypt { counter: 1 })
+        );
+        assert!(decrypt_monitor_frame(&key, AudioDirection::In, 0, &frame.ciphertext).is_err());
+        let mut modified = frame.ciphertext.clone();
+        modified[0] ^= 1;
+        assert!(decrypt_monitor_frame(&key, frame.direction, 0, &modified).is_err());
+        assert!(decrypt_monitor_frame(&[0; 32], frame.direction, 0, &frame.ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_nonce_layout() {
+        let nonce = monitor_nonce(0x0102);
+        assert_eq!(&nonce[..22], &[0; 22]);
+        assert_eq!(&nonce[22..], &[1, 2]);
+    }
+}
@@ -0,0 +1,511 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use super::monitor_audio::{MONITOR_KEY_LEN, MonitorAudioCipher, MonitorAudioFrame};
+use crate::config::SinkAudioPolicy;
+use crate::core::session::AudioDirection;
+use crate::core::sink_audio::{SinkAudioFormat, SinkAudioTransform};
+
+/// Number of recent events kept per session for `Last-Event-ID` resume.
+pub const SESSION_EVENT_REPLAY_SIZE: usize = 256;
+
+/// Maximum number of concurrent observers of a single session.
+pub const MAX_SESSION_OBSERVERS: usize = 8;
+
+/// Monitor audio frames buffered per observer before it starts skipping.
+pub const MONITOR_AUDIO_BUFFER_SIZE: usize = 64;
+
+/// One event published to a session's observers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservedEvent {
+    /// Sequence number, starting at 1 for each session
+    pub id: u64,
+    /// Message type (the `type` field of the JSON message)
+    pub event_type: String,
+    /// JSON-encoded message
+    pub data: String,
+}
+
+/// Error returned when an observer cannot subscribe to a session.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubscribeError {
+    /// No active session with this identifier
+    #[error("Session '{0}' not found")]
+    NotFound(String),
+    /// The session already has the maximum number of observers
+    #[error("Session '{stream_id}' already has {max} observers")]
+    TooManyObservers { stream_id: String, max: usize },
+}
+
+/// Error returned when a session's monitor audio key cannot be handed out.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MonitorKeyError {
+    /// No active session with this identifier
+    #[error("Session '{0}' not found")]
+    NotFound(String),
+    /// The key was already handed out
+    #[error("Monitor key of session '{0}' was already retrieved")]
+    AlreadyRetrieved(String),
+}
+
+struct SessionChannel {
+    /// Auth id of the client that owns the session
+    owner: Option<String>,
+    /// Recent events, oldest first. Also serializes publishing so ids and
+    /// broadcast order always match.
+    replay: Mutex<ReplayBuffer>,
+    sender: broadcast::Sender<ObservedEvent>,
+    observers: Arc<AtomicUsize>,
+    /// Encrypts monitor audio under the session's key
+    monitor: MonitorAudioCipher,
+    /// Applies the monitor audio policy to caller audio
+    inbound_transform: Mutex<SinkAudioTransform>,
+    /// Applies the monitor audio policy to session audio
+    outbound_transform: Mutex<SinkAudioTransform>,
+    /// Encrypted audio frames; not replayed on resume
+    audio_sender: broadcast::Sender<MonitorAudioFrame>,
+}
+
+#[derive(Default)]
+struct ReplayBuffer {
+    last_id: u64,
+    events: VecDeque<ObservedEvent>,
+}
+
+/// A subscription to a session's events.
+///
+/// Holds an observer slot until dropped.
+pub struct Subscription {
+    /// Buffered events after the requested `Last-Event-ID`, oldest first
+    pub replay: Vec<ObservedEvent>,
+    /// Events published after the subscription was made. Closed when the
+    /// session ends.
+    pub receiver: broadcast::Receiver<ObservedEvent>,
+    _slot: ObserverSlot,
+}
+
+struct ObserverSlot(Arc<AtomicUsize>);
+
+impl Drop for ObserverSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Per-session event bus for observers such as dashboards.
+///
+/// Sessions open a channel when configured and close it on teardown. JSON
+/// messages sent to the client are published with sequential ids; a bounded
+/// replay buffer lets observers that reconnect pick up where they left off.
+#[derive(Default)]
+pub struct SessionEventBus {
+    channels: DashMap<String, Arc<SessionChannel>>,
+    /// Downsampling and downmixing applied to monitor audio
+    monitor_policy: SinkAudioPolicy,
+}
+
+impl SessionEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a bus that applies `policy` to monitor audio
+    pub fn with_monitor_policy(policy: SinkAudioPolicy) -> Self {
+        Self {
+            channels: DashMap::new(),
+            monitor_policy: policy,
+        }
+    }
+
+    /// Open a session's channel, keeping the existing one if already open.
+    pub fn open(&self, stream_id: &str, owner: Option<String>) {
+        self.channels
+            .entry(stream_id.to_string())
+            .or_insert_with(|| {
+                debug!(stream_id = %stream_id, "Opened session event channel");
+                let (sender, _) = broadcast::channel(SESSION_EVENT_REPLAY_SIZE);
+                let (audio_sender, _) = broadcast::channel(MONITOR_AUDIO_BUFFER_SIZE);
+                Arc::new(SessionChannel {
+                    owner,
+                    replay: Mutex::new(ReplayBuffer::default()),
+                    sender,
+                    observers: Arc::new(AtomicUsize::new(0)),
+                    monitor: MonitorAudioCipher::new(),
+                    inbound_transform: Mutex::new(SinkAudioTransform::new(self.monitor_policy)),
+                    outbound_transform: Mutex::new(SinkAudioTransform::new(self.monitor_policy)),
+                    audio_sender,
+                })
+            });
+    }
+
+    /// Close a session's channel, ending all subscriptions.
+    pub fn close(&self, stream_id: &str) {
+        if self.channels.remove(stream_id).is_some() {
+            debug!(stream_id = %stream_id, "Closed session event channel");
+        }
+    }
+
+    /// Owner of an open session: `None` if the session is not open,
+    /// `Some(None)` if it was opened without authentication.
+    pub fn owner(&self, stream_id: &str) -> Option<Option<String>> {
+        self.channels
+            .get(stream_id)
+            .map(|channel| channel.owner.clone())
+    }
+
+    /// Publish a JSON message to a session's observers.
+    ///
+    /// The event type is taken from the message's `type` field. Does nothing
+    /// if the session's channel is not open.
+    pub fn publish(&self, stream_id: &str, message: &Value) {
+        let Some(channel) = self.channels.get(stream_id).map(|c| c.clone()) else {
+            return;
+        };
+        let event_type = message
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("message")
+            .to_string();
+
+        let mut replay = channel.replay.lock();
+        replay.last_id += 1;
+        let event = ObservedEvent {
+            id: replay.last_id,
+            event_type,
+            data: message.to_string(),
+        };
+        if replay.events.len() == SESSION_EVENT_REPLAY_SIZE {
+            replay.events.pop_front();
+        }
+        replay.events.push_back(event.clone());
+        // No receivers is fine; the event stays in the replay buffer
+        let _ = channel.sender.send(event);
+    }
+
+    /// Subscribe to a session's events.
+    ///
+    /// With `last_event_id`, buffered events after that id are returned for
+    /// replay; without it, only new events are delivered.
+    pub fn subscribe(
+        &self,
+        stream_id: &str,
+        last_event_id: Option<u64>,
+    ) -> Result<Subscription, SubscribeError> {
+        let channel = self
+            .channels
+            .get(stream_id)
+            .map(|c| c.clone())
+            .ok_or_else(|| SubscribeError::NotFound(stream_id.to_string()))?;
+
+        channel
+            .observers
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < MAX_SESSION_OBSERVERS).then_some(count + 1)
+            })
+            .map_err(|_| SubscribeError::TooManyObservers {
+                stream_id: stream_id.to_string(),
+                max: MAX_SESSION_OBSERVERS,
+            })?;
+        let slot = ObserverSlot(channel.observers.clone());
+
+        // Subscribe under the replay lock so no event is missed or duplicated
+        let replay = channel.replay.lock();
+        let receiver = channel.sender.subscribe();
+        let replay = match last_event_id {
+            Some(last_id) => replay
+                .events
+                .iter()
+                .filter(|event| event.id > last_id)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(Subscription {
+            replay,
+            receiver,
+            _slot: slot,
+        })
+    }
+
+    /// Publish a chunk of session audio to the monitor audio stream.
+    ///
+    /// 16-bit PCM (`pcm16`) is downsampled and downmixed as the monitor
+    /// policy asks; other encodings are sent as they are. The audio is
+    /// encrypted with the session's monitor key. Does nothing unless an
+    /// observer is receiving monitor audio.
+    pub fn publish_audio(
+        &self,
+        stream_id: &str,
+        direction: AudioDirection,
+        format: SinkAudioFormat,
+        pcm16: bool,
+        audio: &[u8],
+    ) {
+        let Some(channel) = self.channels.get(stream_id) else {
+            return;
+        };
+        if channel.audio_sender.receiver_count() == 0 {
+            return;
+        }
+        let transform = match direction {
+            AudioDirection::In => &channel.inbound_transform,
+            AudioDirection::Out => &channel.outbound_transform,
+        };
+        let (format, audio) = if pcm16 {
+            transform.lock().process(format, audio)
+        } else {
+            (format, audio.to_vec())
+        };
+        // The resampler may hold back a chunk too small to produce a sample
+        if audio.is_empty() {
+            return;
+        }
+        let frame = channel.monitor.encrypt(direction, format, &audio);
+        let _ = channel.audio_sender.send(frame);
+    }
+
+    /// Receive a session's encrypted monitor audio.
+    ///
+    /// Audio is not part of the replay buffer; only frames published after
+    /// this call are delivered.
+    pub fn subscribe_audio(
+        &self,
+        stream_id: &str,
+    ) -> Result<broadcast::Receiver<MonitorAudioFrame>, SubscribeError> {
+        self.channels
+            .get(stream_id)
+            .map(|channel| channel.audio_sender.subscribe())
+            .ok_or_else(|| SubscribeError::NotFound(stream_id.to_string()))
+    }
+
+    /// Hand out the key that decrypts a session's monitor audio.
+    ///
+    /// Each session's key is handed out only once.
+    pub fn take_monitor_key(
+        &self,
+        stream_id: &str,
+    ) -> Result<[u8; MONITOR_KEY_LEN], MonitorKeyError> {
+        let channel = self
+            .channels
+            .get(stream_id)
+            .ok_or_else(|| MonitorKeyError::NotFound(stream_id.to_string()))?;
+        channel
+            .monitor
+            .take_key()
+            .ok_or_else(|| MonitorKeyError::AlreadyRetrieved(stream_id.to_string()))
+    }
+
+    /// Number of observers subscribed to a session.
+    pub fn observer_count(&self, stream_id: &str) -> usize {
+        self.channels
+            .get(stream_id)
+            .map_or(0, |channel| channel.observers.load(Ordering::Acquire))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(events: &[ObservedEvent]) -> Vec<u64> {
+        events.iter().map(|event| event.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_disconnect_replays_missed_events() {
+        let bus = SessionEventBus::new();
+        bus.open("stream-1", None);
+
+        let mut first = bus.subscribe("stream-1", None).unwrap();
+        assert!(first.replay.is_empty());
+        bus.publish(
+            "stream-1",
+            &json!({"type": "stt_result", "transcript": "hi"}),
+        );
+        let seen = first.receiver.recv().await.unwrap();
+        assert_eq!(seen.id, 1);
+        assert_eq!(seen.event_type, "stt_result");
+        drop(first);
+
+        // Events published while the observer was away
+        bus.publish("stream-1", &json!({"type": "speech_started"}));
+        bus.publish(
+            "stream-1",
+            &json!({"type": "stt_result", "transcript": "there"}),
+        );
+
+        let mut resumed = bus.subscribe("stream-1", Some(seen.id)).unwrap();
+        assert_eq!(ids(&resumed.replay), vec![2, 3]);
+        assert_eq!(resumed.replay[0].event_type, "speech_started");
+
+        // Live events continue after the replayed ones
+        bus.publish("stream-1", &json!({"type": "tts_playback_complete"}));
+        assert_eq!(resumed.receiver.recv().await.unwrap().id, 4);
+
+        // A last id older than the buffer replays what is left
+        let fresh = bus.subscribe("stream-1", Some(0)).unwrap();
+        assert_eq!(ids(&fresh.replay), vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_buffer_is_bounded() {
+        let bus = SessionEventBus::new();
+        bus.open("stream-1", None);
+        for _ in 0..SESSION_EVENT_REPLAY_SIZE + 10 {
+            bus.publish("stream-1", &json!({"type": "stt_result"}));
+        }
+        let sub = bus.subscribe("stream-1", Some(0)).unwrap();
+        assert_eq!(sub.replay.len(), SESSION_EVENT_REPLAY_SIZE);
+        assert_eq!(sub.replay[0].id, 11);
+    }
+
+    #[tokio::test]
+    async fn test_observer_cap() {
+        let bus = SessionEventBus::new();
+        bus.open("stream-1", Some("project1".to_string()));
+
+        let subs: Vec<_> = (0..MAX_SESSION_OBSERVERS)
+            .map(|_| bus.subscribe("stream-1", None).unwrap())
+            .collect();
+        assert_eq!(bus.observer_count("stream-1"), MAX_SESSION_OBSERVERS);
+        assert!(matches!(
+            bus.subscribe("stream-1", None),
+            Err(SubscribeError::TooManyObservers { .. })
+        ));
+
+        drop(subs);
+        assert_eq!(bus.observer_count("stream-1"), 0);
+        assert!(bus.subscribe("stream-1", None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_close_ends_subscriptions() {
+        let bus = SessionEventBus::new();
+        bus.open("stream-1", Some("project1".to_string()));
+        assert_eq!(bus.owner("stream-1"), Some(Some("project1".to_string())));
+
+        let mut sub = bus.subscribe("stream-1", None).unwrap();
+        bus.close("stream-1");
+        assert!(matches!(
+            sub.receiver.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+        assert_eq!(bus.owner("stream-1"), None);
+        assert!(matches!(
+            bus.subscribe("stream-1", None),
+            Err(SubscribeError::NotFound(_))
+        ));
+
+        // Publishing to a closed session is a no-op
+        bus.publish("stream-1", &json!({"type": "stt_result"}));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_audio_decrypts_with_session_key() {
+        use crate::state::monitor_audio::decrypt_monitor_frame;
+
+        let bus = SessionEventBus::new();
+        bus.open("stream-1", None);
+        // Nothing is encrypted while no one listens
+        bus.publish_audio(
+            "stream-1",
+            AudioDirection::In,
+            SinkAudioFormat::mono(16000),
+            true,
+            &[9; 8],
+        );
+
+        let mut audio = bus.subscribe_audio("stream-1").unwrap();
+        bus.publish_audio(
+            "stream-1",
+            AudioDirection::In,
+            SinkAudioFormat::mono(16000),
+            true,
+            &[1, 2, 3, 4],
+        );
+        bus.publish_audio(
+            "stream-1",
+            AudioDirection::Out,
+            SinkAudioFormat::mono(24000),
+            true,
+            &[5, 6],
+        );
+
+        let key = bus.take_monitor_key("stream-1").unwrap();
+        assert_eq!(
+            bus.take_monitor_key("stream-1"),
+            Err(MonitorKeyError::AlreadyRetrieved("stream-1".to_string()))
+        );
+
+        let first = audio.recv().await.unwrap();
+        assert_eq!(first.counter, 0);
+        assert_eq!(first.sample_rate, 16000);
+        assert_eq!(first.channels, 1);
+        assert_eq!(
+            decrypt_monitor_frame(&key, first.direction, first.counter, &first.ciphertext).unwrap(),
+            vec![1, 2, 3, 4]
+        );
+        let second = audio.recv().await.unwrap();
+        assert_eq!(second.direction, AudioDirection::Out);
+        assert_eq!(
+            decrypt_monitor_frame(&key, second.direction, second.counter, &second.ciphertext)
+                .unwrap(),
+            vec![5, 6]
+        );
+
+        bus.close("stream-1");
+        assert!(matches!(
+            bus.take_monitor_key("stream-1"),
+            Err(MonitorKeyError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_audio_policy_downsamples_and_downmixes() {
+        use crate::state::monitor_audio::decrypt_monitor_frame;
+
+        let bus = SessionEventBus::with_monitor_policy(SinkAudioPolicy {
+            sample_rate: Some(8000),
+            mono: true,
+            opus_bitrate_kbps: None,
+        });
+        bus.open("stream-1", None);
+        let mut audio = bus.subscribe_audio("stream-1").unwrap();
+        let key = bus.take_monitor_key("stream-1").unwrap();
+
+        // 100ms of 16kHz stereo silence becomes about 100ms of 8kHz mono
+        let stereo = SinkAudioFormat {
+            sample_rate: 16000,
+            channels: 2,
+        };
+        bus.publish_audio("stream-1", AudioDirection::In, stereo, true, &[0; 6400]);
+        let frame = audio.recv().await.unwrap();
+        assert_eq!((frame.sample_rate, frame.channels), (8000, 1));
+        let pcm =
+            decrypt_monitor_frame(&key, frame.direction, frame.counter, &frame.ciphertext).unwrap();
+        assert!((1596..=1600).contains(&pcm.len()), "{}", pcm.len());
+
+        // Non-PCM audio is sent as it is
+        bus.publish_audio(
+            "stream-1",
+            AudioDirection::Out,
+            SinkAudioFormat::mono(8000),
+            false,
+            &[0xff; 160],
+        );
+        let frame = audio.recv().await.unwrap();
+        assert_eq!((frame.sample_rate, frame.channels), (8000, 1));
+        let audio =
+            decrypt_monitor_frame(&key, frame.direction, frame.counter, &frame.ciphertext).unwrap();
+        assert_eq!(audio, vec![0xff; 160]);
+    }
+}
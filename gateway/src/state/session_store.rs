@@ -0,0 +1,508 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use tracing::debug;
+
+/// Maximum total size of session metadata in bytes (sum of all keys and values).
+///
+/// Matches the S3 user-defined metadata limit so recordings can carry the
+/// full metadata map as object metadata.
+pub const MAX_SESSION_METADATA_SIZE: usize = 2 * 1024;
+
+/// Maximum number of metadata entries per session.
+///
+/// Matches the S3 object tag limit so every entry can be applied as a tag.
+pub const MAX_SESSION_METADATA_ENTRIES: usize = 10;
+
+/// Maximum length of a single metadata key (S3 tag key limit).
+pub const MAX_SESSION_METADATA_KEY_SIZE: usize = 128;
+
+/// Maximum length of a single metadata value (S3 tag value limit).
+pub const MAX_SESSION_METADATA_VALUE_SIZE: usize = 256;
+
+/// How long a played greeting is remembered after it was claimed.
+///
+/// A client that reconnects with the same `stream_id` within this window is
+/// resuming its session and does not hear the greeting again.
+pub const GREETING_RESUME_WINDOW_MS: u64 = 60 * 60 * 1000;
+
+/// Client-supplied key/value metadata attached to a session.
+///
+/// Values are restricted to strings so the map can be forwarded unchanged to
+/// webhooks and stored as S3 object metadata and tags on recordings.
+pub type SessionMetadata = HashMap<String, String>;
+
+/// Error returned when client-supplied session metadata violates a limit.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SessionMetadataError {
+    /// Total size of all keys and values exceeds the byte limit
+    #[error("Session metadata too large: {size} bytes (max: {max} bytes)")]
+    TooLarge { size: usize, max: usize },
+    /// Too many entries
+    #[error("Session metadata has too many entries: {count} (max: {max})")]
+    TooManyEntries { count: usize, max: usize },
+    /// A key is empty
+    #[error("Session metadata keys must not be empty")]
+    EmptyKey,
+    /// A single key exceeds the per-key limit
+    #[error("Session metadata key '{key}' too long: {size} bytes (max: {max} bytes)")]
+    KeyTooLong {
+        key: String,
+        size: usize,
+        max: usize,
+    },
+    /// A single value exceeds the per-value limit
+    #[error("Session metadata value for '{key}' too long: {size} bytes (max: {max} bytes)")]
+    ValueTooLong {
+        key: String,
+        size: usize,
+        max: usize,
+    },
+}
+
+/// Total size in bytes of all keys and values in the metadata map.
+pub fn session_metadata_size(metadata: &SessionMetadata) -> usize {
+    metadata.iter().map(|(k, v)| k.len() + v.len()).sum()
+}
+
+/// Validate client-supplied session metadata against the size limits.
+///
+/// # Returns
+/// * `Ok(())` if the metadata is within all limits
+/// * `Err(SessionMetadataError)` describing the first violated limit
+pub fn validate_session_metadata(metadata: &SessionMetadata) -> Result<(), SessionMetadataError> {
+    if metadata.len() > MAX_SESSION_METADATA_ENTRIES {
+        return Err(SessionMetadataError::TooManyEntries {
+            count: metadata.len(),
+            max: MAX_SESSION_METADATA_ENTRIES,
+        });
+    }
+
+    let size = session_metadata_size(metadata);
+    if size > MAX_SESSION_METADATA_SIZE {
+        return Err(SessionMetadataError::TooLarge {
+            size,
+            max: MAX_SESSION_METADATA_SIZE,
+        });
+    }
+
+    for (key, value) in metadata {
+        if key.is_empty() {
+            return Err(SessionMetadataError::EmptyKey);
+        }
+        if key.len() > MAX_SESSION_METADATA_KEY_SIZE {
+            return Err(SessionMetadataError::KeyTooLong {
+                key: key.clone(),
+                size: key.len(),
+                max: MAX_SESSION_METADATA_KEY_SIZE,
+            });
+        }
+        if value.len() > MAX_SESSION_METADATA_VALUE_SIZE {
+            return Err(SessionMetadataError::ValueTooLong {
+                key: key.clone(),
+                size: value.len(),
+                max: MAX_SESSION_METADATA_VALUE_SIZE,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Insert a gateway-recorded entry into session metadata without taking it
+/// past the limits.
+///
+/// Client metadata is validated on its own, so a client may already use the
+/// whole budget; entries the gateway adds afterwards must not push the map
+/// over the S3 metadata and tag limits. An entry replacing a key only counts
+/// its change in size.
+///
+/// # Returns
+/// * `Ok(())` if the entry was inserted
+/// * `Err(SessionMetadataError)` describing the violated limit, with the
+///   metadata left unchanged
+pub fn insert_session_metadata(
+    metadata: &mut SessionMetadata,
+    key: &str,
+    value: String,
+) -> Result<(), SessionMetadataError> {
+    if key.is_empty() {
+        return Err(SessionMetadataError::EmptyKey);
+    }
+    if key.len() > MAX_SESSION_METADATA_KEY_SIZE {
+        return Err(SessionMetadataError::KeyTooLong {
+            key: key.to_string(),
+            size: key.len(),
+            max: MAX_SESSION_METADATA_KEY_SIZE,
+        });
+    }
+    if value.len() > MAX_SESSION_METADATA_VALUE_SIZE {
+        return Err(SessionMetadataError::ValueTooLong {
+            key: key.to_string(),
+            size: value.len(),
+            max: MAX_SESSION_METADATA_VALUE_SIZE,
+        });
+    }
+
+    let replaced = metadata.get(key).map(|old| key.len() + old.len());
+    let count = metadata.len() + usize::from(replaced.is_none());
+    if count > MAX_SESSION_METADATA_ENTRIES {
+        return Err(SessionMetadataError::TooManyEntries {
+            count,
+            max: MAX_SESSION_METADATA_ENTRIES,
+        });
+    }
+    let size = session_metadata_size(metadata) - replaced.unwrap_or(0) + key.len() + value.len();
+    if size > MAX_SESSION_METADATA_SIZE {
+        return Err(SessionMetadataError::TooLarge {
+            size,
+            max: MAX_SESSION_METADATA_SIZE,
+        });
+    }
+
+    metadata.insert(key.to_string(), value);
+    Ok(())
+}
+
+/// A registered session and its client-supplied metadata.
+#[derive(Debug, Clone)]
+pub struct SessionEntry {
+    /// Session identifier (`stream_id` for `/ws`, `session_id` for `/realtime`)
+    pub stream_id: String,
+    /// LiveKit room bound to this session, if any
+    pub livekit_room_name: Option<String>,
+    /// Client-supplied metadata
+    pub metadata: SessionMetadata,
+    /// Registration time (milliseconds since epoch)
+    pub created_at_ms: u64,
+}
+
+/// Registry of active sessions keyed by session identifier.
+///
+/// Sessions are registered when a client sends its config message and removed
+/// when the connection terminates. Webhook forwarding and recording setup look
+/// up metadata here so it follows the session to every outbound sink.
+#[derive(Debug, Default)]
+pub struct SessionStore {
+    sessions: DashMap<String, SessionEntry>,
+    /// Sessions whose greeting was played, with the claim time (ms since epoch).
+    /// Kept after the session is removed so resumed sessions skip the greeting.
+    greetings: DashMap<String, u64>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a session with its metadata.
+    pub fn register(&self, stream_id: &str, metadata: SessionMetadata) {
+        let created_at_ms = now_ms();
+
+        debug!(
+            stream_id = %stream_id,
+            metadata_entries = metadata.len(),
+            "Registered session"
+        );
+
+        self.sessions.insert(
+            stream_id.to_string(),
+            SessionEntry {
+                stream_id: stream_id.to_string(),
+                livekit_room_name: None,
+                metadata,
+                created_at_ms,
+            },
+        );
+    }
+
+    /// Bind a LiveKit room to an existing session.
+    pub fn set_livekit_room(&self, stream_id: &str, room_name: &str) {
+        if let Some(mut entry) = self.sessions.get_mut(stream_id) {
+            entry.livekit_room_name = Some(room_name.to_string());
+        }
+    }
+
+    /// Set a server-recorded metadata entry of an existing session, replacing
+    /// any client-supplied value under the same key.
+    ///
+    /// The entry is only set if the metadata stays within the limits, see
+    /// [`insert_session_metadata`]. Unknown sessions are ignored.
+    pub fn set_metadata(
+        &self,
+        stream_id: &str,
+        key: &str,
+        value: String,
+    ) -> Result<(), SessionMetadataError> {
+        match self.sessions.get_mut(stream_id) {
+            Some(mut entry) => insert_session_metadata(&mut entry.metadata, key, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Get a snapshot of a session.
+    pub fn get(&self, stream_id: &str) -> Option<SessionEntry> {
+        self.sessions.get(stream_id).map(|entry| entry.clone())
+    }
+
+    /// Get the metadata of a session.
+    pub fn metadata(&self, stream_id: &str) -> Option<SessionMetadata> {
+        self.sessions
+            .get(stream_id)
+            .map(|entry| entry.metadata.clone())
+    }
+
+    /// Get the metadata of the session bound to a LiveKit room.
+    pub fn metadata_for_room(&self, room_name: &str) -> Option<SessionMetadata> {
+        self.sessions
+            .iter()
+            .find(|entry| entry.livekit_room_name.as_deref() == Some(room_name))
+            .map(|entry| entry.metadata.clone())
+    }
+
+    /// Remove a session, returning its last known state.
+    pub fn remove(&self, stream_id: &str) -> Option<SessionEntry> {
+        self.sessions.remove(stream_id).map(|(_, entry)| entry)
+    }
+
+    /// Number of registered sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Claim the greeting of a session.
+    ///
+    /// Returns `true` the first time it is called for a `stream_id`, and
+    /// `false` while the greeting is remembered (a re-sent config or a client
+    /// resuming the session), so the greeting plays at most once.
+    pub fn claim_greeting(&self, stream_id: &str) -> bool {
+        let now = now_ms();
+        self.greetings
+            .retain(|_, claimed_at| now.saturating_sub(*claimed_at) < GREETING_RESUME_WINDOW_MS);
+
+        match self.greetings.entry(stream_id.to_string()) {
+            dashmap::mapref::entry::Entry::Occupied(_) => false,
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(now);
+                true
+            }
+        }
+    }
+
+    /// Release a claimed greeting that could not be played.
+    pub fn release_greeting(&self, stream_id: &str) {
+        self.greetings.remove(stream_id);
+    }
+}
+
+/// Current time in milliseconds since epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(pairs: &[(&str, &str)]) -> SessionMetadata {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_session_metadata_ok() {
+        let md = metadata(&[("customer_id", "c-123"), ("campaign", "spring")]);
+        assert!(validate_session_metadata(&md).is_ok());
+        assert!(validate_session_metadata(&SessionMetadata::new()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_session_metadata_too_large() {
+        let mut md = SessionMetadata::new();
+        for i in 0..MAX_SESSION_METADATA_ENTRIES {
+            md.insert(
+                format!("key{i}"),
+                "v".repeat(MAX_SESSION_METADATA_VALUE_SIZE),
+            );
+        }
+        let err = validate_session_metadata(&md).unwrap_err();
+        assert!(
+            matches!(err, SessionMetadataError::TooLarge { max, .. } if max == MAX_SESSION_METADATA_SIZE)
+        );
+        assert!(err.to_string().contains("too large"));
+    }
+
+    #[test]
+    fn test_validate_session_metadata_too_many_entries() {
+        let mut md = SessionMetadata::new();
+        for i in 0..=MAX_SESSION_METADATA_ENTRIES {
+            md.insert(format!("k{i}"), "v".to_string());
+        }
+        assert_eq!(
+            validate_session_metadata(&md),
+            Err(SessionMetadataError::TooManyEntries {
+                count: MAX_SESSION_METADATA_ENTRIES + 1,
+                max: MAX_SESSION_METADATA_ENTRIES,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_session_metadata_key_and_value_limits() {
+        let md = metadata(&[("", "value")]);
+        assert_eq!(
+            validate_session_metadata(&md),
+            Err(SessionMetadataError::EmptyKey)
+        );
+
+        let long_key = "k".repeat(MAX_SESSION_METADATA_KEY_SIZE + 1);
+        let md = metadata(&[(long_key.as_str(), "value")]);
+        assert!(matches!(
+            validate_session_metadata(&md),
+            Err(SessionMetadataError::KeyTooLong { .. })
+        ));
+
+        let long_value = "v".repeat(MAX_SESSION_METADATA_VALUE_SIZE + 1);
+        let md = metadata(&[("key", long_value.as_str())]);
+        assert!(matches!(
+            validate_session_metadata(&md),
+            Err(SessionMetadataError::ValueTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_insert_session_metadata_within_limits() {
+        let mut md = metadata(&[("customer_id", "c-123")]);
+        insert_session_metadata(&mut md, "language_pack", "es".to_string()).unwrap();
+        assert_eq!(md["language_pack"], "es");
+
+        // A client already at the entry limit leaves no room for a new key,
+        // but an existing key can still be replaced
+        let mut md = SessionMetadata::new();
+        for i in 0..MAX_SESSION_METADATA_ENTRIES {
+            md.insert(format!("k{i}"), "v".to_string());
+        }
+        assert_eq!(
+            insert_session_metadata(&mut md, "language_pack", "es".to_string()),
+            Err(SessionMetadataError::TooManyEntries {
+                count: MAX_SESSION_METADATA_ENTRIES + 1,
+                max: MAX_SESSION_METADATA_ENTRIES,
+            })
+        );
+        assert!(!md.contains_key("language_pack"));
+        insert_session_metadata(&mut md, "k0", "replaced".to_string()).unwrap();
+        assert_eq!(md["k0"], "replaced");
+        assert!(validate_session_metadata(&md).is_ok());
+    }
+
+    #[test]
+    fn test_insert_session_metadata_size_limit() {
+        // A client at the byte limit with room for more entries
+        let mut md = SessionMetadata::new();
+        for i in 0..8 {
+            md.insert(
+                format!("key{i}"),
+                "v".repeat(MAX_SESSION_METADATA_VALUE_SIZE),
+            );
+        }
+        let fill = MAX_SESSION_METADATA_SIZE - session_metadata_size(&md) - "pad".len();
+        md.insert("pad".to_string(), "v".repeat(fill));
+        assert_eq!(session_metadata_size(&md), MAX_SESSION_METADATA_SIZE);
+
+        let err = insert_session_metadata(&mut md, "language_pack", "es".to_string()).unwrap_err();
+        assert!(matches!(err, SessionMetadataError::TooLarge { .. }));
+        assert_eq!(md.len(), 9);
+
+        // Shrinking an entry frees its bytes
+        insert_session_metadata(&mut md, "pad", String::new()).unwrap();
+        insert_session_metadata(&mut md, "language_pack", "es".to_string()).unwrap();
+        assert!(validate_session_metadata(&md).is_ok());
+    }
+
+    #[test]
+    fn test_session_store_lifecycle() {
+        let store = SessionStore::new();
+        assert!(store.is_empty());
+
+        store.register("stream-1", metadata(&[("customer_id", "c-123")]));
+        assert_eq!(store.len(), 1);
+        assert_eq!(
+            store.metadata("stream-1").unwrap().get("customer_id"),
+            Some(&"c-123".to_string())
+        );
+
+        // Room lookup only works once a room is bound
+        assert!(store.metadata_for_room("room-1").is_none());
+        store.set_livekit_room("stream-1", "room-1");
+        assert_eq!(
+            store
+                .metadata_for_room("room-1")
+                .unwrap()
+                .get("customer_id"),
+            Some(&"c-123".to_string())
+        );
+
+        store
+            .set_metadata("stream-1", "recording_consent", "granted".to_string())
+            .unwrap();
+        store
+            .set_metadata("stream-2", "recording_consent", "granted".to_string())
+            .unwrap();
+        assert_eq!(
+            store.metadata("stream-1").unwrap().get("recording_consent"),
+            Some(&"granted".to_string())
+        );
+        assert_eq!(store.len(), 1);
+
+        let removed = store.remove("stream-1").unwrap();
+        assert_eq!(removed.livekit_room_name.as_deref(), Some("room-1"));
+        assert!(store.get("stream-1").is_none());
+        assert!(store.metadata_for_room("room-1").is_none());
+    }
+
+    #[test]
+    fn test_set_metadata_with_client_at_limit() {
+        let store = SessionStore::new();
+        let mut md = SessionMetadata::new();
+        for i in 0..MAX_SESSION_METADATA_ENTRIES {
+            md.insert(format!("k{i}"), "v".to_string());
+        }
+        store.register("stream-1", md);
+
+        assert!(matches!(
+            store.set_metadata("stream-1", "recording_consent", "granted".to_string()),
+            Err(SessionMetadataError::TooManyEntries { .. })
+        ));
+        let md = store.metadata("stream-1").unwrap();
+        assert_eq!(md.len(), MAX_SESSION_METADATA_ENTRIES);
+        assert!(!md.contains_key("recording_consent"));
+        assert!(validate_session_metadata(&md).is_ok());
+    }
+
+    #[test]
+    fn test_greeting_claimed_once_per_stream() {
+        let store = SessionStore::new();
+        assert!(store.claim_greeting("stream-1"));
+        assert!(!store.claim_greeting("stream-1"));
+        assert!(store.claim_greeting("stream-2"));
+
+        // Removing the session keeps the claim so a resume does not replay it
+        store.register("stream-1", SessionMetadata::new());
+        store.remove("stream-1");
+        assert!(!store.claim_greeting("stream-1"));
+
+        // A released claim can be taken again
+        store.release_greeting("stream-1");
+        assert!(store.claim_greeting("stream-1"));
+    }
+}
@@ -0,0 +1,449 @@
+//! Progress of outbound SIP calls
+//!
+//! A session placing an outbound call (`livekit.outbound_call`) waits for
+//! the callee to answer before it starts STT and counts usage. The call's
+//! progress is read from the LiveKit SIP participant's `sip.callStatus`
+//! attribute, reported by LiveKit webhooks and by polling the room while the
+//! call is unanswered. Calls are tracked per LiveKit room.
+
+use std::collections::HashMap;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// SIP participant attribute holding the call status
+pub const SIP_CALL_STATUS_ATTRIBUTE: &str = "sip.callStatus";
+
+/// SIP participant attribute holding the final SIP response code, when the
+/// SIP gateway reports it
+pub const SIP_CALL_STATUS_CODE_ATTRIBUTE: &str = "sip.callStatusCode";
+
+/// Why an outbound call ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CallEndReason {
+    /// The callee was busy (SIP 486 or 600)
+    Busy,
+    /// The callee did not answer before the no-answer timer ran out, or the
+    /// call timed out in the network (SIP 408 or 480)
+    NoAnswer,
+    /// The call ended before it was answered for any other reason
+    Failed,
+    /// The call ended after it was answered
+    Hangup,
+}
+
+impl CallEndReason {
+    /// Name used in `call_state` messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Busy => "busy",
+            Self::NoAnswer => "no_answer",
+            Self::Failed => "failed",
+            Self::Hangup => "hangup",
+        }
+    }
+}
+
+/// State of an outbound call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallState {
+    /// The call is being placed
+    Dialing,
+    /// The callee's phone is ringing; early media may flow
+    EarlyMedia,
+    /// The callee answered
+    Answered,
+    /// The call is over
+    Ended(CallEndReason),
+}
+
+impl CallState {
+    /// Name used in `call_state` messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dialing => "dialing",
+            Self::EarlyMedia => "early_media",
+            Self::Answered => "answered",
+            Self::Ended(_) => "ended",
+        }
+    }
+
+    /// Why the call ended, once it has
+    pub fn end_reason(&self) -> Option<CallEndReason> {
+        match self {
+            Self::Ended(reason) => Some(*reason),
+            _ => None,
+        }
+    }
+
+    /// Whether the call was neither answered nor ended yet
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Dialing | Self::EarlyMedia)
+    }
+
+    /// State of the call after a signal
+    ///
+    /// Calls only move forward: a signal that would take the call back to an
+    /// earlier state, or that arrives after it ended, is ignored.
+    ///
+    /// # Returns
+    /// * `Some(CallState)` - The call's new state
+    /// * `None` - The signal does not change the call
+    pub fn advance(self, signal: CallSignal) -> Option<CallState> {
+        match (self, signal) {
+            (Self::Ended(_), _) => None,
+            (Self::Answered, CallSignal::HungUp(_)) => Some(Self::Ended(CallEndReason::Hangup)),
+            (Self::Answered, _) => None,
+            (_, CallSignal::Answered) => Some(Self::Answered),
+            (_, CallSignal::HungUp(code)) => Some(Self::Ended(match code {
+                Some(486 | 600) => CallEndReason::Busy,
+                Some(408 | 480) => CallEndReason::NoAnswer,
+                _ => CallEndReason::Failed,
+            })),
+            (_, CallSignal::NoAnswerTimeout) => Some(Self::Ended(CallEndReason::NoAnswer)),
+            (Self::Dialing, CallSignal::Ringing) => Some(Self::EarlyMedia),
+            _ => None,
+        }
+    }
+}
+
+/// What was learned about a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallSignal {
+    /// The call is being placed (`sip.callStatus: dialing`)
+    Dialing,
+    /// The callee's phone is ringing (`sip.callStatus: ringing`)
+    Ringing,
+    /// The callee answered (`sip.callStatus: active` or `automation`)
+    Answered,
+    /// The SIP participant hung up or left the room, with the final SIP
+    /// response code when known
+    HungUp(Option<u16>),
+    /// The no-answer timer ran out
+    NoAnswerTimeout,
+}
+
+impl CallSignal {
+    /// Read the call status from SIP participant attributes
+    ///
+    /// # Returns
+    /// * `Some(CallSignal)` - The status the attributes report
+    /// * `None` - No `sip.callStatus` attribute, or an unknown status
+    pub fn from_attributes(attributes: &HashMap<String, String>) -> Option<Self> {
+        match attributes.get(SIP_CALL_STATUS_ATTRIBUTE)?.as_str() {
+            "dialing" => Some(Self::Dialing),
+            "ringing" => Some(Self::Ringing),
+            "active" | "automation" => Some(Self::Answered),
+            "hangup" => Some(Self::HungUp(status_code(attributes))),
+            _ => None,
+        }
+    }
+
+    /// Signal of a SIP participant that left the room
+    pub fn left(attributes: &HashMap<String, String>) -> Self {
+        Self::HungUp(status_code(attributes))
+    }
+}
+
+fn status_code(attributes: &HashMap<String, String>) -> Option<u16> {
+    attributes
+        .get(SIP_CALL_STATUS_CODE_ATTRIBUTE)
+        .and_then(|code| code.trim().parse().ok())
+}
+
+struct TrackedCall {
+    state: watch::Sender<CallState>,
+    /// Identity of the SIP participant, once seen
+    participant: Option<String>,
+}
+
+/// Outbound calls by LiveKit room
+///
+/// A room is tracked from the moment a session places a call in it, or a
+/// webhook reports a call being dialed there. Calls that are answered
+/// before anyone tracks them (inbound calls) are never tracked.
+#[derive(Default)]
+pub struct SipCallRegistry {
+    calls: DashMap<String, TrackedCall>,
+}
+
+impl SipCallRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the outbound call of a room
+    ///
+    /// Progress reported before the room was tracked is kept.
+    pub fn track(&self, room: &str) -> watch::Receiver<CallState> {
+        self.calls
+            .entry(room.to_string())
+            .or_insert_with(|| TrackedCall {
+                state: watch::channel(CallState::Dialing).0,
+                participant: None,
+            })
+            .state
+            .subscribe()
+    }
+
+    /// Apply a signal to the call of a room
+    ///
+    /// A room that is not tracked yet starts being tracked on a dialing or
+    /// ringing signal; other signals for it are ignored.
+    ///
+    /// # Returns
+    /// * `Some(CallState)` - The call's new state
+    /// * `None` - The signal did not change the call
+    pub fn observe(
+        &self,
+        room: &str,
+        participant: Option<&str>,
+        signal: CallSignal,
+    ) -> Option<CallState> {
+        let mut call = match self.calls.get_mut(room) {
+            Some(call) => call,
+            None if matches!(signal, CallSignal::Dialing | CallSignal::Ringing) => {
+                self.track(room);
+                self.calls.get_mut(room)?
+            }
+            None => return None,
+        };
+        if let Some(participant) = participant {
+            call.participant = Some(participant.to_string());
+        }
+
+        let next = call.state.borrow().advance(signal)?;
+        debug!(room = %room, state = next.as_str(), "Outbound call progressed");
+        call.state.send_replace(next);
+
+        // Nobody is waiting on a finished call
+        let finished = matches!(next, CallState::Ended(_)) && call.state.receiver_count() == 0;
+        drop(call);
+        if finished {
+            self.calls.remove(room);
+        }
+        Some(next)
+    }
+
+    /// Current state of the call of a room, if it is tracked
+    pub fn state(&self, room: &str) -> Option<CallState> {
+        self.calls.get(room).map(|call| *call.state.borrow())
+    }
+
+    /// Identity of the SIP participant of a room's call, once seen
+    pub fn participant(&self, room: &str) -> Option<String> {
+        self.calls
+            .get(room)
+            .and_then(|call| call.participant.clone())
+    }
+
+    /// Stop tracking the call of a room
+    pub fn remove(&self, room: &str) {
+        self.calls.remove(room);
+    }
+
+    /// Wait for the next state of a call
+    ///
+    /// An unanswered call ends as [`CallEndReason::NoAnswer`] at `deadline`.
+    /// A call that stops being tracked before it ended is reported as
+    /// [`CallEndReason::Failed`].
+    pub async fn progress(
+        &self,
+        room: &str,
+        state: &mut watch::Receiver<CallState>,
+        deadline: Instant,
+    ) -> CallState {
+        if state.has_changed().unwrap_or(false) {
+            return *state.borrow_and_update();
+        }
+        tokio::select! {
+            changed = state.changed() => {
+                let current = *state.borrow_and_update();
+                match changed {
+                    Ok(()) => current,
+                    Err(_) if matches!(current, CallState::Ended(_)) => current,
+                    Err(_) => CallState::Ended(CallEndReason::Failed),
+                }
+            }
+            _ = tokio::time::sleep_until(deadline) => {
+                self.observe(room, None, CallSignal::NoAnswerTimeout);
+                *state.borrow_and_update()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn attributes(status: &str, code: Option<&str>) -> HashMap<String, String> {
+        let mut attributes =
+            HashMap::from([(SIP_CALL_STATUS_ATTRIBUTE.to_string(), status.to_string())]);
+        if let Some(code) = code {
+            attributes.insert(SIP_CALL_STATUS_CODE_ATTRIBUTE.to_string(), code.to_string());
+        }
+        attributes
+    }
+
+    /// Feed webhook-like attribute updates and collect the states a session sees
+    async fn states_seen(
+        registry: &SipCallRegistry,
+        updates: &[(&str, Option<&str>)],
+        timeout: Duration,
+    ) -> Vec<CallState> {
+        let mut state = registry.track("room");
+        for (status, code) in updates {
+            let signal = CallSignal::from_attributes(&attributes(status, *code)).unwrap();
+            registry.observe("room", Some("sip-callee"), signal);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut seen = vec![*state.borrow_and_update()];
+        while seen.last().unwrap().is_pending() {
+            let next = registry.progress("room", &mut state, deadline).await;
+            seen.push(next);
+        }
+        seen
+    }
+
+    #[test]
+    fn test_signal_from_attributes() {
+        let signal = |status: &str, code: Option<&str>| {
+            CallSignal::from_attributes(&attributes(status, code))
+        };
+        assert_eq!(signal("dialing", None), Some(CallSignal::Dialing));
+        assert_eq!(signal("ringing", None), Some(CallSignal::Ringing));
+        assert_eq!(signal("active", None), Some(CallSignal::Answered));
+        assert_eq!(signal("automation", None), Some(CallSignal::Answered));
+        assert_eq!(
+            signal("hangup", Some("486")),
+            Some(CallSignal::HungUp(Some(486)))
+        );
+        assert_eq!(
+            signal("hangup", Some("n/a")),
+            Some(CallSignal::HungUp(None))
+        );
+        assert_eq!(signal("transferring", None), None);
+        assert_eq!(CallSignal::from_attributes(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_calls_only_move_forward() {
+        use CallState::*;
+
+        assert_eq!(Dialing.advance(CallSignal::Ringing), Some(EarlyMedia));
+        assert_eq!(EarlyMedia.advance(CallSignal::Dialing), None);
+        assert_eq!(EarlyMedia.advance(CallSignal::Ringing), None);
+        // Some callees answer without ringing first
+        assert_eq!(Dialing.advance(CallSignal::Answered), Some(Answered));
+        assert_eq!(Answered.advance(CallSignal::Ringing), None);
+        assert_eq!(Answered.advance(CallSignal::NoAnswerTimeout), None);
+        assert_eq!(
+            Answered.advance(CallSignal::HungUp(Some(486))),
+            Some(Ended(CallEndReason::Hangup))
+        );
+        assert_eq!(
+            EarlyMedia.advance(CallSignal::HungUp(Some(480))),
+            Some(Ended(CallEndReason::NoAnswer))
+        );
+        assert_eq!(
+            Dialing.advance(CallSignal::HungUp(Some(503))),
+            Some(Ended(CallEndReason::Failed))
+        );
+        assert_eq!(
+            Ended(CallEndReason::Busy).advance(CallSignal::Answered),
+            None
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_answered_call() {
+        let registry = SipCallRegistry::new();
+        let seen = states_seen(
+            &registry,
+            &[("dialing", None), ("ringing", None), ("active", None)],
+            Duration::from_secs(30),
+        )
+        .await;
+        assert_eq!(seen, vec![CallState::Answered]);
+        assert_eq!(registry.participant("room").as_deref(), Some("sip-callee"));
+
+        // Hanging up after the answer is a plain hangup
+        registry.observe("room", None, CallSignal::HungUp(Some(486)));
+        assert_eq!(
+            registry.state("room"),
+            Some(CallState::Ended(CallEndReason::Hangup))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_busy_call() {
+        let registry = SipCallRegistry::new();
+        let mut state = registry.track("room");
+        registry.observe("room", Some("sip-callee"), CallSignal::Ringing);
+        let deadline = Instant::now() + Duration::from_secs(30);
+        assert_eq!(
+            registry.progress("room", &mut state, deadline).await,
+            CallState::EarlyMedia
+        );
+
+        let signal = CallSignal::from_attributes(&attributes("hangup", Some("486"))).unwrap();
+        registry.observe("room", None, signal);
+        assert_eq!(
+            registry.progress("room", &mut state, deadline).await,
+            CallState::Ended(CallEndReason::Busy)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unanswered_call_times_out() {
+        let registry = SipCallRegistry::new();
+        let started = Instant::now();
+        let seen = states_seen(
+            &registry,
+            &[("dialing", None), ("ringing", None)],
+            Duration::from_secs(30),
+        )
+        .await;
+        assert_eq!(
+            seen,
+            vec![
+                CallState::EarlyMedia,
+                CallState::Ended(CallEndReason::NoAnswer)
+            ]
+        );
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+
+        // An answer after the timer ran out is too late
+        assert_eq!(registry.observe("room", None, CallSignal::Answered), None);
+    }
+
+    #[test]
+    fn test_untracked_rooms() {
+        let registry = SipCallRegistry::new();
+        // Inbound calls are answered before anyone tracks them
+        assert_eq!(
+            registry.observe("inbound", None, CallSignal::Answered),
+            None
+        );
+        assert_eq!(registry.state("inbound"), None);
+
+        // A webhook may report the dialing before the session tracks the room
+        registry.observe("outbound", Some("sip-callee"), CallSignal::Ringing);
+        let state = registry.track("outbound");
+        assert_eq!(*state.borrow(), CallState::EarlyMedia);
+
+        // Finished calls nobody waits on are forgotten
+        drop(state);
+        registry.observe("outbound", None, CallSignal::HungUp(None));
+        assert_eq!(registry.state("outbound"), None);
+    }
+}
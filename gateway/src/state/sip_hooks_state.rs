@@ -100,6 +100,8 @@ mod tests {
             hooks: config_hooks.clone(),
             hook_secret: Some("global".to_string()),
             naming_prefix: "waav".to_string(),
+            language_routing: Default::default(),
+            outbound: Default::default(),
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -126,6 +128,8 @@ mod tests {
             hooks: config_hooks,
             hook_secret: Some("global".to_string()),
             naming_prefix: "waav".to_string(),
+            language_routing: Default::default(),
+            outbound: Default::default(),
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -157,6 +161,8 @@ mod tests {
             hooks: vec![],
             hook_secret: Some("global-secret".to_string()),
             naming_prefix: "waav".to_string(),
+            language_routing: Default::default(),
+            outbound: Default::default(),
         };
 
         let temp_dir = TempDir::new().unwrap();
@@ -184,6 +190,8 @@ mod tests {
             hooks: config_hooks.clone(),
             hook_secret: Some("global".to_string()),
             naming_prefix: "waav".to_string(),
+            language_routing: Default::default(),
+            outbound: Default::default(),
         };
 
         let state = SipHooksState::new(&sip_config, None).await;
@@ -218,6 +226,8 @@ mod tests {
             hooks: config_hooks,
             hook_secret: Some("global".to_string()),
             naming_prefix: "waav".to_string(),
+            language_routing: Default::default(),
+            outbound: Default::default(),
         };
 
         let state = SipHooksState::new(&sip_config, None).await;
@@ -0,0 +1,34 @@
+//! # Usage Records
+//!
+//! When a session ends the gateway writes one [`UsageRecord`] describing it:
+//! who used it, for how long, which STT/TTS or realtime providers it used and
+//! how much (audio seconds, characters, minutes), the estimated cost from the
+//! [pricing](crate::config::pricing) table and the recording size.
+//!
+//! Records go to the sinks configured in [`UsageConfig`](crate::config::UsageConfig):
+//! - `file` - appended to a JSON-lines file, rotated by size
+//! - `webhook` - POSTed as JSON, signed with the `X-WaaV-Signature` headers
+//!   used for SIP hook forwarding (`X-WaaV-Event-Id` is the record ID)
+//! - `both`
+//!
+//! With a [webhook queue](crate::webhooks) configured, webhook records are
+//! queued in the cache backend and delivered in the background instead.
+//!
+//! A record is written on every teardown, including when the connection
+//! handler panics or is cancelled; those records have `termination: aborted`.
+//!
+//! With `reconciliation_path` set, every record is also [reconciled](Reconciler):
+//! TTS utterances whose audio length is far from what their characters take
+//! to speak are reported for checking provider invoices.
+
+mod reconciliation;
+mod record;
+mod sink;
+
+pub use reconciliation::{
+    EXPECTED_CHARS_PER_SECOND, Reconciler, ReconciliationOutlier, ReconciliationSummary,
+};
+pub use record::{
+    RealtimeUsage, SttUsage, TtsUsage, TtsUtteranceUsage, UsageRecord, UsageTermination,
+};
+pub use sink::{UsageRecorder, UsageSinkError};
@@ -0,0 +1,378 @@
+//! Billing reconciliation of TTS usage
+//!
+//! Provider invoices occasionally disagree with our character and second
+//! counts. For every completed TTS utterance of a usage record, [`Reconciler`]
+//! compares the audio length measured from the synthesized WAV, MP3 or PCM
+//! audio with the length its characters take at a typical speaking rate.
+//! Utterances further off than the configured tolerance are appended to a
+//! JSON-lines report, and every checked utterance is added to a daily summary
+//! per provider, served by `GET /admin/reconciliation`.
+//!
+//! Reconciliation only observes; sessions and usage records are unaffected.
+
+use std::collections::BTreeMap;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::record::UsageRecord;
+use super::sink::{JsonLinesFile, UsageSinkError};
+use crate::config::UsageConfig;
+
+/// Characters per second of synthesized speech at the default speaking rate
+/// (about 150 words per minute), the rate providers document for estimates
+pub const EXPECTED_CHARS_PER_SECOND: f64 = 14.0;
+
+/// Utterances shorter than this are dominated by leading and trailing
+/// silence, so they are not checked
+const MIN_RECONCILED_CHARACTERS: u64 = 20;
+
+/// Days of summaries kept in memory
+const MAX_SUMMARY_DAYS: usize = 31;
+
+/// An utterance whose audio length is off by more than the tolerance
+///
+/// # Example JSON
+/// ```json
+/// {
+///   "record_id": "5f0c1d2e-...",
+///   "session_id": "call-1234",
+///   "provider": "elevenlabs",
+///   "model": "eleven_turbo_v2",
+///   "utterance": 3,
+///   "characters": 140,
+///   "audio_seconds": 2.1,
+///   "expected_seconds": 10.0,
+///   "deviation": -0.79,
+///   "ended_at": 1700000065000
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconciliationOutlier {
+    /// ID of the usage record the utterance belongs to
+    pub record_id: String,
+    /// Session (stream) ID
+    pub session_id: String,
+    /// TTS provider name
+    pub provider: String,
+    /// TTS model; empty when the provider default was used
+    pub model: String,
+    /// Position of the utterance in the session, from zero
+    pub utterance: usize,
+    /// Characters of text sent to the provider
+    pub characters: u64,
+    /// Seconds of audio the provider produced
+    pub audio_seconds: f64,
+    /// Seconds the characters take at [`EXPECTED_CHARS_PER_SECOND`]
+    pub expected_seconds: f64,
+    /// `(audio_seconds - expected_seconds) / expected_seconds`
+    pub deviation: f64,
+    /// Unix timestamp in milliseconds when the session ended
+    pub ended_at: u64,
+}
+
+/// Reconciliation totals of one provider on one day (UTC)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReconciliationSummary {
+    /// Day the sessions ended, `YYYY-MM-DD`
+    pub date: String,
+    /// TTS provider name
+    pub provider: String,
+    /// Sessions with at least one checked utterance
+    pub sessions: u64,
+    /// Utterances long enough to check
+    pub utterances: u64,
+    /// Checked utterances off by more than the tolerance
+    pub outliers: u64,
+    /// Characters of the checked utterances
+    pub characters: u64,
+    /// Seconds of audio of the checked utterances
+    pub audio_seconds: f64,
+    /// Seconds the checked characters take at the expected speaking rate
+    pub expected_seconds: f64,
+}
+
+/// Checks the TTS utterances of usage records and reports outliers
+pub struct Reconciler {
+    report: JsonLinesFile,
+    /// Largest tolerated deviation, as a fraction
+    tolerance: f64,
+    /// Summaries by date and provider
+    summaries: Mutex<BTreeMap<(String, String), ReconciliationSummary>>,
+}
+
+impl Reconciler {
+    /// Create a reconciler for a usage configuration
+    ///
+    /// The report is rotated like the usage file.
+    ///
+    /// # Returns
+    /// * `Some(Reconciler)` - `reconciliation_path` is set
+    /// * `None` - Reconciliation is off
+    pub fn new(config: &UsageConfig) -> Option<Self> {
+        let path = config.reconciliation_path.as_ref()?;
+        Some(Self {
+            report: JsonLinesFile::new(path.clone(), config.file_max_bytes, config.file_max_files),
+            tolerance: config.reconciliation_tolerance_percent as f64 / 100.0,
+            summaries: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Check every TTS utterance of a record
+    ///
+    /// Outliers are appended to the report; all checked utterances count
+    /// towards the day's summary of the record's TTS provider.
+    ///
+    /// # Returns
+    /// * `Ok(outliers)` - The utterances off by more than the tolerance
+    /// * `Err(UsageSinkError)` - The report could not be written; the summary
+    ///   is still updated
+    pub fn reconcile(
+        &self,
+        record: &UsageRecord,
+    ) -> Result<Vec<ReconciliationOutlier>, UsageSinkError> {
+        let Some(tts) = &record.tts else {
+            return Ok(Vec::new());
+        };
+
+        let mut summary = ReconciliationSummary {
+            date: utc_date(record.ended_at),
+            provider: tts.provider.clone(),
+            sessions: 1,
+            ..Default::default()
+        };
+        let mut outliers = Vec::new();
+        for (index, utterance) in record.tts_utterances.iter().enumerate() {
+            if utterance.characters < MIN_RECONCILED_CHARACTERS {
+                continue;
+            }
+            let expected_seconds = utterance.characters as f64 / EXPECTED_CHARS_PER_SECOND;
+            let deviation = (utterance.audio_seconds - expected_seconds) / expected_seconds;
+
+            summary.utterances += 1;
+            summary.characters += utterance.characters;
+            summary.audio_seconds += utterance.audio_seconds;
+            summary.expected_seconds += expected_seconds;
+            if deviation.abs() > self.tolerance {
+                summary.outliers += 1;
+                outliers.push(ReconciliationOutlier {
+                    record_id: record.record_id.clone(),
+                    session_id: record.session_id.clone(),
+                    provider: tts.provider.clone(),
+                    model: tts.model.clone(),
+                    utterance: index,
+                    characters: utterance.characters,
+                    audio_seconds: utterance.audio_seconds,
+                    expected_seconds,
+                    deviation,
+                    ended_at: record.ended_at,
+                });
+            }
+        }
+        if summary.utterances == 0 {
+            return Ok(outliers);
+        }
+        self.add_summary(summary);
+
+        if !outliers.is_empty() {
+            warn!(
+                session_id = %record.session_id,
+                provider = %tts.provider,
+                outliers = outliers.len(),
+                "TTS audio length does not match the characters sent"
+            );
+        }
+        for outlier in &outliers {
+            self.report.append(&serde_json::to_string(outlier)?)?;
+        }
+        Ok(outliers)
+    }
+
+    /// Daily summaries per provider, oldest day first
+    pub fn summaries(&self) -> Vec<ReconciliationSummary> {
+        self.summaries.lock().values().cloned().collect()
+    }
+
+    fn add_summary(&self, summary: ReconciliationSummary) {
+        let mut summaries = self.summaries.lock();
+        let totals = summaries
+            .entry((summary.date.clone(), summary.provider.clone()))
+            .or_insert_with(|| ReconciliationSummary {
+                date: summary.date.clone(),
+                provider: summary.provider.clone(),
+                ..Default::default()
+            });
+        totals.sessions += summary.sessions;
+        totals.utterances += summary.utterances;
+        totals.outliers += summary.outliers;
+        totals.characters += summary.characters;
+        totals.audio_seconds += summary.audio_seconds;
+        totals.expected_seconds += summary.expected_seconds;
+
+        // Forget the oldest days once more than MAX_SUMMARY_DAYS are kept
+        loop {
+            let mut dates: Vec<&String> = summaries.keys().map(|(date, _)| date).collect();
+            dates.dedup();
+            if dates.len() <= MAX_SUMMARY_DAYS {
+                break;
+            }
+            let oldest = dates[0].clone();
+            summaries.retain(|(date, _), _| *date != oldest);
+        }
+    }
+}
+
+/// UTC date of a Unix timestamp in milliseconds, `YYYY-MM-DD`
+fn utc_date(timestamp_ms: u64) -> String {
+    time::OffsetDateTime::from_unix_timestamp_nanos(timestamp_ms as i128 * 1_000_000)
+        .map(|time| time.date().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UsageSinkKind;
+    use crate::usage::{TtsUsage, TtsUtteranceUsage, UsageTermination};
+    use tempfile::TempDir;
+
+    /// 2023-11-14T22:13:20Z
+    const ENDED_AT: u64 = 1_700_000_000_000;
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    fn reconciler(dir: &TempDir) -> Reconciler {
+        let config = UsageConfig {
+            reconciliation_path: Some(dir.path().join("reconciliation.jsonl")),
+            ..UsageConfig::new(UsageSinkKind::File)
+        };
+        Reconciler::new(&config).unwrap()
+    }
+
+    fn record(ended_at: u64, utterances: &[(u64, f64)]) -> UsageRecord {
+        UsageRecord {
+            record_id: "record-1".to_string(),
+            session_id: "call-1".to_string(),
+            client_id: None,
+            started_at: ended_at - 60_000,
+            ended_at,
+            duration_seconds: 60.0,
+            termination: UsageTermination::Closed,
+            stt: None,
+            stt_secondary: None,
+            stt_fast: None,
+            stt_turns: Vec::new(),
+            tts: Some(TtsUsage {
+                provider: "elevenlabs".to_string(),
+                model: "eleven_turbo_v2".to_string(),
+                characters: utterances.iter().map(|(characters, _)| characters).sum(),
+                audio_seconds: utterances.iter().map(|(_, seconds)| seconds).sum(),
+                estimated_cost_usd: None,
+            }),
+            realtime: None,
+            estimated_cost_usd: None,
+            recording_bytes: None,
+            turns: 0,
+            turn_ids: Vec::new(),
+            feature_flags: Default::default(),
+            consent: None,
+            tts_utterances: utterances
+                .iter()
+                .map(|&(characters, audio_seconds)| TtsUtteranceUsage {
+                    characters,
+                    audio_seconds,
+                })
+                .collect(),
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_outliers_beyond_tolerance_are_reported() {
+        let dir = TempDir::new().unwrap();
+        let reconciler = reconciler(&dir);
+
+        // 140 characters are expected to take 10 seconds; 50% tolerance
+        let outliers = reconciler
+            .reconcile(&record(
+                ENDED_AT,
+                &[(140, 11.0), (140, 2.0), (10, 60.0), (140, 16.0)],
+            ))
+            .unwrap();
+        assert_eq!(outliers.len(), 2);
+        assert_eq!(outliers[0].utterance, 1);
+        assert!((outliers[0].deviation + 0.8).abs() < 1e-9);
+        assert_eq!(outliers[1].utterance, 3);
+        assert_eq!(outliers[1].expected_seconds, 10.0);
+
+        let report = std::fs::read_to_string(dir.path().join("reconciliation.jsonl")).unwrap();
+        let lines: Vec<ReconciliationOutlier> = report
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, outliers);
+    }
+
+    #[test]
+    fn test_summaries_aggregate_per_day_and_provider() {
+        let dir = TempDir::new().unwrap();
+        let reconciler = reconciler(&dir);
+
+        reconciler
+            .reconcile(&record(ENDED_AT, &[(140, 10.0)]))
+            .unwrap();
+        reconciler
+            .reconcile(&record(ENDED_AT + 1000, &[(70, 20.0), (5, 1.0)]))
+            .unwrap();
+        reconciler
+            .reconcile(&record(ENDED_AT + DAY_MS, &[(140, 10.0)]))
+            .unwrap();
+
+        let summaries = reconciler.summaries();
+        assert_eq!(summaries.len(), 2);
+        let first = &summaries[0];
+        assert_eq!(first.date, "2023-11-14");
+        assert_eq!(first.provider, "elevenlabs");
+        assert_eq!(first.sessions, 2);
+        assert_eq!(first.utterances, 2);
+        assert_eq!(first.outliers, 1);
+        assert_eq!(first.characters, 210);
+        assert_eq!(first.audio_seconds, 30.0);
+        assert_eq!(first.expected_seconds, 15.0);
+        assert_eq!(summaries[1].date, "2023-11-15");
+
+        // Only the most recent days are kept
+        for day in 2..=MAX_SUMMARY_DAYS as u64 {
+            reconciler
+                .reconcile(&record(ENDED_AT + day * DAY_MS, &[(140, 10.0)]))
+                .unwrap();
+        }
+        let summaries = reconciler.summaries();
+        assert_eq!(summaries.len(), MAX_SUMMARY_DAYS);
+        assert_eq!(summaries[0].date, "2023-11-15");
+    }
+
+    #[test]
+    fn test_sessions_without_checked_utterances_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        let reconciler = reconciler(&dir);
+        assert!(
+            reconciler
+                .reconcile(&record(ENDED_AT, &[]))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            reconciler
+                .reconcile(&record(ENDED_AT, &[(5, 9.0)]))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(reconciler.summaries().is_empty());
+        assert!(!dir.path().join("reconciliation.jsonl").exists());
+
+        let config = UsageConfig::new(UsageSinkKind::File);
+        assert!(Reconciler::new(&config).is_none());
+    }
+}
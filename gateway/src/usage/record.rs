@@ -0,0 +1,551 @@
+//! The usage record written for every session
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{
+    FeatureFlags, PricingUnit, estimate_stt_cost, estimate_tts_cost, get_tts_pricing,
+};
+use crate::core::session::{ConsentRecord, SessionUsage};
+use crate::core::stt::{STTRoute, UtteranceKind};
+use crate::state::SessionMetadata;
+
+/// How a session ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageTermination {
+    /// The connection closed and the session was torn down normally
+    Closed,
+    /// A new configuration replaced the session mid-connection
+    Reconfigured,
+    /// The handler panicked or was cancelled before teardown finished
+    Aborted,
+}
+
+/// STT usage of a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SttUsage {
+    /// STT provider name
+    pub provider: String,
+    /// STT model; empty when the provider default was used
+    pub model: String,
+    /// Seconds of audio sent to the provider
+    pub audio_seconds: f64,
+    /// Estimated cost in USD, if the model is in the pricing table
+    pub estimated_cost_usd: Option<f64>,
+}
+
+/// STT provider that transcribed one user turn of a routed session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SttTurnUsage {
+    /// ID of the user turn
+    pub turn_id: String,
+    /// STT provider name
+    pub provider: String,
+    /// STT model; empty when the provider default was used
+    pub model: String,
+    /// Whether the premium or the fast provider transcribed the turn
+    pub route: STTRoute,
+    /// Latest `expect` hint when the turn started, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<UtteranceKind>,
+}
+
+/// TTS usage of a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsUsage {
+    /// TTS provider name
+    pub provider: String,
+    /// TTS model; empty when the provider default was used
+    pub model: String,
+    /// Characters of text sent to the provider
+    pub characters: u64,
+    /// Seconds of audio produced
+    pub audio_seconds: f64,
+    /// Estimated cost in USD, if the model is in the pricing table
+    pub estimated_cost_usd: Option<f64>,
+}
+
+/// Text and audio of one TTS utterance of a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsUtteranceUsage {
+    /// Characters of text sent to the provider
+    pub characters: u64,
+    /// Seconds of audio produced, measured from WAV and MP3 containers
+    pub audio_seconds: f64,
+}
+
+/// Realtime provider usage of a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealtimeUsage {
+    /// Realtime provider name
+    pub provider: String,
+    /// Realtime model
+    pub model: String,
+    /// Minutes the provider connection was open
+    pub minutes: f64,
+}
+
+/// Machine-readable usage of one session, written when it ends
+///
+/// # Example JSON
+/// ```json
+/// {
+///   "record_id": "5f0c1d2e-...",
+///   "session_id": "call-1234",
+///   "client_id": "project1",
+///   "started_at": 1700000000000,
+///   "ended_at": 1700000065000,
+///   "duration_seconds": 65.0,
+///   "termination": "closed",
+///   "stt": {"provider": "deepgram", "model": "nova-2", "audio_seconds": 60.0, "estimated_cost_usd": 0.0043},
+///   "tts": {"provider": "openai", "model": "tts-1", "characters": 420, "audio_seconds": 24.5, "estimated_cost_usd": 0.0063},
+///   "tts_utterances": [{"characters": 180, "audio_seconds": 10.5}, {"characters": 240, "audio_seconds": 14.0}],
+///   "realtime": null,
+///   "estimated_cost_usd": 0.0106,
+///   "recording_bytes": 1048576,
+///   "turns": 2,
+///   "turn_ids": ["9b2e7c4a-...", "turn-2"],
+///   "feature_flags": {"adaptive_endpointing": true, "echo_guard": false},
+///   "consent": {"state": "granted", "source": "dtmf", "participant": "sip_caller", "timestamp": 1700000004000},
+///   "metadata": {"customer_id": "c-123", "recording_consent": "granted"}
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// Unique ID of this record, for de-duplicating webhook retries
+    pub record_id: String,
+    /// Session (stream) ID
+    pub session_id: String,
+    /// Authenticated client ID, when auth is enabled
+    pub client_id: Option<String>,
+    /// Unix timestamp in milliseconds when the session started
+    pub started_at: u64,
+    /// Unix timestamp in milliseconds when the session ended
+    pub ended_at: u64,
+    /// Wall-clock session duration in seconds
+    pub duration_seconds: f64,
+    /// How the session ended
+    pub termination: UsageTermination,
+    /// STT usage (voice sessions)
+    ///
+    /// With STT failover, the primary provider's usage.
+    pub stt: Option<SttUsage>,
+    /// Usage of the secondary STT provider (voice sessions with STT failover)
+    ///
+    /// A warm standby receives the same audio as the primary, so both are billed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stt_secondary: Option<SttUsage>,
+    /// Usage of the fast STT provider (voice sessions with turn routing)
+    ///
+    /// Only the audio of turns routed to it is billed here; `stt` covers the rest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stt_fast: Option<SttUsage>,
+    /// Provider that transcribed each user turn (voice sessions with turn
+    /// routing, first 1000 turns)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stt_turns: Vec<SttTurnUsage>,
+    /// TTS usage (voice sessions)
+    pub tts: Option<TtsUsage>,
+    /// Realtime usage (realtime sessions)
+    pub realtime: Option<RealtimeUsage>,
+    /// Sum of the estimated STT (primary, secondary and fast) and TTS costs in
+    /// USD, if any is priced
+    pub estimated_cost_usd: Option<f64>,
+    /// Size of the session recording in bytes as reported when it was stopped
+    pub recording_bytes: Option<u64>,
+    /// Number of distinct user and `speak` turns
+    #[serde(default)]
+    pub turns: u64,
+    /// IDs of the session's turns in the order they started (first 1000)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub turn_ids: Vec<String>,
+    /// Feature flags the session was built with (omitted when none are configured)
+    #[serde(default, skip_serializing_if = "FeatureFlags::is_empty")]
+    pub feature_flags: FeatureFlags,
+    /// The caller's recording consent (sessions configured with a consent gate)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consent: Option<ConsentRecord>,
+    /// Text and audio of each completed TTS utterance (voice sessions, first
+    /// 1000), checked by [reconciliation](super::Reconciler)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tts_utterances: Vec<TtsUtteranceUsage>,
+    /// The session's metadata when it ended, including the entries the
+    /// gateway recorded (omitted when empty)
+    #[serde(default, skip_serializing_if = "SessionMetadata::is_empty")]
+    pub metadata: SessionMetadata,
+}
+
+impl UsageRecord {
+    /// Build the record for a session from its usage snapshot
+    ///
+    /// Costs are estimated from the pricing module. A session that was never
+    /// closed (aborted teardown) ends now.
+    ///
+    /// # Arguments
+    /// * `session_id` - Session (stream) ID
+    /// * `client_id` - Authenticated client ID, if any
+    /// * `usage` - Usage snapshot from `Session::usage`
+    /// * `termination` - How the session ended
+    /// * `recording_bytes` - Recording size, if the session was recorded
+    pub fn new(
+        session_id: impl Into<String>,
+        client_id: Option<String>,
+        usage: &SessionUsage,
+        termination: UsageTermination,
+        recording_bytes: Option<u64>,
+    ) -> Self {
+        let ended_at = usage.ended_at.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64
+        });
+        let duration_seconds = ended_at.saturating_sub(usage.started_at) as f64 / 1000.0;
+
+        let stt = usage.stt.as_ref().map(|stt| SttUsage {
+            provider: stt.provider.clone(),
+            model: stt.model.clone(),
+            audio_seconds: usage.stt_audio_seconds,
+            estimated_cost_usd: estimate_stt_cost(
+                &stt.provider,
+                &stt.model,
+                usage.stt_audio_seconds,
+            ),
+        });
+        let stt_secondary = usage.stt_secondary.as_ref().map(|stt| SttUsage {
+            provider: stt.provider.clone(),
+            model: stt.model.clone(),
+            audio_seconds: usage.stt_secondary_audio_seconds,
+            estimated_cost_usd: estimate_stt_cost(
+                &stt.provider,
+                &stt.model,
+                usage.stt_secondary_audio_seconds,
+            ),
+        });
+        let stt_fast = usage.stt_fast.as_ref().map(|stt| SttUsage {
+            provider: stt.provider.clone(),
+            model: stt.model.clone(),
+            audio_seconds: usage.stt_fast_audio_seconds,
+            estimated_cost_usd: estimate_stt_cost(
+                &stt.provider,
+                &stt.model,
+                usage.stt_fast_audio_seconds,
+            ),
+        });
+        let stt_turns = usage
+            .stt_turns
+            .iter()
+            .map(|turn| SttTurnUsage {
+                turn_id: turn.turn_id.clone(),
+                provider: turn.stt.provider.clone(),
+                model: turn.stt.model.clone(),
+                route: turn.route,
+                expected: turn.expected,
+            })
+            .collect();
+        let tts = usage.tts.as_ref().map(|tts| TtsUsage {
+            provider: tts.provider.clone(),
+            model: tts.model.clone(),
+            characters: usage.tts_characters,
+            audio_seconds: usage.tts_audio_seconds,
+            estimated_cost_usd: estimate_tts_usage_cost(
+                &tts.provider,
+                &tts.model,
+                usage.tts_characters,
+                usage.tts_audio_seconds,
+            ),
+        });
+        let tts_utterances = usage
+            .tts_utterances
+            .iter()
+            .map(|utterance| TtsUtteranceUsage {
+                characters: utterance.characters,
+                audio_seconds: utterance.audio_ms as f64 / 1000.0,
+            })
+            .collect();
+        let realtime = usage.realtime.as_ref().map(|realtime| RealtimeUsage {
+            provider: realtime.provider.clone(),
+            model: realtime.model.clone(),
+            minutes: duration_seconds / 60.0,
+        });
+
+        let costs = [
+            stt.as_ref().and_then(|s| s.estimated_cost_usd),
+            stt_secondary.as_ref().and_then(|s| s.estimated_cost_usd),
+            stt_fast.as_ref().and_then(|s| s.estimated_cost_usd),
+            tts.as_ref().and_then(|t| t.estimated_cost_usd),
+        ];
+        let estimated_cost_usd = costs
+            .iter()
+            .any(Option::is_some)
+            .then(|| costs.iter().flatten().sum());
+
+        Self {
+            record_id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.into(),
+            client_id,
+            started_at: usage.started_at,
+            ended_at,
+            duration_seconds,
+            termination,
+            stt,
+            stt_secondary,
+            stt_fast,
+            stt_turns,
+            tts,
+            realtime,
+            estimated_cost_usd,
+            recording_bytes,
+            turns: usage.turns,
+            turn_ids: usage.turn_ids.clone(),
+            feature_flags: usage.feature_flags.clone(),
+            consent: usage.consent.clone(),
+            tts_utterances,
+            metadata: SessionMetadata::new(),
+        }
+    }
+
+    /// Attach the session's metadata, as read from the session store when
+    /// the session ended
+    pub fn with_metadata(mut self, metadata: SessionMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+/// Estimate TTS cost from characters, or from audio duration for models
+/// priced by time
+fn estimate_tts_usage_cost(
+    provider: &str,
+    model: &str,
+    characters: u64,
+    audio_seconds: f64,
+) -> Option<f64> {
+    let pricing = get_tts_pricing(provider, model)?;
+    match pricing.unit {
+        PricingUnit::Per1KChars | PricingUnit::Per1MChars => {
+            estimate_tts_cost(provider, model, characters as usize)
+        }
+        PricingUnit::PerHour | PricingUnit::PerMinute | PricingUnit::PerSecond => {
+            Some(pricing.to_per_hour() * audio_seconds / 3600.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session::{ConsentSource, ConsentState, ProviderModel, TurnSTTUsage};
+
+    fn voice_usage() -> SessionUsage {
+        SessionUsage {
+            started_at: 1_700_000_000_000,
+            ended_at: Some(1_700_000_065_000),
+            stt: Some(ProviderModel {
+                provider: "deepgram".to_string(),
+                model: "nova-2".to_string(),
+            }),
+            stt_audio_seconds: 60.0,
+            stt_secondary: None,
+            stt_secondary_audio_seconds: 0.0,
+            stt_fast: None,
+            stt_fast_audio_seconds: 0.0,
+            stt_turns: Vec::new(),
+            tts: Some(ProviderModel {
+                provider: "openai".to_string(),
+                model: "tts-1".to_string(),
+            }),
+            tts_characters: 2000,
+            tts_audio_seconds: 24.5,
+            tts_utterances: Vec::new(),
+            realtime: None,
+            turns: 2,
+            turn_ids: vec!["turn-1".to_string(), "turn-2".to_string()],
+            feature_flags: FeatureFlags::default(),
+            consent: None,
+        }
+    }
+
+    #[test]
+    fn test_record_estimates_costs() {
+        let record = UsageRecord::new(
+            "call-1234",
+            Some("project1".to_string()),
+            &voice_usage(),
+            UsageTermination::Closed,
+            Some(1024),
+        );
+
+        assert_eq!(record.session_id, "call-1234");
+        assert_eq!(record.duration_seconds, 65.0);
+        let stt_cost = record.stt.as_ref().unwrap().estimated_cost_usd.unwrap();
+        assert!((stt_cost - 0.0043).abs() < 1e-9);
+        let tts_cost = record.tts.as_ref().unwrap().estimated_cost_usd.unwrap();
+        assert!((tts_cost - 0.03).abs() < 1e-9);
+        assert!((record.estimated_cost_usd.unwrap() - (stt_cost + tts_cost)).abs() < 1e-9);
+        assert!(record.realtime.is_none());
+        assert_eq!(record.recording_bytes, Some(1024));
+        assert_eq!(record.turns, 2);
+        assert_eq!(record.turn_ids, vec!["turn-1", "turn-2"]);
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert!(json.get("stt_secondary").is_none());
+        assert!(json.get("stt_fast").is_none());
+        assert!(json.get("stt_turns").is_none());
+        assert!(json.get("feature_flags").is_none());
+        assert!(json.get("consent").is_none());
+        assert!(json.get("tts_utterances").is_none());
+        assert!(json.get("metadata").is_none());
+    }
+
+    #[test]
+    fn test_record_reports_feature_flags() {
+        let usage = SessionUsage {
+            feature_flags: FeatureFlags::from(std::collections::BTreeMap::from([
+                ("adaptive_endpointing".to_string(), true),
+                ("echo_guard".to_string(), false),
+            ])),
+            ..voice_usage()
+        };
+
+        let record = UsageRecord::new("call", None, &usage, UsageTermination::Closed, None);
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json["feature_flags"],
+            serde_json::json!({"adaptive_endpointing": true, "echo_guard": false})
+        );
+        let parsed: UsageRecord = serde_json::from_value(json).unwrap();
+        assert!(parsed.feature_flags.is_enabled("adaptive_endpointing"));
+    }
+
+    #[test]
+    fn test_record_reports_consent() {
+        let consent = ConsentRecord {
+            state: ConsentState::Granted,
+            source: Some(ConsentSource::Dtmf),
+            participant: Some("sip_caller".to_string()),
+            timestamp: Some(1_700_000_004_000),
+        };
+        let usage = SessionUsage {
+            consent: Some(consent.clone()),
+            ..voice_usage()
+        };
+
+        let record = UsageRecord::new("call", None, &usage, UsageTermination::Closed, None);
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["consent"]["state"], "granted");
+        assert_eq!(json["consent"]["source"], "dtmf");
+        assert_eq!(json["consent"]["timestamp"], 1_700_000_004_000u64);
+        let parsed: UsageRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.consent, Some(consent));
+    }
+
+    #[test]
+    fn test_stt_failover_bills_both_providers() {
+        let usage = SessionUsage {
+            stt_secondary: Some(ProviderModel {
+                provider: "deepgram".to_string(),
+                model: "nova-2".to_string(),
+            }),
+            stt_secondary_audio_seconds: 60.0,
+            ..voice_usage()
+        };
+
+        let record = UsageRecord::new("call", None, &usage, UsageTermination::Closed, None);
+        let stt_cost = record.stt.as_ref().unwrap().estimated_cost_usd.unwrap();
+        let secondary = record.stt_secondary.as_ref().unwrap();
+        assert_eq!(secondary.audio_seconds, 60.0);
+        let secondary_cost = secondary.estimated_cost_usd.unwrap();
+        assert!((secondary_cost - stt_cost).abs() < 1e-9);
+        let tts_cost = record.tts.as_ref().unwrap().estimated_cost_usd.unwrap();
+        assert!(
+            (record.estimated_cost_usd.unwrap() - (stt_cost + secondary_cost + tts_cost)).abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_stt_routing_reports_fast_usage_and_turns() {
+        let fast = ProviderModel {
+            provider: "deepgram".to_string(),
+            model: "nova-2".to_string(),
+        };
+        let usage = SessionUsage {
+            stt_audio_seconds: 45.0,
+            stt_fast: Some(fast.clone()),
+            stt_fast_audio_seconds: 15.0,
+            stt_turns: vec![TurnSTTUsage {
+                turn_id: "turn-2".to_string(),
+                stt: fast,
+                route: STTRoute::Fast,
+                expected: Some(UtteranceKind::Digits),
+            }],
+            ..voice_usage()
+        };
+
+        let record = UsageRecord::new("call", None, &usage, UsageTermination::Closed, None);
+        let stt_cost = record.stt.as_ref().unwrap().estimated_cost_usd.unwrap();
+        let fast_cost = record
+            .stt_fast
+            .as_ref()
+            .unwrap()
+            .estimated_cost_usd
+            .unwrap();
+        assert!((fast_cost - stt_cost / 3.0).abs() < 1e-9);
+        let tts_cost = record.tts.as_ref().unwrap().estimated_cost_usd.unwrap();
+        assert!(
+            (record.estimated_cost_usd.unwrap() - (stt_cost + fast_cost + tts_cost)).abs() < 1e-9
+        );
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json["stt_turns"],
+            serde_json::json!([{
+                "turn_id": "turn-2",
+                "provider": "deepgram",
+                "model": "nova-2",
+                "route": "fast",
+                "expected": "digits"
+            }])
+        );
+        let parsed: UsageRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.stt_turns, record.stt_turns);
+    }
+
+    #[test]
+    fn test_unpriced_models_have_no_cost() {
+        let mut usage = voice_usage();
+        usage.stt.as_mut().unwrap().model = "unknown-model".to_string();
+        usage.tts.as_mut().unwrap().model = "unknown-model".to_string();
+
+        let record = UsageRecord::new("call", None, &usage, UsageTermination::Aborted, None);
+        assert_eq!(record.stt.unwrap().estimated_cost_usd, None);
+        assert_eq!(record.estimated_cost_usd, None);
+    }
+
+    #[test]
+    fn test_realtime_minutes_follow_duration() {
+        let usage = SessionUsage {
+            stt: None,
+            stt_audio_seconds: 0.0,
+            tts: None,
+            tts_characters: 0,
+            tts_audio_seconds: 0.0,
+            realtime: Some(ProviderModel {
+                provider: "openai".to_string(),
+                model: "gpt-4o-realtime-preview".to_string(),
+            }),
+            ..voice_usage()
+        };
+
+        let record = UsageRecord::new("call", None, &usage, UsageTermination::Closed, None);
+        let realtime = record.realtime.as_ref().unwrap();
+        assert!((realtime.minutes - 65.0 / 60.0).abs() < 1e-9);
+        assert_eq!(record.estimated_cost_usd, None);
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["termination"], "closed");
+        assert!(json["stt"].is_null());
+    }
+}
@@ -0,0 +1,372 @@
+//! Usage record sinks: a rotating JSON-lines file and a signed webhook
+
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use thiserror::Error;
+use tracing::{debug, error, warn};
+
+use super::reconciliation::{Reconciler, ReconciliationSummary};
+use super::record::UsageRecord;
+use crate::config::UsageConfig;
+use crate::utils::webhook_signing::generate_webhook_signature;
+use crate::webhooks::{USAGE_DESTINATION, WebhookDispatcher};
+
+/// Timeout for a usage webhook request
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors raised while writing a usage record
+#[derive(Debug, Error)]
+pub enum UsageSinkError {
+    #[error("Failed to serialize usage record: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("Failed to write usage file {path}: {source}")]
+    File { path: PathBuf, source: io::Error },
+
+    #[error("Usage webhook failed: {0}")]
+    Webhook(String),
+}
+
+/// Writes usage records to the sinks in a [`UsageConfig`]
+///
+/// Every record goes to each configured sink; a failing sink is logged and
+/// does not stop the others. With `reconciliation_path` set, the record's TTS
+/// usage is also [reconciled](Reconciler).
+pub struct UsageRecorder {
+    file: Option<JsonLinesFile>,
+    webhook: Option<UsageWebhook>,
+    reconciler: Option<Reconciler>,
+}
+
+impl UsageRecorder {
+    /// Create a recorder for a validated usage configuration
+    ///
+    /// # Errors
+    /// Returns `UsageSinkError::Webhook` if the HTTP client cannot be built
+    pub fn new(config: &UsageConfig) -> Result<Self, UsageSinkError> {
+        let file = config
+            .file_path
+            .as_ref()
+            .filter(|_| config.sink.writes_file())
+            .map(|path| {
+                JsonLinesFile::new(path.clone(), config.file_max_bytes, config.file_max_files)
+            });
+
+        let webhook = match (&config.webhook_url, &config.webhook_secret) {
+            (Some(url), Some(secret)) if config.sink.posts_webhook() => {
+                let client = reqwest::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()
+                    .map_err(|e| UsageSinkError::Webhook(e.to_string()))?;
+                Some(UsageWebhook {
+                    client,
+                    url: url.clone(),
+                    secret: secret.clone(),
+                    queue: None,
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            file,
+            webhook,
+            reconciler: Reconciler::new(config),
+        })
+    }
+
+    /// Deliver webhook records through the durable webhook queue
+    ///
+    /// Records are queued for the `usage` destination instead of being
+    /// POSTed inline. Does nothing without a webhook sink.
+    pub fn with_webhook_queue(mut self, queue: Arc<WebhookDispatcher>) -> Self {
+        if let Some(webhook) = &mut self.webhook {
+            queue.register(USAGE_DESTINATION, &webhook.url, &webhook.secret);
+            webhook.queue = Some(queue);
+        }
+        self
+    }
+
+    /// Daily reconciliation summaries per TTS provider
+    ///
+    /// # Returns
+    /// * `None` - Reconciliation is not configured
+    pub fn reconciliation_summaries(&self) -> Option<Vec<ReconciliationSummary>> {
+        self.reconciler.as_ref().map(Reconciler::summaries)
+    }
+
+    /// Reconcile the record's TTS usage; failures are logged only
+    fn reconcile(&self, record: &UsageRecord) {
+        if let Some(reconciler) = &self.reconciler
+            && let Err(e) = reconciler.reconcile(record)
+        {
+            error!(session_id = %record.session_id, "Failed to write reconciliation report: {}", e);
+        }
+    }
+
+    /// Write a record to every sink
+    ///
+    /// # Returns
+    /// * `Ok(())` - Every sink accepted the record
+    /// * `Err(UsageSinkError)` - The first failure; later sinks were still tried
+    pub async fn record(&self, record: &UsageRecord) -> Result<(), UsageSinkError> {
+        self.reconcile(record);
+        let payload = serde_json::to_string(record)?;
+
+        let file_result = match &self.file {
+            Some(file) => file.append(&payload),
+            None => Ok(()),
+        };
+        let webhook_result = match &self.webhook {
+            Some(webhook) => {
+                webhook
+                    .post(&record.record_id, &record.session_id, payload)
+                    .await
+            }
+            None => Ok(()),
+        };
+
+        for result in [&file_result, &webhook_result] {
+            if let Err(e) = result {
+                error!(session_id = %record.session_id, "Failed to write usage record: {}", e);
+            }
+        }
+        debug!(
+            session_id = %record.session_id,
+            termination = ?record.termination,
+            "Usage record written"
+        );
+        file_result.and(webhook_result)
+    }
+
+    /// Write a record without awaiting, for use from `Drop`
+    ///
+    /// The file is written before returning. The webhook is posted from a
+    /// spawned task when a Tokio runtime is available and skipped otherwise.
+    pub fn record_detached(self: &Arc<Self>, record: UsageRecord) {
+        self.reconcile(&record);
+        let payload = match serde_json::to_string(&record) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!(session_id = %record.session_id, "Failed to serialize usage record: {}", e);
+                return;
+            }
+        };
+
+        if let Some(file) = &self.file
+            && let Err(e) = file.append(&payload)
+        {
+            error!(session_id = %record.session_id, "Failed to write usage record: {}", e);
+        }
+
+        if self.webhook.is_none() {
+            return;
+        }
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let recorder = self.clone();
+                handle.spawn(async move {
+                    if let Some(webhook) = &recorder.webhook
+                        && let Err(e) = webhook
+                            .post(&record.record_id, &record.session_id, payload)
+                            .await
+                    {
+                        error!(session_id = %record.session_id, "Failed to write usage record: {}", e);
+                    }
+                });
+            }
+            Err(_) => warn!(
+                session_id = %record.session_id,
+                "No runtime available - usage record not posted to the webhook"
+            ),
+        }
+    }
+}
+
+/// Append-only JSON-lines file rotated by size
+///
+/// When the next line would push the file past `max_bytes`, `usage.jsonl`
+/// becomes `usage.jsonl.1`, `usage.jsonl.1` becomes `usage.jsonl.2` and so on;
+/// files beyond `max_files` are deleted.
+pub(super) struct JsonLinesFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    /// Serializes rotation and appends within this process
+    lock: Mutex<()>,
+}
+
+impl JsonLinesFile {
+    pub(super) fn new(path: PathBuf, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            path,
+            max_bytes,
+            max_files,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub(super) fn append(&self, line: &str) -> Result<(), UsageSinkError> {
+        let _guard = self.lock.lock();
+        self.append_locked(line)
+            .map_err(|source| UsageSinkError::File {
+                path: self.path.clone(),
+                source,
+            })
+    }
+
+    fn append_locked(&self, line: &str) -> io::Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if size > 0 && size + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // One write per line so concurrent appends never interleave
+        file.write_all(format!("{line}\n").as_bytes())
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        let oldest = rotated_path(&self.path, self.max_files);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                fs::rename(from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))
+    }
+}
+
+/// Path of the `index`th rotated file (`usage.jsonl` -> `usage.jsonl.1`)
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+/// Webhook records are POSTed to, signed like SIP hook forwarding
+struct UsageWebhook {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+    /// Durable queue the records are handed to instead, if configured
+    queue: Option<Arc<WebhookDispatcher>>,
+}
+
+impl UsageWebhook {
+    async fn post(
+        &self,
+        record_id: &str,
+        session_id: &str,
+        payload: String,
+    ) -> Result<(), UsageSinkError> {
+        if let Some(queue) = &self.queue {
+            queue
+                .enqueue(USAGE_DESTINATION, record_id, Some(session_id), payload)
+                .await;
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let signing_headers =
+            generate_webhook_signature(&self.secret, timestamp, record_id, &payload)
+                .map_err(UsageSinkError::Webhook)?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        for (key, value) in signing_headers {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| UsageSinkError::Webhook(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(UsageSinkError::Webhook(format!(
+                "{} responded with {}",
+                self.url, status
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn file_sink(dir: &TempDir, max_bytes: u64, max_files: usize) -> JsonLinesFile {
+        JsonLinesFile {
+            path: dir.path().join("logs").join("usage.jsonl"),
+            max_bytes,
+            max_files,
+            lock: Mutex::new(()),
+        }
+    }
+
+    #[test]
+    fn test_file_appends_json_lines() {
+        let dir = TempDir::new().unwrap();
+        let sink = file_sink(&dir, 1024, 2);
+        sink.append(r#"{"n":1}"#).unwrap();
+        sink.append(r#"{"n":2}"#).unwrap();
+
+        let contents = fs::read_to_string(&sink.path).unwrap();
+        assert_eq!(contents, "{\"n\":1}\n{\"n\":2}\n");
+    }
+
+    #[test]
+    fn test_file_rotates_by_size() {
+        let dir = TempDir::new().unwrap();
+        // Each line is 8 bytes with its newline, so two fit per file
+        let sink = file_sink(&dir, 16, 2);
+        for n in 1..=7 {
+            sink.append(&format!(r#"{{"n":{n}}}"#)).unwrap();
+        }
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(sink.path.clone()), "{\"n\":7}\n");
+        assert_eq!(read(rotated_path(&sink.path, 1)), "{\"n\":5}\n{\"n\":6}\n");
+        assert_eq!(read(rotated_path(&sink.path, 2)), "{\"n\":3}\n{\"n\":4}\n");
+        // Older files are dropped
+        assert!(!rotated_path(&sink.path, 3).exists());
+    }
+
+    #[test]
+    fn test_rotated_path() {
+        assert_eq!(
+            rotated_path(Path::new("/var/log/usage.jsonl"), 2),
+            PathBuf::from("/var/log/usage.jsonl.2")
+        );
+    }
+}
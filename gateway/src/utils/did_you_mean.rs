@@ -0,0 +1,53 @@
+//! "Did you mean" suggestions for mistyped names
+//!
+//! Used for unknown config fields and unknown provider models, where the
+//! closest known name is usually the one the client meant.
+
+/// The known name closest to `name`, if it is close enough to be a typo
+pub fn suggest<'a>(name: &str, known: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    known
+        .iter()
+        .map(|candidate| (levenshtein(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein edit distance between two strings
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("provider", "provider"), 0);
+        assert_eq!(levenshtein("porvider", "provider"), 2);
+        assert_eq!(levenshtein("sample_rat", "sample_rate"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let known = ["provider", "model", "sample_rate"];
+        assert_eq!(suggest("porvider", &known), Some("provider"));
+        assert_eq!(suggest("modle", &known), Some("model"));
+        assert_eq!(suggest("voice_id", &known), None);
+    }
+}
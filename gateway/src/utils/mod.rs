@@ -1,3 +1,4 @@
+pub mod did_you_mean;
 pub mod noise_filter;
 pub use noise_filter::reduce_noise_async;
 pub mod phone_validation;
@@ -7,3 +8,4 @@ pub mod sip_api_client;
 pub mod sip_hooks;
 pub mod url_validation;
 pub use url_validation::{UrlValidationError, validate_webhook_url, validate_webhook_url_dev};
+pub mod webhook_signing;
@@ -94,6 +94,12 @@ pub struct ReqManager {
 
     /// Configuration for retry and timeout behavior
     config: ReqManagerConfig,
+
+    /// When the manager was created, the reference for `last_used_ms`
+    created_at: Instant,
+
+    /// Last time a client was acquired, in milliseconds after `created_at`
+    last_used_ms: AtomicU64,
 }
 
 /// A guard that holds a client from the pool and returns it when dropped.
@@ -402,6 +408,8 @@ impl ReqManager {
             semaphore: Arc::new(Semaphore::new(config.max_concurrent_requests)),
             metrics: Arc::new(RequestMetrics::default()),
             config,
+            created_at: Instant::now(),
+            last_used_ms: AtomicU64::new(0),
         })
     }
 
@@ -449,6 +457,11 @@ impl ReqManager {
             .peak_concurrent
             .fetch_max(active, Ordering::Relaxed);
 
+        self.last_used_ms.store(
+            self.created_at.elapsed().as_millis() as u64,
+            Ordering::Relaxed,
+        );
+
         let client = Arc::clone(&self.client);
 
         Ok(ClientGuard {
@@ -763,6 +776,12 @@ impl ReqManager {
         self.metrics.active_requests.load(Ordering::Relaxed)
     }
 
+    /// Time since a client was last acquired, or since creation if never
+    pub fn idle_for(&self) -> Duration {
+        let last_used = Duration::from_millis(self.last_used_ms.load(Ordering::Relaxed));
+        self.created_at.elapsed().saturating_sub(last_used)
+    }
+
     /// Get performance metrics
     pub fn metrics(&self) -> &RequestMetrics {
         &self.metrics
@@ -825,6 +844,16 @@ mod tests {
         drop(guard2);
     }
 
+    #[tokio::test]
+    async fn test_idle_for_resets_on_acquire() {
+        let manager = ReqManager::new(1).await.unwrap();
+        sleep(Duration::from_millis(30)).await;
+        assert!(manager.idle_for() >= Duration::from_millis(30));
+
+        drop(manager.acquire().await.unwrap());
+        assert!(manager.idle_for() < Duration::from_millis(30));
+    }
+
     #[tokio::test]
     async fn test_concurrent_requests_limited() {
         let manager = Arc::new(ReqManager::new(3).await.unwrap());
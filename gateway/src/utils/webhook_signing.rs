@@ -0,0 +1,64 @@
+//! HMAC-SHA256 signing for outbound webhooks
+//!
+//! Every webhook WaaV sends (SIP hook forwarding, usage records) carries the
+//! same `X-WaaV-Signature` headers so receivers verify them one way.
+
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Generates an HMAC-SHA256 signature for webhook payload authentication.
+///
+/// Creates a canonical string `v1:{timestamp}:{event_id}:{payload}` and signs it
+/// with HMAC-SHA256. Returns signing headers for inclusion in webhook requests.
+///
+/// # Arguments
+/// * `secret` - The signing secret (hook-level or global)
+/// * `timestamp` - Unix timestamp in seconds
+/// * `event_id` - Unique ID of the event being delivered
+/// * `payload` - The JSON payload string
+///
+/// # Returns
+/// * `Ok(headers)` - Map of signing headers (X-WaaV-Signature, X-WaaV-Timestamp, etc.)
+/// * `Err(String)` - If HMAC initialization fails
+///
+/// # Security
+/// - Uses HMAC-SHA256 for signature generation
+/// - Canonical string format prevents replay attacks when combined with timestamp validation
+/// - Secrets are never logged or exposed in error messages
+pub fn generate_webhook_signature(
+    secret: &str,
+    timestamp: u64,
+    event_id: &str,
+    payload: &str,
+) -> Result<HashMap<String, String>, String> {
+    // Build canonical string: v1:{timestamp}:{event_id}:{payload}
+    let canonical_string = format!("v1:{}:{}:{}", timestamp, event_id, payload);
+
+    // Initialize HMAC-SHA256
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| format!("HMAC initialization failed: {}", e))?;
+
+    // Compute signature
+    mac.update(canonical_string.as_bytes());
+    let result = mac.finalize();
+    let signature_bytes = result.into_bytes();
+
+    // Encode as hex
+    let signature_hex = hex::encode(signature_bytes);
+
+    // Build signing headers
+    let mut headers = HashMap::new();
+    headers.insert(
+        "X-WaaV-Signature".to_string(),
+        format!("v1={}", signature_hex),
+    );
+    headers.insert("X-WaaV-Timestamp".to_string(), timestamp.to_string());
+    headers.insert("X-WaaV-Event-Id".to_string(), event_id.to_string());
+    headers.insert("X-WaaV-Signature-Version".to_string(), "v1".to_string());
+
+    Ok(headers)
+}
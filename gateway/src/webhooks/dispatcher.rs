@@ -0,0 +1,667 @@
+//! Dispatcher delivering queued webhook events in the background
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::{Mutex, Notify};
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
+
+use super::queue::{DestinationQueue, DropReason, DroppedWebhook, QueuedEvent, QueuedWebhook};
+use crate::config::WebhookQueueConfig;
+use crate::core::cache::store::CacheStore;
+use crate::metrics::global_metrics;
+use crate::utils::webhook_signing::generate_webhook_signature;
+
+/// Timeout for one delivery attempt
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cache key prefix of the destination queues
+const QUEUE_KEY_PREFIX: &str = "webhooks:queue:";
+
+/// Errors raised by the webhook queue
+#[derive(Debug, Error)]
+pub enum WebhookQueueError {
+    #[error("Webhook client could not be built: {0}")]
+    Client(String),
+
+    #[error("Unknown webhook destination '{0}'")]
+    UnknownDestination(String),
+}
+
+/// Queued and dropped events of one destination
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookBacklog {
+    /// Destination name (`usage`, `session_export` or `enrichment`)
+    pub destination: String,
+    /// URL the events are POSTed to
+    pub url: String,
+    /// Events waiting for delivery, oldest first
+    pub pending: Vec<QueuedWebhook>,
+    /// Most recently dropped events, oldest first
+    pub dropped: Vec<DroppedWebhook>,
+}
+
+/// Result of a replay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookReplay {
+    /// Pending events retried right away
+    pub retried: usize,
+    /// Dropped events queued again
+    pub requeued: usize,
+}
+
+/// Where a destination's events are POSTed
+struct Destination {
+    url: String,
+    secret: String,
+}
+
+/// Delivers the events of registered destinations from their durable queues
+///
+/// Dropping the dispatcher stops delivery; the queues stay in the cache and
+/// are picked up by the next dispatcher registering the same destinations.
+pub struct WebhookDispatcher {
+    inner: Arc<DispatcherInner>,
+    delivery: AbortHandle,
+}
+
+impl WebhookDispatcher {
+    /// Start the dispatcher of a validated configuration
+    ///
+    /// Must be called from within a Tokio runtime. Queues are loaded from
+    /// `cache` as their destinations are [registered](Self::register).
+    ///
+    /// # Errors
+    /// Returns `WebhookQueueError::Client` if the HTTP client cannot be built
+    pub fn start(
+        config: &WebhookQueueConfig,
+        cache: Arc<CacheStore>,
+    ) -> Result<Self, WebhookQueueError> {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| WebhookQueueError::Client(e.to_string()))?;
+        let inner = Arc::new(DispatcherInner {
+            cache,
+            client,
+            limits: *config,
+            destinations: RwLock::new(BTreeMap::new()),
+            queues: Mutex::new(BTreeMap::new()),
+            wake: Notify::new(),
+        });
+
+        let delivery = tokio::spawn({
+            let inner = inner.clone();
+            async move {
+                loop {
+                    match inner.deliver_due().await {
+                        Some(due) => {
+                            let wait = Duration::from_millis(due.saturating_sub(now_ms()));
+                            tokio::select! {
+                                _ = inner.wake.notified() => {}
+                                _ = tokio::time::sleep(wait) => {}
+                            }
+                        }
+                        None => inner.wake.notified().await,
+                    }
+                }
+            }
+        })
+        .abort_handle();
+
+        Ok(Self { inner, delivery })
+    }
+
+    /// Deliver a destination's events to `url`, signed with `secret`
+    ///
+    /// Events a previous run left in the destination's queue are delivered
+    /// as well. The secret is only kept in memory.
+    pub fn register(&self, destination: &str, url: &str, secret: &str) {
+        self.inner.destinations.write().insert(
+            destination.to_string(),
+            Destination {
+                url: url.to_string(),
+                secret: secret.to_string(),
+            },
+        );
+        self.inner.wake.notify_one();
+    }
+
+    /// Queue an event for delivery
+    ///
+    /// # Arguments
+    /// * `destination` - Registered destination the event is POSTed to
+    /// * `event_id` - Event ID, sent as `X-WaaV-Event-Id`
+    /// * `session_id` - Session whose events are delivered in order, if any
+    /// * `payload` - JSON body
+    pub async fn enqueue(
+        &self,
+        destination: &str,
+        event_id: &str,
+        session_id: Option<&str>,
+        payload: String,
+    ) {
+        let inner = &self.inner;
+        let mut queues = inner.queues.lock().await;
+        let queue = inner.queue(&mut queues, destination).await;
+        if let Some(evicted) = queue.push(event_id, session_id, payload, now_ms(), &inner.limits) {
+            warn!(
+                destination,
+                event_id = %evicted.event_id,
+                "Webhook queue full - dropped oldest event"
+            );
+            record_dropped(destination, DropReason::QueueFull.as_str());
+        }
+        inner.save(destination, queue).await;
+        drop(queues);
+        inner.wake.notify_one();
+    }
+
+    /// Queued and dropped events of every registered destination
+    pub async fn backlog(&self) -> Vec<WebhookBacklog> {
+        let inner = &self.inner;
+        let destinations: Vec<(String, String)> = inner
+            .destinations
+            .read()
+            .iter()
+            .map(|(name, destination)| (name.clone(), destination.url.clone()))
+            .collect();
+
+        let mut queues = inner.queues.lock().await;
+        let mut backlog = Vec::with_capacity(destinations.len());
+        for (destination, url) in destinations {
+            let (pending, dropped) = inner.queue(&mut queues, &destination).await.backlog();
+            backlog.push(WebhookBacklog {
+                destination,
+                url,
+                pending,
+                dropped,
+            });
+        }
+        backlog
+    }
+
+    /// Retry pending events now and queue dropped events again
+    ///
+    /// # Arguments
+    /// * `destination` - Only replay this destination; every registered one when None
+    ///
+    /// # Errors
+    /// Returns `WebhookQueueError::UnknownDestination` if `destination` is not registered
+    pub async fn replay(
+        &self,
+        destination: Option<&str>,
+    ) -> Result<WebhookReplay, WebhookQueueError> {
+        let inner = &self.inner;
+        let destinations: Vec<String> = {
+            let registered = inner.destinations.read();
+            match destination {
+                Some(name) if !registered.contains_key(name) => {
+                    return Err(WebhookQueueError::UnknownDestination(name.to_string()));
+                }
+                Some(name) => vec![name.to_string()],
+                None => registered.keys().cloned().collect(),
+            }
+        };
+
+        let mut replay = WebhookReplay::default();
+        let mut queues = inner.queues.lock().await;
+        for destination in destinations {
+            let queue = inner.queue(&mut queues, &destination).await;
+            let (retried, requeued) = queue.replay(now_ms());
+            inner.save(&destination, queue).await;
+            info!(
+                destination = %destination,
+                retried, requeued, "Webhook events replayed"
+            );
+            replay.retried += retried;
+            replay.requeued += requeued;
+        }
+        drop(queues);
+        inner.wake.notify_one();
+        Ok(replay)
+    }
+}
+
+impl Drop for WebhookDispatcher {
+    fn drop(&mut self) {
+        self.delivery.abort();
+    }
+}
+
+struct DispatcherInner {
+    cache: Arc<CacheStore>,
+    client: reqwest::Client,
+    limits: WebhookQueueConfig,
+    destinations: RwLock<BTreeMap<String, Destination>>,
+    /// Queues loaded from the cache so far
+    queues: Mutex<BTreeMap<String, DestinationQueue>>,
+    /// Woken when events are queued or replayed, or a destination registers
+    wake: Notify,
+}
+
+impl DispatcherInner {
+    /// Deliver every due event of the registered destinations
+    ///
+    /// # Returns
+    /// The earliest time a pending event becomes due, if any is pending
+    async fn deliver_due(&self) -> Option<u64> {
+        let destinations: Vec<String> = self.destinations.read().keys().cloned().collect();
+        let mut next_due: Option<u64> = None;
+        for destination in destinations {
+            loop {
+                let event = {
+                    let mut queues = self.queues.lock().await;
+                    let queue = self.queue(&mut queues, &destination).await;
+                    match queue.start_next(now_ms()) {
+                        Some(event) => event,
+                        None => {
+                            next_due = match (next_due, queue.next_attempt_at()) {
+                                (Some(a), Some(b)) => Some(a.min(b)),
+                                (a, b) => a.or(b),
+                            };
+                            break;
+                        }
+                    }
+                };
+
+                let result = self.post(&destination, &event).await;
+
+                let mut queues = self.queues.lock().await;
+                let queue = self.queue(&mut queues, &destination).await;
+                match result {
+                    Ok(()) => {
+                        debug!(destination = %destination, event_id = %event.event_id, "Webhook delivered");
+                        queue.delivered(event.seq);
+                        record_delivery(&destination, "delivered");
+                    }
+                    Err(e) => {
+                        record_delivery(&destination, "failed");
+                        if let Some(reason) = queue.failed(event.seq, e, now_ms(), &self.limits) {
+                            warn!(
+                                destination = %destination,
+                                event_id = %event.event_id,
+                                attempts = self.limits.max_attempts,
+                                "Webhook delivery attempts exhausted - dropped event"
+                            );
+                            record_dropped(&destination, reason.as_str());
+                        }
+                    }
+                }
+                self.save(&destination, queue).await;
+            }
+        }
+        next_due
+    }
+
+    /// POST an event, signed with its destination's secret
+    async fn post(&self, destination: &str, event: &QueuedEvent) -> Result<(), String> {
+        let (url, secret) = match self.destinations.read().get(destination) {
+            Some(registered) => (registered.url.clone(), registered.secret.clone()),
+            None => return Err(format!("destination '{destination}' is not registered")),
+        };
+        let timestamp = now_ms() / 1_000;
+        let signing_headers =
+            generate_webhook_signature(&secret, timestamp, &event.event_id, &event.payload)?;
+
+        let mut request = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        for (key, value) in signing_headers {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .body(event.payload.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(format!("{url} responded with {status}"))
+        }
+    }
+
+    /// Queue of a destination, loaded from the cache on first use
+    ///
+    /// A queue that cannot be read starts out empty.
+    async fn queue<'a>(
+        &self,
+        queues: &'a mut BTreeMap<String, DestinationQueue>,
+        destination: &str,
+    ) -> &'a mut DestinationQueue {
+        if !queues.contains_key(destination) {
+            let key = format!("{QUEUE_KEY_PREFIX}{destination}");
+            let queue = match self.cache.get(&key).await {
+                Ok(Some(stored)) => match serde_json::from_slice::<DestinationQueue>(&stored) {
+                    Ok(queue) => {
+                        if queue.pending_len() > 0 {
+                            info!(
+                                destination,
+                                pending = queue.pending_len(),
+                                "Resuming webhook delivery"
+                            );
+                        }
+                        queue
+                    }
+                    Err(e) => {
+                        warn!(destination, "Discarding unreadable webhook queue: {}", e);
+                        DestinationQueue::default()
+                    }
+                },
+                Ok(None) => DestinationQueue::default(),
+                Err(e) => {
+                    warn!(destination, "Failed to load webhook queue: {}", e);
+                    DestinationQueue::default()
+                }
+            };
+            queues.insert(destination.to_string(), queue);
+        }
+        queues
+            .get_mut(destination)
+            .expect("queue was inserted above")
+    }
+
+    /// Write a destination's queue to the cache
+    ///
+    /// A failed write is logged; the queue is still kept in memory.
+    async fn save(&self, destination: &str, queue: &DestinationQueue) {
+        global_metrics().set_gauge(
+            "waav_webhook_events_pending",
+            "Webhook events waiting for delivery",
+            &[("destination", destination)],
+            queue.pending_len() as f64,
+        );
+        let key = format!("{QUEUE_KEY_PREFIX}{destination}");
+        let result = match serde_json::to_vec(queue) {
+            Ok(stored) => self
+                .cache
+                .put(&key, stored)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            warn!(destination, "Failed to save webhook queue: {}", e);
+        }
+    }
+}
+
+fn record_delivery(destination: &str, result: &str) {
+    global_metrics().inc_counter(
+        "waav_webhook_deliveries_total",
+        "Webhook delivery attempts by result",
+        &[("destination", destination), ("result", result)],
+    );
+}
+
+fn record_dropped(destination: &str, reason: &str) {
+    global_metrics().inc_counter(
+        "waav_webhook_events_dropped_total",
+        "Webhook events dropped without being delivered",
+        &[("destination", destination), ("reason", reason)],
+    );
+}
+
+/// Current Unix time in milliseconds
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cache::store::CacheConfig;
+    use axum::{Router, http::HeaderMap, http::StatusCode, routing::post};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::mpsc;
+
+    const SECRET: &str = "webhook-signing-secret";
+
+    /// Filesystem cache, opened again by each "restarted" dispatcher
+    async fn filesystem_cache(path: &Path) -> Arc<CacheStore> {
+        Arc::new(
+            CacheStore::from_config(CacheConfig::Filesystem {
+                path: path.to_path_buf(),
+                ttl_seconds: None,
+            })
+            .await
+            .unwrap(),
+        )
+    }
+
+    /// Local webhook receiver answering 200 while `accept` is set and 503
+    /// otherwise; forwards the event ID of every request and whether it was
+    /// accepted
+    async fn webhook_receiver() -> (
+        String,
+        Arc<AtomicBool>,
+        mpsc::UnboundedReceiver<(String, bool)>,
+    ) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let accept = Arc::new(AtomicBool::new(false));
+        let app = Router::new().route(
+            "/hooks",
+            post({
+                let accept = accept.clone();
+                move |headers: HeaderMap| {
+                    let tx = tx.clone();
+                    let accepted = accept.load(Ordering::SeqCst);
+                    async move {
+                        let event_id = headers["X-WaaV-Event-Id"].to_str().unwrap().to_string();
+                        assert!(headers.contains_key("X-WaaV-Signature"));
+                        let _ = tx.send((event_id, accepted));
+                        if accepted {
+                            StatusCode::OK
+                        } else {
+                            StatusCode::SERVICE_UNAVAILABLE
+                        }
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}/hooks"), accept, rx)
+    }
+
+    async fn next_request(
+        requests: &mut mpsc::UnboundedReceiver<(String, bool)>,
+    ) -> (String, bool) {
+        tokio::time::timeout(Duration::from_secs(5), requests.recv())
+            .await
+            .expect("Timed out waiting for a webhook request")
+            .unwrap()
+    }
+
+    /// Wait until the dispatcher's view of the destination matches `done`
+    async fn wait_for_backlog(
+        dispatcher: &WebhookDispatcher,
+        done: impl Fn(&WebhookBacklog) -> bool,
+    ) -> WebhookBacklog {
+        for _ in 0..100 {
+            let backlog = dispatcher.backlog().await.remove(0);
+            if done(&backlog) {
+                return backlog;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Webhook backlog did not settle");
+    }
+
+    #[tokio::test]
+    async fn test_restart_mid_backoff_delivers_each_event_once_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, accept, mut requests) = webhook_receiver().await;
+        let limits = WebhookQueueConfig {
+            retry_base_delay_ms: 1_000,
+            retry_max_delay_ms: 1_000,
+            ..Default::default()
+        };
+
+        let first = WebhookDispatcher::start(&limits, filesystem_cache(dir.path()).await).unwrap();
+        first.register("usage", &url, SECRET);
+        first
+            .enqueue("usage", "a1", Some("a"), "{}".to_string())
+            .await;
+        first
+            .enqueue("usage", "a2", Some("a"), "{}".to_string())
+            .await;
+        first
+            .enqueue("usage", "b1", Some("b"), "{}".to_string())
+            .await;
+
+        // a1 and b1 fail; a2 waits behind a1
+        let mut failed = vec![
+            next_request(&mut requests).await,
+            next_request(&mut requests).await,
+        ];
+        failed.sort();
+        assert_eq!(
+            failed,
+            [("a1".to_string(), false), ("b1".to_string(), false)]
+        );
+        wait_for_backlog(&first, |backlog| {
+            backlog
+                .pending
+                .iter()
+                .filter(|event| event.attempts == 1)
+                .count()
+                == 2
+        })
+        .await;
+        drop(first);
+
+        // The restarted dispatcher waits out the backoff before retrying
+        accept.store(true, Ordering::SeqCst);
+        let second = WebhookDispatcher::start(&limits, filesystem_cache(dir.path()).await).unwrap();
+        second.register("usage", &url, SECRET);
+        let backlog = second.backlog().await.remove(0);
+        let pending: Vec<_> = backlog
+            .pending
+            .iter()
+            .map(|event| (event.event_id.as_str(), event.attempts))
+            .collect();
+        assert_eq!(pending, [("a1", 1), ("a2", 0), ("b1", 1)]);
+        assert!(backlog.pending[0].next_attempt_at > now_ms());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), requests.recv())
+                .await
+                .is_err(),
+            "Retried before the backoff expired"
+        );
+
+        let mut delivered = Vec::new();
+        for _ in 0..3 {
+            let (event_id, accepted) = next_request(&mut requests).await;
+            assert!(accepted);
+            delivered.push(event_id);
+        }
+        let position = |id: &str| delivered.iter().position(|event| event == id).unwrap();
+        assert!(position("a1") < position("a2"));
+        let mut unique = delivered.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique, ["a1", "a2", "b1"]);
+
+        wait_for_backlog(&second, |backlog| backlog.pending.is_empty()).await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), requests.recv())
+                .await
+                .is_err(),
+            "An event was delivered twice"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_events_are_marked_dropped_and_replayed() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, accept, mut requests) = webhook_receiver().await;
+        let limits = WebhookQueueConfig {
+            max_pending_per_destination: 2,
+            max_attempts: 1,
+            ..Default::default()
+        };
+
+        // Queued before the destination registers, so nothing is in flight
+        let dispatcher =
+            WebhookDispatcher::start(&limits, filesystem_cache(dir.path()).await).unwrap();
+        for id in ["e1", "e2", "e3"] {
+            dispatcher
+                .enqueue("session_export", id, None, "{}".to_string())
+                .await;
+        }
+        dispatcher.register("session_export", &url, SECRET);
+
+        // e1 made room for e3; e2 and e3 fail their only attempt
+        let mut failed = vec![
+            next_request(&mut requests).await.0,
+            next_request(&mut requests).await.0,
+        ];
+        failed.sort();
+        assert_eq!(failed, ["e2", "e3"]);
+        let backlog = wait_for_backlog(&dispatcher, |backlog| backlog.dropped.len() == 3).await;
+        assert!(backlog.pending.is_empty());
+        let dropped: Vec<_> = backlog
+            .dropped
+            .iter()
+            .map(|event| (event.event_id.as_str(), event.reason))
+            .collect();
+        assert_eq!(dropped[0], ("e1", DropReason::QueueFull));
+        assert!(
+            dropped[1..]
+                .iter()
+                .all(|(_, reason)| *reason == DropReason::AttemptsExhausted)
+        );
+        let dropped_total = |reason: &str| {
+            global_metrics()
+                .get(
+                    "waav_webhook_events_dropped_total",
+                    &[("destination", "session_export"), ("reason", reason)],
+                )
+                .unwrap_or_default()
+        };
+        assert!(dropped_total("queue_full") >= 1.0);
+        assert!(dropped_total("attempts_exhausted") >= 2.0);
+
+        assert!(matches!(
+            dispatcher.replay(Some("usage")).await,
+            Err(WebhookQueueError::UnknownDestination(_))
+        ));
+        accept.store(true, Ordering::SeqCst);
+        let replay = dispatcher.replay(None).await.unwrap();
+        assert_eq!(
+            replay,
+            WebhookReplay {
+                retried: 0,
+                requeued: 3
+            }
+        );
+
+        let mut delivered = Vec::new();
+        for _ in 0..3 {
+            let (event_id, accepted) = next_request(&mut requests).await;
+            assert!(accepted);
+            delivered.push(event_id);
+        }
+        delivered.sort();
+        assert_eq!(delivered, ["e1", "e2", "e3"]);
+        let backlog = wait_for_backlog(&dispatcher, |backlog| backlog.pending.is_empty()).await;
+        assert!(backlog.dropped.is_empty());
+    }
+}
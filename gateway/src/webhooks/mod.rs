@@ -0,0 +1,42 @@
+//! # Outbound Webhook Queue
+//!
+//! With [`WebhookQueueConfig`](crate::config::WebhookQueueConfig) set, the
+//! gateway's outbound webhooks are delivered through a durable queue instead
+//! of being POSTed inline:
+//!
+//! 1. each event is appended to its destination's queue (`usage`,
+//!    `session_export` or `enrichment`), which is written to the cache
+//!    backend; when the queue is full its oldest event is dropped
+//! 2. a background dispatcher POSTs the events, signed with the
+//!    `X-WaaV-Signature` headers used for SIP hook forwarding; a failed
+//!    event is retried with exponential backoff and dropped once its
+//!    attempts run out
+//! 3. events of one session are delivered in the order they were queued: a
+//!    session's next event waits until the one before it is delivered or
+//!    dropped
+//! 4. on startup the queues a previous run left in the cache are loaded and
+//!    delivery resumes where it stopped, backoff included
+//!
+//! `GET /admin/webhooks/pending` lists the queued and dropped events and
+//! `POST /admin/webhooks/replay` retries them right away. Dropped events are
+//! counted in `waav_webhook_events_dropped_total`.
+//!
+//! Queues only survive a restart with a filesystem cache (`cache_path`).
+//! An event is removed from its queue once the receiver answers with a 2xx;
+//! a gateway stopping between the two delivers it again, with the same
+//! `X-WaaV-Event-Id`.
+
+mod dispatcher;
+mod queue;
+
+pub use dispatcher::{WebhookBacklog, WebhookDispatcher, WebhookQueueError, WebhookReplay};
+pub use queue::{DropReason, DroppedWebhook, QueuedWebhook};
+
+/// Destination of usage records
+pub const USAGE_DESTINATION: &str = "usage";
+
+/// Destination of `session.ended` events
+pub const SESSION_EXPORT_DESTINATION: &str = "session_export";
+
+/// Destination of `transcript.enriched` events
+pub const ENRICHMENT_DESTINATION: &str = "enrichment";
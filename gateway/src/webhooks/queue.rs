@@ -0,0 +1,417 @@
+//! Queue of one webhook destination, stored as a single cache entry
+
+use std::collections::{HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::WebhookQueueConfig;
+
+/// Why an event was dropped without being delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum DropReason {
+    /// The queue was full when a newer event arrived
+    QueueFull,
+    /// Every delivery attempt failed
+    AttemptsExhausted,
+}
+
+impl DropReason {
+    /// Name used in metrics and logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::QueueFull => "queue_full",
+            DropReason::AttemptsExhausted => "attempts_exhausted",
+        }
+    }
+}
+
+/// An event waiting to be delivered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct QueuedWebhook {
+    /// Event ID, sent as `X-WaaV-Event-Id`
+    pub event_id: String,
+    /// Session the event belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// When the event was queued (Unix milliseconds)
+    pub queued_at: u64,
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+    /// Earliest time of the next attempt (Unix milliseconds)
+    pub next_attempt_at: u64,
+    /// Error of the last failed attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// An event that was dropped and can be replayed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DroppedWebhook {
+    /// Event ID, sent as `X-WaaV-Event-Id`
+    pub event_id: String,
+    /// Session the event belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// When the event was queued (Unix milliseconds)
+    pub queued_at: u64,
+    /// When the event was dropped (Unix milliseconds)
+    pub dropped_at: u64,
+    /// Why the event was dropped
+    pub reason: DropReason,
+    /// Failed delivery attempts
+    pub attempts: u32,
+    /// Error of the last failed attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// A queued event with its body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct QueuedEvent {
+    pub(super) event_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) session_id: Option<String>,
+    /// Position in the destination's queue, increasing with every event
+    pub(super) seq: u64,
+    pub(super) queued_at: u64,
+    pub(super) attempts: u32,
+    pub(super) next_attempt_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) last_error: Option<String>,
+    /// JSON body POSTed to the destination
+    pub(super) payload: String,
+}
+
+/// A dropped event, kept for replay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct DroppedEvent {
+    pub(super) event: QueuedEvent,
+    pub(super) reason: DropReason,
+    pub(super) dropped_at: u64,
+}
+
+/// Pending and dropped events of one destination
+///
+/// Pending events are ordered by `seq`. Only the first pending event of each
+/// session may be delivered, so a session's events arrive in order; events
+/// without a session do not wait for each other.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct DestinationQueue {
+    next_seq: u64,
+    pending: VecDeque<QueuedEvent>,
+    dropped: VecDeque<DroppedEvent>,
+    /// Event being delivered right now; it is never dropped for space
+    #[serde(skip)]
+    in_flight: Option<u64>,
+}
+
+impl DestinationQueue {
+    /// Append an event
+    ///
+    /// # Returns
+    /// * `Some(event)` - The queue was full and its oldest event was dropped
+    pub(super) fn push(
+        &mut self,
+        event_id: &str,
+        session_id: Option<&str>,
+        payload: String,
+        now: u64,
+        limits: &WebhookQueueConfig,
+    ) -> Option<QueuedEvent> {
+        let mut evicted = None;
+        if self.pending.len() >= limits.max_pending_per_destination
+            && let Some(index) = self
+                .pending
+                .iter()
+                .position(|event| Some(event.seq) != self.in_flight)
+            && let Some(event) = self.pending.remove(index)
+        {
+            self.mark_dropped(event.clone(), DropReason::QueueFull, now, limits);
+            evicted = Some(event);
+        }
+
+        self.pending.push_back(QueuedEvent {
+            event_id: event_id.to_string(),
+            session_id: session_id.map(str::to_string),
+            seq: self.next_seq,
+            queued_at: now,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            payload,
+        });
+        self.next_seq += 1;
+        evicted
+    }
+
+    /// Take the oldest event that is due and first of its session
+    ///
+    /// The event stays queued until [`delivered`](Self::delivered) or
+    /// [`failed`](Self::failed) is called for it.
+    pub(super) fn start_next(&mut self, now: u64) -> Option<QueuedEvent> {
+        let mut sessions = HashSet::new();
+        let next = self
+            .pending
+            .iter()
+            .filter(|event| {
+                event
+                    .session_id
+                    .as_deref()
+                    .is_none_or(|session| sessions.insert(session))
+            })
+            .find(|event| event.next_attempt_at <= now)
+            .cloned()?;
+        self.in_flight = Some(next.seq);
+        Some(next)
+    }
+
+    /// Earliest time an event becomes due, if any is pending
+    pub(super) fn next_attempt_at(&self) -> Option<u64> {
+        let mut sessions = HashSet::new();
+        self.pending
+            .iter()
+            .filter(|event| {
+                event
+                    .session_id
+                    .as_deref()
+                    .is_none_or(|session| sessions.insert(session))
+            })
+            .map(|event| event.next_attempt_at)
+            .min()
+    }
+
+    /// Remove a delivered event
+    pub(super) fn delivered(&mut self, seq: u64) {
+        self.in_flight = None;
+        self.pending.retain(|event| event.seq != seq);
+    }
+
+    /// Record a failed attempt, scheduling a retry or dropping the event
+    ///
+    /// # Returns
+    /// * `Some(DropReason::AttemptsExhausted)` - The event was dropped
+    pub(super) fn failed(
+        &mut self,
+        seq: u64,
+        error: String,
+        now: u64,
+        limits: &WebhookQueueConfig,
+    ) -> Option<DropReason> {
+        self.in_flight = None;
+        let index = self.pending.iter().position(|event| event.seq == seq)?;
+        let event = &mut self.pending[index];
+        event.attempts += 1;
+        event.last_error = Some(error);
+        if event.attempts < limits.max_attempts {
+            event.next_attempt_at = now.saturating_add(retry_delay_ms(event.attempts, limits));
+            return None;
+        }
+        let event = self.pending.remove(index)?;
+        self.mark_dropped(event, DropReason::AttemptsExhausted, now, limits);
+        Some(DropReason::AttemptsExhausted)
+    }
+
+    /// Retry every pending event now and queue the dropped events again
+    ///
+    /// Both start over with no failed attempts. Dropped events go back to
+    /// their original place in the queue.
+    ///
+    /// # Returns
+    /// The number of pending events retried and dropped events queued again
+    pub(super) fn replay(&mut self, now: u64) -> (usize, usize) {
+        let retried = self.pending.len();
+        for event in &mut self.pending {
+            event.attempts = 0;
+            event.next_attempt_at = now;
+        }
+
+        let requeued = self.dropped.len();
+        for DroppedEvent { mut event, .. } in std::mem::take(&mut self.dropped) {
+            event.attempts = 0;
+            event.next_attempt_at = now;
+            let index = self
+                .pending
+                .partition_point(|pending| pending.seq < event.seq);
+            self.pending.insert(index, event);
+        }
+        (retried, requeued)
+    }
+
+    /// Number of events waiting for delivery
+    pub(super) fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Pending and dropped events, without their bodies
+    pub(super) fn backlog(&self) -> (Vec<QueuedWebhook>, Vec<DroppedWebhook>) {
+        let pending = self
+            .pending
+            .iter()
+            .map(|event| QueuedWebhook {
+                event_id: event.event_id.clone(),
+                session_id: event.session_id.clone(),
+                queued_at: event.queued_at,
+                attempts: event.attempts,
+                next_attempt_at: event.next_attempt_at,
+                last_error: event.last_error.clone(),
+            })
+            .collect();
+        let dropped = self
+            .dropped
+            .iter()
+            .map(|dropped| DroppedWebhook {
+                event_id: dropped.event.event_id.clone(),
+                session_id: dropped.event.session_id.clone(),
+                queued_at: dropped.event.queued_at,
+                dropped_at: dropped.dropped_at,
+                reason: dropped.reason,
+                attempts: dropped.event.attempts,
+                last_error: dropped.event.last_error.clone(),
+            })
+            .collect();
+        (pending, dropped)
+    }
+
+    /// Keep a dropped event, forgetting the oldest beyond the limit
+    fn mark_dropped(
+        &mut self,
+        event: QueuedEvent,
+        reason: DropReason,
+        now: u64,
+        limits: &WebhookQueueConfig,
+    ) {
+        self.dropped.push_back(DroppedEvent {
+            event,
+            reason,
+            dropped_at: now,
+        });
+        while self.dropped.len() > limits.max_dropped_per_destination {
+            self.dropped.pop_front();
+        }
+    }
+}
+
+/// Delay after failed attempt `attempt`, doubling up to the maximum
+fn retry_delay_ms(attempt: u32, limits: &WebhookQueueConfig) -> u64 {
+    limits
+        .retry_base_delay_ms
+        .saturating_mul(1 << (attempt - 1).min(16))
+        .min(limits.retry_max_delay_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: WebhookQueueConfig = WebhookQueueConfig {
+        max_pending_per_destination: 3,
+        max_attempts: 2,
+        retry_base_delay_ms: 100,
+        retry_max_delay_ms: 1_000,
+        max_dropped_per_destination: 2,
+    };
+
+    fn push(queue: &mut DestinationQueue, event_id: &str, session_id: Option<&str>) {
+        queue.push(event_id, session_id, "{}".to_string(), 0, &LIMITS);
+    }
+
+    fn pending_ids(queue: &DestinationQueue) -> Vec<String> {
+        let (pending, _) = queue.backlog();
+        pending.into_iter().map(|event| event.event_id).collect()
+    }
+
+    #[test]
+    fn test_session_events_wait_for_earlier_ones() {
+        let mut queue = DestinationQueue::default();
+        push(&mut queue, "a1", Some("a"));
+        push(&mut queue, "a2", Some("a"));
+        push(&mut queue, "b1", Some("b"));
+
+        let first = queue.start_next(0).unwrap();
+        assert_eq!(first.event_id, "a1");
+        assert_eq!(queue.failed(first.seq, "503".to_string(), 0, &LIMITS), None);
+
+        // a2 waits behind a1's backoff, b1 does not
+        let next = queue.start_next(0).unwrap();
+        assert_eq!(next.event_id, "b1");
+        queue.delivered(next.seq);
+        assert!(queue.start_next(50).is_none());
+        assert_eq!(queue.next_attempt_at(), Some(100));
+
+        let retry = queue.start_next(100).unwrap();
+        assert_eq!(retry.event_id, "a1");
+        queue.delivered(retry.seq);
+        assert_eq!(queue.start_next(100).unwrap().event_id, "a2");
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest_event() {
+        let mut queue = DestinationQueue::default();
+        for id in ["e1", "e2", "e3"] {
+            push(&mut queue, id, None);
+        }
+        // e1 is being delivered, so e2 makes room
+        let in_flight = queue.start_next(0).unwrap();
+        let evicted = queue
+            .push("e4", None, "{}".to_string(), 0, &LIMITS)
+            .unwrap();
+        assert_eq!(evicted.event_id, "e2");
+        queue.delivered(in_flight.seq);
+        assert_eq!(pending_ids(&queue), ["e3", "e4"]);
+
+        let (_, dropped) = queue.backlog();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].event_id, "e2");
+        assert_eq!(dropped[0].reason, DropReason::QueueFull);
+    }
+
+    #[test]
+    fn test_exhausted_event_is_dropped_and_replayed_in_place() {
+        let mut queue = DestinationQueue::default();
+        push(&mut queue, "a1", Some("a"));
+        push(&mut queue, "a2", Some("a"));
+
+        let event = queue.start_next(0).unwrap();
+        assert_eq!(queue.failed(event.seq, "503".to_string(), 0, &LIMITS), None);
+        let event = queue.start_next(100).unwrap();
+        assert_eq!(
+            queue.failed(event.seq, "503".to_string(), 100, &LIMITS),
+            Some(DropReason::AttemptsExhausted)
+        );
+        assert_eq!(pending_ids(&queue), ["a2"]);
+
+        assert_eq!(queue.replay(200), (1, 1));
+        assert_eq!(pending_ids(&queue), ["a1", "a2"]);
+        let (pending, dropped) = queue.backlog();
+        assert!(dropped.is_empty());
+        assert_eq!(pending[0].attempts, 0);
+        assert_eq!(queue.start_next(200).unwrap().event_id, "a1");
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_maximum() {
+        assert_eq!(retry_delay_ms(1, &LIMITS), 100);
+        assert_eq!(retry_delay_ms(2, &LIMITS), 200);
+        assert_eq!(retry_delay_ms(4, &LIMITS), 800);
+        assert_eq!(retry_delay_ms(5, &LIMITS), 1_000);
+        assert_eq!(retry_delay_ms(60, &LIMITS), 1_000);
+    }
+
+    #[test]
+    fn test_queue_round_trips_through_json() {
+        let mut queue = DestinationQueue::default();
+        push(&mut queue, "e1", Some("s"));
+        queue.start_next(0);
+
+        let restored: DestinationQueue =
+            serde_json::from_slice(&serde_json::to_vec(&queue).unwrap()).unwrap();
+        assert_eq!(restored.pending, queue.pending);
+        assert_eq!(restored.next_seq, 1);
+        // A delivery cut short by a restart is simply attempted again
+        assert_eq!(restored.in_flight, None);
+    }
+}
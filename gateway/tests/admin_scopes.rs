@@ -0,0 +1,371 @@
+//! Integration tests for admin API scopes.
+//!
+//! Runs the admin router behind the auth middlewares with API secrets for
+//! clients holding single scopes, and checks:
+//!
+//! 1. reads need `admin:read` and changes need `admin:write`, and a refused
+//!    call answers 403 naming the missing scope
+//! 2. `admin_ids` hold every scope
+//! 3. every mutating call, allowed or refused, is recorded in the audit log
+//!    with the acting client id, and reads are not
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    middleware,
+};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use waav_gateway::{
+    config::{AdminScope, AdminScopeIds, AuthApiSecret, PluginConfig, ServerConfig},
+    middleware::{admin_auth_middleware, auth_middleware},
+    routes,
+    state::AppState,
+};
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+async fn create_test_state(auth_required: bool) -> Arc<AppState> {
+    let config = ServerConfig {
+        host: "localhost".to_string(),
+        port: 3001,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: ["admin", "reader", "writer", "operator", "resilience"]
+            .into_iter()
+            .map(|id| AuthApiSecret {
+                id: id.to_string(),
+                secret: format!("{id}-token"),
+            })
+            .collect(),
+        auth_timeout_seconds: 5,
+        auth_required,
+        auth_admin_ids: ids(&["admin"]),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: AdminScopeIds {
+            read: ids(&["reader", "operator"]),
+            write: ids(&["writer", "operator"]),
+            chaos: ids(&["resilience"]),
+        },
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    };
+
+    AppState::new(config).await
+}
+
+fn admin_app(state: Arc<AppState>) -> axum::Router {
+    routes::admin::create_admin_router()
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin_auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state)
+}
+
+/// Send a request as `client` (its API secret is `{client}-token`)
+async fn call(
+    state: &Arc<AppState>,
+    method: Method,
+    uri: &str,
+    client: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(client) = client {
+        builder = builder.header("authorization", format!("Bearer {client}-token"));
+    }
+    let body = match body {
+        Some(body) => {
+            builder = builder.header("content-type", "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = admin_app(state.clone())
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
+
+#[tokio::test]
+async fn test_read_and_write_scopes_are_separate() {
+    let state = create_test_state(true).await;
+    let flags = json!({"echo_guard": {"default": true}});
+
+    let (status, _) = call(
+        &state,
+        Method::GET,
+        "/admin/feature_flags",
+        Some("reader"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = call(
+        &state,
+        Method::PUT,
+        "/admin/feature_flags",
+        Some("reader"),
+        Some(flags.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "missing_scope");
+    assert_eq!(body["scope"], "admin:write");
+
+    let (status, _) = call(
+        &state,
+        Method::PUT,
+        "/admin/feature_flags",
+        Some("writer"),
+        Some(flags),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // admin:write does not grant admin:read
+    let (status, body) = call(
+        &state,
+        Method::GET,
+        "/admin/feature_flags",
+        Some("writer"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["scope"], "admin:read");
+}
+
+#[cfg(feature = "chaos")]
+#[tokio::test]
+async fn test_chaos_needs_its_own_scope() {
+    let state = create_test_state(true).await;
+
+    // Holding admin:read and admin:write does not grant admin:chaos
+    let (status, body) = call(
+        &state,
+        Method::PUT,
+        "/admin/chaos",
+        Some("operator"),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["scope"], "admin:chaos");
+
+    let (_, body) = call(
+        &state,
+        Method::PUT,
+        "/admin/chaos",
+        Some("resilience"),
+        Some(json!({})),
+    )
+    .await;
+    assert_ne!(body["error"], "missing_scope");
+    assert_eq!(state.admin_audit.entries().len(), 2);
+}
+
+#[tokio::test]
+async fn test_each_route_requires_its_scope() {
+    let state = create_test_state(true).await;
+    let routes = [
+        (Method::GET, "/admin/audit", "admin:read"),
+        (Method::GET, "/admin/agents", "admin:read"),
+        (Method::GET, "/admin/agents/support", "admin:read"),
+        (Method::POST, "/admin/agents", "admin:write"),
+        (Method::PUT, "/admin/agents/support", "admin:write"),
+        (Method::DELETE, "/admin/agents/support", "admin:write"),
+        (Method::GET, "/admin/load_shedding", "admin:read"),
+        (Method::PUT, "/admin/load_shedding", "admin:write"),
+        (Method::GET, "/admin/feature_flags", "admin:read"),
+        (Method::PUT, "/admin/feature_flags", "admin:write"),
+        (Method::GET, "/admin/replay", "admin:read"),
+        (Method::POST, "/admin/replay", "admin:write"),
+        (Method::GET, "/admin/replay/job-1", "admin:read"),
+        (Method::DELETE, "/admin/replay/job-1", "admin:write"),
+        (Method::GET, "/admin/reconciliation", "admin:read"),
+        (Method::GET, "/admin/webhooks/pending", "admin:read"),
+        (Method::POST, "/admin/webhooks/replay", "admin:write"),
+        (
+            Method::POST,
+            "/providers/tts/deepgram/validate_credentials",
+            "admin:read",
+        ),
+    ];
+
+    for (method, uri, scope) in routes {
+        // A client holding only another scope is refused, naming the scope
+        let other = if scope == "admin:read" {
+            "writer"
+        } else {
+            "reader"
+        };
+        let (status, body) = call(&state, method.clone(), uri, Some(other), Some(json!({}))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+        assert_eq!(body["scope"], scope, "{method} {uri}");
+
+        // Admins hold every scope
+        let (status, _) = call(&state, method.clone(), uri, Some("admin"), Some(json!({}))).await;
+        assert_ne!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+        assert_ne!(status, StatusCode::UNAUTHORIZED, "{method} {uri}");
+    }
+}
+
+#[tokio::test]
+async fn test_mutating_calls_are_audited() {
+    let state = create_test_state(true).await;
+
+    call(
+        &state,
+        Method::GET,
+        "/admin/load_shedding",
+        Some("reader"),
+        None,
+    )
+    .await;
+    call(
+        &state,
+        Method::DELETE,
+        "/admin/agents/support",
+        Some("reader"),
+        None,
+    )
+    .await;
+    let (status, _) = call(
+        &state,
+        Method::PUT,
+        "/admin/feature_flags",
+        Some("writer"),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let entries = state.admin_audit.entries();
+    assert_eq!(entries.len(), 2, "reads are not audited: {entries:?}");
+
+    assert_eq!(entries[0].client_id.as_deref(), Some("reader"));
+    assert_eq!(entries[0].scope, AdminScope::Write);
+    assert_eq!(entries[0].method, "DELETE");
+    assert_eq!(entries[0].path, "/admin/agents/support");
+    assert_eq!(entries[0].status, 403);
+
+    assert_eq!(entries[1].client_id.as_deref(), Some("writer"));
+    assert_eq!(entries[1].method, "PUT");
+    assert_eq!(entries[1].path, "/admin/feature_flags");
+    assert_eq!(entries[1].status, 200);
+
+    // The log is served to admin:read holders
+    let (status, body) = call(&state, Method::GET, "/admin/audit", Some("reader"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let served = body["entries"].as_array().unwrap();
+    assert_eq!(served.len(), 2);
+    assert_eq!(served[1]["client_id"], "writer");
+    assert_eq!(served[1]["scope"], "admin:write");
+}
+
+#[tokio::test]
+async fn test_auth_disabled_allows_and_audits_without_client() {
+    let state = create_test_state(false).await;
+
+    let (status, _) = call(
+        &state,
+        Method::PUT,
+        "/admin/feature_flags",
+        None,
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let entries = state.admin_audit.entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].client_id, None);
+    assert_eq!(entries[0].status, 200);
+}
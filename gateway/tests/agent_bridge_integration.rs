@@ -0,0 +1,359 @@
+//! # Agent Bridge Integration Tests
+//!
+//! Drives `AgentBridge` against a local mock server that streams
+//! OpenAI-compatible chat completion chunks as server-sent events:
+//!
+//! 1. Streamed tokens are spoken sentence by sentence, with a final flush.
+//! 2. Final transcripts are collected until `is_speech_final` starts a turn.
+//! 3. New user speech cancels the LLM stream and clears queued TTS.
+//! 4. Endpoint errors reach the error callback.
+//! 5. Spoken replies are sent back as history on the next turn.
+//! 6. A truncated reply ends after its next sentence.
+//!
+//! The mock server is a raw TCP listener so the SSE body can be streamed with
+//! chunked encoding and held open mid-reply.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test agent_bridge_integration
+//! ```
+
+use async_trait::async_trait;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use waav_gateway::core::agent_bridge::{
+    AgentBridge, AgentBridgeConfig, AgentBridgeError, AgentBridgeResult, SpeechSink,
+};
+use waav_gateway::core::stt::STTResult;
+
+/// Sink that records what the bridge asks TTS to do
+#[derive(Default)]
+struct RecordingSink {
+    spoken: Mutex<Vec<(String, bool)>>,
+    flushes: Mutex<usize>,
+    clears: Mutex<usize>,
+}
+
+#[async_trait]
+impl SpeechSink for RecordingSink {
+    async fn speak(&self, text: &str, flush: bool) -> AgentBridgeResult<()> {
+        self.spoken.lock().await.push((text.to_string(), flush));
+        Ok(())
+    }
+
+    async fn flush(&self) -> AgentBridgeResult<()> {
+        *self.flushes.lock().await += 1;
+        Ok(())
+    }
+
+    async fn clear(&self) -> AgentBridgeResult<()> {
+        *self.clears.lock().await += 1;
+        Ok(())
+    }
+}
+
+/// Request captured by the mock server
+#[derive(Debug, Clone)]
+struct CapturedRequest {
+    headers: String,
+    body: serde_json::Value,
+}
+
+/// Read one HTTP request and return its headers and body
+async fn read_request(stream: &mut TcpStream) -> Option<(String, String)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let text = String::from_utf8_lossy(&buf).to_string();
+        let Some(header_end) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let headers = text[..header_end].to_string();
+        let content_length = headers
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())
+                    .flatten()
+            })
+            .unwrap_or(0);
+        if buf.len() >= header_end + 4 + content_length {
+            return Some((headers, text[header_end + 4..].to_string()));
+        }
+    }
+}
+
+/// Format a content delta as an SSE event
+fn content_event(text: &str) -> String {
+    let chunk = serde_json::json!({
+        "choices": [{"index": 0, "delta": {"content": text}}]
+    });
+    format!("data: {chunk}\n\n")
+}
+
+async fn write_chunk(stream: &mut TcpStream, data: &str) {
+    let _ = stream
+        .write_all(format!("{:x}\r\n{}\r\n", data.len(), data).as_bytes())
+        .await;
+    let _ = stream.flush().await;
+}
+
+/// Start a mock chat completions server.
+///
+/// The last user message selects the behaviour:
+/// - contains "stall": one sentence is streamed, then the connection is held open
+/// - contains "fail": HTTP 500
+/// - otherwise: a two-sentence reply streamed token by token
+async fn start_mock_server() -> (String, Arc<Mutex<Vec<CapturedRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let captured = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let captured = captured.clone();
+            tokio::spawn(async move {
+                let Some((headers, body)) = read_request(&mut stream).await else {
+                    return;
+                };
+                let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+                let user_text = body["messages"]
+                    .as_array()
+                    .and_then(|m| m.last())
+                    .and_then(|m| m["content"].as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                captured
+                    .lock()
+                    .await
+                    .push(CapturedRequest { headers, body });
+
+                if user_text.contains("fail") {
+                    let body = r#"{"error":{"message":"model overloaded"}}"#;
+                    let response = format!(
+                        "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    return;
+                }
+
+                let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n";
+                let _ = stream.write_all(head.as_bytes()).await;
+                write_chunk(
+                    &mut stream,
+                    "data: {\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+                )
+                .await;
+
+                if user_text.contains("stall") {
+                    write_chunk(&mut stream, &content_event("Let me check that. ")).await;
+                    sleep(Duration::from_secs(60)).await;
+                    return;
+                }
+
+                for token in ["Hello", " there", "! How", " can I", " help?"] {
+                    write_chunk(&mut stream, &content_event(token)).await;
+                    sleep(Duration::from_millis(10)).await;
+                }
+                write_chunk(&mut stream, "data: [DONE]\n\n").await;
+                let _ = stream.write_all(b"0\r\n\r\n").await;
+            });
+        }
+    });
+
+    (format!("http://{}/v1/chat/completions", addr), requests)
+}
+
+fn create_bridge(endpoint: &str) -> (Arc<AgentBridge>, Arc<RecordingSink>) {
+    let sink = Arc::new(RecordingSink::default());
+    let config = AgentBridgeConfig {
+        endpoint: endpoint.to_string(),
+        api_key: Some("test-llm-key".to_string()),
+        model: "test-model".to_string(),
+        system_prompt: Some("You are a helpful voice assistant.".to_string()),
+        max_tokens: Some(64),
+    };
+    let weak = Arc::downgrade(&sink) as Weak<dyn SpeechSink>;
+    let bridge = Arc::new(AgentBridge::new(config, weak).unwrap());
+    (bridge, sink)
+}
+
+fn stt(transcript: &str, is_final: bool, is_speech_final: bool) -> STTResult {
+    STTResult::new(transcript.to_string(), is_final, is_speech_final, 0.9)
+}
+
+#[tokio::test]
+async fn test_streamed_reply_is_spoken_by_sentence() {
+    let (endpoint, requests) = start_mock_server().await;
+    let (bridge, sink) = create_bridge(&endpoint);
+
+    bridge.start_turn("hi".to_string()).await;
+    sleep(Duration::from_millis(500)).await;
+
+    let spoken = sink.spoken.lock().await.clone();
+    assert_eq!(
+        spoken,
+        vec![
+            ("Hello there!".to_string(), false),
+            ("How can I help?".to_string(), true),
+        ]
+    );
+    assert_eq!(*sink.clears.lock().await, 0);
+
+    let requests = requests.lock().await;
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert!(
+        request
+            .headers
+            .to_lowercase()
+            .contains("authorization: bearer test-llm-key")
+    );
+    assert_eq!(request.body["model"], "test-model");
+    assert_eq!(request.body["stream"], true);
+    assert_eq!(request.body["max_tokens"], 64);
+    assert_eq!(request.body["messages"][0]["role"], "system");
+    assert_eq!(request.body["messages"][1]["role"], "user");
+    assert_eq!(request.body["messages"][1]["content"], "hi");
+}
+
+#[tokio::test]
+async fn test_turn_starts_on_speech_final() {
+    let (endpoint, requests) = start_mock_server().await;
+    let (bridge, sink) = create_bridge(&endpoint);
+
+    bridge
+        .handle_stt_result(&stt("what is", false, false))
+        .await;
+    bridge
+        .handle_stt_result(&stt("what is the", true, false))
+        .await;
+    bridge.handle_stt_result(&stt("weather", true, false)).await;
+    sleep(Duration::from_millis(100)).await;
+    assert!(requests.lock().await.is_empty());
+
+    // Turn detection emits an empty forced speech_final
+    bridge.handle_stt_result(&stt("", true, true)).await;
+    sleep(Duration::from_millis(500)).await;
+
+    let requests = requests.lock().await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].body["messages"][1]["content"],
+        "what is the weather"
+    );
+    assert_eq!(sink.spoken.lock().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_barge_in_cancels_stream_and_clears_tts() {
+    let (endpoint, _requests) = start_mock_server().await;
+    let (bridge, sink) = create_bridge(&endpoint);
+
+    bridge.start_turn("please stall".to_string()).await;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(
+        sink.spoken.lock().await.clone(),
+        vec![("Let me check that.".to_string(), false)]
+    );
+
+    // User starts talking over the reply
+    bridge.handle_stt_result(&stt("wait", false, false)).await;
+    assert_eq!(*sink.clears.lock().await, 1);
+
+    // Further speech does not clear again, and nothing more is spoken
+    bridge
+        .handle_stt_result(&stt("wait actually", false, false))
+        .await;
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(*sink.clears.lock().await, 1);
+    assert_eq!(sink.spoken.lock().await.len(), 1);
+    assert_eq!(*sink.flushes.lock().await, 0);
+}
+
+#[tokio::test]
+async fn test_endpoint_error_reaches_callback() {
+    let (endpoint, _requests) = start_mock_server().await;
+    let (bridge, sink) = create_bridge(&endpoint);
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let errors_clone = errors.clone();
+    bridge.on_error(move |error| {
+        let errors = errors_clone.clone();
+        Box::pin(async move {
+            errors.lock().await.push(error);
+        })
+    });
+
+    bridge.start_turn("this will fail".to_string()).await;
+    sleep(Duration::from_millis(300)).await;
+
+    let errors = errors.lock().await;
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AgentBridgeError::HttpStatus { status: 500, .. }
+    ));
+    assert!(sink.spoken.lock().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_spoken_reply_is_sent_as_history() {
+    let (endpoint, requests) = start_mock_server().await;
+    let (bridge, _sink) = create_bridge(&endpoint);
+
+    bridge.start_turn("hi".to_string()).await;
+    sleep(Duration::from_millis(400)).await;
+    bridge.start_turn("tell me more".to_string()).await;
+    sleep(Duration::from_millis(400)).await;
+
+    let requests = requests.lock().await;
+    assert_eq!(requests.len(), 2);
+    let messages = requests[1].body["messages"].as_array().unwrap();
+    let roles: Vec<&str> = messages
+        .iter()
+        .map(|m| m["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, vec!["system", "user", "assistant", "user"]);
+    assert_eq!(messages[2]["content"], "Hello there! How can I help?");
+}
+
+#[tokio::test]
+async fn test_truncated_reply_ends_after_next_sentence() {
+    let (endpoint, requests) = start_mock_server().await;
+    let (bridge, sink) = create_bridge(&endpoint);
+    assert!(!bridge.truncate_reply());
+
+    bridge.start_turn("hi".to_string()).await;
+    assert!(bridge.truncate_reply());
+    sleep(Duration::from_millis(500)).await;
+
+    assert_eq!(
+        sink.spoken.lock().await.clone(),
+        vec![("Hello there!".to_string(), false)]
+    );
+    assert_eq!(*sink.flushes.lock().await, 1);
+
+    // The next turn is not truncated, and history holds what was spoken
+    bridge.start_turn("tell me more".to_string()).await;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(sink.spoken.lock().await.len(), 3);
+    let requests = requests.lock().await;
+    assert_eq!(requests[1].body["messages"][2]["content"], "Hello there!");
+}
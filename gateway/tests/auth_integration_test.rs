@@ -58,13 +58,46 @@ async fn create_test_state_auth_disabled() -> Arc<AppState> {
         auth_api_secrets: Vec::new(),
         auth_timeout_seconds: 5,
         auth_required: false, // Auth disabled
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
         sip: None,
         cors_allowed_origins: None,
         rate_limit_requests_per_second: 60,
         rate_limit_burst_size: 10,
         max_websocket_connections: None,
         max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
         plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
     };
 
     AppState::new(config).await
@@ -217,6 +250,9 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             auth_api_secrets: Vec::new(),
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             tls: None,
             cors_allowed_origins: None,
@@ -224,7 +260,37 @@ V/reoL3Jcy/mQ9MrmJx+K1VC
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         let state = AppState::new(config).await;
@@ -515,6 +581,9 @@ mod with_api_secret {
             auth_api_secrets,
             auth_timeout_seconds: 5,
             auth_required: true,
+            auth_admin_ids: Vec::new(),
+            auth_monitor_audio_ids: Vec::new(),
+            auth_admin_scopes: Default::default(),
             sip: None,
             tls: None,
             cors_allowed_origins: None,
@@ -522,7 +591,37 @@ mod with_api_secret {
             rate_limit_burst_size: 10,
             max_websocket_connections: None,
             max_connections_per_ip: 100,
+            trusted_proxies: Vec::new(),
+            provider_connect_timeout_secs: 10,
             plugins: PluginConfig::default(),
+            greeting: None,
+            greeting_assets_dir: None,
+            tts_fallback_voices: Default::default(),
+            tts_max_pending_utterances: 5,
+            tts_queue_policy: Default::default(),
+            tts_system_speak_max_chars: 500,
+            provider_error_max_chars: 300,
+            deepgram_connection_budget: Default::default(),
+            usage: None,
+            agents: Vec::new(),
+            strict_config: false,
+            selftest: None,
+            load_shedding: None,
+            replay_max_concurrent_jobs: 1,
+            feature_flags: Default::default(),
+            transcript_buffer: Default::default(),
+            transcript_enrichment: None,
+            session_export: None,
+            audio_sinks: Default::default(),
+            recording_upload: None,
+            recording_redaction: Default::default(),
+            chaos: Default::default(),
+            voice_profiles: Default::default(),
+            ws_heartbeat: Default::default(),
+            console: Default::default(),
+            webhook_queue: None,
+            ingest: None,
+            flight_recorder: None,
         };
 
         AppState::new(config).await
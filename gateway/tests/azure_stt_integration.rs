@@ -42,6 +42,8 @@ fn test_create_azure_provider_by_name() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider("microsoft-azure", config);
@@ -558,6 +560,8 @@ async fn test_azure_real_connection() {
         encoding: "linear16".to_string(),
         model: "".to_string(),
         provider: "microsoft-azure".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut stt = AzureSTT::new(config).unwrap();
@@ -621,6 +625,8 @@ async fn test_azure_send_silence_audio() {
         encoding: "linear16".to_string(),
         model: "".to_string(),
         provider: "microsoft-azure".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     // Parse and set region
@@ -0,0 +1,259 @@
+//! # Barge-In Integration Tests
+//!
+//! Builds sessions on in-process mock providers. The mock STT replays frames
+//! recorded from the Deepgram live API when audio arrives: a `SpeechStarted`
+//! VAD event immediately, the first interim transcript [`INTERIM_DELAY`]
+//! later, then the final transcript and `UtteranceEnd`.
+//!
+//! 1. `on_vad` clears TTS output on the VAD event, ahead of the transcript.
+//! 2. `on_interim` clears TTS output on the first interim transcript.
+//! 3. `off` never clears TTS output.
+//! 4. `on_vad` falls back to interim transcripts for providers without VAD.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test barge_in
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use fixtures::session_mocks::{
+    Silent, SttBehavior, SttCallbacks, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{
+    BargeInMode, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::stt::deepgram::{DeepgramResponse, DeepgramVadEvent};
+use waav_gateway::core::stt::{STTError, STTResult, STTVadEvent};
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+const VAD_STT: &str = "barge-in-vad-stt";
+const NO_VAD_STT: &str = "barge-in-no-vad-stt";
+const MOCK_TTS: &str = "barge-in-mock-tts";
+
+/// Delay between the speech-started event and the first interim transcript
+const INTERIM_DELAY: Duration = Duration::from_millis(300);
+
+/// How long to collect session events after pushing audio
+const COLLECT_FOR: Duration = Duration::from_millis(900);
+
+/// Recorded Deepgram frames, each with its offset from the first audio chunk
+const RECORDED_FRAMES: &[(u64, &str)] = &[
+    (
+        0,
+        r#"{"type":"SpeechStarted","channel":[0],"timestamp":0.02}"#,
+    ),
+    (
+        300,
+        r#"{"type":"Results","channel_index":[0,1],"duration":0.5,"start":0.0,"is_final":false,"speech_final":false,"channel":{"alternatives":[{"transcript":"hold on","confidence":0.91,"words":[]}]}}"#,
+    ),
+    (
+        450,
+        r#"{"type":"Results","channel_index":[0,1],"duration":0.9,"start":0.0,"is_final":true,"speech_final":true,"channel":{"alternatives":[{"transcript":"hold on a second","confidence":0.97,"words":[]}]}}"#,
+    ),
+    (
+        600,
+        r#"{"type":"UtteranceEnd","channel":[0,1],"last_word_end":0.88}"#,
+    ),
+];
+
+/// STT behavior that replays [`RECORDED_FRAMES`] once audio arrives
+struct RecordedFrames {
+    reports_vad: bool,
+    replaying: bool,
+}
+
+#[async_trait]
+impl SttBehavior for RecordedFrames {
+    async fn send_audio(
+        &mut self,
+        _audio: Bytes,
+        callbacks: &SttCallbacks,
+    ) -> Result<(), STTError> {
+        if std::mem::replace(&mut self.replaying, true) {
+            return Ok(());
+        }
+
+        let callbacks = callbacks.clone();
+        tokio::spawn(async move {
+            let started = tokio::time::Instant::now();
+            for (offset_ms, frame) in RECORDED_FRAMES {
+                tokio::time::sleep_until(started + Duration::from_millis(*offset_ms)).await;
+                if let Ok(event) = serde_json::from_str::<DeepgramVadEvent>(frame)
+                    && let Some(event) = event.to_stt_event()
+                {
+                    callbacks.vad(event).await;
+                    continue;
+                }
+                let response: DeepgramResponse = serde_json::from_str(frame).unwrap();
+                let alternative = &response.channel.as_ref().unwrap().alternatives[0];
+                let result = STTResult::new(
+                    alternative.transcript.clone(),
+                    response.is_final.unwrap_or(false),
+                    response.speech_final.unwrap_or(false),
+                    alternative.confidence,
+                );
+                callbacks.result(result).await;
+            }
+        });
+        Ok(())
+    }
+
+    fn reports_vad(&self) -> bool {
+        self.reports_vad
+    }
+}
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    for (name, reports_vad) in [(VAD_STT, true), (NO_VAD_STT, false)] {
+        registry.register_stt(
+            name,
+            stt_factory(move |_| RecordedFrames {
+                reports_vad,
+                replaying: false,
+            }),
+            ProviderMetadata::stt(name, "Barge-In Mock STT"),
+        );
+    }
+    registry.register_tts(
+        MOCK_TTS,
+        tts_factory(|_| Silent),
+        ProviderMetadata::tts(MOCK_TTS, "Barge-In Mock TTS"),
+    );
+    registry
+}
+
+/// Push one audio chunk and return the session events with their arrival time
+async fn run_session(stt_provider: &str, mode: BargeInMode) -> Vec<(Duration, SessionEvent)> {
+    let session = SessionPipelineBuilder::new()
+        .plugin_registry(mock_registry())
+        .stt(mock_stt_config(stt_provider))
+        .tts(mock_tts_config(MOCK_TTS))
+        .barge_in(mode)
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("session should build with mock providers");
+    let mut events = session.take_events().unwrap();
+
+    let started = Instant::now();
+    session.push_audio(vec![0u8; 3200].into()).await.unwrap();
+    let collected = collect_events(&mut events, started).await;
+
+    session.close().await.unwrap();
+    collected
+}
+
+async fn collect_events(
+    events: &mut SessionEventStream,
+    started: Instant,
+) -> Vec<(Duration, SessionEvent)> {
+    let mut collected = Vec::new();
+    let _ = tokio::time::timeout(COLLECT_FOR, async {
+        while let Some(event) = events.recv().await {
+            collected.push((started.elapsed(), event));
+        }
+    })
+    .await;
+    collected
+}
+
+/// Arrival time of the first event matching `predicate`
+fn first_at(
+    events: &[(Duration, SessionEvent)],
+    predicate: impl Fn(&SessionEvent) -> bool,
+) -> Option<Duration> {
+    events
+        .iter()
+        .find(|(_, event)| predicate(event))
+        .map(|(at, _)| *at)
+}
+
+fn is_cleared(event: &SessionEvent) -> bool {
+    matches!(event, SessionEvent::AudioCleared)
+}
+
+fn is_transcript(event: &SessionEvent) -> bool {
+    matches!(event, SessionEvent::Transcript(_))
+}
+
+fn cleared_count(events: &[(Duration, SessionEvent)]) -> usize {
+    events.iter().filter(|(_, event)| is_cleared(event)).count()
+}
+
+#[tokio::test]
+async fn test_on_vad_interrupts_before_transcript() {
+    let events = run_session(VAD_STT, BargeInMode::OnVad).await;
+
+    let cleared_at = first_at(&events, is_cleared).expect("TTS output should be cleared");
+    let transcript_at = first_at(&events, is_transcript).expect("transcript should arrive");
+    assert!(
+        cleared_at < INTERIM_DELAY,
+        "cleared at {cleared_at:?}, expected before the interim transcript"
+    );
+    assert!(cleared_at < transcript_at);
+
+    // The VAD event is surfaced ahead of the transcript
+    let vad_at = first_at(&events, |event| {
+        matches!(
+            event,
+            SessionEvent::Vad(STTVadEvent::SpeechStarted { timestamp: Some(_) })
+        )
+    })
+    .expect("speech started should be surfaced");
+    assert!(vad_at < transcript_at);
+    assert!(
+        events
+            .iter()
+            .any(|(_, event)| matches!(event, SessionEvent::Vad(STTVadEvent::UtteranceEnd { .. })))
+    );
+
+    // Later transcripts of the same turn do not clear again
+    assert_eq!(cleared_count(&events), 1);
+}
+
+#[tokio::test]
+async fn test_on_interim_interrupts_on_first_transcript() {
+    let events = run_session(VAD_STT, BargeInMode::OnInterim).await;
+
+    let cleared_at = first_at(&events, is_cleared).expect("TTS output should be cleared");
+    let transcript_at = first_at(&events, is_transcript).expect("transcript should arrive");
+    assert!(
+        cleared_at >= INTERIM_DELAY,
+        "cleared at {cleared_at:?}, expected with the interim transcript"
+    );
+    assert!(cleared_at <= transcript_at);
+    assert_eq!(cleared_count(&events), 1);
+}
+
+#[tokio::test]
+async fn test_off_never_interrupts() {
+    let events = run_session(VAD_STT, BargeInMode::Off).await;
+
+    assert!(first_at(&events, is_transcript).is_some());
+    assert_eq!(cleared_count(&events), 0);
+}
+
+#[tokio::test]
+async fn test_on_vad_falls_back_to_interim_without_vad_events() {
+    let events = run_session(NO_VAD_STT, BargeInMode::OnVad).await;
+
+    let cleared_at = first_at(&events, is_cleared).expect("TTS output should be cleared");
+    assert!(
+        cleared_at >= INTERIM_DELAY,
+        "cleared at {cleared_at:?}, expected with the interim transcript"
+    );
+    assert!(
+        !events
+            .iter()
+            .any(|(_, event)| matches!(event, SessionEvent::Vad(_)))
+    );
+    assert_eq!(cleared_count(&events), 1);
+}
@@ -0,0 +1,208 @@
+//! # Bench Smoke Test
+//!
+//! Runs `waav_gateway::bench` with 5 sessions against an in-process gateway
+//! whose STT and TTS are the mock providers of `fixtures::session_mocks`,
+//! registered through the provider registry. The mock STT transcribes each
+//! burst of non-silent audio and the mock TTS answers every utterance with a
+//! short chunk of audio, so every latency of the report gets samples.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test bench_smoke
+//! ```
+
+mod fixtures;
+
+use axum::middleware;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+use fixtures::session_mocks::{Clip, PerBurst, mock_registry, stt_factory, tts_factory};
+use waav_gateway::bench::{self, BenchOptions};
+use waav_gateway::core::stt::STTResult;
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::{
+    ServerConfig, config::PluginConfig, middleware::auth::auth_middleware, routes, state::AppState,
+};
+
+const MOCK_PROVIDER: &str = "bench-smoke-mock";
+
+const SESSIONS: usize = 5;
+
+/// Providers whose STT reports one final transcript per burst of audio and
+/// whose TTS answers every utterance with 50ms of silence
+fn providers() -> Arc<PluginRegistry> {
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| {
+            PerBurst::new(|_, _| vec![STTResult::new("synthetic".to_string(), true, true, 0.9)])
+        }),
+        tts_factory(|_| Clip::silence(24000, 50)),
+    )
+}
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Serve `/ws` on a local port, `None` if binding is not allowed
+async fn start_gateway() -> Option<SocketAddr> {
+    let app_state = AppState::with_plugin_registry(test_config(), providers()).await;
+    let app = routes::ws::create_ws_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .with_state(app_state);
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping bench smoke test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind bench test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    Some(addr)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_bench_five_sessions_against_mock_gateway() {
+    let Some(addr) = start_gateway().await else {
+        return;
+    };
+
+    let report = bench::run(BenchOptions {
+        url: format!("ws://{addr}/ws"),
+        sessions: SESSIONS,
+        ramp_up_secs: 1,
+        duration_secs: 2,
+        turn_ms: 600,
+        speak: Some("How can I help?".to_string()),
+        stt_provider: MOCK_PROVIDER.to_string(),
+        stt_model: "mock".to_string(),
+        tts_provider: MOCK_PROVIDER.to_string(),
+        tts_model: "mock".to_string(),
+        stt_api_key: Some("test-key".to_string()),
+        tts_api_key: Some("test-key".to_string()),
+        ..Default::default()
+    })
+    .await
+    .expect("bench should run");
+
+    assert_eq!(report.sessions, SESSIONS);
+    assert_eq!(report.failed_sessions, 0, "errors: {:?}", report.errors);
+    assert_eq!(report.completed_sessions, SESSIONS);
+    assert_eq!(report.error_messages, 0, "errors: {:?}", report.errors);
+    assert_eq!(report.connect.unwrap().samples, SESSIONS);
+
+    // Two seconds of 600ms turns: at least three turns per session
+    assert!(
+        report.turns >= 3 * SESSIONS as u64,
+        "turns: {}",
+        report.turns
+    );
+    assert!(report.tts_requests >= 3 * SESSIONS as u64);
+    let transcripts = report.first_transcript.expect("transcript latencies");
+    assert!(transcripts.samples >= 3 * SESSIONS);
+    let first_audio = report.first_audio_byte.expect("first audio latencies");
+    assert!(first_audio.samples >= 3 * SESSIONS);
+    assert!(first_audio.p50_ms <= first_audio.max_ms);
+    assert!(report.tts_audio_bytes > 0);
+    assert!(report.audio_sent_secs >= 1.5 * SESSIONS as f64);
+
+    // Both output formats render
+    assert!(report.to_string().contains("first audio byte"));
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["completed_sessions"], SESSIONS);
+}
@@ -0,0 +1,194 @@
+//! # C Plugin Integration Tests
+//!
+//! Loads the TTS plugin written in C from `examples/c-tts-plugin` (built
+//! with cargo and the system C compiler on first use) through
+//! `DynamicPluginLoader`, and drives it through the gateway's `BaseTTS`
+//! adapter:
+//!
+//! 1. The manifest from the C plugin registers the `c-tone-tts` provider.
+//! 2. Errors returned by the C functions carry the plugin's message.
+//! 3. Queued text is synthesized on flush into the expected amount of
+//!    audio at the configured sample rate, followed by completion.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --features plugins-dynamic --test c_plugin
+//! ```
+
+#![cfg(all(feature = "plugins-dynamic", target_os = "linux"))]
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OnceCell, mpsc};
+
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError};
+use waav_gateway::plugin::{DynamicPluginLoader, PluginLoadStatus, PluginRegistry};
+
+const PROVIDER: &str = "c-tone-tts";
+
+/// How long to wait for each callback from the plugin
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+static PLUGIN_REGISTRY: OnceCell<Arc<PluginRegistry>> = OnceCell::const_new();
+
+/// Build the C plugin example and return the path of its library
+fn build_c_plugin() -> PathBuf {
+    let manifest =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/c-tts-plugin/Cargo.toml");
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c-tts-plugin");
+
+    let status = Command::new(env!("CARGO"))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("Failed to run cargo for the C plugin");
+    assert!(status.success(), "Building the C plugin failed");
+
+    target_dir.join("debug").join("libwaav_plugin_c_tts.so")
+}
+
+/// Registry with the C plugin, loaded once per test binary
+async fn plugin_registry() -> &'static PluginRegistry {
+    PLUGIN_REGISTRY
+        .get_or_init(|| async {
+            let library = build_c_plugin();
+
+            let plugin_dir = tempfile::tempdir().unwrap();
+            std::fs::copy(
+                &library,
+                plugin_dir.path().join(library.file_name().unwrap()),
+            )
+            .unwrap();
+
+            let registry = Arc::new(PluginRegistry::new_isolated());
+            let mut loader = DynamicPluginLoader::new();
+            let reports = loader
+                .load_all_from_directory(plugin_dir.path(), &registry)
+                .await
+                .unwrap();
+            assert_eq!(reports.len(), 1);
+            assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+            loader.hand_over_to(&registry);
+            registry
+        })
+        .await
+}
+
+/// Events the plugin delivered through the adapter
+enum Event {
+    Audio(AudioData),
+    Error(String),
+    Complete,
+}
+
+struct ChannelCallback(mpsc::UnboundedSender<Event>);
+
+impl AudioCallback for ChannelCallback {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = self.0.send(Event::Audio(audio_data));
+        Box::pin(async {})
+    }
+
+    fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = self.0.send(Event::Error(error.to_string()));
+        Box::pin(async {})
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        let _ = self.0.send(Event::Complete);
+        Box::pin(async {})
+    }
+}
+
+async fn recv(events: &mut mpsc::UnboundedReceiver<Event>) -> Event {
+    tokio::time::timeout(RECV_TIMEOUT, events.recv())
+        .await
+        .expect("Timed out waiting for the C plugin")
+        .expect("Callback channel closed")
+}
+
+fn tts_config(sample_rate: u32) -> TTSConfig {
+    TTSConfig {
+        provider: PROVIDER.to_string(),
+        sample_rate: Some(sample_rate),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_c_plugin_registers_tts_provider() {
+    let registry = plugin_registry().await;
+    assert!(registry.has_tts_provider(PROVIDER));
+    let metadata = registry.get_tts_metadata(PROVIDER).unwrap();
+    assert_eq!(metadata.display_name, "C Tone TTS");
+    assert!(
+        registry
+            .dynamic_plugins()
+            .iter()
+            .any(|plugin| plugin.id == PROVIDER && plugin.version == "1.0.0")
+    );
+}
+
+#[tokio::test]
+async fn test_c_plugin_synthesizes_on_flush() {
+    let mut tts = plugin_registry()
+        .await
+        .create_tts(PROVIDER, tts_config(16000))
+        .unwrap();
+    let (tx, mut events) = mpsc::unbounded_channel();
+    tts.on_audio(Arc::new(ChannelCallback(tx))).unwrap();
+
+    // The C plugin's error message is reported with its error code
+    let error = tts.speak("too early", true).await.unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("NotConnected: speak before connect"),
+        "{error}"
+    );
+
+    tts.connect().await.unwrap();
+    assert!(tts.is_ready());
+    assert_eq!(tts.get_provider_info()["provider"], PROVIDER);
+
+    // Queued text waits for the flush
+    tts.speak("Hi ", false).await.unwrap();
+    tts.speak("you", true).await.unwrap();
+
+    let audio = match recv(&mut events).await {
+        Event::Audio(audio) => audio,
+        Event::Error(error) => panic!("C plugin failed: {error}"),
+        Event::Complete => panic!("C plugin completed without audio"),
+    };
+    assert_eq!(audio.sample_rate, 16000);
+    assert_eq!(audio.format, "linear16");
+    // 6 characters of 40 ms at 16 kHz, 16-bit samples
+    assert_eq!(audio.data.len(), 6 * 640 * 2);
+    assert_eq!(audio.duration_ms, Some(240));
+    // The space is silent, the letters are not
+    let sample =
+        |index: usize| i16::from_le_bytes([audio.data[index * 2], audio.data[index * 2 + 1]]);
+    assert_ne!(sample(0), 0);
+    assert!((2 * 640..3 * 640).all(|index| sample(index) == 0));
+    assert!(matches!(recv(&mut events).await, Event::Complete));
+
+    // Cleared text is never synthesized
+    tts.speak("dropped", false).await.unwrap();
+    tts.clear().await.unwrap();
+    tts.flush().await.unwrap();
+    tts.disconnect().await.unwrap();
+    assert!(!tts.is_ready());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), events.recv())
+            .await
+            .is_err()
+    );
+}
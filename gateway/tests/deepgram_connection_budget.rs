@@ -0,0 +1,121 @@
+//! # Deepgram Connection Budget Integration Tests
+//!
+//! Deepgram limits concurrent connections per API key across STT and TTS.
+//! These tests use up a key's budget and check that both Deepgram providers
+//! refuse to connect, before opening any connection, with an error naming
+//! the budget and the key's fingerprint but never the key itself:
+//!
+//! 1. A TTS connection uses up the budget an STT connect needs
+//! 2. An STT connect fails fast while the budget is used up
+//! 3. Releasing the permit frees the budget again
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test deepgram_connection_budget
+//! ```
+
+use waav_gateway::core::providers::connection_budget::{
+    ConnectionBudgetConfig, ConnectionBudgetPolicy, DEEPGRAM_CONNECTION_BUDGET, connection_budgets,
+};
+use waav_gateway::core::providers::credential_health::key_fingerprint;
+use waav_gateway::core::stt::{BaseSTT, DeepgramSTT, STTConfig, STTError};
+use waav_gateway::core::tts::{BaseTTS, DeepgramTTS, TTSConfig, TTSError};
+
+fn limit_budget(max_connections: usize) {
+    connection_budgets().configure(
+        DEEPGRAM_CONNECTION_BUDGET,
+        ConnectionBudgetConfig {
+            max_connections: Some(max_connections),
+            policy: ConnectionBudgetPolicy::FailFast,
+            ..Default::default()
+        },
+    );
+}
+
+fn stt(api_key: &str) -> DeepgramSTT {
+    DeepgramSTT::new(STTConfig {
+        provider: "deepgram".to_string(),
+        api_key: api_key.to_string(),
+        model: "nova-3".to_string(),
+        sample_rate: 16000,
+        encoding: "linear16".to_string(),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn tts(api_key: &str) -> DeepgramTTS {
+    DeepgramTTS::new(TTSConfig {
+        provider: "deepgram".to_string(),
+        api_key: api_key.to_string(),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn assert_names_budget(message: &str, api_key: &str) {
+    assert!(
+        message.contains("deepgram connection budget exhausted"),
+        "{message}"
+    );
+    assert!(message.contains(&key_fingerprint(api_key)), "{message}");
+    assert!(!message.contains(api_key), "key leaked: {message}");
+}
+
+#[tokio::test]
+async fn test_exhausted_budget_fails_stt_connect() {
+    let api_key = "dg-budget-stt-key-0a1b2c3d4e5f";
+    limit_budget(1);
+
+    // A TTS session of the same key holds the only permit
+    let permit = connection_budgets()
+        .acquire(DEEPGRAM_CONNECTION_BUDGET, api_key)
+        .await
+        .unwrap();
+
+    let err = stt(api_key).connect().await.unwrap_err();
+    assert!(matches!(err, STTError::ConnectionFailed(_)), "{err:?}");
+    assert_names_budget(&err.to_string(), api_key);
+
+    let fingerprint = key_fingerprint(api_key);
+    let status = connection_budgets()
+        .snapshot()
+        .into_iter()
+        .find(|status| status.fingerprint == fingerprint)
+        .expect("the key is tracked");
+    assert_eq!(status.provider, DEEPGRAM_CONNECTION_BUDGET);
+    assert_eq!((status.in_use, status.limit), (1, Some(1)));
+    assert_eq!(status.rejected, 1);
+
+    // Closing the connection frees the budget
+    drop(permit);
+    let permit = connection_budgets()
+        .acquire(DEEPGRAM_CONNECTION_BUDGET, api_key)
+        .await;
+    assert!(permit.is_ok());
+}
+
+#[tokio::test]
+async fn test_exhausted_budget_fails_tts_connect() {
+    let api_key = "dg-budget-tts-key-6f7e8d9c0b1a";
+    limit_budget(1);
+
+    // An STT session of the same key holds the only permit
+    let _permit = connection_budgets()
+        .acquire(DEEPGRAM_CONNECTION_BUDGET, api_key)
+        .await
+        .unwrap();
+
+    let mut tts = tts(api_key);
+    let err = tts.connect().await.unwrap_err();
+    assert!(matches!(err, TTSError::ConnectionFailed(_)), "{err:?}");
+    assert_names_budget(&err.to_string(), api_key);
+    assert!(!tts.is_ready());
+
+    // Other keys have their own budget
+    let other = connection_budgets()
+        .acquire(DEEPGRAM_CONNECTION_BUDGET, "dg-budget-other-key-1234")
+        .await;
+    assert!(other.is_ok());
+}
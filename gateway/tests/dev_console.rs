@@ -0,0 +1,170 @@
+//! # Development Console Tests
+//!
+//! 1. With `console.enabled: true`, `/console` serves the embedded page and
+//!    `/console/{file}` its scripts and stylesheet.
+//! 2. With the console disabled (the default), every console route answers
+//!    `404 Not Found`.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --features dev-console --test dev_console
+//! ```
+
+#![cfg(feature = "dev-console")]
+
+use axum::{Router, body::Body, http::Request, http::StatusCode, http::header};
+use tower::ServiceExt;
+
+use waav_gateway::config::{ConsoleConfig, PluginConfig};
+use waav_gateway::{ServerConfig, routes, state::AppState};
+
+fn test_config(console_enabled: bool) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: ConsoleConfig {
+            enabled: console_enabled,
+        },
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+async fn console_app(enabled: bool) -> Router {
+    let app_state = AppState::new(test_config(enabled)).await;
+    routes::console::create_console_router().with_state(app_state)
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Option<String>, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, content_type, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_console_served_when_enabled() {
+    let app = console_app(true).await;
+
+    let (status, content_type, body) = get(&app, "/console").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+    let page = String::from_utf8(body).unwrap();
+    assert!(page.contains("/console/console.js"));
+    assert!(page.contains("/console/console.css"));
+
+    for (uri, expected_type) in [
+        ("/console/console.js", "text/javascript; charset=utf-8"),
+        (
+            "/console/capture-worklet.js",
+            "text/javascript; charset=utf-8",
+        ),
+        ("/console/console.css", "text/css; charset=utf-8"),
+    ] {
+        let (status, content_type, body) = get(&app, uri).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert_eq!(content_type.as_deref(), Some(expected_type), "{uri}");
+        assert!(!body.is_empty(), "{uri}");
+    }
+
+    // Only the embedded files are served
+    let (status, ..) = get(&app, "/console/config.yaml").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_console_hidden_when_disabled() {
+    let app = console_app(false).await;
+    for uri in ["/console", "/console/", "/console/console.js"] {
+        let (status, ..) = get(&app, uri).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{uri}");
+    }
+}
@@ -0,0 +1,227 @@
+//! # ElevenLabs Manual Commit Test
+//!
+//! Runs one caller turn through `ElevenLabsSTT` against a mock ElevenLabs
+//! realtime server, with scripted gateway VAD events:
+//!
+//! 1. With the default `vad` commit strategy the mock commits once it has
+//!    heard `vad_silence_threshold_secs` of silence, like the real service.
+//! 2. With manual commit the connection asks for `commit_strategy=manual` and
+//!    the script calls `finalize()` as soon as the gateway's end-of-turn
+//!    silence has passed; the mock commits on the `commit` flag.
+//!
+//! The time from the end of speech to the committed transcript is logged for
+//! both modes, and manual commit must deliver the final sooner.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test elevenlabs_manual_commit -- --nocapture
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message};
+
+use waav_gateway::core::stt::{BaseSTT, ElevenLabsSTT, STTConfig, STTResult};
+
+/// Length of each audio chunk (ms)
+const CHUNK_MS: u64 = 20;
+
+/// Scripted caller speech before the gateway VAD reports the end of speech (ms)
+const SPEECH_MS: u64 = 400;
+
+/// End-of-turn silence of the gateway's turn detection (ms)
+const GATEWAY_SILENCE_MS: u64 = 300;
+
+/// Longest the script waits for the committed transcript (ms)
+const MAX_SILENCE_MS: u64 = 3000;
+
+/// 20 ms of 16 kHz PCM16, loud or silent
+fn chunk(speech: bool) -> Vec<u8> {
+    let sample: i16 = if speech { 6000 } else { 0 };
+    (0..CHUNK_MS * 16)
+        .flat_map(|_| sample.to_le_bytes())
+        .collect()
+}
+
+/// Query parameter `name` of a request path and query
+fn query_param(uri: &str, name: &str) -> Option<String> {
+    let url = url::Url::parse(&format!("ws://mock{uri}")).ok()?;
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Mock ElevenLabs realtime STT server
+///
+/// Answers speech with a partial transcript and commits either on its own
+/// VAD or on the client's `commit` flag, depending on the commit strategy
+/// the client connected with. Reports the request URI of each connection.
+async fn mock_elevenlabs() -> (String, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (uri_tx, uri_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let uri_tx = uri_tx.clone();
+            tokio::spawn(async move {
+                let mut uri = String::new();
+                let capture_uri =
+                    |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+                        if let Some(path_and_query) = request.uri().path_and_query() {
+                            uri = path_and_query.to_string();
+                        }
+                        Ok(response)
+                    };
+                let ws = accept_hdr_async(stream, capture_uri).await.unwrap();
+                let _ = uri_tx.send(uri.clone());
+
+                let manual = query_param(&uri, "commit_strategy").as_deref() == Some("manual");
+                let vad_silence = query_param(&uri, "vad_silence_threshold_secs")
+                    .and_then(|secs| secs.parse::<f64>().ok())
+                    .map(Duration::from_secs_f64)
+                    .unwrap_or(Duration::from_millis(500));
+
+                let (mut sink, mut stream) = ws.split();
+                let started = json!({"message_type": "session_started", "session_id": "mock"});
+                sink.send(Message::Text(started.to_string().into()))
+                    .await
+                    .unwrap();
+
+                let mut last_speech: Option<Instant> = None;
+                while let Some(Ok(Message::Text(text))) = stream.next().await {
+                    let message: Value = serde_json::from_str(&text).unwrap();
+                    let audio = message["audio_base_64"].as_str().unwrap_or_default();
+                    let commit = message["commit"].as_bool().unwrap_or(false);
+
+                    // Silent chunks encode to runs of 'A'
+                    if audio.chars().any(|c| c != 'A' && c != '=') {
+                        last_speech = Some(Instant::now());
+                        let partial = json!({
+                            "message_type": "partial_transcript",
+                            "text": "book a table"
+                        });
+                        sink.send(Message::Text(partial.to_string().into()))
+                            .await
+                            .unwrap();
+                    }
+
+                    let vad_commit =
+                        !manual && last_speech.is_some_and(|at| at.elapsed() >= vad_silence);
+                    if (manual && commit && last_speech.is_some()) || vad_commit {
+                        last_speech = None;
+                        let committed = json!({
+                            "message_type": "committed_transcript",
+                            "text": "book a table for four"
+                        });
+                        sink.send(Message::Text(committed.to_string().into()))
+                            .await
+                            .unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    (format!("ws://{addr}"), uri_rx)
+}
+
+/// Run one scripted turn and return the request URI and the time from the
+/// end of speech to the committed transcript
+async fn run_turn(manual_commit: bool) -> (String, Duration) {
+    let (base_url, mut uris) = mock_elevenlabs().await;
+
+    let config = STTConfig {
+        provider: "elevenlabs".to_string(),
+        api_key: "test-api-key".to_string(),
+        language: "en".to_string(),
+        ..Default::default()
+    };
+    let mut stt = <ElevenLabsSTT as BaseSTT>::new(config)
+        .unwrap()
+        .with_base_url(base_url);
+    assert!(stt.set_manual_commit(manual_commit).await.unwrap());
+
+    let (result_tx, mut results) = mpsc::unbounded_channel::<STTResult>();
+    stt.on_result(Arc::new(move |result| {
+        let result_tx = result_tx.clone();
+        Box::pin(async move {
+            let _ = result_tx.send(result);
+        }) as Pin<Box<dyn Future<Output = ()> + Send>>
+    }))
+    .await
+    .unwrap();
+    stt.connect().await.unwrap();
+    let uri = uris.recv().await.unwrap();
+
+    // Gateway VAD: speech, then end of speech
+    for _ in 0..SPEECH_MS / CHUNK_MS {
+        stt.send_audio(chunk(true).into()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(CHUNK_MS)).await;
+    }
+    let speech_ended = Instant::now();
+
+    // Keep streaming silence; the gateway ends the turn after its threshold
+    let mut committed = false;
+    let mut finalized = false;
+    while speech_ended.elapsed() < Duration::from_millis(MAX_SILENCE_MS) {
+        if manual_commit
+            && !finalized
+            && speech_ended.elapsed() >= Duration::from_millis(GATEWAY_SILENCE_MS)
+        {
+            assert!(stt.finalize().await.unwrap());
+            finalized = true;
+        }
+        stt.send_audio(chunk(false).into()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(CHUNK_MS)).await;
+
+        while let Ok(result) = results.try_recv() {
+            if result.is_speech_final {
+                assert_eq!(result.transcript, "book a table for four");
+                committed = true;
+            }
+        }
+        if committed {
+            break;
+        }
+    }
+    let latency = speech_ended.elapsed();
+    stt.disconnect().await.unwrap();
+
+    assert!(
+        committed,
+        "no committed transcript within {MAX_SILENCE_MS}ms"
+    );
+    (uri, latency)
+}
+
+#[tokio::test]
+async fn test_manual_commit_delivers_finals_sooner() {
+    let (vad_uri, vad_latency) = run_turn(false).await;
+    let (manual_uri, manual_latency) = run_turn(true).await;
+
+    assert!(vad_uri.contains("commit_strategy=vad"));
+    assert!(manual_uri.contains("commit_strategy=manual"));
+
+    println!("\n=== ElevenLabs final latency after end of speech ===");
+    println!("Provider VAD commit:           {:?}", vad_latency);
+    println!("Gateway-driven manual commit:  {:?}", manual_latency);
+    println!(
+        "Improvement:                   {:?}",
+        vad_latency.saturating_sub(manual_latency)
+    );
+
+    assert!(
+        manual_latency < vad_latency,
+        "manual commit ({manual_latency:?}) should beat provider VAD ({vad_latency:?})"
+    );
+}
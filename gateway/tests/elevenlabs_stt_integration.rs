@@ -36,6 +36,8 @@ fn test_create_elevenlabs_provider_by_name() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider("elevenlabs", config);
@@ -61,6 +63,8 @@ fn test_create_elevenlabs_provider_by_enum() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider_from_enum(STTProvider::ElevenLabs, config);
@@ -283,6 +287,8 @@ async fn test_real_connection() {
         encoding: "linear16".to_string(),
         model: "".to_string(),
         provider: "elevenlabs".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut stt = ElevenLabsSTT::new(config).unwrap();
@@ -339,6 +345,8 @@ async fn test_transcription_with_audio() {
         encoding: "linear16".to_string(),
         model: "".to_string(),
         provider: "elevenlabs".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut stt = ElevenLabsSTT::new(config).unwrap();
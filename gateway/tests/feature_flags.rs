@@ -0,0 +1,160 @@
+//! # Feature Flag Integration Tests
+//!
+//! Builds sessions on in-process mock providers with flags resolved from a
+//! [`FeatureFlagStore`]:
+//!
+//! 1. Enabled flags switch on adaptive endpointing and the echo guard with
+//!    their default settings, and the resolved flags are reported in the
+//!    session usage.
+//! 2. Settings the session configured itself win over the flag defaults.
+//! 3. A rollout resolves the same way for a client in every session, and a
+//!    flag update only affects sessions resolved afterwards.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test feature_flags
+//! ```
+
+mod fixtures;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Silent, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::config::{FeatureFlagConfig, FeatureFlags};
+use waav_gateway::core::session::{EchoGuardConfig, Session, SessionPipelineBuilder};
+use waav_gateway::core::voice_manager::AdaptiveEndpointingConfig;
+use waav_gateway::state::FeatureFlagStore;
+
+const MOCK_PROVIDER: &str = "feature-flags-mock";
+
+fn builder() -> SessionPipelineBuilder {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|_| Silent),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(mock_tts_config(MOCK_PROVIDER))
+        .ready_timeout(Duration::from_secs(5))
+}
+
+async fn build(builder: SessionPipelineBuilder) -> Session {
+    builder.build().await.expect("mock session should build")
+}
+
+fn adaptive_endpointing(session: &Session) -> Option<AdaptiveEndpointingConfig> {
+    session
+        .voice_manager()
+        .expect("voice session")
+        .get_config()
+        .adaptive_endpointing
+}
+
+fn enabled(names: &[&str]) -> BTreeMap<String, FeatureFlagConfig> {
+    names
+        .iter()
+        .map(|name| {
+            (
+                name.to_string(),
+                FeatureFlagConfig {
+                    default: true,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_flags_enable_components() {
+    let store = FeatureFlagStore::new(enabled(&["adaptive_endpointing", "echo_guard"]));
+    let flags = store.resolve("project-1");
+
+    let session = build(builder().feature_flags(flags.clone())).await;
+    assert_eq!(
+        adaptive_endpointing(&session),
+        Some(AdaptiveEndpointingConfig::default())
+    );
+    assert!(session.echo_guard_stats().is_some());
+    assert_eq!(session.usage().feature_flags, flags);
+
+    // Without flags the components stay off
+    let session = build(builder()).await;
+    assert_eq!(adaptive_endpointing(&session), None);
+    assert!(session.echo_guard_stats().is_none());
+    assert!(session.usage().feature_flags.is_empty());
+}
+
+#[tokio::test]
+async fn test_session_settings_win_over_flag_defaults() {
+    let flags = FeatureFlags::resolve(&enabled(&["adaptive_endpointing", "echo_guard"]), "p");
+    let endpointing = AdaptiveEndpointingConfig {
+        min_silence_ms: 200,
+        max_silence_ms: 1500,
+        ..Default::default()
+    };
+
+    let session = build(
+        builder()
+            .adaptive_endpointing(endpointing)
+            .echo_guard(EchoGuardConfig::default())
+            .feature_flags(flags),
+    )
+    .await;
+    assert_eq!(adaptive_endpointing(&session), Some(endpointing));
+    assert!(session.echo_guard_stats().is_some());
+
+    // A disabled flag does not turn off what the session configured
+    let disabled = FeatureFlags::resolve(&enabled(&[]), "p");
+    let session = build(
+        builder()
+            .adaptive_endpointing(endpointing)
+            .feature_flags(disabled),
+    )
+    .await;
+    assert_eq!(adaptive_endpointing(&session), Some(endpointing));
+}
+
+#[tokio::test]
+async fn test_rollout_is_stable_per_client() {
+    let store = FeatureFlagStore::new(BTreeMap::from([(
+        "echo_guard".to_string(),
+        FeatureFlagConfig {
+            rollout_percent: Some(50.0),
+            ..Default::default()
+        },
+    )]));
+
+    let clients: Vec<String> = (0..200).map(|i| format!("project-{i}")).collect();
+    let first: Vec<bool> = clients
+        .iter()
+        .map(|client| store.resolve(client).is_enabled("echo_guard"))
+        .collect();
+    let again: Vec<bool> = clients
+        .iter()
+        .map(|client| store.resolve(client).is_enabled("echo_guard"))
+        .collect();
+    assert_eq!(first, again);
+    assert!(first.contains(&true) && first.contains(&false));
+
+    // Sessions keep the flags they resolved when the store changes
+    let inside = clients[first.iter().position(|&on| on).unwrap()].clone();
+    let resolved = store.resolve(&inside);
+    store
+        .update(BTreeMap::from([(
+            "echo_guard".to_string(),
+            FeatureFlagConfig {
+                clients: BTreeMap::from([(inside.clone(), false)]),
+                ..Default::default()
+            },
+        )]))
+        .unwrap();
+    assert!(resolved.is_enabled("echo_guard"));
+    assert!(!store.resolve(&inside).is_enabled("echo_guard"));
+}
@@ -0,0 +1,337 @@
+//! # Filler Audio Integration Tests
+//!
+//! Builds sessions on the in-process providers of `fixtures::session_mocks`.
+//! The mock TTS delivers the audio of every utterance after a delay set by
+//! its voice id: silent PCM, so the filler faded into it can be measured. The
+//! filler asset is a WAV file of constant amplitude.
+//!
+//! 1. A slow reply plays filler first, cross-faded out into the real audio,
+//!    and only the reply reaches the transcript.
+//! 2. A fast reply plays no filler.
+//! 3. Interrupting stops the filler.
+//! 4. Filler audio that does not match the session output is rejected.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test filler_audio
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use fixtures::session_mocks::{
+    Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::realtime::TranscriptRole;
+use waav_gateway::core::session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSResult};
+use waav_gateway::core::voice_manager::FillerAudioConfig;
+use waav_gateway::plugin::PluginRegistry;
+
+const MOCK_PROVIDER: &str = "filler-audio-mock";
+const SAMPLE_RATE: u32 = 16000;
+
+/// Amplitude of every filler sample
+const FILLER_LEVEL: i16 = 1000;
+
+/// Samples in each chunk of real audio (100ms)
+const CHUNK_SAMPLES: usize = 1600;
+
+/// Real audio chunks per utterance
+const CHUNKS: usize = 3;
+
+/// Delay before the first audio of an utterance spoken with `voice_id`
+fn first_audio_delay(voice_id: &str) -> Duration {
+    match voice_id {
+        "slow" => Duration::from_millis(500),
+        "stalled" => Duration::from_secs(30),
+        _ => Duration::ZERO,
+    }
+}
+
+/// TTS behavior that delivers silent audio after a per-voice delay
+struct DelayedAudio {
+    voice_id: String,
+    pending: Vec<JoinHandle<()>>,
+}
+
+#[async_trait]
+impl TtsBehavior for DelayedAudio {
+    async fn speak(
+        &mut self,
+        _text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        let Some(callback) = audio.cloned() else {
+            return Ok(());
+        };
+        let delay = first_audio_delay(&self.voice_id);
+        self.pending.push(tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            for _ in 0..CHUNKS {
+                callback
+                    .on_audio(AudioData {
+                        data: vec![0; CHUNK_SAMPLES * 2],
+                        sample_rate: SAMPLE_RATE,
+                        format: "linear16".to_string(),
+                        duration_ms: Some(100),
+                    })
+                    .await;
+            }
+            callback.on_complete().await;
+        }));
+        Ok(())
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        for task in self.pending.drain(..) {
+            task.abort();
+        }
+        Ok(())
+    }
+}
+
+fn providers() -> Arc<PluginRegistry> {
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|config| DelayedAudio {
+            voice_id: config.voice_id.clone().unwrap_or_default(),
+            pending: Vec::new(),
+        }),
+    )
+}
+
+fn write_wav(path: &Path, sample_rate: u32, samples: &[i16]) {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for &sample in samples {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// Filler starting after 150ms and fading out over 50ms (800 samples)
+fn filler_config() -> FillerAudioConfig {
+    FillerAudioConfig {
+        threshold_ms: 150,
+        fade_out_ms: 50,
+        ..FillerAudioConfig::new("typing")
+    }
+}
+
+async fn build_session(
+    voice_id: &str,
+    assets_dir: &Path,
+    asset_sample_rate: u32,
+) -> Result<Session, SessionError> {
+    write_wav(
+        &assets_dir.join("typing.wav"),
+        asset_sample_rate,
+        &[FILLER_LEVEL; 4000],
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(providers())
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            voice_id: Some(voice_id.to_string()),
+            audio_format: Some("linear16".to_string()),
+            sample_rate: Some(SAMPLE_RATE),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .filler_audio(filler_config(), Some(assets_dir.to_path_buf()))
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+}
+
+/// Collect events until `predicate` matches, failing after a timeout
+async fn collect_until(
+    events: &mut SessionEventStream,
+    predicate: impl Fn(&SessionEvent) -> bool,
+) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            let done = predicate(&event);
+            collected.push(event);
+            if done {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("expected session event did not arrive");
+    collected
+}
+
+/// Events that arrive within `window`
+async fn collect_for(events: &mut SessionEventStream, window: Duration) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    let _ = tokio::time::timeout(window, async {
+        while let Some(event) = events.recv().await {
+            collected.push(event);
+        }
+    })
+    .await;
+    collected
+}
+
+fn samples(audio: &AudioData) -> Vec<i16> {
+    audio
+        .data
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect()
+}
+
+fn audio_chunks(events: &[SessionEvent]) -> Vec<Vec<i16>> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::Audio(audio) => Some(samples(audio)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_filler(chunk: &[i16]) -> bool {
+    chunk.iter().all(|&sample| sample == FILLER_LEVEL)
+}
+
+#[tokio::test]
+async fn test_slow_reply_cross_fades_filler_into_real_audio() {
+    let dir = tempfile::tempdir().unwrap();
+    let session = build_session("slow", dir.path(), SAMPLE_RATE)
+        .await
+        .unwrap();
+    let mut events = session.take_events().unwrap();
+
+    session.speak("One moment please.", true).await.unwrap();
+    let collected = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::SpeechComplete { .. })
+    })
+    .await;
+
+    // Filler plays before the reply starts, and never after
+    let started = collected
+        .iter()
+        .position(|event| matches!(event, SessionEvent::SpeechStarted { .. }))
+        .expect("the reply should start");
+    let filler = audio_chunks(&collected[..started]);
+    assert!(
+        filler.len() >= 5,
+        "expected ~350ms of 20ms filler chunks, got {}",
+        filler.len()
+    );
+    assert!(filler.iter().all(|chunk| is_filler(chunk)));
+
+    let real = audio_chunks(&collected[started..]);
+    assert_eq!(real.len(), CHUNKS);
+    assert!(real.iter().all(|chunk| chunk.len() == CHUNK_SAMPLES));
+
+    // The filler fades out over the first 800 samples of the silent reply
+    let fade = &real[0][..800];
+    assert_eq!(fade[0], FILLER_LEVEL);
+    assert!(fade.windows(2).all(|pair| pair[0] > pair[1]), "{fade:?}");
+    assert!(fade[799] > 0);
+    assert!(real[0][800..].iter().all(|&sample| sample == 0));
+    assert!(real[1..].iter().flatten().all(|&sample| sample == 0));
+
+    // Filler is not speech
+    let transcript = session.transcript().await;
+    assert_eq!(transcript.len(), 1);
+    assert_eq!(transcript[0].role, TranscriptRole::Assistant);
+    assert_eq!(transcript[0].text, "One moment please.");
+
+    let stats = session.filler_stats().unwrap();
+    assert_eq!(stats.played, 1);
+    assert_eq!(stats.crossfaded, 1);
+    assert!(stats.filler_ms >= 100);
+
+    assert!(
+        audio_chunks(&collect_for(&mut events, Duration::from_millis(300)).await).is_empty(),
+        "no audio should follow the reply"
+    );
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_fast_reply_plays_no_filler() {
+    let dir = tempfile::tempdir().unwrap();
+    let session = build_session("fast", dir.path(), SAMPLE_RATE)
+        .await
+        .unwrap();
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Sure.", true).await.unwrap();
+    let mut collected = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::SpeechComplete { .. })
+    })
+    .await;
+    collected.extend(collect_for(&mut events, Duration::from_millis(400)).await);
+
+    let chunks = audio_chunks(&collected);
+    assert_eq!(chunks.len(), CHUNKS);
+    assert!(chunks.iter().flatten().all(|&sample| sample == 0));
+    assert_eq!(session.filler_stats().unwrap().played, 0);
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_interrupt_stops_filler() {
+    let dir = tempfile::tempdir().unwrap();
+    let session = build_session("stalled", dir.path(), SAMPLE_RATE)
+        .await
+        .unwrap();
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Let me check.", true).await.unwrap();
+    collect_until(
+        &mut events,
+        |event| matches!(event, SessionEvent::Audio(audio) if is_filler(&samples(audio))),
+    )
+    .await;
+
+    assert!(session.interrupt().await.unwrap());
+    collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::AudioCleared)
+    })
+    .await;
+    assert!(
+        audio_chunks(&collect_for(&mut events, Duration::from_millis(300)).await).is_empty(),
+        "filler should stop once interrupted"
+    );
+
+    let stats = session.filler_stats().unwrap();
+    assert_eq!(stats.played, 1);
+    assert_eq!(stats.crossfaded, 0);
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_filler_at_wrong_sample_rate_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let result = build_session("fast", dir.path(), 8000).await;
+    assert!(
+        matches!(&result, Err(SessionError::InvalidConfig(message)) if message.contains("8000Hz")),
+        "unexpected result: {:?}",
+        result.err()
+    );
+}
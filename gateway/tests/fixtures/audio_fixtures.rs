@@ -17,6 +17,7 @@
 //! - Sine wave tones (various frequencies)
 //! - Speech-like patterns (variable amplitude)
 //! - Chirp signals (frequency sweep)
+//! - WAV files and MP3 frame streams
 
 use std::f32::consts::PI;
 
@@ -24,9 +25,9 @@ use std::f32::consts::PI;
 pub const SAMPLE_RATE: u32 = 16000;
 
 /// Duration constants (in samples at 16kHz)
-pub const MS_100: usize = 1600;   // 100ms at 16kHz
-pub const MS_500: usize = 8000;   // 500ms at 16kHz
-pub const SECOND: usize = 16000;  // 1 second at 16kHz
+pub const MS_100: usize = 1600; // 100ms at 16kHz
+pub const MS_500: usize = 8000; // 500ms at 16kHz
+pub const SECOND: usize = 16000; // 1 second at 16kHz
 
 /// Sample rate constant
 pub const SAMPLE_RATE_USIZE: usize = 16000;
@@ -114,7 +115,8 @@ pub fn generate_chirp(
             let t = i as f32 / SAMPLE_RATE as f32;
             // Linear chirp
             let freq = start_freq + (end_freq - start_freq) * t / duration_secs;
-            let phase = 2.0 * PI * (start_freq * t + 0.5 * (end_freq - start_freq) * t * t / duration_secs);
+            let phase =
+                2.0 * PI * (start_freq * t + 0.5 * (end_freq - start_freq) * t * t / duration_secs);
             let sample = phase.sin() * max_amplitude;
             sample as i16
         })
@@ -128,7 +130,12 @@ pub fn generate_chirp_bytes(
     end_freq: f32,
     amplitude: f32,
 ) -> Vec<u8> {
-    samples_to_bytes(&generate_chirp(duration_samples, start_freq, end_freq, amplitude))
+    samples_to_bytes(&generate_chirp(
+        duration_samples,
+        start_freq,
+        end_freq,
+        amplitude,
+    ))
 }
 
 /// Generate speech-like pattern with variable amplitude envelope
@@ -223,10 +230,7 @@ pub fn generate_noisy_speech_bytes(duration_samples: usize, snr_db: f32) -> Vec<
 
 /// Convert i16 samples to little-endian bytes
 pub fn samples_to_bytes(samples: &[i16]) -> Vec<u8> {
-    samples
-        .iter()
-        .flat_map(|s| s.to_le_bytes())
-        .collect()
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
 }
 
 /// Convert bytes to i16 samples
@@ -303,6 +307,19 @@ pub fn create_wav_file(samples: &[i16]) -> Vec<u8> {
     wav
 }
 
+/// MPEG-2 Layer III frame header: 64 kbit/s, 22.05kHz, no padding
+const MP3_22050_FRAME_HEADER: [u8; 4] = [0xFF, 0xF3, 0x80, 0x00];
+
+/// Length of a 64 kbit/s, 22.05kHz Layer III frame in bytes
+pub const MP3_22050_FRAME_BYTES: usize = 208;
+
+/// Create an MP3 stream of silent 22.05kHz frames (576 samples each)
+pub fn create_mp3_file(num_frames: usize) -> Vec<u8> {
+    let mut frame = vec![0u8; MP3_22050_FRAME_BYTES];
+    frame[..4].copy_from_slice(&MP3_22050_FRAME_HEADER);
+    frame.repeat(num_frames)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,7 +382,10 @@ mod tests {
         assert_eq!(speech.len(), SECOND * 2);
 
         // Should have some silence segments
-        let silence_count = speech.windows(100).filter(|w| w.iter().all(|&s| s == 0)).count();
+        let silence_count = speech
+            .windows(100)
+            .filter(|w| w.iter().all(|&s| s == 0))
+            .count();
         assert!(silence_count > 0);
     }
 
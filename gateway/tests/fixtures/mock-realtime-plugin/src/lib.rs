@@ -0,0 +1,332 @@
+//! Mock Realtime Plugin
+//!
+//! Realtime provider plugin used by `tests/realtime_plugin.rs` to drive the
+//! `/realtime` route end to end through the dynamic plugin loader. It makes
+//! no network calls; every request is answered from the plugin itself:
+//!
+//! - `send_text` replies with an assistant transcript prefixed by the
+//!   session's voice, so the test can check the config reached the plugin
+//! - `send_audio` echoes the audio from a separate thread, as a real plugin
+//!   with its own I/O threads would, followed by a user transcript
+//! - `create_response` requests a `lookup` tool call, and the tool result is
+//!   answered with an assistant transcript
+//! - `cancel_response` reports a connection error (code 1)
+//!
+//! The test builds this crate with cargo; it is not part of the gateway build.
+
+use abi_stable::{
+    export_root_module,
+    prefix_type::PrefixTypeTrait,
+    sabi_extern_fn,
+    std_types::{ROption, RResult, RString},
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use waav_plugin_api::{
+    ffi_err, ffi_ok, ErrorCallbackFn, FFIConfig, FFIRealtimeAudio, FFITranscriptResult,
+    PluginCapabilityType, PluginManifest, PluginModule, PluginModule_Ref, ProviderHandle,
+    RealtimeAudioCallbackFn, RealtimeProvider, RealtimeTranscriptCallbackFn, RealtimeVTable,
+    ToolCallCallbackFn, PLUGIN_API_VERSION,
+};
+
+/// Sample rate reported with echoed audio
+const OUTPUT_SAMPLE_RATE: u32 = 24000;
+
+/// Callback registered by the gateway, with its user data pointer
+struct Registered<F> {
+    callback: F,
+    user_data: *mut (),
+}
+
+// The gateway's callbacks may be invoked from any thread
+unsafe impl<F: Send> Send for Registered<F> {}
+
+/// Plugin state stored in the ProviderHandle
+#[derive(Default)]
+struct MockRealtimeState {
+    connected: AtomicBool,
+    /// Voice from the session config, echoed in transcripts
+    voice: String,
+    transcript_callback: Option<Registered<RealtimeTranscriptCallbackFn>>,
+    audio_callback: Option<Registered<RealtimeAudioCallbackFn>>,
+    error_callback: Option<Registered<ErrorCallbackFn>>,
+    tool_call_callback: Option<Registered<ToolCallCallbackFn>>,
+}
+
+impl MockRealtimeState {
+    fn emit_transcript(&self, text: &str, role: &str) {
+        if let Some(registered) = &self.transcript_callback {
+            let result = FFITranscriptResult::new(text, role, true);
+            (registered.callback.func)(&result, registered.user_data);
+        }
+    }
+}
+
+/// Run `f` on the provider state behind `handle`
+fn with_state<R>(
+    handle: *mut ProviderHandle,
+    default: R,
+    f: impl FnOnce(&mut MockRealtimeState) -> R,
+) -> R {
+    if handle.is_null() {
+        return default;
+    }
+    unsafe {
+        let handle = &mut *handle;
+        if handle.is_null() {
+            return default;
+        }
+        f(handle.as_mut::<MockRealtimeState>())
+    }
+}
+
+extern "C" fn mock_connect(handle: *mut ProviderHandle) -> RResult<(), RString> {
+    with_state(handle, ffi_err("Null handle"), |state| {
+        state.connected.store(true, Ordering::SeqCst);
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_disconnect(handle: *mut ProviderHandle) -> RResult<(), RString> {
+    with_state(handle, ffi_err("Null handle"), |state| {
+        state.connected.store(false, Ordering::SeqCst);
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_is_ready(handle: *const ProviderHandle) -> bool {
+    if handle.is_null() {
+        return false;
+    }
+    unsafe {
+        let handle = &*handle;
+        !handle.is_null()
+            && handle
+                .as_ref::<MockRealtimeState>()
+                .connected
+                .load(Ordering::SeqCst)
+    }
+}
+
+extern "C" fn mock_send_audio(
+    handle: *mut ProviderHandle,
+    audio_data: *const u8,
+    audio_len: usize,
+) -> RResult<(), RString> {
+    if audio_data.is_null() {
+        return ffi_err("Null audio data");
+    }
+    let audio = unsafe { std::slice::from_raw_parts(audio_data, audio_len) }.to_vec();
+
+    with_state(handle, ffi_err("Null handle"), |state| {
+        if let Some(registered) = &state.audio_callback {
+            let registered = Registered {
+                callback: registered.callback,
+                user_data: registered.user_data,
+            };
+            // Deliver the echo from a thread the gateway does not own
+            let echo = std::thread::spawn(move || {
+                let registered = registered;
+                let audio = FFIRealtimeAudio::new(audio, OUTPUT_SAMPLE_RATE);
+                (registered.callback.func)(&audio, registered.user_data);
+            });
+            if echo.join().is_err() {
+                return ffi_err("Audio echo thread panicked");
+            }
+        }
+        state.emit_transcript(&format!("received {audio_len} bytes"), "user");
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_send_text(
+    handle: *mut ProviderHandle,
+    text: *const RString,
+) -> RResult<(), RString> {
+    if text.is_null() {
+        return ffi_err("Null text");
+    }
+    let text = unsafe { &*text }.to_string();
+
+    with_state(handle, ffi_err("Null handle"), |state| {
+        state.emit_transcript(&format!("[{}] {}", state.voice, text), "assistant");
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_create_response(handle: *mut ProviderHandle) -> RResult<(), RString> {
+    with_state(handle, ffi_err("Null handle"), |state| {
+        if let Some(registered) = &state.tool_call_callback {
+            let call: RString =
+                r#"{"call_id": "call-1", "name": "lookup", "arguments": "{\"query\": \"weather\"}"}"#
+                    .into();
+            (registered.callback.func)(&call, registered.user_data);
+        }
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_cancel_response(handle: *mut ProviderHandle) -> RResult<(), RString> {
+    with_state(handle, ffi_err("Null handle"), |state| {
+        if let Some(registered) = &state.error_callback {
+            let message: RString = "mock connection dropped".into();
+            (registered.callback.func)(1, &message, registered.user_data);
+        }
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_set_transcript_callback(
+    handle: *mut ProviderHandle,
+    callback: RealtimeTranscriptCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.transcript_callback = Some(Registered {
+            callback,
+            user_data,
+        });
+    })
+}
+
+extern "C" fn mock_set_audio_callback(
+    handle: *mut ProviderHandle,
+    callback: RealtimeAudioCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.audio_callback = Some(Registered {
+            callback,
+            user_data,
+        });
+    })
+}
+
+extern "C" fn mock_set_error_callback(
+    handle: *mut ProviderHandle,
+    callback: ErrorCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.error_callback = Some(Registered {
+            callback,
+            user_data,
+        });
+    })
+}
+
+extern "C" fn mock_set_tool_call_callback(
+    handle: *mut ProviderHandle,
+    callback: ToolCallCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.tool_call_callback = Some(Registered {
+            callback,
+            user_data,
+        });
+    })
+}
+
+extern "C" fn mock_send_tool_response(
+    handle: *mut ProviderHandle,
+    response_json: *const RString,
+) -> RResult<(), RString> {
+    if response_json.is_null() {
+        return ffi_err("Null tool response");
+    }
+    let response: serde_json::Value =
+        match serde_json::from_str(unsafe { &*response_json }.as_str()) {
+            Ok(response) => response,
+            Err(e) => return ffi_err(format!("Invalid tool response: {e}")),
+        };
+    let call_id = response["call_id"].as_str().unwrap_or_default();
+    let result = response["result"].as_str().unwrap_or_default();
+
+    with_state(handle, ffi_err("Null handle"), |state| {
+        state.emit_transcript(&format!("tool {call_id}: {result}"), "assistant");
+        ffi_ok()
+    })
+}
+
+extern "C" fn mock_get_provider_info(_handle: *const ProviderHandle) -> RString {
+    r#"{"provider": "mock-realtime", "version": "1.0.0", "type": "dynamic"}"#.into()
+}
+
+const MOCK_REALTIME_VTABLE: RealtimeVTable = RealtimeVTable {
+    connect: mock_connect,
+    disconnect: mock_disconnect,
+    is_ready: mock_is_ready,
+    send_audio: mock_send_audio,
+    send_text: mock_send_text,
+    create_response: mock_create_response,
+    cancel_response: mock_cancel_response,
+    set_transcript_callback: mock_set_transcript_callback,
+    set_audio_callback: mock_set_audio_callback,
+    set_error_callback: mock_set_error_callback,
+    set_tool_call_callback: mock_set_tool_call_callback,
+    send_tool_response: mock_send_tool_response,
+    get_provider_info: mock_get_provider_info,
+};
+
+/// Factory function to create a Realtime provider
+///
+/// The config is the gateway's `RealtimeConfig` serialized as JSON.
+#[sabi_extern_fn]
+fn create_realtime(config: *const FFIConfig) -> RResult<RealtimeProvider, RString> {
+    if config.is_null() {
+        return RResult::RErr("Null config".into());
+    }
+    let config: serde_json::Value = match serde_json::from_str(unsafe { &*config }.as_str()) {
+        Ok(config) => config,
+        Err(e) => return RResult::RErr(format!("Invalid config: {e}").into()),
+    };
+    if config["provider"] != "mock-realtime" {
+        return RResult::RErr(format!("Unexpected provider: {}", config["provider"]).into());
+    }
+
+    let state = MockRealtimeState {
+        voice: config["voice"].as_str().unwrap_or("default").to_string(),
+        ..Default::default()
+    };
+
+    RResult::ROk(RealtimeProvider {
+        handle: ProviderHandle::new(state),
+        vtable: MOCK_REALTIME_VTABLE,
+    })
+}
+
+/// Return the plugin manifest
+#[sabi_extern_fn]
+fn get_manifest() -> PluginManifest {
+    PluginManifest::new("mock-realtime", "Mock Realtime Plugin", "1.0.0")
+        .with_gateway_version(">=1.0.0")
+        .with_capability(PluginCapabilityType::Realtime)
+        .with_description("Answers realtime sessions locally for gateway tests")
+}
+
+#[sabi_extern_fn]
+fn init(_config: *const FFIConfig) -> RResult<(), RString> {
+    ffi_ok()
+}
+
+#[sabi_extern_fn]
+fn shutdown() -> RResult<(), RString> {
+    ffi_ok()
+}
+
+/// Export the root module that the gateway will load
+#[export_root_module]
+fn get_root_module() -> PluginModule_Ref {
+    PluginModule {
+        manifest: get_manifest,
+        init,
+        shutdown,
+        create_stt: ROption::RNone,
+        create_tts: ROption::RNone,
+        create_realtime: ROption::RSome(create_realtime),
+        abi_version: PLUGIN_API_VERSION,
+        provider_capabilities: ROption::RNone,
+        handle_http: ROption::RNone,
+    }
+    .leak_into_prefix()
+}
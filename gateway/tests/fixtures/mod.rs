@@ -2,6 +2,7 @@
 //!
 //! This module provides test fixtures for WaaV Gateway testing:
 //! - Audio fixtures (programmatically generated)
+//! - In-process STT and TTS providers for session tests
 //! - Configuration fixtures
 //! - Message fixtures
 
@@ -9,5 +10,6 @@
 #![allow(dead_code)]
 
 pub mod audio_fixtures;
+pub mod session_mocks;
 
 pub use audio_fixtures::*;
@@ -0,0 +1,513 @@
+//! In-Process Session Providers
+//!
+//! Mock STT and TTS providers for tests that run a `Session` without a
+//! network. The provider plumbing (connection state, callback registration,
+//! config handling) lives here; what a provider does with audio and text is
+//! a behavior the test passes in:
+//!
+//! - [`SttBehavior`] decides how a mock STT connects and answers audio.
+//! - [`TtsBehavior`] decides how a mock TTS connects, speaks and clears.
+//!
+//! Each provider instance gets a fresh behavior from the closure given to
+//! [`stt_factory`] or [`tts_factory`], so per-connection state (an utterance
+//! in progress, a voice id) belongs in the behavior and cross-connection
+//! state (connect counters, recorded calls) in the test.
+//!
+//! Ready-made behaviors:
+//! - [`Silent`] - connects, accepts everything, never answers
+//! - [`PerBurst`] - transcribes each burst of non-silent audio
+//! - [`UntilSilence`] - transcribes the audio received when silence arrives
+//! - [`Clip`] - answers every utterance with one fixed chunk of audio
+//! - [`EchoText`] - answers every utterance with its text as the audio payload
+//!
+//! Providers are registered in a [`PluginRegistry::new_isolated`] registry,
+//! so tests running in parallel never see each other's mocks.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::sync::Arc;
+
+use waav_gateway::core::stt::{
+    BaseSTT, STTConfig, STTError, STTErrorCallback, STTResult, STTResultCallback, STTVadCallback,
+    STTVadEvent,
+};
+use waav_gateway::core::tts::{
+    AudioCallback, AudioData, BaseTTS, ConnectionState, TTSConfig, TTSResult,
+};
+use waav_gateway::plugin::registry::{STTFactoryFn, TTSFactoryFn};
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+/// API key the mock configs carry; the mocks never check it
+pub const MOCK_API_KEY: &str = "test-key";
+
+/// Callbacks the session registered on a mock STT
+#[derive(Clone, Default)]
+pub struct SttCallbacks {
+    pub result: Option<STTResultCallback>,
+    pub vad: Option<STTVadCallback>,
+}
+
+impl SttCallbacks {
+    /// Report a transcript, if the session registered for results
+    pub async fn result(&self, result: STTResult) {
+        if let Some(callback) = &self.result {
+            callback(result).await;
+        }
+    }
+
+    /// Report a voice activity event, if the session registered for them
+    pub async fn vad(&self, event: STTVadEvent) {
+        if let Some(callback) = &self.vad {
+            callback(event).await;
+        }
+    }
+}
+
+/// What a mock STT does with its connection and the audio it receives
+#[async_trait]
+pub trait SttBehavior: Send + Sync + 'static {
+    /// Called for every `connect()`, including reconnects
+    async fn connect(&mut self) -> Result<(), STTError> {
+        Ok(())
+    }
+
+    /// Called for every audio chunk the session sends
+    async fn send_audio(&mut self, audio: Bytes, callbacks: &SttCallbacks) -> Result<(), STTError> {
+        let _ = (audio, callbacks);
+        Ok(())
+    }
+
+    /// Whether the provider reports VAD events
+    fn reports_vad(&self) -> bool {
+        false
+    }
+}
+
+/// What a mock TTS does with its connection and the text it receives
+#[async_trait]
+pub trait TtsBehavior: Send + Sync + 'static {
+    /// Called for every `connect()`, including reconnects
+    async fn connect(&mut self) -> TTSResult<()> {
+        Ok(())
+    }
+
+    /// Called for every `speak()`, with the session's audio callback if one is registered
+    async fn speak(
+        &mut self,
+        text: &str,
+        flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        let _ = (text, flush, audio);
+        Ok(())
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        Ok(())
+    }
+
+    async fn flush(&self) -> TTSResult<()> {
+        Ok(())
+    }
+
+    async fn validate_voice(&self) -> TTSResult<()> {
+        Ok(())
+    }
+
+    /// Called when the session registers its audio callback
+    fn on_audio(&mut self, callback: &Arc<dyn AudioCallback>) {
+        let _ = callback;
+    }
+}
+
+/// Provider that connects, accepts everything and never answers
+pub struct Silent;
+
+impl SttBehavior for Silent {}
+
+impl TtsBehavior for Silent {}
+
+/// STT behavior that answers the first chunk of each burst of non-silent audio
+///
+/// `transcribe` gets the 1-based burst number and the burst's first chunk and
+/// returns the results to report for it.
+pub struct PerBurst<F> {
+    transcribe: F,
+    in_speech: bool,
+    bursts: usize,
+}
+
+impl<F> PerBurst<F>
+where
+    F: FnMut(usize, &Bytes) -> Vec<STTResult> + Send + Sync + 'static,
+{
+    pub fn new(transcribe: F) -> Self {
+        Self {
+            transcribe,
+            in_speech: false,
+            bursts: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl<F> SttBehavior for PerBurst<F>
+where
+    F: FnMut(usize, &Bytes) -> Vec<STTResult> + Send + Sync + 'static,
+{
+    async fn send_audio(&mut self, audio: Bytes, callbacks: &SttCallbacks) -> Result<(), STTError> {
+        let voiced = audio.iter().any(|&byte| byte != 0);
+        if voiced && !self.in_speech {
+            self.in_speech = true;
+            self.bursts += 1;
+            for result in (self.transcribe)(self.bursts, &audio) {
+                callbacks.result(result).await;
+            }
+        } else if !voiced {
+            self.in_speech = false;
+        }
+        Ok(())
+    }
+}
+
+/// STT behavior that buffers non-silent audio and reports one final
+/// transcript of it once a chunk of silence arrives
+///
+/// `transcribe` turns the buffered audio into the transcript.
+pub struct UntilSilence<F> {
+    transcribe: F,
+    audio: Vec<u8>,
+}
+
+impl<F> UntilSilence<F>
+where
+    F: Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    pub fn new(transcribe: F) -> Self {
+        Self {
+            transcribe,
+            audio: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<F> SttBehavior for UntilSilence<F>
+where
+    F: Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    async fn send_audio(&mut self, audio: Bytes, callbacks: &SttCallbacks) -> Result<(), STTError> {
+        if audio.iter().any(|&byte| byte != 0) {
+            self.audio.extend_from_slice(&audio);
+            return Ok(());
+        }
+        if self.audio.is_empty() {
+            return Ok(());
+        }
+
+        let transcript = (self.transcribe)(&self.audio);
+        self.audio.clear();
+        callbacks
+            .result(STTResult::new(transcript, true, true, 0.9))
+            .await;
+        Ok(())
+    }
+}
+
+/// TTS behavior that answers every utterance with one chunk of PCM
+#[derive(Clone)]
+pub struct Clip {
+    pub data: Vec<u8>,
+    pub sample_rate: u32,
+    pub duration_ms: Option<u32>,
+}
+
+impl Clip {
+    /// `duration_ms` of silence at `sample_rate`, as 16-bit PCM
+    pub fn silence(sample_rate: u32, duration_ms: u32) -> Self {
+        let bytes = sample_rate as usize * duration_ms as usize / 1000 * 2;
+        Self {
+            data: vec![0; bytes],
+            sample_rate,
+            duration_ms: Some(duration_ms),
+        }
+    }
+
+    /// Send the clip and complete the utterance
+    pub async fn play(&self, callback: &Arc<dyn AudioCallback>) {
+        callback
+            .on_audio(AudioData {
+                data: self.data.clone(),
+                sample_rate: self.sample_rate,
+                format: "linear16".to_string(),
+                duration_ms: self.duration_ms,
+            })
+            .await;
+        callback.on_complete().await;
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for Clip {
+    async fn speak(
+        &mut self,
+        _text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        if let Some(callback) = audio {
+            self.play(callback).await;
+        }
+        Ok(())
+    }
+}
+
+/// TTS behavior that returns the spoken text as the audio payload
+#[derive(Clone, Copy)]
+pub struct EchoText {
+    pub sample_rate: u32,
+    pub duration_ms: Option<u32>,
+}
+
+impl Default for EchoText {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            duration_ms: Some(100),
+        }
+    }
+}
+
+impl EchoText {
+    /// Send `text` as audio and complete the utterance
+    pub async fn play(&self, text: &str, callback: &Arc<dyn AudioCallback>) {
+        callback
+            .on_audio(AudioData {
+                data: text.as_bytes().to_vec(),
+                sample_rate: self.sample_rate,
+                format: "linear16".to_string(),
+                duration_ms: self.duration_ms,
+            })
+            .await;
+        callback.on_complete().await;
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for EchoText {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        if let Some(callback) = audio {
+            self.play(text, callback).await;
+        }
+        Ok(())
+    }
+}
+
+/// STT provider that delegates to an [`SttBehavior`]
+pub struct MockSTT {
+    config: STTConfig,
+    connected: bool,
+    callbacks: SttCallbacks,
+    behavior: Box<dyn SttBehavior>,
+}
+
+impl MockSTT {
+    pub fn with_behavior(config: STTConfig, behavior: impl SttBehavior) -> Self {
+        Self {
+            config,
+            connected: false,
+            callbacks: SttCallbacks::default(),
+            behavior: Box::new(behavior),
+        }
+    }
+}
+
+#[async_trait]
+impl BaseSTT for MockSTT {
+    fn new(config: STTConfig) -> Result<Self, STTError> {
+        Ok(Self::with_behavior(config, Silent))
+    }
+
+    async fn connect(&mut self) -> Result<(), STTError> {
+        self.behavior.connect().await?;
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), STTError> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.connected
+    }
+
+    async fn send_audio(&mut self, audio_data: Bytes) -> Result<(), STTError> {
+        self.behavior.send_audio(audio_data, &self.callbacks).await
+    }
+
+    async fn on_result(&mut self, callback: STTResultCallback) -> Result<(), STTError> {
+        self.callbacks.result = Some(callback);
+        Ok(())
+    }
+
+    async fn on_error(&mut self, _callback: STTErrorCallback) -> Result<(), STTError> {
+        Ok(())
+    }
+
+    async fn on_vad_event(&mut self, callback: STTVadCallback) -> Result<bool, STTError> {
+        if !self.behavior.reports_vad() {
+            return Ok(false);
+        }
+        self.callbacks.vad = Some(callback);
+        Ok(true)
+    }
+
+    fn get_config(&self) -> Option<&STTConfig> {
+        Some(&self.config)
+    }
+
+    async fn update_config(&mut self, config: STTConfig) -> Result<(), STTError> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn get_provider_info(&self) -> &'static str {
+        "Mock STT"
+    }
+}
+
+/// TTS provider that delegates to a [`TtsBehavior`]
+pub struct MockTTS {
+    provider: String,
+    state: ConnectionState,
+    callback: Option<Arc<dyn AudioCallback>>,
+    behavior: Box<dyn TtsBehavior>,
+}
+
+impl MockTTS {
+    pub fn with_behavior(config: TTSConfig, behavior: impl TtsBehavior) -> Self {
+        Self {
+            provider: config.provider,
+            state: ConnectionState::Disconnected,
+            callback: None,
+            behavior: Box::new(behavior),
+        }
+    }
+}
+
+#[async_trait]
+impl BaseTTS for MockTTS {
+    fn new(config: TTSConfig) -> TTSResult<Self> {
+        Ok(Self::with_behavior(config, Silent))
+    }
+
+    async fn connect(&mut self) -> TTSResult<()> {
+        self.behavior.connect().await?;
+        self.state = ConnectionState::Connected;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> TTSResult<()> {
+        self.state = ConnectionState::Disconnected;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        matches!(self.state, ConnectionState::Connected)
+    }
+
+    fn get_connection_state(&self) -> ConnectionState {
+        self.state.clone()
+    }
+
+    async fn speak(&mut self, text: &str, flush: bool) -> TTSResult<()> {
+        self.behavior
+            .speak(text, flush, self.callback.as_ref())
+            .await
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        self.behavior.clear().await
+    }
+
+    async fn flush(&self) -> TTSResult<()> {
+        self.behavior.flush().await
+    }
+
+    fn on_audio(&mut self, callback: Arc<dyn AudioCallback>) -> TTSResult<()> {
+        self.behavior.on_audio(&callback);
+        self.callback = Some(callback);
+        Ok(())
+    }
+
+    fn remove_audio_callback(&mut self) -> TTSResult<()> {
+        self.callback = None;
+        Ok(())
+    }
+
+    fn get_provider_info(&self) -> serde_json::Value {
+        serde_json::json!({ "provider": self.provider })
+    }
+
+    async fn validate_voice(&self) -> TTSResult<()> {
+        self.behavior.validate_voice().await
+    }
+}
+
+/// Factory for a mock STT whose behavior `behavior` creates per instance
+pub fn stt_factory<B, F>(behavior: F) -> STTFactoryFn
+where
+    B: SttBehavior,
+    F: Fn(&STTConfig) -> B + Send + Sync + 'static,
+{
+    Arc::new(move |config| {
+        let behavior = behavior(&config);
+        Ok(Box::new(MockSTT::with_behavior(config, behavior)) as Box<dyn BaseSTT>)
+    })
+}
+
+/// Factory for a mock TTS whose behavior `behavior` creates per instance
+pub fn tts_factory<B, F>(behavior: F) -> TTSFactoryFn
+where
+    B: TtsBehavior,
+    F: Fn(&TTSConfig) -> B + Send + Sync + 'static,
+{
+    Arc::new(move |config| {
+        let behavior = behavior(&config);
+        Ok(Box::new(MockTTS::with_behavior(config, behavior)) as Box<dyn BaseTTS>)
+    })
+}
+
+/// Isolated registry with one mock STT and one mock TTS, both named `provider`
+pub fn mock_registry(provider: &str, stt: STTFactoryFn, tts: TTSFactoryFn) -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(provider, stt, ProviderMetadata::stt(provider, "Mock STT"));
+    registry.register_tts(provider, tts, ProviderMetadata::tts(provider, "Mock TTS"));
+    registry
+}
+
+/// STT config for the mock named `provider`
+pub fn mock_stt_config(provider: &str) -> STTConfig {
+    STTConfig {
+        provider: provider.to_string(),
+        api_key: MOCK_API_KEY.to_string(),
+        ..Default::default()
+    }
+}
+
+/// TTS config for the mock named `provider`
+pub fn mock_tts_config(provider: &str) -> TTSConfig {
+    TTSConfig {
+        provider: provider.to_string(),
+        api_key: MOCK_API_KEY.to_string(),
+        voice_id: Some("mock-voice".to_string()),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,320 @@
+//! # Session Flight Recorder Integration Tests
+//!
+//! Scripts the same session on `/ws` twice against mock providers, with the
+//! flight recorder on, and replays the flight log each run left in the
+//! cache. Both runs must record the same entries, and rendering a log must
+//! give the same timeline every time.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test flight_recorder
+//! ```
+
+mod fixtures;
+
+use axum::middleware;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+use fixtures::session_mocks::{Clip, Silent, mock_registry, stt_factory, tts_factory};
+use waav_gateway::config::FlightRecorderConfig;
+use waav_gateway::flight_recorder::{
+    EntryKind, ParsedFlightLog, RenderOptions, SCRUBBED, parse_flight_log, render,
+};
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::{
+    ServerConfig, config::PluginConfig, middleware::auth::auth_middleware, routes, state::AppState,
+};
+
+const MOCK_PROVIDER: &str = "flight-recorder-mock";
+
+const STREAM_ID: &str = "flight-recorder-call";
+
+/// 100ms of 16kHz PCM16 speech
+const VOICED: [u8; 3200] = [0x40; 3200];
+
+/// Providers whose TTS speaks every utterance as a chunk of non-silent audio
+fn providers() -> Arc<PluginRegistry> {
+    let speech = Clip {
+        data: vec![0x20; 4800],
+        sample_rate: 24000,
+        duration_ms: Some(100),
+    };
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(move |_| speech.clone()),
+    )
+}
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: Some(FlightRecorderConfig {
+            scrub_transcripts: true,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Serve `/ws` on a local port, `None` if binding is not allowed
+async fn start_gateway() -> Option<(SocketAddr, Arc<AppState>)> {
+    let app_state = AppState::with_plugin_registry(test_config(), providers()).await;
+    let app = routes::ws::create_ws_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .with_state(app_state.clone());
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping flight recorder test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind flight recorder test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    Some((addr, app_state))
+}
+
+/// Next JSON message of type `kind`; other messages and audio are skipped
+async fn next_of<S>(stream: &mut S, kind: &str) -> Value
+where
+    S: futures::Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(message) = stream.next().await {
+            if let Message::Text(text) = message.unwrap() {
+                let value: Value = serde_json::from_str(&text).unwrap();
+                if value["type"] == kind {
+                    return value;
+                }
+            }
+        }
+        panic!("connection closed");
+    })
+    .await
+    .unwrap_or_else(|_| panic!("no {kind} message"))
+}
+
+/// Script a session and return the flight log it left in the cache
+async fn scripted_session(addr: SocketAddr, app_state: &AppState) -> ParsedFlightLog {
+    let (ws, _) = connect_async(format!("ws://{addr}/ws")).await.unwrap();
+    let (mut sink, mut stream) = ws.split();
+
+    let config = json!({
+        "type": "config",
+        "stream_id": STREAM_ID,
+        "audio": true,
+        "flight_recorder": true,
+        "stt_config": {
+            "provider": MOCK_PROVIDER,
+            "api_key": "test-key",
+            "language": "en-US",
+            "sample_rate": 16000,
+            "channels": 1,
+            "punctuation": true,
+            "encoding": "linear16",
+            "model": "mock",
+        },
+        "tts_config": {
+            "provider": MOCK_PROVIDER,
+            "api_key": "test-key",
+            "model": "mock",
+            "audio_format": "linear16",
+        },
+    });
+    sink.send(Message::Text(config.to_string().into()))
+        .await
+        .unwrap();
+    next_of(&mut stream, "ready").await;
+
+    sink.send(Message::Binary(Bytes::from_static(&VOICED)))
+        .await
+        .unwrap();
+    let speak = json!({"type": "speak", "text": "Your card ends in 4242"});
+    sink.send(Message::Text(speak.to_string().into()))
+        .await
+        .unwrap();
+    next_of(&mut stream, "tts_playback_complete").await;
+    sink.send(Message::Close(None)).await.unwrap();
+
+    // The final log is stored once the connection is torn down
+    let recorder = app_state.flight_recorder.as_ref().unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(contents) = recorder.load(STREAM_ID).await.unwrap() {
+                let log = parse_flight_log(std::str::from_utf8(&contents).unwrap()).unwrap();
+                if log
+                    .entries
+                    .last()
+                    .is_some_and(|entry| entry.kind == EntryKind::Close)
+                {
+                    return log;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("flight log was not stored")
+}
+
+/// Kind and message type of each entry
+fn skeleton(log: &ParsedFlightLog) -> Vec<(EntryKind, Option<String>)> {
+    log.entries
+        .iter()
+        .map(|entry| {
+            let message_type = entry.data["type"].as_str().map(str::to_string);
+            (entry.kind, message_type)
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_scripted_session_replays_identically() {
+    let Some((addr, app_state)) = start_gateway().await else {
+        return;
+    };
+
+    let first = scripted_session(addr, &app_state).await;
+    let second = scripted_session(addr, &app_state).await;
+    assert_eq!(first.header.session_id, STREAM_ID);
+    assert_eq!(skeleton(&first), skeleton(&second));
+
+    let entries = skeleton(&first);
+    assert_eq!(
+        entries[..3],
+        [
+            (EntryKind::Inbound, Some("config".to_string())),
+            (EntryKind::Config, None),
+            (EntryKind::Outbound, Some("ready".to_string())),
+        ]
+    );
+    assert!(entries.contains(&(EntryKind::Inbound, Some("speak".to_string()))));
+    assert!(entries.contains(&(
+        EntryKind::Outbound,
+        Some("tts_playback_complete".to_string())
+    )));
+    assert_eq!(entries.last().unwrap().0, EntryKind::Close);
+
+    // Secrets are redacted and, with scrub_transcripts, what was said too
+    let config = &first.entries[0].data;
+    assert_eq!(config["stt_config"]["api_key"], "[redacted]");
+    assert_eq!(config["stt_config"]["provider"], MOCK_PROVIDER);
+    let speak = first
+        .entries
+        .iter()
+        .find(|entry| entry.data["type"] == "speak")
+        .unwrap();
+    assert_eq!(speak.data["text"], SCRUBBED);
+
+    // Rendering a log gives the same timeline every time
+    for log in [&first, &second] {
+        let timeline = render(log, RenderOptions::default());
+        assert_eq!(timeline, render(log, RenderOptions::default()));
+        assert_eq!(timeline.lines().count(), log.entries.len() + 1);
+
+        let frames = render(log, RenderOptions { frames: true });
+        let frames: Vec<Value> = frames
+            .lines()
+            .map(|frame| serde_json::from_str(frame).unwrap())
+            .collect();
+        assert_eq!(frames[0]["type"], "ready");
+        assert_eq!(frames[0]["stream_id"], STREAM_ID);
+    }
+}
@@ -117,6 +117,8 @@ fn test_gnani_stt_factory_creation() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider("gnani", config);
@@ -142,6 +144,8 @@ fn test_gnani_stt_factory_aliases() {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider(alias, config);
@@ -164,6 +168,8 @@ fn test_gnani_stt_factory_case_insensitive() {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider(variant, config);
@@ -190,7 +196,12 @@ fn test_gnani_tts_factory_creation() {
         request_timeout: Some(60),
         pronunciations: Vec::new(),
         request_pool_size: Some(4),
+        utterance_timeout: Some(10),
         emotion_config: None,
+        custom_headers: Default::default(),
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
     };
 
     let result = create_tts_provider("gnani", config);
@@ -215,7 +226,12 @@ fn test_gnani_tts_factory_aliases() {
             request_timeout: Some(60),
             pronunciations: Vec::new(),
             request_pool_size: Some(4),
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         };
 
         let result = create_tts_provider(alias, config);
@@ -239,6 +255,8 @@ fn test_gnani_stt_not_ready_before_connect() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let provider = create_stt_provider("gnani", config).unwrap();
@@ -257,6 +275,8 @@ fn test_gnani_stt_provider_info_content() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let provider = create_stt_provider("gnani", config).unwrap();
@@ -279,6 +299,8 @@ async fn test_gnani_stt_send_audio_fails_not_connected() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut provider = create_stt_provider("gnani", config).unwrap();
@@ -308,6 +330,8 @@ async fn test_gnani_stt_disconnect_not_connected() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut provider = create_stt_provider("gnani", config).unwrap();
@@ -336,7 +360,12 @@ fn test_gnani_tts_not_connected_initially() {
         request_timeout: Some(60),
         pronunciations: Vec::new(),
         request_pool_size: Some(4),
+        utterance_timeout: Some(10),
         emotion_config: None,
+        custom_headers: Default::default(),
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
     };
 
     let provider = create_tts_provider("gnani", config).unwrap();
@@ -359,7 +388,12 @@ async fn test_gnani_tts_speak_fails_not_connected() {
         request_timeout: Some(60),
         pronunciations: Vec::new(),
         request_pool_size: Some(4),
+        utterance_timeout: Some(10),
         emotion_config: None,
+        custom_headers: Default::default(),
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
     };
 
     let mut provider = create_tts_provider("gnani", config).unwrap();
@@ -391,6 +425,8 @@ fn test_gnani_stt_all_languages() {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("gnani", config);
@@ -417,7 +453,12 @@ fn test_gnani_tts_all_languages() {
             request_timeout: Some(60),
             pronunciations: Vec::new(),
             request_pool_size: Some(4),
+            utterance_timeout: Some(10),
             emotion_config: None,
+            custom_headers: Default::default(),
+            output_profile: None,
+            context_hints: None,
+            visemes: None,
         };
 
         let result = create_tts_provider("gnani", config);
@@ -444,6 +485,8 @@ fn test_gnani_stt_audio_encodings() {
             punctuation: true,
             encoding: encoding.to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("gnani", config);
@@ -466,6 +509,8 @@ fn test_gnani_stt_sample_rates() {
             punctuation: true,
             encoding: "pcm16".to_string(),
             model: "default".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let result = create_stt_provider("gnani", config);
@@ -480,9 +525,9 @@ fn test_gnani_stt_sample_rates() {
 /// Test that Gnani is registered in the plugin registry.
 #[test]
 fn test_gnani_registered_in_plugin_registry() {
-    use waav_gateway::plugin::global_registry;
+    use waav_gateway::plugin::PluginRegistry;
 
-    let registry = global_registry();
+    let registry = PluginRegistry::new_isolated();
 
     // Check STT registration
     assert!(registry.has_stt_provider("gnani"), "Gnani STT should be registered");
@@ -497,9 +542,9 @@ fn test_gnani_registered_in_plugin_registry() {
 /// Test Gnani metadata is available.
 #[test]
 fn test_gnani_metadata_available() {
-    use waav_gateway::plugin::global_registry;
+    use waav_gateway::plugin::PluginRegistry;
 
-    let registry = global_registry();
+    let registry = PluginRegistry::new_isolated();
 
     // Check STT metadata
     let stt_meta = registry.get_stt_metadata("gnani");
@@ -517,9 +562,9 @@ fn test_gnani_metadata_available() {
 /// Test Gnani appears in provider lists.
 #[test]
 fn test_gnani_in_provider_lists() {
-    use waav_gateway::plugin::global_registry;
+    use waav_gateway::plugin::PluginRegistry;
 
-    let registry = global_registry();
+    let registry = PluginRegistry::new_isolated();
 
     let stt_providers = registry.get_stt_provider_names();
     assert!(stt_providers.contains(&"gnani".to_string()),
@@ -546,6 +591,8 @@ fn test_invalid_provider_error() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider("nonexistent", config);
@@ -575,6 +622,8 @@ async fn test_gnani_stt_callback_registration() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let mut provider = create_stt_provider("gnani", config).unwrap();
@@ -604,6 +653,8 @@ fn test_gnani_stt_empty_api_key() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     // Provider creation might succeed, but connect should fail
@@ -624,6 +675,8 @@ fn test_gnani_stt_config_retrieval() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let provider = create_stt_provider("gnani", config).unwrap();
@@ -653,6 +706,8 @@ fn test_gnani_multiple_instances() {
         punctuation: true,
         encoding: "pcm16".to_string(),
         model: "default".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     }).collect();
 
     let providers: Vec<_> = configs.into_iter()
@@ -678,6 +733,8 @@ async fn test_gnani_concurrent_creation() {
                 punctuation: true,
                 encoding: "pcm16".to_string(),
                 model: "default".to_string(),
+                custom_headers: Default::default(),
+                certificate_path: None,
             };
             create_stt_provider("gnani", config)
         })
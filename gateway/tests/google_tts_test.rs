@@ -72,7 +72,12 @@ fn create_google_tts_config() -> Option<TTSConfig> {
         request_timeout: Some(60),
         pronunciations: Vec::new(),
         request_pool_size: Some(4),
+        utterance_timeout: Some(10),
         emotion_config: None,
+        custom_headers: Default::default(),
+        output_profile: None,
+        context_hints: None,
+        visemes: None,
     })
 }
 
@@ -0,0 +1,167 @@
+//! # Idle Memory Test
+//!
+//! Builds the gateway state with no provider keys and no sessions and checks
+//! how much resident memory it takes. Provider HTTP pools are created on
+//! first use, so an idle gateway holds none of them.
+//!
+//! The growth is read from `VmRSS` in `/proc/self/status`, so the budget is
+//! only checked on Linux.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test idle_memory -- --nocapture
+//! ```
+
+use std::time::Duration;
+
+use waav_gateway::{ServerConfig, config::PluginConfig, state::AppState};
+
+/// Resident memory an idle gateway may add on top of the test binary
+const IDLE_RSS_BUDGET_BYTES: u64 = 64 * 1024 * 1024;
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Resident set size of this process, from `VmRSS` in `/proc/self/status`
+#[cfg(target_os = "linux")]
+fn resident_bytes() -> u64 {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap();
+    let kib: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+        .expect("VmRSS in /proc/self/status");
+    kib * 1024
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_idle_gateway_stays_within_rss_budget() {
+    let before = resident_bytes();
+    let state = AppState::new(test_config()).await;
+    let after = resident_bytes();
+
+    let growth = after.saturating_sub(before);
+    println!("idle RSS: before={before} after={after} growth={growth}");
+    assert!(
+        growth < IDLE_RSS_BUDGET_BYTES,
+        "idle gateway added {growth} bytes of RSS, budget is {IDLE_RSS_BUDGET_BYTES}"
+    );
+
+    drop(state);
+}
+
+#[tokio::test]
+async fn test_tts_pools_are_created_on_first_use() {
+    let state = AppState::new(test_config()).await;
+    assert!(state.core_state.tts_req_managers.read().await.is_empty());
+
+    let pool = state.get_tts_req_manager("deepgram").await.unwrap();
+    let again = state.get_tts_req_manager("deepgram").await.unwrap();
+    assert!(std::sync::Arc::ptr_eq(&pool, &again));
+    assert_eq!(state.core_state.tts_req_managers.read().await.len(), 1);
+    assert!(state.get_tts_req_manager("unknown").await.is_none());
+
+    // Pools held by a session are kept however long they idle
+    assert_eq!(
+        state
+            .core_state
+            .evict_idle_req_managers(Duration::ZERO)
+            .await,
+        0
+    );
+    drop((pool, again));
+    assert_eq!(
+        state
+            .core_state
+            .evict_idle_req_managers(Duration::ZERO)
+            .await,
+        1
+    );
+    assert!(state.core_state.tts_req_managers.read().await.is_empty());
+}
@@ -8,7 +8,12 @@ use serde_json::{Value, json};
 use sha2::{Digest, Sha256};
 use tower::util::ServiceExt;
 
-use waav_gateway::{ServerConfig, config::PluginConfig, routes, state::AppState};
+use waav_gateway::{
+    ServerConfig,
+    config::PluginConfig,
+    routes,
+    state::{AppState, CallEndReason, CallState},
+};
 
 /// Helper to create a minimal test configuration with LiveKit credentials
 fn create_test_config_with_livekit() -> ServerConfig {
@@ -55,13 +60,46 @@ fn create_test_config_with_livekit() -> ServerConfig {
         auth_api_secrets: Vec::new(),
         auth_timeout_seconds: 5,
         auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
         sip: None,
         cors_allowed_origins: None,
         rate_limit_requests_per_second: 60,
         rate_limit_burst_size: 10,
         max_websocket_connections: None,
         max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
         plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
     }
 }
 
@@ -110,13 +148,46 @@ fn create_test_config_without_livekit() -> ServerConfig {
         auth_api_secrets: Vec::new(),
         auth_timeout_seconds: 5,
         auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
         sip: None,
         cors_allowed_origins: None,
         rate_limit_requests_per_second: 60,
         rate_limit_burst_size: 10,
         max_websocket_connections: None,
         max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
         plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
     }
 }
 
@@ -578,6 +649,52 @@ async fn test_webhook_without_sip_config_no_forwarding() {
     // debug logs in production confirms this works as intended.
 }
 
+/// Build a signed webhook request for the outbound call of `sip-test-room`
+fn outbound_call_request(event: &str, attributes: Value) -> Request<Body> {
+    let mut payload = create_participant_joined_event_with_sip();
+    payload["event"] = json!(event);
+    payload["participant"]["kind"] = json!(3);
+    payload["participant"]["attributes"] = attributes;
+    let (payload_str, token) = create_signed_webhook("test-api-key", "test-api-secret", payload);
+
+    Request::builder()
+        .method("POST")
+        .uri("/livekit/webhook")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(payload_str))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_webhook_tracks_outbound_call_progress() {
+    let config = create_test_config_with_livekit();
+    let app_state = AppState::new(config).await;
+    let _call = app_state.sip_calls.track("sip-test-room");
+    let app = routes::webhooks::create_webhook_router().with_state(app_state.clone());
+
+    // The callee's phone rings
+    let request = outbound_call_request("participant_joined", json!({"sip.callStatus": "ringing"}));
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        app_state.sip_calls.state("sip-test-room"),
+        Some(CallState::EarlyMedia)
+    );
+
+    // The callee rejects the call as busy
+    let request = outbound_call_request(
+        "participant_left",
+        json!({"sip.callStatus": "hangup", "sip.callStatusCode": "486"}),
+    );
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        app_state.sip_calls.state("sip-test-room"),
+        Some(CallState::Ended(CallEndReason::Busy))
+    );
+}
+
 // Note: We don't create full integration tests that initialize AppState with SIP hooks
 // because that would require either:
 // 1. A running LiveKit server for SIP provisioning
@@ -0,0 +1,297 @@
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{Router, middleware, routing::get};
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::{self, protocol::Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+use waav_gateway::{
+    ServerConfig,
+    config::{LoadSheddingConfig, PluginConfig},
+    handlers,
+    metrics::global_metrics,
+    middleware::{auth_middleware, connection_limit_middleware, load_shedding_middleware},
+    routes,
+    state::{AppState, ShedReason},
+};
+
+type Session = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+fn test_config(load_shedding: LoadSheddingConfig) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: Some(load_shedding),
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Serve `/ws` behind the same middleware stack as the gateway binary
+///
+/// Returns None when the sandbox does not allow binding a socket.
+async fn start_server(config: ServerConfig) -> Option<(SocketAddr, Arc<AppState>)> {
+    let app_state = AppState::new(config).await;
+
+    let ws_routes = routes::ws::create_ws_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            connection_limit_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            load_shedding_middleware,
+        ));
+    let app = Router::new()
+        .route("/readyz", get(handlers::api::readiness_check))
+        .merge(ws_routes)
+        .with_state(app_state.clone());
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping load shedding test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind load shedding test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    Some((addr, app_state))
+}
+
+async fn open_session(addr: SocketAddr) -> Result<Session, tungstenite::Error> {
+    connect_async(format!("ws://{addr}/ws"))
+        .await
+        .map(|(stream, _)| stream)
+}
+
+/// Send an unparseable message and expect the session to answer with an error
+async fn assert_session_responsive(session: &mut Session) {
+    session
+        .send(Message::Text("not json".into()))
+        .await
+        .expect("session should accept messages");
+    let reply = tokio::time::timeout(Duration::from_secs(5), session.next())
+        .await
+        .expect("session should reply")
+        .expect("session should stay open")
+        .expect("session should reply without error");
+    let Message::Text(text) = reply else {
+        panic!("expected a text reply, got {reply:?}");
+    };
+    let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(parsed["type"], "error");
+}
+
+#[tokio::test]
+async fn test_sheds_new_sessions_at_watermark() {
+    let config = test_config(LoadSheddingConfig {
+        max_active_sessions: Some(3),
+        retry_after_secs: 7,
+        ..Default::default()
+    });
+    let Some((addr, app_state)) = start_server(config).await else {
+        return;
+    };
+    let rejections = || {
+        global_metrics()
+            .get(
+                "waav_load_shed_rejections_total",
+                &[("reason", "active_sessions")],
+            )
+            .unwrap_or(0.0)
+    };
+    let rejections_before = rejections();
+
+    // Sessions below the watermark are accepted
+    let mut sessions = Vec::new();
+    for _ in 0..3 {
+        sessions.push(open_session(addr).await.expect("session below watermark"));
+    }
+    assert_eq!(app_state.ws_connection_count(), 3);
+
+    // The next session is turned away with a reason and Retry-After
+    let err = open_session(addr)
+        .await
+        .expect_err("session at the watermark should be shed");
+    let tungstenite::Error::Http(response) = err else {
+        panic!("expected an HTTP rejection, got {err:?}");
+    };
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.headers()["retry-after"], "7");
+    let body: serde_json::Value =
+        serde_json::from_slice(response.body().as_deref().expect("rejection body")).unwrap();
+    assert_eq!(body["reason"], "active_sessions");
+    assert_eq!(body["retry_after_secs"], 7);
+    assert_eq!(app_state.ws_connection_count(), 3);
+    assert!(rejections() >= rejections_before + 1.0);
+
+    // Readiness reports the shedding state
+    let readyz = reqwest::get(format!("http://{addr}/readyz")).await.unwrap();
+    assert_eq!(readyz.status(), 503);
+    let readyz: serde_json::Value = readyz.json().await.unwrap();
+    assert_eq!(readyz["status"], "not_ready");
+    assert_eq!(readyz["load_shedding"]["shedding"], true);
+    assert_eq!(readyz["load_shedding"]["reason"], "active_sessions");
+    assert_eq!(readyz["load_shedding"]["active_sessions"], 3);
+
+    // Sessions that were already open are unaffected
+    for session in &mut sessions {
+        assert_session_responsive(session).await;
+    }
+
+    // Raising the watermark at runtime lets new sessions in again
+    app_state
+        .load_shedder
+        .update_config(LoadSheddingConfig {
+            max_active_sessions: Some(10),
+            ..Default::default()
+        })
+        .unwrap();
+    let mut session = open_session(addr)
+        .await
+        .expect("session below the raised watermark");
+    assert_session_responsive(&mut session).await;
+
+    let readyz = reqwest::get(format!("http://{addr}/readyz")).await.unwrap();
+    assert_eq!(readyz.status(), 200);
+}
+
+#[tokio::test]
+async fn test_closed_sessions_free_capacity() {
+    let config = test_config(LoadSheddingConfig {
+        max_active_sessions: Some(1),
+        ..Default::default()
+    });
+    let Some((addr, app_state)) = start_server(config).await else {
+        return;
+    };
+
+    let mut session = open_session(addr).await.expect("first session");
+    assert!(open_session(addr).await.is_err());
+
+    session.close(None).await.unwrap();
+    drop(session);
+    for _ in 0..50 {
+        if app_state.ws_connection_count() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(app_state.ws_connection_count(), 0);
+    assert!(open_session(addr).await.is_ok());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_sheds_on_event_loop_lag() {
+    let config = test_config(LoadSheddingConfig {
+        max_event_loop_lag_ms: Some(100),
+        ..Default::default()
+    });
+    let app_state = AppState::new(config).await;
+    assert!(!app_state.load_shedding_status().await.shedding);
+
+    // Stall the only runtime thread so the lag monitor wakes up late
+    std::thread::sleep(Duration::from_millis(400));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let status = app_state.load_shedding_status().await;
+    assert!(status.shedding);
+    assert_eq!(status.reason, Some(ShedReason::EventLoopLag));
+    assert!(status.event_loop_lag_ms >= 100);
+}
@@ -36,6 +36,8 @@ fn test_create_openai_provider_by_name() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "whisper-1".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider("openai", config);
@@ -59,6 +61,8 @@ fn test_create_openai_provider_by_enum() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "whisper-1".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let result = create_stt_provider_from_enum(STTProvider::OpenAI, config);
@@ -258,6 +262,8 @@ async fn test_openai_live_transcription() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "whisper-1".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     });
 
     let mut stt = OpenAISTT::with_config(config).expect("Failed to create OpenAI STT");
@@ -336,6 +342,8 @@ async fn test_openai_verbose_json_format() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "whisper-1".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     });
 
     let mut stt = OpenAISTT::with_config(config).expect("Failed to create OpenAI STT");
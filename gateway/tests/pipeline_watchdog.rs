@@ -0,0 +1,279 @@
+//! # Pipeline Watchdog Integration Tests
+//!
+//! Runs sessions with a pipeline watchdog on in-process mock providers that
+//! stop responding mid-stream without closing their connection:
+//!
+//! 1. The STT mock transcribes every chunk until it wedges. Caller speech
+//!    keeps flowing, so the watchdog reconnects it, replays the unanswered
+//!    audio, and transcripts resume.
+//! 2. Silence sent to the wedged STT mock does not trigger a reconnect.
+//! 3. The TTS mock accepts an utterance and never answers it. The watchdog
+//!    reconnects it and drops the stuck utterance, and later speech plays.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test pipeline_watchdog
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Clip, Silent, SttBehavior, SttCallbacks, TtsBehavior, mock_stt_config, mock_tts_config,
+    stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{
+    PipelineWatchdogConfig, Session, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+    WatchdogStage,
+};
+use waav_gateway::core::stt::{STTError, STTResult};
+use waav_gateway::core::tts::{AudioCallback, TTSResult};
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+/// Transcribes until told to wedge
+const STALLING_STT: &str = "watchdog-stalling-stt";
+
+/// Never transcribes anything
+const SILENT_STT: &str = "watchdog-silent-stt";
+
+/// Answers every utterance
+const WORKING_TTS: &str = "watchdog-working-tts";
+
+/// Never answers the first utterance of its first connection
+const STALLING_TTS: &str = "watchdog-stalling-tts";
+
+const STALL_TIMEOUT_MS: u64 = 300;
+
+/// 20ms of 16kHz PCM16
+const CHUNK_BYTES: usize = 640;
+
+/// Observations of the stalling STT mock, shared across its reconnects
+static STT_WEDGED: AtomicBool = AtomicBool::new(false);
+static STT_CONNECTS: AtomicUsize = AtomicUsize::new(0);
+static STT_AUDIO_AFTER_RECONNECT: AtomicUsize = AtomicUsize::new(0);
+
+/// Observations of the stalling TTS mock
+static TTS_CONNECTS: AtomicUsize = AtomicUsize::new(0);
+
+/// STT behavior that emits an interim result per audio chunk, until
+/// `STT_WEDGED` is set on its first connection
+struct WedgingStt;
+
+#[async_trait]
+impl SttBehavior for WedgingStt {
+    async fn connect(&mut self) -> Result<(), STTError> {
+        STT_CONNECTS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, audio: Bytes, callbacks: &SttCallbacks) -> Result<(), STTError> {
+        let reconnected = STT_CONNECTS.load(Ordering::SeqCst) > 1;
+        if reconnected {
+            STT_AUDIO_AFTER_RECONNECT.fetch_add(audio.len(), Ordering::SeqCst);
+        } else if STT_WEDGED.load(Ordering::SeqCst) {
+            // The connection stays open and swallows the audio
+            return Ok(());
+        }
+        callbacks
+            .result(STTResult::new("hello".to_string(), false, false, 0.9))
+            .await;
+        Ok(())
+    }
+}
+
+/// TTS behavior that answers every utterance with a short clip, except the
+/// first utterance of its first connection
+struct StallingTts;
+
+#[async_trait]
+impl TtsBehavior for StallingTts {
+    async fn connect(&mut self) -> TTSResult<()> {
+        TTS_CONNECTS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn speak(
+        &mut self,
+        text: &str,
+        flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        if TTS_CONNECTS.load(Ordering::SeqCst) == 1 {
+            // Accepted, never answered
+            return Ok(());
+        }
+        Clip::silence(24000, 100).speak(text, flush, audio).await
+    }
+}
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(
+        STALLING_STT,
+        stt_factory(|_| WedgingStt),
+        ProviderMetadata::stt(STALLING_STT, "Pipeline Watchdog Mock STT"),
+    );
+    registry.register_stt(
+        SILENT_STT,
+        stt_factory(|_| Silent),
+        ProviderMetadata::stt(SILENT_STT, "Pipeline Watchdog Mock STT"),
+    );
+    registry.register_tts(
+        WORKING_TTS,
+        tts_factory(|_| Clip::silence(24000, 100)),
+        ProviderMetadata::tts(WORKING_TTS, "Pipeline Watchdog Mock TTS"),
+    );
+    registry.register_tts(
+        STALLING_TTS,
+        tts_factory(|_| StallingTts),
+        ProviderMetadata::tts(STALLING_TTS, "Pipeline Watchdog Mock TTS"),
+    );
+    registry
+}
+
+async fn build_session(stt_provider: &str, tts_provider: &str) -> (Session, SessionEventStream) {
+    let session = SessionPipelineBuilder::new()
+        .plugin_registry(mock_registry())
+        .stt(mock_stt_config(stt_provider))
+        .tts(mock_tts_config(tts_provider))
+        .watchdog(PipelineWatchdogConfig {
+            stall_timeout_ms: STALL_TIMEOUT_MS,
+            min_speech_ms: 100,
+            replay_ms: 200,
+            max_recoveries: 3,
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build");
+    let events = session.take_events().expect("events are taken once");
+    (session, events)
+}
+
+/// 20ms of PCM16 at `amplitude` (fraction of full scale)
+fn chunk(amplitude: f64) -> Bytes {
+    let value = (amplitude * i16::MAX as f64) as i16;
+    (0..CHUNK_BYTES / 2)
+        .flat_map(|i| {
+            let sample = if i % 2 == 0 { value } else { -value };
+            sample.to_le_bytes()
+        })
+        .collect()
+}
+
+/// Push `duration` of audio in real time
+async fn stream(session: &Session, amplitude: f64, duration: Duration) {
+    for _ in 0..duration.as_millis() / 20 {
+        session.push_audio(chunk(amplitude)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Events that arrive within `window`
+async fn collect_for(events: &mut SessionEventStream, window: Duration) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    let _ = tokio::time::timeout(window, async {
+        while let Some(event) = events.recv().await {
+            collected.push(event);
+        }
+    })
+    .await;
+    collected
+}
+
+#[tokio::test]
+async fn test_wedged_stt_is_reconnected_with_replay() {
+    let (session, mut events) = build_session(STALLING_STT, WORKING_TTS).await;
+
+    // Transcripts arrive, then the provider stops responding mid-stream
+    stream(&session, 0.3, Duration::from_millis(200)).await;
+    STT_WEDGED.store(true, Ordering::SeqCst);
+    stream(&session, 0.3, Duration::from_millis(2 * STALL_TIMEOUT_MS)).await;
+
+    assert_eq!(STT_CONNECTS.load(Ordering::SeqCst), 2);
+    // The reconnected provider received the unanswered audio before new audio
+    assert!(STT_AUDIO_AFTER_RECONNECT.load(Ordering::SeqCst) >= 200 * 32);
+
+    let events = collect_for(&mut events, Duration::from_millis(100)).await;
+    let recovered: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::PipelineRecovered(recovered) => Some(recovered),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(recovered.len(), 1, "{recovered:?}");
+    assert_eq!(recovered[0].stage, WatchdogStage::Stt);
+    assert_eq!(recovered[0].replayed_bytes, 200 * 32);
+    assert!(recovered[0].stalled_ms >= STALL_TIMEOUT_MS);
+
+    // Transcripts resume after the recovery
+    let recovered_at = events
+        .iter()
+        .position(|event| matches!(event, SessionEvent::PipelineRecovered(_)))
+        .unwrap();
+    assert!(
+        events[recovered_at..]
+            .iter()
+            .any(|event| matches!(event, SessionEvent::Transcript(_)))
+    );
+
+    let stats = session.watchdog_stats().expect("watchdog is configured");
+    assert_eq!(stats.stt_recoveries, 1);
+    assert_eq!(stats.failed_recoveries, 0);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_silence_does_not_reconnect_stt() {
+    let (session, mut events) = build_session(SILENT_STT, WORKING_TTS).await;
+
+    stream(&session, 0.0, Duration::from_millis(3 * STALL_TIMEOUT_MS)).await;
+
+    assert!(
+        !collect_for(&mut events, Duration::from_millis(100))
+            .await
+            .iter()
+            .any(|event| matches!(event, SessionEvent::PipelineRecovered(_)))
+    );
+    assert_eq!(session.watchdog_stats().unwrap().stt_recoveries, 0);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_stalled_tts_is_reconnected() {
+    let (session, mut events) = build_session(SILENT_STT, STALLING_TTS).await;
+
+    session.speak("Hello there.", true).await.unwrap();
+    assert_eq!(session.tts_queue_stats().unwrap().pending, 1);
+    tokio::time::sleep(Duration::from_millis(2 * STALL_TIMEOUT_MS)).await;
+
+    assert_eq!(TTS_CONNECTS.load(Ordering::SeqCst), 2);
+    assert_eq!(session.tts_queue_stats().unwrap().pending, 0);
+    assert_eq!(session.watchdog_stats().unwrap().tts_recoveries, 1);
+
+    // The new connection plays later speech
+    session.speak("How can I help?", true).await.unwrap();
+    let events = collect_for(&mut events, Duration::from_millis(100)).await;
+    let recovered_at = events
+        .iter()
+        .position(|event| {
+            matches!(
+                event,
+                SessionEvent::PipelineRecovered(recovered) if recovered.stage == WatchdogStage::Tts
+            )
+        })
+        .expect("tts recovery is reported");
+    assert!(
+        events[recovered_at..]
+            .iter()
+            .any(|event| matches!(event, SessionEvent::Audio(_)))
+    );
+    session.close().await.unwrap();
+}
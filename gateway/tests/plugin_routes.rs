@@ -0,0 +1,376 @@
+//! # Plugin Route Tests
+//!
+//! 1. Requests to `/plugins/{plugin_id}/...` reach the plugin's HTTP handler
+//!    with the method, path suffix, query, body and client id, and the
+//!    plugin's status, headers and body are returned.
+//! 2. Plugins without a handler answer 404; clients without the
+//!    `plugin:{plugin_id}` scope get 403.
+//! 3. Bodies over `http_max_body_bytes` are rejected with 413 before the
+//!    plugin is called, whether or not they declare a length.
+//! 4. A plugin that doesn't answer within `http_timeout_ms` yields 504;
+//!    plugin errors and invalid statuses yield 502.
+//! 5. Calls that timed out hold their slot until the plugin returns, so a
+//!    hanging plugin answers 503 beyond `http_max_concurrency` while other
+//!    plugins are still served.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test plugin_routes
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::{
+    Extension, Router,
+    body::{Body, Bytes},
+    http::{Request, StatusCode},
+    routing::any,
+};
+use tower::ServiceExt;
+
+use waav_gateway::auth::Auth;
+use waav_gateway::config::PluginConfig;
+use waav_gateway::handlers::plugin_routes;
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::plugin::capabilities::{PluginHttpRequest, PluginHttpResponse};
+use waav_gateway::{ServerConfig, state::AppState};
+
+const ECHO_PLUGIN: &str = "routes-test-echo";
+const LIMITED_PLUGIN: &str = "routes-test-limited";
+const SLOW_PLUGIN: &str = "routes-test-slow";
+const BROKEN_PLUGIN: &str = "routes-test-broken";
+const HANGING_PLUGIN: &str = "routes-test-hanging";
+const MAX_BODY_BYTES: usize = 16;
+const TIMEOUT_MS: u64 = 100;
+const MAX_CONCURRENCY: usize = 2;
+
+/// Requests that reached the body limit test's plugin
+static LIMITED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Lets the hanging plugin's calls return
+static RELEASE_HANGING: AtomicBool = AtomicBool::new(false);
+
+fn test_config() -> ServerConfig {
+    let grant = |plugin_id: &str| (plugin_id.to_string(), vec!["ops".to_string()]);
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: true,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig {
+            http_max_body_bytes: MAX_BODY_BYTES,
+            http_timeout_ms: TIMEOUT_MS,
+            http_max_concurrency: MAX_CONCURRENCY,
+            http_access: [
+                grant(ECHO_PLUGIN),
+                grant(LIMITED_PLUGIN),
+                grant(SLOW_PLUGIN),
+                grant(BROKEN_PLUGIN),
+                grant(HANGING_PLUGIN),
+            ]
+            .into(),
+            ..PluginConfig::default()
+        },
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Echo the request back, with the client id the gateway passed on
+fn echo(request: PluginHttpRequest) -> Result<PluginHttpResponse, String> {
+    let client = request
+        .headers
+        .iter()
+        .find(|(name, _)| name == plugin_routes::CLIENT_ID_HEADER)
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default();
+    let body = format!(
+        "{} {}?{} {} {}",
+        request.method,
+        request.path,
+        request.query,
+        String::from_utf8_lossy(&request.body),
+        client
+    );
+    Ok(PluginHttpResponse {
+        status: 201,
+        headers: vec![("x-plugin".to_string(), "echo".to_string())],
+        body: body.into(),
+    })
+}
+
+/// Registry with the test plugins' handlers
+fn plugin_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_http_handler(ECHO_PLUGIN, Arc::new(echo));
+    registry.register_http_handler(
+        LIMITED_PLUGIN,
+        Arc::new(|request: PluginHttpRequest| {
+            LIMITED_CALLS.fetch_add(1, Ordering::SeqCst);
+            echo(request)
+        }),
+    );
+    registry.register_http_handler(
+        SLOW_PLUGIN,
+        Arc::new(|_: PluginHttpRequest| {
+            std::thread::sleep(Duration::from_millis(TIMEOUT_MS * 5));
+            Ok(PluginHttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: Bytes::new(),
+            })
+        }),
+    );
+    registry.register_http_handler(
+        BROKEN_PLUGIN,
+        Arc::new(|request: PluginHttpRequest| match request.path.as_str() {
+            "/status" => Ok(PluginHttpResponse {
+                status: 1000,
+                headers: Vec::new(),
+                body: Bytes::new(),
+            }),
+            _ => Err("cache unavailable".to_string()),
+        }),
+    );
+    registry.register_http_handler(
+        HANGING_PLUGIN,
+        Arc::new(|request: PluginHttpRequest| {
+            while !RELEASE_HANGING.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            echo(request)
+        }),
+    );
+    registry
+}
+
+/// Plugin routes as mounted by the gateway, for a client authenticated as `client_id`
+async fn app(client_id: &str) -> Router {
+    let app_state = AppState::with_plugin_registry(test_config(), plugin_registry()).await;
+    Router::new()
+        .route("/plugins/{plugin_id}", any(plugin_routes::plugin_route))
+        .route(
+            "/plugins/{plugin_id}/{*path}",
+            any(plugin_routes::plugin_route),
+        )
+        .layer(Extension(Auth::new(client_id)))
+        .with_state(app_state)
+}
+
+async fn send(client_id: &str, request: Request<Body>) -> (StatusCode, String) {
+    send_to(&app(client_id).await, request).await
+}
+
+async fn send_to(app: &Router, request: Request<Body>) -> (StatusCode, String) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn post(uri: &str, body: impl Into<Body>) -> Request<Body> {
+    Request::post(uri).body(body.into()).unwrap()
+}
+
+#[tokio::test]
+async fn test_request_reaches_plugin() {
+    // Client-supplied x-waav-* headers are dropped
+    let request = Request::post(format!("/plugins/{ECHO_PLUGIN}/cache/flush?force=true"))
+        .header(plugin_routes::CLIENT_ID_HEADER, "spoofed")
+        .body(Body::from("hello"))
+        .unwrap();
+    let response = app("ops").await.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["x-plugin"], "echo");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "POST /cache/flush?force=true hello ops");
+}
+
+#[tokio::test]
+async fn test_plugin_root_path() {
+    let (status, body) = send(
+        "ops",
+        Request::get(format!("/plugins/{ECHO_PLUGIN}"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body, "GET /?  ops");
+}
+
+#[tokio::test]
+async fn test_unknown_plugin_not_found() {
+    let (status, _) = send("ops", post("/plugins/routes-test-missing/voices", "")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_requires_plugin_scope() {
+    let (status, body) = send("other", post(&format!("/plugins/{ECHO_PLUGIN}/voices"), "")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body.contains(&format!("plugin:{ECHO_PLUGIN}")), "{body}");
+}
+
+#[tokio::test]
+async fn test_oversized_body_rejected() {
+    let uri = format!("/plugins/{LIMITED_PLUGIN}/cache/flush");
+
+    // Declared with Content-Length
+    let request = Request::post(&uri)
+        .header("content-length", MAX_BODY_BYTES + 1)
+        .body(Body::from(vec![b'a'; MAX_BODY_BYTES + 1]))
+        .unwrap();
+    let (status, _) = send("ops", request).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Streamed without a declared length
+    let chunks: Vec<Result<Bytes, std::io::Error>> = vec![
+        Ok(Bytes::from(vec![b'a'; 10])),
+        Ok(Bytes::from(vec![b'a'; 10])),
+    ];
+    let request = post(&uri, Body::from_stream(futures::stream::iter(chunks)));
+    let (status, _) = send("ops", request).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Neither reached the plugin; a body at the limit does
+    assert_eq!(LIMITED_CALLS.load(Ordering::SeqCst), 0);
+    let (status, _) = send("ops", post(&uri, vec![b'a'; MAX_BODY_BYTES])).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_slow_plugin_times_out() {
+    let started = Instant::now();
+    let (status, body) = send("ops", post(&format!("/plugins/{SLOW_PLUGIN}/voices"), "")).await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    assert!(body.contains(&format!("{TIMEOUT_MS} ms")), "{body}");
+    assert!(started.elapsed() < Duration::from_millis(TIMEOUT_MS * 5));
+}
+
+#[tokio::test]
+async fn test_plugin_failures_are_bad_gateway() {
+    let (status, body) = send("ops", post(&format!("/plugins/{BROKEN_PLUGIN}/flush"), "")).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert!(body.contains("cache unavailable"), "{body}");
+
+    let (status, _) = send("ops", post(&format!("/plugins/{BROKEN_PLUGIN}/status"), "")).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test]
+async fn test_hanging_plugin_is_limited() {
+    let app = app("ops").await;
+    let uri = format!("/plugins/{HANGING_PLUGIN}/voices");
+
+    // Timed out calls keep running and hold their slots
+    for _ in 0..MAX_CONCURRENCY {
+        let (status, _) = send_to(&app, post(&uri, "")).await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    }
+    let (status, body) = send_to(&app, post(&uri, "")).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(
+        body.contains(&format!("{MAX_CONCURRENCY} requests")),
+        "{body}"
+    );
+
+    // Other plugins have their own slots
+    let (status, _) = send_to(&app, post(&format!("/plugins/{ECHO_PLUGIN}/voices"), "")).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Slots are freed once the plugin returns
+    RELEASE_HANGING.store(true, Ordering::SeqCst);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let (status, _) = send_to(&app, post(&uri, "")).await;
+        if status == StatusCode::CREATED {
+            break;
+        }
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(Instant::now() < deadline, "slots were not freed");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
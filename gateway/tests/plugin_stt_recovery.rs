@@ -0,0 +1,157 @@
+//! # Plugin STT Recovery Integration Tests
+//!
+//! Drives an STT provider loaded from the test plugin in
+//! `examples/test-plugin`, compiled with cargo on first use and loaded into
+//! the global registry through `DynamicPluginLoader`.
+//!
+//! With the `flaky` model the plugin loses its upstream connection partway
+//! through the audio and reports a recoverable error. The gateway's adapter
+//! buffers the audio, reconnects the plugin and lets it resume, so the
+//! transcript continues without a gap and the session sees no error.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --features plugins-dynamic --test plugin_stt_recovery
+//! ```
+
+#![cfg(feature = "plugins-dynamic")]
+
+use bytes::Bytes;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OnceCell, mpsc};
+
+use waav_gateway::core::stt::{STTConfig, STTError, STTResult};
+use waav_gateway::plugin::{DynamicPluginLoader, PluginLoadStatus, PluginRegistry};
+
+const PLUGIN_ID: &str = "test-stt";
+
+/// Audio per chunk: 100ms of 16kHz 16-bit mono
+const CHUNK_BYTES: usize = 3_200;
+
+/// How long to wait for each transcript
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+static PLUGIN_REGISTRY: OnceCell<Arc<PluginRegistry>> = OnceCell::const_new();
+
+/// Build the test plugin and return the path of its library
+fn build_test_plugin() -> PathBuf {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/test-plugin/Cargo.toml");
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test-plugin");
+
+    let status = Command::new(env!("CARGO"))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("Failed to run cargo for the test plugin");
+    assert!(status.success(), "Building the test plugin failed");
+
+    target_dir.join("debug").join(format!(
+        "{}waav_plugin_test{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+/// Registry with the test plugin, loaded once per test binary
+async fn plugin_registry() -> &'static PluginRegistry {
+    PLUGIN_REGISTRY
+        .get_or_init(|| async {
+            let library = build_test_plugin();
+
+            // Only the library itself may be in the plugin directory
+            let plugin_dir = tempfile::tempdir().unwrap();
+            std::fs::copy(
+                &library,
+                plugin_dir.path().join(library.file_name().unwrap()),
+            )
+            .unwrap();
+
+            let registry = Arc::new(PluginRegistry::new_isolated());
+            let mut loader = DynamicPluginLoader::new();
+            let reports = loader
+                .load_all_from_directory(plugin_dir.path(), &registry)
+                .await
+                .unwrap();
+            assert_eq!(reports.len(), 1);
+            assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+            assert!(registry.has_stt_provider(PLUGIN_ID));
+            loader.hand_over_to(&registry);
+            registry
+        })
+        .await
+}
+
+#[tokio::test]
+async fn test_recoverable_drop_keeps_transcript_continuous() {
+    let config = STTConfig {
+        provider: PLUGIN_ID.to_string(),
+        model: "flaky".to_string(),
+        ..Default::default()
+    };
+    let mut stt = plugin_registry()
+        .await
+        .create_stt(PLUGIN_ID, config)
+        .unwrap();
+
+    let (result_tx, mut results) = mpsc::unbounded_channel::<STTResult>();
+    let (error_tx, mut errors) = mpsc::unbounded_channel::<STTError>();
+    stt.on_result(Arc::new(move |result| {
+        let result_tx = result_tx.clone();
+        Box::pin(async move {
+            let _ = result_tx.send(result);
+        })
+    }))
+    .await
+    .unwrap();
+    stt.on_error(Arc::new(move |error| {
+        let error_tx = error_tx.clone();
+        Box::pin(async move {
+            let _ = error_tx.send(error);
+        })
+    }))
+    .await
+    .unwrap();
+    stt.connect().await.unwrap();
+
+    // 3 seconds of audio; the plugin drops its connection after 1.25 seconds
+    for _ in 0..30 {
+        stt.send_audio(Bytes::from(vec![0u8; CHUNK_BYTES]))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // One transcript per second of audio, none lost or restarted by the drop
+    let mut offsets = Vec::new();
+    for _ in 0..6 {
+        let result = tokio::time::timeout(RECV_TIMEOUT, results.recv())
+            .await
+            .expect("Timed out waiting for a transcript")
+            .unwrap();
+        let offset = result
+            .transcript
+            .strip_prefix("Test transcript at ")
+            .and_then(|rest| rest.strip_suffix(" bytes"))
+            .and_then(|bytes| bytes.parse::<u64>().ok())
+            .expect("Unexpected transcript");
+        offsets.push(offset);
+    }
+    // Results are delivered by spawned tasks, in any order
+    offsets.sort_unstable();
+    assert_eq!(offsets, [16_000, 32_000, 48_000, 64_000, 80_000, 96_000]);
+
+    assert!(stt.is_ready());
+    assert!(
+        errors.try_recv().is_err(),
+        "A recovered drop must not reach the session"
+    );
+
+    stt.disconnect().await.unwrap();
+}
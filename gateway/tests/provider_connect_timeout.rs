@@ -0,0 +1,150 @@
+//! # Provider Connect Timeout Integration Tests
+//!
+//! Builds sessions on top of in-process mock providers whose `connect()` dials
+//! a non-routable address, the way a provider stalls on an unresponsive
+//! network. Session setup must give up after the configured connect timeout
+//! instead of waiting for the operating system's TCP timeout.
+//!
+//! Hosts without a route to 10.0.0.0/8 fail the dial immediately; the tests
+//! then still check that setup fails within the timeout.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test provider_connect_timeout
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use fixtures::session_mocks::{
+    Silent, SttBehavior, TtsBehavior, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{SessionError, SessionPipelineBuilder};
+use waav_gateway::core::stt::STTError;
+use waav_gateway::core::tts::{TTSError, TTSResult};
+use waav_gateway::core::voice_manager::VoiceManagerError;
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+const STALLING_STT: &str = "connect-timeout-stalling-stt";
+const READY_STT: &str = "connect-timeout-ready-stt";
+const STALLING_TTS: &str = "connect-timeout-stalling-tts";
+const READY_TTS: &str = "connect-timeout-ready-tts";
+
+/// Non-routable address; SYNs are dropped so the dial never completes
+const BLACKHOLE_ADDR: &str = "10.255.255.1:443";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Upper bound on session setup when a provider stalls
+const SETUP_DEADLINE: Duration = Duration::from_secs(3);
+
+/// Dial the non-routable address
+async fn dial_blackhole() -> std::io::Result<()> {
+    tokio::net::TcpStream::connect(BLACKHOLE_ADDR).await?;
+    Ok(())
+}
+
+/// Provider whose connect dials the non-routable address
+struct Blackholed;
+
+#[async_trait]
+impl SttBehavior for Blackholed {
+    async fn connect(&mut self) -> Result<(), STTError> {
+        dial_blackhole()
+            .await
+            .map_err(|e| STTError::ConnectionFailed(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for Blackholed {
+    async fn connect(&mut self) -> TTSResult<()> {
+        dial_blackhole()
+            .await
+            .map_err(|e| TTSError::ConnectionFailed(e.to_string()))
+    }
+}
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(
+        STALLING_STT,
+        stt_factory(|_| Blackholed),
+        ProviderMetadata::stt(STALLING_STT, "Connect Timeout Mock STT"),
+    );
+    registry.register_stt(
+        READY_STT,
+        stt_factory(|_| Silent),
+        ProviderMetadata::stt(READY_STT, "Connect Timeout Mock STT"),
+    );
+    registry.register_tts(
+        STALLING_TTS,
+        tts_factory(|_| Blackholed),
+        ProviderMetadata::tts(STALLING_TTS, "Connect Timeout Mock TTS"),
+    );
+    registry.register_tts(
+        READY_TTS,
+        tts_factory(|_| Silent),
+        ProviderMetadata::tts(READY_TTS, "Connect Timeout Mock TTS"),
+    );
+    registry
+}
+
+/// Build a session and return the setup error with the time it took
+async fn build_session(stt_provider: &str, tts_provider: &str) -> (SessionError, Duration) {
+    let started = Instant::now();
+    let result = SessionPipelineBuilder::new()
+        .plugin_registry(mock_registry())
+        .stt(mock_stt_config(stt_provider))
+        .tts(mock_tts_config(tts_provider))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .ready_timeout(Duration::from_secs(30))
+        .build()
+        .await;
+    let elapsed = started.elapsed();
+    match result {
+        Ok(_) => panic!("session setup should fail when a provider cannot connect"),
+        Err(e) => (e, elapsed),
+    }
+}
+
+#[tokio::test]
+async fn test_stalled_stt_connect_times_out() {
+    let (err, elapsed) = build_session(STALLING_STT, READY_TTS).await;
+    assert!(elapsed < SETUP_DEADLINE, "setup took {elapsed:?}");
+
+    match err {
+        SessionError::StartFailed(VoiceManagerError::STTError(STTError::ConnectionTimeout {
+            provider,
+            timeout,
+        })) => {
+            assert_eq!(provider, STALLING_STT);
+            assert_eq!(timeout, CONNECT_TIMEOUT);
+        }
+        // No route to the address on this host; the dial failed immediately
+        SessionError::StartFailed(VoiceManagerError::STTError(STTError::ConnectionFailed(_))) => {}
+        other => panic!("unexpected setup error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_stalled_tts_connect_times_out() {
+    let (err, elapsed) = build_session(READY_STT, STALLING_TTS).await;
+    assert!(elapsed < SETUP_DEADLINE, "setup took {elapsed:?}");
+
+    match err {
+        SessionError::StartFailed(VoiceManagerError::TTSError(TTSError::ConnectionTimeout {
+            provider,
+            timeout,
+        })) => {
+            assert_eq!(provider, STALLING_TTS);
+            assert_eq!(timeout, CONNECT_TIMEOUT);
+        }
+        SessionError::StartFailed(VoiceManagerError::TTSError(TTSError::ConnectionFailed(_))) => {}
+        other => panic!("unexpected setup error: {other:?}"),
+    }
+}
@@ -0,0 +1,425 @@
+//! Integration tests for provider credential validation.
+//!
+//! Each provider's `validate_credentials_at` is exercised against a mock HTTP
+//! server, and the admin endpoint is checked for routing and admin gating.
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    middleware,
+};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use waav_gateway::{
+    config::{AuthApiSecret, PluginConfig, ServerConfig},
+    core::credentials::{INVALID_API_KEY, ResembleCredentials},
+    core::stt::{deepgram::DeepgramSTT, groq::GroqSTT, openai::OpenAISTT},
+    core::tts::{
+        cartesia::CartesiaTTS, deepgram::DeepgramTTS, elevenlabs::ElevenLabsTTS, openai::OpenAITTS,
+    },
+    middleware::{admin_auth_middleware, auth_middleware},
+    routes,
+    state::AppState,
+};
+
+const VALID_KEY: &str = "valid-key";
+const INVALID_KEY: &str = "invalid-key";
+
+/// Mount a mock that accepts `VALID_KEY` in the given header and rejects everything else.
+async fn mock_provider(server: &MockServer, endpoint: &str, auth_header: &str, valid_value: &str) {
+    Mock::given(method("GET"))
+        .and(path(endpoint))
+        .and(header(auth_header, valid_value))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .with_priority(1)
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(endpoint))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({"error": "unauthorized"})))
+        .with_priority(2)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_deepgram_validate_credentials() {
+    let server = MockServer::start().await;
+    mock_provider(&server, "/v1/projects", "authorization", "Token valid-key").await;
+
+    assert!(
+        DeepgramTTS::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert!(
+        DeepgramSTT::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        DeepgramTTS::validate_credentials_at(&server.uri(), INVALID_KEY).await,
+        Err(INVALID_API_KEY.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_openai_validate_credentials() {
+    let server = MockServer::start().await;
+    mock_provider(&server, "/v1/models", "authorization", "Bearer valid-key").await;
+
+    assert!(
+        OpenAITTS::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert!(
+        OpenAISTT::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        OpenAISTT::validate_credentials_at(&server.uri(), INVALID_KEY).await,
+        Err(INVALID_API_KEY.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_elevenlabs_validate_credentials() {
+    let server = MockServer::start().await;
+    mock_provider(&server, "/v1/user", "xi-api-key", VALID_KEY).await;
+
+    assert!(
+        ElevenLabsTTS::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        ElevenLabsTTS::validate_credentials_at(&server.uri(), INVALID_KEY).await,
+        Err(INVALID_API_KEY.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_resemble_validate_credentials() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v2/voices"))
+        .and(query_param("page", "1"))
+        .and(query_param("page_size", "1"))
+        .and(header("authorization", "Bearer valid-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"items": []})))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v2/voices"))
+        .respond_with(ResponseTemplate::new(401))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    assert!(
+        ResembleCredentials::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        ResembleCredentials::validate_credentials_at(&server.uri(), INVALID_KEY).await,
+        Err(INVALID_API_KEY.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_cartesia_validate_credentials() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/voices"))
+        .and(header("x-api-key", VALID_KEY))
+        .and(header("cartesia-version", "2025-04-16"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/voices"))
+        .respond_with(ResponseTemplate::new(403))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    assert!(
+        CartesiaTTS::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        CartesiaTTS::validate_credentials_at(&server.uri(), INVALID_KEY).await,
+        Err(INVALID_API_KEY.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_groq_validate_credentials() {
+    let server = MockServer::start().await;
+    mock_provider(
+        &server,
+        "/openai/v1/models",
+        "authorization",
+        "Bearer valid-key",
+    )
+    .await;
+
+    assert!(
+        GroqSTT::validate_credentials_at(&server.uri(), VALID_KEY)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        GroqSTT::validate_credentials_at(&server.uri(), INVALID_KEY).await,
+        Err(INVALID_API_KEY.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_validate_credentials_unexpected_status() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/projects"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let err = DeepgramTTS::validate_credentials_at(&server.uri(), VALID_KEY)
+        .await
+        .unwrap_err();
+    assert!(err.contains("503"), "unexpected error: {err}");
+}
+
+#[tokio::test]
+async fn test_validate_credentials_unreachable_provider() {
+    // Port 1 is reserved and refuses connections
+    let err = OpenAITTS::validate_credentials_at("http://127.0.0.1:1", VALID_KEY)
+        .await
+        .unwrap_err();
+    assert!(
+        err.starts_with("Failed to reach provider"),
+        "unexpected error: {err}"
+    );
+}
+
+// Endpoint tests
+
+async fn create_test_state(auth_required: bool, auth_admin_ids: Vec<String>) -> Arc<AppState> {
+    let config = ServerConfig {
+        host: "localhost".to_string(),
+        port: 3001,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: vec![
+            AuthApiSecret {
+                id: "admin".to_string(),
+                secret: "admin-token".to_string(),
+            },
+            AuthApiSecret {
+                id: "client".to_string(),
+                secret: "client-token".to_string(),
+            },
+        ],
+        auth_timeout_seconds: 5,
+        auth_required,
+        auth_admin_ids,
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    };
+
+    AppState::new(config).await
+}
+
+fn admin_app(state: Arc<AppState>) -> axum::Router {
+    routes::admin::create_admin_router()
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin_auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state)
+}
+
+fn validate_request(uri: &str, token: Option<&str>, body: Value) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {token}"));
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+async fn response_json(response: axum::response::Response) -> Value {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_validate_endpoint_unsupported_provider_returns_404() {
+    let state = create_test_state(false, Vec::new()).await;
+    let response = admin_app(state)
+        .oneshot(validate_request(
+            "/providers/tts/lmnt/validate_credentials",
+            None,
+            json!({"api_key": "key"}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = response_json(response).await;
+    assert!(body["error"].as_str().unwrap().contains("lmnt"));
+}
+
+#[tokio::test]
+async fn test_validate_endpoint_empty_key_returns_400() {
+    let state = create_test_state(false, Vec::new()).await;
+    let response = admin_app(state)
+        .oneshot(validate_request(
+            "/providers/tts/deepgram/validate_credentials",
+            None,
+            json!({"api_key": "  "}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_validate_endpoint_requires_authentication() {
+    let state = create_test_state(true, vec!["admin".to_string()]).await;
+    let response = admin_app(state)
+        .oneshot(validate_request(
+            "/providers/tts/lmnt/validate_credentials",
+            None,
+            json!({"api_key": "key"}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_validate_endpoint_rejects_non_admin() {
+    let state = create_test_state(true, vec!["admin".to_string()]).await;
+    let response = admin_app(state)
+        .oneshot(validate_request(
+            "/providers/tts/lmnt/validate_credentials",
+            Some("client-token"),
+            json!({"api_key": "key"}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body = response_json(response).await;
+    assert_eq!(body["error"], "missing_scope");
+    assert_eq!(body["scope"], "admin:read");
+}
+
+#[tokio::test]
+async fn test_validate_endpoint_allows_admin() {
+    let state = create_test_state(true, vec!["admin".to_string()]).await;
+    let response = admin_app(state)
+        .oneshot(validate_request(
+            "/providers/tts/lmnt/validate_credentials",
+            Some("admin-token"),
+            json!({"api_key": "key"}),
+        ))
+        .await
+        .unwrap();
+
+    // Admin passes the gate and reaches the handler, which rejects the unknown provider
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
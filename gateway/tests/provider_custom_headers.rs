@@ -0,0 +1,215 @@
+//! # Provider Custom Headers Test
+//!
+//! Verifies that `TTSConfig::custom_headers` reach the provider over HTTP and
+//! that invalid header maps are rejected when a provider is created:
+//!
+//! 1. Custom headers are sent with every synthesis request.
+//! 2. A custom header replaces a header of the same name set by the provider.
+//! 3. Header values containing CRLF are rejected with a configuration error.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test provider_custom_headers
+//! ```
+
+use futures::Future;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use waav_gateway::core::stt::{STTConfig, STTError, create_stt_provider};
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError, create_tts_provider};
+use waav_gateway::core::tts::{TTSProvider, TTSRequestBuilder};
+use waav_gateway::utils::req_manager::ReqManager;
+
+/// Bytes of PCM returned by the mock provider (100ms at 24kHz linear16)
+const AUDIO_BYTES: usize = 4800;
+
+#[derive(Clone)]
+struct MockTTSRequestBuilder {
+    config: TTSConfig,
+    base_url: String,
+}
+
+impl TTSRequestBuilder for MockTTSRequestBuilder {
+    fn build_http_request(&self, client: &reqwest::Client, text: &str) -> reqwest::RequestBuilder {
+        client
+            .post(format!("{}/tts", self.base_url))
+            .header("Content-Type", "text/plain")
+            .header("X-Client", "provider-default")
+            .body(text.to_string())
+    }
+
+    fn get_config(&self) -> &TTSConfig {
+        &self.config
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingCallback {
+    audio_bytes: Arc<Mutex<usize>>,
+}
+
+impl AudioCallback for RecordingCallback {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            *self.audio_bytes.lock().await += audio_data.data.len();
+        })
+    }
+
+    fn on_error(&self, _error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+}
+
+/// Read one HTTP request and return its headers, lowercased names mapped to values
+async fn read_request_headers(stream: &mut TcpStream) -> HashMap<String, Vec<String>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            return HashMap::new();
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let text = String::from_utf8_lossy(&buf).to_string();
+        let Some(header_end) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let mut headers: HashMap<String, Vec<String>> = HashMap::new();
+        for line in text[..header_end].lines().skip(1) {
+            if let Some((name, value)) = line.split_once(':') {
+                headers
+                    .entry(name.trim().to_lowercase())
+                    .or_default()
+                    .push(value.trim().to_string());
+            }
+        }
+        let content_length = headers
+            .get("content-length")
+            .and_then(|v| v[0].parse::<usize>().ok())
+            .unwrap_or(0);
+        if buf.len() >= header_end + 4 + content_length {
+            return headers;
+        }
+    }
+}
+
+/// Start a mock TTS server that records the headers of every request
+async fn start_mock_server() -> (String, Arc<Mutex<Vec<HashMap<String, Vec<String>>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let captured = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let captured = captured.clone();
+            tokio::spawn(async move {
+                let headers = read_request_headers(&mut stream).await;
+                captured.lock().await.push(headers);
+
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    AUDIO_BYTES
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&vec![1u8; AUDIO_BYTES]).await;
+                let _ = stream.flush().await;
+            });
+        }
+    });
+
+    (format!("http://{}", addr), requests)
+}
+
+#[tokio::test]
+async fn test_custom_headers_sent_to_provider() {
+    let (base_url, requests) = start_mock_server().await;
+
+    let mut provider = TTSProvider::new().unwrap();
+    let req_manager = ReqManager::new(4).await.unwrap();
+    provider.set_req_manager(Arc::new(req_manager)).await;
+    let callback = RecordingCallback::default();
+    provider
+        .generic_on_audio(Arc::new(callback.clone()))
+        .unwrap();
+
+    let config = TTSConfig {
+        audio_format: Some("linear16".to_string()),
+        sample_rate: Some(24000),
+        custom_headers: HashMap::from([
+            ("X-Proxy-Auth".to_string(), "proxy-token-123".to_string()),
+            ("X-Client".to_string(), "custom-client".to_string()),
+        ]),
+        ..Default::default()
+    };
+    provider
+        .generic_connect_with_config(&base_url, &config)
+        .await
+        .unwrap();
+
+    let builder = MockTTSRequestBuilder {
+        config,
+        base_url: base_url.clone(),
+    };
+    provider
+        .generic_speak(builder.clone(), "first sentence", false)
+        .await
+        .unwrap();
+    provider
+        .generic_speak(builder, "second sentence", true)
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(*callback.audio_bytes.lock().await, AUDIO_BYTES * 2);
+
+    let requests = requests.lock().await;
+    let synthesis: Vec<_> = requests
+        .iter()
+        .filter(|headers| headers.contains_key("x-client"))
+        .collect();
+    assert_eq!(synthesis.len(), 2);
+    for headers in synthesis {
+        assert_eq!(headers["x-proxy-auth"], vec!["proxy-token-123".to_string()]);
+        // The custom value replaces the provider's own header
+        assert_eq!(headers["x-client"], vec!["custom-client".to_string()]);
+        assert_eq!(headers["content-type"], vec!["text/plain".to_string()]);
+    }
+}
+
+#[tokio::test]
+async fn test_crlf_header_value_rejected_on_creation() {
+    let config = TTSConfig {
+        provider: "deepgram".to_string(),
+        api_key: "test-key".to_string(),
+        custom_headers: HashMap::from([(
+            "X-Proxy-Auth".to_string(),
+            "token\r\nX-Injected: 1".to_string(),
+        )]),
+        ..Default::default()
+    };
+    let result = create_tts_provider("deepgram", config);
+    assert!(matches!(result, Err(TTSError::InvalidConfiguration(_))));
+
+    let config = STTConfig {
+        provider: "deepgram".to_string(),
+        api_key: "test-key".to_string(),
+        custom_headers: HashMap::from([("Bad Header".to_string(), "value".to_string())]),
+        ..Default::default()
+    };
+    let result = create_stt_provider("deepgram", config);
+    assert!(matches!(result, Err(STTError::ConfigurationError(_))));
+}
@@ -0,0 +1,218 @@
+//! # Provider Retry Tests
+//!
+//! Runs the shared HTTP TTS dispatch path (used by OpenAI and ElevenLabs) and
+//! the Groq Whisper client against a wiremock server that throttles the first
+//! request:
+//!
+//! 1. A 429 with `Retry-After` is retried after the requested delay and the
+//!    audio is still delivered.
+//! 2. A 429 for an exhausted quota is reported without retrying.
+//! 3. A 503 with `retry-after-ms` is retried by the Groq client.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test provider_retry
+//! ```
+
+use futures::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use waav_gateway::core::stt::{BaseSTT, GroqResponseFormat, GroqSTT, GroqSTTConfig, STTConfig};
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError};
+use waav_gateway::core::tts::{TTSProvider, TTSRequestBuilder};
+use waav_gateway::utils::req_manager::ReqManager;
+
+/// Bytes of PCM in a successful response (100ms at 24kHz linear16)
+const AUDIO_BYTES: usize = 4800;
+
+#[derive(Clone)]
+struct MockTTSRequestBuilder {
+    config: TTSConfig,
+    base_url: String,
+}
+
+impl TTSRequestBuilder for MockTTSRequestBuilder {
+    fn build_http_request(&self, client: &reqwest::Client, text: &str) -> reqwest::RequestBuilder {
+        client
+            .post(format!("{}/tts", self.base_url))
+            .header("Content-Type", "text/plain")
+            .body(text.to_string())
+    }
+
+    fn get_config(&self) -> &TTSConfig {
+        &self.config
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingCallback {
+    audio_bytes: Arc<Mutex<usize>>,
+    errors: Arc<Mutex<Vec<TTSError>>>,
+}
+
+impl AudioCallback for RecordingCallback {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            *self.audio_bytes.lock().await += audio_data.data.len();
+        })
+    }
+
+    fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            self.errors.lock().await.push(error);
+        })
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+}
+
+async fn speak(server: &MockServer, text: &str) -> RecordingCallback {
+    let mut provider = TTSProvider::new().unwrap();
+    let req_manager = ReqManager::new(4).await.unwrap();
+    provider.set_req_manager(Arc::new(req_manager)).await;
+
+    let callback = RecordingCallback::default();
+    provider
+        .generic_on_audio(Arc::new(callback.clone()))
+        .unwrap();
+
+    let config = TTSConfig {
+        provider: "retry-mock".to_string(),
+        audio_format: Some("linear16".to_string()),
+        sample_rate: Some(24000),
+        ..Default::default()
+    };
+    provider
+        .generic_connect_with_config(&server.uri(), &config)
+        .await
+        .unwrap();
+
+    let builder = MockTTSRequestBuilder {
+        config,
+        base_url: server.uri(),
+    };
+    provider.generic_speak(builder, text, true).await.unwrap();
+    callback
+}
+
+async fn request_count(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn test_tts_retries_after_retry_after() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "1")
+                .set_body_string(r#"{"detail":{"status":"too_many_concurrent_requests"}}"#),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![2u8; AUDIO_BYTES]))
+        .mount(&server)
+        .await;
+
+    let started = Instant::now();
+    let callback = speak(&server, "Hello after a pause.").await;
+
+    // The retry waits for the one second the server asked for
+    sleep(Duration::from_millis(700)).await;
+    assert_eq!(request_count(&server).await, 1);
+    assert_eq!(*callback.audio_bytes.lock().await, 0);
+
+    sleep(Duration::from_millis(1000)).await;
+    assert_eq!(request_count(&server).await, 2);
+    assert_eq!(*callback.audio_bytes.lock().await, AUDIO_BYTES);
+    assert!(callback.errors.lock().await.is_empty());
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_tts_does_not_retry_exhausted_quota() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "1")
+                .set_body_string(r#"{"error":{"code":"insufficient_quota"}}"#),
+        )
+        .mount(&server)
+        .await;
+
+    let callback = speak(&server, "Out of credits.").await;
+    sleep(Duration::from_millis(1500)).await;
+
+    assert_eq!(request_count(&server).await, 1);
+    let errors = callback.errors.lock().await.clone();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        TTSError::RateLimited {
+            retry_after_secs: Some(1),
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_groq_stt_retries_server_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after-ms", "200"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"text":"hello world"}"#))
+        .mount(&server)
+        .await;
+
+    let mut stt = GroqSTT::with_config(GroqSTTConfig {
+        base: STTConfig {
+            provider: "groq".to_string(),
+            api_key: "test-key".to_string(),
+            ..Default::default()
+        },
+        response_format: GroqResponseFormat::Json,
+        custom_endpoint: Some(format!("{}/openai/v1/audio/transcriptions", server.uri())),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let transcripts = Arc::new(Mutex::new(Vec::new()));
+    let sink = transcripts.clone();
+    stt.on_result(Arc::new(move |result| {
+        let sink = sink.clone();
+        Box::pin(async move {
+            sink.lock().await.push(result.transcript);
+        })
+    }))
+    .await
+    .unwrap();
+
+    stt.connect().await.unwrap();
+    let speech: Vec<u8> = (0..1600)
+        .flat_map(|i| ((i % 64) as i16 * 256).to_le_bytes())
+        .collect();
+    stt.send_audio(speech.into()).await.unwrap();
+
+    let started = Instant::now();
+    stt.flush().await.unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert_eq!(request_count(&server).await, 2);
+    assert_eq!(*transcripts.lock().await, vec!["hello world".to_string()]);
+}
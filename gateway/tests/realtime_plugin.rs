@@ -0,0 +1,337 @@
+//! # Realtime Plugin Integration Tests
+//!
+//! Drives the `/realtime` route against a realtime provider loaded from a
+//! dynamic plugin. The plugin is the purpose-built fixture crate in
+//! `tests/fixtures/mock-realtime-plugin`, compiled with cargo on first use
+//! and loaded into the global registry through `DynamicPluginLoader`.
+//!
+//! 1. A session config naming the plugin creates a session, and the voice
+//!    from the config reaches the plugin.
+//! 2. Text, audio (echoed from a plugin-owned thread), tool calls and plugin
+//!    errors all flow back to the client.
+//! 3. Unknown providers are rejected with the plugin listed as supported.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --features plugins-dynamic --test realtime_plugin
+//! ```
+
+#![cfg(feature = "plugins-dynamic")]
+
+use axum::middleware;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::OnceCell;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+
+use waav_gateway::plugin::{DynamicPluginLoader, PluginLoadStatus, PluginRegistry};
+use waav_gateway::{
+    ServerConfig, config::PluginConfig, middleware::auth::auth_middleware, routes, state::AppState,
+};
+
+const PLUGIN_ID: &str = "mock-realtime";
+
+/// How long to wait for each message from the gateway
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Client = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+static PLUGIN_REGISTRY: OnceCell<Arc<PluginRegistry>> = OnceCell::const_new();
+
+/// Build the fixture plugin and return the path of its library
+fn build_mock_plugin() -> PathBuf {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/mock-realtime-plugin/Cargo.toml");
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("mock-realtime-plugin");
+
+    let status = Command::new(env!("CARGO"))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("Failed to run cargo for the mock realtime plugin");
+    assert!(status.success(), "Building the mock realtime plugin failed");
+
+    target_dir.join("debug").join(format!(
+        "{}waav_plugin_mock_realtime{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+/// Registry with the fixture plugin, loaded once per test binary
+async fn plugin_registry() -> Arc<PluginRegistry> {
+    PLUGIN_REGISTRY
+        .get_or_init(|| async {
+            let library = build_mock_plugin();
+
+            // Only the library itself may be in the plugin directory
+            let plugin_dir = tempfile::tempdir().unwrap();
+            std::fs::copy(
+                &library,
+                plugin_dir.path().join(library.file_name().unwrap()),
+            )
+            .unwrap();
+
+            let registry = Arc::new(PluginRegistry::new_isolated());
+            let mut loader = DynamicPluginLoader::new();
+            let reports = loader
+                .load_all_from_directory(plugin_dir.path(), &registry)
+                .await
+                .unwrap();
+            assert_eq!(reports.len(), 1);
+            assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+            assert!(registry.has_realtime_provider(PLUGIN_ID));
+            loader.hand_over_to(&registry);
+            registry
+        })
+        .await
+        .clone()
+}
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Serve the realtime route on a local port, `None` if binding is not allowed
+async fn start_gateway() -> Option<SocketAddr> {
+    let app_state = AppState::with_plugin_registry(test_config(), plugin_registry().await).await;
+    let app = routes::realtime::create_realtime_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .with_state(app_state);
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping realtime plugin test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind realtime test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    Some(addr)
+}
+
+async fn connect(addr: SocketAddr) -> Client {
+    let (client, _) = connect_async(format!("ws://{addr}/realtime"))
+        .await
+        .expect("Failed to connect to /realtime");
+    client
+}
+
+async fn send_json(client: &mut Client, message: Value) {
+    client
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+async fn recv(client: &mut Client) -> Message {
+    tokio::time::timeout(RECV_TIMEOUT, client.next())
+        .await
+        .expect("Timed out waiting for the gateway")
+        .expect("Connection closed")
+        .unwrap()
+}
+
+async fn recv_json(client: &mut Client) -> Value {
+    match recv(client).await {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("Expected a JSON message, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_plugin_realtime_session() {
+    let Some(addr) = start_gateway().await else {
+        return;
+    };
+    let mut client = connect(addr).await;
+
+    send_json(
+        &mut client,
+        json!({"type": "config", "provider": PLUGIN_ID, "voice": "echo-voice"}),
+    )
+    .await;
+    let created = recv_json(&mut client).await;
+    assert_eq!(created["type"], "session_created");
+    assert_eq!(created["provider"], PLUGIN_ID);
+
+    // The voice from the session config reached the plugin
+    send_json(&mut client, json!({"type": "text", "text": "hello"})).await;
+    let transcript = recv_json(&mut client).await;
+    assert_eq!(transcript["type"], "transcript");
+    assert_eq!(transcript["text"], "[echo-voice] hello");
+    assert_eq!(transcript["role"], "assistant");
+    assert_eq!(transcript["is_final"], true);
+
+    // Audio and transcripts are delivered by separate callbacks, in any order
+    let audio: Vec<u8> = (0..320).map(|i| (i % 256) as u8).collect();
+    client
+        .send(Message::Binary(audio.clone().into()))
+        .await
+        .unwrap();
+    let mut echoed = None;
+    let mut user_transcript = None;
+    for _ in 0..2 {
+        match recv(&mut client).await {
+            Message::Binary(data) => echoed = Some(data.to_vec()),
+            Message::Text(text) => {
+                user_transcript = Some(serde_json::from_str::<Value>(&text).unwrap())
+            }
+            other => panic!("Unexpected message {other:?}"),
+        }
+    }
+    assert_eq!(echoed, Some(audio));
+    let user_transcript = user_transcript.expect("No transcript for the audio");
+    assert_eq!(user_transcript["text"], "received 320 bytes");
+    assert_eq!(user_transcript["role"], "user");
+
+    // Tool calls round-trip through the plugin
+    send_json(&mut client, json!({"type": "create_response"})).await;
+    let call = recv_json(&mut client).await;
+    assert_eq!(call["type"], "function_call");
+    assert_eq!(call["call_id"], "call-1");
+    assert_eq!(call["name"], "lookup");
+    assert_eq!(call["arguments"], r#"{"query": "weather"}"#);
+
+    send_json(
+        &mut client,
+        json!({"type": "function_result", "call_id": "call-1", "result": "sunny"}),
+    )
+    .await;
+    let answer = recv_json(&mut client).await;
+    assert_eq!(answer["text"], "tool call-1: sunny");
+
+    // Plugin error code 1 maps to a connection failure
+    send_json(&mut client, json!({"type": "cancel_response"})).await;
+    let error = recv_json(&mut client).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["code"], "provider_error");
+    assert_eq!(
+        error["message"],
+        "Connection failed: mock connection dropped"
+    );
+
+    client.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unknown_provider_lists_plugin() {
+    let Some(addr) = start_gateway().await else {
+        return;
+    };
+    let mut client = connect(addr).await;
+
+    send_json(
+        &mut client,
+        json!({"type": "config", "provider": "no-such-provider"}),
+    )
+    .await;
+    let error = recv_json(&mut client).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["code"], "invalid_provider");
+    let message = error["message"].as_str().unwrap();
+    assert!(message.contains("no-such-provider"));
+    assert!(message.contains(PLUGIN_ID));
+    assert!(message.contains("openai"));
+}
@@ -0,0 +1,251 @@
+//! # Recording Consent Integration Tests
+//!
+//! Runs sessions with a consent gate on in-process mock providers. The mock
+//! STT keeps every chunk it receives, so each test can check that no caller
+//! audio reached it before consent was granted:
+//!
+//! 1. Granting consent sends the held audio on, oldest first and ahead of
+//!    audio pushed afterwards, and the consent prompt plays while consent is
+//!    pending.
+//! 2. With `pending_audio: "drop"`, audio pushed before the grant never
+//!    reaches STT.
+//! 3. A denied session never sends audio on, and later decisions are ignored.
+//! 4. A session without a decision times out, and the usage reports it.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test recording_consent
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Clip, SttBehavior, SttCallbacks, mock_registry, mock_stt_config, mock_tts_config, stt_factory,
+    tts_factory,
+};
+use waav_gateway::core::session::{
+    ConsentConfig, ConsentSource, ConsentState, PendingAudioPolicy, Session, SessionEvent,
+    SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::stt::STTError;
+
+const MOCK_PROVIDER: &str = "recording-consent-mock";
+
+/// 100ms of 16kHz PCM16
+const CHUNK_BYTES: usize = 3200;
+
+/// Audio the mock STT received, in order
+type Received = Arc<Mutex<Vec<Bytes>>>;
+
+/// STT behavior that keeps the audio it receives and never transcribes
+struct KeepAudio {
+    received: Received,
+}
+
+#[async_trait]
+impl SttBehavior for KeepAudio {
+    async fn send_audio(
+        &mut self,
+        audio: Bytes,
+        _callbacks: &SttCallbacks,
+    ) -> Result<(), STTError> {
+        self.received.lock().push(audio);
+        Ok(())
+    }
+}
+
+async fn build_session(consent: ConsentConfig) -> (Session, SessionEventStream, Received) {
+    let received = Received::default();
+    let stt_received = received.clone();
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(move |_| KeepAudio {
+            received: stt_received.clone(),
+        }),
+        tts_factory(|_| Clip::silence(24000, 100)),
+    );
+    let session = SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(mock_tts_config(MOCK_PROVIDER))
+        .consent(consent)
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build");
+    let events = session.take_events().expect("events are taken once");
+    (session, events, received)
+}
+
+/// A chunk whose bytes all hold `marker`
+fn chunk(marker: u8) -> Bytes {
+    Bytes::from(vec![marker; CHUNK_BYTES])
+}
+
+/// Markers of the chunks the mock STT received
+fn markers(received: &Received) -> Vec<u8> {
+    received.lock().iter().map(|audio| audio[0]).collect()
+}
+
+/// Wait for the next `Consent` event
+async fn next_consent(events: &mut SessionEventStream, within: Duration) -> ConsentState {
+    tokio::time::timeout(within, async {
+        loop {
+            match events.recv().await {
+                Some(SessionEvent::Consent(record)) => return record.state,
+                Some(_) => continue,
+                None => panic!("event stream ended before a consent event"),
+            }
+        }
+    })
+    .await
+    .expect("consent event should arrive")
+}
+
+#[tokio::test]
+async fn test_grant_sends_held_audio_first() {
+    let (session, mut events, received) = build_session(ConsentConfig {
+        max_held_ms: 200,
+        ..Default::default()
+    })
+    .await;
+    assert!(!session.has_consent());
+
+    // The consent prompt plays while caller audio is held
+    session
+        .speak("May we record this call? Press 1 to agree.", true)
+        .await
+        .unwrap();
+    for marker in 1..=3 {
+        session.push_audio(chunk(marker)).await.unwrap();
+    }
+    let saw_prompt = tokio::time::timeout(Duration::from_secs(2), async {
+        while let Some(event) = events.recv().await {
+            if matches!(event, SessionEvent::Audio(_)) {
+                return true;
+            }
+        }
+        false
+    })
+    .await
+    .unwrap_or(false);
+    assert!(saw_prompt, "the prompt should play before consent");
+    assert!(markers(&received).is_empty(), "no audio before consent");
+    assert_eq!(session.usage().stt_audio_seconds, 0.0);
+
+    let record = session
+        .set_consent(true, ConsentSource::Dtmf, Some("sip_caller".to_string()))
+        .await
+        .unwrap()
+        .expect("first decision counts");
+    assert_eq!(record.state, ConsentState::Granted);
+    assert_eq!(
+        next_consent(&mut events, Duration::from_secs(1)).await,
+        ConsentState::Granted
+    );
+
+    session.push_audio(chunk(4)).await.unwrap();
+    // Only the latest 200ms were held
+    assert_eq!(markers(&received), vec![2, 3, 4]);
+    assert!(session.has_consent());
+
+    let usage = session.usage();
+    let consent = usage.consent.expect("usage reports consent");
+    assert_eq!(consent.state, ConsentState::Granted);
+    assert_eq!(consent.source, Some(ConsentSource::Dtmf));
+    assert_eq!(consent.participant.as_deref(), Some("sip_caller"));
+    assert!(consent.timestamp.is_some());
+}
+
+#[tokio::test]
+async fn test_drop_policy_discards_pending_audio() {
+    let (session, mut events, received) = build_session(ConsentConfig {
+        pending_audio: PendingAudioPolicy::Drop,
+        ..Default::default()
+    })
+    .await;
+
+    session.push_audio(chunk(1)).await.unwrap();
+    session.push_audio(chunk(2)).await.unwrap();
+    assert!(markers(&received).is_empty());
+
+    session
+        .set_consent(true, ConsentSource::Client, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        next_consent(&mut events, Duration::from_secs(1)).await,
+        ConsentState::Granted
+    );
+    session.push_audio(chunk(3)).await.unwrap();
+    assert_eq!(markers(&received), vec![3]);
+}
+
+#[tokio::test]
+async fn test_denied_session_never_sends_audio() {
+    let (session, mut events, received) = build_session(ConsentConfig::default()).await;
+
+    session.push_audio(chunk(1)).await.unwrap();
+    let record = session
+        .set_consent(false, ConsentSource::Client, None)
+        .await
+        .unwrap()
+        .expect("first decision counts");
+    assert_eq!(record.state, ConsentState::Denied);
+    assert_eq!(
+        next_consent(&mut events, Duration::from_secs(1)).await,
+        ConsentState::Denied
+    );
+
+    // A later grant is ignored
+    assert!(
+        session
+            .set_consent(true, ConsentSource::Dtmf, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    session.push_audio(chunk(2)).await.unwrap();
+    assert!(markers(&received).is_empty());
+    assert_eq!(
+        session.consent().map(|consent| consent.state),
+        Some(ConsentState::Denied)
+    );
+}
+
+#[tokio::test]
+async fn test_pending_consent_times_out() {
+    let (session, mut events, received) = build_session(ConsentConfig {
+        timeout_ms: 300,
+        ..Default::default()
+    })
+    .await;
+
+    session.push_audio(chunk(1)).await.unwrap();
+    assert_eq!(
+        next_consent(&mut events, Duration::from_secs(2)).await,
+        ConsentState::TimedOut
+    );
+    session.push_audio(chunk(2)).await.unwrap();
+    assert!(markers(&received).is_empty());
+
+    let consent = session.usage().consent.expect("usage reports consent");
+    assert_eq!(consent.state, ConsentState::TimedOut);
+    assert_eq!(consent.source, Some(ConsentSource::Timeout));
+
+    // Too late to grant
+    assert!(
+        session
+            .set_consent(true, ConsentSource::Client, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
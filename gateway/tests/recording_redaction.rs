@@ -0,0 +1,315 @@
+//! # Recording Redaction Integration Tests
+//!
+//! Scripts a session on `/ws` against mock providers that opens a redaction
+//! window with the client's `redaction` message and checks, byte for byte,
+//! that the session's monitor audio holds silence for the window only: the
+//! caller audio sent inside it and the TTS audio spoken inside it. The window
+//! is then expected in the session's exports.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test recording_redaction
+//! ```
+
+mod fixtures;
+
+use axum::middleware;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+use fixtures::session_mocks::{Clip, Silent, mock_registry, stt_factory, tts_factory};
+use waav_gateway::config::SessionExportConfig;
+use waav_gateway::core::session::AudioDirection;
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::state::decrypt_monitor_frame;
+use waav_gateway::{
+    ServerConfig, config::PluginConfig, middleware::auth::auth_middleware, routes, state::AppState,
+};
+
+const MOCK_PROVIDER: &str = "redaction-mock";
+
+const STREAM_ID: &str = "redaction-call";
+
+/// 100ms of 16kHz PCM16 speech
+const VOICED: [u8; 3200] = [0x40; 3200];
+
+/// Providers whose TTS speaks every utterance as a chunk of non-silent audio
+fn providers() -> Arc<PluginRegistry> {
+    let speech = Clip {
+        data: vec![0x20; 4800],
+        sample_rate: 24000,
+        duration_ms: Some(100),
+    };
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(move |_| speech.clone()),
+    )
+}
+
+fn test_config(export_dir: &Path) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: Some(SessionExportConfig {
+            local_dir: Some(export_dir.to_path_buf()),
+            public_base_url: Some("https://voice.example.com".to_string()),
+            signing_key: Some("a-dedicated-key-of-at-least-32-characters".to_string()),
+            ..Default::default()
+        }),
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Serve `/ws` on a local port, `None` if binding is not allowed
+async fn start_gateway(export_dir: &Path) -> Option<(SocketAddr, Arc<AppState>)> {
+    let app_state = AppState::with_plugin_registry(test_config(export_dir), providers()).await;
+    let app = routes::ws::create_ws_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .with_state(app_state.clone());
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping recording redaction test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind recording redaction test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    Some((addr, app_state))
+}
+
+/// Next JSON message of type `kind`; other messages and audio are skipped
+async fn next_of<S>(stream: &mut S, kind: &str) -> Value
+where
+    S: futures::Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(message) = stream.next().await {
+            if let Message::Text(text) = message.unwrap() {
+                let value: Value = serde_json::from_str(&text).unwrap();
+                if value["type"] == kind {
+                    return value;
+                }
+            }
+        }
+        panic!("connection closed");
+    })
+    .await
+    .unwrap_or_else(|_| panic!("no {kind} message"))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_redaction_window_silences_monitor_audio() {
+    let export_dir = tempfile::tempdir().unwrap();
+    let Some((addr, app_state)) = start_gateway(export_dir.path()).await else {
+        return;
+    };
+    let (ws, _) = connect_async(format!("ws://{addr}/ws")).await.unwrap();
+    let (mut sink, mut stream) = ws.split();
+
+    let config = json!({
+        "type": "config",
+        "stream_id": STREAM_ID,
+        "audio": true,
+        "stt_config": {
+            "provider": MOCK_PROVIDER,
+            "api_key": "test-key",
+            "language": "en-US",
+            "sample_rate": 16000,
+            "channels": 1,
+            "punctuation": true,
+            "encoding": "linear16",
+            "model": "mock",
+        },
+        "tts_config": {
+            "provider": MOCK_PROVIDER,
+            "api_key": "test-key",
+            "model": "mock",
+            "audio_format": "linear16",
+        },
+    });
+    sink.send(Message::Text(config.to_string().into()))
+        .await
+        .unwrap();
+    next_of(&mut stream, "ready").await;
+
+    let mut audio = app_state.session_events.subscribe_audio(STREAM_ID).unwrap();
+    let key = app_state
+        .session_events
+        .take_monitor_key(STREAM_ID)
+        .unwrap();
+
+    // Before, inside and after the window: two caller frames and a reply each
+    let mut phases = Vec::new();
+    for phase in ["before", "inside", "after"] {
+        if phase == "inside" {
+            let start = json!({"type": "redaction", "state": "start"});
+            sink.send(Message::Text(start.to_string().into()))
+                .await
+                .unwrap();
+            let ack = next_of(&mut stream, "redaction").await;
+            assert_eq!(ack["state"], "start");
+            assert_eq!(ack["source"], "client");
+        }
+        if phase == "after" {
+            let stop = json!({"type": "redaction", "state": "stop"});
+            sink.send(Message::Text(stop.to_string().into()))
+                .await
+                .unwrap();
+            assert_eq!(next_of(&mut stream, "redaction").await["state"], "stop");
+        }
+        for _ in 0..2 {
+            sink.send(Message::Binary(Bytes::from_static(&VOICED)))
+                .await
+                .unwrap();
+        }
+        let speak = json!({"type": "speak", "text": format!("reply {phase}")});
+        sink.send(Message::Text(speak.to_string().into()))
+            .await
+            .unwrap();
+        next_of(&mut stream, "tts_playback_complete").await;
+
+        // The phase's audio is published before its playback completes
+        let (mut caller, mut reply) = (Vec::new(), Vec::new());
+        while let Ok(frame) = audio.try_recv() {
+            let pcm =
+                decrypt_monitor_frame(&key, frame.direction, frame.counter, &frame.ciphertext)
+                    .unwrap();
+            match frame.direction {
+                AudioDirection::In => caller.push(pcm),
+                AudioDirection::Out => reply.push(pcm),
+            }
+        }
+        assert_eq!(caller.len(), 2, "caller frames {phase} the window");
+        assert!(!reply.is_empty(), "no reply {phase} the window");
+        phases.push((caller, reply));
+    }
+
+    let silent = |pcm: &Vec<u8>| !pcm.is_empty() && pcm.iter().all(|&byte| byte == 0);
+    let voiced = |pcm: &Vec<u8>| pcm.iter().any(|&byte| byte != 0);
+    for (caller, reply) in [&phases[0], &phases[2]] {
+        assert!(caller.iter().all(voiced));
+        assert!(reply.iter().any(voiced));
+    }
+    let (caller, reply) = &phases[1];
+    assert!(caller.iter().all(silent));
+    assert!(reply.iter().all(silent));
+
+    // The window is listed in the session's exports once it is closed
+    sink.send(Message::Close(None)).await.unwrap();
+    let folder = export_dir.path().join(STREAM_ID);
+    let redactions = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Ok(body) = std::fs::read(folder.join("redactions.json")) {
+                return serde_json::from_slice::<Value>(&body).unwrap();
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("redactions.json was not exported");
+    let windows = redactions["redactions"].as_array().unwrap();
+    assert_eq!(windows.len(), 1);
+    assert_eq!(windows[0]["source"], "client");
+    assert!(windows[0]["end_ms"].as_u64() >= windows[0]["start_ms"].as_u64());
+
+    let transcript: Value =
+        serde_json::from_slice(&std::fs::read(folder.join("transcript.json")).unwrap()).unwrap();
+    assert_eq!(transcript["recording_redactions"], redactions["redactions"]);
+}
@@ -0,0 +1,386 @@
+//! # Recording Replay Tests
+//!
+//! Replays generated WAV recordings through the in-process providers of
+//! `fixtures::session_mocks`. The recordings carry text as their PCM
+//! samples, and the mock STT "transcribes" the text it received once silence
+//! arrives, so the transcript of a replay is known in advance.
+//!
+//! 1. `replay::run` replays a file, compares it with the `transcript.txt`
+//!    next to it and writes the JSON report to `--output`.
+//! 2. `POST /admin/replay` replays a stored recording in the background and
+//!    `GET /admin/replay/{job_id}` returns the report once completed.
+//! 3. `DELETE /admin/replay/{job_id}` cancels a running job.
+//! 4. Jobs beyond `replay_max_concurrent_jobs` stay queued.
+//! 5. Requests are rejected without recording storage (503) or with an
+//!    invalid object key (400).
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test replay
+//! ```
+
+mod fixtures;
+
+use axum::{Router, body::Body, http::Request, http::StatusCode};
+use object_store::{ObjectStore, memory::InMemory, path::Path as ObjectPath};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+use fixtures::session_mocks::{Silent, UntilSilence, mock_registry, stt_factory, tts_factory};
+use waav_gateway::config::PluginConfig;
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::replay::{self, ReplayOptions, ReplaySpeed};
+use waav_gateway::{ServerConfig, routes, state::AppState};
+
+const MOCK_PROVIDER: &str = "replay-mock";
+
+/// Providers whose STT transcribes the text it receives as audio; the TTS
+/// never speaks during a replay
+fn providers() -> Arc<PluginRegistry> {
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| {
+            UntilSilence::new(|audio| {
+                let text: Vec<u8> = audio.iter().copied().filter(|&byte| byte != 0).collect();
+                String::from_utf8_lossy(&text).into_owned()
+            })
+        }),
+        tts_factory(|_| Silent),
+    )
+}
+
+fn test_config(replay_max_concurrent_jobs: usize) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+fn session_config() -> Value {
+    json!({
+        "stt_config": {
+            "provider": MOCK_PROVIDER,
+            "language": "en-US",
+            "sample_rate": 16000,
+            "channels": 1,
+            "punctuation": true,
+            "encoding": "linear16",
+            "model": "mock-model"
+        },
+        "tts_config": {"provider": MOCK_PROVIDER, "model": "mock-voice"}
+    })
+}
+
+/// 16 kHz WAV whose PCM bytes are `text`, followed by `silence_ms` of silence
+fn text_wav(text: &str, silence_ms: u32) -> Vec<u8> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+    for pair in text.as_bytes().chunks(2) {
+        let sample = i16::from_le_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]);
+        writer.write_sample(sample).unwrap();
+    }
+    for _ in 0..16 * silence_ms {
+        writer.write_sample(0i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    cursor.into_inner()
+}
+
+/// App state whose recording bucket is an in-memory store
+async fn state_with_store(replay_max_concurrent_jobs: usize) -> (Arc<AppState>, Arc<InMemory>) {
+    let store = Arc::new(InMemory::new());
+    let state =
+        AppState::with_plugin_registry(test_config(replay_max_concurrent_jobs), providers()).await;
+    let mut state = (*state).clone();
+    state.object_store = Some(store.clone());
+    (Arc::new(state), store)
+}
+
+async fn put(store: &InMemory, key: &str, data: Vec<u8>) {
+    store
+        .put(&ObjectPath::from(key), data.into())
+        .await
+        .unwrap();
+}
+
+async fn request(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let body = match body {
+        Some(body) => Body::from(body.to_string()),
+        None => Body::empty(),
+    };
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Poll a job until it reports `status`
+async fn wait_for_status(app: &Router, job_id: &str, status: &str) -> Value {
+    for _ in 0..200 {
+        let (_, job) = request(app, "GET", &format!("/admin/replay/{job_id}"), None).await;
+        if job["status"] == status {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("replay job {job_id} never reached {status}");
+}
+
+#[tokio::test]
+async fn test_replay_file_with_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    let recording = dir.path().join("audio.wav");
+    std::fs::write(&recording, text_wav("move my appointment to sunday", 200)).unwrap();
+    std::fs::write(
+        dir.path().join(replay::REFERENCE_TRANSCRIPT_FILE),
+        "Move my appointment to Friday.\n",
+    )
+    .unwrap();
+    let session_path = dir.path().join("session.json");
+    std::fs::write(&session_path, session_config().to_string()).unwrap();
+    let output = dir.path().join("report.json");
+
+    let options = ReplayOptions {
+        file: Some(recording),
+        s3_key: None,
+        session_config: session_path,
+        speed: ReplaySpeed::Max,
+        reference: None,
+        output: Some(output.clone()),
+        json: false,
+    };
+    let report = replay::run(&options, &test_config(1), &providers())
+        .await
+        .unwrap();
+
+    assert_eq!(report.transcript, "move my appointment to sunday");
+    assert_eq!(report.final_results, 1);
+    assert_eq!(report.stt_provider, MOCK_PROVIDER);
+    assert_eq!(
+        report.reference.as_deref(),
+        Some("Move my appointment to Friday.")
+    );
+    // One substitution out of five words
+    assert!((report.wer.unwrap() - 0.2).abs() < 1e-9);
+    assert!(report.errors.is_empty());
+    assert!(report.to_string().contains("WER:        20.0%"));
+
+    let written: Value = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+    assert_eq!(written["transcript"], "move my appointment to sunday");
+    assert_eq!(written["speed"], "max");
+}
+
+#[tokio::test]
+async fn test_admin_replay_job_completes() {
+    let (state, store) = state_with_store(1).await;
+    put(
+        &store,
+        "calls/call-1/audio.wav",
+        text_wav("hello there", 200),
+    )
+    .await;
+    put(
+        &store,
+        "calls/call-1/transcript.txt",
+        b"hello there".to_vec(),
+    )
+    .await;
+    let app = routes::admin::create_admin_router().with_state(state);
+
+    let (status, job) = request(
+        &app,
+        "POST",
+        "/admin/replay",
+        Some(json!({
+            "s3_key": "calls/call-1/audio.wav",
+            "session": session_config(),
+            "speed": "max"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(job["s3_key"], "calls/call-1/audio.wav");
+    assert_eq!(job["stt_provider"], MOCK_PROVIDER);
+    let job_id = job["id"].as_str().unwrap().to_string();
+
+    let job = wait_for_status(&app, &job_id, "completed").await;
+    assert_eq!(job["report"]["transcript"], "hello there");
+    assert_eq!(job["report"]["wer"], 0.0);
+    assert!(job["finished_at"].is_u64());
+
+    let (status, list) = request(&app, "GET", "/admin/replay", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list["jobs"][0]["id"], job_id);
+
+    // Deleting a finished job forgets it
+    let (status, _) = request(&app, "DELETE", &format!("/admin/replay/{job_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(&app, "GET", &format!("/admin/replay/{job_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_replay_job_cancel_and_queue() {
+    let (state, store) = state_with_store(1).await;
+    // A minute of real-time audio keeps the first job running
+    put(&store, "calls/long.wav", text_wav("long call", 60_000)).await;
+    let app = routes::admin::create_admin_router().with_state(state);
+    let body = json!({"s3_key": "calls/long.wav", "session": session_config()});
+
+    let (_, first) = request(&app, "POST", "/admin/replay", Some(body.clone())).await;
+    let first_id = first["id"].as_str().unwrap().to_string();
+    wait_for_status(&app, &first_id, "running").await;
+
+    // The only slot is taken
+    let (_, second) = request(&app, "POST", "/admin/replay", Some(body)).await;
+    let second_id = second["id"].as_str().unwrap().to_string();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (_, job) = request(&app, "GET", &format!("/admin/replay/{second_id}"), None).await;
+    assert_eq!(job["status"], "queued");
+
+    let (status, job) = request(&app, "DELETE", &format!("/admin/replay/{first_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(job["status"], "running");
+    let job = wait_for_status(&app, &first_id, "cancelled").await;
+    assert!(job.get("report").is_none());
+
+    // The freed slot goes to the queued job
+    wait_for_status(&app, &second_id, "running").await;
+    request(&app, "DELETE", &format!("/admin/replay/{second_id}"), None).await;
+    wait_for_status(&app, &second_id, "cancelled").await;
+
+    let (status, _) = request(&app, "DELETE", "/admin/replay/unknown", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_replay_rejects_invalid_requests() {
+    let app = routes::admin::create_admin_router().with_state(AppState::new(test_config(1)).await);
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/admin/replay",
+        Some(json!({"s3_key": "calls/call-1/audio.wav", "session": session_config()})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"], "Recording storage not configured");
+
+    let (state, _) = state_with_store(1).await;
+    let app = routes::admin::create_admin_router().with_state(state);
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/admin/replay",
+        Some(json!({"s3_key": "calls/../secrets", "session": session_config()})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
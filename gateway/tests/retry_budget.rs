@@ -0,0 +1,213 @@
+//! # Retry Budget Integration Tests
+//!
+//! Runs a session whose providers are down on in-process mocks: STT and TTS
+//! connect once, never answer, and every reconnect fails. The watchdog keeps
+//! reconnecting both while a voice-list refresh loop keeps fetching voices
+//! from a failing mock, all drawing from the session's retry budget:
+//!
+//! 1. The attempts of all three together stay within the budget's capacity
+//!    plus its refill.
+//! 2. Every attempt is counted as spent, the ones held back as denied, and
+//!    STT reconnects get at least as many tokens as TTS retries.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test retry_budget
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use fixtures::session_mocks::{
+    SttBehavior, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory,
+    tts_factory,
+};
+use waav_gateway::core::session::{
+    PipelineWatchdogConfig, RetryBudget, RetryBudgetConfig, RetryPriority, Session,
+    SessionPipelineBuilder,
+};
+use waav_gateway::core::stt::STTError;
+use waav_gateway::core::tts::{TTSError, TTSResult};
+
+const MOCK_PROVIDER: &str = "retry-budget-mock";
+
+const STALL_TIMEOUT_MS: u64 = 100;
+
+/// How long all three components keep retrying
+const RETRY_WINDOW: Duration = Duration::from_millis(1_500);
+
+/// 20ms of 16kHz PCM16
+const CHUNK_BYTES: usize = 640;
+
+const BUDGET: RetryBudgetConfig = RetryBudgetConfig {
+    capacity: 4,
+    refill_per_minute: 60,
+    stt_reserve: 1,
+    tts_reserve: 1,
+};
+
+/// Connection attempts of the mocks, including the first connect
+static STT_CONNECTS: AtomicUsize = AtomicUsize::new(0);
+static TTS_CONNECTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Voice-list fetches sent to the failing mock
+static VOICE_FETCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// Provider that connects once and fails every reconnect
+struct DownAfterFirstConnect {
+    connects: &'static AtomicUsize,
+}
+
+impl DownAfterFirstConnect {
+    fn try_connect(&self) -> Result<(), String> {
+        if self.connects.fetch_add(1, Ordering::SeqCst) > 0 {
+            return Err("provider is down".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SttBehavior for DownAfterFirstConnect {
+    async fn connect(&mut self) -> Result<(), STTError> {
+        self.try_connect().map_err(STTError::ConnectionFailed)
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for DownAfterFirstConnect {
+    async fn connect(&mut self) -> TTSResult<()> {
+        self.try_connect().map_err(TTSError::ConnectionFailed)
+    }
+}
+
+async fn build_session() -> Session {
+    // STT swallows audio and TTS accepts utterances, neither ever answers
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| DownAfterFirstConnect {
+            connects: &STT_CONNECTS,
+        }),
+        tts_factory(|_| DownAfterFirstConnect {
+            connects: &TTS_CONNECTS,
+        }),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(mock_tts_config(MOCK_PROVIDER))
+        .watchdog(PipelineWatchdogConfig {
+            stall_timeout_ms: STALL_TIMEOUT_MS,
+            min_speech_ms: 50,
+            replay_ms: 100,
+            max_recoveries: 100,
+        })
+        .retry_budget(BUDGET)
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+/// 20ms of loud PCM16
+fn chunk() -> Bytes {
+    let value = (0.3 * i16::MAX as f64) as i16;
+    (0..CHUNK_BYTES / 2)
+        .flat_map(|i| {
+            let sample = if i % 2 == 0 { value } else { -value };
+            sample.to_le_bytes()
+        })
+        .collect()
+}
+
+/// Fetch the voice list from the mock provider, which is down
+async fn fetch_voices() -> Result<Vec<String>, String> {
+    VOICE_FETCHES.fetch_add(1, Ordering::SeqCst);
+    Err("provider is down".to_string())
+}
+
+/// Refresh the voice list every 20ms, with a token from the session's budget
+async fn refresh_voices(budget: Arc<RetryBudget>) {
+    loop {
+        if budget.try_acquire(RetryPriority::VoiceListRefresh) {
+            let _ = fetch_voices().await;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_budget_caps_attempts_of_all_components() {
+    let started = Instant::now();
+    let session = build_session().await;
+    let budget = session
+        .retry_budget()
+        .expect("retry budget is configured")
+        .clone();
+
+    // TTS never answers the utterance, STT never answers the caller, and
+    // voices are refreshed all the while
+    let refresh = tokio::spawn(refresh_voices(budget));
+    session.speak("Hello there.", true).await.unwrap();
+    while started.elapsed() < RETRY_WINDOW {
+        session.push_audio(chunk()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    refresh.abort();
+    session.close().await.unwrap();
+    let elapsed = started.elapsed();
+
+    let stt_reconnects = STT_CONNECTS.load(Ordering::SeqCst) - 1;
+    let tts_reconnects = TTS_CONNECTS.load(Ordering::SeqCst) - 1;
+    let fetches = VOICE_FETCHES.load(Ordering::SeqCst);
+    let attempts = (stt_reconnects + tts_reconnects + fetches) as u64;
+
+    // A full bucket plus what refilled while the session ran
+    let cap = BUDGET.capacity as u64
+        + (BUDGET.refill_per_minute as f64 * elapsed.as_secs_f64() / 60.0).ceil() as u64;
+    assert!(
+        attempts <= cap,
+        "{attempts} attempts (stt {stt_reconnects}, tts {tts_reconnects}, \
+         voices {fetches}) exceed the cap of {cap}"
+    );
+
+    let stats = session.retry_budget_stats().expect("budget stats");
+    assert_eq!(stats.stt_reconnect.spent, stt_reconnects as u64);
+    assert_eq!(stats.tts_retry.spent, tts_reconnects as u64);
+    assert_eq!(stats.voice_list_refresh.spent, fetches as u64);
+    assert_eq!(stats.spent(), attempts);
+
+    // Each component retried and was held back
+    assert!(stats.stt_reconnect.spent >= 1, "{stats:?}");
+    assert!(
+        stats.tts_retry.spent + stats.tts_retry.denied >= 1,
+        "{stats:?}"
+    );
+    assert!(stats.voice_list_refresh.denied > 0, "{stats:?}");
+    assert!(stats.denied() > stats.spent(), "{stats:?}");
+
+    // STT reconnects take precedence over TTS retries
+    assert!(
+        stats.stt_reconnect.spent >= stats.tts_retry.spent,
+        "{stats:?}"
+    );
+    // Refreshes never dip into the reserves of STT and TTS
+    assert!(
+        stats.voice_list_refresh.spent <= cap - (BUDGET.stt_reserve + BUDGET.tts_reserve) as u64,
+        "{stats:?}"
+    );
+
+    // Failed reconnects are counted, not reported as recoveries
+    let watchdog = session.watchdog_stats().expect("watchdog is configured");
+    assert_eq!(watchdog.stt_recoveries + watchdog.tts_recoveries, 0);
+    assert_eq!(
+        watchdog.failed_recoveries,
+        (stt_reconnects + tts_reconnects) as u64
+    );
+}
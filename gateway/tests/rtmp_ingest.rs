@@ -0,0 +1,557 @@
+//! # RTMP Ingest Tests
+//!
+//! Publishes to the RTMP ingest listener and follows the session it opens
+//! through the session event bus that feeds `/sessions/{id}/monitor` and
+//! `/sessions/{id}/events`:
+//!
+//! 1. A synthetic publisher (simple handshake, AMF0 commands, 16-bit PCM
+//!    stereo at 44.1kHz) is accepted once the agent profile's session is
+//!    ready; its audio arrives downmixed and resampled to 16kHz and the
+//!    transcript is published to observers. Unpublishing closes the session.
+//! 2. Unknown stream keys and a second publisher on a live stream are refused.
+//! 3. With ffmpeg installed, a generated AAC stream is transcribed as well.
+//!
+//! The mock STT answers each burst of non-silent audio with a final
+//! transcript naming the size of the burst's first chunk.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --features ingest --test rtmp_ingest
+//! ```
+
+#![cfg(feature = "ingest")]
+
+mod fixtures;
+
+use bytes::Bytes;
+use serde_json::json;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
+
+use fixtures::session_mocks::{PerBurst, Silent, mock_registry, stt_factory, tts_factory};
+use waav_gateway::agents::AgentProfile;
+use waav_gateway::config::{IngestConfig, IngestStreamConfig, PluginConfig};
+use waav_gateway::core::stt::STTResult;
+use waav_gateway::ingest::RtmpIngest;
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::state::session_events::Subscription;
+use waav_gateway::{ServerConfig, state::AppState};
+
+const MOCK_PROVIDER: &str = "rtmp-ingest-mock";
+const STREAM_KEY: &str = "sk_test_5f2b9c1e";
+const SESSION_ID: &str = "news-1";
+
+/// FLV audio tag header: 16-bit little-endian PCM, 44.1kHz, stereo
+const PCM_44K_STEREO: u8 = 0x3F;
+
+/// Providers whose STT captions each burst of audio; the TTS never speaks,
+/// as ingest sessions only transcribe
+fn providers() -> Arc<PluginRegistry> {
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| {
+            PerBurst::new(|burst, audio| {
+                let text = format!("caption {burst}: {} bytes", audio.len());
+                vec![STTResult::new(text, true, true, 0.9)]
+            })
+        }),
+        tts_factory(|_| Silent),
+    )
+}
+
+fn captions_agent() -> AgentProfile {
+    let session = json!({
+        "stt_config": {
+            "provider": MOCK_PROVIDER,
+            "language": "en-US",
+            "sample_rate": 16000,
+            "channels": 1,
+            "punctuation": true,
+            "encoding": "linear16",
+            "model": "mock",
+            "api_key": "test-key"
+        },
+        "tts_config": {
+            "provider": MOCK_PROVIDER,
+            "voice_id": "mock-voice",
+            "model": "mock",
+            "api_key": "test-key"
+        }
+    });
+    AgentProfile {
+        name: "captions".to_string(),
+        description: None,
+        clients: vec!["newsroom".to_string()],
+        overridable: vec![],
+        session: session.as_object().cloned(),
+        realtime: None,
+    }
+}
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: vec![captions_agent()],
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: Some(IngestConfig {
+            rtmp_address: "127.0.0.1:0".to_string(),
+            streams: vec![IngestStreamConfig {
+                key: STREAM_KEY.to_string(),
+                name: SESSION_ID.to_string(),
+                agent: "captions".to_string(),
+                client_id: Some("newsroom".to_string()),
+                metadata: [("channel".to_string(), "news".to_string())].into(),
+            }],
+            max_streams: 4,
+            session_timeout_ms: 5_000,
+        }),
+        flight_recorder: None,
+    }
+}
+
+/// Start the ingest listener, `None` if binding is not allowed
+async fn start_ingest() -> Option<(Arc<AppState>, SocketAddr)> {
+    let config = test_config();
+    let ingest = config.ingest.clone().unwrap();
+    let app_state = AppState::with_plugin_registry(config, providers()).await;
+    let listener = match RtmpIngest::bind(ingest, app_state.clone()).await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping RTMP ingest test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind RTMP ingest listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(listener.run());
+    Some((app_state, addr))
+}
+
+fn amf_string(value: &str) -> Vec<u8> {
+    let mut out = vec![0x02];
+    out.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    out.extend_from_slice(value.as_bytes());
+    out
+}
+
+fn amf_number(value: f64) -> Vec<u8> {
+    let mut out = vec![0x00];
+    out.extend_from_slice(&value.to_be_bytes());
+    out
+}
+
+fn amf_object(fields: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut out = vec![0x03];
+    for (key, value) in fields {
+        out.extend_from_slice(&(key.len() as u16).to_be_bytes());
+        out.extend_from_slice(key.as_bytes());
+        out.extend_from_slice(value);
+    }
+    out.extend_from_slice(&[0, 0, 0x09]);
+    out
+}
+
+const AMF_NULL: u8 = 0x05;
+
+/// Minimal RTMP publisher: simple handshake and 128-byte chunks
+struct Publisher {
+    stream: TcpStream,
+    /// Everything the server sent after the handshake
+    received: Vec<u8>,
+}
+
+impl Publisher {
+    async fn connect(addr: SocketAddr) -> Self {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let mut c0c1 = vec![3u8];
+        c0c1.extend((0..1536).map(|i| (i % 256) as u8));
+        stream.write_all(&c0c1).await.unwrap();
+
+        let mut s0s1s2 = vec![0u8; 1 + 2 * 1536];
+        stream.read_exact(&mut s0s1s2).await.unwrap();
+        assert_eq!(s0s1s2[0], 3);
+        assert_eq!(&s0s1s2[1 + 1536..], &c0c1[1..], "S2 echoes C1");
+        stream.write_all(&s0s1s2[1..1 + 1536]).await.unwrap();
+
+        Self {
+            stream,
+            received: Vec::new(),
+        }
+    }
+
+    async fn send(&mut self, csid: u8, type_id: u8, stream_id: u32, payload: &[u8]) {
+        let mut out = vec![csid];
+        out.extend_from_slice(&[0, 0, 0]);
+        out.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+        out.push(type_id);
+        out.extend_from_slice(&stream_id.to_le_bytes());
+        for (index, chunk) in payload.chunks(128).enumerate() {
+            if index > 0 {
+                out.push(0xC0 | csid);
+            }
+            out.extend_from_slice(chunk);
+        }
+        self.stream.write_all(&out).await.unwrap();
+    }
+
+    async fn command(&mut self, stream_id: u32, values: &[Vec<u8>]) {
+        self.send(3, 20, stream_id, &values.concat()).await;
+    }
+
+    /// Connect, create a stream and publish `name`
+    async fn publish(&mut self, name: &str) {
+        self.command(
+            0,
+            &[
+                amf_string("connect"),
+                amf_number(1.0),
+                amf_object(&[
+                    ("app", amf_string("live")),
+                    ("type", amf_string("nonprivate")),
+                    ("tcUrl", amf_string("rtmp://127.0.0.1/live")),
+                ]),
+            ],
+        )
+        .await;
+        assert!(self.wait_for("NetConnection.Connect.Success").await);
+
+        self.command(
+            0,
+            &[
+                amf_string("releaseStream"),
+                amf_number(2.0),
+                vec![AMF_NULL],
+                amf_string(name),
+            ],
+        )
+        .await;
+        self.command(
+            0,
+            &[amf_string("createStream"), amf_number(3.0), vec![AMF_NULL]],
+        )
+        .await;
+        self.command(
+            1,
+            &[
+                amf_string("publish"),
+                amf_number(0.0),
+                vec![AMF_NULL],
+                amf_string(name),
+                amf_string("live"),
+            ],
+        )
+        .await;
+    }
+
+    /// 100ms of 44.1kHz stereo PCM with the given sample values
+    async fn send_audio(&mut self, left: i16, right: i16) {
+        let mut tag = vec![PCM_44K_STEREO];
+        for _ in 0..4410 {
+            tag.extend_from_slice(&left.to_le_bytes());
+            tag.extend_from_slice(&right.to_le_bytes());
+        }
+        self.send(4, 8, 1, &tag).await;
+    }
+
+    async fn unpublish(&mut self) {
+        self.command(
+            0,
+            &[
+                amf_string("deleteStream"),
+                amf_number(4.0),
+                vec![AMF_NULL],
+                amf_number(1.0),
+            ],
+        )
+        .await;
+    }
+
+    /// Read until the server has sent `needle`; false if it closed first
+    async fn wait_for(&mut self, needle: &str) -> bool {
+        let needle = needle.as_bytes();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if self.received.windows(needle.len()).any(|w| w == needle) {
+                    return true;
+                }
+                let mut buf = [0u8; 4096];
+                match self.stream.read(&mut buf).await {
+                    Ok(0) | Err(_) => return false,
+                    Ok(n) => self.received.extend_from_slice(&buf[..n]),
+                }
+            }
+        })
+        .await
+        .expect("RTMP server did not answer in time")
+    }
+
+    /// Read until the server closes the connection
+    async fn wait_for_close(&mut self) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = self.stream.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("RTMP server did not close the connection");
+    }
+}
+
+/// Wait for the first observed event of `event_type`, returning its JSON
+async fn next_event(subscription: &mut Subscription, event_type: &str) -> serde_json::Value {
+    if let Some(event) = subscription
+        .replay
+        .iter()
+        .find(|event| event.event_type == event_type)
+    {
+        return serde_json::from_str(&event.data).unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match subscription.receiver.recv().await {
+                Ok(event) if event.event_type == event_type => {
+                    return serde_json::from_str(&event.data).unwrap();
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => panic!("session closed before a {event_type} event"),
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("no {event_type} event"))
+}
+
+/// Wait until the session's event channel is closed
+async fn wait_for_session_end(app_state: &AppState) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while app_state.session_events.owner(SESSION_ID).is_some() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("session was not closed");
+}
+
+#[tokio::test]
+async fn test_published_audio_is_transcribed_in_agent_session() {
+    let Some((app_state, addr)) = start_ingest().await else {
+        return;
+    };
+
+    let mut publisher = Publisher::connect(addr).await;
+    publisher.publish(STREAM_KEY).await;
+    assert!(publisher.wait_for("NetStream.Publish.Start").await);
+
+    // The session belongs to the stream's client, as if opened on /ws
+    assert_eq!(
+        app_state.session_events.owner(SESSION_ID),
+        Some(Some("newsroom".to_string()))
+    );
+    let mut subscription = app_state
+        .session_events
+        .subscribe(SESSION_ID, Some(0))
+        .unwrap();
+    let ready = next_event(&mut subscription, "ready").await;
+    assert_eq!(ready["stream_id"], SESSION_ID);
+
+    // Stereo at 44.1kHz arrives as mono at the profile's 16kHz:
+    // 100ms is 1600 samples, and left and right are averaged
+    publisher.send_audio(0, 0).await;
+    publisher.send_audio(4000, 2000).await;
+    let result = next_event(&mut subscription, "stt_result").await;
+    assert_eq!(result["transcript"], "caption 1: 3200 bytes");
+    assert_eq!(result["is_final"], true);
+
+    publisher.unpublish().await;
+    publisher.wait_for_close().await;
+    wait_for_session_end(&app_state).await;
+
+    // The stream key can publish again once the session has ended
+    let mut again = Publisher::connect(addr).await;
+    again.publish(STREAM_KEY).await;
+    assert!(again.wait_for("NetStream.Publish.Start").await);
+}
+
+#[tokio::test]
+async fn test_unknown_keys_and_duplicate_publishers_are_refused() {
+    let Some((app_state, addr)) = start_ingest().await else {
+        return;
+    };
+
+    let mut stranger = Publisher::connect(addr).await;
+    stranger.publish("not-a-stream-key").await;
+    assert!(stranger.wait_for("NetStream.Publish.BadName").await);
+
+    // An agent the stream's client may not use
+    let mut other_agent = Publisher::connect(addr).await;
+    other_agent
+        .publish(&format!("{STREAM_KEY}?agent=missing-agent"))
+        .await;
+    assert!(other_agent.wait_for("NetStream.Publish.BadName").await);
+
+    let mut first = Publisher::connect(addr).await;
+    first.publish(STREAM_KEY).await;
+    assert!(first.wait_for("NetStream.Publish.Start").await);
+
+    let mut second = Publisher::connect(addr).await;
+    second.publish(STREAM_KEY).await;
+    assert!(second.wait_for("NetStream.Publish.Denied").await);
+    assert!(second.wait_for("already publishing").await);
+
+    // A different session ID on the same key is a separate stream
+    let mut renamed = Publisher::connect(addr).await;
+    renamed
+        .publish(&format!("{STREAM_KEY}?stream_id=news-1-backup"))
+        .await;
+    assert!(renamed.wait_for("NetStream.Publish.Start").await);
+    assert!(app_state.session_events.owner("news-1-backup").is_some());
+}
+
+#[tokio::test]
+async fn test_ffmpeg_aac_stream_is_transcribed() {
+    let ffmpeg = tokio::process::Command::new("ffmpeg")
+        .arg("-version")
+        .output()
+        .await;
+    if !ffmpeg.is_ok_and(|output| output.status.success()) {
+        eprintln!("Skipping ffmpeg RTMP ingest test: ffmpeg is not installed");
+        return;
+    }
+    let Some((app_state, addr)) = start_ingest().await else {
+        return;
+    };
+
+    let mut ffmpeg = tokio::process::Command::new("ffmpeg")
+        .args([
+            "-loglevel",
+            "error",
+            "-re",
+            "-f",
+            "lavfi",
+            "-i",
+            "sine=frequency=440:sample_rate=48000:duration=3",
+            "-c:a",
+            "aac",
+            "-ac",
+            "2",
+            "-f",
+            "flv",
+        ])
+        .arg(format!("rtmp://{addr}/live/{STREAM_KEY}"))
+        .kill_on_drop(true)
+        .spawn()
+        .unwrap();
+
+    // Subscribe as soon as the session exists
+    let mut subscription = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(subscription) = app_state.session_events.subscribe(SESSION_ID, Some(0)) {
+                return subscription;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("ffmpeg publish did not open a session");
+
+    let result = next_event(&mut subscription, "stt_result").await;
+    assert!(
+        result["transcript"]
+            .as_str()
+            .unwrap()
+            .starts_with("caption 1:")
+    );
+
+    let status = tokio::time::timeout(Duration::from_secs(15), ffmpeg.wait())
+        .await
+        .expect("ffmpeg did not finish")
+        .unwrap();
+    assert!(status.success());
+    wait_for_session_end(&app_state).await;
+}
@@ -0,0 +1,289 @@
+//! # Provider Self-Test Integration Tests
+//!
+//! Runs the startup self-test against the in-process providers of
+//! `fixtures::session_mocks`, registered through the provider registry. The
+//! mock TTS "synthesizes" text by sending its UTF-8 bytes as audio, and the
+//! mock STT "transcribes" the audio it received once trailing silence
+//! arrives, so the canary phrase round-trips exactly. A second STT returns an unrelated transcript.
+//!
+//! 1. A matching transcript passes and a required self-test then reports the
+//!    gateway as ready on `/readyz`, with the result in `/metrics`.
+//! 2. A mismatching transcript fails; it only blocks readiness when the
+//!    self-test is required.
+//! 3. Built-in providers without credentials are skipped without blocking
+//!    readiness.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test selftest
+//! ```
+
+mod fixtures;
+
+use axum::{Router, body::Body, http::Request, http::StatusCode, routing::get};
+use serde_json::Value;
+use serial_test::serial;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use fixtures::session_mocks::{EchoText, UntilSilence, mock_registry, stt_factory, tts_factory};
+use waav_gateway::config::{PluginConfig, SelfTestConfig};
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+use waav_gateway::selftest::{SelfTestStatus, run_selftest, spawn_selftest};
+use waav_gateway::{ServerConfig, handlers, state::AppState};
+
+const MOCK_PROVIDER: &str = "selftest-mock";
+
+/// STT provider that always hears something else
+const GARBLED_PROVIDER: &str = "selftest-garbled";
+
+/// Providers whose TTS sends the text's UTF-8 bytes as audio and whose STT
+/// transcribes it back; the garbled STT always hears something else
+fn providers() -> Arc<PluginRegistry> {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| UntilSilence::new(|audio| String::from_utf8_lossy(audio).into_owned())),
+        tts_factory(|config| EchoText {
+            sample_rate: config.sample_rate.unwrap_or(16000),
+            duration_ms: None,
+        }),
+    );
+    registry.register_stt(
+        GARBLED_PROVIDER,
+        stt_factory(|_| UntilSilence::new(|_| "static on the line".to_string())),
+        ProviderMetadata::stt(GARBLED_PROVIDER, "Self-Test Garbled STT"),
+    );
+    registry
+}
+
+fn test_config(selftest: SelfTestConfig) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: Some(selftest),
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+fn selftest_config(stt_provider: &str, required: bool) -> SelfTestConfig {
+    SelfTestConfig {
+        required,
+        ..SelfTestConfig::new(stt_provider, MOCK_PROVIDER)
+    }
+}
+
+fn public_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/readyz", get(handlers::api::readiness_check))
+        .route("/metrics", get(handlers::api::metrics))
+        .with_state(app_state)
+}
+
+async fn get_readyz(app_state: &Arc<AppState>) -> (StatusCode, Value) {
+    let response = public_router(app_state.clone())
+        .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn get_metrics(app_state: &Arc<AppState>) -> String {
+    let response = public_router(app_state.clone())
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_matching_transcript_passes() {
+    let config = selftest_config(MOCK_PROVIDER, true);
+    let app_state = AppState::with_plugin_registry(test_config(config.clone()), providers()).await;
+
+    // A required self-test blocks readiness until its first run passes
+    let (status, body) = get_readyz(&app_state).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(body["selftest"]["status"], "pending");
+
+    let report = run_selftest(&config, &app_state).await;
+    assert_eq!(report.status, SelfTestStatus::Passed, "{report:?}");
+    assert_eq!(report.transcript.as_deref(), Some(config.phrase.as_str()));
+    assert_eq!(report.similarity, Some(1.0));
+    app_state.selftest.record(report);
+
+    let (status, body) = get_readyz(&app_state).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["selftest"]["status"], "passed");
+    assert_eq!(body["selftest"]["required"], true);
+    assert_eq!(body["selftest"]["stt_provider"], MOCK_PROVIDER);
+
+    let metrics = get_metrics(&app_state).await;
+    assert!(metrics.contains("# TYPE waav_selftest_passed gauge"));
+    assert!(metrics.contains("waav_selftest_passed 1\n"));
+    assert!(metrics.contains("waav_selftest_similarity 1\n"));
+    assert!(metrics.contains("waav_selftest_runs_total{result=\"passed\"}"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_mismatching_transcript_fails() {
+    for required in [false, true] {
+        let config = selftest_config(GARBLED_PROVIDER, required);
+        let app_state =
+            AppState::with_plugin_registry(test_config(config.clone()), providers()).await;
+
+        let report = run_selftest(&config, &app_state).await;
+        assert_eq!(report.status, SelfTestStatus::Failed, "{report:?}");
+        assert_eq!(report.transcript.as_deref(), Some("static on the line"));
+        assert!(report.similarity.unwrap() < config.min_similarity);
+        assert!(
+            report
+                .message
+                .as_deref()
+                .unwrap()
+                .contains("does not match")
+        );
+        app_state.selftest.record(report);
+
+        // Failures are only fatal to readiness when the self-test is required
+        let (status, body) = get_readyz(&app_state).await;
+        assert_eq!(body["selftest"]["status"], "failed");
+        if required {
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(body["status"], "not_ready");
+        } else {
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["status"], "ready");
+        }
+        assert!(
+            get_metrics(&app_state)
+                .await
+                .contains("waav_selftest_passed 0\n")
+        );
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_missing_credentials_are_skipped() {
+    let config = SelfTestConfig {
+        required: true,
+        ..SelfTestConfig::new("deepgram", "deepgram")
+    };
+    let app_state = AppState::with_plugin_registry(test_config(config.clone()), providers()).await;
+
+    let handle = spawn_selftest(app_state.clone()).expect("self-test is configured");
+    handle.await.unwrap();
+
+    let report = app_state.selftest.latest().unwrap();
+    assert_eq!(report.status, SelfTestStatus::Skipped);
+    assert!(report.message.unwrap().contains("Deepgram API key"));
+
+    // Skipped runs never block readiness, even when required
+    let (status, body) = get_readyz(&app_state).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["selftest"]["status"], "skipped");
+}
+
+#[tokio::test]
+async fn test_readyz_without_selftest() {
+    let mut config = test_config(selftest_config(MOCK_PROVIDER, false));
+    config.selftest = None;
+    let app_state = AppState::new(config).await;
+
+    assert!(spawn_selftest(app_state.clone()).is_none());
+    let (status, body) = get_readyz(&app_state).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert!(body.get("selftest").is_none());
+}
@@ -0,0 +1,137 @@
+//! # Session Dry Run Integration Tests
+//!
+//! Compares `SessionPipelineBuilder::dry_run` with the configuration a
+//! session built from the same settings actually uses, on in-process mock
+//! providers registered through the provider registry. The mock factories
+//! count how many providers they create.
+//!
+//! 1. A dry run reports the configuration a real session is built with,
+//!    including components turned on by feature flags, with API keys
+//!    redacted, and creates no provider.
+//! 2. A config the provider's metadata rejects fails the dry run with the
+//!    issues a real build reports, without creating a provider.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test session_dry_run
+//! ```
+
+mod fixtures;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use fixtures::session_mocks::{Silent, mock_stt_config, mock_tts_config, stt_factory, tts_factory};
+use waav_gateway::config::FeatureFlags;
+use waav_gateway::core::session::{BargeInMode, SessionError, SessionPipelineBuilder};
+use waav_gateway::core::stt::STTConfig;
+use waav_gateway::core::tts::TTSConfig;
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+const MOCK_PROVIDER: &str = "dry-run-mock";
+
+/// Providers created by the mock factories
+static STT_CREATED: AtomicUsize = AtomicUsize::new(0);
+static TTS_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(
+        MOCK_PROVIDER,
+        stt_factory(|_| {
+            STT_CREATED.fetch_add(1, Ordering::SeqCst);
+            Silent
+        }),
+        ProviderMetadata::stt(MOCK_PROVIDER, "Dry Run Mock STT")
+            .with_sample_rates([16000])
+            .with_api_key_required(true),
+    );
+    registry.register_tts(
+        MOCK_PROVIDER,
+        tts_factory(|_| {
+            TTS_CREATED.fetch_add(1, Ordering::SeqCst);
+            Silent
+        }),
+        ProviderMetadata::tts(MOCK_PROVIDER, "Dry Run Mock TTS"),
+    );
+    registry
+}
+
+fn providers_created() -> usize {
+    STT_CREATED.load(Ordering::SeqCst) + TTS_CREATED.load(Ordering::SeqCst)
+}
+
+/// Builder for a session on the mock providers
+fn builder(sample_rate: u32) -> SessionPipelineBuilder {
+    let flags = FeatureFlags::from(BTreeMap::from([
+        ("echo_guard".to_string(), true),
+        ("adaptive_endpointing".to_string(), false),
+    ]));
+    SessionPipelineBuilder::new()
+        .plugin_registry(mock_registry())
+        .stt(STTConfig {
+            api_key: "stt-secret".to_string(),
+            sample_rate,
+            ..mock_stt_config(MOCK_PROVIDER)
+        })
+        .tts(TTSConfig {
+            api_key: "tts-secret".to_string(),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .barge_in(BargeInMode::OnInterim)
+        .fallback_voice("mock-fallback".to_string())
+        .system_speak_max_chars(200)
+        .feature_flags(flags)
+        .ready_timeout(Duration::from_secs(5))
+}
+
+#[tokio::test]
+async fn test_dry_run_matches_built_session() {
+    let created = providers_created();
+    let dry_run = builder(16000).dry_run().expect("config should be accepted");
+    // Creating providers is what a dry run avoids
+    assert_eq!(providers_created(), created);
+
+    // The echo guard flag turns the echo guard on; the off flag changes nothing
+    assert!(dry_run.echo_guard.is_some());
+    assert!(dry_run.adaptive_endpointing.is_none());
+    assert_eq!(dry_run.barge_in, BargeInMode::OnInterim);
+    assert_eq!(dry_run.fallback_voice_id.as_deref(), Some("mock-fallback"));
+    assert!(dry_run.feature_flags.is_enabled("echo_guard"));
+
+    let stt = dry_run.stt.as_ref().expect("voice session has stt");
+    let tts = dry_run.tts.as_ref().expect("voice session has tts");
+    assert_eq!(stt.provider, MOCK_PROVIDER);
+    assert_eq!(tts.voice_id.as_deref(), Some("mock-voice"));
+    let json = serde_json::to_string(&dry_run).unwrap();
+    assert!(!json.contains("stt-secret") && !json.contains("tts-secret"));
+
+    let session = builder(16000)
+        .build()
+        .await
+        .expect("mock session should build");
+    assert!(providers_created() > created);
+    assert_eq!(
+        serde_json::to_value(&dry_run).unwrap(),
+        serde_json::to_value(session.effective_config()).unwrap()
+    );
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_config_issues() {
+    let created = providers_created();
+    let error = builder(8000).dry_run().expect_err("8 kHz is not supported");
+    assert_eq!(providers_created(), created);
+
+    let SessionError::InvalidConfig(message) = error else {
+        panic!("expected an invalid config error, got {error:?}");
+    };
+    assert!(message.contains("stt.sample_rate"), "{message}");
+
+    // A real build rejects the same config
+    assert!(builder(8000).build().await.is_err());
+}
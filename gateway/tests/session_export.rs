@@ -0,0 +1,252 @@
+//! # Session Export Tests
+//!
+//! 1. A finished session's transcript is stored in `local_dir` and listed by
+//!    `GET /sessions/{stream_id}/artifacts`, for its tenant only.
+//! 2. The listed gateway URLs download the artifact through `GET /downloads/{key}`.
+//! 3. Expired URLs are refused with 410, and URLs with a tampered key,
+//!    expiry or signature with 403.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test session_export
+//! ```
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{Extension, Router, body::Body, http::Request, http::StatusCode};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use waav_gateway::auth::Auth;
+use waav_gateway::config::SessionExportConfig;
+use waav_gateway::core::realtime::TranscriptRole;
+use waav_gateway::core::session::TranscriptEntry;
+use waav_gateway::routes;
+use waav_gateway::session_export::{FinishedSession, sign_download};
+use waav_gateway::{ServerConfig, state::AppState};
+
+const SIGNING_KEY: &str = "a-dedicated-key-of-at-least-32-characters";
+
+/// Object key of the test session's SRT transcript
+const SRT_KEY: &str = "project1/call-1/transcript.srt";
+
+fn test_config(dir: &Path) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: Default::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: Some(SessionExportConfig {
+            local_dir: Some(dir.to_path_buf()),
+            public_base_url: Some("https://voice.example.com".to_string()),
+            signing_key: Some(SIGNING_KEY.to_string()),
+            ..Default::default()
+        }),
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Router serving one exported session to `project1`
+async fn exported_session(dir: &Path) -> Router {
+    let app_state = AppState::new(test_config(dir)).await;
+    let exporter = app_state
+        .session_export
+        .clone()
+        .expect("session export should be enabled");
+    exporter
+        .export(FinishedSession {
+            session_id: "call-1".to_string(),
+            auth_id: Some("project1".to_string()),
+            started_at: 1_700_000_000_000,
+            transcript: vec![TranscriptEntry {
+                timestamp: 1_700_000_002_000,
+                ..TranscriptEntry::new(TranscriptRole::User, "I need a refund", None)
+            }],
+            usage: None,
+            recorded: false,
+            redactions: Vec::new(),
+            consent: None,
+        })
+        .await
+        .unwrap();
+
+    routes::api::create_api_router()
+        .layer(Extension(Auth::new("project1")))
+        .merge(routes::downloads::create_download_router())
+        .with_state(app_state)
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body.to_vec())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn download_uri(key: &str, expires: u64, sig: &str) -> String {
+    format!("/downloads/{key}?expires={expires}&sig={sig}")
+}
+
+#[tokio::test]
+async fn test_artifacts_are_listed_and_downloadable() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = exported_session(dir.path()).await;
+
+    let (status, body) = get(&app, "/sessions/call-1/artifacts").await;
+    assert_eq!(status, StatusCode::OK);
+    let listing: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(listing["session_id"], "call-1");
+    let artifacts = listing["artifacts"].as_object().unwrap();
+    let mut kinds: Vec<&str> = artifacts.keys().map(String::as_str).collect();
+    kinds.sort_unstable();
+    assert_eq!(
+        kinds,
+        ["transcript_json", "transcript_srt", "transcript_vtt"]
+    );
+
+    let url = artifacts["transcript_srt"].as_str().unwrap();
+    let uri = url.strip_prefix("https://voice.example.com").unwrap();
+    let (status, body) = get(&app, uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        String::from_utf8(body).unwrap(),
+        "1\n00:00:02,000 --> 00:00:03,600\nUser: I need a refund\n\n"
+    );
+
+    let (status, _) = get(&app, "/sessions/call-2/artifacts").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_expired_download_url_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = exported_session(dir.path()).await;
+
+    let expired = now() - 1;
+    let sig = sign_download(SIGNING_KEY, SRT_KEY, expired);
+    let (status, body) = get(&app, &download_uri(SRT_KEY, expired, &sig)).await;
+    assert_eq!(status, StatusCode::GONE);
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["error"], "Download URL has expired");
+}
+
+#[tokio::test]
+async fn test_tampered_download_urls_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = exported_session(dir.path()).await;
+
+    let expires = now() + 60;
+    let sig = sign_download(SIGNING_KEY, SRT_KEY, expires);
+    let (status, _) = get(&app, &download_uri(SRT_KEY, expires, &sig)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Longer expiry, another object, another key, a forged signature
+    let forged = sign_download("not-the-signing-key-but-32-characters", SRT_KEY, expires);
+    for uri in [
+        download_uri(SRT_KEY, expires + 3600, &sig),
+        download_uri("project1/call-1/transcript.json", expires, &sig),
+        download_uri("project2/call-1/transcript.srt", expires, &sig),
+        download_uri(SRT_KEY, expires, &forged),
+        download_uri(SRT_KEY, expires, "00"),
+    ] {
+        let (status, body) = get(&app, &uri).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{uri}");
+        let error: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"], "Invalid download URL signature");
+    }
+
+    // A forged signature is refused as such even once it has expired
+    let (status, _) = get(&app, &download_uri(SRT_KEY, now() - 1, &forged)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
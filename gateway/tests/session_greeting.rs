@@ -0,0 +1,239 @@
+//! # Session Greeting Integration Tests
+//!
+//! Plays greetings through a `Session` built on the in-process mock providers
+//! of `fixtures::session_mocks`, whose TTS returns the spoken text as audio:
+//!
+//! 1. Text greetings are synthesized through the TTS provider.
+//! 2. Asset greetings are loaded from a WAV file and played as PCM.
+//! 3. Assets that do not match the session output format are rejected.
+//! 4. A resumed session (same `stream_id`) does not replay the greeting.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test session_greeting
+//! ```
+
+mod fixtures;
+
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    EchoText, Silent, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::config::{GreetingConfig, GreetingSource};
+use waav_gateway::core::session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::TTSConfig;
+use waav_gateway::state::SessionStore;
+
+const MOCK_PROVIDER: &str = "greeting-mock";
+const SAMPLE_RATE: u32 = 16000;
+
+async fn build_session() -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|_| EchoText {
+            sample_rate: SAMPLE_RATE,
+            duration_ms: Some(100),
+        }),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            audio_format: Some("linear16".to_string()),
+            sample_rate: Some(SAMPLE_RATE),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("session should build with mock providers")
+}
+
+/// Collect events until the greeting has been reported
+async fn collect_until_greeting(events: &mut SessionEventStream) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            let done = matches!(event, SessionEvent::GreetingPlayed { .. });
+            collected.push(event);
+            if done {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("greeting.played should be emitted");
+    collected
+}
+
+fn write_wav(path: &std::path::Path, sample_rate: u32, channels: u16, samples: &[i16]) {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for &sample in samples {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+#[tokio::test]
+async fn test_text_greeting_is_spoken_through_tts() {
+    let session = build_session().await;
+    let mut events = session.take_events().unwrap();
+    let greeting = GreetingConfig {
+        text: Some("Hello, how can I help?".to_string()),
+        ..Default::default()
+    };
+
+    let played = session.play_greeting(&greeting, None).await.unwrap();
+    assert_eq!(
+        played,
+        Some(GreetingSource::Text("Hello, how can I help?".to_string()))
+    );
+
+    let collected = collect_until_greeting(&mut events).await;
+    let audio: Vec<u8> = collected
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::Audio(audio) => Some(audio.data.clone()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    assert_eq!(audio, b"Hello, how can I help?".to_vec());
+    assert!(matches!(
+        collected.last(),
+        Some(SessionEvent::GreetingPlayed {
+            source: GreetingSource::Text(_),
+            ..
+        })
+    ));
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_asset_greeting_plays_wav_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let samples: Vec<i16> = (0..1600).map(|i| (i * 7) as i16).collect();
+    write_wav(&dir.path().join("welcome.wav"), SAMPLE_RATE, 1, &samples);
+
+    let session = build_session().await;
+    let mut events = session.take_events().unwrap();
+    let greeting = GreetingConfig {
+        asset: Some("welcome".to_string()),
+        delay_ms: Some(10),
+        ..Default::default()
+    };
+
+    let played = session
+        .play_greeting(&greeting, Some(dir.path()))
+        .await
+        .unwrap();
+    assert_eq!(played, Some(GreetingSource::Asset("welcome".to_string())));
+
+    let collected = collect_until_greeting(&mut events).await;
+    let pcm: Vec<u8> = collected
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::Audio(audio) => {
+                assert_eq!(audio.sample_rate, SAMPLE_RATE);
+                Some(audio.data.clone())
+            }
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let expected: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    assert_eq!(pcm, expected);
+    assert!(matches!(
+        collected.last(),
+        Some(SessionEvent::GreetingPlayed {
+            source: GreetingSource::Asset(name),
+            ..
+        }) if name == "welcome"
+    ));
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_asset_greeting_rejects_mismatched_wav() {
+    let dir = tempfile::tempdir().unwrap();
+    write_wav(&dir.path().join("stereo.wav"), SAMPLE_RATE, 2, &[0; 320]);
+    write_wav(&dir.path().join("fast.wav"), 48000, 1, &[0; 320]);
+
+    let session = build_session().await;
+    for name in ["stereo", "fast", "missing"] {
+        let greeting = GreetingConfig {
+            asset: Some(name.to_string()),
+            ..Default::default()
+        };
+        let result = session.play_greeting(&greeting, Some(dir.path())).await;
+        assert!(
+            matches!(result, Err(SessionError::Greeting(_))),
+            "{name} should be rejected"
+        );
+    }
+
+    // Assets need an assets directory
+    let greeting = GreetingConfig {
+        asset: Some("welcome".to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        session.play_greeting(&greeting, None).await,
+        Err(SessionError::Greeting(_))
+    ));
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_empty_greeting_plays_nothing() {
+    let session = build_session().await;
+    let played = session
+        .play_greeting(&GreetingConfig::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(played, None);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_resumed_session_does_not_replay_greeting() {
+    let store = SessionStore::new();
+    let greeting = GreetingConfig {
+        text: Some("Welcome back".to_string()),
+        ..Default::default()
+    };
+
+    // First connection claims and plays the greeting
+    assert!(store.claim_greeting("stream-resume"));
+    let session = build_session().await;
+    let mut events = session.take_events().unwrap();
+    session.play_greeting(&greeting, None).await.unwrap();
+    let collected = collect_until_greeting(&mut events).await;
+    assert!(
+        collected
+            .iter()
+            .any(|event| matches!(event, SessionEvent::GreetingPlayed { .. }))
+    );
+    session.close().await.unwrap();
+    store.remove("stream-resume");
+
+    // The client reconnects with the same stream_id: the greeting is suppressed
+    assert!(!store.claim_greeting("stream-resume"));
+
+    // A new stream is greeted as usual
+    assert!(store.claim_greeting("stream-new"));
+}
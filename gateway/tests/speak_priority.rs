@@ -0,0 +1,275 @@
+//! # Speak Priority Integration Tests
+//!
+//! Builds sessions on the in-process mock providers of
+//! `fixtures::session_mocks`. The mock TTS records every utterance and clear
+//! per voice id and never completes on its own; tests report completion
+//! through the provider's registered callback to end an announcement.
+//!
+//! 1. A `system` speak clears the provider, pauses the pending utterances,
+//!    holds new speech and ignores `interrupt()` until it completes, then
+//!    resumes everything in order between `PreemptionStarted` and
+//!    `PreemptionEnded`.
+//! 2. `system` text longer than the configured cap is refused untouched.
+//! 3. Held normal speech is capped like the queue, `high` speech is not.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test speak_priority
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::{AudioCallback, TTSConfig, TTSResult};
+use waav_gateway::core::voice_manager::{
+    SpeakPriority, TTSQueueLimit, TTSQueuePolicy, VoiceManagerError,
+};
+
+const MOCK_PROVIDER: &str = "speak-priority-mock";
+
+/// Marker recorded when the mock TTS is cleared
+const CLEARED: &str = "<clear>";
+
+/// Every utterance and clear sent to the mock TTS, as (voice id, text)
+static SPOKEN: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Latest audio callback registered on the mock TTS, per voice id
+static CALLBACKS: Mutex<Option<HashMap<String, Arc<dyn AudioCallback>>>> = Mutex::new(None);
+
+/// Utterances and clears sent to `voice_id`
+fn spoken_with(voice_id: &str) -> Vec<String> {
+    SPOKEN
+        .lock()
+        .iter()
+        .filter(|(voice, _)| voice == voice_id)
+        .map(|(_, text)| text.clone())
+        .collect()
+}
+
+/// Report that the provider for `voice_id` finished all queued text
+async fn complete(voice_id: &str) {
+    let callback = CALLBACKS
+        .lock()
+        .as_ref()
+        .and_then(|callbacks| callbacks.get(voice_id).cloned())
+        .expect("mock TTS callback registered");
+    callback.on_complete().await;
+}
+
+/// TTS behavior that records utterances and leaves completion to the test
+struct RecordPerVoice {
+    voice_id: String,
+}
+
+#[async_trait]
+impl TtsBehavior for RecordPerVoice {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        _audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), text.to_string()));
+        Ok(())
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), CLEARED.to_string()));
+        Ok(())
+    }
+
+    fn on_audio(&mut self, callback: &Arc<dyn AudioCallback>) {
+        CALLBACKS
+            .lock()
+            .get_or_insert_with(HashMap::new)
+            .insert(self.voice_id.clone(), callback.clone());
+    }
+}
+
+async fn build_session(voice_id: &str, max_pending: usize, max_chars: usize) -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|config| RecordPerVoice {
+            voice_id: config.voice_id.clone().unwrap_or_default(),
+        }),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            voice_id: Some(voice_id.to_string()),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .tts_queue_limit(TTSQueueLimit {
+            max_pending,
+            policy: TTSQueuePolicy::Reject,
+        })
+        .system_speak_max_chars(max_chars)
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+async fn speak(session: &Session, text: &str, priority: SpeakPriority) -> Result<(), SessionError> {
+    session
+        .speak_with_priority(text, true, true, None, priority)
+        .await
+        .map(|_| ())
+}
+
+/// Wait for the next pre-emption event, failing after a timeout
+async fn next_preemption(events: &mut SessionEventStream) -> SessionEvent {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            if matches!(
+                event,
+                SessionEvent::PreemptionStarted { .. } | SessionEvent::PreemptionEnded { .. }
+            ) {
+                return event;
+            }
+        }
+        panic!("event stream ended before a pre-emption event");
+    })
+    .await
+    .expect("pre-emption event did not arrive")
+}
+
+#[tokio::test]
+async fn test_system_speak_preempts_and_resumes_queue() {
+    let session = build_session("resume-voice", 5, 100).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("One.", false).await.unwrap();
+    session.speak("Two.", true).await.unwrap();
+    speak(&session, "This call is recorded.", SpeakPriority::System)
+        .await
+        .unwrap();
+
+    match next_preemption(&mut events).await {
+        SessionEvent::PreemptionStarted { text, paused } => {
+            assert_eq!(text, "This call is recorded.");
+            assert_eq!(paused, 2);
+        }
+        other => panic!("expected PreemptionStarted, got {other:?}"),
+    }
+    assert_eq!(
+        spoken_with("resume-voice"),
+        vec!["One.", "Two.", CLEARED, "This call is recorded."]
+    );
+
+    // New speech is held and barge-in is ignored while the announcement plays
+    session.speak("Three.", true).await.unwrap();
+    assert!(session.is_interruption_blocked().await);
+    assert!(!session.interrupt().await.unwrap());
+    assert_eq!(
+        spoken_with("resume-voice"),
+        vec!["One.", "Two.", CLEARED, "This call is recorded."]
+    );
+
+    complete("resume-voice").await;
+    match next_preemption(&mut events).await {
+        SessionEvent::PreemptionEnded { resumed } => assert_eq!(resumed, 3),
+        other => panic!("expected PreemptionEnded, got {other:?}"),
+    }
+    assert_eq!(
+        spoken_with("resume-voice"),
+        vec![
+            "One.",
+            "Two.",
+            CLEARED,
+            "This call is recorded.",
+            "One.",
+            "Two.",
+            "Three."
+        ]
+    );
+
+    // Resumed speech is interruptible again
+    assert!(session.interrupt().await.unwrap());
+    assert_eq!(session.tts_queue_stats().unwrap().pending, 0);
+}
+
+#[tokio::test]
+async fn test_system_speak_longer_than_cap_is_refused() {
+    let session = build_session("cap-voice", 5, 10).await;
+
+    session.speak("Hello.", true).await.unwrap();
+    match speak(&session, "This call is recorded.", SpeakPriority::System).await {
+        Err(SessionError::VoiceManager(VoiceManagerError::SystemSpeakTooLong { max_chars })) => {
+            assert_eq!(max_chars, 10);
+        }
+        other => panic!("expected SystemSpeakTooLong, got {other:?}"),
+    }
+
+    // Nothing was pre-empted
+    assert_eq!(spoken_with("cap-voice"), vec!["Hello."]);
+    assert!(!session.is_interruption_blocked().await);
+    speak(&session, "Recorded.", SpeakPriority::System)
+        .await
+        .unwrap();
+    assert_eq!(
+        spoken_with("cap-voice"),
+        vec!["Hello.", CLEARED, "Recorded."]
+    );
+}
+
+#[tokio::test]
+async fn test_held_speech_is_capped_except_high_priority() {
+    let session = build_session("held-voice", 1, 100).await;
+    let mut events = session.take_events().unwrap();
+
+    speak(&session, "Recorded.", SpeakPriority::System)
+        .await
+        .unwrap();
+    speak(&session, "A.", SpeakPriority::Normal).await.unwrap();
+    match speak(&session, "B.", SpeakPriority::Normal).await {
+        Err(SessionError::VoiceManager(VoiceManagerError::TTSQueueFull { max_pending })) => {
+            assert_eq!(max_pending, 1);
+        }
+        other => panic!("expected TTSQueueFull, got {other:?}"),
+    }
+    speak(&session, "C.", SpeakPriority::High).await.unwrap();
+    assert_eq!(session.tts_queue_stats().unwrap().rejected, 1);
+
+    complete("held-voice").await;
+    assert!(matches!(
+        next_preemption(&mut events).await,
+        SessionEvent::PreemptionStarted { paused: 0, .. }
+    ));
+    assert!(matches!(
+        next_preemption(&mut events).await,
+        SessionEvent::PreemptionEnded { resumed: 2 }
+    ));
+    assert_eq!(
+        spoken_with("held-voice"),
+        vec![CLEARED, "Recorded.", "A.", "C."]
+    );
+
+    // Outside a pre-emption high priority speech still bypasses the cap
+    assert!(matches!(
+        speak(&session, "D.", SpeakPriority::Normal).await,
+        Err(SessionError::VoiceManager(
+            VoiceManagerError::TTSQueueFull { .. }
+        ))
+    ));
+    speak(&session, "E.", SpeakPriority::High).await.unwrap();
+    assert_eq!(session.tts_queue_stats().unwrap().pending, 3);
+}
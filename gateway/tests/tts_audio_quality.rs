@@ -0,0 +1,253 @@
+//! # TTS Audio Quality Integration Tests
+//!
+//! Builds sessions on the in-process mock providers of
+//! `fixtures::session_mocks`. The mock TTS picks the audio it returns from
+//! the voice id: `silent*` voices return digital silence, `clipped*` voices a
+//! tone flattened at full scale, `flaky*` voices silence for the first attempt
+//! at a text and a clean tone afterwards, and every other voice a clean tone.
+//!
+//! 1. Silent and clipped audio emit `TtsAudioQualityWarning` ahead of
+//!    `SpeechComplete`; clean audio does not.
+//! 2. With `retry`, a failing utterance is synthesized exactly once more.
+//! 3. A disabled check reports nothing.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_audio_quality
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Clip, Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory,
+    tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::{AudioCallback, TTSConfig, TTSResult};
+use waav_gateway::core::voice_manager::{
+    AudioQualityIssue, TTSAudioQualityConfig, TTSAudioQualityWarning,
+};
+
+const MOCK_PROVIDER: &str = "tts-audio-quality-mock";
+
+/// Samples per utterance: 500 ms at 16 kHz
+const UTTERANCE_SAMPLES: usize = 8000;
+
+/// Every utterance sent to the mock TTS, as (voice id, text)
+static SPOKEN: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Utterances sent to `voice_id`
+fn spoken_with(voice_id: &str) -> Vec<String> {
+    SPOKEN
+        .lock()
+        .iter()
+        .filter(|(voice, _)| voice == voice_id)
+        .map(|(_, text)| text.clone())
+        .collect()
+}
+
+/// 16-bit PCM tone at the given amplification, clamped to full scale
+fn tone(gain: f32) -> Vec<u8> {
+    (0..UTTERANCE_SAMPLES)
+        .map(|i| {
+            let phase = i as f32 * 440.0 * std::f32::consts::TAU / 16000.0;
+            (phase.sin() * gain).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        })
+        .flat_map(i16::to_le_bytes)
+        .collect()
+}
+
+/// TTS behavior whose audio quality depends on the voice id
+struct QualityPerVoice {
+    voice_id: String,
+}
+
+impl QualityPerVoice {
+    fn audio_for(&self, text: &str) -> Vec<u8> {
+        let attempts = spoken_with(&self.voice_id)
+            .iter()
+            .filter(|spoken| *spoken == text)
+            .count();
+        if self.voice_id.starts_with("silent")
+            || (self.voice_id.starts_with("flaky") && attempts == 1)
+        {
+            vec![0; UTTERANCE_SAMPLES * 2]
+        } else if self.voice_id.starts_with("clipped") {
+            tone(65536.0)
+        } else {
+            tone(8000.0)
+        }
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for QualityPerVoice {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), text.to_string()));
+        let clip = Clip {
+            data: self.audio_for(text),
+            sample_rate: 16000,
+            duration_ms: Some(500),
+        };
+        if let Some(callback) = audio {
+            clip.play(callback).await;
+        }
+        Ok(())
+    }
+}
+
+async fn build_session(voice_id: &str, audio_quality: TTSAudioQualityConfig) -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|config| QualityPerVoice {
+            voice_id: config.voice_id.clone().unwrap_or_default(),
+        }),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            voice_id: Some(voice_id.to_string()),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .tts_audio_quality(audio_quality)
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+/// Collect quality warnings until `completions` utterances have completed
+async fn warnings_until_complete(
+    events: &mut SessionEventStream,
+    completions: usize,
+) -> Vec<TTSAudioQualityWarning> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        let mut warnings = Vec::new();
+        let mut completed = 0;
+        while let Some(event) = events.recv().await {
+            match event {
+                SessionEvent::TtsAudioQualityWarning { warning, .. } => warnings.push(warning),
+                SessionEvent::SpeechComplete { .. } => {
+                    completed += 1;
+                    if completed == completions {
+                        return warnings;
+                    }
+                }
+                _ => {}
+            }
+        }
+        panic!("event stream ended before {completions} completions");
+    })
+    .await
+    .expect("utterances did not complete")
+}
+
+#[tokio::test]
+async fn test_silent_audio_is_reported() {
+    let session = build_session("silent-voice", TTSAudioQualityConfig::default()).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Hello there.", true).await.unwrap();
+
+    let warnings = warnings_until_complete(&mut events, 1).await;
+    assert_eq!(warnings.len(), 1);
+    let warning = &warnings[0];
+    assert_eq!(
+        warning.issues,
+        vec![AudioQualityIssue::Silent, AudioQualityIssue::LowLevel]
+    );
+    assert_eq!(warning.zero_fraction, 1.0);
+    assert_eq!(warning.duration_ms, 500);
+    assert_eq!(warning.text, "Hello there.");
+    assert!(!warning.retrying);
+    assert!(!warning.retried);
+    assert_eq!(spoken_with("silent-voice"), vec!["Hello there."]);
+}
+
+#[tokio::test]
+async fn test_clipped_audio_is_reported() {
+    let session = build_session("clipped-voice", TTSAudioQualityConfig::default()).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Too loud.", true).await.unwrap();
+
+    let warnings = warnings_until_complete(&mut events, 1).await;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].issues, vec![AudioQualityIssue::Clipped]);
+    assert!(warnings[0].clipped_fraction > 0.5);
+}
+
+#[tokio::test]
+async fn test_clean_audio_is_not_reported() {
+    let session = build_session("clean-voice", TTSAudioQualityConfig::default()).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("All good.", true).await.unwrap();
+
+    assert!(warnings_until_complete(&mut events, 1).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_utterance_is_retried_once() {
+    let retry = TTSAudioQualityConfig {
+        retry: true,
+        ..Default::default()
+    };
+
+    // The retry produces clean audio
+    let session = build_session("flaky-voice", retry).await;
+    let mut events = session.take_events().unwrap();
+    session.speak("Try again.", true).await.unwrap();
+
+    let warnings = warnings_until_complete(&mut events, 2).await;
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].retrying);
+    assert_eq!(spoken_with("flaky-voice"), vec!["Try again.", "Try again."]);
+
+    // The retry fails as well and is not retried again
+    let session = build_session("silent-retry-voice", retry).await;
+    let mut events = session.take_events().unwrap();
+    session.speak("Still silent.", true).await.unwrap();
+
+    let warnings = warnings_until_complete(&mut events, 2).await;
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].retrying && !warnings[0].retried);
+    assert!(!warnings[1].retrying && warnings[1].retried);
+    assert_eq!(warnings[1].text, "Still silent.");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        spoken_with("silent-retry-voice"),
+        vec!["Still silent.", "Still silent."]
+    );
+}
+
+#[tokio::test]
+async fn test_disabled_check_reports_nothing() {
+    let disabled = TTSAudioQualityConfig {
+        enabled: false,
+        ..Default::default()
+    };
+    let session = build_session("silent-disabled-voice", disabled).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Hello?", true).await.unwrap();
+
+    assert!(warnings_until_complete(&mut events, 1).await.is_empty());
+}
@@ -0,0 +1,220 @@
+//! # TTS Chunk Retry Tests
+//!
+//! Speaks one utterance in six chunks through the shared HTTP TTS dispatch
+//! path against a wiremock server that answers each chunk with audio filled
+//! with the chunk's number, so the delivered audio shows the order it was
+//! played in:
+//!
+//! 1. A chunk that fails once with a 503 is retried; the utterance plays in
+//!    full and in order.
+//! 2. A chunk that keeps failing is reported once after its retries; the audio
+//!    delivered before it is kept, the later chunks of the utterance are never
+//!    requested, and the next utterance still plays.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_chunk_retry
+//! ```
+
+use futures::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use wiremock::matchers::{body_string, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError};
+use waav_gateway::core::tts::{TTSProvider, TTSRequestBuilder};
+use waav_gateway::utils::req_manager::ReqManager;
+
+/// Bytes of PCM per chunk (100ms at 24kHz linear16)
+const AUDIO_BYTES: usize = 4800;
+
+const CHUNKS: [&str; 6] = ["one", "two", "three", "four", "five", "six"];
+
+#[derive(Clone)]
+struct MockTTSRequestBuilder {
+    config: TTSConfig,
+    base_url: String,
+}
+
+impl TTSRequestBuilder for MockTTSRequestBuilder {
+    fn build_http_request(&self, client: &reqwest::Client, text: &str) -> reqwest::RequestBuilder {
+        client
+            .post(format!("{}/tts", self.base_url))
+            .header("Content-Type", "text/plain")
+            .body(text.to_string())
+    }
+
+    fn get_config(&self) -> &TTSConfig {
+        &self.config
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingCallback {
+    audio: Arc<Mutex<Vec<u8>>>,
+    errors: Arc<Mutex<Vec<TTSError>>>,
+}
+
+impl AudioCallback for RecordingCallback {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            self.audio.lock().await.extend_from_slice(&audio_data.data);
+        })
+    }
+
+    fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            self.errors.lock().await.push(error);
+        })
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+}
+
+/// Answer every chunk with its number repeated, and `failing` with a 503
+/// `times` times first (`None` for every time)
+async fn start_server(failing: &str, times: Option<u64>) -> MockServer {
+    let server = MockServer::start().await;
+    let failure = Mock::given(method("POST"))
+        .and(body_string(failing))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after-ms", "50"))
+        .with_priority(1);
+    match times {
+        Some(times) => failure.up_to_n_times(times).mount(&server).await,
+        None => failure.mount(&server).await,
+    }
+    for (index, text) in CHUNKS.iter().chain(["seven"].iter()).enumerate() {
+        Mock::given(method("POST"))
+            .and(body_string(*text))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_bytes(vec![index as u8 + 1; AUDIO_BYTES]),
+            )
+            .mount(&server)
+            .await;
+    }
+    server
+}
+
+async fn create_provider(
+    server: &MockServer,
+) -> (TTSProvider, RecordingCallback, MockTTSRequestBuilder) {
+    let mut provider = TTSProvider::new().unwrap();
+    let req_manager = ReqManager::new(4).await.unwrap();
+    provider.set_req_manager(Arc::new(req_manager)).await;
+
+    let callback = RecordingCallback::default();
+    provider
+        .generic_on_audio(Arc::new(callback.clone()))
+        .unwrap();
+
+    let config = TTSConfig {
+        provider: "chunk-retry-mock".to_string(),
+        audio_format: Some("linear16".to_string()),
+        sample_rate: Some(24000),
+        ..Default::default()
+    };
+    provider
+        .generic_connect_with_config(&server.uri(), &config)
+        .await
+        .unwrap();
+
+    let builder = MockTTSRequestBuilder {
+        config,
+        base_url: server.uri(),
+    };
+    (provider, callback, builder)
+}
+
+/// Speak the six chunks as one utterance, flushing the last
+async fn speak_utterance(provider: &mut TTSProvider, builder: &MockTTSRequestBuilder) {
+    for (index, text) in CHUNKS.iter().enumerate() {
+        let flush = index == CHUNKS.len() - 1;
+        provider
+            .generic_speak(builder.clone(), text, flush)
+            .await
+            .unwrap();
+    }
+}
+
+/// The chunk numbers of the delivered audio, in playback order
+fn played_chunks(audio: &[u8]) -> Vec<u8> {
+    assert_eq!(audio.len() % AUDIO_BYTES, 0);
+    audio.chunks(AUDIO_BYTES).map(|chunk| chunk[0]).collect()
+}
+
+async fn requested(server: &MockServer, text: &str) -> usize {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|request| request.body == text.as_bytes())
+        .count()
+}
+
+#[tokio::test]
+async fn test_transient_chunk_failure_is_retried_in_order() {
+    let server = start_server("three", Some(1)).await;
+    let (mut provider, callback, builder) = create_provider(&server).await;
+
+    speak_utterance(&mut provider, &builder).await;
+    sleep(Duration::from_millis(1000)).await;
+
+    let audio = callback.audio.lock().await.clone();
+    assert_eq!(played_chunks(&audio), vec![1, 2, 3, 4, 5, 6]);
+    assert!(
+        audio
+            .chunks(AUDIO_BYTES)
+            .all(|chunk| chunk.iter().all(|b| *b == chunk[0]))
+    );
+    assert!(callback.errors.lock().await.is_empty());
+    assert_eq!(requested(&server, "three").await, 2);
+
+    let stats = provider.get_stats().await;
+    assert_eq!(stats.chunk_retries, 1);
+    assert_eq!(stats.chunks_failed, 0);
+    assert_eq!(stats.chunks_abandoned, 0);
+    assert_eq!(stats.utterances_incomplete, 0);
+    assert_eq!(stats.total_audio_bytes, (6 * AUDIO_BYTES) as u64);
+}
+
+#[tokio::test]
+async fn test_permanent_chunk_failure_abandons_utterance() {
+    let server = start_server("three", None).await;
+    let (mut provider, callback, builder) = create_provider(&server).await;
+
+    speak_utterance(&mut provider, &builder).await;
+    provider
+        .generic_speak(builder.clone(), "seven", true)
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(1500)).await;
+
+    // The first two chunks stay delivered and the next utterance plays after them
+    let audio = callback.audio.lock().await.clone();
+    assert_eq!(played_chunks(&audio), vec![1, 2, 7]);
+
+    let errors = callback.errors.lock().await.clone();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], TTSError::ProviderError(_)));
+
+    // Three attempts for the failing chunk; the rest of its utterance is never sent
+    assert_eq!(requested(&server, "three").await, 3);
+    for text in ["four", "five", "six"] {
+        assert_eq!(requested(&server, text).await, 0, "{text} was requested");
+    }
+
+    let stats = provider.get_stats().await;
+    assert_eq!(stats.chunk_retries, 2);
+    assert_eq!(stats.chunks_failed, 1);
+    assert_eq!(stats.chunks_abandoned, 3);
+    assert_eq!(stats.utterances_incomplete, 1);
+    assert_eq!(stats.utterances_completed, 3);
+}
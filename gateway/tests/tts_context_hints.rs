@@ -0,0 +1,177 @@
+//! # TTS Context Hints Test
+//!
+//! Verifies the text the HTTP TTS dispatch layer passes around each chunk of
+//! an utterance, which ElevenLabs sends as `previous_text`/`next_text`:
+//!
+//! 1. The first chunk gets the next chunk, the last chunk no next chunk, and
+//!    every chunk the one synthesized before it.
+//! 2. `context_hints: false` sends every chunk without context.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_context_hints
+//! ```
+
+use futures::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError};
+use waav_gateway::core::tts::{TTSProvider, TTSRequestBuilder};
+use waav_gateway::utils::req_manager::ReqManager;
+
+/// A request as (text, previous_text, next_text)
+type Recorded = (String, Option<String>, Option<String>);
+
+#[derive(Clone)]
+struct RecordingRequestBuilder {
+    config: TTSConfig,
+    base_url: String,
+    requests: Arc<Mutex<Vec<Recorded>>>,
+}
+
+impl TTSRequestBuilder for RecordingRequestBuilder {
+    fn build_http_request(&self, client: &reqwest::Client, text: &str) -> reqwest::RequestBuilder {
+        self.build_http_request_with_context(client, text, None, None)
+    }
+
+    fn build_http_request_with_context(
+        &self,
+        client: &reqwest::Client,
+        text: &str,
+        previous_text: Option<&str>,
+        next_text: Option<&str>,
+    ) -> reqwest::RequestBuilder {
+        self.requests.lock().push((
+            text.to_string(),
+            previous_text.map(str::to_string),
+            next_text.map(str::to_string),
+        ));
+        client
+            .post(format!("{}/tts", self.base_url))
+            .body(text.to_string())
+    }
+
+    fn get_config(&self) -> &TTSConfig {
+        &self.config
+    }
+}
+
+struct NoopCallback;
+
+impl AudioCallback for NoopCallback {
+    fn on_audio(&self, _audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+
+    fn on_error(&self, _error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
+}
+
+async fn create_provider(
+    server: &MockServer,
+    context_hints: Option<bool>,
+) -> (TTSProvider, RecordingRequestBuilder) {
+    Mock::given(method("POST"))
+        .and(path("/tts"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 480]))
+        .mount(server)
+        .await;
+
+    let mut provider = TTSProvider::new().unwrap();
+    let req_manager = ReqManager::new(4).await.unwrap();
+    provider.set_req_manager(Arc::new(req_manager)).await;
+    provider.generic_on_audio(Arc::new(NoopCallback)).unwrap();
+
+    let config = TTSConfig {
+        context_hints,
+        ..Default::default()
+    };
+    provider
+        .generic_connect_with_config(&server.uri(), &config)
+        .await
+        .unwrap();
+
+    let builder = RecordingRequestBuilder {
+        config,
+        base_url: server.uri(),
+        requests: Arc::new(Mutex::new(Vec::new())),
+    };
+    (provider, builder)
+}
+
+/// Queue `chunks` as one utterance and wait for every request
+async fn speak_utterance(
+    provider: &mut TTSProvider,
+    builder: &RecordingRequestBuilder,
+    chunks: &[&str],
+) -> Vec<Recorded> {
+    // Queued before the worker runs, like sentences streamed faster than synthesis
+    for (i, chunk) in chunks.iter().enumerate() {
+        let flush = i + 1 == chunks.len();
+        provider
+            .generic_speak(builder.clone(), chunk, flush)
+            .await
+            .unwrap();
+    }
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while builder.requests.lock().len() < chunks.len() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("every chunk should be requested");
+    builder.requests.lock().clone()
+}
+
+fn some(text: &str) -> Option<String> {
+    Some(text.to_string())
+}
+
+#[tokio::test]
+async fn test_chunks_get_surrounding_text() {
+    let server = MockServer::start().await;
+    let (mut provider, builder) = create_provider(&server, None).await;
+    let chunks = [
+        "Your order has shipped.",
+        "It should arrive on Tuesday.",
+        "Anything else?",
+    ];
+
+    let requests = speak_utterance(&mut provider, &builder, &chunks).await;
+    assert_eq!(
+        requests,
+        vec![
+            (chunks[0].to_string(), None, some(chunks[1])),
+            (chunks[1].to_string(), some(chunks[0]), some(chunks[2])),
+            (chunks[2].to_string(), some(chunks[1]), None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_context_hints_can_be_disabled() {
+    let server = MockServer::start().await;
+    let (mut provider, builder) = create_provider(&server, Some(false)).await;
+
+    let requests =
+        speak_utterance(&mut provider, &builder, &["First sentence.", "Second one."]).await;
+    assert!(
+        requests
+            .iter()
+            .all(|(_, previous, next)| previous.is_none() && next.is_none()),
+        "{requests:?}"
+    );
+}
@@ -0,0 +1,186 @@
+//! # TTS Partial Dedup Integration Tests
+//!
+//! Builds sessions on the in-process mock providers of
+//! `fixtures::session_mocks` and replays scripted LLM partial streams. The
+//! mock TTS records every utterance, clear and flush per voice id.
+//!
+//! 1. Partials that resend the sentence so far only send the new suffix.
+//! 2. A partial that revises earlier text clears the provider and sends the
+//!    revised text in full, counting the corrected characters.
+//! 3. Without `dedupe_partials`, every partial is sent unchanged.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_partial_dedup
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{Session, SessionPipelineBuilder};
+use waav_gateway::core::tts::{AudioCallback, TTSConfig, TTSResult};
+use waav_gateway::core::voice_manager::{TTSQueueLimit, TextDedupStats};
+
+const MOCK_PROVIDER: &str = "tts-partial-dedup-mock";
+
+/// Marker recorded when the mock TTS is cleared
+const CLEARED: &str = "<clear>";
+
+/// Marker recorded when the mock TTS is flushed
+const FLUSHED: &str = "<flush>";
+
+/// Every utterance, clear and flush sent to the mock TTS, as (voice id, text)
+static SPOKEN: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Utterances, clears and flushes sent to `voice_id`
+fn spoken_with(voice_id: &str) -> Vec<String> {
+    SPOKEN
+        .lock()
+        .iter()
+        .filter(|(voice, _)| voice == voice_id)
+        .map(|(_, text)| text.clone())
+        .collect()
+}
+
+/// TTS behavior that records utterances, clears and flushes
+struct RecordPerVoice {
+    voice_id: String,
+}
+
+impl RecordPerVoice {
+    fn record(&self, text: &str) {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), text.to_string()));
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for RecordPerVoice {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        _audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        self.record(text);
+        Ok(())
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        self.record(CLEARED);
+        Ok(())
+    }
+
+    async fn flush(&self) -> TTSResult<()> {
+        self.record(FLUSHED);
+        Ok(())
+    }
+}
+
+async fn build_session(voice_id: &str, dedupe_partials: bool) -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|config| RecordPerVoice {
+            voice_id: config.voice_id.clone().unwrap_or_default(),
+        }),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            voice_id: Some(voice_id.to_string()),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        // The mock never completes, so keep every partial under the cap
+        .tts_queue_limit(TTSQueueLimit {
+            max_pending: 64,
+            ..Default::default()
+        })
+        .dedupe_partials(dedupe_partials)
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+#[tokio::test]
+async fn test_prefix_resend_sends_only_new_text() {
+    let session = build_session("resend-voice", true).await;
+
+    for partial in ["Hel", "Hell", "Hello", "Hello, wor", "Hello, world"] {
+        session.speak(partial, false).await.unwrap();
+    }
+    session.speak("Hello, world.", true).await.unwrap();
+    // Resending the flushed sentence starts a new utterance
+    session.speak("Bye", false).await.unwrap();
+    session.speak("Bye", true).await.unwrap();
+
+    assert_eq!(
+        spoken_with("resend-voice"),
+        vec!["Hel", "l", "o", ", wor", "ld", ".", "Bye", FLUSHED]
+    );
+    assert_eq!(
+        session.text_dedup_stats().unwrap(),
+        TextDedupStats {
+            deduplicated_chars: 3 + 4 + 5 + 10 + 12 + 3,
+            corrected_chars: 0,
+            replacements: 0,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_mid_sentence_revision_replaces_utterance() {
+    let session = build_session("revise-voice", true).await;
+
+    for partial in [
+        "Your table",
+        "Your table is booked for",
+        "Your room is booked",
+        "Your room is booked for two.",
+    ] {
+        session.speak(partial, false).await.unwrap();
+    }
+    // Flushing an unchanged partial only flushes the provider
+    session
+        .speak("Your room is booked for two.", true)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        spoken_with("revise-voice"),
+        vec![
+            "Your table",
+            " is booked for",
+            CLEARED,
+            "Your room is booked",
+            " for two.",
+            FLUSHED,
+        ]
+    );
+    let stats = session.text_dedup_stats().unwrap();
+    assert_eq!(stats.replacements, 1);
+    assert_eq!(stats.corrected_chars, "table is booked for".len() as u64);
+}
+
+#[tokio::test]
+async fn test_partials_sent_unchanged_when_disabled() {
+    let session = build_session("plain-voice", false).await;
+
+    for partial in ["Hel", "Hello"] {
+        session.speak(partial, false).await.unwrap();
+    }
+
+    assert_eq!(spoken_with("plain-voice"), vec!["Hel", "Hello"]);
+    assert!(session.text_dedup_stats().is_none());
+}
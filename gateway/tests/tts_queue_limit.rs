@@ -0,0 +1,205 @@
+//! # TTS Queue Limit Integration Tests
+//!
+//! Builds sessions on the in-process mock providers of
+//! `fixtures::session_mocks`, registered through the provider registry the
+//! same way dynamic plugins are. The mock TTS records every utterance and
+//! clear per voice id and only reports completion for voices starting with
+//! `complete`, so utterances stay pending.
+//!
+//! 1. The `reject` policy refuses text beyond the cap with
+//!    `VoiceManagerError::TTSQueueFull` and emits `TtsQueueFull`.
+//! 2. The `drop_oldest` policy drops the oldest pending utterance, re-sends
+//!    the rest and emits `TtsQueueFull` with the dropped text.
+//! 3. Completion empties the queue, so a finished utterance never counts
+//!    against the cap.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_queue_limit
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    EchoText, Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory,
+    tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::{AudioCallback, TTSConfig, TTSResult};
+use waav_gateway::core::voice_manager::{TTSQueueLimit, TTSQueuePolicy, VoiceManagerError};
+
+const MOCK_PROVIDER: &str = "tts-queue-limit-mock";
+
+/// Marker recorded when the mock TTS is cleared
+const CLEARED: &str = "<clear>";
+
+/// Every utterance and clear sent to the mock TTS, as (voice id, text)
+static SPOKEN: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Utterances and clears sent to `voice_id`
+fn spoken_with(voice_id: &str) -> Vec<String> {
+    SPOKEN
+        .lock()
+        .iter()
+        .filter(|(voice, _)| voice == voice_id)
+        .map(|(_, text)| text.clone())
+        .collect()
+}
+
+/// TTS behavior that records utterances and only completes for `complete*` voices
+struct RecordPerVoice {
+    voice_id: String,
+}
+
+#[async_trait]
+impl TtsBehavior for RecordPerVoice {
+    async fn speak(
+        &mut self,
+        text: &str,
+        flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), text.to_string()));
+
+        if self.voice_id.starts_with("complete") {
+            EchoText::default().speak(text, flush, audio).await?;
+        }
+        Ok(())
+    }
+
+    async fn clear(&mut self) -> TTSResult<()> {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), CLEARED.to_string()));
+        Ok(())
+    }
+}
+
+async fn build_session(voice_id: &str, max_pending: usize, policy: TTSQueuePolicy) -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|config| RecordPerVoice {
+            voice_id: config.voice_id.clone().unwrap_or_default(),
+        }),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            voice_id: Some(voice_id.to_string()),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .tts_queue_limit(TTSQueueLimit {
+            max_pending,
+            policy,
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+/// Wait for the next `TtsQueueFull` event, failing after a timeout
+async fn next_queue_full(events: &mut SessionEventStream) -> (TTSQueuePolicy, usize, String) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            if let SessionEvent::TtsQueueFull {
+                policy,
+                max_pending,
+                text,
+            } = event
+            {
+                return (policy, max_pending, text);
+            }
+        }
+        panic!("event stream ended before TtsQueueFull");
+    })
+    .await
+    .expect("TtsQueueFull event did not arrive")
+}
+
+#[tokio::test]
+async fn test_reject_policy_refuses_text_beyond_cap() {
+    let session = build_session("reject-voice", 2, TTSQueuePolicy::Reject).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("One.", false).await.unwrap();
+    session.speak("Two.", false).await.unwrap();
+    match session.speak("Three.", true).await {
+        Err(SessionError::VoiceManager(VoiceManagerError::TTSQueueFull { max_pending })) => {
+            assert_eq!(max_pending, 2);
+        }
+        other => panic!("expected TTSQueueFull, got {other:?}"),
+    }
+
+    assert_eq!(
+        next_queue_full(&mut events).await,
+        (TTSQueuePolicy::Reject, 2, "Three.".to_string())
+    );
+    assert_eq!(spoken_with("reject-voice"), vec!["One.", "Two."]);
+
+    let stats = session.tts_queue_stats().unwrap();
+    assert_eq!(stats.pending, 2);
+    assert_eq!(stats.max_pending, 2);
+    assert_eq!(stats.rejected, 1);
+    assert_eq!(stats.dropped, 0);
+
+    // Interrupting empties the queue
+    assert!(session.interrupt().await.unwrap());
+    assert_eq!(session.tts_queue_stats().unwrap().pending, 0);
+    session.speak("Four.", true).await.unwrap();
+    assert_eq!(session.tts_queue_stats().unwrap().pending, 1);
+}
+
+#[tokio::test]
+async fn test_drop_oldest_policy_replaces_oldest_utterance() {
+    let session = build_session("drop-voice", 2, TTSQueuePolicy::DropOldest).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("One.", false).await.unwrap();
+    session.speak("Two.", false).await.unwrap();
+    session.speak("Three.", true).await.unwrap();
+
+    assert_eq!(
+        next_queue_full(&mut events).await,
+        (TTSQueuePolicy::DropOldest, 2, "One.".to_string())
+    );
+    // The provider is cleared and everything but the oldest is sent again
+    assert_eq!(
+        spoken_with("drop-voice"),
+        vec!["One.", "Two.", CLEARED, "Two.", "Three."]
+    );
+
+    let stats = session.tts_queue_stats().unwrap();
+    assert_eq!(stats.pending, 2);
+    assert_eq!(stats.rejected, 0);
+    assert_eq!(stats.dropped, 1);
+}
+
+#[tokio::test]
+async fn test_completed_utterances_do_not_count_against_cap() {
+    let session = build_session("complete-voice", 1, TTSQueuePolicy::Reject).await;
+
+    for text in ["One.", "Two.", "Three."] {
+        session.speak(text, true).await.unwrap();
+    }
+
+    assert_eq!(
+        spoken_with("complete-voice"),
+        vec!["One.", "Two.", "Three."]
+    );
+    let stats = session.tts_queue_stats().unwrap();
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.rejected, 0);
+}
@@ -0,0 +1,193 @@
+//! # TTS Sample Rate Mismatch Integration Tests
+//!
+//! Runs sessions requesting 24kHz audio on top of an in-process mock TTS
+//! provider that returns audio at another rate, as Azure does for some voices
+//! and regions:
+//!
+//! 1. A 16kHz WAV file labelled 24kHz is relabelled from its header.
+//! 2. 22.05kHz MP3 frames labelled 24kHz are relabelled from the frame headers.
+//! 3. 16kHz raw PCM, reported as such, is resampled to 24kHz.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_sample_rate
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::audio_fixtures::{create_mp3_file, create_wav_file, generate_a440_tone};
+use fixtures::session_mocks::{
+    Silent, TtsBehavior, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSResult};
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+const MOCK_STT: &str = "sample-rate-mock";
+const MOCK_TTS: &str = "sample-rate-mock-tts";
+
+/// Sample rate every session requests
+const REQUESTED_RATE: u32 = 24000;
+
+/// Samples of 16kHz audio the mock produces per `speak()` call (300ms)
+const PCM_SAMPLES: usize = 4800;
+
+/// MP3 frames the mock produces per `speak()` call
+const MP3_FRAMES: usize = 12;
+
+/// TTS behavior that ignores the requested rate and labels its chunks with it
+///
+/// `wav` and `mp3` output is a 16kHz WAV file and 22.05kHz MP3 frames; raw
+/// PCM is 16kHz and reported as 16kHz, as a provider sending the rate in a
+/// metadata message would.
+struct MislabeledRate {
+    format: String,
+    requested_rate: u32,
+}
+
+impl MislabeledRate {
+    fn utterance(&self) -> (Vec<u8>, u32) {
+        let tone = generate_a440_tone(PCM_SAMPLES);
+        match self.format.as_str() {
+            "wav" => (create_wav_file(&tone), self.requested_rate),
+            "mp3" => (create_mp3_file(MP3_FRAMES), self.requested_rate),
+            _ => (tone.iter().flat_map(|s| s.to_le_bytes()).collect(), 16000),
+        }
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for MislabeledRate {
+    async fn speak(
+        &mut self,
+        _text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        if let Some(callback) = audio {
+            let (audio, sample_rate) = self.utterance();
+            // Only the first chunk carries the container header
+            for chunk in audio.chunks(333) {
+                callback
+                    .on_audio(AudioData {
+                        data: chunk.to_vec(),
+                        sample_rate,
+                        format: self.format.clone(),
+                        duration_ms: None,
+                    })
+                    .await;
+            }
+            callback.on_complete().await;
+        }
+        Ok(())
+    }
+}
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(
+        MOCK_STT,
+        stt_factory(|_| Silent),
+        ProviderMetadata::stt(MOCK_STT, "Sample Rate Mock STT"),
+    );
+    registry.register_tts(
+        MOCK_TTS,
+        tts_factory(|config| MislabeledRate {
+            format: config.audio_format.clone().unwrap_or_default(),
+            requested_rate: config.sample_rate.unwrap_or_default(),
+        }),
+        ProviderMetadata::tts(MOCK_TTS, "Sample Rate Mock TTS"),
+    );
+    registry
+}
+
+async fn build_session(format: &str) -> Session {
+    SessionPipelineBuilder::new()
+        .plugin_registry(mock_registry())
+        .stt(mock_stt_config(MOCK_STT))
+        .tts(TTSConfig {
+            audio_format: Some(format.to_string()),
+            sample_rate: Some(REQUESTED_RATE),
+            ..mock_tts_config(MOCK_TTS)
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("session should build with mock providers")
+}
+
+/// Collect audio events until the utterance completes
+async fn collect_chunks(events: &mut SessionEventStream) -> Vec<AudioData> {
+    let mut chunks = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            match event {
+                SessionEvent::Audio(audio) => chunks.push(audio),
+                SessionEvent::SpeechComplete { .. } => break,
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("speech should complete");
+    chunks
+}
+
+async fn speak_and_collect(format: &str) -> Vec<AudioData> {
+    let session = build_session(format).await;
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Hello there", true).await.unwrap();
+    let chunks = collect_chunks(&mut events).await;
+
+    session.close().await.unwrap();
+    chunks
+}
+
+#[tokio::test]
+async fn test_wav_at_other_rate_is_relabelled() {
+    let chunks = speak_and_collect("wav").await;
+    assert!(!chunks.is_empty());
+    // Chunks after the header are labelled with the header's rate too
+    assert!(chunks.iter().all(|chunk| chunk.sample_rate == 16000));
+
+    // The file itself is passed through for the client to decode
+    let audio: Vec<u8> = chunks.iter().flat_map(|c| c.data.clone()).collect();
+    assert_eq!(&audio[24..28], &16000u32.to_le_bytes());
+    assert_eq!(audio.len(), 44 + PCM_SAMPLES * 2);
+}
+
+#[tokio::test]
+async fn test_mp3_at_other_rate_is_relabelled() {
+    let chunks = speak_and_collect("mp3").await;
+    assert!(!chunks.is_empty());
+    assert!(chunks.iter().all(|chunk| chunk.sample_rate == 22050));
+    let bytes: usize = chunks.iter().map(|c| c.data.len()).sum();
+    assert_eq!(bytes, create_mp3_file(MP3_FRAMES).len());
+}
+
+#[tokio::test]
+async fn test_pcm_at_other_rate_is_resampled() {
+    let chunks = speak_and_collect("linear16").await;
+    assert!(!chunks.is_empty());
+    assert!(
+        chunks
+            .iter()
+            .all(|chunk| chunk.sample_rate == REQUESTED_RATE)
+    );
+
+    // 300ms of audio stays 300ms long at the requested rate
+    let samples: usize = chunks.iter().map(|c| c.data.len() / 2).sum();
+    let expected = PCM_SAMPLES * REQUESTED_RATE as usize / 16000;
+    assert!(
+        samples.abs_diff(expected) <= 2,
+        "expected about {expected} samples, got {samples}"
+    );
+}
@@ -0,0 +1,218 @@
+//! # TTS Telephony Profile Integration Tests
+//!
+//! Runs sessions with `output_profile: telephony` on top of in-process mock TTS
+//! providers that produce different native formats:
+//!
+//! 1. 8kHz μ-law (like Azure and ElevenLabs), reframed only.
+//! 2. 8kHz PCM (like Polly), μ-law encoded.
+//! 3. 24kHz linear16 (like Deepgram), resampled and μ-law encoded.
+//!
+//! Every audio event must be a 20ms frame of 8kHz μ-law (160 bytes).
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_telephony
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fixtures::session_mocks::{
+    Silent, TtsBehavior, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::{
+    AudioCallback, AudioData, TELEPHONY_FRAME_BYTES, TELEPHONY_FRAME_MS, TELEPHONY_SAMPLE_RATE,
+    TTSConfig, TTSOutputProfile, TTSResult,
+};
+use waav_gateway::plugin::{PluginRegistry, ProviderMetadata};
+
+const MOCK_STT: &str = "telephony-mock";
+const MULAW_PROVIDER: &str = "telephony-mock-mulaw";
+const PCM_8K_PROVIDER: &str = "telephony-mock-pcm8k";
+const PCM_24K_PROVIDER: &str = "telephony-mock-pcm24k";
+
+/// Length of audio each mock produces per `speak()` call
+const UTTERANCE_MS: u32 = 310;
+
+/// TTS behavior that emits a tone in its configured format, in uneven chunks
+struct NativeTone {
+    format: String,
+    sample_rate: u32,
+}
+
+impl NativeTone {
+    fn utterance(&self) -> Vec<u8> {
+        let samples = (self.sample_rate * UTTERANCE_MS / 1000) as usize;
+        let tone = (0..samples).map(|i| ((i as f32 * 0.07).sin() * 6000.0) as i16);
+        match self.format.as_str() {
+            "mulaw" | "ulaw" => tone.map(|s| (s >> 8) as u8).collect(),
+            _ => tone.flat_map(|s| s.to_le_bytes()).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl TtsBehavior for NativeTone {
+    async fn speak(
+        &mut self,
+        _text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        if let Some(callback) = audio {
+            // Odd chunk size splits PCM samples and frames across chunks
+            for chunk in self.utterance().chunks(333) {
+                callback
+                    .on_audio(AudioData {
+                        data: chunk.to_vec(),
+                        sample_rate: self.sample_rate,
+                        format: self.format.clone(),
+                        duration_ms: None,
+                    })
+                    .await;
+            }
+            callback.on_complete().await;
+        }
+        Ok(())
+    }
+}
+
+fn mock_registry() -> Arc<PluginRegistry> {
+    let registry = Arc::new(PluginRegistry::new_isolated());
+    registry.register_stt(
+        MOCK_STT,
+        stt_factory(|_| Silent),
+        ProviderMetadata::stt(MOCK_STT, "Telephony Mock STT"),
+    );
+    for name in [MULAW_PROVIDER, PCM_8K_PROVIDER, PCM_24K_PROVIDER] {
+        registry.register_tts(
+            name,
+            tts_factory(|config| NativeTone {
+                format: config.audio_format.clone().unwrap_or_default(),
+                sample_rate: config.sample_rate.unwrap_or_default(),
+            }),
+            ProviderMetadata::tts(name, "Telephony Mock TTS"),
+        );
+    }
+    registry
+}
+
+async fn build_session(
+    provider: &str,
+    format: &str,
+    sample_rate: u32,
+) -> Result<Session, SessionError> {
+    SessionPipelineBuilder::new()
+        .plugin_registry(mock_registry())
+        .stt(mock_stt_config(MOCK_STT))
+        .tts(TTSConfig {
+            audio_format: Some(format.to_string()),
+            sample_rate: Some(sample_rate),
+            output_profile: Some(TTSOutputProfile::Telephony),
+            ..mock_tts_config(provider)
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+}
+
+/// Collect audio events until the utterance completes
+async fn collect_frames(events: &mut SessionEventStream) -> Vec<AudioData> {
+    let mut frames = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            match event {
+                SessionEvent::Audio(audio) => frames.push(audio),
+                SessionEvent::SpeechComplete { .. } => break,
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("speech should complete");
+    frames
+}
+
+fn assert_telephony_frames(frames: &[AudioData]) {
+    // 310ms of audio is 15.5 frames; the last one is padded
+    assert_eq!(frames.len(), 16);
+    for frame in frames {
+        assert_eq!(frame.data.len(), TELEPHONY_FRAME_BYTES);
+        assert_eq!(frame.sample_rate, TELEPHONY_SAMPLE_RATE);
+        assert_eq!(frame.format, "mulaw");
+        assert_eq!(frame.duration_ms, Some(TELEPHONY_FRAME_MS));
+    }
+}
+
+async fn speak_and_collect(provider: &str, format: &str, sample_rate: u32) -> Vec<AudioData> {
+    let session = build_session(provider, format, sample_rate)
+        .await
+        .expect("session should build with mock providers");
+    assert_eq!(session.output_sample_rate(), TELEPHONY_SAMPLE_RATE);
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Hello caller", true).await.unwrap();
+    let frames = collect_frames(&mut events).await;
+
+    session.close().await.unwrap();
+    frames
+}
+
+#[tokio::test]
+async fn test_native_mulaw_provider_is_reframed() {
+    let frames = speak_and_collect(MULAW_PROVIDER, "mulaw", 8000).await;
+    assert_telephony_frames(&frames);
+}
+
+#[tokio::test]
+async fn test_8khz_pcm_provider_is_encoded() {
+    let frames = speak_and_collect(PCM_8K_PROVIDER, "pcm", 8000).await;
+    assert_telephony_frames(&frames);
+}
+
+#[tokio::test]
+async fn test_24khz_pcm_provider_is_resampled() {
+    let frames = speak_and_collect(PCM_24K_PROVIDER, "linear16", 24000).await;
+    assert_telephony_frames(&frames);
+}
+
+#[tokio::test]
+async fn test_unconvertible_format_is_rejected_at_setup() {
+    let result = build_session(PCM_24K_PROVIDER, "mp3", 24000).await;
+    assert!(result.is_err(), "mp3 output cannot be served as telephony");
+
+    let result = build_session(MULAW_PROVIDER, "mulaw", 16000).await;
+    assert!(
+        result.is_err(),
+        "wideband μ-law cannot be served as telephony"
+    );
+}
+
+#[tokio::test]
+async fn test_injected_audio_is_framed() {
+    let session = build_session(PCM_24K_PROVIDER, "linear16", 24000)
+        .await
+        .expect("session should build with mock providers");
+    let mut events = session.take_events().unwrap();
+
+    // 100ms of 16kHz PCM becomes five 20ms frames
+    let pcm: Vec<u8> = (0..1600i16).flat_map(|s| s.to_le_bytes()).collect();
+    session.play_audio(pcm, 16000, true).await.unwrap();
+
+    let frames = collect_frames(&mut events).await;
+    assert_eq!(frames.len(), 5);
+    assert!(
+        frames
+            .iter()
+            .all(|f| f.data.len() == TELEPHONY_FRAME_BYTES && f.format == "mulaw")
+    );
+
+    session.close().await.unwrap();
+}
@@ -0,0 +1,259 @@
+//! # TTS Per-Utterance Timeout Test
+//!
+//! Verifies that the HTTP TTS dispatch layer abandons an utterance when the
+//! provider stops producing audio mid-stream:
+//!
+//! 1. Audio emitted before the stall is still delivered to the callback.
+//! 2. The error callback receives `TTSError::TimeoutError`.
+//! 3. The utterance is counted as incomplete in the provider stats.
+//! 4. Later utterances in the same session are not blocked by the stalled one.
+//!
+//! The mock provider is a raw TCP server so the response can be streamed with
+//! chunked encoding and then held open without sending more audio.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_utterance_timeout
+//! ```
+
+use futures::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSError};
+use waav_gateway::core::tts::{TTSProvider, TTSRequestBuilder};
+use waav_gateway::utils::req_manager::ReqManager;
+
+/// Bytes of PCM sent before the mock provider stalls (40ms at 24kHz linear16)
+const STALL_AUDIO_BYTES: usize = 1920;
+
+/// Bytes of PCM sent by a well-behaved response (100ms at 24kHz linear16)
+const FULL_AUDIO_BYTES: usize = 4800;
+
+#[derive(Clone)]
+struct MockTTSRequestBuilder {
+    config: TTSConfig,
+    base_url: String,
+}
+
+impl TTSRequestBuilder for MockTTSRequestBuilder {
+    fn build_http_request(&self, client: &reqwest::Client, text: &str) -> reqwest::RequestBuilder {
+        client
+            .post(format!("{}/tts", self.base_url))
+            .header("Content-Type", "text/plain")
+            .body(text.to_string())
+    }
+
+    fn get_config(&self) -> &TTSConfig {
+        &self.config
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingCallback {
+    audio_bytes: Arc<Mutex<usize>>,
+    errors: Arc<Mutex<Vec<TTSError>>>,
+    completions: Arc<Mutex<usize>>,
+}
+
+impl AudioCallback for RecordingCallback {
+    fn on_audio(&self, audio_data: AudioData) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            *self.audio_bytes.lock().await += audio_data.data.len();
+        })
+    }
+
+    fn on_error(&self, error: TTSError) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            self.errors.lock().await.push(error);
+        })
+    }
+
+    fn on_complete(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            *self.completions.lock().await += 1;
+        })
+    }
+}
+
+/// Read one HTTP request and return its body
+async fn read_request_body(stream: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            return String::new();
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let text = String::from_utf8_lossy(&buf).to_string();
+        let Some(header_end) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let content_length = text[..header_end]
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())
+                    .flatten()
+            })
+            .unwrap_or(0);
+        if buf.len() >= header_end + 4 + content_length {
+            return text[header_end + 4..].to_string();
+        }
+    }
+}
+
+/// Start a mock TTS server.
+///
+/// Requests whose text contains "stall" get a partial chunked response that is
+/// then held open; all other requests get a complete response.
+async fn start_mock_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let body = read_request_body(&mut stream).await;
+
+                if body.contains("stall") {
+                    let head = "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nTransfer-Encoding: chunked\r\n\r\n";
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    let _ = stream
+                        .write_all(format!("{:x}\r\n", STALL_AUDIO_BYTES).as_bytes())
+                        .await;
+                    let _ = stream.write_all(&vec![1u8; STALL_AUDIO_BYTES]).await;
+                    let _ = stream.write_all(b"\r\n").await;
+                    let _ = stream.flush().await;
+                    // Hold the connection open without sending more audio
+                    sleep(Duration::from_secs(60)).await;
+                } else {
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        FULL_AUDIO_BYTES
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    let _ = stream.write_all(&vec![2u8; FULL_AUDIO_BYTES]).await;
+                    let _ = stream.flush().await;
+                }
+            });
+        }
+    });
+
+    format!("http://{}", addr)
+}
+
+async fn create_provider(
+    base_url: &str,
+) -> (TTSProvider, RecordingCallback, MockTTSRequestBuilder) {
+    let mut provider = TTSProvider::new().unwrap();
+    let req_manager = ReqManager::new(4).await.unwrap();
+    provider.set_req_manager(Arc::new(req_manager)).await;
+
+    let callback = RecordingCallback::default();
+    provider
+        .generic_on_audio(Arc::new(callback.clone()))
+        .unwrap();
+
+    let config = TTSConfig {
+        audio_format: Some("linear16".to_string()),
+        sample_rate: Some(24000),
+        utterance_timeout: Some(1),
+        ..Default::default()
+    };
+    provider
+        .generic_connect_with_config(base_url, &config)
+        .await
+        .unwrap();
+
+    let builder = MockTTSRequestBuilder {
+        config,
+        base_url: base_url.to_string(),
+    };
+    (provider, callback, builder)
+}
+
+#[tokio::test]
+async fn test_stalled_utterance_times_out_with_partial_audio() {
+    let base_url = start_mock_server().await;
+    let (mut provider, callback, builder) = create_provider(&base_url).await;
+
+    provider
+        .generic_speak(builder, "please stall after the first chunk", true)
+        .await
+        .unwrap();
+
+    // Timeout is 1s; allow time for the partial audio and the timeout to fire
+    sleep(Duration::from_millis(2000)).await;
+
+    assert_eq!(*callback.audio_bytes.lock().await, STALL_AUDIO_BYTES);
+
+    let errors = callback.errors.lock().await.clone();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], TTSError::TimeoutError(_)));
+
+    let stats = provider.get_stats().await;
+    assert_eq!(stats.utterances_incomplete, 1);
+    assert_eq!(stats.utterance_timeouts, 1);
+    assert_eq!(stats.utterances_completed, 0);
+    assert_eq!(stats.total_audio_bytes, STALL_AUDIO_BYTES as u64);
+
+    // Completion still fires so the session does not wait on the stalled utterance
+    assert_eq!(*callback.completions.lock().await, 1);
+}
+
+#[tokio::test]
+async fn test_stalled_utterance_does_not_block_queue() {
+    let base_url = start_mock_server().await;
+    let (mut provider, callback, builder) = create_provider(&base_url).await;
+
+    provider
+        .generic_speak(builder.clone(), "stall here", false)
+        .await
+        .unwrap();
+    provider
+        .generic_speak(builder, "then finish normally", false)
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(2500)).await;
+
+    assert_eq!(
+        *callback.audio_bytes.lock().await,
+        STALL_AUDIO_BYTES + FULL_AUDIO_BYTES
+    );
+
+    let stats = provider.get_stats().await;
+    assert_eq!(stats.utterances_incomplete, 1);
+    assert_eq!(stats.utterances_completed, 1);
+}
+
+#[tokio::test]
+async fn test_utterance_timeout_disabled() {
+    let base_url = start_mock_server().await;
+    let (mut provider, callback, mut builder) = create_provider(&base_url).await;
+    builder.config.utterance_timeout = None;
+
+    provider
+        .generic_speak(builder, "stall with no timeout", true)
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(1500)).await;
+
+    // Partial audio is delivered but the utterance is still in progress
+    assert_eq!(*callback.audio_bytes.lock().await, STALL_AUDIO_BYTES);
+    assert!(callback.errors.lock().await.is_empty());
+    assert_eq!(provider.get_stats().await.utterance_timeouts, 0);
+
+    provider.generic_clear().await.unwrap();
+}
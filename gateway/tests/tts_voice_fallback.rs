@@ -0,0 +1,316 @@
+//! # TTS Voice Fallback Integration Tests
+//!
+//! Builds sessions on the in-process mock providers of
+//! `fixtures::session_mocks`. The mock TTS reports
+//! `TTSError::VoiceNotFound` for every voice id starting with `deleted`, the
+//! way ElevenLabs answers for a voice removed from the account, and records
+//! which voice each utterance was sent to.
+//!
+//! 1. A missing voice is retried once with the fallback voice, which is then
+//!    used for every later utterance and reported as `VoiceFallback`.
+//! 2. A missing fallback voice is reported instead of retried again.
+//! 3. Without a fallback, session setup fails during voice validation.
+//! 4. ElevenLabs voice validation maps `voice_not_found` to `VoiceNotFound`.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test tts_voice_fallback
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use fixtures::session_mocks::{
+    EchoText, Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory,
+    tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionError, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::tts::elevenlabs::ElevenLabsTTS;
+use waav_gateway::core::tts::{AudioCallback, BaseTTS, TTSConfig, TTSError, TTSResult};
+use waav_gateway::core::voice_manager::VoiceManagerError;
+
+const MOCK_PROVIDER: &str = "voice-fallback-mock";
+
+/// Every utterance sent to the mock TTS, as (voice id, text)
+static SPOKEN: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Utterances sent to `voice_id`
+fn spoken_with(voice_id: &str) -> Vec<String> {
+    SPOKEN
+        .lock()
+        .iter()
+        .filter(|(voice, _)| voice == voice_id)
+        .map(|(_, text)| text.clone())
+        .collect()
+}
+
+fn is_deleted(voice_id: &str) -> bool {
+    voice_id.starts_with("deleted")
+}
+
+/// TTS behavior that fails every utterance for deleted voices
+struct DeletedVoices {
+    voice_id: String,
+}
+
+#[async_trait]
+impl TtsBehavior for DeletedVoices {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        SPOKEN
+            .lock()
+            .push((self.voice_id.clone(), text.to_string()));
+
+        let Some(callback) = audio else {
+            return Ok(());
+        };
+        if is_deleted(&self.voice_id) {
+            callback
+                .on_error(TTSError::VoiceNotFound(format!(
+                    "API error (404 Not Found): voice '{}' was not found",
+                    self.voice_id
+                )))
+                .await;
+            return Ok(());
+        }
+        EchoText::default().play(text, callback).await;
+        Ok(())
+    }
+
+    async fn validate_voice(&self) -> TTSResult<()> {
+        if is_deleted(&self.voice_id) {
+            return Err(TTSError::VoiceNotFound(self.voice_id.clone()));
+        }
+        Ok(())
+    }
+}
+
+async fn build_session(
+    voice_id: &str,
+    fallback_voice: Option<&str>,
+) -> Result<Session, SessionError> {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|config| DeletedVoices {
+            voice_id: config.voice_id.clone().unwrap_or_default(),
+        }),
+    );
+    let mut builder = SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            voice_id: Some(voice_id.to_string()),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .ready_timeout(Duration::from_secs(5));
+    if let Some(fallback_voice) = fallback_voice {
+        builder = builder.fallback_voice(fallback_voice);
+    }
+    builder.build().await
+}
+
+/// Collect events until `predicate` matches, failing after a timeout
+async fn collect_until(
+    events: &mut SessionEventStream,
+    predicate: impl Fn(&SessionEvent) -> bool,
+) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            let done = predicate(&event);
+            collected.push(event);
+            if done {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("expected session event did not arrive");
+    collected
+}
+
+fn fallback_count(events: &[SessionEvent]) -> usize {
+    events
+        .iter()
+        .filter(|event| matches!(event, SessionEvent::VoiceFallback { .. }))
+        .count()
+}
+
+fn tts_errors(events: &[SessionEvent]) -> Vec<&TTSError> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::TtsError { error, .. } => Some(error),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_missing_voice_retries_once_with_fallback_and_pins_it() {
+    let session = build_session("deleted-voice-a", Some("backup-voice-a"))
+        .await
+        .expect("validation is skipped when a fallback is configured");
+    let mut events = session.take_events().unwrap();
+
+    let turn_id = session
+        .speak_in_turn("Hello there.", true, true, Some("turn-a"))
+        .await
+        .unwrap();
+    assert_eq!(turn_id.as_deref(), Some("turn-a"));
+    let mut collected = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::SpeechComplete { .. })
+    })
+    .await;
+
+    // The failed utterance was retried exactly once, with the fallback voice
+    assert_eq!(spoken_with("deleted-voice-a"), vec!["Hello there."]);
+    assert_eq!(spoken_with("backup-voice-a"), vec!["Hello there."]);
+    assert!(collected.iter().any(|event| matches!(
+        event,
+        SessionEvent::Audio(audio) if audio.data == b"Hello there."
+    )));
+    assert!(tts_errors(&collected).is_empty());
+
+    // The switch is reported after the replay
+    if fallback_count(&collected) == 0 {
+        collected.extend(
+            collect_until(&mut events, |event| {
+                matches!(event, SessionEvent::VoiceFallback { .. })
+            })
+            .await,
+        );
+    }
+    assert!(collected.iter().any(|event| matches!(
+        event,
+        SessionEvent::VoiceFallback { from_voice_id, to_voice_id, .. }
+            if from_voice_id == "deleted-voice-a" && to_voice_id == "backup-voice-a"
+    )));
+
+    // The replayed utterance keeps its turn across the switch
+    for event in &collected {
+        match event {
+            SessionEvent::SpeechStarted { turn_id, .. } => assert_eq!(turn_id, "turn-a"),
+            SessionEvent::SpeechComplete { turn_id, .. }
+            | SessionEvent::VoiceFallback { turn_id, .. } => {
+                assert_eq!(turn_id.as_deref(), Some("turn-a"))
+            }
+            _ => {}
+        }
+    }
+
+    // Later utterances go straight to the pinned fallback voice
+    session.speak("How can I help?", true).await.unwrap();
+    let later = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::SpeechComplete { .. })
+    })
+    .await;
+    assert_eq!(spoken_with("deleted-voice-a"), vec!["Hello there."]);
+    assert_eq!(
+        spoken_with("backup-voice-a"),
+        vec!["Hello there.", "How can I help?"]
+    );
+    assert_eq!(fallback_count(&collected) + fallback_count(&later), 1);
+    assert!(tts_errors(&later).is_empty());
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_missing_fallback_voice_is_reported_without_further_retries() {
+    let session = build_session("deleted-voice-b", Some("deleted-backup-voice-b"))
+        .await
+        .unwrap();
+    let mut events = session.take_events().unwrap();
+
+    session.speak("Hello there.", true).await.unwrap();
+    let collected = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::TtsError { .. })
+    })
+    .await;
+
+    assert!(matches!(
+        tts_errors(&collected).as_slice(),
+        [TTSError::VoiceNotFound(_)]
+    ));
+    assert_eq!(spoken_with("deleted-voice-b"), vec!["Hello there."]);
+    assert_eq!(spoken_with("deleted-backup-voice-b"), vec!["Hello there."]);
+
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_missing_voice_without_fallback_fails_setup() {
+    let result = build_session("deleted-voice-c", None).await;
+
+    assert!(matches!(
+        result,
+        Err(SessionError::StartFailed(VoiceManagerError::TTSError(
+            TTSError::VoiceNotFound(_)
+        )))
+    ));
+    assert!(spoken_with("deleted-voice-c").is_empty());
+
+    // An existing voice passes validation
+    let session = build_session("voice-c", None).await.unwrap();
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_elevenlabs_validate_voice() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/voices/existing-voice"))
+        .and(header("xi-api-key", "test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "voice_id": "existing-voice",
+            "name": "Rachel"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/voices/deleted-voice"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "detail": {
+                "status": "voice_not_found",
+                "message": "A voice with voice_id 'deleted-voice' was not found."
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let tts = |voice_id: &str| {
+        ElevenLabsTTS::new(TTSConfig {
+            provider: "elevenlabs".to_string(),
+            api_key: "test-key".to_string(),
+            voice_id: Some(voice_id.to_string()),
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    assert!(
+        tts("existing-voice")
+            .validate_voice_at(&server.uri())
+            .await
+            .is_ok()
+    );
+    assert!(matches!(
+        tts("deleted-voice").validate_voice_at(&server.uri()).await,
+        Err(TTSError::VoiceNotFound(_))
+    ));
+}
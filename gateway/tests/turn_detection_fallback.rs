@@ -0,0 +1,188 @@
+//! # Turn Detection Fallback Integration Tests
+//!
+//! Forces the silence-based fallback by pointing the turn detector at a model
+//! file that does not exist, and runs a session against a mock STT provider
+//! from `fixtures::session_mocks`:
+//!
+//! 1. Loading the model from a missing path reports the failure in the
+//!    startup health instead of failing (requires the `turn-detect` feature)
+//! 2. A session that expected the model but has none reports
+//!    `TurnDetectionDegraded` and the silence VAD mode in its stats
+//! 3. In silence VAD mode `speech_final` is withheld while the caller keeps
+//!    talking and follows once the caller's audio goes quiet
+//!
+//! The mock STT sends a final, but never a speech-final, transcript at the
+//! start of each burst of non-silent audio, so the gateway has to decide the
+//! end of the turn itself.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test turn_detection_fallback
+//! cargo test --features turn-detect --test turn_detection_fallback
+//! ```
+
+mod fixtures;
+
+use bytes::Bytes;
+use std::time::{Duration, Instant};
+
+use fixtures::session_mocks::{
+    PerBurst, Silent, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::stt::STTResult;
+use waav_gateway::core::turn_detect::TurnDetectionMode;
+use waav_gateway::core::voice_manager::{SpeechFinalConfig, TurnDetectionDegradedReason};
+
+const MOCK_PROVIDER: &str = "turn-detection-fallback-mock";
+
+/// 100ms of 16kHz PCM16 speech and silence
+const VOICED: [u8; 3200] = [0x40; 3200];
+const SILENCE: [u8; 3200] = [0; 3200];
+
+/// Session that expected a turn detection model but did not get one
+async fn build_degraded_session() -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        // A final, but never a speech-final, transcript per burst
+        stt_factory(|_| {
+            PerBurst::new(|_, _| {
+                vec![STTResult::new(
+                    "I was wondering".to_string(),
+                    true,
+                    false,
+                    0.9,
+                )]
+            })
+        }),
+        tts_factory(|_| Silent),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(mock_tts_config(MOCK_PROVIDER))
+        .turn_detector(None)
+        .turn_detector_expected(true)
+        .speech_final_config(SpeechFinalConfig {
+            stt_speech_final_wait_ms: 100,
+            speech_final_hard_timeout_ms: 3000,
+            ..Default::default()
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+/// Collect events until `predicate` matches, failing after a timeout
+async fn collect_until(
+    events: &mut SessionEventStream,
+    predicate: impl Fn(&SessionEvent) -> bool,
+) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            let done = predicate(&event);
+            collected.push(event);
+            if done {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("expected session event did not arrive");
+    collected
+}
+
+fn is_speech_final(event: &SessionEvent) -> bool {
+    matches!(event, SessionEvent::Transcript(result) if result.is_speech_final)
+}
+
+#[cfg(feature = "turn-detect")]
+#[tokio::test]
+async fn test_missing_model_path_reports_unhealthy() {
+    use waav_gateway::core::turn_detect::{TurnDetectorConfig, load_turn_detector};
+
+    let missing = std::env::temp_dir().join("waav-missing-turn-detector/model.onnx");
+    let (detector, health) = load_turn_detector(TurnDetectorConfig {
+        model_path: Some(missing),
+        ..Default::default()
+    })
+    .await;
+
+    assert!(detector.is_none());
+    assert!(health.enabled);
+    assert!(!health.model_loaded);
+    assert!(health.is_degraded());
+    assert_eq!(health.mode, TurnDetectionMode::SilenceVad);
+    let error = health.error.expect("the load error is reported");
+    assert!(error.contains("waav-missing-turn-detector"), "{error}");
+}
+
+#[tokio::test]
+async fn test_missing_model_degrades_session() {
+    let session = build_degraded_session().await;
+    let mut events = session.take_events().unwrap();
+
+    let collected = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::TurnDetectionDegraded(_))
+    })
+    .await;
+    let Some(SessionEvent::TurnDetectionDegraded(degraded)) = collected.last() else {
+        unreachable!();
+    };
+    assert_eq!(
+        degraded.reason,
+        TurnDetectionDegradedReason::ModelUnavailable
+    );
+    assert_eq!(degraded.inference_ms, None);
+
+    let stats = session.turn_detection_stats().expect("voice session");
+    assert_eq!(stats.mode, TurnDetectionMode::SilenceVad);
+    assert_eq!(
+        stats.degraded_reason,
+        Some(TurnDetectionDegradedReason::ModelUnavailable)
+    );
+    assert_eq!(stats.inferences, 0);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_silence_vad_waits_for_caller_to_stop() {
+    let session = build_degraded_session().await;
+    let mut events = session.take_events().unwrap();
+
+    // Keep talking for well past the STT wait
+    let started = Instant::now();
+    for _ in 0..8 {
+        session
+            .push_audio(Bytes::from_static(&VOICED))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let mut speech_final_during_speech = false;
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(20), events.recv()).await
+    {
+        speech_final_during_speech |= is_speech_final(&event);
+    }
+    assert!(
+        !speech_final_during_speech,
+        "speech_final must wait while the caller is still talking"
+    );
+
+    // Going quiet ends the turn, long before the hard timeout
+    session
+        .push_audio(Bytes::from_static(&SILENCE))
+        .await
+        .unwrap();
+    collect_until(&mut events, is_speech_final).await;
+    assert!(started.elapsed() < Duration::from_millis(3000));
+
+    let stats = session.turn_detection_stats().unwrap();
+    assert_eq!(stats.silence_vad_turns, 1);
+    session.close().await.unwrap();
+}
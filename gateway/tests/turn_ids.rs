@@ -0,0 +1,464 @@
+//! # Turn ID Integration Tests
+//!
+//! Scripts a two-turn conversation against the mock providers of
+//! `fixtures::session_mocks` and checks that transcripts, speech and errors are
+//! tagged consistently:
+//!
+//! 1. Through the session API: events and the turn IDs in `usage()`
+//! 2. Through `/ws`: `stt_result`, `tts_playback_started`,
+//!    `tts_playback_complete` and `error` messages
+//!
+//! The mock STT answers each burst of non-silent audio with an interim and a
+//! speech-final transcript. The mock TTS answers every utterance with a chunk
+//! of audio, except text starting with "fail", which it reports as an error.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test turn_ids
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use axum::middleware;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+use fixtures::session_mocks::{
+    Clip, PerBurst, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory,
+    tts_factory,
+};
+use waav_gateway::core::session::{
+    Session, SessionEvent, SessionEventStream, SessionPipelineBuilder,
+};
+use waav_gateway::core::stt::STTResult;
+use waav_gateway::core::tts::{AudioCallback, TTSError, TTSResult};
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::{
+    ServerConfig, config::PluginConfig, middleware::auth::auth_middleware, routes, state::AppState,
+};
+
+const MOCK_PROVIDER: &str = "turn-ids-mock";
+
+/// 100ms of 16kHz PCM16 speech and silence
+const VOICED: [u8; 3200] = [0x40; 3200];
+const SILENCE: [u8; 3200] = [0; 3200];
+
+/// TTS behavior that speaks every utterance except those starting with "fail"
+struct FailOnFailText;
+
+#[async_trait]
+impl TtsBehavior for FailOnFailText {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        let Some(callback) = audio else {
+            return Ok(());
+        };
+        if text.starts_with("fail") {
+            callback
+                .on_error(TTSError::ProviderError("synthesis failed".to_string()))
+                .await;
+            return Ok(());
+        }
+        Clip {
+            data: vec![1; 2400],
+            sample_rate: 24000,
+            duration_ms: Some(50),
+        }
+        .play(callback)
+        .await;
+        Ok(())
+    }
+}
+
+fn providers() -> Arc<PluginRegistry> {
+    mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| {
+            PerBurst::new(|burst, _| {
+                let text = format!("user turn {burst}");
+                vec![
+                    STTResult::new(text.clone(), false, false, 0.5),
+                    STTResult::new(text, true, true, 0.9),
+                ]
+            })
+        }),
+        tts_factory(|_| FailOnFailText),
+    )
+}
+
+async fn build_session() -> Session {
+    SessionPipelineBuilder::new()
+        .plugin_registry(providers())
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(mock_tts_config(MOCK_PROVIDER))
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+/// Collect events until `predicate` matches, failing after a timeout
+async fn collect_until(
+    events: &mut SessionEventStream,
+    predicate: impl Fn(&SessionEvent) -> bool,
+) -> Vec<SessionEvent> {
+    let mut collected = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.recv().await {
+            let done = predicate(&event);
+            collected.push(event);
+            if done {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("expected session event did not arrive");
+    collected
+}
+
+/// Turn IDs of the transcripts in `events`
+fn transcript_turns(events: &[SessionEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::Transcript(result) => {
+                Some(result.turn_id.clone().expect("transcripts are tagged"))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Turn IDs of the speech start and completion events in `events`
+fn speech_turns(events: &[SessionEvent]) -> Vec<Option<String>> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::SpeechStarted { turn_id, .. } => Some(Some(turn_id.clone())),
+            SessionEvent::SpeechComplete { turn_id, .. } => Some(turn_id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_speech_final(event: &SessionEvent) -> bool {
+    matches!(event, SessionEvent::Transcript(result) if result.is_speech_final)
+}
+
+fn is_speech_complete(event: &SessionEvent) -> bool {
+    matches!(event, SessionEvent::SpeechComplete { .. })
+}
+
+#[tokio::test]
+async fn test_session_tags_two_turn_conversation() {
+    let session = build_session().await;
+    let mut events = session.take_events().unwrap();
+
+    // Turn 1: the reply defaults to the user turn it answers
+    session
+        .push_audio(Bytes::from_static(&VOICED))
+        .await
+        .unwrap();
+    let user_1 = collect_until(&mut events, is_speech_final).await;
+    let turns_1 = transcript_turns(&user_1);
+    assert_eq!(turns_1.len(), 2);
+    assert_eq!(turns_1[0], turns_1[1]);
+    let turn_1 = turns_1[0].clone();
+
+    session.speak("Hi, how can I help?", true).await.unwrap();
+    let reply_1 = collect_until(&mut events, is_speech_complete).await;
+    assert_eq!(
+        speech_turns(&reply_1),
+        vec![Some(turn_1.clone()), Some(turn_1.clone())]
+    );
+    assert!(
+        reply_1
+            .iter()
+            .any(|event| matches!(event, SessionEvent::Audio(_)))
+    );
+
+    // Turn 2: a new user turn, answered under a client-provided ID
+    session
+        .push_audio(Bytes::from_static(&SILENCE))
+        .await
+        .unwrap();
+    session
+        .push_audio(Bytes::from_static(&VOICED))
+        .await
+        .unwrap();
+    let user_2 = collect_until(&mut events, is_speech_final).await;
+    let turns_2 = transcript_turns(&user_2);
+    assert_eq!(turns_2.len(), 2);
+    assert!(turns_2.iter().all(|turn| *turn == turns_2[0]));
+    let turn_2 = turns_2[0].clone();
+    assert_ne!(turn_2, turn_1);
+
+    let tagged = session
+        .speak_in_turn("Booking it now.", true, true, Some("reply-2"))
+        .await
+        .unwrap();
+    assert_eq!(tagged.as_deref(), Some("reply-2"));
+    let reply_2 = collect_until(&mut events, is_speech_complete).await;
+    assert_eq!(
+        speech_turns(&reply_2),
+        vec![Some("reply-2".to_string()), Some("reply-2".to_string())]
+    );
+
+    // Provider errors carry the turn of the failed speech
+    session
+        .speak_in_turn("fail this one", true, true, Some("reply-3"))
+        .await
+        .unwrap();
+    let failed = collect_until(&mut events, |event| {
+        matches!(event, SessionEvent::TtsError { .. })
+    })
+    .await;
+    assert!(matches!(
+        failed.last(),
+        Some(SessionEvent::TtsError { turn_id: Some(turn_id), .. }) if turn_id == "reply-3"
+    ));
+
+    let usage = session.usage();
+    assert_eq!(usage.turns, 4);
+    assert_eq!(
+        usage.turn_ids,
+        vec![turn_1, turn_2, "reply-2".to_string(), "reply-3".to_string()]
+    );
+}
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Serve `/ws` on a local port, `None` if binding is not allowed
+async fn start_gateway() -> Option<SocketAddr> {
+    let app_state = AppState::with_plugin_registry(test_config(), providers()).await;
+    let app = routes::ws::create_ws_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .with_state(app_state);
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping turn ID WebSocket test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind turn ID test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    Some(addr)
+}
+
+/// Next JSON message of type `kind`; other messages and audio are skipped
+async fn next_of<S>(stream: &mut S, kind: &str) -> Value
+where
+    S: futures::Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(message) = stream.next().await {
+            if let Message::Text(text) = message.unwrap() {
+                let value: Value = serde_json::from_str(&text).unwrap();
+                if value["type"] == kind {
+                    return value;
+                }
+            }
+        }
+        panic!("connection closed");
+    })
+    .await
+    .unwrap_or_else(|_| panic!("no {kind} message"))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_websocket_tags_two_turn_conversation() {
+    let Some(addr) = start_gateway().await else {
+        return;
+    };
+    let (ws, _) = connect_async(format!("ws://{addr}/ws")).await.unwrap();
+    let (mut sink, mut stream) = ws.split();
+
+    let config = json!({
+        "type": "config",
+        "audio": true,
+        "stt_config": {
+            "provider": MOCK_PROVIDER,
+            "api_key": "test-key",
+            "language": "en-US",
+            "sample_rate": 16000,
+            "channels": 1,
+            "punctuation": true,
+            "encoding": "linear16",
+            "model": "mock",
+        },
+        "tts_config": {
+            "provider": MOCK_PROVIDER,
+            "api_key": "test-key",
+            "model": "mock",
+            "audio_format": "linear16",
+        },
+    });
+    sink.send(Message::Text(config.to_string().into()))
+        .await
+        .unwrap();
+    next_of(&mut stream, "ready").await;
+
+    // Turn 1: server-generated ID on both transcripts and the reply
+    sink.send(Message::Binary(Bytes::from_static(&VOICED)))
+        .await
+        .unwrap();
+    let interim = next_of(&mut stream, "stt_result").await;
+    let turn_1 = interim["turn_id"].as_str().unwrap().to_string();
+    let last = next_of(&mut stream, "stt_result").await;
+    assert_eq!(last["is_speech_final"], true);
+    assert_eq!(last["turn_id"], turn_1.as_str());
+
+    let speak = json!({"type": "speak", "text": "Hi, how can I help?"});
+    sink.send(Message::Text(speak.to_string().into()))
+        .await
+        .unwrap();
+    let started = next_of(&mut stream, "tts_playback_started").await;
+    assert_eq!(started["turn_id"], turn_1.as_str());
+    let complete = next_of(&mut stream, "tts_playback_complete").await;
+    assert_eq!(complete["turn_id"], turn_1.as_str());
+
+    // Turn 2: a fresh user turn, answered under a client-provided ID
+    sink.send(Message::Binary(Bytes::from_static(&SILENCE)))
+        .await
+        .unwrap();
+    sink.send(Message::Binary(Bytes::from_static(&VOICED)))
+        .await
+        .unwrap();
+    let interim = next_of(&mut stream, "stt_result").await;
+    let turn_2 = interim["turn_id"].as_str().unwrap().to_string();
+    assert_ne!(turn_2, turn_1);
+    assert_eq!(
+        next_of(&mut stream, "stt_result").await["turn_id"],
+        turn_2.as_str()
+    );
+
+    let speak = json!({"type": "speak", "text": "Booking it now.", "turn_id": "reply-2"});
+    sink.send(Message::Text(speak.to_string().into()))
+        .await
+        .unwrap();
+    assert_eq!(
+        next_of(&mut stream, "tts_playback_started").await["turn_id"],
+        "reply-2"
+    );
+    assert_eq!(
+        next_of(&mut stream, "tts_playback_complete").await["turn_id"],
+        "reply-2"
+    );
+
+    // A provider error names the turn whose speech failed
+    let speak = json!({"type": "speak", "text": "fail this one", "turn_id": "reply-3"});
+    sink.send(Message::Text(speak.to_string().into()))
+        .await
+        .unwrap();
+    let error = next_of(&mut stream, "error").await;
+    assert_eq!(error["turn_id"], "reply-3");
+    assert!(error["message"].as_str().unwrap().starts_with("TTS error"));
+}
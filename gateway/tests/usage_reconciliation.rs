@@ -0,0 +1,232 @@
+//! # Usage Reconciliation Integration Tests
+//!
+//! Runs sessions on in-process mock providers registered through the
+//! provider registry and writes their usage records through a
+//! `UsageRecorder` with a reconciliation report in a temp directory.
+//!
+//! The mock TTS streams MPEG-1 Layer III frames in two chunks split mid-frame
+//! and reports a bogus duration with each chunk, so the billed audio length
+//! has to come from the frames themselves. It answers with as much audio as
+//! the text takes at the expected speaking rate, except for text starting
+//! with "Truncated", which gets three frames.
+//!
+//! 1. Each utterance is measured from its MP3 frames and paired with its
+//!    characters; only the truncated one is reported, and the day's summary
+//!    for the provider counts every checked utterance.
+//! 2. Without `reconciliation_path` no report is written and there are no
+//!    summaries.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test usage_reconciliation
+//! ```
+
+mod fixtures;
+
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use fixtures::session_mocks::{
+    Silent, TtsBehavior, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::config::{UsageConfig, UsageSinkKind};
+use waav_gateway::core::session::{Session, SessionPipelineBuilder};
+use waav_gateway::core::tts::{AudioCallback, AudioData, TTSConfig, TTSResult};
+use waav_gateway::usage::{
+    EXPECTED_CHARS_PER_SECOND, ReconciliationOutlier, UsageRecord, UsageRecorder, UsageTermination,
+};
+
+const MOCK_PROVIDER: &str = "usage-reconciliation-mock";
+
+/// MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, no padding
+const MP3_FRAME_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x00];
+const MP3_FRAME_BYTES: usize = 417;
+/// 1152 samples at 44.1 kHz
+const MP3_FRAME_MS: f64 = 1152.0 * 1000.0 / 44100.0;
+
+/// Duration the mock TTS reports with every chunk, unrelated to its contents
+const REPORTED_MS: u32 = 10_000;
+
+const GREETING: &str = "Thanks for calling, how can I help you today?";
+const TRUNCATED: &str = "Truncated answer that the provider cut off early.";
+
+/// Number of MP3 frames the mock TTS answers `text` with
+fn frames_for(text: &str) -> usize {
+    if text.starts_with("Truncated") {
+        return 3;
+    }
+    let expected_ms = text.chars().count() as f64 / EXPECTED_CHARS_PER_SECOND * 1000.0;
+    (expected_ms / MP3_FRAME_MS).round() as usize
+}
+
+/// TTS behavior that streams MP3 frames for every utterance
+struct Mp3Frames;
+
+#[async_trait]
+impl TtsBehavior for Mp3Frames {
+    async fn speak(
+        &mut self,
+        text: &str,
+        _flush: bool,
+        audio: Option<&Arc<dyn AudioCallback>>,
+    ) -> TTSResult<()> {
+        let Some(callback) = audio else {
+            return Ok(());
+        };
+        let mut frame = vec![0u8; MP3_FRAME_BYTES];
+        frame[..4].copy_from_slice(&MP3_FRAME_HEADER);
+        let audio = frame.repeat(frames_for(text));
+
+        // Split inside the second frame, like a provider's network chunks
+        let (first, second) = audio.split_at((MP3_FRAME_BYTES + 100).min(audio.len()));
+        for chunk in [first, second] {
+            callback
+                .on_audio(AudioData {
+                    data: chunk.to_vec(),
+                    sample_rate: 44100,
+                    format: "mp3".to_string(),
+                    duration_ms: Some(REPORTED_MS),
+                })
+                .await;
+        }
+        callback.on_complete().await;
+        Ok(())
+    }
+}
+
+async fn build_session() -> Session {
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(|_| Mp3Frames),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(mock_stt_config(MOCK_PROVIDER))
+        .tts(TTSConfig {
+            model: "mock-tts-model".to_string(),
+            audio_format: Some("mp3".to_string()),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+/// Speak `texts` in a fresh session and build its usage record
+async fn run_session(session_id: &str, texts: &[&str]) -> UsageRecord {
+    let session = build_session().await;
+    for text in texts {
+        session.speak(text, true).await.unwrap();
+    }
+    session.close().await.unwrap();
+    UsageRecord::new(
+        session_id,
+        None,
+        &session.usage(),
+        UsageTermination::Closed,
+        None,
+    )
+}
+
+fn usage_config(dir: &TempDir, reconciliation: bool) -> UsageConfig {
+    UsageConfig {
+        file_path: Some(dir.path().join("usage.jsonl")),
+        reconciliation_path: reconciliation.then(|| dir.path().join("reconciliation.jsonl")),
+        reconciliation_tolerance_percent: 25,
+        ..UsageConfig::new(UsageSinkKind::File)
+    }
+}
+
+fn read_outliers(path: &Path) -> Vec<ReconciliationOutlier> {
+    std::fs::read_to_string(path)
+        .expect("reconciliation report should exist")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is an outlier"))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_truncated_utterance_is_reported() {
+    let dir = TempDir::new().unwrap();
+    let recorder = UsageRecorder::new(&usage_config(&dir, true)).unwrap();
+
+    let record = run_session("reconcile-1", &[GREETING, TRUNCATED, "OK."]).await;
+
+    // Every utterance is measured from its frames, not the reported duration
+    let seconds = |text: &str| frames_for(text) as f64 * MP3_FRAME_MS / 1000.0;
+    let utterances: Vec<(u64, f64)> = record
+        .tts_utterances
+        .iter()
+        .map(|utterance| (utterance.characters, utterance.audio_seconds))
+        .collect();
+    assert_eq!(utterances.len(), 3);
+    for ((characters, audio_seconds), text) in utterances.iter().zip([GREETING, TRUNCATED, "OK."]) {
+        assert_eq!(*characters, text.len() as u64);
+        assert!(
+            (audio_seconds - seconds(text)).abs() < 0.01,
+            "{text:?} measured {audio_seconds}s"
+        );
+    }
+    let tts = record.tts.as_ref().expect("tts usage");
+    assert_eq!(
+        tts.characters,
+        (GREETING.len() + TRUNCATED.len() + 3) as u64
+    );
+
+    recorder.record(&record).await.unwrap();
+
+    // Only the truncated utterance is off by more than the tolerance; "OK."
+    // is too short to judge
+    let outliers = read_outliers(&dir.path().join("reconciliation.jsonl"));
+    assert_eq!(outliers.len(), 1);
+    let outlier = &outliers[0];
+    assert_eq!(outlier.record_id, record.record_id);
+    assert_eq!(outlier.session_id, "reconcile-1");
+    assert_eq!(outlier.provider, MOCK_PROVIDER);
+    assert_eq!(outlier.model, "mock-tts-model");
+    assert_eq!(outlier.utterance, 1);
+    assert_eq!(outlier.characters, TRUNCATED.len() as u64);
+    assert!(outlier.deviation < -0.9, "deviation {}", outlier.deviation);
+
+    // A second session on the same day adds to the provider's summary
+    let record = run_session("reconcile-2", &[GREETING]).await;
+    recorder.record(&record).await.unwrap();
+    assert_eq!(
+        read_outliers(&dir.path().join("reconciliation.jsonl")).len(),
+        1
+    );
+
+    let summaries = recorder.reconciliation_summaries().expect("summaries");
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    assert_eq!(summary.provider, MOCK_PROVIDER);
+    assert_eq!(summary.sessions, 2);
+    assert_eq!(summary.utterances, 3);
+    assert_eq!(summary.outliers, 1);
+    assert_eq!(
+        summary.characters,
+        (2 * GREETING.len() + TRUNCATED.len()) as u64
+    );
+    assert!((summary.audio_seconds - (2.0 * seconds(GREETING) + seconds(TRUNCATED))).abs() < 0.03);
+}
+
+#[tokio::test]
+async fn test_reconciliation_off_without_path() {
+    let dir = TempDir::new().unwrap();
+    let recorder = UsageRecorder::new(&usage_config(&dir, false)).unwrap();
+
+    let record = run_session("reconcile-off", &[TRUNCATED]).await;
+    recorder.record(&record).await.unwrap();
+
+    assert!(recorder.reconciliation_summaries().is_none());
+    assert!(!dir.path().join("reconciliation.jsonl").exists());
+    // The utterances are still part of the usage record
+    assert_eq!(record.tts_utterances.len(), 1);
+    assert!(dir.path().join("usage.jsonl").exists());
+}
@@ -0,0 +1,243 @@
+//! # Usage Record Integration Tests
+//!
+//! Runs a scripted session on in-process mock providers registered through
+//! the provider registry, then writes its usage record through a
+//! `UsageRecorder` configured with both sinks: a JSON-lines file in a temp
+//! directory and a webhook served by wiremock.
+//!
+//! 1. Every field of the record is filled in from the script: one second of
+//!    STT audio, the spoken characters, the mock TTS audio duration, the
+//!    session timestamps, the recording size and the session's metadata as
+//!    the session store holds it at the end.
+//! 2. The file line and the signed webhook body carry the same record.
+//! 3. A record written while a panic unwinds (the path teardown guards use)
+//!    still reaches the file.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test usage_record
+//! ```
+
+mod fixtures;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use wiremock::matchers::{header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use fixtures::session_mocks::{
+    Clip, Silent, mock_registry, mock_stt_config, mock_tts_config, stt_factory, tts_factory,
+};
+use waav_gateway::config::{UsageConfig, UsageSinkKind};
+use waav_gateway::core::session::{Session, SessionPipelineBuilder};
+use waav_gateway::core::stt::STTConfig;
+use waav_gateway::core::tts::TTSConfig;
+use waav_gateway::state::{SessionMetadata, SessionStore};
+use waav_gateway::usage::{UsageRecord, UsageRecorder, UsageTermination};
+
+const MOCK_PROVIDER: &str = "usage-record-mock";
+
+/// Audio reported by the mock TTS for every utterance
+const MOCK_AUDIO_MS: u32 = 250;
+
+const WEBHOOK_SECRET: &str = "usage-record-test-secret";
+
+async fn build_session() -> Session {
+    let audio = Clip::silence(24000, MOCK_AUDIO_MS);
+    let registry = mock_registry(
+        MOCK_PROVIDER,
+        stt_factory(|_| Silent),
+        tts_factory(move |_| audio.clone()),
+    );
+    SessionPipelineBuilder::new()
+        .plugin_registry(registry)
+        .stt(STTConfig {
+            model: "mock-stt-model".to_string(),
+            ..mock_stt_config(MOCK_PROVIDER)
+        })
+        .tts(TTSConfig {
+            model: "mock-tts-model".to_string(),
+            ..mock_tts_config(MOCK_PROVIDER)
+        })
+        .ready_timeout(Duration::from_secs(5))
+        .build()
+        .await
+        .expect("mock session should build")
+}
+
+fn usage_config(dir: &TempDir, webhook_url: Option<String>) -> UsageConfig {
+    UsageConfig {
+        sink: if webhook_url.is_some() {
+            UsageSinkKind::Both
+        } else {
+            UsageSinkKind::File
+        },
+        file_path: Some(dir.path().join("usage.jsonl")),
+        webhook_url,
+        webhook_secret: Some(WEBHOOK_SECRET.to_string()),
+        ..UsageConfig::new(UsageSinkKind::File)
+    }
+}
+
+fn read_records(path: &Path) -> Vec<UsageRecord> {
+    std::fs::read_to_string(path)
+        .expect("usage file should exist")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is a usage record"))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_scripted_session_writes_complete_record() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/usage"))
+        .and(header_exists("X-WaaV-Signature"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = TempDir::new().unwrap();
+    let config = usage_config(&dir, Some(format!("{}/usage", server.uri())));
+    let recorder = UsageRecorder::new(&config).unwrap();
+
+    // The client's metadata, plus an entry the gateway records mid-session
+    let sessions = SessionStore::new();
+    sessions.register(
+        "usage-stream-1",
+        SessionMetadata::from([("customer_id".to_string(), "c-123".to_string())]),
+    );
+    sessions
+        .set_metadata("usage-stream-1", "recording_consent", "granted".to_string())
+        .unwrap();
+
+    // Script: one second of 16kHz PCM16 input and two utterances
+    let session = build_session().await;
+    session.push_audio(vec![0u8; 16000].into()).await.unwrap();
+    session.push_audio(vec![0u8; 16000].into()).await.unwrap();
+    session.speak("Hello there.", true).await.unwrap();
+    session.speak("How can I help?", true).await.unwrap();
+    session.close().await.unwrap();
+
+    let usage = session.usage();
+    let record = UsageRecord::new(
+        "usage-stream-1",
+        Some("project1".to_string()),
+        &usage,
+        UsageTermination::Closed,
+        Some(4096),
+    )
+    .with_metadata(sessions.metadata("usage-stream-1").unwrap_or_default());
+    recorder.record(&record).await.unwrap();
+
+    // Every field is filled in from the script
+    assert!(!record.record_id.is_empty());
+    assert_eq!(record.session_id, "usage-stream-1");
+    assert_eq!(record.client_id.as_deref(), Some("project1"));
+    assert_eq!(Some(record.ended_at), usage.ended_at);
+    assert!(record.ended_at >= record.started_at);
+    assert_eq!(record.termination, UsageTermination::Closed);
+
+    let stt = record.stt.as_ref().expect("stt usage");
+    assert_eq!(stt.provider, MOCK_PROVIDER);
+    assert_eq!(stt.model, "mock-stt-model");
+    assert_eq!(stt.audio_seconds, 1.0);
+    // Mock models are not in the pricing table
+    assert_eq!(stt.estimated_cost_usd, None);
+
+    let tts = record.tts.as_ref().expect("tts usage");
+    assert_eq!(tts.provider, MOCK_PROVIDER);
+    assert_eq!(tts.model, "mock-tts-model");
+    assert_eq!(
+        tts.characters,
+        ("Hello there.".len() + "How can I help?".len()) as u64
+    );
+    assert_eq!(tts.audio_seconds, 2.0 * MOCK_AUDIO_MS as f64 / 1000.0);
+
+    assert!(record.realtime.is_none());
+    assert_eq!(record.estimated_cost_usd, None);
+    assert_eq!(record.recording_bytes, Some(4096));
+    assert_eq!(record.metadata["customer_id"], "c-123");
+    assert_eq!(record.metadata["recording_consent"], "granted");
+
+    // The file holds exactly this record
+    assert_eq!(
+        read_records(&dir.path().join("usage.jsonl")),
+        vec![record.clone()]
+    );
+
+    // The webhook received the same record, signed with the shared scheme
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    let body: UsageRecord = serde_json::from_slice(&request.body).unwrap();
+    assert_eq!(body, record);
+
+    let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+    assert_eq!(header("X-WaaV-Event-Id"), record.record_id);
+    let canonical = format!(
+        "v1:{}:{}:{}",
+        header("X-WaaV-Timestamp"),
+        record.record_id,
+        String::from_utf8_lossy(&request.body)
+    );
+    let mut mac = Hmac::<Sha256>::new_from_slice(WEBHOOK_SECRET.as_bytes()).unwrap();
+    mac.update(canonical.as_bytes());
+    assert_eq!(
+        header("X-WaaV-Signature"),
+        format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+    );
+}
+
+#[tokio::test]
+async fn test_record_written_while_panic_unwinds() {
+    let dir = TempDir::new().unwrap();
+    let recorder = Arc::new(UsageRecorder::new(&usage_config(&dir, None)).unwrap());
+
+    let session = build_session().await;
+    session.speak("Goodbye.", true).await.unwrap();
+    let usage = session.usage();
+
+    /// Writes the record from Drop, like the WebSocket teardown guard
+    struct Guard {
+        recorder: Arc<UsageRecorder>,
+        record: Option<UsageRecord>,
+    }
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if let Some(record) = self.record.take() {
+                self.recorder.record_detached(record);
+            }
+        }
+    }
+
+    let record = UsageRecord::new(
+        "usage-stream-2",
+        None,
+        &usage,
+        UsageTermination::Aborted,
+        None,
+    );
+    let guard = Guard {
+        recorder: recorder.clone(),
+        record: Some(record.clone()),
+    };
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+        let _guard = guard;
+        panic!("handler panicked");
+    }));
+    assert!(result.is_err());
+
+    // The session was never closed, so the record ends when it was written
+    assert!(usage.ended_at.is_none());
+    let records = read_records(&dir.path().join("usage.jsonl"));
+    assert_eq!(records, vec![record]);
+    assert_eq!(records[0].termination, UsageTermination::Aborted);
+    assert_eq!(records[0].tts.as_ref().unwrap().characters, 8);
+}
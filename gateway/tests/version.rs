@@ -0,0 +1,193 @@
+//! # Version Endpoint Tests
+//!
+//! 1. `GET /version` returns the crate version, git commit, rustc version,
+//!    enabled features, dynamic plugins and uptime, and nothing else.
+//! 2. The reported features match the features the tests were compiled with.
+//! 3. Dynamic plugins recorded in the registry are listed with their versions.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test version
+//! ```
+
+use axum::{Router, body::Body, http::Request, http::StatusCode, routing::get};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use waav_gateway::build_info;
+use waav_gateway::config::PluginConfig;
+use waav_gateway::plugin::PluginRegistry;
+use waav_gateway::{ServerConfig, handlers, state::AppState};
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: None,
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: Default::default(),
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+async fn get_version(registry: PluginRegistry) -> Value {
+    let app_state = AppState::with_plugin_registry(test_config(), Arc::new(registry)).await;
+    let app: Router = Router::new()
+        .route("/version", get(handlers::api::version))
+        .with_state(app_state);
+
+    let response = app
+        .oneshot(Request::get("/version").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_version_schema() {
+    let body = get_version(PluginRegistry::new_isolated()).await;
+
+    let mut keys: Vec<&str> = body
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    assert_eq!(
+        keys,
+        [
+            "features",
+            "git_commit",
+            "plugins",
+            "rustc_version",
+            "uptime_secs",
+            "version"
+        ]
+    );
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!body["git_commit"].as_str().unwrap().is_empty());
+    assert!(
+        body["rustc_version"]
+            .as_str()
+            .unwrap()
+            .starts_with("rustc ")
+    );
+    assert!(body["features"].is_array());
+    assert!(body["plugins"].is_array());
+    assert!(body["uptime_secs"].is_u64());
+}
+
+#[tokio::test]
+async fn test_features_match_compile_time_cfg() {
+    #[allow(unused_mut)]
+    let mut expected: Vec<&str> = Vec::new();
+    #[cfg(feature = "dag-routing")]
+    expected.push("dag-routing");
+    #[cfg(feature = "noise-filter")]
+    expected.push("noise-filter");
+    #[cfg(feature = "openapi")]
+    expected.push("openapi");
+    #[cfg(feature = "plugins-dynamic")]
+    expected.push("plugins-dynamic");
+    #[cfg(feature = "turn-detect")]
+    expected.push("turn-detect");
+
+    assert_eq!(build_info::enabled_features(), expected);
+    let body = get_version(PluginRegistry::new_isolated()).await;
+    assert_eq!(body["features"], json!(expected));
+}
+
+#[tokio::test]
+async fn test_version_lists_dynamic_plugins() {
+    let registry = PluginRegistry::new_isolated();
+    registry.record_dynamic_plugin("version-test-plugin", "0.3.1");
+
+    let body = get_version(registry).await;
+    assert_eq!(
+        body["plugins"],
+        json!([{"id": "version-test-plugin", "version": "0.3.1"}])
+    );
+}
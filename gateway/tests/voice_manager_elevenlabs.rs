@@ -19,6 +19,8 @@ fn test_voice_manager_with_elevenlabs_stt() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let tts_config = TTSConfig {
@@ -46,6 +48,8 @@ fn test_voice_manager_elevenlabs_requires_api_key() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let tts_config = TTSConfig::default();
@@ -68,6 +72,8 @@ fn test_voice_manager_elevenlabs_stt_deepgram_tts() {
         punctuation: true,
         encoding: "linear16".to_string(),
         model: "".to_string(),
+        custom_headers: Default::default(),
+        certificate_path: None,
     };
 
     let tts_config = TTSConfig {
@@ -98,6 +104,8 @@ fn test_voice_manager_elevenlabs_different_sample_rates() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let tts_config = TTSConfig {
@@ -132,6 +140,8 @@ fn test_voice_manager_elevenlabs_different_languages() {
             punctuation: true,
             encoding: "linear16".to_string(),
             model: "".to_string(),
+            custom_headers: Default::default(),
+            certificate_path: None,
         };
 
         let tts_config = TTSConfig {
@@ -0,0 +1,223 @@
+//! # Windows Platform Integration Tests
+//!
+//! Runs the gateway binary on Windows and checks that it shuts down
+//! gracefully on Ctrl+Break, the signal service managers and `taskkill`-style
+//! tooling send to console processes, and that dynamic plugins built as DLLs
+//! are discovered, loaded and shut down with it. The test plugin in
+//! `examples/test-plugin` is compiled with cargo on first use.
+//!
+//! ## Running Tests
+//!
+//! ```bash
+//! cargo test --test windows_platform
+//! cargo test --features plugins-dynamic --test windows_platform
+//! ```
+
+#![cfg(windows)]
+
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows_sys::Win32::System::Console::{CTRL_BREAK_EVENT, GenerateConsoleCtrlEvent};
+use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+
+/// How long the gateway may take to start listening
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long the gateway may take to exit after Ctrl+Break
+const EXIT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A port nothing listens on
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Running gateway whose stdout lines are collected in the background
+struct Gateway {
+    child: Child,
+    lines: mpsc::Receiver<String>,
+    output: Vec<String>,
+}
+
+impl Gateway {
+    /// Start the gateway in its own process group, so Ctrl+Break reaches it
+    /// and not the test runner
+    fn start(envs: &[(&str, &str)]) -> Self {
+        let port = free_port().to_string();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_waav-gateway"))
+            .env("HOST", "127.0.0.1")
+            .env("PORT", &port)
+            .envs(envs.iter().copied())
+            .stdout(Stdio::piped())
+            .creation_flags(CREATE_NEW_PROCESS_GROUP)
+            .spawn()
+            .expect("Failed to start the gateway");
+
+        let stdout = child.stdout.take().unwrap();
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut gateway = Self {
+            child,
+            lines,
+            output: Vec::new(),
+        };
+        gateway.wait_for_line("Server listening on", STARTUP_TIMEOUT);
+        gateway
+    }
+
+    fn wait_for_line(&mut self, needle: &str, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match self.lines.recv_timeout(remaining) {
+                Ok(line) => {
+                    let found = line.contains(needle);
+                    self.output.push(line);
+                    if found {
+                        return;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        let _ = self.child.kill();
+        panic!(
+            "Gateway never printed {needle:?}; output:\n{}",
+            self.output.join("\n")
+        );
+    }
+
+    fn ctrl_break(&self) {
+        // SAFETY: sends a console event to the gateway's own process group
+        let sent = unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, self.child.id()) };
+        assert_ne!(sent, 0, "GenerateConsoleCtrlEvent failed");
+    }
+
+    fn wait_for_exit(&mut self) -> ExitStatus {
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        loop {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                self.output.extend(self.lines.try_iter());
+                return status;
+            }
+            if Instant::now() >= deadline {
+                let _ = self.child.kill();
+                panic!(
+                    "Gateway did not exit after Ctrl+Break; output:\n{}",
+                    self.output.join("\n")
+                );
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+#[test]
+fn test_ctrl_break_shuts_down_gracefully() {
+    let mut gateway = Gateway::start(&[]);
+
+    gateway.ctrl_break();
+    let status = gateway.wait_for_exit();
+
+    assert!(status.success(), "exit status: {status}");
+    assert!(
+        gateway
+            .output
+            .iter()
+            .any(|line| line == "Received Ctrl+Break, shutting down"),
+        "{:?}",
+        gateway.output
+    );
+}
+
+#[cfg(feature = "plugins-dynamic")]
+mod plugins {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use waav_gateway::plugin::{DynamicPluginLoader, PluginLoadStatus, PluginRegistry};
+
+    /// Build the test plugin and return the path of its DLL
+    fn build_test_plugin() -> PathBuf {
+        let manifest =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/test-plugin/Cargo.toml");
+        let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test-plugin");
+
+        let status = Command::new(env!("CARGO"))
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(&manifest)
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .expect("Failed to run cargo for the test plugin");
+        assert!(status.success(), "Building the test plugin failed");
+
+        target_dir.join("debug").join("waav_plugin_test.dll")
+    }
+
+    #[tokio::test]
+    async fn test_plugin_dll_discovered_and_loaded() {
+        let library = build_test_plugin();
+        let plugin_dir = tempfile::tempdir().unwrap();
+        // Windows file names are case-insensitive, and so is discovery
+        std::fs::copy(&library, plugin_dir.path().join("WAAV_PLUGIN_TEST.DLL")).unwrap();
+
+        let registry = Arc::new(PluginRegistry::new_isolated());
+        let mut loader = DynamicPluginLoader::new();
+        let candidates = loader.discover(plugin_dir.path()).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].name, "TEST");
+
+        let reports = loader
+            .load_all_from_directory(plugin_dir.path(), &registry)
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].status, PluginLoadStatus::Loaded);
+        assert!(registry.has_stt_provider("test-stt"));
+
+        loader.hand_over_to(&registry);
+        registry.shutdown();
+        assert!(!registry.has_stt_provider("test-stt"));
+    }
+
+    #[test]
+    fn test_gateway_loads_plugin_dll_and_shuts_down() {
+        let library = build_test_plugin();
+        let plugin_dir = tempfile::tempdir().unwrap();
+        std::fs::copy(&library, plugin_dir.path().join("waav_plugin_test.dll")).unwrap();
+
+        let plugin_dir = plugin_dir.path().to_str().unwrap();
+        let mut gateway =
+            Gateway::start(&[("PLUGINS_ENABLED", "true"), ("PLUGINS_DIR", plugin_dir)]);
+        assert!(
+            gateway
+                .output
+                .iter()
+                .any(|line| line.contains("dynamic plugins: test-stt")),
+            "{:?}",
+            gateway.output
+        );
+
+        gateway.ctrl_break();
+        let status = gateway.wait_for_exit();
+        assert!(status.success(), "exit status: {status}");
+    }
+}
@@ -0,0 +1,293 @@
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::middleware;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+use waav_gateway::{
+    ServerConfig,
+    config::{PluginConfig, WsHeartbeatConfig},
+    metrics::global_metrics,
+    middleware::auth_middleware,
+    routes,
+    state::AppState,
+};
+
+type Session = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Ping every 100ms, allow 80ms per answer and reap after two misses
+const FAST_HEARTBEAT: WsHeartbeatConfig = WsHeartbeatConfig {
+    enabled: true,
+    interval_ms: 100,
+    timeout_ms: 80,
+    max_missed: 2,
+};
+
+fn test_config(heartbeat: WsHeartbeatConfig) -> ServerConfig {
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        tls: None,
+        livekit_url: "ws://localhost:7880".to_string(),
+        livekit_public_url: "http://localhost:7880".to_string(),
+        livekit_api_key: None,
+        livekit_api_secret: None,
+        deepgram_api_key: None,
+        elevenlabs_api_key: None,
+        google_credentials: None,
+        azure_speech_subscription_key: None,
+        azure_speech_region: None,
+        cartesia_api_key: None,
+        openai_api_key: None,
+        assemblyai_api_key: None,
+        hume_api_key: None,
+        lmnt_api_key: None,
+        groq_api_key: None,
+        playht_api_key: None,
+        playht_user_id: None,
+        ibm_watson_api_key: None,
+        ibm_watson_instance_id: None,
+        ibm_watson_region: None,
+        aws_access_key_id: None,
+        aws_secret_access_key: None,
+        aws_region: None,
+        gnani_token: None,
+        gnani_access_key: None,
+        gnani_certificate_path: None,
+        recording_s3_bucket: None,
+        recording_s3_region: None,
+        recording_s3_endpoint: None,
+        recording_s3_access_key: None,
+        recording_s3_secret_key: None,
+        recording_s3_prefix: None,
+        cache_path: None,
+        cache_ttl_seconds: Some(3600),
+        auth_service_url: None,
+        auth_signing_key_path: None,
+        auth_api_secrets: Vec::new(),
+        auth_timeout_seconds: 5,
+        auth_required: false,
+        auth_admin_ids: Vec::new(),
+        auth_monitor_audio_ids: Vec::new(),
+        auth_admin_scopes: Default::default(),
+        sip: None,
+        cors_allowed_origins: None,
+        rate_limit_requests_per_second: 60,
+        rate_limit_burst_size: 10,
+        max_websocket_connections: None,
+        max_connections_per_ip: 100,
+        trusted_proxies: Vec::new(),
+        provider_connect_timeout_secs: 10,
+        plugins: PluginConfig::default(),
+        greeting: None,
+        greeting_assets_dir: None,
+        tts_fallback_voices: Default::default(),
+        tts_max_pending_utterances: 5,
+        tts_queue_policy: Default::default(),
+        tts_system_speak_max_chars: 500,
+        provider_error_max_chars: 300,
+        deepgram_connection_budget: Default::default(),
+        usage: None,
+        agents: Vec::new(),
+        strict_config: false,
+        selftest: None,
+        load_shedding: None,
+        replay_max_concurrent_jobs: 1,
+        feature_flags: Default::default(),
+        transcript_buffer: Default::default(),
+        transcript_enrichment: None,
+        session_export: None,
+        audio_sinks: Default::default(),
+        recording_upload: None,
+        recording_redaction: Default::default(),
+        chaos: Default::default(),
+        voice_profiles: Default::default(),
+        ws_heartbeat: heartbeat,
+        console: Default::default(),
+        webhook_queue: None,
+        ingest: None,
+        flight_recorder: None,
+    }
+}
+
+/// Serve `/ws` with the given heartbeat settings
+///
+/// Returns None when the sandbox does not allow binding a socket.
+async fn start_server(heartbeat: WsHeartbeatConfig) -> Option<SocketAddr> {
+    let app_state = AppState::new(test_config(heartbeat)).await;
+
+    let app = routes::ws::create_ws_router()
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth_middleware,
+        ))
+        .with_state(app_state);
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("Skipping heartbeat test: {err}");
+            return None;
+        }
+        Err(err) => panic!("Failed to bind heartbeat test listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    Some(addr)
+}
+
+async fn open_session(addr: SocketAddr) -> Session {
+    connect_async(format!("ws://{addr}/ws"))
+        .await
+        .map(|(stream, _)| stream)
+        .expect("Failed to connect")
+}
+
+async fn send_json(session: &mut Session, message: Value) {
+    session
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+/// Switch the session to JSON heartbeats and wait for it to be ready
+async fn configure_json_heartbeat(session: &mut Session) {
+    send_json(
+        session,
+        json!({"type": "config", "audio": false, "heartbeat": "json"}),
+    )
+    .await;
+    loop {
+        let message = next_json(session).await.expect("session should be ready");
+        if message["type"] == "ready" {
+            return;
+        }
+    }
+}
+
+/// Next JSON message, skipping ping frames (answered by tungstenite)
+///
+/// Returns None once the server closes the connection.
+async fn next_json(session: &mut Session) -> Option<Value> {
+    loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), session.next())
+            .await
+            .expect("server should send a message")?
+            .ok()?;
+        match frame {
+            Message::Text(text) => return Some(serde_json::from_str(&text).unwrap()),
+            Message::Ping(_) | Message::Pong(_) => continue,
+            Message::Close(_) => return None,
+            other => panic!("unexpected frame: {other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_ping_frames_keep_answering_client_open() {
+    let Some(addr) = start_server(FAST_HEARTBEAT).await else {
+        return;
+    };
+    let mut session = open_session(addr).await;
+
+    // Reading lets tungstenite answer each ping frame with a pong
+    let mut pings = 0;
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(700);
+    while let Ok(frame) = tokio::time::timeout_at(deadline, session.next()).await {
+        match frame.expect("connection should stay open").unwrap() {
+            Message::Ping(payload) => {
+                // The payload is the server's timestamp
+                assert_eq!(payload.len(), 8);
+                pings += 1;
+            }
+            other => panic!("unexpected frame: {other:?}"),
+        }
+    }
+    assert!(pings >= 3, "expected several pings, got {pings}");
+
+    // Well past the reap deadline, the session still answers
+    session
+        .send(Message::Text("not json".into()))
+        .await
+        .unwrap();
+    let reply = next_json(&mut session)
+        .await
+        .expect("session should stay open");
+    assert_eq!(reply["type"], "error");
+}
+
+#[tokio::test]
+async fn test_json_heartbeat_reports_rtt() {
+    let Some(addr) = start_server(FAST_HEARTBEAT).await else {
+        return;
+    };
+    let mut session = open_session(addr).await;
+    configure_json_heartbeat(&mut session).await;
+
+    let mut answered = 0;
+    let mut rtt_reported = false;
+    while answered < 4 {
+        let ping = next_json(&mut session)
+            .await
+            .expect("session should stay open");
+        assert_eq!(ping["type"], "ping", "unexpected message: {ping}");
+        rtt_reported |= ping["rtt_ms"].is_u64();
+        send_json(&mut session, json!({"type": "pong", "ts": ping["ts"]})).await;
+        answered += 1;
+    }
+    // Pings after the first answer carry the measured round trip
+    assert!(rtt_reported);
+}
+
+#[tokio::test]
+async fn test_unanswered_client_is_reaped() {
+    let Some(addr) = start_server(FAST_HEARTBEAT).await else {
+        return;
+    };
+    let timeouts = || {
+        global_metrics()
+            .get("waav_ws_heartbeat_timeouts_total", &[])
+            .unwrap_or(0.0)
+    };
+    let timeouts_before = timeouts();
+
+    let mut session = open_session(addr).await;
+    configure_json_heartbeat(&mut session).await;
+
+    // Ignore every ping; after two misses the server gives up
+    let mut pings = 0;
+    let error = loop {
+        let message = next_json(&mut session)
+            .await
+            .expect("expected a close error");
+        match message["type"].as_str() {
+            Some("ping") => pings += 1,
+            Some("error") => break message,
+            _ => panic!("unexpected message: {message}"),
+        }
+    };
+    assert_eq!(pings, 2);
+    assert_eq!(error["code"], "heartbeat_timeout");
+    assert_eq!(error["close_code"], 4009);
+
+    let close = tokio::time::timeout(Duration::from_secs(5), session.next())
+        .await
+        .expect("server should close the connection");
+    match close {
+        Some(Ok(Message::Close(Some(frame)))) => {
+            assert_eq!(u16::from(frame.code), 4009);
+            assert_eq!(frame.reason.as_str(), "heartbeat_timeout");
+        }
+        other => panic!("expected a close frame, got {other:?}"),
+    }
+    assert!(timeouts() >= timeouts_before + 1.0);
+}
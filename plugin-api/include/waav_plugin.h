@@ -0,0 +1,287 @@
+
+/*
+ * C API for WaaV Gateway TTS plugins.
+ *
+ * Generated from waav-plugin-api (src/c_api.rs) by cbindgen. Do not edit;
+ * change the Rust definitions and regenerate with:
+ *
+ *   cbindgen --config cbindgen.toml --output include/waav_plugin.h
+ */
+
+#ifndef WAAV_PLUGIN_H
+#define WAAV_PLUGIN_H
+
+/* Warning: this file is autogenerated by cbindgen. Don't modify it manually. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/*
+ * Version of the C plugin ABI described by `waav_plugin.h`.
+ *
+ * Bumped on any incompatible change to the types in this module. Plugins
+ * set [`CTTSPlugin::abi_version`] to the value they were compiled against.
+ */
+#define WAAV_C_ABI_VERSION 1
+
+/*
+ * Standard error codes for plugin errors.
+ */
+enum WaavErrorCode
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  /*
+   * No error
+   */
+  WaavErrorCode_OK = 0,
+  /*
+   * Connection failed
+   */
+  WaavErrorCode_CONNECTION_FAILED = 1,
+  /*
+   * Authentication failed
+   */
+  WaavErrorCode_AUTHENTICATION_FAILED = 2,
+  /*
+   * Configuration error
+   */
+  WaavErrorCode_CONFIGURATION_ERROR = 3,
+  /*
+   * Provider error
+   */
+  WaavErrorCode_PROVIDER_ERROR = 4,
+  /*
+   * Network error
+   */
+  WaavErrorCode_NETWORK_ERROR = 5,
+  /*
+   * Audio processing error
+   */
+  WaavErrorCode_AUDIO_PROCESSING_ERROR = 6,
+  /*
+   * Timeout error
+   */
+  WaavErrorCode_TIMEOUT_ERROR = 7,
+  /*
+   * Internal error
+   */
+  WaavErrorCode_INTERNAL_ERROR = 8,
+  /*
+   * Not connected
+   */
+  WaavErrorCode_NOT_CONNECTED = 9,
+  /*
+   * Rate limited
+   */
+  WaavErrorCode_RATE_LIMITED = 10,
+  /*
+   * Invalid input
+   */
+  WaavErrorCode_INVALID_INPUT = 11,
+};
+#ifndef __cplusplus
+typedef uint32_t WaavErrorCode;
+#endif // __cplusplus
+
+/*
+ * Audio produced by a C TTS provider.
+ *
+ * Only valid for the duration of the [`CAudioCallback`] call; the host
+ * copies the samples before returning.
+ */
+typedef struct WaavCAudioChunk {
+  /*
+   * Audio bytes
+   */
+  const uint8_t *data;
+  /*
+   * Number of bytes at `data`
+   */
+  size_t len;
+  /*
+   * Audio format, e.g. "linear16" (NUL-terminated; NULL for "linear16")
+   */
+  const char *format;
+  /*
+   * Sample rate in Hz
+   */
+  uint32_t sample_rate;
+  /*
+   * Duration in milliseconds (0 if unknown)
+   */
+  uint32_t duration_ms;
+} WaavCAudioChunk;
+
+/*
+ * Called by a C provider with synthesized audio.
+ */
+typedef void (*WaavCAudioCallback)(const WaavCAudioChunk *chunk, void *host);
+
+/*
+ * Called by a C provider when synthesis fails after `speak` returned.
+ *
+ * `code` is an [`ErrorCode`]; `message` is NUL-terminated and may be NULL.
+ */
+typedef void (*WaavCErrorCallback)(uint32_t code, const char *message, void *host);
+
+/*
+ * Called by a C provider once all audio of a flushed utterance was delivered.
+ */
+typedef void (*WaavCCompleteCallback)(void *host);
+
+/*
+ * Callbacks handed to a C provider when it is created.
+ *
+ * The callbacks may be called from any thread until `destroy` returns,
+ * always with `host` as their last argument. The struct itself is only
+ * valid during `create`; providers copy it.
+ */
+typedef struct WaavCTTSHost {
+  /*
+   * Deliver synthesized audio
+   */
+  WaavCAudioCallback on_audio;
+  /*
+   * Report an asynchronous error
+   */
+  WaavCErrorCallback on_error;
+  /*
+   * Report that a flushed utterance is complete
+   */
+  WaavCCompleteCallback on_complete;
+  /*
+   * Opaque host pointer passed back to every callback
+   */
+  void *host;
+} WaavCTTSHost;
+
+/*
+ * Functions implementing a C TTS provider.
+ *
+ * `create`, `destroy` and `speak` are required; the others may be NULL.
+ * Functions returning `uint32_t` return an [`ErrorCode`], `0` on success;
+ * `last_error` may then describe the failure. The host serializes calls on
+ * one provider, but callbacks may run concurrently with them.
+ */
+typedef struct WaavCTTSVTable {
+  /*
+   * Create a provider from the gateway's TTS config as JSON.
+   *
+   * Returns NULL on failure.
+   */
+  void *(*create)(const char *config_json, const WaavCTTSHost *host);
+  /*
+   * Free a provider. No callback may run once it returns.
+   */
+  void (*destroy)(void *provider);
+  /*
+   * Connect to the TTS service
+   */
+  uint32_t (*connect)(void *provider);
+  /*
+   * Disconnect from the TTS service
+   */
+  uint32_t (*disconnect)(void *provider);
+  /*
+   * Whether the provider accepts text (NULL: always)
+   */
+  bool (*is_ready)(const void *provider);
+  /*
+   * Queue text for synthesis; `text` is NUL-terminated and `text_len`
+   * bytes long. With `flush` the queued text is synthesized right away.
+   */
+  uint32_t (*speak)(void *provider, const char *text, size_t text_len, bool flush);
+  /*
+   * Drop queued text
+   */
+  uint32_t (*clear)(void *provider);
+  /*
+   * Synthesize queued text
+   */
+  uint32_t (*flush)(void *provider);
+  /*
+   * Message describing the last failed call, NUL-terminated, or NULL.
+   *
+   * Must stay valid until the next call on the provider.
+   */
+  const char *(*last_error)(const void *provider);
+  /*
+   * Provider info as a NUL-terminated JSON object, or NULL
+   */
+  const char *(*provider_info)(const void *provider);
+} WaavCTTSVTable;
+
+/*
+ * A TTS plugin implemented in C.
+ *
+ * Returned by the plugin's `waav_c_tts_plugin()` function. The struct and
+ * its strings must live as long as the plugin is loaded, e.g. as statics.
+ */
+typedef struct WaavCTTSPlugin {
+  /*
+   * [`C_ABI_VERSION`] the plugin was compiled against
+   */
+  uint32_t abi_version;
+  /*
+   * Unique plugin identifier, also the provider name
+   */
+  const char *id;
+  /*
+   * Human-readable plugin name
+   */
+  const char *name;
+  /*
+   * Plugin version following semver
+   */
+  const char *version;
+  /*
+   * Plugin description (may be NULL)
+   */
+  const char *description;
+  /*
+   * Provider implementation
+   */
+  WaavCTTSVTable vtable;
+} WaavCTTSPlugin;
+
+#ifdef __cplusplus
+#define WAAV_STATIC_ASSERT(cond, msg) static_assert(cond, msg)
+#else
+#define WAAV_STATIC_ASSERT(cond, msg) _Static_assert(cond, msg)
+#endif
+
+#define WAAV_PTR sizeof(void *)
+WAAV_STATIC_ASSERT(sizeof(WaavCAudioChunk) == 3 * WAAV_PTR + 8, "WaavCAudioChunk size");
+WAAV_STATIC_ASSERT(offsetof(WaavCAudioChunk, len) == WAAV_PTR, "WaavCAudioChunk.len offset");
+WAAV_STATIC_ASSERT(offsetof(WaavCAudioChunk, format) == 2 * WAAV_PTR, "WaavCAudioChunk.format offset");
+WAAV_STATIC_ASSERT(offsetof(WaavCAudioChunk, sample_rate) == 3 * WAAV_PTR, "WaavCAudioChunk.sample_rate offset");
+WAAV_STATIC_ASSERT(offsetof(WaavCAudioChunk, duration_ms) == 3 * WAAV_PTR + 4, "WaavCAudioChunk.duration_ms offset");
+WAAV_STATIC_ASSERT(sizeof(WaavCTTSHost) == 4 * WAAV_PTR, "WaavCTTSHost size");
+WAAV_STATIC_ASSERT(offsetof(WaavCTTSHost, host) == 3 * WAAV_PTR, "WaavCTTSHost.host offset");
+WAAV_STATIC_ASSERT(sizeof(WaavCTTSVTable) == 10 * WAAV_PTR, "WaavCTTSVTable size");
+WAAV_STATIC_ASSERT(offsetof(WaavCTTSVTable, speak) == 5 * WAAV_PTR, "WaavCTTSVTable.speak offset");
+WAAV_STATIC_ASSERT(offsetof(WaavCTTSVTable, provider_info) == 9 * WAAV_PTR, "WaavCTTSVTable.provider_info offset");
+WAAV_STATIC_ASSERT(sizeof(WaavCTTSPlugin) == 15 * WAAV_PTR, "WaavCTTSPlugin size");
+WAAV_STATIC_ASSERT(offsetof(WaavCTTSPlugin, id) == WAAV_PTR, "WaavCTTSPlugin.id offset");
+WAAV_STATIC_ASSERT(offsetof(WaavCTTSPlugin, vtable) == 5 * WAAV_PTR, "WaavCTTSPlugin.vtable offset");
+WAAV_STATIC_ASSERT(sizeof(WaavErrorCode) == 4, "WaavErrorCode size");
+#undef WAAV_PTR
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Implemented by the plugin: returns its description and vtable. The
+ * returned struct must stay valid while the plugin is loaded.
+ */
+const WaavCTTSPlugin *waav_c_tts_plugin(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
+
+#endif  /* WAAV_PLUGIN_H */
@@ -0,0 +1,636 @@
+//! # C Plugin API
+//!
+//! A plain C ABI for TTS plugins written in languages other than Rust.
+//!
+//! The types in this module only contain C scalars, NUL-terminated strings,
+//! byte pointers and nullable function pointers, so they can be declared in
+//! C. `include/waav_plugin.h` is generated from them with `cbindgen`:
+//!
+//! ```bash
+//! cd plugin-api
+//! cbindgen --config cbindgen.toml --output include/waav_plugin.h
+//! ```
+//!
+//! A C plugin fills in a [`CTTSPlugin`] and returns it from
+//! `waav_c_tts_plugin()`. A small Rust shim crate links the C code and
+//! exports the plugin module, using [`c_tts_manifest`] and [`create_c_tts`]
+//! to adapt the C vtable to [`TTSVTable`]. See `examples/c-tts-plugin`.
+//!
+//! # Layout
+//!
+//! The expected size and field offsets of every struct are asserted at
+//! compile time, here and in the header's trailer, as multiples of the
+//! pointer size. A change to either side that is not mirrored in the other
+//! fails the build instead of corrupting memory at runtime.
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::mem::{offset_of, size_of};
+
+use abi_stable::std_types::{ROption, RResult, RString};
+
+use crate::{
+    CallbackRegistry, CompleteCallbackFn, ErrorCallbackFn, ErrorCode, FFIAudioData, FFIConfig,
+    FFIResult, PluginCapabilityType, PluginManifest, ProviderHandle, TTSAudioCallbackFn,
+    TTSProvider, TTSVTable,
+};
+
+/// Version of the C plugin ABI described by `waav_plugin.h`.
+///
+/// Bumped on any incompatible change to the types in this module. Plugins
+/// set [`CTTSPlugin::abi_version`] to the value they were compiled against.
+pub const C_ABI_VERSION: u32 = 1;
+
+/// Audio produced by a C TTS provider.
+///
+/// Only valid for the duration of the [`CAudioCallback`] call; the host
+/// copies the samples before returning.
+#[repr(C)]
+pub struct CAudioChunk {
+    /// Audio bytes
+    pub data: *const u8,
+    /// Number of bytes at `data`
+    pub len: usize,
+    /// Audio format, e.g. "linear16" (NUL-terminated; NULL for "linear16")
+    pub format: *const c_char,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Duration in milliseconds (0 if unknown)
+    pub duration_ms: u32,
+}
+
+/// Called by a C provider with synthesized audio.
+pub type CAudioCallback =
+    Option<unsafe extern "C" fn(chunk: *const CAudioChunk, host: *mut c_void)>;
+
+/// Called by a C provider when synthesis fails after `speak` returned.
+///
+/// `code` is an [`ErrorCode`]; `message` is NUL-terminated and may be NULL.
+pub type CErrorCallback =
+    Option<unsafe extern "C" fn(code: u32, message: *const c_char, host: *mut c_void)>;
+
+/// Called by a C provider once all audio of a flushed utterance was delivered.
+pub type CCompleteCallback = Option<unsafe extern "C" fn(host: *mut c_void)>;
+
+/// Callbacks handed to a C provider when it is created.
+///
+/// The callbacks may be called from any thread until `destroy` returns,
+/// always with `host` as their last argument. The struct itself is only
+/// valid during `create`; providers copy it.
+#[repr(C)]
+pub struct CTTSHost {
+    /// Deliver synthesized audio
+    pub on_audio: CAudioCallback,
+    /// Report an asynchronous error
+    pub on_error: CErrorCallback,
+    /// Report that a flushed utterance is complete
+    pub on_complete: CCompleteCallback,
+    /// Opaque host pointer passed back to every callback
+    pub host: *mut c_void,
+}
+
+/// Functions implementing a C TTS provider.
+///
+/// `create`, `destroy` and `speak` are required; the others may be NULL.
+/// Functions returning `uint32_t` return an [`ErrorCode`], `0` on success;
+/// `last_error` may then describe the failure. The host serializes calls on
+/// one provider, but callbacks may run concurrently with them.
+#[repr(C)]
+pub struct CTTSVTable {
+    /// Create a provider from the gateway's TTS config as JSON.
+    ///
+    /// Returns NULL on failure.
+    pub create: Option<
+        unsafe extern "C" fn(config_json: *const c_char, host: *const CTTSHost) -> *mut c_void,
+    >,
+    /// Free a provider. No callback may run once it returns.
+    pub destroy: Option<unsafe extern "C" fn(provider: *mut c_void)>,
+    /// Connect to the TTS service
+    pub connect: Option<unsafe extern "C" fn(provider: *mut c_void) -> u32>,
+    /// Disconnect from the TTS service
+    pub disconnect: Option<unsafe extern "C" fn(provider: *mut c_void) -> u32>,
+    /// Whether the provider accepts text (NULL: always)
+    pub is_ready: Option<unsafe extern "C" fn(provider: *const c_void) -> bool>,
+    /// Queue text for synthesis; `text` is NUL-terminated and `text_len`
+    /// bytes long. With `flush` the queued text is synthesized right away.
+    pub speak: Option<
+        unsafe extern "C" fn(
+            provider: *mut c_void,
+            text: *const c_char,
+            text_len: usize,
+            flush: bool,
+        ) -> u32,
+    >,
+    /// Drop queued text
+    pub clear: Option<unsafe extern "C" fn(provider: *mut c_void) -> u32>,
+    /// Synthesize queued text
+    pub flush: Option<unsafe extern "C" fn(provider: *mut c_void) -> u32>,
+    /// Message describing the last failed call, NUL-terminated, or NULL.
+    ///
+    /// Must stay valid until the next call on the provider.
+    pub last_error: Option<unsafe extern "C" fn(provider: *const c_void) -> *const c_char>,
+    /// Provider info as a NUL-terminated JSON object, or NULL
+    pub provider_info: Option<unsafe extern "C" fn(provider: *const c_void) -> *const c_char>,
+}
+
+/// A TTS plugin implemented in C.
+///
+/// Returned by the plugin's `waav_c_tts_plugin()` function. The struct and
+/// its strings must live as long as the plugin is loaded, e.g. as statics.
+#[repr(C)]
+pub struct CTTSPlugin {
+    /// [`C_ABI_VERSION`] the plugin was compiled against
+    pub abi_version: u32,
+    /// Unique plugin identifier, also the provider name
+    pub id: *const c_char,
+    /// Human-readable plugin name
+    pub name: *const c_char,
+    /// Plugin version following semver
+    pub version: *const c_char,
+    /// Plugin description (may be NULL)
+    pub description: *const c_char,
+    /// Provider implementation
+    pub vtable: CTTSVTable,
+}
+
+// Keep in sync with the `_Static_assert`s in cbindgen.toml
+const PTR: usize = size_of::<*const ()>();
+const _: () = {
+    assert!(size_of::<CAudioChunk>() == 3 * PTR + 8);
+    assert!(offset_of!(CAudioChunk, len) == PTR);
+    assert!(offset_of!(CAudioChunk, format) == 2 * PTR);
+    assert!(offset_of!(CAudioChunk, sample_rate) == 3 * PTR);
+    assert!(offset_of!(CAudioChunk, duration_ms) == 3 * PTR + 4);
+
+    assert!(size_of::<CTTSHost>() == 4 * PTR);
+    assert!(offset_of!(CTTSHost, host) == 3 * PTR);
+
+    assert!(size_of::<CTTSVTable>() == 10 * PTR);
+    assert!(offset_of!(CTTSVTable, speak) == 5 * PTR);
+    assert!(offset_of!(CTTSVTable, provider_info) == 9 * PTR);
+
+    assert!(size_of::<CTTSPlugin>() == 15 * PTR);
+    assert!(offset_of!(CTTSPlugin, id) == PTR);
+    assert!(offset_of!(CTTSPlugin, vtable) == 5 * PTR);
+
+    assert!(size_of::<ErrorCode>() == 4);
+};
+
+/// Build the plugin manifest of a C plugin.
+///
+/// The manifest lists the TTS capability and requires gateway 1.x.
+pub fn c_tts_manifest(plugin: &CTTSPlugin) -> PluginManifest {
+    let manifest = PluginManifest::new(
+        string_or_empty(plugin.id),
+        string_or_empty(plugin.name),
+        string_or_empty(plugin.version),
+    )
+    .with_gateway_version(">=1.0.0, <2.0.0")
+    .with_capability(PluginCapabilityType::TTS);
+    match string(plugin.description) {
+        Some(description) => manifest.with_description(description),
+        None => manifest,
+    }
+}
+
+/// Create a TTS provider backed by a C plugin.
+///
+/// Use as the body of the shim's `create_tts` factory. Fails if the plugin
+/// was compiled against another [`C_ABI_VERSION`], lacks a required
+/// function or its `create` returns NULL.
+pub fn create_c_tts(
+    plugin: &'static CTTSPlugin,
+    config: *const FFIConfig,
+) -> RResult<TTSProvider, RString> {
+    if plugin.abi_version != C_ABI_VERSION {
+        return RResult::RErr(
+            format!(
+                "C plugin ABI version {} is not supported (expected {})",
+                plugin.abi_version, C_ABI_VERSION
+            )
+            .into(),
+        );
+    }
+    let (Some(create), Some(_), Some(_)) = (
+        plugin.vtable.create,
+        plugin.vtable.destroy,
+        plugin.vtable.speak,
+    ) else {
+        return RResult::RErr("C plugin must implement create, destroy and speak".into());
+    };
+    if config.is_null() {
+        return RResult::RErr("Null config".into());
+    }
+    let config_json = match CString::new(unsafe { &*config }.as_str()) {
+        Ok(json) => json,
+        Err(_) => return RResult::RErr("Config contains a NUL byte".into()),
+    };
+
+    // Boxed so the host pointer stays valid when the state moves
+    let callbacks = Box::new(HostCallbacks::default());
+    let host = CTTSHost {
+        on_audio: Some(host_on_audio),
+        on_error: Some(host_on_error),
+        on_complete: Some(host_on_complete),
+        host: &*callbacks as *const HostCallbacks as *mut c_void,
+    };
+    let provider = unsafe { create(config_json.as_ptr(), &host) };
+    if provider.is_null() {
+        return RResult::RErr("C plugin failed to create the provider".into());
+    }
+
+    RResult::ROk(TTSProvider {
+        handle: ProviderHandle::new(CTTSState {
+            plugin,
+            provider,
+            callbacks,
+        }),
+        vtable: C_TTS_VTABLE,
+    })
+}
+
+/// Callbacks registered by the gateway, invoked from the C host callbacks
+#[derive(Default)]
+struct HostCallbacks {
+    audio: CallbackRegistry<TTSAudioCallbackFn>,
+    error: CallbackRegistry<ErrorCallbackFn>,
+    complete: CallbackRegistry<CompleteCallbackFn>,
+}
+
+/// State behind the handle of a C-backed provider
+struct CTTSState {
+    plugin: &'static CTTSPlugin,
+    provider: *mut c_void,
+    callbacks: Box<HostCallbacks>,
+}
+
+impl CTTSState {
+    /// Turn a C status code into a result, with the plugin's error message
+    fn status(&self, code: u32) -> FFIResult {
+        if code == ErrorCode::Ok.as_u32() {
+            return RResult::ROk(());
+        }
+        let message = self
+            .plugin
+            .vtable
+            .last_error
+            .and_then(|last_error| string(unsafe { last_error(self.provider) }));
+        let code = ErrorCode::from_u32(code);
+        RResult::RErr(match message {
+            Some(message) => format!("{code:?}: {message}").into(),
+            None => format!("{code:?}").into(),
+        })
+    }
+
+    /// Call an optional `provider -> status` function; missing ones succeed
+    fn call(&self, function: Option<unsafe extern "C" fn(*mut c_void) -> u32>) -> FFIResult {
+        match function {
+            Some(function) => self.status(unsafe { function(self.provider) }),
+            None => RResult::ROk(()),
+        }
+    }
+}
+
+impl Drop for CTTSState {
+    fn drop(&mut self) {
+        // Stops the callbacks before `callbacks` is freed
+        if let Some(destroy) = self.plugin.vtable.destroy {
+            unsafe { destroy(self.provider) };
+        }
+    }
+}
+
+unsafe extern "C" fn host_on_audio(chunk: *const CAudioChunk, host: *mut c_void) {
+    if chunk.is_null() || host.is_null() {
+        return;
+    }
+    let (chunk, callbacks) = unsafe { (&*chunk, &*(host as *const HostCallbacks)) };
+    let data = if chunk.data.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(chunk.data, chunk.len) }
+    };
+    let format = string(chunk.format).unwrap_or_else(|| "linear16".to_string());
+    let audio = FFIAudioData::new(data.to_vec(), chunk.sample_rate, format)
+        .with_duration(chunk.duration_ms);
+    callbacks
+        .audio
+        .invoke(|callback, user_data| (callback.func)(&audio, user_data));
+}
+
+unsafe extern "C" fn host_on_error(code: u32, message: *const c_char, host: *mut c_void) {
+    if host.is_null() {
+        return;
+    }
+    let callbacks = unsafe { &*(host as *const HostCallbacks) };
+    let message = RString::from(
+        string(message).unwrap_or_else(|| format!("{:?}", ErrorCode::from_u32(code))),
+    );
+    callbacks
+        .error
+        .invoke(|callback, user_data| (callback.func)(code, &message, user_data));
+}
+
+unsafe extern "C" fn host_on_complete(host: *mut c_void) {
+    if host.is_null() {
+        return;
+    }
+    let callbacks = unsafe { &*(host as *const HostCallbacks) };
+    callbacks
+        .complete
+        .invoke(|callback, user_data| (callback.func)(user_data));
+}
+
+/// Copy a NUL-terminated C string; `None` for NULL
+fn string(ptr: *const c_char) -> Option<String> {
+    (!ptr.is_null()).then(|| {
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+fn string_or_empty(ptr: *const c_char) -> String {
+    string(ptr).unwrap_or_default()
+}
+
+/// Run `f` on the state behind a handle, or return `default` for a null handle
+fn with_state<R>(handle: *const ProviderHandle, default: R, f: impl FnOnce(&CTTSState) -> R) -> R {
+    if handle.is_null() || unsafe { &*handle }.is_null() {
+        return default;
+    }
+    f(unsafe { (*handle).as_ref::<CTTSState>() })
+}
+
+extern "C" fn c_tts_connect(handle: *mut ProviderHandle) -> FFIResult {
+    with_state(handle, RResult::RErr("Null handle".into()), |state| {
+        state.call(state.plugin.vtable.connect)
+    })
+}
+
+extern "C" fn c_tts_disconnect(handle: *mut ProviderHandle) -> FFIResult {
+    with_state(handle, RResult::RErr("Null handle".into()), |state| {
+        state.call(state.plugin.vtable.disconnect)
+    })
+}
+
+extern "C" fn c_tts_is_ready(handle: *const ProviderHandle) -> bool {
+    with_state(handle, false, |state| match state.plugin.vtable.is_ready {
+        Some(is_ready) => unsafe { is_ready(state.provider) },
+        None => true,
+    })
+}
+
+extern "C" fn c_tts_speak(
+    handle: *mut ProviderHandle,
+    text: *const RString,
+    flush: bool,
+) -> FFIResult {
+    if text.is_null() {
+        return RResult::RErr("Null text".into());
+    }
+    let text = match CString::new(unsafe { &*text }.as_str()) {
+        Ok(text) => text,
+        Err(_) => return RResult::RErr("Text contains a NUL byte".into()),
+    };
+    with_state(handle, RResult::RErr("Null handle".into()), |state| {
+        // Checked in create_c_tts
+        let speak = state.plugin.vtable.speak.expect("speak is required");
+        let len = text.as_bytes().len();
+        state.status(unsafe { speak(state.provider, text.as_ptr(), len, flush) })
+    })
+}
+
+extern "C" fn c_tts_clear(handle: *mut ProviderHandle) -> FFIResult {
+    with_state(handle, RResult::RErr("Null handle".into()), |state| {
+        state.call(state.plugin.vtable.clear)
+    })
+}
+
+extern "C" fn c_tts_flush(handle: *mut ProviderHandle) -> FFIResult {
+    with_state(handle, RResult::RErr("Null handle".into()), |state| {
+        state.call(state.plugin.vtable.flush)
+    })
+}
+
+extern "C" fn c_tts_set_audio_callback(
+    handle: *mut ProviderHandle,
+    callback: TTSAudioCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.callbacks.audio.set(callback, user_data);
+    })
+}
+
+extern "C" fn c_tts_set_error_callback(
+    handle: *mut ProviderHandle,
+    callback: ErrorCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.callbacks.error.set(callback, user_data);
+    })
+}
+
+extern "C" fn c_tts_set_complete_callback(
+    handle: *mut ProviderHandle,
+    callback: CompleteCallbackFn,
+    user_data: *mut (),
+) {
+    with_state(handle, (), |state| {
+        state.callbacks.complete.set(callback, user_data);
+    })
+}
+
+extern "C" fn c_tts_get_provider_info(handle: *const ProviderHandle) -> RString {
+    with_state(handle, None, |state| {
+        state
+            .plugin
+            .vtable
+            .provider_info
+            .and_then(|provider_info| string(unsafe { provider_info(state.provider) }))
+    })
+    .unwrap_or_else(|| r#"{"type": "c-plugin"}"#.to_string())
+    .into()
+}
+
+const C_TTS_VTABLE: TTSVTable = TTSVTable {
+    connect: c_tts_connect,
+    disconnect: c_tts_disconnect,
+    is_ready: c_tts_is_ready,
+    speak: c_tts_speak,
+    clear: c_tts_clear,
+    flush: c_tts_flush,
+    set_audio_callback: c_tts_set_audio_callback,
+    set_error_callback: c_tts_set_error_callback,
+    set_complete_callback: c_tts_set_complete_callback,
+    get_provider_info: c_tts_get_provider_info,
+    set_timing_callback: ROption::RNone,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Minimal C-style provider: `speak` with `flush` emits one chunk per byte
+    struct Fake {
+        host_audio: CAudioCallback,
+        host_complete: CCompleteCallback,
+        host: *mut c_void,
+        connected: bool,
+    }
+
+    static DESTROYED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn fake_create(_config: *const c_char, host: *const CTTSHost) -> *mut c_void {
+        let host = unsafe { &*host };
+        Box::into_raw(Box::new(Fake {
+            host_audio: host.on_audio,
+            host_complete: host.on_complete,
+            host: host.host,
+            connected: false,
+        })) as *mut c_void
+    }
+
+    unsafe extern "C" fn fake_destroy(provider: *mut c_void) {
+        drop(unsafe { Box::from_raw(provider as *mut Fake) });
+        DESTROYED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn fake_connect(provider: *mut c_void) -> u32 {
+        unsafe { &mut *(provider as *mut Fake) }.connected = true;
+        0
+    }
+
+    unsafe extern "C" fn fake_is_ready(provider: *const c_void) -> bool {
+        unsafe { &*(provider as *const Fake) }.connected
+    }
+
+    unsafe extern "C" fn fake_speak(
+        provider: *mut c_void,
+        text: *const c_char,
+        len: usize,
+        flush: bool,
+    ) -> u32 {
+        let fake = unsafe { &*(provider as *const Fake) };
+        if !fake.connected {
+            return ErrorCode::NotConnected.as_u32();
+        }
+        let text = unsafe { std::slice::from_raw_parts(text as *const u8, len) };
+        if flush {
+            let chunk = CAudioChunk {
+                data: text.as_ptr(),
+                len: text.len(),
+                format: std::ptr::null(),
+                sample_rate: 16000,
+                duration_ms: 0,
+            };
+            unsafe {
+                fake.host_audio.unwrap()(&chunk, fake.host);
+                fake.host_complete.unwrap()(fake.host);
+            }
+        }
+        0
+    }
+
+    unsafe extern "C" fn fake_last_error(_provider: *const c_void) -> *const c_char {
+        c"not connected yet".as_ptr()
+    }
+
+    const FAKE_PLUGIN: CTTSPlugin = CTTSPlugin {
+        abi_version: C_ABI_VERSION,
+        id: c"fake-c-tts".as_ptr(),
+        name: c"Fake C TTS".as_ptr(),
+        version: c"0.1.0".as_ptr(),
+        description: std::ptr::null(),
+        vtable: CTTSVTable {
+            create: Some(fake_create),
+            destroy: Some(fake_destroy),
+            connect: Some(fake_connect),
+            disconnect: None,
+            is_ready: Some(fake_is_ready),
+            speak: Some(fake_speak),
+            clear: None,
+            flush: None,
+            last_error: Some(fake_last_error),
+            provider_info: None,
+        },
+    };
+
+    /// The plugin's pointers only refer to statics
+    struct SyncPlugin(CTTSPlugin);
+    unsafe impl Sync for SyncPlugin {}
+
+    static AUDIO_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static COMPLETED: AtomicUsize = AtomicUsize::new(0);
+
+    extern "C" fn on_audio(audio: *const FFIAudioData, _user_data: *mut ()) {
+        let audio = unsafe { &*audio };
+        assert_eq!(audio.format.as_str(), "linear16");
+        assert_eq!(audio.sample_rate, 16000);
+        AUDIO_BYTES.fetch_add(audio.data.len(), Ordering::SeqCst);
+    }
+
+    extern "C" fn on_complete(_user_data: *mut ()) {
+        COMPLETED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_c_plugin_drives_tts_provider() {
+        static PLUGIN: SyncPlugin = SyncPlugin(FAKE_PLUGIN);
+        let plugin = &PLUGIN.0;
+        let manifest = c_tts_manifest(plugin);
+        assert_eq!(manifest.id.as_str(), "fake-c-tts");
+        assert_eq!(
+            manifest.capabilities.as_slice(),
+            &[PluginCapabilityType::TTS]
+        );
+
+        let config = FFIConfig::from_json("{}");
+        let mut provider = create_c_tts(plugin, &config).unwrap();
+        (provider.vtable.set_audio_callback)(
+            &mut provider.handle,
+            TTSAudioCallbackFn { func: on_audio },
+            std::ptr::null_mut(),
+        );
+        (provider.vtable.set_complete_callback)(
+            &mut provider.handle,
+            CompleteCallbackFn { func: on_complete },
+            std::ptr::null_mut(),
+        );
+
+        // Failures carry the error code and the plugin's message
+        let error = provider.speak(&"hello".into(), true).unwrap_err();
+        assert_eq!(error.as_str(), "NotConnected: not connected yet");
+        assert!(!provider.is_ready());
+
+        assert!(provider.connect().is_ok());
+        assert!(provider.is_ready());
+        assert!(provider.speak(&"hello".into(), true).is_ok());
+        assert_eq!(AUDIO_BYTES.load(Ordering::SeqCst), 5);
+        assert_eq!(COMPLETED.load(Ordering::SeqCst), 1);
+        // Missing optional functions succeed
+        assert!(provider.clear().is_ok());
+        assert_eq!(
+            provider.get_provider_info().as_str(),
+            r#"{"type": "c-plugin"}"#
+        );
+
+        drop(provider);
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_c_plugin_abi_version_is_checked() {
+        static OLD: SyncPlugin = SyncPlugin(CTTSPlugin {
+            abi_version: C_ABI_VERSION + 1,
+            ..FAKE_PLUGIN
+        });
+        let config = FFIConfig::from_json("{}");
+        match create_c_tts(&OLD.0, &config) {
+            RResult::ROk(_) => panic!("Plugin with another ABI version was accepted"),
+            RResult::RErr(error) => assert!(error.as_str().contains("ABI version 2")),
+        }
+    }
+}
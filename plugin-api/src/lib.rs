@@ -20,6 +20,23 @@
 //! All callback functions use raw pointers instead of references to ensure
 //! `StableAbi` compatibility. Callers must ensure pointers are valid.
 //!
+//! The `user_data` passed to a `set_*_callback` function is only valid until
+//! a later call replaces that callback returns. Store callbacks in a
+//! [`CallbackRegistry`], which delays the replacement until invocations
+//! using the old `user_data` have finished.
+//!
+//! # Storage
+//!
+//! Plugins must not write outside the directory the gateway provisions for
+//! them, since the rest of the filesystem may be read-only. Parse the `init`
+//! config with [`PluginInitConfig::from_ffi`] and use the [`PluginStorage`]
+//! it returns to cache models, voice lists and the like.
+//!
+//! # Plugins in C
+//!
+//! TTS plugins can also be written in C against `include/waav_plugin.h`;
+//! see the [`c_api`] module.
+//!
 //! # Example Plugin
 //!
 //! ```rust,ignore
@@ -50,6 +67,9 @@
 //!         create_stt: ROption::RSome(create_my_stt),
 //!         create_tts: ROption::RNone,
 //!         create_realtime: ROption::RNone,
+//!         abi_version: PLUGIN_API_VERSION,
+//!         provider_capabilities: ROption::RNone,
+//!         handle_http: ROption::RNone,
 //!     }.leak_into_prefix()
 //! }
 //! ```
@@ -60,9 +80,15 @@ use abi_stable::{
     declare_root_module_statics,
     library::RootModule,
     package_version_strings,
+    sabi_types::VersionStrings,
     std_types::{ROption, RResult, RString, RVec},
     StableAbi,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
 
 // =============================================================================
 // Re-exports for plugin developers
@@ -70,6 +96,8 @@ use abi_stable::{
 
 pub use abi_stable;
 
+pub mod c_api;
+
 // =============================================================================
 // FFI Result Type
 // =============================================================================
@@ -201,6 +229,192 @@ impl FFIConfig {
     }
 }
 
+// =============================================================================
+// Plugin Storage
+// =============================================================================
+
+/// Configuration the gateway passes to a plugin's `init` function.
+///
+/// Parse it from the `init` config with [`PluginInitConfig::from_ffi`]. Fields
+/// the gateway does not set are `None`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginInitConfig {
+    /// Directory the plugin may write to, created by the gateway under
+    /// `plugins.data_dir` (one subdirectory per plugin ID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_dir: Option<String>,
+
+    /// Most bytes the plugin may keep in `storage_dir`
+    ///
+    /// The gateway checks the directory periodically and deletes the least
+    /// recently modified files while it is over the quota.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_quota_bytes: Option<u64>,
+}
+
+impl PluginInitConfig {
+    /// Parse the config passed to `init`.
+    ///
+    /// Returns the default (no storage) for a null pointer or a config the
+    /// gateway did not fill in.
+    ///
+    /// # Safety
+    ///
+    /// `config` must be null or point to a valid `FFIConfig`.
+    pub unsafe fn from_ffi(config: *const FFIConfig) -> Self {
+        if config.is_null() {
+            return Self::default();
+        }
+        let json = (*config).as_str();
+        if json.trim().is_empty() {
+            return Self::default();
+        }
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// Serialize for passing to `init`.
+    pub fn to_ffi(&self) -> FFIConfig {
+        FFIConfig::from_json(serde_json::to_string(self).unwrap_or_default())
+    }
+
+    /// The plugin's storage, if the gateway provisioned one.
+    pub fn storage(&self) -> Option<PluginStorage> {
+        self.storage_dir.as_ref().map(|dir| PluginStorage {
+            dir: PathBuf::from(dir),
+            quota_bytes: self.storage_quota_bytes,
+        })
+    }
+}
+
+/// Read and write files in the storage directory the gateway provisioned.
+///
+/// Names are relative paths inside the directory; absolute paths and `..`
+/// are refused. Writes are atomic and refused when they would take the
+/// directory over its quota.
+///
+/// ```rust,ignore
+/// static STORAGE: OnceLock<PluginStorage> = OnceLock::new();
+///
+/// #[sabi_extern_fn]
+/// fn init(config: *const FFIConfig) -> FFIResult {
+///     if let Some(storage) = unsafe { PluginInitConfig::from_ffi(config) }.storage() {
+///         let _ = STORAGE.set(storage);
+///     }
+///     ffi_ok()
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginStorage {
+    dir: PathBuf,
+    quota_bytes: Option<u64>,
+}
+
+impl PluginStorage {
+    /// Storage in `dir`, limited to `quota_bytes` if given.
+    pub fn new(dir: impl Into<PathBuf>, quota_bytes: Option<u64>) -> Self {
+        Self {
+            dir: dir.into(),
+            quota_bytes,
+        }
+    }
+
+    /// The storage directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The storage quota in bytes, if any.
+    pub fn quota_bytes(&self) -> Option<u64> {
+        self.quota_bytes
+    }
+
+    /// Path of the file `name` inside the storage directory.
+    pub fn path(&self, name: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(name);
+        let valid = !name.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid storage file name '{name}'"),
+            ));
+        }
+        Ok(self.dir.join(relative))
+    }
+
+    /// Read the file `name`.
+    pub fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        std::fs::read(self.path(name)?)
+    }
+
+    /// Read the file `name` as UTF-8 text.
+    pub fn read_to_string(&self, name: &str) -> io::Result<String> {
+        std::fs::read_to_string(self.path(name)?)
+    }
+
+    /// Replace the file `name` with `data`, creating parent directories.
+    ///
+    /// Fails without touching the file when the directory would exceed its
+    /// quota.
+    pub fn write(&self, name: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.path(name)?;
+        if let Some(quota) = self.quota_bytes {
+            let replaced = std::fs::metadata(&path).map_or(0, |meta| meta.len());
+            let used = self.used_bytes()?.saturating_sub(replaced);
+            if used + data.len() as u64 > quota {
+                return Err(io::Error::other(format!(
+                    "writing {} bytes to '{name}' would exceed the storage quota of {quota} bytes \
+                     ({used} bytes used)",
+                    data.len()
+                )));
+            }
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // Write then rename, so readers never see a partial file
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp = path.with_file_name(temp_name);
+        std::fs::write(&temp, data)?;
+        std::fs::rename(&temp, &path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+    }
+
+    /// Delete the file `name`; deleting a missing file succeeds.
+    pub fn remove(&self, name: &str) -> io::Result<()> {
+        match std::fs::remove_file(self.path(name)?) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Total size of the files in the storage directory.
+    pub fn used_bytes(&self) -> io::Result<u64> {
+        fn dir_size(dir: &Path) -> io::Result<u64> {
+            let mut total = 0;
+            for entry in std::fs::read_dir(dir)? {
+                let entry = entry?;
+                let meta = entry.metadata()?;
+                total += if meta.is_dir() {
+                    dir_size(&entry.path())?
+                } else {
+                    meta.len()
+                };
+            }
+            Ok(total)
+        }
+        match dir_size(&self.dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            result => result,
+        }
+    }
+}
+
 // =============================================================================
 // Result Types (FFI-safe)
 // =============================================================================
@@ -311,6 +525,102 @@ impl FFIRealtimeAudio {
     }
 }
 
+// =============================================================================
+// HTTP Types (FFI-safe)
+// =============================================================================
+
+/// FFI-safe HTTP header.
+#[repr(C)]
+#[derive(StableAbi, Clone, Debug)]
+pub struct FFIHttpHeader {
+    /// Header name (lowercase)
+    pub name: RString,
+    /// Header value
+    pub value: RString,
+}
+
+impl FFIHttpHeader {
+    /// Create a new header.
+    pub fn new(name: impl Into<RString>, value: impl Into<RString>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// FFI-safe HTTP request routed to a plugin.
+///
+/// The gateway has already authenticated the caller and enforced its body
+/// size limit before the request reaches the plugin.
+#[repr(C)]
+#[derive(StableAbi, Clone, Debug)]
+pub struct FFIHttpRequest {
+    /// Request method (e.g., "GET", "POST")
+    pub method: RString,
+    /// Path below `/plugins/{plugin_id}`, always starting with `/`
+    pub path: RString,
+    /// Raw query string without the leading `?` (empty if none)
+    pub query: RString,
+    /// Request headers
+    pub headers: RVec<FFIHttpHeader>,
+    /// Request body
+    pub body: RVec<u8>,
+}
+
+impl FFIHttpRequest {
+    /// Get the value of the first header named `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.name.as_str().eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+}
+
+/// FFI-safe HTTP response returned by a plugin.
+#[repr(C)]
+#[derive(StableAbi, Clone, Debug)]
+pub struct FFIHttpResponse {
+    /// Status code
+    pub status: u16,
+    /// Response headers
+    pub headers: RVec<FFIHttpHeader>,
+    /// Response body
+    pub body: RVec<u8>,
+}
+
+impl FFIHttpResponse {
+    /// Create a response with an empty body.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: RVec::new(),
+            body: RVec::new(),
+        }
+    }
+
+    /// Create a response with a JSON body.
+    pub fn json(status: u16, json: impl Into<String>) -> Self {
+        let json: String = json.into();
+        Self::new(status)
+            .with_header("content-type", "application/json")
+            .with_body(json.into_bytes())
+    }
+
+    /// Add a header.
+    pub fn with_header(mut self, name: impl Into<RString>, value: impl Into<RString>) -> Self {
+        self.headers.push(FFIHttpHeader::new(name, value));
+        self
+    }
+
+    /// Set the body.
+    pub fn with_body(mut self, body: impl Into<RVec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
 // =============================================================================
 // Opaque Provider Handle
 // =============================================================================
@@ -319,6 +629,26 @@ impl FFIRealtimeAudio {
 ///
 /// This is a type-erased pointer to the plugin's internal state.
 /// The plugin is responsible for managing the memory.
+///
+/// # Shared handles
+///
+/// A handle created with [`ProviderHandle::new_shared`] holds one reference
+/// to an `Arc<T>`, and [`ProviderHandle::try_clone`] returns another handle
+/// to the same state, so several provider instances can share one upstream
+/// connection (e.g. an HTTP client or gRPC channel). The state is dropped
+/// with the last handle. Handles created with [`ProviderHandle::new`] own
+/// their state and cannot be cloned.
+///
+/// Shared state is accessed concurrently through every clone, from any
+/// thread, so it must be `Send + Sync` and only mutated through interior
+/// mutability (`Mutex`, atomics, ...). Callbacks stored in it are shared by
+/// all clones as well.
+///
+/// A handle created with [`ProviderHandle::new_cloneable`] owns its state,
+/// and [`ProviderHandle::try_clone`] creates a new instance with
+/// [`CloneInstance::clone_instance`]. Each clone keeps its own callbacks and
+/// connection state and only shares what the state holds in an `Arc`, such
+/// as its HTTP client.
 #[repr(C)]
 #[derive(StableAbi)]
 pub struct ProviderHandle {
@@ -326,6 +656,9 @@ pub struct ProviderHandle {
     pub ptr: *mut (),
     /// Drop function to clean up the provider
     pub drop_fn: Option<extern "C" fn(*mut ())>,
+    /// Clone function for shared handles, returning the pointer of the new
+    /// reference; `None` for handles that own their state
+    pub clone_fn: Option<extern "C" fn(*mut ()) -> *mut ()>,
 }
 
 impl ProviderHandle {
@@ -343,14 +676,59 @@ impl ProviderHandle {
         Self {
             ptr,
             drop_fn: Some(drop_impl::<T>),
+            clone_fn: None,
         }
     }
 
+    /// Create a shared handle from a reference-counted value.
+    ///
+    /// The handle holds one reference to `value`, released by its drop
+    /// function. See [Shared handles](ProviderHandle#shared-handles) for the
+    /// thread-safety requirements.
+    pub fn new_shared<T: Send + Sync>(value: std::sync::Arc<T>) -> Self {
+        let ptr = std::sync::Arc::into_raw(value) as *mut ();
+
+        extern "C" fn drop_shared<T>(ptr: *mut ()) {
+            unsafe {
+                drop(std::sync::Arc::from_raw(ptr as *const T));
+            }
+        }
+
+        extern "C" fn clone_shared<T>(ptr: *mut ()) -> *mut () {
+            unsafe {
+                std::sync::Arc::increment_strong_count(ptr as *const T);
+            }
+            ptr
+        }
+
+        Self {
+            ptr,
+            drop_fn: Some(drop_shared::<T>),
+            clone_fn: Some(clone_shared::<T>),
+        }
+    }
+
+    /// Create a handle whose clones are new instances of `value`.
+    ///
+    /// [`ProviderHandle::try_clone`] boxes the result of
+    /// [`CloneInstance::clone_instance`]; every handle drops its own instance.
+    pub fn new_cloneable<T: CloneInstance>(value: T) -> Self {
+        extern "C" fn clone_instance<T: CloneInstance>(ptr: *mut ()) -> *mut () {
+            let instance = unsafe { &*(ptr as *const T) }.clone_instance();
+            Box::into_raw(Box::new(instance)) as *mut ()
+        }
+
+        let mut handle = Self::new(value);
+        handle.clone_fn = Some(clone_instance::<T>);
+        handle
+    }
+
     /// Create a null handle (for uninitialized state).
     pub fn null() -> Self {
         Self {
             ptr: std::ptr::null_mut(),
             drop_fn: None,
+            clone_fn: None,
         }
     }
 
@@ -359,6 +737,29 @@ impl ProviderHandle {
         self.ptr.is_null()
     }
 
+    /// Check if the handle can be cloned, i.e. was created with
+    /// [`ProviderHandle::new_shared`] or [`ProviderHandle::new_cloneable`].
+    pub fn is_shared(&self) -> bool {
+        self.clone_fn.is_some()
+    }
+
+    /// Create another handle to the same shared state, or to a new instance
+    /// for handles created with [`ProviderHandle::new_cloneable`].
+    ///
+    /// Returns `None` for null handles and for handles created with
+    /// [`ProviderHandle::new`].
+    pub fn try_clone(&self) -> Option<Self> {
+        let clone_fn = self.clone_fn?;
+        if self.ptr.is_null() {
+            return None;
+        }
+        Some(Self {
+            ptr: clone_fn(self.ptr),
+            drop_fn: self.drop_fn,
+            clone_fn: Some(clone_fn),
+        })
+    }
+
     /// Get a reference to the underlying value.
     ///
     /// # Safety
@@ -371,11 +772,23 @@ impl ProviderHandle {
     ///
     /// # Safety
     /// The caller must ensure T matches the original type and ptr is valid.
+    /// Shared handles alias their state, so they must use [`Self::as_ref`],
+    /// as must cloneable ones.
     pub unsafe fn as_mut<T>(&mut self) -> &mut T {
+        debug_assert!(!self.is_shared(), "as_mut on a shared ProviderHandle");
         &mut *(self.ptr as *mut T)
     }
 }
 
+/// Provider state that [`ProviderHandle::new_cloneable`] handles clone.
+pub trait CloneInstance: Send + Sync + Sized {
+    /// Create an independent instance with the same configuration.
+    ///
+    /// Upstream connections held in an `Arc`, e.g. an HTTP client, should be
+    /// shared with the new instance; callbacks and connection state should not.
+    fn clone_instance(&self) -> Self;
+}
+
 impl Drop for ProviderHandle {
     fn drop(&mut self) {
         if let Some(drop_fn) = self.drop_fn {
@@ -438,6 +851,233 @@ pub struct RealtimeAudioCallbackFn {
     pub func: extern "C" fn(*const FFIRealtimeAudio, *mut ()),
 }
 
+/// Wrapper for realtime tool call callback function.
+///
+/// The string argument is a JSON object describing the tool call:
+/// `{"call_id": "...", "name": "...", "arguments": "<json>", "item_id": "..."}`
+/// (`item_id` is optional).
+#[repr(transparent)]
+#[derive(StableAbi, Clone, Copy)]
+pub struct ToolCallCallbackFn {
+    pub func: extern "C" fn(*const RString, *mut ()),
+}
+
+/// Wrapper for TTS timing callback function.
+///
+/// The string argument is a JSON array of viseme and word timing events, with
+/// offsets in milliseconds from the start of the current utterance's audio:
+/// `[{"kind": "viseme", "offset_ms": 50, "viseme_id": 21},
+/// {"kind": "word", "offset_ms": 50, "duration_ms": 320, "text": "Hello"}]`.
+#[repr(transparent)]
+#[derive(StableAbi, Clone, Copy)]
+pub struct TTSTimingCallbackFn {
+    pub func: extern "C" fn(*const RString, *mut ()),
+}
+
+// =============================================================================
+// Callback Registry
+// =============================================================================
+
+/// Registration generation handed out by a [`CallbackRegistry`].
+///
+/// Every [`CallbackRegistry::set`] and [`CallbackRegistry::clear`] starts a
+/// new generation, so tokens from one registry order its registrations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallbackToken {
+    generation: u64,
+}
+
+impl CallbackToken {
+    /// Generation number; an empty registry starts at 0.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// Bookkeeping guarded by the registry mutex.
+struct RegistryState<T> {
+    /// Registered callback and its `user_data`
+    current: Option<(T, *mut ())>,
+    /// Generation of `current`
+    generation: u64,
+    /// Invocations running outside the lock, counted per generation
+    in_flight: BTreeMap<u64, usize>,
+}
+
+/// Thread-safe slot for one callback and the `user_data` passed with it.
+///
+/// Plugins keep one registry per callback the vtable can set, store into it
+/// from `set_*_callback`, and call the callback only through
+/// [`invoke`](Self::invoke).
+///
+/// # Memory Contract
+///
+/// The gateway frees the `user_data` of a replaced callback as soon as the
+/// `set_*_callback` call that replaced it returns, and frees the remaining
+/// `user_data` after the provider handle is dropped. The registry keeps the
+/// plugin on its side of that contract:
+///
+/// - `invoke` snapshots the callback, its `user_data` and its generation
+///   atomically and counts the invocation as in flight until it returns.
+/// - `set` and `clear` return only once no invocation of an earlier
+///   generation is in flight, so by the time `set_*_callback` returns
+///   nothing references the old `user_data`.
+///
+/// Callbacks run without the registry lock held. A callback must not call
+/// `set` or `clear` on the registry that is invoking it, since that waits
+/// for the invocation itself to finish.
+///
+/// Plugins must still stop invoking callbacks once their last provider
+/// handle is dropped, for example by joining background threads in `Drop`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// struct MyState {
+///     result_callback: CallbackRegistry<STTResultCallbackFn>,
+/// }
+///
+/// extern "C" fn my_set_result_callback(
+///     handle: *mut ProviderHandle,
+///     callback: STTResultCallbackFn,
+///     user_data: *mut (),
+/// ) {
+///     let state = unsafe { (*handle).as_ref::<MyState>() };
+///     state.result_callback.set(callback, user_data);
+/// }
+///
+/// fn emit(state: &MyState, result: &FFISTTResult) {
+///     state
+///         .result_callback
+///         .invoke(|callback, user_data| (callback.func)(result, user_data));
+/// }
+/// ```
+pub struct CallbackRegistry<T: Copy> {
+    state: Mutex<RegistryState<T>>,
+    /// Signalled whenever an invocation finishes
+    retired: Condvar,
+}
+
+impl<T: Copy> CallbackRegistry<T> {
+    /// Create an empty registry at generation 0.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(RegistryState {
+                current: None,
+                generation: 0,
+                in_flight: BTreeMap::new(),
+            }),
+            retired: Condvar::new(),
+        }
+    }
+
+    /// Register `callback` with `user_data`, replacing the previous callback.
+    ///
+    /// Blocks until invocations of earlier generations have returned.
+    pub fn set(&self, callback: T, user_data: *mut ()) -> CallbackToken {
+        self.replace(Some((callback, user_data)))
+    }
+
+    /// Remove the callback.
+    ///
+    /// Blocks until invocations of earlier generations have returned.
+    pub fn clear(&self) -> CallbackToken {
+        self.replace(None)
+    }
+
+    /// Call `f` with the current callback and its `user_data`.
+    ///
+    /// Returns `None` without calling `f` if no callback is registered.
+    /// The `user_data` stays valid until `f` returns.
+    pub fn invoke<R>(&self, f: impl FnOnce(T, *mut ()) -> R) -> Option<R> {
+        let (callback, user_data, generation) = {
+            let mut state = self.lock();
+            let (callback, user_data) = state.current?;
+            let generation = state.generation;
+            *state.in_flight.entry(generation).or_insert(0) += 1;
+            (callback, user_data, generation)
+        };
+        let _in_flight = InFlight {
+            registry: self,
+            generation,
+        };
+        Some(f(callback, user_data))
+    }
+
+    /// Token of the current registration.
+    pub fn token(&self) -> CallbackToken {
+        CallbackToken {
+            generation: self.lock().generation,
+        }
+    }
+
+    /// Whether `token` is still the current registration.
+    pub fn is_current(&self, token: CallbackToken) -> bool {
+        self.token() == token
+    }
+
+    /// Whether a callback is registered.
+    pub fn is_set(&self) -> bool {
+        self.lock().current.is_some()
+    }
+
+    fn replace(&self, current: Option<(T, *mut ())>) -> CallbackToken {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.current = current;
+        let generation = state.generation;
+        while state
+            .in_flight
+            .keys()
+            .next()
+            .is_some_and(|&oldest| oldest < generation)
+        {
+            state = self
+                .retired
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        CallbackToken { generation }
+    }
+
+    /// The mutex only guards counters, so a poisoned lock is still consistent.
+    fn lock(&self) -> MutexGuard<'_, RegistryState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Copy> Default for CallbackRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Safety: the registry never dereferences `user_data`; it only hands it back
+// to the callback it was registered with, which owns its thread-safety. The
+// callback wrappers themselves are plain function pointers.
+unsafe impl<T: Copy + Send> Send for CallbackRegistry<T> {}
+unsafe impl<T: Copy + Send> Sync for CallbackRegistry<T> {}
+
+/// Marks an invocation finished on drop, including when the callback panics.
+struct InFlight<'a, T: Copy> {
+    registry: &'a CallbackRegistry<T>,
+    generation: u64,
+}
+
+impl<T: Copy> Drop for InFlight<'_, T> {
+    fn drop(&mut self) {
+        let mut state = self.registry.lock();
+        if let Some(count) = state.in_flight.get_mut(&self.generation) {
+            *count -= 1;
+            if *count == 0 {
+                state.in_flight.remove(&self.generation);
+            }
+        }
+        drop(state);
+        self.registry.retired.notify_all();
+    }
+}
+
 // =============================================================================
 // STT Provider VTable
 // =============================================================================
@@ -480,6 +1120,18 @@ pub struct STTVTable {
 
     /// Get provider info as JSON string.
     pub get_provider_info: extern "C" fn(handle: *const ProviderHandle) -> RString,
+
+    /// Tell the provider that the gateway reconnected it after a recoverable error.
+    ///
+    /// Called after `connect` succeeded and before the audio buffered during the
+    /// outage is sent; `buffered_len` is its byte count. A provider that can
+    /// resume its upstream session (e.g. by session ID or stream offset) does so
+    /// here instead of starting over. Returning `RErr` counts as a failed
+    /// reconnect attempt. See [`ErrorCode::is_recoverable`].
+    ///
+    /// Set to `ROption::RNone` if the provider has nothing to resume.
+    pub notify_recovered:
+        ROption<extern "C" fn(handle: *mut ProviderHandle, buffered_len: usize) -> FFIResult>,
 }
 
 /// STT Provider instance with handle and vtable.
@@ -517,6 +1169,16 @@ impl STTProvider {
     pub fn get_provider_info(&self) -> RString {
         (self.vtable.get_provider_info)(&self.handle)
     }
+
+    /// Notify the provider that it was reconnected after a recoverable error.
+    ///
+    /// Succeeds without calling the plugin if it has no `notify_recovered`.
+    pub fn notify_recovered(&mut self, buffered_len: usize) -> FFIResult {
+        match self.vtable.notify_recovered {
+            ROption::RSome(notify) => notify(&mut self.handle, buffered_len),
+            ROption::RNone => ffi_ok(),
+        }
+    }
 }
 
 // =============================================================================
@@ -569,6 +1231,17 @@ pub struct TTSVTable {
 
     /// Get provider info as JSON string.
     pub get_provider_info: extern "C" fn(handle: *const ProviderHandle) -> RString,
+
+    /// Set the viseme and word timing callback.
+    ///
+    /// Called alongside the other callback setters. A provider whose config
+    /// JSON has `"visemes": true` reports the timing of each utterance before
+    /// or with its audio. See [`TTSTimingCallbackFn`].
+    ///
+    /// Set to `ROption::RNone` if the provider reports no timing.
+    pub set_timing_callback: ROption<
+        extern "C" fn(handle: *mut ProviderHandle, callback: TTSTimingCallbackFn, user_data: *mut ()),
+    >,
 }
 
 /// TTS Provider instance with handle and vtable.
@@ -616,6 +1289,24 @@ impl TTSProvider {
     pub fn get_provider_info(&self) -> RString {
         (self.vtable.get_provider_info)(&self.handle)
     }
+
+    /// Set the timing callback.
+    ///
+    /// Returns `false` without calling the plugin if it has no
+    /// `set_timing_callback`.
+    pub fn set_timing_callback(
+        &mut self,
+        callback: TTSTimingCallbackFn,
+        user_data: *mut (),
+    ) -> bool {
+        match self.vtable.set_timing_callback {
+            ROption::RSome(set) => {
+                set(&mut self.handle, callback, user_data);
+                true
+            }
+            ROption::RNone => false,
+        }
+    }
 }
 
 // =============================================================================
@@ -668,6 +1359,20 @@ pub struct RealtimeVTable {
         user_data: *mut (),
     ),
 
+    /// Set the tool call (function calling) callback.
+    ///
+    /// The callback receives a JSON description of each tool call requested by the model.
+    pub set_tool_call_callback: extern "C" fn(
+        handle: *mut ProviderHandle,
+        callback: ToolCallCallbackFn,
+        user_data: *mut (),
+    ),
+
+    /// Send the result of a tool call back to the model.
+    ///
+    /// `response_json` is a JSON object: `{"call_id": "...", "result": "..."}`.
+    pub send_tool_response: extern "C" fn(handle: *mut ProviderHandle, response_json: *const RString) -> FFIResult,
+
     /// Get provider info as JSON string.
     pub get_provider_info: extern "C" fn(handle: *const ProviderHandle) -> RString,
 }
@@ -718,6 +1423,11 @@ impl RealtimeProvider {
         (self.vtable.cancel_response)(&mut self.handle)
     }
 
+    /// Send a tool call result.
+    pub fn send_tool_response(&mut self, response_json: &RString) -> FFIResult {
+        (self.vtable.send_tool_response)(&mut self.handle, response_json)
+    }
+
     /// Get provider info.
     pub fn get_provider_info(&self) -> RString {
         (self.vtable.get_provider_info)(&self.handle)
@@ -742,7 +1452,7 @@ pub struct PluginModule {
     /// Initialize the plugin.
     ///
     /// Called once when the plugin is loaded.
-    /// The config parameter contains plugin-specific configuration as JSON.
+    /// The config parameter holds a [`PluginInitConfig`] as JSON.
     pub init: extern "C" fn(config: *const FFIConfig) -> FFIResult,
 
     /// Shutdown the plugin.
@@ -765,14 +1475,93 @@ pub struct PluginModule {
     ///
     /// Set to `ROption::RNone` if this plugin doesn't provide Realtime.
     pub create_realtime: ROption<extern "C" fn(*const FFIConfig) -> RResult<RealtimeProvider, RString>>,
+
+    /// Version of this crate the plugin was built against.
+    ///
+    /// Set to [`PLUGIN_API_VERSION`]. The gateway compares it with its own
+    /// version before calling any other plugin function.
+    pub abi_version: VersionStrings,
+
+    /// Constraints on the configs the plugin's providers accept, as JSON.
+    ///
+    /// The gateway checks session configs against them before calling a
+    /// factory, so problems are reported together and up front. Models are
+    /// matched case-insensitively, `model_aliases` maps other names to a
+    /// listed model, and sessions asking for an unlisted model are rejected
+    /// unless they set `allow_unknown_models`. Each of `stt` and `tts` is
+    /// optional, as is every field inside:
+    ///
+    /// ```json
+    /// {
+    ///   "stt": {
+    ///     "models": ["fast", "accurate"],
+    ///     "model_aliases": {"fast-latest": "fast"},
+    ///     "sample_rates": [8000, 16000],
+    ///     "encodings": ["linear16", "mulaw"],
+    ///     "requires_api_key": true
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// Set to `ROption::RNone` to skip these checks.
+    pub provider_capabilities: ROption<extern "C" fn() -> RString>,
+
+    /// Handler for HTTP requests to `/plugins/{plugin_id}/...`.
+    ///
+    /// Lets a plugin expose a small REST surface of its own, such as listing
+    /// voices or flushing a cache. The gateway authenticates the caller,
+    /// checks the `plugin:{plugin_id}` scope and limits the body size before
+    /// calling it, and answers `504` if it runs past the configured timeout.
+    /// It is called from a blocking worker thread and may be called
+    /// concurrently. Return `RErr` for an internal failure (reported as `502`).
+    ///
+    /// Set to `ROption::RNone` if this plugin doesn't serve HTTP.
+    pub handle_http:
+        ROption<extern "C" fn(*const FFIHttpRequest) -> RResult<FFIHttpResponse, RString>>,
 }
 
+/// Version of the plugin API, as compiled into the plugin or gateway.
+pub const PLUGIN_API_VERSION: VersionStrings = package_version_strings!();
+
 impl RootModule for PluginModule_Ref {
     declare_root_module_statics! {PluginModule_Ref}
 
     const BASE_NAME: &'static str = "waav_plugin";
     const NAME: &'static str = "waav_plugin";
-    const VERSION_STRINGS: abi_stable::sabi_types::VersionStrings = package_version_strings!();
+    const VERSION_STRINGS: VersionStrings = PLUGIN_API_VERSION;
+}
+
+impl PluginModule_Ref {
+    /// Get the plugin API version the plugin was built against.
+    ///
+    /// Returns `None` for plugins built before the version was exported.
+    pub fn plugin_abi_version(&self) -> Option<VersionStrings> {
+        self.abi_version()
+    }
+
+    /// Check whether the plugin's ABI version is compatible with the host.
+    ///
+    /// Compares the plugin's version strings with the host's
+    /// `VERSION_STRINGS`. Plugins that don't report a version, or whose
+    /// major version differs or minor version differs by more than 1, are
+    /// incompatible.
+    pub fn is_compatible_with_host(&self) -> bool {
+        self.plugin_abi_version()
+            .is_some_and(|plugin| abi_versions_compatible(&plugin, &Self::VERSION_STRINGS))
+    }
+}
+
+/// Check whether a plugin ABI version is compatible with a host ABI version.
+///
+/// Versions are compatible when the major versions match and the minor
+/// versions differ by at most 1. Unparseable versions are incompatible.
+pub fn abi_versions_compatible(plugin: &VersionStrings, host: &VersionStrings) -> bool {
+    match (plugin.parsed(), host.parsed()) {
+        (Ok(plugin), Ok(host)) => {
+            plugin.major == host.major && plugin.minor.abs_diff(host.minor) <= 1
+        }
+        _ => false,
+    }
 }
 
 // =============================================================================
@@ -780,6 +1569,13 @@ impl RootModule for PluginModule_Ref {
 // =============================================================================
 
 /// Standard error codes for plugin errors.
+///
+/// Codes reported through an STT provider's error callback are either
+/// recoverable or fatal (see [`ErrorCode::is_recoverable`]). On a recoverable
+/// code the gateway buffers the session's audio, calls `disconnect` and
+/// `connect` with bounded retries, then `notify_recovered`, and sends the
+/// buffered audio; the session sees no error unless every attempt fails. A
+/// fatal code is passed on to the session as an error.
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ErrorCode {
@@ -829,9 +1625,25 @@ impl ErrorCode {
             8 => ErrorCode::InternalError,
             9 => ErrorCode::NotConnected,
             10 => ErrorCode::RateLimited,
+            11 => ErrorCode::InvalidInput,
             _ => ErrorCode::InternalError,
         }
     }
+
+    /// Whether the gateway reconnects the provider instead of failing the session.
+    ///
+    /// Lost or unreachable upstream connections are recoverable:
+    /// `ConnectionFailed`, `NetworkError`, `TimeoutError` and `NotConnected`.
+    /// Everything else, including `RateLimited`, is fatal.
+    pub fn is_recoverable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::ConnectionFailed
+                | ErrorCode::NetworkError
+                | ErrorCode::TimeoutError
+                | ErrorCode::NotConnected
+        )
+    }
 }
 
 // =============================================================================
@@ -856,6 +1668,9 @@ pub type RealtimeTranscriptCallback = extern "C" fn(*const FFITranscriptResult,
 /// Type alias for Realtime audio callback.
 pub type RealtimeAudioCallback = extern "C" fn(*const FFIRealtimeAudio, *mut ());
 
+/// Type alias for Realtime tool call callback.
+pub type ToolCallCallback = extern "C" fn(*const RString, *mut ());
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -863,6 +1678,7 @@ pub type RealtimeAudioCallback = extern "C" fn(*const FFIRealtimeAudio, *mut ())
 #[cfg(test)]
 mod tests {
     use super::*;
+    use abi_stable::prefix_type::PrefixTypeTrait;
 
     #[test]
     fn test_plugin_manifest_builder() {
@@ -909,6 +1725,62 @@ mod tests {
         assert_eq!(audio.duration_ms, 500);
     }
 
+    fn version(s: &'static str) -> VersionStrings {
+        VersionStrings::new(s)
+    }
+
+    extern "C" fn mock_manifest() -> PluginManifest {
+        PluginManifest::new("mock", "Mock", "1.0.0")
+    }
+
+    extern "C" fn mock_init(_config: *const FFIConfig) -> FFIResult {
+        ffi_ok()
+    }
+
+    extern "C" fn mock_shutdown() -> FFIResult {
+        ffi_ok()
+    }
+
+    fn mock_module(abi_version: VersionStrings) -> PluginModule_Ref {
+        PluginModule {
+            manifest: mock_manifest,
+            init: mock_init,
+            shutdown: mock_shutdown,
+            create_stt: ROption::RNone,
+            create_tts: ROption::RNone,
+            create_realtime: ROption::RNone,
+            abi_version,
+            provider_capabilities: ROption::RNone,
+            handle_http: ROption::RNone,
+        }
+        .leak_into_prefix()
+    }
+
+    #[test]
+    fn test_abi_versions_compatible() {
+        let host = version("1.2.0");
+        assert!(abi_versions_compatible(&version("1.2.5"), &host));
+        assert!(abi_versions_compatible(&version("1.3.0"), &host));
+        assert!(abi_versions_compatible(&version("1.1.0"), &host));
+        assert!(!abi_versions_compatible(&version("1.4.0"), &host));
+        assert!(!abi_versions_compatible(&version("1.0.0"), &host));
+        assert!(!abi_versions_compatible(&version("2.2.0"), &host));
+        assert!(!abi_versions_compatible(&version("garbage"), &host));
+    }
+
+    #[test]
+    fn test_module_compatible_with_host() {
+        let module = mock_module(PLUGIN_API_VERSION);
+        assert_eq!(module.plugin_abi_version(), Some(PLUGIN_API_VERSION));
+        assert!(module.is_compatible_with_host());
+    }
+
+    #[test]
+    fn test_module_with_mismatched_abi_version() {
+        let module = mock_module(version("99.0.0"));
+        assert!(!module.is_compatible_with_host());
+    }
+
     #[test]
     fn test_error_code_conversion() {
         assert_eq!(ErrorCode::from_u32(0), ErrorCode::Ok);
@@ -918,6 +1790,20 @@ mod tests {
         assert_eq!(ErrorCode::ConnectionFailed.as_u32(), 1);
     }
 
+    #[test]
+    fn test_error_code_recoverable() {
+        assert!(ErrorCode::ConnectionFailed.is_recoverable());
+        assert!(ErrorCode::NetworkError.is_recoverable());
+        assert!(ErrorCode::TimeoutError.is_recoverable());
+        assert!(ErrorCode::NotConnected.is_recoverable());
+
+        assert!(!ErrorCode::AuthenticationFailed.is_recoverable());
+        assert!(!ErrorCode::ConfigurationError.is_recoverable());
+        assert!(!ErrorCode::RateLimited.is_recoverable());
+        // Unknown codes map to InternalError, which is fatal
+        assert!(!ErrorCode::from_u32(999).is_recoverable());
+    }
+
     #[test]
     fn test_ffi_config() {
         let config = FFIConfig::from_json(r#"{"api_key": "test"}"#);
@@ -944,6 +1830,231 @@ mod tests {
     fn test_provider_handle_null() {
         let handle = ProviderHandle::null();
         assert!(handle.is_null());
+        assert!(!handle.is_shared());
+        assert!(handle.try_clone().is_none());
+    }
+
+    #[test]
+    fn test_owned_provider_handle_cannot_be_cloned() {
+        let handle = ProviderHandle::new(42u32);
+        assert!(!handle.is_shared());
+        assert!(handle.try_clone().is_none());
+    }
+
+    #[test]
+    fn test_shared_provider_handle() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        /// Stand-in for an HTTP client shared by provider instances
+        struct SharedClient {
+            requests: AtomicUsize,
+        }
+
+        let client = Arc::new(SharedClient {
+            requests: AtomicUsize::new(0),
+        });
+        let first = ProviderHandle::new_shared(client.clone());
+        assert!(first.is_shared());
+        assert_eq!(Arc::strong_count(&client), 2);
+
+        let second = first.try_clone().unwrap();
+        assert_eq!(Arc::strong_count(&client), 3);
+        assert_eq!(first.ptr, second.ptr);
+
+        // Both instances use the same client, from any thread
+        let worker = std::thread::spawn(move || {
+            unsafe { second.as_ref::<SharedClient>() }
+                .requests
+                .fetch_add(1, Ordering::SeqCst);
+            second
+        });
+        unsafe { first.as_ref::<SharedClient>() }
+            .requests
+            .fetch_add(1, Ordering::SeqCst);
+        let second = worker.join().unwrap();
+        assert_eq!(client.requests.load(Ordering::SeqCst), 2);
+
+        // Each handle releases its own reference
+        drop(first);
+        assert_eq!(Arc::strong_count(&client), 2);
+        drop(second);
+        assert_eq!(Arc::strong_count(&client), 1);
+    }
+
+    #[test]
+    fn test_cloneable_provider_handle() {
+        use std::sync::Arc;
+
+        /// Instance state around a client shared by all instances
+        struct Instance {
+            client: Arc<u32>,
+            connected: bool,
+        }
+
+        impl CloneInstance for Instance {
+            fn clone_instance(&self) -> Self {
+                Self {
+                    client: Arc::clone(&self.client),
+                    connected: false,
+                }
+            }
+        }
+
+        let client = Arc::new(7u32);
+        let first = ProviderHandle::new_cloneable(Instance {
+            client: client.clone(),
+            connected: true,
+        });
+        assert!(first.is_shared());
+
+        let second = first.try_clone().unwrap();
+        assert_ne!(first.ptr, second.ptr);
+        assert_eq!(Arc::strong_count(&client), 3);
+        unsafe {
+            let (first, second) = (first.as_ref::<Instance>(), second.as_ref::<Instance>());
+            assert!(Arc::ptr_eq(&first.client, &second.client));
+            assert!(first.connected);
+            assert!(!second.connected);
+        }
+
+        // Each handle drops its own instance
+        drop(first);
+        assert_eq!(Arc::strong_count(&client), 2);
+        drop(second);
+        assert_eq!(Arc::strong_count(&client), 1);
+    }
+
+    extern "C" fn noop_complete(_user_data: *mut ()) {}
+
+    #[test]
+    fn test_callback_registry_set_invoke_clear() {
+        let registry = CallbackRegistry::<CompleteCallbackFn>::new();
+        assert_eq!(registry.token().generation(), 0);
+        assert!(!registry.is_set());
+        assert_eq!(registry.invoke(|_, _| ()), None);
+
+        let mut value = 7u32;
+        let user_data = &mut value as *mut u32 as *mut ();
+        let callback = CompleteCallbackFn {
+            func: noop_complete,
+        };
+        let first = registry.set(callback, user_data);
+        assert_eq!(first.generation(), 1);
+        assert!(registry.is_current(first));
+        assert_eq!(
+            registry.invoke(|callback, user_data| {
+                (callback.func)(user_data);
+                unsafe { *(user_data as *const u32) }
+            }),
+            Some(7)
+        );
+
+        let cleared = registry.clear();
+        assert!(cleared > first);
+        assert!(!registry.is_current(first));
+        assert!(!registry.is_set());
+        assert_eq!(registry.invoke(|_, _| ()), None);
+    }
+
+    #[test]
+    fn test_callback_registry_set_waits_for_in_flight_invocation() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::{mpsc, Arc};
+        use std::time::Duration;
+
+        let registry = Arc::new(CallbackRegistry::<CompleteCallbackFn>::new());
+        let callback = CompleteCallbackFn {
+            func: noop_complete,
+        };
+        registry.set(callback, std::ptr::null_mut());
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let (started_tx, started_rx) = mpsc::channel();
+        let invoker = {
+            let registry = registry.clone();
+            let finished = finished.clone();
+            std::thread::spawn(move || {
+                registry.invoke(|_, _| {
+                    started_tx.send(()).unwrap();
+                    std::thread::sleep(Duration::from_millis(50));
+                    finished.store(true, Ordering::SeqCst);
+                })
+            })
+        };
+
+        started_rx.recv().unwrap();
+        registry.set(callback, std::ptr::null_mut());
+        // set returned, so the invocation of the old generation has finished
+        assert!(finished.load(Ordering::SeqCst));
+        assert_eq!(invoker.join().unwrap(), Some(()));
+    }
+
+    #[test]
+    fn test_callback_registry_concurrent_set_and_invoke() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        /// Stand-in for gateway user_data that is retired once replaced
+        struct UserData {
+            retired: AtomicBool,
+        }
+
+        let registry = Arc::new(CallbackRegistry::<CompleteCallbackFn>::new());
+        let callback = CompleteCallbackFn {
+            func: noop_complete,
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let invocations = Arc::new(AtomicUsize::new(0));
+
+        let invokers: Vec<_> = (0..4)
+            .map(|_| {
+                let registry = registry.clone();
+                let stop = stop.clone();
+                let invocations = invocations.clone();
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::SeqCst) {
+                        registry.invoke(|callback, user_data| {
+                            let data = unsafe { &*(user_data as *const UserData) };
+                            assert!(!data.retired.load(Ordering::SeqCst));
+                            (callback.func)(user_data);
+                            std::thread::yield_now();
+                            assert!(!data.retired.load(Ordering::SeqCst));
+                            invocations.fetch_add(1, Ordering::SeqCst);
+                        });
+                    }
+                })
+            })
+            .collect();
+
+        // Replace the callback repeatedly, retiring the old user_data the
+        // moment set returns, as the gateway does when it frees it
+        let mut all = Vec::new();
+        let mut previous: Option<*const UserData> = None;
+        for _ in 0..2_000 {
+            let data = Box::into_raw(Box::new(UserData {
+                retired: AtomicBool::new(false),
+            }));
+            all.push(data);
+            let token = registry.set(callback, data as *mut ());
+            assert!(registry.token() >= token);
+            if let Some(previous) = previous.replace(data) {
+                unsafe { &*previous }.retired.store(true, Ordering::SeqCst);
+            }
+        }
+        while invocations.load(Ordering::SeqCst) == 0 {
+            std::thread::yield_now();
+        }
+        stop.store(true, Ordering::SeqCst);
+        for invoker in invokers {
+            invoker.join().unwrap();
+        }
+        assert_eq!(registry.token().generation(), 2_000);
+
+        registry.clear();
+        for data in all {
+            drop(unsafe { Box::from_raw(data) });
+        }
     }
 
     #[test]
@@ -971,6 +2082,61 @@ mod tests {
         assert_eq!(audio.sample_rate, 24000);
     }
 
+    #[test]
+    fn test_ffi_http_response() {
+        let response = FFIHttpResponse::json(200, r#"{"ok":true}"#).with_header("x-cache", "hit");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers.len(), 2);
+        assert_eq!(response.headers[0].name.as_str(), "content-type");
+        assert_eq!(response.headers[0].value.as_str(), "application/json");
+        assert_eq!(response.body.as_slice(), br#"{"ok":true}"#);
+    }
+
+    #[test]
+    fn test_ffi_http_request_header_lookup() {
+        let request = FFIHttpRequest {
+            method: "GET".into(),
+            path: "/voices".into(),
+            query: RString::new(),
+            headers: vec![FFIHttpHeader::new("content-type", "application/json")].into(),
+            body: RVec::new(),
+        };
+        assert_eq!(request.header("Content-Type"), Some("application/json"));
+        assert_eq!(request.header("accept"), None);
+    }
+
+    #[test]
+    fn test_plugin_init_config_storage() {
+        let dir = std::env::temp_dir().join(format!("waav-plugin-storage-{}", std::process::id()));
+        let init = PluginInitConfig {
+            storage_dir: Some(dir.display().to_string()),
+            storage_quota_bytes: Some(16),
+        };
+        let ffi = init.to_ffi();
+        assert_eq!(unsafe { PluginInitConfig::from_ffi(&ffi) }, init);
+        assert_eq!(
+            unsafe { PluginInitConfig::from_ffi(&FFIConfig::default()) },
+            PluginInitConfig::default()
+        );
+
+        let storage = init.storage().unwrap();
+        storage.write("cache/voices.json", b"[1, 2, 3]").unwrap();
+        assert_eq!(
+            storage.read_to_string("cache/voices.json").unwrap(),
+            "[1, 2, 3]"
+        );
+        // Replacing a file only counts the new size against the quota
+        storage.write("cache/voices.json", b"[1, 2, 3, 4]").unwrap();
+        assert!(storage.write("other.json", b"[1, 2, 3, 4, 5]").is_err());
+        assert!(storage.path("../escape").is_err());
+        assert!(storage.path("/etc/passwd").is_err());
+
+        storage.remove("cache/voices.json").unwrap();
+        storage.remove("cache/voices.json").unwrap();
+        assert_eq!(storage.used_bytes().unwrap(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_callback_wrapper_types() {
         // Verify callback wrapper types have correct size (same as raw function pointer)
@@ -982,5 +2148,13 @@ mod tests {
             std::mem::size_of::<ErrorCallbackFn>(),
             std::mem::size_of::<extern "C" fn(u32, *const RString, *mut ())>()
         );
+        assert_eq!(
+            std::mem::size_of::<ToolCallCallbackFn>(),
+            std::mem::size_of::<extern "C" fn(*const RString, *mut ())>()
+        );
+        assert_eq!(
+            std::mem::size_of::<TTSTimingCallbackFn>(),
+            std::mem::size_of::<extern "C" fn(*const RString, *mut ())>()
+        );
     }
 }